@@ -0,0 +1,10 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Prefer an explicitly configured protoc, otherwise use the vendored one
+    // so building doesn't require protobuf tooling to be installed
+    if std::env::var_os("PROTOC").is_none() {
+        std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    }
+
+    tonic_build::configure().compile(&["proto/lspbridge/query/v1/query.proto"], &["proto"])?;
+    Ok(())
+}
@@ -58,6 +58,8 @@ export class LspBridgeProvider {
         outputPath?: string;
         errorsOnly?: boolean;
         files?: string[];
+        /** Only diagnostics overlapping these one-based, inclusive lines of a file */
+        region?: { file: string; startLine: number; endLine: number };
     }): Promise<string> {
         const args = ['export', '--format', options.format];
 
@@ -78,6 +80,11 @@ export class LspBridgeProvider {
             args.push('--files', options.files.join(','));
         }
 
+        if (options.region) {
+            args.push('--file', options.region.file);
+            args.push('--lines', `${options.region.startLine}-${options.region.endLine}`);
+        }
+
         if (options.outputPath) {
             args.push('--output', options.outputPath);
         }
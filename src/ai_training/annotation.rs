@@ -15,6 +15,19 @@ pub enum FixQuality {
     Incorrect,  // Doesn't fix the issue
 }
 
+impl FixQuality {
+    /// How much of a fix's confidence a reviewer's judgment keeps (0.0-1.0)
+    pub fn weight(self) -> f32 {
+        match self {
+            FixQuality::Perfect => 1.0,
+            FixQuality::Good => 0.8,
+            FixQuality::Acceptable => 0.6,
+            FixQuality::Poor => 0.3,
+            FixQuality::Incorrect => 0.0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Annotation {
     pub id: String,
@@ -64,12 +77,16 @@ pub struct AnnotationTool {
 
 impl AnnotationTool {
     pub fn new() -> Self {
-        let mut quality_weights = HashMap::new();
-        quality_weights.insert(FixQuality::Perfect, 1.0);
-        quality_weights.insert(FixQuality::Good, 0.8);
-        quality_weights.insert(FixQuality::Acceptable, 0.6);
-        quality_weights.insert(FixQuality::Poor, 0.3);
-        quality_weights.insert(FixQuality::Incorrect, 0.0);
+        let quality_weights = [
+            FixQuality::Perfect,
+            FixQuality::Good,
+            FixQuality::Acceptable,
+            FixQuality::Poor,
+            FixQuality::Incorrect,
+        ]
+        .into_iter()
+        .map(|quality| (quality, quality.weight()))
+        .collect();
 
         Self {
             current_session: None,
@@ -1,12 +1,18 @@
 pub mod annotation;
 pub mod data_structures;
 pub mod export;
+pub mod mutation;
+pub mod review;
 pub mod synthetic;
+pub mod verification;
 
 pub use annotation::{AnnotationReport, AnnotationTool, FixQuality};
 pub use data_structures::{FixConfidence, TrainingDataset, TrainingPair};
 pub use export::{ExportFormat, TrainingExporter};
+pub use mutation::{ErrorStage, Mutation, MutationOperator};
+pub use review::{ReviewSession, ReviewThroughput};
 pub use synthetic::{DifficultyLevel, ErrorInjector};
+pub use verification::{CompileOutcome, CompileVerifier, Verification};
 
 use clap::{Subcommand, ValueEnum};
 use std::path::PathBuf;
@@ -64,6 +70,9 @@ pub enum AITrainingAction {
         /// Generate gradient of difficulties
         #[arg(long)]
         gradient: bool,
+        /// Keep only errors the language's compiler confirms (needs the toolchain installed)
+        #[arg(long)]
+        verify: bool,
     },
     /// Annotate training data for quality
     Annotate {
@@ -75,9 +84,9 @@ pub enum AITrainingAction {
         /// Auto-annotate with quality threshold
         #[arg(long, value_enum)]
         auto_quality: Option<FixQuality>,
-        /// Output file for annotated dataset
+        /// Output file for annotated dataset (defaults to updating the dataset in place)
         #[arg(short, long)]
-        output: PathBuf,
+        output: Option<PathBuf>,
     },
     /// Generate annotation report
     Report {
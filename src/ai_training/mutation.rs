@@ -0,0 +1,851 @@
+//! Language-aware mutation operators for synthetic error generation
+//!
+//! Unlike the literal [`TrainingErrorPattern`](super::synthetic::TrainingErrorPattern)
+//! replacements, an operator understands just enough of a language's syntax
+//! to find every place in real code where its error can be introduced: a
+//! `.clone()` whose source is used again, a `let mut` binding, the body of a
+//! Python block, a Go pointer that is dereferenced later.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::ai_training::DifficultyLevel;
+use crate::core::constants::languages;
+
+/// When the injected error surfaces
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorStage {
+    /// Rejected by the compiler or parser
+    Compile,
+    /// Compiles, but fails when run
+    Runtime,
+}
+
+/// One way of introducing an error at one place in the code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mutation {
+    /// The code with the error injected
+    pub code: String,
+    /// Zero-based line of the change
+    pub line: usize,
+    /// Diagnostic message the error is expected to produce
+    pub message: String,
+    /// Compiler error code or error class
+    pub error_code: String,
+}
+
+/// A family of errors for one language
+#[derive(Debug, Clone, Copy)]
+pub struct MutationOperator {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub language: &'static str,
+    pub difficulty: DifficultyLevel,
+    pub stage: ErrorStage,
+    sites: fn(&str) -> Vec<Mutation>,
+}
+
+impl MutationOperator {
+    /// Every mutation this operator can make to the code
+    pub fn mutations(&self, code: &str) -> Vec<Mutation> {
+        (self.sites)(code)
+    }
+}
+
+/// Built-in operators for a language
+pub fn operators_for(language: &str) -> Vec<MutationOperator> {
+    match language {
+        languages::RUST => rust_operators(),
+        languages::PYTHON => python_operators(),
+        languages::GO => go_operators(),
+        _ => Vec::new(),
+    }
+}
+
+fn rust_operators() -> Vec<MutationOperator> {
+    vec![
+        MutationOperator {
+            name: "missing_semicolon",
+            description: "Missing semicolon after a let statement",
+            language: languages::RUST,
+            difficulty: DifficultyLevel::Beginner,
+            stage: ErrorStage::Compile,
+            sites: rust_missing_semicolon,
+        },
+        MutationOperator {
+            name: "missing_mut",
+            description: "Mutated binding not declared mut",
+            language: languages::RUST,
+            difficulty: DifficultyLevel::Beginner,
+            stage: ErrorStage::Compile,
+            sites: rust_missing_mut,
+        },
+        MutationOperator {
+            name: "type_mismatch",
+            description: "String literal assigned to a numeric binding",
+            language: languages::RUST,
+            difficulty: DifficultyLevel::Intermediate,
+            stage: ErrorStage::Compile,
+            sites: rust_type_mismatch,
+        },
+        MutationOperator {
+            name: "use_after_move",
+            description: "Value moved where it was cloned, then used again",
+            language: languages::RUST,
+            difficulty: DifficultyLevel::Intermediate,
+            stage: ErrorStage::Compile,
+            sites: rust_use_after_move,
+        },
+        MutationOperator {
+            name: "borrow_checker",
+            description: "Mutable borrow of an immutable binding",
+            language: languages::RUST,
+            difficulty: DifficultyLevel::Advanced,
+            stage: ErrorStage::Compile,
+            sites: rust_mutable_borrow,
+        },
+        MutationOperator {
+            name: "missing_lifetime",
+            description: "Returned reference without a lifetime to tie it to",
+            language: languages::RUST,
+            difficulty: DifficultyLevel::Expert,
+            stage: ErrorStage::Compile,
+            sites: rust_missing_lifetime,
+        },
+    ]
+}
+
+fn python_operators() -> Vec<MutationOperator> {
+    vec![
+        MutationOperator {
+            name: "indentation_error",
+            description: "Statement indented past its block",
+            language: languages::PYTHON,
+            difficulty: DifficultyLevel::Beginner,
+            stage: ErrorStage::Compile,
+            sites: python_unexpected_indent,
+        },
+        MutationOperator {
+            name: "missing_colon",
+            description: "Compound statement header without a colon",
+            language: languages::PYTHON,
+            difficulty: DifficultyLevel::Beginner,
+            stage: ErrorStage::Compile,
+            sites: python_missing_colon,
+        },
+        MutationOperator {
+            name: "empty_block",
+            description: "Block body dedented to its header",
+            language: languages::PYTHON,
+            difficulty: DifficultyLevel::Intermediate,
+            stage: ErrorStage::Compile,
+            sites: python_empty_block,
+        },
+        MutationOperator {
+            name: "undefined_variable",
+            description: "Misspelled variable name",
+            language: languages::PYTHON,
+            difficulty: DifficultyLevel::Intermediate,
+            stage: ErrorStage::Runtime,
+            sites: python_undefined_name,
+        },
+        MutationOperator {
+            name: "missing_return",
+            description: "Function computes a value but returns None",
+            language: languages::PYTHON,
+            difficulty: DifficultyLevel::Advanced,
+            stage: ErrorStage::Runtime,
+            sites: python_missing_return,
+        },
+        MutationOperator {
+            name: "missing_await",
+            description: "Coroutine called without await",
+            language: languages::PYTHON,
+            difficulty: DifficultyLevel::Expert,
+            stage: ErrorStage::Runtime,
+            sites: python_missing_await,
+        },
+    ]
+}
+
+fn go_operators() -> Vec<MutationOperator> {
+    vec![
+        MutationOperator {
+            name: "redeclaration",
+            description: "Short declaration of an existing variable",
+            language: languages::GO,
+            difficulty: DifficultyLevel::Beginner,
+            stage: ErrorStage::Compile,
+            sites: go_redeclaration,
+        },
+        MutationOperator {
+            name: "undeclared_variable",
+            description: "Assignment to a variable that was never declared",
+            language: languages::GO,
+            difficulty: DifficultyLevel::Beginner,
+            stage: ErrorStage::Compile,
+            sites: go_undeclared_assignment,
+        },
+        MutationOperator {
+            name: "type_mismatch",
+            description: "String constant assigned to a numeric variable",
+            language: languages::GO,
+            difficulty: DifficultyLevel::Intermediate,
+            stage: ErrorStage::Compile,
+            sites: go_type_mismatch,
+        },
+        MutationOperator {
+            name: "missing_return",
+            description: "Function with results ends without a return",
+            language: languages::GO,
+            difficulty: DifficultyLevel::Intermediate,
+            stage: ErrorStage::Compile,
+            sites: go_missing_return,
+        },
+        MutationOperator {
+            name: "nil_dereference",
+            description: "Pointer declared but never allocated",
+            language: languages::GO,
+            difficulty: DifficultyLevel::Advanced,
+            stage: ErrorStage::Runtime,
+            sites: go_nil_dereference,
+        },
+        MutationOperator {
+            name: "nil_map",
+            description: "Map declared but never made",
+            language: languages::GO,
+            difficulty: DifficultyLevel::Advanced,
+            stage: ErrorStage::Runtime,
+            sites: go_nil_map,
+        },
+        MutationOperator {
+            name: "missing_unlock",
+            description: "Mutex locked without a deferred unlock",
+            language: languages::GO,
+            difficulty: DifficultyLevel::Expert,
+            stage: ErrorStage::Runtime,
+            sites: go_missing_unlock,
+        },
+    ]
+}
+
+// Rust
+
+static RUST_LET_STATEMENT: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*let\s.*;\s*$").unwrap());
+static RUST_LET_MUT: Lazy<Regex> = Lazy::new(|| Regex::new(r"\blet mut (\w+)").unwrap());
+static RUST_NUMERIC_LET: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\blet (?:mut )?\w+: (i8|i16|i32|i64|isize|u8|u16|u32|u64|usize|f32|f64) = (-?\d+(?:\.\d+)?)\s*;")
+        .unwrap()
+});
+static RUST_CLONE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b([a-z_]\w*)\.clone\(\)").unwrap());
+static RUST_SHARED_BORROW: Lazy<Regex> = Lazy::new(|| Regex::new(r"&([a-z_]\w*)\b").unwrap());
+static RUST_LIFETIME_FN: Lazy<Regex> = Lazy::new(|| Regex::new(r"\bfn\s+\w+<'(\w+)>\(").unwrap());
+
+fn rust_missing_semicolon(code: &str) -> Vec<Mutation> {
+    let lines = split_lines(code);
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| RUST_LET_STATEMENT.is_match(line))
+        .map(|(i, line)| {
+            let end = line.rfind(';').unwrap_or(line.len());
+            let mutated = format!("{}{}", &line[..end], &line[end + 1..]);
+            mutation(&lines, i, &mutated, "expected `;`".to_string(), "syntax")
+        })
+        .collect()
+}
+
+fn rust_missing_mut(code: &str) -> Vec<Mutation> {
+    let lines = split_lines(code);
+    let mut mutations = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        for caps in RUST_LET_MUT.captures_iter(line) {
+            let name = &caps[1];
+            let whole = caps.get(0).unwrap();
+            let mutated = format!("{}let {}{}", &line[..whole.start()], name, &line[whole.end()..]);
+
+            // Reassignment and mutable borrows are reported differently
+            let rest = code.split_once(whole.as_str()).map(|(_, rest)| rest).unwrap_or("");
+            let reassigned = Regex::new(&format!(r"\b{}\s*[-+*/%]?=[^=]", regex::escape(name)))
+                .map(|re| re.is_match(rest))
+                .unwrap_or(false);
+            let (message, error_code) = if reassigned {
+                (format!("cannot assign twice to immutable variable `{name}`"), "E0384")
+            } else {
+                (
+                    format!("cannot borrow `{name}` as mutable, as it is not declared as mutable"),
+                    "E0596",
+                )
+            };
+            mutations.push(mutation(&lines, i, &mutated, message, error_code));
+        }
+    }
+    mutations
+}
+
+fn rust_type_mismatch(code: &str) -> Vec<Mutation> {
+    let lines = split_lines(code);
+    let mut mutations = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(caps) = RUST_NUMERIC_LET.captures(line) {
+            let literal = caps.get(2).unwrap();
+            let mutated = format!(
+                "{}\"{}\"{}",
+                &line[..literal.start()],
+                literal.as_str(),
+                &line[literal.end()..]
+            );
+            let message = format!("mismatched types: expected `{}`, found `&str`", &caps[1]);
+            mutations.push(mutation(&lines, i, &mutated, message, "E0308"));
+        }
+    }
+    mutations
+}
+
+fn rust_use_after_move(code: &str) -> Vec<Mutation> {
+    let lines = split_lines(code);
+    let mut mutations = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        for caps in RUST_CLONE.captures_iter(line) {
+            let name = &caps[1];
+            if name == "self" || !used_after(&lines, i, name) {
+                continue;
+            }
+            let whole = caps.get(0).unwrap();
+            let mutated = format!("{}{}{}", &line[..whole.start()], name, &line[whole.end()..]);
+            let message = format!("borrow of moved value: `{name}`");
+            mutations.push(mutation(&lines, i, &mutated, message, "E0382"));
+        }
+    }
+    mutations
+}
+
+fn rust_mutable_borrow(code: &str) -> Vec<Mutation> {
+    const NOT_BINDINGS: &[&str] = &["mut", "self", "str", "dyn", "impl"];
+
+    let lines = split_lines(code);
+    let mut mutations = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        for caps in RUST_SHARED_BORROW.captures_iter(line) {
+            let name = &caps[1];
+            let whole = caps.get(0).unwrap();
+            // `&&x` and `a && b` are not borrows of `x`/`b`
+            let doubled = whole.start() > 0 && line.as_bytes()[whole.start() - 1] == b'&';
+            if doubled || NOT_BINDINGS.contains(&name) || !declared_immutable(code, name) {
+                continue;
+            }
+            let mutated = format!("{}&mut {}{}", &line[..whole.start()], name, &line[whole.end()..]);
+            let message = format!("cannot borrow `{name}` as mutable, as it is not declared as mutable");
+            mutations.push(mutation(&lines, i, &mutated, message, "E0596"));
+        }
+    }
+    mutations
+}
+
+fn rust_missing_lifetime(code: &str) -> Vec<Mutation> {
+    let lines = split_lines(code);
+    let mut mutations = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let Some(caps) = RUST_LIFETIME_FN.captures(line) else {
+            continue;
+        };
+        let lifetime = format!("'{}", &caps[1]);
+        let borrowed = format!("&{lifetime} ");
+        // With a single reference parameter the lifetime would be elided
+        if !line.contains(&format!("-> {borrowed}")) || line.matches('&').count() < 3 {
+            continue;
+        }
+        let mutated = line
+            .replacen(&format!("<{lifetime}>"), "", 1)
+            .replace(&borrowed, "&");
+        mutations.push(mutation(
+            &lines,
+            i,
+            &mutated,
+            "missing lifetime specifier".to_string(),
+            "E0106",
+        ));
+    }
+    mutations
+}
+
+/// Whether a local binding exists and is never declared `mut`
+fn declared_immutable(code: &str, name: &str) -> bool {
+    let escaped = regex::escape(name);
+    let declared = Regex::new(&format!(r"\blet {escaped}\b")).map(|re| re.is_match(code));
+    let mutable = Regex::new(&format!(r"\blet mut {escaped}\b")).map(|re| re.is_match(code));
+    matches!((declared, mutable), (Ok(true), Ok(false)))
+}
+
+// Python
+
+const PYTHON_BLOCK_KEYWORDS: &[&str] = &[
+    "def", "class", "if", "elif", "else", "for", "while", "try", "except", "finally", "with",
+    "async",
+];
+
+static PYTHON_ASSIGNMENT: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*([a-z_]\w+)\s*=[^=]").unwrap());
+static PYTHON_RETURN: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\s*)return\s+(.+)$").unwrap());
+static PYTHON_AWAIT: Lazy<Regex> = Lazy::new(|| Regex::new(r"\bawait\s+([\w.]+)\(").unwrap());
+
+fn python_unexpected_indent(code: &str) -> Vec<Mutation> {
+    let lines = split_lines(code);
+    let mut mutations = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() || indent_of(line).is_empty() {
+            continue;
+        }
+        // The first line of a block may be indented by any amount
+        let opens_block = previous_code_line(&lines, i)
+            .is_some_and(|prev| prev.trim_end().ends_with(':'));
+        if opens_block {
+            continue;
+        }
+        let mutated = format!(" {line}");
+        mutations.push(mutation(
+            &lines,
+            i,
+            &mutated,
+            "IndentationError: unexpected indent".to_string(),
+            "IndentationError",
+        ));
+    }
+    mutations
+}
+
+fn python_missing_colon(code: &str) -> Vec<Mutation> {
+    let lines = split_lines(code);
+    let mut mutations = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_end();
+        if block_keyword(line).is_none() || !trimmed.ends_with(':') {
+            continue;
+        }
+        let mutated = format!("{}{}", &trimmed[..trimmed.len() - 1], &line[trimmed.len()..]);
+        mutations.push(mutation(
+            &lines,
+            i,
+            &mutated,
+            "SyntaxError: expected ':'".to_string(),
+            "SyntaxError",
+        ));
+    }
+    mutations
+}
+
+fn python_empty_block(code: &str) -> Vec<Mutation> {
+    let lines = split_lines(code);
+    let mut mutations = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let Some(keyword) = block_keyword(line) else {
+            continue;
+        };
+        if !line.trim_end().ends_with(':') {
+            continue;
+        }
+        let Some(body) = (i + 1..lines.len()).find(|&j| !lines[j].trim().is_empty()) else {
+            continue;
+        };
+        if indent_of(lines[body]).len() <= indent_of(line).len() {
+            continue;
+        }
+        let mutated = format!("{}{}", indent_of(line), lines[body].trim_start());
+        let message = format!(
+            "IndentationError: expected an indented block after '{keyword}' statement on line {}",
+            i + 1
+        );
+        mutations.push(mutation(&lines, body, &mutated, message, "IndentationError"));
+    }
+    mutations
+}
+
+fn python_undefined_name(code: &str) -> Vec<Mutation> {
+    let lines = split_lines(code);
+    let mut mutations = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let Some(caps) = PYTHON_ASSIGNMENT.captures(line) else {
+            continue;
+        };
+        let name = &caps[1];
+        let typo = misspell(name);
+        if code.contains(&typo) {
+            continue;
+        }
+        let Ok(word) = Regex::new(&format!(r"\b{}\b", regex::escape(name))) else {
+            continue;
+        };
+        for (j, later) in lines.iter().enumerate().skip(i + 1) {
+            // Only reads: a new assignment would just define the misspelling
+            if PYTHON_ASSIGNMENT.captures(later).is_some_and(|c| &c[1] == name) {
+                break;
+            }
+            if let Some(found) = word.find(later) {
+                let mutated = format!("{}{}{}", &later[..found.start()], typo, &later[found.end()..]);
+                let message = format!("NameError: name '{typo}' is not defined");
+                mutations.push(mutation(&lines, j, &mutated, message, "NameError"));
+                break;
+            }
+        }
+    }
+    mutations
+}
+
+fn python_missing_return(code: &str) -> Vec<Mutation> {
+    let lines = split_lines(code);
+    let mut mutations = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let Some(caps) = PYTHON_RETURN.captures(line) else {
+            continue;
+        };
+        let value = caps[2].trim();
+        if value == "None" {
+            continue;
+        }
+        let mutated = format!("{}{}", &caps[1], value);
+        mutations.push(mutation(
+            &lines,
+            i,
+            &mutated,
+            "TypeError: function returns None where a value is expected".to_string(),
+            "TypeError",
+        ));
+    }
+    mutations
+}
+
+fn python_missing_await(code: &str) -> Vec<Mutation> {
+    let lines = split_lines(code);
+    let mut mutations = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        for caps in PYTHON_AWAIT.captures_iter(line) {
+            let whole = caps.get(0).unwrap();
+            let mutated = format!("{}{}({}", &line[..whole.start()], &caps[1], &line[whole.end()..]);
+            let function = caps[1].rsplit('.').next().unwrap_or(&caps[1]);
+            let message = format!("RuntimeWarning: coroutine '{function}' was never awaited");
+            mutations.push(mutation(&lines, i, &mutated, message, "RuntimeWarning"));
+        }
+    }
+    mutations
+}
+
+fn block_keyword(line: &str) -> Option<&'static str> {
+    let first = line.trim_start().split(|c: char| !c.is_alphanumeric() && c != '_').next()?;
+    PYTHON_BLOCK_KEYWORDS.iter().copied().find(|&k| k == first)
+}
+
+/// A plausible typo: swap the last two letters, or double the last one
+fn misspell(name: &str) -> String {
+    let mut chars: Vec<char> = name.chars().collect();
+    let n = chars.len();
+    if n >= 2 && chars[n - 1] != chars[n - 2] {
+        chars.swap(n - 1, n - 2);
+    } else {
+        chars.push(chars[n - 1]);
+    }
+    chars.into_iter().collect()
+}
+
+// Go
+
+static GO_ASSIGNMENT: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\s*)(\w+) = ").unwrap());
+static GO_SHORT_DECL: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\s*)(\w+) := ").unwrap());
+static GO_NUMERIC_VAR: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\bvar \w+ (int|int8|int16|int32|int64|uint|uint8|uint16|uint32|uint64|float32|float64) = (-?\d+(?:\.\d+)?)\s*$")
+        .unwrap()
+});
+static GO_RETURN: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*return\s+\S").unwrap());
+static GO_POINTER_LITERAL: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(\s*)(\w+) := &([\w.]+)\{.*\}\s*$").unwrap());
+static GO_MAKE_MAP: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(\s*)(\w+) := make\((map\[[^\]]+\][^,)]+)\)\s*$").unwrap());
+static GO_DEFER_UNLOCK: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*defer [\w.]+\.Unlock\(\)\s*$").unwrap());
+
+fn go_redeclaration(code: &str) -> Vec<Mutation> {
+    let lines = split_lines(code);
+    let mut mutations = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let Some(caps) = GO_ASSIGNMENT.captures(line) else {
+            continue;
+        };
+        let name = &caps[2];
+        if name == "_" || !go_declared_before(&lines, i, name) {
+            continue;
+        }
+        let mutated = line.replacen(&format!("{name} = "), &format!("{name} := "), 1);
+        mutations.push(mutation(
+            &lines,
+            i,
+            &mutated,
+            "no new variables on left side of :=".to_string(),
+            "compile",
+        ));
+    }
+    mutations
+}
+
+fn go_undeclared_assignment(code: &str) -> Vec<Mutation> {
+    let lines = split_lines(code);
+    let mut mutations = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let Some(caps) = GO_SHORT_DECL.captures(line) else {
+            continue;
+        };
+        let name = &caps[2];
+        if name == "_" || go_declared_before(&lines, i, name) {
+            continue;
+        }
+        let mutated = line.replacen(&format!("{name} := "), &format!("{name} = "), 1);
+        mutations.push(mutation(&lines, i, &mutated, format!("undefined: {name}"), "compile"));
+    }
+    mutations
+}
+
+fn go_type_mismatch(code: &str) -> Vec<Mutation> {
+    let lines = split_lines(code);
+    let mut mutations = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let Some(caps) = GO_NUMERIC_VAR.captures(line) else {
+            continue;
+        };
+        let literal = caps.get(2).unwrap();
+        let mutated = format!(
+            "{}\"{}\"{}",
+            &line[..literal.start()],
+            literal.as_str(),
+            &line[literal.end()..]
+        );
+        let message = format!(
+            "cannot use \"{}\" (untyped string constant) as {} value in variable declaration",
+            literal.as_str(),
+            &caps[1]
+        );
+        mutations.push(mutation(&lines, i, &mutated, message, "compile"));
+    }
+    mutations
+}
+
+fn go_missing_return(code: &str) -> Vec<Mutation> {
+    let lines = split_lines(code);
+    let mut mutations = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if !GO_RETURN.is_match(line) {
+            continue;
+        }
+        // Only the final statement of a top-level function
+        let closes_function = (i + 1..lines.len())
+            .find(|&j| !lines[j].trim().is_empty())
+            .is_some_and(|j| lines[j].trim_end() == "}");
+        if !closes_function {
+            continue;
+        }
+        let mut remaining: Vec<&str> = lines.clone();
+        remaining.remove(i);
+        mutations.push(Mutation {
+            code: remaining.join("\n"),
+            line: i,
+            message: "missing return".to_string(),
+            error_code: "compile".to_string(),
+        });
+    }
+    mutations
+}
+
+fn go_nil_dereference(code: &str) -> Vec<Mutation> {
+    let lines = split_lines(code);
+    let mut mutations = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let Some(caps) = GO_POINTER_LITERAL.captures(line) else {
+            continue;
+        };
+        let name = &caps[2];
+        if !used_after(&lines, i, &format!("{name}.")) {
+            continue;
+        }
+        let mutated = format!("{}var {} *{}", &caps[1], name, &caps[3]);
+        mutations.push(mutation(
+            &lines,
+            i,
+            &mutated,
+            "panic: runtime error: invalid memory address or nil pointer dereference".to_string(),
+            "panic",
+        ));
+    }
+    mutations
+}
+
+fn go_nil_map(code: &str) -> Vec<Mutation> {
+    let lines = split_lines(code);
+    let mut mutations = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let Some(caps) = GO_MAKE_MAP.captures(line) else {
+            continue;
+        };
+        let name = &caps[2];
+        if !used_after(&lines, i, &format!("{name}[")) {
+            continue;
+        }
+        let mutated = format!("{}var {} {}", &caps[1], name, caps[3].trim());
+        mutations.push(mutation(
+            &lines,
+            i,
+            &mutated,
+            "panic: assignment to entry in nil map".to_string(),
+            "panic",
+        ));
+    }
+    mutations
+}
+
+fn go_missing_unlock(code: &str) -> Vec<Mutation> {
+    let lines = split_lines(code);
+    let mut mutations = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if !GO_DEFER_UNLOCK.is_match(line) {
+            continue;
+        }
+        let mut remaining: Vec<&str> = lines.clone();
+        remaining.remove(i);
+        mutations.push(Mutation {
+            code: remaining.join("\n"),
+            line: i,
+            message: "fatal error: all goroutines are asleep - deadlock!".to_string(),
+            error_code: "deadlock".to_string(),
+        });
+    }
+    mutations
+}
+
+fn go_declared_before(lines: &[&str], index: usize, name: &str) -> bool {
+    let short = format!("{name} := ");
+    let var = format!("var {name} ");
+    lines[..index].iter().any(|line| {
+        let trimmed = line.trim_start();
+        trimmed.starts_with(&short) || trimmed.starts_with(&var)
+    })
+}
+
+// Shared helpers
+
+fn split_lines(code: &str) -> Vec<&str> {
+    code.split('\n').collect()
+}
+
+fn indent_of(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+fn previous_code_line<'a>(lines: &[&'a str], index: usize) -> Option<&'a str> {
+    lines[..index].iter().rev().find(|line| !line.trim().is_empty()).copied()
+}
+
+/// Whether `needle` appears on a line after `index`
+fn used_after(lines: &[&str], index: usize, needle: &str) -> bool {
+    let word = needle.chars().all(|c| c.is_alphanumeric() || c == '_');
+    let pattern = if word {
+        format!(r"\b{}\b", regex::escape(needle))
+    } else {
+        format!(r"\b{}", regex::escape(needle))
+    };
+    match Regex::new(&pattern) {
+        Ok(re) => lines[index + 1..].iter().any(|line| re.is_match(line)),
+        Err(_) => false,
+    }
+}
+
+fn mutation(lines: &[&str], index: usize, mutated_line: &str, message: String, error_code: &str) -> Mutation {
+    let mut mutated: Vec<&str> = lines.to_vec();
+    mutated[index] = mutated_line;
+    Mutation {
+        code: mutated.join("\n"),
+        line: index,
+        message,
+        error_code: error_code.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn operator(language: &str, name: &str) -> MutationOperator {
+        operators_for(language)
+            .into_iter()
+            .find(|op| op.name == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_every_language_covers_the_difficulty_gradient() {
+        for language in [languages::RUST, languages::PYTHON, languages::GO] {
+            let operators = operators_for(language);
+            for level in [
+                DifficultyLevel::Beginner,
+                DifficultyLevel::Intermediate,
+                DifficultyLevel::Advanced,
+                DifficultyLevel::Expert,
+            ] {
+                assert!(
+                    operators.iter().any(|op| op.difficulty == level),
+                    "{language} has no {level:?} operator"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_rust_operators() {
+        let code = "fn main() {\n    let name = String::from(\"a\");\n    let copy = name.clone();\n    println!(\"{name} {copy}\");\n}\n";
+
+        let moved = operator(languages::RUST, "use_after_move").mutations(code);
+        assert_eq!(moved.len(), 1);
+        assert_eq!(moved[0].line, 2);
+        assert!(moved[0].code.contains("let copy = name;"));
+        assert_eq!(moved[0].error_code, "E0382");
+
+        let code = "fn main() {\n    let mut total = 0;\n    total += 1;\n}\n";
+        let immutable = operator(languages::RUST, "missing_mut").mutations(code);
+        assert_eq!(immutable[0].error_code, "E0384");
+        assert!(immutable[0].code.contains("    let total = 0;"));
+
+        let code = "fn longest<'a>(a: &'a str, b: &'a str) -> &'a str { a }\nfn first<'a>(a: &'a str) -> &'a str { a }";
+        let lifetimes = operator(languages::RUST, "missing_lifetime").mutations(code);
+        assert_eq!(lifetimes.len(), 1);
+        assert!(lifetimes[0].code.starts_with("fn longest(a: &str, b: &str) -> &str { a }\n"));
+    }
+
+    #[test]
+    fn test_python_operators() {
+        let code = "def total(items):\n    result = 0\n    for item in items:\n        result += item\n    return result\n";
+
+        let indents = operator(languages::PYTHON, "indentation_error").mutations(code);
+        // Block openers are skipped: only `for` and `return` may be over-indented
+        assert_eq!(indents.iter().map(|m| m.line).collect::<Vec<_>>(), vec![2, 4]);
+
+        let empty = operator(languages::PYTHON, "empty_block").mutations(code);
+        assert!(empty[0].code.contains("def total(items):\nresult = 0\n"));
+        assert!(empty[0].message.contains("after 'def' statement on line 1"));
+
+        let undefined = operator(languages::PYTHON, "undefined_variable").mutations(code);
+        assert_eq!(undefined[0].line, 3);
+        assert!(undefined[0].code.contains("        resutl += item"));
+        assert_eq!(undefined[0].message, "NameError: name 'resutl' is not defined");
+    }
+
+    #[test]
+    fn test_go_operators() {
+        let code = "package main\n\ntype User struct{ Name string }\n\nfunc main() {\n\tuser := &User{Name: \"a\"}\n\tcounts := make(map[string]int)\n\tcounts[user.Name] = 1\n}\n";
+
+        let nil = operator(languages::GO, "nil_dereference").mutations(code);
+        assert_eq!(nil[0].line, 5);
+        assert!(nil[0].code.contains("\tvar user *User\n"));
+
+        let map = operator(languages::GO, "nil_map").mutations(code);
+        assert!(map[0].code.contains("\tvar counts map[string]int\n"));
+
+        let undeclared = operator(languages::GO, "undeclared_variable").mutations(code);
+        assert_eq!(undeclared.len(), 2);
+        assert_eq!(undeclared[0].message, "undefined: user");
+    }
+}
@@ -0,0 +1,346 @@
+//! Batch review of training pairs
+//!
+//! [`ReviewSession`] holds the state behind `ai-training annotate`: the
+//! queue of pairs still to review, the reviewer's pending comment and their
+//! throughput. Every rating is written back to the dataset file right away,
+//! so an interrupted review loses nothing and resumes at the first pair
+//! without a rating. When the session finishes, its throughput is appended
+//! to a `.reviews.jsonl` log next to the dataset.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use super::annotation::{AnnotationTool, FixQuality, VerificationResult};
+use super::{FixConfidence, TrainingDataset, TrainingPair};
+
+/// Metadata key set on every rated pair
+pub const QUALITY_KEY: &str = "fix_quality";
+
+/// Metadata key holding a pair's full annotation
+pub const ANNOTATION_KEY: &str = "annotation";
+
+/// Metadata key keeping a pair's confidence from before its first rating
+pub const ORIGINAL_CONFIDENCE_KEY: &str = "original_confidence";
+
+/// Longest pause between two ratings still counted as review time
+const IDLE_CUTOFF: Duration = Duration::from_secs(300);
+
+/// One line of a before/after diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Context(String),
+    Removed(String),
+    Added(String),
+}
+
+/// Line diff of a pair's before and after code
+///
+/// Uses a longest-common-subsequence diff, which is fine for the snippet
+/// sizes in training pairs; very long inputs only get their common prefix
+/// and suffix matched.
+pub fn diff_lines(before: &str, after: &str) -> Vec<DiffLine> {
+    const MAX_CELLS: usize = 4_000_000;
+
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old_mid, new_mid) = (&old[prefix..old.len() - suffix], &new[prefix..new.len() - suffix]);
+
+    let mut lines: Vec<DiffLine> = old[..prefix].iter().map(|l| DiffLine::Context(l.to_string())).collect();
+    if old_mid.len().saturating_mul(new_mid.len()) > MAX_CELLS {
+        lines.extend(old_mid.iter().map(|l| DiffLine::Removed(l.to_string())));
+        lines.extend(new_mid.iter().map(|l| DiffLine::Added(l.to_string())));
+    } else {
+        // lcs[i][j]: length of the LCS of old_mid[i..] and new_mid[j..]
+        let mut lcs = vec![vec![0usize; new_mid.len() + 1]; old_mid.len() + 1];
+        for i in (0..old_mid.len()).rev() {
+            for j in (0..new_mid.len()).rev() {
+                lcs[i][j] = if old_mid[i] == new_mid[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < old_mid.len() || j < new_mid.len() {
+            if i < old_mid.len() && j < new_mid.len() && old_mid[i] == new_mid[j] {
+                lines.push(DiffLine::Context(old_mid[i].to_string()));
+                i += 1;
+                j += 1;
+            } else if i < old_mid.len() && (j == new_mid.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+                lines.push(DiffLine::Removed(old_mid[i].to_string()));
+                i += 1;
+            } else {
+                lines.push(DiffLine::Added(new_mid[j].to_string()));
+                j += 1;
+            }
+        }
+    }
+    lines.extend(old[old.len() - suffix..].iter().map(|l| DiffLine::Context(l.to_string())));
+    lines
+}
+
+/// How fast a reviewer got through a session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewThroughput {
+    pub annotator: String,
+    pub dataset_id: String,
+    pub started_at: DateTime<Utc>,
+    pub reviewed: usize,
+    pub skipped: usize,
+    /// Time spent reviewing, not counting long idle pauses
+    pub active_seconds: f64,
+}
+
+impl ReviewThroughput {
+    /// Pairs rated per hour of active review
+    pub fn per_hour(&self) -> f64 {
+        if self.active_seconds <= 0.0 {
+            0.0
+        } else {
+            self.reviewed as f64 * 3600.0 / self.active_seconds
+        }
+    }
+
+    /// Average seconds spent per rated pair
+    pub fn seconds_per_pair(&self) -> f64 {
+        if self.reviewed == 0 {
+            0.0
+        } else {
+            self.active_seconds / self.reviewed as f64
+        }
+    }
+}
+
+/// A reviewer working through a dataset
+pub struct ReviewSession {
+    dataset: TrainingDataset,
+    output: PathBuf,
+    tool: AnnotationTool,
+    /// Indices of the pairs to review, in dataset order
+    queue: Vec<usize>,
+    position: usize,
+    comment: String,
+    throughput: ReviewThroughput,
+    last_activity: Instant,
+}
+
+impl ReviewSession {
+    /// Review the pairs of `dataset` that have no rating yet, saving to `output`
+    pub fn new(dataset: TrainingDataset, annotator: &str, output: impl Into<PathBuf>) -> Self {
+        let mut tool = AnnotationTool::new();
+        tool.start_session(annotator.to_string(), dataset.id.clone());
+        let queue = dataset
+            .pairs
+            .iter()
+            .enumerate()
+            .filter(|(_, pair)| !pair.metadata.contains_key(QUALITY_KEY))
+            .map(|(index, _)| index)
+            .collect();
+        let throughput = ReviewThroughput {
+            annotator: annotator.to_string(),
+            dataset_id: dataset.id.clone(),
+            started_at: Utc::now(),
+            reviewed: 0,
+            skipped: 0,
+            active_seconds: 0.0,
+        };
+
+        Self {
+            dataset,
+            output: output.into(),
+            tool,
+            queue,
+            position: 0,
+            comment: String::new(),
+            throughput,
+            last_activity: Instant::now(),
+        }
+    }
+
+    pub fn dataset(&self) -> &TrainingDataset {
+        &self.dataset
+    }
+
+    /// The pair under review, or `None` once the queue is exhausted
+    pub fn current(&self) -> Option<&TrainingPair> {
+        self.queue.get(self.position).map(|index| &self.dataset.pairs[*index])
+    }
+
+    /// Position in the queue and its length
+    pub fn progress(&self) -> (usize, usize) {
+        (self.position.min(self.queue.len()), self.queue.len())
+    }
+
+    pub fn throughput(&self) -> &ReviewThroughput {
+        &self.throughput
+    }
+
+    /// Comment attached to the next rating
+    pub fn comment(&self) -> &str {
+        &self.comment
+    }
+
+    pub fn set_comment(&mut self, comment: impl Into<String>) {
+        self.comment = comment.into();
+    }
+
+    /// Rate the current pair, save the dataset and move to the next pair
+    ///
+    /// Rating a pair again replaces its earlier rating.
+    pub fn rate(&mut self, quality: FixQuality) -> Result<()> {
+        let Some(&index) = self.queue.get(self.position) else {
+            return Ok(());
+        };
+        let pair = &mut self.dataset.pairs[index];
+
+        // Ratings scale the original confidence, not an earlier rating's result
+        match pair.metadata.get(ORIGINAL_CONFIDENCE_KEY).and_then(|v| v.as_f64()) {
+            Some(original) => pair.confidence = FixConfidence::new(original as f32),
+            None => pair.add_metadata(ORIGINAL_CONFIDENCE_KEY.to_string(), serde_json::json!(pair.confidence.score)),
+        }
+
+        let verification = VerificationResult {
+            compiles: true, // Reviewers read the fix; nothing was compiled
+            tests_pass: None,
+            linter_warnings: vec![],
+            performance_impact: None,
+            side_effects: vec![],
+        };
+        let notes = std::mem::take(&mut self.comment);
+        let annotation = self.tool.annotate_pair(pair, quality, notes, vec!["manual".to_string()], verification)?;
+        pair.add_metadata(ANNOTATION_KEY.to_string(), serde_json::to_value(&annotation)?);
+
+        self.throughput.reviewed += 1;
+        self.record_activity();
+        self.save()?;
+        self.position += 1;
+        Ok(())
+    }
+
+    /// Leave the current pair unrated and move on
+    pub fn skip(&mut self) {
+        if self.position < self.queue.len() {
+            self.throughput.skipped += 1;
+            self.comment.clear();
+            self.record_activity();
+            self.position += 1;
+        }
+    }
+
+    /// Go back to the previous pair in the queue
+    pub fn previous(&mut self) {
+        self.position = self.position.saturating_sub(1);
+        self.comment.clear();
+    }
+
+    /// Save the dataset and append the session's throughput to the review log
+    pub fn finish(mut self) -> Result<ReviewThroughput> {
+        self.dataset.updated_at = Utc::now();
+        self.save()?;
+        self.tool.complete_session()?;
+
+        let log = review_log_path(&self.output);
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log)
+            .with_context(|| format!("Failed to open review log {}", log.display()))?;
+        writeln!(file, "{}", serde_json::to_string(&self.throughput)?)?;
+        Ok(self.throughput)
+    }
+
+    fn record_activity(&mut self) {
+        let idle = self.last_activity.elapsed().min(IDLE_CUTOFF);
+        self.throughput.active_seconds += idle.as_secs_f64();
+        self.last_activity = Instant::now();
+    }
+
+    /// Write the dataset through a temporary file so a crash can't truncate it
+    fn save(&self) -> Result<()> {
+        let temp = self.output.with_extension("json.tmp");
+        std::fs::write(&temp, serde_json::to_string_pretty(&self.dataset)?)
+            .with_context(|| format!("Failed to write {}", temp.display()))?;
+        std::fs::rename(&temp, &self.output)
+            .with_context(|| format!("Failed to write {}", self.output.display()))?;
+        Ok(())
+    }
+}
+
+/// Review log kept next to a dataset, e.g. `data.reviews.jsonl` for `data.json`
+pub fn review_log_path(dataset: &Path) -> PathBuf {
+    dataset.with_extension("reviews.jsonl")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::semantic_context::SemanticContext;
+
+    #[test]
+    fn test_diff_lines() {
+        let diff = diff_lines("let a = 1;\nlet b = a\nprint(b);\n", "let a = 1;\nlet b = a;\nprint(b);\n");
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Context("let a = 1;".to_string()),
+                DiffLine::Removed("let b = a".to_string()),
+                DiffLine::Added("let b = a;".to_string()),
+                DiffLine::Context("print(b);".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ratings_are_saved_incrementally_and_resumed() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let output = dir.path().join("data.json");
+        let mut dataset = TrainingDataset::new("set".to_string(), String::new());
+        for n in 0..3 {
+            let mut pair = TrainingPair::new(
+                format!("let x{n} = 1"),
+                format!("let x{n} = 1;"),
+                vec![],
+                SemanticContext::default(),
+                "rust".to_string(),
+            );
+            pair.confidence = FixConfidence::new(0.8);
+            dataset.add_pair(pair);
+        }
+
+        let mut session = ReviewSession::new(dataset, "ana", &output);
+        session.set_comment("missing semicolon only");
+        session.rate(FixQuality::Good)?;
+        session.skip();
+        session.previous();
+        session.previous();
+        session.rate(FixQuality::Perfect)?;
+
+        // Saved after each rating, before the session finishes
+        let saved: TrainingDataset = serde_json::from_str(&std::fs::read_to_string(&output)?)?;
+        let first = &saved.pairs[0];
+        assert_eq!(first.metadata[QUALITY_KEY], serde_json::json!(FixQuality::Perfect));
+        assert_eq!(first.metadata[ANNOTATION_KEY]["notes"], "");
+        // Re-rating starts again from the original confidence
+        assert!((first.confidence.score - 0.8 * 0.9).abs() < 1e-4);
+
+        let throughput = session.finish()?;
+        assert_eq!((throughput.reviewed, throughput.skipped), (2, 1));
+        assert!(std::fs::read_to_string(review_log_path(&output))?.contains("\"annotator\":\"ana\""));
+
+        let resumed = ReviewSession::new(saved, "ana", &output);
+        assert_eq!(resumed.progress(), (0, 2));
+        Ok(())
+    }
+}
@@ -4,6 +4,8 @@ use rand::{seq::SliceRandom, thread_rng};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::ai_training::mutation::{self, ErrorStage, Mutation, MutationOperator};
+use crate::ai_training::verification::{CompileVerifier, Verification};
 use crate::ai_training::{TrainingDataset, TrainingPair};
 use crate::core::constants::{error_patterns, languages, metadata_keys};
 use crate::core::semantic_context::SemanticContext;
@@ -36,14 +38,34 @@ pub struct CodeTransformation {
 
 pub struct ErrorInjector {
     patterns: HashMap<String, Vec<TrainingErrorPattern>>,
+    operators: HashMap<String, Vec<MutationOperator>>,
     difficulty_weights: HashMap<DifficultyLevel, f32>,
+    verifier: Option<CompileVerifier>,
+}
+
+/// An error ready to be turned into a training pair
+struct InjectedError {
+    code: String,
+    line: usize,
+    message: String,
+    diagnostic_type: String,
+    stage: ErrorStage,
+    description: String,
+}
+
+/// A way of injecting an error that applies to the code at hand
+enum Candidate<'a> {
+    Pattern(&'a TrainingErrorPattern),
+    Operator(&'a MutationOperator, Vec<Mutation>),
 }
 
 impl ErrorInjector {
     pub fn new() -> Self {
         let mut injector = Self {
             patterns: HashMap::new(),
+            operators: HashMap::new(),
             difficulty_weights: HashMap::new(),
+            verifier: None,
         };
 
         // Initialize default difficulty weights
@@ -62,12 +84,23 @@ impl ErrorInjector {
 
         // Initialize common error patterns
         injector.init_typescript_patterns();
-        injector.init_rust_patterns();
-        injector.init_python_patterns();
+        for language in [languages::RUST, languages::PYTHON, languages::GO] {
+            injector
+                .operators
+                .insert(language.to_string(), mutation::operators_for(language));
+        }
 
         injector
     }
 
+    /// Only keep errors the language's compiler confirms
+    ///
+    /// The clean code must compile and the toolchain must be installed.
+    pub fn with_verifier(mut self, verifier: CompileVerifier) -> Self {
+        self.verifier = Some(verifier);
+        self
+    }
+
     pub fn inject_errors(
         &self,
         clean_code: &str,
@@ -75,48 +108,80 @@ impl ErrorInjector {
         difficulty: Option<DifficultyLevel>,
         count: usize,
     ) -> Result<Vec<TrainingPair>> {
-        let patterns = self
-            .patterns
-            .get(language)
-            .context(format!("No patterns available for language: {language}"))?;
+        let patterns = self.patterns.get(language);
+        let operators = self.operators.get(language);
+        if patterns.is_none() && operators.is_none() {
+            anyhow::bail!("No patterns available for language: {language}");
+        }
 
         let mut training_pairs = Vec::new();
         let mut rng = thread_rng();
 
         // Filter patterns by difficulty if specified
-        let filtered_patterns: Vec<&TrainingErrorPattern> = if let Some(diff) = difficulty {
-            patterns.iter().filter(|p| p.difficulty == diff).collect()
-        } else {
-            patterns.iter().collect()
-        };
+        let wanted = |level: DifficultyLevel| difficulty.is_none() || difficulty == Some(level);
+        let filtered_patterns: Vec<&TrainingErrorPattern> = patterns
+            .into_iter()
+            .flatten()
+            .filter(|p| wanted(p.difficulty))
+            .collect();
+        let filtered_operators: Vec<&MutationOperator> = operators
+            .into_iter()
+            .flatten()
+            .filter(|op| wanted(op.difficulty))
+            .collect();
 
-        if filtered_patterns.is_empty() {
+        if filtered_patterns.is_empty() && filtered_operators.is_empty() {
             anyhow::bail!("No patterns available for specified criteria");
         }
 
-        // First, collect all patterns that can match the code
-        let mut applicable_patterns = Vec::new();
-        for pattern in &filtered_patterns {
+        // First, collect everything that can change the code
+        let mut applicable = Vec::new();
+        for pattern in filtered_patterns {
             // Check if any transformation in this pattern can apply
-            for transformation in &pattern.transformations {
-                if clean_code.contains(&transformation.pattern) {
-                    applicable_patterns.push(pattern);
-                    break; // Don't add the same pattern multiple times
-                }
+            if pattern
+                .transformations
+                .iter()
+                .any(|t| clean_code.contains(&t.pattern))
+            {
+                applicable.push(Candidate::Pattern(pattern));
+            }
+        }
+        for operator in filtered_operators {
+            let mutations = operator.mutations(clean_code);
+            if !mutations.is_empty() {
+                applicable.push(Candidate::Operator(operator, mutations));
             }
         }
 
-        if applicable_patterns.is_empty() {
+        if applicable.is_empty() {
             anyhow::bail!("No applicable patterns found for the provided code");
         }
 
-        // Now generate the requested number of training pairs from applicable patterns
-        for _ in 0..count {
-            let pattern = applicable_patterns
+        if let Some(verifier) = &self.verifier {
+            verifier.ensure_compiles(language, clean_code)?;
+        }
+
+        // Now generate the requested number of training pairs; mutations the
+        // compiler rejects are replaced by further attempts, within limits
+        let attempts = if self.verifier.is_some() { count * 4 } else { count };
+        for _ in 0..attempts {
+            if training_pairs.len() >= count {
+                break;
+            }
+            let candidate = applicable
                 .choose(&mut rng)
                 .context("Failed to select applicable pattern")?;
 
-            if let Some(pair) = self.apply_pattern(clean_code, pattern, language)? {
+            let pair = match candidate {
+                Candidate::Pattern(pattern) => self.apply_pattern(clean_code, pattern, language)?,
+                Candidate::Operator(operator, mutations) => {
+                    let mutation = mutations
+                        .choose(&mut rng)
+                        .context("Failed to select mutation")?;
+                    self.apply_mutation(clean_code, operator, mutation, language)?
+                }
+            };
+            if let Some(pair) = pair {
                 training_pairs.push(pair);
             }
         }
@@ -177,48 +242,117 @@ impl ErrorInjector {
             .context("No transformations in pattern")?;
 
         // Apply the transformation
-        if code.contains(&transformation.pattern) {
-            let error_code = code.replace(&transformation.pattern, &transformation.replacement);
-
-            // Find the line number of the change
-            let line_num = code
-                .lines()
-                .position(|line| line.contains(&transformation.pattern))
-                .unwrap_or(0)
-                + 1;
-
-            // Create diagnostic
-            let diagnostic = Diagnostic::new(
-                format!("synthetic.{language}"),
-                Range {
-                    start: Position {
-                        line: line_num as u32,
-                        character: 0,
-                    },
-                    end: Position {
-                        line: line_num as u32,
-                        character: 80,
-                    },
-                },
-                crate::core::types::DiagnosticSeverity::Error,
-                transformation.diagnostic_message.clone(),
-                language.to_string(),
+        if !code.contains(&transformation.pattern) {
+            return Ok(None);
+        }
+        let error_code = code.replace(&transformation.pattern, &transformation.replacement);
+
+        // Find the line number of the change
+        let line = code
+            .lines()
+            .position(|line| line.contains(&transformation.pattern))
+            .unwrap_or(0);
+
+        let error = InjectedError {
+            code: error_code,
+            line,
+            message: transformation.diagnostic_message.clone(),
+            diagnostic_type: transformation.diagnostic_type.clone(),
+            stage: ErrorStage::Compile,
+            description: format!("Fix {}: {}", pattern.name, pattern.description),
+        };
+        self.build_pair(code, language, error)
+    }
+
+    fn apply_mutation(
+        &self,
+        code: &str,
+        operator: &MutationOperator,
+        mutation: &Mutation,
+        language: &str,
+    ) -> Result<Option<TrainingPair>> {
+        let error = InjectedError {
+            code: mutation.code.clone(),
+            line: mutation.line,
+            message: mutation.message.clone(),
+            diagnostic_type: mutation.error_code.clone(),
+            stage: operator.stage,
+            description: format!("Fix {}: {}", operator.name, operator.description),
+        };
+        let pair = self.build_pair(code, language, error)?;
+
+        Ok(pair.map(|mut pair| {
+            pair.add_metadata(
+                metadata_keys::MUTATION.to_string(),
+                serde_json::json!(operator.name),
             );
+            pair
+        }))
+    }
 
-            let pair = TrainingPair::new(
-                error_code,
-                code.to_string(),
-                vec![diagnostic],
-                SemanticContext::default(),
-                language.to_string(),
-            )
-            .with_confidence(1.0) // Synthetic data has perfect confidence
-            .with_description(format!("Fix {}: {}", pattern.name, pattern.description));
-
-            Ok(Some(pair))
-        } else {
-            Ok(None)
-        }
+    /// Turn an injected error into a training pair, verifying it if configured
+    ///
+    /// Returns `None` when the verifier rejects the error.
+    fn build_pair(
+        &self,
+        clean_code: &str,
+        language: &str,
+        error: InjectedError,
+    ) -> Result<Option<TrainingPair>> {
+        let mut message = error.message;
+        let verified = match &self.verifier {
+            Some(verifier) => match verifier.verify(language, &error.code, error.stage)? {
+                Verification::Confirmed { compiler_error } => {
+                    // The compiler's own wording beats the expected message
+                    if let Some(compiler_error) = compiler_error {
+                        message = compiler_error;
+                    }
+                    true
+                }
+                Verification::Rejected(reason) => {
+                    tracing::debug!("Discarding synthetic {} error: {}", language, reason);
+                    return Ok(None);
+                }
+            },
+            None => false,
+        };
+
+        let line_num = error.line + 1;
+        let mut diagnostic = Diagnostic::new(
+            format!("synthetic.{language}"),
+            Range {
+                start: Position {
+                    line: line_num as u32,
+                    character: 0,
+                },
+                end: Position {
+                    line: line_num as u32,
+                    character: 80,
+                },
+            },
+            crate::core::types::DiagnosticSeverity::Error,
+            message,
+            language.to_string(),
+        );
+        diagnostic.code = Some(error.diagnostic_type);
+
+        let mut pair = TrainingPair::new(
+            error.code,
+            clean_code.to_string(),
+            vec![diagnostic],
+            SemanticContext::default(),
+            language.to_string(),
+        )
+        .with_confidence(1.0) // Synthetic data has perfect confidence
+        .with_description(error.description);
+
+        pair.add_metadata(
+            metadata_keys::ERROR_STAGE.to_string(),
+            serde_json::json!(error.stage),
+        );
+        pair.add_metadata(metadata_keys::VERIFIED.to_string(), serde_json::json!(verified));
+
+        Ok(Some(pair))
     }
 
     fn init_typescript_patterns(&mut self) {
@@ -284,73 +418,6 @@ impl ErrorInjector {
             .insert(languages::TYPESCRIPT.to_string(), patterns);
     }
 
-    fn init_rust_patterns(&mut self) {
-        let patterns = vec![
-            // Beginner patterns
-            TrainingErrorPattern {
-                name: "missing_mut".to_string(),
-                description: "Missing mut keyword".to_string(),
-                difficulty: DifficultyLevel::Beginner,
-                languages: vec![languages::RUST.to_string()],
-                transformations: vec![CodeTransformation {
-                    pattern: "let mut x = 5;".to_string(),
-                    replacement: "let x = 5;".to_string(),
-                    diagnostic_message: "cannot assign twice to immutable variable".to_string(),
-                    diagnostic_type: "E0384".to_string(),
-                }],
-            },
-            // Intermediate patterns
-            TrainingErrorPattern {
-                name: "borrow_checker".to_string(),
-                description: "Borrow checker violation".to_string(),
-                difficulty: DifficultyLevel::Intermediate,
-                languages: vec![languages::RUST.to_string()],
-                transformations: vec![CodeTransformation {
-                    pattern: "&data".to_string(),
-                    replacement: "&mut data".to_string(),
-                    diagnostic_message: error_patterns::CANNOT_BORROW_MUTABLE.to_string(),
-                    diagnostic_type: "E0596".to_string(),
-                }],
-            },
-        ];
-
-        self.patterns.insert(languages::RUST.to_string(), patterns);
-    }
-
-    fn init_python_patterns(&mut self) {
-        let patterns = vec![
-            // Beginner patterns
-            TrainingErrorPattern {
-                name: "indentation_error".to_string(),
-                description: "Incorrect indentation".to_string(),
-                difficulty: DifficultyLevel::Beginner,
-                languages: vec![languages::PYTHON.to_string()],
-                transformations: vec![CodeTransformation {
-                    pattern: "    return x".to_string(),
-                    replacement: "   return x".to_string(),
-                    diagnostic_message: "IndentationError: unexpected indent".to_string(),
-                    diagnostic_type: "syntax".to_string(),
-                }],
-            },
-            // Intermediate patterns
-            TrainingErrorPattern {
-                name: "undefined_variable".to_string(),
-                description: "Using undefined variable".to_string(),
-                difficulty: DifficultyLevel::Intermediate,
-                languages: vec![languages::PYTHON.to_string()],
-                transformations: vec![CodeTransformation {
-                    pattern: "result = x + y".to_string(),
-                    replacement: "result = x + z".to_string(),
-                    diagnostic_message: "NameError: name 'z' is not defined".to_string(),
-                    diagnostic_type: "runtime".to_string(),
-                }],
-            },
-        ];
-
-        self.patterns
-            .insert(languages::PYTHON.to_string(), patterns);
-    }
-
     pub fn add_custom_pattern(&mut self, language: String, pattern: TrainingErrorPattern) {
         self.patterns
             .entry(language)
@@ -0,0 +1,203 @@
+//! Compile verification of synthetic errors
+//!
+//! A synthetic pair is only useful if the injected error is one a developer
+//! would actually meet. [`CompileVerifier`] runs the language's own compiler
+//! on the clean and the mutated code: compile-stage errors must turn a
+//! compiling file into a failing one, and runtime-stage errors must leave it
+//! compiling.
+
+use anyhow::{anyhow, Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::ai_training::mutation::ErrorStage;
+use crate::core::constants::languages;
+
+static SCRATCH_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+static RUST_ERROR: Lazy<Regex> = Lazy::new(|| Regex::new(r"^error(?:\[\w+\])?: (.+)$").unwrap());
+static PYTHON_ERROR: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(\w+Error: .+?)(?: \([^()]*, line \d+\))?$").unwrap());
+static GO_ERROR: Lazy<Regex> = Lazy::new(|| Regex::new(r"\.go:\d+:\d+: (.+)$").unwrap());
+static TYPESCRIPT_ERROR: Lazy<Regex> = Lazy::new(|| Regex::new(r"error (TS\d+: .+)$").unwrap());
+
+/// Result of compiling one piece of code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompileOutcome {
+    Success,
+    /// Compilation failed; carries the first error message
+    Failure(String),
+}
+
+/// Whether a mutation produced the error it was meant to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verification {
+    /// The error is real; compile-stage errors carry the compiler's message
+    Confirmed { compiler_error: Option<String> },
+    /// The mutation compiled when it should not have, or the reverse
+    Rejected(String),
+}
+
+/// Checks injected errors with the language toolchain
+#[derive(Debug, Clone, Default)]
+pub struct CompileVerifier;
+
+impl CompileVerifier {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Whether verification is implemented for a language
+    pub fn supports(language: &str) -> bool {
+        toolchain(language).is_some()
+    }
+
+    /// Compile a snippet in a scratch directory
+    pub fn compile(&self, language: &str, code: &str) -> Result<CompileOutcome> {
+        let tool = toolchain(language)
+            .ok_or_else(|| anyhow!("Compile verification is not available for {language}"))?;
+
+        let dir = std::env::temp_dir().join(format!(
+            "lspbridge-verify-{}-{}",
+            std::process::id(),
+            SCRATCH_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&dir)?;
+        let result = tool.run(&dir, code);
+        let _ = std::fs::remove_dir_all(&dir);
+        result
+    }
+
+    /// Fail unless the code compiles cleanly
+    pub fn ensure_compiles(&self, language: &str, code: &str) -> Result<()> {
+        match self.compile(language, code)? {
+            CompileOutcome::Success => Ok(()),
+            CompileOutcome::Failure(error) => Err(anyhow!("Base code does not compile: {error}")),
+        }
+    }
+
+    /// Check that mutated code fails, or keeps compiling, as `stage` says it should
+    ///
+    /// Only meaningful once the clean code passed [`Self::ensure_compiles`].
+    pub fn verify(&self, language: &str, mutated: &str, stage: ErrorStage) -> Result<Verification> {
+        Ok(match (stage, self.compile(language, mutated)?) {
+            (ErrorStage::Compile, CompileOutcome::Failure(error)) => Verification::Confirmed {
+                compiler_error: Some(error),
+            },
+            (ErrorStage::Compile, CompileOutcome::Success) => {
+                Verification::Rejected("Mutated code still compiles".to_string())
+            }
+            (ErrorStage::Runtime, CompileOutcome::Success) => Verification::Confirmed {
+                compiler_error: None,
+            },
+            (ErrorStage::Runtime, CompileOutcome::Failure(error)) => {
+                Verification::Rejected(format!("Runtime error mutation fails to compile: {error}"))
+            }
+        })
+    }
+}
+
+struct Toolchain {
+    program: &'static str,
+    args: &'static [&'static str],
+    file_name: &'static str,
+    error: &'static Lazy<Regex>,
+}
+
+fn toolchain(language: &str) -> Option<Toolchain> {
+    match language {
+        languages::RUST => Some(Toolchain {
+            program: "rustc",
+            args: &["--edition", "2021", "--crate-type", "lib", "--emit", "metadata", "-A", "warnings"],
+            file_name: "snippet.rs",
+            error: &RUST_ERROR,
+        }),
+        languages::PYTHON => Some(Toolchain {
+            program: "python3",
+            args: &["-m", "py_compile"],
+            file_name: "snippet.py",
+            error: &PYTHON_ERROR,
+        }),
+        languages::GO => Some(Toolchain {
+            program: "go",
+            args: &["build", "-o", "snippet.out"],
+            file_name: "snippet.go",
+            error: &GO_ERROR,
+        }),
+        languages::TYPESCRIPT => Some(Toolchain {
+            program: "tsc",
+            args: &["--noEmit", "--pretty", "false"],
+            file_name: "snippet.ts",
+            error: &TYPESCRIPT_ERROR,
+        }),
+        _ => None,
+    }
+}
+
+impl Toolchain {
+    fn run(&self, dir: &Path, code: &str) -> Result<CompileOutcome> {
+        std::fs::write(dir.join(self.file_name), code)?;
+        let output = Command::new(self.program)
+            .args(self.args)
+            .arg(self.file_name)
+            .current_dir(dir)
+            .output()
+            .with_context(|| format!("Failed to run {}; is it installed?", self.program))?;
+
+        if output.status.success() {
+            return Ok(CompileOutcome::Success);
+        }
+
+        // tsc reports on stdout, the others on stderr
+        let text = format!(
+            "{}\n{}",
+            String::from_utf8_lossy(&output.stderr),
+            String::from_utf8_lossy(&output.stdout)
+        );
+        let error = text
+            .lines()
+            .filter(|line| !line.contains("aborting due to"))
+            .find_map(|line| self.error.captures(line.trim()).map(|caps| caps[1].to_string()))
+            .unwrap_or_else(|| format!("{} exited with {}", self.program, output.status));
+        Ok(CompileOutcome::Failure(error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rustc_available() -> bool {
+        Command::new("rustc").arg("--version").output().is_ok()
+    }
+
+    #[test]
+    fn test_rust_errors_are_checked_by_rustc() {
+        if !rustc_available() {
+            return;
+        }
+        let verifier = CompileVerifier::new();
+        let original = "pub fn f() -> usize {\n    let name = String::from(\"a\");\n    let copy = name.clone();\n    name.len() + copy.len()\n}\n";
+        let moved = original.replace("name.clone()", "name");
+
+        verifier.ensure_compiles("rust", original).unwrap();
+        match verifier.verify("rust", &moved, ErrorStage::Compile).unwrap() {
+            Verification::Confirmed { compiler_error } => {
+                assert_eq!(compiler_error.as_deref(), Some("borrow of moved value: `name`"));
+            }
+            other => panic!("expected confirmation, got {other:?}"),
+        }
+
+        // Renaming a binding everywhere is not an error at all
+        let renamed = original.replace("copy", "other");
+        assert!(matches!(
+            verifier.verify("rust", &renamed, ErrorStage::Compile).unwrap(),
+            Verification::Rejected(_)
+        ));
+
+        assert!(verifier.ensure_compiles("rust", "fn broken(").is_err());
+    }
+}
@@ -191,6 +191,222 @@ impl fmt::Display for RustErrorCode {
     }
 }
 
+/// Java problem IDs reported by the Eclipse JDT compiler in `jdtls`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JavaErrorCode {
+    // Null analysis
+    /// Null pointer access: the variable can only be null
+    NullLocalVariableReference = 536871363,
+    /// Potential null pointer access: the variable may be null
+    PotentialNullLocalVariableReference = 536871364,
+    /// Null type mismatch: a null value where `@NonNull` is required
+    RequiredNonNullButProvidedNull = 16778126,
+
+    // Unresolved symbols
+    /// Type cannot be resolved to a type
+    UndefinedType = 16777218,
+    /// Name cannot be resolved
+    UndefinedName = 570425394,
+    /// The method is undefined for the type
+    UndefinedMethod = 67108964,
+    /// Name cannot be resolved or is not a field
+    UndefinedField = 33554502,
+    /// The import cannot be resolved
+    ImportNotFound = 268435846,
+
+    // Type and generics errors
+    /// Type mismatch: cannot convert from one type to another
+    TypeMismatch = 16777233,
+}
+
+impl std::str::FromStr for JavaErrorCode {
+    type Err = String;
+
+    /// Parse from a JDT problem ID
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        match code {
+            "536871363" => Ok(Self::NullLocalVariableReference),
+            "536871364" => Ok(Self::PotentialNullLocalVariableReference),
+            "16778126" => Ok(Self::RequiredNonNullButProvidedNull),
+            "16777218" => Ok(Self::UndefinedType),
+            "570425394" => Ok(Self::UndefinedName),
+            "67108964" => Ok(Self::UndefinedMethod),
+            "33554502" => Ok(Self::UndefinedField),
+            "268435846" => Ok(Self::ImportNotFound),
+            "16777233" => Ok(Self::TypeMismatch),
+            _ => Err(format!("Unknown JDT problem ID: {code}")),
+        }
+    }
+}
+
+impl JavaErrorCode {
+    /// Get the problem ID as a string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NullLocalVariableReference => "536871363",
+            Self::PotentialNullLocalVariableReference => "536871364",
+            Self::RequiredNonNullButProvidedNull => "16778126",
+            Self::UndefinedType => "16777218",
+            Self::UndefinedName => "570425394",
+            Self::UndefinedMethod => "67108964",
+            Self::UndefinedField => "33554502",
+            Self::ImportNotFound => "268435846",
+            Self::TypeMismatch => "16777233",
+        }
+    }
+
+    /// Check if this is a null analysis error
+    pub fn is_null_safety_error(&self) -> bool {
+        matches!(
+            self,
+            Self::NullLocalVariableReference
+                | Self::PotentialNullLocalVariableReference
+                | Self::RequiredNonNullButProvidedNull
+        )
+    }
+
+    /// Check if this is an unresolved symbol error
+    pub fn is_unresolved_error(&self) -> bool {
+        matches!(
+            self,
+            Self::UndefinedType
+                | Self::UndefinedName
+                | Self::UndefinedMethod
+                | Self::UndefinedField
+                | Self::ImportNotFound
+        )
+    }
+}
+
+impl fmt::Display for JavaErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Kotlin compiler diagnostic names reported by `kotlin-language-server`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KotlinErrorCode {
+    // Null safety
+    /// Only safe or non-null asserted calls are allowed on a nullable receiver
+    UnsafeCall,
+    /// Operator call on a nullable receiver
+    UnsafeOperatorCall,
+    /// Invoking a nullable function type
+    UnsafeImplicitInvokeCall,
+    /// Null can not be a value of a non-null type
+    NullForNonnullType,
+    /// Smart cast is impossible because the value could have changed
+    SmartcastImpossible,
+    /// Unnecessary safe call on a non-null receiver
+    UnnecessarySafeCall,
+    /// Unnecessary non-null assertion on a non-null receiver
+    UnnecessaryNotNullAssertion,
+
+    // Unresolved symbols
+    /// Unresolved reference
+    UnresolvedReference,
+    /// Unresolved reference with a receiver of the wrong type
+    UnresolvedReferenceWrongReceiver,
+
+    // Type and generics errors
+    /// Type mismatch between inferred and expected type
+    TypeMismatch,
+    /// Wrong number of type arguments
+    WrongNumberOfTypeArguments,
+    /// Type argument is not within its bounds
+    UpperBoundViolated,
+    /// Not enough information to infer a type variable
+    NoInformationForParameter,
+    /// Unchecked cast to a generic type
+    UncheckedCast,
+}
+
+impl std::str::FromStr for KotlinErrorCode {
+    type Err = String;
+
+    /// Parse from a diagnostic name (e.g., "UNSAFE_CALL")
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        match code {
+            "UNSAFE_CALL" => Ok(Self::UnsafeCall),
+            "UNSAFE_OPERATOR_CALL" => Ok(Self::UnsafeOperatorCall),
+            "UNSAFE_IMPLICIT_INVOKE_CALL" => Ok(Self::UnsafeImplicitInvokeCall),
+            "NULL_FOR_NONNULL_TYPE" => Ok(Self::NullForNonnullType),
+            "SMARTCAST_IMPOSSIBLE" => Ok(Self::SmartcastImpossible),
+            "UNNECESSARY_SAFE_CALL" => Ok(Self::UnnecessarySafeCall),
+            "UNNECESSARY_NOT_NULL_ASSERTION" => Ok(Self::UnnecessaryNotNullAssertion),
+            "UNRESOLVED_REFERENCE" => Ok(Self::UnresolvedReference),
+            "UNRESOLVED_REFERENCE_WRONG_RECEIVER" => Ok(Self::UnresolvedReferenceWrongReceiver),
+            "TYPE_MISMATCH" => Ok(Self::TypeMismatch),
+            "WRONG_NUMBER_OF_TYPE_ARGUMENTS" => Ok(Self::WrongNumberOfTypeArguments),
+            "UPPER_BOUND_VIOLATED" => Ok(Self::UpperBoundViolated),
+            "TYPE_INFERENCE_NO_INFORMATION_FOR_PARAMETER" | "NEW_INFERENCE_NO_INFORMATION_FOR_PARAMETER" => {
+                Ok(Self::NoInformationForParameter)
+            }
+            "UNCHECKED_CAST" => Ok(Self::UncheckedCast),
+            _ => Err(format!("Unknown Kotlin diagnostic name: {code}")),
+        }
+    }
+}
+
+impl KotlinErrorCode {
+    /// Get the diagnostic name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::UnsafeCall => "UNSAFE_CALL",
+            Self::UnsafeOperatorCall => "UNSAFE_OPERATOR_CALL",
+            Self::UnsafeImplicitInvokeCall => "UNSAFE_IMPLICIT_INVOKE_CALL",
+            Self::NullForNonnullType => "NULL_FOR_NONNULL_TYPE",
+            Self::SmartcastImpossible => "SMARTCAST_IMPOSSIBLE",
+            Self::UnnecessarySafeCall => "UNNECESSARY_SAFE_CALL",
+            Self::UnnecessaryNotNullAssertion => "UNNECESSARY_NOT_NULL_ASSERTION",
+            Self::UnresolvedReference => "UNRESOLVED_REFERENCE",
+            Self::UnresolvedReferenceWrongReceiver => "UNRESOLVED_REFERENCE_WRONG_RECEIVER",
+            Self::TypeMismatch => "TYPE_MISMATCH",
+            Self::WrongNumberOfTypeArguments => "WRONG_NUMBER_OF_TYPE_ARGUMENTS",
+            Self::UpperBoundViolated => "UPPER_BOUND_VIOLATED",
+            Self::NoInformationForParameter => "NEW_INFERENCE_NO_INFORMATION_FOR_PARAMETER",
+            Self::UncheckedCast => "UNCHECKED_CAST",
+        }
+    }
+
+    /// Check if this is a null safety error
+    pub fn is_null_safety_error(&self) -> bool {
+        matches!(
+            self,
+            Self::UnsafeCall
+                | Self::UnsafeOperatorCall
+                | Self::UnsafeImplicitInvokeCall
+                | Self::NullForNonnullType
+                | Self::SmartcastImpossible
+                | Self::UnnecessarySafeCall
+                | Self::UnnecessaryNotNullAssertion
+        )
+    }
+
+    /// Check if this is an unresolved symbol error
+    pub fn is_unresolved_error(&self) -> bool {
+        matches!(self, Self::UnresolvedReference | Self::UnresolvedReferenceWrongReceiver)
+    }
+
+    /// Check if this is a generics error
+    pub fn is_generics_error(&self) -> bool {
+        matches!(
+            self,
+            Self::WrongNumberOfTypeArguments
+                | Self::UpperBoundViolated
+                | Self::NoInformationForParameter
+                | Self::UncheckedCast
+        )
+    }
+}
+
+impl fmt::Display for KotlinErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 /// Python error codes (from various linters/type checkers)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PythonErrorCode {
@@ -220,6 +436,8 @@ pub enum PythonErrorCode {
 pub enum ErrorCode {
     TypeScript(TypeScriptErrorCode),
     Rust(RustErrorCode),
+    Java(JavaErrorCode),
+    Kotlin(KotlinErrorCode),
     Python(PythonErrorCode),
     /// Unknown or custom error code
     Custom(String),
@@ -239,6 +457,16 @@ impl ErrorCode {
                     .map(ErrorCode::Rust)
                     .unwrap_or_else(|| ErrorCode::Custom(code.to_string()))
             }
+            "java" | "jdtls" => {
+                code.parse::<JavaErrorCode>()
+                    .map(ErrorCode::Java)
+                    .unwrap_or_else(|_| ErrorCode::Custom(code.to_string()))
+            }
+            "kotlin" | "kotlin-language-server" => {
+                code.parse::<KotlinErrorCode>()
+                    .map(ErrorCode::Kotlin)
+                    .unwrap_or_else(|_| ErrorCode::Custom(code.to_string()))
+            }
             "python" | "mypy" | "pylint" | "pyright" => {
                 // Python doesn't have standardized numeric codes
                 ErrorCode::Custom(code.to_string())
@@ -252,6 +480,8 @@ impl ErrorCode {
         match self {
             ErrorCode::TypeScript(ts) => ts.as_str(),
             ErrorCode::Rust(rust) => rust.as_str(),
+            ErrorCode::Java(java) => java.as_str(),
+            ErrorCode::Kotlin(kotlin) => kotlin.as_str(),
             ErrorCode::Python(_) => "Python",
             ErrorCode::Custom(s) => s,
         }
@@ -263,6 +493,8 @@ impl fmt::Display for ErrorCode {
         match self {
             ErrorCode::TypeScript(ts) => write!(f, "{ts}"),
             ErrorCode::Rust(rust) => write!(f, "{rust}"),
+            ErrorCode::Java(java) => write!(f, "{java}"),
+            ErrorCode::Kotlin(kotlin) => write!(f, "{kotlin}"),
             ErrorCode::Python(py) => write!(f, "{py:?}"),
             ErrorCode::Custom(s) => write!(f, "{s}"),
         }
@@ -301,6 +533,28 @@ mod tests {
         assert!(RustErrorCode::MissingLifetimeSpecifier.is_lifetime_error());
     }
     
+    #[test]
+    fn test_jvm_error_codes() {
+        assert_eq!(
+            "16777218".parse::<JavaErrorCode>(),
+            Ok(JavaErrorCode::UndefinedType)
+        );
+        assert_eq!(JavaErrorCode::UndefinedType as i64, 16777218);
+        assert!(JavaErrorCode::PotentialNullLocalVariableReference.is_null_safety_error());
+        assert!(JavaErrorCode::ImportNotFound.is_unresolved_error());
+
+        assert_eq!(
+            "TYPE_INFERENCE_NO_INFORMATION_FOR_PARAMETER".parse::<KotlinErrorCode>(),
+            Ok(KotlinErrorCode::NoInformationForParameter)
+        );
+        assert_eq!(KotlinErrorCode::UnsafeCall.as_str(), "UNSAFE_CALL");
+        assert!(KotlinErrorCode::UpperBoundViolated.is_generics_error());
+        assert!(matches!(
+            ErrorCode::parse("UNRESOLVED_REFERENCE", "kotlin"),
+            ErrorCode::Kotlin(KotlinErrorCode::UnresolvedReference)
+        ));
+    }
+
     #[test]
     fn test_error_code_parsing() {
         let ts_code = ErrorCode::parse("2339", "typescript");
@@ -0,0 +1,153 @@
+use super::super::JvmLanguage;
+use crate::analyzers::base::AnalyzerBase;
+use crate::analyzers::language_analyzer::{DiagnosticAnalysis, DiagnosticCategory};
+use crate::core::{Diagnostic, SemanticContext};
+use regex::Regex;
+
+pub struct GenericsAnalyzer;
+
+impl AnalyzerBase for GenericsAnalyzer {}
+
+impl Default for GenericsAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GenericsAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Analyze a type mismatch, treating mismatched type arguments as a generics error
+    pub fn analyze_type_mismatch(
+        &self,
+        diagnostic: &Diagnostic,
+        language: JvmLanguage,
+        context: Option<&SemanticContext>,
+    ) -> DiagnosticAnalysis {
+        let types = mismatched_types(&diagnostic.message);
+        if types.iter().any(|ty| ty.contains('<')) {
+            return self.analyze_generics(diagnostic, language, context);
+        }
+
+        let mut analysis = self.create_analysis(
+            DiagnosticCategory::TypeMismatch,
+            0.8,
+            2,
+            "Value has a different type than expected".to_string(),
+            types.clone(),
+        );
+        if let [from, to] = types.as_slice() {
+            analysis.insights.push(format!("Convert the {from} value to {to} or change the declared type"));
+        }
+        analysis
+    }
+
+    pub fn analyze_generics(
+        &self,
+        diagnostic: &Diagnostic,
+        language: JvmLanguage,
+        _context: Option<&SemanticContext>,
+    ) -> DiagnosticAnalysis {
+        let message = &diagnostic.message;
+        let mut analysis = self.create_analysis(
+            DiagnosticCategory::GenericTypeError,
+            0.8,
+            3,
+            "Type arguments do not match the generic declaration".to_string(),
+            generic_types(message),
+        );
+
+        if message.contains("raw type") {
+            analysis.likely_cause = "Generic type used without type arguments".to_string();
+            analysis.fix_complexity = 1;
+            self.add_insight(&mut analysis, "Add type arguments to the raw type, e.g. List<String>");
+        } else if message.contains("Unchecked cast") || message.contains("unchecked conversion") {
+            analysis.likely_cause = "Type arguments are erased at runtime and cannot be checked".to_string();
+            analysis.confidence = 0.7;
+            self.add_insight(&mut analysis, "Avoid the cast by typing the source value precisely");
+            self.add_insight(
+                &mut analysis,
+                match language {
+                    JvmLanguage::Java => "If the cast is safe, suppress it with @SuppressWarnings(\"unchecked\")",
+                    JvmLanguage::Kotlin => "If the cast is safe, suppress it with @Suppress(\"UNCHECKED_CAST\")",
+                },
+            );
+        } else if message.contains("Bound mismatch") || message.contains("not within its bounds") {
+            analysis.likely_cause = "Type argument does not satisfy the type parameter's bound".to_string();
+            self.add_insight(&mut analysis, "Use a type argument that satisfies the declared upper bound");
+        } else if message.contains("Incorrect number of arguments") || message.contains("type argument") {
+            analysis.likely_cause = "Wrong number of type arguments".to_string();
+            analysis.fix_complexity = 2;
+            if let Some(count) = expected_argument_count(message) {
+                analysis.insights.push(format!("The type expects {count} type argument(s)"));
+            }
+        } else if message.contains("Not enough information to infer") {
+            analysis.likely_cause = "Type argument cannot be inferred".to_string();
+            analysis.fix_complexity = 1;
+            self.add_insight(&mut analysis, "Specify the type argument explicitly, e.g. emptyList<String>()");
+        } else if message.contains("cannot convert from") || message.contains("inferred type is") {
+            analysis.likely_cause = "Generic types differ in their type arguments".to_string();
+            self.add_insight(&mut analysis, "Generic types are invariant: List<Object> is not a List<String>");
+            if language == JvmLanguage::Java {
+                self.add_insight(&mut analysis, "Use a wildcard such as List<? extends T> to accept subtypes");
+            } else {
+                self.add_insight(&mut analysis, "Declare the type parameter with out or in variance");
+            }
+        }
+
+        analysis
+    }
+}
+
+/// The actual and expected types of a type mismatch, in that order
+fn mismatched_types(message: &str) -> Vec<String> {
+    let patterns = [
+        r"cannot convert from (.+?) to (.+?)$",
+        r"inferred type is (.+?) but (.+?) was expected",
+        r"Type mismatch: actual type is '?(.+?)'?, but '?(.+?)'? was expected",
+    ];
+
+    for pattern in patterns {
+        if let Some(cap) = Regex::new(pattern).unwrap().captures(message.trim()) {
+            return cap.iter().skip(1).flatten().map(|m| m.as_str().to_string()).collect();
+        }
+    }
+    Vec::new()
+}
+
+/// Generic types named in a generics message
+fn generic_types(message: &str) -> Vec<String> {
+    let types = mismatched_types(message);
+    if !types.is_empty() {
+        return types;
+    }
+
+    let patterns = [
+        r"(\w+) is a raw type",
+        r"Unchecked cast(?: from|:) (.+?) to (.+?)$",
+        r"Incorrect number of arguments for type ([^;]+);",
+        r"expected for (?:class|interface) ([\w.<>, ]+)",
+        r"Bound mismatch: The type (\S+) is not a valid substitute",
+        r"infer type (?:variable|parameter) (\w+)",
+    ];
+    for pattern in patterns {
+        if let Some(cap) = Regex::new(pattern).unwrap().captures(message.trim()) {
+            return cap.iter().skip(1).flatten().map(|m| m.as_str().trim().to_string()).collect();
+        }
+    }
+    Vec::new()
+}
+
+/// Number of type arguments a Kotlin arity message says are expected
+fn expected_argument_count(message: &str) -> Option<usize> {
+    let cap = Regex::new(r"(\w+) type arguments? expected").unwrap().captures(message)?;
+    let count = cap.get(1)?.as_str();
+    match count {
+        "One" => Some(1),
+        "Two" => Some(2),
+        "Three" => Some(3),
+        _ => count.parse().ok(),
+    }
+}
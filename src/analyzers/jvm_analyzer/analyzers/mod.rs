@@ -0,0 +1,7 @@
+pub mod generics;
+pub mod null_safety;
+pub mod symbols;
+
+pub use generics::GenericsAnalyzer;
+pub use null_safety::NullSafetyAnalyzer;
+pub use symbols::SymbolResolutionAnalyzer;
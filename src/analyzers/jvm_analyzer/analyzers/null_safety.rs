@@ -0,0 +1,112 @@
+use super::super::JvmLanguage;
+use crate::analyzers::base::AnalyzerBase;
+use crate::analyzers::language_analyzer::{DiagnosticAnalysis, DiagnosticCategory};
+use crate::core::{Diagnostic, SemanticContext};
+use regex::Regex;
+
+pub struct NullSafetyAnalyzer;
+
+impl AnalyzerBase for NullSafetyAnalyzer {}
+
+impl Default for NullSafetyAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NullSafetyAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn analyze_null_safety(
+        &self,
+        diagnostic: &Diagnostic,
+        language: JvmLanguage,
+        context: Option<&SemanticContext>,
+    ) -> DiagnosticAnalysis {
+        let message = &diagnostic.message;
+        let mut analysis = self.create_analysis(
+            DiagnosticCategory::NullSafety,
+            0.85,
+            2,
+            "Value may be null where a non-null value is required".to_string(),
+            null_symbols(message),
+        );
+
+        match language {
+            JvmLanguage::Java => {
+                if message.contains("can only be null") {
+                    analysis.likely_cause = "Variable is always null at this point".to_string();
+                    analysis.confidence = 0.9;
+                    self.add_insight(&mut analysis, "The variable is dereferenced on a path where it is always null");
+                    self.add_insight(&mut analysis, "Assign a value before use or move the access into a non-null branch");
+                } else if message.contains("may be null") {
+                    self.add_insight(&mut analysis, "Add a null check before dereferencing the variable");
+                    self.add_insight(
+                        &mut analysis,
+                        "Or use Objects.requireNonNull() to fail fast with a clear message",
+                    );
+                } else if message.contains("Null type mismatch") || message.contains("Null type safety") {
+                    analysis.likely_cause = "Null passed where a @NonNull value is required".to_string();
+                    self.add_insight(&mut analysis, "Pass a non-null value or annotate the target as @Nullable");
+                }
+            }
+            JvmLanguage::Kotlin => {
+                if message.contains("Smart cast") {
+                    analysis.likely_cause = "Value could change between the null check and its use".to_string();
+                    analysis.fix_complexity = 1;
+                    self.add_insight(&mut analysis, "Smart casts do not apply to mutable or open properties");
+                    self.add_insight(&mut analysis, "Copy the property into a local val before checking it");
+                } else if message.contains("Null can not be a value of a non-null type") {
+                    analysis.likely_cause = "Null assigned to a non-null type".to_string();
+                    self.add_insight(&mut analysis, "Make the type nullable or provide a non-null default");
+                } else if message.contains("Unnecessary") {
+                    analysis.category = DiagnosticCategory::CodeQuality;
+                    analysis.likely_cause = "Null check on a value that is never null".to_string();
+                    analysis.fix_complexity = 1;
+                    self.add_insight(&mut analysis, "Remove the redundant safe call or !! assertion");
+                } else {
+                    self.add_insight(&mut analysis, "Use a safe call (?.) and handle null with the elvis operator (?:)");
+                    self.add_insight(&mut analysis, "Avoid !! unless the value is guaranteed to be non-null");
+                }
+            }
+        }
+
+        // A variable declared with a nullable type is expected to be null sometimes
+        if let Some(ctx) = context {
+            let declared_nullable = ctx.local_variables.iter().any(|var| {
+                analysis.related_symbols.contains(&var.name)
+                    && var
+                        .type_annotation
+                        .as_ref()
+                        .is_some_and(|ty| ty.ends_with('?') || ty.contains("@Nullable"))
+            });
+            if declared_nullable {
+                self.add_insight(&mut analysis, "The variable is declared nullable; handle the null case explicitly");
+            }
+        }
+
+        analysis
+    }
+}
+
+/// Variables and types named in a null-safety message
+fn null_symbols(message: &str) -> Vec<String> {
+    let patterns = [
+        r"The variable (\w+) (?:can only|may) be null",
+        r"required '([^']+)'",
+        r"receiver of type ([\w.<>, ]+\?)",
+        r"non-null type ([\w.<>, ]+)",
+        r"Smart cast to '([^']+)' is impossible, because '([^']+)'",
+    ];
+
+    let mut symbols = Vec::new();
+    for pattern in patterns {
+        let re = Regex::new(pattern).unwrap();
+        for cap in re.captures_iter(message) {
+            symbols.extend(cap.iter().skip(1).flatten().map(|m| m.as_str().trim().to_string()));
+        }
+    }
+    symbols
+}
@@ -0,0 +1,116 @@
+use super::super::JvmLanguage;
+use crate::analyzers::base::{AnalyzerBase, DiagnosticPatterns};
+use crate::analyzers::language_analyzer::{self, DiagnosticAnalysis, DiagnosticCategory};
+use crate::core::{Diagnostic, SemanticContext};
+use regex::Regex;
+
+pub struct SymbolResolutionAnalyzer;
+
+impl AnalyzerBase for SymbolResolutionAnalyzer {}
+
+impl Default for SymbolResolutionAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SymbolResolutionAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn analyze_unresolved(
+        &self,
+        diagnostic: &Diagnostic,
+        language: JvmLanguage,
+        context: Option<&SemanticContext>,
+    ) -> DiagnosticAnalysis {
+        let message = &diagnostic.message;
+        let symbols = unresolved_symbols(message);
+
+        let (category, cause) = if message.starts_with("The import") || message.contains("unresolved import") {
+            (DiagnosticCategory::MissingImport, "Imported package or class not found on the classpath")
+        } else if message.contains("cannot be resolved to a type") {
+            (DiagnosticCategory::UndefinedType, "Type is not imported or does not exist")
+        } else if message.contains("is undefined for the type") {
+            (DiagnosticCategory::MissingProperty, "Method does not exist on the receiver type")
+        } else {
+            (DiagnosticCategory::UndefinedVariable, "Symbol is not declared or not imported")
+        };
+
+        let mut analysis = self.create_analysis(category.clone(), 0.85, 2, cause.to_string(), symbols.clone());
+        analysis.is_cascading = true; // Unresolved symbols often cause follow-up errors
+
+        match category {
+            DiagnosticCategory::MissingImport => {
+                self.add_insight(&mut analysis, "Check that the dependency is declared in the build file");
+                self.add_insight(&mut analysis, "Reimport the project if the dependency was just added");
+            }
+            DiagnosticCategory::UndefinedType => {
+                self.add_insight(&mut analysis, "Add an import for the type or fix its package");
+            }
+            DiagnosticCategory::MissingProperty => {
+                if let Some(receiver) = symbols.get(1) {
+                    analysis
+                        .insights
+                        .push(format!("Check the methods available on '{receiver}' and their parameter types"));
+                }
+            }
+            _ => {
+                if language == JvmLanguage::Kotlin {
+                    self.add_insight(&mut analysis, "Extension functions must be imported explicitly");
+                }
+            }
+        }
+
+        let Some(symbol) = symbols.first() else {
+            return analysis;
+        };
+        if let Some(ctx) = context {
+            let imported = ctx
+                .imports
+                .iter()
+                .any(|import| import.imported_names.contains(symbol) || import.statement.ends_with(symbol.as_str()));
+            if imported {
+                analysis.likely_cause = "Symbol is imported but could not be resolved".to_string();
+                self.add_insight(&mut analysis, "The import exists; the dependency may be missing from the classpath");
+            } else if let Some(similar) = language_analyzer::DiagnosticPatterns::find_similar_name(symbol, &names_in_scope(ctx)) {
+                if &similar != symbol {
+                    analysis.insights.insert(0, format!("Did you mean '{similar}'?"));
+                    analysis.fix_complexity = 1;
+                }
+            }
+        }
+
+        analysis
+    }
+}
+
+/// Symbols named in an unresolved symbol message, the unresolved name first
+fn unresolved_symbols(message: &str) -> Vec<String> {
+    let patterns = [
+        r"The import ([\w.]+) cannot be resolved",
+        r"The method (\w+)\(.*?\) is undefined for the type (\w+)",
+        r"^(\w+) cannot be resolved",
+        r"Unresolved reference:? '?(\w+)'?",
+    ];
+
+    for pattern in patterns {
+        if let Some(cap) = Regex::new(pattern).unwrap().captures(message) {
+            return cap.iter().skip(1).flatten().map(|m| m.as_str().to_string()).collect();
+        }
+    }
+    DiagnosticPatterns::extract_quoted_identifiers(message)
+}
+
+/// Names declared near the diagnostic, candidates for typo suggestions
+fn names_in_scope(context: &SemanticContext) -> Vec<String> {
+    let mut names: Vec<String> = context.local_variables.iter().map(|var| var.name.clone()).collect();
+    names.extend(context.type_definitions.iter().map(|ty| ty.name.clone()));
+    if let Some(class) = &context.class_context {
+        names.extend(class.fields.iter().cloned());
+        names.extend(class.methods.iter().cloned());
+    }
+    names.extend(context.imports.iter().flat_map(|import| import.imported_names.iter().cloned()));
+    names
+}
@@ -0,0 +1,68 @@
+use super::JvmLanguage;
+use crate::analyzers::base::DiagnosticPatterns;
+use crate::analyzers::language_analyzer::ContextRequirements;
+use crate::core::constants::config_files;
+use crate::core::Diagnostic;
+use regex::Regex;
+
+pub struct JvmContextAnalyzer;
+
+impl Default for JvmContextAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JvmContextAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn extract_context_requirements(&self, diagnostic: &Diagnostic, language: JvmLanguage) -> ContextRequirements {
+        let mut requirements = ContextRequirements::default();
+        let message = &diagnostic.message;
+
+        let identifiers = DiagnosticPatterns::extract_quoted_identifiers(message);
+        requirements.required_symbols.extend(identifiers);
+
+        // Types named in the message, e.g. "for the type Foo" or "List<String>"
+        let type_pattern = Regex::new(r"\b([A-Z]\w*)(?:<[^>]*>)?").unwrap();
+        for cap in type_pattern.captures_iter(message) {
+            let name = cap[1].to_string();
+            if !requirements.required_types.contains(&name) && !is_message_word(&name) {
+                requirements.required_types.push(name);
+            }
+        }
+
+        // Unresolved imports usually mean a missing dependency
+        if let Some(cap) = Regex::new(r"The import ([\w.]+) cannot be resolved").unwrap().captures(message) {
+            requirements.dependencies.push(format!("Package providing {}", &cap[1]));
+        }
+        if message.contains("cannot be resolved") || message.contains("Unresolved reference") {
+            requirements.config_files.push(config_files::BUILD_GRADLE.to_string());
+            if language == JvmLanguage::Java {
+                requirements.config_files.push(config_files::POM_XML.to_string());
+            } else {
+                requirements.config_files.push(format!("{}.kts", config_files::BUILD_GRADLE));
+            }
+        }
+
+        // Null analysis depends on the annotations in use
+        if message.contains("@NonNull") || message.contains("@Nullable") {
+            requirements
+                .required_symbols
+                .push("_nullness_annotations".to_string());
+        }
+
+        requirements
+    }
+}
+
+/// Capitalized words that start compiler messages rather than name types
+fn is_message_word(word: &str) -> bool {
+    [
+        "The", "Type", "Null", "Potential", "Unresolved", "Only", "Smart", "Unchecked", "Incorrect", "Bound",
+        "References", "Not", "One", "Two", "Three", "Unnecessary",
+    ]
+    .contains(&word)
+}
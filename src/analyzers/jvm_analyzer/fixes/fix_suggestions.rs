@@ -0,0 +1,278 @@
+use super::super::JvmLanguage;
+use crate::analyzers::language_analyzer::{DiagnosticCategory, FixSuggestion};
+use crate::core::Diagnostic;
+
+/// Well-known classes and the imports that provide them
+const JAVA_IMPORTS: &[(&str, &str)] = &[
+    ("List", "java.util.List"),
+    ("ArrayList", "java.util.ArrayList"),
+    ("Map", "java.util.Map"),
+    ("HashMap", "java.util.HashMap"),
+    ("Set", "java.util.Set"),
+    ("HashSet", "java.util.HashSet"),
+    ("Optional", "java.util.Optional"),
+    ("Objects", "java.util.Objects"),
+    ("Collectors", "java.util.stream.Collectors"),
+    ("Stream", "java.util.stream.Stream"),
+    ("Path", "java.nio.file.Path"),
+    ("Files", "java.nio.file.Files"),
+    ("File", "java.io.File"),
+    ("IOException", "java.io.IOException"),
+    ("LocalDate", "java.time.LocalDate"),
+    ("Instant", "java.time.Instant"),
+    ("Duration", "java.time.Duration"),
+];
+
+/// Kotlin declarations that are not imported by default
+const KOTLIN_IMPORTS: &[(&str, &str)] = &[
+    ("File", "java.io.File"),
+    ("Path", "java.nio.file.Path"),
+    ("LocalDate", "java.time.LocalDate"),
+    ("Instant", "java.time.Instant"),
+    ("runBlocking", "kotlinx.coroutines.runBlocking"),
+    ("launch", "kotlinx.coroutines.launch"),
+    ("async", "kotlinx.coroutines.async"),
+    ("delay", "kotlinx.coroutines.delay"),
+    ("withContext", "kotlinx.coroutines.withContext"),
+    ("Dispatchers", "kotlinx.coroutines.Dispatchers"),
+    ("Flow", "kotlinx.coroutines.flow.Flow"),
+    ("flowOf", "kotlinx.coroutines.flow.flowOf"),
+    ("Serializable", "kotlinx.serialization.Serializable"),
+];
+
+pub struct JvmFixSuggestionGenerator;
+
+impl Default for JvmFixSuggestionGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JvmFixSuggestionGenerator {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn suggest_fixes(
+        &self,
+        diagnostic: &Diagnostic,
+        language: JvmLanguage,
+        analysis_category: DiagnosticCategory,
+        analysis_insights: &[String],
+        related_symbols: &[String],
+    ) -> Vec<FixSuggestion> {
+        let mut suggestions = Vec::new();
+
+        // A close match in scope is the most likely fix for any unresolved name
+        if let Some(insight) = analysis_insights.iter().find(|i| i.starts_with("Did you mean")) {
+            suggestions.push(FixSuggestion {
+                description: insight.clone(),
+                code_snippet: None,
+                confidence: 0.8,
+                is_automatic: true,
+                prerequisites: vec![],
+            });
+        }
+
+        match analysis_category {
+            DiagnosticCategory::NullSafety => {
+                self.suggest_null_safety_fixes(diagnostic, language, related_symbols, &mut suggestions);
+            }
+
+            DiagnosticCategory::UndefinedType
+            | DiagnosticCategory::UndefinedVariable
+            | DiagnosticCategory::MissingImport => {
+                self.suggest_import_fixes(language, related_symbols, &mut suggestions);
+            }
+
+            DiagnosticCategory::GenericTypeError => {
+                self.suggest_generic_fixes(diagnostic, language, related_symbols, &mut suggestions);
+            }
+
+            DiagnosticCategory::CodeQuality if diagnostic.message.contains("Unnecessary") => {
+                suggestions.push(FixSuggestion {
+                    description: "Remove the redundant null check".to_string(),
+                    code_snippet: None,
+                    confidence: 0.85,
+                    is_automatic: true,
+                    prerequisites: vec![],
+                });
+            }
+
+            _ => {}
+        }
+
+        suggestions
+    }
+
+    fn suggest_null_safety_fixes(
+        &self,
+        diagnostic: &Diagnostic,
+        language: JvmLanguage,
+        related_symbols: &[String],
+        suggestions: &mut Vec<FixSuggestion>,
+    ) {
+        let message = &diagnostic.message;
+        match language {
+            JvmLanguage::Java => {
+                let variable = related_symbols.first().map(String::as_str).unwrap_or("value");
+                suggestions.push(FixSuggestion {
+                    description: format!("Check '{variable}' for null before using it"),
+                    code_snippet: Some(format!("if ({variable} != null) {{\n    // ...\n}}")),
+                    confidence: 0.75,
+                    is_automatic: false,
+                    prerequisites: vec!["Decide how to handle the null case".to_string()],
+                });
+                if message.contains("may be null") {
+                    suggestions.push(FixSuggestion {
+                        description: format!("Fail fast if '{variable}' is null"),
+                        code_snippet: Some(format!(
+                            "Objects.requireNonNull({variable}, \"{variable} must not be null\")"
+                        )),
+                        confidence: 0.6,
+                        is_automatic: false,
+                        prerequisites: vec!["import java.util.Objects".to_string()],
+                    });
+                }
+            }
+            JvmLanguage::Kotlin if message.contains("Smart cast") => {
+                let property = related_symbols.get(1).map(String::as_str).unwrap_or("value");
+                suggestions.push(FixSuggestion {
+                    description: format!("Copy '{property}' into a local val before the null check"),
+                    code_snippet: Some(format!("val {property} = this.{property}\nif ({property} != null) {{\n    // ...\n}}")),
+                    confidence: 0.8,
+                    is_automatic: false,
+                    prerequisites: vec![],
+                });
+                suggestions.push(FixSuggestion {
+                    description: "Scope the access with let".to_string(),
+                    code_snippet: Some(format!("{property}?.let {{ value ->\n    // ...\n}}")),
+                    confidence: 0.75,
+                    is_automatic: false,
+                    prerequisites: vec![],
+                });
+            }
+            JvmLanguage::Kotlin if message.contains("non-null type") => {
+                let ty = related_symbols.first().map(String::as_str).unwrap_or("T");
+                suggestions.push(FixSuggestion {
+                    description: format!("Declare the type as nullable ({ty}?)"),
+                    code_snippet: Some(format!("{ty}?")),
+                    confidence: 0.7,
+                    is_automatic: false,
+                    prerequisites: vec!["Callers must handle null".to_string()],
+                });
+            }
+            JvmLanguage::Kotlin => {
+                suggestions.push(FixSuggestion {
+                    description: "Use a safe call".to_string(),
+                    code_snippet: Some("?.".to_string()),
+                    confidence: 0.8,
+                    is_automatic: true,
+                    prerequisites: vec![],
+                });
+                suggestions.push(FixSuggestion {
+                    description: "Provide a default with the elvis operator".to_string(),
+                    code_snippet: Some("?: default".to_string()),
+                    confidence: 0.7,
+                    is_automatic: false,
+                    prerequisites: vec!["Choose a sensible default".to_string()],
+                });
+                suggestions.push(FixSuggestion {
+                    description: "Assert non-null (throws if null)".to_string(),
+                    code_snippet: Some("!!".to_string()),
+                    confidence: 0.4,
+                    is_automatic: true,
+                    prerequisites: vec![],
+                });
+            }
+        }
+    }
+
+    fn suggest_import_fixes(
+        &self,
+        language: JvmLanguage,
+        related_symbols: &[String],
+        suggestions: &mut Vec<FixSuggestion>,
+    ) {
+        let Some(symbol) = related_symbols.first() else {
+            return;
+        };
+        let imports = match language {
+            JvmLanguage::Java => JAVA_IMPORTS,
+            JvmLanguage::Kotlin => KOTLIN_IMPORTS,
+        };
+
+        if let Some((_, path)) = imports.iter().find(|(name, _)| name == symbol) {
+            let statement = match language {
+                JvmLanguage::Java => format!("import {path};"),
+                JvmLanguage::Kotlin => format!("import {path}"),
+            };
+            suggestions.push(FixSuggestion {
+                description: format!("Add {symbol} import"),
+                code_snippet: Some(statement),
+                confidence: 0.9,
+                is_automatic: true,
+                prerequisites: if path.starts_with("kotlinx.") {
+                    vec![format!("Dependency providing {path}")]
+                } else {
+                    vec![]
+                },
+            });
+        }
+    }
+
+    fn suggest_generic_fixes(
+        &self,
+        diagnostic: &Diagnostic,
+        language: JvmLanguage,
+        related_symbols: &[String],
+        suggestions: &mut Vec<FixSuggestion>,
+    ) {
+        let message = &diagnostic.message;
+        if message.contains("raw type") {
+            if let Some(raw) = related_symbols.first() {
+                suggestions.push(FixSuggestion {
+                    description: format!("Add type arguments to {raw}"),
+                    code_snippet: Some(format!("{raw}<?>")),
+                    confidence: 0.6,
+                    is_automatic: false,
+                    prerequisites: vec!["Knowledge of the element type".to_string()],
+                });
+            }
+        } else if message.contains("Unchecked cast") || message.contains("unchecked conversion") {
+            let annotation = match language {
+                JvmLanguage::Java => "@SuppressWarnings(\"unchecked\")",
+                JvmLanguage::Kotlin => "@Suppress(\"UNCHECKED_CAST\")",
+            };
+            suggestions.push(FixSuggestion {
+                description: "Suppress the unchecked cast warning".to_string(),
+                code_snippet: Some(annotation.to_string()),
+                confidence: 0.5,
+                is_automatic: true,
+                prerequisites: vec!["Verify the cast is safe".to_string()],
+            });
+        } else if message.contains("Not enough information to infer") {
+            let variable = related_symbols.first().map(String::as_str).unwrap_or("T");
+            suggestions.push(FixSuggestion {
+                description: format!("Specify the type argument for {variable} explicitly"),
+                code_snippet: Some("<Type>".to_string()),
+                confidence: 0.7,
+                is_automatic: false,
+                prerequisites: vec!["Knowledge of the expected type".to_string()],
+            });
+        } else if let [_, expected] = related_symbols {
+            if language == JvmLanguage::Java && expected.contains('<') && !expected.contains('?') {
+                // List<String> -> List<? extends String>
+                if let Some((base, args)) = expected.split_once('<') {
+                    suggestions.push(FixSuggestion {
+                        description: "Accept subtypes with a wildcard".to_string(),
+                        code_snippet: Some(format!("{base}<? extends {args}")),
+                        confidence: 0.5,
+                        is_automatic: false,
+                        prerequisites: vec!["The value is only read, not written".to_string()],
+                    });
+                }
+            }
+        }
+    }
+}
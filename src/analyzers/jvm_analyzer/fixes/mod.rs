@@ -0,0 +1,3 @@
+pub mod fix_suggestions;
+
+pub use fix_suggestions::JvmFixSuggestionGenerator;
@@ -0,0 +1,108 @@
+use super::analyzers::{GenericsAnalyzer, NullSafetyAnalyzer, SymbolResolutionAnalyzer};
+use super::context::JvmContextAnalyzer;
+use super::fixes::JvmFixSuggestionGenerator;
+use super::JvmLanguage;
+use crate::analyzers::base::AnalyzerBase;
+use crate::analyzers::error_codes::JavaErrorCode;
+use crate::analyzers::language_analyzer::{
+    ContextRequirements, DiagnosticAnalysis, FixSuggestion, LanguageAnalyzer,
+};
+use crate::core::{Diagnostic, SemanticContext};
+
+/// Analyzer for diagnostics from the Eclipse JDT language server (`jdtls`)
+pub struct JavaAnalyzer {
+    null_safety: NullSafetyAnalyzer,
+    symbols: SymbolResolutionAnalyzer,
+    generics: GenericsAnalyzer,
+    context_analyzer: JvmContextAnalyzer,
+    fix_generator: JvmFixSuggestionGenerator,
+}
+
+impl AnalyzerBase for JavaAnalyzer {}
+
+impl Default for JavaAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JavaAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            null_safety: NullSafetyAnalyzer::new(),
+            symbols: SymbolResolutionAnalyzer::new(),
+            generics: GenericsAnalyzer::new(),
+            context_analyzer: JvmContextAnalyzer::new(),
+            fix_generator: JvmFixSuggestionGenerator::new(),
+        }
+    }
+}
+
+impl LanguageAnalyzer for JavaAnalyzer {
+    fn analyze_diagnostic(
+        &self,
+        diagnostic: &Diagnostic,
+        context: Option<&SemanticContext>,
+    ) -> DiagnosticAnalysis {
+        let language = JvmLanguage::Java;
+
+        // Try to parse the JDT problem ID
+        if let Some(java_code) = diagnostic.code.as_deref().and_then(|code| code.parse::<JavaErrorCode>().ok()) {
+            return if java_code.is_null_safety_error() {
+                self.null_safety.analyze_null_safety(diagnostic, language, context)
+            } else if java_code.is_unresolved_error() {
+                self.symbols.analyze_unresolved(diagnostic, language, context)
+            } else {
+                self.generics.analyze_type_mismatch(diagnostic, language, context)
+            };
+        }
+
+        // Fallback to message-based analysis if no code or unrecognized code
+        let message = &diagnostic.message;
+        if message.contains("null pointer access") || message.contains("Null pointer access") || message.contains("Null type") {
+            self.null_safety.analyze_null_safety(diagnostic, language, context)
+        } else if message.contains("cannot be resolved") || message.contains("is undefined for the type") {
+            self.symbols.analyze_unresolved(diagnostic, language, context)
+        } else if message.contains("raw type")
+            || message.starts_with("Type safety:")
+            || message.starts_with("Bound mismatch")
+            || message.starts_with("Incorrect number of arguments for type")
+        {
+            self.generics.analyze_generics(diagnostic, language, context)
+        } else if message.starts_with("Type mismatch") {
+            self.generics.analyze_type_mismatch(diagnostic, language, context)
+        } else {
+            DiagnosticAnalysis::default()
+        }
+    }
+
+    fn suggest_fix(
+        &self,
+        diagnostic: &Diagnostic,
+        context: Option<&SemanticContext>,
+    ) -> Vec<FixSuggestion> {
+        let analysis = self.analyze_diagnostic(diagnostic, context);
+        self.fix_generator.suggest_fixes(
+            diagnostic,
+            JvmLanguage::Java,
+            analysis.category,
+            &analysis.insights,
+            &analysis.related_symbols,
+        )
+    }
+
+    fn extract_context_requirements(&self, diagnostic: &Diagnostic) -> ContextRequirements {
+        self.context_analyzer
+            .extract_context_requirements(diagnostic, JvmLanguage::Java)
+    }
+
+    fn language(&self) -> &str {
+        JvmLanguage::Java.as_str()
+    }
+
+    /// jdtls reports its diagnostics with source `Java`; "javascript" must not match
+    fn can_analyze(&self, diagnostic: &Diagnostic) -> bool {
+        let source = diagnostic.source.to_lowercase();
+        source == "java" || source.contains("jdtls") || diagnostic.file.ends_with(".java")
+    }
+}
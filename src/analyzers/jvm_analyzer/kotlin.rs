@@ -0,0 +1,133 @@
+use super::analyzers::{GenericsAnalyzer, NullSafetyAnalyzer, SymbolResolutionAnalyzer};
+use super::context::JvmContextAnalyzer;
+use super::fixes::JvmFixSuggestionGenerator;
+use super::JvmLanguage;
+use crate::analyzers::base::AnalyzerBase;
+use crate::analyzers::error_codes::KotlinErrorCode;
+use crate::analyzers::language_analyzer::{
+    ContextRequirements, DiagnosticAnalysis, FixSuggestion, LanguageAnalyzer,
+};
+use crate::core::{Diagnostic, SemanticContext};
+use regex::Regex;
+
+/// Analyzer for diagnostics from `kotlin-language-server`
+pub struct KotlinAnalyzer {
+    null_safety: NullSafetyAnalyzer,
+    symbols: SymbolResolutionAnalyzer,
+    generics: GenericsAnalyzer,
+    context_analyzer: JvmContextAnalyzer,
+    fix_generator: JvmFixSuggestionGenerator,
+}
+
+impl AnalyzerBase for KotlinAnalyzer {}
+
+impl Default for KotlinAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KotlinAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            null_safety: NullSafetyAnalyzer::new(),
+            symbols: SymbolResolutionAnalyzer::new(),
+            generics: GenericsAnalyzer::new(),
+            context_analyzer: JvmContextAnalyzer::new(),
+            fix_generator: JvmFixSuggestionGenerator::new(),
+        }
+    }
+
+    /// Type mismatches between `T?` and `T` are null-safety errors
+    fn analyze_type_mismatch(&self, diagnostic: &Diagnostic, context: Option<&SemanticContext>) -> DiagnosticAnalysis {
+        if is_nullable_mismatch(&diagnostic.message) {
+            self.null_safety
+                .analyze_null_safety(diagnostic, JvmLanguage::Kotlin, context)
+        } else {
+            self.generics
+                .analyze_type_mismatch(diagnostic, JvmLanguage::Kotlin, context)
+        }
+    }
+}
+
+impl LanguageAnalyzer for KotlinAnalyzer {
+    fn analyze_diagnostic(
+        &self,
+        diagnostic: &Diagnostic,
+        context: Option<&SemanticContext>,
+    ) -> DiagnosticAnalysis {
+        let language = JvmLanguage::Kotlin;
+
+        // Try to parse the compiler diagnostic name
+        if let Some(kotlin_code) = diagnostic.code.as_deref().and_then(|code| code.parse::<KotlinErrorCode>().ok()) {
+            return if kotlin_code.is_null_safety_error() {
+                self.null_safety.analyze_null_safety(diagnostic, language, context)
+            } else if kotlin_code.is_unresolved_error() {
+                self.symbols.analyze_unresolved(diagnostic, language, context)
+            } else if kotlin_code.is_generics_error() {
+                self.generics.analyze_generics(diagnostic, language, context)
+            } else {
+                self.analyze_type_mismatch(diagnostic, context)
+            };
+        }
+
+        // Fallback to message-based analysis if no code or unrecognized code
+        let message = &diagnostic.message;
+        if message.contains("nullable receiver")
+            || message.contains("non-null type")
+            || message.contains("Smart cast")
+        {
+            self.null_safety.analyze_null_safety(diagnostic, language, context)
+        } else if message.starts_with("Unresolved reference") {
+            self.symbols.analyze_unresolved(diagnostic, language, context)
+        } else if message.contains("type argument")
+            || message.contains("not within its bounds")
+            || message.contains("Not enough information to infer")
+            || message.starts_with("Unchecked cast")
+        {
+            self.generics.analyze_generics(diagnostic, language, context)
+        } else if message.starts_with("Type mismatch") {
+            self.analyze_type_mismatch(diagnostic, context)
+        } else {
+            DiagnosticAnalysis::default()
+        }
+    }
+
+    fn suggest_fix(
+        &self,
+        diagnostic: &Diagnostic,
+        context: Option<&SemanticContext>,
+    ) -> Vec<FixSuggestion> {
+        let analysis = self.analyze_diagnostic(diagnostic, context);
+        self.fix_generator.suggest_fixes(
+            diagnostic,
+            JvmLanguage::Kotlin,
+            analysis.category,
+            &analysis.insights,
+            &analysis.related_symbols,
+        )
+    }
+
+    fn extract_context_requirements(&self, diagnostic: &Diagnostic) -> ContextRequirements {
+        self.context_analyzer
+            .extract_context_requirements(diagnostic, JvmLanguage::Kotlin)
+    }
+
+    fn language(&self) -> &str {
+        JvmLanguage::Kotlin.as_str()
+    }
+
+    fn can_analyze(&self, diagnostic: &Diagnostic) -> bool {
+        diagnostic.source.to_lowercase().contains("kotlin")
+            || diagnostic.file.ends_with(".kt")
+            || diagnostic.file.ends_with(".kts")
+    }
+}
+
+/// Whether a type mismatch only differs in nullability, e.g. `String?` for `String`
+fn is_nullable_mismatch(message: &str) -> bool {
+    let pattern = Regex::new(r"(?:inferred|actual) type is '?(.+?)\?'?,? but '?(.+?)'? was expected").unwrap();
+    pattern
+        .captures(message)
+        .is_some_and(|cap| cap[1] == cap[2])
+}
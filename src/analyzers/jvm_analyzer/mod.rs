@@ -0,0 +1,128 @@
+//! Analyzers for JVM language servers: `jdtls` for Java and
+//! `kotlin-language-server` for Kotlin
+//!
+//! Both languages share the null-safety, symbol resolution and generics
+//! analyzers; [`JvmLanguage`] tailors their insights and fix snippets.
+
+pub mod analyzers;
+pub mod context;
+pub mod fixes;
+pub mod java;
+pub mod kotlin;
+
+pub use java::JavaAnalyzer;
+pub use kotlin::KotlinAnalyzer;
+
+/// JVM language a diagnostic was reported for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JvmLanguage {
+    Java,
+    Kotlin,
+}
+
+impl JvmLanguage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Java => "java",
+            Self::Kotlin => "kotlin",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzers::{DiagnosticCategory, LanguageAnalyzer};
+    use crate::core::{Diagnostic, DiagnosticSeverity, Position, Range};
+
+    fn diagnostic(file: &str, source: &str, code: Option<&str>, message: &str) -> Diagnostic {
+        let mut diagnostic = Diagnostic::new(
+            file.to_string(),
+            Range {
+                start: Position { line: 4, character: 8 },
+                end: Position { line: 4, character: 12 },
+            },
+            DiagnosticSeverity::Error,
+            message.to_string(),
+            source.to_string(),
+        );
+        diagnostic.code = code.map(String::from);
+        diagnostic
+    }
+
+    #[test]
+    fn test_java_diagnostics() {
+        let analyzer = JavaAnalyzer::new();
+
+        let null = diagnostic(
+            "src/main/java/App.java",
+            "Java",
+            Some("536871364"),
+            "Potential null pointer access: The variable name may be null at this location",
+        );
+        assert!(analyzer.can_analyze(&null));
+        let analysis = analyzer.analyze_diagnostic(&null, None);
+        assert_eq!(analysis.category, DiagnosticCategory::NullSafety);
+        assert_eq!(analysis.related_symbols, vec!["name"]);
+        let fixes = analyzer.suggest_fix(&null, None);
+        assert_eq!(fixes[0].code_snippet.as_deref(), Some("if (name != null) {\n    // ...\n}"));
+
+        let unresolved = diagnostic("App.java", "Java", Some("16777218"), "List cannot be resolved to a type");
+        let analysis = analyzer.analyze_diagnostic(&unresolved, None);
+        assert_eq!(analysis.category, DiagnosticCategory::UndefinedType);
+        let fixes = analyzer.suggest_fix(&unresolved, None);
+        assert_eq!(fixes[0].code_snippet.as_deref(), Some("import java.util.List;"));
+
+        let generic = diagnostic(
+            "App.java",
+            "Java",
+            Some("16777233"),
+            "Type mismatch: cannot convert from List<Object> to List<String>",
+        );
+        let analysis = analyzer.analyze_diagnostic(&generic, None);
+        assert_eq!(analysis.category, DiagnosticCategory::GenericTypeError);
+        assert_eq!(analysis.related_symbols, vec!["List<Object>", "List<String>"]);
+
+        let javascript = diagnostic("app.js", "javascript", None, "x is not defined");
+        assert!(!analyzer.can_analyze(&javascript));
+    }
+
+    #[test]
+    fn test_kotlin_diagnostics() {
+        let analyzer = KotlinAnalyzer::new();
+
+        let unsafe_call = diagnostic(
+            "Main.kt",
+            "kotlin",
+            Some("UNSAFE_CALL"),
+            "Only safe (?.) or non-null asserted (!!.) calls are allowed on a nullable receiver of type String?",
+        );
+        let analysis = analyzer.analyze_diagnostic(&unsafe_call, None);
+        assert_eq!(analysis.category, DiagnosticCategory::NullSafety);
+        let fixes = analyzer.suggest_fix(&unsafe_call, None);
+        assert_eq!(fixes[0].code_snippet.as_deref(), Some("?."));
+
+        // A nullable value where a non-null type is expected is a null-safety error
+        let nullable = diagnostic(
+            "Main.kt",
+            "kotlin",
+            Some("TYPE_MISMATCH"),
+            "Type mismatch: inferred type is String? but String was expected",
+        );
+        assert_eq!(analyzer.analyze_diagnostic(&nullable, None).category, DiagnosticCategory::NullSafety);
+
+        let unresolved = diagnostic("Main.kt", "kotlin", Some("UNRESOLVED_REFERENCE"), "Unresolved reference: runBlocking");
+        let fixes = analyzer.suggest_fix(&unresolved, None);
+        assert_eq!(fixes[0].code_snippet.as_deref(), Some("import kotlinx.coroutines.runBlocking"));
+
+        let arity = diagnostic(
+            "Main.kt",
+            "kotlin",
+            Some("WRONG_NUMBER_OF_TYPE_ARGUMENTS"),
+            "2 type arguments expected for interface Map<K, out V>",
+        );
+        let analysis = analyzer.analyze_diagnostic(&arity, None);
+        assert_eq!(analysis.category, DiagnosticCategory::GenericTypeError);
+        assert!(analysis.insights.iter().any(|i| i.contains("2 type argument")));
+    }
+}
@@ -60,6 +60,7 @@ pub enum DiagnosticCategory {
     UndefinedVariable,
     UnusedVariable,
     UninitializedVariable,
+    NullSafety,
 
     // Import/Module related
     MissingImport,
@@ -1,14 +1,22 @@
 pub mod base;
 pub mod error_codes;
+pub mod jvm_analyzer;
 pub mod language_analyzer;
 pub mod macros;
 pub mod rust_analyzer;
+pub mod security;
+pub mod taxonomy;
 pub mod typescript_analyzer;
 
 pub use base::{AnalyzerBase, ComplexityScorer, DiagnosticPatterns};
-pub use error_codes::{ErrorCode, RustErrorCode, TypeScriptErrorCode, PythonErrorCode};
+pub use error_codes::{
+    ErrorCode, JavaErrorCode, KotlinErrorCode, PythonErrorCode, RustErrorCode, TypeScriptErrorCode,
+};
+pub use jvm_analyzer::{JavaAnalyzer, JvmLanguage, KotlinAnalyzer};
 pub use language_analyzer::{
     ContextRequirements, DiagnosticAnalysis, DiagnosticCategory, FixSuggestion, LanguageAnalyzer,
 };
 pub use rust_analyzer::RustAnalyzer;
+pub use security::{SecurityAnalyzer, SecurityPattern, SECURITY_SOURCE};
+pub use taxonomy::DiagnosticTaxonomy;
 pub use typescript_analyzer::TypeScriptAnalyzer;
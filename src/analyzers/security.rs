@@ -0,0 +1,493 @@
+//! Insecure code patterns found in source
+//!
+//! [`SecurityAnalyzer`] parses Rust, TypeScript/JavaScript and Python with
+//! tree-sitter and reports common vulnerability patterns no language server
+//! flags:
+//!
+//! - SQL built from concatenated or interpolated strings
+//! - code evaluated from strings (`eval`, `exec`, `new Function`)
+//! - credentials assigned as string literals
+//! - disabled TLS certificate verification
+//!
+//! Findings come from the `lspbridge-security` source with the pattern id as
+//! code, the keys severity re-mapping matches on, and record the `security`
+//! taxonomy, the CWE, references and a safer alternative in `data`.
+
+use super::taxonomy::DiagnosticTaxonomy;
+use crate::core::dependency_analyzer::Language;
+use crate::core::static_scan::{grammar, language_for_path, node_range, node_text, visit_nodes};
+use crate::core::{Diagnostic, DiagnosticSeverity};
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+use tree_sitter::{Node, Parser, Tree};
+
+/// `source` of every security finding
+pub const SECURITY_SOURCE: &str = "lspbridge-security";
+
+/// An insecure pattern the analyzer looks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SecurityPattern {
+    SqlConcatenation,
+    DynamicEval,
+    HardcodedCredential,
+    InsecureTls,
+}
+
+impl SecurityPattern {
+    pub const ALL: [Self; 4] = [
+        Self::SqlConcatenation,
+        Self::DynamicEval,
+        Self::HardcodedCredential,
+        Self::InsecureTls,
+    ];
+
+    /// Reported as the diagnostic code
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::SqlConcatenation => "sql-concatenation",
+            Self::DynamicEval => "dynamic-eval",
+            Self::HardcodedCredential => "hardcoded-credential",
+            Self::InsecureTls => "insecure-tls",
+        }
+    }
+
+    pub fn parse(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|pattern| pattern.id() == id)
+    }
+
+    pub fn default_severity(&self) -> DiagnosticSeverity {
+        match self {
+            Self::SqlConcatenation | Self::DynamicEval => DiagnosticSeverity::Warning,
+            Self::HardcodedCredential | Self::InsecureTls => DiagnosticSeverity::Error,
+        }
+    }
+
+    pub fn cwe(&self) -> &'static str {
+        match self {
+            Self::SqlConcatenation => "CWE-89",
+            Self::DynamicEval => "CWE-95",
+            Self::HardcodedCredential => "CWE-798",
+            Self::InsecureTls => "CWE-295",
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            Self::SqlConcatenation => "SQL query built from concatenated strings",
+            Self::DynamicEval => "Code evaluated from a string",
+            Self::HardcodedCredential => "Hardcoded credential",
+            Self::InsecureTls => "TLS certificate verification disabled",
+        }
+    }
+
+    pub fn references(&self) -> Vec<String> {
+        let cwe = self.cwe().trim_start_matches("CWE-");
+        let owasp = match self {
+            Self::SqlConcatenation => "https://owasp.org/www-community/attacks/SQL_Injection",
+            Self::DynamicEval => "https://owasp.org/www-community/attacks/Direct_Dynamic_Code_Evaluation_Eval%20Injection",
+            Self::HardcodedCredential => "https://owasp.org/www-community/vulnerabilities/Use_of_hard-coded_password",
+            Self::InsecureTls => "https://owasp.org/www-community/vulnerabilities/Improper_Certificate_Validation",
+        };
+        vec![format!("https://cwe.mitre.org/data/definitions/{cwe}.html"), owasp.to_string()]
+    }
+
+    /// Safer alternative in the idiom of `language`
+    pub fn suggestion(&self, language: Language) -> &'static str {
+        match (self, language) {
+            (Self::SqlConcatenation, Language::Rust) => {
+                "bind values as query parameters (e.g. `sqlx::query(\"... WHERE id = $1\").bind(id)`) instead of formatting them in"
+            }
+            (Self::SqlConcatenation, Language::Python) => {
+                "pass values separately with `cursor.execute(sql, params)` instead of formatting them in"
+            }
+            (Self::SqlConcatenation, _) => {
+                "use placeholders (`$1`, `?`) and pass values as query parameters instead of concatenating them"
+            }
+            (Self::DynamicEval, Language::Python) => {
+                "parse data with `ast.literal_eval` or `json.loads`, or dispatch to known functions"
+            }
+            (Self::DynamicEval, _) => "parse data with `JSON.parse` or dispatch to known functions",
+            (Self::HardcodedCredential, Language::Rust) => {
+                "read it from the environment (`std::env::var`) or a secret store at runtime"
+            }
+            (Self::HardcodedCredential, Language::Python) => {
+                "read it from the environment (`os.environ`) or a secret store at runtime"
+            }
+            (Self::HardcodedCredential, _) => "read it from the environment (`process.env`) or a secret store at runtime",
+            (Self::InsecureTls, _) => {
+                "keep verification on and trust a private CA through its certificate instead of disabling checks"
+            }
+        }
+    }
+}
+
+/// Finds insecure patterns with tree-sitter
+#[derive(Debug, Clone, Default)]
+pub struct SecurityAnalyzer {
+    severities: HashMap<SecurityPattern, DiagnosticSeverity>,
+}
+
+impl SecurityAnalyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report `pattern` with `severity` instead of its default
+    pub fn with_severity(mut self, pattern: SecurityPattern, severity: DiagnosticSeverity) -> Self {
+        self.severities.insert(pattern, severity);
+        self
+    }
+
+    pub fn severity(&self, pattern: SecurityPattern) -> DiagnosticSeverity {
+        self.severities
+            .get(&pattern)
+            .copied()
+            .unwrap_or_else(|| pattern.default_severity())
+    }
+
+    pub fn analyze_file(&self, path: &Path) -> Result<Vec<Diagnostic>> {
+        let content = std::fs::read_to_string(path).with_context(|| format!("Cannot analyze {}", path.display()))?;
+        self.analyze_source(path, &content)
+    }
+
+    /// Findings in `content`, using `path` for the language and the diagnostics
+    pub fn analyze_source(&self, path: &Path, content: &str) -> Result<Vec<Diagnostic>> {
+        let language = language_for_path(path);
+        if matches!(language, Language::Unknown) {
+            return Ok(Vec::new());
+        }
+        let mut parser = Parser::new();
+        parser.set_language(grammar(language)?)?;
+        Ok(match parser.parse(content, None) {
+            Some(tree) => self.analyze_tree(language, &tree, content, path),
+            None => Vec::new(),
+        })
+    }
+
+    /// Findings in an already parsed file
+    pub(crate) fn analyze_tree(&self, language: Language, tree: &Tree, source: &str, path: &Path) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        visit_nodes(tree, |node| {
+            if let Some((pattern, reported)) = detect(language, node, source) {
+                diagnostics.push(self.diagnostic(pattern, language, path, reported));
+            }
+        });
+        diagnostics
+    }
+
+    fn diagnostic(&self, pattern: SecurityPattern, language: Language, path: &Path, node: Node) -> Diagnostic {
+        let suggestion = pattern.suggestion(language);
+        let mut diagnostic = Diagnostic::new(
+            path.to_string_lossy().to_string(),
+            node_range(&node),
+            self.severity(pattern),
+            format!("{} ({}): {}", pattern.title(), pattern.cwe(), suggestion),
+            SECURITY_SOURCE.to_string(),
+        );
+        diagnostic.code = Some(pattern.id().to_string());
+        diagnostic.data = Some(serde_json::json!({
+            "taxonomy": DiagnosticTaxonomy::Security,
+            "cwe": pattern.cwe(),
+            "references": pattern.references(),
+            "suggestion": suggestion,
+        }));
+        diagnostic
+    }
+}
+
+/// The pattern `node` matches, and the node to report it on
+fn detect<'t>(language: Language, node: Node<'t>, source: &str) -> Option<(SecurityPattern, Node<'t>)> {
+    if builds_sql(language, node, source) {
+        Some((SecurityPattern::SqlConcatenation, node))
+    } else if evaluates_code(language, node, source) {
+        Some((SecurityPattern::DynamicEval, node))
+    } else if disables_tls(language, node, source) {
+        Some((SecurityPattern::InsecureTls, node))
+    } else {
+        hardcoded_credential(language, node, source).map(|value| (SecurityPattern::HardcodedCredential, value))
+    }
+}
+
+/// A SQL string that is interpolated or joined with other values
+fn builds_sql(language: Language, node: Node, source: &str) -> bool {
+    let is_string = matches!(
+        node.kind(),
+        "string" | "template_string" | "string_literal" | "raw_string_literal"
+    );
+    if !is_string || !looks_like_sql(node_text(&node, source)) {
+        return false;
+    }
+    if has_interpolation(node) {
+        return true;
+    }
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    match (language, parent.kind()) {
+        (Language::TypeScript | Language::Rust, "binary_expression") => operator(parent, source) == "+",
+        (Language::Python, "binary_operator") => matches!(operator(parent, source), "+" | "%"),
+        (Language::Python, "attribute") => field_text(parent, "attribute", source) == Some("format"),
+        (Language::Rust, "token_tree") => {
+            node_text(&node, source).contains('{')
+                && parent
+                    .parent()
+                    .filter(|invocation| invocation.kind() == "macro_invocation")
+                    .and_then(|invocation| field_text(invocation, "macro", source))
+                    .is_some_and(|name| name == "format")
+        }
+        _ => false,
+    }
+}
+
+fn evaluates_code(language: Language, node: Node, source: &str) -> bool {
+    match (language, node.kind()) {
+        (Language::TypeScript, "call_expression") => match field_text(node, "function", source) {
+            Some("eval") => true,
+            // Timers also evaluate a string passed in place of a function
+            Some("setTimeout" | "setInterval") => node
+                .child_by_field_name("arguments")
+                .and_then(|arguments| arguments.named_child(0))
+                .is_some_and(|first| matches!(first.kind(), "string" | "template_string")),
+            _ => false,
+        },
+        (Language::TypeScript, "new_expression") => field_text(node, "constructor", source) == Some("Function"),
+        (Language::Python, "call") => matches!(field_text(node, "function", source), Some("eval" | "exec")),
+        _ => false,
+    }
+}
+
+fn disables_tls(language: Language, node: Node, source: &str) -> bool {
+    match (language, node.kind()) {
+        (Language::TypeScript, "pair") => {
+            matches!(field_name(node, "key", source), Some("rejectUnauthorized" | "strictSSL"))
+                && node.child_by_field_name("value").is_some_and(|value| value.kind() == "false")
+        }
+        (Language::TypeScript, "assignment_expression") => {
+            node_text(&node, source).contains("NODE_TLS_REJECT_UNAUTHORIZED")
+                && node
+                    .child_by_field_name("right")
+                    .is_some_and(|right| string_value(right, source).unwrap_or(node_text(&right, source)) == "0")
+        }
+        (Language::Python, "keyword_argument") => {
+            field_text(node, "name", source) == Some("verify")
+                && node.child_by_field_name("value").is_some_and(|value| value.kind() == "false")
+        }
+        (Language::Python, "assignment") => {
+            field_name(node, "left", source) == Some("check_hostname")
+                && node.child_by_field_name("right").is_some_and(|right| right.kind() == "false")
+        }
+        (Language::Python, "attribute") => {
+            matches!(field_text(node, "attribute", source), Some("_create_unverified_context" | "CERT_NONE"))
+        }
+        (Language::Rust, "call_expression") => {
+            let method = node
+                .child_by_field_name("function")
+                .filter(|function| function.kind() == "field_expression")
+                .and_then(|function| field_text(function, "field", source));
+            matches!(method, Some("danger_accept_invalid_certs" | "danger_accept_invalid_hostnames"))
+                && field_text(node, "arguments", source).is_some_and(|arguments| arguments.contains("true"))
+        }
+        (Language::Rust, "scoped_identifier") => node_text(&node, source).ends_with("SslVerifyMode::NONE"),
+        _ => false,
+    }
+}
+
+/// The string literal assigned to a credential-like name
+fn hardcoded_credential<'t>(language: Language, node: Node<'t>, source: &str) -> Option<Node<'t>> {
+    let (name, value) = match (language, node.kind()) {
+        (Language::TypeScript, "variable_declarator") => ("name", "value"),
+        (Language::TypeScript | Language::Python, "pair") => ("key", "value"),
+        (Language::TypeScript, "assignment_expression") | (Language::Python, "assignment") => ("left", "right"),
+        (Language::Python, "keyword_argument") => ("name", "value"),
+        (Language::Rust, "let_declaration") => ("pattern", "value"),
+        (Language::Rust, "const_item" | "static_item") => ("name", "value"),
+        (Language::Rust, "field_initializer") => ("field", "value"),
+        _ => return None,
+    };
+    if !is_credential_name(field_name(node, name, source)?) {
+        return None;
+    }
+    let value = node.child_by_field_name(value)?;
+    string_value(value, source).filter(|secret| looks_like_secret(secret))?;
+    Some(value)
+}
+
+fn is_credential_name(name: &str) -> bool {
+    let name: String = name
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase();
+    [
+        "password", "passwd", "pwd", "secret", "apikey", "accesskey", "secretkey", "privatekey", "token",
+        "credentials",
+    ]
+    .iter()
+    .any(|suffix| name.ends_with(suffix))
+}
+
+/// Whether a literal looks like a real secret rather than a placeholder or an env var name
+fn looks_like_secret(value: &str) -> bool {
+    let lower = value.to_lowercase();
+    value.chars().count() >= 4
+        && !value.chars().any(char::is_whitespace)
+        && !value.starts_with(['<', '$', '{', '%'])
+        && !value.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        && !["example", "placeholder", "your", "xxx", "***", "dummy"]
+            .iter()
+            .any(|marker| lower.contains(marker))
+}
+
+fn looks_like_sql(text: &str) -> bool {
+    static SQL: OnceLock<Regex> = OnceLock::new();
+    SQL.get_or_init(|| {
+        Regex::new(r"(?is)\bselect\b.+\bfrom\b|\binsert\s+into\b|\bupdate\s+\S+\s+set\b|\bdelete\s+from\b")
+            .expect("valid regex")
+    })
+    .is_match(text)
+}
+
+/// Template strings with `${}` and f-strings
+fn has_interpolation(node: Node) -> bool {
+    let mut cursor = node.walk();
+    let interpolated = node
+        .children(&mut cursor)
+        .any(|child| matches!(child.kind(), "template_substitution" | "interpolation"));
+    interpolated
+}
+
+/// Contents of a plain string literal; `None` for other nodes and interpolated strings
+fn string_value<'a>(node: Node, source: &'a str) -> Option<&'a str> {
+    if !matches!(node.kind(), "string" | "string_literal" | "raw_string_literal") || has_interpolation(node) {
+        return None;
+    }
+    let text = node_text(&node, source)
+        .trim_start_matches(|c: char| c.is_ascii_alphabetic() || c == '#')
+        .trim_end_matches('#');
+    Some(text.trim_matches(|c| c == '"' || c == '\'' || c == '`'))
+}
+
+fn field_text<'a>(node: Node, field: &str, source: &'a str) -> Option<&'a str> {
+    node.child_by_field_name(field).map(|child| node_text(&child, source))
+}
+
+/// Last segment of the name in `field`: `password` for `self.password`, `"password"` or `password`
+fn field_name<'a>(node: Node, field: &str, source: &'a str) -> Option<&'a str> {
+    let text = field_text(node, field, source)?.trim_matches(|c| c == '"' || c == '\'');
+    Some(text.rsplit(['.', ':']).next().unwrap_or(text))
+}
+
+fn operator<'a>(node: Node, source: &'a str) -> &'a str {
+    if let Some(operator) = node.child_by_field_name("operator") {
+        return node_text(&operator, source);
+    }
+    let mut cursor = node.walk();
+    let operator = node
+        .children(&mut cursor)
+        .find(|child| !child.is_named())
+        .map(|child| node_text(&child, source))
+        .unwrap_or_default();
+    operator
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn findings(file: &str, source: &str) -> Vec<(String, u32)> {
+        SecurityAnalyzer::new()
+            .analyze_source(Path::new(file), source)
+            .unwrap()
+            .into_iter()
+            .map(|d| (d.code.unwrap(), d.range.start.line + 1))
+            .collect()
+    }
+
+    fn found(file: &str, source: &str) -> Vec<(&'static str, u32)> {
+        findings(file, source)
+            .into_iter()
+            .map(|(code, line)| (SecurityPattern::parse(&code).unwrap().id(), line))
+            .collect()
+    }
+
+    #[test]
+    fn test_typescript_patterns() {
+        let source = r#"
+const q = "SELECT * FROM users WHERE id = " + id;
+const t = `DELETE FROM sessions WHERE user = ${user}`;
+const safe = db.query("SELECT * FROM users WHERE id = $1", [id]);
+eval(userInput);
+const f = new Function("a", body);
+const apiKey = "sk_live_51Habc";
+const token = "API_TOKEN";
+const agent = new https.Agent({ rejectUnauthorized: false });
+process.env.NODE_TLS_REJECT_UNAUTHORIZED = "0";
+"#;
+        assert_eq!(
+            found("app.ts", source),
+            vec![
+                ("sql-concatenation", 2),
+                ("sql-concatenation", 3),
+                ("dynamic-eval", 5),
+                ("dynamic-eval", 6),
+                ("hardcoded-credential", 7),
+                ("insecure-tls", 9),
+                ("insecure-tls", 10),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_python_patterns() {
+        let source = r#"
+cursor.execute("SELECT * FROM users WHERE name = '%s'" % name)
+cursor.execute(f"UPDATE users SET name = '{name}'")
+cursor.execute("SELECT * FROM users WHERE name = %s", (name,))
+exec(code)
+password = "hunter2!"
+password = os.environ["DB_PASSWORD"]
+requests.get(url, verify=False)
+ctx = ssl._create_unverified_context()
+"#;
+        assert_eq!(
+            found("app.py", source),
+            vec![
+                ("sql-concatenation", 2),
+                ("sql-concatenation", 3),
+                ("dynamic-eval", 5),
+                ("hardcoded-credential", 6),
+                ("insecure-tls", 8),
+                ("insecure-tls", 9),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rust_patterns_and_severity_override() {
+        let source = r#"
+const API_SECRET: &str = "9f8e7d6c5b4a";
+fn load(id: &str) -> String {
+    let query = format!("SELECT * FROM users WHERE id = {}", id);
+    let client = Client::builder().danger_accept_invalid_certs(true).build();
+    query
+}
+"#;
+        assert_eq!(
+            found("src/db.rs", source),
+            vec![("hardcoded-credential", 2), ("sql-concatenation", 4), ("insecure-tls", 5)]
+        );
+
+        let analyzer = SecurityAnalyzer::new().with_severity(SecurityPattern::HardcodedCredential, DiagnosticSeverity::Hint);
+        let diagnostics = analyzer.analyze_source(Path::new("src/db.rs"), source).unwrap();
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Hint);
+        assert_eq!(diagnostics[1].severity, DiagnosticSeverity::Warning);
+        assert_eq!(diagnostics[1].source, SECURITY_SOURCE);
+        assert_eq!(DiagnosticTaxonomy::classify(&diagnostics[1]), DiagnosticTaxonomy::Security);
+        let data = diagnostics[1].data.as_ref().unwrap();
+        assert_eq!(data["cwe"], "CWE-89");
+        assert!(data["suggestion"].as_str().unwrap().contains("bind"));
+    }
+}
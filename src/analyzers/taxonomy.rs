@@ -0,0 +1,246 @@
+//! Language-independent diagnostic taxonomy
+//!
+//! Each language analyzer reports its own fine-grained [`DiagnosticCategory`].
+//! The taxonomy folds those into a small set of buckets that mean the same
+//! thing across languages, so a Rust borrow error and a C++ use-after-free
+//! both count as `memory-safety` in queries and trend reports.
+
+use super::language_analyzer::{DiagnosticCategory, LanguageAnalyzer};
+use super::{JavaAnalyzer, KotlinAnalyzer, RustAnalyzer, TypeScriptAnalyzer};
+use crate::core::Diagnostic;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Shared classification of diagnostics across languages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiagnosticTaxonomy {
+    /// Ownership, lifetimes, unsafe memory access
+    MemorySafety,
+    /// Type mismatches and missing members
+    Typing,
+    /// Undefined or uninitialized names
+    Symbols,
+    /// Unresolved imports and module structure
+    Imports,
+    /// Syntax and parse errors
+    Syntax,
+    /// Lints, unused code and conventions
+    Style,
+    /// Async, threading and data races
+    Concurrency,
+    /// Vulnerabilities and insecure patterns
+    Security,
+    /// Inefficient code
+    Performance,
+    Other,
+}
+
+static ANALYZERS: Lazy<Vec<Box<dyn LanguageAnalyzer>>> = Lazy::new(|| {
+    vec![
+        Box::new(RustAnalyzer::new()),
+        Box::new(TypeScriptAnalyzer::new()),
+        Box::new(JavaAnalyzer::new()),
+        Box::new(KotlinAnalyzer::new()),
+    ]
+});
+
+impl DiagnosticTaxonomy {
+    pub const ALL: [Self; 10] = [
+        Self::MemorySafety,
+        Self::Typing,
+        Self::Symbols,
+        Self::Imports,
+        Self::Syntax,
+        Self::Style,
+        Self::Concurrency,
+        Self::Security,
+        Self::Performance,
+        Self::Other,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MemorySafety => "memory-safety",
+            Self::Typing => "typing",
+            Self::Symbols => "symbols",
+            Self::Imports => "imports",
+            Self::Syntax => "syntax",
+            Self::Style => "style",
+            Self::Concurrency => "concurrency",
+            Self::Security => "security",
+            Self::Performance => "performance",
+            Self::Other => "other",
+        }
+    }
+
+    /// Parse a taxonomy name, accepting `_` in place of `-`
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase().replace('_', "-");
+        Self::ALL.into_iter().find(|taxonomy| taxonomy.as_str() == name)
+    }
+
+    /// Classify a diagnostic
+    ///
+    /// A taxonomy recorded in the diagnostic's `data`, as the
+    /// [`SecurityAnalyzer`](super::SecurityAnalyzer) does, is used as is.
+    /// Diagnostics from a language with a dedicated analyzer use that
+    /// analyzer's category; everything else, and anything the analyzer
+    /// cannot place, falls back to source and message heuristics.
+    pub fn classify(diagnostic: &Diagnostic) -> Self {
+        let recorded = diagnostic
+            .data
+            .as_ref()
+            .and_then(|data| data.get("taxonomy"))
+            .and_then(|taxonomy| taxonomy.as_str())
+            .and_then(Self::parse);
+        if let Some(taxonomy) = recorded {
+            return taxonomy;
+        }
+
+        let analyzed = ANALYZERS
+            .iter()
+            .filter(|analyzer| analyzer.can_analyze(diagnostic) || handles_file(analyzer.as_ref(), diagnostic))
+            .map(|analyzer| analyzer.analyze_diagnostic(diagnostic, None).category.taxonomy())
+            .find(|taxonomy| *taxonomy != Self::Other);
+
+        analyzed.unwrap_or_else(|| Self::from_heuristics(diagnostic))
+    }
+
+    fn from_heuristics(diagnostic: &Diagnostic) -> Self {
+        let message = diagnostic.message.to_lowercase();
+        let source = diagnostic.source.to_lowercase();
+        let code = diagnostic.code.as_deref().unwrap_or_default().to_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|needle| message.contains(needle));
+
+        if ["bandit", "semgrep", "gosec", "security"].iter().any(|s| source.contains(s))
+            || has(&["injection", "xss", "insecure", "vulnerab", "hardcoded password", "secret"])
+        {
+            Self::Security
+        } else if has(&["use after free", "null pointer", "dangling", "buffer overflow", "unsafe", "memory leak"]) {
+            Self::MemorySafety
+        } else if has(&["data race", "deadlock", "await", "async", "thread", "mutex"]) {
+            Self::Concurrency
+        } else if has(&["syntax", "unexpected token", "parse error", "unterminated"]) {
+            Self::Syntax
+        } else if has(&["import", "module", "unresolved"]) {
+            Self::Imports
+        } else if has(&["mismatched types", "not assignable", "incompatible type", "type error"]) {
+            Self::Typing
+        } else if has(&["is not defined", "undefined", "cannot find", "undeclared", "uninitialized"]) {
+            Self::Symbols
+        } else if code.contains("perf") || has(&["performance", "inefficient", "needless clone"]) {
+            Self::Performance
+        } else if ["eslint", "clippy", "pylint", "flake8", "ruff", "rubocop", "stylelint", "prettier"]
+            .iter()
+            .any(|s| source.contains(s))
+            || has(&["unused", "naming", "convention", "deprecated"])
+        {
+            Self::Style
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// Route diagnostics from generic sources (e.g. `ts`) to the analyzer for their file type
+fn handles_file(analyzer: &dyn LanguageAnalyzer, diagnostic: &Diagnostic) -> bool {
+    let extension = diagnostic.file.rsplit('.').next().unwrap_or_default();
+    match analyzer.language() {
+        "rust" => extension == "rs",
+        "typescript" => matches!(extension, "ts" | "tsx" | "js" | "jsx" | "mts" | "cts"),
+        _ => false,
+    }
+}
+
+impl fmt::Display for DiagnosticTaxonomy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl DiagnosticCategory {
+    /// Shared taxonomy bucket for this analyzer category
+    pub fn taxonomy(&self) -> DiagnosticTaxonomy {
+        match self {
+            Self::TypeMismatch | Self::MissingProperty | Self::UndefinedType | Self::GenericTypeError => {
+                DiagnosticTaxonomy::Typing
+            }
+            Self::UndefinedVariable | Self::UninitializedVariable => DiagnosticTaxonomy::Symbols,
+            Self::UnusedVariable | Self::CodeQuality => DiagnosticTaxonomy::Style,
+            Self::MissingImport | Self::CircularDependency | Self::ModuleResolution => {
+                DiagnosticTaxonomy::Imports
+            }
+            Self::SyntaxError | Self::ParseError => DiagnosticTaxonomy::Syntax,
+            Self::BorrowChecker | Self::LifetimeError | Self::MoveError | Self::NullSafety => {
+                DiagnosticTaxonomy::MemorySafety
+            }
+            Self::AsyncError | Self::RaceCondition => DiagnosticTaxonomy::Concurrency,
+            Self::Security => DiagnosticTaxonomy::Security,
+            Self::Performance => DiagnosticTaxonomy::Performance,
+            Self::Unknown => DiagnosticTaxonomy::Other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{DiagnosticSeverity, Position, Range};
+
+    fn diagnostic(file: &str, source: &str, code: Option<&str>, message: &str) -> Diagnostic {
+        let mut diagnostic = Diagnostic::new(
+            file.to_string(),
+            Range {
+                start: Position { line: 0, character: 0 },
+                end: Position { line: 0, character: 1 },
+            },
+            DiagnosticSeverity::Error,
+            message.to_string(),
+            source.to_string(),
+        );
+        diagnostic.code = code.map(String::from);
+        diagnostic
+    }
+
+    #[test]
+    fn test_analyzers_map_onto_shared_taxonomy() {
+        let borrow = diagnostic(
+            "src/lib.rs",
+            "rustc",
+            Some("E0502"),
+            "cannot borrow `v` as mutable because it is also borrowed as immutable",
+        );
+        assert_eq!(DiagnosticTaxonomy::classify(&borrow), DiagnosticTaxonomy::MemorySafety);
+
+        let assign = diagnostic(
+            "src/app.ts",
+            "ts",
+            Some("2322"),
+            "Type 'string' is not assignable to type 'number'.",
+        );
+        assert_eq!(DiagnosticTaxonomy::classify(&assign), DiagnosticTaxonomy::Typing);
+    }
+
+    #[test]
+    fn test_heuristics_for_languages_without_analyzer() {
+        let import = diagnostic("app.py", "pyright", None, "Import \"requests\" could not be resolved");
+        assert_eq!(DiagnosticTaxonomy::classify(&import), DiagnosticTaxonomy::Imports);
+
+        let security = diagnostic("app.py", "bandit", Some("B105"), "Possible hardcoded password");
+        assert_eq!(DiagnosticTaxonomy::classify(&security), DiagnosticTaxonomy::Security);
+
+        let lint = diagnostic("main.go", "golint", None, "exported function Foo should have comment");
+        assert_eq!(DiagnosticTaxonomy::classify(&lint), DiagnosticTaxonomy::Other);
+    }
+
+    #[test]
+    fn test_parse_round_trips() {
+        for taxonomy in DiagnosticTaxonomy::ALL {
+            assert_eq!(DiagnosticTaxonomy::parse(taxonomy.as_str()), Some(taxonomy));
+        }
+        assert_eq!(DiagnosticTaxonomy::parse("Memory_Safety"), Some(DiagnosticTaxonomy::MemorySafety));
+        assert_eq!(DiagnosticTaxonomy::parse("nonsense"), None);
+    }
+}
@@ -1,14 +1,18 @@
 use crate::core::{
-    CaptureMethod, Diagnostic, DiagnosticGroup, DiagnosticGrouper, DiagnosticSnapshot,
-    DiagnosticsCache, DiagnosticsCaptureService, EditorInfo, FormatConverter, IncrementalProcessor,
-    PrivacyFilter, ProcessingStats, RawDiagnostics, SnapshotMetadata, WorkspaceInfo,
+    ApiSurfaceAnalyzer, AuditLog, Baseline, CaptureMethod, CrashCorrelator, Diagnostic, DiagnosticGroup, DiagnosticGrouper, DiagnosticSnapshot,
+    DiagnosticsCache, DiagnosticsCaptureService, DynamicConfigManager, EditorInfo, FormatConverter, GeneratedCodeMapper,
+    IncrementalProcessor,
+    PrivacyFilter, ProcessingStats, RawDiagnostics, SeverityRules, SnapshotMetadata, WorkspaceInfo, WorkspaceRoot,
 };
+use crate::core::telemetry::{self, Operation};
 use anyhow::Result;
 use async_trait::async_trait;
 use chrono::Utc;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Instant;
+use tokio::sync::{broadcast, RwLock};
 use uuid::Uuid;
 
 pub struct CaptureService<C, P, F>
@@ -18,7 +22,7 @@ where
     F: FormatConverter + Send + Sync,
 {
     cache: Arc<RwLock<C>>,
-    privacy_filter: Arc<P>,
+    privacy_filter: Arc<std::sync::RwLock<P>>,
     format_converter: Arc<F>,
     diagnostic_grouper: Arc<DiagnosticGrouper>,
     incremental_processor: Arc<IncrementalProcessor>,
@@ -29,6 +33,12 @@ where
     enable_grouping: Arc<RwLock<bool>>,
     enable_incremental: Arc<RwLock<bool>>,
     last_stats: Arc<RwLock<Option<ProcessingStats>>>,
+    workspace_roots: Vec<WorkspaceRoot>,
+    generated_code: Option<Arc<GeneratedCodeMapper>>,
+    api_surface: Option<Arc<ApiSurfaceAnalyzer>>,
+    crash_reports: Option<Arc<CrashCorrelator>>,
+    baseline: Option<(Arc<Baseline>, PathBuf)>,
+    severity_rules: Arc<std::sync::RwLock<SeverityRules>>,
 }
 
 impl<C, P, F> CaptureService<C, P, F>
@@ -40,7 +50,7 @@ where
     pub fn new(cache: C, privacy_filter: P, format_converter: F) -> Self {
         Self {
             cache: Arc::new(RwLock::new(cache)),
-            privacy_filter: Arc::new(privacy_filter),
+            privacy_filter: Arc::new(std::sync::RwLock::new(privacy_filter)),
             format_converter: Arc::new(format_converter),
             diagnostic_grouper: Arc::new(DiagnosticGrouper::new()),
             incremental_processor: Arc::new(IncrementalProcessor::new()),
@@ -51,6 +61,73 @@ where
             enable_grouping: Arc::new(RwLock::new(true)),
             enable_incremental: Arc::new(RwLock::new(true)),
             last_stats: Arc::new(RwLock::new(None)),
+            workspace_roots: Vec::new(),
+            generated_code: None,
+            api_surface: None,
+            crash_reports: None,
+            baseline: None,
+            severity_rules: Arc::new(std::sync::RwLock::new(SeverityRules::new())),
+        }
+    }
+
+    /// Configure the roots of a multi-root workspace.
+    ///
+    /// Each captured diagnostic is tagged with the root containing its file
+    /// (see [`Diagnostic::workspace_root`]) and snapshots list the roots in
+    /// their workspace info. Roots sent by the editor with the raw
+    /// diagnostics take precedence.
+    pub fn with_workspace_roots(mut self, roots: Vec<WorkspaceRoot>) -> Self {
+        self.workspace_roots = roots;
+        self
+    }
+
+    /// Re-attribute diagnostics in generated files to their generator sources.
+    ///
+    /// Diagnostics are moved before privacy filtering, so file exclusions
+    /// apply to the generator source rather than the generated file.
+    pub fn with_generated_code(mut self, mapper: GeneratedCodeMapper) -> Self {
+        self.generated_code = Some(Arc::new(mapper));
+        self
+    }
+
+    /// Tag diagnostics on exported Rust and TypeScript items with semver impact hints.
+    ///
+    /// Files are read from disk, so tagging runs before paths are anonymized.
+    pub fn with_api_surface(mut self, analyzer: ApiSurfaceAnalyzer) -> Self {
+        self.api_surface = Some(Arc::new(analyzer));
+        self
+    }
+
+    /// Flag diagnostics at locations that appear in runtime crash reports.
+    ///
+    /// Correlation runs before paths are anonymized.
+    pub fn with_crash_reports(mut self, correlator: CrashCorrelator) -> Self {
+        self.crash_reports = Some(Arc::new(correlator));
+        self
+    }
+
+    /// Leave out diagnostics accepted in a baseline, with paths relative to `root`.
+    ///
+    /// Applied right after normalization, before anything rewrites
+    /// diagnostics, so they match what `baseline create` recorded.
+    pub fn with_baseline(mut self, baseline: Baseline, root: impl Into<PathBuf>) -> Self {
+        self.baseline = Some((Arc::new(baseline), root.into()));
+        self
+    }
+
+    /// Promote or demote diagnostics by source and code.
+    ///
+    /// Applied after the baseline, so baselined diagnostics match what was
+    /// reported, and before anything is cached, recorded or exported.
+    pub fn with_severity_rules(mut self, rules: SeverityRules) -> Self {
+        self.severity_rules = Arc::new(std::sync::RwLock::new(rules));
+        self
+    }
+
+    fn roots_for<'a>(&'a self, raw: &'a RawDiagnostics) -> &'a [WorkspaceRoot] {
+        match &raw.workspace {
+            Some(workspace) if !workspace.roots.is_empty() => &workspace.roots,
+            _ => &self.workspace_roots,
         }
     }
 
@@ -70,22 +147,120 @@ where
     }
 
     /// Update the privacy policy for this capture service
-    /// This allows dynamic reconfiguration without recreating the service
-    pub fn update_privacy_policy(&self, _policy: crate::core::PrivacyPolicy) -> Result<()> {
-        // For now, policy updates require recreating the service since the
-        // privacy filter is Arc-wrapped and immutable. In a production system,
-        // we might want to use Arc<RwLock<PrivacyFilter>> to allow updates.
-        
-        // Callers should use the set_privacy_policy methods on DiagnosticsCapture
-        // which properly recreate the service with updated configuration.
-        
+    /// This allows dynamic reconfiguration without recreating the service;
+    /// the new policy applies from the next diagnostics processed
+    pub fn update_privacy_policy(&self, policy: crate::core::PrivacyPolicy) -> Result<()> {
+        let mut filter = self
+            .privacy_filter
+            .write()
+            .map_err(|_| anyhow::anyhow!("Privacy filter lock poisoned"))?;
+        filter.update_policy(policy);
         Ok(())
     }
 
     /// Get the current privacy policy from the privacy filter
-    /// Note: This returns a cloned policy since the filter is Arc-wrapped
+    /// Note: This returns a cloned policy since the filter is shared
     pub fn get_privacy_policy(&self) -> crate::core::PrivacyPolicy {
-        self.privacy_filter.get_policy().clone()
+        match self.privacy_filter.read() {
+            Ok(filter) => filter.get_policy().clone(),
+            Err(poisoned) => poisoned.into_inner().get_policy().clone(),
+        }
+    }
+
+    /// Keep the privacy policy in sync with a dynamic configuration.
+    ///
+    /// Every change under `privacy.` (level, exclusions, redaction patterns)
+    /// rebuilds the policy from the manager's current configuration, so it
+    /// takes effect on the next diagnostics processed without restarting
+    /// capture. Each change is recorded in `audit_log` when given.
+    pub fn follow_privacy_config(
+        &self,
+        manager: Arc<DynamicConfigManager>,
+        audit_log: Option<AuditLog>,
+    ) -> tokio::task::JoinHandle<()>
+    where
+        C: 'static,
+        P: 'static,
+        F: 'static,
+    {
+        let service = self.clone();
+        let mut changes = manager.subscribe_to_changes();
+
+        tokio::spawn(async move {
+            loop {
+                let change = match changes.recv().await {
+                    Ok(change) if change.field_path.starts_with("privacy.") => Some(change),
+                    Ok(_) => continue,
+                    // Missed notifications: resync from the current configuration
+                    Err(broadcast::error::RecvError::Lagged(_)) => None,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+
+                let policy = manager.get_config().await.privacy.to_privacy_policy();
+                if let Err(e) = service.update_privacy_policy(policy) {
+                    tracing::error!("Failed to apply privacy policy change: {}", e);
+                    continue;
+                }
+
+                if let Some(change) = &change {
+                    tracing::info!("Privacy policy reloaded: {} changed", change.field_path);
+                }
+                if let Some(audit_log) = &audit_log {
+                    let details = match &change {
+                        Some(change) => serde_json::json!({
+                            "field": change.field_path,
+                            "old_value": change.old_value,
+                            "new_value": change.new_value,
+                        }),
+                        None => serde_json::json!({ "field": "privacy", "resync": true }),
+                    };
+                    if let Err(e) = audit_log.record("privacy_policy_changed", details) {
+                        tracing::warn!("Failed to record privacy change in audit log: {}", e);
+                    }
+                }
+            }
+        })
+    }
+
+    /// Replace the severity rules; they apply from the next diagnostics processed
+    pub fn update_severity_rules(&self, rules: SeverityRules) -> Result<()> {
+        *self
+            .severity_rules
+            .write()
+            .map_err(|_| anyhow::anyhow!("Severity rules lock poisoned"))? = rules;
+        Ok(())
+    }
+
+    /// Keep the severity rules in sync with a dynamic configuration.
+    ///
+    /// Rules that fail to compile are logged and the previous rules stay
+    /// in effect.
+    pub fn follow_severity_rules(&self, manager: Arc<DynamicConfigManager>) -> tokio::task::JoinHandle<()>
+    where
+        C: 'static,
+        P: 'static,
+        F: 'static,
+    {
+        let service = self.clone();
+        let mut changes = manager.subscribe_to_changes();
+
+        tokio::spawn(async move {
+            loop {
+                match changes.recv().await {
+                    Ok(change) if change.field_path.starts_with("severity_rules.") => {}
+                    Ok(_) => continue,
+                    Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+
+                let result = SeverityRules::from_config(&manager.get_config().await.severity_rules)
+                    .and_then(|rules| service.update_severity_rules(rules));
+                match result {
+                    Ok(()) => tracing::info!("Severity rules reloaded"),
+                    Err(e) => tracing::error!("Failed to apply severity rules change: {:#}", e),
+                }
+            }
+        })
     }
 
     pub async fn clear_incremental_cache(&self) -> Result<()> {
@@ -140,7 +315,7 @@ where
             filtered_count: diagnostics.len(),
         };
 
-        let workspace = raw.workspace.clone().unwrap_or_else(|| WorkspaceInfo {
+        let mut workspace = raw.workspace.clone().unwrap_or_else(|| WorkspaceInfo {
             name: "unknown".to_string(),
             root_path: std::env::current_dir()
                 .unwrap_or_default()
@@ -148,7 +323,11 @@ where
                 .to_string(),
             language: None,
             version: None,
+            roots: Vec::new(),
         });
+        if workspace.roots.is_empty() {
+            workspace.roots = self.workspace_roots.clone();
+        }
 
         DiagnosticSnapshot {
             id: Uuid::new_v4(),
@@ -156,39 +335,69 @@ where
             workspace,
             diagnostics,
             metadata,
+            code_lenses: HashMap::new(),
         }
     }
 
-    async fn notify_subscribers(&self, snapshot: &DiagnosticSnapshot) -> Result<()> {
-        let subscribers = self.subscribers.read().await;
-        for callback in subscribers.iter() {
-            callback(snapshot.clone());
-        }
-        Ok(())
-    }
-}
-
-#[async_trait]
-impl<C, P, F> DiagnosticsCaptureService for CaptureService<C, P, F>
-where
-    C: DiagnosticsCache + Send + Sync,
-    P: PrivacyFilter + Send + Sync,
-    F: FormatConverter + Send + Sync,
-{
-    async fn process_diagnostics(&mut self, raw: RawDiagnostics) -> Result<()> {
-        let is_capturing = *self.is_capturing.read().await;
-        if !is_capturing {
-            return Ok(());
-        }
-
+    /// Run a batch through the pipeline into a new snapshot; returns how many diagnostics it kept
+    async fn capture(&self, raw: RawDiagnostics) -> Result<usize> {
         tracing::debug!("Processing diagnostics from source: {}", raw.source);
 
         // 1. Normalize format across different LSPs
         let normalized = self.format_converter.normalize(raw.clone()).await?;
         tracing::debug!("Normalized {} diagnostics", normalized.len());
 
+        let mut normalized = match &self.baseline {
+            Some((baseline, root)) => {
+                let outcome = baseline.apply(normalized, root);
+                tracing::debug!("Left out {} baselined diagnostics", outcome.suppressed);
+                outcome.new
+            }
+            None => normalized,
+        };
+
+        let remapped = self
+            .severity_rules
+            .read()
+            .map_err(|_| anyhow::anyhow!("Severity rules lock poisoned"))?
+            .apply(&mut normalized);
+        if remapped > 0 {
+            tracing::debug!("Re-mapped the severity of {} diagnostics", remapped);
+        }
+
+        // Tag diagnostics with their workspace root (before paths may be anonymized)
+        let roots = self.roots_for(&raw);
+        if !roots.is_empty() {
+            for diagnostic in &mut normalized {
+                if let Some(root) = crate::core::root_for_path(roots, &diagnostic.file) {
+                    diagnostic.set_workspace_root(&root.name);
+                }
+            }
+        }
+
+        if let Some(mapper) = &self.generated_code {
+            let moved = mapper.attribute_all(&mut normalized);
+            if moved > 0 {
+                tracing::debug!("Re-attributed {} diagnostics from generated code", moved);
+            }
+        }
+
+        if let Some(analyzer) = &self.api_surface {
+            let tagged = analyzer.tag_all(&mut normalized);
+            tracing::debug!("Tagged {} diagnostics on public API", tagged);
+        }
+
+        if let Some(correlator) = &self.crash_reports {
+            let tagged = correlator.tag_all(&mut normalized);
+            tracing::debug!("Correlated {} diagnostics with crash reports", tagged);
+        }
+
         // 2. Apply privacy filtering
-        let filtered = self.privacy_filter.apply(normalized)?;
+        let filtered = self
+            .privacy_filter
+            .read()
+            .map_err(|_| anyhow::anyhow!("Privacy filter lock poisoned"))?
+            .apply(normalized)?;
         tracing::debug!("Filtered to {} diagnostics", filtered.len());
 
         // 3. Deduplicate diagnostics
@@ -241,8 +450,38 @@ where
             snapshot.id,
             snapshot.diagnostics.len()
         );
+        Ok(snapshot.diagnostics.len())
+    }
+
+    async fn notify_subscribers(&self, snapshot: &DiagnosticSnapshot) -> Result<()> {
+        let subscribers = self.subscribers.read().await;
+        for callback in subscribers.iter() {
+            callback(snapshot.clone());
+        }
         Ok(())
     }
+}
+
+#[async_trait]
+impl<C, P, F> DiagnosticsCaptureService for CaptureService<C, P, F>
+where
+    C: DiagnosticsCache + Send + Sync,
+    P: PrivacyFilter + Send + Sync,
+    F: FormatConverter + Send + Sync,
+{
+    #[tracing::instrument(name = "capture", skip_all, fields(source = %raw.source))]
+    async fn process_diagnostics(&mut self, raw: RawDiagnostics) -> Result<()> {
+        let is_capturing = *self.is_capturing.read().await;
+        if !is_capturing {
+            return Ok(());
+        }
+
+        let started = Instant::now();
+        let result = self.capture(raw).await;
+        let captured = *result.as_ref().unwrap_or(&0);
+        telemetry::record_operation(Operation::Capture, started.elapsed(), captured, result.is_ok());
+        result.map(|_| ())
+    }
 
     async fn subscribe(
         &mut self,
@@ -284,6 +523,126 @@ where
             enable_grouping: Arc::clone(&self.enable_grouping),
             enable_incremental: Arc::clone(&self.enable_incremental),
             last_stats: Arc::clone(&self.last_stats),
+            workspace_roots: self.workspace_roots.clone(),
+            generated_code: self.generated_code.clone(),
+            api_surface: self.api_surface.clone(),
+            crash_reports: self.crash_reports.clone(),
+            baseline: self.baseline.clone(),
+            severity_rules: Arc::clone(&self.severity_rules),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::MemoryCache;
+    use crate::core::PrivacyPolicy;
+    use crate::format::FormatConverter as Converter;
+    use crate::privacy::PrivacyFilter as Filter;
+
+    #[tokio::test]
+    async fn test_privacy_policy_follows_dynamic_config() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let manager = Arc::new(
+            DynamicConfigManager::new(temp_dir.path().join("dynamic.toml"))
+                .await
+                .unwrap(),
+        );
+        let audit_log = AuditLog::new(temp_dir.path().join("audit.log"));
+
+        let service = CaptureService::new(
+            MemoryCache::with_defaults(),
+            Filter::new(PrivacyPolicy::default()),
+            Converter::new(),
+        );
+        let handle = service.follow_privacy_config(manager.clone(), Some(audit_log.clone()));
+
+        manager
+            .update_config(|config| {
+                config.privacy.level = "strict".to_string();
+                config.privacy.redaction_patterns = vec![r"token-\w+".to_string()];
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(2);
+        while audit_log.read_events().unwrap().len() < 2 && std::time::Instant::now() < deadline {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
         }
+
+        let policy = service.get_privacy_policy();
+        assert!(policy.include_only_errors);
+        assert_eq!(policy.redaction_patterns, vec![r"token-\w+".to_string()]);
+
+        let events = audit_log.read_events().unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|e| e.event == "privacy_policy_changed"));
+        assert_eq!(events[0].details["field"], "privacy.level");
+        assert_eq!(events[0].details["new_value"], "strict");
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_severity_rules_follow_dynamic_config() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let manager = Arc::new(
+            DynamicConfigManager::new(temp_dir.path().join("dynamic.toml"))
+                .await
+                .unwrap(),
+        );
+
+        let mut service = CaptureService::new(
+            MemoryCache::with_defaults(),
+            Filter::new(PrivacyPolicy::permissive()),
+            Converter::new(),
+        );
+        service.start_capture().await.unwrap();
+        let handle = service.follow_severity_rules(manager.clone());
+
+        manager
+            .update_config(|config| {
+                config.severity_rules.rules = vec![crate::core::SeverityRule {
+                    source: Some("typescript".to_string()),
+                    code: Some("6133".to_string()),
+                    from: Some("warning".to_string()),
+                    to: "information".to_string(),
+                }];
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        let raw = RawDiagnostics {
+            source: "stdin".to_string(),
+            data: serde_json::json!({
+                "diagnostics": [{
+                    "uri": "file:///work/src/app.ts",
+                    "range": {"start": {"line": 2, "character": 6}, "end": {"line": 2, "character": 7}},
+                    "severity": 2,
+                    "code": "6133",
+                    "source": "typescript",
+                    "message": "'x' is declared but its value is never read."
+                }]
+            }),
+            timestamp: Utc::now(),
+            workspace: None,
+        };
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(2);
+        let severity = loop {
+            service.process_diagnostics(raw.clone()).await.unwrap();
+            let snapshot = service.get_current_snapshot().await.unwrap().unwrap();
+            let diagnostic = &snapshot.diagnostics[0];
+            if diagnostic.severity != crate::core::DiagnosticSeverity::Warning || std::time::Instant::now() > deadline {
+                assert_eq!(diagnostic.original_severity(), Some(crate::core::DiagnosticSeverity::Warning));
+                break diagnostic.severity;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        };
+        assert_eq!(severity, crate::core::DiagnosticSeverity::Information);
+
+        handle.abort();
     }
 }
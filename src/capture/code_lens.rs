@@ -0,0 +1,291 @@
+//! Code lenses and inlay hints from recorded LSP traffic
+//!
+//! Editors ask servers for `textDocument/codeLens` and
+//! `textDocument/inlayHint` as documents are shown, so a recorded session
+//! already contains them; collection only has to pair each response with
+//! its request. Lenses resolved later through `codeLens/resolve` replace
+//! their unresolved counterpart, and the latest response for a document
+//! replaces earlier ones, mirroring what the editor displays.
+//!
+//! Servers don't name the symbol a lens belongs to, so the identifier at the
+//! start of the lens range is read from the document text the client sent
+//! in `didOpen` or a full-text `didChange`.
+//!
+//! Without a recorded session, live capture asks the servers it runs for
+//! lenses itself and parses them the same way.
+
+use super::lsp_trace::{LspTrace, TraceDirection};
+use crate::core::{CodeLens, CodeLensKind, Range};
+use crate::format::format_converter::utils::normalize_file_path;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Request awaiting its response, by JSON-RPC id
+enum Pending {
+    Lenses(String),
+    Hints(String),
+    Resolve(Value),
+}
+
+/// A lens along with the `data` a server uses to resolve it
+struct Collected {
+    data: Option<Value>,
+    lens: CodeLens,
+}
+
+/// Latest code lenses and inlay hints of every document in `trace`, by file
+pub fn collect_code_lenses(trace: &LspTrace) -> HashMap<PathBuf, Vec<CodeLens>> {
+    let mut texts: HashMap<String, String> = HashMap::new();
+    let mut pending: HashMap<String, Pending> = HashMap::new();
+    let mut lenses: HashMap<String, Vec<Collected>> = HashMap::new();
+    let mut hints: HashMap<String, Vec<CodeLens>> = HashMap::new();
+
+    for message in &trace.messages {
+        let body = &message.message;
+        match (message.direction, message.method()) {
+            (TraceDirection::ClientToServer, Some(method)) => {
+                let params = &body["params"];
+                let uri = params["textDocument"]["uri"].as_str().map(str::to_string);
+                match method {
+                    "textDocument/didOpen" => {
+                        if let (Some(uri), Some(text)) = (uri, params["textDocument"]["text"].as_str()) {
+                            texts.insert(uri, text.to_string());
+                        }
+                    }
+                    "textDocument/didChange" => {
+                        let Some(uri) = uri else { continue };
+                        // Incremental edits aren't applied; symbols are skipped until the next full text
+                        match params["contentChanges"].as_array().and_then(|changes| changes.last()) {
+                            Some(change) if change.get("range").is_none() => {
+                                if let Some(text) = change["text"].as_str() {
+                                    texts.insert(uri, text.to_string());
+                                }
+                            }
+                            _ => {
+                                texts.remove(&uri);
+                            }
+                        }
+                    }
+                    "textDocument/codeLens" | "textDocument/inlayHint" | "codeLens/resolve" => {
+                        let Some(id) = body.get("id") else { continue };
+                        let request = match (method, uri) {
+                            ("codeLens/resolve", _) => Pending::Resolve(params.clone()),
+                            ("textDocument/codeLens", Some(uri)) => Pending::Lenses(uri),
+                            (_, Some(uri)) => Pending::Hints(uri),
+                            _ => continue,
+                        };
+                        pending.insert(id.to_string(), request);
+                    }
+                    _ => {}
+                }
+            }
+            (TraceDirection::ServerToClient, None) => {
+                let Some(request) = body.get("id").and_then(|id| pending.remove(&id.to_string())) else {
+                    continue;
+                };
+                let result = &body["result"];
+                match request {
+                    Pending::Lenses(uri) => {
+                        let text = texts.get(&uri).map(String::as_str);
+                        let collected = result
+                            .as_array()
+                            .into_iter()
+                            .flatten()
+                            .filter_map(|lens| {
+                                Some(Collected {
+                                    data: lens.get("data").cloned(),
+                                    lens: parse_lens(lens, text)?,
+                                })
+                            })
+                            .collect();
+                        lenses.insert(uri, collected);
+                    }
+                    Pending::Hints(uri) => {
+                        let parsed = result.as_array().into_iter().flatten().filter_map(parse_hint).collect();
+                        hints.insert(uri, parsed);
+                    }
+                    Pending::Resolve(unresolved) => {
+                        let target = lenses.iter_mut().find_map(|(uri, collected)| {
+                            let index = collected.iter().position(|c| {
+                                c.data.as_ref() == unresolved.get("data")
+                                    && serde_json::to_value(&c.lens.range).ok().as_ref() == unresolved.get("range")
+                            })?;
+                            Some((uri.clone(), &mut collected[index]))
+                        });
+                        if let Some((uri, collected)) = target {
+                            if let Some(lens) = parse_lens(result, texts.get(&uri).map(String::as_str)) {
+                                collected.lens = lens;
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut by_file: HashMap<PathBuf, Vec<CodeLens>> = HashMap::new();
+    for (uri, collected) in lenses {
+        by_file
+            .entry(PathBuf::from(normalize_file_path(&uri)))
+            .or_default()
+            .extend(collected.into_iter().map(|c| c.lens));
+    }
+    for (uri, parsed) in hints {
+        by_file.entry(PathBuf::from(normalize_file_path(&uri))).or_default().extend(parsed);
+    }
+    for file_lenses in by_file.values_mut() {
+        file_lenses.sort_by_key(|lens| (lens.range.start.line, lens.range.start.character));
+    }
+    by_file.retain(|_, file_lenses| !file_lenses.is_empty());
+    by_file
+}
+
+/// A `CodeLens` from the LSP, named after the identifier its range starts at in `text`
+pub(crate) fn parse_lens(lens: &Value, text: Option<&str>) -> Option<CodeLens> {
+    let range: Range = serde_json::from_value(lens.get("range")?.clone()).ok()?;
+    let title = lens["command"]["title"].as_str().unwrap_or_default().to_string();
+    let command = lens["command"]["command"].as_str().map(str::to_string);
+    Some(CodeLens {
+        kind: CodeLensKind::CodeLens,
+        symbol: text.and_then(|text| identifier_at(text, &range)),
+        references: parse_references(&title),
+        test_status: parse_test_status(&title, command.as_deref()),
+        range,
+        title,
+        command,
+    })
+}
+
+fn parse_hint(hint: &Value) -> Option<CodeLens> {
+    let position = hint.get("position")?.clone();
+    let range: Range = serde_json::from_value(serde_json::json!({ "start": position, "end": position })).ok()?;
+    // Labels are either a string or a list of parts
+    let title = match &hint["label"] {
+        Value::String(label) => label.clone(),
+        Value::Array(parts) => parts.iter().filter_map(|part| part["value"].as_str()).collect(),
+        _ => return None,
+    };
+    Some(CodeLens {
+        kind: CodeLensKind::InlayHint,
+        range,
+        symbol: None,
+        title,
+        command: None,
+        references: None,
+        test_status: None,
+    })
+}
+
+fn identifier_at(text: &str, range: &Range) -> Option<String> {
+    let line = text.lines().nth(range.start.line as usize)?;
+    let identifier: String = line
+        .chars()
+        .skip(range.start.character as usize)
+        .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '$')
+        .collect();
+    (!identifier.is_empty()).then_some(identifier)
+}
+
+/// Reference count from titles like "3 references", "1 usage" or "No references"
+pub fn parse_references(title: &str) -> Option<u32> {
+    let title = title.to_lowercase();
+    let words: Vec<&str> = title.split_whitespace().collect();
+    words.windows(2).find_map(|pair| {
+        if !(pair[1].starts_with("reference") || pair[1].starts_with("usage")) {
+            return None;
+        }
+        match pair[0] {
+            "no" => Some(0),
+            count => count.parse().ok(),
+        }
+    })
+}
+
+/// Test status of a lens, from its title or the command it runs
+pub fn parse_test_status(title: &str, command: Option<&str>) -> Option<String> {
+    let title = title.to_lowercase();
+    let status = if title.contains("fail") {
+        "failed"
+    } else if title.contains("pass") {
+        "passed"
+    } else if title.contains("skip") {
+        "skipped"
+    } else if title.contains("test") || command.is_some_and(|command| command.to_lowercase().contains("test")) {
+        "runnable"
+    } else {
+        return None;
+    };
+    Some(status.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::lsp_trace::TraceMessage;
+    use serde_json::json;
+
+    fn range(line: u32, start: u32, end: u32) -> Value {
+        json!({ "start": { "line": line, "character": start }, "end": { "line": line, "character": end } })
+    }
+
+    #[test]
+    fn test_lenses_are_paired_with_requests_and_resolved() {
+        let uri = "file:///work/src/lib.rs";
+        let text = "pub fn used() {}\n\nfn unused() {}\n\n#[test]\nfn checks() {}\n";
+        let messages = [
+            (TraceDirection::ClientToServer, json!({ "method": "textDocument/didOpen", "params": { "textDocument": { "uri": uri, "text": text } } })),
+            (TraceDirection::ClientToServer, json!({ "id": 7, "method": "textDocument/codeLens", "params": { "textDocument": { "uri": uri } } })),
+            (TraceDirection::ServerToClient, json!({ "id": 7, "result": [
+                { "range": range(0, 7, 11), "command": { "title": "2 references", "command": "editor.action.showReferences" } },
+                { "range": range(2, 3, 9), "data": { "id": 1 } },
+                { "range": range(5, 3, 9), "command": { "title": "▶︎ Run Test", "command": "rust-analyzer.runSingle" } },
+            ] })),
+            (TraceDirection::ClientToServer, json!({ "id": 8, "method": "codeLens/resolve", "params": { "range": range(2, 3, 9), "data": { "id": 1 } } })),
+            (TraceDirection::ServerToClient, json!({ "id": 8, "result": { "range": range(2, 3, 9), "command": { "title": "0 references" } } })),
+            (TraceDirection::ClientToServer, json!({ "id": 9, "method": "textDocument/inlayHint", "params": { "textDocument": { "uri": uri } } })),
+            (TraceDirection::ServerToClient, json!({ "id": 9, "result": [
+                { "position": { "line": 0, "character": 14 }, "label": [{ "value": "-> " }, { "value": "()" }] },
+            ] })),
+        ];
+        let trace = LspTrace {
+            server: "rust-analyzer".to_string(),
+            root: None,
+            recorded_at: chrono::Utc::now(),
+            messages: messages
+                .into_iter()
+                .enumerate()
+                .map(|(seq, (direction, message))| TraceMessage {
+                    seq: seq as u64,
+                    elapsed_ms: 0,
+                    direction,
+                    message,
+                })
+                .collect(),
+        };
+
+        let collected = collect_code_lenses(&trace);
+        let lenses = &collected[&PathBuf::from("/work/src/lib.rs")];
+        assert_eq!(lenses.len(), 4);
+
+        let summary: Vec<_> = lenses
+            .iter()
+            .filter(|lens| lens.kind == CodeLensKind::CodeLens)
+            .map(|lens| (lens.symbol.as_deref(), lens.references, lens.test_status.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (Some("used"), Some(2), None),
+                (Some("unused"), Some(0), None),
+                (Some("checks"), None, Some("runnable")),
+            ]
+        );
+
+        let hint = lenses.iter().find(|lens| lens.kind == CodeLensKind::InlayHint).unwrap();
+        assert_eq!(hint.title, "-> ()");
+        assert_eq!(parse_references("No references"), Some(0));
+        assert_eq!(parse_test_status("✓ 3 passed", None).as_deref(), Some("passed"));
+    }
+}
@@ -0,0 +1,252 @@
+//! `cargo clippy --message-format json` import
+//!
+//! Cargo prints one JSON object per line. Only `compiler-message` lines
+//! carry diagnostics; each becomes one diagnostic at its primary span, with
+//! the other labelled spans as related information. Lints named `clippy::*`
+//! get the `clippy` source and everything else the compiler's. Messages
+//! without a span, like the closing "N warnings emitted", are left out, as
+//! are repeats of a message cargo prints once per target that builds the
+//! same file. Lines that aren't JSON, such as progress output captured
+//! with `2>&1`, are ignored.
+
+use super::{resolve_file, ImportReport, SkippedRow};
+use crate::core::{Diagnostic, DiagnosticSeverity, Location, Position, Range, RelatedInformation};
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// Source of clippy lints
+pub const CLIPPY_SOURCE: &str = "clippy";
+/// Source of compiler errors and lints cargo reports alongside clippy's
+pub const RUSTC_SOURCE: &str = "rustc";
+
+#[derive(Debug, Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<CompilerMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompilerMessage {
+    message: String,
+    code: Option<Code>,
+    level: String,
+    #[serde(default)]
+    spans: Vec<Span>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Code {
+    code: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Span {
+    file_name: String,
+    line_start: u32,
+    line_end: u32,
+    column_start: u32,
+    column_end: u32,
+    is_primary: bool,
+    label: Option<String>,
+}
+
+impl Span {
+    /// Cargo's one-based, end-exclusive columns as an LSP range
+    fn range(&self) -> Range {
+        Range {
+            start: Position {
+                line: self.line_start.saturating_sub(1),
+                character: self.column_start.saturating_sub(1),
+            },
+            end: Position {
+                line: self.line_end.saturating_sub(1),
+                character: self.column_end.saturating_sub(1),
+            },
+        }
+    }
+}
+
+/// Reads the JSON lines cargo prints with `--message-format json`
+#[derive(Debug, Clone, Default)]
+pub struct ClippyImporter {
+    base_dir: Option<PathBuf>,
+}
+
+impl ClippyImporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolve relative file paths against `dir`, the workspace root cargo ran in
+    pub fn with_base_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.base_dir = Some(dir.into());
+        self
+    }
+
+    pub fn import(&self, input: &str) -> Result<ImportReport> {
+        let mut report = ImportReport::default();
+        let mut seen = HashSet::new();
+        for (index, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if !line.starts_with('{') {
+                continue;
+            }
+            let message = match serde_json::from_str::<CargoMessage>(line) {
+                Ok(CargoMessage {
+                    reason,
+                    message: Some(message),
+                }) if reason == "compiler-message" => message,
+                Ok(_) => continue,
+                Err(e) => {
+                    report.skipped.push(SkippedRow {
+                        line: index + 1,
+                        reason: format!("Invalid cargo message: {e}"),
+                    });
+                    continue;
+                }
+            };
+            let Some(diagnostic) = self.diagnostic(message) else {
+                continue;
+            };
+            let key = (
+                diagnostic.file.clone(),
+                diagnostic.range.start.line,
+                diagnostic.range.start.character,
+                diagnostic.code.clone(),
+                diagnostic.message.clone(),
+            );
+            if seen.insert(key) {
+                report.diagnostics.push(diagnostic);
+            }
+        }
+        Ok(report)
+    }
+
+    fn diagnostic(&self, message: CompilerMessage) -> Option<Diagnostic> {
+        let primary = message.spans.iter().find(|span| span.is_primary)?;
+        let severity = match message.level.as_str() {
+            level if level.starts_with("error") => DiagnosticSeverity::Error,
+            "warning" => DiagnosticSeverity::Warning,
+            "note" | "failure-note" => DiagnosticSeverity::Information,
+            _ => DiagnosticSeverity::Hint,
+        };
+        let code = message.code.map(|code| code.code);
+        let source = match &code {
+            Some(code) if code.starts_with("clippy::") => CLIPPY_SOURCE,
+            _ => RUSTC_SOURCE,
+        };
+
+        let related: Vec<RelatedInformation> = message
+            .spans
+            .iter()
+            .filter(|span| !span.is_primary)
+            .filter_map(|span| {
+                Some(RelatedInformation {
+                    location: Location {
+                        uri: resolve_file(&span.file_name, self.base_dir.as_deref()),
+                        range: span.range(),
+                    },
+                    message: span.label.clone()?,
+                })
+            })
+            .collect();
+
+        let mut diagnostic = Diagnostic::new(
+            resolve_file(&primary.file_name, self.base_dir.as_deref()),
+            primary.range(),
+            severity,
+            message.message,
+            source.to_string(),
+        );
+        diagnostic.code = code;
+        diagnostic.related_information = (!related.is_empty()).then_some(related);
+        Some(diagnostic)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(file: &str, line: u32, columns: (u32, u32), primary: bool, label: Option<&str>) -> serde_json::Value {
+        serde_json::json!({
+            "file_name": file, "byte_start": 0, "byte_end": 0,
+            "line_start": line, "line_end": line,
+            "column_start": columns.0, "column_end": columns.1,
+            "is_primary": primary, "label": label, "text": [],
+            "suggested_replacement": null, "expansion": null
+        })
+    }
+
+    fn compiler_message(level: &str, code: Option<&str>, text: &str, spans: Vec<serde_json::Value>) -> String {
+        serde_json::json!({
+            "reason": "compiler-message",
+            "package_id": "demo 0.1.0 (path+file:///work/demo)",
+            "manifest_path": "/work/demo/Cargo.toml",
+            "target": {"name": "demo", "kind": ["lib"]},
+            "message": {
+                "$message_type": "diagnostic",
+                "message": text,
+                "code": code.map(|code| serde_json::json!({"code": code, "explanation": null})),
+                "level": level,
+                "spans": spans,
+                "children": [],
+                "rendered": text
+            }
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_cargo_messages() {
+        let needless_return = compiler_message(
+            "warning",
+            Some("clippy::needless_return"),
+            "unneeded `return` statement",
+            vec![span("src/lib.rs", 4, (5, 18), true, None)],
+        );
+        let input = [
+            r#"{"reason":"compiler-artifact","package_id":"dep 1.0.0","target":{"name":"dep"}}"#.to_string(),
+            "    Checking demo v0.1.0 (/work/demo)".to_string(),
+            needless_return.clone(),
+            compiler_message(
+                "error",
+                Some("E0308"),
+                "mismatched types",
+                vec![
+                    span("src/main.rs", 9, (18, 23), true, Some("expected `u32`, found `&str`")),
+                    span("src/main.rs", 9, (12, 15), false, Some("expected due to this")),
+                ],
+            ),
+            // Once per target building src/lib.rs
+            needless_return,
+            compiler_message("warning", None, "1 warning emitted", vec![]),
+            "{\"reason\": ".to_string(),
+            r#"{"reason":"build-finished","success":false}"#.to_string(),
+        ]
+        .join("\n");
+
+        let report = ClippyImporter::new().with_base_dir("/work/demo").import(&input).unwrap();
+        assert_eq!(report.diagnostics.len(), 2);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].line, 7);
+
+        let lint = &report.diagnostics[0];
+        assert_eq!(lint.file, "/work/demo/src/lib.rs");
+        assert_eq!(lint.source, "clippy");
+        assert_eq!(lint.severity, DiagnosticSeverity::Warning);
+        assert_eq!(lint.code.as_deref(), Some("clippy::needless_return"));
+        assert_eq!((lint.range.start.line, lint.range.start.character), (3, 4));
+        assert_eq!(lint.range.end.character, 17);
+
+        let error = &report.diagnostics[1];
+        assert_eq!(error.source, "rustc");
+        assert_eq!(error.severity, DiagnosticSeverity::Error);
+        let related = error.related_information.as_ref().unwrap();
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].message, "expected due to this");
+        assert_eq!(related[0].location.uri, "/work/demo/src/main.rs");
+    }
+}
@@ -0,0 +1,421 @@
+//! CSV and TSV import with configurable column mapping
+//!
+//! Each row becomes one diagnostic. A [`ColumnMapping`], usually loaded
+//! from a TOML file, says which column holds which field, by header name
+//! or one-based position, and how the tool's severities and line numbers
+//! translate. Without one, columns are found by common header names such
+//! as `file`, `line`, `severity` and `message`. Rows that can't be read are
+//! reported and skipped rather than failing the whole import.
+
+use super::{resolve_file, ImportFormat, ImportReport, SkippedRow, IMPORT_SOURCE};
+use crate::core::{Diagnostic, DiagnosticSeverity, Position, Range};
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// How delimited columns map to diagnostic fields
+///
+/// ```toml
+/// source = "shellcheck"
+/// default_severity = "warning"
+///
+/// [columns]
+/// file = "path"
+/// line = "lineno"
+/// message = "text"
+/// code = 4
+///
+/// [severity]
+/// style = "hint"
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColumnMapping {
+    /// Field separator; defaults to `,` for CSV and a tab for TSV
+    pub delimiter: Option<char>,
+    /// Whether the first row names the columns
+    pub has_headers: bool,
+    /// Whether line and column numbers start at 1, as most tools print them
+    pub one_based: bool,
+    /// Source of diagnostics in rows without a source column
+    pub source: Option<String>,
+    /// Severity of rows without one
+    pub default_severity: String,
+    pub columns: Columns,
+    /// The tool's severity names mapped to `error`, `warning`, `information` or `hint`
+    pub severity: HashMap<String, String>,
+}
+
+/// Column holding each diagnostic field, by header name or one-based position
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Columns {
+    pub file: Option<ColumnRef>,
+    pub line: Option<ColumnRef>,
+    pub column: Option<ColumnRef>,
+    pub end_line: Option<ColumnRef>,
+    pub end_column: Option<ColumnRef>,
+    pub severity: Option<ColumnRef>,
+    pub message: Option<ColumnRef>,
+    pub code: Option<ColumnRef>,
+    pub source: Option<ColumnRef>,
+}
+
+/// A column named by its header or its one-based position
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ColumnRef {
+    Position(usize),
+    Name(String),
+}
+
+impl Default for ColumnMapping {
+    fn default() -> Self {
+        Self {
+            delimiter: None,
+            has_headers: true,
+            one_based: true,
+            source: None,
+            default_severity: "warning".to_string(),
+            columns: Columns::default(),
+            severity: HashMap::new(),
+        }
+    }
+}
+
+impl ColumnMapping {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read column mapping {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("Invalid column mapping {}", path.display()))
+    }
+}
+
+/// Header names recognized for each field when the mapping names no column
+const FILE_HEADERS: &[&str] = &["file", "path", "filename", "file_path"];
+const LINE_HEADERS: &[&str] = &["line", "line_number", "lineno", "row"];
+const COLUMN_HEADERS: &[&str] = &["column", "col", "character"];
+const END_LINE_HEADERS: &[&str] = &["end_line", "endline"];
+const END_COLUMN_HEADERS: &[&str] = &["end_column", "endcolumn", "end_col"];
+const SEVERITY_HEADERS: &[&str] = &["severity", "level", "type"];
+const MESSAGE_HEADERS: &[&str] = &["message", "msg", "description", "text"];
+const CODE_HEADERS: &[&str] = &["code", "rule", "rule_id", "check"];
+const SOURCE_HEADERS: &[&str] = &["source", "tool", "linter"];
+
+/// Column positions resolved against the header
+struct Layout {
+    file: usize,
+    message: usize,
+    line: Option<usize>,
+    column: Option<usize>,
+    end_line: Option<usize>,
+    end_column: Option<usize>,
+    severity: Option<usize>,
+    code: Option<usize>,
+    source: Option<usize>,
+}
+
+/// Imports CSV or TSV rows as diagnostics
+pub struct DelimitedImporter {
+    mapping: ColumnMapping,
+    delimiter: char,
+    base_dir: Option<PathBuf>,
+}
+
+impl DelimitedImporter {
+    pub fn new(format: ImportFormat, mapping: ColumnMapping) -> Self {
+        let delimiter = mapping.delimiter.unwrap_or(match format {
+            ImportFormat::Tsv => '\t',
+            _ => ',',
+        });
+        Self {
+            mapping,
+            delimiter,
+            base_dir: None,
+        }
+    }
+
+    /// Resolve relative file paths against `dir`, where the tool ran
+    pub fn with_base_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.base_dir = Some(dir.into());
+        self
+    }
+
+    pub fn import(&self, input: &str) -> Result<ImportReport> {
+        let default_severity = self
+            .parse_severity(&self.mapping.default_severity)
+            .ok_or_else(|| anyhow!("Unknown default_severity '{}'", self.mapping.default_severity))?;
+
+        let mut records = parse_records(input, self.delimiter)?.into_iter();
+        let headers: Vec<String> = if self.mapping.has_headers {
+            match records.next() {
+                Some((_, header)) => header.iter().map(|name| name.trim().to_lowercase()).collect(),
+                None => return Ok(ImportReport::default()),
+            }
+        } else {
+            Vec::new()
+        };
+        let layout = self.layout(&headers)?;
+        let source = self.mapping.source.as_deref().unwrap_or(IMPORT_SOURCE);
+
+        let mut report = ImportReport::default();
+        for (line, fields) in records {
+            match self.diagnostic(&layout, &fields, default_severity, source) {
+                Ok(diagnostic) => report.diagnostics.push(diagnostic),
+                Err(reason) => report.skipped.push(SkippedRow { line, reason }),
+            }
+        }
+        Ok(report)
+    }
+
+    fn layout(&self, headers: &[String]) -> Result<Layout> {
+        let columns = &self.mapping.columns;
+        let find = |field: &str, column: &Option<ColumnRef>, known: &[&str]| -> Result<Option<usize>> {
+            match column {
+                Some(ColumnRef::Position(0)) => bail!("Column positions for `{field}` start at 1"),
+                Some(ColumnRef::Position(position)) => Ok(Some(position - 1)),
+                Some(ColumnRef::Name(name)) => headers
+                    .iter()
+                    .position(|header| header.eq_ignore_ascii_case(name))
+                    .map(Some)
+                    .ok_or_else(|| anyhow!("No column named '{name}' for `{field}`")),
+                None => Ok(known.iter().find_map(|name| headers.iter().position(|header| header == name))),
+            }
+        };
+        let required = |field: &str, column: &Option<ColumnRef>, known: &[&str]| -> Result<usize> {
+            find(field, column, known)?
+                .ok_or_else(|| anyhow!("No column for `{field}`; name one under [columns] in the mapping"))
+        };
+
+        Ok(Layout {
+            file: required("file", &columns.file, FILE_HEADERS)?,
+            message: required("message", &columns.message, MESSAGE_HEADERS)?,
+            line: find("line", &columns.line, LINE_HEADERS)?,
+            column: find("column", &columns.column, COLUMN_HEADERS)?,
+            end_line: find("end_line", &columns.end_line, END_LINE_HEADERS)?,
+            end_column: find("end_column", &columns.end_column, END_COLUMN_HEADERS)?,
+            severity: find("severity", &columns.severity, SEVERITY_HEADERS)?,
+            code: find("code", &columns.code, CODE_HEADERS)?,
+            source: find("source", &columns.source, SOURCE_HEADERS)?,
+        })
+    }
+
+    fn diagnostic(
+        &self,
+        layout: &Layout,
+        fields: &[String],
+        default_severity: DiagnosticSeverity,
+        source: &str,
+    ) -> std::result::Result<Diagnostic, String> {
+        let field = |index: Option<usize>| {
+            index
+                .and_then(|index| fields.get(index))
+                .map(|value| value.trim())
+                .filter(|value| !value.is_empty())
+        };
+        let number = |index: Option<usize>, name: &str| -> std::result::Result<Option<u32>, String> {
+            let Some(value) = field(index) else {
+                return Ok(None);
+            };
+            let number: u32 = value.parse().map_err(|_| format!("Invalid {name} '{value}'"))?;
+            Ok(Some(if self.mapping.one_based { number.saturating_sub(1) } else { number }))
+        };
+
+        let file = field(Some(layout.file)).ok_or("Missing file")?;
+        let message = field(Some(layout.message)).ok_or("Missing message")?;
+        let line = number(layout.line, "line")?.unwrap_or(0);
+        let character = number(layout.column, "column")?.unwrap_or(0);
+        let end_line = number(layout.end_line, "end line")?.unwrap_or(line);
+        let end_character = number(layout.end_column, "end column")?.unwrap_or(character);
+        let severity = match field(layout.severity) {
+            Some(value) => self
+                .parse_severity(value)
+                .ok_or_else(|| format!("Unknown severity '{value}'"))?,
+            None => default_severity,
+        };
+
+        let mut diagnostic = Diagnostic::new(
+            resolve_file(file, self.base_dir.as_deref()),
+            Range {
+                start: Position { line, character },
+                end: Position {
+                    line: end_line,
+                    character: end_character,
+                },
+            },
+            severity,
+            message.to_string(),
+            field(layout.source).unwrap_or(source).to_string(),
+        );
+        diagnostic.code = field(layout.code).map(str::to_string);
+        Ok(diagnostic)
+    }
+
+    /// A severity name through the mapping's `[severity]` table, then common aliases
+    fn parse_severity(&self, value: &str) -> Option<DiagnosticSeverity> {
+        let value = self
+            .mapping
+            .severity
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(value))
+            .map_or(value, |(_, mapped)| mapped.as_str());
+        match value.to_lowercase().as_str() {
+            "error" | "err" | "fatal" | "critical" | "high" => Some(DiagnosticSeverity::Error),
+            "warning" | "warn" | "medium" => Some(DiagnosticSeverity::Warning),
+            "information" | "info" | "note" | "low" => Some(DiagnosticSeverity::Information),
+            "hint" | "style" | "suggestion" => Some(DiagnosticSeverity::Hint),
+            _ => None,
+        }
+    }
+}
+
+/// Records of delimited text, each with the one-based line it starts on
+///
+/// Fields may be quoted to contain the delimiter, newlines or doubled
+/// quotes. Blank lines are skipped.
+fn parse_records(input: &str, delimiter: char) -> Result<Vec<(usize, Vec<String>)>> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let (mut line, mut start) = (1, 1);
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c);
+                }
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                push_record(&mut records, start, std::mem::take(&mut fields));
+                line += 1;
+                start = line;
+            }
+            c if c == delimiter => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        bail!("Unterminated quoted field in the record starting on line {start}");
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        push_record(&mut records, start, fields);
+    }
+    Ok(records)
+}
+
+fn push_record(records: &mut Vec<(usize, Vec<String>)>, line: usize, fields: Vec<String>) {
+    let blank = fields.len() == 1 && fields[0].trim().is_empty();
+    if !blank {
+        records.push((line, fields));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mapped_columns_and_skipped_rows() {
+        let mapping: ColumnMapping = toml::from_str(
+            r#"
+            source = "shellcheck"
+
+            [columns]
+            file = "Path"
+            line = "lineno"
+            message = "text"
+            code = 4
+
+            [severity]
+            style = "hint"
+            "#,
+        )
+        .unwrap();
+        let input = "path,lineno,level,check,text\r\n\
+                     deploy.sh,12,style,SC2086,\"Double quote to prevent globbing, word splitting\"\r\n\
+                     \r\n\
+                     /opt/run.sh,3,error,SC1009,\"Line one\nline \"\"two\"\"\"\r\n\
+                     deploy.sh,twelve,warning,SC2034,unused\r\n";
+
+        let report = DelimitedImporter::new(ImportFormat::Csv, mapping)
+            .with_base_dir("/work")
+            .import(input)
+            .unwrap();
+
+        assert_eq!(report.diagnostics.len(), 2);
+        let first = &report.diagnostics[0];
+        assert_eq!(first.file, "/work/deploy.sh");
+        assert_eq!(first.range.start, Position { line: 11, character: 0 });
+        assert_eq!(first.severity, DiagnosticSeverity::Hint);
+        assert_eq!(first.code.as_deref(), Some("SC2086"));
+        assert_eq!(first.message, "Double quote to prevent globbing, word splitting");
+        assert_eq!(first.source, "shellcheck");
+
+        let second = &report.diagnostics[1];
+        assert_eq!(second.file, "/opt/run.sh");
+        assert_eq!(second.severity, DiagnosticSeverity::Error);
+        assert_eq!(second.message, "Line one\nline \"two\"");
+
+        assert_eq!(
+            report.skipped,
+            vec![SkippedRow {
+                line: 6,
+                reason: "Invalid line 'twelve'".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_known_headers_and_positions() {
+        let input = "File\tLine\tColumn\tSeverity\tMessage\tTool\nsrc/a.py\t3\t5\tinfo\tconsider a comprehension\tpylint\n";
+        let report = DelimitedImporter::new(ImportFormat::Tsv, ColumnMapping::default())
+            .import(input)
+            .unwrap();
+        let diagnostic = &report.diagnostics[0];
+        assert_eq!(diagnostic.file, "src/a.py");
+        assert_eq!(diagnostic.range.start, Position { line: 2, character: 4 });
+        assert_eq!(diagnostic.severity, DiagnosticSeverity::Information);
+        assert_eq!(diagnostic.source, "pylint");
+
+        let mapping = ColumnMapping {
+            has_headers: false,
+            one_based: false,
+            columns: Columns {
+                file: Some(ColumnRef::Position(1)),
+                message: Some(ColumnRef::Position(2)),
+                ..Columns::default()
+            },
+            ..ColumnMapping::default()
+        };
+        let report = DelimitedImporter::new(ImportFormat::Csv, mapping)
+            .import("lib.rs,todo left\n")
+            .unwrap();
+        assert_eq!(report.diagnostics[0].severity, DiagnosticSeverity::Warning);
+        assert_eq!(report.diagnostics[0].source, IMPORT_SOURCE);
+
+        let error = DelimitedImporter::new(ImportFormat::Csv, ColumnMapping::default())
+            .import("name,text\nx,y\n")
+            .unwrap_err();
+        assert!(error.to_string().starts_with("No column for `file`"));
+        assert!(parse_records("a,\"open\n", ',').is_err());
+    }
+}
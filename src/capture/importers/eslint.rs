@@ -0,0 +1,157 @@
+//! `eslint --format json` import
+//!
+//! ESLint reports one result per linted file, each with its messages.
+//! Lines and columns are one-based and end columns exclusive; messages
+//! about a whole file, such as ignored-file warnings, carry no position and
+//! land on the first line. Parse failures are reported as fatal messages
+//! without a rule and become errors with no code.
+
+use super::{resolve_file, ImportReport, SkippedRow};
+use crate::core::{Diagnostic, DiagnosticSeverity, Position, Range};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// Source of every diagnostic ESLint reports
+pub const ESLINT_SOURCE: &str = "eslint";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileResult {
+    file_path: String,
+    #[serde(default)]
+    messages: Vec<Message>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Message {
+    rule_id: Option<String>,
+    #[serde(default)]
+    severity: u8,
+    #[serde(default)]
+    fatal: bool,
+    message: String,
+    line: Option<u32>,
+    column: Option<u32>,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+}
+
+/// Reads the JSON ESLint prints with `--format json`
+#[derive(Debug, Clone, Default)]
+pub struct EslintImporter {
+    base_dir: Option<PathBuf>,
+}
+
+impl EslintImporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolve relative file paths against `dir`, where ESLint ran
+    pub fn with_base_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.base_dir = Some(dir.into());
+        self
+    }
+
+    pub fn import(&self, input: &str) -> Result<ImportReport> {
+        let results: Vec<FileResult> = serde_json::from_str(input.trim_start_matches('\u{feff}'))
+            .context("Expected the JSON array ESLint prints with --format json")?;
+
+        let mut report = ImportReport::default();
+        let mut position = 0;
+        for result in results {
+            let file = resolve_file(&result.file_path, self.base_dir.as_deref());
+            for message in result.messages {
+                position += 1;
+                match diagnostic(&file, message) {
+                    Some(diagnostic) => report.diagnostics.push(diagnostic),
+                    None => report.skipped.push(SkippedRow {
+                        line: position,
+                        reason: "Severity 0 (rule turned off)".to_string(),
+                    }),
+                }
+            }
+        }
+        Ok(report)
+    }
+}
+
+fn diagnostic(file: &str, message: Message) -> Option<Diagnostic> {
+    let severity = match (message.fatal, message.severity) {
+        (true, _) | (false, 2) => DiagnosticSeverity::Error,
+        (false, 1) => DiagnosticSeverity::Warning,
+        _ => return None,
+    };
+    let start = Position {
+        line: message.line.unwrap_or(1).saturating_sub(1),
+        character: message.column.unwrap_or(1).saturating_sub(1),
+    };
+    let end = match message.end_line {
+        Some(end_line) => Position {
+            line: end_line.saturating_sub(1),
+            character: message.end_column.unwrap_or(1).saturating_sub(1),
+        },
+        None => start.clone(),
+    };
+
+    let mut diagnostic = Diagnostic::new(
+        file.to_string(),
+        Range { start, end },
+        severity,
+        message.message,
+        ESLINT_SOURCE.to_string(),
+    );
+    diagnostic.code = message.rule_id;
+    Some(diagnostic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eslint_results() {
+        let input = r#"[
+  {
+    "filePath": "/work/web/src/app.ts",
+    "messages": [
+      {"ruleId": "no-unused-vars", "severity": 2, "message": "'x' is defined but never used.",
+       "line": 3, "column": 7, "endLine": 3, "endColumn": 8, "nodeType": "Identifier"},
+      {"ruleId": "eqeqeq", "severity": 1, "message": "Expected '===' and instead saw '=='.",
+       "line": 10, "column": 9}
+    ],
+    "errorCount": 1, "warningCount": 1
+  },
+  {"filePath": "src/clean.ts", "messages": []},
+  {
+    "filePath": "src/broken.ts",
+    "messages": [{"ruleId": null, "fatal": true, "severity": 2, "message": "Parsing error: ';' expected.",
+                  "line": 2, "column": 14}]
+  }
+]"#;
+        let report = EslintImporter::new().with_base_dir("/work/web").import(input).unwrap();
+        assert!(report.skipped.is_empty());
+        assert_eq!(report.diagnostics.len(), 3);
+
+        let unused = &report.diagnostics[0];
+        assert_eq!(unused.file, "/work/web/src/app.ts");
+        assert_eq!(unused.severity, DiagnosticSeverity::Error);
+        assert_eq!(unused.code.as_deref(), Some("no-unused-vars"));
+        assert_eq!(unused.source, "eslint");
+        assert_eq!((unused.range.start.line, unused.range.start.character), (2, 6));
+        assert_eq!((unused.range.end.line, unused.range.end.character), (2, 7));
+
+        let eqeqeq = &report.diagnostics[1];
+        assert_eq!(eqeqeq.severity, DiagnosticSeverity::Warning);
+        assert_eq!(eqeqeq.range.start, eqeqeq.range.end);
+
+        let parse_error = &report.diagnostics[2];
+        assert_eq!(parse_error.file, "/work/web/src/broken.ts");
+        assert_eq!(parse_error.severity, DiagnosticSeverity::Error);
+        assert_eq!(parse_error.code, None);
+
+        assert!(EslintImporter::new().import("not json").is_err());
+    }
+}
@@ -0,0 +1,204 @@
+//! Diagnostics imported from tools without a language server
+//!
+//! Linters like shellcheck and in-house scripts report problems in their
+//! own formats, and CI runs ESLint and clippy without an editor attached.
+//! Importers turn that output into [`Diagnostic`]s, which `lspbridge
+//! import` prints as the LSP-style JSON `export`, `query` and the rest of
+//! the pipeline read from stdin, or records into history next to the
+//! diagnostics captured from language servers.
+
+pub mod clippy;
+pub mod delimited;
+pub mod eslint;
+
+pub use clippy::ClippyImporter;
+pub use delimited::{ColumnMapping, DelimitedImporter};
+pub use eslint::EslintImporter;
+
+use crate::core::{Diagnostic, DiagnosticSeverity, FileHash};
+use crate::history::{DiagnosticSnapshot, HistoryStorage};
+use anyhow::Result;
+use clap::ValueEnum;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Source reported for imported diagnostics that name none
+pub const IMPORT_SOURCE: &str = "import";
+
+/// Input formats `lspbridge import` understands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImportFormat {
+    /// Comma-separated values, columns mapped by `--mapping`
+    Csv,
+    /// Tab-separated values, columns mapped by `--mapping`
+    Tsv,
+    /// `eslint --format json`
+    Eslint,
+    /// `cargo clippy --message-format json`, or any cargo build with it
+    Clippy,
+}
+
+/// An input row that could not become a diagnostic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedRow {
+    /// One-based line of the input the row starts on; for ESLint, the
+    /// message's one-based position in the report
+    pub line: usize,
+    pub reason: String,
+}
+
+/// Diagnostics read from an import
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    pub diagnostics: Vec<Diagnostic>,
+    pub skipped: Vec<SkippedRow>,
+}
+
+impl ImportReport {
+    /// Diagnostics as LSP-style JSON, the format `lspbridge export` reads from stdin
+    pub fn to_lsp_json(&self, source: &str) -> serde_json::Value {
+        let diagnostics: Vec<serde_json::Value> = self
+            .diagnostics
+            .iter()
+            .map(|d| {
+                serde_json::json!({
+                    "uri": d.file,
+                    "range": d.range,
+                    "severity": d.severity as u8,
+                    "code": d.code,
+                    "message": d.message,
+                    "source": d.source,
+                })
+            })
+            .collect();
+        serde_json::json!({ "source": source, "diagnostics": diagnostics })
+    }
+
+    /// Record the imported diagnostics in history, one snapshot per file
+    ///
+    /// Each file's latest snapshot keeps its diagnostics from other sources,
+    /// so imported lints sit next to what language servers reported, while
+    /// those from the imported sources are replaced. Files the import
+    /// reports nothing for keep their history. Returns the number of files
+    /// recorded.
+    pub async fn record(&self, history: &HistoryStorage) -> Result<usize> {
+        let mut by_file: BTreeMap<&str, Vec<Diagnostic>> = BTreeMap::new();
+        for diagnostic in &self.diagnostics {
+            by_file.entry(&diagnostic.file).or_default().push(diagnostic.clone());
+        }
+
+        let files = by_file.len();
+        for (file, mut diagnostics) in by_file {
+            let file = PathBuf::from(file);
+            let sources: HashSet<String> = diagnostics.iter().map(|d| d.source.clone()).collect();
+            // A `since` bound reads past the snapshot cache
+            if let Some(latest) = history
+                .get_snapshots_for_file(&file, Some(UNIX_EPOCH), Some(1))
+                .await?
+                .pop()
+            {
+                let kept = latest.diagnostics.into_iter().filter(|d| !sources.contains(&d.source));
+                diagnostics.splice(0..0, kept);
+            }
+
+            let content = tokio::fs::read(&file).await.unwrap_or_default();
+            let count = |severity| diagnostics.iter().filter(|d| d.severity == severity).count();
+            history
+                .record_snapshot(DiagnosticSnapshot {
+                    id: 0,
+                    timestamp: SystemTime::now(),
+                    file_path: file,
+                    file_hash: FileHash::new(&content),
+                    error_count: count(DiagnosticSeverity::Error),
+                    warning_count: count(DiagnosticSeverity::Warning),
+                    info_count: count(DiagnosticSeverity::Information),
+                    hint_count: count(DiagnosticSeverity::Hint),
+                    diagnostics,
+                })
+                .await?;
+        }
+        Ok(files)
+    }
+}
+
+/// Resolve a reported path against the directory the tool ran in
+fn resolve_file(file: &str, base_dir: Option<&Path>) -> String {
+    match base_dir {
+        Some(base) if Path::new(file).is_relative() => base.join(file).to_string_lossy().into_owned(),
+        _ => file.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Position, Range};
+    use crate::history::HistoryConfig;
+    use tempfile::TempDir;
+
+    fn lint(file: &str, line: u32, message: &str, source: &str) -> Diagnostic {
+        let position = Position { line, character: 0 };
+        Diagnostic::new(
+            file.to_string(),
+            Range {
+                start: position.clone(),
+                end: position,
+            },
+            DiagnosticSeverity::Warning,
+            message.to_string(),
+            source.to_string(),
+        )
+    }
+
+    async fn latest_messages(history: &HistoryStorage, file: &str) -> Vec<String> {
+        let mut snapshots = history
+            .get_snapshots_for_file(Path::new(file), Some(UNIX_EPOCH), Some(1))
+            .await
+            .unwrap();
+        let mut messages: Vec<String> = snapshots.pop().unwrap().diagnostics.into_iter().map(|d| d.message).collect();
+        messages.sort();
+        messages
+    }
+
+    #[tokio::test]
+    async fn test_record_merges_with_other_sources() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = HistoryStorage::new(HistoryConfig {
+            db_path: temp_dir.path().join("history.db"),
+            ..HistoryConfig::default()
+        })
+        .await
+        .unwrap();
+
+        let captured = ImportReport {
+            diagnostics: vec![lint("/work/src/lib.rs", 1, "unresolved import", "rust-analyzer")],
+            skipped: vec![],
+        };
+        captured.record(&history).await.unwrap();
+
+        let first_run = ImportReport {
+            diagnostics: vec![
+                lint("/work/src/lib.rs", 4, "unneeded `return` statement", "clippy"),
+                lint("/work/src/main.rs", 2, "redundant clone", "clippy"),
+            ],
+            skipped: vec![],
+        };
+        assert_eq!(first_run.record(&history).await.unwrap(), 2);
+        assert_eq!(
+            latest_messages(&history, "/work/src/lib.rs").await,
+            vec!["unneeded `return` statement", "unresolved import"]
+        );
+
+        let second_run = ImportReport {
+            diagnostics: vec![lint("/work/src/lib.rs", 8, "this `if` has identical blocks", "clippy")],
+            skipped: vec![],
+        };
+        assert_eq!(second_run.record(&history).await.unwrap(), 1);
+        assert_eq!(
+            latest_messages(&history, "/work/src/lib.rs").await,
+            vec!["this `if` has identical blocks", "unresolved import"]
+        );
+        assert_eq!(latest_messages(&history, "/work/src/main.rs").await, vec!["redundant clone"]);
+    }
+}
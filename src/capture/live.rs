@@ -0,0 +1,818 @@
+//! Live capture straight from language servers
+//!
+//! Without an editor extension pushing diagnostics, LSPbridge can start the
+//! workspace's language servers itself. Each server is initialized the way
+//! an editor would initialize it, requests it sends back are answered from
+//! its profile, and every `textDocument/publishDiagnostics` notification is
+//! forwarded as a [`LiveEvent`]. Servers that only report on open documents
+//! get the workspace's source files opened for them. Servers with a code
+//! lens provider are asked for the lenses of every document they publish
+//! diagnostics for, and lenses without a command are resolved when the
+//! server supports it.
+//!
+//! [`LiveCapture`] keeps the latest diagnostics and code lenses of every
+//! document, as an editor does, feeds them to a capture service and records
+//! the documents that changed in history.
+
+use super::code_lens::parse_lens;
+use super::lsp_trace::{read_message, write_message};
+use crate::core::{
+    CodeLens, DiagnosticSnapshot, DiagnosticsCaptureService, FileHash, LanguageServerLaunch, LanguageServerProfiles,
+    RawDiagnostics,
+};
+use crate::format::format_converter::utils::normalize_file_path;
+use crate::history::{self, HistoryStorage};
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, BufReader};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Source under which live diagnostics are normalized
+pub const LIVE_SOURCE: &str = "lsp";
+
+/// Request id of `initialize`; code lens requests are numbered after it
+const INITIALIZE_ID: u64 = 1;
+
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules", "dist", "build", "vendor", "__pycache__"];
+
+/// Servers started when the workspace has one of their marker files
+const DEFAULT_SERVERS: &[(&str, &[&str])] = &[
+    ("rust-analyzer", &["Cargo.toml"]),
+    ("typescript-language-server", &["tsconfig.json", "jsconfig.json", "package.json"]),
+    ("pylsp", &["pyproject.toml", "setup.py", "setup.cfg", "requirements.txt"]),
+];
+
+/// Servers to run for a workspace: defaults whose marker files exist, then any configured profile
+pub fn detect_servers(profiles: &LanguageServerProfiles) -> Vec<String> {
+    let mut servers: Vec<String> = DEFAULT_SERVERS
+        .iter()
+        .filter(|(_, markers)| markers.iter().any(|marker| profiles.root().join(marker).exists()))
+        .map(|(server, _)| server.to_string())
+        .collect();
+    let mut configured: Vec<&str> = profiles.servers().collect();
+    configured.sort_unstable();
+    for server in configured {
+        if !servers.iter().any(|s| s == server) {
+            servers.push(server.to_string());
+        }
+    }
+    servers
+}
+
+/// `(extension, languageId)` of documents a server only reports on once they are open
+fn document_languages(server: &str) -> &'static [(&'static str, &'static str)] {
+    match server {
+        "typescript-language-server" | "vtsls" => &[
+            ("ts", "typescript"),
+            ("tsx", "typescriptreact"),
+            ("js", "javascript"),
+            ("jsx", "javascriptreact"),
+        ],
+        "pylsp" | "pyright-langserver" | "basedpyright-langserver" => &[("py", "python")],
+        _ => &[],
+    }
+}
+
+/// Up to `limit` workspace files `server` needs opened, with their language ids
+pub fn documents_to_open(root: &Path, server: &str, limit: usize) -> Vec<(PathBuf, &'static str)> {
+    let languages = document_languages(server);
+    if languages.is_empty() {
+        return Vec::new();
+    }
+    let mut documents: Vec<(PathBuf, &'static str)> = walkdir::WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            entry.depth() == 0
+                || !(name.starts_with('.') || (entry.file_type().is_dir() && SKIPPED_DIRS.contains(&name.as_ref())))
+        })
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| {
+            let extension = entry.path().extension()?.to_str()?.to_string();
+            let (_, language) = languages.iter().find(|(ext, _)| *ext == extension)?;
+            Some((entry.into_path(), *language))
+        })
+        .collect();
+    documents.sort();
+    documents.truncate(limit);
+    documents
+}
+
+/// What a running language server reported
+#[derive(Debug, Clone, PartialEq)]
+pub enum LiveEvent {
+    /// The diagnostics of one document, replacing its previous ones
+    Published {
+        server: String,
+        uri: String,
+        diagnostics: Vec<Value>,
+    },
+    /// The code lenses of one document, replacing its previous ones
+    Lenses {
+        server: String,
+        uri: String,
+        lenses: Vec<CodeLens>,
+    },
+    /// The server exited or failed; its diagnostics and lenses are dropped
+    Stopped { server: String, reason: String },
+}
+
+/// Start each server in the background, sending what they report to the returned receiver
+pub fn spawn_servers(
+    sessions: Vec<(LanguageServerLaunch, Vec<(PathBuf, &'static str)>)>,
+) -> (mpsc::Receiver<LiveEvent>, Vec<JoinHandle<()>>) {
+    let (sender, receiver) = mpsc::channel(256);
+    let handles = sessions
+        .into_iter()
+        .map(|(launch, documents)| {
+            let events = sender.clone();
+            tokio::spawn(async move {
+                let server = launch.server.clone();
+                let reason = match run_server(&launch, &documents, &events).await {
+                    Ok(()) => "exited".to_string(),
+                    Err(e) => format!("{e:#}"),
+                };
+                let _ = events.send(LiveEvent::Stopped { server, reason }).await;
+            })
+        })
+        .collect();
+    (receiver, handles)
+}
+
+/// Run `launch` and forward its diagnostics to `events` until it exits
+pub async fn run_server(
+    launch: &LanguageServerLaunch,
+    documents: &[(PathBuf, &'static str)],
+    events: &mpsc::Sender<LiveEvent>,
+) -> Result<()> {
+    let mut child = launch
+        .command()
+        .spawn()
+        .with_context(|| format!("Failed to start {}", launch.program.display()))?;
+    let server_in = child.stdin.take().ok_or_else(|| anyhow!("Server stdin unavailable"))?;
+    let server_out = child.stdout.take().ok_or_else(|| anyhow!("Server stdout unavailable"))?;
+    if let Some(stderr) = child.stderr.take() {
+        let server = launch.server.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                tracing::debug!("{}: {}", server, line);
+            }
+        });
+    }
+
+    drive_session(launch, server_out, server_in, documents, events).await?;
+    let status = child.wait().await?;
+    if !status.success() {
+        return Err(anyhow!("{} exited with {}", launch.server, status));
+    }
+    Ok(())
+}
+
+/// Speak LSP as the client of a server connected through `server_out` and `server_in`
+///
+/// Returns when the server closes its output or nobody listens to `events` anymore.
+pub async fn drive_session<R, W>(
+    launch: &LanguageServerLaunch,
+    server_out: R,
+    mut server_in: W,
+    documents: &[(PathBuf, &'static str)],
+    events: &mpsc::Sender<LiveEvent>,
+) -> Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut reader = BufReader::new(server_out);
+    let mut lens_requests: Option<LensRequests> = None;
+    let initialize = json!({
+        "jsonrpc": "2.0",
+        "id": INITIALIZE_ID,
+        "method": "initialize",
+        "params": launch.initialize_params(Some(std::process::id())),
+    });
+    write_message(&mut server_in, &initialize).await?;
+
+    while let Some(message) = read_message(&mut reader).await? {
+        let method = message.get("method").and_then(Value::as_str);
+        match (method, message.get("id")) {
+            (Some("textDocument/publishDiagnostics"), None) => {
+                let params = &message["params"];
+                let Some(uri) = params.get("uri").and_then(Value::as_str) else {
+                    continue;
+                };
+                let event = LiveEvent::Published {
+                    server: launch.server.clone(),
+                    uri: uri.to_string(),
+                    diagnostics: params
+                        .get("diagnostics")
+                        .and_then(Value::as_array)
+                        .cloned()
+                        .unwrap_or_default(),
+                };
+                if events.send(event).await.is_err() {
+                    return Ok(());
+                }
+                if let Some(lens_requests) = &mut lens_requests {
+                    lens_requests.request(&mut server_in, uri).await?;
+                }
+            }
+            (Some(method), Some(id)) => {
+                let result = reply(launch, method, &message["params"]);
+                write_message(&mut server_in, &json!({ "jsonrpc": "2.0", "id": id, "result": result })).await?;
+            }
+            (None, Some(id)) if *id == json!(INITIALIZE_ID) => {
+                if let Some(error) = message.get("error") {
+                    return Err(anyhow!("{} failed to initialize: {}", launch.server, error));
+                }
+                lens_requests = LensRequests::for_capabilities(&message["result"]["capabilities"]);
+                initialized(launch, &mut server_in, documents).await?;
+            }
+            (None, Some(id)) => {
+                let (Some(lens_requests), Some(id)) = (&mut lens_requests, id.as_u64()) else {
+                    continue;
+                };
+                if let Some((uri, lenses)) = lens_requests.respond(&mut server_in, id, &message).await? {
+                    let event = LiveEvent::Lenses {
+                        server: launch.server.clone(),
+                        uri,
+                        lenses,
+                    };
+                    if events.send(event).await.is_err() {
+                        return Ok(());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Finish the handshake and open the documents the server should report on
+async fn initialized<W: AsyncWrite + Unpin>(
+    launch: &LanguageServerLaunch,
+    server_in: &mut W,
+    documents: &[(PathBuf, &'static str)],
+) -> Result<()> {
+    let notify = |method: &str, params: Value| json!({ "jsonrpc": "2.0", "method": method, "params": params });
+
+    write_message(server_in, &notify("initialized", json!({}))).await?;
+    if let Some(params) = launch.configuration_params() {
+        write_message(server_in, &notify("workspace/didChangeConfiguration", params)).await?;
+    }
+    for (path, language) in documents {
+        let Ok(text) = tokio::fs::read_to_string(path).await else {
+            continue;
+        };
+        let params = json!({
+            "textDocument": {
+                "uri": crate::core::language_servers::file_uri(path),
+                "languageId": language,
+                "version": 1,
+                "text": text,
+            }
+        });
+        write_message(server_in, &notify("textDocument/didOpen", params)).await?;
+    }
+    Ok(())
+}
+
+/// Code lens request awaiting its response
+enum PendingLens {
+    Document(String),
+    /// Index of the lens being resolved among its document's lenses
+    Resolve(String, usize),
+}
+
+/// Lenses of one document while some are being resolved
+struct DocumentLenses {
+    lenses: Vec<CodeLens>,
+    unresolved: usize,
+}
+
+/// `textDocument/codeLens` and `codeLens/resolve` requests of a session
+///
+/// A document has at most one round of requests in flight; diagnostics
+/// published meanwhile request its lenses again once the round completes.
+struct LensRequests {
+    /// Whether the server resolves lenses it returns without a command
+    resolve: bool,
+    next_id: u64,
+    pending: HashMap<u64, PendingLens>,
+    documents: HashMap<String, DocumentLenses>,
+    outdated: HashSet<String>,
+}
+
+impl LensRequests {
+    /// Requests for a server with `capabilities`, or `None` when it provides no code lenses
+    fn for_capabilities(capabilities: &Value) -> Option<Self> {
+        let provider = capabilities.get("codeLensProvider")?;
+        if provider.is_null() || *provider == Value::Bool(false) {
+            return None;
+        }
+        Some(Self {
+            resolve: provider["resolveProvider"].as_bool().unwrap_or(false),
+            next_id: INITIALIZE_ID + 1,
+            pending: HashMap::new(),
+            documents: HashMap::new(),
+            outdated: HashSet::new(),
+        })
+    }
+
+    /// Ask for the lenses of `uri`, unless a round for it is still in flight
+    async fn request<W: AsyncWrite + Unpin>(&mut self, server_in: &mut W, uri: &str) -> Result<()> {
+        let requested = self.pending.values().any(|p| matches!(p, PendingLens::Document(u) if u == uri));
+        if requested || self.documents.contains_key(uri) {
+            self.outdated.insert(uri.to_string());
+            return Ok(());
+        }
+        let params = json!({ "textDocument": { "uri": uri } });
+        self.send(server_in, "textDocument/codeLens", params, PendingLens::Document(uri.to_string()))
+            .await
+    }
+
+    async fn send<W: AsyncWrite + Unpin>(
+        &mut self,
+        server_in: &mut W,
+        method: &str,
+        params: Value,
+        pending: PendingLens,
+    ) -> Result<()> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.pending.insert(id, pending);
+        write_message(server_in, &json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })).await
+    }
+
+    /// Handle the response to request `id`; returns a document's lenses once all of them are resolved
+    async fn respond<W: AsyncWrite + Unpin>(
+        &mut self,
+        server_in: &mut W,
+        id: u64,
+        response: &Value,
+    ) -> Result<Option<(String, Vec<CodeLens>)>> {
+        let Some(pending) = self.pending.remove(&id) else {
+            return Ok(None);
+        };
+        let result = &response["result"];
+        let uri = match pending {
+            PendingLens::Document(uri) => {
+                // Servers don't name the symbol a lens belongs to; read it from the file
+                let text = tokio::fs::read_to_string(normalize_file_path(&uri)).await.ok();
+                let mut lenses = Vec::new();
+                let mut unresolved = Vec::new();
+                for lens in result.as_array().into_iter().flatten() {
+                    let Some(parsed) = parse_lens(lens, text.as_deref()) else {
+                        continue;
+                    };
+                    if self.resolve && lens.get("command").map_or(true, Value::is_null) {
+                        unresolved.push((lenses.len(), lens.clone()));
+                    }
+                    lenses.push(parsed);
+                }
+                self.documents.insert(
+                    uri.clone(),
+                    DocumentLenses {
+                        lenses,
+                        unresolved: unresolved.len(),
+                    },
+                );
+                for (index, lens) in unresolved {
+                    self.send(server_in, "codeLens/resolve", lens, PendingLens::Resolve(uri.clone(), index))
+                        .await?;
+                }
+                uri
+            }
+            PendingLens::Resolve(uri, index) => {
+                let Some(document) = self.documents.get_mut(&uri) else {
+                    return Ok(None);
+                };
+                document.unresolved -= 1;
+                if result.is_object() {
+                    let text = tokio::fs::read_to_string(normalize_file_path(&uri)).await.ok();
+                    if let Some(lens) = parse_lens(result, text.as_deref()) {
+                        document.lenses[index] = lens;
+                    }
+                }
+                uri
+            }
+        };
+
+        if self.documents.get(&uri).map_or(true, |document| document.unresolved > 0) {
+            return Ok(None);
+        }
+        let lenses = self.documents.remove(&uri).map(|document| document.lenses).unwrap_or_default();
+        if self.outdated.remove(&uri) {
+            self.request(server_in, &uri).await?;
+        }
+        Ok(Some((uri, lenses)))
+    }
+}
+
+/// Result for a request the server sent; anything but configuration is acknowledged with `null`
+fn reply(launch: &LanguageServerLaunch, method: &str, params: &Value) -> Value {
+    if method != "workspace/configuration" {
+        return Value::Null;
+    }
+    let items = params.get("items").and_then(Value::as_array).cloned().unwrap_or_default();
+    Value::Array(
+        items
+            .iter()
+            .map(|item| {
+                let Some(settings) = &launch.settings else {
+                    return Value::Null;
+                };
+                match item.get("section").and_then(Value::as_str) {
+                    Some(section) => section
+                        .split('.')
+                        .try_fold(settings, |value, key| value.get(key))
+                        .cloned()
+                        .unwrap_or(Value::Null),
+                    None => settings.clone(),
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Latest diagnostics and code lenses of every document across running servers
+#[derive(Debug, Default)]
+pub struct LiveCapture {
+    documents: BTreeMap<(String, String), Vec<Value>>,
+    lenses: BTreeMap<(String, String), Vec<CodeLens>>,
+    /// Documents whose diagnostics changed since the last flush
+    changed: BTreeSet<String>,
+    /// Whether lenses changed since the last flush
+    lenses_changed: bool,
+}
+
+impl LiveCapture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply what a server reported; returns whether any diagnostics or lenses changed
+    pub fn apply(&mut self, event: &LiveEvent) -> bool {
+        match event {
+            LiveEvent::Published {
+                server,
+                uri,
+                diagnostics,
+            } => {
+                let key = (server.clone(), uri.clone());
+                let diagnostics: Vec<Value> = diagnostics
+                    .iter()
+                    .map(|diagnostic| {
+                        let mut diagnostic = diagnostic.clone();
+                        diagnostic["uri"] = Value::String(uri.clone());
+                        diagnostic
+                    })
+                    .collect();
+                let previous = if diagnostics.is_empty() {
+                    self.documents.remove(&key)
+                } else {
+                    self.documents.insert(key, diagnostics.clone())
+                };
+                if previous.unwrap_or_default() == diagnostics {
+                    return false;
+                }
+                self.changed.insert(uri.clone());
+                true
+            }
+            LiveEvent::Lenses { server, uri, lenses } => {
+                let key = (server.clone(), uri.clone());
+                let previous = if lenses.is_empty() {
+                    self.lenses.remove(&key)
+                } else {
+                    self.lenses.insert(key, lenses.clone())
+                };
+                if previous.as_ref().unwrap_or(&Vec::new()) == lenses {
+                    return false;
+                }
+                self.lenses_changed = true;
+                true
+            }
+            LiveEvent::Stopped { server, .. } => {
+                let stopped: Vec<_> = self.documents.keys().filter(|(s, _)| s == server).cloned().collect();
+                for key in &stopped {
+                    self.documents.remove(key);
+                    self.changed.insert(key.1.clone());
+                }
+                let lenses_before = self.lenses.len();
+                self.lenses.retain(|(s, _), _| s != server);
+                self.lenses_changed |= self.lenses.len() != lenses_before;
+                !stopped.is_empty() || self.lenses.len() != lenses_before
+            }
+        }
+    }
+
+    /// Whether diagnostics or lenses changed since the last flush
+    pub fn has_changes(&self) -> bool {
+        !self.changed.is_empty() || self.lenses_changed
+    }
+
+    /// Documents with at least one diagnostic
+    pub fn document_count(&self) -> usize {
+        self.documents.keys().map(|(_, uri)| uri).collect::<BTreeSet<_>>().len()
+    }
+
+    /// Current diagnostics of all documents, ready for capture
+    pub fn raw_diagnostics(&self) -> RawDiagnostics {
+        RawDiagnostics {
+            source: LIVE_SOURCE.to_string(),
+            data: json!({ "diagnostics": self.documents.values().flatten().collect::<Vec<_>>() }),
+            timestamp: chrono::Utc::now(),
+            workspace: None,
+        }
+    }
+
+    /// Current code lenses of all documents, by file
+    pub fn code_lenses(&self) -> HashMap<PathBuf, Vec<CodeLens>> {
+        let mut by_file: HashMap<PathBuf, Vec<CodeLens>> = HashMap::new();
+        for ((_, uri), lenses) in &self.lenses {
+            by_file
+                .entry(PathBuf::from(normalize_file_path(uri)))
+                .or_default()
+                .extend(lenses.iter().cloned());
+        }
+        for lenses in by_file.values_mut() {
+            lenses.sort_by_key(|lens| (lens.range.start.line, lens.range.start.character));
+        }
+        by_file
+    }
+
+    /// Feed the current diagnostics to `capture` and record changed documents in `history`
+    ///
+    /// History receives the diagnostics as they left the capture service, so
+    /// they are privacy-filtered. The snapshot keeps the code lenses of the
+    /// files whose diagnostics made it through, so files the privacy policy
+    /// excludes or anonymizes don't carry lenses either. Returns the new
+    /// snapshot, or `None` when nothing changed since the last flush.
+    pub async fn flush<C: DiagnosticsCaptureService>(
+        &mut self,
+        capture: &mut C,
+        history: Option<&HistoryStorage>,
+    ) -> Result<Option<DiagnosticSnapshot>> {
+        if !self.has_changes() {
+            return Ok(None);
+        }
+        capture.process_diagnostics(self.raw_diagnostics()).await?;
+        let Some(mut snapshot) = capture.get_current_snapshot().await? else {
+            return Ok(None);
+        };
+        self.lenses_changed = false;
+        let captured: HashSet<&Path> = snapshot.diagnostics.iter().map(|d| Path::new(&d.file)).collect();
+        let code_lenses = self
+            .code_lenses()
+            .into_iter()
+            .filter(|(file, _)| captured.contains(file.as_path()))
+            .collect();
+        snapshot.code_lenses = code_lenses;
+
+        let changed = std::mem::take(&mut self.changed);
+        if let Some(history) = history {
+            for uri in changed {
+                let file = normalize_file_path(&uri);
+                let diagnostics: Vec<_> = snapshot.diagnostics.iter().filter(|d| d.file == file).cloned().collect();
+                let content = tokio::fs::read(&file).await.unwrap_or_default();
+                let count = |severity| diagnostics.iter().filter(|d| d.severity == severity).count();
+                history
+                    .record_snapshot(history::DiagnosticSnapshot {
+                        id: 0,
+                        timestamp: SystemTime::now(),
+                        file_path: PathBuf::from(&file),
+                        file_hash: FileHash::new(&content),
+                        error_count: count(crate::core::DiagnosticSeverity::Error),
+                        warning_count: count(crate::core::DiagnosticSeverity::Warning),
+                        info_count: count(crate::core::DiagnosticSeverity::Information),
+                        hint_count: count(crate::core::DiagnosticSeverity::Hint),
+                        diagnostics,
+                    })
+                    .await?;
+            }
+        }
+        Ok(Some(snapshot))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::LanguageServerProfile;
+
+    fn publish(uri: &str, messages: &[&str]) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": {
+                "uri": uri,
+                "diagnostics": messages.iter().map(|message| json!({
+                    "range": { "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 3 } },
+                    "severity": 1,
+                    "source": "pylsp",
+                    "message": message,
+                })).collect::<Vec<_>>(),
+            }
+        })
+    }
+
+    #[test]
+    fn test_detects_servers_from_markers_and_profiles() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join("Cargo.toml"), "[package]")?;
+        std::fs::write(dir.path().join("requirements.txt"), "")?;
+        std::fs::create_dir(dir.path().join("node_modules"))?;
+        std::fs::write(dir.path().join("node_modules/vendored.py"), "")?;
+        std::fs::write(dir.path().join("app.py"), "")?;
+
+        let profiles = LanguageServerProfiles::new(dir.path())
+            .with_profile("pyright-langserver", LanguageServerProfile::default());
+        assert_eq!(detect_servers(&profiles), ["rust-analyzer", "pylsp", "pyright-langserver"]);
+
+        assert_eq!(documents_to_open(dir.path(), "pylsp", 10), [(dir.path().join("app.py"), "python")]);
+        assert!(documents_to_open(dir.path(), "rust-analyzer", 10).is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_session_handshake_and_diagnostics() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join("app.py"), "x = ")?;
+        let profiles = LanguageServerProfiles::new(dir.path()).with_profile(
+            "pylsp",
+            LanguageServerProfile {
+                settings: Some(json!({ "pylsp": { "plugins": { "pyflakes": { "enabled": true } } } })),
+                ..Default::default()
+            },
+        );
+        let launch = profiles.launch("pylsp");
+        let documents = documents_to_open(dir.path(), "pylsp", 10);
+
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        let (client_read, client_write) = tokio::io::split(client);
+        let (events, mut received) = mpsc::channel(8);
+        let session = tokio::spawn(async move { drive_session(&launch, client_read, client_write, &documents, &events).await });
+
+        // Play the server's side of the conversation
+        let (server_read, mut server_write) = tokio::io::split(server);
+        let mut server_read = BufReader::new(server_read);
+        let initialize = read_message(&mut server_read).await?.unwrap();
+        assert_eq!(initialize["method"], "initialize");
+        write_message(&mut server_write, &json!({ "jsonrpc": "2.0", "id": 1, "result": { "capabilities": {} } })).await?;
+
+        assert_eq!(read_message(&mut server_read).await?.unwrap()["method"], "initialized");
+        let configuration = read_message(&mut server_read).await?.unwrap();
+        assert_eq!(configuration["method"], "workspace/didChangeConfiguration");
+        let open = read_message(&mut server_read).await?.unwrap();
+        assert_eq!(open["params"]["textDocument"]["languageId"], "python");
+        assert_eq!(open["params"]["textDocument"]["text"], "x = ");
+
+        let request = json!({
+            "jsonrpc": "2.0", "id": 7, "method": "workspace/configuration",
+            "params": { "items": [{ "section": "pylsp.plugins" }, { "section": "missing" }] }
+        });
+        write_message(&mut server_write, &request).await?;
+        let response = read_message(&mut server_read).await?.unwrap();
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["result"], json!([{ "pyflakes": { "enabled": true } }, null]));
+
+        write_message(&mut server_write, &publish("file:///w/app.py", &["invalid syntax"])).await?;
+        drop(server_write);
+        drop(server_read);
+
+        let event = received.recv().await.unwrap();
+        assert!(matches!(&event, LiveEvent::Published { server, diagnostics, .. } if server == "pylsp" && diagnostics.len() == 1));
+        session.await??;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_session_requests_and_resolves_code_lenses() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("lib.rs");
+        std::fs::write(&file, "fn unused() {}\n#[test]\nfn checks() {}\n")?;
+        let uri = crate::core::language_servers::file_uri(&file);
+        let launch = LanguageServerProfiles::new(dir.path()).launch("rust-analyzer");
+
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        let (client_read, client_write) = tokio::io::split(client);
+        let (events, mut received) = mpsc::channel(8);
+        let session = tokio::spawn(async move { drive_session(&launch, client_read, client_write, &[], &events).await });
+
+        let (server_read, mut server_write) = tokio::io::split(server);
+        let mut server_read = BufReader::new(server_read);
+        read_message(&mut server_read).await?.unwrap();
+        let capabilities = json!({ "codeLensProvider": { "resolveProvider": true } });
+        write_message(&mut server_write, &json!({ "jsonrpc": "2.0", "id": 1, "result": { "capabilities": capabilities } }))
+            .await?;
+        assert_eq!(read_message(&mut server_read).await?.unwrap()["method"], "initialized");
+
+        write_message(&mut server_write, &publish(&uri, &["function `unused` is never used"])).await?;
+        let request = read_message(&mut server_read).await?.unwrap();
+        assert_eq!(request["method"], "textDocument/codeLens");
+        assert_eq!(request["params"]["textDocument"]["uri"], uri.as_str());
+
+        let range = |line: u32| json!({ "start": { "line": line, "character": 3 }, "end": { "line": line, "character": 9 } });
+        let lenses = json!([
+            { "range": range(0), "data": { "references": 0 } },
+            { "range": range(2), "command": { "title": "▶ Run Test", "command": "rust-analyzer.runSingle" } },
+        ]);
+        write_message(&mut server_write, &json!({ "jsonrpc": "2.0", "id": request["id"], "result": lenses })).await?;
+
+        // Only the lens without a command is resolved
+        let resolve = read_message(&mut server_read).await?.unwrap();
+        assert_eq!(resolve["method"], "codeLens/resolve");
+        assert_eq!(resolve["params"]["data"]["references"], 0);
+        let resolved = json!({ "range": range(0), "command": { "title": "0 references", "command": "" } });
+        write_message(&mut server_write, &json!({ "jsonrpc": "2.0", "id": resolve["id"], "result": resolved })).await?;
+        drop(server_write);
+        drop(server_read);
+
+        assert!(matches!(received.recv().await.unwrap(), LiveEvent::Published { .. }));
+        let LiveEvent::Lenses { server, uri: lens_uri, lenses } = received.recv().await.unwrap() else {
+            panic!("expected code lenses");
+        };
+        assert_eq!((server.as_str(), lens_uri), ("rust-analyzer", uri));
+        assert_eq!(lenses.len(), 2);
+        assert_eq!(lenses[0].symbol.as_deref(), Some("unused"));
+        assert_eq!(lenses[0].references, Some(0));
+        assert_eq!(lenses[1].test_status.as_deref(), Some("runnable"));
+        session.await??;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_flush_feeds_capture_and_history() -> Result<()> {
+        use crate::capture::{CaptureService, MemoryCache};
+        use crate::core::PrivacyPolicy;
+        use crate::format::format_converter::FormatConverter;
+        use crate::history::HistoryConfig;
+        use crate::privacy::privacy_filter::PrivacyFilter;
+
+        let dir = tempfile::tempdir()?;
+        let storage = HistoryStorage::new(HistoryConfig {
+            db_path: dir.path().join("history.db"),
+            ..Default::default()
+        })
+        .await?;
+        let mut capture = CaptureService::new(
+            MemoryCache::new(10, 3600),
+            PrivacyFilter::new(PrivacyPolicy::permissive()),
+            FormatConverter::new(),
+        );
+        capture.start_capture().await?;
+
+        let published = |uri: &str, messages: &[&str]| LiveEvent::Published {
+            server: "pylsp".to_string(),
+            uri: uri.to_string(),
+            diagnostics: publish(uri, messages)["params"]["diagnostics"].as_array().cloned().unwrap(),
+        };
+        let mut live = LiveCapture::new();
+        assert!(live.apply(&published("file:///w/a.py", &["one", "two"])));
+        assert!(live.apply(&published("file:///w/b.py", &["three"])));
+        let snapshot = live.flush(&mut capture, Some(&storage)).await?.unwrap();
+        assert_eq!(snapshot.diagnostics.len(), 3);
+        assert_eq!(storage.get_snapshots_for_file(Path::new("/w/a.py"), None, Some(10)).await?[0].error_count, 2);
+
+        // Republishing the same diagnostics is not a change
+        assert!(!live.apply(&published("file:///w/a.py", &["one", "two"])));
+        assert!(live.flush(&mut capture, Some(&storage)).await?.is_none());
+
+        // Lenses are kept with the snapshot for files whose diagnostics were captured
+        let lenses = |uri: &str| LiveEvent::Lenses {
+            server: "pylsp".to_string(),
+            uri: uri.to_string(),
+            lenses: vec![CodeLens {
+                kind: crate::core::CodeLensKind::CodeLens,
+                range: crate::core::Range {
+                    start: crate::core::Position { line: 0, character: 3 },
+                    end: crate::core::Position { line: 0, character: 7 },
+                },
+                symbol: Some("main".to_string()),
+                title: "1 reference".to_string(),
+                command: None,
+                references: Some(1),
+                test_status: None,
+            }],
+        };
+        assert!(live.apply(&lenses("file:///w/a.py")));
+        assert!(live.apply(&lenses("file:///w/c.py")));
+        assert!(!live.apply(&lenses("file:///w/a.py")));
+        let snapshot = live.flush(&mut capture, Some(&storage)).await?.unwrap();
+        assert_eq!(snapshot.code_lenses.keys().collect::<Vec<_>>(), [Path::new("/w/a.py")]);
+        assert_eq!(storage.get_snapshots_for_file(Path::new("/w/a.py"), None, Some(10)).await?.len(), 1);
+
+        assert!(live.apply(&LiveEvent::Stopped {
+            server: "pylsp".to_string(),
+            reason: "exited".to_string(),
+        }));
+        assert_eq!(live.document_count(), 0);
+        Ok(())
+    }
+}
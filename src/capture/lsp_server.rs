@@ -0,0 +1,392 @@
+//! Serving enriched diagnostics back to editors
+//!
+//! The reverse of capture: `lspbridge lsp-server` is configured in the
+//! editor as one more language server, and publishes the diagnostics
+//! LSPbridge has processed through `textDocument/publishDiagnostics`.
+//! Upstream diagnostics are deduplicated and privacy-filtered, and the
+//! project's scan rules are re-run on each document as it is opened or
+//! saved.
+//!
+//! Every published diagnostic names its taxonomy bucket and priority score
+//! at the end of its message and in `data`. Diagnostics that cascade from a
+//! root cause link back to it through `relatedInformation`, and the root
+//! cause lists its cascade, so editors show the grouping natively.
+
+use super::lsp_trace::{read_message, write_message};
+use crate::analyzers::DiagnosticTaxonomy;
+use crate::core::language_servers::file_uri;
+use crate::core::{
+    Diagnostic, DiagnosticGrouper, DiagnosticPrioritizer, DiagnosticTag, PrivacyFilter as _, StaticScanner,
+};
+use crate::format::format_converter::utils::normalize_file_path;
+use crate::privacy::PrivacyFilter;
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncRead, AsyncWrite, BufReader};
+use tokio::sync::mpsc;
+
+/// Name the server reports in `initialize`
+pub const SERVER_NAME: &str = "lspbridge";
+
+/// JSON-RPC error code for requests the server doesn't implement
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Diagnostics as published to editors, by document URI
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnrichedDiagnostics {
+    documents: BTreeMap<String, Vec<Value>>,
+}
+
+impl EnrichedDiagnostics {
+    /// Deduplicate, group and score `diagnostics`; relative paths are resolved against `root`
+    pub fn build(root: &Path, diagnostics: Vec<Diagnostic>) -> Self {
+        let grouper = DiagnosticGrouper::new();
+        let groups = grouper.group_diagnostics(grouper.deduplicate_diagnostics(diagnostics));
+
+        let mut documents: BTreeMap<String, Vec<Value>> = BTreeMap::new();
+        for prioritized in DiagnosticPrioritizer::new().prioritize(groups) {
+            let score = prioritized.priority_score;
+            let group = &prioritized.group;
+            let primary_location = location(root, &group.primary);
+
+            let mut primary = lsp_diagnostic(&group.primary, score);
+            if !group.related.is_empty() {
+                primary["message"] = json!(format!(
+                    "{} [root cause of {}]",
+                    primary["message"].as_str().unwrap_or_default(),
+                    group.related.len()
+                ));
+                primary["relatedInformation"] = group
+                    .related
+                    .iter()
+                    .map(|related| json!({ "location": location(root, related), "message": format!("Cascade: {}", related.message) }))
+                    .collect();
+                primary["data"]["cascade"] = json!(group.related.len());
+            }
+            documents.entry(uri(root, &group.primary)).or_default().push(primary);
+
+            for related in &group.related {
+                let mut diagnostic = lsp_diagnostic(related, score);
+                diagnostic["relatedInformation"] = json!([{
+                    "location": primary_location,
+                    "message": format!("Likely caused by: {}", group.primary.message),
+                }]);
+                diagnostic["data"]["root_cause"] = primary_location.clone();
+                documents.entry(uri(root, related)).or_default().push(diagnostic);
+            }
+        }
+
+        for diagnostics in documents.values_mut() {
+            diagnostics.sort_by_key(|d| (d["range"]["start"]["line"].as_u64(), d["range"]["start"]["character"].as_u64()));
+        }
+        Self { documents }
+    }
+
+    /// Documents with at least one diagnostic
+    pub fn uris(&self) -> impl Iterator<Item = &str> {
+        self.documents.keys().map(String::as_str)
+    }
+
+    /// Published diagnostics of `uri`, empty if it has none
+    pub fn diagnostics(&self, uri: &str) -> &[Value] {
+        self.documents.get(uri).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+/// A diagnostic in LSP form, annotated with its taxonomy and priority
+fn lsp_diagnostic(diagnostic: &Diagnostic, priority: f32) -> Value {
+    let taxonomy = DiagnosticTaxonomy::classify(diagnostic);
+    let mut value = json!({
+        "range": diagnostic.range,
+        "severity": diagnostic.severity as u8,
+        "source": diagnostic.source,
+        "message": format!("{} [{}, priority {:.0}]", diagnostic.message, taxonomy, priority),
+        "data": { "taxonomy": taxonomy.as_str(), "priority": priority },
+    });
+    if let Some(code) = &diagnostic.code {
+        value["code"] = json!(code);
+    }
+    if let Some(tags) = &diagnostic.tags {
+        value["tags"] = tags
+            .iter()
+            .map(|tag| match tag {
+                DiagnosticTag::Unnecessary => 1,
+                DiagnosticTag::Deprecated => 2,
+            })
+            .collect();
+    }
+    value
+}
+
+fn uri(root: &Path, diagnostic: &Diagnostic) -> String {
+    file_uri(&root.join(normalize_file_path(&diagnostic.file)))
+}
+
+fn location(root: &Path, diagnostic: &Diagnostic) -> Value {
+    json!({ "uri": uri(root, diagnostic), "range": diagnostic.range })
+}
+
+/// File path of a `file://` URI
+fn uri_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    // `file:///C:/src` on Windows
+    let path = match path.strip_prefix('/') {
+        Some(rest) if rest.as_bytes().get(1) == Some(&b':') => rest,
+        _ => path,
+    };
+    Some(PathBuf::from(path.replace("%20", " ")))
+}
+
+/// Minimal language server publishing LSPbridge's diagnostics
+pub struct DiagnosticsServer {
+    root: PathBuf,
+    filter: PrivacyFilter,
+    scanner: Option<StaticScanner>,
+    upstream: Vec<Diagnostic>,
+    /// Scan rule diagnostics by file, from its last open or save
+    scanned: HashMap<PathBuf, Vec<Diagnostic>>,
+    /// Documents given diagnostics by the last publish
+    published: BTreeSet<String>,
+    initialized: bool,
+}
+
+impl DiagnosticsServer {
+    pub fn new(root: &Path, filter: PrivacyFilter) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            filter,
+            scanner: None,
+            upstream: Vec::new(),
+            scanned: HashMap::new(),
+            published: BTreeSet::new(),
+            initialized: false,
+        }
+    }
+
+    /// Re-run `scanner` on documents as they are opened or saved
+    pub fn with_scanner(mut self, scanner: StaticScanner) -> Self {
+        self.scanner = Some(scanner);
+        self
+    }
+
+    /// Replace the upstream diagnostics, returning the notifications to send
+    pub fn set_upstream(&mut self, diagnostics: Vec<Diagnostic>) -> Result<Vec<Value>> {
+        self.upstream = self.filter.apply(diagnostics)?;
+        Ok(self.publish())
+    }
+
+    /// Handle one client message, returning the messages to send back
+    ///
+    /// `None` means the client asked the server to exit.
+    pub async fn handle(&mut self, message: &Value) -> Result<Option<Vec<Value>>> {
+        let id = message.get("id").cloned();
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // Responses to requests we never send
+            return Ok(Some(Vec::new()));
+        };
+        let params = &message["params"];
+
+        let outgoing = match method {
+            "initialize" => vec![response(id, json!({
+                "capabilities": { "textDocumentSync": { "openClose": true, "save": true } },
+                "serverInfo": { "name": SERVER_NAME, "version": env!("CARGO_PKG_VERSION") },
+            }))],
+            "initialized" => {
+                self.initialized = true;
+                self.publish()
+            }
+            "textDocument/didOpen" | "textDocument/didSave" => {
+                match params["textDocument"]["uri"].as_str().and_then(uri_path) {
+                    Some(path) => self.rescan(&path).await?,
+                    None => Vec::new(),
+                }
+            }
+            "shutdown" => vec![response(id, Value::Null)],
+            "exit" => return Ok(None),
+            _ => match id {
+                Some(id) => vec![json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": METHOD_NOT_FOUND, "message": format!("Unsupported method {method}") },
+                })],
+                None => Vec::new(),
+            },
+        };
+        Ok(Some(outgoing))
+    }
+
+    /// Serve `client_in`/`client_out` until the client exits or hangs up
+    ///
+    /// Each batch of diagnostics received on `updates` replaces the upstream
+    /// diagnostics and is published straight away.
+    pub async fn run<R, W>(mut self, client_in: R, mut client_out: W, mut updates: mpsc::Receiver<Vec<Diagnostic>>) -> Result<()>
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+        W: AsyncWrite + Unpin,
+    {
+        // Reading a frame isn't cancel-safe, so it gets its own task
+        let (messages_tx, mut messages) = mpsc::channel(16);
+        let reader = tokio::spawn(async move {
+            let mut client_in = BufReader::new(client_in);
+            while let Some(message) = read_message(&mut client_in).await? {
+                if messages_tx.send(message).await.is_err() {
+                    break;
+                }
+            }
+            anyhow::Ok(())
+        });
+
+        loop {
+            let outgoing = tokio::select! {
+                message = messages.recv() => match message {
+                    Some(message) => match self.handle(&message).await? {
+                        Some(outgoing) => outgoing,
+                        None => break,
+                    },
+                    None => break,
+                },
+                Some(diagnostics) = updates.recv() => self.set_upstream(diagnostics)?,
+            };
+            for message in &outgoing {
+                write_message(&mut client_out, message).await?;
+            }
+        }
+
+        reader.abort();
+        Ok(())
+    }
+
+    async fn rescan(&mut self, path: &Path) -> Result<Vec<Value>> {
+        let Some(scanner) = &self.scanner else {
+            return Ok(Vec::new());
+        };
+        let diagnostics = match scanner.scan_file(path).await {
+            Ok(diagnostics) => self.filter.apply(diagnostics)?,
+            Err(e) => {
+                tracing::debug!("Skipping scan of {}: {}", path.display(), e);
+                Vec::new()
+            }
+        };
+        self.scanned.insert(path.to_path_buf(), diagnostics);
+        Ok(self.publish())
+    }
+
+    /// Notifications for every document whose diagnostics may have changed
+    ///
+    /// Documents that lost all their diagnostics get an empty list so the
+    /// editor clears them.
+    fn publish(&mut self) -> Vec<Value> {
+        if !self.initialized {
+            return Vec::new();
+        }
+        let diagnostics = self
+            .upstream
+            .iter()
+            .chain(self.scanned.values().flatten())
+            .cloned()
+            .collect();
+        let enriched = EnrichedDiagnostics::build(&self.root, diagnostics);
+
+        let current: BTreeSet<String> = enriched.uris().map(str::to_string).collect();
+        let notifications = current
+            .union(&self.published)
+            .map(|uri| {
+                json!({
+                    "jsonrpc": "2.0",
+                    "method": "textDocument/publishDiagnostics",
+                    "params": { "uri": uri, "diagnostics": enriched.diagnostics(uri) },
+                })
+            })
+            .collect();
+        self.published = current;
+        notifications
+    }
+}
+
+fn response(id: Option<Value>, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{DiagnosticSeverity, Position, Range};
+
+    fn diagnostic(file: &str, line: u32, message: &str) -> Diagnostic {
+        Diagnostic {
+            id: format!("{file}:{line}:{message}"),
+            file: file.to_string(),
+            range: Range {
+                start: Position { line, character: 4 },
+                end: Position { line, character: 9 },
+            },
+            severity: DiagnosticSeverity::Error,
+            message: message.to_string(),
+            code: Some("E0425".to_string()),
+            source: "rustc".to_string(),
+            related_information: None,
+            tags: None,
+            data: None,
+        }
+    }
+
+    fn request(id: u64, method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+    }
+
+    #[test]
+    fn test_cascades_link_to_their_root_cause() {
+        let root = Path::new("/work");
+        let enriched = EnrichedDiagnostics::build(
+            root,
+            vec![
+                diagnostic("src/lib.rs", 3, "cannot find value `total` in this scope"),
+                diagnostic("src/lib.rs", 9, "cannot find value `total` in this scope"),
+                diagnostic("src/lib.rs", 9, "cannot find value `total` in this scope"),
+                diagnostic("/work/src/main.rs", 1, "unused variable: `x`"),
+            ],
+        );
+
+        assert_eq!(enriched.uris().collect::<Vec<_>>(), vec!["file:///work/src/lib.rs", "file:///work/src/main.rs"]);
+        let lib = enriched.diagnostics("file:///work/src/lib.rs");
+        // The duplicate on line 9 is dropped
+        assert_eq!(lib.len(), 2);
+        assert!(lib[0]["message"].as_str().unwrap().ends_with("[root cause of 1]"));
+        assert_eq!(lib[0]["severity"], 1);
+        assert_eq!(lib[0]["code"], "E0425");
+        assert_eq!(lib[1]["relatedInformation"][0]["location"]["range"]["start"]["line"], 3);
+        assert_eq!(lib[1]["data"]["root_cause"]["uri"], "file:///work/src/lib.rs");
+        assert!(lib[1]["data"]["taxonomy"].is_string());
+        assert!(lib[1]["message"].as_str().unwrap().contains(", priority "));
+    }
+
+    #[tokio::test]
+    async fn test_publishes_after_initialized_and_clears_fixed_documents() -> Result<()> {
+        let mut server = DiagnosticsServer::new(Path::new("/work"), PrivacyFilter::with_permissive_policy());
+        assert!(server.set_upstream(vec![diagnostic("src/a.rs", 1, "first")])?.is_empty());
+
+        let reply = server.handle(&request(1, "initialize", json!({}))).await?.unwrap();
+        assert_eq!(reply[0]["id"], 1);
+        assert_eq!(reply[0]["result"]["serverInfo"]["name"], SERVER_NAME);
+
+        let published = server.handle(&json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} })).await?.unwrap();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0]["params"]["uri"], "file:///work/src/a.rs");
+
+        let published = server.set_upstream(vec![diagnostic("src/b.rs", 1, "second")])?;
+        let cleared: Vec<_> = published
+            .iter()
+            .filter(|n| n["params"]["diagnostics"].as_array().unwrap().is_empty())
+            .map(|n| n["params"]["uri"].as_str().unwrap())
+            .collect();
+        assert_eq!(cleared, vec!["file:///work/src/a.rs"]);
+
+        let reply = server.handle(&request(2, "textDocument/hover", json!({}))).await?.unwrap();
+        assert_eq!(reply[0]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(server.handle(&request(3, "shutdown", Value::Null)).await?.unwrap()[0]["result"], Value::Null);
+        assert!(server.handle(&json!({ "jsonrpc": "2.0", "method": "exit" })).await?.is_none());
+        Ok(())
+    }
+}
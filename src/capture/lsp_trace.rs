@@ -0,0 +1,459 @@
+//! Recording and replaying LSP traffic
+//!
+//! In record mode LSPbridge sits between an editor and a language server as
+//! a stdio proxy, forwarding every JSON-RPC message unchanged and appending
+//! it to a trace file. A trace is JSON Lines: a header naming the server,
+//! then one record per message with its direction and the time since the
+//! session started.
+//!
+//! Replay feeds the `textDocument/publishDiagnostics` notifications of a
+//! trace back through a capture service in recorded order. Each notification
+//! replaces the diagnostics of its document, as it does in an editor, so the
+//! capture sees the same workspace state after every step as it did live.
+//! Traces of real sessions make deterministic reproductions and regression
+//! fixtures for server-specific behavior.
+
+use crate::core::{DiagnosticsCaptureService, LanguageServerLaunch, RawDiagnostics};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use clap::Subcommand;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+
+/// Trace file format version
+pub const TRACE_VERSION: u32 = 1;
+
+/// Source under which replayed diagnostics are normalized
+pub const REPLAY_SOURCE: &str = "lsp";
+
+/// Actions for recording and replaying LSP traffic
+#[derive(Debug, Clone, Subcommand)]
+pub enum LspTraceAction {
+    /// Proxy a language server over stdio and record its traffic
+    ///
+    /// Configure this command as the language server in the editor.
+    Record {
+        /// Language server name, used to look up its project profile
+        #[arg(long)]
+        server: String,
+        /// Trace file to write
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Project root (defaults to the current directory)
+        #[arg(long)]
+        root: Option<PathBuf>,
+    },
+    /// Replay the diagnostics of a trace through capture
+    Replay {
+        /// Trace file to replay
+        trace: PathBuf,
+        /// File for the final diagnostics as JSON (default: stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Which way a message travelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceDirection {
+    ClientToServer,
+    ServerToClient,
+}
+
+/// One line of a trace file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TraceRecord {
+    Header {
+        version: u32,
+        server: String,
+        root: Option<PathBuf>,
+        recorded_at: DateTime<Utc>,
+    },
+    Message(TraceMessage),
+}
+
+/// A recorded JSON-RPC message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceMessage {
+    pub seq: u64,
+    /// Milliseconds since the session started
+    pub elapsed_ms: u64,
+    pub direction: TraceDirection,
+    pub message: Value,
+}
+
+impl TraceMessage {
+    pub fn method(&self) -> Option<&str> {
+        self.message.get("method").and_then(Value::as_str)
+    }
+}
+
+/// Appends messages to a trace file, one line each
+///
+/// Every line is flushed as it is written, so the trace survives the
+/// proxy being killed along with the editor.
+pub struct TraceRecorder {
+    writer: Mutex<std::io::BufWriter<std::fs::File>>,
+    started: Instant,
+    seq: AtomicU64,
+}
+
+impl TraceRecorder {
+    /// Create `path` and write the trace header
+    pub fn create(path: &Path, server: &str, root: Option<&Path>) -> Result<Self> {
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("Failed to create trace file {}", path.display()))?;
+        let recorder = Self {
+            writer: Mutex::new(std::io::BufWriter::new(file)),
+            started: Instant::now(),
+            seq: AtomicU64::new(0),
+        };
+        recorder.write(&TraceRecord::Header {
+            version: TRACE_VERSION,
+            server: server.to_string(),
+            root: root.map(Path::to_path_buf),
+            recorded_at: Utc::now(),
+        })?;
+        Ok(recorder)
+    }
+
+    pub fn record(&self, direction: TraceDirection, message: &Value) -> Result<()> {
+        self.write(&TraceRecord::Message(TraceMessage {
+            seq: self.seq.fetch_add(1, Ordering::Relaxed),
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+            direction,
+            message: message.clone(),
+        }))
+    }
+
+    fn write(&self, record: &TraceRecord) -> Result<()> {
+        let line = serde_json::to_string(record)?;
+        let mut writer = self.writer.lock().map_err(|_| anyhow!("Trace writer lock poisoned"))?;
+        writeln!(writer, "{line}")?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// A loaded trace
+#[derive(Debug, Clone)]
+pub struct LspTrace {
+    pub server: String,
+    pub root: Option<PathBuf>,
+    pub recorded_at: DateTime<Utc>,
+    pub messages: Vec<TraceMessage>,
+}
+
+/// Outcome of replaying a trace
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ReplaySummary {
+    pub messages: usize,
+    /// `publishDiagnostics` notifications fed to capture
+    pub notifications: usize,
+    /// Documents with diagnostics after the last notification
+    pub documents: usize,
+}
+
+impl LspTrace {
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Failed to open trace file {}", path.display()))?;
+        Self::from_lines(std::io::BufReader::new(file).lines())
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        Self::from_lines(content.lines().map(|line| Ok(line.to_string())))
+    }
+
+    fn from_lines(lines: impl Iterator<Item = std::io::Result<String>>) -> Result<Self> {
+        let mut header = None;
+        let mut messages = Vec::new();
+
+        for (index, line) in lines.enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: TraceRecord = serde_json::from_str(&line)
+                .with_context(|| format!("Invalid trace record on line {}", index + 1))?;
+            match record {
+                TraceRecord::Header {
+                    version,
+                    server,
+                    root,
+                    recorded_at,
+                } => {
+                    if version > TRACE_VERSION {
+                        return Err(anyhow!("Unsupported trace version {version}"));
+                    }
+                    header = Some((server, root, recorded_at));
+                }
+                TraceRecord::Message(message) => messages.push(message),
+            }
+        }
+
+        let (server, root, recorded_at) = header.ok_or_else(|| anyhow!("Trace has no header"))?;
+        messages.sort_by_key(|message| message.seq);
+        Ok(Self {
+            server,
+            root,
+            recorded_at,
+            messages,
+        })
+    }
+
+    /// Workspace diagnostics after each `publishDiagnostics` notification
+    ///
+    /// Each entry holds every document's latest diagnostics, each tagged
+    /// with its document `uri`, timestamped as when it was recorded.
+    pub fn raw_diagnostics(&self) -> Vec<RawDiagnostics> {
+        let mut documents: BTreeMap<String, Vec<Value>> = BTreeMap::new();
+        let mut states = Vec::new();
+
+        for message in &self.messages {
+            if message.direction != TraceDirection::ServerToClient
+                || message.method() != Some("textDocument/publishDiagnostics")
+            {
+                continue;
+            }
+            let params = &message.message["params"];
+            let Some(uri) = params.get("uri").and_then(Value::as_str) else {
+                continue;
+            };
+            let diagnostics: Vec<Value> = params
+                .get("diagnostics")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .map(|diagnostic| {
+                    let mut diagnostic = diagnostic.clone();
+                    diagnostic["uri"] = Value::String(uri.to_string());
+                    diagnostic
+                })
+                .collect();
+
+            if diagnostics.is_empty() {
+                documents.remove(uri);
+            } else {
+                documents.insert(uri.to_string(), diagnostics);
+            }
+
+            let elapsed = chrono::Duration::from_std(Duration::from_millis(message.elapsed_ms)).unwrap_or_default();
+            states.push(RawDiagnostics {
+                source: REPLAY_SOURCE.to_string(),
+                data: serde_json::json!({ "diagnostics": documents.values().flatten().collect::<Vec<_>>() }),
+                timestamp: self.recorded_at + elapsed,
+                workspace: None,
+            });
+        }
+
+        states
+    }
+
+    /// Feed every recorded diagnostics update through `capture`, in order
+    pub async fn replay<S>(&self, capture: &mut S) -> Result<ReplaySummary>
+    where
+        S: DiagnosticsCaptureService + ?Sized,
+    {
+        let states = self.raw_diagnostics();
+        let mut summary = ReplaySummary {
+            messages: self.messages.len(),
+            notifications: states.len(),
+            documents: 0,
+        };
+
+        for raw in states {
+            summary.documents = raw.data["diagnostics"]
+                .as_array()
+                .map(|diagnostics| {
+                    diagnostics
+                        .iter()
+                        .filter_map(|d| d["uri"].as_str())
+                        .collect::<std::collections::BTreeSet<_>>()
+                        .len()
+                })
+                .unwrap_or_default();
+            capture.process_diagnostics(raw).await?;
+        }
+
+        Ok(summary)
+    }
+}
+
+/// Read one `Content-Length` framed message; `None` at end of stream
+pub async fn read_message<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            if content_length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = Some(value.trim().parse::<usize>().context("Invalid Content-Length header")?);
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length.unwrap_or_default()];
+    reader.read_exact(&mut body).await?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+/// Write one message with a `Content-Length` header
+pub async fn write_message<W: AsyncWrite + Unpin>(writer: &mut W, message: &Value) -> Result<()> {
+    let body = serde_json::to_vec(message)?;
+    writer
+        .write_all(format!("Content-Length: {}\r\n\r\n", body.len()).as_bytes())
+        .await?;
+    writer.write_all(&body).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Forward messages from `from` to `to`, recording each one
+async fn pump<R, W>(from: R, mut to: W, direction: TraceDirection, recorder: Arc<TraceRecorder>) -> Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut from = BufReader::new(from);
+    while let Some(message) = read_message(&mut from).await? {
+        if let Err(e) = recorder.record(direction, &message) {
+            tracing::warn!("Failed to record LSP message: {}", e);
+        }
+        write_message(&mut to, &message).await?;
+    }
+    Ok(())
+}
+
+/// Run `launch` behind a recording proxy between `client_in` and `client_out`
+///
+/// Returns when either side closes its stream or the server exits.
+pub async fn record_session<R, W>(
+    launch: &LanguageServerLaunch,
+    recorder: Arc<TraceRecorder>,
+    client_in: R,
+    client_out: W,
+) -> Result<()>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    let mut child = launch
+        .command()
+        .spawn()
+        .with_context(|| format!("Failed to start {}", launch.program.display()))?;
+    let server_in = child.stdin.take().ok_or_else(|| anyhow!("Server stdin unavailable"))?;
+    let server_out = child.stdout.take().ok_or_else(|| anyhow!("Server stdout unavailable"))?;
+    if let Some(mut stderr) = child.stderr.take() {
+        tokio::spawn(async move {
+            let _ = tokio::io::copy(&mut stderr, &mut tokio::io::stderr()).await;
+        });
+    }
+
+    let to_server = tokio::spawn(pump(client_in, server_in, TraceDirection::ClientToServer, recorder.clone()));
+    let to_client = tokio::spawn(pump(server_out, client_out, TraceDirection::ServerToClient, recorder));
+
+    tokio::select! {
+        result = to_server => result??,
+        result = to_client => result??,
+        status = child.wait() => tracing::info!("Language server exited: {}", status?),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::{CaptureService, MemoryCache};
+    use crate::format::format_converter::FormatConverter;
+    use crate::privacy::privacy_filter::PrivacyFilter;
+    use serde_json::json;
+
+    fn publish(uri: &str, messages: &[&str]) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": {
+                "uri": uri,
+                "diagnostics": messages.iter().map(|message| json!({
+                    "range": { "start": { "line": 1, "character": 0 }, "end": { "line": 1, "character": 4 } },
+                    "severity": 1,
+                    "source": "rustc",
+                    "message": message,
+                })).collect::<Vec<_>>(),
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_framing_round_trip() -> Result<()> {
+        let message = json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": { "ünïcode": true } });
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, &message).await?;
+        write_message(&mut buffer, &message).await?;
+
+        let mut reader = BufReader::new(buffer.as_slice());
+        assert_eq!(read_message(&mut reader).await?, Some(message.clone()));
+        assert_eq!(read_message(&mut reader).await?, Some(message));
+        assert_eq!(read_message(&mut reader).await?, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_recorded_trace_replays_through_capture() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("trace.jsonl");
+
+        let recorder = TraceRecorder::create(&path, "rust-analyzer", Some(dir.path()))?;
+        recorder.record(TraceDirection::ClientToServer, &json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize" }))?;
+        recorder.record(TraceDirection::ServerToClient, &publish("file:///src/a.rs", &["first", "second"]))?;
+        recorder.record(TraceDirection::ServerToClient, &publish("file:///src/b.rs", &["third"]))?;
+        recorder.record(TraceDirection::ServerToClient, &publish("file:///src/a.rs", &[]))?;
+        drop(recorder);
+
+        let trace = LspTrace::load(&path)?;
+        assert_eq!(trace.server, "rust-analyzer");
+        assert_eq!(trace.messages.len(), 4);
+
+        let states = trace.raw_diagnostics();
+        let counts: Vec<usize> = states
+            .iter()
+            .map(|raw| raw.data["diagnostics"].as_array().unwrap().len())
+            .collect();
+        assert_eq!(counts, [2, 3, 1]);
+
+        let mut capture = CaptureService::new(
+            MemoryCache::new(10, 3600),
+            PrivacyFilter::new(crate::core::PrivacyPolicy::permissive()),
+            FormatConverter::new(),
+        );
+        capture.start_capture().await?;
+        let summary = trace.replay(&mut capture).await?;
+        assert_eq!((summary.notifications, summary.documents), (3, 1));
+
+        let snapshot = capture.get_current_snapshot().await?.unwrap();
+        assert_eq!(snapshot.diagnostics.len(), 1);
+        assert_eq!(snapshot.diagnostics[0].message, "third");
+        assert_eq!(snapshot.diagnostics[0].source, "rustc");
+        assert!(snapshot.diagnostics[0].file.ends_with("src/b.rs"));
+        Ok(())
+    }
+}
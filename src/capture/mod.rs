@@ -1,15 +1,26 @@
 pub mod capture_service;
+pub mod code_lens;
+pub mod importers;
+pub mod live;
+pub mod lsp_server;
+pub mod lsp_trace;
 pub mod memory_cache;
 
 pub use capture_service::CaptureService;
+pub use code_lens::collect_code_lenses;
+pub use importers::{ClippyImporter, ColumnMapping, DelimitedImporter, EslintImporter, ImportFormat, ImportReport};
+pub use live::{LiveCapture, LiveEvent};
+pub use lsp_server::{DiagnosticsServer, EnrichedDiagnostics};
+pub use lsp_trace::{LspTrace, LspTraceAction, ReplaySummary, TraceDirection, TraceRecorder};
 pub use memory_cache::MemoryCache;
 
 use crate::core::{
-    DiagnosticSnapshot, RawDiagnostics, PrivacyPolicy
+    DiagnosticSnapshot, RawDiagnostics, PrivacyPolicy, WorkspaceRoot
 };
 use crate::privacy::privacy_filter::PrivacyFilter;
 use crate::format::format_converter::FormatConverter;
 use anyhow::Result;
+use std::collections::HashMap;
 
 /// Simplified wrapper for diagnostic capture functionality
 /// 
@@ -67,6 +78,29 @@ impl DiagnosticsCapture {
         self.service = CaptureService::new(cache, privacy_filter, format_converter);
     }
     
+    /// Set privacy policy for a multi-root workspace
+    ///
+    /// Each root is filtered against its own ignore files, diagnostics are
+    /// tagged with their root, and `root_policies` (keyed by root name)
+    /// override `policy` for individual roots.
+    pub fn set_privacy_policy_with_roots(
+        &mut self,
+        policy: PrivacyPolicy,
+        roots: Vec<WorkspaceRoot>,
+        root_policies: HashMap<String, PrivacyPolicy>,
+    ) {
+        let mut privacy_filter = PrivacyFilter::new(policy).with_workspace_roots(&roots);
+        for (name, root_policy) in root_policies {
+            privacy_filter = privacy_filter.with_root_policy(&name, root_policy);
+        }
+
+        let cache = MemoryCache::new(100, 3600);
+        let format_converter = FormatConverter::new();
+
+        self.service = CaptureService::new(cache, privacy_filter, format_converter)
+            .with_workspace_roots(roots);
+    }
+
     /// Get the current privacy policy
     pub fn get_privacy_policy(&self) -> PrivacyPolicy {
         self.service.get_privacy_policy()
@@ -137,6 +171,7 @@ impl DiagnosticsCapture {
                 .to_string(),
             language: None,
             version: None,
+            roots: Vec::new(),
         };
 
         DiagnosticSnapshot {
@@ -145,6 +180,7 @@ impl DiagnosticsCapture {
             workspace,
             diagnostics,
             metadata,
+            code_lenses: HashMap::new(),
         }
     }
 }
@@ -1,11 +1,19 @@
-use clap::{Parser, Subcommand, ValueEnum};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
+use std::net::SocketAddr;
 use std::path::PathBuf;
 
 use crate::core::security_config::PrivacyLevel;
-use crate::history::HistoryAction;
+use crate::history::{AsOf, HistoryAction, ReportAction};
+use crate::capture::{ImportFormat, LspTraceAction};
 use crate::ai_training::AITrainingAction;
 use crate::quick_fix::QuickFixAction;
 use crate::config::ConfigAction;
+use crate::core::{ApiAction, BaselineAction, BreakerAction, DebtAction, GraphAction, ServersAction};
+use crate::export::Compression;
+use crate::format::ModelFamily;
+use crate::privacy::PreviewStyle;
 
 /// Main CLI structure for LSPbridge - a universal bridge for exporting IDE diagnostics.
 /// 
@@ -36,6 +44,11 @@ pub struct Cli {
     /// Enable verbose logging for debugging
     #[arg(short, long)]
     pub verbose: bool,
+
+    /// Make no network calls at all, whatever is configured, and append a
+    /// record of the run to network-audit.jsonl in the data directory
+    #[arg(long, global = true)]
+    pub offline: bool,
 }
 
 /// Available CLI commands for LSPbridge.
@@ -43,39 +56,43 @@ pub struct Cli {
 /// Each command provides specific functionality for working with diagnostic data:
 /// - `Export` - One-time export of current diagnostics
 /// - `Watch` - Continuous monitoring and export of diagnostics 
+/// - `Serve` - Live capture from the workspace's own language servers
 /// - `Query` - Interactive or scripted querying of diagnostic data
 /// - `History` - Analysis of historical diagnostic trends
+/// - `LspTrace` - Record and replay language server traffic
+/// - `LspServer` - Publish enriched diagnostics back to editors over LSP
+/// - `Report` - Weekly narrative reports for team channels
 /// - `AITraining` - AI/ML training data generation
 /// - `QuickFix` - Automated code fix generation and application
 /// - `Config` - Configuration management
+/// - `Breakers` - Per-subsystem circuit breaker status and reset
+/// - `Scan` - Static checks for projects without a language server
+/// - `Whatif` - Sandboxed estimate of autofix health gains
+/// - `Trust` - Allow a workspace to run project-defined commands
+/// - `Graph` - Relationship graphs for docs and dashboards
+/// - `Servers` - Managed language server installs for direct capture
+/// - `Debt` - Aging TODOs, FIXMEs and deprecations
+/// - `Baseline` - Accepted diagnostics left out of exports and queries
+/// - `Hook` - Git hooks that block commits introducing new diagnostics
 /// - `MultiRepo` - Cross-repository analysis
 #[derive(Subcommand)]
 pub enum Commands {
     /// Export current diagnostics
     Export {
-        /// Export format
-        #[arg(short, long, value_enum, default_value = "json")]
-        format: OutputFormat,
+        /// Export format(s), comma-separated (e.g. `sarif,html,claude`)
+        #[arg(short, long, value_enum, value_delimiter = ',', default_value = "json")]
+        format: Vec<OutputFormat>,
 
         /// Output file (default: stdout)
-        #[arg(short, long)]
+        #[arg(short, long, conflicts_with = "out_dir")]
         output: Option<PathBuf>,
 
-        /// Include only errors
-        #[arg(long)]
-        errors_only: bool,
-
-        /// Include only warnings and errors
+        /// Directory to write one `diagnostics.<ext>` file per format
         #[arg(long)]
-        warnings_and_errors: bool,
+        out_dir: Option<PathBuf>,
 
-        /// File patterns to include (comma-separated)
-        #[arg(long)]
-        files: Option<String>,
-
-        /// File patterns to exclude (comma-separated)
-        #[arg(long)]
-        exclude: Option<String>,
+        #[command(flatten)]
+        filter: Box<DiagnosticFilterArgs>,
 
         /// Maximum number of diagnostics
         #[arg(long)]
@@ -92,6 +109,125 @@ pub enum Commands {
         /// Privacy level for data sanitization
         #[arg(long, value_enum, default_value = "balanced")]
         privacy: PrivacyLevel,
+
+        /// Append every privacy exclusion and redaction (rule, hashes of what was
+        /// removed) to this JSON-lines log; overrides `privacy.audit_log`
+        #[arg(long, value_name = "FILE", conflicts_with = "preview_redaction")]
+        audit_log: Option<PathBuf>,
+
+        /// Include triage suggestions (priority, owners, fix time, related issues)
+        #[arg(long)]
+        triage: bool,
+
+        /// Learn chronically ignored diagnostics, downrank them and mute long-lived ones
+        #[arg(long)]
+        mute_noise: bool,
+
+        /// Days a noisy pattern must exist before it is muted
+        #[arg(long, default_value = "14", requires = "mute_noise")]
+        noise_after_days: u64,
+
+        /// Model family used for token and cost estimates in Claude exports
+        #[arg(long, value_enum, default_value = "claude")]
+        model: ModelFamily,
+
+        /// Trim Claude exports to this many tokens, keeping the most relevant diagnostics
+        #[arg(long)]
+        max_tokens: Option<usize>,
+
+        /// Regenerate the export from history as of a snapshot id or an
+        /// RFC 3339 timestamp / `YYYY-MM-DD` date instead of capturing live diagnostics
+        #[arg(long, value_name = "SNAPSHOT_ID|TIMESTAMP", conflicts_with_all = ["triage", "mute_noise"])]
+        as_of: Option<String>,
+
+        /// Tag diagnostics on exported Rust/TypeScript items with semver impact hints
+        #[arg(long, conflicts_with = "as_of")]
+        api_surface: bool,
+
+        /// Log file with runtime crash reports (Rust panics, Node.js stack traces, Python
+        /// tracebacks); diagnostics at crashing lines are flagged. Can be repeated.
+        #[arg(long, value_name = "FILE", conflicts_with = "as_of")]
+        crash_log: Vec<PathBuf>,
+
+        /// Show original vs privacy-filtered diagnostics for a sample instead of exporting
+        #[arg(long, conflicts_with_all = ["as_of", "out_dir"])]
+        preview_redaction: bool,
+
+        /// Number of diagnostics in the redaction preview; changed ones are shown first
+        #[arg(long, default_value = "10", requires = "preview_redaction")]
+        preview_sample: usize,
+
+        /// Layout of the redaction preview
+        #[arg(long, value_enum, default_value = "side-by-side", requires = "preview_redaction")]
+        preview_style: PreviewStyle,
+
+        /// Only export diagnostics from repositories in a saved multi-repo fleet
+        #[arg(long)]
+        fleet: Option<String>,
+
+        /// Diff-friendly output: canonical ordering (path, range, code), content-derived
+        /// ids and no timestamps, so identical diagnostics export identically
+        #[arg(long)]
+        stable: bool,
+
+        /// Split the export between the routes in `[export_routing]` of lspbridge.toml;
+        /// each route is written to its own directory or to --out-dir
+        #[arg(long, conflicts_with_all = ["output", "preview_redaction"])]
+        route: bool,
+
+        /// Export only diagnostics in this file, with their code context
+        #[arg(long, value_name = "PATH")]
+        file: Option<PathBuf>,
+
+        /// Limit --file to a line range such as `100-250` (one-based, inclusive)
+        #[arg(long, value_name = "START-END", requires = "file")]
+        lines: Option<String>,
+
+        /// Compress written files, adding `.gz` or `.zst` to their names
+        #[arg(long, value_enum)]
+        compress: Option<Compression>,
+
+        /// Write every output into one tar archive with a `manifest.json`
+        /// listing each file's format, size and SHA-256
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["output", "out_dir", "preview_redaction"])]
+        bundle: Option<PathBuf>,
+
+        /// Stream diagnostics into a Parquet file instead of rendering formats;
+        /// --compress applies to its pages
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["output", "out_dir", "bundle", "route", "preview_redaction"])]
+        parquet: Option<PathBuf>,
+
+        /// Rows per Parquet row group; bounds memory use while exporting
+        #[arg(long, value_name = "ROWS", default_value = "65536", requires = "parquet")]
+        row_group_size: usize,
+
+        /// Export every diagnostic recorded in history, one row per capture,
+        /// instead of the current diagnostics
+        #[arg(long, requires = "parquet", conflicts_with_all = ["as_of", "fleet", "mute_noise", "triage", "dedupe"])]
+        all_history: bool,
+
+        /// Collapse diagnostics with the same code, normalized message and symbol
+        /// into one, reporting how many times and where they occurred
+        #[arg(long)]
+        dedupe: bool,
+
+        /// Include diagnostics accepted in the project's .lspbridge-baseline.json
+        #[arg(long)]
+        no_baseline: bool,
+
+        /// Read diagnostics from a JSON file instead of stdin or the IDE
+        #[arg(long, value_name = "FILE", conflicts_with = "as_of")]
+        input: Option<PathBuf>,
+
+        /// Re-export whenever source files or the --input file change; with
+        /// --out-dir, each source file gets its own document and only files
+        /// whose diagnostics changed are re-rendered
+        #[arg(long, conflicts_with_all = ["as_of", "preview_redaction", "bundle", "parquet", "route", "fleet", "mute_noise"])]
+        watch: bool,
+
+        /// Quiet period in milliseconds before a batch of changes triggers an export
+        #[arg(long, value_name = "MS", default_value = "300", requires = "watch")]
+        debounce_ms: u64,
     },
 
     /// Watch for diagnostic changes
@@ -111,6 +247,69 @@ pub enum Commands {
         /// Privacy level for data sanitization
         #[arg(long, value_enum, default_value = "balanced")]
         privacy: PrivacyLevel,
+
+        /// Dynamic config file whose `[privacy]` and `[severity_rules]` sections override
+        /// `--privacy` and `lspbridge.toml` and are reloaded on change
+        #[arg(long, value_name = "FILE")]
+        privacy_config: Option<PathBuf>,
+
+        /// Re-scan files in history that changed since their last capture or are older than this many days
+        ///
+        /// Only diagnostics from the built-in static scanner (and its security
+        /// findings) are refreshed. Language-server diagnostics in history are
+        /// kept for files older than the limit and dropped for changed files
+        /// until the next full capture.
+        #[arg(long, value_name = "DAYS")]
+        refresh_stale_days: Option<u64>,
+    },
+
+    /// Capture diagnostics live from the workspace's language servers
+    ///
+    /// Starts rust-analyzer, typescript-language-server and pylsp when the
+    /// workspace has their project files, plus any server with a profile in
+    /// `.lspbridge.toml`, and records every `textDocument/publishDiagnostics`
+    /// in capture and history. No editor extension is needed.
+    #[command(group(ArgGroup::new("api_server").args(["grpc", "http"]).multiple(true)))]
+    Serve {
+        /// Workspace root
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Language server to run instead of the detected ones (repeatable)
+        #[arg(long = "server", value_name = "NAME")]
+        servers: Vec<String>,
+
+        /// Most files to open in servers that only report on open documents
+        #[arg(long, default_value = "500")]
+        open_limit: usize,
+
+        /// Seconds to collect server updates before recording them
+        #[arg(short, long, default_value = "2")]
+        interval: u64,
+
+        /// Privacy level for data sanitization
+        #[arg(long, value_enum, default_value = "balanced")]
+        privacy: PrivacyLevel,
+
+        /// Also serve the query API over gRPC on this address (e.g. `127.0.0.1:50051`),
+        /// answering from the live capture
+        #[arg(long, value_name = "ADDR")]
+        grpc: Option<SocketAddr>,
+
+        /// Also serve diagnostics, queries, history trends, fix suggestions and
+        /// health over HTTP on this address (e.g. `127.0.0.1:8080`)
+        #[arg(long, value_name = "ADDR")]
+        http: Option<SocketAddr>,
+
+        /// Access file mapping API keys to CODEOWNERS principals; gRPC and HTTP
+        /// callers then need an `x-api-key` header and only see files they own
+        #[arg(long, requires = "api_server")]
+        access_file: Option<PathBuf>,
+
+        /// Print the OpenAPI 3.1 document for the HTTP API and exit, for
+        /// generating typed clients
+        #[arg(long)]
+        openapi: bool,
     },
 
     /// Query diagnostic history
@@ -130,6 +329,27 @@ pub enum Commands {
         /// Interactive mode
         #[arg(short, long)]
         interactive: bool,
+
+        /// Browse the result in a sortable, filterable table; Enter shows a
+        /// row's diagnostic with its code frame and fixes, `e` opens $EDITOR
+        #[arg(long, conflicts_with_all = ["interactive", "output"])]
+        tui: bool,
+
+        /// LSP trace to collect code lenses and inlay hints from (`FROM lenses`)
+        #[arg(long)]
+        lenses: Option<PathBuf>,
+
+        /// Narrow the live diagnostics before the query runs
+        #[command(flatten)]
+        filter: DiagnosticFilterArgs,
+
+        /// Collapse duplicate diagnostics into one row with an `occurrences` count
+        #[arg(long)]
+        dedupe: bool,
+
+        /// Include diagnostics accepted in the project's .lspbridge-baseline.json
+        #[arg(long)]
+        no_baseline: bool,
     },
 
     /// Manage diagnostic history
@@ -139,6 +359,51 @@ pub enum Commands {
         action: HistoryAction,
     },
 
+    /// Record language server traffic or replay a recorded trace
+    #[command(name = "lsp-trace")]
+    LspTrace {
+        /// Trace action to perform
+        #[command(subcommand)]
+        action: LspTraceAction,
+    },
+
+    /// Act as a language server publishing LSPbridge's enriched diagnostics
+    ///
+    /// Configure this command as an extra language server in the editor.
+    /// Diagnostics are deduplicated, privacy-filtered, augmented with the
+    /// project's scan rules and annotated with taxonomy, priority and root
+    /// cause. Stdout carries LSP traffic.
+    #[command(name = "lsp-server")]
+    LspServer {
+        /// Diagnostics to publish, in the format `export` reads from stdin;
+        /// re-read whenever it changes
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+
+        /// Project root (defaults to the current directory)
+        #[arg(long)]
+        root: Option<PathBuf>,
+
+        /// Privacy level for data sanitization
+        #[arg(long, value_enum, default_value = "balanced")]
+        privacy: PrivacyLevel,
+
+        /// Seconds between checks of the input file for changes
+        #[arg(long, default_value = "2")]
+        interval: u64,
+
+        /// Don't run the project's scan rules on opened and saved documents
+        #[arg(long)]
+        no_scan: bool,
+    },
+
+    /// Generate narrative reports from diagnostic history
+    Report {
+        /// Report to generate
+        #[command(subcommand)]
+        action: ReportAction,
+    },
+
     /// Generate AI training data
     #[command(name = "ai-training")]
     AITraining {
@@ -162,6 +427,171 @@ pub enum Commands {
         action: ConfigAction,
     },
 
+    /// Inspect or reset per-subsystem circuit breakers
+    Breakers {
+        /// Circuit breaker action to perform
+        #[command(subcommand)]
+        action: BreakerAction,
+    },
+
+    /// Inspect query API usage and quotas
+    Api {
+        /// API action to perform
+        #[command(subcommand)]
+        action: ApiAction,
+    },
+
+    /// Produce initial diagnostics with static checks when no language server is available
+    ///
+    /// Prints LSP-style JSON that `lspbridge export` reads from stdin.
+    Scan {
+        /// Project root to scan
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Write the diagnostics to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Skip the unresolved local import check
+        #[arg(long)]
+        no_imports: bool,
+
+        /// Skip TODO/FIXME reporting
+        #[arg(long)]
+        no_todos: bool,
+
+        /// Skip the insecure code pattern checks
+        #[arg(long)]
+        no_security: bool,
+
+        /// Report TODO/FIXME comments older than this many days as warnings
+        #[arg(long)]
+        todo_max_age_days: Option<u64>,
+    },
+
+    /// Import diagnostics reported by linters, CI jobs and scripts without a language server
+    ///
+    /// Prints LSP-style JSON that `lspbridge export` reads from stdin, and
+    /// with `--record` adds the diagnostics to history.
+    Import {
+        /// File to import (default: stdin)
+        input: Option<PathBuf>,
+
+        /// Input format
+        #[arg(short, long, value_enum, default_value = "csv")]
+        format: ImportFormat,
+
+        /// TOML file mapping CSV/TSV columns to diagnostic fields; without one,
+        /// columns are recognized by common header names (file, line, severity, message, ...)
+        #[arg(short, long)]
+        mapping: Option<PathBuf>,
+
+        /// Source of CSV/TSV diagnostics that name none, overriding the mapping's
+        #[arg(long)]
+        source: Option<String>,
+
+        /// Write the diagnostics to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Also record the diagnostics in history, merged with each file's
+        /// diagnostics from other sources
+        #[arg(long)]
+        record: bool,
+    },
+
+    /// Summarize a workspace's diagnostics in one screen, with no prior capture or config
+    ///
+    /// Combines recorded history, a static scan and the compilers and linters
+    /// the workspace uses (go vet, ESLint), whichever are available.
+    Stats {
+        /// Workspace root
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Don't run compilers or linters, only read history and scan
+        ///
+        /// Tools never run in workspaces not marked with `lspbridge trust`.
+        #[arg(long)]
+        no_tools: bool,
+
+        /// Seconds after which a compiler or linter is skipped
+        #[arg(long, default_value = "20")]
+        tool_timeout: u64,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "markdown")]
+        format: OutputFormat,
+    },
+
+    /// Estimate how much automatic fixes would improve health without touching the working tree
+    ///
+    /// Forks the affected files into a temporary sandbox, applies confident fixes there and
+    /// compares health metrics against the current diagnostics.
+    Whatif {
+        /// Minimum fix confidence to apply in the sandbox (0.0-1.0)
+        #[arg(short = 't', long, default_value = "0.9")]
+        apply_threshold: f32,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "markdown")]
+        format: OutputFormat,
+    },
+
+    /// Trust a workspace to run project-defined commands
+    ///
+    /// Fix verification builds and tests and language server profiles only
+    /// run in trusted workspaces; untrusted ones get read-only analysis.
+    Trust {
+        /// Workspace root
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Revoke trust instead, even if a parent directory is trusted
+        #[arg(long)]
+        revoke: bool,
+
+        /// List trusted and untrusted workspaces
+        #[arg(long, conflicts_with = "revoke")]
+        list: bool,
+    },
+
+    /// Export repository, call and diagnostic cause graphs as DOT, GraphML or Mermaid
+    Graph {
+        /// Graph action to perform
+        #[command(subcommand)]
+        action: GraphAction,
+    },
+
+    /// Install the language servers direct capture launches
+    Servers {
+        /// Server action to perform
+        #[command(subcommand)]
+        action: ServersAction,
+    },
+
+    /// Track TODO/FIXME comments and deprecation warnings as aging debt
+    Debt {
+        /// Debt action to perform
+        #[command(subcommand)]
+        action: DebtAction,
+    },
+
+    /// Accept existing diagnostics so only new ones are exported, queried and checked
+    Baseline {
+        /// Baseline action to perform
+        #[command(subcommand)]
+        action: BaselineAction,
+    },
+
+    /// Install git hooks that check staged or pushed files for new diagnostics
+    Hook {
+        /// Hook action to perform
+        #[command(subcommand)]
+        action: crate::cli::hooks::HookAction,
+    },
+
     /// Multi-repository operations
     #[command(name = "multi-repo")]
     MultiRepo {
@@ -180,6 +610,36 @@ pub enum OutputFormat {
     Markdown,
     /// Claude-optimized XML format
     Claude,
+    /// SARIF 2.1.0 for code scanning (export and watch only)
+    Sarif,
+    /// Standalone HTML report (export and watch only)
+    Html,
+    /// GitHub Actions workflow commands that annotate PRs (export and watch only)
+    GithubActions,
+}
+
+impl OutputFormat {
+    /// Error for commands that only render JSON, Markdown or Claude output
+    pub fn unsupported_by(self, command: &str) -> anyhow::Error {
+        let name = self
+            .to_possible_value()
+            .map(|v| v.get_name().to_string())
+            .unwrap_or_default();
+        anyhow::anyhow!("--format {name} is only supported by export and watch, not {command}")
+    }
+}
+
+impl From<OutputFormat> for crate::core::ExportFormat {
+    fn from(format: OutputFormat) -> Self {
+        match format {
+            OutputFormat::Json => crate::core::ExportFormat::Json,
+            OutputFormat::Markdown => crate::core::ExportFormat::Markdown,
+            OutputFormat::Claude => crate::core::ExportFormat::ClaudeOptimized,
+            OutputFormat::Sarif => crate::core::ExportFormat::Sarif,
+            OutputFormat::Html => crate::core::ExportFormat::Html,
+            OutputFormat::GithubActions => crate::core::ExportFormat::GithubActions,
+        }
+    }
 }
 
 /// Output formats for query commands
@@ -191,20 +651,231 @@ pub enum QueryOutputFormat {
     Json,
     /// CSV format
     Csv,
+    /// Arrow IPC (Feather) format for Polars/pandas
+    Arrow,
+}
+
+/// Diagnostic filter flags shared by export, query, quick-fix and history
+///
+/// Every command turns these into a [`DiagnosticFilter`] with
+/// [`Self::to_filter`], so a flag means the same thing everywhere.
+///
+/// [`DiagnosticFilter`]: crate::core::DiagnosticFilter
+#[derive(Debug, Clone, Default, Args)]
+pub struct DiagnosticFilterArgs {
+    /// Include only errors (same as `--min-severity error`)
+    #[arg(long, conflicts_with_all = ["warnings_and_errors", "min_severity"])]
+    pub errors_only: bool,
+
+    /// Include only warnings and errors (same as `--min-severity warning`)
+    #[arg(long, conflicts_with = "min_severity")]
+    pub warnings_and_errors: bool,
+
+    /// Least severe level to include
+    #[arg(long, value_enum)]
+    pub min_severity: Option<crate::core::DiagnosticSeverity>,
+
+    /// Diagnostic sources to include (comma-separated, e.g. `rustc,eslint`)
+    #[arg(long, value_delimiter = ',')]
+    pub source: Vec<String>,
+
+    /// Diagnostic codes to include (comma-separated, e.g. `E0308,TS2322`)
+    #[arg(long, value_delimiter = ',')]
+    pub code: Vec<String>,
+
+    /// File patterns to include (comma-separated globs)
+    #[arg(long, value_delimiter = ',')]
+    pub files: Vec<String>,
+
+    /// File patterns to exclude (comma-separated globs)
+    #[arg(long, value_delimiter = ',')]
+    pub exclude: Vec<String>,
+
+    /// Only diagnostics captured at or after this RFC 3339 timestamp or `YYYY-MM-DD` date
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// Only diagnostics captured at or before this RFC 3339 timestamp or `YYYY-MM-DD` date
+    #[arg(long)]
+    pub until: Option<String>,
+
+    /// Include only diagnostics carrying one of these tags (comma-separated)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub tag: Vec<crate::core::DiagnosticTag>,
+}
+
+impl DiagnosticFilterArgs {
+    /// Build the filter, failing on unparseable times
+    pub fn to_filter(&self, max_results: Option<usize>) -> Result<crate::core::DiagnosticFilter> {
+        use crate::core::DiagnosticSeverity;
+
+        let min_severity = if self.errors_only {
+            Some(DiagnosticSeverity::Error)
+        } else if self.warnings_and_errors {
+            Some(DiagnosticSeverity::Warning)
+        } else {
+            self.min_severity
+        };
+        // Lower values are more severe
+        let severities = min_severity.map(|min| {
+            [
+                DiagnosticSeverity::Error,
+                DiagnosticSeverity::Warning,
+                DiagnosticSeverity::Information,
+                DiagnosticSeverity::Hint,
+            ]
+            .into_iter()
+            .filter(|severity| *severity <= min)
+            .collect()
+        });
+
+        let since = self.since.as_deref().map(|value| parse_time(value, false)).transpose()?;
+        let until = self.until.as_deref().map(|value| parse_time(value, true)).transpose()?;
+        if let (Some(since), Some(until)) = (since, until) {
+            if until < since {
+                return Err(anyhow!("--until is before --since"));
+            }
+        }
+
+        let non_empty = |values: &[String]| -> Option<Vec<String>> {
+            let values: Vec<String> = values
+                .iter()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+                .collect();
+            (!values.is_empty()).then_some(values)
+        };
+
+        Ok(crate::core::DiagnosticFilter {
+            severities,
+            sources: non_empty(&self.source),
+            file_patterns: non_empty(&self.files),
+            exclude_patterns: non_empty(&self.exclude),
+            max_results,
+            since,
+            until,
+            codes: non_empty(&self.code),
+            tags: (!self.tag.is_empty()).then(|| self.tag.clone()),
+        })
+    }
+}
+
+/// Parse an RFC 3339 timestamp or a UTC `YYYY-MM-DD` date; a bare date is the
+/// start of the day, or its last second when `end_of_day` is set
+pub fn parse_time(value: &str, end_of_day: bool) -> Result<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let time = if end_of_day {
+            date.and_hms_opt(23, 59, 59)
+        } else {
+            date.and_hms_opt(0, 0, 0)
+        };
+        let time = time.ok_or_else(|| anyhow!("Invalid date: {value}"))?;
+        return Ok(time.and_utc());
+    }
+    Err(anyhow!(
+        "Invalid time '{value}': expected an RFC 3339 timestamp or YYYY-MM-DD"
+    ))
+}
+
+/// Parse a point in history: a snapshot id, an RFC 3339 timestamp or a UTC
+/// `YYYY-MM-DD` date, which means the end of that day
+pub fn parse_as_of(value: &str) -> Result<AsOf> {
+    if let Ok(id) = value.trim().parse::<i64>() {
+        return Ok(AsOf::Snapshot(id));
+    }
+    parse_time(value, true)
+        .map(|time| AsOf::Time(time.into()))
+        .map_err(|_| {
+            anyhow!("Invalid point in history '{value}': expected a snapshot id, an RFC 3339 timestamp or YYYY-MM-DD")
+        })
 }
 
 // Argument structures for command handlers
 pub struct ExportArgs {
-    pub format: OutputFormat,
+    pub formats: Vec<OutputFormat>,
     pub output: Option<PathBuf>,
-    pub errors_only: bool,
-    pub warnings_and_errors: bool,
-    pub files: Option<String>,
-    pub exclude: Option<String>,
+    pub out_dir: Option<PathBuf>,
+    pub filter: DiagnosticFilterArgs,
     pub max_results: Option<usize>,
     pub include_context: bool,
     pub context_lines: usize,
     pub privacy: PrivacyLevel,
+    pub audit_log: Option<PathBuf>,
+    pub triage: bool,
+    pub mute_noise: bool,
+    pub noise_after_days: u64,
+    pub model: ModelFamily,
+    pub max_tokens: Option<usize>,
+    pub as_of: Option<String>,
+    pub api_surface: bool,
+    pub crash_log: Vec<PathBuf>,
+    pub preview_redaction: bool,
+    pub preview_sample: usize,
+    pub preview_style: PreviewStyle,
+    pub fleet: Option<String>,
+    pub stable: bool,
+    pub route: bool,
+    pub file: Option<PathBuf>,
+    pub lines: Option<String>,
+    pub compress: Option<Compression>,
+    pub bundle: Option<PathBuf>,
+    pub parquet: Option<PathBuf>,
+    pub row_group_size: usize,
+    pub all_history: bool,
+    pub dedupe: bool,
+    pub no_baseline: bool,
+    pub input: Option<PathBuf>,
+    pub watch: bool,
+    pub debounce_ms: u64,
+}
+
+pub struct LspServerArgs {
+    pub input: Option<PathBuf>,
+    pub root: Option<PathBuf>,
+    pub privacy: PrivacyLevel,
+    pub interval: u64,
+    pub no_scan: bool,
+}
+
+pub struct ScanArgs {
+    pub path: PathBuf,
+    pub output: Option<PathBuf>,
+    pub no_imports: bool,
+    pub no_todos: bool,
+    pub no_security: bool,
+    pub todo_max_age_days: Option<u64>,
+}
+
+pub struct ImportArgs {
+    pub input: Option<PathBuf>,
+    pub format: ImportFormat,
+    pub mapping: Option<PathBuf>,
+    pub source: Option<String>,
+    pub output: Option<PathBuf>,
+    pub record: bool,
+}
+
+pub struct StatsArgs {
+    pub path: PathBuf,
+    pub no_tools: bool,
+    pub tool_timeout: u64,
+    pub format: OutputFormat,
+}
+
+pub struct ServeArgs {
+    pub path: PathBuf,
+    pub servers: Vec<String>,
+    pub open_limit: usize,
+    pub interval: u64,
+    pub privacy: PrivacyLevel,
+    pub grpc: Option<SocketAddr>,
+    pub http: Option<SocketAddr>,
+    pub access_file: Option<PathBuf>,
+    pub openapi: bool,
 }
 
 pub struct WatchArgs {
@@ -212,6 +883,8 @@ pub struct WatchArgs {
     pub interval: u64,
     pub errors_only: bool,
     pub privacy: PrivacyLevel,
+    pub privacy_config: Option<PathBuf>,
+    pub refresh_stale_days: Option<u64>,
 }
 
 pub struct QueryArgs {
@@ -219,4 +892,79 @@ pub struct QueryArgs {
     pub format: QueryOutputFormat,
     pub output: Option<PathBuf>,
     pub interactive: bool,
-}
\ No newline at end of file
+    pub tui: bool,
+    pub lenses: Option<PathBuf>,
+    pub filter: DiagnosticFilterArgs,
+    pub dedupe: bool,
+    pub no_baseline: bool,
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Diagnostic, DiagnosticSeverity, DiagnosticTag, Position, Range};
+
+    fn diagnostic(file: &str, severity: DiagnosticSeverity, source: &str, code: &str) -> Diagnostic {
+        let mut diagnostic = Diagnostic::new(
+            file.to_string(),
+            Range {
+                start: Position { line: 0, character: 0 },
+                end: Position { line: 0, character: 1 },
+            },
+            severity,
+            "message".to_string(),
+            source.to_string(),
+        );
+        diagnostic.code = Some(code.to_string());
+        diagnostic
+    }
+
+    fn filter_args(cli: Cli) -> DiagnosticFilterArgs {
+        match cli.command {
+            Commands::Export { filter, .. } => *filter,
+            Commands::Query { filter, .. } => filter,
+            _ => panic!("expected a command with filter flags"),
+        }
+    }
+
+    #[test]
+    fn test_filter_flags_mean_the_same_everywhere() {
+        let flags = [
+            "--min-severity", "warning", "--source", "rustc,Clippy", "--code", "E0308",
+            "--files", "src/**/*.rs", "--exclude", "generated", "--since", "2024-01-01",
+        ];
+        let export = Cli::try_parse_from(["lsp-bridge", "export"].iter().chain(&flags)).unwrap();
+        let query = Cli::try_parse_from(["lsp-bridge", "query"].iter().chain(&flags)).unwrap();
+        let export = filter_args(export).to_filter(Some(10)).unwrap();
+        let query = filter_args(query).to_filter(Some(10)).unwrap();
+        assert_eq!(export, query);
+
+        let now = Utc::now();
+        let kept = diagnostic("src/core/types.rs", DiagnosticSeverity::Warning, "clippy", "e0308");
+        assert!(export.matches(&kept, now));
+        assert!(!export.matches(&diagnostic("src/a.rs", DiagnosticSeverity::Hint, "rustc", "E0308"), now));
+        assert!(!export.matches(&diagnostic("src/a.rs", DiagnosticSeverity::Error, "eslint", "E0308"), now));
+        assert!(!export.matches(&diagnostic("src/a.rs", DiagnosticSeverity::Error, "rustc", "E0382"), now));
+        assert!(!export.matches(&diagnostic("src/generated/a.rs", DiagnosticSeverity::Error, "rustc", "E0308"), now));
+        assert!(!export.matches(&diagnostic("tests/a.rs", DiagnosticSeverity::Error, "rustc", "E0308"), now));
+        assert!(!export.matches(&kept, parse_time("2023-12-31", true).unwrap()));
+    }
+
+    #[test]
+    fn test_severity_shorthands_and_tags() {
+        let cli = Cli::try_parse_from(["lsp-bridge", "export", "--errors-only", "--tag", "deprecated"]).unwrap();
+        let filter = filter_args(cli).to_filter(None).unwrap();
+        assert_eq!(filter.severities, Some(vec![DiagnosticSeverity::Error]));
+
+        let mut deprecated = diagnostic("a.rs", DiagnosticSeverity::Error, "rustc", "E1");
+        assert!(!filter.matches(&deprecated, Utc::now()));
+        deprecated.tags = Some(vec![DiagnosticTag::Deprecated]);
+        assert!(filter.matches(&deprecated, Utc::now()));
+
+        assert!(Cli::try_parse_from(["lsp-bridge", "export", "--errors-only", "--min-severity", "hint"]).is_err());
+        let inverted = Cli::try_parse_from(["lsp-bridge", "query", "--since", "2024-02-01", "--until", "2024-01-01"]);
+        assert!(filter_args(inverted.unwrap()).to_filter(None).is_err());
+
+        let unfiltered = Cli::try_parse_from(["lsp-bridge", "query"]).unwrap();
+        assert!(filter_args(unfiltered).to_filter(None).unwrap().is_empty());
+    }
+}
@@ -3,9 +3,11 @@ use async_trait::async_trait;
 use std::path::PathBuf;
 use tokio::fs;
 
+use crate::ai_training::review::{diff_lines, review_log_path, DiffLine};
 use crate::ai_training::{
-    AIExportFormat, AITrainingAction, AnnotationTool, DifficultyLevel, ErrorInjector,
-    ExportFormat as AIFormat, FixQuality, TrainingDataset, TrainingExporter, TrainingPair,
+    AIExportFormat, AITrainingAction, AnnotationTool, CompileVerifier, DifficultyLevel, ErrorInjector,
+    ExportFormat as AIFormat, FixQuality, ReviewSession, TrainingDataset, TrainingExporter,
+    TrainingPair,
 };
 use crate::cli::args::OutputFormat;
 use crate::cli::commands::Command;
@@ -48,16 +50,23 @@ impl Command for AITrainingCommand {
                 difficulty,
                 count,
                 gradient,
+                verify,
             } => {
-                self.generate_synthetic_data(
-                    input,
-                    output,
-                    language,
-                    difficulty.as_ref(),
-                    *count,
-                    *gradient,
-                )
-                .await
+                let injector = synthetic_injector(language, *verify)?;
+                if *gradient {
+                    self.generate_gradient_data(&injector, input, output, language, *count)
+                        .await
+                } else {
+                    self.generate_synthetic_data(
+                        &injector,
+                        input,
+                        output,
+                        language,
+                        difficulty.as_ref(),
+                        *count,
+                    )
+                    .await
+                }
             }
             AITrainingAction::Annotate {
                 dataset,
@@ -65,7 +74,7 @@ impl Command for AITrainingCommand {
                 auto_quality,
                 output,
             } => {
-                self.annotate_dataset(dataset, annotator, auto_quality.as_ref(), output)
+                self.annotate_dataset(dataset, annotator, auto_quality.as_ref(), output.as_ref())
                     .await
             }
             AITrainingAction::Report { dataset, format } => {
@@ -153,67 +162,74 @@ impl AITrainingCommand {
         Ok(())
     }
 
-    async fn generate_synthetic_data(
+    async fn generate_gradient_data(
         &self,
+        injector: &ErrorInjector,
         input: &PathBuf,
         output: &PathBuf,
         language: &str,
-        difficulty: Option<&DifficultyLevel>,
         count: usize,
-        gradient: bool,
     ) -> Result<()> {
-        let injector = ErrorInjector::new();
+        // Read base code
+        let base_code = fs::read_to_string(input).await?;
 
-        if gradient {
-            // Read base code
-            let base_code = fs::read_to_string(input).await?;
+        // Generate gradient dataset
+        let dataset = injector.generate_gradient_dataset(&base_code, language, count / 4)?;
 
-            // Generate gradient dataset
-            let dataset = injector.generate_gradient_dataset(&base_code, language, count / 4)?;
+        // Save dataset
+        let json = serde_json::to_string_pretty(&dataset)?;
+        fs::write(output, json).await?;
 
-            // Save dataset
-            let json = serde_json::to_string_pretty(&dataset)?;
-            fs::write(output, json).await?;
+        println!(
+            "✅ Generated gradient dataset with {} examples",
+            dataset.pairs.len()
+        );
 
-            println!(
-                "✅ Generated gradient dataset with {} examples",
-                dataset.pairs.len()
-            );
-        } else {
-            // Read base code
-            let base_code = fs::read_to_string(input).await?;
-
-            // Convert difficulty level
-            let diff = difficulty.map(|d| match d {
-                DifficultyLevel::Beginner => crate::ai_training::DifficultyLevel::Beginner,
-                DifficultyLevel::Intermediate => crate::ai_training::DifficultyLevel::Intermediate,
-                DifficultyLevel::Advanced => crate::ai_training::DifficultyLevel::Advanced,
-                DifficultyLevel::Expert => crate::ai_training::DifficultyLevel::Expert,
-            });
-
-            // Generate synthetic errors
-            let pairs = injector.inject_errors(&base_code, language, diff, count)?;
-
-            // Create dataset
-            let mut dataset = TrainingDataset::new(
-                format!("{language} Synthetic Dataset"),
-                "Synthetic training data with injected errors".to_string(),
-            );
+        Ok(())
+    }
 
-            for pair in pairs {
-                dataset.add_pair(pair);
-            }
+    async fn generate_synthetic_data(
+        &self,
+        injector: &ErrorInjector,
+        input: &PathBuf,
+        output: &PathBuf,
+        language: &str,
+        difficulty: Option<&DifficultyLevel>,
+        count: usize,
+    ) -> Result<()> {
+        // Read base code
+        let base_code = fs::read_to_string(input).await?;
 
-            // Save dataset
-            let json = serde_json::to_string_pretty(&dataset)?;
-            fs::write(output, json).await?;
+        // Convert difficulty level
+        let diff = difficulty.map(|d| match d {
+            DifficultyLevel::Beginner => crate::ai_training::DifficultyLevel::Beginner,
+            DifficultyLevel::Intermediate => crate::ai_training::DifficultyLevel::Intermediate,
+            DifficultyLevel::Advanced => crate::ai_training::DifficultyLevel::Advanced,
+            DifficultyLevel::Expert => crate::ai_training::DifficultyLevel::Expert,
+        });
 
-            println!(
-                "✅ Generated {} synthetic training examples",
-                dataset.pairs.len()
-            );
+        // Generate synthetic errors
+        let pairs = injector.inject_errors(&base_code, language, diff, count)?;
+
+        // Create dataset
+        let mut dataset = TrainingDataset::new(
+            format!("{language} Synthetic Dataset"),
+            "Synthetic training data with injected errors".to_string(),
+        );
+
+        for pair in pairs {
+            dataset.add_pair(pair);
         }
 
+        // Save dataset
+        let json = serde_json::to_string_pretty(&dataset)?;
+        fs::write(output, json).await?;
+
+        println!(
+            "✅ Generated {} synthetic training examples",
+            dataset.pairs.len()
+        );
+
         Ok(())
     }
 
@@ -222,215 +238,43 @@ impl AITrainingCommand {
         dataset: &PathBuf,
         annotator: &str,
         auto_quality: Option<&FixQuality>,
-        output: &PathBuf,
+        output: Option<&PathBuf>,
     ) -> Result<()> {
         // Load dataset
         let json = fs::read_to_string(dataset).await?;
         let mut training_dataset: TrainingDataset = serde_json::from_str(&json)?;
-
-        let mut tool = AnnotationTool::new();
-        tool.start_session(annotator.to_string(), training_dataset.id.clone());
+        let output = output.unwrap_or(dataset);
 
         if let Some(quality_threshold) = auto_quality {
             // Auto-annotate
+            let mut tool = AnnotationTool::new();
+            tool.start_session(annotator.to_string(), training_dataset.id.clone());
             let annotations = tool.batch_annotate(&mut training_dataset, *quality_threshold)?;
             println!("✅ Auto-annotated {} training pairs", annotations.len());
-        } else {
-            // Interactive manual annotation
-            self.interactive_annotation(&mut tool, &mut training_dataset)?;
-        }
-
-        // Save annotated dataset
-        let json = serde_json::to_string_pretty(&training_dataset)?;
-        fs::write(output, json).await?;
-
-        println!("✅ Saved annotated dataset to {}", output.display());
 
-        Ok(())
-    }
-
-    fn interactive_annotation(
-        &self,
-        tool: &mut AnnotationTool,
-        training_dataset: &mut TrainingDataset,
-    ) -> Result<()> {
-        use std::io::{self, Write};
-
-        println!("🔍 Starting manual annotation session...");
-        println!(
-            "Dataset: {} ({} pairs)",
-            training_dataset.name,
-            training_dataset.pairs.len()
-        );
-        println!();
-
-        let stdin = io::stdin();
-        let mut stdout = io::stdout();
-
-        // Iterate through unannotated pairs
-        let mut annotated_count = 0;
-        let total_pairs = training_dataset.pairs.len();
-
-        for (idx, pair) in training_dataset.pairs.iter_mut().enumerate() {
-            // Check if already annotated
-            if pair.metadata.contains_key("annotation") {
-                continue;
+            let json = serde_json::to_string_pretty(&training_dataset)?;
+            fs::write(output, json).await?;
+        } else {
+            // Interactive review; ratings are saved as they are made
+            let mut session = ReviewSession::new(training_dataset, annotator, output);
+            if session.progress().1 == 0 {
+                println!("✅ Every pair in {} is already annotated", dataset.display());
+                return Ok(());
             }
+            review_interactively(&mut session)?;
 
-            // Clear screen for better readability
-            print!("\x1B[2J\x1B[1;1H");
-
-            // Display progress
-            println!("📊 Progress: {annotated_count}/{total_pairs} pairs annotated");
-            println!("📍 Current: {}/{}\n", idx + 1, total_pairs);
-
-            // Display pair information
-            println!("🔧 Fix Description: {}", pair.fix_description);
-            println!("📝 Language: {}", pair.language);
-            println!("📁 File: {}", pair.file_path);
+            let throughput = session.finish()?;
             println!(
-                "⚡ Confidence: {:.2} ({:?})",
-                pair.confidence.score, pair.confidence.category
+                "✅ Reviewed {} pairs, skipped {} ({:.0} pairs/hour, {:.1}s per pair)",
+                throughput.reviewed,
+                throughput.skipped,
+                throughput.per_hour(),
+                throughput.seconds_per_pair()
             );
-            println!();
-
-            // Display diagnostics
-            if !pair.diagnostics.is_empty() {
-                println!("🚨 Diagnostics:");
-                for diag in &pair.diagnostics {
-                    println!("  - [{}] {}", diag.severity, diag.message);
-                }
-                println!();
-            }
-
-            // Display code diff
-            println!("📄 Before:");
-            println!("```{}", pair.language);
-            let before_lines: Vec<&str> = pair.before_code.lines().collect();
-            for (i, line) in before_lines.iter().enumerate() {
-                println!("{:4} | {}", i + 1, line);
-            }
-            println!("```\n");
-
-            println!("✅ After:");
-            println!("```{}", pair.language);
-            let after_lines: Vec<&str> = pair.after_code.lines().collect();
-            for (i, line) in after_lines.iter().enumerate() {
-                println!("{:4} | {}", i + 1, line);
-            }
-            println!("```\n");
-
-            // Show annotation options
-            println!("🏷️  Quality Assessment:");
-            println!("  [1] Perfect    - Fixes all issues correctly");
-            println!("  [2] Good       - Fixes main issue but may have minor issues");
-            println!("  [3] Acceptable - Works but not ideal");
-            println!("  [4] Poor       - Has problems");
-            println!("  [5] Incorrect  - Doesn't fix the issue");
-            println!("  [s] Skip       - Skip this pair");
-            println!("  [q] Quit       - Save and exit");
-            println!();
-
-            // Get user input
-            print!("Enter your choice: ");
-            stdout.flush()?;
-
-            let mut input = String::new();
-            stdin.read_line(&mut input)?;
-            let choice = input.trim().to_lowercase();
-
-            // Create a default verification result for manual annotations
-            let verification = crate::ai_training::annotation::VerificationResult {
-                compiles: true,  // Assume manual review verified compilation
-                tests_pass: None, // Unknown without running tests
-                linter_warnings: vec![],
-                performance_impact: None,
-                side_effects: vec![],
-            };
-
-            match choice.as_str() {
-                "1" => {
-                    tool.annotate_pair(
-                        pair,
-                        FixQuality::Perfect,
-                        "Manual annotation".to_string(),
-                        vec![],
-                        verification.clone(),
-                    )?;
-                    annotated_count += 1;
-                    println!("✓ Annotated as Perfect");
-                }
-                "2" => {
-                    tool.annotate_pair(
-                        pair,
-                        FixQuality::Good,
-                        "Manual annotation".to_string(),
-                        vec![],
-                        verification.clone(),
-                    )?;
-                    annotated_count += 1;
-                    println!("✓ Annotated as Good");
-                }
-                "3" => {
-                    tool.annotate_pair(
-                        pair,
-                        FixQuality::Acceptable,
-                        "Manual annotation".to_string(),
-                        vec![],
-                        verification.clone(),
-                    )?;
-                    annotated_count += 1;
-                    println!("✓ Annotated as Acceptable");
-                }
-                "4" => {
-                    tool.annotate_pair(
-                        pair,
-                        FixQuality::Poor,
-                        "Manual annotation".to_string(),
-                        vec![],
-                        verification.clone(),
-                    )?;
-                    annotated_count += 1;
-                    println!("✓ Annotated as Poor");
-                }
-                "5" => {
-                    tool.annotate_pair(
-                        pair,
-                        FixQuality::Incorrect,
-                        "Manual annotation".to_string(),
-                        vec![],
-                        verification.clone(),
-                    )?;
-                    annotated_count += 1;
-                    println!("✓ Annotated as Incorrect");
-                }
-                "s" => {
-                    println!("⏭️  Skipped");
-                    continue;
-                }
-                "q" => {
-                    println!("💾 Saving and exiting...");
-                    break;
-                }
-                _ => {
-                    println!("❌ Invalid choice. Please try again.");
-                    std::thread::sleep(std::time::Duration::from_secs(1));
-                    continue;
-                }
-            }
-
-            // Brief pause to show confirmation
-            std::thread::sleep(std::time::Duration::from_millis(500));
+            println!("   Throughput logged to {}", review_log_path(output).display());
         }
 
-        // Complete the session
-        if let Ok(session) = tool.complete_session() {
-            println!("\n✅ Annotation session completed!");
-            println!("   Total annotations: {}", session.annotations.len());
-            if let Some(completed_at) = session.completed_at {
-                println!("   Duration: {:?}", completed_at - session.started_at);
-            }
-        }
+        println!("✅ Saved annotated dataset to {}", output.display());
 
         Ok(())
     }
@@ -448,6 +292,9 @@ impl AITrainingCommand {
             OutputFormat::Json => serde_json::to_string_pretty(&report)?,
             OutputFormat::Markdown => format_annotation_report_markdown(&report, &training_dataset),
             OutputFormat::Claude => format_annotation_report_claude(&report, &training_dataset),
+            OutputFormat::Sarif | OutputFormat::Html | OutputFormat::GithubActions => {
+                return Err(format.unsupported_by("ai-training report"))
+            }
         };
 
         println!("{output}");
@@ -456,6 +303,195 @@ impl AITrainingCommand {
     }
 }
 
+/// Restores the terminal when the review UI exits, including on errors
+struct RawTerminal;
+
+impl RawTerminal {
+    fn enter() -> Result<Self> {
+        crossterm::terminal::enable_raw_mode()?;
+        crossterm::execute!(
+            std::io::stdout(),
+            crossterm::terminal::EnterAlternateScreen,
+            crossterm::cursor::Hide
+        )?;
+        Ok(Self)
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = crossterm::execute!(
+            std::io::stdout(),
+            crossterm::cursor::Show,
+            crossterm::terminal::LeaveAlternateScreen
+        );
+        let _ = crossterm::terminal::disable_raw_mode();
+    }
+}
+
+/// Keyboard-driven review of the session's pairs
+///
+/// `1`-`5` rate the pair (Perfect to Incorrect), `c` writes a comment for the
+/// next rating, `n`/`→` skips, `p`/`←` goes back, `j`/`k` scroll the diff and
+/// `q` quits.
+fn review_interactively(session: &mut ReviewSession) -> Result<()> {
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+
+    let _terminal = RawTerminal::enter()?;
+    let mut scroll = 0usize;
+    let mut editing: Option<String> = None;
+    let mut status = String::new();
+
+    while session.current().is_some() {
+        draw_review(session, scroll, editing.as_deref(), &status)?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind == KeyEventKind::Release {
+            continue;
+        }
+
+        if let Some(comment) = editing.as_mut() {
+            match key.code {
+                KeyCode::Enter => {
+                    session.set_comment(std::mem::take(comment));
+                    editing = None;
+                }
+                KeyCode::Esc => editing = None,
+                KeyCode::Backspace => {
+                    comment.pop();
+                }
+                KeyCode::Char(c) => comment.push(c),
+                _ => {}
+            }
+            continue;
+        }
+
+        let quality = match key.code {
+            KeyCode::Char('1') => Some(FixQuality::Perfect),
+            KeyCode::Char('2') => Some(FixQuality::Good),
+            KeyCode::Char('3') => Some(FixQuality::Acceptable),
+            KeyCode::Char('4') => Some(FixQuality::Poor),
+            KeyCode::Char('5') => Some(FixQuality::Incorrect),
+            _ => None,
+        };
+        if let Some(quality) = quality {
+            session.rate(quality)?;
+            status = format!("Rated {quality:?}");
+            scroll = 0;
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => break,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
+            KeyCode::Char('c') => editing = Some(session.comment().to_string()),
+            KeyCode::Char('n') | KeyCode::Right => {
+                session.skip();
+                status = "Skipped".to_string();
+                scroll = 0;
+            }
+            KeyCode::Char('p') | KeyCode::Left => {
+                session.previous();
+                status.clear();
+                scroll = 0;
+            }
+            KeyCode::Char('j') | KeyCode::Down => scroll += 1,
+            KeyCode::Char('k') | KeyCode::Up => scroll = scroll.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn draw_review(session: &ReviewSession, scroll: usize, editing: Option<&str>, status: &str) -> Result<()> {
+    use colored::Colorize;
+    use std::io::Write;
+
+    let Some(pair) = session.current() else {
+        return Ok(());
+    };
+    let (width, height) = crossterm::terminal::size().unwrap_or((80, 24));
+    let width = width as usize;
+    let fit = |line: String| -> String { line.chars().take(width).collect() };
+
+    let (position, total) = session.progress();
+    let throughput = session.throughput();
+    let mut header = vec![
+        format!(
+            "{} — pair {}/{} │ rated {} skipped {} │ {:.0}/h, {:.1}s per pair",
+            session.dataset().name,
+            position + 1,
+            total,
+            throughput.reviewed,
+            throughput.skipped,
+            throughput.per_hour(),
+            throughput.seconds_per_pair()
+        )
+        .bold()
+        .to_string(),
+        fit(format!(
+            "{} │ {} │ confidence {:.2} ({:?})",
+            pair.language, pair.file_path, pair.confidence.score, pair.confidence.category
+        )),
+    ];
+    if !pair.fix_description.is_empty() {
+        header.push(fit(format!("Fix: {}", pair.fix_description)));
+    }
+    for diagnostic in pair.diagnostics.iter().take(3) {
+        header.push(fit(format!("[{}] {}", diagnostic.severity, diagnostic.message)).red().to_string());
+    }
+    header.push(String::new());
+
+    let comment = match editing {
+        Some(text) => format!("Comment: {text}▏").yellow().to_string(),
+        None if !session.comment().is_empty() => format!("Comment: {}", session.comment()),
+        None => String::new(),
+    };
+    let footer = [
+        String::new(),
+        comment,
+        "[1] Perfect [2] Good [3] Acceptable [4] Poor [5] Incorrect │ [c] comment [n] skip [p] back [j/k] scroll [q] quit"
+            .dimmed()
+            .to_string(),
+        status.green().to_string(),
+    ];
+
+    let diff = diff_lines(&pair.before_code, &pair.after_code);
+    let room = (height as usize).saturating_sub(header.len() + footer.len()).max(1);
+    let scroll = scroll.min(diff.len().saturating_sub(room));
+    let body = diff.iter().skip(scroll).take(room).map(|line| match line {
+        DiffLine::Context(text) => fit(format!("  {text}")),
+        DiffLine::Removed(text) => fit(format!("- {text}")).red().to_string(),
+        DiffLine::Added(text) => fit(format!("+ {text}")).green().to_string(),
+    });
+
+    let mut stdout = std::io::stdout();
+    crossterm::queue!(
+        stdout,
+        crossterm::terminal::Clear(crossterm::terminal::ClearType::All),
+        crossterm::cursor::MoveTo(0, 0)
+    )?;
+    let body: Vec<String> = body.collect();
+    let padding = room.saturating_sub(body.len());
+    let blank = String::new();
+    let lines: Vec<&String> = header
+        .iter()
+        .chain(&body)
+        .chain(std::iter::repeat(&blank).take(padding))
+        .chain(&footer)
+        .collect();
+    // No trailing newline, which would scroll the screen when it is full
+    for (index, line) in lines.iter().enumerate() {
+        let end = if index + 1 < lines.len() { "\r\n" } else { "" };
+        write!(stdout, "{line}{end}")?;
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
 fn detect_language(path: &PathBuf) -> String {
     use crate::core::constants::languages;
     
@@ -541,4 +577,16 @@ fn format_annotation_report_claude(
     dataset: &TrainingDataset,
 ) -> String {
     format_annotation_report_markdown(report, dataset) // Same format for now
-}
\ No newline at end of file
+}
+
+/// Error injector for `ai-training synthetic`, compile-verified on request
+fn synthetic_injector(language: &str, verify: bool) -> Result<ErrorInjector> {
+    let injector = ErrorInjector::new();
+    if !verify {
+        return Ok(injector);
+    }
+    if !CompileVerifier::supports(language) {
+        anyhow::bail!("Compile verification is not available for {language}");
+    }
+    Ok(injector.with_verifier(CompileVerifier::new()))
+}
@@ -0,0 +1,95 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::path::Path;
+
+use crate::cli::args::OutputFormat;
+use crate::cli::commands::Command;
+use crate::core::config::UnifiedConfig;
+use crate::core::usage::current_month;
+use crate::core::{ApiAction, QuotaConfig, UsageStore};
+
+pub struct ApiCommand {
+    action: ApiAction,
+}
+
+impl ApiCommand {
+    pub fn new(action: ApiAction) -> Self {
+        Self { action }
+    }
+}
+
+#[async_trait]
+impl Command for ApiCommand {
+    async fn execute(&self) -> Result<()> {
+        match &self.action {
+            ApiAction::Usage { month, client, format } => {
+                let month = month.clone().unwrap_or_else(current_month);
+                let quotas = UnifiedConfig::load_or_default(Path::new("lspbridge.toml"))
+                    .await?
+                    .api_quotas;
+                let store = UsageStore::open(&UsageStore::default_path()?)?;
+
+                let mut records = store.report(&month).await?;
+                if let Some(client) = client {
+                    records.retain(|record| &record.client_id == client);
+                }
+
+                match format {
+                    OutputFormat::Json => {
+                        let report: Vec<_> = records
+                            .iter()
+                            .map(|record| {
+                                let limits = quotas.limits_for(&record.client_id);
+                                serde_json::json!({
+                                    "usage": record,
+                                    "quota": limits,
+                                    "exceeded": limits.exceeded_by(record),
+                                })
+                            })
+                            .collect();
+                        println!("{}", serde_json::to_string_pretty(&report)?);
+                    }
+                    OutputFormat::Sarif | OutputFormat::Html | OutputFormat::GithubActions => {
+                        return Err(format.unsupported_by("api usage"));
+                    }
+                    OutputFormat::Markdown | OutputFormat::Claude => {
+                        println!("# API Usage ({month})\n");
+                        if records.is_empty() {
+                            println!("No API usage recorded.");
+                            return Ok(());
+                        }
+                        println!("| Client | Queries | Rows Scanned | Bytes Exported | Status |");
+                        println!("|--------|---------|--------------|----------------|--------|");
+                        for record in &records {
+                            let limits = quotas.limits_for(&record.client_id);
+                            let status = if limits.exceeded_by(record).is_some() {
+                                "over quota"
+                            } else {
+                                "ok"
+                            };
+                            println!(
+                                "| {} | {} | {} | {} | {status} |",
+                                record.client_id,
+                                with_limit(record.queries, limits.max_queries),
+                                with_limit(record.rows_scanned, limits.max_rows_scanned),
+                                with_limit(record.bytes_exported, limits.max_bytes_exported),
+                            );
+                        }
+                        if quotas == QuotaConfig::default() {
+                            println!("\nNo quotas configured; set `[api_quotas]` in lspbridge.toml to enforce limits.");
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn with_limit(used: u64, limit: Option<u64>) -> String {
+    match limit {
+        Some(limit) => format!("{used} / {limit}"),
+        None => used.to_string(),
+    }
+}
@@ -0,0 +1,93 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+
+use crate::cli::commands::Command;
+use crate::core::{Baseline, BaselineAction, Diagnostic, DiagnosticSeverity};
+use crate::format::FormatConverter;
+
+use super::export::read_raw_diagnostics;
+
+pub struct BaselineCommand {
+    action: BaselineAction,
+}
+
+impl BaselineCommand {
+    pub fn new(action: BaselineAction) -> Self {
+        Self { action }
+    }
+}
+
+#[async_trait]
+impl Command for BaselineCommand {
+    async fn execute(&self) -> Result<()> {
+        let root = std::env::current_dir()?;
+        match &self.action {
+            BaselineAction::Create { file } => {
+                let baseline = Baseline::from_diagnostics(&current_diagnostics().await?, &root);
+                baseline.save(file)?;
+                println!(
+                    "✅ Baselined {} diagnostic(s) in {} file(s) to {}",
+                    baseline.len(),
+                    baseline.files.len(),
+                    file.display()
+                );
+                Ok(())
+            }
+            BaselineAction::Check {
+                file,
+                max_new_errors,
+                max_new_warnings,
+            } => {
+                if !file.exists() {
+                    return Err(anyhow!(
+                        "No baseline at {}; create one with `lspbridge baseline create`",
+                        file.display()
+                    ));
+                }
+                let outcome = Baseline::load(file)?.apply(current_diagnostics().await?, &root);
+
+                for diagnostic in &outcome.new {
+                    println!(
+                        "+ {}:{} {:?}: {}",
+                        diagnostic.file,
+                        diagnostic.range.start.line + 1,
+                        diagnostic.severity,
+                        diagnostic.message
+                    );
+                }
+                let count = |severity| outcome.new.iter().filter(|d| d.severity == severity).count();
+                let (new_errors, new_warnings) = (count(DiagnosticSeverity::Error), count(DiagnosticSeverity::Warning));
+                println!(
+                    "{} new ({} errors, {} warnings), {} baselined, {} fixed since the baseline",
+                    outcome.new.len(),
+                    new_errors,
+                    new_warnings,
+                    outcome.suppressed,
+                    outcome.fixed
+                );
+                if outcome.fixed > 0 {
+                    println!("Run `lspbridge baseline create` to lock in the fixes");
+                }
+
+                let mut violations = Vec::new();
+                if new_errors > *max_new_errors {
+                    violations.push(format!("{new_errors} new errors (max {max_new_errors})"));
+                }
+                if let Some(max) = max_new_warnings.filter(|max| new_warnings > *max) {
+                    violations.push(format!("{new_warnings} new warnings (max {max})"));
+                }
+                if violations.is_empty() {
+                    Ok(())
+                } else {
+                    Err(anyhow!("Baseline check failed: {}", violations.join(", ")))
+                }
+            }
+        }
+    }
+}
+
+/// Diagnostics piped to stdin, or else from the IDE
+async fn current_diagnostics() -> Result<Vec<Diagnostic>> {
+    use crate::core::FormatConverter as FormatConverterTrait;
+    Ok(FormatConverter::new().normalize(read_raw_diagnostics().await?).await?)
+}
@@ -0,0 +1,76 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::cli::args::OutputFormat;
+use crate::cli::commands::Command;
+use crate::core::{BreakerAction, CircuitState, ErrorRecoverySystem, RecoveryStrategy, Subsystem};
+
+pub struct BreakersCommand {
+    action: BreakerAction,
+}
+
+impl BreakersCommand {
+    pub fn new(action: BreakerAction) -> Self {
+        Self { action }
+    }
+}
+
+#[async_trait]
+impl Command for BreakersCommand {
+    async fn execute(&self) -> Result<()> {
+        let recovery = ErrorRecoverySystem::new(RecoveryStrategy::default())
+            .with_state_file(ErrorRecoverySystem::default_state_path()?);
+
+        match &self.action {
+            BreakerAction::Status { format } => {
+                let statuses = recovery.breaker_status().await;
+
+                match format {
+                    OutputFormat::Json => {
+                        println!("{}", serde_json::to_string_pretty(&statuses)?);
+                    }
+                    OutputFormat::Sarif | OutputFormat::Html | OutputFormat::GithubActions => {
+                        return Err(format.unsupported_by("breakers"));
+                    }
+                    OutputFormat::Markdown | OutputFormat::Claude => {
+                        println!("# Circuit Breakers\n");
+                        println!("| Subsystem | State | Failures | Last Failure |");
+                        println!("|-----------|-------|----------|--------------|");
+                        for status in &statuses {
+                            let state = match status.state {
+                                CircuitState::Closed => "closed",
+                                CircuitState::Open => "open",
+                                CircuitState::HalfOpen => "half-open",
+                            };
+                            let last_failure = status
+                                .last_failure
+                                .map(|at| {
+                                    chrono::DateTime::<chrono::Utc>::from(at)
+                                        .format("%Y-%m-%d %H:%M:%S")
+                                        .to_string()
+                                })
+                                .unwrap_or_else(|| "-".to_string());
+                            println!(
+                                "| {} | {state} | {} | {last_failure} |",
+                                status.subsystem, status.failure_count
+                            );
+                        }
+                    }
+                }
+            }
+
+            BreakerAction::Reset { subsystem, all } => {
+                let targets: Vec<Subsystem> = match subsystem {
+                    Some(subsystem) if !*all => vec![*subsystem],
+                    _ => Subsystem::ALL.to_vec(),
+                };
+                for subsystem in targets {
+                    recovery.reset_breaker(subsystem).await?;
+                    println!("✅ Reset circuit breaker for {subsystem}");
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
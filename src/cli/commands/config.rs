@@ -1,10 +1,13 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use std::path::Path;
 use tokio::fs;
 
 use crate::cli::commands::Command;
 use crate::config::ConfigAction;
-use crate::core::BridgeConfig;
+use crate::core::config::migration::{backup_file, CONFIG_FILE};
+use crate::core::config::{LegacyConfigFiles, UnifiedConfig};
+use crate::security::validate_path;
 
 pub struct ConfigCommand {
     action: ConfigAction,
@@ -14,18 +17,49 @@ impl ConfigCommand {
     pub fn new(action: ConfigAction) -> Self {
         Self { action }
     }
+
+    async fn migrate(&self, dir: &Path, dry_run: bool) -> Result<()> {
+        let dir = validate_path(dir)?;
+        let legacy = LegacyConfigFiles::detect(&dir)?;
+        if legacy.is_empty() {
+            println!("No legacy configuration found in {}", dir.display());
+            return Ok(());
+        }
+
+        let migration = legacy.migrate(&dir)?;
+        for path in legacy.config.iter().chain(&legacy.multi_repo) {
+            println!("Migrating {}", path.display());
+        }
+        print!("{}", migration.summary());
+
+        if dry_run {
+            println!("\n{}", toml::to_string_pretty(&migration.config)?);
+            return Ok(());
+        }
+
+        let config_path = dir.join(CONFIG_FILE);
+        if config_path.exists() {
+            println!("Backed up {} to {}", config_path.display(), backup_file(&config_path)?.display());
+        }
+        migration.config.save(&config_path).await?;
+        if let Some(multi_repo) = &legacy.multi_repo {
+            let backup = backup_file(multi_repo)?;
+            fs::remove_file(multi_repo).await?;
+            println!("Moved {} to {}", multi_repo.display(), backup.display());
+        }
+        println!("Configuration written to {}", config_path.display());
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl Command for ConfigCommand {
     async fn execute(&self) -> Result<()> {
-        let config_path = std::env::current_dir()?.join("lspbridge.toml");
+        let config_path = std::env::current_dir()?.join(CONFIG_FILE);
 
         match &self.action {
             ConfigAction::Init => {
-                let default_config = BridgeConfig::default();
-                let toml_content = toml::to_string_pretty(&default_config)?;
-                fs::write(&config_path, toml_content).await?;
+                UnifiedConfig::default().save(&config_path).await?;
                 println!("Configuration initialized at {}", config_path.display());
             }
 
@@ -37,6 +71,8 @@ impl Command for ConfigCommand {
             ConfigAction::Set { key: _, value: _ } => {
                 println!("Set configuration not implemented yet");
             }
+
+            ConfigAction::Migrate { path, dry_run } => self.migrate(path, *dry_run).await?,
         }
 
         Ok(())
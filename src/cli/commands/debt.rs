@@ -0,0 +1,78 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::cli::commands::Command;
+use crate::core::config::UnifiedConfig;
+use crate::core::{DebtAction, DebtFormat, DebtTracker, Diagnostic, RawDiagnostics};
+use crate::format::{parse_json_stream, FormatConverter};
+use crate::security::validate_path;
+
+use super::export::{find_ide_diagnostics, read_stdin};
+
+pub struct DebtCommand {
+    action: DebtAction,
+}
+
+impl DebtCommand {
+    pub fn new(action: DebtAction) -> Self {
+        Self { action }
+    }
+}
+
+#[async_trait]
+impl Command for DebtCommand {
+    async fn execute(&self) -> Result<()> {
+        match &self.action {
+            DebtAction::Todos {
+                path,
+                format,
+                kind,
+                min_age_days,
+                output,
+            } => {
+                let config = UnifiedConfig::load_or_default(&path.join("lspbridge.toml")).await?;
+                let tracker = DebtTracker::new(path, config.debt).with_max_file_size(config.scan.max_file_size);
+                let mut report = tracker.collect(&current_diagnostics().await?).await?;
+                report.retain(kind, *min_age_days);
+
+                let escalated = report.items.iter().filter(|item| item.escalated()).count();
+                eprintln!("{} debt item(s), {} escalated by age", report.items.len(), escalated);
+
+                let rendered = match format {
+                    DebtFormat::Markdown => report.to_markdown(),
+                    DebtFormat::Json => serde_json::to_string_pretty(&report)?,
+                    DebtFormat::Csv => report.to_csv(),
+                };
+                match output {
+                    Some(output) => {
+                        let validated_path = validate_path(output)?;
+                        tokio::fs::write(&validated_path, rendered).await?;
+                        eprintln!("Debt report written to {}", validated_path.display());
+                    }
+                    None => print!("{rendered}"),
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Diagnostics to look for deprecations in; none when no source is available
+async fn current_diagnostics() -> Result<Vec<Diagnostic>> {
+    let raw = match find_ide_diagnostics().await {
+        Ok(diags) => diags,
+        Err(_) if atty::isnt(atty::Stream::Stdin) => RawDiagnostics {
+            source: "stdin".to_string(),
+            data: parse_json_stream(&read_stdin().await?)?,
+            timestamp: chrono::Utc::now(),
+            workspace: None,
+        },
+        Err(_) => {
+            tracing::debug!("No diagnostics available; reporting comments only");
+            return Ok(Vec::new());
+        }
+    };
+
+    use crate::core::FormatConverter as FormatConverterTrait;
+    Ok(FormatConverter::new().normalize(raw).await?)
+}
@@ -1,109 +1,517 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::io::{BufWriter, Write};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Component, Path, PathBuf};
 use tokio::fs;
+use uuid::Uuid;
 
 use crate::capture::{CaptureService, MemoryCache};
 use crate::core::DiagnosticsCaptureService;
-use crate::cli::args::{ExportArgs, OutputFormat};
+use crate::cli::args::{parse_as_of, ExportArgs, OutputFormat};
 use crate::cli::commands::Command;
-use crate::core::traits::ExportService as ExportServiceTrait;
+use crate::core::config::UnifiedConfig;
 use crate::core::{
-    DiagnosticFilter, DiagnosticSnapshot, ExportConfig, ExportFormat,
-    RawDiagnostics, SortBy,
+    dedupe, ApiSurfaceAnalyzer, Baseline, CaptureMethod, Diagnostic, CrashCorrelator, CrashReportParser, DiagnosticFilter, DiagnosticRegion, DiagnosticSnapshot, ErrorRecoverySystem, ExportConfig,
+    ExportFormat, FileGuard, GeneratedCodeMapper, NoiseConfig, NoiseModel, NoiseReport, RawDiagnostics, SeverityRules, SortBy, SourceWatcher, Subsystem,
+    TriageEngine, TriageSuggestion, WorkspaceInfo, BASELINE_FILE,
 };
+use crate::core::FormatConverter as _;
+use crate::core::PrivacyFilter as _;
 use crate::core::security_config::PrivacyLevel;
-use crate::core::PrivacyPolicy;
-use crate::export::ExportService;
-use crate::format::FormatConverter;
-use crate::privacy::PrivacyFilter;
+use crate::core::{LicenseFilter, PrivacyPolicy};
+use crate::export::{Compression, ExportBundle, ExportOutput, ExportService, ParquetWriter, RoutedExportSet};
+use crate::format::{parse_json_stream, FormatConverter, TokenEstimator};
+use crate::history::{AsOf, HistoryConfig, HistoryService};
+use crate::multi_repo::RepositoryRegistry;
+use crate::privacy::{PrivacyFilter, RedactionAudit, RedactionPreview};
 use crate::security::validate_path;
 
-use super::utils::create_diagnostic_filter;
-
 pub struct ExportCommand {
     args: ExportArgs,
+    /// Breakers that export writes run under
+    recovery: ErrorRecoverySystem,
 }
 
 impl ExportCommand {
     pub fn new(args: ExportArgs) -> Self {
-        Self { args }
+        Self {
+            args,
+            recovery: ErrorRecoverySystem::persistent(),
+        }
+    }
+
+    /// Audit trail in `--audit-log`, or else `privacy.audit_log` of the project config
+    fn privacy_audit(&self, config: &UnifiedConfig) -> Option<RedactionAudit> {
+        self.args
+            .audit_log
+            .as_ref()
+            .or(config.privacy.audit_log.as_ref())
+            .map(RedactionAudit::at)
+    }
+
+    /// The `--privacy` filter, audited when an audit log is configured
+    fn privacy_filter(&self, config: &UnifiedConfig) -> PrivacyFilter {
+        let filter = PrivacyFilter::new(get_privacy_policy(&self.args.privacy));
+        match self.privacy_audit(config) {
+            Some(audit) => filter.with_audit(audit),
+            None => filter,
+        }
+    }
+
+    /// An empty bundle stamped with the snapshot time, or the epoch for `--stable`
+    fn new_bundle(&self, snapshot: &DiagnosticSnapshot) -> ExportBundle {
+        let generated_at = if self.args.stable {
+            DateTime::<Utc>::default()
+        } else {
+            snapshot.timestamp
+        };
+        ExportBundle::new(generated_at)
+    }
+
+    fn parquet_writer(&self, path: &Path) -> Result<ParquetWriter<BufWriter<std::fs::File>>> {
+        let file = std::fs::File::create(validate_path(path)?)?;
+        Ok(ParquetWriter::new(BufWriter::new(file))?
+            .with_row_group_size(self.args.row_group_size)
+            .with_compression(self.args.compress))
+    }
+
+    /// Stream every recorded history snapshot into Parquet, one file's history at a time
+    async fn export_history_parquet(&self, filter: &DiagnosticFilter, config: &UnifiedConfig) -> Result<()> {
+        let Some(path) = &self.args.parquet else {
+            return Err(anyhow!("--all-history needs --parquet"));
+        };
+        let history = HistoryService::connect(HistoryConfig::default(), "export").await?;
+        let privacy_filter = self.privacy_filter(config);
+        let mut writer = self.parquet_writer(path)?;
+
+        for file in history.get_files_since(std::time::UNIX_EPOCH).await? {
+            // A `since` bound reads past the snapshot cache, which would otherwise keep every file's history
+            for snapshot in history
+                .get_snapshots_for_file(&file, Some(std::time::UNIX_EPOCH), None)
+                .await?
+            {
+                let captured_at: DateTime<Utc> = snapshot.timestamp.into();
+                let diagnostics = filter.apply(privacy_filter.apply(snapshot.diagnostics)?, captured_at);
+                for diagnostic in &diagnostics {
+                    writer.write(diagnostic, Some(captured_at))?;
+                }
+            }
+        }
+        finish_parquet(writer, path)
+    }
+
+    /// Diagnostics from `--input`, stdin or a running IDE
+    async fn read_raw_diagnostics(&self) -> Result<RawDiagnostics> {
+        let Some(input) = &self.args.input else {
+            return read_raw_diagnostics().await;
+        };
+        let path = validate_path(input)?;
+        let content = fs::read_to_string(&path).await?;
+        Ok(RawDiagnostics {
+            source: path.display().to_string(),
+            data: parse_json_stream(&content)?,
+            timestamp: chrono::Utc::now(),
+            workspace: None,
+        })
+    }
+
+    /// Capture diagnostics from `--input`, stdin or a running IDE
+    async fn capture_live_snapshot(&self, cwd: Option<&Path>, config: &UnifiedConfig) -> Result<DiagnosticSnapshot> {
+        let privacy_filter = self.privacy_filter(config);
+        let format_converter = FormatConverter::new();
+        let cache = MemoryCache::with_defaults();
+        let severity_rules =
+            SeverityRules::from_config(&config.severity_rules).context("Invalid [severity_rules] in lspbridge.toml")?;
+        let mut capture_service =
+            CaptureService::new(cache, privacy_filter, format_converter).with_severity_rules(severity_rules);
+
+        if let Some(cwd) = cwd {
+            if let Some(mapper) = generated_code_mapper(cwd, config) {
+                capture_service = capture_service.with_generated_code(mapper);
+            }
+            if self.args.api_surface {
+                capture_service = capture_service.with_api_surface(ApiSurfaceAnalyzer::new(cwd));
+            }
+            if !self.args.no_baseline {
+                if let Some(baseline) = Baseline::discover(cwd)? {
+                    capture_service = capture_service.with_baseline(baseline, cwd);
+                }
+            }
+        }
+
+        if !self.args.crash_log.is_empty() {
+            let mut reports = Vec::new();
+            for path in &self.args.crash_log {
+                reports.extend(CrashReportParser::parse_file(path)?);
+            }
+            let mut correlator = CrashCorrelator::new(reports);
+            if let Some(cwd) = cwd {
+                correlator = correlator.with_workspace_root(cwd);
+            }
+            capture_service = capture_service.with_crash_reports(correlator);
+        }
+
+        let raw_diagnostics = self.read_raw_diagnostics().await?;
+
+        // Process diagnostics
+        capture_service.start_capture().await?;
+        capture_service.process_diagnostics(raw_diagnostics).await?;
+        capture_service
+            .get_current_snapshot()
+            .await?
+            .ok_or_else(|| anyhow!("No diagnostics found"))
+    }
+
+    /// Re-export on every change to the sources or the `--input` file
+    async fn watch(&self, session: WatchSession<'_>) -> Result<()> {
+        if self.args.input.is_none() && atty::isnt(atty::Stream::Stdin) {
+            return Err(anyhow!("--watch cannot re-read stdin; pass the diagnostics file with --input"));
+        }
+        let root = session.cwd.map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from("."));
+        let mut watched = vec![root.clone()];
+        watched.extend(self.args.input.clone());
+        let mut watcher =
+            SourceWatcher::new(&watched)?.with_debounce(std::time::Duration::from_millis(self.args.debounce_ms));
+        if let Some(out_dir) = &self.args.out_dir {
+            watcher = watcher.ignore(&prepare_out_dir(out_dir).await?);
+        }
+        if let Some(output) = &self.args.output {
+            watcher = watcher.ignore(output);
+        }
+
+        eprintln!("Watching {} for changes", root.display());
+        let mut rendered = HashMap::new();
+        let mut changed_sources = BTreeSet::new();
+        loop {
+            if let Err(e) = self.export_changes(&session, &mut rendered, &changed_sources).await {
+                eprintln!("Export failed: {e}");
+            }
+            match watcher.next_change().await {
+                Some(changed) => changed_sources = changed,
+                None => return Ok(()),
+            }
+        }
+    }
+
+    /// Capture and re-render the files whose diagnostics, or sources when
+    /// context is included, changed since the last export
+    ///
+    /// `rendered` holds the diagnostics last exported per file, by content id.
+    async fn export_changes(
+        &self,
+        session: &WatchSession<'_>,
+        rendered: &mut HashMap<String, Vec<String>>,
+        changed_sources: &BTreeSet<PathBuf>,
+    ) -> Result<()> {
+        let snapshot = self.capture_live_snapshot(session.cwd, session.config).await?;
+        let mut snapshot = apply_filtering(snapshot, session.filter)?;
+        if self.args.dedupe {
+            dedupe_snapshot(&mut snapshot);
+        }
+
+        let mut by_file: BTreeMap<String, Vec<Diagnostic>> = BTreeMap::new();
+        for diagnostic in &snapshot.diagnostics {
+            by_file.entry(diagnostic.file.clone()).or_default().push(diagnostic.clone());
+        }
+        let current: HashMap<String, Vec<String>> = by_file
+            .iter()
+            .map(|(file, diagnostics)| {
+                let mut ids: Vec<String> = diagnostics.iter().map(Diagnostic::content_id).collect();
+                ids.sort();
+                (file.clone(), ids)
+            })
+            .collect();
+
+        let mut changed: BTreeSet<&String> = current
+            .keys()
+            .chain(rendered.keys())
+            .filter(|file| current.get(*file) != rendered.get(*file))
+            .collect();
+        if self.args.include_context {
+            changed.extend(current.keys().filter(|file| {
+                changed_sources.contains(&source_path(file, session.cwd))
+            }));
+        }
+        if changed.is_empty() {
+            return Ok(());
+        }
+
+        let formats: Vec<ExportFormat> = self.args.formats.iter().map(|f| (*f).into()).collect();
+        let compress = self.args.compress;
+        match &self.args.out_dir {
+            // One document per source file; only changed files are rendered
+            Some(out_dir) => {
+                let out_dir = prepare_out_dir(out_dir).await?;
+                for file in &changed {
+                    let base = out_dir.join(document_path(file, session.cwd));
+                    let Some(diagnostics) = by_file.get(*file) else {
+                        for format in &formats {
+                            let path = with_extension(&base, format.file_extension(), compress);
+                            if fs::remove_file(&path).await.is_ok() {
+                                eprintln!("Removed {}", path.display());
+                            }
+                        }
+                        continue;
+                    };
+                    let mut part = snapshot.clone();
+                    part.diagnostics = diagnostics.clone();
+                    if let Some(parent) = base.parent() {
+                        fs::create_dir_all(parent).await?;
+                    }
+                    for output in session.export_service.export_multi(&part, session.export_config, &formats)? {
+                        let path = with_extension(&base, output.format.file_extension(), compress);
+                        write_export(&self.recovery, &path, &encode(&output, compress)?).await?;
+                        eprintln!("Diagnostics exported to {}", path.display());
+                    }
+                }
+            }
+            None => {
+                let [format] = formats.as_slice() else {
+                    return Err(anyhow!("Exporting several formats requires --out-dir"));
+                };
+                let outputs =
+                    session.export_service.export_multi(&snapshot, session.export_config, std::slice::from_ref(format))?;
+                for output in &outputs {
+                    match &self.args.output {
+                        Some(output_path) => {
+                            let validated_path = validate_path(output_path)?;
+                            write_export(&self.recovery, &validated_path, &encode(output, compress)?).await?;
+                            eprintln!(
+                                "Diagnostics exported to {} ({} file(s) changed)",
+                                validated_path.display(),
+                                changed.len()
+                            );
+                        }
+                        None => println!("{}", output.content),
+                    }
+                }
+            }
+        }
+
+        *rendered = current;
+        Ok(())
+    }
+
+    /// Render original vs privacy-filtered diagnostics without exporting
+    async fn preview_redaction(&self) -> Result<()> {
+        let raw_diagnostics = self.read_raw_diagnostics().await?;
+        let diagnostics = FormatConverter::new().normalize(raw_diagnostics).await?;
+        let privacy_filter = PrivacyFilter::new(get_privacy_policy(&self.args.privacy));
+        let preview = RedactionPreview::build(&privacy_filter, diagnostics, self.args.preview_sample)?;
+
+        let width = crossterm::terminal::size()
+            .map(|(columns, _)| columns as usize)
+            .unwrap_or(120);
+        let rendered = preview.render(self.args.preview_style, width);
+
+        if let Some(output_path) = &self.args.output {
+            let validated_path = validate_path(output_path)?;
+            write_export(&self.recovery, &validated_path, rendered.as_bytes()).await?;
+            eprintln!("Redaction preview written to {}", validated_path.display());
+        } else {
+            println!("{rendered}");
+        }
+        Ok(())
     }
 }
 
 #[async_trait]
 impl Command for ExportCommand {
     async fn execute(&self) -> Result<()> {
-        // Setup services
-        let privacy_filter = PrivacyFilter::new(get_privacy_policy(&self.args.privacy));
-        let format_converter = FormatConverter::new();
-        let cache = MemoryCache::with_defaults();
-        let mut capture_service = CaptureService::new(cache, privacy_filter, format_converter);
-        
-        // Try to detect project info from current directory
-        let export_service = match std::env::current_dir() {
-            Ok(cwd) => ExportService::with_project_info(&cwd),
-            Err(_) => ExportService::new(),
-        };
+        if self.args.preview_redaction {
+            return self.preview_redaction().await;
+        }
 
         // Create filter from options
-        let filter = create_diagnostic_filter(
-            self.args.errors_only,
-            self.args.warnings_and_errors,
-            self.args.files.clone(),
-            self.args.exclude.clone(),
-            self.args.max_results,
-        )?;
+        let filter = self.args.filter.to_filter(self.args.max_results)?;
+
+        let cwd = std::env::current_dir().ok();
+        let config = match &cwd {
+            Some(cwd) => load_project_config(cwd).await,
+            None => UnifiedConfig::default(),
+        };
+
+        if self.args.all_history {
+            return self.export_history_parquet(&filter, &config).await;
+        }
+        let file_guard = FileGuard::from(&config.performance);
 
         // Create export config
         let export_config = create_export_config(&self.args)?;
 
-        // Try to read diagnostics from standard input or find from IDE
-        let raw_diagnostics = if atty::is(atty::Stream::Stdin) {
-            // Not piped, try to find diagnostics from running IDE
-            find_ide_diagnostics().await?
-        } else {
-            // Read from stdin
-            let input = read_stdin().await?;
-            RawDiagnostics {
-                source: "stdin".to_string(),
-                data: serde_json::from_str(&input)?,
-                timestamp: chrono::Utc::now(),
-                workspace: None,
+        if self.args.watch {
+            let export_service = match &cwd {
+                Some(cwd) => ExportService::with_project_info(cwd),
+                None => ExportService::new(),
+            };
+            let session = WatchSession {
+                cwd: cwd.as_deref(),
+                config: &config,
+                filter: &filter,
+                export_config: &export_config,
+                export_service: export_service.with_file_guard(file_guard),
+            };
+            return self.watch(session).await;
+        }
+
+        // Historical exports leave out live project info so they reproduce exactly
+        let (snapshot, export_service) = match &self.args.as_of {
+            Some(as_of) => {
+                let as_of = parse_as_of(as_of)?;
+                let snapshot = reconstruct_snapshot(as_of, cwd.as_deref(), &self.privacy_filter(&config)).await?;
+                (snapshot, ExportService::new())
+            }
+            None => {
+                let export_service = match &cwd {
+                    Some(cwd) => ExportService::with_project_info(cwd),
+                    None => ExportService::new(),
+                };
+                if !self.args.no_baseline && cwd.as_ref().is_some_and(|cwd| cwd.join(BASELINE_FILE).exists()) {
+                    eprintln!("Leaving out diagnostics accepted in {BASELINE_FILE}; pass --no-baseline to include them");
+                }
+                (self.capture_live_snapshot(cwd.as_deref(), &config).await?, export_service)
             }
         };
+        let mut export_service = export_service.with_file_guard(file_guard.clone());
 
-        // Process diagnostics
-        capture_service.start_capture().await?;
-        capture_service.process_diagnostics(raw_diagnostics).await?;
-        let snapshot = capture_service
-            .get_current_snapshot()
-            .await?
-            .ok_or_else(|| anyhow!("No diagnostics found"))?;
+        // The noise model learns from everything captured, not just what this export keeps
+        let captured = self.args.mute_noise.then(|| snapshot.diagnostics.clone());
 
         // Apply additional filtering if specified
-        let filtered_snapshot = apply_filtering(snapshot, &filter)?;
+        let mut filtered_snapshot = apply_filtering(snapshot, &filter)?;
 
-        // Export
-        let output_content = match self.args.format {
-            OutputFormat::Markdown => {
-                export_service.export_to_markdown(&filtered_snapshot, &export_config)?
-            }
-            OutputFormat::Claude => {
-                export_service.export_to_claude_optimized(&filtered_snapshot, &export_config)?
+        if let Some(fleet) = &self.args.fleet {
+            filtered_snapshot = restrict_to_fleet(filtered_snapshot, fleet, cwd.as_deref(), &config).await?;
+        }
+
+        if let Some(captured) = captured {
+            let config = NoiseConfig {
+                mute_after_days: self.args.noise_after_days,
+                ..NoiseConfig::default()
+            };
+            let report = apply_noise_model(&captured, &mut filtered_snapshot, config)?;
+            if report.muted_count() > 0 {
+                eprintln!(
+                    "Muted {} chronically ignored diagnostic(s) from {} pattern(s)",
+                    report.muted_count(),
+                    report.muted.len()
+                );
             }
-            OutputFormat::Json => {
-                export_service.export_to_json(&filtered_snapshot, &export_config)?
+            export_service = export_service.with_noise_report(report);
+        }
+
+        if self.args.dedupe {
+            dedupe_snapshot(&mut filtered_snapshot);
+        }
+
+        if let Some(path) = &self.args.parquet {
+            let mut writer = self.parquet_writer(path)?;
+            for diagnostic in &filtered_snapshot.diagnostics {
+                writer.write(diagnostic, None)?;
             }
+            return finish_parquet(writer, path);
+        }
+
+        if self.args.triage {
+            let suggestions = build_triage(cwd.as_deref(), &filtered_snapshot).await;
+            export_service = export_service.with_triage(suggestions);
+        }
+
+        if self.args.route && config.export_routing.is_empty() {
+            return Err(anyhow!("--route needs [[export_routing.routes]] in lspbridge.toml"));
+        }
+        let wants_claude = if self.args.route {
+            config
+                .export_routing
+                .routes
+                .iter()
+                .any(|route| route.formats.iter().any(|f| matches!(f, ExportFormat::ClaudeOptimized)))
+        } else {
+            self.args.formats.contains(&OutputFormat::Claude)
         };
+        let estimator = TokenEstimator::new(self.args.model);
+        if wants_claude {
+            export_service =
+                export_service.with_token_estimator(estimator.clone(), self.args.max_tokens);
+        }
+
+        let license_filter = cwd
+            .as_deref()
+            .and_then(|cwd| LicenseFilter::from_rules(cwd, &config.privacy.license_rules));
+        if let Some(filter) = license_filter {
+            export_service = export_service.with_license_filter(filter);
+            let exclusions = export_service.license_exclusions(&filtered_snapshot.diagnostics);
+            if wants_claude && !exclusions.is_empty() {
+                eprintln!("License rules affect {} file(s) in the AI export:", exclusions.len());
+                for exclusion in &exclusions {
+                    eprintln!("  {} ({}): {}", exclusion.file, exclusion.license.spdx, exclusion.action);
+                }
+            }
+        }
+
+        if self.args.route {
+            if let Some(audit) = self.privacy_audit(&config) {
+                export_service = export_service.with_privacy_audit(audit);
+            }
+            let routed = export_service.export_routed(&filtered_snapshot, &export_config, &config.export_routing)?;
+            report_skipped_files(&file_guard);
+            for route in &routed.routes {
+                report_token_estimates(&route.outputs, &estimator);
+            }
+            if let Some(bundle_path) = &self.args.bundle {
+                let mut bundle = self.new_bundle(&filtered_snapshot);
+                for route in &routed.routes {
+                    for output in &route.outputs {
+                        bundle.add_output(&format!("{}/{}", route.route, route.file_name(output)), output)?;
+                    }
+                }
+                write_bundle(&self.recovery, &bundle, bundle_path, self.args.compress).await?;
+                report_unrouted(&routed);
+                return Ok(());
+            }
+            return write_routed(&self.recovery, &routed, self.args.out_dir.as_deref(), self.args.compress).await;
+        }
+
+        // Export every requested format from the same snapshot in one pass
+        let formats: Vec<ExportFormat> = self.args.formats.iter().map(|f| (*f).into()).collect();
+        let outputs = export_service.export_multi(&filtered_snapshot, &export_config, &formats)?;
+
+        report_skipped_files(&file_guard);
+        report_token_estimates(&outputs, &estimator);
 
         // Write output
-        if let Some(output_path) = &self.args.output {
-            // Validate the output path for security
-            let validated_path = validate_path(output_path)?;
-            fs::write(&validated_path, &output_content).await?;
-            eprintln!("Diagnostics exported to {}", validated_path.display());
+        let compress = self.args.compress;
+        if let Some(bundle_path) = &self.args.bundle {
+            let mut bundle = self.new_bundle(&filtered_snapshot);
+            for output in &outputs {
+                bundle.add_output(&output.file_name(), output)?;
+            }
+            write_bundle(&self.recovery, &bundle, bundle_path, compress).await?;
+        } else if let Some(out_dir) = &self.args.out_dir {
+            let validated_dir = prepare_out_dir(out_dir).await?;
+            for output in &outputs {
+                let path = validated_dir.join(compressed_name(output.file_name(), compress));
+                write_export(&self.recovery, &path, &encode(output, compress)?).await?;
+                eprintln!("Diagnostics exported to {}", path.display());
+            }
+        } else if let [output] = outputs.as_slice() {
+            if let Some(output_path) = &self.args.output {
+                // Validate the output path for security; the name is used as given
+                let validated_path = validate_path(output_path)?;
+                write_export(&self.recovery, &validated_path, &encode(output, compress)?).await?;
+                eprintln!("Diagnostics exported to {}", validated_path.display());
+            } else if compress.is_some() {
+                if atty::is(atty::Stream::Stdout) {
+                    return Err(anyhow!("Refusing to write compressed output to a terminal; use --output or a pipe"));
+                }
+                std::io::stdout().write_all(&encode(output, compress)?)?;
+            } else {
+                print!("{}", output.content);
+            }
         } else {
-            print!("{output_content}");
+            return Err(anyhow!("Exporting several formats requires --out-dir"));
         }
 
         Ok(())
@@ -112,54 +520,401 @@ impl Command for ExportCommand {
 
 // Helper functions specific to export command
 
+fn report_skipped_files(file_guard: &FileGuard) {
+    let skipped = file_guard.skipped();
+    if !skipped.is_empty() {
+        eprintln!("Skipped {} file(s) while extracting context:", skipped.len());
+        for file in &skipped {
+            eprintln!("  {}: {}", file.path.display(), file.reason);
+        }
+    }
+}
+
+fn report_token_estimates(outputs: &[ExportOutput], estimator: &TokenEstimator) {
+    for output in outputs {
+        if matches!(output.format, ExportFormat::ClaudeOptimized) {
+            let estimate = estimator.estimate_text(&output.content);
+            eprintln!(
+                "Estimated ~{} tokens (~${:.4}) for {}",
+                estimate.tokens, estimate.estimated_cost_usd, estimate.model
+            );
+        }
+    }
+}
+
+fn finish_parquet(writer: ParquetWriter<BufWriter<std::fs::File>>, path: &Path) -> Result<()> {
+    let rows = writer.rows_written();
+    writer.finish()?;
+    eprintln!("{rows} diagnostic(s) exported to {}", path.display());
+    Ok(())
+}
+
+/// File name with the compression suffix, e.g. `diagnostics.json.gz`
+fn compressed_name(name: String, compress: Option<Compression>) -> String {
+    match compress {
+        Some(compression) => format!("{name}.{}", compression.extension()),
+        None => name,
+    }
+}
+
+/// The bytes written for an output
+fn encode(output: &ExportOutput, compress: Option<Compression>) -> Result<Vec<u8>> {
+    match compress {
+        Some(compression) => compression.compress(output.content.as_bytes()),
+        None => Ok(output.content.clone().into_bytes()),
+    }
+}
+
+/// Write a bundle to `path`, adding `.tar`, `.tar.gz` or `.tar.zst` if it has no extension
+/// Write one export file under the export breaker, retrying transient failures
+async fn write_export(recovery: &ErrorRecoverySystem, path: &Path, contents: &[u8]) -> Result<()> {
+    recovery
+        .execute_in(Subsystem::Export, || fs::write(path, contents))
+        .await
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+async fn write_bundle(
+    recovery: &ErrorRecoverySystem,
+    bundle: &ExportBundle,
+    path: &Path,
+    compress: Option<Compression>,
+) -> Result<()> {
+    let mut path = validate_path(path)?;
+    if path.extension().is_none() {
+        path.set_extension(ExportBundle::extension(compress));
+    }
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).await?;
+    }
+    let archive = bundle.to_archive(compress)?;
+    write_export(recovery, &path, &archive).await?;
+    eprintln!(
+        "{} file(s) bundled into {} ({} bytes)",
+        bundle.manifest(compress).files.len(),
+        path.display(),
+        archive.len()
+    );
+    Ok(())
+}
+
+fn report_unrouted(routed: &RoutedExportSet) {
+    if routed.unrouted > 0 {
+        eprintln!("{} diagnostic(s) matched no route and were not exported", routed.unrouted);
+    }
+}
+
+/// What every `--watch` export shares
+struct WatchSession<'a> {
+    cwd: Option<&'a Path>,
+    config: &'a UnifiedConfig,
+    filter: &'a DiagnosticFilter,
+    export_config: &'a ExportConfig,
+    export_service: ExportService,
+}
+
+/// A diagnostic's file as an absolute path, for matching watcher events
+fn source_path(file: &str, cwd: Option<&Path>) -> PathBuf {
+    let path = Path::new(file.strip_prefix("file://").unwrap_or(file));
+    match cwd {
+        Some(cwd) if path.is_relative() => cwd.join(path),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Where a source file's document goes below `--out-dir`, e.g. `src/lib.rs`
+///
+/// Paths outside the workspace keep their full path below the output
+/// directory; `..` components never leave it.
+fn document_path(file: &str, cwd: Option<&Path>) -> PathBuf {
+    let path = source_path(file, cwd);
+    let relative = cwd.and_then(|cwd| path.strip_prefix(cwd).ok()).unwrap_or(&path);
+    relative
+        .components()
+        .filter_map(|component| match component {
+            std::path::Component::Normal(part) => Some(part.to_os_string()),
+            std::path::Component::ParentDir => Some("__".into()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// `src/lib.rs` + `json` -> `src/lib.rs.json`, plus the compression suffix
+fn with_extension(base: &Path, extension: &str, compress: Option<Compression>) -> PathBuf {
+    let mut name = base.as_os_str().to_os_string();
+    name.push(".");
+    name.push(compressed_name(extension.to_string(), compress));
+    PathBuf::from(name)
+}
+
+/// Collapse duplicate diagnostics, reporting how many were folded
+fn dedupe_snapshot(snapshot: &mut DiagnosticSnapshot) {
+    let outcome = dedupe(std::mem::take(&mut snapshot.diagnostics));
+    if outcome.collapsed() > 0 {
+        eprintln!(
+            "Collapsed {} duplicate diagnostic(s); {} remain",
+            outcome.collapsed(),
+            outcome.diagnostics.len()
+        );
+    }
+    snapshot.diagnostics = outcome.diagnostics;
+}
+
+/// Write each route's documents to the route's directory or `--out-dir`
+/// Create `dir` if needed and return its validated path
+///
+/// Validation canonicalizes, which needs the directory to exist, so
+/// traversal is rejected before anything is created.
+async fn prepare_out_dir(dir: &Path) -> Result<PathBuf> {
+    if dir.components().any(|component| component == Component::ParentDir) {
+        return Err(anyhow!("Invalid path: path traversal detected in {}", dir.display()));
+    }
+    fs::create_dir_all(dir).await?;
+    Ok(validate_path(dir)?)
+}
+
+async fn write_routed(
+    recovery: &ErrorRecoverySystem,
+    routed: &RoutedExportSet,
+    out_dir: Option<&Path>,
+    compress: Option<Compression>,
+) -> Result<()> {
+    // Resolve every directory first so a misconfigured route writes nothing
+    let mut targets = Vec::with_capacity(routed.routes.len());
+    for route in &routed.routes {
+        let dir = route
+            .out_dir
+            .as_deref()
+            .or(out_dir)
+            .ok_or_else(|| anyhow!("Export route '{}' has no out_dir; pass --out-dir", route.route))?;
+        targets.push((route, dir));
+    }
+
+    for (route, dir) in targets {
+        let dir = prepare_out_dir(dir).await?;
+        for output in &route.outputs {
+            let path = dir.join(compressed_name(route.file_name(output), compress));
+            write_export(recovery, &path, &encode(output, compress)?).await?;
+            eprintln!(
+                "Route {}: {} diagnostic(s) exported to {}",
+                route.route,
+                route.diagnostics,
+                path.display()
+            );
+        }
+    }
+    report_unrouted(routed);
+    Ok(())
+}
+
+/// Diagnostics piped to stdin, or else from a running IDE under the capture breaker
+pub async fn read_raw_diagnostics() -> Result<RawDiagnostics> {
+    if atty::is(atty::Stream::Stdin) {
+        ErrorRecoverySystem::persistent()
+            .execute_in(Subsystem::Capture, find_ide_diagnostics)
+            .await
+    } else {
+        // Read from stdin
+        let input = read_stdin().await?;
+        Ok(RawDiagnostics {
+            source: "stdin".to_string(),
+            data: parse_json_stream(&input)?,
+            timestamp: chrono::Utc::now(),
+            workspace: None,
+        })
+    }
+}
+
+/// `lspbridge.toml` from the project root, or the defaults if it is missing or invalid
+async fn load_project_config(root: &Path) -> UnifiedConfig {
+    match UnifiedConfig::load_or_default(&root.join("lspbridge.toml")).await {
+        Ok(config) => config,
+        Err(e) => {
+            tracing::debug!("Ignoring lspbridge.toml: {}", e);
+            UnifiedConfig::default()
+        }
+    }
+}
+
+/// Generated-code rules from the project config, if any
+fn generated_code_mapper(root: &Path, config: &UnifiedConfig) -> Option<GeneratedCodeMapper> {
+    match GeneratedCodeMapper::from_config(root, &config.generated_code) {
+        Ok(mapper) if !mapper.is_empty() => Some(mapper),
+        Ok(_) => None,
+        Err(e) => {
+            eprintln!("Warning: {e:#}");
+            None
+        }
+    }
+}
+
+/// Rebuild a workspace snapshot from recorded history
+///
+/// The snapshot id and timestamp come from the newest history snapshot
+/// included, and every collection is ordered, so exporting the same point
+/// twice produces identical bytes.
+async fn reconstruct_snapshot(
+    as_of: AsOf,
+    workspace_root: Option<&Path>,
+    privacy_filter: &PrivacyFilter,
+) -> Result<DiagnosticSnapshot> {
+    let history = HistoryService::connect(HistoryConfig::default(), "export")
+        .await?
+        .reconstruct(as_of)
+        .await?;
+    let newest = history
+        .iter()
+        .max_by_key(|snapshot| (snapshot.timestamp, snapshot.id))
+        .map(|snapshot| (snapshot.id, snapshot.timestamp))
+        .ok_or_else(|| match as_of {
+            AsOf::Snapshot(id) => anyhow!("No history snapshot with id {id}"),
+            AsOf::Time(_) => anyhow!("No diagnostics history recorded at or before --as-of"),
+        })?;
+
+    let diagnostics = history
+        .into_iter()
+        .flat_map(|snapshot| snapshot.diagnostics)
+        .collect();
+    let diagnostics = privacy_filter.apply(diagnostics)?;
+
+    let workspace = WorkspaceInfo {
+        name: workspace_root
+            .and_then(|root| root.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "workspace".to_string()),
+        root_path: workspace_root
+            .map(|root| root.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        language: None,
+        version: None,
+        roots: Vec::new(),
+    };
+
+    let mut snapshot = DiagnosticSnapshot::new(workspace, diagnostics);
+    snapshot.id = Uuid::from_u128(newest.0 as u128);
+    snapshot.timestamp = newest.1.into();
+    snapshot.metadata.capture_method = CaptureMethod::Manual;
+    snapshot.metadata.language_servers.sort();
+    Ok(snapshot)
+}
+
 fn create_export_config(args: &ExportArgs) -> Result<ExportConfig> {
     Ok(ExportConfig {
-        format: match args.format {
-            OutputFormat::Json => ExportFormat::Json,
-            OutputFormat::Markdown => ExportFormat::Markdown,
-            OutputFormat::Claude => ExportFormat::ClaudeOptimized,
-        },
+        format: args
+            .formats
+            .first()
+            .copied()
+            .unwrap_or(OutputFormat::Json)
+            .into(),
         include_context: args.include_context,
         context_lines: args.context_lines,
         include_summary: true,
         group_by_file: false,
         sort_by: SortBy::Severity,
+        stable: args.stable,
+        region: export_region(args)?,
     })
 }
 
-pub fn get_privacy_policy(level: &PrivacyLevel) -> PrivacyPolicy {
-    match level {
-        PrivacyLevel::Strict => PrivacyPolicy::strict(),
-        PrivacyLevel::Minimal => PrivacyPolicy::permissive(),
-        PrivacyLevel::Balanced => PrivacyPolicy::default(),
+/// The region named by `--file` and `--lines`
+fn export_region(args: &ExportArgs) -> Result<Option<DiagnosticRegion>> {
+    let Some(file) = &args.file else {
+        return Ok(None);
+    };
+    // Absolute paths match both absolute and workspace-relative diagnostic paths
+    let file = match std::env::current_dir() {
+        Ok(cwd) if file.is_relative() => cwd.join(file),
+        _ => file.clone(),
+    };
+    let file = file.to_string_lossy().into_owned();
+    match &args.lines {
+        Some(lines) => DiagnosticRegion::lines(file, lines).map(Some),
+        None => Ok(Some(DiagnosticRegion::file(file))),
     }
 }
 
+/// Update the persisted noise model and mute/downrank noisy diagnostics
+fn apply_noise_model(
+    captured: &[Diagnostic],
+    snapshot: &mut DiagnosticSnapshot,
+    config: NoiseConfig,
+) -> Result<NoiseReport> {
+    let path = NoiseModel::default_path();
+    let mut model = NoiseModel::load(&path, config)?;
+    // Observing only the filtered diagnostics would read filtered-out patterns as fixed
+    model.observe(captured, chrono::Utc::now());
+    model.save(&path)?;
+
+    let outcome = model.apply(std::mem::take(&mut snapshot.diagnostics));
+    snapshot.diagnostics = outcome.diagnostics;
+    Ok(outcome.report)
+}
+
+/// Run the triage engine with CODEOWNERS and history when available
+async fn build_triage(
+    project_root: Option<&std::path::Path>,
+    snapshot: &DiagnosticSnapshot,
+) -> Vec<TriageSuggestion> {
+    let mut engine = TriageEngine::new();
+    if let Some(root) = project_root {
+        engine = engine.with_project_root(root);
+    }
+    // Triage reads the database directly, so it only uses history while holding the store lock
+    let history = HistoryService::connect(HistoryConfig::default(), "export").await;
+    match history.as_ref().map(|history| (history.storage(), history.daemon())) {
+        Ok((Some(storage), _)) => engine = engine.with_history(storage),
+        Ok((None, Some(daemon))) => tracing::debug!("History is managed by {}; triaging without it", daemon.owner()),
+        Ok((None, None)) => {}
+        Err(e) => tracing::debug!("History unavailable for triage: {}", e),
+    }
+    engine.triage(&snapshot.diagnostics).await
+}
+
+pub fn get_privacy_policy(level: &PrivacyLevel) -> PrivacyPolicy {
+    PrivacyPolicy::for_level(level)
+}
+
 fn apply_filtering(
     snapshot: DiagnosticSnapshot,
     filter: &DiagnosticFilter,
 ) -> Result<DiagnosticSnapshot> {
-    if filter == &DiagnosticFilter::default() {
+    if filter.is_empty() {
         return Ok(snapshot);
     }
 
-    let mut filtered_diagnostics = snapshot.diagnostics.clone();
-
-    if let Some(severities) = &filter.severities {
-        let severity_set: std::collections::HashSet<_> = severities.iter().collect();
-        filtered_diagnostics.retain(|d| severity_set.contains(&d.severity));
-    }
-
-    if let Some(max_results) = filter.max_results {
-        filtered_diagnostics.truncate(max_results);
-    }
-
     Ok(DiagnosticSnapshot {
-        diagnostics: filtered_diagnostics,
+        diagnostics: filter.apply(snapshot.diagnostics, snapshot.timestamp),
         ..snapshot
     })
 }
 
+/// Keep only diagnostics in files of the repositories in a saved fleet
+async fn restrict_to_fleet(
+    mut snapshot: DiagnosticSnapshot,
+    fleet: &str,
+    cwd: Option<&Path>,
+    config: &UnifiedConfig,
+) -> Result<DiagnosticSnapshot> {
+    let registry = RepositoryRegistry::connect(&config.multi_repo).await?;
+    let members = registry.fleet_members(fleet).await?;
+
+    snapshot.diagnostics.retain(|diagnostic| {
+        let file = Path::new(&diagnostic.file);
+        let file = match cwd {
+            Some(cwd) if file.is_relative() => cwd.join(file),
+            _ => file.to_path_buf(),
+        };
+        members.iter().any(|repo| file.starts_with(&repo.path))
+    });
+    eprintln!(
+        "Fleet '{}': {} diagnostic(s) from {} repositories",
+        fleet,
+        snapshot.diagnostics.len(),
+        members.len()
+    );
+    Ok(snapshot)
+}
+
 pub async fn find_ide_diagnostics() -> Result<RawDiagnostics> {
     // This is a placeholder - in a real implementation, this would:
     // 1. Look for VS Code diagnostics via extension API
@@ -0,0 +1,91 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::path::Path;
+
+use crate::cli::commands::Command;
+use crate::core::config::UnifiedConfig;
+use crate::core::{
+    DiagnosticGrouper, GraphAction, GraphKind, RawDiagnostics, RelationGraph, SymbolIndex,
+};
+use crate::format::{parse_json_stream, FormatConverter};
+use crate::multi_repo::registry::RepositoryRegistry;
+
+use super::export::{find_ide_diagnostics, read_stdin};
+
+pub struct GraphCommand {
+    action: GraphAction,
+}
+
+impl GraphCommand {
+    pub fn new(action: GraphAction) -> Self {
+        Self { action }
+    }
+}
+
+#[async_trait]
+impl Command for GraphCommand {
+    async fn execute(&self) -> Result<()> {
+        match &self.action {
+            GraphAction::Export {
+                kind,
+                format,
+                output,
+                root,
+                focus,
+                depth,
+                include_tests,
+            } => {
+                let mut graph = match kind {
+                    GraphKind::RepoDeps => repository_graph().await?,
+                    GraphKind::Calls => RelationGraph::call_graph(&SymbolIndex::build(root)?, *include_tests),
+                    GraphKind::Causes => cause_graph().await?,
+                };
+                if let Some(focus) = focus {
+                    graph = graph.focus(focus, *depth);
+                    if graph.nodes.is_empty() {
+                        return Err(anyhow!("Nothing named '{focus}' in the {} graph", graph.name));
+                    }
+                }
+
+                let rendered = graph.render(*format);
+                match output {
+                    Some(path) => {
+                        tokio::fs::write(path, rendered).await?;
+                        eprintln!(
+                            "Wrote {} nodes and {} edges to {}",
+                            graph.nodes.len(),
+                            graph.edges.len(),
+                            path.display()
+                        );
+                    }
+                    None => print!("{rendered}"),
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+async fn repository_graph() -> Result<RelationGraph> {
+    let config = UnifiedConfig::load_or_default(Path::new("lspbridge.toml")).await?;
+    let registry = RepositoryRegistry::connect(&config.multi_repo).await?;
+    registry.dependency_graph().await
+}
+
+async fn cause_graph() -> Result<RelationGraph> {
+    let raw = match find_ide_diagnostics().await {
+        Ok(diags) => diags,
+        Err(_) if atty::isnt(atty::Stream::Stdin) => RawDiagnostics {
+            source: "stdin".to_string(),
+            data: parse_json_stream(&read_stdin().await?)?,
+            timestamp: chrono::Utc::now(),
+            workspace: None,
+        },
+        Err(_) => return Err(anyhow!("No diagnostics available")),
+    };
+
+    use crate::core::FormatConverter as FormatConverterTrait;
+    let diagnostics = FormatConverter::new().normalize(raw).await?;
+    let groups = DiagnosticGrouper::new().group_diagnostics(diagnostics);
+    Ok(RelationGraph::cause_chains(&groups))
+}
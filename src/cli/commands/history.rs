@@ -1,10 +1,18 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 use crate::cli::args::OutputFormat;
 use crate::cli::commands::Command;
-use crate::history::{HistoryAction, HistoryConfig, HistoryManager};
+use crate::cli::args::{parse_as_of, parse_time};
+use crate::core::config::UnifiedConfig;
+use crate::core::{restore_database, OwnershipMap, BackupConfig, BackupGeneration, LockRole, StoreLock};
+use crate::history::{
+    AnnotationMode, BackupDatabase, CleanPreview, HistoryAction, HistoryAnnotation, HistoryConfig, HistoryService,
+    HotSpotFormat, OwnerHotSpotReport, TrendOptions,
+};
+use crate::history::owners::{csv_field, UNOWNED};
 use crate::security::validate_path;
 
 pub struct HistoryCommand {
@@ -20,20 +28,38 @@ impl HistoryCommand {
 #[async_trait]
 impl Command for HistoryCommand {
     async fn execute(&self) -> Result<()> {
-        // Initialize history manager with default config
+        // Backups work on the database files, not through the history service
+        match &self.action {
+            HistoryAction::Backup { database, list, format } => return backup(*database, *list, format).await,
+            HistoryAction::Restore { to, database, dry_run } => return restore(*database, to, *dry_run).await,
+            _ => {}
+        }
+
+        // Goes through the daemon when one owns the history database
         let config = HistoryConfig::default();
-        let manager = HistoryManager::new(config).await?;
+        let manager = HistoryService::connect(config, "history").await?;
 
         match &self.action {
-            HistoryAction::Trends { hours, format } => {
+            HistoryAction::Trends {
+                hours,
+                annotations,
+                filter,
+                format,
+            } => {
                 let window = Duration::from_secs(hours * 3600);
-                let trends = manager.get_trends(window).await?;
+                let options = TrendOptions::default()
+                    .with_annotations(*annotations)
+                    .with_filter(filter.to_filter(None)?);
+                let trends = manager.get_trends(window, &options).await?;
 
                 match format {
                     OutputFormat::Json => {
                         let json = serde_json::to_string_pretty(&trends)?;
                         println!("{json}");
                     }
+                    OutputFormat::Sarif | OutputFormat::Html | OutputFormat::GithubActions => {
+                        return Err(format.unsupported_by("history"));
+                    }
                     OutputFormat::Markdown | OutputFormat::Claude => {
                         println!("# Diagnostic Trends (Last {hours} hours)\n");
                         println!("**Health Score**: {:.1}%", trends.health_score * 100.0);
@@ -47,6 +73,13 @@ impl Command for HistoryCommand {
                             trends.warning_velocity
                         );
 
+                        if !trends.annotations.is_empty() {
+                            print_annotated_windows(&trends.annotations, *annotations);
+                            if trends.excluded_buckets > 0 {
+                                println!("{} hourly buckets excluded\n", trends.excluded_buckets);
+                            }
+                        }
+
                         if !trends.hot_spots.is_empty() {
                             println!("## Hot Spots");
                             for (i, file) in trends.hot_spots.iter().take(5).enumerate() {
@@ -69,20 +102,74 @@ impl Command for HistoryCommand {
                                     pattern.description, pattern.occurrence_rate
                                 );
                             }
+                            println!();
+                        }
+
+                        if !trends.taxonomy_breakdown.is_empty() {
+                            println!("## Issues by Taxonomy");
+                            for trend in &trends.taxonomy_breakdown {
+                                let sources: Vec<String> = trend
+                                    .by_source
+                                    .iter()
+                                    .map(|(source, count)| format!("{source}: {count}"))
+                                    .collect();
+                                println!(
+                                    "- {} - {} occurrences ({})",
+                                    trend.taxonomy,
+                                    trend.occurrences,
+                                    sources.join(", ")
+                                );
+                            }
                         }
                     }
                 }
             }
 
-            HistoryAction::HotSpots { limit, format } => {
+            HistoryAction::HotSpots {
+                limit,
+                by_owner: true,
+                days,
+                format,
+            } => {
+                let end = SystemTime::now();
+                let start = end - Duration::from_secs(days * 86_400);
+                let changes = manager.get_file_changes(start, end).await?;
+
+                let ownership = OwnershipMap::discover(&std::env::current_dir()?)?;
+                if ownership.is_empty() {
+                    eprintln!("No CODEOWNERS file found; every file is reported as {UNOWNED}");
+                }
+                let report = OwnerHotSpotReport::build(start, end, &changes, &ownership, *limit);
+
+                match format {
+                    HotSpotFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+                    HotSpotFormat::Csv => print!("{}", report.to_csv()),
+                    HotSpotFormat::Markdown => print!("{}", report.to_markdown()),
+                }
+            }
+
+            HistoryAction::HotSpots { limit, format, .. } => {
                 let hot_spots = manager.get_hot_spots(*limit).await?;
 
                 match format {
-                    OutputFormat::Json => {
+                    HotSpotFormat::Json => {
                         let json = serde_json::to_string_pretty(&hot_spots)?;
                         println!("{json}");
                     }
-                    OutputFormat::Markdown | OutputFormat::Claude => {
+                    HotSpotFormat::Csv => {
+                        println!("file,score,recent_errors,recent_warnings,trend");
+                        for spot in &hot_spots {
+                            println!(
+                                "{},{:.1},{},{},{:?}",
+                                csv_field(&spot.file_path.to_string_lossy()),
+                                spot.score,
+                                spot.recent_errors,
+                                spot.recent_warnings,
+                                spot.trend
+                            );
+                        }
+                    }
+                    HotSpotFormat::Markdown => {
                         println!("# Diagnostic Hot Spots\n");
 
                         for (i, spot) in hot_spots.iter().enumerate() {
@@ -102,17 +189,27 @@ impl Command for HistoryCommand {
             HistoryAction::File {
                 path,
                 hours,
+                annotations,
+                filter,
                 format,
             } => {
                 let validated_path = validate_path(path)?;
                 let window = Duration::from_secs(hours * 3600);
-                let report = manager.get_file_trends(&validated_path, window).await?;
+                let options = TrendOptions::default()
+                    .with_annotations(*annotations)
+                    .with_filter(filter.to_filter(None)?);
+                let report = manager
+                    .get_file_trends(&validated_path, window, &options)
+                    .await?;
 
                 match format {
                     OutputFormat::Json => {
                         let json = serde_json::to_string_pretty(&report)?;
                         println!("{json}");
                     }
+                    OutputFormat::Sarif | OutputFormat::Html | OutputFormat::GithubActions => {
+                        return Err(format.unsupported_by("history"));
+                    }
                     OutputFormat::Markdown | OutputFormat::Claude => {
                         println!("# File History: {}\n", validated_path.display());
                         println!("**Time Period**: Last {hours} hours");
@@ -140,19 +237,301 @@ impl Command for HistoryCommand {
                             println!("**Error trend points**: {}", report.error_trend.len());
                             println!("**Warning trend points**: {}", report.warning_trend.len());
                         }
+
+                        if !report.annotations.is_empty() {
+                            println!();
+                            print_annotated_windows(&report.annotations, *annotations);
+                        }
+                    }
+                }
+            }
+
+            HistoryAction::Diff {
+                from,
+                to,
+                fail_on_added,
+                format,
+            } => {
+                let diff = manager.diff_snapshots(parse_as_of(from)?, parse_as_of(to)?).await?;
+
+                match format {
+                    OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&diff)?),
+                    OutputFormat::Sarif | OutputFormat::Html | OutputFormat::GithubActions => {
+                        return Err(format.unsupported_by("history"));
                     }
+                    OutputFormat::Markdown | OutputFormat::Claude => print!("{}", diff.to_markdown()),
+                }
+
+                if *fail_on_added && diff.added > 0 {
+                    return Err(anyhow!("{} diagnostics added between {from} and {to}", diff.added));
                 }
             }
 
-            HistoryAction::Clean { older_than_days } => {
+            HistoryAction::Annotate { range, label } => {
+                let (start, end) = parse_range(range)?;
+                let id = manager.annotate(start, end, label).await?;
+                println!("📌 Added annotation #{id}: {}", label.trim());
+            }
+
+            HistoryAction::Annotations { remove, format } => {
+                if let Some(id) = remove {
+                    if !manager.remove_annotation(*id).await? {
+                        return Err(anyhow!("No annotation with id {id}"));
+                    }
+                    println!("🗑️  Removed annotation #{id}");
+                    return Ok(());
+                }
+
+                let annotations = manager.get_annotations().await?;
+                match format {
+                    OutputFormat::Json => {
+                        println!("{}", serde_json::to_string_pretty(&annotations)?);
+                    }
+                    OutputFormat::Sarif | OutputFormat::Html | OutputFormat::GithubActions => {
+                        return Err(format.unsupported_by("history"));
+                    }
+                    OutputFormat::Markdown | OutputFormat::Claude => {
+                        println!("# Annotated Windows\n");
+                        if annotations.is_empty() {
+                            println!("No annotations.");
+                        }
+                        for annotation in &annotations {
+                            println!("- #{} {}", annotation.id, describe_annotation(annotation));
+                        }
+                    }
+                }
+            }
+
+            HistoryAction::Clean {
+                older_than_days,
+                preview,
+                backup,
+                backup_path,
+                format,
+            } => {
                 let cutoff_date = chrono::Utc::now() - chrono::Duration::days(*older_than_days as i64);
+
+                if *preview {
+                    let preview = manager.preview_clean(cutoff_date).await?;
+                    match format {
+                        OutputFormat::Json => {
+                            println!("{}", serde_json::to_string_pretty(&preview)?);
+                        }
+                        OutputFormat::Sarif | OutputFormat::Html | OutputFormat::GithubActions => {
+                            return Err(format.unsupported_by("history"));
+                        }
+                        OutputFormat::Markdown | OutputFormat::Claude => {
+                            print_clean_preview(&preview, *older_than_days);
+                        }
+                    }
+                    return Ok(());
+                }
+
+                if *backup {
+                    let path = backup_path
+                        .clone()
+                        .unwrap_or_else(crate::history::pruning::default_backup_path);
+                    let backed_up = manager.backup_before(cutoff_date, &path).await?;
+                    println!("💾 Backed up {backed_up} snapshots to {}", path.display());
+                }
+
                 let deleted_count = manager.clean_old_data(cutoff_date).await?;
                 println!(
                     "✅ Cleaned {deleted_count} old diagnostic entries (older than {older_than_days} days)"
                 );
             }
+            HistoryAction::Backup { .. } | HistoryAction::Restore { .. } => unreachable!("handled above"),
         }
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Backup settings and location of a database
+async fn backup_target(database: BackupDatabase) -> Result<(BackupConfig, PathBuf)> {
+    let config = UnifiedConfig::load_or_default(Path::new("lspbridge.toml")).await?;
+    let path = database
+        .path(&config)
+        .ok_or_else(|| anyhow!("No team database is configured; set multi_repo.team_db_path"))?;
+    Ok((config.backup, path))
+}
+
+async fn backup(database: BackupDatabase, list: bool, format: &OutputFormat) -> Result<()> {
+    let (config, db_path) = backup_target(database).await?;
+
+    if list {
+        let catalog = config.catalog(database.name())?;
+        let generations = catalog.generations()?;
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&generations)?),
+            OutputFormat::Sarif | OutputFormat::Html | OutputFormat::GithubActions => return Err(format.unsupported_by("history")),
+            OutputFormat::Markdown | OutputFormat::Claude => {
+                print_generations(database, catalog.dir(), &generations)
+            }
+        }
+        return Ok(());
+    }
+
+    if !db_path.exists() {
+        return Err(anyhow!("{} does not exist", db_path.display()));
+    }
+    let generation =
+        tokio::task::spawn_blocking(move || config.archiver(database.name(), &db_path)?.base_backup()).await??;
+    println!(
+        "💾 Backed up the {} database as generation {} ({} bytes)",
+        database.name(),
+        generation.id,
+        generation.size_bytes
+    );
+    Ok(())
+}
+
+async fn restore(database: BackupDatabase, to: &str, dry_run: bool) -> Result<()> {
+    let at = parse_time(to, true)?;
+    let (config, db_path) = backup_target(database).await?;
+    let catalog = config.catalog(database.name())?;
+
+    if dry_run {
+        let point = catalog.plan(at)?;
+        println!(
+            "Would restore the {} database from generation {} (base copy {}, {} segments) to its state at {}",
+            database.name(),
+            point.generation,
+            point.base_time.format("%Y-%m-%d %H:%M:%S UTC"),
+            point.segments_applied,
+            point.restored_to.format("%Y-%m-%d %H:%M:%S UTC")
+        );
+        return Ok(());
+    }
+
+    // Nothing else may have the database open while it is replaced
+    let data_dir = crate::config::data_dir()?;
+    let Some(_lock) = StoreLock::try_acquire(&data_dir, LockRole::Cli, "history restore")? else {
+        return Err(match StoreLock::owner(&data_dir) {
+            Some(owner) => anyhow!("The lspbridge stores are in use by {owner}; stop it before restoring"),
+            None => anyhow!("The lspbridge stores are in use; stop other lspbridge processes before restoring"),
+        });
+    };
+
+    let report = tokio::task::spawn_blocking(move || restore_database(&catalog, &db_path, at)).await??;
+    println!(
+        "✅ Restored the {} database to its state at {} from generation {} ({} segments)",
+        database.name(),
+        report.point.restored_to.format("%Y-%m-%d %H:%M:%S UTC"),
+        report.point.generation,
+        report.point.segments_applied
+    );
+    if let Some(previous) = report.replaced {
+        println!("The previous database was kept at {}", previous.display());
+    }
+    Ok(())
+}
+
+fn print_generations(database: BackupDatabase, dir: &Path, generations: &[BackupGeneration]) {
+    if generations.is_empty() {
+        println!("No backups of the {} database in {}", database.name(), dir.display());
+        return;
+    }
+    println!("## Backups of the {} database\n", database.name());
+    for generation in generations {
+        println!(
+            "- {}: {} to {} ({} segments, {} bytes)",
+            generation.id,
+            generation.started.format("%Y-%m-%d %H:%M:%S UTC"),
+            generation.latest.format("%Y-%m-%d %H:%M:%S UTC"),
+            generation.segments,
+            generation.size_bytes
+        );
+    }
+}
+fn print_annotated_windows(annotations: &[HistoryAnnotation], mode: AnnotationMode) {
+    println!("## Annotated Windows");
+    let treatment = match mode {
+        AnnotationMode::Mark => "included",
+        AnnotationMode::Exclude => "excluded",
+    };
+    for annotation in annotations {
+        println!("- {} ({treatment})", describe_annotation(annotation));
+    }
+    println!();
+}
+
+fn describe_annotation(annotation: &HistoryAnnotation) -> String {
+    let start: chrono::DateTime<chrono::Utc> = annotation.start.into();
+    let end: chrono::DateTime<chrono::Utc> = annotation.end.into();
+    format!(
+        "{} → {}: {}",
+        start.format("%Y-%m-%d %H:%M"),
+        end.format("%Y-%m-%d %H:%M"),
+        annotation.label
+    )
+}
+
+/// Parse `--range START..END`; a bare date covers the whole day and an
+/// empty END means now
+fn parse_range(value: &str) -> Result<(SystemTime, SystemTime)> {
+    let (start, end) = value
+        .split_once("..")
+        .ok_or_else(|| anyhow!("Invalid --range '{value}': expected START..END"))?;
+    let start = parse_time(start, false)?.into();
+    let end = if end.trim().is_empty() {
+        SystemTime::now()
+    } else {
+        parse_time(end, true)?.into()
+    };
+    Ok((start, end))
+}
+
+fn print_clean_preview(preview: &CleanPreview, older_than_days: u32) {
+    println!("# History Clean Preview (older than {older_than_days} days)\n");
+    if preview.is_empty() {
+        println!("Nothing would be removed.");
+        return;
+    }
+
+    println!("**Snapshots removed**: {}", preview.snapshots.len());
+    println!("**Files losing all history**: {}", preview.files_removed.len());
+    println!("**Files losing older history**: {}", preview.files_truncated.len());
+    println!("**Error patterns removed**: {}", preview.patterns.len());
+    println!("**Daily time-series buckets removed**: {}", preview.time_series_buckets.len());
+
+    let impact = &preview.impact;
+    println!("\n## Trend Impact");
+    println!(
+        "**History span**: {:.1} days → {:.1} days ({:.0}% of snapshots removed)",
+        impact.span_days_before,
+        impact.span_days_after,
+        impact.removed_fraction * 100.0
+    );
+    println!("\n| Window | Coverage before | Coverage after | Gap |");
+    println!("|--------|-----------------|----------------|-----|");
+    for window in &impact.windows {
+        println!(
+            "| {}d | {:.0}% | {:.0}% | {} |",
+            window.window_days,
+            window.coverage_before * 100.0,
+            window.coverage_after * 100.0,
+            if window.has_gap() { "yes" } else { "no" }
+        );
+    }
+
+    if !preview.files_removed.is_empty() {
+        println!("\n## Files Losing All History");
+        for file in &preview.files_removed {
+            println!("- {}", file.display());
+        }
+    }
+
+    println!("\n## Snapshots");
+    for snapshot in &preview.snapshots {
+        let timestamp: chrono::DateTime<chrono::Utc> = snapshot.timestamp.into();
+        println!(
+            "- #{} {} {} ({} errors, {} warnings)",
+            snapshot.id,
+            timestamp.format("%Y-%m-%d %H:%M"),
+            snapshot.file_path.display(),
+            snapshot.error_count,
+            snapshot.warning_count
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::cli::commands::Command;
+use crate::cli::hooks::{hook_script, install_hook, uninstall_hook, HookAction, HookCounts, HookKind, HookThresholds};
+use crate::core::{DiagnosticSeverity, ErrorRecoverySystem, GitIntegration, Subsystem};
+use crate::history::{AsOf, FileDiff, HistoryConfig, HistoryService};
+use crate::quick_fix::FixSuggestionService;
+
+use super::quick_fix::calibrated_scorer;
+
+pub struct HookCommand {
+    action: HookAction,
+}
+
+impl HookCommand {
+    pub fn new(action: HookAction) -> Self {
+        Self { action }
+    }
+}
+
+#[async_trait]
+impl Command for HookCommand {
+    async fn execute(&self) -> Result<()> {
+        match &self.action {
+            HookAction::Install { kind, thresholds, force } => {
+                let hooks_dir = repository(&ErrorRecoverySystem::persistent()).await?.get_hooks_dir().await?;
+                let program = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("lspbridge"));
+                let script = hook_script(&program, *kind, thresholds);
+                let path = install_hook(&hooks_dir, *kind, &script, *force)?;
+                println!("✅ Installed {kind} hook at {}", path.display());
+                Ok(())
+            }
+            HookAction::Uninstall { kind } => {
+                let hooks_dir = repository(&ErrorRecoverySystem::persistent()).await?.get_hooks_dir().await?;
+                if uninstall_hook(&hooks_dir, *kind)? {
+                    println!("✅ Removed the {kind} hook");
+                } else {
+                    println!("No {kind} hook installed");
+                }
+                Ok(())
+            }
+            HookAction::Run { kind, thresholds } => run(*kind, thresholds).await,
+        }
+    }
+}
+
+async fn repository(recovery: &ErrorRecoverySystem) -> Result<GitIntegration> {
+    let git = recovery.execute_in(Subsystem::Git, GitIntegration::new).await?;
+    if !git.is_git_available().await {
+        return Err(anyhow!("Not in a git repository"));
+    }
+    Ok(git)
+}
+
+/// Check the files being committed or pushed and fail when a threshold is exceeded
+///
+/// Problems gathering diagnostics, such as nothing captured yet, skip the
+/// check rather than block the commit.
+async fn run(kind: HookKind, thresholds: &HookThresholds) -> Result<()> {
+    let files = match changed_files(kind).await {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("lspbridge {kind}: skipping checks: {e}");
+            return Ok(());
+        }
+    };
+    if files.is_empty() {
+        return Ok(());
+    }
+
+    let counts = HookCounts::from_diffs(&files);
+    print_new_diagnostics(&files);
+    print_fix_preview(&files).await;
+
+    let violations = thresholds.violations(&counts);
+    if violations.is_empty() {
+        println!(
+            "lspbridge {kind}: {} file(s) checked, {} new errors, {} new warnings",
+            files.len(),
+            counts.new_errors,
+            counts.new_warnings
+        );
+        return Ok(());
+    }
+    Err(anyhow!("lspbridge {kind} blocked: {}", violations.join(", ")))
+}
+
+/// Captured diagnostics of the files the hook covers, diffed against the base commit
+async fn changed_files(kind: HookKind) -> Result<Vec<FileDiff>> {
+    let recovery = ErrorRecoverySystem::persistent();
+    let git = repository(&recovery).await?;
+    let (files, base) = match kind {
+        HookKind::PreCommit => (
+            recovery.execute_in(Subsystem::Git, || git.get_staged_files()).await?,
+            "HEAD".to_string(),
+        ),
+        HookKind::PrePush => {
+            let upstream = git
+                .get_upstream()
+                .await
+                .ok_or_else(|| anyhow!("no upstream branch to compare against"))?;
+            let files = recovery
+                .execute_in(Subsystem::Git, || git.get_files_changed_from(&upstream))
+                .await?;
+            (files, upstream)
+        }
+    };
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+    // Before the first commit nothing is recorded against a base
+    let base_time = git.get_commit_time(&base).await.unwrap_or(SystemTime::UNIX_EPOCH);
+
+    let history = HistoryService::connect(HistoryConfig::default(), "hook").await?;
+    let diff = history
+        .diff_snapshots(AsOf::Time(base_time), AsOf::Time(SystemTime::now()))
+        .await?;
+
+    let cwd = std::env::current_dir()?;
+    let files: HashSet<PathBuf> = files.into_iter().collect();
+    Ok(diff
+        .files
+        .into_iter()
+        .filter(|file| files.contains(&cwd.join(&file.file_path)))
+        .collect())
+}
+
+fn print_new_diagnostics(files: &[FileDiff]) {
+    for file in files.iter().filter(|file| !file.added.is_empty()) {
+        println!("{}", file.file_path.display());
+        for diagnostic in &file.added {
+            println!(
+                "  + {:?} line {}: {}",
+                diagnostic.severity,
+                diagnostic.range.start.line + 1,
+                diagnostic.message
+            );
+        }
+    }
+}
+
+/// Dry run of the quick fixes available for the checked files' errors
+async fn print_fix_preview(files: &[FileDiff]) {
+    let suggestions = FixSuggestionService::new().with_scorer(calibrated_scorer().await);
+    let fixes: Vec<_> = files
+        .iter()
+        .flat_map(|file| file.added.iter().chain(&file.persisting))
+        .filter(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+        .filter_map(|diagnostic| suggestions.fixes(diagnostic).into_iter().find(|fix| fix.edit.is_some()))
+        .collect();
+    if fixes.is_empty() {
+        return;
+    }
+    for fix in &fixes {
+        println!(
+            "Would fix: {} (confidence: {:.2})",
+            fix.diagnostic_message, fix.confidence
+        );
+    }
+    println!("{} fix(es) available; apply them with `lspbridge quick-fix apply`", fixes.len());
+}
@@ -0,0 +1,103 @@
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use std::path::Path;
+use tokio::fs;
+
+use crate::capture::importers::{
+    clippy, eslint, ClippyImporter, ColumnMapping, DelimitedImporter, EslintImporter, ImportFormat, IMPORT_SOURCE,
+};
+use crate::cli::args::ImportArgs;
+use crate::cli::commands::Command;
+use crate::core::config::UnifiedConfig;
+use crate::core::SeverityRules;
+use crate::history::{HistoryConfig, HistoryService};
+use crate::security::validate_path;
+
+use super::export::read_stdin;
+
+pub struct ImportCommand {
+    args: ImportArgs,
+}
+
+impl ImportCommand {
+    pub fn new(args: ImportArgs) -> Self {
+        Self { args }
+    }
+}
+
+#[async_trait]
+impl Command for ImportCommand {
+    async fn execute(&self) -> Result<()> {
+        let input = match &self.args.input {
+            Some(path) => {
+                let validated_path = validate_path(path)?;
+                fs::read_to_string(&validated_path)
+                    .await
+                    .with_context(|| format!("Failed to read {}", validated_path.display()))?
+            }
+            None => read_stdin().await?,
+        };
+
+        // Tools report paths relative to where they ran
+        let base_dir = std::env::current_dir()?;
+        let (mut report, source) = match self.args.format {
+            format @ (ImportFormat::Csv | ImportFormat::Tsv) => {
+                let mut mapping = match &self.args.mapping {
+                    Some(path) => ColumnMapping::load(path)?,
+                    None => ColumnMapping::default(),
+                };
+                if let Some(source) = &self.args.source {
+                    mapping.source = Some(source.clone());
+                }
+                let source = mapping.source.clone().unwrap_or_else(|| IMPORT_SOURCE.to_string());
+                let report = DelimitedImporter::new(format, mapping)
+                    .with_base_dir(base_dir)
+                    .import(&input)?;
+                (report, source)
+            }
+            ImportFormat::Eslint | ImportFormat::Clippy if self.args.mapping.is_some() || self.args.source.is_some() => {
+                bail!("--mapping and --source only apply to csv and tsv imports")
+            }
+            ImportFormat::Eslint => (
+                EslintImporter::new().with_base_dir(base_dir).import(&input)?,
+                eslint::ESLINT_SOURCE.to_string(),
+            ),
+            ImportFormat::Clippy => (
+                ClippyImporter::new().with_base_dir(base_dir).import(&input)?,
+                clippy::CLIPPY_SOURCE.to_string(),
+            ),
+        };
+
+        let config = UnifiedConfig::load_or_default(Path::new("lspbridge.toml")).await?;
+        SeverityRules::from_config(&config.severity_rules)
+            .context("Invalid [severity_rules] in lspbridge.toml")?
+            .apply(&mut report.diagnostics);
+
+        eprintln!(
+            "Imported {} diagnostic(s), skipped {} row(s)",
+            report.diagnostics.len(),
+            report.skipped.len()
+        );
+        for row in &report.skipped {
+            eprintln!("  line {}: {}", row.line, row.reason);
+        }
+
+        if self.args.record {
+            let history = HistoryService::connect(HistoryConfig::default(), "import").await?;
+            let files = history.record_import(&report).await?;
+            eprintln!("Recorded diagnostics for {files} file(s) in history");
+        }
+
+        let json = serde_json::to_string_pretty(&report.to_lsp_json(&source))?;
+        match &self.args.output {
+            Some(output) => {
+                let validated_path = validate_path(output)?;
+                fs::write(&validated_path, json).await?;
+                eprintln!("Diagnostics written to {}", validated_path.display());
+            }
+            None => println!("{json}"),
+        }
+
+        Ok(())
+    }
+}
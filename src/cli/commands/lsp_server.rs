@@ -0,0 +1,95 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tokio::sync::mpsc;
+
+use crate::capture::DiagnosticsServer;
+use crate::cli::args::LspServerArgs;
+use crate::cli::commands::Command;
+use crate::core::config::UnifiedConfig;
+use crate::core::FormatConverter as _;
+use crate::core::{Diagnostic, RawDiagnostics, StaticScanner};
+use crate::format::{parse_json_stream, FormatConverter};
+use crate::privacy::PrivacyFilter;
+use crate::security::validate_path;
+
+use super::export::get_privacy_policy;
+
+pub struct LspServerCommand {
+    args: LspServerArgs,
+}
+
+impl LspServerCommand {
+    pub fn new(args: LspServerArgs) -> Self {
+        Self { args }
+    }
+}
+
+#[async_trait]
+impl Command for LspServerCommand {
+    /// Stdout carries LSP traffic here, so everything else goes to stderr
+    async fn execute(&self) -> Result<()> {
+        let root = match &self.args.root {
+            Some(root) => validate_path(root)?,
+            None => std::env::current_dir()?,
+        };
+        let filter = PrivacyFilter::new(get_privacy_policy(&self.args.privacy)).with_workspace(root.clone());
+        let mut server = DiagnosticsServer::new(&root, filter);
+        if !self.args.no_scan {
+            let config = UnifiedConfig::load_or_default(&root.join("lspbridge.toml")).await?;
+            match StaticScanner::new(&root, config.scan) {
+                Ok(scanner) => server = server.with_scanner(scanner),
+                Err(e) => eprintln!("Scan rules disabled: {e}"),
+            }
+        }
+
+        let (updates, receiver) = mpsc::channel(4);
+        let _follow = match &self.args.input {
+            Some(input) => {
+                let input = validate_path(input)?;
+                let interval = Duration::from_secs(self.args.interval.max(1));
+                Some(tokio::spawn(follow_input(input, interval, updates)))
+            }
+            None => None,
+        };
+
+        server.run(tokio::io::stdin(), tokio::io::stdout(), receiver).await
+    }
+}
+
+/// Send the diagnostics of `input` now and again after every change
+async fn follow_input(input: PathBuf, interval: Duration, updates: mpsc::Sender<Vec<Diagnostic>>) {
+    let mut last_modified: Option<SystemTime> = None;
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        let Ok(modified) = std::fs::metadata(&input).and_then(|m| m.modified()) else {
+            continue;
+        };
+        if last_modified == Some(modified) {
+            continue;
+        }
+        last_modified = Some(modified);
+
+        match read_diagnostics(&input).await {
+            Ok(diagnostics) => {
+                if updates.send(diagnostics).await.is_err() {
+                    return;
+                }
+            }
+            Err(e) => eprintln!("Failed to read {}: {e:#}", input.display()),
+        }
+    }
+}
+
+async fn read_diagnostics(input: &Path) -> Result<Vec<Diagnostic>> {
+    let content = tokio::fs::read_to_string(input).await?;
+    let raw = RawDiagnostics {
+        source: "lsp-server".to_string(),
+        data: parse_json_stream(&content).context("Invalid diagnostics JSON")?,
+        timestamp: chrono::Utc::now(),
+        workspace: None,
+    };
+    Ok(FormatConverter::new().normalize(raw).await?)
+}
@@ -0,0 +1,81 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::capture::lsp_trace::record_session;
+use crate::capture::{CaptureService, LspTrace, LspTraceAction, MemoryCache, TraceRecorder};
+use crate::cli::commands::Command;
+use crate::core::config::UnifiedConfig;
+use crate::core::{DiagnosticsCaptureService, LanguageServerProfiles, PrivacyPolicy, WorkspaceTrust};
+use crate::format::format_converter::FormatConverter;
+use crate::privacy::privacy_filter::PrivacyFilter;
+use crate::security::validate_path;
+
+pub struct LspTraceCommand {
+    action: LspTraceAction,
+}
+
+impl LspTraceCommand {
+    pub fn new(action: LspTraceAction) -> Self {
+        Self { action }
+    }
+
+    /// Stdout carries LSP traffic here, so everything else goes to stderr
+    async fn record(&self, server: &str, output: &Path, root: Option<&Path>) -> Result<()> {
+        let root = match root {
+            Some(root) => validate_path(root)?,
+            None => std::env::current_dir()?,
+        };
+        let trust = WorkspaceTrust::load()?.level(&root);
+        let config = UnifiedConfig::load_or_default(Path::new("lspbridge.toml")).await?;
+        let launch = LanguageServerProfiles::load(&root)?
+            .with_trust(trust)
+            .with_managed_servers(config.servers.installer()?)
+            .launch(server);
+        let recorder = Arc::new(TraceRecorder::create(&validate_path(output)?, server, Some(&root))?);
+
+        record_session(&launch, recorder, tokio::io::stdin(), tokio::io::stdout()).await?;
+        eprintln!("LSP trace written to {}", output.display());
+        Ok(())
+    }
+
+    async fn replay(&self, trace: &Path, output: Option<&Path>) -> Result<()> {
+        let trace = LspTrace::load(&validate_path(trace)?)?;
+
+        // The trace is already local, so replay keeps diagnostics as recorded
+        let mut capture = CaptureService::new(
+            MemoryCache::new(100, 3600),
+            PrivacyFilter::new(PrivacyPolicy::permissive()),
+            FormatConverter::new(),
+        );
+        capture.start_capture().await?;
+        let summary = trace.replay(&mut capture).await?;
+        eprintln!(
+            "Replayed {} diagnostics notification(s) from {} message(s) of {}; {} document(s) with diagnostics",
+            summary.notifications, summary.messages, trace.server, summary.documents
+        );
+
+        let diagnostics = capture
+            .get_current_snapshot()
+            .await?
+            .map(|snapshot| snapshot.diagnostics)
+            .unwrap_or_default();
+        let json = serde_json::to_string_pretty(&diagnostics)?;
+        match output {
+            Some(path) => std::fs::write(validate_path(path)?, json)?,
+            None => println!("{json}"),
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Command for LspTraceCommand {
+    async fn execute(&self) -> Result<()> {
+        match &self.action {
+            LspTraceAction::Record { server, output, root } => self.record(server, output, root.as_deref()).await,
+            LspTraceAction::Replay { trace, output } => self.replay(trace, output.as_deref()).await,
+        }
+    }
+}
@@ -5,9 +5,25 @@ pub mod export;
 pub mod watch;
 pub mod query;
 pub mod history;
+pub mod lsp_trace;
+pub mod lsp_server;
+pub mod report;
 pub mod ai_training;
 pub mod quick_fix;
 pub mod config;
+pub mod breakers;
+pub mod api;
+pub mod scan;
+pub mod import;
+pub mod stats;
+pub mod whatif;
+pub mod trust;
+pub mod graph;
+pub mod serve;
+pub mod servers;
+pub mod debt;
+pub mod hook;
+pub mod baseline;
 
 /// Trait for CLI command implementations
 #[async_trait]
@@ -15,38 +31,3 @@ pub trait Command {
     /// Execute the command with the given arguments
     async fn execute(&self) -> Result<()>;
 }
-
-/// Common utilities for command implementations
-pub mod utils {
-    use crate::core::types::{DiagnosticFilter, DiagnosticSeverity};
-    use anyhow::Result;
-
-    /// Create a diagnostic filter from command line options
-    pub fn create_diagnostic_filter(
-        errors_only: bool,
-        warnings_and_errors: bool,
-        files: Option<String>,
-        exclude: Option<String>,
-        max_results: Option<usize>,
-    ) -> Result<DiagnosticFilter> {
-        let severities = if errors_only {
-            Some(vec![DiagnosticSeverity::Error])
-        } else if warnings_and_errors {
-            Some(vec![DiagnosticSeverity::Error, DiagnosticSeverity::Warning])
-        } else {
-            None
-        };
-
-        let file_patterns = files.map(|f| f.split(',').map(String::from).collect());
-        let exclude_patterns = exclude.map(|e| e.split(',').map(String::from).collect());
-
-        Ok(DiagnosticFilter {
-            severities,
-            sources: None,
-            file_patterns,
-            exclude_patterns,
-            max_results,
-            since: None,
-        })
-    }
-}
\ No newline at end of file
@@ -1,12 +1,24 @@
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
 
+use crate::capture::{collect_code_lenses, LspTrace};
 use crate::cli::args::{QueryArgs, QueryOutputFormat};
 use crate::cli::commands::Command;
-use crate::core::{DiagnosticResult, DiagnosticSeverity, RawDiagnostics};
-use crate::format::FormatConverter;
-use crate::query::{InteractiveRepl, QueryApi, QueryResult};
+use crate::core::config::{EnvironmentSnapshot, UnifiedConfig};
+use crate::core::{
+    dedupe, Baseline, CalendarConfig, DiagnosticResult, RawDiagnostics, SymbolIndex, SymbolStore, BASELINE_FILE,
+};
+use crate::format::{parse_json_stream, FormatConverter};
+use crate::history::{HistoryConfig, HistoryService};
+use crate::multi_repo::monorepo::BazelTargetMap;
+use crate::query::executor::arrow;
+use crate::query::parser::FromClause;
+use crate::query::warm::query_daemon;
+use crate::query::{InteractiveRepl, QueryApi, QueryParser, QueryResult, ResultBrowser};
+use crate::security::validate_path;
 
 use super::export::{find_ide_diagnostics, read_stdin};
 
@@ -18,11 +30,63 @@ impl QueryCommand {
     pub fn new(args: QueryArgs) -> Self {
         Self { args }
     }
+
+    /// The query to hand to a running daemon, when it can answer it
+    ///
+    /// Interactive sessions, piped diagnostics, LSP traces and deduplication
+    /// need this process's own data, so they always run locally.
+    fn daemon_query(&self) -> Option<&str> {
+        let local_only = self.args.tui
+            || self.args.interactive
+            || self.args.lenses.is_some()
+            || self.args.dedupe
+            || atty::isnt(atty::Stream::Stdin);
+        if local_only {
+            None
+        } else {
+            self.args.query.as_deref()
+        }
+    }
+
+    fn write_result(&self, result: &QueryResult) -> Result<()> {
+        let formatted = match self.args.format {
+            QueryOutputFormat::Table => format_as_table(result).into_bytes(),
+            QueryOutputFormat::Json => serde_json::to_string_pretty(result)?.into_bytes(),
+            QueryOutputFormat::Csv => format_as_csv(result).into_bytes(),
+            QueryOutputFormat::Arrow => arrow::to_ipc_bytes(result)?,
+        };
+
+        if let Some(output_path) = &self.args.output {
+            std::fs::write(output_path, formatted)?;
+        } else if self.args.format == QueryOutputFormat::Arrow {
+            if atty::is(atty::Stream::Stdout) {
+                return Err(anyhow!(
+                    "Refusing to write binary Arrow output to a terminal; use --output or redirect stdout"
+                ));
+            }
+            std::io::stdout().write_all(&formatted)?;
+        } else {
+            println!("{}", String::from_utf8_lossy(&formatted));
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl Command for QueryCommand {
     async fn execute(&self) -> Result<()> {
+        let filter = self.args.filter.to_filter(None)?;
+
+        let cwd = std::env::current_dir()?;
+        let baseline = if self.args.no_baseline { None } else { Baseline::discover(&cwd)? };
+
+        // A running daemon has everything loaded already, but knows nothing of the baseline
+        if let (Some(query_str), Ok(data_dir), None) = (self.daemon_query(), crate::config::data_dir(), &baseline) {
+            if let Some(result) = query_daemon(&data_dir, query_str, &filter).await {
+                return self.write_result(&result);
+            }
+        }
+
         // Load current diagnostics
         let diagnostics = match find_ide_diagnostics().await {
             Ok(diags) => diags,
@@ -32,7 +96,7 @@ impl Command for QueryCommand {
                     let data = read_stdin().await?;
                     RawDiagnostics {
                         source: "stdin".to_string(),
-                        data: serde_json::from_str(&data)?,
+                        data: parse_json_stream(&data)?,
                         timestamp: chrono::Utc::now(),
                         workspace: None,
                     }
@@ -44,67 +108,124 @@ impl Command for QueryCommand {
 
         // Convert and process diagnostics
         use crate::core::FormatConverter as FormatConverterTrait;
+        let captured_at = diagnostics.timestamp;
         let converter = FormatConverter::new();
-        let normalized = converter.normalize(diagnostics).await?;
-
-        // Create DiagnosticResult
-        let mut processed = DiagnosticResult::new();
-        for diagnostic in normalized {
-            let file_path = PathBuf::from(&diagnostic.file);
-            processed
-                .diagnostics
-                .entry(file_path)
-                .or_default()
-                .push(diagnostic);
+        let mut normalized = converter.normalize(diagnostics).await?;
+        if let Some(baseline) = &baseline {
+            let outcome = baseline.apply(normalized, &cwd);
+            if outcome.suppressed > 0 {
+                eprintln!(
+                    "Left out {} diagnostic(s) accepted in {BASELINE_FILE}; pass --no-baseline to include them",
+                    outcome.suppressed
+                );
+            }
+            normalized = outcome.new;
+        }
+        let mut normalized = filter.apply(normalized, captured_at);
+        if self.args.dedupe {
+            normalized = dedupe(normalized).diagnostics;
         }
+        let mut processed = DiagnosticResult::from_diagnostics(normalized);
 
-        // Update summary
-        for diags in processed.diagnostics.values() {
-            for diag in diags {
-                processed.summary.total_diagnostics += 1;
-                match diag.severity {
-                    DiagnosticSeverity::Error => processed.summary.error_count += 1,
-                    DiagnosticSeverity::Warning => processed.summary.warning_count += 1,
-                    DiagnosticSeverity::Information => processed.summary.info_count += 1,
-                    DiagnosticSeverity::Hint => processed.summary.hint_count += 1,
-                }
-            }
+        if let Some(trace) = &self.args.lenses {
+            let trace = LspTrace::load(&validate_path(trace)?)?;
+            processed.code_lenses = collect_code_lenses(&trace);
         }
 
-        if self.args.interactive || self.args.query.is_none() {
+        let calendar = UnifiedConfig::load_or_default(Path::new("lspbridge.toml"))
+            .await?
+            .calendar;
+
+        if self.args.tui {
+            let query_str = self.args.query.as_deref().unwrap_or(DEFAULT_TUI_QUERY);
+            let api = query_api(processed.clone(), calendar, query_str).await?;
+            let result = api.execute(query_str).await?;
+            ResultBrowser::new(query_str, &result, processed).run()?;
+        } else if self.args.interactive || self.args.query.is_none() {
             // Start interactive REPL
-            let mut repl = InteractiveRepl::new().with_diagnostics(processed);
+            let mut repl = InteractiveRepl::new()
+                .with_diagnostics(processed)
+                .with_calendar(calendar);
 
-            // Try to add history if available
-            let history_config = crate::history::HistoryConfig::default();
-            if let Ok(storage) = crate::history::HistoryStorage::new(history_config).await {
-                repl = repl.with_history(storage);
+            // The REPL queries the database directly, so history needs the store lock for the session
+            let history = HistoryService::connect(HistoryConfig::default(), "query").await;
+            match history.as_ref().map(|history| (history.storage(), history.daemon())) {
+                Ok((Some(storage), _)) => repl = repl.with_shared_history(storage),
+                Ok((None, Some(daemon))) => {
+                    eprintln!("History is managed by {}; history queries are unavailable here", daemon.owner())
+                }
+                Ok((None, None)) => {}
+                Err(e) => tracing::debug!("History unavailable for the REPL: {}", e),
             }
 
+            repl = repl.with_environment(capture_environment().await?);
+
             repl.run().await?;
         } else if let Some(query_str) = &self.args.query {
             // Execute single query
-            let api = QueryApi::new();
-            api.with_diagnostics(processed).await?;
-
+            let api = query_api(processed, calendar, query_str).await?;
             let result = api.execute(query_str).await?;
+            self.write_result(&result)?;
+        }
 
-            // Format and output result
-            let formatted = match self.args.format {
-                QueryOutputFormat::Table => format_as_table(&result),
-                QueryOutputFormat::Json => serde_json::to_string_pretty(&result)?,
-                QueryOutputFormat::Csv => format_as_csv(&result),
-            };
+        Ok(())
+    }
+}
 
-            if let Some(output_path) = &self.args.output {
-                std::fs::write(output_path, formatted)?;
-            } else {
-                println!("{formatted}");
+/// Query used by `--tui` when none is given
+const DEFAULT_TUI_QUERY: &str = "SELECT * FROM diagnostics";
+
+/// Query API over the diagnostics, with whatever extra sources `query_str` needs
+async fn query_api(processed: DiagnosticResult, calendar: CalendarConfig, query_str: &str) -> Result<QueryApi> {
+    let api = QueryApi::new();
+    api.with_diagnostics(processed).await?;
+    api.with_calendar(calendar).await?;
+
+    // Expose the `target` field inside Bazel workspaces
+    if let Ok(cwd) = std::env::current_dir() {
+        if BazelTargetMap::is_workspace(&cwd) {
+            match BazelTargetMap::load(&cwd) {
+                Ok(targets) => api.with_bazel_targets(targets).await?,
+                Err(e) => tracing::warn!("Failed to load Bazel targets: {}", e),
             }
         }
+    }
 
-        Ok(())
+    // Probing tool versions and indexing the workspace are slow, so only do either when asked for
+    let parsed = QueryParser::new().parse(query_str).ok();
+    let reads = |source: FromClause| {
+        parsed.as_ref().is_some_and(|query| {
+            query.from == source
+                || query.join.as_ref().is_some_and(|join| join.source == source)
+                || query.subqueries().any(|subquery| subquery.from == source)
+        })
+    };
+    if reads(FromClause::Config) {
+        api.with_environment(capture_environment().await?).await?;
+    }
+    if reads(FromClause::Symbols) {
+        match sync_symbol_index().await {
+            Ok(index) => api.with_symbol_index(Arc::new(index)).await?,
+            Err(e) => tracing::warn!("Failed to index workspace symbols: {}", e),
+        }
     }
+    Ok(api)
+}
+
+/// The current workspace's symbol index, brought up to date
+async fn sync_symbol_index() -> Result<SymbolIndex> {
+    let data_dir = crate::config::data_dir()?;
+    std::fs::create_dir_all(&data_dir)?;
+    let store = SymbolStore::open(data_dir.join("symbols.db")).await?;
+    store.sync(&std::env::current_dir()?).await
+}
+
+/// Snapshot of the effective configuration for the `config` source
+async fn capture_environment() -> Result<EnvironmentSnapshot> {
+    let config = UnifiedConfig::load_or_default(Path::new("lspbridge.toml"))
+        .await
+        .unwrap_or_default();
+    EnvironmentSnapshot::capture(&config).await
 }
 
 fn format_as_table(result: &QueryResult) -> String {
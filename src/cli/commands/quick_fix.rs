@@ -1,15 +1,26 @@
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::cli::args::OutputFormat;
 use crate::cli::commands::Command;
-use crate::core::{Diagnostic, DiagnosticResult, DiagnosticSeverity};
+use crate::core::config::UnifiedConfig;
+use crate::core::{
+    Diagnostic, DiagnosticFilter, DiagnosticResult, DiagnosticSeverity, FileGuard, FormatConverter as _, RawDiagnostics,
+    SeverityRules, WorkspaceTrust,
+};
+use crate::ai_training::TrainingDataset;
+use crate::format::{parse_json_stream, FormatConverter};
+use crate::multi_repo::monorepo::BazelTargetMap;
+use crate::quick_fix::interactive::record_outcomes;
 use crate::quick_fix::{
-    ConfidenceThreshold, FixApplicationEngine, FixConfidenceScorer, FixEdit, FixVerifier,
-    QuickFixAction, RollbackManager,
+    calibration::MIN_THRESHOLD_SAMPLES, AcceptanceStore, ConfidenceCalibration, ConfidenceExplanation, ConfidenceThreshold, FixApplicationEngine, FixCandidate, FixConfidenceScorer,
+    FixDecision, FixEdit, FixReview, IngestSummary, FixReviewer, FixSuggestionService, FixVerifier, QuickFixAction, RenameImpactAnalyzer,
+    RollbackManager,
 };
 
+use super::export::{find_ide_diagnostics, read_stdin};
+
 pub struct QuickFixCommand {
     action: QuickFixAction,
 }
@@ -26,23 +37,35 @@ impl Command for QuickFixCommand {
         match &self.action {
             QuickFixAction::Apply {
                 threshold,
-                errors_only,
                 verify_tests,
                 verify_build,
                 backup,
                 dry_run,
-                files,
+                explain,
+                filter,
             } => {
-                self.apply_fixes(
-                    *threshold,
-                    *errors_only,
-                    *verify_tests,
-                    *verify_build,
-                    *backup,
-                    *dry_run,
-                    files.clone(),
-                )
-                .await
+                let threshold = match threshold {
+                    Some(threshold) => *threshold,
+                    None => calibrated_threshold(),
+                };
+                let options = ApplyOptions {
+                    threshold,
+                    verify_tests: *verify_tests,
+                    verify_build: *verify_build,
+                    backup: *backup,
+                    dry_run: *dry_run,
+                    explain: *explain,
+                };
+                self.apply_fixes(options, &filter.to_filter(None)?).await
+            }
+            QuickFixAction::Interactive {
+                min_confidence,
+                threshold,
+                backup,
+                filter,
+            } => {
+                self.review_fixes(*min_confidence, *threshold, *backup, &filter.to_filter(None)?)
+                    .await
             }
             QuickFixAction::Rollback { session_id, list } => {
                 self.rollback_fixes(session_id.clone(), *list).await
@@ -50,26 +73,42 @@ impl Command for QuickFixCommand {
             QuickFixAction::Analyze { detailed, format } => {
                 self.analyze_fixes(*detailed, format).await
             }
+            QuickFixAction::Calibrate { datasets, reset, format } => {
+                self.calibrate(datasets, *reset, format).await
+            }
+            QuickFixAction::Stats { language, format } => {
+                self.show_stats(language.as_deref(), format).await
+            }
         }
     }
 }
 
+/// Flags of `quick-fix apply`
+struct ApplyOptions {
+    threshold: f64,
+    verify_tests: bool,
+    verify_build: bool,
+    backup: bool,
+    dry_run: bool,
+    explain: bool,
+}
+
 impl QuickFixCommand {
-    async fn apply_fixes(
-        &self,
-        threshold: f64,
-        errors_only: bool,
-        verify_tests: bool,
-        verify_build: bool,
-        backup: bool,
-        dry_run: bool,
-        files: Option<String>,
-    ) -> Result<()> {
+    async fn apply_fixes(&self, options: ApplyOptions, filter: &DiagnosticFilter) -> Result<()> {
+        let ApplyOptions {
+            threshold,
+            verify_tests,
+            verify_build,
+            backup,
+            dry_run,
+            explain,
+        } = options;
+
         // Get current diagnostics
         let diagnostics = DiagnosticResult::new(); // Would normally capture from LSP
 
         // Set up confidence scorer
-        let scorer = FixConfidenceScorer::new();
+        let scorer = calibrated_scorer().await;
         let confidence_threshold = ConfidenceThreshold {
             auto_apply: threshold as f32,
             suggest: (threshold * 0.7) as f32,
@@ -77,16 +116,31 @@ impl QuickFixCommand {
         };
 
         // Set up fix engine
-        let engine = FixApplicationEngine::new().with_backups(backup);
+        let config = UnifiedConfig::load_or_default(Path::new("lspbridge.toml"))
+            .await
+            .unwrap_or_default();
+        let engine = FixApplicationEngine::new()
+            .with_backups(backup)
+            .with_file_guard(FileGuard::from(&config.performance));
 
-        // Set up verifier if needed
+        // Set up verifier if needed; it runs project commands, so refuse untrusted workspaces up front
         let verifier = if verify_tests || verify_build {
-            Some(
-                FixVerifier::new()
-                    .with_tests(verify_tests)
-                    .with_build_check(verify_build)
-                    .with_lsp_validation(true), // Enable LSP validation
-            )
+            let workspace = std::env::current_dir()?;
+            let trust = WorkspaceTrust::load()?;
+            trust.ensure_trusted(&workspace, "run build and test verification")?;
+            let mut verifier = FixVerifier::new()
+                .with_tests(verify_tests)
+                .with_build_check(verify_build)
+                .with_lsp_validation(true) // Enable LSP validation
+                .with_workspace_trust(&workspace, trust.level(&workspace));
+            // Build only the targets owning the fixed files inside Bazel workspaces
+            if verify_build && BazelTargetMap::is_workspace(&workspace) {
+                match BazelTargetMap::load(&workspace) {
+                    Ok(targets) => verifier = verifier.with_bazel_targets(targets),
+                    Err(e) => tracing::warn!("Failed to load Bazel targets: {}", e),
+                }
+            }
+            Some(verifier)
         } else {
             None
         };
@@ -102,25 +156,24 @@ impl QuickFixCommand {
         let mut fixes_to_apply = Vec::new();
         let mut all_backups = Vec::new();
 
+        // Built on the first rename so previews without renames skip indexing the workspace
+        let mut rename_analyzer: Option<RenameImpactAnalyzer> = None;
+
         // Analyze each diagnostic
         for (file_path, file_diagnostics) in diagnostics.diagnostics {
-            // Filter by file pattern if specified
-            if let Some(ref pattern) = files {
-                if !file_path.to_string_lossy().contains(pattern) {
-                    continue;
-                }
+            if !filter.matches_path(&file_path.to_string_lossy()) {
+                continue;
             }
 
             for diag in file_diagnostics {
-                // Filter by severity if needed
-                if errors_only && diag.severity != DiagnosticSeverity::Error {
+                if !filter.matches(&diag, diagnostics.timestamp) {
                     continue;
                 }
 
                 // For demo purposes, create a simple fix
                 // In real implementation, would get from LSP code actions
                 if let Some(fix_edit) = create_demo_fix(&diag) {
-                    let (confidence, _factors) =
+                    let (confidence, factors) =
                         scorer.score_fix(&diag, &fix_edit.new_text, false);
 
                     if dry_run {
@@ -129,12 +182,20 @@ impl QuickFixCommand {
                             diag.message,
                             confidence.value()
                         );
+                        if explain {
+                            print_explanation(&factors.explain());
+                        }
                         if confidence.is_auto_applicable(&confidence_threshold) {
                             println!("  ✓ Auto-applicable");
                         } else {
                             println!("  ⚠ Requires confirmation");
                         }
+                        self.preview_rename(&mut rename_analyzer, &fix_edit)?;
                     } else if confidence.is_auto_applicable(&confidence_threshold) {
+                        if explain {
+                            println!("Fixing: {}", diag.message);
+                            print_explanation(&factors.explain());
+                        }
                         fixes_to_apply.push((fix_edit, confidence));
                     }
                 }
@@ -155,6 +216,10 @@ impl QuickFixCommand {
             .apply_fixes_with_confidence(&fixes_to_apply, &confidence_threshold)
             .await?;
 
+        for file in engine.skipped_files() {
+            println!("⚠️  Skipped {}: {}", file.path.display(), file.reason);
+        }
+
         // Collect backups for rollback
         for (result, _) in &results {
             if let Some(ref backup) = result.backup {
@@ -247,6 +312,24 @@ impl QuickFixCommand {
         Ok(())
     }
 
+    /// Print the blast radius of a fix that renames a symbol
+    fn preview_rename(&self, analyzer: &mut Option<RenameImpactAnalyzer>, edit: &FixEdit) -> Result<()> {
+        let analyzer = match analyzer {
+            Some(analyzer) => analyzer,
+            None => analyzer.insert(RenameImpactAnalyzer::for_workspace(&std::env::current_dir()?)?),
+        };
+        match analyzer.analyze(edit) {
+            Ok(Some(impact)) => {
+                for line in impact.render().lines() {
+                    println!("  {line}");
+                }
+            }
+            Ok(None) => {}
+            Err(e) => println!("  ⚠ Could not assess rename impact: {e}"),
+        }
+        Ok(())
+    }
+
     async fn rollback_fixes(&self, session_id: Option<String>, list: bool) -> Result<()> {
         let rollback_dir = dirs::data_dir()
             .unwrap_or_else(|| PathBuf::from("."))
@@ -293,7 +376,7 @@ impl QuickFixCommand {
 
     async fn analyze_fixes(&self, detailed: bool, format: &OutputFormat) -> Result<()> {
         let diagnostics = DiagnosticResult::new(); // Would normally capture from LSP
-        let scorer = FixConfidenceScorer::new();
+        let scorer = calibrated_scorer().await;
 
         let mut analysis_results = Vec::new();
 
@@ -301,7 +384,8 @@ impl QuickFixCommand {
             for diag in file_diagnostics {
                 if let Some(fix_edit) = create_demo_fix(&diag) {
                     let (confidence, factors) = scorer.score_fix(&diag, &fix_edit.new_text, false);
-                    analysis_results.push((diag, confidence, factors));
+                    let explanation = factors.explain();
+                    analysis_results.push((diag, confidence, factors, explanation));
                 }
             }
         }
@@ -309,27 +393,39 @@ impl QuickFixCommand {
         // Format output
         match format {
             OutputFormat::Json => {
-                let json = serde_json::to_string_pretty(&analysis_results)?;
+                let results: Vec<_> = analysis_results
+                    .iter()
+                    .map(|(diag, confidence, factors, explanation)| {
+                        serde_json::json!({
+                            "diagnostic": diag,
+                            "confidence": confidence,
+                            "factors": factors,
+                            "explanation": explanation,
+                        })
+                    })
+                    .collect();
+                let json = serde_json::to_string_pretty(&results)?;
                 println!("{json}");
             }
             OutputFormat::Markdown => {
                 println!("# Fix Confidence Analysis\n");
-                for (diag, confidence, factors) in &analysis_results {
+                for (diag, confidence, _, explanation) in &analysis_results {
                     println!("## {}", diag.message);
                     println!("- **File**: {}", diag.file);
                     println!("- **Confidence**: {:.2}", confidence.value());
                     if detailed {
-                        println!("- **Factors**:");
-                        println!(
-                            "  - Pattern recognition: {:.2}",
-                            factors.pattern_recognition
-                        );
-                        println!("  - Fix complexity: {:.2}", factors.fix_complexity);
-                        println!("  - Safety score: {:.2}", factors.safety_score);
-                        println!(
-                            "  - Language confidence: {:.2}",
-                            factors.language_confidence
-                        );
+                        println!("- **Factors** (value x weight = contribution):");
+                        for factor in &explanation.factors {
+                            println!(
+                                "  - {}: {:.2} x {:.2} = {:.3}",
+                                factor.label, factor.value, factor.weight, factor.contribution
+                            );
+                        }
+                        let limiting = explanation.limiting_factors(2);
+                        if !limiting.is_empty() {
+                            let names: Vec<_> = limiting.iter().map(|f| f.label.as_str()).collect();
+                            println!("- **Held back by**: {}", names.join(", "));
+                        }
                     }
                     println!();
                 }
@@ -341,7 +437,7 @@ impl QuickFixCommand {
                     "Diagnostic", "Confidence", "Auto-Apply"
                 );
                 println!("{}", "-".repeat(72));
-                for (diag, confidence, _) in &analysis_results {
+                for (diag, confidence, _, explanation) in &analysis_results {
                     let auto = if confidence.value() >= 0.9 { "Yes" } else { "No" };
                     println!(
                         "{:<50} {:<10.2} {:<10}",
@@ -349,14 +445,293 @@ impl QuickFixCommand {
                         confidence.value(),
                         auto
                     );
+                    if detailed {
+                        print_explanation(explanation);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl QuickFixCommand {
+    async fn review_fixes(
+        &self,
+        min_confidence: f32,
+        threshold: f32,
+        backup: bool,
+        filter: &DiagnosticFilter,
+    ) -> Result<()> {
+        let raw = match find_ide_diagnostics().await {
+            Ok(diags) => diags,
+            Err(_) if atty::isnt(atty::Stream::Stdin) => RawDiagnostics {
+                source: "stdin".to_string(),
+                data: parse_json_stream(&read_stdin().await?)?,
+                timestamp: chrono::Utc::now(),
+                workspace: None,
+            },
+            Err(_) => return Err(anyhow!("No diagnostics available")),
+        };
+        let captured_at = raw.timestamp;
+        let mut diagnostics = FormatConverter::new().normalize(raw).await?;
+        let config = UnifiedConfig::load_or_default(Path::new("lspbridge.toml"))
+            .await
+            .unwrap_or_default();
+        SeverityRules::from_config(&config.severity_rules)
+            .context("Invalid [severity_rules] in lspbridge.toml")?
+            .apply(&mut diagnostics);
+
+        // The best fix with an edit for each diagnostic, most confident first
+        let suggestions = FixSuggestionService::new().with_scorer(calibrated_scorer().await);
+        let mut candidates: Vec<FixCandidate> = diagnostics
+            .iter()
+            .filter(|diagnostic| filter.matches(diagnostic, captured_at))
+            .filter_map(|diagnostic| {
+                let fix = suggestions
+                    .fixes(diagnostic)
+                    .into_iter()
+                    .find(|fix| fix.edit.is_some() && fix.confidence >= min_confidence)?;
+                FixCandidate::new(diagnostic.clone(), fix)
+            })
+            .collect();
+        if candidates.is_empty() {
+            println!("No fixes to review");
+            return Ok(());
+        }
+        candidates.sort_by(|a, b| b.fix.confidence.total_cmp(&a.fix.confidence));
+
+        let Some(review) = FixReviewer::new(FixReview::new(candidates), threshold).run()? else {
+            println!("Review abandoned; no fixes applied");
+            return Ok(());
+        };
+
+        let engine = FixApplicationEngine::new()
+            .with_backups(backup)
+            .with_file_guard(FileGuard::from(&config.performance));
+        let edits = review.edits();
+        let mut backups = Vec::new();
+        let mut applied = 0;
+        for edit in &edits {
+            match engine.apply_fix(edit).await {
+                Ok(result) if result.success => {
+                    applied += 1;
+                    backups.extend(result.backup);
+                }
+                Ok(result) => println!(
+                    "⚠️  Failed to fix {}: {}",
+                    edit.file_path.display(),
+                    result.error.unwrap_or_default()
+                ),
+                Err(e) => println!("⚠️  Failed to fix {}: {e}", edit.file_path.display()),
+            }
+        }
+
+        if let Err(e) = record_outcomes(&review, &AcceptanceStore::open(&AcceptanceStore::default_path()?)?).await {
+            eprintln!("Failed to record fix outcomes: {e}");
+        }
+
+        println!(
+            "Applied {applied} of {} fix(es): {} accepted, {} edited, {} skipped",
+            edits.len(),
+            review.count(FixDecision::Accepted),
+            review.count(FixDecision::Edited),
+            review.count(FixDecision::Skipped)
+        );
+        if !backups.is_empty() {
+            let rollback_dir = dirs::data_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("lspbridge")
+                .join("rollback");
+            let mut rollback_manager = RollbackManager::new(rollback_dir);
+            rollback_manager.init().await?;
+            let state = RollbackManager::create_state(backups, format!("Applied {applied} reviewed fixes"));
+            let session_id = state.session_id.clone();
+            rollback_manager.save_state(state).await?;
+            println!("Rollback session: {session_id}");
+        }
+        Ok(())
+    }
+
+    async fn calibrate(&self, datasets: &[PathBuf], reset: bool, format: &OutputFormat) -> Result<()> {
+        let path = ConfidenceCalibration::default_path();
+        let mut calibration = if reset {
+            ConfidenceCalibration::default()
+        } else {
+            ConfidenceCalibration::load(&path)?
+        };
+
+        let mut summary = IngestSummary::default();
+        for dataset in datasets {
+            let json = tokio::fs::read_to_string(dataset)
+                .await
+                .with_context(|| format!("Failed to read dataset {}", dataset.display()))?;
+            let dataset: TrainingDataset = serde_json::from_str(&json)
+                .with_context(|| format!("Failed to parse dataset {}", dataset.display()))?;
+            let ingested = calibration.ingest(&dataset);
+            summary.ingested += ingested.ingested;
+            summary.replaced += ingested.replaced;
+            summary.skipped += ingested.skipped;
+        }
+        calibration.save(&path)?;
+
+        let patterns = calibration.patterns();
+        let recommended = calibration.recommended_auto_apply();
+        match format {
+            OutputFormat::Json => {
+                let report = serde_json::json!({
+                    "ingested": summary,
+                    "samples": calibration.sample_count(),
+                    "recommended_auto_apply": recommended,
+                    "patterns": patterns,
+                });
+                println!("{}", serde_json::to_string_pretty(&report)?);
+                return Ok(());
+            }
+            OutputFormat::Markdown => {
+                println!("# Fix Confidence Calibration
+");
+                println!("| Fingerprint | Language | Ratings | Mean quality |");
+                println!("|---|---|---|---|");
+                for pattern in &patterns {
+                    println!(
+                        "| {} | {} | {} | {:.2} |",
+                        pattern.fingerprint, pattern.language, pattern.samples, pattern.mean_quality
+                    );
+                }
+            }
+            _ => {
+                println!(
+                    "{:<40} {:<12} {:>8} {:>13}",
+                    "Fingerprint", "Language", "Ratings", "Mean quality"
+                );
+                println!("{}", "-".repeat(76));
+                for pattern in &patterns {
+                    println!(
+                        "{:<40} {:<12} {:>8} {:>13.2}",
+                        pattern.fingerprint.chars().take(40).collect::<String>(),
+                        pattern.language,
+                        pattern.samples,
+                        pattern.mean_quality
+                    );
+                }
+            }
+        }
+
+        println!(
+            "\nIngested {} rating(s) ({} replacing earlier ones, {} without a diagnostic skipped); {} in total",
+            summary.ingested,
+            summary.replaced,
+            summary.skipped,
+            calibration.sample_count()
+        );
+        match recommended {
+            Some(threshold) => println!("Recommended auto-apply threshold: {threshold:.2}"),
+            None => println!(
+                "Not enough ratings to recommend an auto-apply threshold yet ({MIN_THRESHOLD_SAMPLES} needed above it)"
+            ),
+        }
+        println!("Saved calibration to {}", path.display());
+        Ok(())
+    }
+
+    async fn show_stats(&self, language: Option<&str>, format: &OutputFormat) -> Result<()> {
+        let path = AcceptanceStore::default_path()?;
+        if !path.exists() {
+            println!("No fix outcomes recorded yet");
+            return Ok(());
+        }
+        let stats = AcceptanceStore::open(&path)?.stats(language).await?;
+
+        match format {
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&stats)?);
+            }
+            OutputFormat::Markdown => {
+                println!("# Fix Acceptance\n");
+                println!("| Fingerprint | Language | Accepted | Modified | Rejected | Acceptance |");
+                println!("|---|---|---|---|---|---|");
+                for stat in &stats {
+                    println!(
+                        "| {} | {} | {} | {} | {} | {:.0}% |",
+                        stat.fingerprint,
+                        stat.language,
+                        stat.accepted,
+                        stat.modified,
+                        stat.rejected,
+                        stat.acceptance_rate() * 100.0
+                    );
+                }
+            }
+            _ => {
+                println!(
+                    "{:<40} {:<12} {:>8} {:>8} {:>8} {:>10}",
+                    "Fingerprint", "Language", "Accepted", "Modified", "Rejected", "Acceptance"
+                );
+                println!("{}", "-".repeat(91));
+                for stat in &stats {
+                    println!(
+                        "{:<40} {:<12} {:>8} {:>8} {:>8} {:>9.0}%",
+                        stat.fingerprint.chars().take(40).collect::<String>(),
+                        stat.language,
+                        stat.accepted,
+                        stat.modified,
+                        stat.rejected,
+                        stat.acceptance_rate() * 100.0
+                    );
                 }
             }
         }
 
+        let total: u64 = stats.iter().map(|stat| stat.total()).sum();
+        if total == 0 {
+            println!("No fix outcomes recorded yet");
+        } else if !matches!(format, OutputFormat::Json) {
+            let kept: f32 = stats.iter().map(|stat| stat.acceptance_rate() * stat.total() as f32).sum();
+            println!("\n{} outcome(s), {:.0}% acceptance overall", total, kept / total as f32 * 100.0);
+        }
         Ok(())
     }
 }
 
+/// Print a confidence breakdown indented under the fix it explains
+fn print_explanation(explanation: &ConfidenceExplanation) {
+    for line in explanation.render().lines() {
+        println!("  {line}");
+    }
+}
+
+/// Auto-apply threshold recommended by annotation calibration, or 0.9 without one
+fn calibrated_threshold() -> f64 {
+    ConfidenceCalibration::load(&ConfidenceCalibration::default_path())
+        .ok()
+        .and_then(|calibration| calibration.recommended_auto_apply())
+        .map_or(0.9, f64::from)
+}
+
+/// Confidence scorer calibrated with reviewer ratings and the fix outcomes editors have reported
+pub(crate) async fn calibrated_scorer() -> FixConfidenceScorer {
+    let mut scorer = FixConfidenceScorer::new();
+    match ConfidenceCalibration::load(&ConfidenceCalibration::default_path()) {
+        Ok(calibration) => scorer = scorer.with_calibration(&calibration),
+        Err(e) => tracing::warn!("Ignoring fix calibration: {e}"),
+    }
+    let Ok(path) = AcceptanceStore::default_path() else {
+        return scorer;
+    };
+    if !path.exists() {
+        return scorer;
+    }
+    match AcceptanceStore::open(&path) {
+        Ok(store) => match store.stats(None).await {
+            Ok(stats) => scorer.with_acceptance(&stats),
+            Err(_) => scorer,
+        },
+        Err(_) => scorer,
+    }
+}
+
 fn create_demo_fix(diagnostic: &Diagnostic) -> Option<FixEdit> {
     // This is a simplified demo - real implementation would use LSP code actions
     match diagnostic.code.as_deref() {
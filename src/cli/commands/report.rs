@@ -0,0 +1,61 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::cli::commands::Command;
+use crate::core::config::UnifiedConfig;
+use crate::history::{
+    CommandSummaryProvider, HistoryConfig, HistoryService, ReportAction, WeeklyReportArgs,
+};
+use crate::security::validate_path;
+
+pub struct ReportCommand {
+    action: ReportAction,
+}
+
+impl ReportCommand {
+    pub fn new(action: ReportAction) -> Self {
+        Self { action }
+    }
+
+    async fn weekly(&self, args: &WeeklyReportArgs) -> Result<()> {
+        let history = HistoryService::connect(HistoryConfig::default(), "report").await?;
+        let window = Duration::from_secs(args.days.max(1) * 24 * 60 * 60);
+        let config = UnifiedConfig::load_or_default(Path::new("lspbridge.toml")).await?;
+        let mut report = history.weekly_report(window, args.limit, config.calendar).await?;
+        // Summaries can take a while; release the store for other commands first
+        drop(history);
+
+        if let Some(command) = &args.summary_command {
+            let provider = CommandSummaryProvider::parse(command)?;
+            if let Err(e) = report.summarize(&provider).await {
+                eprintln!("Warning: skipping executive summary: {e}");
+            }
+        }
+
+        let template = match &args.template {
+            Some(path) => Some(std::fs::read_to_string(validate_path(path)?)?),
+            None => None,
+        };
+        let rendered = report.render(args.format, template.as_deref());
+
+        match &args.output {
+            Some(path) => {
+                std::fs::write(validate_path(path)?, rendered)?;
+                eprintln!("Report written to {}", path.display());
+            }
+            None => println!("{rendered}"),
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Command for ReportCommand {
+    async fn execute(&self) -> Result<()> {
+        match &self.action {
+            ReportAction::Weekly(args) => self.weekly(args).await,
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use tokio::fs;
+
+use crate::cli::args::ScanArgs;
+use crate::cli::commands::Command;
+use crate::core::config::UnifiedConfig;
+use crate::core::StaticScanner;
+use crate::security::validate_path;
+
+pub struct ScanCommand {
+    args: ScanArgs,
+}
+
+impl ScanCommand {
+    pub fn new(args: ScanArgs) -> Self {
+        Self { args }
+    }
+}
+
+#[async_trait]
+impl Command for ScanCommand {
+    async fn execute(&self) -> Result<()> {
+        let config_path = self.args.path.join("lspbridge.toml");
+        let mut config = UnifiedConfig::load_or_default(&config_path).await?.scan;
+        if self.args.no_imports {
+            config.check_imports = false;
+        }
+        if self.args.no_todos {
+            config.check_todos = false;
+        }
+        if self.args.no_security {
+            config.check_security = false;
+        }
+        if let Some(days) = self.args.todo_max_age_days {
+            config.todo_max_age_days = days;
+        }
+
+        let scanner = StaticScanner::new(&self.args.path, config)?;
+        let report = scanner.scan().await?;
+
+        eprintln!(
+            "Scanned {} file(s) in {}: {} diagnostic(s)",
+            report.files_scanned,
+            report.root.display(),
+            report.diagnostics.len()
+        );
+        for (code, count) in report.count_by_code() {
+            eprintln!("  {code}: {count}");
+        }
+
+        let json = serde_json::to_string_pretty(&report.to_lsp_json())?;
+        match &self.args.output {
+            Some(output) => {
+                let validated_path = validate_path(output)?;
+                fs::write(&validated_path, json).await?;
+                eprintln!("Diagnostics written to {}", validated_path.display());
+            }
+            None => println!("{json}"),
+        }
+
+        Ok(())
+    }
+}
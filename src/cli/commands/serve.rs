@@ -0,0 +1,288 @@
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use std::future::Future;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::capture::live::{detect_servers, documents_to_open, spawn_servers};
+use crate::capture::{CaptureService, LiveCapture, LiveEvent, MemoryCache};
+use crate::cli::args::ServeArgs;
+use crate::cli::commands::Command;
+use crate::core::config::UnifiedConfig;
+use crate::core::{
+    ControlRouter, Daemon, DiagnosticResult, DiagnosticSnapshot, HealthMonitor, LanguageServerProfiles, OwnershipMap,
+    SeverityRules, SimpleEnhancedConfig, SimpleEnhancedProcessor, StoreLock, TrustLevel, UsageAccounting, UsageStore,
+    WorkspaceTrust,
+};
+use crate::format::FormatConverter;
+use crate::history::{HistoryConfig, HistoryControlHandler, HistoryManager, HistoryStorage};
+use crate::privacy::PrivacyFilter;
+use crate::quick_fix::AcceptanceStore;
+use crate::query::api::{grpc, http, openapi, AccessConfig, HttpService, OwnershipAuthorizer};
+use crate::query::{QueryApi, WarmQueryRequest, WarmQueryService};
+use crate::security::validate_path;
+
+use super::export::get_privacy_policy;
+
+pub struct ServeCommand {
+    args: ServeArgs,
+}
+
+/// Query API served over gRPC and HTTP, answering from the live capture
+struct ApiServers {
+    api: Arc<QueryApi>,
+    /// Backs the HTTP `/health` endpoint
+    health: Option<Arc<HealthMonitor>>,
+}
+
+impl ApiServers {
+    async fn update(&self, snapshot: &DiagnosticSnapshot) {
+        let diagnostics = &snapshot.diagnostics;
+        let mut result = DiagnosticResult::from_diagnostics(diagnostics.clone());
+        result.code_lenses = snapshot.code_lenses.clone();
+        if let Err(e) = self.api.with_shared_diagnostics(Arc::new(result)).await {
+            eprintln!("Failed to update served queries: {e}");
+        }
+        // Suggestions read source files, so warm them off the capture loop
+        let api = self.api.clone();
+        tokio::spawn(async move { api.warm_fix_cache().await });
+        if let Some(health) = &self.health {
+            health.record_diagnostics(diagnostics.clone()).await;
+        }
+    }
+}
+
+impl ServeCommand {
+    pub fn new(args: ServeArgs) -> Self {
+        Self { args }
+    }
+}
+
+#[async_trait]
+impl Command for ServeCommand {
+    async fn execute(&self) -> Result<()> {
+        if self.args.openapi {
+            println!("{}", openapi::to_json()?);
+            return Ok(());
+        }
+
+        let root = validate_path(&self.args.path)?;
+        let trust = WorkspaceTrust::load()?.level(&root);
+        let config = UnifiedConfig::load_or_default(&root.join("lspbridge.toml")).await?;
+        let profiles = LanguageServerProfiles::load(&root)?
+            .with_trust(trust)
+            .with_managed_servers(config.servers.installer()?);
+
+        let servers = if self.args.servers.is_empty() {
+            detect_servers(&profiles)
+        } else {
+            self.args.servers.clone()
+        };
+        if servers.is_empty() {
+            return Err(anyhow!(
+                "No language servers to run in {}; pass --server or add a profile to .lspbridge.toml",
+                root.display()
+            ));
+        }
+
+        let (history, warm_queries) = self.start_daemon(&root).await?.unzip();
+        if history.is_none() {
+            eprintln!("Capturing without recording history");
+        }
+        let api_servers = self.start_api_servers(&root, &config, trust, history.clone()).await?;
+
+        let sessions = servers
+            .iter()
+            .map(|server| {
+                let documents = documents_to_open(&root, server, self.args.open_limit);
+                eprintln!("Starting {server} ({} document(s) to open)", documents.len());
+                (profiles.launch(server), documents)
+            })
+            .collect();
+        let (mut events, _servers) = spawn_servers(sessions);
+
+        let privacy_filter = PrivacyFilter::new(get_privacy_policy(&self.args.privacy)).with_workspace(root.clone());
+        let severity_rules =
+            SeverityRules::from_config(&config.severity_rules).context("Invalid [severity_rules] in lspbridge.toml")?;
+        let mut capture_service = CaptureService::new(MemoryCache::with_defaults(), privacy_filter, FormatConverter::new())
+            .with_severity_rules(severity_rules);
+        capture_service.start_capture().await?;
+
+        let mut live = LiveCapture::new();
+        let mut running = servers.len();
+        let mut interval = tokio::time::interval(Duration::from_secs(self.args.interval.max(1)));
+        loop {
+            tokio::select! {
+                event = events.recv() => {
+                    let Some(event) = event else { break };
+                    if let LiveEvent::Stopped { server, reason } = &event {
+                        eprintln!("{server} stopped: {reason}");
+                        running -= 1;
+                    }
+                    live.apply(&event);
+                    if running == 0 {
+                        break;
+                    }
+                }
+                _ = interval.tick() => {
+                    self.record(
+                        &mut live,
+                        &mut capture_service,
+                        history.as_deref(),
+                        warm_queries.as_deref(),
+                        api_servers.as_ref(),
+                    )
+                    .await;
+                }
+            }
+        }
+
+        self.record(
+            &mut live,
+            &mut capture_service,
+            history.as_deref(),
+            warm_queries.as_deref(),
+            api_servers.as_ref(),
+        )
+        .await;
+        Err(anyhow!("All language servers stopped"))
+    }
+}
+
+impl ServeCommand {
+    /// Record collected updates, reporting failures without stopping the capture
+    async fn record(
+        &self,
+        live: &mut LiveCapture,
+        capture_service: &mut CaptureService<MemoryCache, PrivacyFilter, FormatConverter>,
+        history: Option<&HistoryStorage>,
+        warm_queries: Option<&WarmQueryService>,
+        api_servers: Option<&ApiServers>,
+    ) {
+        let snapshot = match live.flush(capture_service, history).await {
+            Ok(Some(snapshot)) => snapshot,
+            Ok(None) => return,
+            Err(e) => {
+                eprintln!("Failed to record diagnostics: {e}");
+                return;
+            }
+        };
+        eprintln!(
+            "{} diagnostic(s) in {} document(s)",
+            snapshot.diagnostics.len(),
+            live.document_count()
+        );
+        if let Some(api_servers) = api_servers {
+            api_servers.update(&snapshot).await;
+        }
+        if let Some(warm_queries) = warm_queries {
+            if let Err(e) = warm_queries.update(snapshot.diagnostics, snapshot.timestamp).await {
+                eprintln!("Failed to update warm queries: {e}");
+            }
+        }
+    }
+
+    /// Serve the query API over gRPC and/or HTTP, as requested
+    ///
+    /// Callers are rate limited and held to the `[api_quotas]` in
+    /// `lspbridge.toml`; with `--access-file` they are also authorized by
+    /// API key and only see the files they own. Fix outcomes editors report
+    /// go to the same store as `quick-fix stats` reads, and `/health`
+    /// includes the `[[metric_plugins]]` configured for the workspace.
+    async fn start_api_servers(
+        &self,
+        root: &Path,
+        config: &UnifiedConfig,
+        trust: TrustLevel,
+        history: Option<Arc<HistoryStorage>>,
+    ) -> Result<Option<ApiServers>> {
+        if self.args.grpc.is_none() && self.args.http.is_none() {
+            return Ok(None);
+        }
+
+        let usage = UsageAccounting::new(UsageStore::open(&UsageStore::default_path()?)?, config.api_quotas.clone());
+        let mut api = QueryApi::new()
+            .with_usage_accounting(usage)
+            .with_fix_outcomes(AcceptanceStore::open(&AcceptanceStore::default_path()?)?)
+            .await?;
+        if let Some(path) = &self.args.access_file {
+            let ownership = OwnershipMap::discover(root)?;
+            api = api.with_authorization(OwnershipAuthorizer::from_config(ownership, AccessConfig::load(path)?));
+        }
+        api.with_calendar(config.calendar).await?;
+        if let Some(history) = &history {
+            api.with_shared_history(history.clone()).await?;
+        }
+        let api = Arc::new(api);
+
+        if let Some(addr) = self.args.grpc {
+            spawn_server("gRPC", grpc::serve(api.clone(), addr)?);
+            eprintln!("Serving the query API over gRPC on {addr}");
+        }
+
+        let mut health = None;
+        if let Some(addr) = self.args.http {
+            let processor = SimpleEnhancedProcessor::new(SimpleEnhancedConfig {
+                cache_dir: crate::config::data_dir()?.join("health-cache"),
+                config_file: Some(root.join("lspbridge.toml")),
+                ..Default::default()
+            })
+            .await?;
+            let monitor = HealthMonitor::new(Arc::new(processor), None)
+                .await?
+                .with_script_plugins(&config.metric_plugins, root, trust);
+            let monitor = Arc::new(monitor);
+            monitor.clone().start_monitoring().await?;
+
+            let mut service = HttpService::new(api.clone(), monitor.clone()).with_workspace_root(root.to_path_buf());
+            if let Some(history) = history {
+                service = service.with_history(Arc::new(HistoryManager::from_storage(history)));
+            }
+            spawn_server("HTTP", http::serve(service, addr)?);
+            eprintln!("Serving diagnostics, queries, fixes and history over HTTP on http://{addr}");
+            health = Some(monitor);
+        }
+
+        Ok(Some(ApiServers { api, health }))
+    }
+
+    /// Take the store lock and serve history and query requests from other commands
+    ///
+    /// Returns `None` if another process holds the lock; its owner keeps
+    /// recording history, so this process only captures.
+    async fn start_daemon(&self, root: &Path) -> Result<Option<(Arc<HistoryStorage>, Arc<WarmQueryService>)>> {
+        let data_dir = crate::config::data_dir()?;
+        let Some(daemon) = Daemon::start(&data_dir, "serve")? else {
+            if let Some(owner) = StoreLock::owner(&data_dir) {
+                eprintln!("History is managed by {owner}; not serving history requests");
+            }
+            return Ok(None);
+        };
+
+        // The daemon holds the store lock for as long as it runs, so it alone opens the database
+        let storage = Arc::new(HistoryStorage::new(HistoryConfig::default()).await?);
+        let calendar = UnifiedConfig::load_or_default(&root.join("lspbridge.toml")).await?.calendar;
+        let warm_queries = Arc::new(WarmQueryService::new(calendar).await?.with_history(storage.clone()).await?);
+        warm_queries.index_symbols(root);
+
+        let history = Arc::new(HistoryControlHandler::new(HistoryManager::from_storage(storage.clone())));
+        let handler = Arc::new(ControlRouter::new(history).with_route(WarmQueryRequest::OPS, warm_queries.clone()));
+        tokio::spawn(async move {
+            if let Err(e) = daemon.serve(handler).await {
+                eprintln!("Control socket stopped: {e}");
+            }
+        });
+
+        Ok(Some((storage, warm_queries)))
+    }
+}
+
+/// Run a server in the background, reporting when it stops
+fn spawn_server(name: &'static str, server: impl Future<Output = Result<()>> + Send + 'static) {
+    tokio::spawn(async move {
+        if let Err(e) = server.await {
+            eprintln!("{name} server stopped: {e}");
+        }
+    });
+}
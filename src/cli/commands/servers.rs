@@ -0,0 +1,75 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use std::path::Path;
+
+use crate::cli::commands::Command;
+use crate::core::config::UnifiedConfig;
+use crate::core::{KnownServer, ServersAction};
+
+pub struct ServersCommand {
+    action: ServersAction,
+}
+
+impl ServersCommand {
+    pub fn new(action: ServersAction) -> Self {
+        Self { action }
+    }
+}
+
+#[async_trait]
+impl Command for ServersCommand {
+    async fn execute(&self) -> Result<()> {
+        let config = UnifiedConfig::load_or_default(Path::new("lspbridge.toml")).await?;
+        let installer = config.servers.installer()?;
+
+        match &self.action {
+            ServersAction::Install { server, version, force } => {
+                let known = KnownServer::parse(server).ok_or_else(|| {
+                    anyhow!(
+                        "Don't know how to install '{server}'; supported: {}",
+                        KnownServer::ALL.map(KnownServer::name).join(", ")
+                    )
+                })?;
+                eprintln!("Installing {} into {}...", known.name(), installer.dir().display());
+
+                let (version, force) = (version.clone(), *force);
+                let (installer, installed) = tokio::task::spawn_blocking(move || {
+                    let installed = installer.install(known, version.as_deref(), force);
+                    (installer, installed)
+                })
+                .await?;
+                let installed = installed?;
+
+                println!("✅ {} {} at {}", installed.server, installed.reported_version, installed.binary.display());
+                if installer.pin(known).is_none() {
+                    // Suggest a pin so other machines and CI install the same thing
+                    println!("\nPin it in lspbridge.toml:\n");
+                    println!("[servers.pins.{}]", installed.server);
+                    println!("version = \"{}\"", installed.version);
+                    if let Some(sha256) = &installed.sha256 {
+                        println!("sha256 = \"{sha256}\"");
+                    }
+                }
+            }
+            ServersAction::List => {
+                for server in KnownServer::ALL {
+                    let pinned = installer
+                        .pin(server)
+                        .map(|pin| format!("pinned to {}", pin.version))
+                        .unwrap_or_else(|| "not pinned".to_string());
+                    match installer.installed(server) {
+                        Some(installed) => println!(
+                            "{} ({}): {} installed at {}, {pinned}",
+                            server.name(),
+                            server.language(),
+                            installed.version,
+                            installed.binary.display()
+                        ),
+                        None => println!("{} ({}): not installed, {pinned}", server.name(), server.language()),
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::time::Duration;
+
+use crate::cli::args::{OutputFormat, StatsArgs};
+use crate::cli::commands::Command;
+use crate::core::config::UnifiedConfig;
+use crate::core::{QuickStats, WorkspaceStats, WorkspaceTrust};
+use crate::security::validate_path;
+
+pub struct StatsCommand {
+    args: StatsArgs,
+}
+
+impl StatsCommand {
+    pub fn new(args: StatsArgs) -> Self {
+        Self { args }
+    }
+}
+
+#[async_trait]
+impl Command for StatsCommand {
+    async fn execute(&self) -> Result<()> {
+        let root = validate_path(&self.args.path)?;
+        let config = UnifiedConfig::load_or_default(&root.join("lspbridge.toml"))
+            .await
+            .unwrap_or_default();
+
+        let mut quick_stats = QuickStats::new(&root)
+            .with_scan_config(config.scan)
+            .with_workspace_trust(WorkspaceTrust::load()?.level(&root))
+            .with_tool_timeout(Duration::from_secs(self.args.tool_timeout.max(1)));
+        if self.args.no_tools {
+            quick_stats = quick_stats.with_tools(false);
+        }
+        let stats = quick_stats.collect().await?;
+
+        match self.args.format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&stats)?),
+            OutputFormat::Sarif | OutputFormat::Html | OutputFormat::GithubActions => return Err(self.args.format.unsupported_by("stats")),
+            OutputFormat::Markdown | OutputFormat::Claude => print_summary(&stats),
+        }
+        Ok(())
+    }
+}
+
+fn print_summary(stats: &WorkspaceStats) {
+    println!(
+        "# {}: {} diagnostic(s) in {:.1}s\n",
+        stats.root.display(),
+        stats.total,
+        stats.elapsed_ms as f64 / 1000.0
+    );
+
+    let severities: Vec<String> = stats
+        .by_severity
+        .iter()
+        .map(|(severity, count)| format!("{count} {}", severity.to_string().to_lowercase()))
+        .collect();
+    println!("Severity:  {}", severities.join(", "));
+    if !stats.by_language.is_empty() {
+        let languages: Vec<String> = stats
+            .by_language
+            .iter()
+            .map(|(language, count)| format!("{language} {count}"))
+            .collect();
+        println!("Languages: {}", languages.join(", "));
+    }
+    println!(
+        "Health:    {:.0}% estimated ({} file(s) with errors, {} hot spot(s))",
+        stats.health.health_score * 100.0,
+        stats.health.files_with_errors,
+        stats.health.hot_spots
+    );
+
+    if !stats.top_files.is_empty() {
+        println!("\n## Top files\n");
+        for file in &stats.top_files {
+            println!(
+                "- {} ({} error(s), {} warning(s), {} total)",
+                file.file, file.errors, file.warnings, file.total
+            );
+        }
+    }
+
+    println!("\n## Sources\n");
+    for source in &stats.sources {
+        match &source.note {
+            Some(note) => println!("- {}: skipped, {note}", source.name),
+            None => println!("- {}: {} diagnostic(s)", source.name, source.diagnostics),
+        }
+    }
+
+    println!("\n## Next\n");
+    for next in &stats.next_commands {
+        println!("- `{}`: {}", next.command, next.reason);
+    }
+}
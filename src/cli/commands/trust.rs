@@ -0,0 +1,48 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+use crate::cli::commands::Command;
+use crate::core::WorkspaceTrust;
+use crate::security::validate_path;
+
+pub struct TrustCommand {
+    path: PathBuf,
+    revoke: bool,
+    list: bool,
+}
+
+impl TrustCommand {
+    pub fn new(path: PathBuf, revoke: bool, list: bool) -> Self {
+        Self { path, revoke, list }
+    }
+}
+
+#[async_trait]
+impl Command for TrustCommand {
+    async fn execute(&self) -> Result<()> {
+        let config_path = WorkspaceTrust::default_path();
+        let mut trust = WorkspaceTrust::load_from(&config_path)?;
+
+        if self.list {
+            for root in &trust.trusted {
+                println!("trusted    {}", root.display());
+            }
+            for root in &trust.untrusted {
+                println!("untrusted  {}", root.display());
+            }
+            return Ok(());
+        }
+
+        let path = validate_path(&self.path)?;
+        if self.revoke {
+            trust.revoke(&path);
+            println!("Revoked trust in {}", path.display());
+        } else {
+            trust.trust(&path);
+            println!("Trusted {}; project commands may now run there", path.display());
+        }
+        trust.save_to(&config_path)?;
+        Ok(())
+    }
+}
@@ -1,17 +1,24 @@
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
+use std::path::Path;
+use std::sync::Arc;
 
 use crate::capture::{CaptureService, MemoryCache};
 use crate::core::DiagnosticsCaptureService;
-use crate::cli::args::{OutputFormat, WatchArgs};
+use crate::cli::args::WatchArgs;
 use crate::cli::commands::Command;
-use crate::core::traits::ExportService as ExportServiceTrait;
+use crate::core::config::UnifiedConfig;
 use crate::core::{
-    DiagnosticFilter, DiagnosticSeverity, DiagnosticSnapshot, ExportConfig, ExportFormat,
+    AuditLog, ControlRouter, Daemon, DiagnosticFilter, DiagnosticSeverity, DiagnosticSnapshot, DynamicConfigManager,
+    ExportConfig, SeverityRules, StaticScanner, StoreLock,
 };
 use crate::export::ExportService;
 use crate::format::FormatConverter;
+use crate::history::{
+    BackupDatabase, HistoryConfig, HistoryControlHandler, HistoryManager, HistoryStorage, StaleFileRefresher,
+};
 use crate::privacy::PrivacyFilter;
+use crate::query::{WarmQueryRequest, WarmQueryService};
 
 use super::export::{find_ide_diagnostics, get_privacy_policy};
 
@@ -33,8 +40,31 @@ impl Command for WatchCommand {
         let privacy_filter = PrivacyFilter::new(get_privacy_policy(&self.args.privacy));
         let format_converter = FormatConverter::new();
         let cache = MemoryCache::with_defaults();
-        let mut capture_service = CaptureService::new(cache, privacy_filter, format_converter);
-        
+        let config = UnifiedConfig::load_or_default(Path::new("lspbridge.toml")).await?;
+        let severity_rules =
+            SeverityRules::from_config(&config.severity_rules).context("Invalid [severity_rules] in lspbridge.toml")?;
+        let mut capture_service =
+            CaptureService::new(cache, privacy_filter, format_converter).with_severity_rules(severity_rules);
+
+        // Hot-reload privacy and severity rules; the handles must outlive the watch loop
+        let _config_reload = match &self.args.privacy_config {
+            Some(path) => Some(self.follow_dynamic_config(&capture_service, path, &config).await?),
+            None => None,
+        };
+
+        // Own the stores for the session so other commands route through us
+        let (storage, warm_queries) = self.start_daemon().await?.unzip();
+
+        let _stale_refresh = match (self.args.refresh_stale_days, storage) {
+            (Some(days), Some(storage)) => Some(self.refresh_stale_files(days, storage).await?),
+            (Some(_), None) => {
+                return Err(anyhow!(
+                    "--refresh-stale-days writes to the history database, which another process is using"
+                ))
+            }
+            (None, _) => None,
+        };
+
         // Try to detect project info from current directory
         let export_service = match std::env::current_dir() {
             Ok(cwd) => ExportService::with_project_info(&cwd),
@@ -49,7 +79,10 @@ impl Command for WatchCommand {
         loop {
             interval.tick().await;
 
-            match self.watch_iteration(&mut capture_service, &export_service).await {
+            match self
+                .watch_iteration(&mut capture_service, &export_service, warm_queries.as_deref())
+                .await
+            {
                 Ok(Some(output)) => {
                     if output != last_output {
                         println!("{output}");
@@ -68,10 +101,111 @@ impl Command for WatchCommand {
 }
 
 impl WatchCommand {
+    /// Apply the `[privacy]` and `[severity_rules]` sections of a dynamic config file and follow their changes
+    ///
+    /// Severity rules in the file replace those from `lspbridge.toml` once
+    /// the file has any.
+    async fn follow_dynamic_config(
+        &self,
+        capture_service: &CaptureService<MemoryCache, PrivacyFilter, FormatConverter>,
+        path: &Path,
+        config: &UnifiedConfig,
+    ) -> Result<(tokio::task::JoinHandle<()>, tokio::task::JoinHandle<()>)> {
+        let manager = Arc::new(DynamicConfigManager::new(path.to_path_buf()).await?);
+        let dynamic = manager.get_config().await;
+        capture_service.update_privacy_policy(dynamic.privacy.to_privacy_policy())?;
+        if !dynamic.severity_rules.rules.is_empty() {
+            let rules = SeverityRules::from_config(&dynamic.severity_rules)
+                .with_context(|| format!("Invalid [severity_rules] in {}", path.display()))?;
+            capture_service.update_severity_rules(rules)?;
+        }
+        manager.start_auto_reload().await?;
+
+        let audit_log = AuditLog::from_config(&config.security.audit);
+
+        eprintln!("Reloading privacy and severity rules from {}", path.display());
+        Ok((
+            capture_service.follow_privacy_config(manager.clone(), audit_log),
+            capture_service.follow_severity_rules(manager),
+        ))
+    }
+
+    /// Take the store lock and serve history and query requests from other commands
+    ///
+    /// Returns the history storage this process now owns and the query
+    /// service to keep fed with diagnostics, or `None` if another process
+    /// holds the lock, in which case watching continues without touching
+    /// the stores.
+    async fn start_daemon(&self) -> Result<Option<(Arc<HistoryStorage>, Arc<WarmQueryService>)>> {
+        let data_dir = crate::config::data_dir()?;
+        let Some(daemon) = Daemon::start(&data_dir, "watch")? else {
+            if let Some(owner) = StoreLock::owner(&data_dir) {
+                eprintln!("History is managed by {owner}; not serving history requests");
+            }
+            return Ok(None);
+        };
+
+        // The daemon holds the store lock for as long as it runs, so it alone opens the database
+        let storage = Arc::new(HistoryStorage::new(HistoryConfig::default()).await?);
+        let calendar = UnifiedConfig::load_or_default(Path::new("lspbridge.toml")).await?.calendar;
+        let warm_queries = Arc::new(WarmQueryService::new(calendar).await?.with_history(storage.clone()).await?);
+        warm_queries.index_symbols(&std::env::current_dir()?);
+
+        let history = Arc::new(HistoryControlHandler::new(HistoryManager::from_storage(storage.clone())));
+        let handler = Arc::new(ControlRouter::new(history).with_route(WarmQueryRequest::OPS, warm_queries.clone()));
+        tokio::spawn(async move {
+            if let Err(e) = daemon.serve(handler).await {
+                eprintln!("Control socket stopped: {e}");
+            }
+        });
+        self.start_backups().await?;
+
+        Ok(Some((storage, warm_queries)))
+    }
+
+    /// Keep backups of the history and team databases while the daemon runs
+    async fn start_backups(&self) -> Result<()> {
+        let config = UnifiedConfig::load_or_default(Path::new("lspbridge.toml")).await?;
+        if !config.backup.enabled {
+            return Ok(());
+        }
+        for database in [BackupDatabase::History, BackupDatabase::Team] {
+            if let Some(path) = database.path(&config) {
+                config
+                    .backup
+                    .archiver(database.name(), &path)?
+                    .spawn(config.backup.segment_interval());
+            }
+        }
+        Ok(())
+    }
+
+    /// Re-scan stale files in history at low priority in the background
+    ///
+    /// Only static-scan diagnostics are refreshed.
+    async fn refresh_stale_files(
+        &self,
+        days: u64,
+        storage: Arc<HistoryStorage>,
+    ) -> Result<tokio::task::JoinHandle<()>> {
+        let root = std::env::current_dir()?;
+        let config = UnifiedConfig::load_or_default(Path::new("lspbridge.toml")).await?;
+        let scanner = StaticScanner::new(&root, config.scan)?;
+
+        eprintln!(
+            "Refreshing static-scan history of files changed or older than {days} days; \
+             language-server diagnostics of changed files are dropped until the next capture"
+        );
+        Ok(StaleFileRefresher::new(storage, Arc::new(scanner), root)
+            .with_max_age(std::time::Duration::from_secs(days * 24 * 60 * 60))
+            .spawn())
+    }
+
     async fn watch_iteration(
         &self,
         capture_service: &mut CaptureService<MemoryCache, PrivacyFilter, FormatConverter>,
         export_service: &ExportService,
+        warm_queries: Option<&WarmQueryService>,
     ) -> Result<Option<String>> {
         let raw_diagnostics = find_ide_diagnostics().await?;
         capture_service.process_diagnostics(raw_diagnostics).await?;
@@ -80,6 +214,11 @@ impl WatchCommand {
             Some(s) => s,
             None => return Ok(None),
         };
+        if let Some(warm_queries) = warm_queries {
+            warm_queries
+                .update(snapshot.diagnostics.clone(), snapshot.timestamp)
+                .await?;
+        }
 
         let filter = if self.args.errors_only {
             DiagnosticFilter {
@@ -90,38 +229,17 @@ impl WatchCommand {
             DiagnosticFilter::default()
         };
 
-        // Apply filtering
-        let mut filtered_diagnostics = snapshot.diagnostics.clone();
-        if let Some(severities) = &filter.severities {
-            let severity_set: std::collections::HashSet<_> = severities.iter().collect();
-            filtered_diagnostics.retain(|d| severity_set.contains(&d.severity));
-        }
-
         let filtered_snapshot = DiagnosticSnapshot {
-            diagnostics: filtered_diagnostics,
+            diagnostics: filter.apply(snapshot.diagnostics, snapshot.timestamp),
             ..snapshot
         };
 
         let export_config = ExportConfig {
-            format: match self.args.format {
-                OutputFormat::Json => ExportFormat::Json,
-                OutputFormat::Markdown => ExportFormat::Markdown,
-                OutputFormat::Claude => ExportFormat::ClaudeOptimized,
-            },
+            format: self.args.format.into(),
             ..Default::default()
         };
 
-        let output = match self.args.format {
-            OutputFormat::Markdown => {
-                export_service.export_to_markdown(&filtered_snapshot, &export_config)?
-            }
-            OutputFormat::Claude => {
-                export_service.export_to_claude_optimized(&filtered_snapshot, &export_config)?
-            }
-            OutputFormat::Json => {
-                export_service.export_to_json(&filtered_snapshot, &export_config)?
-            }
-        };
+        let output = export_service.export(&filtered_snapshot, &export_config)?;
 
         Ok(Some(output))
     }
@@ -0,0 +1,133 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+
+use crate::cli::args::OutputFormat;
+use crate::cli::commands::Command;
+use crate::core::config::UnifiedConfig;
+use crate::core::{RawDiagnostics, TrustLevel, WorkspaceTrust};
+use crate::format::{parse_json_stream, FormatConverter};
+use crate::quick_fix::{CargoCheck, HealthMetrics, StaticScanCheck, WhatIfAnalyzer};
+
+use super::export::{find_ide_diagnostics, read_stdin};
+
+pub struct WhatifCommand {
+    threshold: f32,
+    format: OutputFormat,
+}
+
+impl WhatifCommand {
+    pub fn new(threshold: f32, format: OutputFormat) -> Self {
+        Self { threshold, format }
+    }
+}
+
+#[async_trait]
+impl Command for WhatifCommand {
+    async fn execute(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.threshold) {
+            return Err(anyhow!("--apply-threshold must be between 0.0 and 1.0"));
+        }
+
+        let raw = match find_ide_diagnostics().await {
+            Ok(diags) => diags,
+            Err(_) if atty::isnt(atty::Stream::Stdin) => RawDiagnostics {
+                source: "stdin".to_string(),
+                data: parse_json_stream(&read_stdin().await?)?,
+                timestamp: chrono::Utc::now(),
+                workspace: None,
+            },
+            Err(_) => return Err(anyhow!("No diagnostics available")),
+        };
+
+        use crate::core::FormatConverter as FormatConverterTrait;
+        let diagnostics = FormatConverter::new().normalize(raw).await?;
+
+        // Re-check the sandbox with whatever can reproduce the diagnostics here
+        let root = std::env::current_dir()?;
+        let config = UnifiedConfig::load_or_default(&root.join("lspbridge.toml")).await?;
+        let mut analyzer = WhatIfAnalyzer::new(self.threshold)
+            .with_workspace(&root)
+            .with_check(StaticScanCheck::new(config.scan));
+        if root.join("Cargo.toml").exists() {
+            let trust = WorkspaceTrust::load()?.level(&root);
+            if trust == TrustLevel::Untrusted {
+                eprintln!("Workspace is not trusted; compiler diagnostics will not be re-checked");
+            }
+            analyzer = analyzer.with_check(CargoCheck::new(&root, trust));
+        }
+        let report = analyzer.analyze(&diagnostics).await?;
+
+        match self.format {
+            OutputFormat::Json => {
+                let mut value = serde_json::to_value(&report)?;
+                value["healthImprovement"] = serde_json::json!(report.health_improvement());
+                println!("{}", serde_json::to_string_pretty(&value)?);
+            }
+            OutputFormat::Sarif | OutputFormat::Html | OutputFormat::GithubActions => {
+                return Err(self.format.unsupported_by("whatif"));
+            }
+            OutputFormat::Markdown | OutputFormat::Claude => {
+                println!("# What-if: autofix at confidence >= {:.2}\n", report.threshold);
+                println!(
+                    "Applied {} of {} candidate fixes in a sandbox ({} skipped, {} not re-checked).\n",
+                    report.applied.len(),
+                    report.candidates,
+                    report.skipped,
+                    report.unverified
+                );
+                println!("| Metric | Current | After autofix |");
+                println!("|--------|---------|---------------|");
+                print_row("Errors", &report.before, &report.after, |m| m.errors.to_string());
+                print_row("Warnings", &report.before, &report.after, |m| m.warnings.to_string());
+                print_row("Total", &report.before, &report.after, |m| m.total.to_string());
+                print_row("Files with errors", &report.before, &report.after, |m| {
+                    m.files_with_errors.to_string()
+                });
+                print_row("Hot spots", &report.before, &report.after, |m| m.hot_spots.to_string());
+                print_row("Health score", &report.before, &report.after, |m| {
+                    format!("{:.1}%", m.health_score * 100.0)
+                });
+                println!(
+                    "\nHealth would change by {:+.1} points.",
+                    report.health_improvement()
+                );
+
+                if !report.applied.is_empty() {
+                    println!("\n## Fixes applied\n");
+                    for fix in &report.applied {
+                        let outcome = match fix.verified {
+                            Some(true) => "resolved",
+                            Some(false) => "still reported",
+                            None => "not re-checked",
+                        };
+                        println!(
+                            "- {}:{} {} ({:.0}%, {outcome})",
+                            fix.file,
+                            fix.line,
+                            fix.title,
+                            fix.confidence * 100.0
+                        );
+                    }
+                }
+
+                if !report.introduced.is_empty() {
+                    println!("\n## Introduced by the fixes\n");
+                    for diagnostic in &report.introduced {
+                        println!("- {}:{} {}", diagnostic.file, diagnostic.range.start.line + 1, diagnostic.message);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn print_row(
+    name: &str,
+    before: &HealthMetrics,
+    after: &HealthMetrics,
+    value: impl Fn(&HealthMetrics) -> String,
+) {
+    println!("| {name} | {} | {} |", value(before), value(after));
+}
@@ -0,0 +1,254 @@
+//! Git hooks that check commits and pushes against captured diagnostics
+//!
+//! `lspbridge hook install` writes a pre-commit or pre-push hook that runs
+//! `lspbridge hook run`. Only the files being committed (staged) or pushed
+//! (changed since the upstream) are checked: their latest captured
+//! diagnostics are compared with history at the base commit, and quick
+//! fixes for their errors are dry-run. [`HookThresholds`] decide whether
+//! the new diagnostics block the commit or push.
+
+use anyhow::{anyhow, Result};
+use clap::{Args, Subcommand, ValueEnum};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::core::DiagnosticSeverity;
+use crate::history::FileDiff;
+
+/// First line after the shebang of every hook lspbridge writes, so it
+/// only ever replaces or removes its own hooks
+pub const HOOK_MARKER: &str = "# Installed by `lspbridge hook install`";
+
+/// Hook actions
+#[derive(Debug, Clone, Subcommand)]
+pub enum HookAction {
+    /// Write a git hook that blocks commits or pushes introducing new diagnostics
+    Install {
+        /// Which hook to install
+        #[arg(value_enum, default_value = "pre-commit")]
+        kind: HookKind,
+        /// When to block
+        #[command(flatten)]
+        thresholds: HookThresholds,
+        /// Replace an existing hook that lspbridge didn't write
+        #[arg(long)]
+        force: bool,
+    },
+    /// Remove a hook installed by lspbridge
+    Uninstall {
+        /// Which hook to remove
+        #[arg(value_enum, default_value = "pre-commit")]
+        kind: HookKind,
+    },
+    /// Check the files being committed or pushed; this is what installed hooks run
+    Run {
+        /// Which hook is running
+        #[arg(value_enum)]
+        kind: HookKind,
+        /// When to block
+        #[command(flatten)]
+        thresholds: HookThresholds,
+    },
+}
+
+/// Git hooks lspbridge can install
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum HookKind {
+    /// Check staged files before each commit
+    PreCommit,
+    /// Check files changed since the upstream before each push
+    PrePush,
+}
+
+impl HookKind {
+    /// File name of the hook in the hooks directory
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::PreCommit => "pre-commit",
+            Self::PrePush => "pre-push",
+        }
+    }
+}
+
+impl fmt::Display for HookKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.file_name())
+    }
+}
+
+/// Limits on the checked files' diagnostics; exceeding any blocks the hook
+#[derive(Debug, Clone, Default, PartialEq, Eq, Args)]
+pub struct HookThresholds {
+    /// Most errors the change may introduce
+    #[arg(long, default_value = "0")]
+    pub max_new_errors: usize,
+    /// Most warnings the change may introduce (default: unlimited)
+    #[arg(long)]
+    pub max_new_warnings: Option<usize>,
+    /// Most errors the checked files may have in total, new or not (default: unlimited)
+    #[arg(long)]
+    pub max_errors: Option<usize>,
+}
+
+impl HookThresholds {
+    /// The flags that reproduce these thresholds on `hook run`
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = vec![format!("--max-new-errors {}", self.max_new_errors)];
+        if let Some(max) = self.max_new_warnings {
+            args.push(format!("--max-new-warnings {max}"));
+        }
+        if let Some(max) = self.max_errors {
+            args.push(format!("--max-errors {max}"));
+        }
+        args
+    }
+
+    /// Why the counts block the hook; empty when they pass
+    pub fn violations(&self, counts: &HookCounts) -> Vec<String> {
+        let mut violations = Vec::new();
+        if counts.new_errors > self.max_new_errors {
+            violations.push(format!("{} new errors (max {})", counts.new_errors, self.max_new_errors));
+        }
+        if let Some(max) = self.max_new_warnings.filter(|max| counts.new_warnings > *max) {
+            violations.push(format!("{} new warnings (max {max})", counts.new_warnings));
+        }
+        if let Some(max) = self.max_errors.filter(|max| counts.errors > *max) {
+            violations.push(format!("{} errors in total (max {max})", counts.errors));
+        }
+        violations
+    }
+}
+
+/// Diagnostic counts over the files a hook checks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HookCounts {
+    pub new_errors: usize,
+    pub new_warnings: usize,
+    /// Errors present after the change, new or persisting
+    pub errors: usize,
+}
+
+impl HookCounts {
+    pub fn from_diffs<'a>(files: impl IntoIterator<Item = &'a FileDiff>) -> Self {
+        let count = |diagnostics: &[crate::core::Diagnostic], severity| {
+            diagnostics.iter().filter(|d| d.severity == severity).count()
+        };
+        files.into_iter().fold(Self::default(), |mut counts, file| {
+            let new_errors = count(&file.added, DiagnosticSeverity::Error);
+            counts.new_errors += new_errors;
+            counts.new_warnings += count(&file.added, DiagnosticSeverity::Warning);
+            counts.errors += new_errors + count(&file.persisting, DiagnosticSeverity::Error);
+            counts
+        })
+    }
+}
+
+/// Shell script for a hook that runs `program hook run`
+pub fn hook_script(program: &Path, kind: HookKind, thresholds: &HookThresholds) -> String {
+    format!(
+        "#!/bin/sh\n{HOOK_MARKER}; remove it with `lspbridge hook uninstall {kind}`\nexec {} hook run {kind} {}\n",
+        shell_quote(&program.to_string_lossy()),
+        thresholds.to_args().join(" ")
+    )
+}
+
+/// Write a hook into `hooks_dir`, returning its path
+///
+/// A hook lspbridge didn't write is only replaced with `force`.
+pub fn install_hook(hooks_dir: &Path, kind: HookKind, script: &str, force: bool) -> Result<PathBuf> {
+    let path = hooks_dir.join(kind.file_name());
+    if !force && path.exists() && !is_lspbridge_hook(&path) {
+        return Err(anyhow!(
+            "{} already exists and wasn't installed by lspbridge; pass --force to replace it",
+            path.display()
+        ));
+    }
+    fs::create_dir_all(hooks_dir)?;
+    fs::write(&path, script)?;
+    make_executable(&path)?;
+    Ok(path)
+}
+
+/// Remove a hook lspbridge wrote, returning whether there was one
+pub fn uninstall_hook(hooks_dir: &Path, kind: HookKind) -> Result<bool> {
+    let path = hooks_dir.join(kind.file_name());
+    if !path.exists() {
+        return Ok(false);
+    }
+    if !is_lspbridge_hook(&path) {
+        return Err(anyhow!("{} wasn't installed by lspbridge; leaving it in place", path.display()));
+    }
+    fs::remove_file(&path)?;
+    Ok(true)
+}
+
+fn is_lspbridge_hook(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|script| script.contains(HOOK_MARKER))
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_script_carries_thresholds() {
+        let thresholds = HookThresholds {
+            max_errors: Some(10),
+            ..HookThresholds::default()
+        };
+        let script = hook_script(Path::new("/opt/it's/lspbridge"), HookKind::PrePush, &thresholds);
+        assert!(script.starts_with("#!/bin/sh\n# Installed by `lspbridge hook install`"));
+        assert!(script.ends_with("exec '/opt/it'\\''s/lspbridge' hook run pre-push --max-new-errors 0 --max-errors 10\n"));
+    }
+
+    #[test]
+    fn test_install_only_replaces_own_hooks() -> Result<()> {
+        let dir = TempDir::new()?;
+        let script = hook_script(Path::new("lspbridge"), HookKind::PreCommit, &HookThresholds::default());
+        let path = install_hook(dir.path(), HookKind::PreCommit, &script, false)?;
+        install_hook(dir.path(), HookKind::PreCommit, &script, false)?;
+        assert!(uninstall_hook(dir.path(), HookKind::PreCommit)?);
+        assert!(!uninstall_hook(dir.path(), HookKind::PreCommit)?);
+
+        fs::write(&path, "#!/bin/sh\nmake lint\n")?;
+        assert!(install_hook(dir.path(), HookKind::PreCommit, &script, false).is_err());
+        assert!(uninstall_hook(dir.path(), HookKind::PreCommit).is_err());
+        install_hook(dir.path(), HookKind::PreCommit, &script, true)?;
+        assert!(is_lspbridge_hook(&path));
+        Ok(())
+    }
+
+    #[test]
+    fn test_violations() {
+        let thresholds = HookThresholds {
+            max_new_errors: 1,
+            max_new_warnings: Some(0),
+            max_errors: None,
+        };
+        let counts = HookCounts {
+            new_errors: 1,
+            new_warnings: 2,
+            errors: 40,
+        };
+        assert_eq!(thresholds.violations(&counts), vec!["2 new warnings (max 0)".to_string()]);
+        assert!(thresholds.violations(&HookCounts::default()).is_empty());
+    }
+}
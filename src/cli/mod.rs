@@ -1,18 +1,27 @@
 use anyhow::Result;
 use clap::Parser;
+use std::path::Path;
+
+use crate::core::config::UnifiedConfig;
+use crate::core::dynamic_config::loader::EnvLoader;
+use crate::core::{telemetry, NetworkAudit, NetworkGuard, NetworkMode};
 
 // Re-export command modules
 pub mod args;
 pub mod commands;
+pub mod hooks;
 pub mod multi_repo;
 
 // Re-export commonly used types
-pub use args::{Cli, Commands, OutputFormat, QueryOutputFormat};
+pub use args::{Cli, Commands, DiagnosticFilterArgs, OutputFormat, QueryOutputFormat};
 pub use multi_repo::{handle_multi_repo_command, MultiRepoCommand};
 
 use commands::{
-    ai_training::AITrainingCommand, config::ConfigCommand, export::ExportCommand,
-    history::HistoryCommand, query::QueryCommand, quick_fix::QuickFixCommand, watch::WatchCommand,
+    ai_training::AITrainingCommand, api::ApiCommand, baseline::BaselineCommand, breakers::BreakersCommand, config::ConfigCommand,
+    debt::DebtCommand, export::ExportCommand, graph::GraphCommand, hook::HookCommand,
+    history::HistoryCommand, import::ImportCommand, lsp_server::LspServerCommand, lsp_trace::LspTraceCommand, query::QueryCommand, quick_fix::QuickFixCommand,
+    report::ReportCommand, scan::ScanCommand, serve::ServeCommand, servers::ServersCommand, stats::StatsCommand, trust::TrustCommand,
+    watch::WatchCommand, whatif::WhatifCommand,
     Command,
 };
 
@@ -33,38 +42,130 @@ use commands::{
 /// ```
 pub async fn run_cli() -> Result<()> {
     let cli = Cli::parse();
+    let config = UnifiedConfig::load_or_default(Path::new("lspbridge.toml")).await;
+
+    // Deny network access before anything can reach out, telemetry export included
+    let offline = cli.offline || configured_network_mode(&config) == NetworkMode::Deny;
+    let guard = NetworkGuard::global();
+    if offline {
+        guard.set_mode(NetworkMode::Deny);
+    }
 
-    // Initialize logging
+    // Initialize logging, and OpenTelemetry export when configured
     let log_level = if cli.verbose { "debug" } else { "info" };
-    tracing_subscriber::fmt()
-        .with_env_filter(format!("lsp_bridge={log_level}"))
-        .init();
+    let metrics = EnvLoader::default()
+        .apply_env_overrides(config.as_ref().map(UnifiedConfig::to_dynamic_config).unwrap_or_default())
+        .metrics;
+    let _telemetry = telemetry::init(&metrics, &format!("lsp_bridge={log_level}"), guard)?;
+
+    if !offline {
+        return dispatch(cli.command).await;
+    }
+    let result = dispatch(cli.command).await;
+
+    let audit = guard.audit(std::env::args().collect(), result.is_ok());
+    let path = NetworkAudit::default_path();
+    match audit.append_to(&path) {
+        Ok(()) => tracing::debug!(
+            "Offline run recorded in {} ({} network call(s) blocked)",
+            path.display(),
+            audit.blocked
+        ),
+        Err(e) => eprintln!("Warning: failed to record offline run: {e}"),
+    }
+    result
+}
+
+/// `[network] mode` from `lspbridge.toml`
+///
+/// A config that fails to load leaves network access as the flag set it;
+/// the command itself reports the error when it loads the config.
+fn configured_network_mode(config: &Result<UnifiedConfig>) -> NetworkMode {
+    match config {
+        Ok(config) => config.network.mode,
+        Err(e) => {
+            tracing::debug!("Could not read network mode from lspbridge.toml: {}", e);
+            NetworkMode::Allow
+        }
+    }
+}
 
-    // Route to appropriate command handler
-    match cli.command {
+/// Route a parsed command to its handler
+async fn dispatch(command: Commands) -> Result<()> {
+    match command {
         Commands::Export {
             format,
             output,
-            errors_only,
-            warnings_and_errors,
-            files,
-            exclude,
+            out_dir,
+            filter,
             max_results,
             include_context,
             context_lines,
             privacy,
+            audit_log,
+            triage,
+            mute_noise,
+            noise_after_days,
+            model,
+            max_tokens,
+            as_of,
+            api_surface,
+            crash_log,
+            preview_redaction,
+            preview_sample,
+            preview_style,
+            fleet,
+            stable,
+            route,
+            file,
+            lines,
+            compress,
+            bundle,
+            parquet,
+            row_group_size,
+            all_history,
+            dedupe,
+            no_baseline,
+            input,
+            watch,
+            debounce_ms,
         } => {
             let args = args::ExportArgs {
-                format,
+                formats: format,
                 output,
-                errors_only,
-                warnings_and_errors,
-                files,
-                exclude,
+                out_dir,
+                filter: *filter,
                 max_results,
                 include_context,
                 context_lines,
                 privacy,
+                audit_log,
+                triage,
+                mute_noise,
+                noise_after_days,
+                model,
+                max_tokens,
+                as_of,
+                api_surface,
+                crash_log,
+                preview_redaction,
+                preview_sample,
+                preview_style,
+                fleet,
+                stable,
+                route,
+                file,
+                lines,
+                compress,
+                bundle,
+                parquet,
+                row_group_size,
+                all_history,
+                dedupe,
+                no_baseline,
+                input,
+                watch,
+                debounce_ms,
             };
             ExportCommand::new(args).execute().await
         }
@@ -74,12 +175,16 @@ pub async fn run_cli() -> Result<()> {
             interval,
             errors_only,
             privacy,
+            privacy_config,
+            refresh_stale_days,
         } => {
             let args = args::WatchArgs {
                 format,
                 interval,
                 errors_only,
                 privacy,
+                privacy_config,
+                refresh_stale_days,
             };
             WatchCommand::new(args).execute().await
         }
@@ -89,24 +194,154 @@ pub async fn run_cli() -> Result<()> {
             format,
             output,
             interactive,
+            tui,
+            lenses,
+            filter,
+            dedupe,
+            no_baseline,
         } => {
             let args = args::QueryArgs {
                 query,
                 format,
                 output,
                 interactive,
+                tui,
+                lenses,
+                filter,
+                dedupe,
+                no_baseline,
             };
             QueryCommand::new(args).execute().await
         }
 
         Commands::History { action } => HistoryCommand::new(action).execute().await,
 
+        Commands::LspTrace { action } => LspTraceCommand::new(action).execute().await,
+
+        Commands::LspServer {
+            input,
+            root,
+            privacy,
+            interval,
+            no_scan,
+        } => {
+            let args = args::LspServerArgs {
+                input,
+                root,
+                privacy,
+                interval,
+                no_scan,
+            };
+            LspServerCommand::new(args).execute().await
+        }
+
+        Commands::Report { action } => ReportCommand::new(action).execute().await,
+
         Commands::AITraining { action } => AITrainingCommand::new(action).execute().await,
 
         Commands::QuickFix { action } => QuickFixCommand::new(action).execute().await,
 
         Commands::Config { action } => ConfigCommand::new(action).execute().await,
 
+        Commands::Breakers { action } => BreakersCommand::new(action).execute().await,
+
+        Commands::Api { action } => ApiCommand::new(action).execute().await,
+
+        Commands::Scan {
+            path,
+            output,
+            no_imports,
+            no_todos,
+            no_security,
+            todo_max_age_days,
+        } => {
+            let args = args::ScanArgs {
+                path,
+                output,
+                no_imports,
+                no_todos,
+                no_security,
+                todo_max_age_days,
+            };
+            ScanCommand::new(args).execute().await
+        }
+
+        Commands::Import {
+            input,
+            format,
+            mapping,
+            source,
+            output,
+            record,
+        } => {
+            let args = args::ImportArgs {
+                input,
+                format,
+                mapping,
+                source,
+                output,
+                record,
+            };
+            ImportCommand::new(args).execute().await
+        }
+
+        Commands::Serve {
+            path,
+            servers,
+            open_limit,
+            interval,
+            privacy,
+            grpc,
+            http,
+            access_file,
+            openapi,
+        } => {
+            let args = args::ServeArgs {
+                path,
+                servers,
+                open_limit,
+                interval,
+                privacy,
+                grpc,
+                http,
+                access_file,
+                openapi,
+            };
+            ServeCommand::new(args).execute().await
+        }
+
+        Commands::Stats {
+            path,
+            no_tools,
+            tool_timeout,
+            format,
+        } => {
+            let args = args::StatsArgs {
+                path,
+                no_tools,
+                tool_timeout,
+                format,
+            };
+            StatsCommand::new(args).execute().await
+        }
+
+        Commands::Whatif {
+            apply_threshold,
+            format,
+        } => WhatifCommand::new(apply_threshold, format).execute().await,
+
+        Commands::Trust { path, revoke, list } => TrustCommand::new(path, revoke, list).execute().await,
+
+        Commands::Graph { action } => GraphCommand::new(action).execute().await,
+
+        Commands::Servers { action } => ServersCommand::new(action).execute().await,
+
+        Commands::Debt { action } => DebtCommand::new(action).execute().await,
+
+        Commands::Baseline { action } => BaselineCommand::new(action).execute().await,
+
+        Commands::Hook { action } => HookCommand::new(action).execute().await,
+
         Commands::MultiRepo { command } => handle_multi_repo_command(command, None).await,
     }
 }
\ No newline at end of file
@@ -3,13 +3,21 @@
 //! This module contains the implementation of all multi-repository command handlers,
 //! including repository registration, listing, analysis, and team management.
 
-use super::types::{MultiRepoCommand, OutputFormat, RelationTypeArg, TeamCommand};
+use super::types::{
+    ConflictStrategyArg, FleetCommand, MultiRepoCommand, OutputFormat, RelationTypeArg, SyncModeArg,
+    TeamCommand,
+};
+use super::workspace::{
+    ConflictChoice, ConflictResolver, ConflictStrategy, SyncConflict, WorkspaceSynchronizer, CONFLICT_LOG,
+};
 use anyhow::{Context, Result};
 use colored::Colorize;
+use std::io::{BufRead, Write};
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use uuid::Uuid;
 
-use crate::multi_repo::{MultiRepoContext, RepositoryInfo};
+use crate::multi_repo::{tag_matches, Fleet, MultiRepoContext, RepositoryInfo, Shard};
 use crate::project::BuildSystemDetector;
 use crate::security::validate_path;
 
@@ -36,16 +44,24 @@ pub async fn handle_multi_repo_command(
             handle_register(&mut context, path, name, remote_url, language, tags).await?;
         }
 
-        MultiRepoCommand::List { all, tag, format } => {
-            handle_list(&context, all, tag, format).await?;
+        MultiRepoCommand::List {
+            all,
+            tag,
+            fleet,
+            format,
+        } => {
+            handle_list(&context, all, tag, fleet, format).await?;
         }
 
         MultiRepoCommand::Analyze {
             min_impact,
             output,
             format,
+            fleet,
+            shard,
+            resume,
         } => {
-            handle_analyze(&mut context, min_impact, output, format).await?;
+            handle_analyze(&mut context, min_impact, output, format, fleet, shard, resume).await?;
         }
 
         MultiRepoCommand::DetectMonorepo { path, register } => {
@@ -68,6 +84,20 @@ pub async fn handle_multi_repo_command(
         MultiRepoCommand::Types { format } => {
             handle_types(&mut context, format).await?;
         }
+
+        MultiRepoCommand::Sync {
+            workspace,
+            repos,
+            fleet,
+            mode,
+            strategy,
+        } => {
+            handle_sync(&context, workspace, repos, fleet, mode, strategy).await?;
+        }
+
+        MultiRepoCommand::Fleet { command } => {
+            handle_fleet_command(&context, command).await?;
+        }
     }
 
     Ok(())
@@ -75,7 +105,7 @@ pub async fn handle_multi_repo_command(
 
 /// Handle repository registration
 pub async fn handle_register(
-    _context: &mut MultiRepoContext,
+    context: &mut MultiRepoContext,
     path: PathBuf,
     name: Option<String>,
     remote_url: Option<String>,
@@ -117,17 +147,13 @@ pub async fn handle_register(
         build_system,
         is_monorepo_member: false,
         monorepo_id: None,
-        tags: tags
-            .map(|t| t.split(',').map(|s| s.trim().to_string()).collect())
-            .unwrap_or_default(),
+        tags: split_list(tags),
         active: true,
         last_diagnostic_run: None,
         metadata: serde_json::json!({}),
     };
 
-    // TODO: Implement actual registration
-    // context.register_repo(info).await?;
-    let _ = info; // Placeholder
+    context.register_repo(info).await?;
 
     println!(
         "{} Repository '{}' registered successfully",
@@ -142,28 +168,38 @@ pub async fn handle_register(
 
 /// Handle repository listing
 pub async fn handle_list(
-    _context: &MultiRepoContext,
-    _all: bool,
-    _tag: Option<String>,
+    context: &MultiRepoContext,
+    all: bool,
+    tag: Option<String>,
+    fleet: Option<String>,
     format: OutputFormat,
 ) -> Result<()> {
-    println!("{} Listing repositories...", "→".blue());
+    let mut repos = match &fleet {
+        Some(fleet) => context.registry().fleet_members(fleet).await?,
+        None => context.list_repositories(all).await?,
+    };
+    if let Some(tag) = &tag {
+        repos.retain(|repo| repo.tags.iter().any(|t| tag_matches(t, tag)));
+    }
 
     match format {
-        OutputFormat::Table => {
-            println!("{}", "┌─────────────────────────────────────────────────────┐".bright_black());
-            println!("{}", "│                Repository List                     │".bright_black());
-            println!("{}", "├─────────────────────────────────────────────────────┤".bright_black());
-            println!("{}", "│ ID        │ Name      │ Language  │ Status     │".bright_black());
-            println!("{}", "├─────────────────────────────────────────────────────┤".bright_black());
-            println!("{}", "│ (none)    │ (none)    │ (none)    │ (none)     │".bright_black());
-            println!("{}", "└─────────────────────────────────────────────────────┘".bright_black());
-        }
+        OutputFormat::Table => display_repositories_table(&repos),
         OutputFormat::Json => {
-            println!("{{\"repositories\": []}}");
+            let json = serde_json::json!({ "repositories": repos });
+            println!("{}", serde_json::to_string_pretty(&json)?);
         }
         OutputFormat::Csv => {
-            println!("id,name,language,status");
+            println!("id,name,language,tags,status");
+            for repo in &repos {
+                println!(
+                    "{},{},{},{},{}",
+                    repo.id,
+                    repo.name,
+                    repo.primary_language.as_deref().unwrap_or(""),
+                    repo.tags.join(";"),
+                    if repo.active { "active" } else { "inactive" }
+                );
+            }
         }
     }
 
@@ -172,19 +208,44 @@ pub async fn handle_list(
 
 /// Handle diagnostic analysis across repositories
 pub async fn handle_analyze(
-    _context: &mut MultiRepoContext,
+    context: &mut MultiRepoContext,
     min_impact: f32,
     output: Option<PathBuf>,
     format: OutputFormat,
+    fleet: Option<String>,
+    shard: Option<Shard>,
+    resume: bool,
 ) -> Result<()> {
     println!(
-        "{} Analyzing cross-repository diagnostics (min impact: {})",
+        "{} Analyzing cross-repository diagnostics{}{} (min impact: {})",
         "→".blue(),
+        fleet.as_deref().map(|f| format!(" in fleet '{f}'")).unwrap_or_default(),
+        shard.map(|s| format!(", shard {s}")).unwrap_or_default(),
         min_impact
     );
 
-    // TODO: Implement actual analysis
-    let diagnostics = Vec::new(); // Placeholder
+    let run = context.analyze_checkpointed(fleet.as_deref(), shard, resume).await?;
+    if run.resumed > 0 {
+        println!(
+            "{} Resumed {} repositories from the last run, analyzed {}",
+            "→".blue(),
+            run.resumed,
+            run.analyzed
+        );
+    }
+    for (repo_id, error) in &run.failed {
+        eprintln!("{} Failed to analyze {}: {}", "✗".red(), repo_id, error);
+    }
+    if !run.failed.is_empty() {
+        eprintln!(
+            "{} {} repositories failed; rerun with --resume to retry only those",
+            "!".yellow(),
+            run.failed.len()
+        );
+    }
+
+    let mut diagnostics = run.diagnostics;
+    diagnostics.retain(|d| d.cross_repo_impact >= min_impact);
 
     match format {
         OutputFormat::Table => {
@@ -194,8 +255,11 @@ pub async fn handle_analyze(
             let json = serde_json::json!({
                 "diagnostics": diagnostics,
                 "analysis_config": {
-                    "min_impact": min_impact
-                }
+                    "min_impact": min_impact,
+                    "fleet": fleet,
+                    "shard": shard.map(|s| s.to_string())
+                },
+                "failed_repositories": run.failed.iter().map(|(repo_id, _)| repo_id).collect::<Vec<_>>()
             });
             println!("{}", serde_json::to_string_pretty(&json)?);
         }
@@ -423,6 +487,218 @@ pub async fn handle_types(_context: &mut MultiRepoContext, format: OutputFormat)
     Ok(())
 }
 
+/// Handle workspace synchronization
+pub async fn handle_sync(
+    context: &MultiRepoContext,
+    workspace: PathBuf,
+    repos: Option<String>,
+    fleet: Option<String>,
+    mode: SyncModeArg,
+    strategy: Option<ConflictStrategyArg>,
+) -> Result<()> {
+    let resolver: Option<Arc<dyn ConflictResolver>> = match strategy {
+        Some(strategy) => Some(Arc::new(ConflictStrategy::from(strategy))),
+        None if atty::is(atty::Stream::Stdin) => Some(Arc::new(PromptConflictResolver::default())),
+        None => None,
+    };
+
+    let mut synchronizer = WorkspaceSynchronizer::new(workspace.clone()).with_sync_mode(mode.into());
+    if let Some(fleet) = &fleet {
+        let members = context.registry().fleet_members(fleet).await?;
+        if members.is_empty() {
+            println!("{} Fleet '{}' has no active repositories", "!".yellow(), fleet);
+            return Ok(());
+        }
+        synchronizer = synchronizer.with_repositories(members.into_iter().map(|r| r.id).collect());
+    } else if repos.is_some() {
+        synchronizer = synchronizer.with_repositories(split_list(repos));
+    }
+    if let Some(resolver) = resolver {
+        synchronizer = synchronizer.with_conflict_resolver(resolver);
+    }
+
+    println!("{} Synchronizing workspace {}...", "→".blue(), workspace.display());
+    let result = synchronizer.synchronize_workspace(context).await?;
+    let stats = &result.sync_statistics;
+
+    println!(
+        "{} Synced {} repositories ({} files)",
+        "✓".green(),
+        stats.successful_syncs,
+        stats.total_files_synced
+    );
+    for failed in &result.failed_repos {
+        println!("  {} {}: {}", "✗".red(), failed.repository_id, failed.error);
+    }
+    if stats.conflicts_resolved + stats.conflicts_unresolved > 0 {
+        println!(
+            "{} {} conflicts resolved, {} unresolved (log: {})",
+            "!".yellow(),
+            stats.conflicts_resolved,
+            stats.conflicts_unresolved,
+            workspace.join("logs").join(CONFLICT_LOG).display()
+        );
+    }
+    for warning in &result.warnings {
+        println!("  {} {}", "!".yellow(), warning);
+    }
+
+    Ok(())
+}
+
+/// Handle fleet management commands
+pub async fn handle_fleet_command(context: &MultiRepoContext, command: FleetCommand) -> Result<()> {
+    let registry = context.registry();
+
+    match command {
+        FleetCommand::Save {
+            name,
+            tags,
+            repos,
+            description,
+        } => {
+            let fleet = Fleet {
+                name: name.clone(),
+                description,
+                tags: split_list(tags),
+                repositories: split_list(repos),
+            };
+            if fleet.tags.is_empty() && fleet.repositories.is_empty() {
+                anyhow::bail!("A fleet needs at least one tag (--tags) or repository (--repos)");
+            }
+            registry.save_fleet(&fleet).await?;
+            let members = registry.fleet_members(&name).await?;
+            println!(
+                "{} Fleet '{}' saved ({} repositories)",
+                "✓".green(),
+                name,
+                members.len()
+            );
+        }
+
+        FleetCommand::List { format } => {
+            let fleets = registry.list_fleets().await?;
+            match format {
+                OutputFormat::Table => {
+                    if fleets.is_empty() {
+                        println!("No fleets saved");
+                    }
+                    for fleet in &fleets {
+                        println!("{}", fleet.name.bold());
+                        if let Some(description) = &fleet.description {
+                            println!("  {description}");
+                        }
+                        if !fleet.tags.is_empty() {
+                            println!("  tags: {}", fleet.tags.join(", "));
+                        }
+                        if !fleet.repositories.is_empty() {
+                            println!("  repositories: {}", fleet.repositories.join(", "));
+                        }
+                    }
+                }
+                OutputFormat::Json => {
+                    let json = serde_json::json!({ "fleets": fleets });
+                    println!("{}", serde_json::to_string_pretty(&json)?);
+                }
+                OutputFormat::Csv => {
+                    println!("name,description,tags,repositories");
+                    for fleet in &fleets {
+                        println!(
+                            "{},{},{},{}",
+                            fleet.name,
+                            fleet.description.as_deref().unwrap_or(""),
+                            fleet.tags.join(";"),
+                            fleet.repositories.join(";")
+                        );
+                    }
+                }
+            }
+        }
+
+        FleetCommand::Show { name, format } => {
+            handle_list(context, false, None, Some(name), format).await?;
+        }
+
+        FleetCommand::Delete { name } => {
+            if registry.delete_fleet(&name).await? {
+                println!("{} Fleet '{}' deleted", "✓".green(), name);
+            } else {
+                anyhow::bail!("Unknown fleet '{name}'");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Split a comma-separated argument, dropping empty entries
+fn split_list(list: Option<String>) -> Vec<String> {
+    list.map(|list| {
+        list.split(',')
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// Asks on the terminal how to resolve each sync conflict
+#[derive(Default)]
+pub struct PromptConflictResolver {
+    /// Choice applied to all remaining conflicts
+    remembered: Mutex<Option<ConflictChoice>>,
+}
+
+impl ConflictResolver for PromptConflictResolver {
+    fn name(&self) -> String {
+        "interactive".to_string()
+    }
+
+    fn resolve(&self, conflict: &SyncConflict) -> Result<Option<ConflictChoice>> {
+        let mut remembered = self.remembered.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(choice) = *remembered {
+            return Ok(Some(choice));
+        }
+
+        eprintln!(
+            "{} Conflict in {}/{}",
+            "!".yellow(),
+            conflict.repository_name,
+            conflict.relative_path.display()
+        );
+        eprintln!(
+            "  workspace modified {}, repository modified {}",
+            conflict.workspace_modified.format("%Y-%m-%d %H:%M:%S"),
+            conflict.source_modified.format("%Y-%m-%d %H:%M:%S")
+        );
+
+        let stdin = std::io::stdin();
+        loop {
+            eprint!("  Keep [o]urs, take [t]heirs, [s]kip, or O/T for all remaining? ");
+            std::io::stderr().flush()?;
+
+            let mut answer = String::new();
+            if stdin.lock().read_line(&mut answer)? == 0 {
+                return Ok(None);
+            }
+            match answer.trim() {
+                "o" => return Ok(Some(ConflictChoice::Ours)),
+                "t" => return Ok(Some(ConflictChoice::Theirs)),
+                "s" | "" => return Ok(None),
+                "O" => {
+                    *remembered = Some(ConflictChoice::Ours);
+                    return Ok(Some(ConflictChoice::Ours));
+                }
+                "T" => {
+                    *remembered = Some(ConflictChoice::Theirs);
+                    return Ok(Some(ConflictChoice::Theirs));
+                }
+                _ => eprintln!("  Please answer o, t, s, O or T"),
+            }
+        }
+    }
+}
+
 /// Detect the primary programming language of a repository
 pub async fn detect_primary_language(path: &PathBuf) -> Option<String> {
     use std::collections::HashMap;
@@ -463,6 +739,29 @@ pub async fn detect_primary_language(path: &PathBuf) -> Option<String> {
         .map(|(language, _)| language)
 }
 
+/// Display repositories in a formatted table
+fn display_repositories_table(repos: &[RepositoryInfo]) {
+    if repos.is_empty() {
+        println!("No repositories found");
+        return;
+    }
+
+    println!(
+        "{}",
+        format!("{:<36}  {:<20}  {:<12}  {:<8}  TAGS", "ID", "NAME", "LANGUAGE", "STATUS").bold()
+    );
+    for repo in repos {
+        println!(
+            "{:<36}  {:<20}  {:<12}  {:<8}  {}",
+            repo.id,
+            repo.name.chars().take(20).collect::<String>(),
+            repo.primary_language.as_deref().unwrap_or("-"),
+            if repo.active { "active" } else { "inactive" },
+            repo.tags.join(", ")
+        );
+    }
+}
+
 /// Display diagnostics in a formatted table
 pub fn display_diagnostics_table(diagnostics: &[crate::multi_repo::AggregatedDiagnostic]) {
     if diagnostics.is_empty() {
@@ -62,14 +62,22 @@ pub use handlers::{
 
 pub use types::{
     AssignmentStatusArg,
+    ConflictStrategyArg,
+    FleetCommand,
     MultiRepoCommand,
     PriorityArg,
     RelationTypeArg,
+    SyncModeArg,
     TeamCommand,
     TeamRoleArg
 };
 
 pub use workspace::{
+    ConflictChoice,
+    ConflictRecord,
+    ConflictResolver,
+    ConflictStrategy,
+    SyncConflict,
     WorkspaceIndex,
     WorkspaceSynchronizer,
     WorkspaceSyncConfig,
@@ -104,6 +112,7 @@ use std::path::PathBuf;
 /// let cmd = MultiRepoCommand::List {
 ///     all: false,
 ///     tag: None,
+///     fleet: None,
 ///     format: crate::cli::multi_repo::OutputFormat::Table,
 /// };
 ///
@@ -351,6 +360,9 @@ mod tests {
             min_impact: 1.5, // Invalid value > 1.0
             output: None,
             format: types::OutputFormat::Table,
+            fleet: None,
+            shard: None,
+            resume: false,
         };
 
         assert!(utils::validate_command_args(&invalid_cmd).is_err());
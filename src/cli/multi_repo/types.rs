@@ -26,7 +26,7 @@ pub enum MultiRepoCommand {
         #[arg(short, long)]
         language: Option<String>,
 
-        /// Tags (comma-separated)
+        /// Tags (comma-separated); use `/` for hierarchy, e.g. `team/payments`
         #[arg(short, long)]
         tags: Option<String>,
     },
@@ -37,10 +37,14 @@ pub enum MultiRepoCommand {
         #[arg(short, long)]
         all: bool,
 
-        /// Filter by tag
+        /// Filter by tag, including tags below it (`team` matches `team/payments`)
         #[arg(short, long)]
         tag: Option<String>,
 
+        /// Only list repositories in a saved fleet
+        #[arg(long)]
+        fleet: Option<String>,
+
         /// Output format
         #[arg(short, long, value_enum, default_value = "table")]
         format: OutputFormat,
@@ -59,6 +63,19 @@ pub enum MultiRepoCommand {
         /// Output format
         #[arg(short, long, value_enum, default_value = "table")]
         format: OutputFormat,
+
+        /// Only analyze repositories in a saved fleet
+        #[arg(long)]
+        fleet: Option<String>,
+
+        /// Only analyze one slice of the repositories, as INDEX/COUNT (e.g. 2/4),
+        /// to split the work across CI jobs
+        #[arg(long)]
+        shard: Option<crate::multi_repo::Shard>,
+
+        /// Skip repositories finished by the last run of the same fleet and shard
+        #[arg(long)]
+        resume: bool,
     },
 
     /// Detect monorepo structure
@@ -101,6 +118,80 @@ pub enum MultiRepoCommand {
         #[arg(short, long, value_enum, default_value = "table")]
         format: OutputFormat,
     },
+
+    /// Synchronize registered repositories into a shared workspace
+    Sync {
+        /// Workspace directory
+        #[arg(default_value = ".lspbridge/workspace")]
+        workspace: PathBuf,
+
+        /// Repository IDs or names to sync (comma-separated, default: all)
+        #[arg(short, long)]
+        repos: Option<String>,
+
+        /// Only sync repositories in a saved fleet
+        #[arg(long, conflicts_with = "repos")]
+        fleet: Option<String>,
+
+        /// Synchronization mode
+        #[arg(short, long, value_enum, default_value = "incremental")]
+        mode: SyncModeArg,
+
+        /// Resolve conflicts without prompting
+        #[arg(short, long, value_enum)]
+        strategy: Option<ConflictStrategyArg>,
+    },
+
+    /// Manage saved fleets (named sets of repositories)
+    Fleet {
+        #[command(subcommand)]
+        command: FleetCommand,
+    },
+}
+
+/// Fleet sub-commands
+#[derive(Debug, Subcommand)]
+pub enum FleetCommand {
+    /// Save a fleet, replacing an existing one with the same name
+    Save {
+        /// Fleet name, e.g. `payments-services`
+        name: String,
+
+        /// Include repositories with these tags or tags below them (comma-separated)
+        #[arg(short, long)]
+        tags: Option<String>,
+
+        /// Include these repository IDs or names (comma-separated)
+        #[arg(short, long)]
+        repos: Option<String>,
+
+        /// Description
+        #[arg(short, long)]
+        description: Option<String>,
+    },
+
+    /// List saved fleets
+    List {
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Show the repositories in a fleet
+    Show {
+        /// Fleet name
+        name: String,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Delete a fleet
+    Delete {
+        /// Fleet name
+        name: String,
+    },
 }
 
 /// Team collaboration sub-commands
@@ -191,6 +282,25 @@ pub enum OutputFormat {
     Csv,
 }
 
+/// Workspace synchronization modes
+#[derive(Debug, Clone, clap::ValueEnum)]
+pub enum SyncModeArg {
+    Full,
+    Incremental,
+    Symlinks,
+}
+
+/// Conflict resolution strategies for `sync`
+#[derive(Debug, Clone, clap::ValueEnum)]
+pub enum ConflictStrategyArg {
+    /// Keep workspace edits
+    Ours,
+    /// Take the repository version
+    Theirs,
+    /// Keep whichever was modified last
+    Newest,
+}
+
 /// Repository relationship types
 #[derive(Debug, Clone, clap::ValueEnum)]
 pub enum RelationTypeArg {
@@ -241,6 +351,26 @@ impl From<RelationTypeArg> for crate::multi_repo::registry::RelationType {
     }
 }
 
+impl From<SyncModeArg> for super::workspace::SyncMode {
+    fn from(arg: SyncModeArg) -> Self {
+        match arg {
+            SyncModeArg::Full => super::workspace::SyncMode::Full,
+            SyncModeArg::Incremental => super::workspace::SyncMode::Incremental,
+            SyncModeArg::Symlinks => super::workspace::SyncMode::SymbolicLinks,
+        }
+    }
+}
+
+impl From<ConflictStrategyArg> for super::workspace::ConflictStrategy {
+    fn from(arg: ConflictStrategyArg) -> Self {
+        match arg {
+            ConflictStrategyArg::Ours => super::workspace::ConflictStrategy::Ours,
+            ConflictStrategyArg::Theirs => super::workspace::ConflictStrategy::Theirs,
+            ConflictStrategyArg::Newest => super::workspace::ConflictStrategy::Newest,
+        }
+    }
+}
+
 impl From<TeamRoleArg> for crate::multi_repo::collaboration::TeamRole {
     fn from(arg: TeamRoleArg) -> Self {
         match arg {
@@ -6,7 +6,10 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tracing::{info, warn};
 
 use crate::multi_repo::{MultiRepoContext, RepositoryInfo};
 
@@ -23,6 +26,9 @@ pub struct WorkspaceSynchronizer {
     
     /// Sync configuration
     config: WorkspaceSyncConfig,
+
+    /// Decides conflicts between local workspace edits and repository changes
+    resolver: Option<Arc<dyn ConflictResolver>>,
 }
 
 impl WorkspaceSynchronizer {
@@ -33,6 +39,7 @@ impl WorkspaceSynchronizer {
             sync_mode: SyncMode::Incremental,
             repositories: Vec::new(),
             config: WorkspaceSyncConfig::default(),
+            resolver: None,
         }
     }
 
@@ -54,6 +61,13 @@ impl WorkspaceSynchronizer {
         self
     }
 
+    /// Resolve sync conflicts with a strategy or an interactive prompt.
+    /// Without a resolver, conflicting workspace files are kept and reported.
+    pub fn with_conflict_resolver(mut self, resolver: Arc<dyn ConflictResolver>) -> Self {
+        self.resolver = Some(resolver);
+        self
+    }
+
     /// Synchronize workspace with registered repositories
     pub async fn synchronize_workspace(
         &self,
@@ -109,6 +123,19 @@ impl WorkspaceSynchronizer {
             .map(|r| r.size_synced)
             .sum();
 
+        for conflict in sync_result.synchronized_repos.iter().flat_map(|r| &r.conflicts) {
+            if conflict.choice.is_some() {
+                sync_result.sync_statistics.conflicts_resolved += 1;
+            } else {
+                sync_result.sync_statistics.conflicts_unresolved += 1;
+                sync_result.warnings.push(format!(
+                    "Kept locally modified {} in {}; pass --strategy to resolve",
+                    conflict.relative_path.display(),
+                    conflict.repository_id
+                ));
+            }
+        }
+
         Ok(sync_result)
     }
 
@@ -127,6 +154,7 @@ impl WorkspaceSynchronizer {
             size_synced: 0,
             sync_mode: self.sync_mode.clone(),
             last_sync: chrono::Utc::now(),
+            conflicts: Vec::new(),
         };
 
         match self.sync_mode {
@@ -143,6 +171,7 @@ impl WorkspaceSynchronizer {
 
         // Create repository metadata
         self.create_repository_metadata(repo, &repo_workspace_path).await?;
+        self.save_sync_metadata(&sync_result).await?;
 
         Ok(sync_result)
     }
@@ -154,6 +183,9 @@ impl WorkspaceSynchronizer {
         workspace_path: &Path,
         mut sync_result: RepositorySyncResult,
     ) -> Result<RepositorySyncResult> {
+        // Local edits are detected against the previous sync
+        let last_sync = self.get_last_sync_timestamp(&repo.id).await?;
+
         // Copy files based on configuration
         let source_patterns = &self.config.include_patterns;
         let exclude_patterns = &self.config.exclude_patterns;
@@ -162,24 +194,8 @@ impl WorkspaceSynchronizer {
             let matches = self.find_matching_files(&repo.path, pattern, exclude_patterns).await?;
             
             for source_file in matches {
-                let relative_path = source_file.strip_prefix(&repo.path)?;
-                let target_file = workspace_path.join(relative_path);
-
-                // Ensure target directory exists
-                if let Some(parent) = target_file.parent() {
-                    fs::create_dir_all(parent).await?;
-                }
-
-                // Copy file
-                fs::copy(&source_file, &target_file).await
-                    .context("Failed to copy file to workspace")?;
-
-                sync_result.files_synced += 1;
-                
-                // Update size
-                if let Ok(metadata) = fs::metadata(&source_file).await {
-                    sync_result.size_synced += metadata.len();
-                }
+                self.sync_file(repo, &source_file, workspace_path, last_sync, &mut sync_result)
+                    .await?;
             }
         }
 
@@ -209,20 +225,8 @@ impl WorkspaceSynchronizer {
                         let modified_time = chrono::DateTime::<chrono::Utc>::from(modified);
                         
                         if modified_time > last_sync {
-                            let relative_path = source_file.strip_prefix(&repo.path)?;
-                            let target_file = workspace_path.join(relative_path);
-
-                            // Ensure target directory exists
-                            if let Some(parent) = target_file.parent() {
-                                fs::create_dir_all(parent).await?;
-                            }
-
-                            // Copy file
-                            fs::copy(&source_file, &target_file).await
-                                .context("Failed to copy file to workspace")?;
-
-                            sync_result.files_synced += 1;
-                            sync_result.size_synced += metadata.len();
+                            self.sync_file(repo, &source_file, workspace_path, last_sync, &mut sync_result)
+                                .await?;
                         }
                     }
                 }
@@ -232,6 +236,130 @@ impl WorkspaceSynchronizer {
         Ok(sync_result)
     }
 
+    /// Copy one repository file into the workspace, resolving conflicts with
+    /// workspace copies edited since the last sync
+    async fn sync_file(
+        &self,
+        repo: &RepositoryInfo,
+        source_file: &Path,
+        workspace_path: &Path,
+        last_sync: chrono::DateTime<chrono::Utc>,
+        sync_result: &mut RepositorySyncResult,
+    ) -> Result<()> {
+        let relative_path = source_file.strip_prefix(&repo.path)?;
+        let target_file = workspace_path.join(relative_path);
+
+        if let Some(conflict) = self
+            .detect_conflict(repo, relative_path, source_file, &target_file, last_sync)
+            .await?
+        {
+            let record = self.resolve_conflict(&conflict).await?;
+            let take_source = record.choice == Some(ConflictChoice::Theirs);
+            sync_result.conflicts.push(record);
+            if !take_source {
+                return Ok(());
+            }
+        }
+
+        // Ensure target directory exists
+        if let Some(parent) = target_file.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        // Copy file
+        fs::copy(source_file, &target_file).await
+            .context("Failed to copy file to workspace")?;
+
+        sync_result.files_synced += 1;
+        
+        // Update size
+        if let Ok(metadata) = fs::metadata(source_file).await {
+            sync_result.size_synced += metadata.len();
+        }
+
+        Ok(())
+    }
+
+    /// A workspace file conflicts when it was modified after the last sync and
+    /// no longer matches the repository file
+    async fn detect_conflict(
+        &self,
+        repo: &RepositoryInfo,
+        relative_path: &Path,
+        source_file: &Path,
+        target_file: &Path,
+        last_sync: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<SyncConflict>> {
+        let Ok(target_metadata) = fs::metadata(target_file).await else {
+            return Ok(None);
+        };
+        let workspace_modified = chrono::DateTime::<chrono::Utc>::from(target_metadata.modified()?);
+        if workspace_modified <= last_sync {
+            return Ok(None);
+        }
+        if fs::read(source_file).await? == fs::read(target_file).await? {
+            return Ok(None);
+        }
+
+        let source_modified = chrono::DateTime::<chrono::Utc>::from(
+            fs::metadata(source_file).await?.modified()?,
+        );
+        Ok(Some(SyncConflict {
+            repository_id: repo.id.clone(),
+            repository_name: repo.name.clone(),
+            relative_path: relative_path.to_path_buf(),
+            workspace_modified,
+            source_modified,
+        }))
+    }
+
+    /// Ask the resolver about a conflict and append the decision to the conflict log
+    async fn resolve_conflict(&self, conflict: &SyncConflict) -> Result<ConflictRecord> {
+        let (choice, decided_by) = match &self.resolver {
+            Some(resolver) => (resolver.resolve(conflict)?, resolver.name()),
+            None => (None, "none".to_string()),
+        };
+
+        let record = ConflictRecord {
+            timestamp: chrono::Utc::now(),
+            repository_id: conflict.repository_id.clone(),
+            relative_path: conflict.relative_path.clone(),
+            workspace_modified: conflict.workspace_modified,
+            source_modified: conflict.source_modified,
+            choice,
+            decided_by,
+        };
+
+        match record.choice {
+            Some(choice) => info!(
+                "Sync conflict in {}/{} resolved as {:?} by {}",
+                conflict.repository_name,
+                conflict.relative_path.display(),
+                choice,
+                record.decided_by
+            ),
+            None => warn!(
+                "Sync conflict in {}/{} left unresolved; keeping workspace copy",
+                conflict.repository_name,
+                conflict.relative_path.display()
+            ),
+        }
+
+        let log_path = self.workspace_root.join("logs").join(CONFLICT_LOG);
+        let mut log = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)
+            .await
+            .context("Failed to open sync conflict log")?;
+        let mut line = serde_json::to_string(&record)?;
+        line.push('\n');
+        log.write_all(line.as_bytes()).await
+            .context("Failed to write sync conflict log")?;
+
+        Ok(record)
+    }
+
     /// Perform symbolic link synchronization
     async fn perform_symlink_sync(
         &self,
@@ -402,6 +530,24 @@ impl WorkspaceSynchronizer {
         Ok(())
     }
 
+    /// Record when a repository was last synchronized
+    async fn save_sync_metadata(&self, sync_result: &RepositorySyncResult) -> Result<()> {
+        let metadata = RepositorySyncMetadata {
+            repository_id: sync_result.repository_id.clone(),
+            last_sync: chrono::Utc::now(),
+            files_synced: sync_result.files_synced,
+            sync_mode: sync_result.sync_mode.clone(),
+        };
+
+        let metadata_dir = self.workspace_root.join("metadata");
+        fs::create_dir_all(&metadata_dir).await?;
+        let metadata_path = metadata_dir.join(format!("{}.json", sync_result.repository_id));
+        fs::write(metadata_path, serde_json::to_string_pretty(&metadata)?).await
+            .context("Failed to write repository sync metadata")?;
+
+        Ok(())
+    }
+
     /// Get last sync timestamp for a repository
     async fn get_last_sync_timestamp(&self, repo_id: &str) -> Result<chrono::DateTime<chrono::Utc>> {
         let metadata_path = self.workspace_root.join("metadata").join(format!("{repo_id}.json"));
@@ -428,6 +574,79 @@ pub enum SyncMode {
     SymbolicLinks,
 }
 
+/// File in `<workspace>/logs` that receives one JSON line per sync conflict
+pub const CONFLICT_LOG: &str = "sync_conflicts.jsonl";
+
+/// A workspace file edited locally while its repository file also changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncConflict {
+    pub repository_id: String,
+    pub repository_name: String,
+    /// Path relative to the repository root
+    pub relative_path: PathBuf,
+    pub workspace_modified: chrono::DateTime<chrono::Utc>,
+    pub source_modified: chrono::DateTime<chrono::Utc>,
+}
+
+/// Which side of a conflict wins
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictChoice {
+    /// Keep the workspace copy
+    Ours,
+    /// Overwrite it with the repository file
+    Theirs,
+}
+
+/// Non-interactive conflict strategies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictStrategy {
+    Ours,
+    Theirs,
+    /// Whichever side was modified last
+    Newest,
+}
+
+/// Decides sync conflicts
+pub trait ConflictResolver: Send + Sync {
+    /// Name recorded in the conflict log
+    fn name(&self) -> String;
+
+    /// Choose a side, or `None` to leave the conflict unresolved
+    fn resolve(&self, conflict: &SyncConflict) -> Result<Option<ConflictChoice>>;
+}
+
+impl ConflictResolver for ConflictStrategy {
+    fn name(&self) -> String {
+        format!("strategy:{}", format!("{self:?}").to_lowercase())
+    }
+
+    fn resolve(&self, conflict: &SyncConflict) -> Result<Option<ConflictChoice>> {
+        Ok(Some(match self {
+            ConflictStrategy::Ours => ConflictChoice::Ours,
+            ConflictStrategy::Theirs => ConflictChoice::Theirs,
+            ConflictStrategy::Newest if conflict.source_modified > conflict.workspace_modified => {
+                ConflictChoice::Theirs
+            }
+            ConflictStrategy::Newest => ConflictChoice::Ours,
+        }))
+    }
+}
+
+/// A conflict and how it was decided, as written to the conflict log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictRecord {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub repository_id: String,
+    pub relative_path: PathBuf,
+    pub workspace_modified: chrono::DateTime<chrono::Utc>,
+    pub source_modified: chrono::DateTime<chrono::Utc>,
+    /// `None` when the conflict was left unresolved
+    pub choice: Option<ConflictChoice>,
+    pub decided_by: String,
+}
+
 /// Workspace synchronization configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkspaceSyncConfig {
@@ -496,6 +715,8 @@ pub struct RepositorySyncResult {
     pub size_synced: u64,
     pub sync_mode: SyncMode,
     pub last_sync: chrono::DateTime<chrono::Utc>,
+    #[serde(default)]
+    pub conflicts: Vec<ConflictRecord>,
 }
 
 /// Failed synchronization record
@@ -512,6 +733,10 @@ pub struct SyncStatistics {
     pub failed_syncs: usize,
     pub total_files_synced: usize,
     pub total_size_synced: u64,
+    #[serde(default)]
+    pub conflicts_resolved: usize,
+    #[serde(default)]
+    pub conflicts_unresolved: usize,
 }
 
 /// Workspace index metadata
@@ -609,6 +834,102 @@ mod tests {
         assert_eq!(matches.len(), 3); // main.rs, lib.rs, src/helper.rs
     }
 
+    fn test_repository(path: &Path) -> RepositoryInfo {
+        RepositoryInfo {
+            id: "repo-1".to_string(),
+            name: "service".to_string(),
+            path: path.to_path_buf(),
+            remote_url: None,
+            primary_language: None,
+            build_system: None,
+            is_monorepo_member: false,
+            monorepo_id: None,
+            tags: Vec::new(),
+            active: true,
+            last_diagnostic_run: None,
+            metadata: serde_json::Value::Null,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_conflicting_workspace_edits_are_resolved_and_logged() {
+        let repo_dir = TempDir::new().unwrap();
+        let workspace_dir = TempDir::new().unwrap();
+        fs::write(repo_dir.path().join("main.rs"), "fn main() {}").unwrap();
+        let repo = test_repository(repo_dir.path());
+        let target = workspace_dir.path().join("service/main.rs");
+
+        let synchronizer = WorkspaceSynchronizer::new(workspace_dir.path().to_path_buf())
+            .with_sync_mode(SyncMode::Full);
+        synchronizer.ensure_workspace_structure().await.unwrap();
+        let first = synchronizer.sync_repository(&repo).await.unwrap();
+        assert_eq!(first.files_synced, 1);
+        assert!(first.conflicts.is_empty());
+
+        // Both sides change after the last sync
+        let metadata_path = workspace_dir.path().join("metadata/repo-1.json");
+        let mut metadata: RepositorySyncMetadata =
+            serde_json::from_str(&fs::read_to_string(&metadata_path).unwrap()).unwrap();
+        let rewind = |metadata: &mut RepositorySyncMetadata| {
+            metadata.last_sync = chrono::Utc::now() - chrono::Duration::hours(1);
+            fs::write(&metadata_path, serde_json::to_string(metadata).unwrap()).unwrap();
+        };
+        rewind(&mut metadata);
+        fs::write(repo_dir.path().join("main.rs"), "fn main() { upstream(); }").unwrap();
+        fs::write(&target, "fn main() { local(); }").unwrap();
+
+        // Without a resolver the local edit is kept and reported
+        let kept = synchronizer.sync_repository(&repo).await.unwrap();
+        assert_eq!(kept.conflicts.len(), 1);
+        assert_eq!(kept.conflicts[0].choice, None);
+        assert_eq!(fs::read_to_string(&target).unwrap(), "fn main() { local(); }");
+
+        rewind(&mut metadata);
+        let ours = WorkspaceSynchronizer::new(workspace_dir.path().to_path_buf())
+            .with_sync_mode(SyncMode::Full)
+            .with_conflict_resolver(Arc::new(ConflictStrategy::Ours));
+        let result = ours.sync_repository(&repo).await.unwrap();
+        assert_eq!(result.conflicts[0].choice, Some(ConflictChoice::Ours));
+        assert_eq!(result.files_synced, 0);
+        assert_eq!(fs::read_to_string(&target).unwrap(), "fn main() { local(); }");
+
+        rewind(&mut metadata);
+        let theirs = WorkspaceSynchronizer::new(workspace_dir.path().to_path_buf())
+            .with_sync_mode(SyncMode::Full)
+            .with_conflict_resolver(Arc::new(ConflictStrategy::Theirs));
+        let result = theirs.sync_repository(&repo).await.unwrap();
+        assert_eq!(result.conflicts[0].decided_by, "strategy:theirs");
+        assert_eq!(fs::read_to_string(&target).unwrap(), "fn main() { upstream(); }");
+
+        let log = fs::read_to_string(workspace_dir.path().join("logs").join(CONFLICT_LOG)).unwrap();
+        let records: Vec<ConflictRecord> = log
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[2].relative_path, PathBuf::from("main.rs"));
+
+        // Once in sync, a fresh sync reports nothing
+        let clean = theirs.sync_repository(&repo).await.unwrap();
+        assert!(clean.conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_newest_strategy() {
+        let now = chrono::Utc::now();
+        let mut conflict = SyncConflict {
+            repository_id: "repo-1".to_string(),
+            repository_name: "service".to_string(),
+            relative_path: PathBuf::from("main.rs"),
+            workspace_modified: now,
+            source_modified: now - chrono::Duration::minutes(5),
+        };
+        let newest = ConflictStrategy::Newest;
+        assert_eq!(newest.resolve(&conflict).unwrap(), Some(ConflictChoice::Ours));
+        conflict.source_modified = now + chrono::Duration::minutes(5);
+        assert_eq!(newest.resolve(&conflict).unwrap(), Some(ConflictChoice::Theirs));
+    }
+
     #[test]
     fn test_sync_config_default() {
         let config = WorkspaceSyncConfig::default();
@@ -5,6 +5,7 @@ pub use paths::{PlatformPaths, config_dir, cache_dir, data_dir, log_dir, temp_di
 pub use validation::{ConfigValidator, validate_startup_config};
 
 use clap::Subcommand;
+use std::path::PathBuf;
 
 /// Configuration actions for LSPbridge
 #[derive(Debug, Clone, Subcommand)]
@@ -20,4 +21,16 @@ pub enum ConfigAction {
         /// Configuration value
         value: String,
     },
+    /// Rewrite legacy configuration (`multi-repo.toml`, old field names) into the unified `lspbridge.toml`
+    ///
+    /// The replaced files are kept as timestamped `.bak` copies, and settings
+    /// with no unified equivalent are reported.
+    Migrate {
+        /// Project directory holding the configuration
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Print the migrated configuration without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
\ No newline at end of file
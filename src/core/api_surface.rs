@@ -0,0 +1,486 @@
+//! Semantic versioning impact hints for diagnostics on public API
+//!
+//! In library crates and packages, fixing a diagnostic inside an exported
+//! item can change what downstream users compile against. [`ApiSurfaceAnalyzer`]
+//! parses Rust and TypeScript sources with tree-sitter, finds the innermost
+//! public item (`pub` in Rust, `export` in TypeScript) around each diagnostic
+//! and tags the diagnostic under [`API_SURFACE_KEY`] in its `data` with an
+//! estimate of the version bump a fix would need:
+//!
+//! - in a signature or a type definition: likely breaking (major)
+//! - in the value of a public constant: observable but compatible (minor)
+//! - inside a function body: internal (patch)
+//!
+//! Restricted visibility (`pub(crate)`, `pub(super)`) and `private` or
+//! `protected` class members are not part of the public surface.
+
+use super::types::Diagnostic;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tree_sitter::{Node, Parser};
+
+/// Key in [`Diagnostic::data`] holding the [`ApiSurfaceInfo`] of a diagnostic
+pub const API_SURFACE_KEY: &str = "lspbridgeApiSurface";
+
+/// Version bump a fix to a public item would likely require
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SemverImpact {
+    /// Internal change, invisible to callers
+    Patch,
+    /// Observable change that keeps existing callers compiling
+    Minor,
+    /// Changes a signature or type definition callers depend on
+    Major,
+}
+
+impl SemverImpact {
+    /// Whether a fix would likely break downstream users
+    pub fn is_breaking(&self) -> bool {
+        matches!(self, SemverImpact::Major)
+    }
+}
+
+impl std::fmt::Display for SemverImpact {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SemverImpact::Patch => "patch",
+            SemverImpact::Minor => "minor",
+            SemverImpact::Major => "major",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The public item a diagnostic touches
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiSurfaceInfo {
+    /// Name of the public item
+    pub item: String,
+    /// tree-sitter node kind of the item (e.g. `function_item`)
+    pub kind: String,
+    /// Whether the diagnostic is in the item's signature rather than its body
+    pub in_signature: bool,
+    /// Estimated version bump of a fix
+    pub impact: SemverImpact,
+}
+
+/// A public item found in a source file
+#[derive(Debug, Clone)]
+struct PublicItem {
+    name: String,
+    kind: String,
+    start: (usize, usize),
+    end: (usize, usize),
+    /// Start of the body; positions before it are in the signature
+    body_start: Option<(usize, usize)>,
+    /// Impact of a change after `body_start`
+    body_impact: SemverImpact,
+}
+
+impl PublicItem {
+    fn contains(&self, at: (usize, usize)) -> bool {
+        self.start <= at && at < self.end
+    }
+
+    fn classify(&self, at: (usize, usize)) -> ApiSurfaceInfo {
+        let in_signature = !matches!(self.body_start, Some(body) if at >= body);
+        ApiSurfaceInfo {
+            item: self.name.clone(),
+            kind: self.kind.clone(),
+            in_signature,
+            impact: if in_signature {
+                SemverImpact::Major
+            } else {
+                self.body_impact
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SourceLanguage {
+    Rust,
+    TypeScript,
+    Tsx,
+}
+
+impl SourceLanguage {
+    fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "rs" => Some(SourceLanguage::Rust),
+            "ts" | "mts" | "cts" => Some(SourceLanguage::TypeScript),
+            "tsx" => Some(SourceLanguage::Tsx),
+            _ => None,
+        }
+    }
+
+    fn grammar(&self) -> tree_sitter::Language {
+        match self {
+            SourceLanguage::Rust => tree_sitter_rust::language(),
+            SourceLanguage::TypeScript => tree_sitter_typescript::language_typescript(),
+            SourceLanguage::Tsx => tree_sitter_typescript::language_tsx(),
+        }
+    }
+}
+
+/// Tags diagnostics that touch exported items of Rust and TypeScript sources
+#[derive(Debug, Clone)]
+pub struct ApiSurfaceAnalyzer {
+    root: PathBuf,
+}
+
+impl ApiSurfaceAnalyzer {
+    /// Create an analyzer resolving relative diagnostic paths against `root`
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Public item around a diagnostic in `source`, if any
+    pub fn analyze_source(&self, path: &Path, source: &str, diagnostic: &Diagnostic) -> Option<ApiSurfaceInfo> {
+        let items = public_items(SourceLanguage::from_path(path)?, source);
+        surface_at(&items, diagnostic)
+    }
+
+    /// Tag every diagnostic on a public item; returns how many were tagged.
+    ///
+    /// Each file is read and parsed once. Unreadable files and unsupported
+    /// languages are skipped.
+    pub fn tag_all(&self, diagnostics: &mut [Diagnostic]) -> usize {
+        let mut items_by_file: HashMap<String, Vec<PublicItem>> = HashMap::new();
+        let mut tagged = 0;
+
+        for diagnostic in diagnostics.iter_mut() {
+            if !items_by_file.contains_key(&diagnostic.file) {
+                let items = self.load_items(&diagnostic.file).unwrap_or_default();
+                items_by_file.insert(diagnostic.file.clone(), items);
+            }
+            let Some(info) = surface_at(&items_by_file[&diagnostic.file], diagnostic) else {
+                continue;
+            };
+            if tag(diagnostic, &info) {
+                tagged += 1;
+            }
+        }
+        tagged
+    }
+
+    fn load_items(&self, file: &str) -> Option<Vec<PublicItem>> {
+        let path = Path::new(file);
+        let language = SourceLanguage::from_path(path)?;
+        let full_path = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.root.join(path)
+        };
+        let source = std::fs::read_to_string(full_path).ok()?;
+        Some(public_items(language, &source))
+    }
+}
+
+fn tag(diagnostic: &mut Diagnostic, info: &ApiSurfaceInfo) -> bool {
+    match &mut diagnostic.data {
+        None => {
+            diagnostic.data = Some(serde_json::json!({ API_SURFACE_KEY: info }));
+            true
+        }
+        Some(serde_json::Value::Object(map)) => {
+            map.insert(API_SURFACE_KEY.to_string(), serde_json::json!(info));
+            true
+        }
+        // Never rewrite language server payloads
+        Some(_) => false,
+    }
+}
+
+fn surface_at(items: &[PublicItem], diagnostic: &Diagnostic) -> Option<ApiSurfaceInfo> {
+    let at = (
+        diagnostic.range.start.line as usize,
+        diagnostic.range.start.character as usize,
+    );
+    // Items are collected outermost first, so the last match is the innermost
+    items
+        .iter()
+        .rev()
+        .find(|item| item.contains(at))
+        .map(|item| item.classify(at))
+}
+
+fn public_items(language: SourceLanguage, source: &str) -> Vec<PublicItem> {
+    let mut parser = Parser::new();
+    if parser.set_language(language.grammar()).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(source, None) else {
+        return Vec::new();
+    };
+
+    let mut items = Vec::new();
+    match language {
+        SourceLanguage::Rust => collect_rust(tree.root_node(), source, false, &mut items),
+        SourceLanguage::TypeScript | SourceLanguage::Tsx => {
+            collect_typescript(tree.root_node(), source, false, &mut items)
+        }
+    }
+    items
+}
+
+fn position(point: tree_sitter::Point) -> (usize, usize) {
+    (point.row, point.column)
+}
+
+fn text<'a>(node: Node, source: &'a str) -> &'a str {
+    node.utf8_text(source.as_bytes()).unwrap_or_default()
+}
+
+fn item(node: Node, source: &str, body: Option<Node>, body_impact: SemverImpact) -> PublicItem {
+    PublicItem {
+        name: node
+            .child_by_field_name("name")
+            .map(|name| text(name, source).to_string())
+            .unwrap_or_else(|| node.kind().to_string()),
+        kind: node.kind().to_string(),
+        start: position(node.start_position()),
+        end: position(node.end_position()),
+        body_start: body.map(|body| position(body.start_position())),
+        body_impact,
+    }
+}
+
+/// `pub` without a restriction such as `(crate)`
+fn is_rust_public(node: Node, source: &str) -> bool {
+    let mut cursor = node.walk();
+    let public = node
+        .children(&mut cursor)
+        .any(|child| child.kind() == "visibility_modifier" && text(child, source) == "pub");
+    public
+}
+
+/// Collect public Rust items; `in_public_trait` marks methods of an exported trait
+fn collect_rust(node: Node, source: &str, in_public_trait: bool, items: &mut Vec<PublicItem>) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        let public = is_rust_public(child, source);
+        match child.kind() {
+            "function_item" | "function_signature_item" if public || in_public_trait => {
+                let body = child.child_by_field_name("body");
+                items.push(item(child, source, body, SemverImpact::Patch));
+            }
+            "struct_item" | "enum_item" | "union_item" | "type_item" if public => {
+                items.push(item(child, source, None, SemverImpact::Major));
+            }
+            "const_item" | "static_item" if public => {
+                let value = child.child_by_field_name("value");
+                items.push(item(child, source, value, SemverImpact::Minor));
+            }
+            "trait_item" if public => {
+                items.push(item(child, source, None, SemverImpact::Major));
+                if let Some(body) = child.child_by_field_name("body") {
+                    collect_rust(body, source, true, items);
+                }
+            }
+            // Inherent and trait impls: only `pub` methods are collected
+            "impl_item" => {
+                if let Some(body) = child.child_by_field_name("body") {
+                    collect_rust(body, source, false, items);
+                }
+            }
+            "mod_item" if public => {
+                if let Some(body) = child.child_by_field_name("body") {
+                    collect_rust(body, source, false, items);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Collect exported TypeScript declarations; `in_exported_class` marks class members
+fn collect_typescript(node: Node, source: &str, in_exported_class: bool, items: &mut Vec<PublicItem>) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        match child.kind() {
+            "export_statement" => {
+                if let Some(declaration) = child.child_by_field_name("declaration") {
+                    collect_exported_declaration(declaration, source, items);
+                }
+            }
+            "method_definition" | "public_field_definition" | "method_signature"
+                if in_exported_class && is_public_member(child, source) =>
+            {
+                let body = child
+                    .child_by_field_name("body")
+                    .or_else(|| child.child_by_field_name("value"));
+                let body_impact = if child.kind() == "public_field_definition" {
+                    SemverImpact::Minor
+                } else {
+                    SemverImpact::Patch
+                };
+                items.push(item(child, source, body, body_impact));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn collect_exported_declaration(declaration: Node, source: &str, items: &mut Vec<PublicItem>) {
+    match declaration.kind() {
+        "function_declaration" | "generator_function_declaration" => {
+            let body = declaration.child_by_field_name("body");
+            items.push(item(declaration, source, body, SemverImpact::Patch));
+        }
+        "class_declaration" | "abstract_class_declaration" => {
+            let body = declaration.child_by_field_name("body");
+            items.push(item(declaration, source, body, SemverImpact::Patch));
+            if let Some(body) = body {
+                collect_typescript(body, source, true, items);
+            }
+        }
+        "interface_declaration" | "type_alias_declaration" | "enum_declaration" => {
+            items.push(item(declaration, source, None, SemverImpact::Major));
+        }
+        "lexical_declaration" | "variable_declaration" => {
+            let mut cursor = declaration.walk();
+            for declarator in declaration.children(&mut cursor) {
+                if declarator.kind() == "variable_declarator" {
+                    let value = declarator.child_by_field_name("value");
+                    // Exported arrow functions behave like functions
+                    let body_impact = match value.map(|v| v.kind()) {
+                        Some("arrow_function") | Some("function") => SemverImpact::Patch,
+                        _ => SemverImpact::Minor,
+                    };
+                    let body = match value {
+                        Some(v) if body_impact == SemverImpact::Patch => v.child_by_field_name("body"),
+                        other => other,
+                    };
+                    items.push(item(declarator, source, body, body_impact));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_public_member(node: Node, source: &str) -> bool {
+    let mut cursor = node.walk();
+    let restricted = node.children(&mut cursor).any(|child| {
+        child.kind() == "accessibility_modifier" && text(child, source) != "public"
+    });
+    let private_name = node
+        .child_by_field_name("name")
+        .is_some_and(|name| name.kind() == "private_property_identifier");
+    !restricted && !private_name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{DiagnosticSeverity, Position, Range};
+
+    fn diagnostic_at(file: &str, line: u32, character: u32) -> Diagnostic {
+        Diagnostic::new(
+            file.to_string(),
+            Range {
+                start: Position { line, character },
+                end: Position { line, character: character + 1 },
+            },
+            DiagnosticSeverity::Warning,
+            "problem".to_string(),
+            "test".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_rust_visibility_and_impact() {
+        let source = r#"pub fn parse(input: &str) -> Result<u32, String> {
+    input.parse().map_err(|e| format!("{e}"))
+}
+
+pub(crate) fn helper() {}
+
+pub struct Config {
+    pub name: String,
+}
+
+pub const LIMIT: usize = 10;
+
+impl Config {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    fn secret(&self) {}
+}
+"#;
+        let analyzer = ApiSurfaceAnalyzer::new("/repo");
+        let path = Path::new("src/lib.rs");
+        let at = |line, character| analyzer.analyze_source(path, source, &diagnostic_at("src/lib.rs", line, character));
+
+        let signature = at(0, 20).unwrap();
+        assert_eq!(signature.item, "parse");
+        assert!(signature.in_signature);
+        assert_eq!(signature.impact, SemverImpact::Major);
+
+        let body = at(1, 10).unwrap();
+        assert!(!body.in_signature);
+        assert_eq!(body.impact, SemverImpact::Patch);
+
+        assert!(at(4, 16).is_none());
+        assert_eq!(at(7, 14).unwrap().item, "Config");
+        assert_eq!(at(7, 14).unwrap().impact, SemverImpact::Major);
+        assert_eq!(at(10, 25).unwrap().impact, SemverImpact::Minor);
+        assert_eq!(at(13, 12).unwrap().item, "name");
+        assert!(at(16, 8).is_none());
+    }
+
+    #[test]
+    fn test_typescript_exports_and_tagging() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("index.ts"),
+            r#"export function load(path: string): number {
+  return path.length;
+}
+
+function internal() {}
+
+export class Store {
+  private cache = 1;
+  get(key: string): string {
+    return key;
+  }
+}
+
+export interface Options {
+  verbose: boolean;
+}
+"#,
+        )
+        .unwrap();
+
+        let mut diagnostics = vec![
+            diagnostic_at("index.ts", 0, 25),
+            diagnostic_at("index.ts", 1, 10),
+            diagnostic_at("index.ts", 4, 10),
+            diagnostic_at("index.ts", 7, 10),
+            diagnostic_at("index.ts", 8, 5),
+            diagnostic_at("index.ts", 14, 4),
+        ];
+        let analyzer = ApiSurfaceAnalyzer::new(dir.path());
+        assert_eq!(analyzer.tag_all(&mut diagnostics), 5);
+
+        let impacts: Vec<_> = diagnostics
+            .iter()
+            .map(|d| d.api_surface().map(|info| (info.item, info.impact)))
+            .collect();
+        assert_eq!(impacts[0], Some(("load".to_string(), SemverImpact::Major)));
+        assert_eq!(impacts[1], Some(("load".to_string(), SemverImpact::Patch)));
+        assert_eq!(impacts[2], None);
+        // Private members fall back to the class body
+        assert_eq!(impacts[3], Some(("Store".to_string(), SemverImpact::Patch)));
+        assert_eq!(impacts[4], Some(("get".to_string(), SemverImpact::Major)));
+        assert_eq!(impacts[5], Some(("Options".to_string(), SemverImpact::Major)));
+        assert!(diagnostics[4].touches_api_surface());
+    }
+}
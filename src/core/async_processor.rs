@@ -33,7 +33,9 @@
 //! ```
 
 use super::context_ranking::{ContextRanker, RankedContext};
+use super::file_guard::{FileGuard, SkippedFile};
 use super::semantic_context::{ContextExtractor, SemanticContext};
+use super::symbol_index::SymbolIndex;
 use super::types::Diagnostic;
 use crate::core::config::{HasPerformanceConfig, UnifiedConfig};
 use anyhow::Result;
@@ -53,6 +55,9 @@ pub struct AsyncDiagnosticProcessor {
     /// Semaphore for controlling concurrent operations
     semaphore: Arc<Semaphore>,
 
+    /// Size and binary limits shared with the context extractor
+    file_guard: FileGuard,
+
     /// Configuration for performance limits
     config: UnifiedConfig,
 }
@@ -104,7 +109,10 @@ pub struct ProcessingStats {
 impl AsyncDiagnosticProcessor {
     /// Create a new async diagnostic processor
     pub async fn new(config: UnifiedConfig) -> Result<Self> {
-        let context_extractor = Arc::new(tokio::sync::Mutex::new(ContextExtractor::new()?));
+        let file_guard = FileGuard::from(config.performance_config());
+        let context_extractor = Arc::new(tokio::sync::Mutex::new(
+            ContextExtractor::new()?.with_file_guard(file_guard.clone()),
+        ));
 
         let context_ranker = Arc::new(
             ContextRanker::builder()
@@ -120,10 +128,23 @@ impl AsyncDiagnosticProcessor {
             context_extractor,
             context_ranker,
             semaphore,
+            file_guard,
             config,
         })
     }
 
+    /// Resolve call hierarchies across files with a workspace symbol index,
+    /// typically one synced through [`crate::core::SymbolStore`]
+    pub async fn with_symbol_index(self, index: Arc<SymbolIndex>) -> Self {
+        self.context_extractor.lock().await.set_symbol_index(Some(index));
+        self
+    }
+
+    /// Files excluded from context extraction for size or binary content
+    pub fn skipped_files(&self) -> Vec<SkippedFile> {
+        self.file_guard.skipped()
+    }
+
     /// Process a single diagnostic asynchronously
     pub async fn process_diagnostic(&self, diagnostic: Diagnostic) -> Result<ProcessedDiagnostic> {
         let start_time = std::time::Instant::now();
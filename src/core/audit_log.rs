@@ -0,0 +1,92 @@
+//! Append-only audit log of security-relevant events
+//!
+//! Events are written as JSON lines to the path configured under
+//! `security.audit` so they can be shipped to a log pipeline or reviewed
+//! with `jq`.
+
+use super::security_config::AuditConfig;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// A single audit log entry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub timestamp: DateTime<Utc>,
+    /// Event name, e.g. `privacy_policy_changed`
+    pub event: String,
+    /// Event-specific details
+    pub details: serde_json::Value,
+}
+
+/// JSON-lines audit log file
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Audit log configured by `security.audit`, or `None` when audit logging is disabled
+    pub fn from_config(config: &AuditConfig) -> Option<Self> {
+        config
+            .enable_audit_logging
+            .then(|| Self::new(&config.audit_log_path))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append an event to the log
+    pub fn record(&self, event: &str, details: serde_json::Value) -> Result<()> {
+        self.record_all([(event, details)])
+    }
+
+    /// Append several events with a single write, so they land together
+    pub fn record_all<'a>(&self, events: impl IntoIterator<Item = (&'a str, serde_json::Value)>) -> Result<()> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).context("Failed to create audit log directory")?;
+        }
+
+        let timestamp = Utc::now();
+        let mut lines = String::new();
+        for (event, details) in events {
+            let entry = AuditEvent {
+                timestamp,
+                event: event.to_string(),
+                details,
+            };
+            lines.push_str(&serde_json::to_string(&entry)?);
+            lines.push('\n');
+        }
+        if lines.is_empty() {
+            return Ok(());
+        }
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open audit log {}", self.path.display()))?;
+        file.write_all(lines.as_bytes())?;
+        Ok(())
+    }
+
+    /// All events in the log, oldest first; malformed lines are skipped
+    pub fn read_events(&self) -> Result<Vec<AuditEvent>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&self.path)?;
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+}
@@ -0,0 +1,732 @@
+//! Database backups with point-in-time restore
+//!
+//! [`DatabaseArchiver`] keeps generations of backups for one SQLite
+//! database. A generation starts with a byte copy of the database file and
+//! grows by segments: the WAL frames committed since the previous segment,
+//! copied as they appear. [`BackupCatalog::restore_into`] rebuilds the
+//! database as of any moment a generation covers by replaying its segments
+//! over the base copy, the same way a checkpoint would.
+//!
+//! Databases that are not in WAL mode only get base copies, so they restore
+//! to the last copy taken before the requested time.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const WAL_MAGIC: u32 = 0x377f_0682;
+const WAL_HEADER_SIZE: usize = 32;
+const WAL_FRAME_HEADER_SIZE: usize = 24;
+const BASE_FILE: &str = "base.db";
+const MANIFEST_FILE: &str = "generation.json";
+const SEGMENT_EXTENSION: &str = "frames";
+
+/// Database backup settings, under `[backup]` in `lspbridge.toml`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupConfig {
+    /// Back up the history and team databases while `lspbridge watch` runs
+    pub enabled: bool,
+    /// Directory holding the backups; defaults to `backups/databases` in the data directory
+    pub destination: Option<PathBuf>,
+    /// Hours between full copies of a database
+    pub base_interval_hours: u64,
+    /// Seconds between copies of newly committed WAL frames
+    pub segment_interval_secs: u64,
+    /// Days a generation is kept after a newer one replaces it
+    pub retention_days: u64,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            destination: None,
+            base_interval_hours: 24,
+            segment_interval_secs: 60,
+            retention_days: 7,
+        }
+    }
+}
+
+impl BackupConfig {
+    /// The configured backup directory
+    pub fn destination_dir(&self) -> Result<PathBuf> {
+        match &self.destination {
+            Some(dir) => Ok(dir.clone()),
+            None => Ok(crate::config::data_dir()?.join("backups").join("databases")),
+        }
+    }
+
+    pub fn segment_interval(&self) -> Duration {
+        Duration::from_secs(self.segment_interval_secs.max(1))
+    }
+
+    /// Backups of the database called `name`
+    pub fn catalog(&self, name: &str) -> Result<BackupCatalog> {
+        Ok(BackupCatalog::new(&self.destination_dir()?, name))
+    }
+
+    /// An archiver backing up `db_path` under `name`
+    pub fn archiver(&self, name: &str, db_path: &Path) -> Result<DatabaseArchiver> {
+        Ok(DatabaseArchiver {
+            db_path: db_path.to_path_buf(),
+            catalog: self.catalog(name)?,
+            base_interval: chrono::Duration::hours(self.base_interval_hours.max(1) as i64),
+            retention: chrono::Duration::days(self.retention_days as i64),
+            current: None,
+        })
+    }
+}
+
+/// One base copy and the segments archived after it
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupGeneration {
+    pub id: String,
+    pub started: DateTime<Utc>,
+    /// Time of the newest segment, or of the base copy when there is none
+    pub latest: DateTime<Utc>,
+    pub segments: usize,
+    pub size_bytes: u64,
+    #[serde(skip)]
+    dir: PathBuf,
+    #[serde(skip)]
+    page_size: usize,
+    #[serde(skip)]
+    segment_files: Vec<(DateTime<Utc>, PathBuf)>,
+}
+
+impl BackupGeneration {
+    /// The latest moment at or before `at` this generation can restore
+    fn restorable_to(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        self.segment_files
+            .iter()
+            .map(|(time, _)| *time)
+            .filter(|time| *time <= at)
+            .fold(self.started, DateTime::max)
+    }
+}
+
+/// What a restore rebuilt
+#[derive(Debug, Clone, Serialize)]
+pub struct RestorePoint {
+    pub generation: String,
+    pub base_time: DateTime<Utc>,
+    /// Time of the last change included in the restored database
+    pub restored_to: DateTime<Utc>,
+    pub segments_applied: usize,
+}
+
+/// Outcome of replacing a database with a restored copy
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreReport {
+    pub point: RestorePoint,
+    /// Where the database that was replaced now lives
+    pub replaced: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GenerationManifest {
+    database: PathBuf,
+    started: DateTime<Utc>,
+    page_size: usize,
+}
+
+/// Backups of one database in a backup destination
+#[derive(Debug, Clone)]
+pub struct BackupCatalog {
+    dir: PathBuf,
+}
+
+impl BackupCatalog {
+    pub fn new(destination: &Path, name: &str) -> Self {
+        Self {
+            dir: destination.join(name),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Complete generations, oldest first
+    pub fn generations(&self) -> Result<Vec<BackupGeneration>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", self.dir.display())),
+        };
+
+        let mut generations = Vec::new();
+        for entry in entries {
+            let dir = entry?.path();
+            // A generation without a manifest is a base copy still being taken
+            if dir.join(MANIFEST_FILE).is_file() {
+                generations.push(load_generation(&dir)?);
+            }
+        }
+        generations.sort_by_key(|generation| generation.started);
+        Ok(generations)
+    }
+
+    /// Delete generations replaced by a newer one more than `retention` ago
+    ///
+    /// The newest generation is always kept. Returns how many were deleted.
+    pub fn prune(&self, retention: chrono::Duration) -> Result<usize> {
+        let cutoff = Utc::now() - retention;
+        let generations = self.generations()?;
+        let mut removed = 0;
+        for pair in generations.windows(2) {
+            if pair[1].started < cutoff {
+                fs::remove_dir_all(&pair[0].dir)
+                    .with_context(|| format!("Failed to remove backup {}", pair[0].dir.display()))?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// What restoring to `at` would rebuild, without touching any files
+    pub fn plan(&self, at: DateTime<Utc>) -> Result<RestorePoint> {
+        let generation = self.select(at)?;
+        Ok(restore_point(&generation, at))
+    }
+
+    /// Rebuild the database as it was at `at` into `target`
+    pub fn restore_into(&self, at: DateTime<Utc>, target: &Path) -> Result<RestorePoint> {
+        let generation = self.select(at)?;
+        fs::copy(generation.dir.join(BASE_FILE), target)
+            .with_context(|| format!("Failed to copy base backup to {}", target.display()))?;
+        let mut file = OpenOptions::new().write(true).open(target)?;
+        let page_size = generation.page_size;
+        let frame_size = WAL_FRAME_HEADER_SIZE + page_size;
+
+        let mut database_pages = None;
+        for (_, path) in generation.segment_files.iter().filter(|(time, _)| *time <= at) {
+            let frames = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+            if frames.len() % frame_size != 0 {
+                return Err(anyhow!("Backup segment {} is truncated", path.display()));
+            }
+            for frame in frames.chunks_exact(frame_size) {
+                let page = u64::from(read_u32(&frame[0..4]));
+                if page == 0 {
+                    return Err(anyhow!("Backup segment {} is corrupt", path.display()));
+                }
+                file.seek(SeekFrom::Start((page - 1) * page_size as u64))?;
+                file.write_all(&frame[WAL_FRAME_HEADER_SIZE..])?;
+                match read_u32(&frame[4..8]) {
+                    0 => {}
+                    pages => database_pages = Some(u64::from(pages)),
+                }
+            }
+        }
+        if let Some(pages) = database_pages {
+            file.set_len(pages * page_size as u64)?;
+        }
+        file.sync_all()?;
+        drop(file);
+
+        let check: String = Connection::open(target)?.query_row("PRAGMA integrity_check", [], |row| row.get(0))?;
+        if check != "ok" {
+            return Err(anyhow!("Restored database failed its integrity check: {check}"));
+        }
+
+        Ok(restore_point(&generation, at))
+    }
+
+    /// The generation restoring the most recent state at or before `at`
+    fn select(&self, at: DateTime<Utc>) -> Result<BackupGeneration> {
+        let generations = self.generations()?;
+        let oldest = generations.first().map(|generation| generation.started);
+        generations
+            .into_iter()
+            .filter(|generation| generation.started <= at)
+            .max_by_key(|generation| generation.restorable_to(at))
+            .ok_or_else(|| match oldest {
+                Some(oldest) => anyhow!(
+                    "No backup of {} at or before {}; the oldest is from {}",
+                    self.dir.display(),
+                    at.to_rfc3339(),
+                    oldest.to_rfc3339()
+                ),
+                None => anyhow!("No backups in {}", self.dir.display()),
+            })
+    }
+}
+
+fn restore_point(generation: &BackupGeneration, at: DateTime<Utc>) -> RestorePoint {
+    RestorePoint {
+        generation: generation.id.clone(),
+        base_time: generation.started,
+        restored_to: generation.restorable_to(at),
+        segments_applied: generation.segment_files.iter().filter(|(time, _)| *time <= at).count(),
+    }
+}
+
+/// Replace the database at `db_path` with its state at `at`
+///
+/// The replaced database is renamed with a `.before-restore-<time>` suffix
+/// rather than deleted. Nothing else may have the database open.
+pub fn restore_database(catalog: &BackupCatalog, db_path: &Path, at: DateTime<Utc>) -> Result<RestoreReport> {
+    let staging = with_suffix(db_path, ".restoring");
+    let _ = fs::remove_file(&staging);
+    let point = match catalog.restore_into(at, &staging) {
+        Ok(point) => point,
+        Err(e) => {
+            let _ = fs::remove_file(&staging);
+            return Err(e);
+        }
+    };
+
+    let replaced = if db_path.exists() {
+        let moved = with_suffix(db_path, &format!(".before-restore-{}", Utc::now().format("%Y%m%dT%H%M%SZ")));
+        fs::rename(db_path, &moved)?;
+        // Keep the old WAL with the old database so it stays readable
+        for suffix in ["-wal", "-shm"] {
+            let sidecar = with_suffix(db_path, suffix);
+            if sidecar.exists() {
+                fs::rename(&sidecar, with_suffix(&moved, suffix))?;
+            }
+        }
+        Some(moved)
+    } else {
+        None
+    };
+    fs::rename(&staging, db_path)?;
+
+    Ok(RestoreReport { point, replaced })
+}
+
+/// Position in a WAL up to which frames have been archived
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WalCursor {
+    salt: [u32; 2],
+    /// Running checksum after the last archived frame
+    checksum: [u32; 2],
+    offset: u64,
+}
+
+struct ActiveGeneration {
+    dir: PathBuf,
+    started: DateTime<Utc>,
+    /// `None` until the database has a WAL
+    wal: Option<WalCursor>,
+    next_segment: u32,
+}
+
+/// Keeps the backups of one SQLite database current
+pub struct DatabaseArchiver {
+    db_path: PathBuf,
+    catalog: BackupCatalog,
+    base_interval: chrono::Duration,
+    retention: chrono::Duration,
+    current: Option<ActiveGeneration>,
+}
+
+impl DatabaseArchiver {
+    pub fn catalog(&self) -> &BackupCatalog {
+        &self.catalog
+    }
+
+    /// Take a base copy when one is due, otherwise archive newly committed WAL frames
+    pub fn tick(&mut self) -> Result<()> {
+        if !self.db_path.exists() {
+            return Ok(());
+        }
+        let due = match &self.current {
+            Some(generation) => Utc::now() - generation.started >= self.base_interval,
+            None => true,
+        };
+        if due {
+            self.base_backup()?;
+            return Ok(());
+        }
+
+        if !self.archive_wal()? {
+            // Checkpointing restarted the WAL, so frames may have been lost
+            tracing::debug!("WAL of {} restarted; starting a new backup generation", self.db_path.display());
+            self.base_backup()?;
+        }
+        Ok(())
+    }
+
+    /// Start a new generation with a copy of the database
+    pub fn base_backup(&mut self) -> Result<BackupGeneration> {
+        let started = Utc::now();
+        let dir = self.catalog.dir.join(started.format("%Y%m%dT%H%M%S%.6fZ").to_string());
+        fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+        let conn = Connection::open_with_flags(
+            &self.db_path,
+            OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .with_context(|| format!("Failed to open {}", self.db_path.display()))?;
+        conn.busy_timeout(Duration::from_secs(30))?;
+        let page_size: i64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+
+        // Holding the write lock keeps the file and the WAL still while they are copied
+        conn.execute_batch("BEGIN IMMEDIATE")?;
+        let copied = self.copy_locked(&dir, started);
+        conn.execute_batch("ROLLBACK")?;
+        drop(conn);
+        let wal = copied?;
+
+        let manifest = GenerationManifest {
+            database: self.db_path.clone(),
+            started,
+            page_size: page_size as usize,
+        };
+        fs::write(dir.join(MANIFEST_FILE), serde_json::to_vec_pretty(&manifest)?)?;
+        self.current = Some(ActiveGeneration {
+            dir: dir.clone(),
+            started,
+            wal,
+            next_segment: 1,
+        });
+
+        if let Err(e) = self.catalog.prune(self.retention) {
+            tracing::warn!("Failed to prune old backups: {e:#}");
+        }
+        load_generation(&dir)
+    }
+
+    /// Copy the database and its committed WAL frames; the caller holds the write lock
+    fn copy_locked(&self, dir: &Path, started: DateTime<Utc>) -> Result<Option<WalCursor>> {
+        let partial = dir.join(format!("{BASE_FILE}.partial"));
+        fs::copy(&self.db_path, &partial)
+            .with_context(|| format!("Failed to copy {}", self.db_path.display()))?;
+        File::open(&partial)?.sync_all()?;
+        fs::rename(&partial, dir.join(BASE_FILE))?;
+
+        // Frames not yet checkpointed become the generation's first segment
+        let Some((header, frames)) = read_wal(&wal_path(&self.db_path), WAL_HEADER_SIZE as u64)? else {
+            return Ok(None);
+        };
+        let mut cursor = WalCursor {
+            salt: header.salt,
+            checksum: header.checksum,
+            offset: WAL_HEADER_SIZE as u64,
+        };
+        if let Some((length, checksum)) = committed_frames(&header, &frames, cursor.checksum) {
+            write_segment(dir, 0, started, &frames[..length])?;
+            cursor.offset += length as u64;
+            cursor.checksum = checksum;
+        }
+        Ok(Some(cursor))
+    }
+
+    /// Archive frames committed since the last segment
+    ///
+    /// Returns `false` when the WAL restarted since the last segment.
+    fn archive_wal(&mut self) -> Result<bool> {
+        let Some(generation) = self.current.as_mut() else {
+            return Ok(true);
+        };
+        let offset = generation.wal.map_or(WAL_HEADER_SIZE as u64, |cursor| cursor.offset);
+        let Some((header, frames)) = read_wal(&wal_path(&self.db_path), offset)? else {
+            return Ok(true);
+        };
+        let mut cursor = match generation.wal {
+            Some(cursor) if cursor.salt != header.salt => return Ok(false),
+            Some(cursor) => cursor,
+            None => WalCursor {
+                salt: header.salt,
+                checksum: header.checksum,
+                offset,
+            },
+        };
+
+        if let Some((length, checksum)) = committed_frames(&header, &frames, cursor.checksum) {
+            write_segment(&generation.dir, generation.next_segment, Utc::now(), &frames[..length])?;
+            generation.next_segment += 1;
+            cursor.offset += length as u64;
+            cursor.checksum = checksum;
+        }
+        generation.wal = Some(cursor);
+        Ok(true)
+    }
+
+    /// Run [`Self::tick`] every `interval` on the blocking pool
+    pub fn spawn(self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut archiver = self;
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let (returned, result) = match tokio::task::spawn_blocking(move || {
+                    let result = archiver.tick();
+                    (archiver, result)
+                })
+                .await
+                {
+                    Ok(outcome) => outcome,
+                    Err(e) => {
+                        tracing::warn!("Database backups stopped: {e}");
+                        return;
+                    }
+                };
+                archiver = returned;
+                if let Err(e) = result {
+                    tracing::warn!("Backup of {} failed: {e:#}", archiver.db_path.display());
+                }
+            }
+        })
+    }
+}
+
+struct WalHeader {
+    /// Checksums use big-endian words
+    big_endian: bool,
+    page_size: usize,
+    salt: [u32; 2],
+    checksum: [u32; 2],
+}
+
+fn wal_path(db_path: &Path) -> PathBuf {
+    with_suffix(db_path, "-wal")
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+/// Read a WAL's header and its bytes from `offset` on
+///
+/// Returns `None` when there is no WAL or it has no valid header yet.
+fn read_wal(path: &Path, offset: u64) -> Result<Option<(WalHeader, Vec<u8>)>> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to open {}", path.display())),
+    };
+    let mut header = [0u8; WAL_HEADER_SIZE];
+    if file.read_exact(&mut header).is_err() {
+        return Ok(None);
+    }
+    let Some(header) = parse_wal_header(&header) else {
+        return Ok(None);
+    };
+    let mut frames = Vec::new();
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_to_end(&mut frames)?;
+    Ok(Some((header, frames)))
+}
+
+fn parse_wal_header(bytes: &[u8; WAL_HEADER_SIZE]) -> Option<WalHeader> {
+    let magic = read_u32(&bytes[0..4]);
+    if magic & !1 != WAL_MAGIC {
+        return None;
+    }
+    let big_endian = magic & 1 == 1;
+    let checksum = [read_u32(&bytes[24..28]), read_u32(&bytes[28..32])];
+    if wal_checksum(big_endian, &bytes[..24], [0, 0]) != checksum {
+        return None;
+    }
+    Some(WalHeader {
+        big_endian,
+        page_size: read_u32(&bytes[8..12]) as usize,
+        salt: [read_u32(&bytes[16..20]), read_u32(&bytes[20..24])],
+        checksum,
+    })
+}
+
+/// SQLite's WAL checksum over 8-byte chunks of `data`, continuing from `seed`
+fn wal_checksum(big_endian: bool, data: &[u8], seed: [u32; 2]) -> [u32; 2] {
+    let [mut s0, mut s1] = seed;
+    for chunk in data.chunks_exact(8) {
+        let (x0, x1) = if big_endian {
+            (read_u32(&chunk[0..4]), read_u32(&chunk[4..8]))
+        } else {
+            (
+                u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]),
+                u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]),
+            )
+        };
+        s0 = s0.wrapping_add(x0).wrapping_add(s1);
+        s1 = s1.wrapping_add(x1).wrapping_add(s0);
+    }
+    [s0, s1]
+}
+
+/// Length of the valid frames in `frames` up to the last commit, with the checksum there
+///
+/// Stops at the first frame from an earlier WAL or with a bad checksum,
+/// which is where a writer's unfinished or stale frames begin.
+fn committed_frames(header: &WalHeader, frames: &[u8], seed: [u32; 2]) -> Option<(usize, [u32; 2])> {
+    let frame_size = WAL_FRAME_HEADER_SIZE + header.page_size;
+    let mut checksum = seed;
+    let mut committed = None;
+    for (index, frame) in frames.chunks_exact(frame_size).enumerate() {
+        if [read_u32(&frame[8..12]), read_u32(&frame[12..16])] != header.salt {
+            break;
+        }
+        checksum = wal_checksum(header.big_endian, &frame[..8], checksum);
+        checksum = wal_checksum(header.big_endian, &frame[WAL_FRAME_HEADER_SIZE..], checksum);
+        if checksum != [read_u32(&frame[16..20]), read_u32(&frame[20..24])] {
+            break;
+        }
+        if read_u32(&frame[4..8]) != 0 {
+            committed = Some(((index + 1) * frame_size, checksum));
+        }
+    }
+    committed
+}
+
+fn write_segment(dir: &Path, sequence: u32, time: DateTime<Utc>, frames: &[u8]) -> Result<()> {
+    let name = format!("{sequence:08}-{}.{SEGMENT_EXTENSION}", time.timestamp_millis());
+    let partial = dir.join(format!("{name}.partial"));
+    let mut file = File::create(&partial)?;
+    file.write_all(frames)?;
+    file.sync_all()?;
+    fs::rename(&partial, dir.join(name))?;
+    Ok(())
+}
+
+fn load_generation(dir: &Path) -> Result<BackupGeneration> {
+    let manifest: GenerationManifest = serde_json::from_slice(&fs::read(dir.join(MANIFEST_FILE))?)
+        .with_context(|| format!("Invalid backup manifest in {}", dir.display()))?;
+    let mut size_bytes = fs::metadata(dir.join(BASE_FILE))?.len();
+
+    let mut segments = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some(SEGMENT_EXTENSION) {
+            continue;
+        }
+        let Some((sequence, millis)) = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.split_once('-'))
+            .and_then(|(sequence, millis)| Some((sequence.parse::<u32>().ok()?, millis.parse::<i64>().ok()?)))
+        else {
+            continue;
+        };
+        let Some(time) = Utc.timestamp_millis_opt(millis).single() else {
+            continue;
+        };
+        size_bytes += fs::metadata(&path)?.len();
+        segments.push((sequence, time, path));
+    }
+    segments.sort_by_key(|(sequence, _, _)| *sequence);
+    let segment_files: Vec<_> = segments.into_iter().map(|(_, time, path)| (time, path)).collect();
+
+    Ok(BackupGeneration {
+        id: dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        started: manifest.started,
+        latest: segment_files.last().map_or(manifest.started, |(time, _)| *time),
+        segments: segment_files.len(),
+        size_bytes,
+        dir: dir.to_path_buf(),
+        page_size: manifest.page_size,
+        segment_files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn open_wal(path: &Path) -> Connection {
+        let conn = Connection::open(path).unwrap();
+        conn.query_row("PRAGMA journal_mode=WAL", [], |row| row.get::<_, String>(0))
+            .unwrap();
+        conn.execute_batch("CREATE TABLE IF NOT EXISTS items (value INTEGER)").unwrap();
+        conn
+    }
+
+    fn count(path: &Path) -> i64 {
+        Connection::open(path)
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM items", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    fn archiver(dir: &TempDir, db: &Path) -> DatabaseArchiver {
+        let config = BackupConfig {
+            destination: Some(dir.path().join("backups")),
+            ..BackupConfig::default()
+        };
+        config.archiver("history", db).unwrap()
+    }
+
+    #[test]
+    fn test_restore_to_point_in_time() {
+        let dir = TempDir::new().unwrap();
+        let db = dir.path().join("history.db");
+        // Kept open so closing it does not checkpoint away the WAL
+        let writer = open_wal(&db);
+        writer.execute("INSERT INTO items VALUES (1)", []).unwrap();
+
+        let mut archiver = archiver(&dir, &db);
+        archiver.tick().unwrap();
+        let base = archiver.catalog().generations().unwrap()[0].started;
+
+        writer.execute("INSERT INTO items VALUES (2)", []).unwrap();
+        archiver.tick().unwrap();
+        let middle = Utc::now();
+        std::thread::sleep(Duration::from_millis(5));
+
+        writer.execute("INSERT INTO items VALUES (3)", []).unwrap();
+        archiver.tick().unwrap();
+
+        let generations = archiver.catalog().generations().unwrap();
+        assert_eq!(generations.len(), 1);
+        assert_eq!(generations[0].segments, 3);
+
+        let target = dir.path().join("restored.db");
+        let point = archiver.catalog().restore_into(middle, &target).unwrap();
+        assert_eq!(point.segments_applied, 2);
+        assert_eq!(count(&target), 2);
+
+        let target = dir.path().join("restored-base.db");
+        archiver.catalog().restore_into(base, &target).unwrap();
+        assert_eq!(count(&target), 1);
+
+        let before = base - chrono::Duration::seconds(1);
+        assert!(archiver.catalog().restore_into(before, &dir.path().join("none.db")).is_err());
+
+        drop(writer);
+        let report = restore_database(archiver.catalog(), &db, middle).unwrap();
+        assert_eq!(count(&db), 2);
+        assert_eq!(count(&report.replaced.unwrap()), 3);
+    }
+
+    #[test]
+    fn test_wal_restart_starts_new_generation() {
+        let dir = TempDir::new().unwrap();
+        let db = dir.path().join("history.db");
+        let writer = open_wal(&db);
+        writer.execute("INSERT INTO items VALUES (1)", []).unwrap();
+
+        let mut archiver = archiver(&dir, &db);
+        archiver.tick().unwrap();
+
+        writer
+            .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| row.get::<_, i64>(0))
+            .unwrap();
+        writer.execute("INSERT INTO items VALUES (2)", []).unwrap();
+        archiver.tick().unwrap();
+
+        let generations = archiver.catalog().generations().unwrap();
+        assert_eq!(generations.len(), 2);
+
+        let target = dir.path().join("restored.db");
+        archiver.catalog().restore_into(Utc::now(), &target).unwrap();
+        assert_eq!(count(&target), 2);
+    }
+}
@@ -0,0 +1,283 @@
+//! Baselines: accept today's diagnostics, fail only on new ones
+//!
+//! A legacy codebase can't adopt a "no new errors" policy while it carries
+//! thousands of existing warnings. `lspbridge baseline create` records the
+//! current diagnostics in [`BASELINE_FILE`] at the project root, meant to be
+//! committed; from then on exports and queries leave baselined diagnostics
+//! out, and `lspbridge baseline check` fails when anything new shows up.
+//!
+//! Diagnostics are matched per file on severity, source, code and message,
+//! not position, so edits that shift lines around don't resurface accepted
+//! issues. Each entry remembers how often it occurred: a third copy of an
+//! issue the baseline holds twice is new.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::Subcommand;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use super::types::{Diagnostic, DiagnosticSeverity};
+
+/// Baseline file name, looked up in the project root
+pub const BASELINE_FILE: &str = ".lspbridge-baseline.json";
+
+/// Baseline actions
+#[derive(Debug, Clone, Subcommand)]
+pub enum BaselineAction {
+    /// Record the current diagnostics as accepted
+    ///
+    /// Diagnostics are read from the IDE, or from stdin when piped in.
+    /// Recreating the baseline after fixing issues tightens it.
+    Create {
+        /// Baseline file to write
+        #[arg(long, default_value = BASELINE_FILE)]
+        file: PathBuf,
+    },
+    /// Fail if the current diagnostics include any not in the baseline
+    Check {
+        /// Baseline file to compare against
+        #[arg(long, default_value = BASELINE_FILE)]
+        file: PathBuf,
+        /// Most new errors allowed
+        #[arg(long, default_value = "0")]
+        max_new_errors: usize,
+        /// Most new warnings allowed (default: unlimited)
+        #[arg(long)]
+        max_new_warnings: Option<usize>,
+    },
+}
+
+/// One accepted diagnostic, possibly occurring several times in its file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub severity: DiagnosticSeverity,
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    pub message: String,
+    pub count: usize,
+}
+
+/// Diagnostics accepted when the baseline was created
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub created_at: DateTime<Utc>,
+    /// Entries by file, relative to the project root
+    pub files: BTreeMap<String, Vec<BaselineEntry>>,
+}
+
+/// Diagnostics left after filtering out a baseline
+#[derive(Debug, Clone)]
+pub struct BaselineOutcome {
+    /// Diagnostics the baseline doesn't cover, in their original order
+    pub new: Vec<Diagnostic>,
+    /// Diagnostics left out as baselined
+    pub suppressed: usize,
+    /// Baselined occurrences no longer present
+    pub fixed: usize,
+}
+
+/// What identifies a diagnostic in a file, independent of position
+type MatchKey<'a> = (DiagnosticSeverity, &'a str, Option<&'a str>, &'a str);
+
+fn match_key(diagnostic: &Diagnostic) -> MatchKey<'_> {
+    (
+        diagnostic.severity,
+        &diagnostic.source,
+        diagnostic.code.as_deref(),
+        &diagnostic.message,
+    )
+}
+
+/// `file` relative to `root` when inside it
+fn relative_file(file: &str, root: &Path) -> String {
+    Path::new(file)
+        .strip_prefix(root)
+        .map_or_else(|_| file.to_string(), |relative| relative.to_string_lossy().into_owned())
+}
+
+impl Baseline {
+    /// Baseline accepting `diagnostics`, with paths relative to `root`
+    pub fn from_diagnostics(diagnostics: &[Diagnostic], root: &Path) -> Self {
+        let mut counts: BTreeMap<String, HashMap<MatchKey<'_>, usize>> = BTreeMap::new();
+        for diagnostic in diagnostics {
+            *counts
+                .entry(relative_file(&diagnostic.file, root))
+                .or_default()
+                .entry(match_key(diagnostic))
+                .or_default() += 1;
+        }
+
+        let files = counts
+            .into_iter()
+            .map(|(file, keys)| {
+                let mut entries: Vec<BaselineEntry> = keys
+                    .into_iter()
+                    .map(|((severity, source, code, message), count)| BaselineEntry {
+                        severity,
+                        source: source.to_string(),
+                        code: code.map(str::to_string),
+                        message: message.to_string(),
+                        count,
+                    })
+                    .collect();
+                // Stable order keeps the committed file's diffs readable
+                entries.sort_by(|a, b| {
+                    (&a.source, &a.code, &a.message, a.severity).cmp(&(&b.source, &b.code, &b.message, b.severity))
+                });
+                (file, entries)
+            })
+            .collect();
+
+        Self {
+            created_at: Utc::now(),
+            files,
+        }
+    }
+
+    /// The baseline in `root`, if the project has one
+    pub fn discover(root: &Path) -> Result<Option<Self>> {
+        let path = root.join(BASELINE_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Self::load(&path).map(Some)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read baseline {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse baseline {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content + "\n").with_context(|| format!("Failed to write baseline {}", path.display()))
+    }
+
+    /// Accepted occurrences across all files
+    pub fn len(&self) -> usize {
+        self.files.values().flatten().map(|entry| entry.count).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Split `diagnostics`, whose paths are resolved against `root`, into new and baselined
+    ///
+    /// When a file has more copies of a diagnostic than were accepted, the
+    /// ones furthest down the file are new.
+    pub fn apply(&self, diagnostics: Vec<Diagnostic>, root: &Path) -> BaselineOutcome {
+        let mut remaining: HashMap<&str, HashMap<MatchKey<'_>, usize>> = self
+            .files
+            .iter()
+            .map(|(file, entries)| {
+                let keys = entries
+                    .iter()
+                    .map(|entry| {
+                        let key = (entry.severity, entry.source.as_str(), entry.code.as_deref(), entry.message.as_str());
+                        (key, entry.count)
+                    })
+                    .collect();
+                (file.as_str(), keys)
+            })
+            .collect();
+
+        let files: Vec<String> = diagnostics.iter().map(|d| relative_file(&d.file, root)).collect();
+        let mut order: Vec<usize> = (0..diagnostics.len()).collect();
+        order.sort_by_key(|&i| (&files[i], diagnostics[i].range.start.line, diagnostics[i].range.start.character));
+
+        let mut baselined = vec![false; diagnostics.len()];
+        for i in order {
+            let accepted = remaining
+                .get_mut(files[i].as_str())
+                .and_then(|keys| keys.get_mut(&match_key(&diagnostics[i])));
+            if let Some(count) = accepted.filter(|count| **count > 0) {
+                *count -= 1;
+                baselined[i] = true;
+            }
+        }
+
+        let fixed = remaining.values().flat_map(HashMap::values).sum();
+        let suppressed = baselined.iter().filter(|b| **b).count();
+        let new = diagnostics
+            .into_iter()
+            .zip(baselined)
+            .filter(|(_, baselined)| !baselined)
+            .map(|(diagnostic, _)| diagnostic)
+            .collect();
+        BaselineOutcome { new, suppressed, fixed }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Position, Range};
+
+    fn diagnostic(file: &str, line: u32, severity: DiagnosticSeverity, message: &str) -> Diagnostic {
+        Diagnostic::new(
+            file.to_string(),
+            Range {
+                start: Position { line, character: 0 },
+                end: Position { line, character: 1 },
+            },
+            severity,
+            message.to_string(),
+            "eslint".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_only_new_diagnostics_remain() {
+        let root = Path::new("/work/app");
+        let accepted = vec![
+            diagnostic("/work/app/src/a.ts", 3, DiagnosticSeverity::Warning, "Unexpected any"),
+            diagnostic("/work/app/src/a.ts", 9, DiagnosticSeverity::Warning, "Unexpected any"),
+            diagnostic("/work/app/src/b.ts", 1, DiagnosticSeverity::Error, "Missing return type"),
+        ];
+        let baseline = Baseline::from_diagnostics(&accepted, root);
+        assert_eq!(baseline.len(), 3);
+        assert_eq!(baseline.files["src/a.ts"][0].count, 2);
+
+        // Lines moved, a third `any` appeared further down, and b.ts was fixed
+        let current = vec![
+            diagnostic("/work/app/src/a.ts", 40, DiagnosticSeverity::Warning, "Unexpected any"),
+            diagnostic("/work/app/src/a.ts", 5, DiagnosticSeverity::Warning, "Unexpected any"),
+            diagnostic("/work/app/src/a.ts", 12, DiagnosticSeverity::Warning, "Unexpected any"),
+            diagnostic("/work/app/src/a.ts", 12, DiagnosticSeverity::Error, "Unexpected any"),
+            diagnostic("/work/app/src/c.ts", 1, DiagnosticSeverity::Error, "Missing return type"),
+        ];
+        let outcome = baseline.apply(current, root);
+        let new: Vec<(&str, u32)> = outcome
+            .new
+            .iter()
+            .map(|d| (d.file.as_str(), d.range.start.line))
+            .collect();
+        assert_eq!(
+            new,
+            [("/work/app/src/a.ts", 40), ("/work/app/src/a.ts", 12), ("/work/app/src/c.ts", 1)]
+        );
+        assert_eq!((outcome.suppressed, outcome.fixed), (2, 1));
+    }
+
+    #[test]
+    fn test_round_trips_through_file() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        assert!(Baseline::discover(dir.path())?.is_none());
+
+        let file = dir.path().join("lib.rs").to_string_lossy().into_owned();
+        let mut unused = diagnostic(&file, 0, DiagnosticSeverity::Warning, "unused variable: `x`");
+        unused.code = Some("unused_variables".to_string());
+        let baseline = Baseline::from_diagnostics(&[unused], dir.path());
+        baseline.save(&dir.path().join(BASELINE_FILE))?;
+
+        let loaded = Baseline::discover(dir.path())?.expect("baseline saved");
+        assert_eq!(loaded, baseline);
+        assert_eq!(loaded.files["lib.rs"][0].code.as_deref(), Some("unused_variables"));
+        Ok(())
+    }
+}
@@ -0,0 +1,277 @@
+//! Calendar time in a configured time zone
+//!
+//! Relative query ranges (`LAST 7 DAYS`) and day/week/month buckets in
+//! reports are computed on the wall clock of `[calendar] time_zone` in
+//! `lspbridge.toml`, so a team in UTC+10 sees days split at its own midnight
+//! and a range crossing a daylight saving change still starts at the same
+//! local time of day.
+
+use anyhow::{anyhow, Result};
+use chrono::{
+    DateTime, Datelike, Days, FixedOffset, Local, Months, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc,
+    Weekday,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Zone whose wall clock defines calendar boundaries
+///
+/// Written as `UTC`, `local` (the system zone, honouring `TZ`) or a fixed
+/// offset such as `+05:30`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum TimeZoneSetting {
+    #[default]
+    Utc,
+    Local,
+    Fixed(FixedOffset),
+}
+
+impl FromStr for TimeZoneSetting {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim() {
+            zone if zone.eq_ignore_ascii_case("utc") || zone.eq_ignore_ascii_case("z") => Ok(Self::Utc),
+            zone if zone.eq_ignore_ascii_case("local") => Ok(Self::Local),
+            zone => zone.parse::<FixedOffset>().map(Self::Fixed).map_err(|_| {
+                anyhow!(
+                    "Unknown time zone '{zone}'; use UTC, local or an offset like +05:30 \
+                     (for a named zone, set TZ and use local)"
+                )
+            }),
+        }
+    }
+}
+
+impl TryFrom<String> for TimeZoneSetting {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl From<TimeZoneSetting> for String {
+    fn from(zone: TimeZoneSetting) -> Self {
+        zone.to_string()
+    }
+}
+
+impl fmt::Display for TimeZoneSetting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Utc => write!(f, "UTC"),
+            Self::Local => write!(f, "local"),
+            Self::Fixed(offset) => write!(f, "{offset}"),
+        }
+    }
+}
+
+/// Length of a calendar bucket
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CalendarUnit {
+    Hour,
+    Day,
+    Week,
+    Month,
+}
+
+impl CalendarUnit {
+    /// Parse a unit name, singular or plural
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        match name.strip_suffix('s').unwrap_or(&name) {
+            "hour" => Some(Self::Hour),
+            "day" => Some(Self::Day),
+            "week" => Some(Self::Week),
+            "month" => Some(Self::Month),
+            _ => None,
+        }
+    }
+}
+
+/// Calendar settings, under `[calendar]` in `lspbridge.toml`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CalendarConfig {
+    /// Zone for day, week and month boundaries
+    pub time_zone: TimeZoneSetting,
+    /// First day of a week bucket
+    pub week_start: Weekday,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            time_zone: TimeZoneSetting::Utc,
+            week_start: Weekday::Mon,
+        }
+    }
+}
+
+impl CalendarConfig {
+    pub fn with_time_zone(mut self, time_zone: TimeZoneSetting) -> Self {
+        self.time_zone = time_zone;
+        self
+    }
+
+    pub fn with_week_start(mut self, week_start: Weekday) -> Self {
+        self.week_start = week_start;
+        self
+    }
+
+    /// Start of the bucket containing `time`
+    pub fn bucket_start(&self, time: DateTime<Utc>, unit: CalendarUnit) -> DateTime<Utc> {
+        self.on_wall_clock(time, |local| floor(local, unit, self.week_start))
+    }
+
+    /// Start of the bucket after the one starting at `start`
+    pub fn next_bucket(&self, start: DateTime<Utc>, unit: CalendarUnit) -> DateTime<Utc> {
+        match unit {
+            CalendarUnit::Hour => start + chrono::Duration::hours(1),
+            _ => self.on_wall_clock(start, |local| {
+                shift(floor(local, unit, self.week_start), unit, 1, true)
+            }),
+        }
+    }
+
+    /// The same wall-clock time `count` units before `time`
+    ///
+    /// Hours are exact durations; days, weeks and months step the local
+    /// calendar, so `LAST 1 DAYS` across a daylight saving change is 23 or
+    /// 25 hours long.
+    pub fn units_before(&self, time: DateTime<Utc>, unit: CalendarUnit, count: u32) -> DateTime<Utc> {
+        match unit {
+            CalendarUnit::Hour => time - chrono::Duration::hours(count as i64),
+            _ => self.on_wall_clock(time, |local| shift(local, unit, count, false)),
+        }
+    }
+
+    /// Bucket starts covering `[start, end)`
+    pub fn buckets(&self, start: DateTime<Utc>, end: DateTime<Utc>, unit: CalendarUnit) -> Vec<DateTime<Utc>> {
+        let mut buckets = Vec::new();
+        let mut bucket = self.bucket_start(start, unit);
+        while bucket < end {
+            buckets.push(bucket);
+            bucket = self.next_bucket(bucket, unit);
+        }
+        buckets
+    }
+
+    /// Format `time` on the configured wall clock
+    pub fn format(&self, time: DateTime<Utc>, pattern: &str) -> String {
+        match self.time_zone {
+            TimeZoneSetting::Utc => time.format(pattern).to_string(),
+            TimeZoneSetting::Local => time.with_timezone(&Local).format(pattern).to_string(),
+            TimeZoneSetting::Fixed(offset) => time.with_timezone(&offset).format(pattern).to_string(),
+        }
+    }
+
+    /// Map `time` to the local wall clock, adjust it there and map it back
+    fn on_wall_clock(&self, time: DateTime<Utc>, adjust: impl Fn(NaiveDateTime) -> NaiveDateTime) -> DateTime<Utc> {
+        match self.time_zone {
+            TimeZoneSetting::Utc => to_utc(&Utc, adjust(time.naive_utc())),
+            TimeZoneSetting::Local => to_utc(&Local, adjust(time.with_timezone(&Local).naive_local())),
+            TimeZoneSetting::Fixed(offset) => to_utc(&offset, adjust(time.with_timezone(&offset).naive_local())),
+        }
+    }
+}
+
+/// Truncate a wall-clock time to the start of its bucket
+fn floor(local: NaiveDateTime, unit: CalendarUnit, week_start: Weekday) -> NaiveDateTime {
+    let date = local.date();
+    match unit {
+        CalendarUnit::Hour => midnight(date) + chrono::Duration::hours(local.hour() as i64),
+        CalendarUnit::Day => midnight(date),
+        CalendarUnit::Week => {
+            let into_week = (date.weekday().num_days_from_monday() + 7 - week_start.num_days_from_monday()) % 7;
+            midnight(date - Days::new(into_week as u64))
+        }
+        CalendarUnit::Month => midnight(date.with_day(1).unwrap_or(date)),
+    }
+}
+
+/// Step a wall-clock time by whole calendar units
+fn shift(local: NaiveDateTime, unit: CalendarUnit, count: u32, forward: bool) -> NaiveDateTime {
+    let shifted = match (unit, forward) {
+        (CalendarUnit::Hour, true) => local.checked_add_signed(chrono::Duration::hours(count as i64)),
+        (CalendarUnit::Hour, false) => local.checked_sub_signed(chrono::Duration::hours(count as i64)),
+        (CalendarUnit::Day, true) => local.checked_add_days(Days::new(count as u64)),
+        (CalendarUnit::Day, false) => local.checked_sub_days(Days::new(count as u64)),
+        (CalendarUnit::Week, true) => local.checked_add_days(Days::new(count as u64 * 7)),
+        (CalendarUnit::Week, false) => local.checked_sub_days(Days::new(count as u64 * 7)),
+        (CalendarUnit::Month, true) => local.checked_add_months(Months::new(count)),
+        (CalendarUnit::Month, false) => local.checked_sub_months(Months::new(count)),
+    };
+    shifted.unwrap_or(local)
+}
+
+fn midnight(date: NaiveDate) -> NaiveDateTime {
+    date.and_hms_opt(0, 0, 0).unwrap_or_default()
+}
+
+/// Resolve a wall-clock time, taking the earlier instant when it is ambiguous
+/// and the first instant after the gap when a clock change skipped it
+fn to_utc<Tz: TimeZone>(zone: &Tz, local: NaiveDateTime) -> DateTime<Utc> {
+    let mut candidate = local;
+    for _ in 0..3 {
+        if let Some(time) = zone.from_local_datetime(&candidate).earliest() {
+            return time.with_timezone(&Utc);
+        }
+        candidate += chrono::Duration::minutes(30);
+    }
+    Utc.from_utc_datetime(&local)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_days_split_at_configured_midnight() {
+        let sydney = CalendarConfig::default().with_time_zone("+10:00".parse().unwrap());
+        // 20:00 UTC is already 06:00 the next day in UTC+10
+        let time = utc("2026-03-04T20:00:00Z");
+        assert_eq!(sydney.bucket_start(time, CalendarUnit::Day), utc("2026-03-04T14:00:00Z"));
+        assert_eq!(CalendarConfig::default().bucket_start(time, CalendarUnit::Day), utc("2026-03-04T00:00:00Z"));
+        assert_eq!(sydney.format(time, "%Y-%m-%d"), "2026-03-05");
+
+        let days = sydney.buckets(utc("2026-03-04T13:00:00Z"), utc("2026-03-05T15:00:00Z"), CalendarUnit::Day);
+        assert_eq!(days, vec![utc("2026-03-03T14:00:00Z"), utc("2026-03-04T14:00:00Z"), utc("2026-03-05T14:00:00Z")]);
+    }
+
+    #[test]
+    fn test_week_and_month_boundaries() {
+        let calendar = CalendarConfig::default();
+        // 2026-03-04 is a Wednesday
+        let time = utc("2026-03-04T12:00:00Z");
+        assert_eq!(calendar.bucket_start(time, CalendarUnit::Week), utc("2026-03-02T00:00:00Z"));
+        assert_eq!(
+            calendar.with_week_start(Weekday::Sun).bucket_start(time, CalendarUnit::Week),
+            utc("2026-03-01T00:00:00Z")
+        );
+        assert_eq!(calendar.bucket_start(time, CalendarUnit::Month), utc("2026-03-01T00:00:00Z"));
+        assert_eq!(
+            calendar.next_bucket(utc("2026-01-01T00:00:00Z"), CalendarUnit::Month),
+            utc("2026-02-01T00:00:00Z")
+        );
+        assert_eq!(calendar.units_before(utc("2026-03-31T08:00:00Z"), CalendarUnit::Month, 1), utc("2026-02-28T08:00:00Z"));
+    }
+
+    #[test]
+    fn test_time_zone_setting_round_trips() {
+        let config: CalendarConfig = toml::from_str("time_zone = \"-03:30\"\nweek_start = \"sunday\"").unwrap();
+        assert_eq!(config.week_start, Weekday::Sun);
+        assert_eq!(config.time_zone.to_string(), "-03:30");
+        assert_eq!("local".parse::<TimeZoneSetting>().unwrap(), TimeZoneSetting::Local);
+        assert!("Europe/Berlin".parse::<TimeZoneSetting>().is_err());
+        assert_eq!(CalendarUnit::parse("DAYS"), Some(CalendarUnit::Day));
+    }
+}
@@ -0,0 +1,200 @@
+/// Effective configuration and environment, flattened for inspection
+///
+/// Support scripts debugging an install need to know which settings are in
+/// effect and what the install runs on. [`EnvironmentSnapshot`] flattens the
+/// effective [`UnifiedConfig`] into dotted fields (`cache.max_size_mb`) and
+/// adds environment info under `env.*`, compiled-in cargo features under
+/// `feature.*` and the versions of tools found on `PATH` under `tool.*`.
+///
+/// String values of fields that look like credentials are redacted.
+use super::unified::UnifiedConfig;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Tools whose versions are reported when found on `PATH`
+const TOOLS: &[&str] = &[
+    "git",
+    "cargo",
+    "rustc",
+    "rust-analyzer",
+    "node",
+    "typescript-language-server",
+    "pyright",
+    "gopls",
+    "clangd",
+];
+
+/// Longest a `--version` probe may take
+const TOOL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Words marking a field as holding a credential
+const SENSITIVE_WORDS: &[&str] = &["key", "token", "secret", "password", "credential"];
+
+/// Replacement for redacted values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// What an entry describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryCategory {
+    /// An effective configuration value
+    Config,
+    /// Build and host information
+    Environment,
+    /// A cargo feature compiled into this build
+    Feature,
+    /// Version of a tool found on `PATH`
+    Tool,
+}
+
+impl EntryCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntryCategory::Config => "config",
+            EntryCategory::Environment => "environment",
+            EntryCategory::Feature => "feature",
+            EntryCategory::Tool => "tool",
+        }
+    }
+}
+
+/// One configuration value or fact about the environment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnvironmentEntry {
+    pub field: String,
+    pub value: String,
+    pub category: EntryCategory,
+}
+
+/// Configuration and environment of a running install
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EnvironmentSnapshot {
+    pub entries: Vec<EnvironmentEntry>,
+}
+
+impl EnvironmentSnapshot {
+    /// Snapshot of `config` and the build, without probing tools
+    pub fn from_config(config: &UnifiedConfig) -> Result<Self> {
+        let mut snapshot = Self::default();
+
+        let mut flattened = Vec::new();
+        flatten("", &serde_json::to_value(config)?, &mut flattened);
+        for (field, value) in flattened {
+            snapshot.push(EntryCategory::Config, field, value);
+        }
+
+        snapshot.push(EntryCategory::Environment, "env.version", env!("CARGO_PKG_VERSION"));
+        snapshot.push(EntryCategory::Environment, "env.os", std::env::consts::OS);
+        snapshot.push(EntryCategory::Environment, "env.arch", std::env::consts::ARCH);
+        if let Ok(dir) = crate::config::config_dir() {
+            snapshot.push(EntryCategory::Environment, "env.config_dir", dir.display().to_string());
+        }
+        if let Ok(cwd) = std::env::current_dir() {
+            snapshot.push(EntryCategory::Environment, "env.cwd", cwd.display().to_string());
+        }
+
+        let features = [
+            ("cli", cfg!(feature = "cli")),
+            ("git-integration", cfg!(feature = "git-integration")),
+            ("network", cfg!(feature = "network")),
+            ("experimental", cfg!(feature = "experimental")),
+        ];
+        for (feature, enabled) in features {
+            snapshot.push(EntryCategory::Feature, format!("feature.{feature}"), enabled.to_string());
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Snapshot of `config`, the build, and the versions of tools on `PATH`
+    pub async fn capture(config: &UnifiedConfig) -> Result<Self> {
+        let mut snapshot = Self::from_config(config)?;
+        let probes = TOOLS.iter().map(|tool| async move { (*tool, tool_version(tool).await) });
+        for (tool, version) in futures::future::join_all(probes).await {
+            if let Some(version) = version {
+                snapshot.push(EntryCategory::Tool, format!("tool.{tool}"), version);
+            }
+        }
+        Ok(snapshot)
+    }
+
+    fn push(&mut self, category: EntryCategory, field: impl Into<String>, value: impl Into<String>) {
+        self.entries.push(EnvironmentEntry {
+            field: field.into(),
+            value: value.into(),
+            category,
+        });
+    }
+}
+
+/// Flatten a JSON value into dotted fields; arrays are kept as JSON
+fn flatten(prefix: &str, value: &serde_json::Value, out: &mut Vec<(String, String)>) {
+    match value {
+        serde_json::Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                let field = if prefix.is_empty() { key.clone() } else { format!("{prefix}.{key}") };
+                flatten(&field, value, out);
+            }
+        }
+        serde_json::Value::String(text) => {
+            let value = if is_sensitive(prefix) && !text.is_empty() { REDACTED.to_string() } else { text.clone() };
+            out.push((prefix.to_string(), value));
+        }
+        serde_json::Value::Null => out.push((prefix.to_string(), String::new())),
+        other => out.push((prefix.to_string(), other.to_string())),
+    }
+}
+
+fn is_sensitive(field: &str) -> bool {
+    let name = field.rsplit('.').next().unwrap_or(field).to_lowercase();
+    SENSITIVE_WORDS.iter().any(|word| name.contains(word))
+}
+
+/// First line of `tool --version`, if the tool runs
+async fn tool_version(tool: &str) -> Option<String> {
+    let output = tokio::process::Command::new(tool)
+        .arg("--version")
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(TOOL_TIMEOUT, output).await.ok()?.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.lines().next().map(|line| line.trim().to_string()).filter(|line| !line.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_is_flattened_and_redacted() -> Result<()> {
+        let snapshot = EnvironmentSnapshot::from_config(&UnifiedConfig::default())?;
+        let value = |field: &str| {
+            snapshot
+                .entries
+                .iter()
+                .find(|entry| entry.field == field)
+                .map(|entry| (entry.value.clone(), entry.category))
+        };
+
+        let (max_size, category) = value("cache.max_size_mb").unwrap();
+        assert_eq!(max_size, UnifiedConfig::default().cache.max_size_mb.to_string());
+        assert_eq!(category, EntryCategory::Config);
+        assert_eq!(value("env.version").unwrap().0, env!("CARGO_PKG_VERSION"));
+        assert_eq!(value("feature.cli").unwrap().1, EntryCategory::Feature);
+
+        let mut flattened = Vec::new();
+        flatten("", &serde_json::json!({ "webhook": { "api_token": "abc", "url": "https://x" } }), &mut flattened);
+        assert_eq!(
+            flattened,
+            [
+                ("webhook.api_token".to_string(), REDACTED.to_string()),
+                ("webhook.url".to_string(), "https://x".to_string()),
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,323 @@
+//! Migrating legacy configuration into the unified schema
+//!
+//! Before the unified config, multi-repo settings lived in their own
+//! `multi-repo.toml` (the deprecated `multi_repo::MultiRepoConfig`), and
+//! `lspbridge.toml` used the field names of the dynamic config and the
+//! original `BridgeConfig` layout. `lspbridge config migrate` folds both into
+//! one unified `lspbridge.toml`, keeping backups of the files it replaces
+//! and reporting settings that have no place in the unified schema.
+//!
+//! Until a project is migrated, [`UnifiedConfig::load`] accepts the legacy
+//! field names through the same mapping and warns on every load.
+
+use super::unified::UnifiedConfig;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use toml::value::Table;
+use toml::Value;
+
+/// File name of the unified configuration
+pub const CONFIG_FILE: &str = "lspbridge.toml";
+
+/// File name of the deprecated standalone multi-repo configuration
+pub const LEGACY_MULTI_REPO_FILE: &str = "multi-repo.toml";
+
+/// How a legacy value is converted for its unified field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Conversion {
+    Unchanged,
+    SecondsToHours,
+}
+
+/// Legacy field paths and the unified fields they became
+const RENAMED_FIELDS: &[(&str, &str, Conversion)] = &[
+    ("processing.parallel_processing", "performance.parallel_processing", Conversion::Unchanged),
+    ("processing.chunk_size", "performance.chunk_size", Conversion::Unchanged),
+    ("processing.max_concurrent_files", "performance.max_concurrent_files", Conversion::Unchanged),
+    ("processing.file_size_limit_mb", "performance.file_size_limit_mb", Conversion::Unchanged),
+    ("processing.timeout_seconds", "timeouts.processing_timeout_seconds", Conversion::Unchanged),
+    ("cache.enable_memory_cache", "cache.enable_cache", Conversion::Unchanged),
+    ("cache.max_age_seconds", "cache.ttl_hours", Conversion::SecondsToHours),
+    ("features.enable_smart_caching", "features.auto_optimization", Conversion::Unchanged),
+    ("features.enable_advanced_filtering", "features.health_monitoring", Conversion::Unchanged),
+    ("features.enable_batch_processing", "features.cache_warming", Conversion::Unchanged),
+    ("features.enable_experimental_features", "features.experimental_features", Conversion::Unchanged),
+];
+
+/// Top-level sections of the `BridgeConfig` layout with no unified counterpart
+const LEGACY_SECTIONS: &[&str] = &["processing", "capture", "export"];
+
+/// Result of mapping legacy configuration onto the unified schema
+#[derive(Debug, Clone)]
+pub struct ConfigMigration {
+    pub config: UnifiedConfig,
+    /// Legacy fields moved to their unified name, as `(old, new)`
+    pub renamed: Vec<(String, String)>,
+    /// Legacy fields the unified schema has no place for; they are dropped
+    pub unmapped: Vec<String>,
+}
+
+impl ConfigMigration {
+    /// Map a parsed `lspbridge.toml` and `multi-repo.toml` onto the unified schema
+    ///
+    /// Settings missing from both keep their defaults. A setting present in
+    /// both files keeps the `lspbridge.toml` value.
+    pub fn from_documents(config: Option<Value>, multi_repo: Option<Value>) -> Result<Self> {
+        let mut document = match config {
+            Some(Value::Table(table)) => table,
+            Some(_) => anyhow::bail!("Configuration must be a table"),
+            None => Table::new(),
+        };
+
+        let mut renamed = Vec::new();
+        for (old, new, conversion) in RENAMED_FIELDS {
+            let Some(value) = take_path(&mut document, old) else {
+                continue;
+            };
+            if get_path(&document, new).is_none() {
+                set_path(&mut document, new, convert(value, *conversion));
+            }
+            renamed.push((old.to_string(), new.to_string()));
+        }
+        document.retain(|_, value| !matches!(value, Value::Table(table) if table.is_empty()));
+
+        if let Some(multi_repo) = multi_repo {
+            let Value::Table(legacy) = multi_repo else {
+                anyhow::bail!("{LEGACY_MULTI_REPO_FILE} must be a table");
+            };
+            let section = document
+                .entry("multi_repo")
+                .or_insert_with(|| Value::Table(Table::new()));
+            if let Value::Table(section) = section {
+                for (key, value) in legacy {
+                    section.entry(key).or_insert(value);
+                }
+            }
+        }
+
+        let Value::Table(mut merged) = Value::try_from(UnifiedConfig::default())? else {
+            anyhow::bail!("Default configuration is not a table");
+        };
+        merge(&mut merged, document.clone());
+        let config: UnifiedConfig = Value::Table(merged)
+            .try_into()
+            .context("Migrated configuration does not match the unified schema")?;
+
+        // Whatever didn't survive a round trip through the schema was ignored by it
+        let written = Value::try_from(&config)?;
+        let unmapped = leaf_paths(&document, "")
+            .into_iter()
+            .filter(|path| !contains_path(&written, path))
+            .collect();
+
+        Ok(Self {
+            config,
+            renamed,
+            unmapped,
+        })
+    }
+
+    /// Human-readable summary of what the migration changed
+    pub fn summary(&self) -> String {
+        let mut out = String::new();
+        for (old, new) in &self.renamed {
+            out.push_str(&format!("  renamed {old} -> {new}\n"));
+        }
+        for field in &self.unmapped {
+            out.push_str(&format!("  dropped {field} (no unified equivalent)\n"));
+        }
+        if out.is_empty() {
+            out.push_str("  no legacy fields\n");
+        }
+        out
+    }
+}
+
+/// Legacy fields present in a parsed `lspbridge.toml`
+pub fn legacy_fields(document: &Value) -> Vec<String> {
+    let Value::Table(table) = document else {
+        return Vec::new();
+    };
+    let mut fields: Vec<String> = RENAMED_FIELDS
+        .iter()
+        .filter(|(old, _, _)| get_path(table, old).is_some())
+        .map(|(old, _, _)| old.to_string())
+        .collect();
+    for section in LEGACY_SECTIONS {
+        if table.contains_key(*section) && !fields.iter().any(|field| field.starts_with(&format!("{section}."))) {
+            fields.push(section.to_string());
+        }
+    }
+    fields
+}
+
+/// Legacy configuration files found in a project directory
+#[derive(Debug, Clone, Default)]
+pub struct LegacyConfigFiles {
+    /// `lspbridge.toml`, when it still uses legacy fields
+    pub config: Option<PathBuf>,
+    /// A standalone `multi-repo.toml`
+    pub multi_repo: Option<PathBuf>,
+}
+
+impl LegacyConfigFiles {
+    pub fn detect(dir: &Path) -> Result<Self> {
+        let config_path = dir.join(CONFIG_FILE);
+        let config = if config_path.exists() {
+            let document = read_document(&config_path)?;
+            (!legacy_fields(&document).is_empty()).then_some(config_path)
+        } else {
+            None
+        };
+        let multi_repo = Some(dir.join(LEGACY_MULTI_REPO_FILE)).filter(|path| path.exists());
+        Ok(Self { config, multi_repo })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.config.is_none() && self.multi_repo.is_none()
+    }
+
+    /// Map the files found in `dir` onto the unified schema
+    pub fn migrate(&self, dir: &Path) -> Result<ConfigMigration> {
+        let config_path = dir.join(CONFIG_FILE);
+        let config = if config_path.exists() {
+            Some(read_document(&config_path)?)
+        } else {
+            None
+        };
+        let multi_repo = self.multi_repo.as_deref().map(read_document).transpose()?;
+        ConfigMigration::from_documents(config, multi_repo)
+    }
+}
+
+/// Parse a config file as TOML, or as JSON, which the legacy loader wrote
+pub fn read_document(path: &Path) -> Result<Value> {
+    let content = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    match toml::from_str(&content) {
+        Ok(document) => Ok(document),
+        Err(toml_error) => match serde_json::from_str::<serde_json::Value>(&content) {
+            Ok(json) => Value::try_from(json).with_context(|| format!("Unsupported values in {}", path.display())),
+            Err(_) => Err(toml_error).with_context(|| format!("Failed to parse {}", path.display())),
+        },
+    }
+}
+
+/// Copy `path` next to itself with a timestamped `.bak` suffix
+pub fn backup_file(path: &Path) -> Result<PathBuf> {
+    let stamp = chrono::Utc::now().format("%Y%m%d-%H%M%S");
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let backup = path.with_file_name(format!("{name}.{stamp}.bak"));
+    std::fs::copy(path, &backup).with_context(|| format!("Failed to back up {}", path.display()))?;
+    Ok(backup)
+}
+
+fn convert(value: Value, conversion: Conversion) -> Value {
+    match (conversion, &value) {
+        (Conversion::SecondsToHours, Value::Integer(seconds)) => Value::Integer((seconds + 3599) / 3600),
+        _ => value,
+    }
+}
+
+fn get_path<'a>(table: &'a Table, path: &str) -> Option<&'a Value> {
+    let (section, key) = path.split_once('.')?;
+    table.get(section)?.get(key)
+}
+
+fn take_path(table: &mut Table, path: &str) -> Option<Value> {
+    let (section, key) = path.split_once('.')?;
+    table.get_mut(section)?.as_table_mut()?.remove(key)
+}
+
+fn set_path(table: &mut Table, path: &str, value: Value) {
+    let Some((section, key)) = path.split_once('.') else {
+        return;
+    };
+    if let Value::Table(section) = table
+        .entry(section)
+        .or_insert_with(|| Value::Table(Table::new()))
+    {
+        section.insert(key.to_string(), value);
+    }
+}
+
+fn contains_path(value: &Value, path: &str) -> bool {
+    path.split('.')
+        .try_fold(value, |current, key| current.get(key))
+        .is_some()
+}
+
+/// Overlay `overrides` onto `base`, recursing into tables
+fn merge(base: &mut Table, overrides: Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(overrides)) => merge(base, overrides),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Dotted paths of every non-table value
+fn leaf_paths(table: &Table, prefix: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    for (key, value) in table {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match value {
+            Value::Table(table) => paths.extend(leaf_paths(table, &path)),
+            _ => paths.push(path),
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_fields_and_multi_repo_file_migrate() -> Result<()> {
+        let config: Value = toml::from_str(
+            r#"
+            [processing]
+            chunk_size = 250
+            timeout_seconds = 45
+
+            [cache]
+            max_age_seconds = 7200
+            max_snapshots = 10
+
+            [capture]
+            real_time = true
+
+            [debt]
+            info_after_days = 7
+            "#,
+        )?;
+        assert_eq!(
+            legacy_fields(&config),
+            vec!["processing.chunk_size", "processing.timeout_seconds", "cache.max_age_seconds", "capture"]
+        );
+
+        let multi_repo: Value = toml::from_str("max_concurrent_repos = 8\nregistry_path = \"repos.db\"\n")?;
+        let migration = ConfigMigration::from_documents(Some(config), Some(multi_repo))?;
+
+        assert_eq!(migration.config.performance.chunk_size, 250);
+        assert_eq!(migration.config.timeouts.processing_timeout_seconds, 45);
+        assert_eq!(migration.config.cache.ttl_hours, 2);
+        assert_eq!(migration.config.multi_repo.max_concurrent_repos, 8);
+        assert_eq!(migration.config.multi_repo.registry_path, PathBuf::from("repos.db"));
+        assert_eq!(migration.config.debt.info_after_days, Some(7));
+        assert_eq!(migration.renamed.len(), 3);
+        assert_eq!(migration.unmapped, vec!["cache.max_snapshots", "capture.real_time"]);
+
+        // The migrated file loads without the compatibility mapping
+        let written = Value::try_from(&migration.config)?;
+        assert!(legacy_fields(&written).is_empty());
+        Ok(())
+    }
+}
@@ -7,6 +7,8 @@
 /// - Configuration traits for type-safe access patterns
 /// - Migration utilities for backward compatibility
 /// - Validation and serialization support
+pub mod environment;
+pub mod migration;
 pub mod traits;
 pub mod unified;
 
@@ -17,6 +19,8 @@ pub use traits::{
     PerformanceConfig, TimeoutConfig,
 };
 
+pub use environment::{EntryCategory, EnvironmentEntry, EnvironmentSnapshot};
+pub use migration::{ConfigMigration, LegacyConfigFiles};
 pub use unified::{ErrorRecoveryConfig, FeatureFlags, MetricsConfig, UnifiedConfig};
 
 
@@ -183,6 +183,10 @@ pub struct MultiRepoConfig {
     pub registry_path: PathBuf,
     /// Path to the team collaboration database
     pub team_db_path: Option<PathBuf>,
+    /// PostgreSQL URL of a registry and team database shared across machines;
+    /// replaces `registry_path` and `team_db_path` when set
+    #[serde(default)]
+    pub registry_url: Option<String>,
     /// Enable automatic monorepo detection
     pub auto_detect_monorepo: bool,
     /// Enable cross-repository type tracking
@@ -191,6 +195,9 @@ pub struct MultiRepoConfig {
     pub max_concurrent_repos: usize,
     /// Cache directory for cross-repo analysis
     pub cache_dir: PathBuf,
+    /// Email and Slack notifications about diagnostic assignments
+    #[serde(default)]
+    pub notifications: crate::multi_repo::collaboration::NotificationConfig,
 }
 
 impl Default for MultiRepoConfig {
@@ -198,10 +205,12 @@ impl Default for MultiRepoConfig {
         Self {
             registry_path: PathBuf::from(".lspbridge/repos.db"),
             team_db_path: None,
+            registry_url: None,
             auto_detect_monorepo: true,
             enable_cross_repo_types: true,
             max_concurrent_repos: 4,
             cache_dir: PathBuf::from(".lspbridge/cache/multi-repo"),
+            notifications: Default::default(),
         }
     }
 }
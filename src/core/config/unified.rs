@@ -47,6 +47,50 @@ pub struct UnifiedConfig {
 
     /// Privacy policy configuration for diagnostic filtering
     pub privacy: crate::core::PrivacyPolicy,
+
+    /// Re-attribution of diagnostics in generated files
+    #[serde(default)]
+    pub generated_code: crate::core::GeneratedCodeConfig,
+
+    /// Static checks run by `lspbridge scan`
+    #[serde(default)]
+    pub scan: crate::core::ScanConfig,
+
+    /// Monthly per-client quotas for the query API
+    #[serde(default)]
+    pub api_quotas: crate::core::QuotaConfig,
+
+    /// Retry and offline queue settings for network operations
+    #[serde(default)]
+    pub network: crate::core::NetworkConfig,
+
+    /// Periodic backups of the history and team databases
+    #[serde(default)]
+    pub backup: crate::core::BackupConfig,
+
+    /// Routes splitting `lspbridge export --route` between targets
+    #[serde(default)]
+    pub export_routing: crate::core::ExportRoutingConfig,
+
+    /// Managed language server installs and their pinned versions
+    #[serde(default)]
+    pub servers: crate::core::ServersConfig,
+
+    /// Time zone and week start for time ranges and calendar buckets
+    #[serde(default)]
+    pub calendar: crate::core::CalendarConfig,
+
+    /// Aging thresholds for TODOs, FIXMEs and deprecations
+    #[serde(default)]
+    pub debt: crate::core::DebtConfig,
+
+    /// Rules promoting or demoting diagnostics by source and code
+    #[serde(default)]
+    pub severity_rules: crate::core::SeverityRulesConfig,
+
+    /// Scripts whose metrics the health dashboard collects
+    #[serde(default)]
+    pub metric_plugins: Vec<crate::core::health_dashboard::metrics::ScriptPluginConfig>,
 }
 
 /// Error recovery configuration
@@ -85,6 +129,12 @@ pub struct MetricsConfig {
     pub collection_interval_seconds: u64,
     pub retention_hours: u64,
     pub export_format: String,
+    #[serde(default)]
+    pub enable_opentelemetry: bool,
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+    #[serde(default = "crate::core::dynamic_config::types::default_service_name")]
+    pub service_name: String,
 }
 
 impl Default for MetricsConfig {
@@ -95,6 +145,9 @@ impl Default for MetricsConfig {
             collection_interval_seconds: 10,
             retention_hours: 72,
             export_format: "prometheus".to_string(),
+            enable_opentelemetry: false,
+            otlp_endpoint: None,
+            service_name: crate::core::dynamic_config::types::default_service_name(),
         }
     }
 }
@@ -137,6 +190,17 @@ impl Default for UnifiedConfig {
             features: FeatureFlags::default(),
             security: security.clone(),
             privacy: crate::core::PrivacyPolicy::default(),
+            generated_code: crate::core::GeneratedCodeConfig::default(),
+            scan: crate::core::ScanConfig::default(),
+            api_quotas: crate::core::QuotaConfig::default(),
+            network: crate::core::NetworkConfig::default(),
+            backup: crate::core::BackupConfig::default(),
+            export_routing: crate::core::ExportRoutingConfig::default(),
+            servers: crate::core::ServersConfig::default(),
+            calendar: crate::core::CalendarConfig::default(),
+            debt: crate::core::DebtConfig::default(),
+            severity_rules: crate::core::SeverityRulesConfig::default(),
+            metric_plugins: Vec::new(),
         };
         
         // Apply security config to ensure secure defaults
@@ -173,6 +237,17 @@ impl UnifiedConfig {
             },
             security: security.clone(),
             privacy: crate::core::PrivacyPolicy::strict(),
+            generated_code: crate::core::GeneratedCodeConfig::default(),
+            scan: crate::core::ScanConfig::default(),
+            api_quotas: crate::core::QuotaConfig::default(),
+            network: crate::core::NetworkConfig::default(),
+            backup: crate::core::BackupConfig::default(),
+            export_routing: crate::core::ExportRoutingConfig::default(),
+            servers: crate::core::ServersConfig::default(),
+            calendar: crate::core::CalendarConfig::default(),
+            debt: crate::core::DebtConfig::default(),
+            severity_rules: crate::core::SeverityRulesConfig::default(),
+            metric_plugins: Vec::new(),
         };
         
         // Apply strict security constraints
@@ -219,6 +294,17 @@ impl UnifiedConfig {
             },
             security: security.clone(),
             privacy: crate::core::PrivacyPolicy::permissive(),
+            generated_code: crate::core::GeneratedCodeConfig::default(),
+            scan: crate::core::ScanConfig::default(),
+            api_quotas: crate::core::QuotaConfig::default(),
+            network: crate::core::NetworkConfig::default(),
+            backup: crate::core::BackupConfig::default(),
+            export_routing: crate::core::ExportRoutingConfig::default(),
+            servers: crate::core::ServersConfig::default(),
+            calendar: crate::core::CalendarConfig::default(),
+            debt: crate::core::DebtConfig::default(),
+            severity_rules: crate::core::SeverityRulesConfig::default(),
+            metric_plugins: Vec::new(),
             ..Self::default()
         };
         
@@ -273,6 +359,17 @@ impl UnifiedConfig {
             },
             security,
             privacy: crate::core::PrivacyPolicy::default(),
+            generated_code: crate::core::GeneratedCodeConfig::default(),
+            scan: crate::core::ScanConfig::default(),
+            api_quotas: crate::core::QuotaConfig::default(),
+            network: crate::core::NetworkConfig::default(),
+            backup: crate::core::BackupConfig::default(),
+            export_routing: crate::core::ExportRoutingConfig::default(),
+            servers: crate::core::ServersConfig::default(),
+            calendar: crate::core::CalendarConfig::default(),
+            debt: crate::core::DebtConfig::default(),
+            severity_rules: crate::core::SeverityRulesConfig::default(),
+            metric_plugins: Vec::new(),
             ..Self::default()
         }
     }
@@ -287,9 +384,30 @@ impl UnifiedConfig {
     }
 
     /// Load configuration from TOML file
+    ///
+    /// Files still using legacy field names are mapped onto the unified
+    /// schema with a warning until `lspbridge config migrate` rewrites them.
     pub async fn load(path: &Path) -> Result<Self> {
         let content = tokio::fs::read_to_string(path).await?;
-        let config: Self = toml::from_str(&content)?;
+        let document: toml::Value = toml::from_str(&content)?;
+        let legacy = super::migration::legacy_fields(&document);
+        let config: Self = if legacy.is_empty() {
+            toml::from_str(&content)?
+        } else {
+            tracing::warn!(
+                "{} uses legacy configuration fields ({}); run `lspbridge config migrate` to update it",
+                path.display(),
+                legacy.join(", ")
+            );
+            super::migration::ConfigMigration::from_documents(Some(document), None)?.config
+        };
+        if path.with_file_name(super::migration::LEGACY_MULTI_REPO_FILE).exists() {
+            tracing::warn!(
+                "{} is no longer read; run `lspbridge config migrate` to move it into {}",
+                super::migration::LEGACY_MULTI_REPO_FILE,
+                path.display()
+            );
+        }
         config.validate()?;
         Ok(config)
     }
@@ -467,6 +585,9 @@ impl UnifiedConfig {
                 collection_interval_seconds: dynamic.metrics.collection_interval_seconds,
                 retention_hours: dynamic.metrics.retention_hours,
                 export_format: dynamic.metrics.export_format.clone(),
+                enable_opentelemetry: dynamic.metrics.enable_opentelemetry,
+                otlp_endpoint: dynamic.metrics.otlp_endpoint.clone(),
+                service_name: dynamic.metrics.service_name.clone(),
             },
             features: FeatureFlags {
                 auto_optimization: dynamic.features.enable_smart_caching,
@@ -477,6 +598,17 @@ impl UnifiedConfig {
             },
             security: SecurityConfig::default(), // Not in dynamic config
             privacy: crate::core::PrivacyPolicy::default(), // Not in dynamic config
+            generated_code: crate::core::GeneratedCodeConfig::default(),
+            scan: crate::core::ScanConfig::default(),
+            api_quotas: crate::core::QuotaConfig::default(),
+            network: crate::core::NetworkConfig::default(),
+            backup: crate::core::BackupConfig::default(),
+            export_routing: crate::core::ExportRoutingConfig::default(),
+            servers: crate::core::ServersConfig::default(),
+            calendar: crate::core::CalendarConfig::default(),
+            debt: crate::core::DebtConfig::default(),
+            severity_rules: dynamic.severity_rules.clone(),
+            metric_plugins: Vec::new(),
         }
     }
 
@@ -533,6 +665,9 @@ impl UnifiedConfig {
                 collection_interval_seconds: self.metrics.collection_interval_seconds,
                 retention_hours: self.metrics.retention_hours,
                 export_format: self.metrics.export_format.clone(),
+                enable_opentelemetry: self.metrics.enable_opentelemetry,
+                otlp_endpoint: self.metrics.otlp_endpoint.clone(),
+                service_name: self.metrics.service_name.clone(),
             },
             features: crate::core::dynamic_config::FeatureFlags {
                 enable_smart_caching: self.features.auto_optimization,
@@ -545,6 +680,11 @@ impl UnifiedConfig {
                 io_priority: "normal".to_string(), // Default
                 enable_parallel_io: self.performance.parallel_processing,
             },
+            privacy: crate::core::DynamicPrivacyConfig {
+                redaction_patterns: self.privacy.redaction_patterns.clone(),
+                ..Default::default()
+            },
+            severity_rules: self.severity_rules.clone(),
         }
     }
 }
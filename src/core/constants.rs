@@ -110,6 +110,9 @@ pub mod metadata_keys {
     pub const CONFIDENCE: &str = "confidence";
     pub const WORKSPACE: &str = "workspace";
     pub const TIMESTAMP: &str = "timestamp";
+    pub const MUTATION: &str = "mutation";
+    pub const ERROR_STAGE: &str = "error_stage";
+    pub const VERIFIED: &str = "verified";
 }
 
 /// LSP and editor integration constants
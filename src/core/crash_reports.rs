@@ -0,0 +1,498 @@
+//! Runtime crash reports correlated with static diagnostics
+//!
+//! A warning that the language server reports on a line that also shows up
+//! in production stack traces deserves more attention than its severity
+//! suggests. [`CrashReportParser`] reads crash reports out of log files:
+//!
+//! - Rust panics (`thread 'main' panicked at src/main.rs:10:5:`) with their
+//!   `RUST_BACKTRACE` frames
+//! - Node.js stack traces (`    at handler (/app/src/index.js:10:5)`)
+//! - Python tracebacks (`  File "/app/app.py", line 10, in handler`)
+//!
+//! [`CrashCorrelator`] then matches the frames against diagnostics by file
+//! and line and tags matching diagnostics under [`CRASH_KEY`] in their
+//! `data`. Frames from deployed builds usually carry a different path
+//! prefix (`/app/src/...` vs `/home/me/project/src/...`), so files match
+//! when one path ends with the other's workspace-relative components.
+
+use super::types::Diagnostic;
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Key in [`Diagnostic::data`] holding the [`CrashCorrelation`] of a diagnostic
+pub const CRASH_KEY: &str = "lspbridgeCrash";
+
+/// Runtime a crash report came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CrashKind {
+    RustPanic,
+    NodeStackTrace,
+    PythonTraceback,
+}
+
+impl std::fmt::Display for CrashKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            CrashKind::RustPanic => "Rust panic",
+            CrashKind::NodeStackTrace => "Node.js exception",
+            CrashKind::PythonTraceback => "Python exception",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A source location in a stack trace; `line` is 1-based
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrashFrame {
+    pub file: String,
+    pub line: u32,
+    pub function: Option<String>,
+}
+
+/// A crash parsed from a log, frames ordered from the crash site outwards
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub kind: CrashKind,
+    pub message: String,
+    pub frames: Vec<CrashFrame>,
+}
+
+/// Crashes seen at a diagnostic's location
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashCorrelation {
+    pub kind: CrashKind,
+    /// Message of the first matching crash
+    pub message: String,
+    pub function: Option<String>,
+    /// Number of crash reports with a frame at this location
+    pub occurrences: usize,
+    /// Whether the location is the innermost frame, i.e. where the crash happened
+    pub at_crash_site: bool,
+}
+
+fn rust_panic_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    // `panicked at src/main.rs:10:5:` (1.73+) or `panicked at 'msg', src/main.rs:10:5`
+    RE.get_or_init(|| {
+        Regex::new(r"thread '[^']*' panicked at (?:'(?P<msg>.*)', )?(?P<file>[^\s:]+):(?P<line>\d+):\d+").unwrap()
+    })
+}
+
+fn rust_frame_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^\s+at (?P<file>[^\s:]+):(?P<line>\d+)(?::\d+)?\s*$").unwrap())
+}
+
+fn rust_function_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^\s*\d+: (?P<function>\S+)").unwrap())
+}
+
+fn node_frame_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"^\s+at (?:(?P<function>[^(]+?) \()?(?:file://)?(?P<file>[^():]+):(?P<line>\d+):\d+\)?\s*$").unwrap()
+    })
+}
+
+fn node_error_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^(?:Uncaught )?(?P<msg>[A-Za-z]*Error\b.*)$").unwrap())
+}
+
+fn python_frame_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r#"^\s+File "(?P<file>[^"]+)", line (?P<line>\d+)(?:, in (?P<function>\S+))?"#).unwrap())
+}
+
+fn python_exception_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^(?P<msg>[A-Za-z_][\w.]*(?:Error|Exception|Interrupt|Exit)\b.*)$").unwrap())
+}
+
+/// Extracts crash reports from log text
+pub struct CrashReportParser;
+
+impl CrashReportParser {
+    /// Parse crash reports from a log file
+    pub fn parse_file(path: &Path) -> Result<Vec<CrashReport>> {
+        let log = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read crash log {}", path.display()))?;
+        Ok(Self::parse(&log))
+    }
+
+    /// Parse every crash report in a log, in order of appearance
+    pub fn parse(log: &str) -> Vec<CrashReport> {
+        let lines: Vec<&str> = log.lines().collect();
+        let mut reports = Vec::new();
+        let mut i = 0;
+
+        while i < lines.len() {
+            let line = lines[i];
+            let parsed = if let Some(caps) = rust_panic_re().captures(line) {
+                Some(Self::rust_panic(&lines, i, &caps))
+            } else if line.trim_start().starts_with("Traceback (most recent call last):") {
+                Some(Self::python_traceback(&lines, i))
+            } else if node_error_re().is_match(line.trim_start())
+                && lines.get(i + 1).is_some_and(|next| node_frame_re().is_match(next))
+            {
+                Some(Self::node_stack_trace(&lines, i))
+            } else {
+                None
+            };
+
+            match parsed {
+                Some((report, next)) => {
+                    if !report.frames.is_empty() {
+                        reports.push(report);
+                    }
+                    i = next.max(i + 1);
+                }
+                None => i += 1,
+            }
+        }
+
+        reports
+    }
+
+    fn rust_panic(lines: &[&str], start: usize, caps: &regex::Captures) -> (CrashReport, usize) {
+        let mut frames = vec![CrashFrame {
+            file: caps["file"].to_string(),
+            line: caps["line"].parse().unwrap_or(0),
+            function: None,
+        }];
+        let mut i = start + 1;
+
+        // Since 1.73 the message follows on its own line
+        let message = match caps.name("msg") {
+            Some(msg) => msg.as_str().to_string(),
+            None => {
+                let message = lines.get(i).map(|l| l.trim().to_string()).unwrap_or_default();
+                i += 1;
+                message
+            }
+        };
+
+        // Backtrace frames: `  N: function` followed by `at file:line:col`
+        let mut function = None;
+        while i < lines.len() {
+            let line = lines[i];
+            if let Some(caps) = rust_function_re().captures(line) {
+                function = Some(caps["function"].to_string());
+            } else if let Some(caps) = rust_frame_re().captures(line) {
+                let frame = CrashFrame {
+                    file: caps["file"].to_string(),
+                    line: caps["line"].parse().unwrap_or(0),
+                    function: function.take(),
+                };
+                if frame.line == frames[0].line && components(&frame.file) == components(&frames[0].file) {
+                    // The panic site again; keep the function name it adds
+                    frames[0].function = frame.function;
+                } else if !frame.file.starts_with("/rustc/") && !frame.file.contains("/.cargo/registry/") {
+                    // std and dependency frames are skipped
+                    frames.push(frame);
+                }
+            } else if !(line.trim().is_empty() || line.contains("stack backtrace:") || line.starts_with("note:")) {
+                break;
+            }
+            i += 1;
+        }
+
+        (
+            CrashReport {
+                kind: CrashKind::RustPanic,
+                message,
+                frames,
+            },
+            i,
+        )
+    }
+
+    fn node_stack_trace(lines: &[&str], start: usize) -> (CrashReport, usize) {
+        let message = lines[start].trim().trim_start_matches("Uncaught ").to_string();
+        let mut frames = Vec::new();
+        let mut i = start + 1;
+
+        while let Some(line) = lines.get(i).filter(|line| line.trim_start().starts_with("at ")) {
+            // Runtime internals (`node:internal/...`) don't match and are skipped
+            if let Some(caps) = node_frame_re().captures(line) {
+                let file = caps["file"].trim().to_string();
+                if !file.contains("/node_modules/") {
+                    frames.push(CrashFrame {
+                        file,
+                        line: caps["line"].parse().unwrap_or(0),
+                        function: caps.name("function").map(|f| f.as_str().trim().to_string()),
+                    });
+                }
+            }
+            i += 1;
+        }
+
+        (
+            CrashReport {
+                kind: CrashKind::NodeStackTrace,
+                message,
+                frames,
+            },
+            i,
+        )
+    }
+
+    fn python_traceback(lines: &[&str], start: usize) -> (CrashReport, usize) {
+        let mut frames = Vec::new();
+        let mut i = start + 1;
+
+        while i < lines.len() {
+            let line = lines[i];
+            if let Some(caps) = python_frame_re().captures(line) {
+                frames.push(CrashFrame {
+                    file: caps["file"].to_string(),
+                    line: caps["line"].parse().unwrap_or(0),
+                    function: caps.name("function").map(|f| f.as_str().to_string()),
+                });
+            } else if !line.starts_with(' ') {
+                break;
+            }
+            i += 1;
+        }
+
+        let message = lines
+            .get(i)
+            .filter(|line| python_exception_re().is_match(line))
+            .map(|line| line.trim().to_string())
+            .unwrap_or_default();
+        if !message.is_empty() {
+            i += 1;
+        }
+
+        // Python prints the innermost call last
+        frames.reverse();
+        frames.retain(|frame| !frame.file.contains("site-packages") && !frame.file.starts_with('<'));
+
+        (
+            CrashReport {
+                kind: CrashKind::PythonTraceback,
+                message,
+                frames,
+            },
+            i,
+        )
+    }
+}
+
+/// Tags diagnostics that sit on lines appearing in crash reports
+pub struct CrashCorrelator {
+    /// (frame, index into `reports`, innermost frame) keyed by file name
+    frames: HashMap<String, Vec<(CrashFrame, usize, bool)>>,
+    reports: Vec<CrashReport>,
+    root: Option<PathBuf>,
+}
+
+impl CrashCorrelator {
+    pub fn new(reports: Vec<CrashReport>) -> Self {
+        let mut frames: HashMap<String, Vec<(CrashFrame, usize, bool)>> = HashMap::new();
+        for (index, report) in reports.iter().enumerate() {
+            for (depth, frame) in report.frames.iter().enumerate() {
+                let Some(name) = file_name(&frame.file) else {
+                    continue;
+                };
+                frames.entry(name).or_default().push((frame.clone(), index, depth == 0));
+            }
+        }
+        Self {
+            frames,
+            reports,
+            root: None,
+        }
+    }
+
+    /// Workspace root used to make diagnostic paths relative before matching
+    pub fn with_workspace_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = Some(root.into());
+        self
+    }
+
+    pub fn reports(&self) -> &[CrashReport] {
+        &self.reports
+    }
+
+    /// Crashes whose frames fall within the diagnostic's lines
+    pub fn correlate(&self, diagnostic: &Diagnostic) -> Option<CrashCorrelation> {
+        let candidates = self.frames.get(&file_name(&diagnostic.file)?)?;
+        let relative = self.relative_components(&diagnostic.file);
+        let lines = diagnostic.range.start.line + 1..=diagnostic.range.end.line.max(diagnostic.range.start.line) + 1;
+
+        let mut matched: Vec<&(CrashFrame, usize, bool)> = candidates
+            .iter()
+            .filter(|(frame, _, _)| lines.contains(&frame.line))
+            .filter(|(frame, _, _)| paths_match(&components(&frame.file), &relative))
+            .collect();
+        if matched.is_empty() {
+            return None;
+        }
+        // Prefer a crash site over an outer frame, then the earliest report
+        matched.sort_by_key(|(_, index, at_site)| (!*at_site, *index));
+
+        let (frame, index, at_crash_site) = matched[0];
+        let mut reports: Vec<usize> = matched.iter().map(|(_, index, _)| *index).collect();
+        reports.sort_unstable();
+        reports.dedup();
+
+        let report = &self.reports[*index];
+        Some(CrashCorrelation {
+            kind: report.kind,
+            message: report.message.clone(),
+            function: frame.function.clone(),
+            occurrences: reports.len(),
+            at_crash_site: *at_crash_site,
+        })
+    }
+
+    /// Tag every diagnostic that correlates with a crash, returning how many were tagged
+    pub fn tag_all(&self, diagnostics: &mut [Diagnostic]) -> usize {
+        let mut tagged = 0;
+        for diagnostic in diagnostics.iter_mut() {
+            let Some(correlation) = self.correlate(diagnostic) else {
+                continue;
+            };
+            match &mut diagnostic.data {
+                None => {
+                    diagnostic.data = Some(serde_json::json!({ CRASH_KEY: correlation }));
+                    tagged += 1;
+                }
+                Some(serde_json::Value::Object(map)) => {
+                    map.insert(CRASH_KEY.to_string(), serde_json::json!(correlation));
+                    tagged += 1;
+                }
+                // Never rewrite language server payloads
+                Some(_) => {}
+            }
+        }
+        tagged
+    }
+
+    fn relative_components(&self, file: &str) -> Vec<String> {
+        let path = Path::new(file);
+        let relative = self
+            .root
+            .as_deref()
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path);
+        components(&relative.to_string_lossy())
+    }
+}
+
+fn file_name(file: &str) -> Option<String> {
+    Path::new(file).file_name().map(|name| name.to_string_lossy().to_string())
+}
+
+fn components(file: &str) -> Vec<String> {
+    Path::new(file)
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether one path ends with all components of the other
+fn paths_match(a: &[String], b: &[String]) -> bool {
+    let (shorter, longer) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    !shorter.is_empty() && longer.ends_with(shorter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{DiagnosticSeverity, Position, Range};
+
+    const LOG: &str = r#"
+2024-05-01T10:00:00Z INFO starting
+thread 'main' panicked at src/parser.rs:42:17:
+called `Option::unwrap()` on a `None` value
+stack backtrace:
+   0: rust_begin_unwind
+             at /rustc/abc/library/std/src/panicking.rs:645:5
+   1: app::parser::parse_header
+             at ./src/parser.rs:42:17
+   2: app::main
+             at ./src/main.rs:7:5
+note: Some details are omitted
+TypeError: Cannot read properties of undefined (reading 'id')
+    at getUser (/srv/app/src/users.ts:18:22)
+    at processTicksAndRejections (node:internal/process/task_queues:95:5)
+    at /srv/app/node_modules/express/lib/router.js:10:1
+Traceback (most recent call last):
+  File "/opt/app/service/main.py", line 30, in <module>
+    run()
+  File "/opt/app/service/jobs.py", line 12, in run
+    total = sum(values) / len(values)
+ZeroDivisionError: division by zero
+"#;
+
+    fn diagnostic(file: &str, line: u32) -> Diagnostic {
+        Diagnostic::new(
+            file.to_string(),
+            Range {
+                start: Position { line, character: 0 },
+                end: Position { line, character: 10 },
+            },
+            DiagnosticSeverity::Warning,
+            "warning".to_string(),
+            "test".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_parse_mixed_log() {
+        let reports = CrashReportParser::parse(LOG);
+        assert_eq!(reports.len(), 3);
+
+        assert_eq!(reports[0].kind, CrashKind::RustPanic);
+        assert!(reports[0].message.contains("unwrap()"));
+        let rust_lines: Vec<(String, u32)> = reports[0].frames.iter().map(|f| (f.file.clone(), f.line)).collect();
+        assert_eq!(
+            rust_lines,
+            vec![("src/parser.rs".to_string(), 42), ("./src/main.rs".to_string(), 7)]
+        );
+        assert_eq!(reports[0].frames[0].function.as_deref(), Some("app::parser::parse_header"));
+
+        assert_eq!(reports[1].kind, CrashKind::NodeStackTrace);
+        assert_eq!(reports[1].frames.len(), 1);
+        assert_eq!(reports[1].frames[0].function.as_deref(), Some("getUser"));
+
+        assert_eq!(reports[2].kind, CrashKind::PythonTraceback);
+        assert_eq!(reports[2].message, "ZeroDivisionError: division by zero");
+        assert_eq!(reports[2].frames[0].file, "/opt/app/service/jobs.py");
+        assert_eq!(reports[2].frames[0].line, 12);
+    }
+
+    #[test]
+    fn test_correlates_by_relative_path_and_line() {
+        let correlator = CrashCorrelator::new(CrashReportParser::parse(LOG)).with_workspace_root("/home/dev/app");
+
+        let mut diagnostics = vec![
+            diagnostic("/home/dev/app/src/parser.rs", 41),
+            diagnostic("/home/dev/app/src/users.ts", 17),
+            diagnostic("/home/dev/app/src/users.ts", 30),
+            diagnostic("/home/dev/app/service/jobs.py", 11),
+            diagnostic("/home/dev/app/other/jobs.py", 11),
+        ];
+        assert_eq!(correlator.tag_all(&mut diagnostics), 3);
+
+        let panic = diagnostics[0].crash_correlation().unwrap();
+        assert_eq!(panic.kind, CrashKind::RustPanic);
+        assert!(panic.at_crash_site);
+        assert!(diagnostics[1].crashes_in_production());
+        assert!(!diagnostics[2].crashes_in_production());
+        assert!(diagnostics[3].crashes_in_production());
+        assert!(!diagnostics[4].crashes_in_production());
+    }
+}
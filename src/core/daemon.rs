@@ -0,0 +1,495 @@
+//! Single-writer coordination between the CLI and a running daemon
+//!
+//! The SQLite stores in the data directory are not safe with several
+//! processes writing at once: history cleanup racing the stale-file
+//! refresher of `lspbridge watch` can leave snapshots half deleted. Writers
+//! therefore take an exclusive advisory lock on `daemon.lock` first.
+//!
+//! A long-running process that keeps the stores open (the daemon) holds the
+//! lock for its whole lifetime and listens on the `daemon.sock` control
+//! socket. Short-lived CLI commands check the lock before opening a store:
+//! when a daemon holds it they send the operation over the socket and the
+//! daemon performs it with its own connection; otherwise they take the lock
+//! themselves for the duration of the command.
+//!
+//! The protocol is one JSON request per line, answered by one JSON
+//! [`ControlResponse`] line. Control sockets are Unix domain sockets, created
+//! with owner-only permissions; on other platforms CLI commands refuse to
+//! touch a store while a daemon holds the lock.
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use fs2::FileExt;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Lock file in the data directory
+pub const LOCK_FILE: &str = "daemon.lock";
+
+/// Control socket in the data directory
+pub const SOCKET_FILE: &str = "daemon.sock";
+
+/// How often a waiting writer retries the lock
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Why a process holds the store lock
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LockRole {
+    /// Long-running process serving the control socket
+    Daemon,
+    /// CLI command writing directly for its own duration
+    Cli,
+}
+
+/// Process currently holding the store lock, as recorded in the lock file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockOwner {
+    pub pid: u32,
+    pub role: LockRole,
+    pub command: String,
+    pub since: DateTime<Utc>,
+}
+
+impl std::fmt::Display for LockOwner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "`lspbridge {}` (pid {}, since {})",
+            self.command,
+            self.pid,
+            self.since.format("%Y-%m-%d %H:%M:%S UTC")
+        )
+    }
+}
+
+/// Exclusive advisory lock on the data directory's stores
+///
+/// Released when dropped. The lock file itself is left in place: removing
+/// it would let a waiting process lock a file that a newcomer then replaces.
+#[derive(Debug)]
+pub struct StoreLock {
+    file: File,
+    owner: LockOwner,
+}
+
+impl StoreLock {
+    /// Path of the lock file for a data directory
+    pub fn path(data_dir: &Path) -> PathBuf {
+        data_dir.join(LOCK_FILE)
+    }
+
+    /// Take the lock if no other process holds it
+    pub fn try_acquire(data_dir: &Path, role: LockRole, command: &str) -> Result<Option<Self>> {
+        std::fs::create_dir_all(data_dir)
+            .with_context(|| format!("Failed to create data directory {}", data_dir.display()))?;
+        let path = Self::path(data_dir);
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .with_context(|| format!("Failed to open lock file {}", path.display()))?;
+
+        match FileExt::try_lock_exclusive(&file) {
+            Ok(()) => {}
+            Err(e) if e.kind() == fs2::lock_contended_error().kind() => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to lock {}", path.display())),
+        }
+
+        let owner = LockOwner {
+            pid: std::process::id(),
+            role,
+            command: command.to_string(),
+            since: Utc::now(),
+        };
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(serde_json::to_string(&owner)?.as_bytes())?;
+        file.sync_data()?;
+
+        Ok(Some(Self { file, owner }))
+    }
+
+    /// Take the lock, waiting up to `timeout` for the current holder
+    ///
+    /// Waits on the tokio timer between attempts, so runtime workers stay
+    /// free for the servers a command may be running meanwhile.
+    pub async fn acquire(data_dir: &Path, role: LockRole, command: &str, timeout: Duration) -> Result<Self> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(lock) = Self::try_acquire(data_dir, role, command)? {
+                return Ok(lock);
+            }
+            if Instant::now() >= deadline {
+                return Err(match Self::owner(data_dir) {
+                    Some(owner) => anyhow!("The lspbridge stores are locked by {owner}"),
+                    None => anyhow!("The lspbridge stores in {} are locked by another process", data_dir.display()),
+                });
+            }
+            tokio::time::sleep(LOCK_POLL_INTERVAL).await;
+        }
+    }
+
+    /// The process holding the lock, if any
+    ///
+    /// Returns `None` when the lock is free, even if a previous holder left
+    /// its details behind in the file.
+    pub fn owner(data_dir: &Path) -> Option<LockOwner> {
+        let mut file = File::open(Self::path(data_dir)).ok()?;
+        if FileExt::try_lock_shared(&file).is_ok() {
+            let _ = FileExt::unlock(&file);
+            return None;
+        }
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    /// Details recorded for this lock
+    pub fn info(&self) -> &LockOwner {
+        &self.owner
+    }
+}
+
+impl Drop for StoreLock {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+    }
+}
+
+/// Reply to one control request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ControlResponse {
+    Ok { result: serde_json::Value },
+    Error { message: String },
+}
+
+/// Performs the operations a daemon accepts over its control socket
+#[async_trait]
+pub trait ControlHandler: Send + Sync {
+    /// Handle one request; errors are sent back to the client as messages
+    async fn handle(&self, request: serde_json::Value) -> Result<serde_json::Value>;
+}
+
+/// Sends each control request to the handler registered for its `op`
+///
+/// Requests with an op no route claims go to the fallback handler, so a
+/// daemon can add services without touching the ones it already serves.
+pub struct ControlRouter {
+    routes: Vec<(&'static [&'static str], Arc<dyn ControlHandler>)>,
+    fallback: Arc<dyn ControlHandler>,
+}
+
+impl ControlRouter {
+    pub fn new(fallback: Arc<dyn ControlHandler>) -> Self {
+        Self {
+            routes: Vec::new(),
+            fallback,
+        }
+    }
+
+    /// Handle requests whose `op` is one of `ops` with `handler`
+    pub fn with_route(mut self, ops: &'static [&'static str], handler: Arc<dyn ControlHandler>) -> Self {
+        self.routes.push((ops, handler));
+        self
+    }
+}
+
+#[async_trait]
+impl ControlHandler for ControlRouter {
+    async fn handle(&self, request: serde_json::Value) -> Result<serde_json::Value> {
+        let op = request.get("op").and_then(serde_json::Value::as_str).unwrap_or_default();
+        let handler = self
+            .routes
+            .iter()
+            .find(|(ops, _)| ops.contains(&op))
+            .map_or(&self.fallback, |(_, handler)| handler);
+        handler.handle(request).await
+    }
+}
+
+/// Path of the control socket for a data directory
+pub fn socket_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(SOCKET_FILE)
+}
+
+/// A daemon: the store lock plus its control socket
+pub struct Daemon {
+    lock: StoreLock,
+    socket: PathBuf,
+    #[cfg(unix)]
+    listener: tokio::net::UnixListener,
+}
+
+impl Daemon {
+    /// Become the daemon for a data directory
+    ///
+    /// Returns `None` if another process already holds the store lock.
+    pub fn start(data_dir: &Path, command: &str) -> Result<Option<Self>> {
+        let Some(lock) = StoreLock::try_acquire(data_dir, LockRole::Daemon, command)? else {
+            return Ok(None);
+        };
+        let socket = socket_path(data_dir);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            // Holding the lock means any existing socket is left over from a crash
+            if socket.exists() {
+                std::fs::remove_file(&socket)
+                    .with_context(|| format!("Failed to remove stale socket {}", socket.display()))?;
+            }
+            let listener = tokio::net::UnixListener::bind(&socket)
+                .with_context(|| format!("Failed to bind control socket {}", socket.display()))?;
+            std::fs::set_permissions(&socket, std::fs::Permissions::from_mode(0o600))?;
+            Ok(Some(Self { lock, socket, listener }))
+        }
+
+        #[cfg(not(unix))]
+        {
+            Ok(Some(Self { lock, socket }))
+        }
+    }
+
+    /// The lock held by this daemon
+    pub fn lock(&self) -> &StoreLock {
+        &self.lock
+    }
+
+    /// Answer control requests until the daemon is dropped
+    pub async fn serve(&self, handler: Arc<dyn ControlHandler>) -> Result<()> {
+        #[cfg(unix)]
+        loop {
+            let (stream, _) = self.listener.accept().await?;
+            let handler = handler.clone();
+            tokio::spawn(async move {
+                if let Err(e) = serve_connection(stream, handler).await {
+                    tracing::debug!("Control connection closed: {}", e);
+                }
+            });
+        }
+
+        #[cfg(not(unix))]
+        {
+            let _ = handler;
+            std::future::pending::<()>().await;
+            Ok(())
+        }
+    }
+}
+
+impl Drop for Daemon {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.socket);
+    }
+}
+
+#[cfg(unix)]
+async fn serve_connection(stream: tokio::net::UnixStream, handler: Arc<dyn ControlHandler>) -> Result<()> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    while let Some(line) = lines.next_line().await? {
+        let response = match serde_json::from_str(&line) {
+            Ok(request) => match handler.handle(request).await {
+                Ok(result) => ControlResponse::Ok { result },
+                Err(e) => ControlResponse::Error { message: format!("{e:#}") },
+            },
+            Err(e) => ControlResponse::Error {
+                message: format!("Malformed control request: {e}"),
+            },
+        };
+        let mut reply = serde_json::to_vec(&response)?;
+        reply.push(b'\n');
+        writer.write_all(&reply).await?;
+    }
+    Ok(())
+}
+
+/// Connection details for a running daemon
+#[derive(Debug, Clone)]
+pub struct DaemonClient {
+    socket: PathBuf,
+    owner: LockOwner,
+}
+
+impl DaemonClient {
+    /// A client for the daemon holding the store lock, if one is running
+    ///
+    /// Fails when a daemon holds the lock but cannot be reached, since the
+    /// caller must then neither write directly nor go through the socket.
+    pub fn detect(data_dir: &Path) -> Result<Option<Self>> {
+        let owner = match StoreLock::owner(data_dir) {
+            Some(owner) if owner.role == LockRole::Daemon => owner,
+            _ => return Ok(None),
+        };
+        let socket = socket_path(data_dir);
+        if cfg!(unix) && socket.exists() {
+            Ok(Some(Self { socket, owner }))
+        } else {
+            Err(anyhow!(
+                "A daemon is using the lspbridge stores: {owner}. It has no control socket here, so stop it before running this command"
+            ))
+        }
+    }
+
+    /// The daemon process
+    pub fn owner(&self) -> &LockOwner {
+        &self.owner
+    }
+
+    /// Send one request and decode the daemon's result
+    pub async fn request<Req, Resp>(&self, request: &Req) -> Result<Resp>
+    where
+        Req: Serialize + Sync,
+        Resp: DeserializeOwned,
+    {
+        let response = self.round_trip(serde_json::to_vec(request)?).await?;
+        match response {
+            ControlResponse::Ok { result } => {
+                serde_json::from_value(result).context("Unexpected reply from the daemon")
+            }
+            ControlResponse::Error { message } => Err(anyhow!("Daemon error: {message}")),
+        }
+    }
+
+    #[cfg(unix)]
+    async fn round_trip(&self, mut request: Vec<u8>) -> Result<ControlResponse> {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let stream = tokio::net::UnixStream::connect(&self.socket)
+            .await
+            .with_context(|| format!("Failed to reach the daemon {} at {}", self.owner, self.socket.display()))?;
+        let (reader, mut writer) = stream.into_split();
+        request.push(b'\n');
+        writer.write_all(&request).await?;
+
+        let mut line = String::new();
+        BufReader::new(reader).read_line(&mut line).await?;
+        if line.is_empty() {
+            return Err(anyhow!("The daemon closed the control connection without replying"));
+        }
+        Ok(serde_json::from_str(&line)?)
+    }
+
+    #[cfg(not(unix))]
+    async fn round_trip(&self, _request: Vec<u8>) -> Result<ControlResponse> {
+        Err(anyhow!("Daemon control sockets are only supported on Unix"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_store_lock_is_exclusive_and_released_on_drop() {
+        let dir = TempDir::new().unwrap();
+
+        let lock = StoreLock::try_acquire(dir.path(), LockRole::Cli, "history clean")
+            .unwrap()
+            .unwrap();
+        assert!(StoreLock::try_acquire(dir.path(), LockRole::Cli, "history clean")
+            .unwrap()
+            .is_none());
+
+        let owner = StoreLock::owner(dir.path()).unwrap();
+        assert_eq!(owner.pid, std::process::id());
+        assert_eq!(owner.command, "history clean");
+        assert!(DaemonClient::detect(dir.path()).unwrap().is_none());
+
+        drop(lock);
+        assert!(StoreLock::owner(dir.path()).is_none());
+        assert!(StoreLock::try_acquire(dir.path(), LockRole::Cli, "history clean")
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_store_lock_acquire_waits_for_the_holder() {
+        let dir = TempDir::new().unwrap();
+        let lock = StoreLock::try_acquire(dir.path(), LockRole::Daemon, "serve")
+            .unwrap()
+            .unwrap();
+
+        let error = StoreLock::acquire(dir.path(), LockRole::Cli, "stats", Duration::ZERO)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("`lspbridge serve`"), "{error}");
+
+        let release = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(150)).await;
+            drop(lock);
+        });
+        let lock = StoreLock::acquire(dir.path(), LockRole::Cli, "stats", Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(lock.info().command, "stats");
+        release.await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_daemon_routes_requests_over_control_socket() {
+        struct Echo;
+
+        #[async_trait]
+        impl ControlHandler for Echo {
+            async fn handle(&self, request: serde_json::Value) -> Result<serde_json::Value> {
+                match request["op"].as_str() {
+                    Some("echo") => Ok(request["value"].clone()),
+                    _ => Err(anyhow!("unknown op")),
+                }
+            }
+        }
+
+        let dir = TempDir::new().unwrap();
+        let daemon = Arc::new(Daemon::start(dir.path(), "watch").unwrap().unwrap());
+        assert!(Daemon::start(dir.path(), "watch").unwrap().is_none());
+
+        let server = daemon.clone();
+        let serving = tokio::spawn(async move { server.serve(Arc::new(Echo)).await });
+
+        let client = DaemonClient::detect(dir.path()).unwrap().unwrap();
+        assert_eq!(client.owner().role, LockRole::Daemon);
+
+        let value: u32 = client
+            .request(&serde_json::json!({ "op": "echo", "value": 7 }))
+            .await
+            .unwrap();
+        assert_eq!(value, 7);
+
+        let error = client
+            .request::<_, serde_json::Value>(&serde_json::json!({ "op": "nope" }))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("unknown op"));
+
+        struct Fixed;
+
+        #[async_trait]
+        impl ControlHandler for Fixed {
+            async fn handle(&self, _request: serde_json::Value) -> Result<serde_json::Value> {
+                Ok(serde_json::json!("routed"))
+            }
+        }
+
+        let router = ControlRouter::new(Arc::new(Echo)).with_route(&["fixed"], Arc::new(Fixed));
+        assert_eq!(router.handle(serde_json::json!({ "op": "fixed" })).await.unwrap(), "routed");
+        assert_eq!(router.handle(serde_json::json!({ "op": "echo", "value": 3 })).await.unwrap(), 3);
+
+        serving.abort();
+    }
+}
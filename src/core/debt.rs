@@ -0,0 +1,430 @@
+//! Slow-burn debt: aging TODOs, FIXMEs and deprecations
+//!
+//! Hard errors get fixed because they break the build; `TODO`/`FIXME`
+//! comments and uses of deprecated APIs don't, and quietly pile up.
+//! [`DebtTracker`] collects them as long-lived items aged by `git blame` of
+//! their line, and escalates their severity as they pass the configured
+//! thresholds:
+//!
+//! ```toml
+//! [debt]
+//! info_after_days = 30
+//! warning_after_days = 90
+//! error_after_days = 365
+//! ```
+//!
+//! Comments are found with tree-sitter for Rust, TypeScript/JavaScript and
+//! Python, so markers inside string literals don't count. Deprecations are
+//! diagnostics tagged `deprecated` or whose code or message says so.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use clap::{Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use std::path::{Path, PathBuf};
+
+use super::static_scan::{find_todos, line_ages, source_files, ScanConfig};
+use super::types::{Diagnostic, DiagnosticSeverity, DiagnosticTag};
+use crate::history::owners::csv_field;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Kind of debt item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum DebtKind {
+    Todo,
+    Fixme,
+    /// Use of a deprecated API reported by a language server
+    Deprecation,
+}
+
+impl fmt::Display for DebtKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DebtKind::Todo => "TODO",
+            DebtKind::Fixme => "FIXME",
+            DebtKind::Deprecation => "deprecation",
+        })
+    }
+}
+
+/// Aging thresholds in `lspbridge.toml`
+///
+/// An item is raised to the highest severity whose threshold its age has
+/// passed, and never lowered below its own severity.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DebtConfig {
+    /// Age at which items become at least information
+    #[serde(default = "default_info_after_days")]
+    pub info_after_days: Option<u64>,
+    /// Age at which items become at least warnings
+    #[serde(default = "default_warning_after_days")]
+    pub warning_after_days: Option<u64>,
+    /// Age at which items become errors; unset never escalates that far
+    #[serde(default)]
+    pub error_after_days: Option<u64>,
+}
+
+fn default_info_after_days() -> Option<u64> {
+    Some(30)
+}
+
+fn default_warning_after_days() -> Option<u64> {
+    Some(90)
+}
+
+impl Default for DebtConfig {
+    fn default() -> Self {
+        Self {
+            info_after_days: default_info_after_days(),
+            warning_after_days: default_warning_after_days(),
+            error_after_days: None,
+        }
+    }
+}
+
+impl DebtConfig {
+    /// Severity of an item of `base` severity that is `age_days` old
+    pub fn escalate(&self, base: DiagnosticSeverity, age_days: Option<u64>) -> DiagnosticSeverity {
+        let Some(age) = age_days else {
+            return base;
+        };
+        let thresholds = [
+            (self.error_after_days, DiagnosticSeverity::Error),
+            (self.warning_after_days, DiagnosticSeverity::Warning),
+            (self.info_after_days, DiagnosticSeverity::Information),
+        ];
+        thresholds
+            .iter()
+            .find(|(days, _)| days.is_some_and(|days| age >= days))
+            // Lower values are more severe
+            .map(|(_, severity)| base.min(*severity))
+            .unwrap_or(base)
+    }
+}
+
+/// Debt actions
+#[derive(Debug, Clone, Subcommand)]
+pub enum DebtAction {
+    /// Report TODO/FIXME comments and deprecation warnings with their age
+    ///
+    /// Deprecations are read from the IDE's diagnostics, or from stdin when
+    /// diagnostics are piped in.
+    Todos {
+        /// Project root to scan
+        #[arg(default_value = ".")]
+        path: PathBuf,
+        /// Report format
+        #[arg(short, long, value_enum, default_value = "markdown")]
+        format: DebtFormat,
+        /// Only these kinds, comma-separated
+        #[arg(long, value_enum, value_delimiter = ',')]
+        kind: Vec<DebtKind>,
+        /// Only items at least this many days old
+        #[arg(long)]
+        min_age_days: Option<u64>,
+        /// Output file (default: stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Output format of debt reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DebtFormat {
+    Markdown,
+    Json,
+    Csv,
+}
+
+/// One TODO, FIXME or deprecation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DebtItem {
+    pub kind: DebtKind,
+    pub file: PathBuf,
+    /// One-based line
+    pub line: u32,
+    /// Comment text or diagnostic message
+    pub text: String,
+    /// Days since the line was committed; `None` if it isn't
+    pub age_days: Option<u64>,
+    /// Severity before aging
+    pub base_severity: DiagnosticSeverity,
+    pub severity: DiagnosticSeverity,
+}
+
+impl DebtItem {
+    /// Whether aging raised the item's severity
+    pub fn escalated(&self) -> bool {
+        self.severity < self.base_severity
+    }
+}
+
+/// Debt items of a project, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebtReport {
+    pub root: PathBuf,
+    pub generated_at: DateTime<Utc>,
+    pub items: Vec<DebtItem>,
+}
+
+impl DebtReport {
+    pub fn count_by_kind(&self) -> BTreeMap<DebtKind, usize> {
+        let mut counts = BTreeMap::new();
+        for item in &self.items {
+            *counts.entry(item.kind).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Keep only items of the given kinds (all when empty) and minimum age
+    pub fn retain(&mut self, kinds: &[DebtKind], min_age_days: Option<u64>) {
+        self.items.retain(|item| {
+            let old_enough = match min_age_days {
+                Some(min) => item.age_days.is_some_and(|age| age >= min),
+                None => true,
+            };
+            (kinds.is_empty() || kinds.contains(&item.kind)) && old_enough
+        });
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("# Debt: TODOs and Deprecations\n\n");
+        if self.items.is_empty() {
+            out.push_str("_No debt items._\n");
+            return out;
+        }
+
+        let counts: Vec<String> = self
+            .count_by_kind()
+            .iter()
+            .map(|(kind, count)| format!("{count} {kind}"))
+            .collect();
+        let escalated = self.items.iter().filter(|item| item.escalated()).count();
+        let _ = writeln!(out, "{} ({} escalated by age).\n", counts.join(", "), escalated);
+
+        out.push_str("| Age | Severity | Kind | Location | Text |\n");
+        out.push_str("|-----|----------|------|----------|------|\n");
+        for item in &self.items {
+            let age = item.age_days.map(|days| format!("{days}d")).unwrap_or_else(|| "new".to_string());
+            let severity = if item.escalated() {
+                format!("**{:?}**", item.severity)
+            } else {
+                format!("{:?}", item.severity)
+            };
+            let file = item.file.strip_prefix(&self.root).unwrap_or(&item.file);
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | `{}:{}` | {} |",
+                age,
+                severity,
+                item.kind,
+                file.display(),
+                item.line,
+                item.text.replace('|', "\\|")
+            );
+        }
+        out
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("kind,file,line,age_days,base_severity,severity,text\n");
+        for item in &self.items {
+            let _ = writeln!(
+                out,
+                "{},{},{},{},{:?},{:?},{}",
+                item.kind,
+                csv_field(&item.file.to_string_lossy()),
+                item.line,
+                item.age_days.map(|days| days.to_string()).unwrap_or_default(),
+                item.base_severity,
+                item.severity,
+                csv_field(&item.text)
+            );
+        }
+        out
+    }
+}
+
+/// Collects and ages the debt items of a source tree
+pub struct DebtTracker {
+    root: PathBuf,
+    config: DebtConfig,
+    max_file_size: u64,
+}
+
+impl DebtTracker {
+    pub fn new(root: &Path, config: DebtConfig) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            config,
+            max_file_size: ScanConfig::default().max_file_size,
+        }
+    }
+
+    /// Skip files larger than `bytes`
+    pub fn with_max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = bytes;
+        self
+    }
+
+    /// TODO/FIXME comments under the root plus the deprecations among `diagnostics`
+    pub async fn collect(&self, diagnostics: &[Diagnostic]) -> Result<DebtReport> {
+        let root = self.root.canonicalize()?;
+        // (kind, file, zero-based line, text, base severity)
+        let mut found = Vec::new();
+
+        for path in source_files(&root, self.max_file_size) {
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            for todo in find_todos(&path, &content) {
+                let (kind, base) = if todo.marker == "FIXME" {
+                    (DebtKind::Fixme, DiagnosticSeverity::Information)
+                } else {
+                    (DebtKind::Todo, DiagnosticSeverity::Hint)
+                };
+                found.push((kind, todo.file, todo.range.start.line, todo.text, base));
+            }
+        }
+
+        for diagnostic in diagnostics.iter().filter(|d| is_deprecation(d)) {
+            let file = Path::new(&diagnostic.file);
+            let file = if file.is_absolute() { file.to_path_buf() } else { root.join(file) };
+            found.push((
+                DebtKind::Deprecation,
+                file,
+                diagnostic.range.start.line,
+                diagnostic.message.clone(),
+                diagnostic.severity,
+            ));
+        }
+
+        let lines: Vec<(PathBuf, u32)> = found.iter().map(|(_, file, line, _, _)| (file.clone(), *line)).collect();
+        let ages = line_ages(&root, &lines).await;
+
+        let mut items: Vec<DebtItem> = found
+            .into_iter()
+            .zip(ages)
+            .map(|((kind, file, line, text, base), age)| {
+                let age_days = age.map(|age| age.as_secs() / SECONDS_PER_DAY);
+                DebtItem {
+                    kind,
+                    file,
+                    line: line + 1,
+                    text,
+                    age_days,
+                    base_severity: base,
+                    severity: self.config.escalate(base, age_days),
+                }
+            })
+            .collect();
+        items.sort_by(|a, b| {
+            b.age_days
+                .cmp(&a.age_days)
+                .then_with(|| a.file.cmp(&b.file))
+                .then_with(|| a.line.cmp(&b.line))
+        });
+
+        Ok(DebtReport {
+            root,
+            generated_at: Utc::now(),
+            items,
+        })
+    }
+}
+
+/// Whether a diagnostic reports use of something deprecated
+pub fn is_deprecation(diagnostic: &Diagnostic) -> bool {
+    let tagged = diagnostic
+        .tags
+        .as_ref()
+        .is_some_and(|tags| tags.contains(&DiagnosticTag::Deprecated));
+    tagged
+        || diagnostic.code.as_deref().is_some_and(|code| code.to_lowercase().contains("deprecat"))
+        || diagnostic.message.to_lowercase().contains("deprecated")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{Position, Range};
+
+    #[test]
+    fn test_thresholds_escalate_but_never_lower() {
+        let config = DebtConfig {
+            error_after_days: Some(365),
+            ..DebtConfig::default()
+        };
+        assert_eq!(config.escalate(DiagnosticSeverity::Hint, None), DiagnosticSeverity::Hint);
+        assert_eq!(config.escalate(DiagnosticSeverity::Hint, Some(29)), DiagnosticSeverity::Hint);
+        assert_eq!(config.escalate(DiagnosticSeverity::Hint, Some(30)), DiagnosticSeverity::Information);
+        assert_eq!(config.escalate(DiagnosticSeverity::Hint, Some(120)), DiagnosticSeverity::Warning);
+        assert_eq!(config.escalate(DiagnosticSeverity::Information, Some(400)), DiagnosticSeverity::Error);
+        assert_eq!(config.escalate(DiagnosticSeverity::Warning, Some(31)), DiagnosticSeverity::Warning);
+        assert_eq!(
+            DebtConfig::default().escalate(DiagnosticSeverity::Hint, Some(1000)),
+            DiagnosticSeverity::Warning
+        );
+    }
+
+    #[tokio::test]
+    async fn test_collects_comments_and_deprecations() -> Result<()> {
+        let dir = tempfile::TempDir::new()?;
+        std::fs::write(
+            dir.path().join("lib.rs"),
+            "// TODO: split this module\nfn f() -> &'static str {\n    \"// TODO: not a comment\" /* FIXME(ops): flaky */\n}\n",
+        )?;
+        std::fs::write(dir.path().join("tool.py"), "x = '# TODO: string'\n# FIXME handle None\n")?;
+
+        let range = Range {
+            start: Position { line: 1, character: 4 },
+            end: Position { line: 1, character: 10 },
+        };
+        let mut deprecated = Diagnostic::new(
+            "lib.rs".to_string(),
+            range.clone(),
+            DiagnosticSeverity::Hint,
+            "`old_api` is deprecated: use `new_api`".to_string(),
+            "rustc".to_string(),
+        );
+        deprecated.code = Some("deprecated".to_string());
+        let unrelated = Diagnostic::new(
+            "lib.rs".to_string(),
+            range,
+            DiagnosticSeverity::Error,
+            "mismatched types".to_string(),
+            "rustc".to_string(),
+        );
+
+        let mut report = DebtTracker::new(dir.path(), DebtConfig::default())
+            .collect(&[deprecated, unrelated])
+            .await?;
+        let found: Vec<(DebtKind, u32, &str)> = report
+            .items
+            .iter()
+            .map(|item| (item.kind, item.line, item.text.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (DebtKind::Todo, 1, "split this module"),
+                (DebtKind::Deprecation, 2, "`old_api` is deprecated: use `new_api`"),
+                (DebtKind::Fixme, 3, "flaky"),
+                (DebtKind::Fixme, 2, "handle None"),
+            ]
+        );
+        // Not in a repository, so nothing has an age to escalate
+        assert!(report.items.iter().all(|item| item.age_days.is_none() && !item.escalated()));
+        assert!(report.to_csv().lines().nth(1).unwrap().starts_with("TODO,"));
+
+        report.retain(&[DebtKind::Fixme], None);
+        assert_eq!(report.items.len(), 2);
+        report.retain(&[], Some(1));
+        assert!(report.items.is_empty());
+        Ok(())
+    }
+}
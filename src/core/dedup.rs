@@ -0,0 +1,348 @@
+//! Diagnostic deduplication
+//!
+//! Clusters diagnostics that say the same thing: same source and code, the
+//! same message once numbers, paths and quoted names are normalized, and the
+//! same symbol (the first quoted name in the message). Each cluster is kept
+//! once, as its most severe member, tagged with how often and where it
+//! occurred; see [`Diagnostic::duplicates`].
+//!
+//! Clusters span files, snapshots and repositories. Diagnostics are added
+//! with an optional origin label, such as a repository name or snapshot id,
+//! so a cluster can tell how many of them it was seen in.
+
+use super::types::Diagnostic;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Key in [`Diagnostic::data`] holding the [`DuplicateInfo`] of a collapsed cluster
+pub const DEDUP_KEY: &str = "lspbridgeDuplicates";
+
+/// Locations listed per cluster; `occurrences` still counts them all
+const MAX_LOCATIONS: usize = 10;
+
+/// What makes two diagnostics duplicates of each other
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DedupKey {
+    pub source: String,
+    pub code: Option<String>,
+    /// Message with quoted names, numbers and paths replaced by placeholders
+    pub message: String,
+    /// First quoted name in the message
+    pub symbol: Option<String>,
+}
+
+impl DedupKey {
+    pub fn of(diagnostic: &Diagnostic) -> Self {
+        let (message, symbol) = normalize_message(&diagnostic.message);
+        Self {
+            source: diagnostic.source.clone(),
+            code: diagnostic.code.clone(),
+            message,
+            symbol,
+        }
+    }
+}
+
+/// One place a duplicated diagnostic occurred
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateLocation {
+    pub file: String,
+    /// Zero-based line
+    pub line: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+}
+
+/// Occurrences of a diagnostic collapsed into one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateInfo {
+    /// Diagnostics in the cluster, the kept one included
+    pub occurrences: usize,
+    /// Distinct files the cluster occurred in
+    pub files: usize,
+    /// Distinct origins (repositories, snapshots) the cluster occurred in
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub origins: Vec<String>,
+    /// The first few locations, in the order they were seen
+    pub locations: Vec<DuplicateLocation>,
+}
+
+/// Result of deduplicating a set of diagnostics
+#[derive(Debug, Clone)]
+pub struct DedupOutcome {
+    /// One diagnostic per cluster, in the order clusters were first seen
+    pub diagnostics: Vec<Diagnostic>,
+    /// Diagnostics before deduplication
+    pub input_count: usize,
+}
+
+impl DedupOutcome {
+    /// Number of diagnostics folded into another one
+    pub fn collapsed(&self) -> usize {
+        self.input_count - self.diagnostics.len()
+    }
+}
+
+struct Cluster {
+    kept: Diagnostic,
+    occurrences: usize,
+    files: HashSet<String>,
+    origins: Vec<String>,
+    locations: Vec<DuplicateLocation>,
+}
+
+/// Incrementally clusters diagnostics from any number of sources
+#[derive(Default)]
+pub struct Deduplicator {
+    clusters: Vec<Cluster>,
+    by_key: HashMap<DedupKey, usize>,
+    input_count: usize,
+}
+
+impl Deduplicator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add one diagnostic seen in `origin`
+    pub fn add(&mut self, diagnostic: Diagnostic, origin: Option<&str>) {
+        self.input_count += 1;
+        let location = DuplicateLocation {
+            file: diagnostic.file.clone(),
+            line: diagnostic.range.start.line,
+            origin: origin.map(String::from),
+        };
+
+        let key = DedupKey::of(&diagnostic);
+        let cluster = match self.by_key.get(&key) {
+            Some(&index) => &mut self.clusters[index],
+            None => {
+                self.by_key.insert(key, self.clusters.len());
+                self.clusters.push(Cluster {
+                    files: HashSet::new(),
+                    kept: diagnostic.clone(),
+                    occurrences: 0,
+                    origins: Vec::new(),
+                    locations: Vec::new(),
+                });
+                self.clusters.last_mut().expect("cluster was just pushed")
+            }
+        };
+
+        cluster.occurrences += 1;
+        cluster.files.insert(diagnostic.file.clone());
+        if let Some(origin) = origin {
+            if !cluster.origins.iter().any(|o| o == origin) {
+                cluster.origins.push(origin.to_string());
+            }
+        }
+        if cluster.locations.len() < MAX_LOCATIONS {
+            cluster.locations.push(location);
+        }
+        // Lower values are more severe
+        if (diagnostic.severity as u8) < (cluster.kept.severity as u8) {
+            cluster.kept = diagnostic;
+        }
+    }
+
+    /// Add every diagnostic of one snapshot or repository
+    pub fn extend(&mut self, diagnostics: impl IntoIterator<Item = Diagnostic>, origin: Option<&str>) {
+        for diagnostic in diagnostics {
+            self.add(diagnostic, origin);
+        }
+    }
+
+    /// Number of clusters so far
+    pub fn len(&self) -> usize {
+        self.clusters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clusters.is_empty()
+    }
+
+    /// One diagnostic per cluster, tagged with its occurrences when it has duplicates
+    pub fn finish(self) -> DedupOutcome {
+        let diagnostics = self
+            .clusters
+            .into_iter()
+            .map(|cluster| {
+                let mut diagnostic = cluster.kept;
+                if cluster.occurrences > 1 {
+                    let info = DuplicateInfo {
+                        occurrences: cluster.occurrences,
+                        files: cluster.files.len(),
+                        origins: cluster.origins,
+                        locations: cluster.locations,
+                    };
+                    tag(&mut diagnostic, &info);
+                }
+                diagnostic
+            })
+            .collect();
+
+        DedupOutcome {
+            diagnostics,
+            input_count: self.input_count,
+        }
+    }
+}
+
+/// Deduplicate one set of diagnostics, using workspace roots as origins
+pub fn dedupe(diagnostics: Vec<Diagnostic>) -> DedupOutcome {
+    let mut deduplicator = Deduplicator::new();
+    for diagnostic in diagnostics {
+        let origin = diagnostic.workspace_root().map(String::from);
+        deduplicator.add(diagnostic, origin.as_deref());
+    }
+    deduplicator.finish()
+}
+
+fn tag(diagnostic: &mut Diagnostic, info: &DuplicateInfo) {
+    match &mut diagnostic.data {
+        None => diagnostic.data = Some(serde_json::json!({ DEDUP_KEY: info })),
+        Some(serde_json::Value::Object(map)) => {
+            map.insert(DEDUP_KEY.to_string(), serde_json::json!(info));
+        }
+        // Never rewrite language server payloads
+        Some(_) => {}
+    }
+}
+
+/// Message with quoted names, numbers and paths replaced, and its first quoted name
+///
+/// `expected 2 arguments for `parse` in /a/src/lib.rs` and
+/// `expected 3 arguments for `parse` in /b/src/lib.rs` normalize the same.
+fn normalize_message(message: &str) -> (String, Option<String>) {
+    let mut normalized = String::with_capacity(message.len());
+    let mut symbol = None;
+    let mut quoted: Option<(char, String)> = None;
+    let mut word = String::new();
+
+    for c in message.chars() {
+        if let Some((quote, content)) = &mut quoted {
+            if c == *quote {
+                if symbol.is_none() && !content.is_empty() {
+                    symbol = Some(std::mem::take(content));
+                }
+                normalized.push_str("{}");
+                quoted = None;
+            } else {
+                content.push(c);
+            }
+            continue;
+        }
+        match c {
+            '`' | '"' => quoted = Some((c, String::new())),
+            // An apostrophe inside a word is not a quote
+            '\'' if word.is_empty() => quoted = Some((c, String::new())),
+            c if c.is_whitespace() => {
+                push_word(&mut normalized, &mut word);
+                if !normalized.is_empty() && !normalized.ends_with(' ') {
+                    normalized.push(' ');
+                }
+            }
+            c => word.push(c),
+        }
+    }
+    push_word(&mut normalized, &mut word);
+    // An unterminated quote is kept as text
+    if let Some((quote, content)) = quoted {
+        normalized.push(quote);
+        normalized.push_str(&content);
+    }
+
+    (normalized.trim_end().to_lowercase(), symbol)
+}
+
+fn push_word(normalized: &mut String, word: &mut String) {
+    if word.is_empty() {
+        return;
+    }
+    if word.contains('/') || word.contains('\\') {
+        // Only the file name of a path is meaningful across checkouts
+        let name = word.rsplit(['/', '\\']).next().unwrap_or_default();
+        normalized.push_str(&replace_digits(name));
+    } else {
+        normalized.push_str(&replace_digits(word));
+    }
+    word.clear();
+}
+
+fn replace_digits(text: &str) -> String {
+    let mut replaced = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_ascii_digit() {
+            if !replaced.ends_with('#') {
+                replaced.push('#');
+            }
+        } else {
+            replaced.push(c);
+        }
+    }
+    replaced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{DiagnosticSeverity, Position, Range};
+
+    fn diagnostic(file: &str, line: u32, severity: DiagnosticSeverity, message: &str) -> Diagnostic {
+        let mut diagnostic = Diagnostic::new(
+            file.to_string(),
+            Range {
+                start: Position { line, character: 0 },
+                end: Position { line, character: 4 },
+            },
+            severity,
+            message.to_string(),
+            "rustc".to_string(),
+        );
+        diagnostic.code = Some("E0061".to_string());
+        diagnostic
+    }
+
+    #[test]
+    fn test_normalize_message() {
+        let (message, symbol) = normalize_message("expected 2 arguments for `parse` in /a/src/lib.rs:10");
+        assert_eq!(message, "expected # arguments for {} in lib.rs:#");
+        assert_eq!(symbol.as_deref(), Some("parse"));
+        assert_eq!(normalize_message("can't  find 'x'").0, "can't find {}");
+    }
+
+    #[test]
+    fn test_clusters_across_repositories() {
+        let mut deduplicator = Deduplicator::new();
+        deduplicator.extend(
+            [
+                diagnostic("/api/src/lib.rs", 3, DiagnosticSeverity::Warning, "expected 2 arguments for `parse`"),
+                diagnostic("/api/src/main.rs", 8, DiagnosticSeverity::Error, "expected 3 arguments for `parse`"),
+                diagnostic("/api/src/main.rs", 9, DiagnosticSeverity::Error, "expected 2 arguments for `load`"),
+            ],
+            Some("api"),
+        );
+        deduplicator.extend(
+            [diagnostic("/web/src/lib.rs", 3, DiagnosticSeverity::Warning, "expected 2 arguments for `parse`")],
+            Some("web"),
+        );
+        assert_eq!(deduplicator.len(), 2);
+
+        let outcome = deduplicator.finish();
+        assert_eq!(outcome.collapsed(), 2);
+        let parse = &outcome.diagnostics[0];
+        // The most severe member is kept
+        assert_eq!(parse.file, "/api/src/main.rs");
+        let info = parse.duplicates().unwrap();
+        assert_eq!((info.occurrences, info.files), (3, 3));
+        assert_eq!(info.origins, vec!["api", "web"]);
+        assert_eq!(info.locations[0].line, 3);
+        assert_eq!(parse.occurrences(), 3);
+
+        let load = &outcome.diagnostics[1];
+        assert!(load.duplicates().is_none());
+        assert_eq!(load.occurrences(), 1);
+    }
+}
@@ -5,6 +5,7 @@ use crate::core::dependency_analyzer::types::{
 };
 use crate::core::dependency_analyzer::cache::DependencyCache;
 use crate::core::dependency_analyzer::resolvers;
+use crate::core::language_detection::{self, DetectedLanguage};
 use anyhow::{anyhow, Context, Result};
 use std::collections::{HashMap, HashSet};
 use std::fs;
@@ -143,7 +144,13 @@ impl AnalysisEngine {
 
         let content = fs::read_to_string(file_path)
             .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
-        let language = self.detect_language(file_path);
+        let language = self.detect_language(file_path, &content);
+
+        // Only the first script block of a template carries its imports
+        let content = match language_detection::embedded_blocks(file_path, &content).first() {
+            Some(block) => block.masked(&content),
+            None => content,
+        };
 
         let tree = self.parse_file(&content, language)?;
         let root_node = tree.root_node();
@@ -173,12 +180,11 @@ impl AnalysisEngine {
         Ok(dependencies)
     }
 
-    fn detect_language(&self, file_path: &Path) -> Language {
-        match file_path.extension().and_then(|ext| ext.to_str()) {
-            Some("ts") | Some("tsx") => Language::TypeScript,
-            Some("js") | Some("jsx") => Language::TypeScript,
-            Some("rs") => Language::Rust,
-            Some("py") => Language::Python,
+    fn detect_language(&self, file_path: &Path, content: &str) -> Language {
+        match language_detection::detect_language(file_path, Some(content)) {
+            Some(DetectedLanguage::TypeScript | DetectedLanguage::JavaScript) => Language::TypeScript,
+            Some(DetectedLanguage::Rust) => Language::Rust,
+            Some(DetectedLanguage::Python) => Language::Python,
             _ => Language::Unknown,
         }
     }
@@ -1,8 +1,10 @@
 use super::diagnostic_grouping::DiagnosticGroup;
+use super::language_detection::{detect_file_language, DetectedLanguage};
 use super::types::{Diagnostic, DiagnosticSeverity};
-use crate::analyzers::{LanguageAnalyzer, RustAnalyzer, TypeScriptAnalyzer};
+use crate::analyzers::{JavaAnalyzer, KotlinAnalyzer, LanguageAnalyzer, RustAnalyzer, TypeScriptAnalyzer};
 use crate::simple_builder;
 use std::collections::HashMap;
+use std::path::Path;
 
 /// A prioritized diagnostic with scoring information
 #[derive(Debug, Clone)]
@@ -44,6 +46,8 @@ impl DiagnosticPrioritizer {
             Box::new(TypeScriptAnalyzer::new()),
         );
         analyzers.insert("rust".to_string(), Box::new(RustAnalyzer::new()));
+        analyzers.insert("java".to_string(), Box::new(JavaAnalyzer::new()));
+        analyzers.insert("kotlin".to_string(), Box::new(KotlinAnalyzer::new()));
 
         Self { analyzers }
     }
@@ -115,8 +119,18 @@ impl DiagnosticPrioritizer {
             self.analyzers.get("typescript")
         } else if language.contains("rust") {
             self.analyzers.get("rust")
+        } else if language == "java" || language.contains("jdtls") {
+            self.analyzers.get("java")
+        } else if language.contains("kotlin") {
+            self.analyzers.get("kotlin")
         } else {
-            None
+            // Fall back to the file itself, e.g. a TypeScript block in a .vue file
+            match detect_file_language(Path::new(&diagnostic.file))? {
+                DetectedLanguage::TypeScript | DetectedLanguage::JavaScript => self.analyzers.get("typescript"),
+                DetectedLanguage::Rust => self.analyzers.get("rust"),
+                DetectedLanguage::Java => self.analyzers.get("java"),
+                _ => None,
+            }
         }
     }
 
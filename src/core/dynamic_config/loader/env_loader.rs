@@ -88,6 +88,13 @@ impl EnvLoader {
             }
         }
 
+        // Setting a collector endpoint turns OTLP export on
+        if let Ok(val) = env::var(format!("{}OTEL_ENDPOINT", self.prefix)) {
+            config.metrics.enable_opentelemetry = true;
+            debug!("Applied env override: metrics.otlp_endpoint = {}", val);
+            config.metrics.otlp_endpoint = Some(val);
+        }
+
         // Performance overrides
         if let Ok(val) = env::var(format!("{}MAX_CPU_USAGE_PERCENT", self.prefix)) {
             if let Ok(cpu) = val.parse::<f64>() {
@@ -187,6 +194,21 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_otel_endpoint_enables_export() {
+        // Own prefix so parallel tests can't see the variable
+        let loader = EnvLoader::new("LSPBRIDGE_OTEL_TEST_".to_string());
+        assert!(!loader.apply_env_overrides(DynamicConfig::default()).metrics.enable_opentelemetry);
+
+        env::set_var("LSPBRIDGE_OTEL_TEST_OTEL_ENDPOINT", "http://collector:4317");
+        let metrics = loader.apply_env_overrides(DynamicConfig::default()).metrics;
+        env::remove_var("LSPBRIDGE_OTEL_TEST_OTEL_ENDPOINT");
+
+        assert!(metrics.enable_opentelemetry);
+        assert_eq!(metrics.otlp_endpoint.as_deref(), Some("http://collector:4317"));
+        assert_eq!(metrics.service_name, "lspbridge");
+    }
+
     #[tokio::test]
     async fn test_env_loader_cannot_save() -> anyhow::Result<()> {
         let loader = EnvLoader::default();
@@ -46,7 +46,7 @@ pub mod watchers;
 // Re-export main types for convenience
 pub use types::{
     ConfigChange, DynamicCacheConfig, DynamicConfig, DynamicErrorRecoveryConfig,
-    DynamicMemoryConfig, FeatureFlags, GitConfig, MetricsConfig, PerformanceConfig,
+    DynamicMemoryConfig, DynamicPrivacyConfig, FeatureFlags, GitConfig, MetricsConfig, PerformanceConfig,
     ProcessingConfig,
 };
 
@@ -97,10 +97,16 @@ impl DynamicConfigManager {
         }
 
         let (change_notifier, _) = ConfigChangeNotifier::new(100);
-        let watcher = Some(FileWatcher::new(config_file));
+        let config = Arc::new(RwLock::new(config));
+        // File edits update this manager's configuration and notify its subscribers
+        let watcher = Some(FileWatcher::with_shared_state(
+            config_file,
+            Arc::clone(&config),
+            change_notifier.clone(),
+        ));
 
         let manager = Self {
-            config: Arc::new(RwLock::new(config)),
+            config,
             loader,
             validator,
             watcher,
@@ -384,6 +390,43 @@ impl DynamicConfigManager {
             });
         }
 
+        // Privacy changes
+        if old.privacy.level != new.privacy.level {
+            changes.push(ConfigChange {
+                field_path: "privacy.level".to_string(),
+                old_value: old.privacy.level.clone(),
+                new_value: new.privacy.level.clone(),
+                timestamp,
+            });
+        }
+
+        if old.privacy.exclude_patterns != new.privacy.exclude_patterns {
+            changes.push(ConfigChange {
+                field_path: "privacy.exclude_patterns".to_string(),
+                old_value: old.privacy.exclude_patterns.join(","),
+                new_value: new.privacy.exclude_patterns.join(","),
+                timestamp,
+            });
+        }
+
+        if old.privacy.redaction_patterns != new.privacy.redaction_patterns {
+            changes.push(ConfigChange {
+                field_path: "privacy.redaction_patterns".to_string(),
+                old_value: old.privacy.redaction_patterns.join(","),
+                new_value: new.privacy.redaction_patterns.join(","),
+                timestamp,
+            });
+        }
+
+        if old.severity_rules != new.severity_rules {
+            changes.push(ConfigChange {
+                field_path: "severity_rules.rules".to_string(),
+                old_value: serde_json::to_string(&old.severity_rules.rules).unwrap_or_default(),
+                new_value: serde_json::to_string(&new.severity_rules.rules).unwrap_or_default(),
+                timestamp,
+            });
+        }
+
         changes
     }
 }
@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::time::{Duration, SystemTime};
 
-use crate::core::{CacheConfig, EvictionPolicy, MemoryConfig, RecoveryStrategy};
+use crate::core::{CacheConfig, EvictionPolicy, MemoryConfig, PrivacyPolicy, RecoveryStrategy, SeverityRulesConfig};
 
 /// Main dynamic configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,6 +32,14 @@ pub struct DynamicConfig {
 
     /// Performance tuning
     pub performance: PerformanceConfig,
+
+    /// Privacy rules, applied to the next diagnostics captured after a change
+    #[serde(default)]
+    pub privacy: DynamicPrivacyConfig,
+
+    /// Severity re-mapping by source and code
+    #[serde(default)]
+    pub severity_rules: SeverityRulesConfig,
 }
 
 /// Processing configuration
@@ -99,6 +107,19 @@ pub struct MetricsConfig {
     pub collection_interval_seconds: u64,
     pub retention_hours: u64,
     pub export_format: String, // "prometheus", "json", "csv"
+    /// Export spans and metrics over OTLP (needs the `opentelemetry` feature)
+    #[serde(default)]
+    pub enable_opentelemetry: bool,
+    /// OTLP gRPC collector; `None` uses `OTEL_EXPORTER_OTLP_ENDPOINT` or `http://localhost:4317`
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+    /// `service.name` reported with every span and metric
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+}
+
+pub(crate) fn default_service_name() -> String {
+    "lspbridge".to_string()
 }
 
 /// Feature flags configuration
@@ -118,6 +139,27 @@ pub struct PerformanceConfig {
     pub enable_parallel_io: bool,
 }
 
+/// Dynamic privacy configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DynamicPrivacyConfig {
+    pub level: String, // "strict", "balanced", "minimal"
+    /// Additional globs of files to exclude
+    pub exclude_patterns: Vec<String>,
+    /// Regexes redacted from diagnostic messages
+    pub redaction_patterns: Vec<String>,
+}
+
+impl Default for DynamicPrivacyConfig {
+    fn default() -> Self {
+        Self {
+            level: "balanced".to_string(),
+            exclude_patterns: Vec::new(),
+            redaction_patterns: Vec::new(),
+        }
+    }
+}
+
 /// Configuration change notification
 #[derive(Debug, Clone)]
 pub struct ConfigChange {
@@ -178,6 +220,9 @@ impl Default for DynamicConfig {
                 collection_interval_seconds: 60,
                 retention_hours: 168, // 1 week
                 export_format: "prometheus".to_string(),
+                enable_opentelemetry: false,
+                otlp_endpoint: None,
+                service_name: default_service_name(),
             },
             features: FeatureFlags {
                 enable_smart_caching: true,
@@ -190,6 +235,8 @@ impl Default for DynamicConfig {
                 io_priority: "normal".to_string(),
                 enable_parallel_io: true,
             },
+            privacy: DynamicPrivacyConfig::default(),
+            severity_rules: SeverityRulesConfig::default(),
         }
     }
 }
@@ -244,4 +291,18 @@ impl DynamicErrorRecoveryConfig {
             circuit_breaker_timeout: Duration::from_millis(self.timeout_ms),
         }
     }
-}
\ No newline at end of file
+}
+
+impl DynamicPrivacyConfig {
+    /// Convert to the static privacy policy type
+    pub fn to_privacy_policy(&self) -> PrivacyPolicy {
+        let mut policy = match self.level.as_str() {
+            "strict" => PrivacyPolicy::strict(),
+            "minimal" => PrivacyPolicy::permissive(),
+            _ => PrivacyPolicy::default(),
+        };
+        policy.exclude_patterns.extend(self.exclude_patterns.iter().cloned());
+        policy.redaction_patterns = self.redaction_patterns.clone();
+        policy
+    }
+}
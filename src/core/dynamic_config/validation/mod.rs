@@ -75,6 +75,8 @@ impl ConfigValidationEngine {
             "metrics.collection_interval_seconds" => Ok(config.metrics.collection_interval_seconds.to_string()),
             "metrics.retention_hours" => Ok(config.metrics.retention_hours.to_string()),
             "metrics.export_format" => Ok(config.metrics.export_format.clone()),
+            "metrics.enable_opentelemetry" => Ok(config.metrics.enable_opentelemetry.to_string()),
+            "metrics.service_name" => Ok(config.metrics.service_name.clone()),
             
             "performance.max_cpu_usage_percent" => Ok(config.performance.max_cpu_usage_percent.to_string()),
             "performance.io_priority" => Ok(config.performance.io_priority.clone()),
@@ -213,6 +213,12 @@ impl ConfigValidator {
             });
         }
 
+        if config.enable_opentelemetry && config.service_name.trim().is_empty() {
+            return Err(ConfigError::ValidationFailed {
+                reason: "Metrics service_name must not be empty when OpenTelemetry is enabled".to_string(),
+            });
+        }
+
         // Validate export format
         if !matches!(config.export_format.as_str(), "prometheus" | "json" | "csv") {
             return Err(ConfigError::ValidationFailed {
@@ -50,6 +50,19 @@ impl FileWatcher {
         watcher
     }
 
+    /// Create a file watcher that reloads into an existing configuration
+    /// and reports changes through an existing notifier
+    pub fn with_shared_state(
+        file_path: PathBuf,
+        config: Arc<RwLock<DynamicConfig>>,
+        change_notifier: ConfigChangeNotifier,
+    ) -> Self {
+        let mut watcher = Self::new(file_path);
+        watcher.current_config = config;
+        watcher.change_notifier = change_notifier;
+        watcher
+    }
+
     /// Set the current configuration
     pub async fn set_config(&self, config: DynamicConfig) {
         let mut current = self.current_config.write().await;
@@ -165,6 +178,43 @@ impl FileWatcher {
             });
         }
 
+        // Privacy changes
+        if old.privacy.level != new.privacy.level {
+            changes.push(ConfigChange {
+                field_path: "privacy.level".to_string(),
+                old_value: old.privacy.level.clone(),
+                new_value: new.privacy.level.clone(),
+                timestamp,
+            });
+        }
+
+        if old.privacy.exclude_patterns != new.privacy.exclude_patterns {
+            changes.push(ConfigChange {
+                field_path: "privacy.exclude_patterns".to_string(),
+                old_value: old.privacy.exclude_patterns.join(","),
+                new_value: new.privacy.exclude_patterns.join(","),
+                timestamp,
+            });
+        }
+
+        if old.privacy.redaction_patterns != new.privacy.redaction_patterns {
+            changes.push(ConfigChange {
+                field_path: "privacy.redaction_patterns".to_string(),
+                old_value: old.privacy.redaction_patterns.join(","),
+                new_value: new.privacy.redaction_patterns.join(","),
+                timestamp,
+            });
+        }
+
+        if old.severity_rules != new.severity_rules {
+            changes.push(ConfigChange {
+                field_path: "severity_rules.rules".to_string(),
+                old_value: serde_json::to_string(&old.severity_rules.rules).unwrap_or_default(),
+                new_value: serde_json::to_string(&new.severity_rules.rules).unwrap_or_default(),
+                timestamp,
+            });
+        }
+
         changes
     }
 
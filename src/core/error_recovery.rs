@@ -1,12 +1,70 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
+use clap::{Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+/// Subsystems with their own recovery policy and circuit breaker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Subsystem {
+    /// Reading diagnostics from IDEs and language servers
+    Capture,
+    /// Formatting and writing exports
+    Export,
+    /// History and cache databases
+    Storage,
+    /// Git invocations
+    Git,
+}
+
+impl Subsystem {
+    pub const ALL: [Subsystem; 4] = [
+        Subsystem::Capture,
+        Subsystem::Export,
+        Subsystem::Storage,
+        Subsystem::Git,
+    ];
+}
+
+impl fmt::Display for Subsystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Subsystem::Capture => "capture",
+            Subsystem::Export => "export",
+            Subsystem::Storage => "storage",
+            Subsystem::Git => "git",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Actions for inspecting and resetting circuit breakers
+#[derive(Debug, Clone, Subcommand)]
+pub enum BreakerAction {
+    /// Show the state of each subsystem's circuit breaker
+    Status {
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "markdown")]
+        format: crate::cli::OutputFormat,
+    },
+    /// Close a circuit breaker so the subsystem is tried again immediately
+    Reset {
+        /// Subsystem to reset
+        #[arg(value_enum, required_unless_present = "all")]
+        subsystem: Option<Subsystem>,
+        /// Reset every subsystem
+        #[arg(long, conflicts_with = "subsystem")]
+        all: bool,
+    },
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ErrorSeverity {
     Low,      // Single file failures
@@ -58,7 +116,43 @@ impl Default for RecoveryStrategy {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl RecoveryStrategy {
+    /// Default policy for a subsystem
+    ///
+    /// Capture failures are usually transient (an IDE restarting), so they
+    /// retry quickly and recover fast. Storage and git failures tend to persist
+    /// (locked database, broken repository) and back off for longer.
+    pub fn for_subsystem(subsystem: Subsystem) -> Self {
+        let defaults = Self::default();
+        match subsystem {
+            Subsystem::Capture => Self {
+                max_retries: 2,
+                max_delay: Duration::from_secs(2),
+                circuit_breaker_timeout: Duration::from_secs(30),
+                ..defaults
+            },
+            Subsystem::Export => Self {
+                max_retries: 2,
+                ..defaults
+            },
+            Subsystem::Storage => Self {
+                initial_delay: Duration::from_millis(250),
+                circuit_breaker_threshold: 3,
+                circuit_breaker_timeout: Duration::from_secs(120),
+                ..defaults
+            },
+            Subsystem::Git => Self {
+                max_retries: 1,
+                circuit_breaker_threshold: 3,
+                circuit_breaker_timeout: Duration::from_secs(300),
+                ..defaults
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum CircuitState {
     Closed,   // Normal operation
     Open,     // Failing fast
@@ -89,24 +183,9 @@ impl CircuitBreaker {
         F: std::future::Future<Output = Result<T, E>>,
         E: std::fmt::Display + From<String>,
     {
-        let state = *self.state.read().await;
-
-        match state {
-            CircuitState::Open => {
-                if self.should_attempt_reset().await {
-                    *self.state.write().await = CircuitState::HalfOpen;
-                    info!("Circuit breaker transitioning to half-open");
-                } else {
-                    warn!("Circuit breaker is open - failing fast");
-                    return Err(E::from("Circuit breaker is open".to_string()));
-                }
-            }
-            CircuitState::HalfOpen => {
-                // Allow limited requests through
-            }
-            CircuitState::Closed => {
-                // Normal operation
-            }
+        if !self.allow_request().await {
+            warn!("Circuit breaker is open - failing fast");
+            return Err(E::from("Circuit breaker is open".to_string()));
         }
 
         match operation.await {
@@ -121,6 +200,40 @@ impl CircuitBreaker {
         }
     }
 
+    /// Restore a breaker from persisted state
+    fn restored(strategy: RecoveryStrategy, status: &BreakerStatus) -> Self {
+        let last_failure = status.last_failure.and_then(|at| {
+            let elapsed = at.elapsed().unwrap_or_default();
+            Instant::now().checked_sub(elapsed)
+        });
+        Self {
+            state: RwLock::new(status.state),
+            failure_count: AtomicUsize::new(status.failure_count),
+            last_failure_time: RwLock::new(last_failure),
+            success_count: AtomicUsize::new(0),
+            strategy,
+        }
+    }
+
+    /// Check whether a request may go through, moving an expired open breaker to half-open
+    async fn allow_request(&self) -> bool {
+        let state = *self.state.read().await;
+
+        match state {
+            CircuitState::Open => {
+                if self.should_attempt_reset().await {
+                    *self.state.write().await = CircuitState::HalfOpen;
+                    info!("Circuit breaker transitioning to half-open");
+                    true
+                } else {
+                    false
+                }
+            }
+            // Half-open allows limited requests through to test recovery
+            CircuitState::HalfOpen | CircuitState::Closed => true,
+        }
+    }
+
     async fn should_attempt_reset(&self) -> bool {
         if let Some(last_failure) = *self.last_failure_time.read().await {
             last_failure.elapsed() >= self.strategy.circuit_breaker_timeout
@@ -179,10 +292,38 @@ impl CircuitBreaker {
     pub async fn is_open(&self) -> bool {
         *self.state.read().await == CircuitState::Open
     }
+
+    pub async fn state(&self) -> CircuitState {
+        *self.state.read().await
+    }
+
+    pub fn failure_count(&self) -> usize {
+        self.failure_count.load(Ordering::SeqCst)
+    }
+
+    /// Close the breaker and forget past failures
+    pub async fn reset(&self) {
+        *self.state.write().await = CircuitState::Closed;
+        *self.last_failure_time.write().await = None;
+        self.failure_count.store(0, Ordering::SeqCst);
+        self.success_count.store(0, Ordering::SeqCst);
+    }
+}
+
+/// Point-in-time state of a subsystem's circuit breaker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakerStatus {
+    pub subsystem: Subsystem,
+    pub state: CircuitState,
+    pub failure_count: usize,
+    pub last_failure: Option<SystemTime>,
 }
 
 pub struct ErrorRecoverySystem {
     circuit_breaker: Arc<CircuitBreaker>,
+    policies: HashMap<Subsystem, RecoveryStrategy>,
+    breakers: HashMap<Subsystem, Arc<CircuitBreaker>>,
+    state_path: Option<PathBuf>,
     error_history: RwLock<Vec<ErrorEvent>>,
     retry_counts: RwLock<HashMap<PathBuf, usize>>,
     strategy: RecoveryStrategy,
@@ -192,8 +333,20 @@ pub struct ErrorRecoverySystem {
 
 impl ErrorRecoverySystem {
     pub fn new(strategy: RecoveryStrategy) -> Self {
+        let policies: HashMap<Subsystem, RecoveryStrategy> = Subsystem::ALL
+            .iter()
+            .map(|&subsystem| (subsystem, RecoveryStrategy::for_subsystem(subsystem)))
+            .collect();
+        let breakers = policies
+            .iter()
+            .map(|(&subsystem, policy)| (subsystem, Arc::new(CircuitBreaker::new(policy.clone()))))
+            .collect();
+
         Self {
             circuit_breaker: Arc::new(CircuitBreaker::new(strategy.clone())),
+            policies,
+            breakers,
+            state_path: None,
             error_history: RwLock::new(Vec::new()),
             retry_counts: RwLock::new(HashMap::new()),
             strategy,
@@ -202,6 +355,159 @@ impl ErrorRecoverySystem {
         }
     }
 
+    /// Override the recovery policy of a subsystem
+    ///
+    /// Replaces the subsystem's circuit breaker, so call this before use.
+    pub fn with_subsystem_policy(mut self, subsystem: Subsystem, strategy: RecoveryStrategy) -> Self {
+        self.breakers
+            .insert(subsystem, Arc::new(CircuitBreaker::new(strategy.clone())));
+        self.policies.insert(subsystem, strategy);
+        self
+    }
+
+    /// Persist breaker state to `path` so it survives across invocations
+    ///
+    /// Breakers recorded in an existing file are restored, which keeps an
+    /// open breaker open until its timeout expires or it is reset manually.
+    pub fn with_state_file(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        match load_breaker_states(&path) {
+            Ok(statuses) => {
+                for status in statuses {
+                    let policy = self.policy(status.subsystem).clone();
+                    self.breakers.insert(
+                        status.subsystem,
+                        Arc::new(CircuitBreaker::restored(policy, &status)),
+                    );
+                }
+            }
+            Err(e) => warn!("Ignoring unreadable breaker state {}: {e}", path.display()),
+        }
+        self.state_path = Some(path);
+        self
+    }
+
+    /// Default policies with breaker state shared by every lspbridge process
+    ///
+    /// Falls back to in-memory breakers when there is no data directory.
+    pub fn persistent() -> Self {
+        let recovery = Self::new(RecoveryStrategy::default());
+        match Self::default_state_path() {
+            Ok(path) => recovery.with_state_file(path),
+            Err(_) => recovery,
+        }
+    }
+
+    /// Default location of the persisted breaker state
+    pub fn default_state_path() -> Result<PathBuf> {
+        Ok(crate::config::data_dir()?.join("circuit_breakers.json"))
+    }
+
+    /// Recovery policy of a subsystem
+    pub fn policy(&self, subsystem: Subsystem) -> &RecoveryStrategy {
+        &self.policies[&subsystem]
+    }
+
+    /// Circuit breaker of a subsystem
+    pub fn breaker(&self, subsystem: Subsystem) -> Arc<CircuitBreaker> {
+        self.breakers[&subsystem].clone()
+    }
+
+    /// Current state of every subsystem breaker
+    pub async fn breaker_status(&self) -> Vec<BreakerStatus> {
+        let mut statuses = Vec::with_capacity(Subsystem::ALL.len());
+        for subsystem in Subsystem::ALL {
+            let breaker = &self.breakers[&subsystem];
+            let last_failure = breaker
+                .last_failure_time
+                .read()
+                .await
+                .and_then(|at| SystemTime::now().checked_sub(at.elapsed()));
+            statuses.push(BreakerStatus {
+                subsystem,
+                state: breaker.state().await,
+                failure_count: breaker.failure_count(),
+                last_failure,
+            });
+        }
+        statuses
+    }
+
+    /// Manually close a subsystem's breaker
+    pub async fn reset_breaker(&self, subsystem: Subsystem) -> Result<()> {
+        self.breakers[&subsystem].reset().await;
+        info!("Circuit breaker for {subsystem} reset manually");
+        self.persist_breakers().await
+    }
+
+    /// Run an operation under a subsystem's policy and circuit breaker
+    ///
+    /// Fails fast while the breaker is open. Otherwise retries with backoff
+    /// up to the policy's limit, stopping early if the breaker opens.
+    pub async fn execute_in<F, Fut, T, E>(&self, subsystem: Subsystem, operation: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T, E>>,
+        E: std::fmt::Display,
+    {
+        let policy = self.policy(subsystem).clone();
+        let breaker = self.breaker(subsystem);
+
+        if !breaker.allow_request().await {
+            return Err(anyhow!(
+                "Circuit breaker for {subsystem} is open; retry later or run `lspbridge breakers reset {subsystem}`"
+            ));
+        }
+
+        self.recovery_attempts.fetch_add(1, Ordering::SeqCst);
+        let mut delay = policy.initial_delay;
+        let mut last_error = String::new();
+
+        for attempt in 0..policy.max_retries.max(1) {
+            match operation().await {
+                Ok(result) => {
+                    breaker.record_success().await;
+                    self.persist_breakers_logged().await;
+                    return Ok(result);
+                }
+                Err(error) => {
+                    last_error = error.to_string();
+                    breaker.record_failure().await;
+                    self.handle_error(ErrorEvent {
+                        error: last_error.clone(),
+                        file_path: None,
+                        severity: self.classify_error(&last_error),
+                        timestamp: Instant::now(),
+                        context: HashMap::from([("subsystem".to_string(), subsystem.to_string())]),
+                    })
+                    .await;
+
+                    if breaker.is_open().await || attempt + 1 >= policy.max_retries {
+                        break;
+                    }
+                    tokio::time::sleep(delay).await;
+                    delay = std::cmp::min(delay.mul_f64(policy.backoff_multiplier), policy.max_delay);
+                }
+            }
+        }
+
+        self.persist_breakers_logged().await;
+        Err(anyhow!("{subsystem} operation failed: {last_error}"))
+    }
+
+    async fn persist_breakers(&self) -> Result<()> {
+        match &self.state_path {
+            Some(path) => save_breaker_states(path, &self.breaker_status().await),
+            None => Ok(()),
+        }
+    }
+
+    async fn persist_breakers_logged(&self) {
+        if let Err(e) = self.persist_breakers().await {
+            warn!("Failed to persist circuit breaker state: {e}");
+        }
+    }
+
     pub async fn handle_error(&self, error_event: ErrorEvent) -> RecoveryAction {
         self.total_errors.fetch_add(1, Ordering::SeqCst);
 
@@ -382,11 +688,19 @@ impl ErrorRecoverySystem {
             0.0
         };
 
+        let mut open_breakers = Vec::new();
+        for subsystem in Subsystem::ALL {
+            if self.breakers[&subsystem].is_open().await {
+                open_breakers.push(subsystem);
+            }
+        }
+
         ErrorStatistics {
             total_errors: self.total_errors.load(Ordering::SeqCst),
             recovery_attempts: self.recovery_attempts.load(Ordering::SeqCst),
             recent_error_rate: error_rate,
             circuit_breaker_open: self.circuit_breaker.is_open().await,
+            open_breakers,
             active_retry_files: self.retry_counts.read().await.len(),
         }
     }
@@ -407,9 +721,29 @@ pub struct ErrorStatistics {
     pub recovery_attempts: u64,
     pub recent_error_rate: f64,
     pub circuit_breaker_open: bool,
+    pub open_breakers: Vec<Subsystem>,
     pub active_retry_files: usize,
 }
 
+/// Load persisted breaker state; a missing file means all breakers are closed
+pub fn load_breaker_states(path: &Path) -> Result<Vec<BreakerStatus>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Persist breaker state
+pub fn save_breaker_states(path: &Path, statuses: &[BreakerStatus]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(statuses)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -460,4 +794,65 @@ mod tests {
         let stats = recovery_system.get_error_statistics().await;
         assert_eq!(stats.total_errors, 1);
     }
+
+    #[tokio::test]
+    async fn test_subsystem_breakers_are_independent() {
+        let recovery = ErrorRecoverySystem::new(RecoveryStrategy::default()).with_subsystem_policy(
+            Subsystem::Git,
+            RecoveryStrategy {
+                max_retries: 1,
+                circuit_breaker_threshold: 2,
+                ..Default::default()
+            },
+        );
+        let fail = || -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), String>> + Send>> {
+            Box::pin(async { Err("not a git repository".to_string()) })
+        };
+
+        assert!(recovery.execute_in(Subsystem::Git, fail).await.is_err());
+        assert!(recovery.execute_in(Subsystem::Git, fail).await.is_err());
+        let stats = recovery.get_error_statistics().await;
+        assert_eq!(stats.open_breakers, vec![Subsystem::Git]);
+
+        // Open breaker fails fast without running the operation
+        let err = recovery
+            .execute_in(Subsystem::Git, || Box::pin(async { Ok::<_, String>(1) }))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("breakers reset git"));
+
+        // Other subsystems are unaffected
+        let value = recovery
+            .execute_in(Subsystem::Export, || Box::pin(async { Ok::<_, String>(2) }))
+            .await
+            .unwrap();
+        assert_eq!(value, 2);
+    }
+
+    #[tokio::test]
+    async fn test_breaker_state_persists_and_resets() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("breakers.json");
+        let policy = RecoveryStrategy {
+            max_retries: 1,
+            circuit_breaker_threshold: 1,
+            ..Default::default()
+        };
+
+        let recovery = ErrorRecoverySystem::new(RecoveryStrategy::default())
+            .with_subsystem_policy(Subsystem::Storage, policy.clone())
+            .with_state_file(&path);
+        let _ = recovery
+            .execute_in(Subsystem::Storage, || Box::pin(async { Err::<(), _>("database is locked") }))
+            .await;
+
+        let reloaded = ErrorRecoverySystem::new(RecoveryStrategy::default())
+            .with_subsystem_policy(Subsystem::Storage, policy)
+            .with_state_file(&path);
+        assert!(reloaded.breaker(Subsystem::Storage).is_open().await);
+
+        reloaded.reset_breaker(Subsystem::Storage).await.unwrap();
+        let statuses = load_breaker_states(&path).unwrap();
+        assert!(statuses.iter().all(|s| s.state == CircuitState::Closed));
+    }
 }
@@ -3,6 +3,7 @@
 /// This module provides structured error types that replace generic anyhow::Error
 /// usage in specific domains. These error types enable better error matching,
 /// recovery strategies, and integration with the sophisticated error recovery system.
+use super::file_guard::SkipReason;
 use std::io;
 use std::path::PathBuf;
 use thiserror::Error;
@@ -77,6 +78,9 @@ pub enum FileError {
         #[source]
         source: io::Error,
     },
+
+    #[error("Skipped {path}: {reason}")]
+    Skipped { path: PathBuf, reason: SkipReason },
 }
 
 /// Parsing errors for various formats
@@ -276,6 +280,24 @@ pub enum ParseError {
         field: String,
         available_fields: Vec<String>,
     },
+
+    #[error("Unbound parameter placeholder at line {line}, column {column}")]
+    UnboundParameter {
+        line: usize,
+        column: usize,
+    },
+
+    #[error("Parameter count mismatch: query has {expected} placeholder(s), {found} value(s) given")]
+    ParameterCount {
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("Invalid value for parameter {index}: {reason}")]
+    InvalidParameter {
+        index: usize,
+        reason: String,
+    },
 }
 
 /// Processing errors for analyzers and processors
@@ -0,0 +1,208 @@
+//! Rules that split one export between several targets
+//!
+//! Different classes of diagnostics often belong in different places: security
+//! findings in a locked-down SARIF report, style issues in a permissive
+//! assistant export. `[export_routing]` in `lspbridge.toml` lists routes in
+//! priority order, and `lspbridge export --route` sends every diagnostic to the
+//! first route it matches.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use super::security_config::PrivacyLevel;
+use super::{Diagnostic, DiagnosticFilter, ExportFormat};
+use crate::analyzers::DiagnosticTaxonomy;
+
+/// Export routes, under `[export_routing]` in `lspbridge.toml`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportRoutingConfig {
+    /// Routes in priority order; a diagnostic goes to the first one it matches
+    pub routes: Vec<ExportRoute>,
+}
+
+/// One class of diagnostics and where it is exported
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportRoute {
+    /// Route name; output files are called `<name>.<ext>`
+    pub name: String,
+    /// Taxonomy buckets the route takes; empty takes any
+    #[serde(default)]
+    pub taxonomy: Vec<DiagnosticTaxonomy>,
+    /// Further criteria with the same meaning as the export filter flags
+    #[serde(default, flatten)]
+    pub filter: DiagnosticFilter,
+    /// Formats written for the route
+    pub formats: Vec<ExportFormat>,
+    /// Privacy applied to the route on top of the capture's `--privacy` level
+    #[serde(default)]
+    pub privacy: PrivacyLevel,
+    /// Directory for the route's files; defaults to `--out-dir`
+    #[serde(default)]
+    pub out_dir: Option<PathBuf>,
+}
+
+impl ExportRoute {
+    /// Whether the route takes a diagnostic of the given taxonomy
+    pub fn matches(&self, diagnostic: &Diagnostic, taxonomy: DiagnosticTaxonomy, captured_at: DateTime<Utc>) -> bool {
+        (self.taxonomy.is_empty() || self.taxonomy.contains(&taxonomy)) && self.filter.matches(diagnostic, captured_at)
+    }
+}
+
+/// Diagnostics split between routes
+#[derive(Debug, Clone, Default)]
+pub struct RoutedDiagnostics {
+    /// One entry per route, in route order
+    pub routes: Vec<Vec<Diagnostic>>,
+    /// Diagnostics no route matched; they are left out of the export
+    pub unrouted: Vec<Diagnostic>,
+}
+
+impl ExportRoutingConfig {
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// Check route names are unique file stems and every route writes something
+    pub fn validate(&self) -> Result<()> {
+        let mut names = HashSet::new();
+        for route in &self.routes {
+            let valid_name = !route.name.is_empty()
+                && route
+                    .name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+            if !valid_name {
+                return Err(anyhow!(
+                    "Export route name '{}' must be letters, digits, '-', '_' or '.'",
+                    route.name
+                ));
+            }
+            if !names.insert(route.name.as_str()) {
+                return Err(anyhow!("Export route '{}' is defined twice", route.name));
+            }
+            if route.formats.is_empty() {
+                return Err(anyhow!("Export route '{}' has no formats", route.name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Send each diagnostic to the first route it matches
+    pub fn partition(&self, diagnostics: Vec<Diagnostic>, captured_at: DateTime<Utc>) -> RoutedDiagnostics {
+        let mut routed = RoutedDiagnostics {
+            routes: vec![Vec::new(); self.routes.len()],
+            unrouted: Vec::new(),
+        };
+        for diagnostic in diagnostics {
+            let taxonomy = DiagnosticTaxonomy::classify(&diagnostic);
+            match self
+                .routes
+                .iter()
+                .position(|route| route.matches(&diagnostic, taxonomy, captured_at))
+            {
+                Some(index) => routed.routes[index].push(diagnostic),
+                None => routed.unrouted.push(diagnostic),
+            }
+        }
+        routed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{DiagnosticSeverity, Position, Range};
+
+    fn diagnostic(source: &str, code: &str, message: &str) -> Diagnostic {
+        let mut diagnostic = Diagnostic::new(
+            "app/main.py".to_string(),
+            Range {
+                start: Position { line: 0, character: 0 },
+                end: Position { line: 0, character: 1 },
+            },
+            DiagnosticSeverity::Warning,
+            message.to_string(),
+            source.to_string(),
+        );
+        diagnostic.code = Some(code.to_string());
+        diagnostic
+    }
+
+    #[test]
+    fn test_routes_take_first_match_in_order() {
+        let config: ExportRoutingConfig = toml::from_str(
+            r#"
+            [[routes]]
+            name = "security"
+            taxonomy = ["security"]
+            formats = ["sarif"]
+            privacy = "strict"
+
+            [[routes]]
+            name = "style"
+            taxonomy = ["style"]
+            formats = ["claude"]
+            privacy = "minimal"
+
+            [[routes]]
+            name = "ruff"
+            sources = ["ruff"]
+            formats = ["json", "markdown"]
+            "#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+        assert!(matches!(config.routes[0].privacy, PrivacyLevel::Strict));
+        assert!(matches!(config.routes[1].formats[..], [ExportFormat::ClaudeOptimized]));
+        assert!(matches!(config.routes[2].privacy, PrivacyLevel::Balanced));
+
+        let routed = config.partition(
+            vec![
+                diagnostic("bandit", "B105", "Possible hardcoded password"),
+                diagnostic("ruff", "E501", "Line too long"),
+                diagnostic("ruff", "F821", "Undefined name `x`"),
+                diagnostic("mypy", "arg-type", "Incompatible type"),
+            ],
+            Utc::now(),
+        );
+        let codes: Vec<Vec<_>> = routed
+            .routes
+            .iter()
+            .map(|route| route.iter().map(|d| d.code.clone().unwrap()).collect())
+            .collect();
+        // The style lint also matches the ruff route, but style comes first
+        assert_eq!(codes, vec![vec!["B105"], vec!["E501"], vec!["F821"]]);
+        assert_eq!(routed.unrouted.len(), 1);
+        assert_eq!(routed.unrouted[0].source, "mypy");
+    }
+
+    #[test]
+    fn test_validate_rejects_duplicate_and_empty_routes() {
+        let route = |name: &str, formats: Vec<ExportFormat>| ExportRoute {
+            name: name.to_string(),
+            taxonomy: Vec::new(),
+            filter: DiagnosticFilter::default(),
+            formats,
+            privacy: PrivacyLevel::default(),
+            out_dir: None,
+        };
+        let duplicate = ExportRoutingConfig {
+            routes: vec![route("a", vec![ExportFormat::Json]), route("a", vec![ExportFormat::Sarif])],
+        };
+        assert!(duplicate.validate().is_err());
+
+        let empty = ExportRoutingConfig {
+            routes: vec![route("a", Vec::new())],
+        };
+        assert!(empty.validate().is_err());
+
+        let path = ExportRoutingConfig {
+            routes: vec![route("../a", vec![ExportFormat::Json])],
+        };
+        assert!(path.validate().is_err());
+    }
+}
@@ -0,0 +1,208 @@
+//! Size and binary guards for reading workspace files
+//!
+//! Context extraction, fix application and export all read source files
+//! named by diagnostics. A diagnostic on a minified bundle, a generated
+//! dump or a stray binary would otherwise load the whole file into memory.
+//! A [`FileGuard`] enforces `processing.file_size_limit_mb` and rejects files
+//! that do not look like text, recording every file it turned away so the
+//! caller can report what was excluded.
+
+use super::config::PerformanceConfig;
+use super::dynamic_config::ProcessingConfig;
+use super::errors::FileError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Bytes inspected when deciding whether a file is binary
+const SNIFF_BYTES: usize = 8 * 1024;
+
+/// Why a file was not read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum SkipReason {
+    /// Larger than the configured limit
+    TooLarge { size: u64, limit: u64 },
+    /// Contains NUL bytes or is not valid UTF-8
+    Binary,
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooLarge { size, limit } => write!(f, "too large ({size} bytes, limit {limit})"),
+            Self::Binary => f.write_str("binary content"),
+        }
+    }
+}
+
+/// A file the guard refused to read
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedFile {
+    pub path: PathBuf,
+    pub reason: SkipReason,
+}
+
+/// Reads text files within the configured size limit
+///
+/// Clones share the list of skipped files, so one guard can be handed to
+/// several components and report everything they excluded.
+#[derive(Debug, Clone)]
+pub struct FileGuard {
+    max_bytes: u64,
+    skipped: Arc<Mutex<Vec<SkippedFile>>>,
+}
+
+impl FileGuard {
+    /// Guard that rejects files larger than `limit_mb` megabytes
+    pub fn new(limit_mb: usize) -> Self {
+        Self::with_max_bytes(limit_mb as u64 * 1024 * 1024)
+    }
+
+    pub fn with_max_bytes(max_bytes: u64) -> Self {
+        Self {
+            max_bytes,
+            skipped: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    pub fn max_bytes(&self) -> u64 {
+        self.max_bytes
+    }
+
+    /// Read a text file, or record and return why it was skipped
+    pub fn read_to_string(&self, path: &Path) -> Result<String, FileError> {
+        let metadata = std::fs::metadata(path).map_err(|e| FileError::read_error(path.to_path_buf(), e))?;
+        self.check_size(path, metadata.len())?;
+        let bytes = std::fs::read(path).map_err(|e| FileError::read_error(path.to_path_buf(), e))?;
+        self.decode(path, bytes)
+    }
+
+    /// Async variant of [`FileGuard::read_to_string`]
+    pub async fn read_to_string_async(&self, path: &Path) -> Result<String, FileError> {
+        let metadata = tokio::fs::metadata(path)
+            .await
+            .map_err(|e| FileError::read_error(path.to_path_buf(), e))?;
+        self.check_size(path, metadata.len())?;
+        let bytes = tokio::fs::read(path)
+            .await
+            .map_err(|e| FileError::read_error(path.to_path_buf(), e))?;
+        self.decode(path, bytes)
+    }
+
+    /// Files skipped so far, in the order they were first encountered
+    pub fn skipped(&self) -> Vec<SkippedFile> {
+        self.skipped.lock().map(|skipped| skipped.clone()).unwrap_or_default()
+    }
+
+    fn check_size(&self, path: &Path, size: u64) -> Result<(), FileError> {
+        if size > self.max_bytes {
+            return Err(self.skip(
+                path,
+                SkipReason::TooLarge {
+                    size,
+                    limit: self.max_bytes,
+                },
+            ));
+        }
+        Ok(())
+    }
+
+    fn decode(&self, path: &Path, bytes: Vec<u8>) -> Result<String, FileError> {
+        if is_binary(&bytes) {
+            return Err(self.skip(path, SkipReason::Binary));
+        }
+        String::from_utf8(bytes).map_err(|_| self.skip(path, SkipReason::Binary))
+    }
+
+    fn skip(&self, path: &Path, reason: SkipReason) -> FileError {
+        if let Ok(mut skipped) = self.skipped.lock() {
+            if !skipped.iter().any(|file| file.path == path) {
+                tracing::debug!("Skipping {}: {}", path.display(), reason);
+                skipped.push(SkippedFile {
+                    path: path.to_path_buf(),
+                    reason,
+                });
+            }
+        }
+        FileError::Skipped {
+            path: path.to_path_buf(),
+            reason,
+        }
+    }
+}
+
+impl Default for FileGuard {
+    fn default() -> Self {
+        Self::from(&PerformanceConfig::default())
+    }
+}
+
+impl From<&PerformanceConfig> for FileGuard {
+    fn from(config: &PerformanceConfig) -> Self {
+        Self::new(config.file_size_limit_mb)
+    }
+}
+
+impl From<&ProcessingConfig> for FileGuard {
+    fn from(config: &ProcessingConfig) -> Self {
+        Self::new(config.file_size_limit_mb)
+    }
+}
+
+/// Whether the start of some content looks binary
+///
+/// Only the first few kilobytes are inspected; a multi-byte character cut
+/// off at the end of that window does not count as invalid UTF-8.
+pub fn is_binary(bytes: &[u8]) -> bool {
+    let sample = &bytes[..bytes.len().min(SNIFF_BYTES)];
+    if sample.contains(&0) {
+        return true;
+    }
+    match std::str::from_utf8(sample) {
+        Ok(_) => false,
+        Err(e) => e.error_len().is_some(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_large_and_binary_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let text = dir.path().join("lib.rs");
+        let large = dir.path().join("bundle.js");
+        let binary = dir.path().join("image.png");
+        std::fs::write(&text, "fn main() {}\n").unwrap();
+        std::fs::write(&large, "x".repeat(2048)).unwrap();
+        std::fs::write(&binary, [0x89, b'P', b'N', b'G', 0x00, 0x1a]).unwrap();
+
+        let guard = FileGuard::with_max_bytes(1024);
+        assert_eq!(guard.read_to_string(&text).unwrap(), "fn main() {}\n");
+        assert!(matches!(
+            guard.read_to_string(&large),
+            Err(FileError::Skipped {
+                reason: SkipReason::TooLarge { size: 2048, limit: 1024 },
+                ..
+            })
+        ));
+        assert!(guard.clone().read_to_string(&binary).is_err());
+        assert!(guard.read_to_string(&binary).is_err());
+
+        let skipped = guard.skipped();
+        assert_eq!(skipped.len(), 2);
+        assert_eq!(skipped[0].path, large);
+        assert_eq!(skipped[1].reason, SkipReason::Binary);
+    }
+
+    #[test]
+    fn test_truncated_utf8_sample_is_text() {
+        let mut content = "a".repeat(SNIFF_BYTES - 1).into_bytes();
+        content.extend_from_slice("é".as_bytes());
+        assert!(!is_binary(&content));
+        assert!(is_binary(&[b'a', 0xff, b'b']));
+    }
+}
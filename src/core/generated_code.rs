@@ -0,0 +1,360 @@
+//! Re-attribution of diagnostics in generated code
+//!
+//! Diagnostics reported inside generated files (protobuf stubs, OpenAPI
+//! clients, build script output) cannot be fixed where they are reported:
+//! the next generator run overwrites any edit. [`GeneratedCodeMapper`] maps
+//! such files back to the schema or source they were generated from, moves
+//! the diagnostic there and tags it as generated, so ownership, triage and
+//! exports point at the people who can actually fix it.
+//!
+//! Rules map a glob to a source template and are configured in
+//! `lspbridge.toml`:
+//!
+//! ```toml
+//! [[generated_code.rules]]
+//! pattern = "clients/petstore/**"
+//! source = "api/petstore.yaml"
+//! generator = "openapi-generator"
+//!
+//! [[generated_code.rules]]
+//! pattern = "**/*_pb2.py"
+//! source = "proto/{stem}.proto"
+//! strip_suffix = "_pb2"
+//! generator = "protoc"
+//! ```
+//!
+//! Templates may use `{dir}` (directory of the generated file relative to
+//! the root), `{name}` (its file name) and `{stem}` (its file name up to the
+//! first `.`, minus `strip_suffix`). The original location is kept as related
+//! information and under [`GENERATED_KEY`] in the diagnostic's `data`.
+
+use super::types::{Diagnostic, Location, RelatedInformation};
+use anyhow::{Context, Result};
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Key in [`Diagnostic::data`] describing where a re-attributed diagnostic came from
+pub const GENERATED_KEY: &str = "lspbridgeGenerated";
+
+/// A glob → generator source mapping
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeneratedCodeRule {
+    /// Glob matched against paths relative to the workspace root
+    pub pattern: String,
+    /// Template for the generating file
+    pub source: String,
+    /// Name of the generator, shown in exports
+    #[serde(default)]
+    pub generator: Option<String>,
+    /// Suffix removed from `{stem}` (e.g. `_pb2`)
+    #[serde(default)]
+    pub strip_suffix: Option<String>,
+}
+
+/// Generated-code settings in `lspbridge.toml`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeneratedCodeConfig {
+    /// Re-attribute diagnostics in generated files
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Also apply the built-in protobuf rules
+    #[serde(default = "default_enabled")]
+    pub builtin_rules: bool,
+    /// User rules, checked before the built-in ones
+    #[serde(default)]
+    pub rules: Vec<GeneratedCodeRule>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl Default for GeneratedCodeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            builtin_rules: true,
+            rules: Vec::new(),
+        }
+    }
+}
+
+/// Where a re-attributed diagnostic was originally reported
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedOrigin {
+    pub generated_file: String,
+    pub generator: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    rule: GeneratedCodeRule,
+    matcher: Pattern,
+    /// Built-in rules only apply when the mapped source exists
+    builtin: bool,
+}
+
+/// Maps diagnostics in generated files to their generator sources
+#[derive(Debug, Clone)]
+pub struct GeneratedCodeMapper {
+    root: PathBuf,
+    rules: Vec<CompiledRule>,
+}
+
+impl GeneratedCodeMapper {
+    /// Create a mapper without rules for a workspace root
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            rules: Vec::new(),
+        }
+    }
+
+    /// Create a mapper from `lspbridge.toml` settings
+    pub fn from_config(root: impl Into<PathBuf>, config: &GeneratedCodeConfig) -> Result<Self> {
+        let mut mapper = Self::new(root);
+        if !config.enabled {
+            return Ok(mapper);
+        }
+        for rule in &config.rules {
+            mapper = mapper.with_rule(rule.clone())?;
+        }
+        if config.builtin_rules {
+            mapper = mapper.with_builtin_rules();
+        }
+        Ok(mapper)
+    }
+
+    /// Add a rule; rules are tried in the order they were added
+    pub fn with_rule(mut self, rule: GeneratedCodeRule) -> Result<Self> {
+        let matcher = Pattern::new(&rule.pattern)
+            .with_context(|| format!("Invalid generated-code pattern '{}'", rule.pattern))?;
+        self.rules.push(CompiledRule {
+            rule,
+            matcher,
+            builtin: false,
+        });
+        Ok(self)
+    }
+
+    /// Add the protobuf conventions for Go, Python and JavaScript stubs
+    pub fn with_builtin_rules(mut self) -> Self {
+        let builtin = [
+            ("**/*_grpc.pb.go", "_grpc"),
+            ("**/*.pb.go", ""),
+            ("**/*_pb2_grpc.py", "_pb2_grpc"),
+            ("**/*_pb2.py", "_pb2"),
+            ("**/*_pb.js", "_pb"),
+            ("**/*_pb.ts", "_pb"),
+        ];
+        for (pattern, suffix) in builtin {
+            let rule = GeneratedCodeRule {
+                pattern: pattern.to_string(),
+                source: "{dir}/{stem}.proto".to_string(),
+                generator: Some("protoc".to_string()),
+                strip_suffix: (!suffix.is_empty()).then(|| suffix.to_string()),
+            };
+            self.rules.push(CompiledRule {
+                matcher: Pattern::new(&rule.pattern).expect("valid built-in pattern"),
+                rule,
+                builtin: true,
+            });
+        }
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Generator source for a generated file, with the rule that matched
+    pub fn source_for(&self, file: &str) -> Option<(PathBuf, &GeneratedCodeRule)> {
+        let path = Path::new(file);
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..MatchOptions::new()
+        };
+
+        self.rules.iter().find_map(|compiled| {
+            if !compiled.matcher.matches_path_with(relative, options) {
+                return None;
+            }
+            let source = self.root.join(expand(&compiled.rule, relative));
+            if compiled.builtin && !source.exists() {
+                return None;
+            }
+            Some((source, &compiled.rule))
+        })
+    }
+
+    /// Re-attribute a diagnostic if its file is generated; returns whether it moved
+    pub fn attribute(&self, diagnostic: &mut Diagnostic) -> bool {
+        if diagnostic.generated_origin().is_some() {
+            return false;
+        }
+        let Some((source, rule)) = self.source_for(&diagnostic.file) else {
+            return false;
+        };
+
+        let origin = GeneratedOrigin {
+            generated_file: diagnostic.file.clone(),
+            generator: rule.generator.clone(),
+        };
+        let tagged = match &mut diagnostic.data {
+            None => {
+                diagnostic.data = Some(serde_json::json!({ GENERATED_KEY: origin }));
+                true
+            }
+            Some(serde_json::Value::Object(map)) => {
+                map.insert(GENERATED_KEY.to_string(), serde_json::json!(origin));
+                true
+            }
+            Some(_) => false,
+        };
+        if !tagged {
+            // Never rewrite language server payloads; leave the diagnostic as reported
+            return false;
+        }
+
+        diagnostic
+            .related_information
+            .get_or_insert_with(Vec::new)
+            .push(RelatedInformation {
+                location: Location {
+                    uri: diagnostic.file.clone(),
+                    range: diagnostic.range.clone(),
+                },
+                message: match &rule.generator {
+                    Some(generator) => format!("Reported in code generated by {generator}"),
+                    None => "Reported in generated code".to_string(),
+                },
+            });
+        diagnostic.file = source.to_string_lossy().to_string();
+        true
+    }
+
+    /// Re-attribute every diagnostic in generated files; returns how many moved
+    pub fn attribute_all(&self, diagnostics: &mut [Diagnostic]) -> usize {
+        if self.rules.is_empty() {
+            return 0;
+        }
+        diagnostics
+            .iter_mut()
+            .filter_map(|d| self.attribute(d).then_some(()))
+            .count()
+    }
+}
+
+fn expand(rule: &GeneratedCodeRule, relative: &Path) -> String {
+    let name = relative
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut stem = name.split('.').next().unwrap_or_default().to_string();
+    if let Some(suffix) = &rule.strip_suffix {
+        if let Some(stripped) = stem.strip_suffix(suffix.as_str()) {
+            stem = stripped.to_string();
+        }
+    }
+    let dir = relative
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let expanded = rule
+        .source
+        .replace("{dir}", &dir)
+        .replace("{name}", &name)
+        .replace("{stem}", &stem);
+    // An empty `{dir}` leaves a leading slash that would escape the root
+    expanded.trim_start_matches('/').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{DiagnosticSeverity, Position, Range};
+
+    fn diagnostic(file: &str) -> Diagnostic {
+        Diagnostic::new(
+            file.to_string(),
+            Range {
+                start: Position { line: 41, character: 4 },
+                end: Position { line: 41, character: 9 },
+            },
+            DiagnosticSeverity::Warning,
+            "unused import".to_string(),
+            "pyright".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_user_rules_reattribute_and_tag() {
+        let mapper = GeneratedCodeMapper::new("/repo")
+            .with_rule(GeneratedCodeRule {
+                pattern: "**/*_pb2.py".to_string(),
+                source: "proto/{stem}.proto".to_string(),
+                generator: Some("protoc".to_string()),
+                strip_suffix: Some("_pb2".to_string()),
+            })
+            .unwrap()
+            .with_rule(GeneratedCodeRule {
+                pattern: "clients/petstore/**".to_string(),
+                source: "api/petstore.yaml".to_string(),
+                generator: None,
+                strip_suffix: None,
+            })
+            .unwrap();
+
+        let mut diagnostics = vec![
+            diagnostic("/repo/gen/python/user_pb2.py"),
+            diagnostic("/repo/clients/petstore/models/pet.ts"),
+            diagnostic("/repo/src/main.py"),
+        ];
+        assert_eq!(mapper.attribute_all(&mut diagnostics), 2);
+
+        assert_eq!(diagnostics[0].file, "/repo/proto/user.proto");
+        let origin = diagnostics[0].generated_origin().unwrap();
+        assert_eq!(origin.generated_file, "/repo/gen/python/user_pb2.py");
+        assert_eq!(origin.generator.as_deref(), Some("protoc"));
+        let related = diagnostics[0].related_information.as_ref().unwrap();
+        assert_eq!(related[0].location.uri, "/repo/gen/python/user_pb2.py");
+        assert_eq!(related[0].location.range.start.line, 41);
+
+        assert_eq!(diagnostics[1].file, "/repo/api/petstore.yaml");
+        assert_eq!(diagnostics[2].file, "/repo/src/main.py");
+        assert!(diagnostics[2].generated_origin().is_none());
+
+        // Already re-attributed diagnostics are left alone
+        assert_eq!(mapper.attribute_all(&mut diagnostics), 0);
+    }
+
+    #[test]
+    fn test_builtin_rules_require_existing_source() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("api")).unwrap();
+        std::fs::write(dir.path().join("api/user.proto"), "syntax = \"proto3\";").unwrap();
+
+        let config: GeneratedCodeConfig = toml::from_str("rules = []").unwrap();
+        let mapper = GeneratedCodeMapper::from_config(dir.path(), &config).unwrap();
+
+        let generated = dir.path().join("api/user_grpc.pb.go");
+        let (source, rule) = mapper.source_for(&generated.to_string_lossy()).unwrap();
+        assert_eq!(source, dir.path().join("api/user.proto"));
+        assert_eq!(rule.generator.as_deref(), Some("protoc"));
+
+        let orphan = dir.path().join("api/order.pb.go");
+        assert!(mapper.source_for(&orphan.to_string_lossy()).is_none());
+
+        let disabled = GeneratedCodeConfig {
+            enabled: false,
+            ..GeneratedCodeConfig::default()
+        };
+        assert!(GeneratedCodeMapper::from_config(dir.path(), &disabled)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -151,6 +151,77 @@ impl GitIntegration {
         Ok(files)
     }
 
+    /// Files added, copied, modified or renamed in the index
+    pub async fn get_staged_files(&self) -> Result<Vec<PathBuf>> {
+        self.changed_files(&["diff", "--cached", "--name-only", "--diff-filter=ACMR"])
+    }
+
+    /// Files changed since `upstream`'s merge base with HEAD, as a push would publish them
+    pub async fn get_files_changed_from(&self, upstream: &str) -> Result<Vec<PathBuf>> {
+        let range = format!("{upstream}...HEAD");
+        self.changed_files(&["diff", "--name-only", "--diff-filter=ACMR", &range])
+    }
+
+    /// The upstream of the current branch, falling back to the remote's default branch
+    pub async fn get_upstream(&self) -> Option<String> {
+        ["@{upstream}", "origin/HEAD"]
+            .into_iter()
+            .find(|rev| self.git(&["rev-parse", "--verify", "--quiet", rev]).is_ok())
+            .map(String::from)
+    }
+
+    /// Commit time of a revision
+    pub async fn get_commit_time(&self, rev: &str) -> Result<SystemTime> {
+        let output = self.git(&["log", "-1", "--format=%ct", rev])?;
+        let secs: u64 = output
+            .trim()
+            .parse()
+            .map_err(|_| anyhow!("No commit time for {rev}"))?;
+        Ok(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+    }
+
+    /// Directory git runs hooks from, honoring `core.hooksPath` and worktrees
+    pub async fn get_hooks_dir(&self) -> Result<PathBuf> {
+        let repo_root = self
+            .repo_root
+            .as_ref()
+            .ok_or_else(|| anyhow!("No Git repository"))?;
+        let output = self.git(&["rev-parse", "--git-path", "hooks"])?;
+        Ok(repo_root.join(output.trim()))
+    }
+
+    /// Paths printed one per line by a git command, resolved against the repository root
+    fn changed_files(&self, args: &[&str]) -> Result<Vec<PathBuf>> {
+        let repo_root = self
+            .repo_root
+            .as_ref()
+            .ok_or_else(|| anyhow!("No Git repository"))?;
+        Ok(self
+            .git(args)?
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| repo_root.join(line))
+            .collect())
+    }
+
+    /// Stdout of a git command run in the repository root
+    fn git(&self, args: &[&str]) -> Result<String> {
+        let repo_root = self
+            .repo_root
+            .as_ref()
+            .ok_or_else(|| anyhow!("No Git repository"))?;
+
+        let output = Command::new("git").current_dir(repo_root).args(args).output()?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "git {} failed: {}",
+                args.first().copied().unwrap_or_default(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
     pub async fn get_modified_files(&self) -> Result<Vec<PathBuf>> {
         self.maybe_refresh().await?;
 
@@ -235,6 +306,49 @@ impl GitIntegration {
         }
     }
 
+    /// Commit time of every committed line in a file, keyed by 0-based line
+    ///
+    /// Lines that are not committed yet are left out.
+    pub async fn get_line_commit_times(&self, file_path: &Path) -> Result<HashMap<u32, SystemTime>> {
+        let repo_root = self
+            .repo_root
+            .as_ref()
+            .ok_or_else(|| anyhow!("No Git repository"))?;
+
+        let output = Command::new("git")
+            .current_dir(repo_root)
+            .args([
+                "blame",
+                "--line-porcelain",
+                "--",
+                file_path.to_string_lossy().as_ref(),
+            ])
+            .output()?;
+
+        if !output.status.success() {
+            return Ok(HashMap::new());
+        }
+
+        let mut times = HashMap::new();
+        let mut line: Option<u32> = None;
+        let mut uncommitted = false;
+        for entry in String::from_utf8_lossy(&output.stdout).lines() {
+            let mut parts = entry.split_whitespace();
+            let first = parts.next().unwrap_or_default();
+            // Each line's block starts with "<sha> <orig line> <final line> [count]"
+            if first.len() == 40 && first.chars().all(|c| c.is_ascii_hexdigit()) {
+                uncommitted = first.chars().all(|c| c == '0');
+                line = parts.nth(1).and_then(|n| n.parse::<u32>().ok()).map(|n| n.saturating_sub(1));
+            } else if first == "author-time" && !uncommitted {
+                if let (Some(line), Some(secs)) = (line, parts.next().and_then(|s| s.parse::<u64>().ok())) {
+                    times.insert(line, SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+                }
+            }
+        }
+
+        Ok(times)
+    }
+
     pub async fn get_branch_info(&self) -> Result<(String, Option<(usize, usize)>)> {
         let repo_root = self
             .repo_root
@@ -568,6 +682,28 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_staged_files_and_hooks_dir() -> Result<()> {
+        let temp_dir = setup_test_repo().await?;
+        let repo_path = temp_dir.path();
+        fs::write(repo_path.join("test.txt"), "changed content")?;
+        fs::write(repo_path.join("unstaged.txt"), "new file")?;
+        Command::new("git")
+            .current_dir(repo_path)
+            .args(["add", "test.txt"])
+            .output()?;
+
+        let integration = GitIntegration::new_with_repo(repo_path.to_path_buf()).await?;
+        let staged = integration.get_staged_files().await?;
+        assert_eq!(staged.len(), 1);
+        assert!(staged[0].ends_with("test.txt"));
+
+        assert!(integration.get_hooks_dir().await?.ends_with(".git/hooks"));
+        assert!(integration.get_commit_time("HEAD").await? <= SystemTime::now());
+        assert!(integration.get_upstream().await.is_none());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_git_status_parsing() -> Result<()> {
         let integration = GitIntegration {
@@ -0,0 +1,499 @@
+//! Relationship graphs and their DOT, GraphML and Mermaid renderings
+//!
+//! A [`RelationGraph`] is a plain directed graph of labelled nodes, built
+//! from whichever relationship is being documented: repository dependencies
+//! from the multi-repo registry, a lexical call graph from the
+//! [`SymbolIndex`], or the cause chains the [`DiagnosticGrouper`] finds
+//! between diagnostics. Nodes may carry a group (a file, a language), which
+//! the renderers draw as clusters.
+//!
+//! [`DiagnosticGrouper`]: super::DiagnosticGrouper
+
+use clap::{Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use super::diagnostic_grouping::DiagnosticGroup;
+use super::symbol_index::{SymbolDefinition, SymbolIndex};
+
+/// Longest diagnostic message shown in a node label
+const MAX_MESSAGE_LABEL: usize = 60;
+
+/// Relationship graph to export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GraphKind {
+    /// Dependencies between repositories in the multi-repo registry
+    RepoDeps,
+    /// Function calls in the workspace, from the symbol index
+    Calls,
+    /// Diagnostics likely caused by other diagnostics
+    Causes,
+}
+
+/// Graph file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GraphFormat {
+    /// Graphviz DOT
+    Dot,
+    /// GraphML XML
+    Graphml,
+    /// Mermaid flowchart, for Markdown docs
+    Mermaid,
+}
+
+/// Graph actions
+#[derive(Debug, Clone, Subcommand)]
+pub enum GraphAction {
+    /// Export a relationship graph
+    Export {
+        /// Relationship to draw
+        #[arg(short, long, value_enum)]
+        kind: GraphKind,
+        /// Graph format
+        #[arg(short, long, value_enum, default_value = "mermaid")]
+        format: GraphFormat,
+        /// Output file (default: stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Workspace to index for `--kind calls`
+        #[arg(long, default_value = ".")]
+        root: PathBuf,
+        /// Only draw what is within `--depth` edges of nodes with this label,
+        /// e.g. a function or repository name
+        #[arg(long)]
+        focus: Option<String>,
+        /// Edge distance kept around `--focus`
+        #[arg(long, default_value = "2", requires = "focus")]
+        depth: usize,
+        /// Include calls made from test code
+        #[arg(long)]
+        include_tests: bool,
+    },
+}
+
+/// A node in a relationship graph
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphNode {
+    /// Unique identifier
+    pub id: String,
+    pub label: String,
+    /// Cluster the node is drawn in
+    pub group: Option<String>,
+}
+
+/// A directed edge between two nodes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub label: Option<String>,
+}
+
+/// A directed graph of labelled nodes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelationGraph {
+    pub name: String,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+impl RelationGraph {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    /// Add a node unless one with the same id exists
+    pub fn add_node(&mut self, id: impl Into<String>, label: impl Into<String>, group: Option<String>) {
+        let id = id.into();
+        if !self.nodes.iter().any(|node| node.id == id) {
+            self.nodes.push(GraphNode {
+                id,
+                label: label.into(),
+                group,
+            });
+        }
+    }
+
+    /// Add an edge unless the same edge exists
+    pub fn add_edge(&mut self, from: impl Into<String>, to: impl Into<String>, label: Option<String>) {
+        let edge = GraphEdge {
+            from: from.into(),
+            to: to.into(),
+            label,
+        };
+        if !self.edges.contains(&edge) {
+            self.edges.push(edge);
+        }
+    }
+
+    /// Lexical call graph of the functions in a symbol index
+    ///
+    /// A function calls another when the callee's name occurs inside the
+    /// caller, meaning after the caller's definition and before the next
+    /// definition in the same file. Where several functions share a name,
+    /// one in the calling file is preferred.
+    pub fn call_graph(index: &SymbolIndex, include_tests: bool) -> Self {
+        let mut graph = Self::new("calls");
+        let definitions: Vec<&SymbolDefinition> = index
+            .functions()
+            .filter(|definition| include_tests || !definition.in_test)
+            .collect();
+
+        let mut by_file: HashMap<&PathBuf, Vec<&SymbolDefinition>> = HashMap::new();
+        let mut by_name: HashMap<&str, Vec<&SymbolDefinition>> = HashMap::new();
+        for definition in &definitions {
+            by_file.entry(&definition.file).or_default().push(definition);
+            by_name.entry(&definition.name).or_default().push(definition);
+        }
+        for in_file in by_file.values_mut() {
+            in_file.sort_by_key(|definition| definition.line);
+        }
+
+        for (name, callees) in &by_name {
+            for occurrence in index.references(name) {
+                if occurrence.in_test && !include_tests {
+                    continue;
+                }
+                let Some(in_file) = by_file.get(&occurrence.file) else {
+                    continue;
+                };
+                // The enclosing function is the last one defined at or above the occurrence
+                let Some(caller) = in_file.iter().rev().find(|d| d.line <= occurrence.line) else {
+                    continue;
+                };
+                if caller.name == *name && caller.line == occurrence.line {
+                    continue; // the definition itself
+                }
+                let local: Vec<_> = callees.iter().filter(|d| d.file == occurrence.file).collect();
+                let targets = if local.is_empty() { callees.iter().collect() } else { local };
+                for callee in targets {
+                    if callee == caller {
+                        continue;
+                    }
+                    graph.add_function(caller);
+                    graph.add_function(callee);
+                    graph.add_edge(function_id(caller), function_id(callee), None);
+                }
+            }
+        }
+
+        graph.sort();
+        graph
+    }
+
+    /// Cause chains: each group's primary diagnostic points at the ones it likely causes
+    pub fn cause_chains(groups: &[DiagnosticGroup]) -> Self {
+        let mut graph = Self::new("causes");
+        for group in groups.iter().filter(|group| !group.related.is_empty()) {
+            let primary = &group.primary;
+            graph.add_node(&primary.id, diagnostic_label(primary), Some(primary.file.clone()));
+            for related in &group.related {
+                graph.add_node(&related.id, diagnostic_label(related), Some(related.file.clone()));
+                graph.add_edge(
+                    &primary.id,
+                    &related.id,
+                    Some(format!("{:.0}%", group.confidence * 100.0)),
+                );
+            }
+        }
+        graph
+    }
+
+    /// Keep only nodes within `depth` edges, in either direction, of nodes labelled `label`
+    pub fn focus(&self, label: &str, depth: usize) -> Self {
+        let mut adjacent: HashMap<&str, Vec<&str>> = HashMap::new();
+        for edge in &self.edges {
+            adjacent.entry(&edge.from).or_default().push(&edge.to);
+            adjacent.entry(&edge.to).or_default().push(&edge.from);
+        }
+
+        let mut kept: HashSet<&str> = HashSet::new();
+        let mut queue: VecDeque<(&str, usize)> = self
+            .nodes
+            .iter()
+            .filter(|node| node.label == label)
+            .map(|node| (node.id.as_str(), 0))
+            .collect();
+        while let Some((id, distance)) = queue.pop_front() {
+            if !kept.insert(id) || distance == depth {
+                continue;
+            }
+            for next in adjacent.get(id).into_iter().flatten() {
+                queue.push_back((next, distance + 1));
+            }
+        }
+
+        Self {
+            name: self.name.clone(),
+            nodes: self
+                .nodes
+                .iter()
+                .filter(|node| kept.contains(node.id.as_str()))
+                .cloned()
+                .collect(),
+            edges: self
+                .edges
+                .iter()
+                .filter(|edge| kept.contains(edge.from.as_str()) && kept.contains(edge.to.as_str()))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::Graphml => self.to_graphml(),
+            GraphFormat::Mermaid => self.to_mermaid(),
+        }
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "digraph {} {{", dot_quote(&self.name));
+        out.push_str("  rankdir=LR;\n  node [shape=box];\n");
+
+        for (index, (group, nodes)) in self.grouped_nodes().into_iter().enumerate() {
+            let indent = match group {
+                Some(group) => {
+                    let _ = writeln!(out, "  subgraph \"cluster_{index}\" {{");
+                    let _ = writeln!(out, "    label={};", dot_quote(group));
+                    "    "
+                }
+                None => "  ",
+            };
+            for node in nodes {
+                let _ = writeln!(out, "{indent}{} [label={}];", dot_quote(&node.id), dot_quote(&node.label));
+            }
+            if group.is_some() {
+                out.push_str("  }\n");
+            }
+        }
+
+        for edge in &self.edges {
+            let _ = write!(out, "  {} -> {}", dot_quote(&edge.from), dot_quote(&edge.to));
+            if let Some(label) = &edge.label {
+                let _ = write!(out, " [label={}]", dot_quote(label));
+            }
+            out.push_str(";\n");
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    pub fn to_graphml(&self) -> String {
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        out.push_str("  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n");
+        out.push_str("  <key id=\"group\" for=\"node\" attr.name=\"group\" attr.type=\"string\"/>\n");
+        out.push_str("  <key id=\"relation\" for=\"edge\" attr.name=\"label\" attr.type=\"string\"/>\n");
+        let _ = writeln!(out, "  <graph id=\"{}\" edgedefault=\"directed\">", xml_escape(&self.name));
+
+        for node in &self.nodes {
+            let _ = writeln!(out, "    <node id=\"{}\">", xml_escape(&node.id));
+            let _ = writeln!(out, "      <data key=\"label\">{}</data>", xml_escape(&node.label));
+            if let Some(group) = &node.group {
+                let _ = writeln!(out, "      <data key=\"group\">{}</data>", xml_escape(group));
+            }
+            out.push_str("    </node>\n");
+        }
+        for (index, edge) in self.edges.iter().enumerate() {
+            let _ = write!(
+                out,
+                "    <edge id=\"e{index}\" source=\"{}\" target=\"{}\"",
+                xml_escape(&edge.from),
+                xml_escape(&edge.to)
+            );
+            match &edge.label {
+                Some(label) => {
+                    let _ = writeln!(out, ">\n      <data key=\"relation\">{}</data>\n    </edge>", xml_escape(label));
+                }
+                None => out.push_str("/>\n"),
+            }
+        }
+
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+
+    /// Mermaid flowchart; node ids are renumbered since Mermaid ids must be plain words
+    pub fn to_mermaid(&self) -> String {
+        let ids: HashMap<&str, String> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (node.id.as_str(), format!("n{index}")))
+            .collect();
+
+        let mut out = String::from("flowchart LR\n");
+        for (index, (group, nodes)) in self.grouped_nodes().into_iter().enumerate() {
+            let indent = match group {
+                Some(group) => {
+                    let _ = writeln!(out, "  subgraph g{index}[\"{}\"]", mermaid_escape(group));
+                    "    "
+                }
+                None => "  ",
+            };
+            for node in nodes {
+                let _ = writeln!(out, "{indent}{}[\"{}\"]", ids[node.id.as_str()], mermaid_escape(&node.label));
+            }
+            if group.is_some() {
+                out.push_str("  end\n");
+            }
+        }
+
+        for edge in &self.edges {
+            let (Some(from), Some(to)) = (ids.get(edge.from.as_str()), ids.get(edge.to.as_str())) else {
+                continue;
+            };
+            match &edge.label {
+                Some(label) => {
+                    let _ = writeln!(out, "  {from} -->|\"{}\"| {to}", mermaid_escape(label));
+                }
+                None => {
+                    let _ = writeln!(out, "  {from} --> {to}");
+                }
+            }
+        }
+        out
+    }
+
+    fn add_function(&mut self, definition: &SymbolDefinition) {
+        self.add_node(
+            function_id(definition),
+            definition.name.clone(),
+            Some(definition.file.display().to_string()),
+        );
+    }
+
+    /// Stable order, since the call graph is built from hash maps
+    fn sort(&mut self) {
+        self.nodes.sort_by(|a, b| a.id.cmp(&b.id));
+        self.edges
+            .sort_by(|a, b| a.from.cmp(&b.from).then_with(|| a.to.cmp(&b.to)));
+    }
+
+    /// Nodes by group, ungrouped nodes first, in node order within each group
+    fn grouped_nodes(&self) -> Vec<(Option<&str>, Vec<&GraphNode>)> {
+        let mut ungrouped = Vec::new();
+        let mut groups: BTreeMap<&str, Vec<&GraphNode>> = BTreeMap::new();
+        for node in &self.nodes {
+            match &node.group {
+                Some(group) => groups.entry(group).or_default().push(node),
+                None => ungrouped.push(node),
+            }
+        }
+
+        let mut grouped = Vec::new();
+        if !ungrouped.is_empty() {
+            grouped.push((None, ungrouped));
+        }
+        grouped.extend(groups.into_iter().map(|(group, nodes)| (Some(group), nodes)));
+        grouped
+    }
+}
+
+fn function_id(definition: &SymbolDefinition) -> String {
+    format!("{}:{}:{}", definition.file.display(), definition.line + 1, definition.name)
+}
+
+fn diagnostic_label(diagnostic: &crate::core::types::Diagnostic) -> String {
+    let message = diagnostic.message.lines().next().unwrap_or_default();
+    let message = if message.chars().count() > MAX_MESSAGE_LABEL {
+        format!("{}...", message.chars().take(MAX_MESSAGE_LABEL).collect::<String>())
+    } else {
+        message.to_string()
+    };
+    format!(
+        "{}:{} {:?}: {}",
+        diagnostic.file,
+        diagnostic.range.start.line + 1,
+        diagnostic.severity,
+        message
+    )
+}
+
+fn dot_quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn sample_index() -> SymbolIndex {
+        let mut index = SymbolIndex::new("/work");
+        index.add_file(
+            Path::new("src/lib.rs"),
+            "pub fn run() {\n    let config = load();\n    apply(config);\n}\n\nfn load() -> u32 { 1 }\n\nfn apply(x: u32) { load(); }\n\n#[cfg(test)]\nmod tests {\n    fn it_runs() { run(); }\n}\n",
+        );
+        index
+    }
+
+    #[test]
+    fn test_call_graph_from_symbol_index() {
+        let graph = RelationGraph::call_graph(&sample_index(), false);
+        let calls: Vec<_> = graph
+            .edges
+            .iter()
+            .map(|edge| (edge.from.as_str(), edge.to.as_str()))
+            .collect();
+        assert_eq!(
+            calls,
+            vec![
+                ("src/lib.rs:1:run", "src/lib.rs:6:load"),
+                ("src/lib.rs:1:run", "src/lib.rs:8:apply"),
+                ("src/lib.rs:8:apply", "src/lib.rs:6:load"),
+            ]
+        );
+
+        let with_tests = RelationGraph::call_graph(&sample_index(), true);
+        assert!(with_tests.edges.iter().any(|edge| edge.from.ends_with(":it_runs")));
+
+        let focused = with_tests.focus("it_runs", 1);
+        assert_eq!(focused.nodes.len(), 2);
+        assert_eq!(focused.edges.len(), 1);
+    }
+
+    #[test]
+    fn test_renderers_escape_and_cluster() {
+        let mut graph = RelationGraph::new("repo-deps");
+        graph.add_node("web", "web \"app\"", Some("typescript".to_string()));
+        graph.add_node("api", "api", None);
+        graph.add_edge("web", "api", Some("dependency".to_string()));
+
+        let dot = graph.to_dot();
+        assert!(dot.contains("\"web\" [label=\"web \\\"app\\\"\"];"));
+        assert!(dot.contains("subgraph \"cluster_1\" {\n    label=\"typescript\";"));
+        assert!(dot.contains("\"web\" -> \"api\" [label=\"dependency\"];"));
+
+        let graphml = graph.to_graphml();
+        assert!(graphml.contains("<data key=\"label\">web &quot;app&quot;</data>"));
+        assert!(graphml.contains("<edge id=\"e0\" source=\"web\" target=\"api\">"));
+
+        let mermaid = graph.to_mermaid();
+        assert_eq!(
+            mermaid,
+            "flowchart LR\n  n1[\"api\"]\n  subgraph g1[\"typescript\"]\n    n0[\"web #quot;app#quot;\"]\n  end\n  n0 -->|\"dependency\"| n1\n"
+        );
+    }
+}
@@ -0,0 +1,619 @@
+//! Sending health alerts to external services
+//!
+//! Each [`AlertChannel`] turns an alert into the payload its service expects
+//! and posts it: [`WebhookChannel`] to any HTTP endpoint, [`SlackChannel`]
+//! and [`DiscordChannel`] to incoming webhooks. Channels are configured as
+//! [`AlertChannelConfig`]s in [`MonitoringConfig`](super::super::MonitoringConfig);
+//! [`AlertDispatcher`] sends new alerts to every channel whose minimum
+//! severity they reach, retrying with backoff and queueing alerts that
+//! cannot be delivered while offline.
+
+use crate::core::health_dashboard::types::{AlertSeverity, HealthAlert};
+use crate::core::net::{deliver, post_json, Delivery, NetError, OfflineQueue, QueuedOperation, RetryPolicy};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Text of Slack and Discord messages unless a channel sets its own
+pub const DEFAULT_TEMPLATE: &str = "[{severity}] {component}: {message}";
+
+/// How long a single POST may take
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Kind of queued operations; the channel name follows
+const QUEUE_KIND_PREFIX: &str = "health-alert:";
+
+/// How often queued alerts are retried
+const QUEUE_DRAIN_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A destination for health alerts
+#[async_trait]
+pub trait AlertChannel: Send + Sync {
+    /// Name the channel is configured and queued under
+    fn name(&self) -> &str;
+
+    /// Body to send for `alert`
+    fn payload(&self, alert: &HealthAlert) -> Result<Value, NetError>;
+
+    /// Send a body built by [`payload`](Self::payload), once
+    async fn send(&self, payload: &Value) -> Result<(), NetError>;
+}
+
+/// Message text with `{id}`, `{severity}`, `{component}`, `{message}` and
+/// `{timestamp}` replaced by the alert's values
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AlertTemplate(pub String);
+
+impl Default for AlertTemplate {
+    fn default() -> Self {
+        Self(DEFAULT_TEMPLATE.to_string())
+    }
+}
+
+impl AlertTemplate {
+    pub fn new(template: impl Into<String>) -> Self {
+        Self(template.into())
+    }
+
+    /// The template with the alert's values in place
+    pub fn render(&self, alert: &HealthAlert) -> String {
+        self.render_with(alert, |value| value.to_string())
+    }
+
+    /// The template with the alert's values escaped for a JSON string
+    ///
+    /// For JSON body templates, where placeholders sit inside string
+    /// literals: `{"text": "{message}"}`.
+    pub fn render_json(&self, alert: &HealthAlert) -> Result<Value, NetError> {
+        let body = self.render_with(alert, |value| {
+            let quoted = Value::String(value.to_string()).to_string();
+            quoted[1..quoted.len() - 1].to_string()
+        });
+        serde_json::from_str(&body).map_err(|e| NetError::Permanent(format!("alert body template is not JSON: {e}")))
+    }
+
+    fn render_with(&self, alert: &HealthAlert, escape: impl Fn(&str) -> String) -> String {
+        let timestamp = chrono::DateTime::<chrono::Utc>::from(alert.timestamp).to_rfc3339();
+        let severity = severity_name(&alert.severity);
+        [
+            ("{id}", alert.id.as_str()),
+            ("{severity}", severity),
+            ("{component}", alert.component.as_str()),
+            ("{message}", alert.message.as_str()),
+            ("{timestamp}", timestamp.as_str()),
+        ]
+        .iter()
+        .fold(self.0.clone(), |text, (placeholder, value)| text.replace(placeholder, &escape(value)))
+    }
+}
+
+fn severity_name(severity: &AlertSeverity) -> &'static str {
+    match severity {
+        AlertSeverity::Info => "info",
+        AlertSeverity::Warning => "warning",
+        AlertSeverity::Error => "error",
+        AlertSeverity::Critical => "critical",
+    }
+}
+
+/// Generic HTTP POST of a JSON body
+///
+/// Without a body template the alert itself is sent, with a `text` field
+/// rendered from the default template.
+pub struct WebhookChannel {
+    name: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    body_template: Option<AlertTemplate>,
+}
+
+impl WebhookChannel {
+    pub fn new(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            url: url.into(),
+            headers: Vec::new(),
+            body_template: None,
+        }
+    }
+
+    /// Send this header with every request, e.g. for authentication
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Send this JSON template instead of the alert
+    pub fn with_body_template(mut self, template: AlertTemplate) -> Self {
+        self.body_template = Some(template);
+        self
+    }
+}
+
+#[async_trait]
+impl AlertChannel for WebhookChannel {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn payload(&self, alert: &HealthAlert) -> Result<Value, NetError> {
+        if let Some(template) = &self.body_template {
+            return template.render_json(alert);
+        }
+        let mut body = serde_json::to_value(alert).map_err(|e| NetError::Permanent(e.to_string()))?;
+        body["text"] = Value::String(AlertTemplate::default().render(alert));
+        Ok(body)
+    }
+
+    async fn send(&self, payload: &Value) -> Result<(), NetError> {
+        post_json(&self.url, &self.headers, payload, REQUEST_TIMEOUT).await
+    }
+}
+
+/// Slack incoming webhook; the message is colored by severity
+pub struct SlackChannel {
+    name: String,
+    webhook_url: String,
+    template: AlertTemplate,
+    /// Overrides the webhook's default channel
+    channel: Option<String>,
+}
+
+impl SlackChannel {
+    pub fn new(name: impl Into<String>, webhook_url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            webhook_url: webhook_url.into(),
+            template: AlertTemplate::default(),
+            channel: None,
+        }
+    }
+
+    pub fn with_template(mut self, template: AlertTemplate) -> Self {
+        self.template = template;
+        self
+    }
+
+    pub fn with_channel(mut self, channel: impl Into<String>) -> Self {
+        self.channel = Some(channel.into());
+        self
+    }
+}
+
+#[async_trait]
+impl AlertChannel for SlackChannel {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn payload(&self, alert: &HealthAlert) -> Result<Value, NetError> {
+        let color = match alert.severity {
+            AlertSeverity::Info => "#439fe0",
+            AlertSeverity::Warning => "warning",
+            AlertSeverity::Error | AlertSeverity::Critical => "danger",
+        };
+        let text = self.template.render(alert);
+        let mut body = json!({
+            "text": text,
+            "attachments": [{ "color": color, "fallback": text, "footer": alert.id }],
+        });
+        if let Some(channel) = &self.channel {
+            body["channel"] = Value::String(channel.clone());
+        }
+        Ok(body)
+    }
+
+    async fn send(&self, payload: &Value) -> Result<(), NetError> {
+        post_json(&self.webhook_url, &[], payload, REQUEST_TIMEOUT).await
+    }
+}
+
+/// Discord webhook; the alert is sent as an embed colored by severity
+pub struct DiscordChannel {
+    name: String,
+    webhook_url: String,
+    template: AlertTemplate,
+    /// Overrides the webhook's default name
+    username: Option<String>,
+}
+
+impl DiscordChannel {
+    pub fn new(name: impl Into<String>, webhook_url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            webhook_url: webhook_url.into(),
+            template: AlertTemplate::default(),
+            username: None,
+        }
+    }
+
+    pub fn with_template(mut self, template: AlertTemplate) -> Self {
+        self.template = template;
+        self
+    }
+
+    pub fn with_username(mut self, username: impl Into<String>) -> Self {
+        self.username = Some(username.into());
+        self
+    }
+}
+
+#[async_trait]
+impl AlertChannel for DiscordChannel {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn payload(&self, alert: &HealthAlert) -> Result<Value, NetError> {
+        let color = match alert.severity {
+            AlertSeverity::Info => 0x3498db,
+            AlertSeverity::Warning => 0xf1c40f,
+            AlertSeverity::Error => 0xe67e22,
+            AlertSeverity::Critical => 0xe74c3c,
+        };
+        let mut body = json!({
+            // Discord rejects content over 2000 characters
+            "content": self.template.render(alert).chars().take(2000).collect::<String>(),
+            "embeds": [{
+                "title": alert.component,
+                "description": alert.message,
+                "color": color,
+                "timestamp": chrono::DateTime::<chrono::Utc>::from(alert.timestamp).to_rfc3339(),
+            }],
+        });
+        if let Some(username) = &self.username {
+            body["username"] = Value::String(username.clone());
+        }
+        Ok(body)
+    }
+
+    async fn send(&self, payload: &Value) -> Result<(), NetError> {
+        post_json(&self.webhook_url, &[], payload, REQUEST_TIMEOUT).await
+    }
+}
+
+/// Where and when to send alerts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertChannelConfig {
+    /// Unique name of the channel; its kind when omitted
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub kind: AlertChannelKind,
+    /// Alerts below this severity are not sent
+    #[serde(default = "default_min_severity")]
+    pub min_severity: AlertSeverity,
+}
+
+fn default_min_severity() -> AlertSeverity {
+    AlertSeverity::Warning
+}
+
+/// Service an [`AlertChannelConfig`] sends to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum AlertChannelKind {
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: Vec<(String, String)>,
+        /// JSON body with placeholders, see [`AlertTemplate::render_json`]
+        #[serde(default)]
+        body_template: Option<String>,
+    },
+    Slack {
+        webhook_url: String,
+        #[serde(default)]
+        template: Option<String>,
+        #[serde(default)]
+        channel: Option<String>,
+    },
+    Discord {
+        webhook_url: String,
+        #[serde(default)]
+        template: Option<String>,
+        #[serde(default)]
+        username: Option<String>,
+    },
+}
+
+impl AlertChannelConfig {
+    pub fn new(kind: AlertChannelKind) -> Self {
+        Self {
+            name: None,
+            kind,
+            min_severity: default_min_severity(),
+        }
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn with_min_severity(mut self, severity: AlertSeverity) -> Self {
+        self.min_severity = severity;
+        self
+    }
+
+    /// Configured name, or the kind of channel
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or(match self.kind {
+            AlertChannelKind::Webhook { .. } => "webhook",
+            AlertChannelKind::Slack { .. } => "slack",
+            AlertChannelKind::Discord { .. } => "discord",
+        })
+    }
+
+    pub fn build(&self) -> Arc<dyn AlertChannel> {
+        let name = self.name().to_string();
+        match &self.kind {
+            AlertChannelKind::Webhook {
+                url,
+                headers,
+                body_template,
+            } => {
+                let mut channel = WebhookChannel::new(name, url.clone());
+                for (header, value) in headers {
+                    channel = channel.with_header(header.clone(), value.clone());
+                }
+                if let Some(template) = body_template {
+                    channel = channel.with_body_template(AlertTemplate::new(template.clone()));
+                }
+                Arc::new(channel)
+            }
+            AlertChannelKind::Slack {
+                webhook_url,
+                template,
+                channel,
+            } => {
+                let mut slack = SlackChannel::new(name, webhook_url.clone());
+                if let Some(template) = template {
+                    slack = slack.with_template(AlertTemplate::new(template.clone()));
+                }
+                if let Some(channel) = channel {
+                    slack = slack.with_channel(channel.clone());
+                }
+                Arc::new(slack)
+            }
+            AlertChannelKind::Discord {
+                webhook_url,
+                template,
+                username,
+            } => {
+                let mut discord = DiscordChannel::new(name, webhook_url.clone());
+                if let Some(template) = template {
+                    discord = discord.with_template(AlertTemplate::new(template.clone()));
+                }
+                if let Some(username) = username {
+                    discord = discord.with_username(username.clone());
+                }
+                Arc::new(discord)
+            }
+        }
+    }
+}
+
+/// Sends alerts to every configured channel
+pub struct AlertDispatcher {
+    channels: Vec<(Arc<dyn AlertChannel>, AlertSeverity)>,
+    policy: RetryPolicy,
+    queue: OfflineQueue,
+}
+
+impl AlertDispatcher {
+    /// Dispatcher for `configs`, queueing undeliverable alerts under the default offline queue
+    ///
+    /// Alerts get a queue of their own so draining it never touches other
+    /// kinds of operations.
+    pub fn new(configs: &[AlertChannelConfig], policy: RetryPolicy) -> Self {
+        let channels = configs
+            .iter()
+            .map(|config| (config.build(), config.min_severity.clone()))
+            .collect();
+        Self {
+            channels,
+            policy,
+            queue: OfflineQueue::new(OfflineQueue::default_dir().join("health-alerts")),
+        }
+    }
+
+    pub fn with_channel(mut self, channel: Arc<dyn AlertChannel>, min_severity: AlertSeverity) -> Self {
+        self.channels.push((channel, min_severity));
+        self
+    }
+
+    pub fn with_queue(mut self, queue: OfflineQueue) -> Self {
+        self.queue = queue;
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty()
+    }
+
+    /// Send each unresolved alert to the channels whose minimum severity it reaches
+    ///
+    /// Failures are logged rather than returned so one unreachable service
+    /// doesn't hold up the others. Returns how many messages were delivered
+    /// right away.
+    pub async fn dispatch(&self, alerts: &[HealthAlert]) -> usize {
+        let mut delivered = 0;
+        for alert in alerts.iter().filter(|alert| !alert.resolved) {
+            for (channel, min_severity) in &self.channels {
+                if alert.severity < *min_severity {
+                    continue;
+                }
+                let payload = match channel.payload(alert) {
+                    Ok(payload) => payload,
+                    Err(e) => {
+                        warn!("Alert {} not sent to {}: {}", alert.id, channel.name(), e);
+                        continue;
+                    }
+                };
+                let kind = format!("{QUEUE_KIND_PREFIX}{}", channel.name());
+                let send = |payload: &Value| {
+                    let (channel, payload) = (channel.clone(), payload.clone());
+                    async move { channel.send(&payload).await }
+                };
+                match deliver(&self.policy, &self.queue, &kind, payload, send).await {
+                    Ok(Delivery::Delivered) => delivered += 1,
+                    Ok(Delivery::Queued(id)) => debug!("Alert {} for {} queued as {}", alert.id, channel.name(), id),
+                    Err(e) => warn!("Alert {} not sent to {}: {}", alert.id, channel.name(), e),
+                }
+            }
+        }
+        delivered
+    }
+
+    /// Send a queued alert to the channel it was meant for
+    ///
+    /// For draining the offline queue; operations of other kinds and of
+    /// channels no longer configured fail permanently.
+    pub async fn resend(&self, operation: &QueuedOperation) -> Result<(), NetError> {
+        let name = operation
+            .kind
+            .strip_prefix(QUEUE_KIND_PREFIX)
+            .ok_or_else(|| NetError::Permanent(format!("{} is not a health alert", operation.kind)))?;
+        let (channel, _) = self
+            .channels
+            .iter()
+            .find(|(channel, _)| channel.name() == name)
+            .ok_or_else(|| NetError::Permanent(format!("no alert channel named {name}")))?;
+        channel.send(&operation.payload).await
+    }
+
+    /// Retry queued alerts in the background until the process exits
+    pub fn spawn_drainer(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let queue = Arc::new(OfflineQueue::new(self.queue.dir()));
+        let policy = self.policy.clone();
+        queue.spawn_drainer(QUEUE_DRAIN_INTERVAL, policy, move |operation| {
+            let dispatcher = self.clone();
+            let operation = operation.clone();
+            async move { dispatcher.resend(&operation).await }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use std::time::SystemTime;
+
+    fn alert(severity: AlertSeverity) -> HealthAlert {
+        HealthAlert {
+            id: "cpu-critical-processor".to_string(),
+            severity,
+            component: "processor".to_string(),
+            message: "Critical CPU usage: \"97.0%\"".to_string(),
+            timestamp: SystemTime::UNIX_EPOCH,
+            resolved: false,
+            resolution_time: None,
+        }
+    }
+
+    /// Records what it is asked to send, failing the first `failures` times
+    struct RecordingChannel {
+        sent: Mutex<Vec<Value>>,
+        failures: Mutex<u32>,
+    }
+
+    #[async_trait]
+    impl AlertChannel for RecordingChannel {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        fn payload(&self, alert: &HealthAlert) -> Result<Value, NetError> {
+            Ok(json!({ "id": alert.id }))
+        }
+
+        async fn send(&self, payload: &Value) -> Result<(), NetError> {
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                return Err(NetError::from_status(503, "unavailable"));
+            }
+            self.sent.lock().unwrap().push(payload.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_templated_payloads() {
+        let alert = alert(AlertSeverity::Critical);
+
+        let text = AlertTemplate::new("{severity} on {component} at {timestamp}").render(&alert);
+        assert_eq!(text, "critical on processor at 1970-01-01T00:00:00+00:00");
+
+        let webhook = WebhookChannel::new("ops", "http://localhost/hook")
+            .with_body_template(AlertTemplate::new(r#"{"summary": "{message}", "source": "lspbridge/{id}"}"#));
+        let body = webhook.payload(&alert).unwrap();
+        assert_eq!(body["summary"], "Critical CPU usage: \"97.0%\"");
+        assert_eq!(body["source"], "lspbridge/cpu-critical-processor");
+
+        let broken = WebhookChannel::new("ops", "http://localhost/hook").with_body_template(AlertTemplate::new("{message}"));
+        assert!(broken.payload(&alert).is_err());
+
+        let slack = SlackChannel::new("slack", "http://localhost/slack").with_channel("#alerts");
+        let body = slack.payload(&alert).unwrap();
+        assert_eq!(body["text"], "[critical] processor: Critical CPU usage: \"97.0%\"");
+        assert_eq!(body["attachments"][0]["color"], "danger");
+        assert_eq!(body["channel"], "#alerts");
+
+        let discord = DiscordChannel::new("discord", "http://localhost/discord");
+        let body = discord.payload(&alert).unwrap();
+        assert_eq!(body["embeds"][0]["title"], "processor");
+        assert_eq!(body["embeds"][0]["color"], 0xe74c3c);
+    }
+
+    #[test]
+    fn test_channel_config_from_toml() {
+        let config: AlertChannelConfig = toml::from_str(
+            r##"
+            kind = "slack"
+            webhook_url = "https://hooks.slack.com/services/T000/B000/XXXX"
+            channel = "#oncall"
+            min_severity = "Error"
+            "##,
+        )
+        .unwrap();
+        assert_eq!(config.name(), "slack");
+        assert_eq!(config.min_severity, AlertSeverity::Error);
+        assert_eq!(config.build().name(), "slack");
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_retries_and_filters_by_severity() {
+        let dir = tempfile::tempdir().unwrap();
+        let channel = Arc::new(RecordingChannel {
+            sent: Mutex::new(Vec::new()),
+            failures: Mutex::new(1),
+        });
+        let policy = RetryPolicy {
+            max_attempts: 2,
+            initial_delay: Duration::from_millis(1),
+            ..RetryPolicy::default()
+        };
+        let dispatcher = AlertDispatcher::new(&[], policy)
+            .with_queue(OfflineQueue::new(dir.path()))
+            .with_channel(channel.clone(), AlertSeverity::Error);
+
+        let resolved = HealthAlert {
+            resolved: true,
+            ..alert(AlertSeverity::Critical)
+        };
+        let delivered = dispatcher
+            .dispatch(&[alert(AlertSeverity::Warning), alert(AlertSeverity::Critical), resolved])
+            .await;
+
+        assert_eq!(delivered, 1);
+        assert_eq!(*channel.sent.lock().unwrap(), vec![json!({ "id": "cpu-critical-processor" })]);
+        assert!(OfflineQueue::new(dir.path()).is_empty().unwrap());
+    }
+}
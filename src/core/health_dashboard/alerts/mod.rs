@@ -1,5 +1,10 @@
+pub mod channels;
 pub mod rules;
 pub mod notifier;
 
+pub use channels::{
+    AlertChannel, AlertChannelConfig, AlertChannelKind, AlertDispatcher, AlertTemplate, DiscordChannel, SlackChannel,
+    WebhookChannel,
+};
 pub use rules::AlertRulesEngine;
-pub use notifier::AlertNotifier;
\ No newline at end of file
+pub use notifier::AlertNotifier;
@@ -33,13 +33,4 @@ impl AlertNotifier {
             }
         }
     }
-
-    /// In a production system, this could send notifications via:
-    /// - Email
-    /// - Slack/Discord
-    /// - PagerDuty
-    /// - Custom webhooks
-    pub async fn send_external_notifications(_alerts: &[HealthAlert]) {
-        // Placeholder for external notification integrations
-    }
 }
\ No newline at end of file
@@ -1,6 +1,10 @@
+use crate::core::health_dashboard::metrics::plugins::plugin_component;
+use crate::core::health_dashboard::metrics::subprojects::subproject_component;
 use crate::core::health_dashboard::types::{
-    AlertSeverity, AlertThresholds, ComponentHealthMap, HealthAlert,
+    AlertSeverity, AlertThresholds, ComponentHealthMap, HealthAlert, PluginHealth, SubprojectHealth,
+    TrendDirection,
 };
+use std::collections::BTreeMap;
 use std::time::SystemTime;
 
 pub struct AlertRulesEngine {
@@ -119,16 +123,97 @@ impl AlertRulesEngine {
         alerts
     }
 
+    /// Check each subproject against the thresholds and flag degrading trends
+    ///
+    /// Alerts are raised against the `subproject:<name>` component.
+    pub fn check_subprojects(&self, subprojects: &BTreeMap<String, SubprojectHealth>) -> Vec<HealthAlert> {
+        let components: ComponentHealthMap = subprojects
+            .iter()
+            .map(|(name, subproject)| (subproject_component(name), subproject.health.clone()))
+            .collect();
+        let mut alerts = self.check_components(&components);
+
+        for (name, subproject) in subprojects {
+            if subproject.trend == TrendDirection::Degrading {
+                let component = subproject_component(name);
+                alerts.push(HealthAlert {
+                    id: format!("trend-degrading-{component}"),
+                    severity: AlertSeverity::Warning,
+                    component,
+                    message: format!("Health degrading: score {:.1}", subproject.health.score),
+                    timestamp: SystemTime::now(),
+                    resolved: false,
+                    resolution_time: None,
+                });
+            }
+        }
+
+        alerts
+    }
+
+    /// Check plugin metrics against the thresholds each plugin reports
+    ///
+    /// Alerts are raised against the `plugin:<name>` component; a failed
+    /// collection raises an error alert.
+    pub fn check_plugins(&self, plugins: &BTreeMap<String, PluginHealth>) -> Vec<HealthAlert> {
+        let mut alerts = Vec::new();
+
+        for (name, plugin) in plugins {
+            let component = plugin_component(name);
+
+            if let Some(error) = &plugin.error {
+                alerts.push(HealthAlert {
+                    id: format!("plugin-failed-{component}"),
+                    severity: AlertSeverity::Error,
+                    component: component.clone(),
+                    message: format!("Metric plugin failed: {error}"),
+                    timestamp: SystemTime::now(),
+                    resolved: false,
+                    resolution_time: None,
+                });
+            }
+
+            for metric in &plugin.metrics {
+                let Some(severity) = metric.breach() else { continue };
+                let (level, threshold) = match severity {
+                    AlertSeverity::Critical => ("critical", metric.critical),
+                    _ => ("warning", metric.warning),
+                };
+                let unit = metric.unit.as_deref().unwrap_or_default();
+                alerts.push(HealthAlert {
+                    id: format!("plugin-{level}-{component}-{}", metric.name),
+                    severity,
+                    component: component.clone(),
+                    message: format!(
+                        "{} is {}{unit}, beyond its {level} threshold of {}{unit}",
+                        metric.name,
+                        metric.value,
+                        threshold.unwrap_or_default()
+                    ),
+                    timestamp: SystemTime::now(),
+                    resolved: false,
+                    resolution_time: None,
+                });
+            }
+        }
+
+        alerts
+    }
+
     /// Merge new alerts with existing ones, avoiding duplicates
+    ///
+    /// Returns the alerts that were not already active.
     pub fn merge_alerts(
         existing: &mut Vec<HealthAlert>,
         new_alerts: Vec<HealthAlert>,
         max_alerts: usize,
-    ) {
+    ) -> Vec<HealthAlert> {
+        let mut added = Vec::new();
         for new_alert in new_alerts {
             // Check if this alert already exists
             let exists = existing.iter().any(|a| a.id == new_alert.id && !a.resolved);
             if !exists {
+                added.push(new_alert.clone());
                 existing.push(new_alert);
             }
         }
@@ -138,5 +223,6 @@ impl AlertRulesEngine {
             let excess = existing.len() - max_alerts;
             existing.drain(0..excess);
         }
+        added
     }
 }
\ No newline at end of file
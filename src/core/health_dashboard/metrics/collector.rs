@@ -2,7 +2,8 @@ use anyhow::Result;
 use std::time::{Duration, SystemTime};
 
 use crate::core::{
-    DynamicConfigManager, ErrorRecoverySystem, GitIntegration, SimpleEnhancedProcessor,
+    CircuitState, DynamicConfigManager, ErrorRecoverySystem, GitIntegration,
+    SimpleEnhancedProcessor,
 };
 use crate::core::health_dashboard::types::{ComponentHealth, ComponentMetrics, ComponentStatus};
 
@@ -160,6 +161,32 @@ impl MetricsCollector {
             issues.push("Circuit breaker is open".to_string());
         }
 
+        // Check per-subsystem breakers
+        let mut custom_metrics = std::collections::HashMap::new();
+        for breaker in recovery.breaker_status().await {
+            let open = match breaker.state {
+                CircuitState::Open => {
+                    score -= 25.0;
+                    issues.push(format!(
+                        "Circuit breaker open for {} after {} failures",
+                        breaker.subsystem, breaker.failure_count
+                    ));
+                    1.0
+                }
+                CircuitState::HalfOpen => {
+                    issues.push(format!("Circuit breaker for {} is recovering", breaker.subsystem));
+                    0.5
+                }
+                CircuitState::Closed => 0.0,
+            };
+            custom_metrics.insert(format!("breaker.{}.open", breaker.subsystem), open);
+            custom_metrics.insert(
+                format!("breaker.{}.failures", breaker.subsystem),
+                breaker.failure_count as f64,
+            );
+        }
+        let score = f64::max(score, 0.0);
+
         let status = if score >= 90.0 {
             ComponentStatus::Online
         } else if score >= 50.0 {
@@ -178,7 +205,7 @@ impl MetricsCollector {
                 error_rate: stats.recent_error_rate,
                 response_time: Duration::from_millis(100),
                 throughput: 75.0,
-                custom_metrics: Default::default(),
+                custom_metrics,
             },
             last_check: SystemTime::now(),
             issues,
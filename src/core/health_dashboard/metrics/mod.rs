@@ -1,5 +1,9 @@
 pub mod collector;
 pub mod aggregator;
+pub mod subprojects;
+pub mod plugins;
 
 pub use collector::MetricsCollector;
-pub use aggregator::MetricsAggregator;
\ No newline at end of file
+pub use aggregator::MetricsAggregator;
+pub use subprojects::SubprojectCollector;
+pub use plugins::{MetricPlugin, PluginCollector, ScriptMetricPlugin, ScriptPluginConfig};
\ No newline at end of file
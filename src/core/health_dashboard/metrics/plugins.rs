@@ -0,0 +1,318 @@
+//! Metric plugins
+//!
+//! Plugins contribute named metrics such as code coverage or bundle size.
+//! They are collected on every monitor update and each becomes a
+//! `plugin:<name>` component, so plugin metrics take part in the overall
+//! status, alerts, recommendations and Prometheus export like built-in ones.
+//!
+//! [`ScriptMetricPlugin`] runs an external program that prints its metrics
+//! as JSON. Other runtimes, such as a WASM host, implement [`MetricPlugin`]
+//! directly.
+//!
+//! Script plugins are declared under `[[metric_plugins]]` in `lspbridge.toml`:
+//!
+//! ```toml
+//! [[metric_plugins]]
+//! name = "coverage"
+//! command = "./scripts/coverage.sh"
+//! args = ["--json"]
+//! timeout_secs = 60
+//! ```
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tokio::process::Command;
+
+use crate::core::health_dashboard::types::{
+    AlertSeverity, ComponentHealth, ComponentMetrics, ComponentStatus, PluginHealth, PluginMetric,
+};
+use crate::core::workspace_trust::{untrusted_error, TrustLevel};
+
+/// Score lost per metric over its warning threshold
+const WARNING_PENALTY: f64 = 15.0;
+
+/// Score lost per metric over its critical threshold
+const CRITICAL_PENALTY: f64 = 40.0;
+
+/// Component name under which a plugin's health and alerts are tracked
+pub fn plugin_component(name: &str) -> String {
+    format!("plugin:{name}")
+}
+
+/// Source of custom dashboard metrics
+#[async_trait]
+pub trait MetricPlugin: Send + Sync {
+    /// Unique name, used in component names and metric labels
+    fn name(&self) -> &str;
+
+    /// Current values of the plugin's metrics
+    async fn collect(&self) -> Result<Vec<PluginMetric>>;
+}
+
+/// Script output: either a list of metrics or an object holding one
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ScriptOutput {
+    Metrics(Vec<PluginMetric>),
+    Wrapped { metrics: Vec<PluginMetric> },
+}
+
+/// Plugin backed by an external program
+///
+/// The program runs in the workspace and prints its metrics to stdout,
+/// either as a list or as `{"metrics": [...]}`:
+///
+/// ```json
+/// {"metrics": [{"name": "coverage", "value": 71.5, "unit": "%", "warning": 80, "critical": 60, "higher_is_better": true}]}
+/// ```
+///
+/// Scripts are project code, so they only run in trusted workspaces.
+pub struct ScriptMetricPlugin {
+    name: String,
+    program: String,
+    args: Vec<String>,
+    workspace: PathBuf,
+    trust: TrustLevel,
+    timeout: Duration,
+}
+
+impl ScriptMetricPlugin {
+    pub fn new(name: impl Into<String>, program: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            program: program.into(),
+            args: Vec::new(),
+            workspace: PathBuf::from("."),
+            trust: TrustLevel::Untrusted,
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    pub fn with_args(mut self, args: Vec<String>) -> Self {
+        self.args = args;
+        self
+    }
+
+    /// Longest a collection may run before it is killed and reported as failed
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Directory the script runs in and its trust level; untrusted workspaces never run it
+    pub fn with_workspace_trust(mut self, workspace: impl Into<PathBuf>, trust: TrustLevel) -> Self {
+        self.workspace = workspace.into();
+        self.trust = trust;
+        self
+    }
+}
+
+/// A script plugin declared under `[[metric_plugins]]` in `lspbridge.toml`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScriptPluginConfig {
+    /// Plugin name, reported as the `plugin:<name>` component
+    pub name: String,
+    /// Program to run, resolved against the workspace when relative
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Longest a collection may run
+    #[serde(default = "default_script_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_script_timeout_secs() -> u64 {
+    30
+}
+
+impl ScriptPluginConfig {
+    /// Plugin running the configured script in `workspace`
+    pub fn plugin(&self, workspace: &Path, trust: TrustLevel) -> ScriptMetricPlugin {
+        ScriptMetricPlugin::new(&self.name, &self.command)
+            .with_args(self.args.clone())
+            .with_timeout(Duration::from_secs(self.timeout_secs))
+            .with_workspace_trust(workspace, trust)
+    }
+}
+
+#[async_trait]
+impl MetricPlugin for ScriptMetricPlugin {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn collect(&self) -> Result<Vec<PluginMetric>> {
+        if self.trust == TrustLevel::Untrusted {
+            return Err(untrusted_error(&self.workspace, &format!("run metric plugin {}", self.name)));
+        }
+
+        let output = Command::new(&self.program)
+            .args(&self.args)
+            .current_dir(&self.workspace)
+            .kill_on_drop(true)
+            .output();
+        let output = tokio::time::timeout(self.timeout, output)
+            .await
+            .map_err(|_| anyhow!("{} timed out after {:?}", self.program, self.timeout))?
+            .with_context(|| format!("Failed to run {}", self.program))?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "{} exited with {}: {}",
+                self.program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        parse_script_output(&output.stdout)
+            .with_context(|| format!("Invalid metrics printed by {}", self.program))
+    }
+}
+
+/// Parse the JSON metrics a script prints
+pub fn parse_script_output(stdout: &[u8]) -> Result<Vec<PluginMetric>> {
+    Ok(match serde_json::from_slice(stdout)? {
+        ScriptOutput::Metrics(metrics) | ScriptOutput::Wrapped { metrics } => metrics,
+    })
+}
+
+pub struct PluginCollector;
+
+impl PluginCollector {
+    /// Collect every plugin, turning each result into plugin health
+    ///
+    /// Plugins run concurrently; a failing plugin is reported offline
+    /// without affecting the others.
+    pub async fn collect(plugins: &[std::sync::Arc<dyn MetricPlugin>]) -> Vec<PluginHealth> {
+        let collections = plugins
+            .iter()
+            .map(|plugin| async move { Self::plugin_health(plugin.name(), plugin.collect().await) });
+        futures::future::join_all(collections).await
+    }
+
+    /// Health of a plugin from the outcome of one collection
+    pub fn plugin_health(name: &str, result: Result<Vec<PluginMetric>>) -> PluginHealth {
+        let (metrics, error) = match result {
+            Ok(metrics) => (metrics, None),
+            Err(e) => (Vec::new(), Some(format!("{e:#}"))),
+        };
+
+        let mut score: f64 = 100.0;
+        let mut issues = Vec::new();
+        for metric in &metrics {
+            let (penalty, threshold) = match metric.breach() {
+                Some(AlertSeverity::Critical) => (CRITICAL_PENALTY, metric.critical),
+                Some(_) => (WARNING_PENALTY, metric.warning),
+                None => continue,
+            };
+            score -= penalty;
+            issues.push(format!(
+                "{} is {}{} (threshold {})",
+                metric.name,
+                metric.value,
+                metric.unit.as_deref().unwrap_or_default(),
+                threshold.unwrap_or_default()
+            ));
+        }
+        if let Some(error) = &error {
+            issues.push(format!("Collection failed: {error}"));
+        }
+        let score = if error.is_some() { 0.0 } else { score.max(0.0) };
+
+        let custom_metrics: HashMap<String, f64> =
+            metrics.iter().map(|metric| (metric.name.clone(), metric.value)).collect();
+
+        let health = ComponentHealth {
+            name: plugin_component(name),
+            status: if error.is_some() || score < 70.0 {
+                ComponentStatus::Offline
+            } else if score < 90.0 {
+                ComponentStatus::Degraded
+            } else {
+                ComponentStatus::Online
+            },
+            score,
+            metrics: ComponentMetrics {
+                cpu_usage: 0.0,
+                memory_usage: 0.0,
+                error_rate: 0.0,
+                // Slow scripts such as coverage runs shouldn't raise response time alerts
+                response_time: Duration::ZERO,
+                throughput: 0.0,
+                custom_metrics,
+            },
+            last_check: SystemTime::now(),
+            issues,
+        };
+
+        PluginHealth {
+            name: name.to_string(),
+            health,
+            metrics,
+            error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_output_and_thresholds() -> Result<()> {
+        let metrics = parse_script_output(
+            br#"{"metrics": [
+                {"name": "coverage", "value": 55.0, "unit": "%", "warning": 80, "critical": 60, "higher_is_better": true},
+                {"name": "bundle_kb", "value": 410, "warning": 400}
+            ]}"#,
+        )?;
+        assert!(matches!(metrics[0].breach(), Some(AlertSeverity::Critical)));
+        assert!(matches!(metrics[1].breach(), Some(AlertSeverity::Warning)));
+        assert!(parse_script_output(br#"[{"name": "files", "value": 3}]"#)?[0].breach().is_none());
+
+        let health = PluginCollector::plugin_health("frontend", Ok(metrics));
+        assert_eq!(health.health.name, "plugin:frontend");
+        assert_eq!(health.health.score, 100.0 - CRITICAL_PENALTY - WARNING_PENALTY);
+        assert_eq!(health.health.metrics.custom_metrics["bundle_kb"], 410.0);
+        assert_eq!(health.health.issues.len(), 2);
+
+        let failed = PluginCollector::plugin_health("frontend", Err(anyhow!("no such file")));
+        assert!(matches!(failed.health.status, ComponentStatus::Offline));
+        assert_eq!(failed.error.as_deref(), Some("no such file"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_untrusted_script_is_not_run() {
+        let plugin = ScriptMetricPlugin::new("coverage", "./coverage.sh")
+            .with_workspace_trust("/untrusted/repo", TrustLevel::Untrusted);
+        let error = plugin.collect().await.unwrap_err();
+        assert!(error.to_string().contains("lspbridge trust /untrusted/repo"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_configured_script_plugin() -> Result<()> {
+        let config: ScriptPluginConfig = toml::from_str(
+            r#"
+            name = "coverage"
+            command = "sh"
+            args = ["-c", "echo '[{\"name\": \"coverage\", \"value\": 71.5}]'"]
+            "#,
+        )?;
+        assert_eq!(config.timeout_secs, 30);
+
+        let workspace = tempfile::tempdir()?;
+        let plugin = config.plugin(workspace.path(), TrustLevel::Trusted);
+        assert_eq!(plugin.name(), "coverage");
+        let metrics = plugin.collect().await?;
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0].value, 71.5);
+        Ok(())
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::core::health_dashboard::types::{
+    ComponentHealth, ComponentMetrics, ComponentStatus, SubprojectHealth, TrendDirection,
+};
+use crate::core::{Diagnostic, DiagnosticSeverity};
+use crate::history::health_score;
+use crate::multi_repo::monorepo::WorkspaceLayout;
+
+/// Files with at least this many errors and warnings count as hot spots
+pub(crate) const HOT_SPOT_THRESHOLD: usize = 5;
+
+/// Score changes smaller than this, in points, count as stable
+const TREND_THRESHOLD: f64 = 2.0;
+
+/// Component name under which a subproject's health and alerts are tracked
+pub fn subproject_component(name: &str) -> String {
+    format!("subproject:{name}")
+}
+
+pub struct SubprojectCollector;
+
+impl SubprojectCollector {
+    /// Score each subproject of a monorepo from the current diagnostics
+    ///
+    /// A diagnostic belongs to the subproject whose directory is the longest
+    /// prefix of its file; diagnostics outside every subproject are ignored.
+    /// `baseline` holds earlier scores by subproject name, used for the trend.
+    pub fn collect(
+        layout: &WorkspaceLayout,
+        diagnostics: &[Diagnostic],
+        baseline: &HashMap<String, f64>,
+    ) -> BTreeMap<String, SubprojectHealth> {
+        let mut roots: Vec<(usize, PathBuf)> = layout
+            .subprojects
+            .iter()
+            .enumerate()
+            .map(|(index, subproject)| (index, layout.root.join(&subproject.relative_path)))
+            .collect();
+        // Longest paths first so nested packages win over their parents
+        roots.sort_by_key(|(_, path)| std::cmp::Reverse(path.components().count()));
+
+        // Per subproject: file -> (errors, warnings)
+        let mut counts: Vec<HashMap<&str, (usize, usize)>> = vec![HashMap::new(); layout.subprojects.len()];
+        for diagnostic in diagnostics {
+            let file = resolve(&layout.root, &diagnostic.file);
+            let Some((index, _)) = roots.iter().find(|(_, root)| file.starts_with(root)) else {
+                continue;
+            };
+            let entry = counts[*index].entry(diagnostic.file.as_str()).or_default();
+            match diagnostic.severity {
+                DiagnosticSeverity::Error => entry.0 += 1,
+                DiagnosticSeverity::Warning => entry.1 += 1,
+                _ => {}
+            }
+        }
+
+        layout
+            .subprojects
+            .iter()
+            .zip(counts)
+            .map(|(subproject, per_file)| {
+                let errors: usize = per_file.values().map(|(e, _)| e).sum();
+                let warnings: usize = per_file.values().map(|(_, w)| w).sum();
+                let hot_spots = per_file.values().filter(|(e, w)| e + w >= HOT_SPOT_THRESHOLD).count();
+                let files = per_file.len();
+                let per_file_divisor = files.max(1) as f64;
+                let score = f64::from(health_score(
+                    errors as f64 / per_file_divisor,
+                    warnings as f64 / per_file_divisor,
+                    hot_spots,
+                )) * 100.0;
+
+                let trend = baseline
+                    .get(&subproject.name)
+                    .map(|previous| trend_between(*previous, score))
+                    .unwrap_or(TrendDirection::Stable);
+
+                let mut issues = Vec::new();
+                if errors > 0 {
+                    issues.push(format!("{errors} error(s) in {files} file(s)"));
+                }
+                if hot_spots > 0 {
+                    issues.push(format!("{hot_spots} hot spot file(s)"));
+                }
+                if trend == TrendDirection::Degrading {
+                    issues.push("Health is degrading".to_string());
+                }
+
+                let mut custom_metrics = HashMap::new();
+                custom_metrics.insert("warnings".to_string(), warnings as f64);
+                custom_metrics.insert("files".to_string(), files as f64);
+                custom_metrics.insert("hot_spots".to_string(), hot_spots as f64);
+
+                let health = ComponentHealth {
+                    name: subproject.name.clone(),
+                    status: if score >= 90.0 {
+                        ComponentStatus::Online
+                    } else if score >= 70.0 {
+                        ComponentStatus::Degraded
+                    } else {
+                        ComponentStatus::Offline
+                    },
+                    score,
+                    metrics: ComponentMetrics {
+                        cpu_usage: 0.0,
+                        memory_usage: 0.0,
+                        error_rate: errors as f64,
+                        response_time: Duration::ZERO,
+                        throughput: 0.0,
+                        custom_metrics,
+                    },
+                    last_check: SystemTime::now(),
+                    issues,
+                };
+
+                (
+                    subproject.name.clone(),
+                    SubprojectHealth {
+                        name: subproject.name.clone(),
+                        path: subproject.relative_path.clone(),
+                        health,
+                        errors,
+                        warnings,
+                        files,
+                        trend,
+                        active_alerts: 0,
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+fn trend_between(previous: f64, current: f64) -> TrendDirection {
+    if current - previous > TREND_THRESHOLD {
+        TrendDirection::Improving
+    } else if previous - current > TREND_THRESHOLD {
+        TrendDirection::Degrading
+    } else {
+        TrendDirection::Stable
+    }
+}
+
+/// Absolute path of a diagnostic's file, which may be a URI or relative to the root
+fn resolve(root: &Path, file: &str) -> PathBuf {
+    let path = Path::new(file.strip_prefix("file://").unwrap_or(file));
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        root.join(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Position, Range};
+    use crate::multi_repo::monorepo::{SubprojectInfo, WorkspaceConfig, WorkspaceType};
+
+    fn subproject(name: &str, path: &str) -> SubprojectInfo {
+        SubprojectInfo {
+            name: name.to_string(),
+            relative_path: PathBuf::from(path),
+            absolute_path: PathBuf::from("/repo").join(path),
+            language: None,
+            build_system: None,
+            internal_deps: Vec::new(),
+            external_deps: Vec::new(),
+            package_config: None,
+        }
+    }
+
+    fn diagnostic(file: &str, severity: DiagnosticSeverity) -> Diagnostic {
+        Diagnostic::new(
+            file.to_string(),
+            Range {
+                start: Position { line: 0, character: 0 },
+                end: Position { line: 0, character: 1 },
+            },
+            severity,
+            "problem".to_string(),
+            "tsc".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_diagnostics_are_attributed_to_the_innermost_subproject() {
+        let layout = WorkspaceLayout {
+            root: PathBuf::from("/repo"),
+            workspace_type: WorkspaceType::NpmWorkspace,
+            subprojects: vec![subproject("app", "packages/app"), subproject("ui", "packages/app/ui")],
+            config: WorkspaceConfig {
+                patterns: Vec::new(),
+                excludes: Vec::new(),
+                dependencies: HashMap::new(),
+                build_config: None,
+            },
+            shared_configs: Vec::new(),
+        };
+        let diagnostics = vec![
+            diagnostic("packages/app/src/main.ts", DiagnosticSeverity::Error),
+            diagnostic("file:///repo/packages/app/ui/button.ts", DiagnosticSeverity::Error),
+            diagnostic("/repo/packages/app/ui/button.ts", DiagnosticSeverity::Warning),
+            diagnostic("scripts/build.ts", DiagnosticSeverity::Error),
+        ];
+        let baseline = HashMap::from([("app".to_string(), 100.0), ("ui".to_string(), 50.0)]);
+
+        let health = SubprojectCollector::collect(&layout, &diagnostics, &baseline);
+        assert_eq!((health["app"].errors, health["app"].files), (1, 1));
+        assert_eq!((health["ui"].errors, health["ui"].warnings, health["ui"].files), (1, 1, 2));
+        assert_eq!(health["app"].trend, TrendDirection::Degrading);
+        assert_eq!(health["ui"].trend, TrendDirection::Improving);
+        assert_eq!(health["ui"].health.metrics.error_rate, 1.0);
+    }
+}
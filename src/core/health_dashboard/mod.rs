@@ -8,6 +8,8 @@
 //!
 //! - **HealthMonitor**: Main monitoring engine that coordinates all health checks
 //! - **MetricsCollector**: Collects health metrics from various system components
+//! - **SubprojectCollector**: Breaks diagnostic health down by monorepo subproject
+//! - **MetricPlugin**: Extension point for custom metrics such as coverage or bundle size
 //! - **AlertRulesEngine**: Evaluates metrics against thresholds and generates alerts
 //! - **DashboardRenderer**: Exports health data in various formats (JSON, Prometheus, etc.)
 
@@ -19,19 +21,25 @@ pub mod visualization;
 pub use types::*;
 
 use anyhow::{anyhow, Result};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime};
 use tokio::sync::RwLock;
 use tracing::{debug, error, info};
 
 use crate::core::{
-    DynamicConfigManager, ErrorRecoverySystem, GitIntegration, MetricsCollector as CoreMetricsCollector,
-    SimpleEnhancedProcessor,
+    Diagnostic, DynamicConfigManager, ErrorRecoverySystem, GitIntegration,
+    MetricsCollector as CoreMetricsCollector, SimpleEnhancedProcessor, TrustLevel,
 };
+use crate::multi_repo::monorepo::{MonorepoDetector, WorkspaceLayout};
 
-use alerts::{AlertNotifier, AlertRulesEngine};
-use metrics::{MetricsAggregator, MetricsCollector};
+use alerts::{AlertDispatcher, AlertNotifier, AlertRulesEngine};
+use metrics::subprojects::subproject_component;
+use metrics::plugins::plugin_component;
+use metrics::{
+    MetricPlugin, MetricsAggregator, MetricsCollector, PluginCollector, ScriptPluginConfig, SubprojectCollector,
+};
 use visualization::{DashboardComponents, DashboardRenderer};
 
 pub struct HealthMonitor {
@@ -40,18 +48,23 @@ pub struct HealthMonitor {
     config_manager: Option<Arc<DynamicConfigManager>>,
     git_integration: Option<Arc<GitIntegration>>,
     error_recovery: Option<Arc<ErrorRecoverySystem>>,
+    workspace_layout: Option<Arc<WorkspaceLayout>>,
+    metric_plugins: Vec<Arc<dyn MetricPlugin>>,
 
     // Monitoring state
     dashboard_data: Arc<RwLock<HealthDashboard>>,
     start_time: Instant,
     alert_history: Arc<RwLock<Vec<HealthAlert>>>,
     component_history: Arc<RwLock<HashMap<String, Vec<ComponentHealth>>>>,
+    latest_diagnostics: Arc<RwLock<Vec<Diagnostic>>>,
 
     // Configuration
     monitoring_config: MonitoringConfig,
     
     // Components
     alert_engine: AlertRulesEngine,
+    /// Sends new alerts to the configured channels; `None` without channels
+    alert_dispatcher: Option<Arc<AlertDispatcher>>,
 }
 
 impl HealthMonitor {
@@ -61,6 +74,12 @@ impl HealthMonitor {
     ) -> Result<Self> {
         let monitoring_config = config.unwrap_or_default();
         let alert_engine = AlertRulesEngine::new(monitoring_config.alert_thresholds.clone());
+        let alert_dispatcher = (!monitoring_config.alert_channels.is_empty()).then(|| {
+            Arc::new(AlertDispatcher::new(
+                &monitoring_config.alert_channels,
+                monitoring_config.alert_retry.clone(),
+            ))
+        });
 
         let initial_dashboard = HealthDashboard {
             timestamp: SystemTime::now(),
@@ -78,6 +97,8 @@ impl HealthMonitor {
             },
             alerts: Vec::new(),
             recommendations: Vec::new(),
+            subprojects: BTreeMap::new(),
+            plugins: BTreeMap::new(),
         };
 
         let monitor = Self {
@@ -86,12 +107,16 @@ impl HealthMonitor {
             config_manager: None,
             git_integration: None,
             error_recovery: None,
+            workspace_layout: None,
+            metric_plugins: Vec::new(),
             dashboard_data: Arc::new(RwLock::new(initial_dashboard)),
             start_time: Instant::now(),
             alert_history: Arc::new(RwLock::new(Vec::new())),
             component_history: Arc::new(RwLock::new(HashMap::new())),
+            latest_diagnostics: Arc::new(RwLock::new(Vec::new())),
             monitoring_config,
             alert_engine,
+            alert_dispatcher,
         };
 
         info!("Health monitor initialized");
@@ -119,11 +144,55 @@ impl HealthMonitor {
         self
     }
 
+    /// Break diagnostic health down by the subprojects of this layout
+    pub fn with_workspace_layout(mut self, layout: WorkspaceLayout) -> Self {
+        self.workspace_layout = Some(Arc::new(layout));
+        self
+    }
+
+    /// Collect a plugin's metrics on every update as the `plugin:<name>` component
+    pub fn with_metric_plugin(mut self, plugin: Arc<dyn MetricPlugin>) -> Self {
+        self.metric_plugins.push(plugin);
+        self
+    }
+
+    /// Collect the script plugins configured under `[[metric_plugins]]`
+    ///
+    /// Scripts run in `workspace` and only in trusted workspaces; untrusted
+    /// ones report their plugins offline.
+    pub fn with_script_plugins(mut self, plugins: &[ScriptPluginConfig], workspace: &Path, trust: TrustLevel) -> Self {
+        for config in plugins {
+            self.metric_plugins.push(Arc::new(config.plugin(workspace, trust)));
+        }
+        self
+    }
+
+    /// Detect a monorepo at `root` and track its subprojects
+    ///
+    /// Leaves the monitor unchanged when `root` is not a monorepo.
+    pub async fn detect_subprojects(self, root: &Path) -> Result<Self> {
+        match MonorepoDetector::new().detect(root).await? {
+            Some(layout) => {
+                info!("Tracking health of {} subprojects", layout.subprojects.len());
+                Ok(self.with_workspace_layout(layout))
+            }
+            None => Ok(self),
+        }
+    }
+
+    /// Replace the diagnostics subproject health is computed from
+    pub async fn record_diagnostics(&self, diagnostics: Vec<Diagnostic>) {
+        *self.latest_diagnostics.write().await = diagnostics;
+    }
+
     /// Start the monitoring loop
     pub async fn start_monitoring(self: Arc<Self>) -> Result<()> {
         info!("Starting health monitoring");
 
         let update_interval = self.monitoring_config.update_interval;
+        if let Some(dispatcher) = &self.alert_dispatcher {
+            dispatcher.clone().spawn_drainer();
+        }
 
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(update_interval);
@@ -162,6 +231,16 @@ impl HealthMonitor {
         dashboard.components.get(component).cloned()
     }
 
+    pub async fn get_subproject_health(&self, subproject: &str) -> Option<SubprojectHealth> {
+        let dashboard = self.dashboard_data.read().await;
+        dashboard.subprojects.get(subproject).cloned()
+    }
+
+    pub async fn get_plugin_health(&self, plugin: &str) -> Option<PluginHealth> {
+        let dashboard = self.dashboard_data.read().await;
+        dashboard.plugins.get(plugin).cloned()
+    }
+
     pub async fn get_active_alerts(&self) -> Vec<HealthAlert> {
         let dashboard = self.dashboard_data.read().await;
         dashboard.alerts.clone()
@@ -188,6 +267,9 @@ impl HealthMonitor {
 
     // Dashboard update methods
     pub async fn update_dashboard(&self) -> Result<()> {
+        // Plugins may run external programs, so collect them before locking the dashboard
+        let plugins = PluginCollector::collect(&self.metric_plugins).await;
+
         let mut dashboard = self.dashboard_data.write().await;
 
         // Update timestamp and uptime
@@ -197,6 +279,12 @@ impl HealthMonitor {
         // Update component health
         self.update_component_health(&mut dashboard).await?;
 
+        // Update per-subproject health
+        self.update_subproject_health(&mut dashboard).await;
+
+        // Update plugin-contributed health
+        self.update_plugin_health(&mut dashboard, plugins).await;
+
         // Update overall metrics
         self.update_dashboard_metrics(&mut dashboard).await?;
 
@@ -247,6 +335,54 @@ impl HealthMonitor {
         Ok(())
     }
 
+    async fn update_subproject_health(&self, dashboard: &mut HealthDashboard) {
+        let Some(layout) = &self.workspace_layout else {
+            return;
+        };
+
+        let mut component_history = self.component_history.write().await;
+
+        // Trends compare against the oldest score still within the retention period
+        let baseline: HashMap<String, f64> = layout
+            .subprojects
+            .iter()
+            .filter_map(|subproject| {
+                let history = component_history.get(&subproject_component(&subproject.name))?;
+                Some((subproject.name.clone(), history.first()?.score))
+            })
+            .collect();
+
+        let diagnostics = self.latest_diagnostics.read().await;
+        let mut subprojects = SubprojectCollector::collect(layout, &diagnostics, &baseline);
+
+        for (name, subproject) in &mut subprojects {
+            let history = component_history.entry(subproject_component(name)).or_default();
+            if history.last().map(|last| last.score) != Some(subproject.health.score) {
+                history.push(subproject.health.clone());
+            }
+            subproject.active_alerts = dashboard.subprojects.get(name).map_or(0, |s| s.active_alerts);
+        }
+
+        dashboard.subprojects = subprojects;
+    }
+
+    async fn update_plugin_health(&self, dashboard: &mut HealthDashboard, plugins: Vec<PluginHealth>) {
+        if plugins.is_empty() {
+            return;
+        }
+
+        let mut component_history = self.component_history.write().await;
+        for plugin in plugins {
+            let component = plugin_component(&plugin.name);
+            component_history
+                .entry(component.clone())
+                .or_default()
+                .push(plugin.health.clone());
+            dashboard.components.insert(component, plugin.health.clone());
+            dashboard.plugins.insert(plugin.name.clone(), plugin);
+        }
+    }
+
     async fn update_dashboard_metrics(&self, dashboard: &mut HealthDashboard) -> Result<()> {
         dashboard.metrics = MetricsAggregator::aggregate_dashboard_metrics(
             &self.processor,
@@ -260,7 +396,9 @@ impl HealthMonitor {
 
     pub async fn check_alerts(&self) -> Result<()> {
         let dashboard = self.dashboard_data.read().await;
-        let new_alerts = self.alert_engine.check_components(&dashboard.components);
+        let mut new_alerts = self.alert_engine.check_components(&dashboard.components);
+        new_alerts.extend(self.alert_engine.check_subprojects(&dashboard.subprojects));
+        new_alerts.extend(self.alert_engine.check_plugins(&dashboard.plugins));
 
         if !new_alerts.is_empty() {
             // Notify about new alerts
@@ -269,11 +407,30 @@ impl HealthMonitor {
             // Add to dashboard
             drop(dashboard); // Release read lock
             let mut dashboard = self.dashboard_data.write().await;
-            AlertRulesEngine::merge_alerts(
+            let added = AlertRulesEngine::merge_alerts(
                 &mut dashboard.alerts,
                 new_alerts,
                 self.monitoring_config.max_alerts,
             );
+
+            // Alerts that stay active are only sent when first raised; retries
+            // back off for seconds, so they don't hold up the monitoring loop
+            if let Some(dispatcher) = self.alert_dispatcher.clone().filter(|_| !added.is_empty()) {
+                tokio::spawn(async move {
+                    dispatcher.dispatch(&added).await;
+                });
+            }
+        }
+
+        // Count active alerts per subproject
+        let mut dashboard = self.dashboard_data.write().await;
+        let HealthDashboard { alerts, subprojects, .. } = &mut *dashboard;
+        for (name, subproject) in subprojects.iter_mut() {
+            let component = subproject_component(name);
+            subproject.active_alerts = alerts
+                .iter()
+                .filter(|alert| !alert.resolved && alert.component == component)
+                .count();
         }
 
         Ok(())
@@ -285,7 +442,8 @@ impl HealthMonitor {
         }
 
         let dashboard = self.dashboard_data.read().await;
-        let recommendations = DashboardComponents::generate_recommendations(&dashboard.metrics);
+        let mut recommendations = DashboardComponents::generate_recommendations(&dashboard.metrics);
+        recommendations.extend(DashboardComponents::generate_plugin_recommendations(&dashboard.plugins));
 
         if !recommendations.is_empty() {
             drop(dashboard); // Release read lock
@@ -404,4 +562,142 @@ mod tests {
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_subproject_breakdown() -> Result<()> {
+        use crate::core::{DiagnosticSeverity, Position, Range};
+        use crate::multi_repo::monorepo::{SubprojectInfo, WorkspaceConfig, WorkspaceType};
+
+        let temp_dir = TempDir::new()?;
+        let config = SimpleEnhancedConfig {
+            cache_dir: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        let root = temp_dir.path().to_path_buf();
+        let package = |name: &str| SubprojectInfo {
+            name: name.to_string(),
+            relative_path: Path::new("packages").join(name),
+            absolute_path: root.join("packages").join(name),
+            language: Some("typescript".to_string()),
+            build_system: None,
+            internal_deps: Vec::new(),
+            external_deps: Vec::new(),
+            package_config: None,
+        };
+        let layout = WorkspaceLayout {
+            root: root.clone(),
+            workspace_type: WorkspaceType::NpmWorkspace,
+            subprojects: vec![package("api"), package("web")],
+            config: WorkspaceConfig {
+                patterns: vec!["packages/*".to_string()],
+                excludes: Vec::new(),
+                dependencies: HashMap::new(),
+                build_config: None,
+            },
+            shared_configs: Vec::new(),
+        };
+
+        let processor = Arc::new(SimpleEnhancedProcessor::new(config).await?);
+        let monitor = HealthMonitor::new(processor, None).await?.with_workspace_layout(layout);
+
+        let diagnostics = (0..12)
+            .map(|line| {
+                Diagnostic::new(
+                    "packages/api/src/index.ts".to_string(),
+                    Range {
+                        start: Position { line, character: 0 },
+                        end: Position { line, character: 1 },
+                    },
+                    DiagnosticSeverity::Error,
+                    "Type error".to_string(),
+                    "typescript".to_string(),
+                )
+            })
+            .collect();
+        monitor.record_diagnostics(diagnostics).await;
+        monitor.update_dashboard().await?;
+        monitor.check_alerts().await?;
+
+        let api = monitor.get_subproject_health("api").await.unwrap();
+        let web = monitor.get_subproject_health("web").await.unwrap();
+        assert_eq!((api.errors, api.files), (12, 1));
+        assert!(api.health.score < web.health.score);
+        assert_eq!(api.active_alerts, 1);
+        assert_eq!(web.active_alerts, 0);
+
+        let prometheus = monitor.export_metrics_prometheus().await?;
+        assert!(prometheus.contains("lsp_bridge_subproject_errors{subproject=\"api\"} 12"));
+        assert!(prometheus.contains("lsp_bridge_subproject_errors{subproject=\"web\"} 0"));
+        assert_eq!(prometheus.matches("# TYPE lsp_bridge_subproject_errors").count(), 1);
+
+        // Fixing the errors shows up as an improving trend
+        monitor.record_diagnostics(Vec::new()).await;
+        monitor.update_dashboard().await?;
+        let api = monitor.get_subproject_health("api").await.unwrap();
+        assert_eq!(api.trend, TrendDirection::Improving);
+
+        Ok(())
+    }
+
+    struct FixedPlugin(Vec<PluginMetric>);
+
+    #[async_trait::async_trait]
+    impl MetricPlugin for FixedPlugin {
+        fn name(&self) -> &str {
+            "frontend"
+        }
+
+        async fn collect(&self) -> Result<Vec<PluginMetric>> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metric_plugins_are_components() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = SimpleEnhancedConfig {
+            cache_dir: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        let metric = |name: &str, value: f64, warning: f64, higher_is_better: bool| PluginMetric {
+            name: name.to_string(),
+            value,
+            unit: None,
+            warning: Some(warning),
+            critical: None,
+            higher_is_better,
+            recommendation: None,
+        };
+        let plugin = FixedPlugin(vec![
+            metric("coverage", 62.0, 80.0, true),
+            metric("bundle_kb", 300.0, 400.0, false),
+        ]);
+
+        let processor = Arc::new(SimpleEnhancedProcessor::new(config).await?);
+        let monitor = HealthMonitor::new(processor, None).await?.with_metric_plugin(Arc::new(plugin));
+        monitor.update_dashboard().await?;
+        monitor.check_alerts().await?;
+        monitor.generate_recommendations().await?;
+
+        let component = monitor.get_component_health("plugin:frontend").await.unwrap();
+        assert_eq!(component.metrics.custom_metrics["coverage"], 62.0);
+        assert!(matches!(component.status, ComponentStatus::Degraded));
+
+        let alerts = monitor.get_active_alerts().await;
+        let plugin_alerts: Vec<_> = alerts.iter().filter(|a| a.component == "plugin:frontend").collect();
+        assert_eq!(plugin_alerts.len(), 1);
+        assert_eq!(plugin_alerts[0].id, "plugin-warning-plugin:frontend-coverage");
+
+        assert!(monitor
+            .get_recommendations()
+            .await
+            .iter()
+            .any(|r| r.id == "plugin-frontend-coverage"));
+
+        let prometheus = monitor.export_metrics_prometheus().await?;
+        assert!(prometheus.contains("lsp_bridge_plugin_metric{plugin=\"frontend\",metric=\"bundle_kb\"} 300"));
+        assert!(prometheus.contains("lsp_bridge_component_health_score{component=\"plugin:frontend\"}"));
+
+        Ok(())
+    }
+}
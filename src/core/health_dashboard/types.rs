@@ -1,6 +1,13 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
 use std::time::{Duration, SystemTime};
+use utoipa::ToSchema;
+
+pub use crate::history::TrendDirection;
+
+use super::alerts::AlertChannelConfig;
+use crate::core::net::RetryPolicy;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HealthDashboard {
@@ -10,9 +17,15 @@ pub struct HealthDashboard {
     pub metrics: DashboardMetrics,
     pub alerts: Vec<HealthAlert>,
     pub recommendations: Vec<PerformanceRecommendation>,
+    /// Diagnostic health per monorepo subproject, keyed by subproject name
+    #[serde(default)]
+    pub subprojects: BTreeMap<String, SubprojectHealth>,
+    /// Metrics contributed by plugins, keyed by plugin name
+    #[serde(default)]
+    pub plugins: BTreeMap<String, PluginHealth>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub enum SystemHealthStatus {
     Healthy,   // All systems operational
     Degraded,  // Some issues but functional
@@ -21,17 +34,18 @@ pub enum SystemHealthStatus {
     Unknown,   // Status cannot be determined
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ComponentHealth {
     pub name: String,
     pub status: ComponentStatus,
     pub score: f64, // 0-100 health score
     pub metrics: ComponentMetrics,
+    #[schema(value_type = Object)]
     pub last_check: SystemTime,
     pub issues: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub enum ComponentStatus {
     Online,
     Degraded,
@@ -39,11 +53,12 @@ pub enum ComponentStatus {
     Unknown,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ComponentMetrics {
     pub cpu_usage: f64,
     pub memory_usage: f64,
     pub error_rate: f64,
+    #[schema(value_type = Object)]
     pub response_time: Duration,
     pub throughput: f64,
     pub custom_metrics: HashMap<String, f64>,
@@ -51,6 +66,81 @@ pub struct ComponentMetrics {
 
 pub type ComponentHealthMap = HashMap<String, ComponentHealth>;
 
+/// Diagnostic health of one monorepo subproject
+///
+/// `health.metrics.error_rate` is the number of open errors in the
+/// subproject, so the regular error-rate thresholds apply per package.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubprojectHealth {
+    pub name: String,
+    /// Path relative to the workspace root
+    pub path: PathBuf,
+    pub health: ComponentHealth,
+    pub errors: usize,
+    pub warnings: usize,
+    /// Files with at least one diagnostic
+    pub files: usize,
+    /// Direction of the score since the oldest retained update
+    pub trend: TrendDirection,
+    /// Unresolved alerts raised for this subproject
+    pub active_alerts: usize,
+}
+
+/// One named value reported by a metric plugin
+///
+/// Thresholds are optional; without them the metric is exported but never
+/// alerts. They are upper bounds unless `higher_is_better` is set, as for
+/// code coverage.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginMetric {
+    pub name: String,
+    pub value: f64,
+    #[serde(default)]
+    pub unit: Option<String>,
+    #[serde(default)]
+    pub warning: Option<f64>,
+    #[serde(default)]
+    pub critical: Option<f64>,
+    #[serde(default)]
+    pub higher_is_better: bool,
+    /// Advice shown as a recommendation while a threshold is breached
+    #[serde(default)]
+    pub recommendation: Option<String>,
+}
+
+impl PluginMetric {
+    /// Severity of the threshold the value breaches, if any
+    pub fn breach(&self) -> Option<AlertSeverity> {
+        let breached = |threshold: Option<f64>| {
+            threshold.is_some_and(|threshold| {
+                if self.higher_is_better {
+                    self.value < threshold
+                } else {
+                    self.value > threshold
+                }
+            })
+        };
+        if breached(self.critical) {
+            Some(AlertSeverity::Critical)
+        } else if breached(self.warning) {
+            Some(AlertSeverity::Warning)
+        } else {
+            None
+        }
+    }
+}
+
+/// Latest collection of one metric plugin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginHealth {
+    pub name: String,
+    /// The plugin as a dashboard component, also found in `components`
+    pub health: ComponentHealth,
+    pub metrics: Vec<PluginMetric>,
+    /// Why the last collection failed; the previous metrics are dropped
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DashboardMetrics {
     pub files_processed_total: u64,
@@ -74,7 +164,7 @@ pub struct HealthAlert {
     pub resolution_time: Option<SystemTime>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum AlertSeverity {
     Info,
     Warning,
@@ -116,6 +206,10 @@ pub struct MonitoringConfig {
     pub enable_recommendations: bool,
     pub max_alerts: usize,
     pub max_history_entries: usize,
+    /// External services new alerts are sent to, besides the log
+    pub alert_channels: Vec<AlertChannelConfig>,
+    /// Retries of a failed alert delivery before it is queued
+    pub alert_retry: RetryPolicy,
 }
 
 #[derive(Debug, Clone)]
@@ -148,6 +242,8 @@ impl Default for MonitoringConfig {
             enable_recommendations: true,
             max_alerts: 1000,
             max_history_entries: 10000,
+            alert_channels: Vec::new(),
+            alert_retry: RetryPolicy::default(),
         }
     }
 }
\ No newline at end of file
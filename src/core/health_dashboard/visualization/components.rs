@@ -1,6 +1,8 @@
+use crate::core::health_dashboard::metrics::plugins::plugin_component;
 use crate::core::health_dashboard::types::{
-    DashboardMetrics, EffortLevel, ImpactLevel, PerformanceRecommendation,
+    AlertSeverity, DashboardMetrics, EffortLevel, ImpactLevel, PerformanceRecommendation, PluginHealth,
 };
+use std::collections::BTreeMap;
 use std::time::SystemTime;
 
 pub struct DashboardComponents;
@@ -89,6 +91,43 @@ impl DashboardComponents {
         recommendations
     }
 
+    /// Recommend action on plugin metrics beyond their thresholds
+    ///
+    /// Uses the advice the plugin supplied with the metric when there is any.
+    pub fn generate_plugin_recommendations(
+        plugins: &BTreeMap<String, PluginHealth>,
+    ) -> Vec<PerformanceRecommendation> {
+        let mut recommendations = Vec::new();
+
+        for (name, plugin) in plugins {
+            for metric in &plugin.metrics {
+                let Some(severity) = metric.breach() else { continue };
+                let impact = match severity {
+                    AlertSeverity::Critical => ImpactLevel::High,
+                    _ => ImpactLevel::Medium,
+                };
+                let direction = if metric.higher_is_better { "Raise" } else { "Reduce" };
+                recommendations.push(PerformanceRecommendation {
+                    id: format!("plugin-{name}-{}", metric.name),
+                    component: plugin_component(name),
+                    recommendation: metric.recommendation.clone().unwrap_or_else(|| {
+                        format!(
+                            "{direction} {} ({}{}) back within its thresholds.",
+                            metric.name,
+                            metric.value,
+                            metric.unit.as_deref().unwrap_or_default()
+                        )
+                    }),
+                    impact,
+                    effort: EffortLevel::Medium,
+                    timestamp: SystemTime::now(),
+                });
+            }
+        }
+
+        recommendations
+    }
+
     /// Generate a health score summary
     pub fn calculate_health_summary(metrics: &DashboardMetrics) -> (f64, String) {
         let mut score = 100.0;
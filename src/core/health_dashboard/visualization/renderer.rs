@@ -1,6 +1,9 @@
-use crate::core::health_dashboard::types::HealthDashboard;
+use crate::core::health_dashboard::types::{HealthDashboard, SubprojectHealth, TrendDirection};
 use anyhow::Result;
 
+/// Reads one gauge value from a subproject
+type SubprojectGauge = fn(&SubprojectHealth) -> f64;
+
 pub struct DashboardRenderer;
 
 impl DashboardRenderer {
@@ -87,6 +90,54 @@ impl DashboardRenderer {
             ));
         }
 
+        // Subproject metrics, one family at a time so HELP/TYPE appear once
+        if !dashboard.subprojects.is_empty() {
+            let families: [(&str, &str, SubprojectGauge); 5] = [
+                ("health_score", "Subproject health score", |s| s.health.score),
+                ("errors", "Open errors in the subproject", |s| s.errors as f64),
+                ("warnings", "Open warnings in the subproject", |s| s.warnings as f64),
+                ("trend", "Subproject health trend (1 improving, 0 stable, -1 degrading)", |s| {
+                    match s.trend {
+                        TrendDirection::Improving => 1.0,
+                        TrendDirection::Stable => 0.0,
+                        TrendDirection::Degrading => -1.0,
+                    }
+                }),
+                ("active_alerts", "Active alerts for the subproject", |s| s.active_alerts as f64),
+            ];
+            for (family, help, value) in families {
+                output.push_str(&format!(
+                    "# HELP lsp_bridge_subproject_{family} {help}\n\
+                     # TYPE lsp_bridge_subproject_{family} gauge\n"
+                ));
+                for (name, subproject) in &dashboard.subprojects {
+                    output.push_str(&format!(
+                        "lsp_bridge_subproject_{family}{{subproject=\"{}\"}} {}\n",
+                        escape_label(name),
+                        value(subproject)
+                    ));
+                }
+            }
+        }
+
+        // Plugin metrics share one family, labelled by plugin and metric
+        if dashboard.plugins.values().any(|plugin| !plugin.metrics.is_empty()) {
+            output.push_str(
+                "# HELP lsp_bridge_plugin_metric Metric contributed by a plugin\n\
+                 # TYPE lsp_bridge_plugin_metric gauge\n",
+            );
+            for (name, plugin) in &dashboard.plugins {
+                for metric in &plugin.metrics {
+                    output.push_str(&format!(
+                        "lsp_bridge_plugin_metric{{plugin=\"{}\",metric=\"{}\"}} {}\n",
+                        escape_label(name),
+                        escape_label(&metric.name),
+                        metric.value
+                    ));
+                }
+            }
+        }
+
         // Alert metrics
         let active_alerts = dashboard.alerts.iter().filter(|a| !a.resolved).count();
         output.push_str(&format!(
@@ -143,6 +194,45 @@ impl DashboardRenderer {
             }
         }
 
+        if !dashboard.subprojects.is_empty() {
+            output.push_str("\n=== Subprojects ===\n");
+            for (name, subproject) in &dashboard.subprojects {
+                output.push_str(&format!(
+                    "{}: {:?} (Score: {:.1}, Errors: {}, Warnings: {}, Trend: {:?}, Alerts: {})\n",
+                    name,
+                    subproject.health.status,
+                    subproject.health.score,
+                    subproject.errors,
+                    subproject.warnings,
+                    subproject.trend,
+                    subproject.active_alerts
+                ));
+            }
+        }
+
+        if !dashboard.plugins.is_empty() {
+            output.push_str("\n=== Plugins ===\n");
+            for (name, plugin) in &dashboard.plugins {
+                match &plugin.error {
+                    Some(error) => output.push_str(&format!("{name}: failed ({error})\n")),
+                    None => {
+                        let metrics: Vec<String> = plugin
+                            .metrics
+                            .iter()
+                            .map(|m| format!("{}={}{}", m.name, m.value, m.unit.as_deref().unwrap_or_default()))
+                            .collect();
+                        output.push_str(&format!(
+                            "{}: {:?} (Score: {:.1}) {}\n",
+                            name,
+                            plugin.health.status,
+                            plugin.health.score,
+                            metrics.join(", ")
+                        ));
+                    }
+                }
+            }
+        }
+
         if !dashboard.alerts.is_empty() {
             output.push_str("\n=== Active Alerts ===\n");
             for alert in dashboard.alerts.iter().filter(|a| !a.resolved) {
@@ -165,4 +255,9 @@ impl DashboardRenderer {
 
         output
     }
-}
\ No newline at end of file
+}
+
+/// Escape a Prometheus label value
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
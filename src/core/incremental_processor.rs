@@ -21,6 +21,16 @@ impl FileHash {
         Self(hash)
     }
 
+    /// Rebuild a hash from its hex digest, as stored in history
+    pub fn from_hex(hex: &str) -> Self {
+        // Older history rows hold the `Debug` form, `FileHash("<hex>")`
+        let hex = hex
+            .strip_prefix("FileHash(\"")
+            .and_then(|hex| hex.strip_suffix("\")"))
+            .unwrap_or(hex);
+        Self(hex.to_string())
+    }
+
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
         let content = fs::read(path)?;
         Ok(Self::new(&content))
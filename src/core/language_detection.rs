@@ -0,0 +1,375 @@
+//! Language detection from file names and content
+//!
+//! Extensions cover most files, but not all: scripts are often extensionless
+//! and name their interpreter in a shebang, and single-file components
+//! (`.vue`, `.svelte`) and MDX embed TypeScript or JavaScript inside markup.
+//! [`detect_language`] falls back to the shebang when the extension says
+//! nothing, and [`embedded_blocks`] locates the script blocks of templates so
+//! callers can parse just the code, at its original line and column.
+
+use std::io::Read;
+use std::path::Path;
+
+/// Bytes read when a file's language has to be sniffed from its shebang
+const SNIFF_BYTES: u64 = 8 * 1024;
+
+/// Bytes read when looking for the script blocks of a template
+const TEMPLATE_BYTES: u64 = 1024 * 1024;
+
+/// Programming language of a file or an embedded block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DetectedLanguage {
+    TypeScript,
+    JavaScript,
+    Rust,
+    Python,
+    Go,
+    Java,
+    Cpp,
+    Shell,
+    Ruby,
+    Perl,
+    Php,
+}
+
+impl DetectedLanguage {
+    /// Lower-case name as used across the codebase (`"typescript"`, `"rust"`, ...)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TypeScript => "typescript",
+            Self::JavaScript => "javascript",
+            Self::Rust => "rust",
+            Self::Python => "python",
+            Self::Go => "go",
+            Self::Java => "java",
+            Self::Cpp => "cpp",
+            Self::Shell => "shell",
+            Self::Ruby => "ruby",
+            Self::Perl => "perl",
+            Self::Php => "php",
+        }
+    }
+
+    /// Language of a plain source file extension
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        Some(match extension {
+            "ts" | "tsx" | "mts" | "cts" => Self::TypeScript,
+            "js" | "jsx" | "mjs" | "cjs" => Self::JavaScript,
+            "rs" => Self::Rust,
+            "py" | "pyi" => Self::Python,
+            "go" => Self::Go,
+            "java" => Self::Java,
+            "cpp" | "cc" | "cxx" | "hpp" | "hh" => Self::Cpp,
+            "sh" | "bash" | "zsh" => Self::Shell,
+            "rb" => Self::Ruby,
+            "pl" | "pm" => Self::Perl,
+            "php" => Self::Php,
+            _ => return None,
+        })
+    }
+
+    /// Language named by a `#!` line, e.g. `#!/usr/bin/env -S deno run`
+    pub fn from_shebang(first_line: &str) -> Option<Self> {
+        let command = first_line.strip_prefix("#!")?.trim();
+        let mut words = command.split_whitespace();
+        let mut interpreter = basename(words.next()?);
+        if interpreter == "env" {
+            interpreter = words.find(|word| !word.starts_with('-')).map(basename)?;
+        }
+
+        // Strip version suffixes such as python3.12 or ruby2.7
+        let name = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+        Some(match name {
+            "node" | "nodejs" | "bun" | "qjs" => Self::JavaScript,
+            "deno" | "ts-node" | "tsx" => Self::TypeScript,
+            "python" | "pypy" | "uv" => Self::Python,
+            "sh" | "bash" | "zsh" | "dash" | "ksh" => Self::Shell,
+            "ruby" => Self::Ruby,
+            "perl" => Self::Perl,
+            "php" => Self::Php,
+            "rust-script" | "cargo" => Self::Rust,
+            _ => return None,
+        })
+    }
+
+    /// Language of a `lang` attribute or a code fence tag
+    fn from_tag(tag: &str) -> Option<Self> {
+        match tag.to_ascii_lowercase().as_str() {
+            "ts" | "typescript" | "tsx" => Some(Self::TypeScript),
+            "js" | "javascript" | "jsx" => Some(Self::JavaScript),
+            "rust" => Some(Self::Rust),
+            "python" => Some(Self::Python),
+            other => Self::from_extension(other),
+        }
+    }
+}
+
+/// Code embedded in a template file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedBlock {
+    pub language: DetectedLanguage,
+    /// Byte range of the code within the file
+    pub start: usize,
+    pub end: usize,
+    /// Zero-based lines of the first and last code character
+    pub start_line: u32,
+    pub end_line: u32,
+}
+
+impl EmbeddedBlock {
+    pub fn contains_line(&self, line: u32) -> bool {
+        (self.start_line..=self.end_line).contains(&line)
+    }
+
+    /// The file with everything outside the block blanked out
+    ///
+    /// Whitespace is kept and other characters become spaces, so a parser
+    /// sees only the block's code while lines and columns stay the same as
+    /// in the original file.
+    pub fn masked(&self, content: &str) -> String {
+        content
+            .char_indices()
+            .map(|(index, c)| {
+                if (self.start..self.end).contains(&index) || c.is_whitespace() {
+                    c
+                } else {
+                    ' '
+                }
+            })
+            .collect()
+    }
+}
+
+/// Whether files with this extension embed code in markup
+pub fn is_template(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("vue" | "svelte" | "mdx")
+    )
+}
+
+/// Language of a file from its extension, shebang or embedded scripts
+///
+/// `content` may be just the start of the file. Templates report the
+/// language of their first script block.
+pub fn detect_language(path: &Path, content: Option<&str>) -> Option<DetectedLanguage> {
+    if is_template(path) {
+        return embedded_blocks(path, content?).first().map(|block| block.language);
+    }
+    if let Some(language) = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(DetectedLanguage::from_extension)
+    {
+        return Some(language);
+    }
+    DetectedLanguage::from_shebang(content?.lines().next()?)
+}
+
+/// Language at a given zero-based line, looking inside template blocks
+pub fn language_at(path: &Path, content: &str, line: u32) -> Option<DetectedLanguage> {
+    if is_template(path) {
+        return embedded_blocks(path, content)
+            .into_iter()
+            .find(|block| block.contains_line(line))
+            .map(|block| block.language);
+    }
+    detect_language(path, Some(content))
+}
+
+/// Detect a file's language, reading its start only when the extension is not enough
+pub fn detect_file_language(path: &Path) -> Option<DetectedLanguage> {
+    let by_extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(DetectedLanguage::from_extension);
+    if by_extension.is_some() {
+        return by_extension;
+    }
+
+    let mut head = Vec::new();
+    std::fs::File::open(path)
+        .ok()?
+        .take(if is_template(path) { TEMPLATE_BYTES } else { SNIFF_BYTES })
+        .read_to_end(&mut head)
+        .ok()?;
+    detect_language(path, Some(&String::from_utf8_lossy(&head)))
+}
+
+/// Script blocks of a Vue, Svelte or MDX file, in file order
+///
+/// Vue and Svelte `<script>` blocks are JavaScript unless a `lang`
+/// attribute says otherwise. MDX contributes its fenced code blocks and its
+/// top-level `import`/`export` lines, which are JavaScript.
+pub fn embedded_blocks(path: &Path, content: &str) -> Vec<EmbeddedBlock> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("vue" | "svelte") => script_blocks(content),
+        Some("mdx") => mdx_blocks(content),
+        _ => Vec::new(),
+    }
+}
+
+fn script_blocks(content: &str) -> Vec<EmbeddedBlock> {
+    let lower = content.to_ascii_lowercase();
+    let mut blocks = Vec::new();
+    let mut cursor = 0;
+
+    while let Some(offset) = lower[cursor..].find("<script") {
+        let tag_start = cursor + offset;
+        let Some(tag_len) = lower[tag_start..].find('>') else {
+            break;
+        };
+        let tag = &content[tag_start..tag_start + tag_len];
+        let start = tag_start + tag_len + 1;
+        let Some(close) = lower[start..].find("</script") else {
+            break;
+        };
+        let end = start + close;
+        cursor = end;
+
+        let language = attribute(tag, "lang")
+            .and_then(DetectedLanguage::from_tag)
+            .unwrap_or(DetectedLanguage::JavaScript);
+        if let Some(block) = block(content, language, start, end) {
+            blocks.push(block);
+        }
+    }
+
+    blocks
+}
+
+fn mdx_blocks(content: &str) -> Vec<EmbeddedBlock> {
+    let mut blocks = Vec::new();
+    let mut fence: Option<(usize, Option<DetectedLanguage>)> = None;
+    let mut offset = 0;
+
+    for line in content.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+        let trimmed = line.trim_start();
+
+        match fence {
+            Some((code_start, language)) if trimmed.starts_with("```") => {
+                if let Some(block) = language.and_then(|language| block(content, language, code_start, line_start)) {
+                    blocks.push(block);
+                }
+                fence = None;
+            }
+            Some(_) => {}
+            None if trimmed.starts_with("```") => {
+                let tag = trimmed.trim_start_matches('`').split_whitespace().next().unwrap_or("");
+                fence = Some((offset, DetectedLanguage::from_tag(tag)));
+            }
+            None if line.starts_with("import ") || line.starts_with("export ") => {
+                if let Some(block) = block(content, DetectedLanguage::JavaScript, line_start, offset) {
+                    blocks.push(block);
+                }
+            }
+            None => {}
+        }
+    }
+
+    blocks
+}
+
+/// Block over `start..end`, trimmed to its code, or `None` if it is blank
+fn block(content: &str, language: DetectedLanguage, start: usize, end: usize) -> Option<EmbeddedBlock> {
+    let code = &content[start..end];
+    let leading = code.len() - code.trim_start().len();
+    let trailing = code.len() - code.trim_end().len();
+    if leading == code.len() {
+        return None;
+    }
+    let (start, end) = (start + leading, end - trailing);
+    Some(EmbeddedBlock {
+        language,
+        start,
+        end,
+        start_line: line_of(content, start),
+        end_line: line_of(content, end - 1),
+    })
+}
+
+fn line_of(content: &str, offset: usize) -> u32 {
+    content[..offset].bytes().filter(|b| *b == b'\n').count() as u32
+}
+
+/// Value of `name="value"` (or single-quoted) within an opening tag
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = tag;
+    while let Some(index) = rest.find(name) {
+        let preceded_by_space = rest[..index].ends_with(|c: char| c.is_whitespace());
+        let after = rest[index + name.len()..].trim_start();
+        rest = &rest[index + name.len()..];
+        if !preceded_by_space {
+            continue;
+        }
+        let Some(value) = after.strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start();
+        let quote = value.chars().next()?;
+        if quote == '"' || quote == '\'' {
+            return value[1..].split(quote).next();
+        }
+        return value.split(|c: char| c.is_whitespace() || c == '>').next();
+    }
+    None
+}
+
+fn basename(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shebang_detection() {
+        let detect = |content: &str| detect_language(Path::new("bin/tool"), Some(content));
+        assert_eq!(detect("#!/usr/bin/env python3.12\nprint(1)"), Some(DetectedLanguage::Python));
+        assert_eq!(detect("#!/usr/bin/env -S deno run -A\n"), Some(DetectedLanguage::TypeScript));
+        assert_eq!(detect("#!/bin/bash\nset -e"), Some(DetectedLanguage::Shell));
+        assert_eq!(detect("#!/usr/local/bin/node"), Some(DetectedLanguage::JavaScript));
+        assert_eq!(detect("plain text"), None);
+        assert_eq!(
+            detect_language(Path::new("src/main.rs"), None),
+            Some(DetectedLanguage::Rust)
+        );
+    }
+
+    #[test]
+    fn test_vue_script_blocks_keep_their_position() {
+        let content = "<template>\n  <div>{{ count }}</div>\n</template>\n\n<script setup lang=\"ts\">\nconst count: number = 1\n</script>\n";
+        let path = Path::new("src/Counter.vue");
+
+        let blocks = embedded_blocks(path, content);
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].language, DetectedLanguage::TypeScript);
+        assert_eq!((blocks[0].start_line, blocks[0].end_line), (5, 5));
+        assert_eq!(detect_language(path, Some(content)), Some(DetectedLanguage::TypeScript));
+        assert_eq!(language_at(path, content, 1), None);
+
+        let masked = blocks[0].masked(content);
+        assert_eq!(masked.lines().count(), content.lines().count());
+        assert_eq!(masked.lines().nth(5), Some("const count: number = 1"));
+        assert!(masked.lines().nth(1).unwrap().trim().is_empty());
+    }
+
+    #[test]
+    fn test_svelte_and_mdx_blocks() {
+        let svelte = "<script>\n  let name = 'world';\n</script>\n<h1>Hello {name}</h1>\n";
+        let blocks = embedded_blocks(Path::new("App.svelte"), svelte);
+        assert_eq!(blocks[0].language, DetectedLanguage::JavaScript);
+        assert_eq!(blocks[0].start_line, 1);
+
+        let mdx = "import { Chart } from './chart'\n\n# Usage\n\n```ts\nconst x: number = 1\n```\n\n```\nplain\n```\n";
+        let blocks = embedded_blocks(Path::new("docs/usage.mdx"), mdx);
+        let languages: Vec<_> = blocks.iter().map(|b| (b.language, b.start_line)).collect();
+        assert_eq!(
+            languages,
+            [(DetectedLanguage::JavaScript, 0), (DetectedLanguage::TypeScript, 5)]
+        );
+    }
+}
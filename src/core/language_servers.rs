@@ -0,0 +1,414 @@
+//! Per-project language server profiles
+//!
+//! When LSPbridge launches language servers itself, each project needs its own
+//! server settings: clippy flags for one crate, a tsconfig or TypeScript SDK
+//! path for a frontend, the virtualenv interpreter for a Python service.
+//! Profiles are read from `.lspbridge.toml` in the project root and keyed by
+//! server name:
+//!
+//! ```toml
+//! [language_servers.rust-analyzer]
+//! initialization_options = { check = { command = "clippy", extraArgs = ["--", "-W", "clippy::pedantic"] } }
+//!
+//! [language_servers.typescript-language-server]
+//! command = "node_modules/.bin/typescript-language-server"
+//! initialization_options = { tsserver = { path = "${workspaceFolder}/node_modules/typescript/lib" } }
+//!
+//! [language_servers.pyright-langserver]
+//! env = { VIRTUAL_ENV = "${workspaceFolder}/.venv" }
+//! settings = { python = { pythonPath = "${workspaceFolder}/.venv/bin/python" } }
+//! ```
+//!
+//! `${workspaceFolder}` and `${env:NAME}` are expanded in the command, args,
+//! env values, initialization options and settings. Relative commands
+//! containing a path separator are resolved against the project root.
+//!
+//! A server without a `command` runs from the managed install directory when
+//! `lspbridge servers install` has put it there, and from `PATH` otherwise.
+//!
+//! Profiles choose what a server executes, so they are ignored in untrusted
+//! workspaces (see [`crate::core::workspace_trust`]), where servers start
+//! with their defaults.
+
+use crate::core::server_install::ServerInstaller;
+use crate::core::workspace_trust::TrustLevel;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+/// Per-project configuration file holding server profiles
+pub const PROJECT_CONFIG_FILE: &str = ".lspbridge.toml";
+
+/// How to launch and initialize one language server
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LanguageServerProfile {
+    /// Server binary; defaults to the profile name
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Arguments; defaults to `--stdio` for servers that need it
+    #[serde(default)]
+    pub args: Option<Vec<String>>,
+    /// Extra environment variables
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Sent as `initializationOptions` in the `initialize` request
+    #[serde(default, alias = "initializationOptions")]
+    pub initialization_options: Option<serde_json::Value>,
+    /// Sent with `workspace/didChangeConfiguration` after initialization
+    #[serde(default)]
+    pub settings: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ProjectFile {
+    #[serde(default)]
+    language_servers: HashMap<String, LanguageServerProfile>,
+}
+
+/// Language server profiles of one project
+#[derive(Debug, Clone)]
+pub struct LanguageServerProfiles {
+    root: PathBuf,
+    profiles: HashMap<String, LanguageServerProfile>,
+    managed: Option<ServerInstaller>,
+}
+
+impl LanguageServerProfiles {
+    /// Profiles for a project without a configuration file
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            profiles: HashMap::new(),
+            managed: None,
+        }
+    }
+
+    /// Load `.lspbridge.toml` from a project root; a missing file yields no profiles
+    pub fn load(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        let path = root.join(PROJECT_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::new(root));
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(root, &content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    /// Parse the contents of a project configuration file
+    pub fn parse(root: impl Into<PathBuf>, content: &str) -> Result<Self> {
+        let file: ProjectFile = toml::from_str(content)?;
+        Ok(Self {
+            root: root.into(),
+            profiles: file.language_servers,
+            managed: None,
+        })
+    }
+
+    /// Drop every profile unless the workspace is trusted
+    pub fn with_trust(mut self, trust: TrustLevel) -> Self {
+        if trust == TrustLevel::Untrusted && !self.profiles.is_empty() {
+            tracing::warn!(
+                "Ignoring language server profiles of untrusted workspace {}",
+                self.root.display()
+            );
+            self.profiles.clear();
+        }
+        self
+    }
+
+    /// Set or replace the profile for a server
+    pub fn with_profile(mut self, server: impl Into<String>, profile: LanguageServerProfile) -> Self {
+        self.profiles.insert(server.into(), profile);
+        self
+    }
+
+    /// Launch servers installed by `lspbridge servers install` when a profile names no command
+    pub fn with_managed_servers(mut self, installer: ServerInstaller) -> Self {
+        self.managed = Some(installer);
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn get(&self, server: &str) -> Option<&LanguageServerProfile> {
+        self.profiles.get(server)
+    }
+
+    /// Configured server names
+    pub fn servers(&self) -> impl Iterator<Item = &str> {
+        self.profiles.keys().map(String::as_str)
+    }
+
+    /// Resolve how to launch a server in this project, using defaults when it has no profile
+    pub fn launch(&self, server: &str) -> LanguageServerLaunch {
+        let profile = self.profiles.get(server).cloned().unwrap_or_default();
+        let expand = |value: &str| expand_variables(value, &self.root);
+
+        let managed = match &profile.command {
+            Some(_) => None,
+            None => self.managed.as_ref().and_then(|installer| installer.binary_for(server)),
+        };
+        let program = match managed {
+            Some(binary) => binary,
+            None => {
+                let command = expand(profile.command.as_deref().unwrap_or(server));
+                if command.contains('/') || command.contains('\\') {
+                    self.root.join(&command)
+                } else {
+                    PathBuf::from(command)
+                }
+            }
+        };
+        let args = profile
+            .args
+            .unwrap_or_else(|| default_args(server))
+            .iter()
+            .map(|arg| expand(arg))
+            .collect();
+        let mut env: Vec<(String, String)> = profile
+            .env
+            .iter()
+            .map(|(key, value)| (key.clone(), expand(value)))
+            .collect();
+        env.sort();
+
+        LanguageServerLaunch {
+            server: server.to_string(),
+            program,
+            args,
+            env,
+            root: self.root.clone(),
+            initialization_options: profile
+                .initialization_options
+                .map(|options| expand_json(options, &self.root)),
+            settings: profile.settings.map(|settings| expand_json(settings, &self.root)),
+        }
+    }
+}
+
+/// A resolved language server launch for one project
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguageServerLaunch {
+    pub server: String,
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>,
+    pub root: PathBuf,
+    pub initialization_options: Option<serde_json::Value>,
+    pub settings: Option<serde_json::Value>,
+}
+
+impl LanguageServerLaunch {
+    /// Process command speaking LSP over stdio, started in the project root
+    pub fn command(&self) -> tokio::process::Command {
+        let mut command = tokio::process::Command::new(&self.program);
+        command
+            .args(&self.args)
+            .envs(self.env.iter().map(|(key, value)| (key, value)))
+            .current_dir(&self.root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        command
+    }
+
+    /// Parameters of the `initialize` request, carrying the profile's initialization options
+    pub fn initialize_params(&self, process_id: Option<u32>) -> serde_json::Value {
+        let root_uri = file_uri(&self.root);
+        let name = self
+            .root
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.root.display().to_string());
+
+        let mut params = serde_json::json!({
+            "processId": process_id,
+            "clientInfo": { "name": "lspbridge", "version": env!("CARGO_PKG_VERSION") },
+            "rootPath": self.root.display().to_string(),
+            "rootUri": root_uri,
+            "workspaceFolders": [{ "uri": root_uri, "name": name }],
+            "capabilities": {
+                "workspace": { "configuration": true, "workspaceFolders": true },
+                "textDocument": {
+                    "publishDiagnostics": {
+                        "relatedInformation": true,
+                        "tagSupport": { "valueSet": [1, 2] },
+                        "codeDescriptionSupport": true,
+                        "dataSupport": true
+                    }
+                }
+            }
+        });
+        if let Some(options) = &self.initialization_options {
+            params["initializationOptions"] = options.clone();
+        }
+        params
+    }
+
+    /// Parameters of the `workspace/didChangeConfiguration` notification, if the profile has settings
+    pub fn configuration_params(&self) -> Option<serde_json::Value> {
+        self.settings
+            .as_ref()
+            .map(|settings| serde_json::json!({ "settings": settings }))
+    }
+}
+
+/// Arguments servers need to speak LSP over stdio
+fn default_args(server: &str) -> Vec<String> {
+    match server {
+        "typescript-language-server"
+        | "pyright-langserver"
+        | "basedpyright-langserver"
+        | "vscode-json-language-server"
+        | "vscode-css-language-server"
+        | "vscode-html-language-server"
+        | "bash-language-server" => vec!["--stdio".to_string()],
+        _ => Vec::new(),
+    }
+}
+
+pub(crate) fn file_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    if path.starts_with('/') {
+        format!("file://{path}")
+    } else {
+        format!("file:///{path}")
+    }
+}
+
+/// Expand `${workspaceFolder}` and `${env:NAME}`; unknown variables are left as written
+fn expand_variables(value: &str, root: &Path) -> String {
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        expanded.push_str(&rest[..start]);
+        let variable = &rest[start + 2..start + end];
+        match variable {
+            "workspaceFolder" => expanded.push_str(&root.to_string_lossy()),
+            _ => match variable.strip_prefix("env:") {
+                Some(name) => expanded.push_str(&std::env::var(name).unwrap_or_default()),
+                None => expanded.push_str(&rest[start..start + end + 1]),
+            },
+        }
+        rest = &rest[start + end + 1..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+fn expand_json(value: serde_json::Value, root: &Path) -> serde_json::Value {
+    match value {
+        serde_json::Value::String(s) => serde_json::Value::String(expand_variables(&s, root)),
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.into_iter().map(|v| expand_json(v, root)).collect())
+        }
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.into_iter()
+                .map(|(key, v)| (key, expand_json(v, root)))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROJECT: &str = r#"
+        [language_servers.rust-analyzer]
+        initialization_options = { check = { command = "clippy", extraArgs = ["--", "-W", "clippy::pedantic"] } }
+
+        [language_servers.typescript-language-server]
+        command = "node_modules/.bin/typescript-language-server"
+        initializationOptions = { tsserver = { path = "${workspaceFolder}/node_modules/typescript/lib" } }
+
+        [language_servers.pyright-langserver]
+        env = { VIRTUAL_ENV = "${workspaceFolder}/.venv" }
+        settings = { python = { pythonPath = "${workspaceFolder}/.venv/bin/python" } }
+
+        [unrelated]
+        ignored = true
+    "#;
+
+    #[test]
+    fn test_profiles_resolve_per_project() {
+        let profiles = LanguageServerProfiles::parse("/work/app", PROJECT).unwrap();
+
+        let rust = profiles.launch("rust-analyzer");
+        assert_eq!(rust.program, PathBuf::from("rust-analyzer"));
+        assert!(rust.args.is_empty());
+        let params = rust.initialize_params(Some(42));
+        assert_eq!(params["initializationOptions"]["check"]["command"], "clippy");
+        assert_eq!(params["rootUri"], "file:///work/app");
+        assert_eq!(params["processId"], 42);
+
+        let ts = profiles.launch("typescript-language-server");
+        assert_eq!(
+            ts.program,
+            PathBuf::from("/work/app/node_modules/.bin/typescript-language-server")
+        );
+        assert_eq!(ts.args, vec!["--stdio"]);
+        assert_eq!(
+            ts.initialization_options.unwrap()["tsserver"]["path"],
+            "/work/app/node_modules/typescript/lib"
+        );
+
+        let python = profiles.launch("pyright-langserver");
+        assert_eq!(python.env, vec![("VIRTUAL_ENV".to_string(), "/work/app/.venv".to_string())]);
+        assert_eq!(
+            python.configuration_params().unwrap()["settings"]["python"]["pythonPath"],
+            "/work/app/.venv/bin/python"
+        );
+        assert!(python.initialize_params(None).get("initializationOptions").is_none());
+
+        // Servers without a profile launch with defaults
+        let gopls = profiles.launch("gopls");
+        assert_eq!(gopls.program, PathBuf::from("gopls"));
+        assert!(gopls.configuration_params().is_none());
+
+        // Untrusted workspaces don't get to pick the binary
+        let untrusted = profiles.with_trust(TrustLevel::Untrusted);
+        let ts = untrusted.launch("typescript-language-server");
+        assert_eq!(ts.program, PathBuf::from("typescript-language-server"));
+        assert!(ts.initialization_options.is_none());
+    }
+
+    #[test]
+    fn test_load_and_command() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(LanguageServerProfiles::load(dir.path()).unwrap().servers().next().is_none());
+
+        std::fs::write(
+            dir.path().join(PROJECT_CONFIG_FILE),
+            "[language_servers.clangd]\nargs = [\"--compile-commands-dir=${workspaceFolder}/build\"]\nenv = { HOME = \"/tmp\" }\n",
+        )
+        .unwrap();
+        let profiles = LanguageServerProfiles::load(dir.path()).unwrap();
+        let command = profiles.launch("clangd").command();
+        let std_command = command.as_std();
+        assert_eq!(std_command.get_program(), "clangd");
+        let expected = format!("--compile-commands-dir={}/build", dir.path().display());
+        assert_eq!(std_command.get_args().next().unwrap(), expected.as_str());
+        assert_eq!(std_command.get_current_dir(), Some(dir.path()));
+
+        std::fs::write(dir.path().join(PROJECT_CONFIG_FILE), "[language_servers.clangd]\nargs = 3\n").unwrap();
+        assert!(LanguageServerProfiles::load(dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_expand_variables() {
+        let root = Path::new("/repo");
+        assert_eq!(expand_variables("${workspaceFolder}/x", root), "/repo/x");
+        assert_eq!(expand_variables("${unknown} ${", root), "${unknown} ${");
+    }
+}
@@ -0,0 +1,333 @@
+//! License detection and license-based exclusion from AI exports
+//!
+//! A file's license is taken from an `SPDX-License-Identifier` header near
+//! the top of the file, or else from the nearest `LICENSE`/`COPYING` file in
+//! its directory or a parent directory. License files are identified by
+//! their own SPDX line or by the wording of common licenses; unrecognized
+//! license files that reserve all rights count as `LicenseRef-Proprietary`.
+//!
+//! [`LicenseRule`]s under `privacy.license_rules` name licenses whose code
+//! must not reach AI assistants, for example vendored proprietary SDKs:
+//!
+//! ```toml
+//! [[privacy.license_rules]]
+//! licenses = ["LicenseRef-*", "GPL-3.0*"]
+//! action = "redact"
+//! ```
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Bytes at the top of a file searched for an SPDX header
+const HEADER_BYTES: u64 = 4096;
+
+/// Names of license files, checked in order
+const LICENSE_FILES: &[&str] = &[
+    "LICENSE",
+    "LICENSE.md",
+    "LICENSE.txt",
+    "LICENCE",
+    "LICENCE.md",
+    "LICENCE.txt",
+    "COPYING",
+    "COPYING.md",
+    "COPYING.txt",
+];
+
+/// Identifier used for license files that reserve all rights
+pub const PROPRIETARY: &str = "LicenseRef-Proprietary";
+
+/// Where a file's license was found
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseSource {
+    /// An `SPDX-License-Identifier` line in the file itself
+    Header,
+    /// The nearest license file in an enclosing directory
+    LicenseFile(PathBuf),
+}
+
+/// License of a file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LicenseInfo {
+    /// SPDX license expression, e.g. `MIT OR Apache-2.0`
+    pub spdx: String,
+    pub source: LicenseSource,
+}
+
+/// What happens to files under a matching license in AI exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseAction {
+    /// Leave the file's diagnostics out entirely
+    Exclude,
+    /// Keep the diagnostics but withhold the file's code
+    Redact,
+}
+
+impl fmt::Display for LicenseAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LicenseAction::Exclude => f.write_str("excluded"),
+            LicenseAction::Redact => f.write_str("code withheld"),
+        }
+    }
+}
+
+/// Licenses whose code is kept out of AI exports
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LicenseRule {
+    /// SPDX identifiers; `*` matches any suffix (`LicenseRef-*`)
+    pub licenses: Vec<String>,
+    pub action: LicenseAction,
+}
+
+impl LicenseRule {
+    /// Whether the rule covers an SPDX expression
+    ///
+    /// An expression matches when any license it names matches, so
+    /// `MIT OR LicenseRef-Acme` is covered by a `LicenseRef-*` rule.
+    pub fn matches(&self, expression: &str) -> bool {
+        spdx_identifiers(expression).any(|id| {
+            self.licenses.iter().any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => id.to_lowercase().starts_with(&prefix.to_lowercase()),
+                None => id.eq_ignore_ascii_case(pattern),
+            })
+        })
+    }
+}
+
+/// A file kept out of, or redacted in, an AI export because of its license
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LicenseExclusion {
+    pub file: String,
+    pub license: LicenseInfo,
+    pub action: LicenseAction,
+}
+
+/// License identifiers in an SPDX expression, without operators
+fn spdx_identifiers(expression: &str) -> impl Iterator<Item = &str> {
+    expression
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+        .filter(|token| !token.is_empty() && !matches!(token.to_uppercase().as_str(), "AND" | "OR" | "WITH"))
+}
+
+/// SPDX expression from an `SPDX-License-Identifier` line in `content`
+pub fn spdx_header(content: &str) -> Option<String> {
+    content.lines().take(40).find_map(|line| {
+        let (_, rest) = line.split_once("SPDX-License-Identifier:")?;
+        let expression = rest
+            .trim()
+            .trim_end_matches("*/")
+            .trim_end_matches("-->")
+            .trim();
+        (!expression.is_empty()).then(|| expression.to_string())
+    })
+}
+
+/// SPDX identifier of a license file's text, if it is a common license
+pub fn identify_license_text(text: &str) -> Option<String> {
+    if let Some(spdx) = spdx_header(text) {
+        return Some(spdx);
+    }
+
+    let lower = text.to_lowercase();
+    let has = |phrase: &str| lower.contains(phrase);
+    let id = if has("mit license") || has("permission is hereby granted, free of charge") {
+        "MIT"
+    } else if has("apache license") && has("version 2.0") {
+        "Apache-2.0"
+    } else if has("gnu lesser general public license") {
+        if has("version 3") { "LGPL-3.0" } else { "LGPL-2.1" }
+    } else if has("gnu affero general public license") {
+        "AGPL-3.0"
+    } else if has("gnu general public license") {
+        if has("version 3") { "GPL-3.0" } else { "GPL-2.0" }
+    } else if has("mozilla public license") {
+        "MPL-2.0"
+    } else if has("redistribution and use in source and binary forms") {
+        if has("neither the name") { "BSD-3-Clause" } else { "BSD-2-Clause" }
+    } else if has("isc license") || has("permission to use, copy, modify, and/or distribute") {
+        "ISC"
+    } else if has("this is free and unencumbered software released into the public domain") {
+        "Unlicense"
+    } else if has("all rights reserved") || has("proprietary") || has("confidential") {
+        PROPRIETARY
+    } else {
+        return None;
+    };
+    Some(id.to_string())
+}
+
+/// Finds the license of files, caching license files per directory
+#[derive(Debug, Default)]
+pub struct LicenseDetector {
+    /// Directory above which no license files are looked for
+    root: Option<PathBuf>,
+    directories: Mutex<HashMap<PathBuf, Option<LicenseInfo>>>,
+}
+
+impl LicenseDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop looking for license files at `root`, typically the workspace root
+    pub fn with_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = Some(root.into());
+        self
+    }
+
+    /// License of `file`, from its SPDX header or the nearest license file
+    pub fn detect(&self, file: &Path) -> Option<LicenseInfo> {
+        if let Some(spdx) = read_header(file).ok().as_deref().and_then(spdx_header) {
+            return Some(LicenseInfo {
+                spdx,
+                source: LicenseSource::Header,
+            });
+        }
+        self.directory_license(file.parent()?)
+    }
+
+    fn directory_license(&self, dir: &Path) -> Option<LicenseInfo> {
+        if let Some(cached) = self.directories.lock().ok()?.get(dir) {
+            return cached.clone();
+        }
+
+        let license = LICENSE_FILES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+            .and_then(|path| {
+                let text = std::fs::read_to_string(&path).ok()?;
+                Some(LicenseInfo {
+                    spdx: identify_license_text(&text)?,
+                    source: LicenseSource::LicenseFile(path),
+                })
+            })
+            .or_else(|| {
+                let at_root = self.root.as_deref().is_some_and(|root| dir == root);
+                if at_root {
+                    None
+                } else {
+                    self.directory_license(dir.parent()?)
+                }
+            });
+
+        if let Ok(mut directories) = self.directories.lock() {
+            directories.insert(dir.to_path_buf(), license.clone());
+        }
+        license
+    }
+}
+
+fn read_header(file: &Path) -> Result<String> {
+    let mut header = Vec::new();
+    std::fs::File::open(file)?.take(HEADER_BYTES).read_to_end(&mut header)?;
+    Ok(String::from_utf8_lossy(&header).into_owned())
+}
+
+/// Applies license rules to the files of an export
+#[derive(Debug)]
+pub struct LicenseFilter {
+    detector: LicenseDetector,
+    rules: Vec<LicenseRule>,
+}
+
+impl LicenseFilter {
+    pub fn new(detector: LicenseDetector, rules: Vec<LicenseRule>) -> Self {
+        Self { detector, rules }
+    }
+
+    /// Filter for the rules of a privacy policy, or `None` when it has none
+    pub fn from_rules(root: &Path, rules: &[LicenseRule]) -> Option<Self> {
+        (!rules.is_empty()).then(|| Self::new(LicenseDetector::new().with_root(root), rules.to_vec()))
+    }
+
+    /// The exclusion applying to `file`, if its license matches a rule
+    ///
+    /// When several rules match, excluding wins over redacting.
+    pub fn check(&self, file: &str) -> Option<LicenseExclusion> {
+        let license = self.detector.detect(Path::new(file))?;
+        let action = self
+            .rules
+            .iter()
+            .filter(|rule| rule.matches(&license.spdx))
+            .map(|rule| rule.action)
+            .min_by_key(|action| match action {
+                LicenseAction::Exclude => 0,
+                LicenseAction::Redact => 1,
+            })?;
+        Some(LicenseExclusion {
+            file: file.to_string(),
+            license,
+            action,
+        })
+    }
+
+    /// Exclusions for each distinct file, in the order files are first listed
+    pub fn check_all<'a>(&self, files: impl IntoIterator<Item = &'a str>) -> Vec<LicenseExclusion> {
+        let mut seen = std::collections::HashSet::new();
+        files
+            .into_iter()
+            .filter(|file| seen.insert(*file))
+            .filter_map(|file| self.check(file))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_headers_license_files_and_rules() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let vendor = dir.path().join("vendor/acme-sdk/src");
+        std::fs::create_dir_all(&vendor)?;
+        std::fs::write(
+            dir.path().join("vendor/acme-sdk/LICENSE"),
+            "Copyright (c) Acme Corp. All rights reserved.\nConfidential and proprietary.",
+        )?;
+        std::fs::write(vendor.join("client.ts"), "export const client = 1;\n")?;
+        std::fs::write(dir.path().join("LICENSE"), "MIT License\n\nPermission is hereby granted, free of charge...")?;
+        std::fs::write(dir.path().join("app.ts"), "// SPDX-License-Identifier: GPL-3.0-only\nlet x = 1;\n")?;
+        std::fs::write(dir.path().join("main.ts"), "let y = 2;\n")?;
+
+        let detector = LicenseDetector::new().with_root(dir.path());
+        let vendored = detector.detect(&vendor.join("client.ts")).unwrap();
+        assert_eq!(vendored.spdx, PROPRIETARY);
+        assert!(matches!(vendored.source, LicenseSource::LicenseFile(_)));
+        assert_eq!(detector.detect(&dir.path().join("app.ts")).unwrap().spdx, "GPL-3.0-only");
+        assert_eq!(detector.detect(&dir.path().join("main.ts")).unwrap().spdx, "MIT");
+
+        let filter = LicenseFilter::new(
+            detector,
+            vec![
+                LicenseRule { licenses: vec!["GPL-3.0*".to_string()], action: LicenseAction::Redact },
+                LicenseRule { licenses: vec!["LicenseRef-*".to_string()], action: LicenseAction::Exclude },
+            ],
+        );
+        let file = |path: PathBuf| path.to_string_lossy().into_owned();
+        let files = [
+            file(vendor.join("client.ts")),
+            file(dir.path().join("app.ts")),
+            file(dir.path().join("main.ts")),
+            file(vendor.join("client.ts")),
+        ];
+        let exclusions = filter.check_all(files.iter().map(String::as_str));
+        assert_eq!(exclusions.len(), 2);
+        assert_eq!(exclusions[0].action, LicenseAction::Exclude);
+        assert_eq!(exclusions[1].action, LicenseAction::Redact);
+
+        let rule = LicenseRule { licenses: vec!["mit".to_string()], action: LicenseAction::Redact };
+        assert!(rule.matches("(MIT OR Apache-2.0)"));
+        assert!(!rule.matches("MIT-0"));
+        Ok(())
+    }
+}
@@ -1,36 +1,79 @@
+pub mod api_surface;
 pub mod async_processor;
+pub mod audit_log;
+pub mod backup;
+pub mod baseline;
+pub mod calendar;
 pub mod config;
+pub mod crash_reports;
+pub mod daemon;
 pub mod constants;
 pub mod context_ranking;
 pub mod database_pool;
+pub mod debt;
+pub mod dedup;
 pub mod dependency_analyzer;
 pub mod diagnostic_grouping;
 pub mod diagnostic_prioritization;
 pub mod error_recovery;
 pub mod errors;
+pub mod export_routing;
+pub mod file_guard;
+pub mod generated_code;
+pub mod graph;
 pub mod incremental_processor;
 pub mod io_utils;
+pub mod language_detection;
+pub mod language_servers;
+pub mod license;
 pub mod macros;
 pub mod memory_manager;
 pub mod metrics;
+pub mod net;
+pub mod noise;
+pub mod ownership;
 pub mod performance_optimizer;
 pub mod persistent_cache;
+pub mod quick_stats;
 pub mod rate_limiter;
 pub mod security_config;
 pub mod semantic_context;
+pub mod server_install;
+pub mod severity_rules;
+pub mod source_watcher;
+pub mod static_scan;
+pub mod symbol_index;
+pub mod symbol_parser;
+pub mod symbol_store;
+pub mod telemetry;
 pub mod traits;
+pub mod triage;
 pub mod types;
+pub mod usage;
 pub mod utils;
+pub mod workspace_trust;
 // pub mod enhanced_processor;
 pub mod dynamic_config;
 pub mod git_integration;
 pub mod health_dashboard;
 pub mod simple_enhanced_processor;
 
+pub use api_surface::{ApiSurfaceAnalyzer, ApiSurfaceInfo, SemverImpact, API_SURFACE_KEY};
+pub use backup::{
+    restore_database, BackupCatalog, BackupConfig, BackupGeneration, DatabaseArchiver, RestorePoint, RestoreReport,
+};
+pub use baseline::{Baseline, BaselineAction, BaselineEntry, BaselineOutcome, BASELINE_FILE};
+pub use calendar::{CalendarConfig, CalendarUnit, TimeZoneSetting};
+pub use daemon::{ControlHandler, ControlResponse, ControlRouter, Daemon, DaemonClient, LockOwner, LockRole, StoreLock};
+pub use crash_reports::{
+    CrashCorrelation, CrashCorrelator, CrashFrame, CrashKind, CrashReport, CrashReportParser, CRASH_KEY,
+};
 pub use context_ranking::{
     format_context_for_ai, BudgetOptimizedContext, ContextContent, ContextElement,
     ContextElementType, ContextRanker, PriorityConfig, RankedContext, TokenWeights,
 };
+pub use debt::{DebtAction, DebtConfig, DebtFormat, DebtItem, DebtKind, DebtReport, DebtTracker};
+pub use dedup::{dedupe, DedupKey, DedupOutcome, Deduplicator, DuplicateInfo, DuplicateLocation, DEDUP_KEY};
 pub use dependency_analyzer::{
     DependencyAnalyzer, DependencyGraph, ExportInfo, ExternalFunctionCall, FileDependencies,
     ImportDependency, TypeReference,
@@ -40,25 +83,62 @@ pub use diagnostic_prioritization::{
     DiagnosticPrioritizer, FixRecommendation, PrioritizationSummary, PrioritizedDiagnostic,
 };
 pub use error_recovery::{
-    CircuitBreaker, ErrorEvent, ErrorRecoverySystem, ErrorSeverity, RecoveryAction,
-    RecoveryStrategy,
+    BreakerAction, BreakerStatus, CircuitBreaker, CircuitState, ErrorEvent, ErrorRecoverySystem,
+    ErrorSeverity, RecoveryAction, RecoveryStrategy, Subsystem,
+};
+pub use export_routing::{ExportRoute, ExportRoutingConfig, RoutedDiagnostics};
+pub use file_guard::{FileGuard, SkipReason, SkippedFile};
+pub use language_detection::{detect_file_language, detect_language, DetectedLanguage, EmbeddedBlock};
+pub use graph::{GraphAction, GraphEdge, GraphFormat, GraphKind, GraphNode, RelationGraph};
+pub use generated_code::{
+    GeneratedCodeConfig, GeneratedCodeMapper, GeneratedCodeRule, GeneratedOrigin, GENERATED_KEY,
+};
+pub use language_servers::{
+    LanguageServerLaunch, LanguageServerProfile, LanguageServerProfiles, PROJECT_CONFIG_FILE,
+};
+pub use license::{
+    LicenseAction, LicenseDetector, LicenseExclusion, LicenseFilter, LicenseInfo, LicenseRule, LicenseSource,
 };
 pub use incremental_processor::{FileEntry, FileHash, IncrementalProcessor, ProcessingStats};
 pub use memory_manager::{BoundedCache, EvictionPolicy, MemoryConfig, MemoryReport};
 pub use metrics::{HealthStatus, MetricsCollector, PerformanceSummary, ProcessingMetrics};
+pub use net::{
+    deliver, Delivery, NetError, NetworkAudit, NetworkConfig, NetworkGuard, NetworkMode, OfflineQueue,
+    QueuedOperation, RetryPolicy,
+};
+pub use noise::{
+    noise_key, NoiseConfig, NoiseDecision, NoiseModel, NoiseOutcome, NoiseReport, NoiseStats,
+    NoisyPattern,
+};
+pub use ownership::{OwnershipMap, OwnershipMatch, OwnershipRule, CODEOWNERS_LOCATIONS};
 pub use persistent_cache::{CacheConfig, CacheEntry as PersistentCacheEntry, PersistentCache};
+pub use quick_stats::{HotFile, NextCommand, QuickStats, StatsSource, WorkspaceStats};
 pub use semantic_context::{
     CallHierarchy, ClassContext, ContextExtractor, DependencyInfo, DependencyType, FunctionCall,
     FunctionContext, ImportContext, SemanticContext, TypeDefinition, VariableContext,
 };
+pub use server_install::{
+    InstalledServer, KnownServer, ServerInstaller, ServerPin, ServersAction, ServersConfig,
+};
+pub use severity_rules::{SeverityRule, SeverityRules, SeverityRulesConfig, ORIGINAL_SEVERITY_KEY};
+pub use source_watcher::SourceWatcher;
+pub use static_scan::{ScanConfig, ScanReport, ScanRule, StaticScanner, SCAN_SOURCE};
+pub use symbol_index::{SymbolDefinition, SymbolIndex, SymbolKind, SymbolOccurrence};
+pub use symbol_store::SymbolStore;
 pub use traits::*;
+pub use triage::{RelatedIssue, TriageEngine, TriageSuggestion};
 pub use types::*;
+pub use usage::{
+    ApiAction, QuotaConfig, QuotaLimits, UsageAccounting, UsageRecord, UsageStore,
+};
+pub use workspace_trust::{TrustLevel, WorkspaceTrust};
 // pub use enhanced_processor::{EnhancedIncrementalProcessor, EnhancedProcessorConfig, ComprehensiveStats, OverallHealthStatus};
 pub use async_processor::{
     AsyncDiagnosticProcessor, ProcessedDiagnostic, ProcessingStats as AsyncProcessingStats,
 };
+pub use audit_log::{AuditEvent, AuditLog};
 pub use dynamic_config::{
-    ConfigChange, DynamicConfig, DynamicConfigManager,
+    ConfigChange, DynamicConfig, DynamicConfigManager, DynamicPrivacyConfig,
 };
 pub use errors::{
     AnalysisError, CacheError, ConfigError, DatabaseError, ExportError, FileError,
@@ -68,7 +148,8 @@ pub use git_integration::{GitFileInfo, GitFileStatus, GitIntegration, GitReposit
 pub use health_dashboard::{
     AlertSeverity, AlertThresholds, ComponentHealth, ComponentMetrics, ComponentStatus,
     DashboardMetrics, EffortLevel, HealthAlert, HealthDashboard, HealthMonitor, ImpactLevel,
-    MonitoringConfig, PerformanceRecommendation, SystemHealthStatus,
+    MonitoringConfig, PerformanceRecommendation, PluginHealth, PluginMetric, SubprojectHealth,
+    SystemHealthStatus,
 };
 pub use simple_enhanced_processor::{
     PerformanceSummary as SimplePerformanceSummary, SimpleEnhancedConfig, SimpleEnhancedProcessor,
@@ -0,0 +1,214 @@
+//! Offline mode
+//!
+//! `--offline` or `mode = "deny"` under `[network]` forbids every outbound
+//! network call for the run, whatever webhooks, summary providers or server
+//! installers are configured. Each integration asks the process-wide
+//! [`NetworkGuard`] before it touches the network, and the guard records
+//! every attempt, so at the end of an offline run a [`NetworkAudit`] can be
+//! appended to `network-audit.jsonl` as evidence that nothing left the machine.
+
+use super::NetError;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Attempts kept in detail; later ones are only counted
+const MAX_RECORDED_ATTEMPTS: usize = 100;
+
+/// Whether outbound network access is allowed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkMode {
+    #[default]
+    Allow,
+    /// No outbound network calls at all
+    Deny,
+}
+
+/// One outbound network attempt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkAttempt {
+    pub at: DateTime<Utc>,
+    /// What wanted the network, e.g. `webhook` or `servers install pyright`
+    pub purpose: String,
+    pub allowed: bool,
+}
+
+/// Record of one run's network use
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkAudit {
+    pub mode: NetworkMode,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    /// Command line of the run
+    pub command: Vec<String>,
+    pub succeeded: bool,
+    /// Network calls that went ahead
+    pub allowed: usize,
+    /// Network calls that were refused
+    pub blocked: usize,
+    /// The first attempts in detail
+    pub attempts: Vec<NetworkAttempt>,
+    /// No network call was made during the run
+    pub fully_local: bool,
+}
+
+impl NetworkAudit {
+    /// `network-audit.jsonl` in the user's data directory
+    pub fn default_path() -> PathBuf {
+        crate::config::data_dir()
+            .unwrap_or_else(|_| std::env::temp_dir().join("lspbridge"))
+            .join("network-audit.jsonl")
+    }
+
+    /// Append the record as one JSON line
+    pub fn append_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open network audit log {}", path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct GuardState {
+    mode: NetworkMode,
+    allowed: usize,
+    blocked: usize,
+    attempts: Vec<NetworkAttempt>,
+}
+
+/// Gatekeeper every outbound integration checks before using the network
+#[derive(Debug)]
+pub struct NetworkGuard {
+    started_at: DateTime<Utc>,
+    state: Mutex<GuardState>,
+}
+
+impl Default for NetworkGuard {
+    fn default() -> Self {
+        Self::new(NetworkMode::Allow)
+    }
+}
+
+impl NetworkGuard {
+    pub fn new(mode: NetworkMode) -> Self {
+        Self {
+            started_at: Utc::now(),
+            state: Mutex::new(GuardState {
+                mode,
+                allowed: 0,
+                blocked: 0,
+                attempts: Vec::new(),
+            }),
+        }
+    }
+
+    /// The guard shared by the whole process, allowing access until told otherwise
+    pub fn global() -> &'static NetworkGuard {
+        static GLOBAL: OnceLock<NetworkGuard> = OnceLock::new();
+        GLOBAL.get_or_init(NetworkGuard::default)
+    }
+
+    pub fn set_mode(&self, mode: NetworkMode) {
+        self.lock().mode = mode;
+    }
+
+    pub fn mode(&self) -> NetworkMode {
+        self.lock().mode
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.mode() == NetworkMode::Deny
+    }
+
+    /// Record an attempt to use the network for `purpose`, failing if access is denied
+    pub fn check(&self, purpose: impl Into<String>) -> Result<(), NetError> {
+        let purpose = purpose.into();
+        let mut state = self.lock();
+        let allowed = state.mode == NetworkMode::Allow;
+        if allowed {
+            state.allowed += 1;
+        } else {
+            state.blocked += 1;
+            tracing::debug!("Offline mode blocked network access for {}", purpose);
+        }
+        if state.attempts.len() < MAX_RECORDED_ATTEMPTS {
+            state.attempts.push(NetworkAttempt {
+                at: Utc::now(),
+                purpose: purpose.clone(),
+                allowed,
+            });
+        }
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(NetError::Denied(purpose))
+        }
+    }
+
+    /// Summary of the network use so far
+    pub fn audit(&self, command: Vec<String>, succeeded: bool) -> NetworkAudit {
+        let state = self.lock();
+        NetworkAudit {
+            mode: state.mode,
+            started_at: self.started_at,
+            finished_at: Utc::now(),
+            command,
+            succeeded,
+            allowed: state.allowed,
+            blocked: state.blocked,
+            attempts: state.attempts.clone(),
+            fully_local: state.allowed == 0,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, GuardState> {
+        // The state stays consistent even if a holder panicked
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_denied_attempts_are_blocked_and_audited() -> Result<()> {
+        let guard = NetworkGuard::new(NetworkMode::Allow);
+        guard.check("webhook")?;
+        assert!(!guard.audit(Vec::new(), true).fully_local);
+
+        let guard = NetworkGuard::new(NetworkMode::Deny);
+        let err = guard.check("servers install pyright").unwrap_err();
+        assert!(!err.is_retryable());
+        assert!(err.to_string().contains("servers install pyright"));
+
+        let audit = guard.audit(vec!["lsp-bridge".to_string(), "--offline".to_string()], true);
+        assert!(audit.fully_local);
+        assert_eq!((audit.allowed, audit.blocked), (0, 1));
+        assert!(!audit.attempts[0].allowed);
+
+        let dir = tempfile::tempdir()?;
+        let log = dir.path().join("network-audit.jsonl");
+        audit.append_to(&log)?;
+        audit.append_to(&log)?;
+        let lines: Vec<NetworkAudit> = std::fs::read_to_string(&log)?
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?;
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].mode, NetworkMode::Deny);
+        Ok(())
+    }
+}
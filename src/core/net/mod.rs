@@ -0,0 +1,192 @@
+//! Shared networking utilities
+//!
+//! Everything that talks to a remote service (webhooks, remote caches,
+//! issue sync, agent push) goes through the same two pieces:
+//!
+//! - [`RetryPolicy`] retries transient failures with exponential backoff and
+//!   jitter, and gives up immediately on permanent ones.
+//! - [`OfflineQueue`] persists operations that still fail once retries run
+//!   out, so nothing is lost while offline. A long-running process drains
+//!   the queue with [`OfflineQueue::spawn_drainer`] once connectivity returns.
+//!
+//! [`deliver`] combines the two for fire-and-forget operations. All of them
+//! first ask the [`NetworkGuard`], which refuses every call in offline mode.
+
+pub mod guard;
+pub mod offline_queue;
+pub mod retry;
+
+pub use guard::{NetworkAttempt, NetworkAudit, NetworkGuard, NetworkMode};
+pub use offline_queue::{DrainSummary, OfflineQueue, QueuedOperation};
+pub use retry::{NetworkConfig, RetryPolicy};
+
+use std::future::Future;
+use std::time::Duration;
+
+/// Failure of a network operation
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum NetError {
+    /// The remote could not be reached, e.g. DNS failure or connection refused
+    #[error("network unreachable: {0}")]
+    Unreachable(String),
+    #[error("request timed out after {0:?}")]
+    Timeout(Duration),
+    /// The remote answered with an unsuccessful status
+    #[error("server returned {status}: {message}")]
+    Status { status: u16, message: String },
+    /// A failure that retrying cannot fix, e.g. an invalid payload
+    #[error("{0}")]
+    Permanent(String),
+    /// Offline mode forbids network access for this purpose
+    #[error("network access denied in offline mode: {0}")]
+    Denied(String),
+}
+
+impl NetError {
+    /// Error for an unsuccessful HTTP status
+    pub fn from_status(status: u16, message: impl Into<String>) -> Self {
+        Self::Status {
+            status,
+            message: message.into(),
+        }
+    }
+
+    /// Whether trying again later may succeed
+    ///
+    /// Connectivity problems, timeouts, rate limiting and server errors are
+    /// retryable; other client errors are not.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Unreachable(_) | Self::Timeout(_) => true,
+            Self::Status { status, .. } => matches!(status, 408 | 425 | 429 | 500..=599),
+            Self::Permanent(_) | Self::Denied(_) => false,
+        }
+    }
+}
+
+/// How [`deliver`] disposed of an operation
+#[derive(Debug, Clone, PartialEq)]
+pub enum Delivery {
+    Delivered,
+    /// Retries ran out on a transient failure; the operation was queued under this id
+    Queued(String),
+}
+
+/// Send an operation now, queueing it for later if the network is unavailable
+///
+/// `send` is retried per `policy`. If it still fails with a retryable error
+/// the operation is added to `queue`; permanent errors are returned. In
+/// offline mode nothing is sent or queued.
+pub async fn deliver<F, Fut>(
+    policy: &RetryPolicy,
+    queue: &OfflineQueue,
+    kind: &str,
+    payload: serde_json::Value,
+    send: F,
+) -> anyhow::Result<Delivery>
+where
+    F: Fn(&serde_json::Value) -> Fut,
+    Fut: Future<Output = Result<(), NetError>>,
+{
+    NetworkGuard::global()
+        .check(kind)
+        .map_err(|e| anyhow::anyhow!("{kind} failed: {e}"))?;
+    match policy.retry(|_| send(&payload)).await {
+        Ok(()) => Ok(Delivery::Delivered),
+        Err(e) if e.is_retryable() => {
+            let operation = queue.enqueue_failed(kind, payload, &e)?;
+            tracing::warn!("{} could not be sent ({}), queued as {}", kind, e, operation.id);
+            Ok(Delivery::Queued(operation.id))
+        }
+        Err(e) => Err(anyhow::anyhow!("{kind} failed: {e}")),
+    }
+}
+
+/// POST `body` as JSON to `url`
+///
+/// Sends a single request; callers retry through [`RetryPolicy`] or
+/// [`deliver`]. Needs the `network` feature, without which every call fails
+/// permanently.
+pub async fn post_json(
+    url: &str,
+    headers: &[(String, String)],
+    body: &serde_json::Value,
+    timeout: Duration,
+) -> Result<(), NetError> {
+    #[cfg(feature = "network")]
+    {
+        let mut request = reqwest::Client::new().post(url).timeout(timeout).json(body);
+        for (name, value) in headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        let response = request.send().await.map_err(|e| {
+            if e.is_timeout() {
+                NetError::Timeout(timeout)
+            } else if e.is_builder() {
+                NetError::Permanent(e.to_string())
+            } else {
+                NetError::Unreachable(e.to_string())
+            }
+        })?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let message = response.text().await.unwrap_or_default();
+        Err(NetError::from_status(status.as_u16(), message))
+    }
+    #[cfg(not(feature = "network"))]
+    {
+        let _ = (headers, body, timeout);
+        Err(NetError::Permanent(format!(
+            "cannot POST to {url}: lspbridge was built without the `network` feature"
+        )))
+    }
+}
+
+/// Whether a TCP connection to `address` (`host:port`) opens within `timeout`
+///
+/// Always false in offline mode.
+pub async fn is_reachable(address: &str, timeout: Duration) -> bool {
+    if NetworkGuard::global().check(format!("reachability check of {address}")).is_err() {
+        return false;
+    }
+    matches!(
+        tokio::time::timeout(timeout, tokio::net::TcpStream::connect(address)).await,
+        Ok(Ok(_))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_deliver_queues_on_transient_failure() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let queue = OfflineQueue::new(dir.path());
+        let policy = RetryPolicy {
+            max_attempts: 2,
+            initial_delay: Duration::from_millis(1),
+            ..RetryPolicy::default()
+        };
+        let payload = serde_json::json!({ "text": "build failed" });
+
+        let sent = deliver(&policy, &queue, "webhook", payload.clone(), |_| async { Ok(()) }).await?;
+        assert_eq!(sent, Delivery::Delivered);
+
+        let offline = |_: &serde_json::Value| async { Err(NetError::Unreachable("no route to host".to_string())) };
+        let Delivery::Queued(id) = deliver(&policy, &queue, "webhook", payload.clone(), offline).await? else {
+            panic!("expected the operation to be queued");
+        };
+        let pending = queue.pending()?;
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, id);
+        assert_eq!(pending[0].payload, payload);
+
+        let rejected = |_: &serde_json::Value| async { Err(NetError::from_status(400, "bad payload")) };
+        assert!(deliver(&policy, &queue, "webhook", payload, rejected).await.is_err());
+        assert_eq!(queue.len()?, 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,305 @@
+//! Durable queue of network operations that failed while offline
+//!
+//! Each operation is one JSON file in the queue directory, written to a
+//! temporary file and renamed into place so a crash never leaves a partial
+//! entry. Operations are drained oldest first; draining stops at the first
+//! retryable failure, since that usually means the network is still down.
+//! Operations that fail permanently are moved to `failed/` for inspection
+//! instead of being retried forever.
+
+use super::{NetError, RetryPolicy};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Subdirectory holding operations that failed permanently
+const FAILED_DIR: &str = "failed";
+
+/// A network operation waiting to be sent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuedOperation {
+    pub id: String,
+    /// What the operation is, e.g. `webhook` or `issue_sync`; used to pick a sender
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub enqueued_at: DateTime<Utc>,
+    /// Failed attempts so far
+    pub attempts: u32,
+    /// Earliest time of the next attempt
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+}
+
+/// Outcome of one drain pass
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DrainSummary {
+    pub delivered: usize,
+    /// Operations moved to `failed/` after a permanent error
+    pub failed: usize,
+    /// Operations still queued
+    pub remaining: usize,
+}
+
+/// On-disk queue of operations to retry once connectivity returns
+#[derive(Debug, Clone)]
+pub struct OfflineQueue {
+    dir: PathBuf,
+}
+
+impl OfflineQueue {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// `offline-queue` in the user's data directory
+    pub fn default_dir() -> PathBuf {
+        crate::config::data_dir()
+            .unwrap_or_else(|_| std::env::temp_dir().join("lspbridge"))
+            .join("offline-queue")
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Queue an operation for its first attempt as soon as possible
+    pub fn enqueue(&self, kind: &str, payload: serde_json::Value) -> Result<QueuedOperation> {
+        let now = Utc::now();
+        let operation = QueuedOperation {
+            id: format!("{}-{}", now.format("%Y%m%dT%H%M%S%.6f"), uuid::Uuid::new_v4().simple()),
+            kind: kind.to_string(),
+            payload,
+            enqueued_at: now,
+            attempts: 0,
+            next_attempt_at: now,
+            last_error: None,
+        };
+        self.write(&operation)?;
+        Ok(operation)
+    }
+
+    /// Queue an operation whose immediate delivery already failed
+    pub fn enqueue_failed(&self, kind: &str, payload: serde_json::Value, error: &NetError) -> Result<QueuedOperation> {
+        let mut operation = self.enqueue(kind, payload)?;
+        operation.attempts = 1;
+        operation.last_error = Some(error.to_string());
+        self.write(&operation)?;
+        Ok(operation)
+    }
+
+    /// Queued operations, oldest first; unreadable entries are skipped
+    pub fn pending(&self) -> Result<Vec<QueuedOperation>> {
+        Self::read_dir(&self.dir)
+    }
+
+    /// Operations that failed permanently, oldest first
+    pub fn failed(&self) -> Result<Vec<QueuedOperation>> {
+        Self::read_dir(&self.dir.join(FAILED_DIR))
+    }
+
+    pub fn len(&self) -> Result<usize> {
+        Ok(self.pending()?.len())
+    }
+
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Remove a queued operation; returns whether it existed
+    pub fn remove(&self, id: &str) -> Result<bool> {
+        let path = self.entry_path(&self.dir, id);
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e).with_context(|| format!("Failed to remove {}", path.display())),
+        }
+    }
+
+    /// Try to send every operation that is due
+    ///
+    /// Delivered operations are removed. A retryable failure reschedules the
+    /// operation using `policy`'s backoff and ends the pass. Operations whose
+    /// attempts reach `policy.max_attempts` are still kept: being offline for
+    /// a long time shouldn't lose data. In offline mode nothing is sent.
+    pub async fn drain<F, Fut>(&self, policy: &RetryPolicy, send: F) -> Result<DrainSummary>
+    where
+        F: Fn(&QueuedOperation) -> Fut,
+        Fut: Future<Output = Result<(), NetError>>,
+    {
+        let mut summary = DrainSummary::default();
+        let pending = self.pending()?;
+        if !pending.is_empty() && super::NetworkGuard::global().check("offline queue drain").is_err() {
+            summary.remaining = pending.len();
+            return Ok(summary);
+        }
+        let now = Utc::now();
+
+        let mut operations = pending.into_iter();
+        for mut operation in operations.by_ref() {
+            if operation.next_attempt_at > now {
+                summary.remaining += 1;
+                continue;
+            }
+            match send(&operation).await {
+                Ok(()) => {
+                    self.remove(&operation.id)?;
+                    summary.delivered += 1;
+                }
+                Err(e) if e.is_retryable() => {
+                    operation.attempts += 1;
+                    let delay = policy.delay(operation.attempts.min(policy.max_attempts.max(1)));
+                    operation.next_attempt_at = Utc::now()
+                        + chrono::Duration::from_std(delay).unwrap_or_else(|_| chrono::Duration::seconds(60));
+                    operation.last_error = Some(e.to_string());
+                    self.write(&operation)?;
+                    summary.remaining += 1;
+                    debug!("Still offline ({}), stopping queue drain", e);
+                    break;
+                }
+                Err(e) => {
+                    warn!("Queued {} {} failed permanently: {}", operation.kind, operation.id, e);
+                    operation.attempts += 1;
+                    operation.last_error = Some(e.to_string());
+                    self.move_to_failed(&operation)?;
+                    summary.failed += 1;
+                }
+            }
+        }
+        summary.remaining += operations.count();
+
+        Ok(summary)
+    }
+
+    /// Drain the queue every `interval` in the background
+    ///
+    /// Meant for long-running processes such as a daemon, so queued
+    /// operations go out shortly after connectivity returns.
+    pub fn spawn_drainer<F, Fut>(
+        self: Arc<Self>,
+        interval: Duration,
+        policy: RetryPolicy,
+        send: F,
+    ) -> tokio::task::JoinHandle<()>
+    where
+        F: Fn(&QueuedOperation) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), NetError>> + Send,
+    {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match self.drain(&policy, &send).await {
+                    Ok(summary) if summary.delivered > 0 || summary.failed > 0 => debug!(
+                        "Offline queue drained: {} delivered, {} failed, {} remaining",
+                        summary.delivered, summary.failed, summary.remaining
+                    ),
+                    Ok(_) => {}
+                    Err(e) => warn!("Failed to drain offline queue: {}", e),
+                }
+            }
+        })
+    }
+
+    fn entry_path(&self, dir: &Path, id: &str) -> PathBuf {
+        dir.join(format!("{id}.json"))
+    }
+
+    fn write(&self, operation: &QueuedOperation) -> Result<()> {
+        self.write_in(&self.dir, operation)
+    }
+
+    fn write_in(&self, dir: &Path, operation: &QueuedOperation) -> Result<()> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create offline queue directory {}", dir.display()))?;
+        let path = self.entry_path(dir, &operation.id);
+        let temp = path.with_extension("json.tmp");
+        std::fs::write(&temp, serde_json::to_vec_pretty(operation)?)
+            .with_context(|| format!("Failed to write {}", temp.display()))?;
+        std::fs::rename(&temp, &path).with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    fn move_to_failed(&self, operation: &QueuedOperation) -> Result<()> {
+        self.write_in(&self.dir.join(FAILED_DIR), operation)?;
+        self.remove(&operation.id)?;
+        Ok(())
+    }
+
+    fn read_dir(dir: &Path) -> Result<Vec<QueuedOperation>> {
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", dir.display())),
+        };
+
+        let mut operations: Vec<QueuedOperation> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| {
+                let content = std::fs::read(&path).ok()?;
+                serde_json::from_slice(&content).ok()
+            })
+            .collect();
+        operations.sort_by(|a, b| a.enqueued_at.cmp(&b.enqueued_at).then_with(|| a.id.cmp(&b.id)));
+        Ok(operations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_drain_delivers_reschedules_and_dead_letters() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let queue = OfflineQueue::new(dir.path());
+        let policy = RetryPolicy {
+            initial_delay: Duration::from_secs(60),
+            max_delay: Duration::from_secs(600),
+            jitter: 0.0,
+            ..RetryPolicy::default()
+        };
+        queue.enqueue("webhook", json!({ "n": 1 }))?;
+        queue.enqueue("webhook", json!({ "n": 2 }))?;
+        queue.enqueue("issue_sync", json!({ "n": 3 }))?;
+
+        // Survives reopening
+        let queue = OfflineQueue::new(dir.path());
+        assert_eq!(queue.len()?, 3);
+
+        // Offline: the first failure ends the pass and reschedules it
+        let summary = queue
+            .drain(&policy, |_| async { Err(NetError::Unreachable("offline".to_string())) })
+            .await?;
+        assert_eq!(summary, DrainSummary { delivered: 0, failed: 0, remaining: 3 });
+        let first = &queue.pending()?[0];
+        assert_eq!(first.attempts, 1);
+        assert!(first.next_attempt_at > Utc::now() + chrono::Duration::seconds(50));
+        assert_eq!(first.last_error.as_deref(), Some("network unreachable: offline"));
+
+        // Back online: due operations go out, a rejected one is set aside
+        let summary = queue
+            .drain(&policy, |operation| {
+                let rejected = operation.kind == "issue_sync";
+                async move {
+                    if rejected {
+                        Err(NetError::from_status(422, "invalid issue"))
+                    } else {
+                        Ok(())
+                    }
+                }
+            })
+            .await?;
+        assert_eq!(summary, DrainSummary { delivered: 1, failed: 1, remaining: 1 });
+        assert_eq!(queue.pending()?[0].payload, json!({ "n": 1 }));
+        assert_eq!(queue.failed()?[0].kind, "issue_sync");
+        Ok(())
+    }
+}
@@ -0,0 +1,212 @@
+//! Retry policies with exponential backoff and jitter
+
+use super::NetError;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+use tracing::debug;
+
+/// How often and how patiently a network operation is retried
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts in total, including the first one
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    pub multiplier: f64,
+    /// Fraction of each delay that is randomized, from 0.0 (none) to 1.0 (full jitter)
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: 0.5,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A single attempt without retries
+    pub fn no_retry() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Delay before retry number `retry` (1 for the first retry), before jitter
+    pub fn base_delay(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let delay = self.initial_delay.as_secs_f64() * self.multiplier.max(1.0).powi(exponent);
+        if delay.is_finite() && delay < self.max_delay.as_secs_f64() {
+            Duration::from_secs_f64(delay)
+        } else {
+            self.max_delay
+        }
+    }
+
+    /// Delay before retry number `retry`, with jitter applied
+    ///
+    /// The jittered part of the delay is drawn uniformly, so clients that
+    /// failed together don't all retry at the same moment.
+    pub fn delay(&self, retry: u32) -> Duration {
+        self.jittered(self.base_delay(retry), &mut rand::thread_rng())
+    }
+
+    fn jittered(&self, delay: Duration, rng: &mut impl Rng) -> Duration {
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 {
+            return delay;
+        }
+        let fixed = delay.mul_f64(1.0 - jitter);
+        fixed + delay.mul_f64(jitter * rng.gen::<f64>())
+    }
+
+    /// Run `operation` until it succeeds, fails permanently, or attempts run out
+    ///
+    /// The operation is passed the attempt number, starting at 1. Only
+    /// [`NetError::is_retryable`] errors are retried; the last error is returned.
+    pub async fn retry<T, F, Fut>(&self, mut operation: F) -> Result<T, NetError>
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = Result<T, NetError>>,
+    {
+        let attempts = self.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            match operation(attempt).await {
+                Ok(value) => return Ok(value),
+                Err(e) if e.is_retryable() && attempt < attempts => {
+                    let delay = self.delay(attempt);
+                    debug!("Attempt {} of {} failed ({}), retrying in {:?}", attempt, attempts, e, delay);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Network settings, under `[network]` in `lspbridge.toml`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// `deny` makes every run offline, as if `--offline` were passed
+    pub mode: super::NetworkMode,
+    pub max_attempts: u32,
+    pub initial_delay_ms: u64,
+    pub max_delay_ms: u64,
+    pub backoff_multiplier: f64,
+    pub jitter: f64,
+    /// Directory of the offline queue; defaults to `offline-queue` in the data directory
+    pub queue_dir: Option<std::path::PathBuf>,
+    /// How often a daemon retries queued operations
+    pub drain_interval_secs: u64,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        let policy = RetryPolicy::default();
+        Self {
+            mode: super::NetworkMode::Allow,
+            max_attempts: policy.max_attempts,
+            initial_delay_ms: policy.initial_delay.as_millis() as u64,
+            max_delay_ms: policy.max_delay.as_millis() as u64,
+            backoff_multiplier: policy.multiplier,
+            jitter: policy.jitter,
+            queue_dir: None,
+            drain_interval_secs: 60,
+        }
+    }
+}
+
+impl NetworkConfig {
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: self.max_attempts,
+            initial_delay: Duration::from_millis(self.initial_delay_ms),
+            max_delay: Duration::from_millis(self.max_delay_ms),
+            multiplier: self.backoff_multiplier,
+            jitter: self.jitter,
+        }
+    }
+
+    pub fn drain_interval(&self) -> Duration {
+        Duration::from_secs(self.drain_interval_secs.max(1))
+    }
+
+    /// The configured offline queue
+    pub fn offline_queue(&self) -> super::OfflineQueue {
+        match &self.queue_dir {
+            Some(dir) => super::OfflineQueue::new(dir),
+            None => super::OfflineQueue::new(super::OfflineQueue::default_dir()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_backoff_grows_and_is_capped() {
+        let policy = RetryPolicy {
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+            ..RetryPolicy::default()
+        };
+        assert_eq!(policy.base_delay(1), Duration::from_millis(100));
+        assert_eq!(policy.base_delay(3), Duration::from_millis(400));
+        assert_eq!(policy.base_delay(10), Duration::from_secs(1));
+        assert_eq!(policy.base_delay(u32::MAX), Duration::from_secs(1));
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        for _ in 0..100 {
+            let delay = policy.jittered(Duration::from_millis(400), &mut rng);
+            assert!(delay >= Duration::from_millis(200) && delay <= Duration::from_millis(400));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_stops_on_success_or_permanent_error() {
+        let policy = RetryPolicy {
+            initial_delay: Duration::from_millis(1),
+            ..RetryPolicy::default()
+        };
+
+        let calls = AtomicU32::new(0);
+        let result = policy
+            .retry(|attempt| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if attempt < 3 {
+                        Err(NetError::Unreachable("connection refused".to_string()))
+                    } else {
+                        Ok(attempt)
+                    }
+                }
+            })
+            .await;
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        calls.store(0, Ordering::SeqCst);
+        let result: Result<(), _> = policy
+            .retry(|_| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async { Err(NetError::from_status(404, "not found")) }
+            })
+            .await;
+        assert!(!result.unwrap_err().is_retryable());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
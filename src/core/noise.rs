@@ -0,0 +1,479 @@
+//! Noise scoring and auto-muting of chronically ignored diagnostics
+//!
+//! The noise model learns which diagnostic patterns a team never acts on.
+//! Every export observes the current diagnostics and updates per-pattern
+//! statistics; a pattern becomes "noisy" when it is:
+//!
+//! - **Long-lived**: present for a long period since it was first seen
+//! - **High-volume**: many occurrences per observation
+//! - **Low-action**: its occurrence count rarely goes down
+//!
+//! Noisy patterns are downranked in exports, and once they have been around
+//! longer than [`NoiseConfig::mute_after_days`] they can be muted entirely.
+//! Muted and downranked patterns are always listed in a [`NoiseReport`] so
+//! nothing disappears silently. Errors are never muted unless explicitly
+//! allowed, only downranked.
+//!
+//! Patterns are keyed by `source` and `code`; diagnostics without a code fall
+//! back to a normalized message prefix.
+
+use super::types::{Diagnostic, DiagnosticSeverity};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Average occurrences per observation at which volume is considered saturated
+const VOLUME_SATURATION: f64 = 10.0;
+
+/// Length of the message prefix used to key diagnostics without a code
+const MESSAGE_KEY_LEN: usize = 60;
+
+/// Configuration for noise scoring and muting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoiseConfig {
+    /// Minimum number of observations before a pattern is scored
+    pub min_observations: u32,
+    /// Score at or above which a pattern is downranked
+    pub downrank_threshold: f64,
+    /// Score at or above which a pattern may be muted
+    pub mute_threshold: f64,
+    /// Days a pattern must have existed before it can be muted
+    pub mute_after_days: u64,
+    /// Whether error-severity diagnostics may be muted (otherwise only downranked)
+    pub mute_errors: bool,
+}
+
+impl Default for NoiseConfig {
+    fn default() -> Self {
+        Self {
+            min_observations: 5,
+            downrank_threshold: 0.5,
+            mute_threshold: 0.8,
+            mute_after_days: 14,
+            mute_errors: false,
+        }
+    }
+}
+
+/// Learned statistics for a single diagnostic pattern
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoiseStats {
+    /// Diagnostic source (e.g. "eslint")
+    pub source: String,
+    /// Diagnostic code, if any
+    pub code: Option<String>,
+    /// Representative message
+    pub sample_message: String,
+    /// When the pattern was first observed
+    pub first_seen: DateTime<Utc>,
+    /// When the pattern was last observed
+    pub last_seen: DateTime<Utc>,
+    /// Number of observations in which the pattern was present
+    pub observations: u32,
+    /// Total occurrences across all observations
+    pub total_occurrences: u64,
+    /// Occurrence count in the most recent observation
+    pub last_count: usize,
+    /// Number of observations in which the count went down (team took action)
+    pub reductions: u32,
+}
+
+impl NoiseStats {
+    /// Age of the pattern in whole days
+    pub fn age_days(&self) -> u64 {
+        (self.last_seen - self.first_seen).num_days().max(0) as u64
+    }
+}
+
+/// What the noise model decided for a diagnostic
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoiseDecision {
+    /// Not noisy; leave as is
+    Keep,
+    /// Noisy; order after everything else
+    Downrank(f64),
+    /// Chronically ignored; exclude from the export
+    Mute(f64),
+}
+
+/// A noisy pattern listed in the noise report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoisyPattern {
+    /// Pattern key (`source:code` or `source:message`)
+    pub key: String,
+    /// Diagnostic source
+    pub source: String,
+    /// Diagnostic code, if any
+    pub code: Option<String>,
+    /// Representative message
+    pub sample_message: String,
+    /// Number of diagnostics affected in this export
+    pub count: usize,
+    /// Noise score (0.0 - 1.0)
+    pub score: f64,
+    /// Days since the pattern was first seen
+    pub age_days: u64,
+}
+
+/// Report of what the noise model muted or downranked
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NoiseReport {
+    /// Patterns removed from the export
+    pub muted: Vec<NoisyPattern>,
+    /// Patterns kept but ordered last
+    pub downranked: Vec<NoisyPattern>,
+}
+
+impl NoiseReport {
+    /// Check whether anything was muted or downranked
+    pub fn is_empty(&self) -> bool {
+        self.muted.is_empty() && self.downranked.is_empty()
+    }
+
+    /// Total number of muted diagnostics
+    pub fn muted_count(&self) -> usize {
+        self.muted.iter().map(|p| p.count).sum()
+    }
+
+    /// Check whether a diagnostic belongs to a downranked pattern
+    pub fn is_downranked(&self, diagnostic: &Diagnostic) -> bool {
+        let key = noise_key(diagnostic);
+        self.downranked.iter().any(|p| p.key == key)
+    }
+}
+
+/// Result of applying the noise model to a set of diagnostics
+#[derive(Debug, Clone)]
+pub struct NoiseOutcome {
+    /// Diagnostics that were not muted
+    pub diagnostics: Vec<Diagnostic>,
+    /// What was muted or downranked
+    pub report: NoiseReport,
+}
+
+/// Compute the pattern key used to group a diagnostic
+pub fn noise_key(diagnostic: &Diagnostic) -> String {
+    match &diagnostic.code {
+        Some(code) => format!("{}:{}", diagnostic.source, code),
+        None => {
+            // Strip digits so "expected 2 arguments" and "expected 3 arguments" match
+            let normalized: String = diagnostic
+                .message
+                .chars()
+                .filter(|c| !c.is_ascii_digit())
+                .take(MESSAGE_KEY_LEN)
+                .collect();
+            format!("{}:{}", diagnostic.source, normalized.trim())
+        }
+    }
+}
+
+/// Learned noise model, persisted between runs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NoiseModel {
+    #[serde(skip)]
+    config: NoiseConfig,
+    patterns: HashMap<String, NoiseStats>,
+}
+
+impl NoiseModel {
+    /// Create an empty model
+    pub fn new(config: NoiseConfig) -> Self {
+        Self {
+            config,
+            patterns: HashMap::new(),
+        }
+    }
+
+    /// Default location of the persisted model
+    pub fn default_path() -> PathBuf {
+        crate::config::data_dir()
+            .unwrap_or_else(|_| std::env::temp_dir().join("lspbridge"))
+            .join("noise.json")
+    }
+
+    /// Load a model from disk, starting empty if the file does not exist
+    pub fn load(path: &Path, config: NoiseConfig) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::new(config));
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read noise model {}", path.display()))?;
+        let mut model: Self = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse noise model {}", path.display()))?;
+        model.config = config;
+        Ok(model)
+    }
+
+    /// Persist the model to disk
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write noise model {}", path.display()))
+    }
+
+    /// Statistics for a pattern key
+    pub fn stats(&self, key: &str) -> Option<&NoiseStats> {
+        self.patterns.get(key)
+    }
+
+    /// Record one observation of the current diagnostics
+    ///
+    /// Patterns whose count drops (or that disappear) are credited with a
+    /// reduction, which lowers their noise score.
+    pub fn observe(&mut self, diagnostics: &[Diagnostic], now: DateTime<Utc>) {
+        let mut counts: HashMap<String, (usize, &Diagnostic)> = HashMap::new();
+        for diagnostic in diagnostics {
+            counts
+                .entry(noise_key(diagnostic))
+                .or_insert((0, diagnostic))
+                .0 += 1;
+        }
+
+        for (key, stats) in self.patterns.iter_mut() {
+            if !counts.contains_key(key) && stats.last_count > 0 {
+                stats.reductions += 1;
+                stats.last_count = 0;
+            }
+        }
+
+        for (key, (count, diagnostic)) in counts {
+            let stats = self.patterns.entry(key).or_insert_with(|| NoiseStats {
+                source: diagnostic.source.clone(),
+                code: diagnostic.code.clone(),
+                sample_message: diagnostic.message.clone(),
+                first_seen: now,
+                last_seen: now,
+                observations: 0,
+                total_occurrences: 0,
+                last_count: count,
+                reductions: 0,
+            });
+            if count < stats.last_count {
+                stats.reductions += 1;
+            }
+            stats.observations += 1;
+            stats.total_occurrences += count as u64;
+            stats.last_count = count;
+            stats.last_seen = now;
+        }
+    }
+
+    /// Noise score of a pattern (0.0 - 1.0)
+    ///
+    /// Longevity and volume are scaled by inaction, so a pattern the team
+    /// keeps reducing never scores high. Patterns with fewer than
+    /// `min_observations` observations score 0.
+    pub fn score(&self, key: &str) -> f64 {
+        let Some(stats) = self.patterns.get(key) else {
+            return 0.0;
+        };
+        if stats.observations < self.config.min_observations {
+            return 0.0;
+        }
+
+        let longevity =
+            (stats.age_days() as f64 / self.config.mute_after_days.max(1) as f64).min(1.0);
+        let average = stats.total_occurrences as f64 / stats.observations as f64;
+        let volume = ((1.0 + average).ln() / (1.0 + VOLUME_SATURATION).ln()).min(1.0);
+        let inaction = 1.0 - stats.reductions as f64 / stats.observations as f64;
+
+        inaction * (0.7 * longevity + 0.3 * volume)
+    }
+
+    /// Decide what to do with a diagnostic
+    pub fn classify(&self, diagnostic: &Diagnostic) -> NoiseDecision {
+        let key = noise_key(diagnostic);
+        let score = self.score(&key);
+        let old_enough = self
+            .patterns
+            .get(&key)
+            .is_some_and(|s| s.age_days() >= self.config.mute_after_days);
+        let mutable = diagnostic.severity != DiagnosticSeverity::Error || self.config.mute_errors;
+
+        if score >= self.config.mute_threshold && old_enough && mutable {
+            NoiseDecision::Mute(score)
+        } else if score >= self.config.downrank_threshold {
+            NoiseDecision::Downrank(score)
+        } else {
+            NoiseDecision::Keep
+        }
+    }
+
+    /// Mute and downrank noisy diagnostics, reporting what was affected
+    ///
+    /// Downranked diagnostics keep their relative order but move after all
+    /// other diagnostics.
+    pub fn apply(&self, diagnostics: Vec<Diagnostic>) -> NoiseOutcome {
+        let mut kept = Vec::with_capacity(diagnostics.len());
+        let mut downranked = Vec::new();
+        let mut muted: HashMap<String, NoisyPattern> = HashMap::new();
+        let mut downranked_patterns: HashMap<String, NoisyPattern> = HashMap::new();
+
+        for diagnostic in diagnostics {
+            match self.classify(&diagnostic) {
+                NoiseDecision::Keep => kept.push(diagnostic),
+                NoiseDecision::Downrank(score) => {
+                    self.record(&mut downranked_patterns, &diagnostic, score);
+                    downranked.push(diagnostic);
+                }
+                NoiseDecision::Mute(score) => {
+                    self.record(&mut muted, &diagnostic, score);
+                }
+            }
+        }
+        kept.extend(downranked);
+
+        NoiseOutcome {
+            diagnostics: kept,
+            report: NoiseReport {
+                muted: Self::sorted(muted),
+                downranked: Self::sorted(downranked_patterns),
+            },
+        }
+    }
+
+    fn record(
+        &self,
+        patterns: &mut HashMap<String, NoisyPattern>,
+        diagnostic: &Diagnostic,
+        score: f64,
+    ) {
+        let key = noise_key(diagnostic);
+        let age_days = self.patterns.get(&key).map_or(0, NoiseStats::age_days);
+        patterns
+            .entry(key.clone())
+            .or_insert_with(|| NoisyPattern {
+                key,
+                source: diagnostic.source.clone(),
+                code: diagnostic.code.clone(),
+                sample_message: diagnostic.message.clone(),
+                count: 0,
+                score,
+                age_days,
+            })
+            .count += 1;
+    }
+
+    fn sorted(patterns: HashMap<String, NoisyPattern>) -> Vec<NoisyPattern> {
+        let mut patterns: Vec<NoisyPattern> = patterns.into_values().collect();
+        patterns.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.key.cmp(&b.key)));
+        patterns
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{Position, Range};
+    use chrono::Duration;
+
+    fn diagnostic(code: &str, severity: DiagnosticSeverity) -> Diagnostic {
+        let mut diagnostic = Diagnostic::new(
+            "src/app.ts".to_string(),
+            Range {
+                start: Position { line: 1, character: 0 },
+                end: Position { line: 1, character: 5 },
+            },
+            severity,
+            format!("rule {code} violated"),
+            "eslint".to_string(),
+        );
+        diagnostic.code = Some(code.to_string());
+        diagnostic
+    }
+
+    fn observe_daily(model: &mut NoiseModel, days: i64, diagnostics: &[Diagnostic]) {
+        let start = Utc::now() - Duration::days(days);
+        for day in 0..=days {
+            model.observe(diagnostics, start + Duration::days(day));
+        }
+    }
+
+    #[test]
+    fn test_untouched_pattern_becomes_noisy() {
+        let mut model = NoiseModel::new(NoiseConfig::default());
+        let diagnostics = vec![diagnostic("no-console", DiagnosticSeverity::Warning); 10];
+        observe_daily(&mut model, 20, &diagnostics);
+
+        let score = model.score("eslint:no-console");
+        assert!(score > 0.9, "score was {score}");
+        assert!(matches!(
+            model.classify(&diagnostics[0]),
+            NoiseDecision::Mute(_)
+        ));
+    }
+
+    #[test]
+    fn test_patterns_being_fixed_are_not_noisy() {
+        let mut model = NoiseModel::new(NoiseConfig::default());
+        let start = Utc::now() - Duration::days(20);
+        for day in 0..=20i64 {
+            let count = if day % 2 == 0 { 5 } else { 3 };
+            let diagnostics = vec![diagnostic("no-unused-vars", DiagnosticSeverity::Warning); count];
+            model.observe(&diagnostics, start + Duration::days(day));
+        }
+
+        assert_eq!(
+            model.classify(&diagnostic("no-unused-vars", DiagnosticSeverity::Warning)),
+            NoiseDecision::Keep
+        );
+    }
+
+    #[test]
+    fn test_errors_are_downranked_not_muted() {
+        let mut model = NoiseModel::new(NoiseConfig::default());
+        let errors = vec![diagnostic("TS2304", DiagnosticSeverity::Error); 4];
+        observe_daily(&mut model, 30, &errors);
+
+        assert!(matches!(
+            model.classify(&errors[0]),
+            NoiseDecision::Downrank(_)
+        ));
+    }
+
+    #[test]
+    fn test_apply_reports_muted_and_orders_downranked_last() {
+        let mut model = NoiseModel::new(NoiseConfig::default());
+        let noisy_warning = diagnostic("no-console", DiagnosticSeverity::Warning);
+        let noisy_error = diagnostic("TS2304", DiagnosticSeverity::Error);
+        observe_daily(
+            &mut model,
+            30,
+            &[noisy_warning.clone(), noisy_warning.clone(), noisy_error.clone()],
+        );
+
+        let fresh = diagnostic("eqeqeq", DiagnosticSeverity::Warning);
+        let outcome = model.apply(vec![
+            noisy_error.clone(),
+            noisy_warning.clone(),
+            fresh.clone(),
+            noisy_warning,
+        ]);
+
+        assert_eq!(outcome.diagnostics.len(), 2);
+        assert_eq!(outcome.diagnostics[0].code, fresh.code);
+        assert_eq!(outcome.diagnostics[1].code, noisy_error.code);
+        assert_eq!(outcome.report.muted_count(), 2);
+        assert_eq!(outcome.report.muted[0].key, "eslint:no-console");
+        assert!(outcome.report.is_downranked(&noisy_error));
+    }
+
+    #[test]
+    fn test_model_round_trips_through_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("noise.json");
+        let mut model = NoiseModel::new(NoiseConfig::default());
+        model.observe(&[diagnostic("no-console", DiagnosticSeverity::Warning)], Utc::now());
+        model.save(&path).unwrap();
+
+        let loaded = NoiseModel::load(&path, NoiseConfig::default()).unwrap();
+        assert_eq!(loaded.stats("eslint:no-console").unwrap().observations, 1);
+    }
+}
@@ -0,0 +1,247 @@
+//! Code ownership resolution from CODEOWNERS files
+//!
+//! Parses GitHub/GitLab style `CODEOWNERS` files and resolves the owners of a
+//! given path. Matching follows the CODEOWNERS semantics: rules are evaluated
+//! top to bottom and the *last* matching rule wins.
+//!
+//! Pattern handling mirrors `.gitignore`:
+//!
+//! - A leading `/` (or a `/` in the middle) anchors the pattern to the repository root
+//! - Patterns without a slash match at any depth (`*.rs`, `Makefile`)
+//! - A trailing `/` or a plain directory name matches everything beneath it
+
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Locations searched for a CODEOWNERS file, in priority order
+pub const CODEOWNERS_LOCATIONS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// A single `pattern owner...` line from a CODEOWNERS file
+#[derive(Debug, Clone)]
+pub struct OwnershipRule {
+    /// Pattern as written in the CODEOWNERS file
+    pub pattern: String,
+    /// Owners (users `@name`, teams `@org/team`, or emails)
+    pub owners: Vec<String>,
+    /// Line number in the source file (1-based)
+    pub line: usize,
+    matchers: Vec<Pattern>,
+}
+
+/// Result of resolving ownership for a path
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OwnershipMatch {
+    /// Owners for the path
+    pub owners: Vec<String>,
+    /// The CODEOWNERS pattern that matched
+    pub pattern: String,
+    /// Line of the matching rule
+    pub line: usize,
+}
+
+/// Ownership map built from a CODEOWNERS file
+#[derive(Debug, Clone, Default)]
+pub struct OwnershipMap {
+    root: PathBuf,
+    rules: Vec<OwnershipRule>,
+    source: Option<PathBuf>,
+}
+
+impl OwnershipMap {
+    /// Create an empty ownership map rooted at `root`
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            rules: Vec::new(),
+            source: None,
+        }
+    }
+
+    /// Locate and parse the CODEOWNERS file for a repository root
+    ///
+    /// Returns an empty map when no CODEOWNERS file exists.
+    pub fn discover(root: &Path) -> std::io::Result<Self> {
+        for location in CODEOWNERS_LOCATIONS {
+            let candidate = root.join(location);
+            if candidate.is_file() {
+                let content = std::fs::read_to_string(&candidate)?;
+                let mut map = Self::parse(root, &content);
+                map.source = Some(candidate);
+                return Ok(map);
+            }
+        }
+        Ok(Self::new(root))
+    }
+
+    /// Parse CODEOWNERS content
+    ///
+    /// Invalid patterns are skipped with a warning rather than failing the
+    /// whole file, matching how hosting providers treat them.
+    pub fn parse(root: &Path, content: &str) -> Self {
+        let mut map = Self::new(root);
+
+        for (index, raw_line) in content.lines().enumerate() {
+            let line = raw_line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            // GitLab section headers: [Section Name]
+            if line.starts_with('[') || line.starts_with("^[") {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            let Some(pattern) = parts.next() else {
+                continue;
+            };
+            let owners: Vec<String> = parts
+                .take_while(|part| !part.starts_with('#'))
+                .map(String::from)
+                .collect();
+
+            match Self::compile(pattern) {
+                Ok(matchers) => map.rules.push(OwnershipRule {
+                    pattern: pattern.to_string(),
+                    owners,
+                    line: index + 1,
+                    matchers,
+                }),
+                Err(e) => {
+                    tracing::warn!("Skipping invalid CODEOWNERS pattern '{}': {}", pattern, e);
+                }
+            }
+        }
+
+        map
+    }
+
+    /// Translate a CODEOWNERS pattern into glob matchers
+    fn compile(pattern: &str) -> Result<Vec<Pattern>, glob::PatternError> {
+        let is_dir = pattern.ends_with('/');
+        let trimmed = pattern.trim_end_matches('/');
+        let anchored = trimmed.starts_with('/') || trimmed.contains('/');
+        let body = trimmed.trim_start_matches('/');
+
+        if body.is_empty() || body == "*" || body == "**" {
+            return Ok(vec![Pattern::new("**")?]);
+        }
+
+        let base = if anchored {
+            body.to_string()
+        } else {
+            format!("**/{body}")
+        };
+
+        let mut matchers = vec![Pattern::new(&format!("{base}/**"))?];
+        if !is_dir {
+            matchers.push(Pattern::new(&base)?);
+        }
+        Ok(matchers)
+    }
+
+    /// Convert a path into the repository-relative form used for matching
+    fn relative_path(&self, path: &Path) -> String {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        relative
+            .to_string_lossy()
+            .replace('\\', "/")
+            .trim_start_matches("./")
+            .trim_start_matches('/')
+            .to_string()
+    }
+
+    /// Resolve the owners of a path (last matching rule wins)
+    pub fn resolve(&self, path: &Path) -> Option<OwnershipMatch> {
+        let relative = self.relative_path(path);
+        let options = MatchOptions {
+            case_sensitive: true,
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        };
+
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| {
+                rule.matchers
+                    .iter()
+                    .any(|m| m.matches_with(&relative, options))
+            })
+            .map(|rule| OwnershipMatch {
+                owners: rule.owners.clone(),
+                pattern: rule.pattern.clone(),
+                line: rule.line,
+            })
+    }
+
+    /// Owners of a path, or an empty list if unowned
+    pub fn owners_for(&self, path: &Path) -> Vec<String> {
+        self.resolve(path).map(|m| m.owners).unwrap_or_default()
+    }
+
+    /// All parsed rules in file order
+    pub fn rules(&self) -> &[OwnershipRule] {
+        &self.rules
+    }
+
+    /// Path of the CODEOWNERS file this map was loaded from
+    pub fn source(&self) -> Option<&Path> {
+        self.source.as_deref()
+    }
+
+    /// Check whether any ownership rules are defined
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+# Default owners
+*                       @org/core
+
+*.ts                    @org/frontend
+/docs/                  @org/docs
+src/query/              @alice @bob   # query team
+Makefile                @build-team
+"#;
+
+    fn owners(map: &OwnershipMap, path: &str) -> Vec<String> {
+        map.owners_for(Path::new(path))
+    }
+
+    #[test]
+    fn test_last_match_wins() {
+        let map = OwnershipMap::parse(Path::new("/repo"), SAMPLE);
+        assert_eq!(owners(&map, "src/main.rs"), vec!["@org/core"]);
+        assert_eq!(owners(&map, "web/app.ts"), vec!["@org/frontend"]);
+        assert_eq!(owners(&map, "src/query/parser/mod.rs"), vec!["@alice", "@bob"]);
+    }
+
+    #[test]
+    fn test_anchoring_and_depth() {
+        let map = OwnershipMap::parse(Path::new("/repo"), SAMPLE);
+        assert_eq!(owners(&map, "docs/guide.md"), vec!["@org/docs"]);
+        assert_eq!(owners(&map, "packages/docs/guide.md"), vec!["@org/core"]);
+        assert_eq!(owners(&map, "tools/Makefile"), vec!["@build-team"]);
+    }
+
+    #[test]
+    fn test_absolute_paths_are_made_relative() {
+        let map = OwnershipMap::parse(Path::new("/repo"), SAMPLE);
+        let matched = map.resolve(Path::new("/repo/src/query/api/mod.rs")).unwrap();
+        assert_eq!(matched.pattern, "src/query/");
+        assert_eq!(matched.line, 7);
+    }
+
+    #[test]
+    fn test_empty_map_has_no_owners() {
+        let map = OwnershipMap::new("/repo");
+        assert!(map.is_empty());
+        assert!(owners(&map, "src/lib.rs").is_empty());
+    }
+}
@@ -0,0 +1,517 @@
+//! Zero-setup workspace statistics
+//!
+//! `lspbridge stats` answers "what state is this workspace in?" without a
+//! prior capture or any configuration. [`QuickStats`] gathers diagnostics
+//! from whatever is cheap to get:
+//!
+//! - the latest snapshot of every file in the history database, when
+//!   `lspbridge watch` has recorded one
+//! - a [`StaticScanner`] pass over the source tree
+//! - `go vet` and ESLint, when the workspace uses them, the tools are
+//!   installed and the workspace is trusted, each bounded by a timeout
+//!
+//! The ESLint adapter runs the workspace's own `node_modules/.bin/eslint`,
+//! so tools only run by default in workspaces marked trusted with
+//! `lspbridge trust`.
+//!
+//! Every source is best effort: one that doesn't apply, is missing or fails
+//! is noted in the [`WorkspaceStats`] instead of failing the whole pass.
+
+use super::static_scan::{ScanConfig, StaticScanner};
+use super::types::{Diagnostic, DiagnosticSeverity, RawDiagnostics};
+use super::workspace_trust::{untrusted_error, TrustLevel};
+use super::FormatConverter as _;
+use super::{detect_language, SCAN_SOURCE};
+use crate::analyzers::SECURITY_SOURCE;
+use crate::format::{parse_json_stream, FormatConverter};
+use crate::history::{AsOf, HistoryConfig, HistoryService};
+use crate::quick_fix::HealthMetrics;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Files listed in the summary
+const TOP_FILES: usize = 5;
+
+/// Where a tool writes its JSON report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ToolOutput {
+    Stdout,
+    Stderr,
+}
+
+/// A compiler or linter run when the workspace contains its marker file
+struct ToolAdapter {
+    /// Also the `source` the output is normalized as
+    name: &'static str,
+    marker: &'static str,
+    /// A command on `PATH`, or a path relative to the workspace root
+    program: &'static str,
+    args: &'static [&'static str],
+    output: ToolOutput,
+}
+
+const TOOL_ADAPTERS: &[ToolAdapter] = &[
+    ToolAdapter {
+        name: "go vet",
+        marker: "go.mod",
+        program: "go",
+        args: &["vet", "-json", "./..."],
+        output: ToolOutput::Stderr,
+    },
+    ToolAdapter {
+        name: "eslint",
+        marker: "package.json",
+        program: "node_modules/.bin/eslint",
+        args: &["-f", "json", "."],
+        output: ToolOutput::Stdout,
+    },
+];
+
+/// What one source contributed to the statistics
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatsSource {
+    pub name: String,
+    pub diagnostics: usize,
+    /// Why the source contributed nothing: not applicable, unavailable or failed
+    pub note: Option<String>,
+}
+
+impl StatsSource {
+    fn found(name: &str, diagnostics: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            diagnostics,
+            note: None,
+        }
+    }
+
+    fn skipped(name: &str, note: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            diagnostics: 0,
+            note: Some(note.into()),
+        }
+    }
+}
+
+/// Diagnostic counts of one file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HotFile {
+    /// Relative to the workspace root when inside it
+    pub file: String,
+    pub errors: usize,
+    pub warnings: usize,
+    pub total: usize,
+}
+
+/// A command worth running next, and why
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NextCommand {
+    pub command: String,
+    pub reason: String,
+}
+
+/// One-screen summary of a workspace's diagnostics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceStats {
+    pub root: PathBuf,
+    pub sources: Vec<StatsSource>,
+    pub total: usize,
+    /// Every severity, most severe first
+    pub by_severity: Vec<(DiagnosticSeverity, usize)>,
+    /// Most diagnostics first; files of no known language count as `other`
+    pub by_language: Vec<(String, usize)>,
+    /// Most errors first, then most diagnostics
+    pub top_files: Vec<HotFile>,
+    /// Estimate from the diagnostics found, weighted like the history trend analyzer
+    pub health: HealthMetrics,
+    pub next_commands: Vec<NextCommand>,
+    pub elapsed_ms: u64,
+}
+
+impl WorkspaceStats {
+    /// Summarize `diagnostics` found under `root`
+    pub fn from_diagnostics(root: &Path, sources: Vec<StatsSource>, diagnostics: &[Diagnostic]) -> Self {
+        let mut by_severity: Vec<(DiagnosticSeverity, usize)> = [
+            DiagnosticSeverity::Error,
+            DiagnosticSeverity::Warning,
+            DiagnosticSeverity::Information,
+            DiagnosticSeverity::Hint,
+        ]
+        .into_iter()
+        .map(|severity| (severity, 0))
+        .collect();
+        let mut by_language: HashMap<String, usize> = HashMap::new();
+        let mut files: HashMap<String, HotFile> = HashMap::new();
+
+        for diagnostic in diagnostics {
+            if let Some((_, count)) = by_severity.iter_mut().find(|(s, _)| *s == diagnostic.severity) {
+                *count += 1;
+            }
+            let language = detect_language(Path::new(&diagnostic.file), None)
+                .map_or("other", |language| language.as_str());
+            *by_language.entry(language.to_string()).or_default() += 1;
+
+            let file = relative_to(root, &diagnostic.file);
+            let entry = files.entry(file.clone()).or_insert_with(|| HotFile {
+                file,
+                errors: 0,
+                warnings: 0,
+                total: 0,
+            });
+            entry.total += 1;
+            match diagnostic.severity {
+                DiagnosticSeverity::Error => entry.errors += 1,
+                DiagnosticSeverity::Warning => entry.warnings += 1,
+                _ => {}
+            }
+        }
+
+        let mut by_language: Vec<(String, usize)> = by_language.into_iter().collect();
+        by_language.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let mut top_files: Vec<HotFile> = files.into_values().collect();
+        top_files.sort_by(|a, b| {
+            (b.errors, b.total)
+                .cmp(&(a.errors, a.total))
+                .then_with(|| a.file.cmp(&b.file))
+        });
+        top_files.truncate(TOP_FILES);
+
+        let health = HealthMetrics::from_diagnostics(diagnostics);
+        let next_commands = next_commands(&sources, diagnostics, &health);
+        Self {
+            root: root.to_path_buf(),
+            sources,
+            total: diagnostics.len(),
+            by_severity,
+            by_language,
+            top_files,
+            health,
+            next_commands,
+            elapsed_ms: 0,
+        }
+    }
+}
+
+/// Gathers diagnostics from every cheap source and summarizes them
+pub struct QuickStats {
+    root: PathBuf,
+    scan: ScanConfig,
+    /// Whether to run tools; by default only in trusted workspaces
+    run_tools: Option<bool>,
+    /// Tools only run in trusted workspaces
+    trust: TrustLevel,
+    tool_timeout: Duration,
+    history_db: PathBuf,
+}
+
+impl QuickStats {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            scan: ScanConfig::default(),
+            run_tools: None,
+            trust: TrustLevel::Untrusted,
+            tool_timeout: Duration::from_secs(20),
+            history_db: HistoryConfig::default().db_path,
+        }
+    }
+
+    pub fn with_scan_config(mut self, scan: ScanConfig) -> Self {
+        self.scan = scan;
+        self
+    }
+
+    /// Whether to run compilers and linters found in the workspace
+    ///
+    /// Tools still refuse to run in an untrusted workspace.
+    pub fn with_tools(mut self, run_tools: bool) -> Self {
+        self.run_tools = Some(run_tools);
+        self
+    }
+
+    /// Trust level of the workspace; tools only run by default in trusted ones
+    pub fn with_workspace_trust(mut self, trust: TrustLevel) -> Self {
+        self.trust = trust;
+        self
+    }
+
+    /// Time after which a tool is stopped and skipped
+    pub fn with_tool_timeout(mut self, timeout: Duration) -> Self {
+        self.tool_timeout = timeout;
+        self
+    }
+
+    /// Read cached diagnostics from this history database instead of the default one
+    pub fn with_history_db(mut self, path: PathBuf) -> Self {
+        self.history_db = path;
+        self
+    }
+
+    pub async fn collect(&self) -> Result<WorkspaceStats> {
+        let started = Instant::now();
+        let mut sources = Vec::new();
+        let mut diagnostics = Vec::new();
+        let mut seen = HashSet::new();
+        let mut add = |source: StatsSource, found: Vec<Diagnostic>, sources: &mut Vec<StatsSource>| {
+            for diagnostic in found {
+                let key = (
+                    relative_to(&self.root, &diagnostic.file),
+                    diagnostic.range.start.line,
+                    diagnostic.message.clone(),
+                );
+                if seen.insert(key) {
+                    diagnostics.push(diagnostic);
+                }
+            }
+            sources.push(source);
+        };
+
+        let (source, found) = self.cached().await;
+        add(source, found, &mut sources);
+
+        let (source, found) = match StaticScanner::new(&self.root, self.scan.clone()) {
+            Ok(scanner) => match scanner.scan().await {
+                Ok(report) => (StatsSource::found(SCAN_SOURCE, report.diagnostics.len()), report.diagnostics),
+                Err(e) => (StatsSource::skipped(SCAN_SOURCE, format!("failed: {e}")), Vec::new()),
+            },
+            Err(e) => (StatsSource::skipped(SCAN_SOURCE, format!("failed: {e}")), Vec::new()),
+        };
+        add(source, found, &mut sources);
+
+        for tool in TOOL_ADAPTERS {
+            let (source, found) = if !self.root.join(tool.marker).exists() {
+                (StatsSource::skipped(tool.name, format!("no {}", tool.marker)), Vec::new())
+            } else if self.run_tools == Some(false) {
+                (StatsSource::skipped(tool.name, "tools disabled"), Vec::new())
+            } else if self.run_tools.is_none() && self.trust == TrustLevel::Untrusted {
+                (StatsSource::skipped(tool.name, "workspace not trusted"), Vec::new())
+            } else {
+                match self.run_tool(tool).await {
+                    Ok(found) => (StatsSource::found(tool.name, found.len()), found),
+                    Err(e) => (StatsSource::skipped(tool.name, e.to_string()), Vec::new()),
+                }
+            };
+            add(source, found, &mut sources);
+        }
+
+        let mut stats = WorkspaceStats::from_diagnostics(&self.root, sources, &diagnostics);
+        stats.elapsed_ms = started.elapsed().as_millis() as u64;
+        Ok(stats)
+    }
+
+    /// Latest recorded diagnostics of files in the workspace, if history exists
+    ///
+    /// Reads through a running daemon when there is one.
+    async fn cached(&self) -> (StatsSource, Vec<Diagnostic>) {
+        const NAME: &str = "history";
+        if !self.history_db.exists() {
+            return (StatsSource::skipped(NAME, "nothing recorded yet"), Vec::new());
+        }
+        let config = HistoryConfig {
+            db_path: self.history_db.clone(),
+            ..HistoryConfig::default()
+        };
+        let snapshots = match HistoryService::connect(config, "stats").await {
+            Ok(history) => history.reconstruct(AsOf::Time(SystemTime::now())).await,
+            Err(e) => Err(e),
+        };
+        match snapshots {
+            Ok(snapshots) => {
+                let found: Vec<Diagnostic> = snapshots
+                    .into_iter()
+                    .filter(|snapshot| snapshot.file_path.is_relative() || snapshot.file_path.starts_with(&self.root))
+                    .flat_map(|snapshot| snapshot.diagnostics)
+                    .collect();
+                (StatsSource::found(NAME, found.len()), found)
+            }
+            Err(e) => (StatsSource::skipped(NAME, format!("unreadable: {e}")), Vec::new()),
+        }
+    }
+
+    async fn run_tool(&self, tool: &ToolAdapter) -> Result<Vec<Diagnostic>> {
+        if self.trust != TrustLevel::Trusted {
+            return Err(untrusted_error(&self.root, &format!("run {}", tool.name)));
+        }
+        let program = if tool.program.contains('/') {
+            let local = self.root.join(tool.program);
+            if !local.exists() {
+                return Err(anyhow!("not installed"));
+            }
+            local
+        } else {
+            PathBuf::from(tool.program)
+        };
+
+        let child = tokio::process::Command::new(&program)
+            .args(tool.args)
+            .current_dir(&self.root)
+            .stdin(std::process::Stdio::null())
+            .kill_on_drop(true)
+            .output();
+        let output = match tokio::time::timeout(self.tool_timeout, child).await {
+            Err(_) => return Err(anyhow!("timed out after {}s", self.tool_timeout.as_secs())),
+            Ok(Err(e)) if e.kind() == std::io::ErrorKind::NotFound => return Err(anyhow!("not installed")),
+            Ok(output) => output?,
+        };
+
+        // Linters exit non-zero when they find something, so only the report matters
+        let report = String::from_utf8_lossy(match tool.output {
+            ToolOutput::Stdout => &output.stdout,
+            ToolOutput::Stderr => &output.stderr,
+        });
+        if report.trim().is_empty() || report.lines().all(|line| line.trim_start().starts_with('#')) {
+            return Ok(Vec::new());
+        }
+        let raw = RawDiagnostics {
+            source: tool.name.to_string(),
+            data: parse_json_stream(&report).map_err(|_| anyhow!("unreadable output"))?,
+            timestamp: chrono::Utc::now(),
+            workspace: None,
+        };
+        Ok(FormatConverter::new().normalize(raw).await?)
+    }
+}
+
+/// Suggestions for the commands that go deeper into what was found
+fn next_commands(sources: &[StatsSource], diagnostics: &[Diagnostic], health: &HealthMetrics) -> Vec<NextCommand> {
+    let suggest = |command: &str, reason: &str| NextCommand {
+        command: command.to_string(),
+        reason: reason.to_string(),
+    };
+    let mut commands = Vec::new();
+
+    let recorded = sources.iter().any(|s| s.name == "history" && s.note.is_none());
+    if !recorded {
+        commands.push(suggest(
+            "lspbridge watch",
+            "capture language server diagnostics continuously and record history",
+        ));
+    } else if health.hot_spots > 0 {
+        commands.push(suggest("lspbridge history hot-spots", "see which files keep accumulating diagnostics"));
+    }
+    if diagnostics.iter().any(|d| d.source == SECURITY_SOURCE) {
+        commands.push(suggest(
+            "lspbridge scan . | lspbridge export --format markdown",
+            "review the insecure code patterns found",
+        ));
+    }
+    if diagnostics
+        .iter()
+        .any(|d| d.source == SCAN_SOURCE && matches!(d.code.as_deref(), Some("todo") | Some("fixme")))
+    {
+        commands.push(suggest("lspbridge debt todos --min-age-days 90", "track aging TODO/FIXME comments"));
+    }
+    if health.errors > 0 {
+        commands.push(suggest("lspbridge whatif", "estimate how much automatic fixes would help"));
+    }
+    commands.push(suggest(
+        "lspbridge export --format sarif --output results.sarif",
+        "upload the findings to code scanning",
+    ));
+    commands
+}
+
+fn relative_to(root: &Path, file: &str) -> String {
+    let file = file.strip_prefix("file://").unwrap_or(file);
+    Path::new(file)
+        .strip_prefix(root)
+        .map(|relative| relative.display().to_string())
+        .unwrap_or_else(|_| file.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Position, Range};
+
+    fn diagnostic(file: &str, severity: DiagnosticSeverity) -> Diagnostic {
+        Diagnostic::new(
+            file.to_string(),
+            Range {
+                start: Position { line: 3, character: 0 },
+                end: Position { line: 3, character: 4 },
+            },
+            severity,
+            "problem".to_string(),
+            "rustc".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_summary_counts_and_ranks_files() {
+        let diagnostics = vec![
+            diagnostic("/ws/src/lib.rs", DiagnosticSeverity::Warning),
+            diagnostic("/ws/src/lib.rs", DiagnosticSeverity::Warning),
+            diagnostic("/ws/src/main.rs", DiagnosticSeverity::Error),
+            diagnostic("/ws/web/app.ts", DiagnosticSeverity::Hint),
+        ];
+        let stats = WorkspaceStats::from_diagnostics(Path::new("/ws"), Vec::new(), &diagnostics);
+
+        assert_eq!(stats.total, 4);
+        assert_eq!(stats.by_severity[0], (DiagnosticSeverity::Error, 1));
+        assert_eq!(stats.by_severity[1], (DiagnosticSeverity::Warning, 2));
+        assert_eq!(stats.by_language[0], ("rust".to_string(), 3));
+        assert_eq!(stats.top_files[0].file, "src/main.rs");
+        assert_eq!(stats.top_files[1].total, 2);
+        assert!(stats.health.health_score < 1.0);
+        assert_eq!(stats.next_commands[0].command, "lspbridge watch");
+    }
+
+    #[tokio::test]
+    async fn test_collect_without_setup() -> Result<()> {
+        let dir = tempfile::TempDir::new()?;
+        std::fs::create_dir(dir.path().join("src"))?;
+        std::fs::write(dir.path().join("src/lib.rs"), "mod missing;\n")?;
+        std::fs::write(dir.path().join("go.mod"), "module example\n")?;
+
+        let stats = QuickStats::new(dir.path())
+            .with_tools(false)
+            .with_history_db(dir.path().join("none.db"))
+            .collect()
+            .await?;
+
+        assert_eq!(stats.total, 1);
+        assert_eq!(stats.top_files[0].file, "src/lib.rs");
+        let notes: Vec<_> = stats.sources.iter().map(|s| (s.name.as_str(), s.note.as_deref())).collect();
+        assert_eq!(
+            notes,
+            vec![
+                ("history", Some("nothing recorded yet")),
+                (SCAN_SOURCE, None),
+                ("go vet", Some("tools disabled")),
+                ("eslint", Some("no package.json")),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_untrusted_workspace_never_runs_tools() -> Result<()> {
+        let dir = tempfile::TempDir::new()?;
+        std::fs::write(dir.path().join("package.json"), "{}")?;
+        std::fs::create_dir_all(dir.path().join("node_modules/.bin"))?;
+        std::fs::write(dir.path().join("node_modules/.bin/eslint"), "#!/bin/sh\ntouch ran\n")?;
+
+        let eslint_note = |stats: &WorkspaceStats| {
+            stats.sources.iter().find(|s| s.name == "eslint").and_then(|s| s.note.clone()).unwrap()
+        };
+        let stats = QuickStats::new(dir.path())
+            .with_history_db(dir.path().join("none.db"))
+            .collect()
+            .await?;
+        assert_eq!(eslint_note(&stats), "workspace not trusted");
+
+        let stats = QuickStats::new(dir.path())
+            .with_tools(true)
+            .with_history_db(dir.path().join("none.db"))
+            .collect()
+            .await?;
+        assert!(eslint_note(&stats).contains("lspbridge trust"));
+        assert!(!dir.path().join("ran").exists());
+        Ok(())
+    }
+}
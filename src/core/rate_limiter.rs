@@ -179,6 +179,21 @@ impl RateLimiter {
         Ok(RateLimitResult::Allowed)
     }
 
+    /// Requests `client_id` may still make in the current window
+    ///
+    /// `None` when per-client limiting is disabled.
+    pub async fn remaining(&self, client_id: &str) -> Option<u32> {
+        if !self.config.per_ip_limiting {
+            return None;
+        }
+        let window_start = Instant::now() - self.config.window_duration;
+        let clients = self.clients.read().await;
+        let used = clients.get(client_id).map_or(0, |state| {
+            state.requests.iter().filter(|&&time| time > window_start).count()
+        });
+        Some(self.config.max_requests.saturating_sub(used as u32))
+    }
+
     /// Get current rate limiting statistics
     pub async fn get_stats(&self) -> RateLimitStats {
         let clients = self.clients.read().await;
@@ -310,7 +325,9 @@ mod tests {
         let limiter = RateLimiter::new(config);
 
         // First two requests should be allowed
+        assert_eq!(limiter.remaining("client1").await, Some(2));
         assert!(limiter.check_request("client1").await.unwrap().is_allowed());
+        assert_eq!(limiter.remaining("client1").await, Some(1));
         assert!(limiter.check_request("client1").await.unwrap().is_allowed());
 
         // Third request should be denied
@@ -150,13 +150,17 @@ pub struct PrivacySecurityConfig {
     pub secure_temp_files: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, clap::ValueEnum)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, clap::ValueEnum, utoipa::ToSchema)]
 pub enum PrivacyLevel {
     /// Maximum privacy protection
+    #[serde(alias = "strict")]
     Strict,
     /// Balanced privacy (recommended)
+    #[default]
+    #[serde(alias = "balanced")]
     Balanced,
     /// Minimal privacy (internal use only)
+    #[serde(alias = "minimal")]
     Minimal,
 }
 
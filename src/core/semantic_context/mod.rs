@@ -7,8 +7,11 @@
 //!
 //! - **SemanticContext**: Core data structure containing all contextual information
 //! - **ContextExtractor**: Multi-language parser-based context extraction engine
-//! - **Language Detection**: Automatic language detection from file extensions
+//! - **Language Detection**: Language detection from file extensions, shebangs and
+//!   the script blocks of Vue, Svelte and MDX files
 //! - **Context Filtering**: Relevance scoring and context optimization
+//! - **Call Hierarchy**: Callees from the parse tree; with a workspace
+//!   [`SymbolIndex`] attached, callers and callee definitions across files
 
 pub mod extractors;
 pub mod types;
@@ -17,10 +20,13 @@ pub use types::*;
 
 use anyhow::{anyhow, Context, Result};
 use std::collections::HashMap;
-use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tree_sitter::{Node, Parser};
 
+use crate::core::file_guard::FileGuard;
+use crate::core::language_detection::{self, DetectedLanguage};
+use crate::core::symbol_index::{identifiers, SymbolIndex};
 use crate::core::types::Diagnostic;
 use extractors::{LanguageExtractor, utils};
 use extractors::{typescript::TypeScriptExtractor, rust::RustExtractor, python::PythonExtractor};
@@ -29,8 +35,13 @@ use extractors::{typescript::TypeScriptExtractor, rust::RustExtractor, python::P
 pub struct ContextExtractor {
     parsers: HashMap<String, Parser>,
     extractors: HashMap<Language, Box<dyn LanguageExtractor>>,
+    file_guard: FileGuard,
+    symbol_index: Option<Arc<SymbolIndex>>,
 }
 
+/// Most callers listed per function; same-file and non-test callers come first
+const MAX_CALLERS: usize = 20;
+
 impl ContextExtractor {
     /// Create a new context extractor with all supported language parsers
     pub fn new() -> Result<Self> {
@@ -43,6 +54,8 @@ impl ContextExtractor {
         let mut extractor = Self {
             parsers: HashMap::new(),
             extractors,
+            file_guard: FileGuard::default(),
+            symbol_index: None,
         };
 
         // Initialize parsers
@@ -51,6 +64,26 @@ impl ContextExtractor {
         Ok(extractor)
     }
 
+    /// Size and binary limits for files read by [`Self::extract_context_from_file`]
+    pub fn with_file_guard(mut self, file_guard: FileGuard) -> Self {
+        self.file_guard = file_guard;
+        self
+    }
+
+    /// Resolve callers and callees across the workspace covered by `index`
+    ///
+    /// Without an index the call hierarchy only lists calls made in the
+    /// diagnostic's own file.
+    pub fn with_symbol_index(mut self, index: Arc<SymbolIndex>) -> Self {
+        self.set_symbol_index(Some(index));
+        self
+    }
+
+    /// Attach, replace or detach the workspace index, e.g. after a re-sync
+    pub fn set_symbol_index(&mut self, index: Option<Arc<SymbolIndex>>) {
+        self.symbol_index = index;
+    }
+
     fn init_parsers(&mut self) -> Result<()> {
         // TypeScript/JavaScript
         let mut ts_parser = Parser::new();
@@ -85,7 +118,21 @@ impl ContextExtractor {
         diagnostic: &Diagnostic,
         file_content: &str,
     ) -> Result<SemanticContext> {
-        let language = self.detect_language(&diagnostic.file);
+        let language = self.detect_language(&diagnostic.file, file_content, diagnostic.range.start.line);
+
+        // Templates are parsed with everything but the diagnostic's script block blanked out
+        let path = Path::new(&diagnostic.file);
+        let masked;
+        let file_content = match language_detection::embedded_blocks(path, file_content)
+            .into_iter()
+            .find(|block| block.contains_line(diagnostic.range.start.line))
+        {
+            Some(block) => {
+                masked = block.masked(file_content);
+                masked.as_str()
+            }
+            None => file_content,
+        };
 
         let parser_key = match language {
             Language::TypeScript => "typescript",
@@ -140,7 +187,8 @@ impl ContextExtractor {
         
         // Extract call hierarchy
         if let Some(node) = diagnostic_node {
-            context.call_hierarchy = self.extract_call_hierarchy(&node, file_content, language, extractor.as_ref())?;
+            context.call_hierarchy =
+                self.extract_call_hierarchy(&node, file_content, &diagnostic.file, extractor.as_ref())?;
         }
 
         // Extract dependencies
@@ -157,20 +205,19 @@ impl ContextExtractor {
         &mut self,
         diagnostic: &Diagnostic,
     ) -> Result<SemanticContext> {
-        let file_content = fs::read_to_string(&diagnostic.file)
+        let file_content = self
+            .file_guard
+            .read_to_string(Path::new(&diagnostic.file))
             .with_context(|| format!("Failed to read file: {}", diagnostic.file))?;
         self.extract_context(diagnostic, &file_content)
     }
 
-    fn detect_language(&self, file_path: &str) -> Language {
-        match Path::new(file_path)
-            .extension()
-            .and_then(|ext| ext.to_str())
-        {
-            Some("ts") | Some("tsx") => Language::TypeScript,
-            Some("js") | Some("jsx") => Language::JavaScript,
-            Some("rs") => Language::Rust,
-            Some("py") => Language::Python,
+    fn detect_language(&self, file_path: &str, content: &str, line: u32) -> Language {
+        match language_detection::language_at(Path::new(file_path), content, line) {
+            Some(DetectedLanguage::TypeScript) => Language::TypeScript,
+            Some(DetectedLanguage::JavaScript) => Language::JavaScript,
+            Some(DetectedLanguage::Rust) => Language::Rust,
+            Some(DetectedLanguage::Python) => Language::Python,
             _ => Language::Unknown,
         }
     }
@@ -179,26 +226,62 @@ impl ContextExtractor {
         &self,
         node: &Node,
         source: &str,
-        _language: Language,
+        file: &str,
         extractor: &dyn LanguageExtractor,
     ) -> Result<CallHierarchy> {
-        let mut hierarchy = CallHierarchy::default();
-
-        // Find the enclosing function
-        if let Some(func_node) = extractor.find_enclosing_function(node, source) {
-            // Extract callees (functions called by this function)
-            hierarchy.callees = extractor.extract_function_calls(&func_node, source);
-            
-            // For callers, we would need to search the entire codebase
-            // This is a simplified version that only looks in the current file
-            // In a real implementation, this would use an index or cross-file analysis
+        let mut hierarchy = CallHierarchy {
+            depth: 1,
+            ..CallHierarchy::default()
+        };
+        let Some(func_node) = extractor.find_enclosing_function(node, source) else {
+            return Ok(hierarchy);
+        };
+
+        hierarchy.callees = extractor.extract_function_calls(&func_node, source);
+        for call in &mut hierarchy.callees {
+            call.file_path = file.to_string();
+        }
+
+        let Some(index) = &self.symbol_index else {
+            return Ok(hierarchy);
+        };
+        let relative = Self::index_path(index, file);
+
+        // Callees point at their definitions wherever they live
+        for call in &mut hierarchy.callees {
+            let name = identifiers(&call.function_name).last().map(|(_, name)| *name).unwrap_or_default();
+            if let Some(definition) = index.resolve(name, &relative) {
+                call.file_path = definition.file.to_string_lossy().into_owned();
+                call.line = definition.line;
+            }
         }
 
-        hierarchy.depth = 1; // Single file analysis for now
+        // Callers are found through every occurrence of the function's name
+        if let Some(function) = extractor.extract_function_context(&func_node, source) {
+            let mut callers = index.callers(&function.name);
+            callers.sort_by_key(|(caller, _)| (caller.file != relative, caller.in_test));
+            hierarchy.callers = callers
+                .into_iter()
+                .take(MAX_CALLERS)
+                .map(|(caller, site)| FunctionCall {
+                    function_name: caller.name.clone(),
+                    file_path: caller.file.to_string_lossy().into_owned(),
+                    line: site.line,
+                    arguments: Vec::new(),
+                    is_direct: true,
+                })
+                .collect();
+        }
 
         Ok(hierarchy)
     }
 
+    /// A diagnostic's file as the index names it, relative to the index root
+    fn index_path(index: &SymbolIndex, file: &str) -> PathBuf {
+        let path = Path::new(file.strip_prefix("file://").unwrap_or(file));
+        path.strip_prefix(index.root()).unwrap_or(path).to_path_buf()
+    }
+
     fn extract_dependencies(
         &self,
         imports: &[ImportContext],
@@ -245,7 +328,7 @@ impl ContextExtractor {
         if !context.local_variables.is_empty() {
             score += 0.15;
         }
-        if !context.call_hierarchy.callees.is_empty() {
+        if !context.call_hierarchy.callees.is_empty() || !context.call_hierarchy.callers.is_empty() {
             score += 0.1;
         }
 
@@ -294,4 +377,62 @@ function processUser(user: User): string {
         assert!(context.function_context.is_some());
         assert!(context.type_definitions.iter().any(|t| t.name == "User"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_cross_file_call_hierarchy() {
+        let source = "pub fn apply(config: &str) -> usize {\n    let parsed = parse(config);\n    parsed.len()\n}\n";
+        let mut index = SymbolIndex::new("/work");
+        index.add_file(Path::new("src/apply.rs"), source);
+        index.add_file(Path::new("src/parse.rs"), "pub fn parse(input: &str) -> String {\n    input.trim().to_string()\n}\n");
+        index.add_file(Path::new("src/main.rs"), "fn main() {\n    let n = apply(\"x\");\n}\n");
+
+        let mut extractor = ContextExtractor::new().unwrap().with_symbol_index(Arc::new(index));
+        let diagnostic = Diagnostic::new(
+            "/work/src/apply.rs".to_string(),
+            Range {
+                start: Position { line: 1, character: 17 },
+                end: Position { line: 1, character: 22 },
+            },
+            DiagnosticSeverity::Error,
+            "mismatched types".to_string(),
+            "rustc".to_string(),
+        );
+
+        let hierarchy = extractor.extract_context(&diagnostic, source).unwrap().call_hierarchy;
+        let callers: Vec<_> = hierarchy.callers.iter().map(|c| (c.function_name.as_str(), c.file_path.as_str(), c.line)).collect();
+        assert_eq!(callers, vec![("main", "src/main.rs", 1)]);
+        let parse = hierarchy.callees.iter().find(|c| c.function_name == "parse").unwrap();
+        assert_eq!((parse.file_path.as_str(), parse.line), ("src/parse.rs", 0));
+    }
+
+    #[test]
+    fn test_context_extraction_vue_script_block() {
+        let mut extractor = ContextExtractor::new().unwrap();
+
+        let source = r#"<template>
+  <p>{{ greet(user) }}</p>
+</template>
+
+<script setup lang="ts">
+function greet(user: { name: string }): string {
+    return user.nme;
+}
+</script>
+"#;
+
+        let diagnostic = Diagnostic::new(
+            "components/Greeting.vue".to_string(),
+            Range {
+                start: Position { line: 6, character: 16 },
+                end: Position { line: 6, character: 19 },
+            },
+            DiagnosticSeverity::Error,
+            "Property 'nme' does not exist.".to_string(),
+            "volar".to_string(),
+        );
+
+        let context = extractor.extract_context(&diagnostic, source).unwrap();
+        let function = context.function_context.expect("function inside the script block");
+        assert_eq!(function.name, "greet");
+    }
+}
@@ -0,0 +1,490 @@
+//! Managed language server installs
+//!
+//! Direct capture launches language servers itself, which fails on fresh
+//! machines and CI runners that have none. `lspbridge servers install` puts
+//! the common servers in an LSPbridge-managed directory using each one's
+//! usual distribution channel, at the versions pinned in `lspbridge.toml`:
+//!
+//! ```toml
+//! [servers.pins.rust-analyzer]
+//! version = "2024-06-24"
+//! sha256 = "…"
+//!
+//! [servers.pins.gopls]
+//! version = "v0.16.1"
+//! ```
+//!
+//! Downloads are checked against the pinned SHA-256 and every install must
+//! answer `--version` before it is recorded. [`LanguageServerProfiles`]
+//! launches managed installs when a profile does not name a command.
+//!
+//! [`LanguageServerProfiles`]: crate::core::LanguageServerProfiles
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use clap::Subcommand;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Marker written next to a completed install
+const INSTALL_RECORD: &str = "installed.json";
+
+/// Managed language servers, under `[servers]` in `lspbridge.toml`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServersConfig {
+    /// Install directory; defaults to `servers` in the data directory
+    pub install_dir: Option<PathBuf>,
+    /// Pinned versions keyed by server name
+    pub pins: HashMap<String, ServerPin>,
+}
+
+/// Version a server is pinned to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerPin {
+    pub version: String,
+    /// Expected SHA-256 of the downloaded release, for servers installed from a download
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+impl ServersConfig {
+    /// The configured install directory
+    pub fn install_dir(&self) -> Result<PathBuf> {
+        match &self.install_dir {
+            Some(dir) => Ok(dir.clone()),
+            None => Ok(crate::config::data_dir()?.join("servers")),
+        }
+    }
+
+    pub fn installer(&self) -> Result<ServerInstaller> {
+        Ok(ServerInstaller {
+            dir: self.install_dir()?,
+            pins: self.pins.clone(),
+        })
+    }
+}
+
+/// Server actions
+#[derive(Debug, Clone, Subcommand)]
+pub enum ServersAction {
+    /// Install a language server into the managed directory
+    Install {
+        /// Language or server name: rust, typescript, python, go, or
+        /// rust-analyzer, typescript-language-server, pylsp, gopls
+        server: String,
+        /// Version to install instead of the pinned one
+        #[arg(long)]
+        version: Option<String>,
+        /// Reinstall even if the same version is already installed
+        #[arg(long)]
+        force: bool,
+    },
+    /// List supported servers and what is installed
+    List,
+}
+
+/// Language servers LSPbridge knows how to install
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KnownServer {
+    RustAnalyzer,
+    TypeScriptLanguageServer,
+    Pylsp,
+    Gopls,
+}
+
+impl KnownServer {
+    pub const ALL: [Self; 4] = [
+        Self::RustAnalyzer,
+        Self::TypeScriptLanguageServer,
+        Self::Pylsp,
+        Self::Gopls,
+    ];
+
+    /// Resolve a language or server name
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "rust" | "rust-analyzer" => Some(Self::RustAnalyzer),
+            "typescript" | "javascript" | "ts" | "js" | "typescript-language-server" => {
+                Some(Self::TypeScriptLanguageServer)
+            }
+            "python" | "py" | "pylsp" | "python-lsp-server" => Some(Self::Pylsp),
+            "go" | "golang" | "gopls" => Some(Self::Gopls),
+            _ => None,
+        }
+    }
+
+    /// Server name, as used for the binary, pins and language server profiles
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::RustAnalyzer => "rust-analyzer",
+            Self::TypeScriptLanguageServer => "typescript-language-server",
+            Self::Pylsp => "pylsp",
+            Self::Gopls => "gopls",
+        }
+    }
+
+    pub fn language(self) -> &'static str {
+        match self {
+            Self::RustAnalyzer => "rust",
+            Self::TypeScriptLanguageServer => "typescript",
+            Self::Pylsp => "python",
+            Self::Gopls => "go",
+        }
+    }
+
+    /// Version installed when nothing is pinned
+    fn default_version(self) -> &'static str {
+        "latest"
+    }
+
+    /// Tool the install runs, besides the server itself
+    fn prerequisite(self) -> &'static str {
+        match self {
+            Self::RustAnalyzer => "curl",
+            Self::TypeScriptLanguageServer => "npm",
+            Self::Pylsp => "python3",
+            Self::Gopls => "go",
+        }
+    }
+
+    /// Executable inside the server's install directory
+    pub fn binary(self, server_dir: &Path) -> PathBuf {
+        let exe = |name: &str| format!("{name}{}", std::env::consts::EXE_SUFFIX);
+        match self {
+            Self::RustAnalyzer | Self::Gopls => server_dir.join("bin").join(exe(self.name())),
+            Self::TypeScriptLanguageServer => server_dir
+                .join("node_modules")
+                .join(".bin")
+                .join(exe(self.name())),
+            Self::Pylsp if cfg!(windows) => server_dir.join("venv").join("Scripts").join(exe("pylsp")),
+            Self::Pylsp => server_dir.join("venv").join("bin").join("pylsp"),
+        }
+    }
+}
+
+/// A completed install, recorded in the server's directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstalledServer {
+    pub server: String,
+    pub version: String,
+    pub binary: PathBuf,
+    /// SHA-256 of the downloaded release, for download installs
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// First line of `--version`
+    pub reported_version: String,
+    pub installed_at: DateTime<Utc>,
+}
+
+/// Installs language servers into one managed directory
+#[derive(Debug, Clone)]
+pub struct ServerInstaller {
+    dir: PathBuf,
+    pins: HashMap<String, ServerPin>,
+}
+
+impl ServerInstaller {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            pins: HashMap::new(),
+        }
+    }
+
+    pub fn with_pin(mut self, server: impl Into<String>, pin: ServerPin) -> Self {
+        self.pins.insert(server.into(), pin);
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn pin(&self, server: KnownServer) -> Option<&ServerPin> {
+        self.pins.get(server.name())
+    }
+
+    /// The recorded install of a server, if its binary is still there
+    pub fn installed(&self, server: KnownServer) -> Option<InstalledServer> {
+        let record = fs::read(self.dir.join(server.name()).join(INSTALL_RECORD)).ok()?;
+        let installed: InstalledServer = serde_json::from_slice(&record).ok()?;
+        installed.binary.is_file().then_some(installed)
+    }
+
+    /// Managed binary for a server name, if one is installed
+    pub fn binary_for(&self, server: &str) -> Option<PathBuf> {
+        let server = KnownServer::parse(server)?;
+        self.installed(server).map(|installed| installed.binary)
+    }
+
+    /// Install a server at `version`, or its pinned version
+    ///
+    /// An install of the same version is kept unless `force` is set.
+    pub fn install(&self, server: KnownServer, version: Option<&str>, force: bool) -> Result<InstalledServer> {
+        let pin = self.pin(server);
+        let version = version
+            .or(pin.map(|pin| pin.version.as_str()))
+            .unwrap_or(server.default_version())
+            .to_string();
+        // A checksum only holds for the version it was pinned with
+        let expected_sha256 = pin
+            .filter(|pin| pin.version == version)
+            .and_then(|pin| pin.sha256.clone());
+
+        if let Some(installed) = self.installed(server) {
+            if installed.version == version && version != "latest" && !force {
+                return Ok(installed);
+            }
+        }
+
+        crate::core::NetworkGuard::global().check(format!("servers install {}", server.name()))?;
+
+        if Command::new(server.prerequisite()).arg("--version").output().is_err() {
+            return Err(anyhow!(
+                "Installing {} needs {}, which was not found on PATH",
+                server.name(),
+                server.prerequisite()
+            ));
+        }
+
+        // Each install gets its own directory: virtualenvs and npm shims embed
+        // their absolute path, so an install cannot be moved once made
+        let server_dir = self.dir.join(server.name());
+        let stamp = Utc::now().format("%Y%m%dT%H%M%S");
+        let install_dir = server_dir.join(format!("{}-{stamp}", sanitize_version(&version)));
+        fs::create_dir_all(&install_dir)
+            .with_context(|| format!("Failed to create {}", install_dir.display()))?;
+
+        let installed = self
+            .install_into(server, &version, expected_sha256.as_deref(), &install_dir)
+            .and_then(|sha256| {
+                let binary = server.binary(&install_dir);
+                Ok(InstalledServer {
+                    server: server.name().to_string(),
+                    version: version.clone(),
+                    reported_version: verify(&binary)?,
+                    binary,
+                    sha256,
+                    installed_at: Utc::now(),
+                })
+            });
+        let installed = match installed {
+            Ok(installed) => installed,
+            Err(e) => {
+                let _ = fs::remove_dir_all(&install_dir);
+                return Err(e);
+            }
+        };
+
+        // The previous install is only replaced once the new one runs
+        fs::write(server_dir.join(INSTALL_RECORD), serde_json::to_vec_pretty(&installed)?)?;
+        for entry in fs::read_dir(&server_dir)? {
+            let path = entry?.path();
+            if path.is_dir() && path != install_dir {
+                if let Err(e) = fs::remove_dir_all(&path) {
+                    tracing::warn!("Failed to remove old install {}: {}", path.display(), e);
+                }
+            }
+        }
+        Ok(installed)
+    }
+
+    /// Run the server's installer into `dir`, returning the download checksum if any
+    fn install_into(
+        &self,
+        server: KnownServer,
+        version: &str,
+        expected_sha256: Option<&str>,
+        dir: &Path,
+    ) -> Result<Option<String>> {
+        match server {
+            KnownServer::RustAnalyzer => {
+                let url = rust_analyzer_url(version)?;
+                let archive = dir.join("rust-analyzer.gz");
+                run(Command::new("curl").args(["-fsSL", "--retry", "3", "-o"]).arg(&archive).arg(&url))?;
+                let bytes = fs::read(&archive)?;
+                let sha256 = verify_sha256(&bytes, expected_sha256)?;
+
+                let binary = server.binary(dir);
+                fs::create_dir_all(binary.parent().unwrap_or(dir))?;
+                let mut decoder = flate2::read::GzDecoder::new(bytes.as_slice());
+                let mut file = fs::File::create(&binary)?;
+                std::io::copy(&mut decoder, &mut file)
+                    .with_context(|| format!("{url} is not a gzip archive"))?;
+                drop(file);
+                fs::remove_file(&archive)?;
+                make_executable(&binary)?;
+                Ok(Some(sha256))
+            }
+            KnownServer::TypeScriptLanguageServer => {
+                let package = format!("typescript-language-server@{version}");
+                run(Command::new("npm")
+                    .args(["install", "--no-audit", "--no-fund", "--prefix"])
+                    .arg(dir)
+                    .args([package.as_str(), "typescript"]))?;
+                Ok(None)
+            }
+            KnownServer::Pylsp => {
+                let venv = dir.join("venv");
+                run(Command::new("python3").args(["-m", "venv"]).arg(&venv))?;
+                let python = if cfg!(windows) {
+                    venv.join("Scripts").join("python.exe")
+                } else {
+                    venv.join("bin").join("python")
+                };
+                let requirement = match version {
+                    "latest" => "python-lsp-server".to_string(),
+                    version => format!("python-lsp-server=={version}"),
+                };
+                run(Command::new(python).args(["-m", "pip", "install", "--quiet", &requirement]))?;
+                Ok(None)
+            }
+            KnownServer::Gopls => {
+                let module = format!("golang.org/x/tools/gopls@{version}");
+                run(Command::new("go").args(["install", &module]).env("GOBIN", dir.join("bin")))?;
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Version as a directory name
+fn sanitize_version(version: &str) -> String {
+    version
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .collect()
+}
+
+/// Release asset of rust-analyzer for this platform
+fn rust_analyzer_url(version: &str) -> Result<String> {
+    let target = match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("linux", "x86_64") => "x86_64-unknown-linux-gnu",
+        ("linux", "aarch64") => "aarch64-unknown-linux-gnu",
+        ("macos", "x86_64") => "x86_64-apple-darwin",
+        ("macos", "aarch64") => "aarch64-apple-darwin",
+        (os, arch) => {
+            return Err(anyhow!(
+                "No rust-analyzer release to install for {os}/{arch}; try `rustup component add rust-analyzer`"
+            ))
+        }
+    };
+    let base = "https://github.com/rust-lang/rust-analyzer/releases";
+    Ok(match version {
+        "latest" => format!("{base}/latest/download/rust-analyzer-{target}.gz"),
+        version => format!("{base}/download/{version}/rust-analyzer-{target}.gz"),
+    })
+}
+
+/// Hex SHA-256 of a download, failing if it differs from the pinned one
+fn verify_sha256(bytes: &[u8], expected: Option<&str>) -> Result<String> {
+    let actual: String = Sha256::digest(bytes).iter().map(|byte| format!("{byte:02x}")).collect();
+    match expected {
+        Some(expected) if !expected.trim().eq_ignore_ascii_case(&actual) => Err(anyhow!(
+            "Checksum mismatch: expected {}, downloaded {actual}",
+            expected.trim()
+        )),
+        _ => Ok(actual),
+    }
+}
+
+/// Check an installed server runs, returning the first line of `--version`
+fn verify(binary: &Path) -> Result<String> {
+    let output = Command::new(binary)
+        .arg("--version")
+        .output()
+        .with_context(|| format!("Installed server {} does not run", binary.display()))?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "{} --version exited with {}: {}",
+            binary.display(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.lines().next().unwrap_or_default().trim().to_string())
+}
+
+fn run(command: &mut Command) -> Result<()> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let output = command
+        .output()
+        .with_context(|| format!("Failed to run {program}"))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    Err(anyhow!(
+        "{program} exited with {}: {}",
+        output.status,
+        String::from_utf8_lossy(&output.stderr).trim()
+    ))
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_languages_and_server_names_resolve() {
+        assert_eq!(KnownServer::parse("rust"), Some(KnownServer::RustAnalyzer));
+        assert_eq!(KnownServer::parse("TypeScript"), Some(KnownServer::TypeScriptLanguageServer));
+        assert_eq!(KnownServer::parse("pylsp"), Some(KnownServer::Pylsp));
+        assert_eq!(KnownServer::parse("go"), Some(KnownServer::Gopls));
+        assert_eq!(KnownServer::parse("cobol"), None);
+        for server in KnownServer::ALL {
+            assert_eq!(KnownServer::parse(server.name()), Some(server));
+        }
+    }
+
+    #[test]
+    fn test_pinned_checksum_is_enforced() {
+        let digest = verify_sha256(b"release", None).unwrap();
+        assert_eq!(verify_sha256(b"release", Some(&digest.to_uppercase())).unwrap(), digest);
+        assert!(verify_sha256(b"tampered", Some(&digest)).is_err());
+    }
+
+    #[test]
+    fn test_installed_requires_record_and_binary() {
+        let dir = TempDir::new().unwrap();
+        let installer = ServerInstaller::new(dir.path());
+        assert!(installer.installed(KnownServer::Gopls).is_none());
+
+        let server_dir = dir.path().join("gopls");
+        let binary = KnownServer::Gopls.binary(&server_dir.join("v0.16.1-20240101T000000"));
+        let record = InstalledServer {
+            server: "gopls".to_string(),
+            version: "v0.16.1".to_string(),
+            binary: binary.clone(),
+            sha256: None,
+            reported_version: "golang.org/x/tools/gopls v0.16.1".to_string(),
+            installed_at: Utc::now(),
+        };
+        fs::create_dir_all(binary.parent().unwrap()).unwrap();
+        fs::write(server_dir.join(INSTALL_RECORD), serde_json::to_vec(&record).unwrap()).unwrap();
+        // Recorded, but the binary has gone
+        assert!(installer.installed(KnownServer::Gopls).is_none());
+
+        fs::write(&binary, b"").unwrap();
+        assert_eq!(installer.installed(KnownServer::Gopls), Some(record));
+        assert_eq!(installer.binary_for("go"), Some(binary));
+    }
+}
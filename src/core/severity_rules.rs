@@ -0,0 +1,260 @@
+//! Severity re-mapping of diagnostics by source and code
+//!
+//! Language servers and linters don't always agree with a project about how
+//! serious a diagnostic is: an unused local reported as a TypeScript warning
+//! may be noise, while a security lint reported as a warning should block a
+//! merge. Rules promote or demote matching diagnostics before they are
+//! recorded in history, exported or offered quick fixes, and are configured
+//! in `lspbridge.toml` or the dynamic configuration:
+//!
+//! ```toml
+//! [[severity_rules.rules]]
+//! source = "typescript"
+//! code = "6133"          # noUnusedLocals
+//! from = "warning"
+//! to = "information"
+//!
+//! [[severity_rules.rules]]
+//! source = "lspbridge-security"
+//! to = "error"
+//! ```
+//!
+//! Rules are tried in order and the first match decides. The severity a
+//! diagnostic had before re-mapping is kept under [`ORIGINAL_SEVERITY_KEY`]
+//! in its `data`, and rules match that original severity, so applying them
+//! again changes nothing.
+
+use super::types::{Diagnostic, DiagnosticSeverity};
+use anyhow::{anyhow, Context, Result};
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+
+/// Key in [`Diagnostic::data`] holding the severity before re-mapping
+pub const ORIGINAL_SEVERITY_KEY: &str = "lspbridgeOriginalSeverity";
+
+/// One re-mapping, matched on source, code and current severity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeverityRule {
+    /// Source the rule applies to, compared ignoring case; any when unset
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Code the rule applies to, or a glob such as `clippy::*`; any when unset
+    #[serde(default)]
+    pub code: Option<String>,
+    /// Severity the rule applies to (`error`, `warning`, `information`, `hint`); any when unset
+    #[serde(default)]
+    pub from: Option<String>,
+    /// Severity matching diagnostics get
+    pub to: String,
+}
+
+/// Severity rules under `[severity_rules]`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeverityRulesConfig {
+    /// Rules in priority order
+    pub rules: Vec<SeverityRule>,
+}
+
+#[derive(Debug, Clone)]
+enum CodeMatcher {
+    Exact(String),
+    Glob(Pattern),
+}
+
+impl CodeMatcher {
+    fn matches(&self, code: &str) -> bool {
+        match self {
+            CodeMatcher::Exact(expected) => expected.eq_ignore_ascii_case(code),
+            CodeMatcher::Glob(pattern) => pattern.matches(code),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    source: Option<String>,
+    code: Option<CodeMatcher>,
+    from: Option<DiagnosticSeverity>,
+    to: DiagnosticSeverity,
+}
+
+impl CompiledRule {
+    fn matches(&self, diagnostic: &Diagnostic, severity: DiagnosticSeverity) -> bool {
+        self.source
+            .as_ref()
+            .map_or(true, |source| source.eq_ignore_ascii_case(&diagnostic.source))
+            && self.code.as_ref().map_or(true, |matcher| {
+                diagnostic.code.as_deref().is_some_and(|code| matcher.matches(code))
+            })
+            && self.from.map_or(true, |from| from == severity)
+    }
+}
+
+/// Promotes and demotes diagnostics by the configured rules
+#[derive(Debug, Clone, Default)]
+pub struct SeverityRules {
+    rules: Vec<CompiledRule>,
+}
+
+impl SeverityRules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compile the rules from configuration
+    pub fn from_config(config: &SeverityRulesConfig) -> Result<Self> {
+        config
+            .rules
+            .iter()
+            .try_fold(Self::new(), |rules, rule| rules.with_rule(rule))
+    }
+
+    /// Add a rule; rules are tried in the order they were added
+    pub fn with_rule(mut self, rule: &SeverityRule) -> Result<Self> {
+        let code = match &rule.code {
+            Some(code) if code.contains(['*', '?', '[']) => Some(CodeMatcher::Glob(
+                Pattern::new(code).with_context(|| format!("Invalid severity rule code pattern '{code}'"))?,
+            )),
+            Some(code) => Some(CodeMatcher::Exact(code.clone())),
+            None => None,
+        };
+        self.rules.push(CompiledRule {
+            source: rule.source.clone(),
+            code,
+            from: rule.from.as_deref().map(parse_severity).transpose()?,
+            to: parse_severity(&rule.to)?,
+        });
+        Ok(self)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Severity the first matching rule assigns, if any
+    pub fn severity_for(&self, diagnostic: &Diagnostic) -> Option<DiagnosticSeverity> {
+        let severity = diagnostic.original_severity().unwrap_or(diagnostic.severity);
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(diagnostic, severity))
+            .map(|rule| rule.to)
+    }
+
+    /// Re-map diagnostics in place, returning how many changed severity
+    pub fn apply(&self, diagnostics: &mut [Diagnostic]) -> usize {
+        let mut changed = 0;
+        for diagnostic in diagnostics {
+            let Some(severity) = self.severity_for(diagnostic) else {
+                continue;
+            };
+            if severity != diagnostic.severity && remember_original(diagnostic) {
+                diagnostic.severity = severity;
+                changed += 1;
+            }
+        }
+        changed
+    }
+}
+
+fn parse_severity(name: &str) -> Result<DiagnosticSeverity> {
+    name.parse()
+        .map_err(|_| anyhow!("Unknown severity '{name}' in severity rule; use error, warning, information or hint"))
+}
+
+/// Store the severity before re-mapping, unless a previous re-mapping already did
+///
+/// Returns false, leaving the diagnostic alone, when `data` is a language
+/// server payload that isn't a JSON object.
+fn remember_original(diagnostic: &mut Diagnostic) -> bool {
+    let original = serde_json::json!(diagnostic.severity);
+    match &mut diagnostic.data {
+        None => {
+            diagnostic.data = Some(serde_json::json!({ ORIGINAL_SEVERITY_KEY: original }));
+            true
+        }
+        Some(serde_json::Value::Object(map)) => {
+            map.entry(ORIGINAL_SEVERITY_KEY).or_insert(original);
+            true
+        }
+        Some(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{Position, Range};
+
+    fn diagnostic(source: &str, code: Option<&str>, severity: DiagnosticSeverity) -> Diagnostic {
+        let mut diagnostic = Diagnostic::new(
+            "src/app.ts".to_string(),
+            Range {
+                start: Position { line: 0, character: 0 },
+                end: Position { line: 0, character: 1 },
+            },
+            severity,
+            "message".to_string(),
+            source.to_string(),
+        );
+        diagnostic.code = code.map(str::to_string);
+        diagnostic
+    }
+
+    fn rule(source: Option<&str>, code: Option<&str>, from: Option<&str>, to: &str) -> SeverityRule {
+        SeverityRule {
+            source: source.map(str::to_string),
+            code: code.map(str::to_string),
+            from: from.map(str::to_string),
+            to: to.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_rules_promote_and_demote() {
+        let config = SeverityRulesConfig {
+            rules: vec![
+                rule(Some("typescript"), Some("6133"), Some("warning"), "information"),
+                rule(Some("lspbridge-security"), None, None, "error"),
+                rule(Some("clippy"), Some("clippy::pedantic*"), None, "hint"),
+            ],
+        };
+        let rules = SeverityRules::from_config(&config).unwrap();
+
+        let mut diagnostics = vec![
+            diagnostic("TypeScript", Some("6133"), DiagnosticSeverity::Warning),
+            diagnostic("typescript", Some("6133"), DiagnosticSeverity::Error),
+            diagnostic("typescript", Some("2322"), DiagnosticSeverity::Warning),
+            diagnostic("lspbridge-security", Some("hardcoded-secret"), DiagnosticSeverity::Warning),
+            diagnostic("clippy", Some("clippy::pedantic_cast"), DiagnosticSeverity::Warning),
+        ];
+        assert_eq!(rules.apply(&mut diagnostics), 3);
+        let severities: Vec<_> = diagnostics.iter().map(|d| d.severity).collect();
+        assert_eq!(
+            severities,
+            vec![
+                DiagnosticSeverity::Information,
+                DiagnosticSeverity::Error,
+                DiagnosticSeverity::Warning,
+                DiagnosticSeverity::Error,
+                DiagnosticSeverity::Hint,
+            ]
+        );
+        assert_eq!(diagnostics[0].original_severity(), Some(DiagnosticSeverity::Warning));
+        assert_eq!(diagnostics[2].original_severity(), None);
+
+        // Rules match the original severity, so a second pass is a no-op
+        assert_eq!(rules.apply(&mut diagnostics), 0);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Information);
+    }
+
+    #[test]
+    fn test_invalid_rules_are_rejected() {
+        let config = |to: &str| SeverityRulesConfig {
+            rules: vec![rule(None, None, None, to)],
+        };
+        assert!(SeverityRules::from_config(&config("fatal")).is_err());
+        assert!(SeverityRules::from_config(&config("Info")).is_ok());
+        assert!(SeverityRules::new().is_empty());
+    }
+}
@@ -0,0 +1,179 @@
+//! Debounced file system watching
+//!
+//! [`SourceWatcher`] reports batches of changed paths under a set of
+//! watched files and directories. Events are coalesced until the tree has
+//! been quiet for the debounce period, so saving many files at once (a
+//! formatter run, a branch switch) yields a single batch. Hidden and build
+//! output directories are ignored, as are paths passed to
+//! [`SourceWatcher::ignore`], typically where the watcher's own output goes.
+
+use super::symbol_index::SKIPPED_DIRS;
+use anyhow::{Context, Result};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Quiet period before a batch of changes is reported
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Watches files and directories, reporting debounced batches of changes
+pub struct SourceWatcher {
+    // Dropping the watcher stops the events
+    _watcher: RecommendedWatcher,
+    events: mpsc::UnboundedReceiver<PathBuf>,
+    watched_files: Vec<PathBuf>,
+    watched_dirs: Vec<PathBuf>,
+    ignored: Vec<PathBuf>,
+    debounce: Duration,
+}
+
+impl SourceWatcher {
+    /// Watch `paths`: directories recursively, files on their own
+    pub fn new(paths: &[PathBuf]) -> Result<Self> {
+        let (sender, events) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            if matches!(event.kind, EventKind::Access(_)) {
+                return;
+            }
+            for path in event.paths {
+                let _ = sender.send(path);
+            }
+        })
+        .context("Failed to start file watcher")?;
+
+        let (mut watched_files, mut watched_dirs) = (Vec::new(), Vec::new());
+        for path in paths {
+            let path = absolute(path);
+            let mode = if path.is_dir() {
+                watched_dirs.push(path.clone());
+                RecursiveMode::Recursive
+            } else {
+                watched_files.push(path.clone());
+                RecursiveMode::NonRecursive
+            };
+            watcher
+                .watch(&path, mode)
+                .with_context(|| format!("Failed to watch {}", path.display()))?;
+        }
+
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            watched_files,
+            watched_dirs,
+            ignored: Vec::new(),
+            debounce: DEFAULT_DEBOUNCE,
+        })
+    }
+
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Never report changes at or below `path`
+    pub fn ignore(mut self, path: &Path) -> Self {
+        self.ignored.push(absolute(path));
+        self
+    }
+
+    /// Wait for the next batch of changes
+    ///
+    /// Returns `None` once the watcher has stopped delivering events.
+    pub async fn next_change(&mut self) -> Option<BTreeSet<PathBuf>> {
+        let mut changed = BTreeSet::new();
+        while changed.is_empty() {
+            let path = self.events.recv().await?;
+            if self.is_relevant(&path) {
+                changed.insert(path);
+            }
+        }
+
+        while let Ok(Some(path)) = tokio::time::timeout(self.debounce, self.events.recv()).await {
+            if self.is_relevant(&path) {
+                changed.insert(path);
+            }
+        }
+        Some(changed)
+    }
+
+    fn is_relevant(&self, path: &Path) -> bool {
+        if self.watched_files.iter().any(|file| file == path) {
+            return true;
+        }
+        if self.ignored.iter().any(|ignored| path.starts_with(ignored)) {
+            return false;
+        }
+        // Only directories below the watched one count, wherever that lives
+        let relative = self
+            .watched_dirs
+            .iter()
+            .find_map(|dir| path.strip_prefix(dir).ok())
+            .unwrap_or(path);
+        !in_skipped_dir(relative)
+    }
+}
+
+/// Whether a path lies in a hidden or build output directory
+fn in_skipped_dir(path: &Path) -> bool {
+    path.parent().into_iter().flat_map(Path::components).any(|component| match component {
+        Component::Normal(name) => {
+            let name = name.to_string_lossy();
+            name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref())
+        }
+        _ => false,
+    })
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().map(|cwd| cwd.join(path)).unwrap_or_else(|_| path.to_path_buf())
+    };
+    // Watchers report resolved paths, e.g. /private/tmp for /tmp on macOS
+    path.canonicalize().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_skipped_dirs() {
+        assert!(in_skipped_dir(Path::new("target/debug/build.rs")));
+        assert!(in_skipped_dir(Path::new(".git/index")));
+        assert!(!in_skipped_dir(Path::new("src/.hidden.rs")));
+        assert!(!in_skipped_dir(Path::new("src/lib.rs")));
+    }
+
+    #[tokio::test]
+    async fn test_debounced_changes() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::create_dir(root.join("out")).unwrap();
+
+        let mut watcher = SourceWatcher::new(&[root.clone()])
+            .unwrap()
+            .with_debounce(Duration::from_millis(100))
+            .ignore(&root.join("out"));
+
+        std::fs::write(root.join("out/report.json"), "{}").unwrap();
+        std::fs::write(root.join("a.rs"), "fn a() {}").unwrap();
+        std::fs::write(root.join("b.rs"), "fn b() {}").unwrap();
+
+        let changed = tokio::time::timeout(Duration::from_secs(5), watcher.next_change())
+            .await
+            .expect("changes are reported")
+            .unwrap();
+        assert!(changed.contains(&root.join("a.rs")));
+        assert!(changed.contains(&root.join("b.rs")));
+        assert!(!changed.iter().any(|path| path.starts_with(root.join("out"))));
+    }
+}
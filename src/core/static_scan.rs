@@ -0,0 +1,757 @@
+//! Static project scan for repositories without a language server
+//!
+//! Some repositories have no language server or compiler available (a fresh
+//! clone on a CI box, an unfamiliar toolchain). [`StaticScanner`] produces an
+//! initial diagnostic set from checks that need nothing but the source tree:
+//!
+//! - local imports that don't resolve to a file (`./foo` in TypeScript,
+//!   `from .foo import x` in Python, `mod foo;` in Rust)
+//! - `TODO`/`FIXME` comments, escalated to warnings once `git blame` says
+//!   they are older than `todo_max_age_days`
+//! - insecure patterns such as SQL string concatenation and disabled TLS
+//!   verification, via [`SecurityAnalyzer`](crate::analyzers::SecurityAnalyzer)
+//! - custom tree-sitter query rules configured in `lspbridge.toml`:
+//!
+//! ```toml
+//! [scan]
+//! todo_max_age_days = 90
+//!
+//! [[scan.rules]]
+//! id = "no-unwrap"
+//! language = "rust"
+//! query = '(call_expression function: (field_expression field: (field_identifier) @m (#eq? @m "unwrap"))) @match'
+//! message = "Avoid unwrap() outside tests"
+//! severity = "warning"
+//! ```
+//!
+//! Rules report the node captured as `@match`, or the first capture when
+//! there is none. The report serializes to LSP-style JSON that
+//! `lspbridge export` reads from stdin, so scan results go through the normal
+//! privacy, filtering and export pipeline.
+
+use crate::analyzers::SecurityAnalyzer;
+use super::dependency_analyzer::resolvers::typescript::TypeScriptResolver;
+use super::dependency_analyzer::resolvers::LanguageResolver;
+use super::dependency_analyzer::Language;
+use super::git_integration::GitIntegration;
+use super::types::{Diagnostic, DiagnosticSeverity, Position, Range};
+use anyhow::{anyhow, Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+use tree_sitter::{Node, Parser, Query, QueryCursor, Tree};
+
+/// `source` of every diagnostic produced by a scan
+pub const SCAN_SOURCE: &str = "lspbridge-scan";
+
+/// Directories never descended into
+const SKIPPED_DIRS: &[&str] = &[
+    "node_modules",
+    "target",
+    "dist",
+    "build",
+    "vendor",
+    "__pycache__",
+    "venv",
+];
+
+/// Extensions checked for TODO/FIXME comments
+const COMMENT_EXTENSIONS: &[&str] = &[
+    "rs", "ts", "tsx", "js", "jsx", "mjs", "cjs", "py", "go", "java", "kt", "c", "h", "cc", "cpp",
+    "hpp", "cs", "rb", "swift", "sh",
+];
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// A tree-sitter query reported as a diagnostic
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanRule {
+    /// Reported as the diagnostic code
+    pub id: String,
+    /// `rust`, `typescript` (also used for JavaScript) or `python`
+    pub language: String,
+    /// Tree-sitter query in S-expression syntax
+    pub query: String,
+    pub message: String,
+    /// `error`, `warning`, `info` or `hint`
+    #[serde(default = "default_rule_severity")]
+    pub severity: String,
+}
+
+/// Scan settings in `lspbridge.toml`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanConfig {
+    /// Report local imports that don't resolve to a file
+    #[serde(default = "default_enabled")]
+    pub check_imports: bool,
+    /// Report TODO/FIXME comments
+    #[serde(default = "default_enabled")]
+    pub check_todos: bool,
+    /// Report insecure code patterns
+    #[serde(default = "default_enabled")]
+    pub check_security: bool,
+    /// Age after which a TODO/FIXME is reported as a warning
+    #[serde(default = "default_todo_max_age_days")]
+    pub todo_max_age_days: u64,
+    /// Larger files are skipped
+    #[serde(default = "default_max_file_size")]
+    pub max_file_size: u64,
+    #[serde(default)]
+    pub rules: Vec<ScanRule>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_todo_max_age_days() -> u64 {
+    90
+}
+
+fn default_max_file_size() -> u64 {
+    1024 * 1024
+}
+
+fn default_rule_severity() -> String {
+    "warning".to_string()
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            check_imports: true,
+            check_todos: true,
+            check_security: true,
+            todo_max_age_days: default_todo_max_age_days(),
+            max_file_size: default_max_file_size(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+/// Diagnostics found by a scan
+#[derive(Debug, Clone)]
+pub struct ScanReport {
+    pub root: PathBuf,
+    pub files_scanned: usize,
+    /// Ordered by file and position
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ScanReport {
+    /// Diagnostics as LSP-style JSON, the format `lspbridge export` reads from stdin
+    pub fn to_lsp_json(&self) -> serde_json::Value {
+        let diagnostics: Vec<serde_json::Value> = self
+            .diagnostics
+            .iter()
+            .map(|d| {
+                serde_json::json!({
+                    "uri": d.file,
+                    "range": d.range,
+                    "severity": d.severity as u8,
+                    "code": d.code,
+                    "message": d.message,
+                    "source": d.source,
+                })
+            })
+            .collect();
+        serde_json::json!({ "source": SCAN_SOURCE, "diagnostics": diagnostics })
+    }
+
+    /// Number of diagnostics per code
+    pub fn count_by_code(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for diagnostic in &self.diagnostics {
+            let code = diagnostic.code.clone().unwrap_or_default();
+            *counts.entry(code).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+struct CompiledRule {
+    id: String,
+    message: String,
+    severity: DiagnosticSeverity,
+    language: Language,
+    query: Query,
+    capture: u32,
+}
+
+/// A TODO/FIXME comment waiting for its age from `git blame`
+pub(crate) struct PendingTodo {
+    pub(crate) file: PathBuf,
+    pub(crate) range: Range,
+    /// `TODO` or `FIXME`
+    pub(crate) marker: String,
+    pub(crate) text: String,
+}
+
+/// Runs static checks over a source tree
+pub struct StaticScanner {
+    root: PathBuf,
+    config: ScanConfig,
+    rules: Vec<CompiledRule>,
+    security: Option<SecurityAnalyzer>,
+}
+
+impl StaticScanner {
+    /// Create a scanner, compiling the configured rules
+    pub fn new(root: &Path, config: ScanConfig) -> Result<Self> {
+        let root = root
+            .canonicalize()
+            .with_context(|| format!("Cannot scan {}", root.display()))?;
+
+        let rules = config
+            .rules
+            .iter()
+            .map(|rule| {
+                let language = language_for_name(&rule.language)
+                    .ok_or_else(|| anyhow!("Scan rule '{}': unsupported language '{}'", rule.id, rule.language))?;
+                let query = Query::new(grammar(language)?, &rule.query)
+                    .map_err(|e| anyhow!("Scan rule '{}': invalid query: {:?}", rule.id, e))?;
+                let capture = query.capture_index_for_name("match").unwrap_or(0);
+                let severity = rule
+                    .severity
+                    .parse::<DiagnosticSeverity>()
+                    .map_err(|e| anyhow!("Scan rule '{}': {}", rule.id, e))?;
+                Ok(CompiledRule {
+                    id: rule.id.clone(),
+                    message: rule.message.clone(),
+                    severity,
+                    language,
+                    query,
+                    capture,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let security = config.check_security.then(SecurityAnalyzer::new);
+        Ok(Self {
+            root,
+            config,
+            rules,
+            security,
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Scan every source file under the root
+    pub async fn scan(&self) -> Result<ScanReport> {
+        let mut diagnostics = Vec::new();
+        let mut todos = Vec::new();
+        let files = self.source_files();
+
+        for path in &files {
+            let content = match std::fs::read_to_string(path) {
+                Ok(content) => content,
+                Err(e) => {
+                    tracing::debug!("Skipping {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            diagnostics.extend(self.check_file(path, &content)?);
+            if self.config.check_todos && has_extension(path, COMMENT_EXTENSIONS) {
+                todos.extend(find_todos(path, &content));
+            }
+        }
+
+        if !todos.is_empty() {
+            diagnostics.extend(self.age_todos(todos).await);
+        }
+
+        diagnostics.sort_by(|a, b| {
+            (&a.file, a.range.start.line, a.range.start.character)
+                .cmp(&(&b.file, b.range.start.line, b.range.start.character))
+        });
+
+        Ok(ScanReport {
+            root: self.root.clone(),
+            files_scanned: files.len(),
+            diagnostics,
+        })
+    }
+
+    /// Scan a single file, e.g. to refresh its diagnostics after it changed
+    pub async fn scan_file(&self, path: &Path) -> Result<Vec<Diagnostic>> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Cannot scan {}", path.display()))?;
+        let mut diagnostics = self.check_file(path, &content)?;
+        if self.config.check_todos && has_extension(path, COMMENT_EXTENSIONS) {
+            let todos = find_todos(path, &content);
+            if !todos.is_empty() {
+                diagnostics.extend(self.age_todos(todos).await);
+            }
+        }
+        diagnostics.sort_by_key(|d| (d.range.start.line, d.range.start.character));
+        Ok(diagnostics)
+    }
+
+    fn source_files(&self) -> Vec<PathBuf> {
+        source_files(&self.root, self.config.max_file_size)
+    }
+
+    /// Parse a file and run the import checks and custom rules on it
+    fn check_file(&self, path: &Path, content: &str) -> Result<Vec<Diagnostic>> {
+        let language = language_for_path(path);
+        let has_rules = self.rules.iter().any(|r| same_language(r.language, language));
+        if matches!(language, Language::Unknown) || !(self.config.check_imports || has_rules || self.security.is_some()) {
+            return Ok(Vec::new());
+        }
+
+        let mut parser = Parser::new();
+        parser.set_language(grammar(language)?)?;
+        let Some(tree) = parser.parse(content, None) else {
+            return Ok(Vec::new());
+        };
+
+        let mut diagnostics = Vec::new();
+        if self.config.check_imports {
+            diagnostics.extend(unresolved_imports(language, &tree, content, path));
+        }
+        if let Some(security) = &self.security {
+            diagnostics.extend(security.analyze_tree(language, &tree, content, path));
+        }
+        for rule in self.rules.iter().filter(|r| same_language(r.language, language)) {
+            let mut cursor = QueryCursor::new();
+            for found in cursor.matches(&rule.query, tree.root_node(), content.as_bytes()) {
+                let node = found
+                    .captures
+                    .iter()
+                    .find(|c| c.index == rule.capture)
+                    .or_else(|| found.captures.first())
+                    .map(|c| c.node);
+                if let Some(node) = node {
+                    diagnostics.push(scan_diagnostic(path, node_range(&node), rule.severity, &rule.id, rule.message.clone()));
+                }
+            }
+        }
+        Ok(diagnostics)
+    }
+
+    /// Turn TODOs into diagnostics, escalating ones older than the configured age
+    async fn age_todos(&self, todos: Vec<PendingTodo>) -> Vec<Diagnostic> {
+        let max_age = Duration::from_secs(self.config.todo_max_age_days * SECONDS_PER_DAY);
+        let lines: Vec<(PathBuf, u32)> = todos.iter().map(|t| (t.file.clone(), t.range.start.line)).collect();
+        let ages = line_ages(&self.root, &lines).await;
+
+        let mut diagnostics = Vec::new();
+        for (todo, age) in todos.into_iter().zip(ages) {
+            let base = if todo.marker == "FIXME" {
+                DiagnosticSeverity::Information
+            } else {
+                DiagnosticSeverity::Hint
+            };
+            let (severity, message) = match age {
+                Some(age) => {
+                    let days = age.as_secs() / SECONDS_PER_DAY;
+                    let severity = if age > max_age { DiagnosticSeverity::Warning } else { base };
+                    (severity, format!("{}: {} ({} days old)", todo.marker, todo.text, days))
+                }
+                None => (base, format!("{}: {}", todo.marker, todo.text)),
+            };
+            diagnostics.push(scan_diagnostic(
+                &todo.file,
+                todo.range,
+                severity,
+                &todo.marker.to_lowercase(),
+                message,
+            ));
+        }
+        diagnostics
+    }
+}
+
+/// Files under `root` that may hold comments, skipping hidden, vendored and oversized ones
+pub(crate) fn source_files(root: &Path, max_file_size: u64) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = walkdir::WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            entry.depth() == 0
+                || !(name.starts_with('.') || (entry.file_type().is_dir() && SKIPPED_DIRS.contains(&name.as_ref())))
+        })
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| entry.metadata().map(|m| m.len() <= max_file_size).unwrap_or(false))
+        .map(|entry| entry.into_path())
+        .filter(|path| has_extension(path, COMMENT_EXTENSIONS))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Age of each `(file, zero-based line)` according to `git blame`
+///
+/// `None` where there is no repository or the line isn't committed yet.
+pub(crate) async fn line_ages(root: &Path, lines: &[(PathBuf, u32)]) -> Vec<Option<Duration>> {
+    let git = GitIntegration::new_with_repo(root.to_path_buf()).await.ok();
+    let now = SystemTime::now();
+
+    let mut blame: BTreeMap<PathBuf, HashMap<u32, SystemTime>> = BTreeMap::new();
+    let mut ages = Vec::with_capacity(lines.len());
+    for (file, line) in lines {
+        if let Some(git) = &git {
+            if !blame.contains_key(file) {
+                let times = git.get_line_commit_times(file).await.unwrap_or_default();
+                blame.insert(file.clone(), times);
+            }
+        }
+        ages.push(
+            blame
+                .get(file)
+                .and_then(|times| times.get(line))
+                .and_then(|time| now.duration_since(*time).ok()),
+        );
+    }
+    ages
+}
+
+fn scan_diagnostic(
+    path: &Path,
+    range: Range,
+    severity: DiagnosticSeverity,
+    code: &str,
+    message: String,
+) -> Diagnostic {
+    let mut diagnostic = Diagnostic::new(
+        path.to_string_lossy().to_string(),
+        range,
+        severity,
+        message,
+        SCAN_SOURCE.to_string(),
+    );
+    diagnostic.code = Some(code.to_string());
+    diagnostic
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.contains(&ext))
+}
+
+pub(crate) fn language_for_path(path: &Path) -> Language {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("ts") | Some("tsx") | Some("js") | Some("jsx") | Some("mjs") | Some("cjs") => Language::TypeScript,
+        Some("rs") => Language::Rust,
+        Some("py") => Language::Python,
+        _ => Language::Unknown,
+    }
+}
+
+fn language_for_name(name: &str) -> Option<Language> {
+    match name.to_lowercase().as_str() {
+        "rust" | "rs" => Some(Language::Rust),
+        "typescript" | "ts" | "javascript" | "js" => Some(Language::TypeScript),
+        "python" | "py" => Some(Language::Python),
+        _ => None,
+    }
+}
+
+fn same_language(a: Language, b: Language) -> bool {
+    std::mem::discriminant(&a) == std::mem::discriminant(&b)
+}
+
+pub(crate) fn grammar(language: Language) -> Result<tree_sitter::Language> {
+    match language {
+        Language::TypeScript => Ok(tree_sitter_typescript::language_typescript()),
+        Language::Rust => Ok(tree_sitter_rust::language()),
+        Language::Python => Ok(tree_sitter_python::language()),
+        Language::Unknown => Err(anyhow!("Unsupported language")),
+    }
+}
+
+pub(crate) fn node_range(node: &Node) -> Range {
+    let start = node.start_position();
+    let end = node.end_position();
+    Range {
+        start: Position { line: start.row as u32, character: start.column as u32 },
+        end: Position { line: end.row as u32, character: end.column as u32 },
+    }
+}
+
+pub(crate) fn node_text<'a>(node: &Node, source: &'a str) -> &'a str {
+    node.utf8_text(source.as_bytes()).unwrap_or_default()
+}
+
+/// Depth-first walk over every node of a tree
+pub(crate) fn visit_nodes<F>(tree: &Tree, mut callback: F)
+where
+    F: FnMut(Node),
+{
+    let mut cursor = tree.walk();
+    loop {
+        callback(cursor.node());
+
+        if cursor.goto_first_child() {
+            continue;
+        }
+
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return;
+            }
+        }
+    }
+}
+
+/// Local imports whose target file doesn't exist
+fn unresolved_imports(language: Language, tree: &Tree, source: &str, path: &Path) -> Vec<Diagnostic> {
+    let Some(dir) = path.parent() else {
+        return Vec::new();
+    };
+    let mut diagnostics = Vec::new();
+
+    visit_nodes(tree, |node| {
+        let unresolved = match (language, node.kind()) {
+            (Language::TypeScript, "import_statement" | "export_statement") => node
+                .child_by_field_name("source")
+                .map(|n| (n, node_text(&n, source).trim_matches(|c| c == '"' || c == '\'' || c == '`')))
+                .filter(|(_, spec)| spec.starts_with("./") || spec.starts_with("../"))
+                .filter(|(_, spec)| {
+                    !dir.join(spec).is_file()
+                        && TypeScriptResolver::new().resolve_import_path(path, spec).is_none()
+                })
+                .map(|(n, spec)| (n, format!("Cannot find module '{spec}'"))),
+            (Language::Python, "import_from_statement") => node
+                .child_by_field_name("module_name")
+                .filter(|n| n.kind() == "relative_import")
+                .map(|n| (n, node_text(&n, source)))
+                .filter(|(_, module)| !python_relative_module_exists(dir, module))
+                .map(|(n, module)| (n, format!("Cannot resolve relative import '{module}'"))),
+            (Language::Rust, "mod_item") if node.child_by_field_name("body").is_none() => {
+                let has_path_attribute = node
+                    .prev_named_sibling()
+                    .filter(|n| n.kind() == "attribute_item")
+                    .is_some_and(|n| node_text(&n, source).contains("path"));
+                node.child_by_field_name("name")
+                    .filter(|_| !has_path_attribute)
+                    .map(|n| (n, node_text(&n, source)))
+                    .filter(|(_, name)| !rust_module_exists(path, name))
+                    .map(|(n, name)| (n, format!("File not found for module `{name}`")))
+            }
+            _ => None,
+        };
+
+        if let Some((target, message)) = unresolved {
+            diagnostics.push(scan_diagnostic(
+                path,
+                node_range(&target),
+                DiagnosticSeverity::Error,
+                "unresolved-import",
+                message,
+            ));
+        }
+    });
+
+    diagnostics
+}
+
+/// Whether `from <module> import ...` resolves, where `module` starts with dots
+fn python_relative_module_exists(dir: &Path, module: &str) -> bool {
+    let dots = module.chars().take_while(|c| *c == '.').count();
+    let mut base = dir.to_path_buf();
+    for _ in 1..dots {
+        if !base.pop() {
+            return false;
+        }
+    }
+
+    let rest = &module[dots..];
+    if rest.is_empty() {
+        // `from . import x` names modules or package attributes; only the package is checked
+        return base.is_dir();
+    }
+    let target = base.join(rest.replace('.', "/"));
+    target.with_extension("py").is_file() || target.join("__init__.py").is_file()
+}
+
+/// Whether `mod name;` declared in `file` has a source file
+fn rust_module_exists(file: &Path, name: &str) -> bool {
+    let Some(parent) = file.parent() else {
+        return false;
+    };
+    let owns_directory = matches!(
+        file.file_name().and_then(|n| n.to_str()),
+        Some("mod.rs") | Some("lib.rs") | Some("main.rs")
+    );
+    let dir = if owns_directory {
+        parent.to_path_buf()
+    } else {
+        match file.file_stem() {
+            Some(stem) => parent.join(stem),
+            None => return false,
+        }
+    };
+    let name = name.trim_start_matches("r#");
+    dir.join(format!("{name}.rs")).is_file() || dir.join(name).join("mod.rs").is_file()
+}
+
+fn todo_regex() -> &'static Regex {
+    static TODO: OnceLock<Regex> = OnceLock::new();
+    TODO.get_or_init(|| {
+        Regex::new(r"(?://+|#|/\*+|^\s*\*|<!--)\s*(TODO|FIXME)\b(?:\([^)]*\))?:?\s*(.*?)\s*(?:\*/|-->)?\s*$")
+            .expect("valid TODO regex")
+    })
+}
+
+/// Marker regex for lines already known to be inside a comment
+fn comment_todo_regex() -> &'static Regex {
+    static TODO: OnceLock<Regex> = OnceLock::new();
+    TODO.get_or_init(|| {
+        Regex::new(r"^[\s/*#!-]*(TODO|FIXME)\b(?:\([^)]*\))?:?\s*(.*?)\s*(?:\*/)?\s*$").expect("valid TODO regex")
+    })
+}
+
+/// TODO/FIXME markers in comments
+///
+/// Comments are found with tree-sitter where a grammar is available, so
+/// markers inside string literals are ignored; other files are matched line
+/// by line.
+pub(crate) fn find_todos(path: &Path, content: &str) -> Vec<PendingTodo> {
+    let language = language_for_path(path);
+    let tree = grammar(language).ok().and_then(|grammar| {
+        let mut parser = Parser::new();
+        parser.set_language(grammar).ok()?;
+        parser.parse(content, None)
+    });
+    let Some(tree) = tree else {
+        return content
+            .lines()
+            .enumerate()
+            .filter_map(|(line, text)| pending_todo(path, todo_regex(), line as u32, 0, text))
+            .collect();
+    };
+
+    let mut todos = Vec::new();
+    visit_nodes(&tree, |node| {
+        if !node.kind().ends_with("comment") {
+            return;
+        }
+        let start = node.start_position();
+        for (offset, text) in node_text(&node, content).lines().enumerate() {
+            let column = if offset == 0 { start.column as u32 } else { 0 };
+            let line = (start.row + offset) as u32;
+            todos.extend(pending_todo(path, comment_todo_regex(), line, column, text));
+        }
+    });
+    todos
+}
+
+fn pending_todo(path: &Path, regex: &Regex, line: u32, column: u32, text: &str) -> Option<PendingTodo> {
+    let captures = regex.captures(text)?;
+    let marker = captures.get(1)?;
+    let description = captures.get(2).map(|m| m.as_str()).unwrap_or_default();
+    Some(PendingTodo {
+        file: path.to_path_buf(),
+        range: Range {
+            start: Position { line, character: column + marker.start() as u32 },
+            end: Position { line, character: column + text.len() as u32 },
+        },
+        marker: marker.as_str().to_string(),
+        text: if description.is_empty() { "(no description)".to_string() } else { description.to_string() },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_scan_reports_unresolved_local_imports() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        write(root, "web/app.ts", "import { a } from './util';\nimport b from './missing';\nimport c from 'react';\n");
+        write(root, "web/util.ts", "export const a = 1;\n");
+        write(root, "pkg/__init__.py", "");
+        write(root, "pkg/main.py", "from .helpers import run\nfrom .gone import x\nimport os\n");
+        write(root, "pkg/helpers.py", "def run(): pass\n");
+        write(root, "src/lib.rs", "mod present;\nmod absent;\nmod inline { }\n");
+        write(root, "src/present.rs", "");
+        write(root, "node_modules/dep/index.ts", "import x from './nowhere';\n");
+
+        let scanner = StaticScanner::new(root, ScanConfig::default()).unwrap();
+        let report = scanner.scan().await.unwrap();
+        let unresolved: Vec<&Diagnostic> = report
+            .diagnostics
+            .iter()
+            .filter(|d| d.code.as_deref() == Some("unresolved-import"))
+            .collect();
+
+        assert_eq!(unresolved.len(), 3, "{unresolved:#?}");
+        assert!(unresolved[0].file.ends_with("pkg/main.py"));
+        assert_eq!(unresolved[0].range.start.line, 1);
+        assert!(unresolved[1].message.contains("`absent`"));
+        assert!(unresolved[2].message.contains("'./missing'"));
+        assert!(unresolved.iter().all(|d| d.severity == DiagnosticSeverity::Error && d.source == SCAN_SOURCE));
+
+        let json = report.to_lsp_json();
+        assert_eq!(json["diagnostics"].as_array().unwrap().len(), report.diagnostics.len());
+        assert_eq!(json["diagnostics"][0]["severity"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_scan_todos_and_custom_rules() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "src/main.rs",
+            "// TODO: handle errors\nfn main() {\n    let v = parse().unwrap(); // FIXME(ops) flaky\n}\n",
+        );
+        write(root, "src/secrets.rs", "const DB_PASSWORD: &str = \"s3cr3t-pass\";\n");
+
+        let config = ScanConfig {
+            rules: vec![ScanRule {
+                id: "no-unwrap".to_string(),
+                language: "rust".to_string(),
+                query: r#"(call_expression function: (field_expression field: (field_identifier) @m (#eq? @m "unwrap"))) @match"#
+                    .to_string(),
+                message: "Avoid unwrap()".to_string(),
+                severity: "error".to_string(),
+            }],
+            ..ScanConfig::default()
+        };
+        let report = StaticScanner::new(root, config).unwrap().scan().await.unwrap();
+        let counts = report.count_by_code();
+        assert_eq!(counts.get("todo"), Some(&1));
+        assert_eq!(counts.get("fixme"), Some(&1));
+        assert_eq!(counts.get("no-unwrap"), Some(&1));
+        assert_eq!(counts.get("hardcoded-credential"), Some(&1));
+
+        let todo = &report.diagnostics[0];
+        assert_eq!(todo.message, "TODO: handle errors");
+        assert_eq!(todo.severity, DiagnosticSeverity::Hint);
+        let fixme = report.diagnostics.iter().find(|d| d.code.as_deref() == Some("fixme")).unwrap();
+        assert_eq!(fixme.message, "FIXME: flaky");
+        let rule = report.diagnostics.iter().find(|d| d.code.as_deref() == Some("no-unwrap")).unwrap();
+        assert_eq!(rule.range.start.line, 2);
+        assert_eq!(rule.severity, DiagnosticSeverity::Error);
+
+        let bad = ScanConfig {
+            rules: vec![ScanRule {
+                id: "broken".to_string(),
+                language: "rust".to_string(),
+                query: "(not_a_node)".to_string(),
+                message: String::new(),
+                severity: default_rule_severity(),
+            }],
+            ..ScanConfig::default()
+        };
+        assert!(StaticScanner::new(root, bad).is_err());
+    }
+}
@@ -0,0 +1,423 @@
+//! Workspace symbol index
+//!
+//! Maps every identifier in the workspace's source files to the places it
+//! occurs. The index is lexical: it does not resolve scopes, imports or
+//! shadowing, so an unrelated symbol sharing a name is reported as well.
+//! That errs on the side of overstating how much a change touches, which is
+//! what impact reports want. Text after a `//` line comment is ignored.
+//!
+//! Occurrences are flagged as test code when the file lives in a test
+//! directory or is named like a test (`foo_test.go`, `foo.spec.ts`), or,
+//! in Rust, when they follow a `#[cfg(test)]` attribute.
+//!
+//! Definitions of Rust, TypeScript/JavaScript and Python files are parsed
+//! with tree-sitter and cover types, traits, constants and modules as well
+//! as functions. In other languages only functions are recognized, the same
+//! lexical way: the first name followed by `(` or `<` after `fn`, `def`,
+//! `func` or `function`. Call hierarchies only consider functions and
+//! methods.
+
+use super::file_guard::FileGuard;
+use super::language_detection::detect_file_language;
+use super::symbol_parser::parse_definitions;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Directories never indexed, besides hidden ones
+pub(crate) const SKIPPED_DIRS: &[&str] = &["target", "node_modules", "dist", "build", "vendor"];
+
+/// Keywords that introduce a function definition
+const DEFINITION_KEYWORDS: &[&str] = &["fn", "def", "func", "function"];
+
+/// One occurrence of an identifier
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolOccurrence {
+    /// Path relative to the index root
+    pub file: PathBuf,
+    /// Zero-based line
+    pub line: u32,
+    /// Zero-based character offset within the line
+    pub character: u32,
+    /// Whether the occurrence is in test code
+    pub in_test: bool,
+}
+
+/// What a definition declares
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolKind {
+    #[default]
+    Function,
+    Method,
+    Class,
+    Struct,
+    Enum,
+    Interface,
+    Trait,
+    TypeAlias,
+    Constant,
+    Module,
+    Macro,
+}
+
+impl SymbolKind {
+    pub const ALL: &'static [SymbolKind] = &[
+        SymbolKind::Function,
+        SymbolKind::Method,
+        SymbolKind::Class,
+        SymbolKind::Struct,
+        SymbolKind::Enum,
+        SymbolKind::Interface,
+        SymbolKind::Trait,
+        SymbolKind::TypeAlias,
+        SymbolKind::Constant,
+        SymbolKind::Module,
+        SymbolKind::Macro,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SymbolKind::Function => "function",
+            SymbolKind::Method => "method",
+            SymbolKind::Class => "class",
+            SymbolKind::Struct => "struct",
+            SymbolKind::Enum => "enum",
+            SymbolKind::Interface => "interface",
+            SymbolKind::Trait => "trait",
+            SymbolKind::TypeAlias => "type_alias",
+            SymbolKind::Constant => "constant",
+            SymbolKind::Module => "module",
+            SymbolKind::Macro => "macro",
+        }
+    }
+
+    /// Parse a kind as written by [`SymbolKind::as_str`]
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|kind| kind.as_str().eq_ignore_ascii_case(name))
+    }
+
+    /// Whether the symbol can be called, and so take part in call hierarchies
+    pub fn is_callable(self) -> bool {
+        matches!(self, SymbolKind::Function | SymbolKind::Method)
+    }
+}
+
+/// A symbol defined in the workspace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolDefinition {
+    pub name: String,
+    /// Path relative to the index root
+    pub file: PathBuf,
+    /// Zero-based line of the definition
+    pub line: u32,
+    /// Whether the definition is in test code
+    pub in_test: bool,
+    #[serde(default)]
+    pub kind: SymbolKind,
+    /// Type, trait, class or module the symbol is declared in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container: Option<String>,
+}
+
+/// Identifier occurrences across a workspace
+#[derive(Debug, Clone, Default)]
+pub struct SymbolIndex {
+    root: PathBuf,
+    occurrences: HashMap<String, Vec<SymbolOccurrence>>,
+    definitions: Vec<SymbolDefinition>,
+}
+
+impl SymbolIndex {
+    /// Empty index for the workspace at `root`
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            occurrences: HashMap::new(),
+            definitions: Vec::new(),
+        }
+    }
+
+    /// Index every source file under `root`
+    ///
+    /// Hidden and build output directories are skipped, as are files the
+    /// default [`FileGuard`] rejects and files in no recognized language.
+    pub fn build(root: &Path) -> Result<Self> {
+        let mut index = Self::new(root);
+        let guard = FileGuard::default();
+
+        for entry in source_files(root) {
+            let Ok(content) = guard.read_to_string(entry.path()) else {
+                continue;
+            };
+            let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+            index.add_file(relative, &content);
+        }
+
+        Ok(index)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Index the contents of one file, given relative to the root
+    pub fn add_file(&mut self, file: &Path, content: &str) {
+        let test_file = is_test_path(file);
+        let is_rust = file.extension().is_some_and(|ext| ext == "rs");
+        let parsed = parse_definitions(file, content);
+        let mut in_test_module = false;
+        // Line from which a Rust file is test code
+        let mut test_module_start = None;
+
+        for (line_number, line) in content.lines().enumerate() {
+            if is_rust && !in_test_module && line.trim_start().starts_with("#[cfg(test)]") {
+                in_test_module = true;
+                test_module_start = Some(line_number as u32);
+            }
+            let found = identifiers(line);
+            if parsed.is_none() {
+                if let Some(name) = defined_function(line, &found) {
+                    self.definitions.push(SymbolDefinition {
+                        name: name.to_string(),
+                        file: file.to_path_buf(),
+                        line: line_number as u32,
+                        in_test: test_file || in_test_module,
+                        kind: SymbolKind::Function,
+                        container: None,
+                    });
+                }
+            }
+            for (character, identifier) in found {
+                self.occurrences
+                    .entry(identifier.to_string())
+                    .or_default()
+                    .push(SymbolOccurrence {
+                        file: file.to_path_buf(),
+                        line: line_number as u32,
+                        character: character as u32,
+                        in_test: test_file || in_test_module,
+                    });
+            }
+        }
+
+        for mut definition in parsed.into_iter().flatten() {
+            definition.in_test = test_file || test_module_start.is_some_and(|start| definition.line >= start);
+            self.definitions.push(definition);
+        }
+    }
+
+    /// Drop everything indexed for one file, given relative to the root
+    pub fn remove_file(&mut self, file: &Path) {
+        self.occurrences.retain(|_, occurrences| {
+            occurrences.retain(|occurrence| occurrence.file != file);
+            !occurrences.is_empty()
+        });
+        self.definitions.retain(|definition| definition.file != file);
+    }
+
+    pub(crate) fn push_occurrence(&mut self, name: String, occurrence: SymbolOccurrence) {
+        self.occurrences.entry(name).or_default().push(occurrence);
+    }
+
+    pub(crate) fn push_definition(&mut self, definition: SymbolDefinition) {
+        self.definitions.push(definition);
+    }
+
+    /// Identifiers with their occurrences, in no particular order
+    pub fn occurrences(&self) -> impl Iterator<Item = (&str, &[SymbolOccurrence])> {
+        self.occurrences.iter().map(|(name, occurrences)| (name.as_str(), occurrences.as_slice()))
+    }
+
+    /// Every occurrence of `name`, in file and line order of indexing
+    pub fn references(&self, name: &str) -> &[SymbolOccurrence] {
+        self.occurrences.get(name).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Definitions of every kind, in file and line order of indexing
+    pub fn definitions(&self) -> &[SymbolDefinition] {
+        &self.definitions
+    }
+
+    /// Function and method definitions
+    pub fn functions(&self) -> impl Iterator<Item = &SymbolDefinition> {
+        self.definitions.iter().filter(|definition| definition.kind.is_callable())
+    }
+
+    /// Function and method definitions named `name`
+    pub fn definitions_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a SymbolDefinition> + 'a {
+        self.functions().filter(move |definition| definition.name == name)
+    }
+
+    /// The function enclosing a line: the last one defined at or above it in the file
+    pub fn enclosing_definition(&self, file: &Path, line: u32) -> Option<&SymbolDefinition> {
+        self.functions()
+            .filter(|definition| definition.file == file && definition.line <= line)
+            .max_by_key(|definition| definition.line)
+    }
+
+    /// Call sites of `name` across the workspace with the function containing each
+    ///
+    /// The definition's own name and occurrences outside any function are
+    /// not calls and are skipped.
+    pub fn callers(&self, name: &str) -> Vec<(&SymbolDefinition, &SymbolOccurrence)> {
+        self.references(name)
+            .iter()
+            .filter_map(|occurrence| {
+                let caller = self.enclosing_definition(&occurrence.file, occurrence.line)?;
+                let is_definition = caller.name == name && caller.line == occurrence.line;
+                (!is_definition).then_some((caller, occurrence))
+            })
+            .collect()
+    }
+
+    /// Where a function called from `file` is defined, preferring a definition in that file
+    pub fn resolve(&self, name: &str, file: &Path) -> Option<&SymbolDefinition> {
+        let mut candidates = self.functions().filter(|definition| definition.name == name);
+        let first = candidates.next()?;
+        if first.file == file {
+            return Some(first);
+        }
+        candidates.find(|definition| definition.file == file).or(Some(first))
+    }
+
+    /// Number of distinct identifiers indexed
+    pub fn len(&self) -> usize {
+        self.occurrences.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.occurrences.is_empty()
+    }
+}
+
+/// Source files under `root`, skipping hidden and build output directories
+pub(crate) fn source_files(root: &Path) -> impl Iterator<Item = walkdir::DirEntry> {
+    WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            entry.depth() == 0
+                || !(name.starts_with('.') || (entry.file_type().is_dir() && SKIPPED_DIRS.contains(&name.as_ref())))
+        })
+        .filter_map(|e| e.ok())
+        .filter(|entry| entry.file_type().is_file() && detect_file_language(entry.path()).is_some())
+}
+
+/// Whether a character can be part of an identifier
+pub fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Identifiers in a line of code with their character offsets
+pub fn identifiers(line: &str) -> Vec<(usize, &str)> {
+    let code = line.find("//").map_or(line, |comment| &line[..comment]);
+    let mut found = Vec::new();
+    let mut start: Option<(usize, usize)> = None;
+
+    for (character, (byte, c)) in code.char_indices().enumerate() {
+        match (start, is_identifier_char(c)) {
+            (None, true) => start = Some((character, byte)),
+            (Some((first, begin)), false) => {
+                push_identifier(&mut found, first, &code[begin..byte]);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some((first, begin)) = start {
+        push_identifier(&mut found, first, &code[begin..]);
+    }
+    found
+}
+
+/// Name of the function a line defines, if any
+fn defined_function<'a>(line: &str, found: &[(usize, &'a str)]) -> Option<&'a str> {
+    let keyword = found
+        .iter()
+        .position(|(_, word)| DEFINITION_KEYWORDS.contains(word))?;
+    let chars: Vec<char> = line.chars().collect();
+    found[keyword + 1..].iter().find_map(|&(character, word)| {
+        let next = chars[character + word.chars().count()..]
+            .iter()
+            .find(|c| !c.is_whitespace());
+        matches!(next, Some('(' | '<')).then_some(word)
+    })
+}
+
+fn push_identifier<'a>(found: &mut Vec<(usize, &'a str)>, character: usize, word: &'a str) {
+    // Numeric literals aren't identifiers
+    if !word.starts_with(|c: char| c.is_ascii_digit()) {
+        found.push((character, word));
+    }
+}
+
+/// Whether a path names a test file or lies in a test directory
+pub fn is_test_path(path: &Path) -> bool {
+    let in_test_dir = path.parent().into_iter().flat_map(Path::components).any(|component| {
+        matches!(
+            component.as_os_str().to_str(),
+            Some("test" | "tests" | "__tests__" | "spec" | "specs")
+        )
+    });
+    let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+    in_test_dir
+        || stem.starts_with("test_")
+        || stem.ends_with("_test")
+        || stem.ends_with("_tests")
+        || stem.ends_with(".test")
+        || stem.ends_with(".spec")
+        || stem.ends_with("Test")
+        || stem.ends_with("Tests")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_occurrences_and_test_code() {
+        let mut index = SymbolIndex::new("/work");
+        index.add_file(
+            Path::new("src/parser.rs"),
+            "pub fn parse_input(s: &str) -> u32 { 42 } // parse_input is public\n\n#[cfg(test)]\nmod tests {\n    fn checks() { parse_input(\"1\"); }\n}\n",
+        );
+        index.add_file(Path::new("web/parser.spec.ts"), "expect(parse_input('1')).toBe(1);\n");
+
+        let references = index.references("parse_input");
+        assert_eq!(references.len(), 3);
+        assert_eq!((references[0].line, references[0].character, references[0].in_test), (0, 7, false));
+        assert!(references[1].in_test);
+        assert!(references[2].in_test);
+        assert!(index.references("42").is_empty());
+        assert!(index.references("missing").is_empty());
+
+        let definitions: Vec<_> = index.functions().map(|d| (d.name.as_str(), d.line, d.in_test)).collect();
+        assert_eq!(definitions, vec![("parse_input", 0, false), ("checks", 4, true)]);
+        let module = &index.definitions()[1];
+        assert_eq!((module.name.as_str(), module.kind, module.in_test), ("tests", SymbolKind::Module, true));
+
+        // The definition itself and the spec's top-level call have no caller
+        let callers: Vec<_> = index.callers("parse_input").iter().map(|(caller, site)| (caller.name.as_str(), site.line)).collect();
+        assert_eq!(callers, vec![("checks", 4)]);
+        assert_eq!(index.resolve("parse_input", Path::new("web/parser.spec.ts")).unwrap().line, 0);
+        index.remove_file(Path::new("web/parser.spec.ts"));
+        assert_eq!(index.references("parse_input").len(), 2);
+        assert!(index.references("expect").is_empty());
+
+        let mut index = SymbolIndex::new("/work");
+        index.add_file(
+            Path::new("server/user.go"),
+            "func (s *Server) Lookup(id int) *User {\n\treturn find(id)\n}\n",
+        );
+        index.add_file(Path::new("app.py"), "async def fetch(url):\n    pass\n");
+        let names: Vec<_> = index.definitions().iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["Lookup", "fetch"]);
+
+        assert!(is_test_path(Path::new("pkg/tests/cli.rs")));
+        assert!(is_test_path(Path::new("server/handler_test.go")));
+        assert!(!is_test_path(Path::new("src/testing.rs")));
+    }
+}
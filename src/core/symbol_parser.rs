@@ -0,0 +1,377 @@
+//! Symbol definitions parsed with tree-sitter
+//!
+//! Rust, TypeScript/JavaScript and Python sources are parsed into their
+//! declarations: functions and methods, types, traits and interfaces,
+//! constants and modules, each with the type or module it is declared in.
+//! Only declaration scopes are walked, so locals inside function bodies are
+//! not definitions. Other languages fall back to the lexical function scan
+//! of [`SymbolIndex`](super::SymbolIndex).
+
+use super::symbol_index::{SymbolDefinition, SymbolKind};
+use std::path::Path;
+use tree_sitter::{Node, Parser};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Grammar {
+    Rust,
+    TypeScript,
+    /// TSX, also used for JavaScript so JSX parses
+    Tsx,
+    Python,
+}
+
+impl Grammar {
+    fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "rs" => Some(Grammar::Rust),
+            "ts" | "mts" | "cts" => Some(Grammar::TypeScript),
+            "tsx" | "js" | "jsx" | "mjs" | "cjs" => Some(Grammar::Tsx),
+            "py" | "pyi" => Some(Grammar::Python),
+            _ => None,
+        }
+    }
+
+    fn language(self) -> tree_sitter::Language {
+        match self {
+            Grammar::Rust => tree_sitter_rust::language(),
+            Grammar::TypeScript => tree_sitter_typescript::language_typescript(),
+            Grammar::Tsx => tree_sitter_typescript::language_tsx(),
+            Grammar::Python => tree_sitter_python::language(),
+        }
+    }
+}
+
+/// Definitions in `source`, in file order, or `None` when no grammar covers `file`
+///
+/// `in_test` is left unset for the caller to decide.
+pub(crate) fn parse_definitions(file: &Path, source: &str) -> Option<Vec<SymbolDefinition>> {
+    let grammar = Grammar::from_path(file)?;
+    let mut parser = Parser::new();
+    parser.set_language(grammar.language()).ok()?;
+    let tree = parser.parse(source, None)?;
+
+    let mut collector = Collector {
+        file,
+        source,
+        definitions: Vec::new(),
+    };
+    match grammar {
+        Grammar::Rust => collector.rust_scope(tree.root_node(), None),
+        Grammar::TypeScript | Grammar::Tsx => collector.typescript_scope(tree.root_node(), None),
+        Grammar::Python => collector.python_scope(tree.root_node(), None),
+    }
+    Some(collector.definitions)
+}
+
+struct Collector<'a> {
+    file: &'a Path,
+    source: &'a str,
+    definitions: Vec<SymbolDefinition>,
+}
+
+impl Collector<'_> {
+    fn text(&self, node: Node) -> &str {
+        node.utf8_text(self.source.as_bytes()).unwrap_or_default()
+    }
+
+    fn name(&self, node: Node) -> Option<String> {
+        node.child_by_field_name("name").map(|name| self.text(name).to_string())
+    }
+
+    fn push(&mut self, name: String, kind: SymbolKind, node: Node, container: Option<&str>) {
+        self.definitions.push(SymbolDefinition {
+            name,
+            file: self.file.to_path_buf(),
+            line: node.start_position().row as u32,
+            in_test: false,
+            kind,
+            container: container.map(str::to_string),
+        });
+    }
+
+    /// Push `node` under its `name` field, if it has one
+    fn push_named(&mut self, node: Node, kind: SymbolKind, container: Option<&str>) -> Option<String> {
+        let name = self.name(node)?;
+        self.push(name.clone(), kind, node, container);
+        Some(name)
+    }
+
+    fn rust_scope(&mut self, scope: Node, container: Option<&str>) {
+        let mut cursor = scope.walk();
+        let children: Vec<Node> = scope.named_children(&mut cursor).collect();
+        let in_type = container.is_some() && self.is_rust_type_body(scope);
+        for node in children {
+            match node.kind() {
+                "function_item" | "function_signature_item" => {
+                    let kind = if in_type { SymbolKind::Method } else { SymbolKind::Function };
+                    self.push_named(node, kind, container);
+                }
+                "struct_item" | "union_item" => {
+                    self.push_named(node, SymbolKind::Struct, container);
+                }
+                "enum_item" => {
+                    self.push_named(node, SymbolKind::Enum, container);
+                }
+                "type_item" => {
+                    self.push_named(node, SymbolKind::TypeAlias, container);
+                }
+                "const_item" | "static_item" => {
+                    self.push_named(node, SymbolKind::Constant, container);
+                }
+                "macro_definition" => {
+                    self.push_named(node, SymbolKind::Macro, container);
+                }
+                "trait_item" => {
+                    if let (Some(name), Some(body)) = (
+                        self.push_named(node, SymbolKind::Trait, container),
+                        node.child_by_field_name("body"),
+                    ) {
+                        self.rust_scope(body, Some(&name));
+                    }
+                }
+                "mod_item" => {
+                    if let (Some(name), Some(body)) = (
+                        self.push_named(node, SymbolKind::Module, container),
+                        node.child_by_field_name("body"),
+                    ) {
+                        self.rust_scope(body, Some(&name));
+                    }
+                }
+                "impl_item" => {
+                    let implemented = node.child_by_field_name("type").map(|ty| {
+                        // `Parser<T>` is declared as `Parser`
+                        let base = ty.child_by_field_name("type").unwrap_or(ty);
+                        self.text(base).to_string()
+                    });
+                    if let (Some(name), Some(body)) = (implemented, node.child_by_field_name("body")) {
+                        self.rust_scope(body, Some(&name));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Whether a declaration list is the body of an `impl` or `trait`
+    fn is_rust_type_body(&self, scope: Node) -> bool {
+        scope
+            .parent()
+            .is_some_and(|parent| matches!(parent.kind(), "impl_item" | "trait_item"))
+    }
+
+    fn typescript_scope(&mut self, scope: Node, container: Option<&str>) {
+        let mut cursor = scope.walk();
+        let children: Vec<Node> = scope.named_children(&mut cursor).collect();
+        for node in children {
+            self.typescript_declaration(node, container);
+        }
+    }
+
+    fn typescript_declaration(&mut self, node: Node, container: Option<&str>) {
+        match node.kind() {
+            "export_statement" | "ambient_declaration" => {
+                let declaration = node.child_by_field_name("declaration").or_else(|| node.named_child(0));
+                if let Some(declaration) = declaration {
+                    self.typescript_declaration(declaration, container);
+                }
+            }
+            "function_declaration" | "generator_function_declaration" | "function_signature" => {
+                self.push_named(node, SymbolKind::Function, container);
+            }
+            "class_declaration" | "abstract_class_declaration" => {
+                if let (Some(name), Some(body)) = (
+                    self.push_named(node, SymbolKind::Class, container),
+                    node.child_by_field_name("body"),
+                ) {
+                    self.typescript_class_body(body, &name);
+                }
+            }
+            "interface_declaration" => {
+                self.push_named(node, SymbolKind::Interface, container);
+            }
+            "type_alias_declaration" => {
+                self.push_named(node, SymbolKind::TypeAlias, container);
+            }
+            "enum_declaration" => {
+                self.push_named(node, SymbolKind::Enum, container);
+            }
+            "internal_module" | "module" => {
+                if let (Some(name), Some(body)) = (
+                    self.push_named(node, SymbolKind::Module, container),
+                    node.child_by_field_name("body"),
+                ) {
+                    self.typescript_scope(body, Some(&name));
+                }
+            }
+            "lexical_declaration" | "variable_declaration" => {
+                let constant = node.child(0).is_some_and(|keyword| keyword.kind() == "const");
+                let mut cursor = node.walk();
+                let declarators: Vec<Node> = node
+                    .named_children(&mut cursor)
+                    .filter(|child| child.kind() == "variable_declarator")
+                    .collect();
+                for declarator in declarators {
+                    // Destructuring patterns declare no single name
+                    let Some(name) = declarator
+                        .child_by_field_name("name")
+                        .filter(|name| name.kind() == "identifier")
+                    else {
+                        continue;
+                    };
+                    let value = declarator.child_by_field_name("value").map(|value| value.kind());
+                    let kind = match value {
+                        Some("arrow_function" | "function" | "function_expression" | "generator_function") => {
+                            SymbolKind::Function
+                        }
+                        _ if constant => SymbolKind::Constant,
+                        _ => continue,
+                    };
+                    let name = self.text(name).to_string();
+                    self.push(name, kind, declarator, container);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn typescript_class_body(&mut self, body: Node, class: &str) {
+        let mut cursor = body.walk();
+        let members: Vec<Node> = body.named_children(&mut cursor).collect();
+        for member in members {
+            if matches!(member.kind(), "method_definition" | "abstract_method_signature" | "method_signature") {
+                self.push_named(member, SymbolKind::Method, Some(class));
+            }
+        }
+    }
+
+    fn python_scope(&mut self, scope: Node, container: Option<&str>) {
+        let mut cursor = scope.walk();
+        let children: Vec<Node> = scope.named_children(&mut cursor).collect();
+        for node in children {
+            let node = match node.kind() {
+                "decorated_definition" => match node.child_by_field_name("definition") {
+                    Some(definition) => definition,
+                    None => continue,
+                },
+                _ => node,
+            };
+            match node.kind() {
+                "function_definition" => {
+                    let in_class = container.is_some() && scope.parent().is_some_and(|p| p.kind() == "class_definition");
+                    let kind = if in_class { SymbolKind::Method } else { SymbolKind::Function };
+                    self.push_named(node, kind, container);
+                }
+                "class_definition" => {
+                    if let (Some(name), Some(body)) = (
+                        self.push_named(node, SymbolKind::Class, container),
+                        node.child_by_field_name("body"),
+                    ) {
+                        self.python_scope(body, Some(&name));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outline(file: &str, source: &str) -> Vec<(String, SymbolKind, u32, Option<String>)> {
+        parse_definitions(Path::new(file), source)
+            .expect("supported language")
+            .into_iter()
+            .map(|d| (d.name, d.kind, d.line, d.container))
+            .collect()
+    }
+
+    fn entry(name: &str, kind: SymbolKind, line: u32, container: Option<&str>) -> (String, SymbolKind, u32, Option<String>) {
+        (name.to_string(), kind, line, container.map(str::to_string))
+    }
+
+    #[test]
+    fn test_rust_definitions() {
+        let source = "\
+pub struct Parser<T> { input: T }
+
+impl<T> Parser<T> {
+    pub fn parse_expr(&self) -> u32 {
+        fn helper() {}
+        let parse_local = 1;
+        parse_local
+    }
+}
+
+pub trait Parse {
+    fn parse(&self);
+}
+
+const LIMIT: usize = 10;
+
+mod tests {
+    fn parse_fixture() {}
+}
+";
+        assert_eq!(
+            outline("src/parser.rs", source),
+            vec![
+                entry("Parser", SymbolKind::Struct, 0, None),
+                entry("parse_expr", SymbolKind::Method, 3, Some("Parser")),
+                entry("Parse", SymbolKind::Trait, 10, None),
+                entry("parse", SymbolKind::Method, 11, Some("Parse")),
+                entry("LIMIT", SymbolKind::Constant, 14, None),
+                entry("tests", SymbolKind::Module, 16, None),
+                entry("parse_fixture", SymbolKind::Function, 17, Some("tests")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_typescript_and_python_definitions() {
+        let source = "\
+export class Tokenizer {
+  parseToken(): string { return ''; }
+}
+export const parseAll = (input: string) => input;
+const MAX = 3;
+let counter = 0;
+export interface Options { strict: boolean }
+function main() {}
+";
+        assert_eq!(
+            outline("web/tokenizer.ts", source),
+            vec![
+                entry("Tokenizer", SymbolKind::Class, 0, None),
+                entry("parseToken", SymbolKind::Method, 1, Some("Tokenizer")),
+                entry("parseAll", SymbolKind::Function, 3, None),
+                entry("MAX", SymbolKind::Constant, 4, None),
+                entry("Options", SymbolKind::Interface, 6, None),
+                entry("main", SymbolKind::Function, 7, None),
+            ]
+        );
+
+        let source = "\
+class Reader:
+    @staticmethod
+    def parse_line(line):
+        def strip(s):
+            return s
+        return strip(line)
+
+def parse_file(path):
+    pass
+";
+        assert_eq!(
+            outline("tools/reader.py", source),
+            vec![
+                entry("Reader", SymbolKind::Class, 0, None),
+                entry("parse_line", SymbolKind::Method, 2, Some("Reader")),
+                entry("parse_file", SymbolKind::Function, 7, None),
+            ]
+        );
+
+        assert!(parse_definitions(Path::new("main.go"), "func main() {}").is_none());
+    }
+}
@@ -0,0 +1,366 @@
+//! Persistent workspace symbol index
+//!
+//! [`SymbolStore`] keeps a [`SymbolIndex`] per workspace root in SQLite, so
+//! call hierarchies can be resolved across files without re-reading the
+//! whole workspace each run. [`SymbolStore::sync`] only re-indexes files
+//! whose modification time changed since they were stored and forgets files
+//! that no longer exist. Stores written by an older indexer are dropped and
+//! rebuilt on open.
+
+use super::file_guard::FileGuard;
+use super::symbol_index::{source_files, SymbolDefinition, SymbolIndex, SymbolKind, SymbolOccurrence};
+use super::{DatabasePool, DatabasePoolBuilder};
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+use tracing::debug;
+
+/// Version of the stored index format, kept in `PRAGMA user_version`
+///
+/// Bumped whenever indexing changes what a file yields, so stale rows are
+/// not mixed with fresh ones.
+const INDEX_VERSION: i64 = 2;
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS symbol_files (
+    root TEXT NOT NULL,
+    file TEXT NOT NULL,
+    modified INTEGER NOT NULL,
+    PRIMARY KEY (root, file)
+);
+CREATE TABLE IF NOT EXISTS symbol_occurrences (
+    root TEXT NOT NULL,
+    file TEXT NOT NULL,
+    name TEXT NOT NULL,
+    line INTEGER NOT NULL,
+    character INTEGER NOT NULL,
+    in_test INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_symbol_occurrences_file ON symbol_occurrences(root, file);
+CREATE TABLE IF NOT EXISTS symbol_definitions (
+    root TEXT NOT NULL,
+    file TEXT NOT NULL,
+    name TEXT NOT NULL,
+    line INTEGER NOT NULL,
+    in_test INTEGER NOT NULL,
+    kind TEXT NOT NULL DEFAULT 'function',
+    container TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_symbol_definitions_file ON symbol_definitions(root, file);
+"#;
+
+/// A re-indexed file and its modification time in milliseconds
+struct IndexedFile {
+    file: PathBuf,
+    modified: i64,
+}
+
+/// SQLite-backed symbol index shared by every workspace it has synced
+pub struct SymbolStore {
+    pool: Arc<DatabasePool>,
+    file_guard: FileGuard,
+}
+
+impl SymbolStore {
+    /// Open or create the store at `db_path`
+    pub async fn open(db_path: impl AsRef<Path>) -> Result<Self> {
+        let pool = DatabasePoolBuilder::new(db_path.as_ref())
+            .min_connections(1)
+            .max_connections(4)
+            .enable_wal(true)
+            .build()
+            .await
+            .context("Failed to open symbol index database")?;
+        pool.with_connection(migrate).await?;
+
+        Ok(Self {
+            pool,
+            file_guard: FileGuard::default(),
+        })
+    }
+
+    /// Size and binary limits for files read while syncing
+    pub fn with_file_guard(mut self, file_guard: FileGuard) -> Self {
+        self.file_guard = file_guard;
+        self
+    }
+
+    /// The index stored for `root`, empty if it was never synced
+    pub async fn load(&self, root: &Path) -> Result<SymbolIndex> {
+        let root = root.to_path_buf();
+        self.pool
+            .with_read_connection(move |conn| load_index(conn, &root))
+            .await
+    }
+
+    /// Bring the stored index for `root` up to date and return it
+    pub async fn sync(&self, root: &Path) -> Result<SymbolIndex> {
+        let stored = self.stored_files(root).await?;
+        let scan_root = root.to_path_buf();
+        let guard = self.file_guard.clone();
+        let (changed, index, present) = tokio::task::spawn_blocking(move || scan(&scan_root, &stored, &guard))
+            .await
+            .context("Symbol indexing task panicked")?;
+
+        let root = root.to_path_buf();
+        let key = root_key(&root);
+        let changed_count = changed.len();
+        let removed = self
+            .pool
+            .with_connection(move |conn| {
+                let tx = conn.transaction()?;
+                let known: Vec<String> = {
+                    let mut stmt = tx.prepare("SELECT file FROM symbol_files WHERE root = ?")?;
+                    let rows = stmt.query_map([&key], |row| row.get(0))?;
+                    rows.collect::<rusqlite::Result<_>>()?
+                };
+                let removed: Vec<String> = known.into_iter().filter(|file| !present.contains(file)).collect();
+                let stale = removed.iter().cloned().chain(changed.iter().map(|c| path_key(&c.file)));
+                for file in stale {
+                    tx.execute("DELETE FROM symbol_occurrences WHERE root = ? AND file = ?", params![key, file])?;
+                    tx.execute("DELETE FROM symbol_definitions WHERE root = ? AND file = ?", params![key, file])?;
+                    tx.execute("DELETE FROM symbol_files WHERE root = ? AND file = ?", params![key, file])?;
+                }
+                store_index(&tx, &key, &index)?;
+                for indexed in &changed {
+                    tx.execute(
+                        "INSERT INTO symbol_files (root, file, modified) VALUES (?, ?, ?)",
+                        params![key, path_key(&indexed.file), indexed.modified],
+                    )?;
+                }
+                tx.commit()?;
+                Ok(removed.len())
+            })
+            .await?;
+
+        debug!("Synced symbol index for {:?}: {changed_count} re-indexed, {removed} removed", root);
+        self.load(&root).await
+    }
+
+    /// Modification times of the files stored for `root`
+    async fn stored_files(&self, root: &Path) -> Result<HashMap<String, i64>> {
+        let key = root_key(root);
+        self.pool
+            .with_read_connection(move |conn| {
+                let mut stmt = conn.prepare("SELECT file, modified FROM symbol_files WHERE root = ?")?;
+                let rows = stmt.query_map([key], |row| Ok((row.get(0)?, row.get(1)?)))?;
+                Ok(rows.collect::<rusqlite::Result<_>>()?)
+            })
+            .await
+    }
+}
+
+/// Create the schema, discarding an index stored in an older format
+fn migrate(conn: &mut Connection) -> Result<()> {
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version < INDEX_VERSION {
+        if version > 0 {
+            debug!("Rebuilding symbol index stored in format {version}");
+        }
+        conn.execute_batch(
+            "DROP TABLE IF EXISTS symbol_files;
+             DROP TABLE IF EXISTS symbol_occurrences;
+             DROP TABLE IF EXISTS symbol_definitions;",
+        )?;
+    }
+    conn.execute_batch(SCHEMA)?;
+    conn.pragma_update(None, "user_version", INDEX_VERSION)?;
+    Ok(())
+}
+
+/// Index the files under `root` that changed since they were stored
+///
+/// Returns the changed files, an index of just those files, and the
+/// relative path of every source file present.
+fn scan(
+    root: &Path,
+    stored: &HashMap<String, i64>,
+    guard: &FileGuard,
+) -> (Vec<IndexedFile>, SymbolIndex, HashSet<String>) {
+    let mut changed = Vec::new();
+    let mut index = SymbolIndex::new(root);
+    let mut present = HashSet::new();
+
+    for entry in source_files(root) {
+        let relative = entry.path().strip_prefix(root).unwrap_or(entry.path()).to_path_buf();
+        let key = path_key(&relative);
+        let modified = entry
+            .metadata()
+            .ok()
+            .and_then(|metadata| metadata.modified().ok())
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |age| age.as_millis() as i64);
+        present.insert(key.clone());
+
+        if stored.get(&key) == Some(&modified) {
+            continue;
+        }
+        let Ok(content) = guard.read_to_string(entry.path()) else {
+            continue;
+        };
+        index.add_file(&relative, &content);
+        changed.push(IndexedFile { file: relative, modified });
+    }
+
+    (changed, index, present)
+}
+
+fn store_index(conn: &Connection, key: &str, index: &SymbolIndex) -> Result<()> {
+    let mut insert = conn.prepare(
+        "INSERT INTO symbol_occurrences (root, file, name, line, character, in_test) VALUES (?, ?, ?, ?, ?, ?)",
+    )?;
+    for (name, occurrences) in index.occurrences() {
+        for occurrence in occurrences {
+            insert.execute(params![
+                key,
+                path_key(&occurrence.file),
+                name,
+                occurrence.line,
+                occurrence.character,
+                occurrence.in_test
+            ])?;
+        }
+    }
+
+    let mut insert = conn.prepare(
+        "INSERT INTO symbol_definitions (root, file, name, line, in_test, kind, container) VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )?;
+    for definition in index.definitions() {
+        insert.execute(params![
+            key,
+            path_key(&definition.file),
+            definition.name,
+            definition.line,
+            definition.in_test,
+            definition.kind.as_str(),
+            definition.container
+        ])?;
+    }
+    Ok(())
+}
+
+fn load_index(conn: &Connection, root: &Path) -> Result<SymbolIndex> {
+    let key = root_key(root);
+    let mut index = SymbolIndex::new(root);
+
+    let mut stmt = conn.prepare(
+        "SELECT name, file, line, character, in_test FROM symbol_occurrences WHERE root = ? ORDER BY file, line, character",
+    )?;
+    let rows = stmt.query_map([&key], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            SymbolOccurrence {
+                file: PathBuf::from(row.get::<_, String>(1)?),
+                line: row.get(2)?,
+                character: row.get(3)?,
+                in_test: row.get(4)?,
+            },
+        ))
+    })?;
+    for row in rows {
+        let (name, occurrence) = row?;
+        index.push_occurrence(name, occurrence);
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT name, file, line, in_test, kind, container FROM symbol_definitions WHERE root = ? ORDER BY file, line",
+    )?;
+    let rows = stmt.query_map([&key], |row| {
+        Ok(SymbolDefinition {
+            name: row.get(0)?,
+            file: PathBuf::from(row.get::<_, String>(1)?),
+            line: row.get(2)?,
+            in_test: row.get(3)?,
+            kind: SymbolKind::parse(&row.get::<_, String>(4)?).unwrap_or_default(),
+            container: row.get(5)?,
+        })
+    })?;
+    for row in rows {
+        index.push_definition(row?);
+    }
+
+    Ok(index)
+}
+
+fn root_key(root: &Path) -> String {
+    root.canonicalize().unwrap_or_else(|_| root.to_path_buf()).to_string_lossy().into_owned()
+}
+
+fn path_key(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_sync_reindexes_changed_files_only() {
+        let workspace = TempDir::new().unwrap();
+        let db = TempDir::new().unwrap();
+        let root = workspace.path();
+        std::fs::create_dir(root.join("src")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "pub fn load() -> u32 { 1 }\n").unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {\n    load();\n}\n").unwrap();
+
+        let store = SymbolStore::open(db.path().join("symbols.db")).await.unwrap();
+        let index = store.sync(root).await.unwrap();
+        let callers: Vec<_> = index.callers("load").iter().map(|(caller, _)| caller.name.clone()).collect();
+        assert_eq!(callers, vec!["main"]);
+
+        // A reopened store serves the same index without re-reading the files
+        drop(store);
+        let store = SymbolStore::open(db.path().join("symbols.db")).await.unwrap();
+        let loaded = store.load(root).await.unwrap();
+        assert_eq!(loaded.references("load").len(), 2);
+
+        std::fs::remove_file(root.join("src/main.rs")).unwrap();
+        let index = store.sync(root).await.unwrap();
+        assert!(index.callers("load").is_empty());
+        assert_eq!(index.definitions().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_kinds_persist_and_old_format_is_rebuilt() {
+        let workspace = TempDir::new().unwrap();
+        let db = TempDir::new().unwrap();
+        let root = workspace.path();
+        std::fs::write(root.join("lib.rs"), "pub struct Parser;
+
+impl Parser {
+    pub fn parse(&self) {}
+}
+").unwrap();
+
+        // A store from before definitions had kinds
+        let db_path = db.path().join("symbols.db");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE symbol_files (root TEXT NOT NULL, file TEXT NOT NULL, modified INTEGER NOT NULL);
+             CREATE TABLE symbol_definitions (root TEXT NOT NULL, file TEXT NOT NULL, name TEXT NOT NULL,
+                 line INTEGER NOT NULL, in_test INTEGER NOT NULL);",
+        )
+        .unwrap();
+        drop(conn);
+
+        let store = SymbolStore::open(&db_path).await.unwrap();
+        store.sync(root).await.unwrap();
+        let loaded = store.load(root).await.unwrap();
+        let definitions: Vec<_> = loaded
+            .definitions()
+            .iter()
+            .map(|d| (d.name.as_str(), d.kind, d.container.as_deref()))
+            .collect();
+        assert_eq!(
+            definitions,
+            vec![
+                ("Parser", SymbolKind::Struct, None),
+                ("parse", SymbolKind::Method, Some("Parser"))
+            ]
+        );
+    }
+}
@@ -0,0 +1,201 @@
+//! OpenTelemetry export of spans and metrics
+//!
+//! Capture, query and quick-fix operations run inside `tracing` spans and
+//! report their duration and item counts through [`record_operation`].
+//! Built with the `opentelemetry` feature and with
+//! `metrics.enable_opentelemetry` set, [`init`] forwards those spans to an
+//! OTLP collector and pushes the metrics to it every
+//! `metrics.collection_interval_seconds`, so LSPbridge shows up next to the
+//! rest of a team's services. Otherwise spans only reach the log output and
+//! metrics are dropped. Export counts as network access: in offline mode the
+//! exporters are not started and the blocked attempt shows up in the
+//! network audit.
+
+use anyhow::Result;
+use std::time::Duration;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+
+use crate::core::dynamic_config::MetricsConfig;
+use crate::core::NetworkGuard;
+
+/// An instrumented operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Capture,
+    Query,
+    QuickFix,
+}
+
+impl Operation {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Capture => "capture",
+            Self::Query => "query",
+            Self::QuickFix => "quick_fix",
+        }
+    }
+}
+
+/// Flushes and shuts down the exporters when dropped
+///
+/// Keep it alive until the process is done; anything recorded after it is
+/// dropped is lost.
+#[must_use = "dropping the guard shuts telemetry export down"]
+pub struct TelemetryGuard {
+    #[cfg(feature = "opentelemetry")]
+    meter_provider: Option<opentelemetry_sdk::metrics::MeterProvider>,
+}
+
+/// Install the global `tracing` subscriber, logging at `filter` and
+/// exporting over OTLP when `config` asks for it and `network` allows it
+///
+/// `RUST_LOG` takes precedence over `filter`. Logs go to stderr so they
+/// never mix with JSON or Arrow written to stdout.
+pub fn init(config: &MetricsConfig, filter: &str, network: &NetworkGuard) -> Result<TelemetryGuard> {
+    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(filter));
+    let registry = tracing_subscriber::registry()
+        .with(env_filter)
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr));
+
+    #[cfg(feature = "opentelemetry")]
+    {
+        let endpoint = config.otlp_endpoint.as_deref().unwrap_or("the default OTLP endpoint");
+        let denied = config.enable_opentelemetry
+            && network.check(format!("OpenTelemetry export to {endpoint}")).is_err();
+        if config.enable_opentelemetry && !denied {
+            let tracer = otlp::tracer(config)?;
+            let meter_provider = otlp::meter_provider(config)?;
+            otlp::init_instruments(&meter_provider);
+            registry.with(tracing_opentelemetry::layer().with_tracer(tracer)).try_init()?;
+            tracing::debug!("Exporting spans and metrics to {}", endpoint);
+            return Ok(TelemetryGuard {
+                meter_provider: Some(meter_provider),
+            });
+        }
+        registry.try_init()?;
+        if denied {
+            tracing::warn!("Network access is denied; not exporting spans and metrics to {}", endpoint);
+        }
+        Ok(TelemetryGuard { meter_provider: None })
+    }
+
+    #[cfg(not(feature = "opentelemetry"))]
+    {
+        // Nothing is exported, so there is no network access to check
+        let _ = network;
+        registry.try_init()?;
+        if config.enable_opentelemetry {
+            tracing::warn!(
+                "metrics.enable_opentelemetry is set, but lspbridge was built without the `opentelemetry` feature"
+            );
+        }
+        Ok(TelemetryGuard {})
+    }
+}
+
+/// Record one finished operation that handled `items` diagnostics or edits
+pub fn record_operation(operation: Operation, elapsed: Duration, items: usize, success: bool) {
+    #[cfg(feature = "opentelemetry")]
+    otlp::record(operation, elapsed, items, success);
+
+    #[cfg(not(feature = "opentelemetry"))]
+    let _ = (operation, elapsed, items, success);
+}
+
+#[cfg(feature = "opentelemetry")]
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        if let Some(provider) = self.meter_provider.take() {
+            // Flush rather than shut down: `shutdown` marks the reader closed
+            // before its final collection, which then fails and drops the
+            // last interval. The reader task ends with the runtime.
+            if let Err(e) = provider.force_flush() {
+                eprintln!("Warning: failed to flush OpenTelemetry metrics: {e}");
+            }
+            opentelemetry::global::shutdown_tracer_provider();
+        }
+    }
+}
+
+#[cfg(feature = "opentelemetry")]
+mod otlp {
+    use anyhow::Result;
+    use opentelemetry::metrics::{Counter, Histogram, MeterProvider as _, Unit};
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::metrics::MeterProvider;
+    use opentelemetry_sdk::{runtime, trace, Resource};
+    use std::sync::OnceLock;
+    use std::time::Duration;
+
+    use super::Operation;
+    use crate::core::dynamic_config::MetricsConfig;
+
+    struct Instruments {
+        duration: Histogram<f64>,
+        items: Counter<u64>,
+    }
+
+    static INSTRUMENTS: OnceLock<Instruments> = OnceLock::new();
+
+    fn resource(config: &MetricsConfig) -> Resource {
+        Resource::new([
+            KeyValue::new("service.name", config.service_name.clone()),
+            KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+        ])
+    }
+
+    fn exporter(config: &MetricsConfig) -> opentelemetry_otlp::TonicExporterBuilder {
+        let exporter = opentelemetry_otlp::new_exporter().tonic();
+        match &config.otlp_endpoint {
+            Some(endpoint) => exporter.with_endpoint(endpoint.clone()),
+            None => exporter,
+        }
+    }
+
+    pub(super) fn tracer(config: &MetricsConfig) -> Result<trace::Tracer> {
+        Ok(opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(exporter(config))
+            .with_trace_config(trace::config().with_resource(resource(config)))
+            .install_batch(runtime::Tokio)?)
+    }
+
+    pub(super) fn meter_provider(config: &MetricsConfig) -> Result<MeterProvider> {
+        Ok(opentelemetry_otlp::new_pipeline()
+            .metrics(runtime::Tokio)
+            .with_exporter(exporter(config))
+            .with_resource(resource(config))
+            .with_period(Duration::from_secs(config.collection_interval_seconds))
+            .build()?)
+    }
+
+    pub(super) fn init_instruments(provider: &MeterProvider) {
+        let meter = provider.meter("lspbridge");
+        let _ = INSTRUMENTS.set(Instruments {
+            duration: meter
+                .f64_histogram("lspbridge.operation.duration")
+                .with_unit(Unit::new("s"))
+                .with_description("Duration of capture, query and quick-fix operations")
+                .init(),
+            items: meter
+                .u64_counter("lspbridge.operation.items")
+                .with_description("Diagnostics captured, rows returned and fixes applied")
+                .init(),
+        });
+    }
+
+    pub(super) fn record(operation: Operation, elapsed: Duration, items: usize, success: bool) {
+        let Some(instruments) = INSTRUMENTS.get() else {
+            return;
+        };
+        let attributes = [
+            KeyValue::new("operation", operation.as_str()),
+            KeyValue::new("success", success),
+        ];
+        instruments.duration.record(elapsed.as_secs_f64(), &attributes);
+        instruments.items.add(items as u64, &attributes);
+    }
+}
@@ -71,6 +71,9 @@ pub trait PrivacyFilter {
 
     /// Get the current privacy policy
     fn get_policy(&self) -> &PrivacyPolicy;
+
+    /// Replace the privacy policy; applies to the next diagnostics filtered
+    fn update_policy(&mut self, policy: PrivacyPolicy);
 }
 
 /// Trait for format conversion
@@ -146,6 +149,15 @@ pub struct PrivacyPolicy {
     pub max_diagnostics_per_file: usize,
     pub anonymize_file_paths: bool,
     pub encrypt_exports: bool,
+    /// Regexes whose matches are replaced with `[REDACTED]` in messages
+    #[serde(default)]
+    pub redaction_patterns: Vec<String>,
+    /// Licenses whose code is kept out of AI exports
+    #[serde(default)]
+    pub license_rules: Vec<super::license::LicenseRule>,
+    /// Append-only log of every exclusion and redaction; no audit trail when unset
+    #[serde(default)]
+    pub audit_log: Option<std::path::PathBuf>,
 }
 
 impl Default for PrivacyPolicy {
@@ -166,11 +178,23 @@ impl Default for PrivacyPolicy {
             max_diagnostics_per_file: 50,
             anonymize_file_paths: false,
             encrypt_exports: false,
+            redaction_patterns: Vec::new(),
+            license_rules: Vec::new(),
+            audit_log: None,
         }
     }
 }
 
 impl PrivacyPolicy {
+    /// The policy a `--privacy` level stands for
+    pub fn for_level(level: &super::security_config::PrivacyLevel) -> Self {
+        match level {
+            super::security_config::PrivacyLevel::Strict => Self::strict(),
+            super::security_config::PrivacyLevel::Minimal => Self::permissive(),
+            super::security_config::PrivacyLevel::Balanced => Self::default(),
+        }
+    }
+
     pub fn strict() -> Self {
         Self {
             exclude_patterns: vec![
@@ -191,6 +215,9 @@ impl PrivacyPolicy {
             max_diagnostics_per_file: 20,
             anonymize_file_paths: true,
             encrypt_exports: true,
+            redaction_patterns: Vec::new(),
+            license_rules: Vec::new(),
+            audit_log: None,
         }
     }
 
@@ -203,12 +230,15 @@ impl PrivacyPolicy {
             max_diagnostics_per_file: 100,
             anonymize_file_paths: false,
             encrypt_exports: false,
+            redaction_patterns: Vec::new(),
+            license_rules: Vec::new(),
+            audit_log: None,
         }
     }
 }
 
 /// Configuration for export settings
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct ExportConfig {
     pub format: ExportFormat,
     pub include_context: bool,
@@ -216,16 +246,36 @@ pub struct ExportConfig {
     pub include_summary: bool,
     pub group_by_file: bool,
     pub sort_by: SortBy,
+    /// Identical output for identical diagnostics: canonical ordering,
+    /// content-derived ids and no capture timestamps, so exports can be
+    /// committed and diffed
+    #[serde(default)]
+    pub stable: bool,
+    /// Export only diagnostics overlapping this region, always with their code context
+    #[serde(default)]
+    pub region: Option<super::DiagnosticRegion>,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub enum ExportFormat {
+    #[serde(alias = "json")]
     Json,
+    #[serde(alias = "markdown")]
     Markdown,
+    #[serde(alias = "claude")]
     ClaudeOptimized,
+    /// SARIF 2.1.0 for code scanning tools
+    #[serde(alias = "sarif")]
+    Sarif,
+    /// Standalone HTML report
+    #[serde(alias = "html")]
+    Html,
+    /// GitHub Actions workflow commands for inline PR annotations
+    #[serde(alias = "github-actions")]
+    GithubActions,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub enum SortBy {
     Severity,
     File,
@@ -242,6 +292,8 @@ impl Default for ExportConfig {
             include_summary: true,
             group_by_file: false,
             sort_by: SortBy::Severity,
+            stable: false,
+            region: None,
         }
     }
 }
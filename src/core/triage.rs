@@ -0,0 +1,430 @@
+//! Automatic triage suggestions for new diagnostics
+//!
+//! The triage engine combines several existing signals into one structured
+//! suggestion per diagnostic, suitable for bots that open or route issues:
+//!
+//! - **Priority** from [`DiagnosticPrioritizer`] scoring
+//! - **Probable owners** from the repository's CODEOWNERS ([`OwnershipMap`])
+//! - **Estimated fix time** from [`TrendAnalyzer::predict_fix_time`]
+//! - **Related prior issues** from recurring patterns in history storage
+//!
+//! History is optional; without it fix times fall back to per-category
+//! defaults and no related issues are reported.
+
+use super::diagnostic_grouping::DiagnosticGrouper;
+use super::diagnostic_prioritization::{DiagnosticPrioritizer, PrioritizedDiagnostic};
+use super::ownership::OwnershipMap;
+use super::types::Diagnostic;
+use crate::analyzers::{
+    DiagnosticCategory as AnalyzerCategory, JavaAnalyzer, KotlinAnalyzer, LanguageAnalyzer, RustAnalyzer,
+    TypeScriptAnalyzer,
+};
+use crate::history::{DiagnosticCategory, HistoricalErrorPattern, HistoryStorage, TrendAnalyzer};
+use crate::multi_repo::collaboration::Priority;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Maximum number of related prior issues attached to a suggestion
+const MAX_RELATED_ISSUES: usize = 3;
+
+/// Structured triage block for a single diagnostic
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriageSuggestion {
+    /// ID of the triaged diagnostic
+    pub diagnostic_id: String,
+    /// File containing the diagnostic
+    pub file: String,
+    /// Suggested priority level
+    pub priority: Priority,
+    /// Underlying priority score (0.0 - 100.0)
+    pub priority_score: f32,
+    /// Coarse category used for fix-time estimation
+    pub category: DiagnosticCategory,
+    /// Probable owners from CODEOWNERS (empty if unowned)
+    pub probable_owners: Vec<String>,
+    /// CODEOWNERS pattern that produced the owners
+    pub ownership_rule: Option<String>,
+    /// Estimated time to fix, in minutes
+    pub estimated_fix_minutes: u64,
+    /// Whether this diagnostic is likely caused by another one
+    pub is_secondary: bool,
+    /// Similar issues seen previously in history
+    pub related_issues: Vec<RelatedIssue>,
+    /// Human-readable rationale for the suggestion
+    pub rationale: String,
+}
+
+/// A previously seen recurring issue related to a diagnostic
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelatedIssue {
+    /// Pattern hash from history storage
+    pub pattern_hash: String,
+    /// Representative message
+    pub message: String,
+    /// Error code, if any
+    pub code: Option<String>,
+    /// Number of times this pattern has occurred
+    pub occurrence_count: usize,
+    /// Number of files the pattern affected
+    pub files_affected: usize,
+    /// When the pattern was first seen
+    pub first_seen: SystemTime,
+}
+
+/// Engine producing triage suggestions
+pub struct TriageEngine {
+    grouper: DiagnosticGrouper,
+    prioritizer: DiagnosticPrioritizer,
+    rust_analyzer: RustAnalyzer,
+    typescript_analyzer: TypeScriptAnalyzer,
+    java_analyzer: JavaAnalyzer,
+    kotlin_analyzer: KotlinAnalyzer,
+    ownership: Option<OwnershipMap>,
+    history: Option<Arc<HistoryStorage>>,
+}
+
+impl TriageEngine {
+    pub fn new() -> Self {
+        Self {
+            grouper: DiagnosticGrouper::new(),
+            prioritizer: DiagnosticPrioritizer::new(),
+            rust_analyzer: RustAnalyzer::new(),
+            typescript_analyzer: TypeScriptAnalyzer::new(),
+            java_analyzer: JavaAnalyzer::new(),
+            kotlin_analyzer: KotlinAnalyzer::new(),
+            ownership: None,
+            history: None,
+        }
+    }
+
+    /// Use a CODEOWNERS-based ownership map for owner routing
+    pub fn with_ownership(mut self, ownership: OwnershipMap) -> Self {
+        self.ownership = Some(ownership);
+        self
+    }
+
+    /// Discover CODEOWNERS under a project root, ignoring read failures
+    pub fn with_project_root(self, root: &Path) -> Self {
+        match OwnershipMap::discover(root) {
+            Ok(map) if !map.is_empty() => self.with_ownership(map),
+            Ok(_) => self,
+            Err(e) => {
+                tracing::warn!("Failed to read CODEOWNERS under {}: {}", root.display(), e);
+                self
+            }
+        }
+    }
+
+    /// Use history storage for fix-time prediction and related issues
+    pub fn with_history(mut self, history: Arc<HistoryStorage>) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Produce a triage suggestion for every diagnostic
+    ///
+    /// Diagnostics are grouped first so that secondary (cascading) diagnostics
+    /// inherit the priority of their root cause.
+    pub async fn triage(&self, diagnostics: &[Diagnostic]) -> Vec<TriageSuggestion> {
+        let groups = self.grouper.group_diagnostics(diagnostics.to_vec());
+        let prioritized = self.prioritizer.prioritize(groups);
+
+        let patterns = self.load_patterns().await;
+        let analyzer = self.history.as_ref().map(|h| TrendAnalyzer::new(h.clone()));
+
+        let mut suggestions = Vec::with_capacity(diagnostics.len());
+        for item in &prioritized {
+            let category = self.categorize(&item.group.primary);
+            let fix_time = match &analyzer {
+                Some(analyzer) => analyzer.predict_fix_time(category).await.ok(),
+                None => None,
+            }
+            .unwrap_or_else(|| default_fix_time(category));
+
+            suggestions.push(self.build_suggestion(
+                item,
+                &item.group.primary,
+                category,
+                fix_time,
+                &patterns,
+                false,
+            ));
+            for related in &item.group.related {
+                suggestions.push(self.build_suggestion(
+                    item, related, category, fix_time, &patterns, true,
+                ));
+            }
+        }
+
+        suggestions
+    }
+
+    fn build_suggestion(
+        &self,
+        item: &PrioritizedDiagnostic,
+        diagnostic: &Diagnostic,
+        category: DiagnosticCategory,
+        fix_time: std::time::Duration,
+        patterns: &[HistoricalErrorPattern],
+        is_secondary: bool,
+    ) -> TriageSuggestion {
+        let ownership = self
+            .ownership
+            .as_ref()
+            .and_then(|map| map.resolve(Path::new(&diagnostic.file)));
+
+        // Secondary diagnostics usually disappear with their root cause
+        let priority_score = if is_secondary {
+            item.priority_score * 0.8
+        } else {
+            item.priority_score
+        };
+        let priority = priority_for_score(priority_score);
+
+        let mut rationale = vec![format!(
+            "{:?} priority (score {:.0})",
+            priority, priority_score
+        )];
+        if is_secondary {
+            rationale.push(format!("likely caused by {}", item.group.primary.id));
+        }
+        if item.impact_radius > 0 && !is_secondary {
+            rationale.push(format!("may resolve {} related diagnostics", item.impact_radius));
+        }
+        if ownership.is_none() {
+            rationale.push("no CODEOWNERS match".to_string());
+        }
+
+        TriageSuggestion {
+            diagnostic_id: diagnostic.id.clone(),
+            file: diagnostic.file.clone(),
+            priority,
+            priority_score,
+            category,
+            probable_owners: ownership
+                .as_ref()
+                .map(|m| m.owners.clone())
+                .unwrap_or_default(),
+            ownership_rule: ownership.map(|m| m.pattern),
+            estimated_fix_minutes: (fix_time.as_secs() + 59) / 60,
+            is_secondary,
+            related_issues: related_issues(diagnostic, patterns),
+            rationale: rationale.join("; "),
+        }
+    }
+
+    /// Map the language analyzer category onto the history category
+    fn categorize(&self, diagnostic: &Diagnostic) -> DiagnosticCategory {
+        let source = diagnostic.source.to_lowercase();
+        let analyzer: Option<&dyn LanguageAnalyzer> =
+            if source.contains("typescript") || source.contains("eslint") {
+                Some(&self.typescript_analyzer)
+            } else if source.contains("rust") {
+                Some(&self.rust_analyzer)
+            } else if self.java_analyzer.can_analyze(diagnostic) {
+                Some(&self.java_analyzer)
+            } else if self.kotlin_analyzer.can_analyze(diagnostic) {
+                Some(&self.kotlin_analyzer)
+            } else {
+                None
+            };
+
+        match analyzer.map(|a| a.analyze_diagnostic(diagnostic, None).category) {
+            Some(category) => history_category(&category),
+            None => DiagnosticCategory::Other,
+        }
+    }
+
+    async fn load_patterns(&self) -> Vec<HistoricalErrorPattern> {
+        let Some(history) = &self.history else {
+            return Vec::new();
+        };
+        match history.get_recurring_patterns(2).await {
+            Ok(patterns) => patterns,
+            Err(e) => {
+                tracing::warn!("Failed to load recurring patterns for triage: {}", e);
+                Vec::new()
+            }
+        }
+    }
+}
+
+impl Default for TriageEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Map a priority score onto a discrete priority level
+pub fn priority_for_score(score: f32) -> Priority {
+    if score >= 80.0 {
+        Priority::Critical
+    } else if score >= 65.0 {
+        Priority::High
+    } else if score >= 45.0 {
+        Priority::Medium
+    } else {
+        Priority::Low
+    }
+}
+
+/// Map fine-grained analyzer categories onto history categories
+pub fn history_category(category: &AnalyzerCategory) -> DiagnosticCategory {
+    match category {
+        AnalyzerCategory::SyntaxError | AnalyzerCategory::ParseError => {
+            DiagnosticCategory::SyntaxErrors
+        }
+        AnalyzerCategory::TypeMismatch
+        | AnalyzerCategory::MissingProperty
+        | AnalyzerCategory::UndefinedType
+        | AnalyzerCategory::GenericTypeError
+        | AnalyzerCategory::UndefinedVariable
+        | AnalyzerCategory::UninitializedVariable
+        | AnalyzerCategory::NullSafety
+        | AnalyzerCategory::BorrowChecker
+        | AnalyzerCategory::LifetimeError
+        | AnalyzerCategory::MoveError => DiagnosticCategory::TypeErrors,
+        AnalyzerCategory::MissingImport
+        | AnalyzerCategory::CircularDependency
+        | AnalyzerCategory::ModuleResolution => DiagnosticCategory::Build,
+        AnalyzerCategory::AsyncError | AnalyzerCategory::RaceCondition => {
+            DiagnosticCategory::Runtime
+        }
+        AnalyzerCategory::UnusedVariable
+        | AnalyzerCategory::CodeQuality
+        | AnalyzerCategory::Performance => DiagnosticCategory::Linting,
+        AnalyzerCategory::Security | AnalyzerCategory::Unknown => DiagnosticCategory::Other,
+    }
+}
+
+/// Default fix time when no history is available, matching `TrendAnalyzer`
+fn default_fix_time(category: DiagnosticCategory) -> std::time::Duration {
+    let minutes = match category {
+        DiagnosticCategory::SyntaxErrors => 5,
+        DiagnosticCategory::Linting => 10,
+        DiagnosticCategory::Build => 20,
+        DiagnosticCategory::Runtime => 30,
+        DiagnosticCategory::TypeErrors | DiagnosticCategory::Other => 15,
+    };
+    std::time::Duration::from_secs(minutes * 60)
+}
+
+/// Find recurring patterns that match a diagnostic by code or message
+fn related_issues(diagnostic: &Diagnostic, patterns: &[HistoricalErrorPattern]) -> Vec<RelatedIssue> {
+    let message = diagnostic.message.to_lowercase();
+
+    let mut matches: Vec<&HistoricalErrorPattern> = patterns
+        .iter()
+        .filter(|pattern| {
+            let same_code = diagnostic.code.is_some() && pattern.error_code == diagnostic.code;
+            let same_source = pattern
+                .source
+                .as_ref()
+                .map_or(true, |s| s.eq_ignore_ascii_case(&diagnostic.source));
+            same_source && (same_code || pattern.error_message.to_lowercase() == message)
+        })
+        .collect();
+
+    matches.sort_by_key(|pattern| std::cmp::Reverse(pattern.occurrence_count));
+    matches
+        .into_iter()
+        .take(MAX_RELATED_ISSUES)
+        .map(|pattern| RelatedIssue {
+            pattern_hash: pattern.pattern_hash.clone(),
+            message: pattern.error_message.clone(),
+            code: pattern.error_code.clone(),
+            occurrence_count: pattern.occurrence_count,
+            files_affected: pattern.files_affected,
+            first_seen: pattern.first_seen,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{DiagnosticSeverity, Position, Range};
+
+    fn diagnostic(id: &str, file: &str, message: &str, code: Option<&str>) -> Diagnostic {
+        Diagnostic {
+            id: id.to_string(),
+            file: file.to_string(),
+            range: Range {
+                start: Position { line: 10, character: 0 },
+                end: Position { line: 10, character: 5 },
+            },
+            severity: DiagnosticSeverity::Error,
+            message: message.to_string(),
+            code: code.map(String::from),
+            source: "rust-analyzer".to_string(),
+            related_information: None,
+            tags: None,
+            data: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_triage_assigns_owners_and_fix_time() {
+        let ownership = OwnershipMap::parse(Path::new("/repo"), "src/query/ @query-team\n");
+        let engine = TriageEngine::new().with_ownership(ownership);
+
+        let diagnostics = vec![diagnostic(
+            "d1",
+            "src/query/mod.rs",
+            "mismatched types: expected `u32`, found `String`",
+            Some("E0308"),
+        )];
+        let suggestions = engine.triage(&diagnostics).await;
+
+        assert_eq!(suggestions.len(), 1);
+        let suggestion = &suggestions[0];
+        assert_eq!(suggestion.probable_owners, vec!["@query-team"]);
+        assert_eq!(suggestion.ownership_rule.as_deref(), Some("src/query/"));
+        assert_eq!(suggestion.category, DiagnosticCategory::TypeErrors);
+        assert_eq!(suggestion.estimated_fix_minutes, 15);
+        assert!(!suggestion.is_secondary);
+    }
+
+    #[tokio::test]
+    async fn test_triage_without_owners() {
+        let engine = TriageEngine::new();
+        let suggestions = engine
+            .triage(&[diagnostic("d1", "lib.rs", "something odd", None)])
+            .await;
+        assert!(suggestions[0].probable_owners.is_empty());
+        assert!(suggestions[0].rationale.contains("no CODEOWNERS match"));
+    }
+
+    #[test]
+    fn test_related_issues_match_by_code() {
+        let now = SystemTime::now();
+        let patterns = vec![HistoricalErrorPattern {
+            pattern_hash: "abc".to_string(),
+            first_seen: now,
+            last_seen: now,
+            occurrence_count: 7,
+            files_affected: 3,
+            error_message: "mismatched types".to_string(),
+            error_code: Some("E0308".to_string()),
+            source: Some("rust-analyzer".to_string()),
+        }];
+
+        let diag = diagnostic("d1", "a.rs", "different message", Some("E0308"));
+        let related = related_issues(&diag, &patterns);
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].occurrence_count, 7);
+
+        let unrelated = diagnostic("d2", "a.rs", "different message", Some("E0599"));
+        assert!(related_issues(&unrelated, &patterns).is_empty());
+    }
+
+    #[test]
+    fn test_priority_thresholds() {
+        assert_eq!(priority_for_score(90.0), Priority::Critical);
+        assert_eq!(priority_for_score(70.0), Priority::High);
+        assert_eq!(priority_for_score(50.0), Priority::Medium);
+        assert_eq!(priority_for_score(10.0), Priority::Low);
+    }
+}
@@ -3,11 +3,12 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use uuid::Uuid;
+use utoipa::ToSchema;
 
 /// A position in a text document expressed as zero-based line and character offset.
 /// 
 /// This follows the LSP specification for position representation.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 pub struct Position {
     /// Zero-based line number
     pub line: u32,
@@ -18,7 +19,7 @@ pub struct Position {
 /// A range in a text document expressed as start and end positions.
 /// 
 /// The end position is exclusive, meaning it represents the position immediately after the last character.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 pub struct Range {
     /// The range's start position
     pub start: Position,
@@ -30,7 +31,7 @@ pub struct Range {
 /// 
 /// Follows the LSP specification for representing a location within a document.
 /// The URI can be a file path or any other resource identifier.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 pub struct Location {
     /// Document URI or file path
     pub uri: String,
@@ -38,7 +39,7 @@ pub struct Location {
     pub range: Range,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, ToSchema, clap::ValueEnum)]
 #[repr(u8)]
 pub enum DiagnosticSeverity {
     Error = 1,
@@ -47,13 +48,13 @@ pub enum DiagnosticSeverity {
     Hint = 4,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema, clap::ValueEnum)]
 pub enum DiagnosticTag {
     Unnecessary,
     Deprecated,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 pub struct RelatedInformation {
     pub location: Location,
     pub message: String,
@@ -85,7 +86,7 @@ pub struct RelatedInformation {
 ///     data: None,
 /// };
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Diagnostic {
     /// Unique identifier for this diagnostic
     pub id: String,
@@ -113,7 +114,7 @@ pub struct Diagnostic {
 /// 
 /// Contains metadata about the project including its name, location,
 /// and primary programming language. Used to provide context in exports.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct WorkspaceInfo {
     /// Human-readable project or workspace name
     pub name: String,
@@ -123,9 +124,27 @@ pub struct WorkspaceInfo {
     pub language: Option<String>,
     /// Project version (from package.json, Cargo.toml, etc.)
     pub version: Option<String>,
+    /// Folders of a multi-root workspace (empty for single-root workspaces)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roots: Vec<WorkspaceRoot>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A single folder of a multi-root workspace.
+///
+/// Multi-root workspaces (e.g. VS Code `.code-workspace` files) open several
+/// unrelated folders together; each folder gets its own root so diagnostics,
+/// project analysis and privacy policies can be resolved per folder.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct WorkspaceRoot {
+    /// Display name of the folder (defaults to the directory name)
+    pub name: String,
+    /// Absolute path to the folder
+    pub path: String,
+    /// Primary programming language of the folder, if known
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SnapshotMetadata {
     pub capture_method: CaptureMethod,
     pub editor_info: EditorInfo,
@@ -134,14 +153,14 @@ pub struct SnapshotMetadata {
     pub filtered_count: usize,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub enum CaptureMethod {
     Manual,
     Automatic,
     Scheduled,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct EditorInfo {
     pub name: String,
     pub version: String,
@@ -158,6 +177,7 @@ pub struct EditorInfo {
 /// ```rust
 /// use lspbridge::core::{DiagnosticSnapshot, WorkspaceInfo, SnapshotMetadata};
 /// use chrono::Utc;
+/// use std::collections::HashMap;
 /// use uuid::Uuid;
 /// 
 /// let snapshot = DiagnosticSnapshot {
@@ -168,12 +188,14 @@ pub struct EditorInfo {
 ///         root_path: "/path/to/project".to_string(),
 ///         language: Some("rust".to_string()),
 ///         version: Some("0.1.0".to_string()),
+///         roots: vec![],
 ///     },
 ///     diagnostics: vec![],
 ///     metadata: SnapshotMetadata { /* ... */ },
+///     code_lenses: HashMap::new(),
 /// };
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct DiagnosticSnapshot {
     /// Unique identifier for this snapshot
     pub id: Uuid,
@@ -185,6 +207,10 @@ pub struct DiagnosticSnapshot {
     pub diagnostics: Vec<Diagnostic>,
     /// Metadata about how this snapshot was captured
     pub metadata: SnapshotMetadata,
+    /// Code lenses and inlay hints the language servers reported, by file
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    #[schema(value_type = HashMap<String, Vec<CodeLens>>)]
+    pub code_lenses: HashMap<PathBuf, Vec<CodeLens>>,
 }
 
 /// Statistical summary of diagnostic data.
@@ -213,7 +239,7 @@ pub struct DiagnosticSnapshot {
 ///     },
 /// };
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct DiagnosticSummary {
     /// Total number of diagnostics across all severities
     pub total_diagnostics: usize,
@@ -259,6 +285,7 @@ pub struct RawDiagnostics {
 ///     exclude_patterns: Some(vec!["target/**".to_string()]),
 ///     max_results: Some(100),
 ///     since: Some(Utc::now() - Duration::hours(24)),
+///     ..Default::default()
 /// };
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -276,6 +303,158 @@ pub struct DiagnosticFilter {
     pub max_results: Option<usize>,
     /// Only include diagnostics captured after this time
     pub since: Option<DateTime<Utc>>,
+    /// Only include diagnostics captured before this time
+    #[serde(default)]
+    pub until: Option<DateTime<Utc>>,
+    /// Filter by diagnostic code (e.g. `E0308`, `TS2322`)
+    #[serde(default)]
+    pub codes: Option<Vec<String>>,
+    /// Include only diagnostics carrying one of these tags
+    #[serde(default)]
+    pub tags: Option<Vec<DiagnosticTag>>,
+}
+
+impl DiagnosticFilter {
+    /// Whether the filter lets everything through
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Whether a diagnostic captured at `captured_at` passes every criterion
+    /// except `max_results`
+    pub fn matches(&self, diagnostic: &Diagnostic, captured_at: DateTime<Utc>) -> bool {
+        self.matches_time(captured_at)
+            && self.matches_path(&diagnostic.file)
+            && allows(&self.severities, |severities| severities.contains(&diagnostic.severity))
+            && allows(&self.sources, |sources| {
+                sources.iter().any(|source| source.eq_ignore_ascii_case(&diagnostic.source))
+            })
+            && allows(&self.codes, |codes| {
+                diagnostic
+                    .code
+                    .as_ref()
+                    .is_some_and(|code| codes.iter().any(|c| c.eq_ignore_ascii_case(code)))
+            })
+            && allows(&self.tags, |tags| {
+                diagnostic
+                    .tags
+                    .as_ref()
+                    .is_some_and(|found| found.iter().any(|tag| tags.contains(tag)))
+            })
+    }
+
+    /// Whether `time` falls within `since` and `until`
+    pub fn matches_time(&self, time: DateTime<Utc>) -> bool {
+        allows(&self.since, |since| time >= *since) && allows(&self.until, |until| time <= *until)
+    }
+
+    /// Whether a file passes the include and exclude patterns
+    ///
+    /// Patterns are globs; a pattern without glob characters matches any path
+    /// containing it, so `--files src/core` works as expected.
+    pub fn matches_path(&self, path: &str) -> bool {
+        let matches = |pattern: &String| {
+            let pattern = pattern.trim();
+            if pattern.contains(['*', '?', '[']) {
+                glob::Pattern::new(pattern).is_ok_and(|glob| glob.matches(path))
+            } else {
+                path.contains(pattern)
+            }
+        };
+        allows(&self.file_patterns, |patterns| patterns.iter().any(matches))
+            && !self
+                .exclude_patterns
+                .as_ref()
+                .is_some_and(|patterns| patterns.iter().any(matches))
+    }
+
+    /// Keep the matching diagnostics, up to `max_results`
+    pub fn apply(&self, diagnostics: Vec<Diagnostic>, captured_at: DateTime<Utc>) -> Vec<Diagnostic> {
+        diagnostics
+            .into_iter()
+            .filter(|diagnostic| self.matches(diagnostic, captured_at))
+            .take(self.max_results.unwrap_or(usize::MAX))
+            .collect()
+    }
+}
+
+/// Lines of one file an export is limited to, such as an editor selection
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct DiagnosticRegion {
+    /// File path, absolute or relative to the workspace root
+    pub file: String,
+    /// First line, one-based
+    pub start_line: u32,
+    /// Last line, one-based and inclusive
+    pub end_line: u32,
+}
+
+impl DiagnosticRegion {
+    /// The whole of a file
+    pub fn file(file: impl Into<String>) -> Self {
+        Self {
+            file: file.into(),
+            start_line: 1,
+            end_line: u32::MAX,
+        }
+    }
+
+    /// Lines of a file given as `START-END` or a single `LINE`
+    pub fn lines(file: impl Into<String>, lines: &str) -> anyhow::Result<Self> {
+        let parse = |value: &str| {
+            value
+                .trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|line| *line > 0)
+                .ok_or_else(|| anyhow::anyhow!("Invalid line '{value}': lines are numbered from 1"))
+        };
+        let (start_line, end_line) = match lines.split_once('-') {
+            Some((start, end)) => (parse(start)?, parse(end)?),
+            None => (parse(lines)?, parse(lines)?),
+        };
+        if end_line < start_line {
+            return Err(anyhow::anyhow!("Line range {lines} ends before it starts"));
+        }
+        Ok(Self {
+            file: file.into(),
+            start_line,
+            end_line,
+        })
+    }
+
+    /// Whether a diagnostic's range overlaps the region
+    pub fn intersects(&self, diagnostic: &Diagnostic) -> bool {
+        let start = diagnostic.range.start.line.saturating_add(1);
+        let end = diagnostic.range.end.line.saturating_add(1).max(start);
+        self.contains_file(&diagnostic.file) && start <= self.end_line && end >= self.start_line
+    }
+
+    /// Whether a diagnostic path names the region's file
+    ///
+    /// A relative path matches an absolute one it is a suffix of, since
+    /// editors and language servers disagree on which to report.
+    pub fn contains_file(&self, file: &str) -> bool {
+        let normalize = |path: &str| {
+            let path = std::path::Path::new(path.strip_prefix("file://").unwrap_or(path));
+            path.strip_prefix(".").unwrap_or(path).to_path_buf()
+        };
+        let region = normalize(&self.file);
+        let file = normalize(file);
+        match (region.is_absolute(), file.is_absolute()) {
+            (true, false) => region.ends_with(&file),
+            (false, true) => file.ends_with(&region),
+            _ => region == file,
+        }
+    }
+}
+
+/// An unset criterion allows everything
+fn allows<T>(criterion: &Option<T>, check: impl FnOnce(&T) -> bool) -> bool {
+    match criterion {
+        Some(value) => check(value),
+        None => true,
+    }
 }
 
 
@@ -284,6 +463,47 @@ pub struct DiagnosticResult {
     pub diagnostics: HashMap<PathBuf, Vec<Diagnostic>>,
     pub summary: DiagnosticSummary,
     pub timestamp: DateTime<Utc>,
+    /// Code lenses and inlay hints collected alongside the diagnostics
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub code_lenses: HashMap<PathBuf, Vec<CodeLens>>,
+}
+
+/// Kind of supplementary annotation a language server attached to a range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CodeLensKind {
+    CodeLens,
+    InlayHint,
+}
+
+impl CodeLensKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CodeLensKind::CodeLens => "code_lens",
+            CodeLensKind::InlayHint => "inlay_hint",
+        }
+    }
+}
+
+/// A code lens or inlay hint reported by a language server
+///
+/// Lenses carry information diagnostics don't, such as reference counts
+/// ("3 references") or test status, which makes questions like "unused
+/// functions with open warnings" answerable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CodeLens {
+    pub kind: CodeLensKind,
+    pub range: Range,
+    /// Identifier at the start of the range, when the document text was known
+    pub symbol: Option<String>,
+    /// Command title of a lens, or label of an inlay hint
+    pub title: String,
+    /// Command the lens runs when clicked
+    pub command: Option<String>,
+    /// Reference count parsed from the title
+    pub references: Option<u32>,
+    /// `passed`, `failed`, `skipped` or `runnable`, for test lenses
+    pub test_status: Option<String>,
 }
 
 impl Default for DiagnosticResult {
@@ -306,7 +526,28 @@ impl DiagnosticResult {
                 source_breakdown: HashMap::new(),
             },
             timestamp: Utc::now(),
+            code_lenses: HashMap::new(),
+        }
+    }
+
+    /// Diagnostics grouped by file, with the summary counts filled in
+    pub fn from_diagnostics(diagnostics: Vec<Diagnostic>) -> Self {
+        let mut result = Self::new();
+        for diagnostic in diagnostics {
+            result.summary.total_diagnostics += 1;
+            match diagnostic.severity {
+                DiagnosticSeverity::Error => result.summary.error_count += 1,
+                DiagnosticSeverity::Warning => result.summary.warning_count += 1,
+                DiagnosticSeverity::Information => result.summary.info_count += 1,
+                DiagnosticSeverity::Hint => result.summary.hint_count += 1,
+            }
+            result
+                .diagnostics
+                .entry(PathBuf::from(&diagnostic.file))
+                .or_default()
+                .push(diagnostic);
         }
+        result
     }
 }
 
@@ -331,6 +572,155 @@ impl Diagnostic {
             data: None,
         }
     }
+
+    /// Name of the workspace root this diagnostic belongs to, if tagged
+    pub fn workspace_root(&self) -> Option<&str> {
+        self.data.as_ref()?.get(WORKSPACE_ROOT_KEY)?.as_str()
+    }
+
+    /// Tag the diagnostic with the workspace root it belongs to
+    ///
+    /// The root is stored under [`WORKSPACE_ROOT_KEY`] in `data`. Diagnostics
+    /// whose `data` is not a JSON object are left untouched so language
+    /// server payloads are never rewritten.
+    pub fn set_workspace_root(&mut self, root: &str) {
+        match &mut self.data {
+            None => {
+                self.data = Some(serde_json::json!({ WORKSPACE_ROOT_KEY: root }));
+            }
+            Some(serde_json::Value::Object(map)) => {
+                map.insert(WORKSPACE_ROOT_KEY.to_string(), root.into());
+            }
+            Some(_) => {}
+        }
+    }
+
+    /// Original location of a diagnostic re-attributed from generated code
+    ///
+    /// See [`crate::core::GeneratedCodeMapper`].
+    pub fn generated_origin(&self) -> Option<crate::core::GeneratedOrigin> {
+        let origin = self.data.as_ref()?.get(crate::core::GENERATED_KEY)?;
+        serde_json::from_value(origin.clone()).ok()
+    }
+
+    /// Whether the diagnostic was re-attributed from generated code
+    pub fn is_generated(&self) -> bool {
+        self.generated_origin().is_some()
+    }
+
+    /// Public item this diagnostic touches, with the estimated impact of a fix
+    ///
+    /// See [`crate::core::ApiSurfaceAnalyzer`].
+    pub fn api_surface(&self) -> Option<crate::core::ApiSurfaceInfo> {
+        let info = self.data.as_ref()?.get(crate::core::API_SURFACE_KEY)?;
+        serde_json::from_value(info.clone()).ok()
+    }
+
+    /// Whether the diagnostic is on an exported item
+    pub fn touches_api_surface(&self) -> bool {
+        self.api_surface().is_some()
+    }
+
+    /// Severity the diagnostic was reported with, if a severity rule changed it
+    ///
+    /// See [`crate::core::SeverityRules`].
+    pub fn original_severity(&self) -> Option<DiagnosticSeverity> {
+        let severity = self.data.as_ref()?.get(crate::core::ORIGINAL_SEVERITY_KEY)?;
+        serde_json::from_value(severity.clone()).ok()
+    }
+
+    /// Canonical order of diagnostics: path, range and code, then source,
+    /// severity and message, so the same diagnostics always sort the same way
+    pub fn canonical_cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let position = |p: &Position| (p.line, p.character);
+        self.file
+            .cmp(&other.file)
+            .then_with(|| position(&self.range.start).cmp(&position(&other.range.start)))
+            .then_with(|| position(&self.range.end).cmp(&position(&other.range.end)))
+            .then_with(|| self.code.cmp(&other.code))
+            .then_with(|| self.source.cmp(&other.source))
+            .then_with(|| (self.severity as u8).cmp(&(other.severity as u8)))
+            .then_with(|| self.message.cmp(&other.message))
+    }
+
+    /// Identifier derived from the diagnostic's content
+    ///
+    /// Unlike `id`, which is a fresh UUID per capture, this is the same for
+    /// the same diagnostic across captures, which keeps exports diffable.
+    pub fn content_id(&self) -> String {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        for part in [
+            self.file.as_str(),
+            &format!(
+                "{}:{}-{}:{}",
+                self.range.start.line, self.range.start.character, self.range.end.line, self.range.end.character
+            ),
+            self.code.as_deref().unwrap_or(""),
+            self.source.as_str(),
+            &format!("{:?}", self.severity),
+            self.message.as_str(),
+        ] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        hasher.finalize().iter().take(8).map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    /// Runtime crashes seen at this diagnostic's location
+    ///
+    /// See [`crate::core::CrashCorrelator`].
+    pub fn crash_correlation(&self) -> Option<crate::core::CrashCorrelation> {
+        let correlation = self.data.as_ref()?.get(crate::core::CRASH_KEY)?;
+        serde_json::from_value(correlation.clone()).ok()
+    }
+
+    /// Where else this diagnostic occurred, when duplicates were collapsed into it
+    ///
+    /// See [`crate::core::Deduplicator`].
+    pub fn duplicates(&self) -> Option<crate::core::DuplicateInfo> {
+        let info = self.data.as_ref()?.get(crate::core::DEDUP_KEY)?;
+        serde_json::from_value(info.clone()).ok()
+    }
+
+    /// How many times this diagnostic occurred, 1 unless duplicates were collapsed
+    pub fn occurrences(&self) -> usize {
+        self.duplicates().map_or(1, |info| info.occurrences)
+    }
+
+    /// Whether crash reports show this location failing at runtime
+    pub fn crashes_in_production(&self) -> bool {
+        self.crash_correlation().is_some()
+    }
+}
+
+/// Key in [`Diagnostic::data`] holding the name of the owning workspace root
+pub const WORKSPACE_ROOT_KEY: &str = "lspbridgeWorkspaceRoot";
+
+impl WorkspaceInfo {
+    /// Check whether this is a multi-root workspace
+    pub fn is_multi_root(&self) -> bool {
+        self.roots.len() > 1
+    }
+
+    /// Find the root containing a file (the most specific root wins)
+    pub fn root_for(&self, file: &str) -> Option<&WorkspaceRoot> {
+        root_for_path(&self.roots, file)
+    }
+}
+
+/// Find the root containing a file among a set of roots
+///
+/// Roots may be nested; the root with the longest matching path wins.
+/// Matching is per path component, so `/work/api` does not contain
+/// `/work/api-client/lib.rs`.
+pub fn root_for_path<'a>(roots: &'a [WorkspaceRoot], file: &str) -> Option<&'a WorkspaceRoot> {
+    let file = std::path::Path::new(file);
+    roots
+        .iter()
+        .filter(|root| file.starts_with(&root.path))
+        .max_by_key(|root| root.path.len())
 }
 
 impl DiagnosticSnapshot {
@@ -361,6 +751,7 @@ impl DiagnosticSnapshot {
             workspace,
             diagnostics,
             metadata,
+            code_lenses: HashMap::new(),
         }
     }
 
@@ -0,0 +1,280 @@
+//! Per-client usage accounting and monthly quotas for the query API
+//!
+//! Rate limiting bounds how fast a client may query; usage accounting bounds
+//! how much it may consume over a calendar month. Counters are kept per client
+//! id (see [`extract_client_id`](crate::core::extract_client_id)) and per
+//! UTC month in a small SQLite database so they survive restarts.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use clap::Subcommand;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Actions for inspecting the query API
+#[derive(Debug, Clone, Subcommand)]
+pub enum ApiAction {
+    /// Report per-client usage against the configured monthly quotas
+    Usage {
+        /// Month to report, as YYYY-MM (defaults to the current month)
+        #[arg(long)]
+        month: Option<String>,
+        /// Only report this client id (e.g. `api:<key>`)
+        #[arg(long)]
+        client: Option<String>,
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "markdown")]
+        format: crate::cli::OutputFormat,
+    },
+}
+
+/// Monthly limits for a single client; `None` means unlimited
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuotaLimits {
+    /// Queries the client may run per month
+    pub max_queries: Option<u64>,
+    /// Rows the client's queries may scan per month
+    pub max_rows_scanned: Option<u64>,
+    /// Bytes of results the client may receive per month
+    pub max_bytes_exported: Option<u64>,
+}
+
+impl QuotaLimits {
+    /// Describe the first limit `usage` has reached, if any
+    pub fn exceeded_by(&self, usage: &UsageRecord) -> Option<String> {
+        let checks = [
+            ("query", usage.queries, self.max_queries),
+            ("rows scanned", usage.rows_scanned, self.max_rows_scanned),
+            ("bytes exported", usage.bytes_exported, self.max_bytes_exported),
+        ];
+        checks.into_iter().find_map(|(name, used, limit)| {
+            limit
+                .filter(|limit| used >= *limit)
+                .map(|limit| format!("Monthly {name} quota of {limit} exhausted for {}", usage.month))
+        })
+    }
+}
+
+/// Quota configuration, under `[api_quotas]` in `lspbridge.toml`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuotaConfig {
+    /// Limits applied to clients without an override
+    pub default: QuotaLimits,
+    /// Per-client overrides keyed by client id
+    pub clients: HashMap<String, QuotaLimits>,
+}
+
+impl QuotaConfig {
+    /// Limits that apply to `client_id`
+    pub fn limits_for(&self, client_id: &str) -> &QuotaLimits {
+        self.clients.get(client_id).unwrap_or(&self.default)
+    }
+}
+
+/// Usage counters of one client for one month
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub client_id: String,
+    /// UTC month as YYYY-MM
+    pub month: String,
+    pub queries: u64,
+    pub rows_scanned: u64,
+    pub bytes_exported: u64,
+}
+
+/// Current UTC month as YYYY-MM
+pub fn current_month() -> String {
+    Utc::now().format("%Y-%m").to_string()
+}
+
+/// SQLite-backed store of per-client usage counters
+pub struct UsageStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl UsageStore {
+    /// Default location of the usage database
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(crate::config::data_dir()?.join("api_usage.db"))
+    }
+
+    /// Open or create the usage database at `path`
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).context("Failed to create usage directory")?;
+        }
+        let conn = Connection::open(path).context("Failed to open usage database")?;
+        Self::from_connection(conn)
+    }
+
+    /// Usage store that is discarded when dropped
+    pub fn in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS api_usage (
+                client_id TEXT NOT NULL,
+                month TEXT NOT NULL,
+                queries INTEGER NOT NULL DEFAULT 0,
+                rows_scanned INTEGER NOT NULL DEFAULT 0,
+                bytes_exported INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (client_id, month)
+            );
+            "#,
+        )
+        .context("Failed to initialize usage schema")?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Add one query to the client's counters for the current month
+    pub async fn record(&self, client_id: &str, rows_scanned: u64, bytes_exported: u64) -> Result<()> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            r#"
+            INSERT INTO api_usage (client_id, month, queries, rows_scanned, bytes_exported)
+            VALUES (?1, ?2, 1, ?3, ?4)
+            ON CONFLICT(client_id, month) DO UPDATE SET
+                queries = queries + 1,
+                rows_scanned = rows_scanned + excluded.rows_scanned,
+                bytes_exported = bytes_exported + excluded.bytes_exported
+            "#,
+            params![client_id, current_month(), rows_scanned as i64, bytes_exported as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Counters of a client for a month, zero when it has no usage yet
+    pub async fn usage(&self, client_id: &str, month: &str) -> Result<UsageRecord> {
+        let conn = self.conn.lock().await;
+        let record = conn
+            .query_row(
+                "SELECT client_id, month, queries, rows_scanned, bytes_exported
+                 FROM api_usage WHERE client_id = ?1 AND month = ?2",
+                params![client_id, month],
+                Self::row_to_record,
+            )
+            .optional()?;
+
+        Ok(record.unwrap_or_else(|| UsageRecord {
+            client_id: client_id.to_string(),
+            month: month.to_string(),
+            ..Default::default()
+        }))
+    }
+
+    /// Counters of every client for a month, heaviest users first
+    pub async fn report(&self, month: &str) -> Result<Vec<UsageRecord>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT client_id, month, queries, rows_scanned, bytes_exported
+             FROM api_usage WHERE month = ?1
+             ORDER BY queries DESC, client_id",
+        )?;
+        let records = stmt
+            .query_map(params![month], Self::row_to_record)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(records)
+    }
+
+    fn row_to_record(row: &rusqlite::Row) -> rusqlite::Result<UsageRecord> {
+        Ok(UsageRecord {
+            client_id: row.get(0)?,
+            month: row.get(1)?,
+            queries: row.get::<_, i64>(2)? as u64,
+            rows_scanned: row.get::<_, i64>(3)? as u64,
+            bytes_exported: row.get::<_, i64>(4)? as u64,
+        })
+    }
+}
+
+/// Usage store paired with the quotas it enforces
+pub struct UsageAccounting {
+    store: UsageStore,
+    quotas: QuotaConfig,
+}
+
+impl UsageAccounting {
+    pub fn new(store: UsageStore, quotas: QuotaConfig) -> Self {
+        Self { store, quotas }
+    }
+
+    /// Reject the client if it has used up any of its monthly quotas
+    pub async fn check(&self, client_id: &str) -> Result<Option<String>> {
+        let limits = self.quotas.limits_for(client_id);
+        if *limits == QuotaLimits::default() {
+            return Ok(None);
+        }
+        let usage = self.store.usage(client_id, &current_month()).await?;
+        Ok(limits.exceeded_by(&usage))
+    }
+
+    /// Account for a completed query
+    pub async fn record(&self, client_id: &str, rows_scanned: u64, bytes_exported: u64) -> Result<()> {
+        self.store.record(client_id, rows_scanned, bytes_exported).await
+    }
+
+    pub fn store(&self) -> &UsageStore {
+        &self.store
+    }
+
+    pub fn quotas(&self) -> &QuotaConfig {
+        &self.quotas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_usage_accumulates_per_client() {
+        let store = UsageStore::in_memory().unwrap();
+        store.record("api:a", 10, 100).await.unwrap();
+        store.record("api:a", 5, 50).await.unwrap();
+        store.record("api:b", 1, 1).await.unwrap();
+
+        let month = current_month();
+        let usage = store.usage("api:a", &month).await.unwrap();
+        assert_eq!((usage.queries, usage.rows_scanned, usage.bytes_exported), (2, 15, 150));
+
+        let report = store.report(&month).await.unwrap();
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].client_id, "api:a");
+        assert_eq!(store.usage("api:c", &month).await.unwrap().queries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_quota_rejects_exhausted_client() {
+        let mut quotas = QuotaConfig::default();
+        quotas.clients.insert(
+            "api:a".to_string(),
+            QuotaLimits {
+                max_queries: Some(2),
+                ..Default::default()
+            },
+        );
+        let accounting = UsageAccounting::new(UsageStore::in_memory().unwrap(), quotas);
+
+        accounting.record("api:a", 1, 1).await.unwrap();
+        assert!(accounting.check("api:a").await.unwrap().is_none());
+        accounting.record("api:a", 1, 1).await.unwrap();
+        let message = accounting.check("api:a").await.unwrap().unwrap();
+        assert!(message.contains("query quota of 2"));
+
+        // Clients without limits are never rejected
+        accounting.record("api:b", 1, 1).await.unwrap();
+        assert!(accounting.check("api:b").await.unwrap().is_none());
+    }
+}
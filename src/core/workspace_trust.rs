@@ -0,0 +1,199 @@
+//! Workspace trust
+//!
+//! Some features run commands a project defines: fix verification runs the
+//! project's build and test scripts, and language server profiles in
+//! `.lspbridge.toml` choose which binary to launch and with what arguments.
+//! Opening a cloned repository must not be enough to run its code, so, as
+//! in editors, workspaces are untrusted until the user trusts them with
+//! `lspbridge trust <path>`. Untrusted workspaces are limited to read-only
+//! analysis.
+//!
+//! Trust is kept in the `[trust]` table of the global configuration file:
+//!
+//! ```toml
+//! [trust]
+//! trusted = ["/home/me/work"]
+//! untrusted = ["/home/me/work/vendor"]
+//! ```
+//!
+//! Entries cover everything beneath them and the most specific entry wins,
+//! so a subdirectory can be excluded from a trusted tree.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Global configuration file, in the platform config directory
+pub const GLOBAL_CONFIG_FILE: &str = "config.toml";
+
+/// Whether a workspace may execute project-defined commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrustLevel {
+    Trusted,
+    /// Read-only analysis only
+    Untrusted,
+}
+
+/// Trusted and untrusted workspace roots
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceTrust {
+    #[serde(default)]
+    pub trusted: Vec<PathBuf>,
+    #[serde(default)]
+    pub untrusted: Vec<PathBuf>,
+}
+
+impl WorkspaceTrust {
+    /// Location of the global configuration file
+    pub fn default_path() -> PathBuf {
+        crate::config::config_dir()
+            .unwrap_or_else(|_| std::env::temp_dir().join("lspbridge"))
+            .join(GLOBAL_CONFIG_FILE)
+    }
+
+    /// Load trust settings from the global configuration
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::default_path())
+    }
+
+    /// Load the `[trust]` table of a configuration file; a missing file trusts nothing
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let table = read_table(path)?;
+        match table.get("trust") {
+            Some(trust) => trust
+                .clone()
+                .try_into()
+                .with_context(|| format!("Invalid [trust] table in {}", path.display())),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Write the `[trust]` table, keeping the rest of the configuration file
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        let mut table = if path.exists() { read_table(path)? } else { toml::value::Table::new() };
+        table.insert("trust".to_string(), toml::Value::try_from(self)?);
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::write(path, toml::to_string_pretty(&table)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Trust level of a workspace, from the most specific entry covering it
+    ///
+    /// Workspaces no entry covers are untrusted; on equally specific entries
+    /// untrusted wins.
+    pub fn level(&self, workspace: &Path) -> TrustLevel {
+        let workspace = normalize(workspace);
+        let depth = |roots: &[PathBuf]| {
+            roots
+                .iter()
+                .map(|root| normalize(root))
+                .filter(|root| workspace.starts_with(root))
+                .map(|root| root.components().count())
+                .max()
+        };
+
+        match (depth(&self.trusted), depth(&self.untrusted)) {
+            (Some(trusted), Some(untrusted)) if trusted > untrusted => TrustLevel::Trusted,
+            (Some(_), None) => TrustLevel::Trusted,
+            _ => TrustLevel::Untrusted,
+        }
+    }
+
+    pub fn is_trusted(&self, workspace: &Path) -> bool {
+        self.level(workspace) == TrustLevel::Trusted
+    }
+
+    /// Trust a workspace and everything beneath it
+    pub fn trust(&mut self, workspace: &Path) {
+        let workspace = normalize(workspace);
+        self.untrusted.retain(|root| normalize(root) != workspace);
+        if !self.trusted.iter().any(|root| normalize(root) == workspace) {
+            self.trusted.push(workspace);
+        }
+    }
+
+    /// Revoke trust in a workspace, overriding any trusted parent
+    pub fn revoke(&mut self, workspace: &Path) {
+        let workspace = normalize(workspace);
+        self.trusted.retain(|root| normalize(root) != workspace);
+        if self.level(&workspace) == TrustLevel::Trusted {
+            self.untrusted.push(workspace);
+        }
+    }
+
+    /// Fail unless `workspace` is trusted; `action` describes what was refused
+    pub fn ensure_trusted(&self, workspace: &Path, action: &str) -> Result<()> {
+        match self.level(workspace) {
+            TrustLevel::Trusted => Ok(()),
+            TrustLevel::Untrusted => Err(untrusted_error(workspace, action)),
+        }
+    }
+}
+
+/// Error for a project command refused in an untrusted workspace
+pub fn untrusted_error(workspace: &Path, action: &str) -> anyhow::Error {
+    anyhow!(
+        "Workspace {} is not trusted, refusing to {}. Run `lspbridge trust {}` to allow project commands",
+        workspace.display(),
+        action,
+        workspace.display()
+    )
+}
+
+fn read_table(path: &Path) -> Result<toml::value::Table> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Absolute, symlink-free form of a path where it exists
+fn normalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| {
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            std::env::current_dir().map(|cwd| cwd.join(path)).unwrap_or_else(|_| path.to_path_buf())
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_most_specific_entry_wins_and_persists() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let work = dir.path().join("work");
+        let vendor = work.join("vendor");
+        std::fs::create_dir_all(&vendor)?;
+
+        let mut trust = WorkspaceTrust::default();
+        assert_eq!(trust.level(&work), TrustLevel::Untrusted);
+
+        trust.trust(&work);
+        trust.revoke(&vendor);
+        assert!(trust.is_trusted(&work.join("src")));
+        assert_eq!(trust.level(&vendor), TrustLevel::Untrusted);
+        assert!(trust.ensure_trusted(&vendor, "run the build").is_err());
+
+        let config = dir.path().join(GLOBAL_CONFIG_FILE);
+        std::fs::write(&config, "log_level = \"debug\"\n")?;
+        trust.save_to(&config)?;
+        assert_eq!(WorkspaceTrust::load_from(&config)?, trust);
+        assert!(std::fs::read_to_string(&config)?.contains("log_level"));
+
+        trust.trust(&vendor);
+        assert!(trust.is_trusted(&vendor));
+        assert!(trust.untrusted.is_empty());
+        Ok(())
+    }
+}
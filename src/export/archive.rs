@@ -0,0 +1,241 @@
+//! Compressed exports and single-file bundles
+//!
+//! Large exports can be written gzip- or zstd-compressed, and exports that
+//! produce several files (one per format or route) can be packed into one
+//! tar archive with a `manifest.json` listing every file, its format and
+//! checksum, which is easier to hand to CI artifact storage than a directory.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+
+use super::ExportOutput;
+use crate::core::ExportFormat;
+
+/// Name of the manifest at the start of every bundle
+pub const MANIFEST_NAME: &str = "manifest.json";
+
+/// Compression applied to written exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// Suffix appended to compressed file names
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Compression::Gzip => "gz",
+            Compression::Zstd => "zst",
+        }
+    }
+
+    pub fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Compression::Gzip => {
+                let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data)?;
+                Ok(encoder.finish()?)
+            }
+            Compression::Zstd => Ok(zstd::stream::encode_all(data, zstd::DEFAULT_COMPRESSION_LEVEL)?),
+        }
+    }
+}
+
+/// One file in a bundle, as listed in its manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleEntry {
+    /// Path inside the archive
+    pub path: String,
+    pub format: Option<ExportFormat>,
+    /// Uncompressed size in bytes
+    pub size: usize,
+    pub sha256: String,
+}
+
+/// `manifest.json` of a bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub generated_at: DateTime<Utc>,
+    pub lspbridge_version: String,
+    /// Compression of the archive as a whole
+    pub compression: Option<Compression>,
+    pub files: Vec<BundleEntry>,
+}
+
+/// Export files collected into one tar archive
+#[derive(Debug, Clone)]
+pub struct ExportBundle {
+    generated_at: DateTime<Utc>,
+    entries: Vec<(BundleEntry, Vec<u8>)>,
+}
+
+impl ExportBundle {
+    pub fn new(generated_at: DateTime<Utc>) -> Self {
+        Self {
+            generated_at,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Add a rendered export at `path` inside the archive
+    pub fn add_output(&mut self, path: &str, output: &ExportOutput) -> Result<()> {
+        self.add(path, Some(output.format.clone()), output.content.as_bytes().to_vec())
+    }
+
+    /// Add a file at `path` inside the archive
+    ///
+    /// Paths are relative and `/`-separated; paths longer than a plain tar
+    /// header allows are stored as GNU long names.
+    pub fn add(&mut self, path: &str, format: Option<ExportFormat>, content: Vec<u8>) -> Result<()> {
+        let valid = !path.is_empty()
+            && !path.starts_with('/')
+            && path.split('/').all(|part| !part.is_empty() && part != "." && part != "..");
+        if !valid || path == MANIFEST_NAME {
+            return Err(anyhow!("'{path}' can't be used as a path inside an export bundle"));
+        }
+        if self.entries.iter().any(|(entry, _)| entry.path == path) {
+            return Err(anyhow!("'{path}' is already in the export bundle"));
+        }
+
+        let sha256 = Sha256::digest(&content).iter().map(|byte| format!("{byte:02x}")).collect();
+        let entry = BundleEntry {
+            path: path.to_string(),
+            format,
+            size: content.len(),
+            sha256,
+        };
+        self.entries.push((entry, content));
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn manifest(&self, compression: Option<Compression>) -> BundleManifest {
+        BundleManifest {
+            generated_at: self.generated_at,
+            lspbridge_version: env!("CARGO_PKG_VERSION").to_string(),
+            compression,
+            files: self.entries.iter().map(|(entry, _)| entry.clone()).collect(),
+        }
+    }
+
+    /// The archive bytes: a tar with the manifest first, then compressed if asked
+    pub fn to_archive(&self, compression: Option<Compression>) -> Result<Vec<u8>> {
+        let manifest = serde_json::to_vec_pretty(&self.manifest(compression))?;
+        let mtime = self.generated_at.timestamp().max(0) as u64;
+
+        let mut tar = tar::Builder::new(Vec::new());
+        append_tar_entry(&mut tar, MANIFEST_NAME, &manifest, mtime)?;
+        for (entry, content) in &self.entries {
+            append_tar_entry(&mut tar, &entry.path, content, mtime)?;
+        }
+        let tar = tar.into_inner()?;
+
+        match compression {
+            Some(compression) => compression.compress(&tar),
+            None => Ok(tar),
+        }
+    }
+
+    /// Conventional file extension for the archive, e.g. `tar.gz`
+    pub fn extension(compression: Option<Compression>) -> String {
+        match compression {
+            Some(compression) => format!("tar.{}", compression.extension()),
+            None => "tar".to_string(),
+        }
+    }
+}
+
+/// Append a regular file owned by root, readable by everyone
+fn append_tar_entry(tar: &mut tar::Builder<Vec<u8>>, path: &str, content: &[u8], mtime: u64) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(mtime);
+    header.set_entry_type(tar::EntryType::Regular);
+    tar.append_data(&mut header, path, content)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    /// (path, contents) of every file in a tar archive
+    fn read_tar(tar: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let mut archive = tar::Archive::new(tar);
+        archive
+            .entries()
+            .unwrap()
+            .map(|entry| {
+                let mut entry = entry.unwrap();
+                let path = entry.path().unwrap().to_string_lossy().to_string();
+                let mut content = Vec::new();
+                entry.read_to_end(&mut content).unwrap();
+                (path, content)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_bundle_lists_files_in_manifest() {
+        let mut bundle = ExportBundle::new(Utc::now());
+        let json = ExportOutput {
+            format: ExportFormat::Json,
+            content: "{\"diagnostics\":[]}".to_string(),
+        };
+        bundle.add_output("diagnostics.json", &json).unwrap();
+        let sarif = ExportOutput {
+            format: ExportFormat::Sarif,
+            content: "x".repeat(700),
+        };
+        bundle.add_output("security/security.sarif", &sarif).unwrap();
+        assert!(bundle.add_output("diagnostics.json", &json).is_err());
+        assert!(bundle.add_output("../escape.json", &json).is_err());
+
+        let files = read_tar(&bundle.to_archive(None).unwrap());
+        let names: Vec<_> = files.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec![MANIFEST_NAME, "diagnostics.json", "security/security.sarif"]);
+        assert_eq!(files[2].1.len(), 700);
+
+        let manifest: BundleManifest = serde_json::from_slice(&files[0].1).unwrap();
+        assert_eq!(manifest.files.len(), 2);
+        assert_eq!(manifest.files[0].sha256.len(), 64);
+        assert!(matches!(manifest.files[1].format, Some(ExportFormat::Sarif)));
+    }
+
+    #[test]
+    fn test_gzip_bundle_round_trips() {
+        let mut bundle = ExportBundle::new(Utc::now());
+        bundle.add("report.html", Some(ExportFormat::Html), b"<html></html>".to_vec()).unwrap();
+        let archive = bundle.to_archive(Some(Compression::Gzip)).unwrap();
+        assert_eq!(ExportBundle::extension(Some(Compression::Gzip)), "tar.gz");
+
+        let mut tar = Vec::new();
+        flate2::read::GzDecoder::new(archive.as_slice()).read_to_end(&mut tar).unwrap();
+        let files = read_tar(&tar);
+        assert_eq!(files[1], ("report.html".to_string(), b"<html></html>".to_vec()));
+    }
+
+    #[test]
+    fn test_zstd_bundle_keeps_long_paths() {
+        let long_path = format!("routes/{}/diagnostics.sarif", "team".repeat(30));
+        assert!(long_path.len() > 100);
+        let mut bundle = ExportBundle::new(Utc::now());
+        bundle.add(&long_path, Some(ExportFormat::Sarif), b"{}".to_vec()).unwrap();
+        let archive = bundle.to_archive(Some(Compression::Zstd)).unwrap();
+
+        let tar = zstd::stream::decode_all(archive.as_slice()).unwrap();
+        let files = read_tar(&tar);
+        assert_eq!(files[1], (long_path, b"{}".to_vec()));
+    }
+}
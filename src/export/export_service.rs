@@ -1,9 +1,12 @@
 use crate::core::constants::severity_labels;
 use crate::core::errors::ExportError;
 use crate::core::{
-    Diagnostic, DiagnosticSeverity, DiagnosticSnapshot, DiagnosticSummary, ExportConfig,
-    ExportService as ExportServiceTrait, SortBy,
+    CrashCorrelation, Diagnostic, DuplicateInfo, DiagnosticSeverity, DiagnosticSnapshot, DiagnosticSummary, ExportConfig,
+    ExportService as ExportServiceTrait, FileGuard, LicenseAction, LicenseExclusion, LicenseFilter,
+    NoiseReport, SortBy, TriageSuggestion,
 };
+use crate::format::{ContextSelection, ContextSelector, TokenEstimator};
+use crate::privacy::RedactionAudit;
 use crate::project::ProjectInfo;
 use std::collections::HashMap;
 use std::path::Path;
@@ -46,6 +49,15 @@ use std::path::Path;
 /// ```
 pub struct ExportService {
     project_info: Option<ProjectInfo>,
+    triage: Vec<TriageSuggestion>,
+    noise: Option<NoiseReport>,
+    token_estimator: Option<TokenEstimator>,
+    max_tokens: Option<usize>,
+    context_budget: Option<usize>,
+    file_guard: Option<FileGuard>,
+    license_filter: Option<LicenseFilter>,
+    /// Audits the privacy filter of each route in [`export_routed`](Self::export_routed)
+    pub(super) privacy_audit: Option<RedactionAudit>,
 }
 
 impl ExportService {
@@ -63,7 +75,17 @@ impl ExportService {
     /// let service = ExportService::new();
     /// ```
     pub fn new() -> Self {
-        Self { project_info: None }
+        Self {
+            project_info: None,
+            triage: Vec::new(),
+            noise: None,
+            token_estimator: None,
+            max_tokens: None,
+            context_budget: None,
+            file_guard: None,
+            license_filter: None,
+            privacy_audit: None,
+        }
     }
 
     /// Create a new ExportService with project context.
@@ -87,31 +109,281 @@ impl ExportService {
     /// ```
     pub fn with_project_info(project_root: &Path) -> Self {
         let project_info = ProjectInfo::analyze(project_root).ok();
-        Self { project_info }
+        Self {
+            project_info,
+            triage: Vec::new(),
+            noise: None,
+            token_estimator: None,
+            max_tokens: None,
+            context_budget: None,
+            file_guard: None,
+            license_filter: None,
+            privacy_audit: None,
+        }
+    }
+
+    /// Attach triage suggestions to be emitted alongside the diagnostics.
+    ///
+    /// Suggestions are produced by [`crate::core::TriageEngine`] and appear as a
+    /// `triage` array in JSON exports and a "Triage" section in Markdown and
+    /// Claude-optimized exports.
+    pub fn with_triage(mut self, triage: Vec<TriageSuggestion>) -> Self {
+        self.triage = triage;
+        self
+    }
+
+    /// Attach the noise report produced by [`crate::core::NoiseModel`].
+    ///
+    /// Diagnostics from downranked patterns are ordered after all others, and
+    /// muted patterns are listed in a `noise` object in JSON exports and a
+    /// "Muted Diagnostics" section in Markdown and Claude-optimized exports.
+    pub fn with_noise_report(mut self, report: NoiseReport) -> Self {
+        self.noise = Some(report);
+        self
+    }
+
+    /// Annotate Claude-optimized exports with token and cost estimates.
+    ///
+    /// When `max_tokens` is set, diagnostics are trimmed by relevance (errors
+    /// first) so the report fits the budget. Diagnostics whose own section
+    /// exceeds the model's practical window are called out in the report.
+    pub fn with_token_estimator(
+        mut self,
+        estimator: TokenEstimator,
+        max_tokens: Option<usize>,
+    ) -> Self {
+        self.token_estimator = Some(estimator);
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// Cap the code context in Claude-optimized exports, in estimated tokens.
+    ///
+    /// With `include_context`, diagnostics in the same function or class share
+    /// one context block, and blocks are kept in order of aggregate relevance
+    /// until the budget is spent. Without an explicit cap, whatever remains of
+    /// `max_tokens` after the diagnostics is used.
+    pub fn with_context_budget(mut self, tokens: usize) -> Self {
+        self.context_budget = Some(tokens);
+        self
+    }
+
+    /// Size and binary limits for source files read into code context
+    ///
+    /// Files the guard skips are listed under the code context so readers
+    /// know which locations have no surrounding code.
+    pub fn with_file_guard(mut self, file_guard: FileGuard) -> Self {
+        self.file_guard = Some(file_guard);
+        self
+    }
+
+    /// Keep code under restricted licenses out of Claude-optimized exports
+    ///
+    /// Diagnostics in files whose license an `exclude` rule matches are left
+    /// out; files matched by a `redact` rule keep their diagnostics but get no
+    /// code context. The report lists every affected file and its license.
+    pub fn with_license_filter(mut self, filter: LicenseFilter) -> Self {
+        self.license_filter = Some(filter);
+        self
+    }
+
+    /// Audit the privacy filters of routed exports into `audit`
+    pub fn with_privacy_audit(mut self, audit: RedactionAudit) -> Self {
+        self.privacy_audit = Some(audit);
+        self
+    }
+
+    /// Files of `diagnostics` that the license filter excludes or redacts
+    pub fn license_exclusions(&self, diagnostics: &[Diagnostic]) -> Vec<LicenseExclusion> {
+        match &self.license_filter {
+            Some(filter) => filter.check_all(diagnostics.iter().map(|d| d.file.as_str())),
+            None => Vec::new(),
+        }
+    }
+
+    fn add_license_exclusions(
+        &self,
+        lines: &mut Vec<String>,
+        exclusions: &[LicenseExclusion],
+        diagnostics: &[Diagnostic],
+    ) {
+        if exclusions.is_empty() {
+            return;
+        }
+
+        lines.push("## License Exclusions".to_string());
+        lines.push(String::new());
+        for exclusion in exclusions {
+            let count = diagnostics.iter().filter(|d| d.file == exclusion.file).count();
+            lines.push(format!(
+                "- `{}` ({}): {}, {} diagnostic(s)",
+                exclusion.file, exclusion.license.spdx, exclusion.action, count
+            ));
+        }
+        lines.push(String::new());
+    }
+
+    fn select_context(
+        &self,
+        diagnostics: &[&Diagnostic],
+        config: &ExportConfig,
+        remaining_tokens: Option<usize>,
+    ) -> ContextSelection {
+        let mut selector = ContextSelector::new(config.context_lines);
+        if let Some(estimator) = &self.token_estimator {
+            selector = selector.with_estimator(estimator.clone());
+        }
+        if let Some(budget) = self.context_budget.or(remaining_tokens) {
+            selector = selector.with_budget(budget);
+        }
+        if let Some(file_guard) = &self.file_guard {
+            selector = selector.with_file_guard(file_guard.clone());
+        }
+        selector.select(diagnostics)
+    }
+
+    fn add_context_blocks(&self, lines: &mut Vec<String>, selection: &ContextSelection) {
+        if selection.is_empty() && selection.skipped.is_empty() {
+            return;
+        }
+
+        lines.push("## Code Context".to_string());
+        lines.push(String::new());
+        for (index, block) in selection.blocks.iter().enumerate() {
+            lines.push(format!(
+                "### [C{}] {}:{}-{} ({})",
+                index + 1,
+                block.file,
+                block.start_line + 1,
+                block.end_line + 1,
+                block.label()
+            ));
+            lines.push(format!(
+                "Shared by {} diagnostic(s)",
+                block.diagnostic_ids.len()
+            ));
+            lines.push("```".to_string());
+            lines.push(block.code.clone());
+            lines.push("```".to_string());
+            lines.push(String::new());
+        }
+        if selection.omitted > 0 {
+            lines.push(format!(
+                "_{} lower-relevance context block(s) omitted to fit the budget._",
+                selection.omitted
+            ));
+            lines.push(String::new());
+        }
+        if !selection.skipped.is_empty() {
+            lines.push(format!("_No context for {} skipped file(s):_", selection.skipped.len()));
+            for file in &selection.skipped {
+                lines.push(format!("- `{}`: {}", file.path.display(), file.reason));
+            }
+            lines.push(String::new());
+        }
+    }
+
+    fn add_token_estimate(
+        &self,
+        lines: &mut Vec<String>,
+        estimator: &TokenEstimator,
+        trimmed: usize,
+        oversized: &[String],
+    ) {
+        let estimate = estimator.estimate_text(&lines.join("\n"));
+
+        lines.push(String::new());
+        lines.push("## Token Estimate".to_string());
+        lines.push(format!(
+            "- **Model**: {} ({} token window)",
+            estimate.model, estimate.context_window
+        ));
+        lines.push(format!(
+            "- **Estimated tokens**: ~{} (~${:.4})",
+            estimate.tokens, estimate.estimated_cost_usd
+        ));
+        if trimmed > 0 {
+            lines.push(format!(
+                "- **Trimmed**: {} lower-priority diagnostic(s) omitted to fit {} tokens",
+                trimmed,
+                self.max_tokens.unwrap_or_default()
+            ));
+        }
+        for location in oversized {
+            lines.push(format!(
+                "- **Warning**: {location} exceeds the model's practical window"
+            ));
+        }
     }
 
-    fn sort_diagnostics(&self, diagnostics: &[Diagnostic], sort_by: &SortBy) -> Vec<Diagnostic> {
+    fn add_markdown_noise(&self, lines: &mut Vec<String>) {
+        let Some(report) = self.noise.as_ref().filter(|r| !r.is_empty()) else {
+            return;
+        };
+
+        lines.push("## Muted Diagnostics".to_string());
+        lines.push(String::new());
+        lines.push(format!(
+            "{} diagnostic(s) from {} chronically ignored pattern(s) were muted; {} pattern(s) were downranked.",
+            report.muted_count(),
+            report.muted.len(),
+            report.downranked.len()
+        ));
+        lines.push(String::new());
+        if !report.muted.is_empty() {
+            lines.push("| Pattern | Count | Age | Noise Score |".to_string());
+            lines.push("|---|---|---|---|".to_string());
+            for pattern in &report.muted {
+                lines.push(format!(
+                    "| {} | {} | {} days | {:.2} |",
+                    pattern.key, pattern.count, pattern.age_days, pattern.score
+                ));
+            }
+            lines.push(String::new());
+        }
+    }
+
+    fn add_markdown_triage(&self, lines: &mut Vec<String>) {
+        if self.triage.is_empty() {
+            return;
+        }
+
+        lines.push("## Triage".to_string());
+        lines.push(String::new());
+        lines.push("| Diagnostic | Priority | Owners | Est. Fix | Related |".to_string());
+        lines.push("|---|---|---|---|---|".to_string());
+        for suggestion in &self.triage {
+            let owners = if suggestion.probable_owners.is_empty() {
+                "-".to_string()
+            } else {
+                suggestion.probable_owners.join(", ")
+            };
+            lines.push(format!(
+                "| {} ({}) | {:?} | {} | {} min | {} |",
+                suggestion.diagnostic_id,
+                suggestion.file,
+                suggestion.priority,
+                owners,
+                suggestion.estimated_fix_minutes,
+                suggestion.related_issues.len()
+            ));
+        }
+        lines.push(String::new());
+    }
+
+    pub(crate) fn sort_diagnostics(&self, diagnostics: &[Diagnostic], sort_by: &SortBy) -> Vec<Diagnostic> {
         let mut sorted = diagnostics.to_vec();
 
         match sort_by {
             SortBy::File => {
-                sorted.sort_by(|a, b| {
-                    let file_cmp = a.file.cmp(&b.file);
-                    if file_cmp == std::cmp::Ordering::Equal {
-                        a.range.start.line.cmp(&b.range.start.line)
-                    } else {
-                        file_cmp
-                    }
-                });
+                sorted.sort_by(|a, b| a.canonical_cmp(b));
             }
             SortBy::Source => {
                 sorted.sort_by(|a, b| {
-                    let source_cmp = a.source.cmp(&b.source);
-                    if source_cmp == std::cmp::Ordering::Equal {
-                        (a.severity as u8).cmp(&(b.severity as u8))
-                    } else {
-                        source_cmp
-                    }
+                    a.source
+                        .cmp(&b.source)
+                        .then((a.severity as u8).cmp(&(b.severity as u8)))
+                        .then_with(|| a.canonical_cmp(b))
                 });
             }
             SortBy::Timestamp => {
@@ -120,16 +392,18 @@ impl ExportService {
             }
             SortBy::Severity => {
                 sorted.sort_by(|a, b| {
-                    let severity_cmp = (a.severity as u8).cmp(&(b.severity as u8));
-                    if severity_cmp == std::cmp::Ordering::Equal {
-                        a.file.cmp(&b.file)
-                    } else {
-                        severity_cmp
-                    }
+                    (a.severity as u8)
+                        .cmp(&(b.severity as u8))
+                        .then_with(|| a.canonical_cmp(b))
                 });
             }
         }
 
+        // Downranked noise goes last, keeping the configured order otherwise
+        if let Some(report) = self.noise.as_ref().filter(|r| !r.downranked.is_empty()) {
+            sorted.sort_by_key(|d| report.is_downranked(d));
+        }
+
         sorted
     }
 
@@ -163,7 +437,7 @@ impl ExportService {
     ) {
         let file_groups = self.group_by_file(diagnostics);
 
-        for (file, file_diagnostics) in &file_groups {
+        for (file, file_diagnostics) in file_groups {
             lines.push(format!("## {file}"));
             lines.push(String::new());
 
@@ -177,7 +451,7 @@ impl ExportService {
         &self,
         lines: &mut Vec<String>,
         diagnostics: &[Diagnostic],
-        config: &ExportConfig,
+        context: Option<&ContextSelection>,
     ) {
         for diagnostic in diagnostics {
             let location = format!(
@@ -197,19 +471,19 @@ impl ExportService {
                 "**{}{}**: {}",
                 diagnostic.source, code, diagnostic.message
             ));
+            if let Some(crash) = diagnostic.crash_correlation() {
+                lines.push(crash_note(&crash));
+            }
+            if let Some(duplicates) = diagnostic.duplicates() {
+                lines.push(duplicates_note(&duplicates));
+            }
             lines.push(String::new());
 
-            // Add context if requested and available
-            if config.include_context {
-                lines.push("```".to_string());
-                lines.push(
-                    "// Context would be added here if file reading is available".to_string(),
-                );
-                lines.push(format!(
-                    "// Line {}: [diagnostic location]",
-                    diagnostic.range.start.line + 1
-                ));
-                lines.push("```".to_string());
+            // Point at the shared context block instead of repeating the code
+            let shared = context
+                .and_then(|c| c.block_for(&diagnostic.id).map(|index| (index, &c.blocks[index])));
+            if let Some((index, block)) = shared {
+                lines.push(format!("_Context: [C{}] {}_", index + 1, block.label()));
                 lines.push(String::new());
             }
         }
@@ -239,6 +513,12 @@ impl ExportService {
             "**{}{}**: {}",
             diagnostic.source, code, diagnostic.message
         ));
+        if let Some(crash) = diagnostic.crash_correlation() {
+            lines.push(crash_note(&crash));
+        }
+        if let Some(duplicates) = diagnostic.duplicates() {
+            lines.push(duplicates_note(&duplicates));
+        }
 
         if let Some(related_info) = &diagnostic.related_information {
             if !related_info.is_empty() {
@@ -293,18 +573,17 @@ impl ExportService {
         groups
     }
 
-    fn group_by_file<'a>(
-        &self,
-        diagnostics: &'a [Diagnostic],
-    ) -> HashMap<String, Vec<&'a Diagnostic>> {
-        let mut groups: HashMap<String, Vec<&'a Diagnostic>> =
-            HashMap::with_capacity(diagnostics.len() / 10); // Assume ~10 diagnostics per file
+    /// Diagnostics grouped by file, files in the order they first appear
+    fn group_by_file<'a>(&self, diagnostics: &'a [Diagnostic]) -> Vec<(&'a str, Vec<&'a Diagnostic>)> {
+        let mut groups: Vec<(&'a str, Vec<&'a Diagnostic>)> = Vec::new();
+        let mut index: HashMap<&'a str, usize> = HashMap::with_capacity(diagnostics.len() / 10); // Assume ~10 diagnostics per file
 
         for diagnostic in diagnostics {
-            groups
-                .entry(diagnostic.file.clone())
-                .or_default()
-                .push(diagnostic);
+            let position = *index.entry(diagnostic.file.as_str()).or_insert_with(|| {
+                groups.push((diagnostic.file.as_str(), Vec::new()));
+                groups.len() - 1
+            });
+            groups[position].1.push(diagnostic);
         }
 
         groups
@@ -334,6 +613,11 @@ impl ExportServiceTrait for ExportService {
             "diagnostics": sorted_diagnostics,
             "metadata": snapshot.metadata
         });
+        if config.stable {
+            if let Some(data) = export_data.as_object_mut() {
+                data.remove("timestamp");
+            }
+        }
 
         if config.include_summary {
             export_data["summary"] = serde_json::to_value(
@@ -346,6 +630,26 @@ impl ExportServiceTrait for ExportService {
             })?;
         }
 
+        if let Some(ref report) = self.noise {
+            export_data["noise"] = serde_json::to_value(report).map_err(|e| {
+                ExportError::DataTransformation {
+                    from_format: "NoiseReport".to_string(),
+                    to_format: "JSON".to_string(),
+                    reason: e.to_string(),
+                }
+            })?;
+        }
+
+        if !self.triage.is_empty() {
+            export_data["triage"] = serde_json::to_value(&self.triage).map_err(|e| {
+                ExportError::DataTransformation {
+                    from_format: "TriageSuggestion".to_string(),
+                    to_format: "JSON".to_string(),
+                    reason: e.to_string(),
+                }
+            })?;
+        }
+
         // Add project info if available
         if let Some(ref info) = self.project_info {
             export_data["project"] = serde_json::json!({
@@ -383,11 +687,13 @@ impl ExportServiceTrait for ExportService {
             snapshot.workspace.name
         ));
         lines.push(String::new());
-        lines.push(format!(
-            "Generated: {}",
-            snapshot.timestamp.format("%Y-%m-%d %H:%M:%S UTC")
-        ));
-        lines.push(String::new());
+        if !config.stable {
+            lines.push(format!(
+                "Generated: {}",
+                snapshot.timestamp.format("%Y-%m-%d %H:%M:%S UTC")
+            ));
+            lines.push(String::new());
+        }
 
         // Project Info section (if available)
         if let Some(ref info) = self.project_info {
@@ -429,6 +735,9 @@ impl ExportServiceTrait for ExportService {
             lines.push(String::new());
         }
 
+        self.add_markdown_triage(&mut lines);
+        self.add_markdown_noise(&mut lines);
+
         // Group by severity or file
         if config.group_by_file {
             self.export_markdown_by_file(&mut lines, &sorted_diagnostics, config);
@@ -445,8 +754,20 @@ impl ExportServiceTrait for ExportService {
         config: &ExportConfig,
     ) -> Result<String, ExportError> {
         let mut lines = Vec::new();
-        let summary = self.generate_summary(&snapshot.diagnostics);
-        let sorted_diagnostics = self.sort_diagnostics(&snapshot.diagnostics, &config.sort_by);
+        let exclusions = self.license_exclusions(&snapshot.diagnostics);
+        let is_licensed = |diagnostic: &Diagnostic, action: LicenseAction| {
+            exclusions
+                .iter()
+                .any(|e| e.action == action && e.file == diagnostic.file)
+        };
+        let included: Vec<Diagnostic> = snapshot
+            .diagnostics
+            .iter()
+            .filter(|d| !is_licensed(d, LicenseAction::Exclude))
+            .cloned()
+            .collect();
+        let summary = self.generate_summary(&included);
+        let sorted_diagnostics = self.sort_diagnostics(&included, &config.sort_by);
 
         // Header optimized for Claude
         lines.push(format!(
@@ -454,11 +775,13 @@ impl ExportServiceTrait for ExportService {
             snapshot.workspace.name
         ));
         lines.push(String::new());
-        lines.push(format!(
-            "Generated: {}",
-            snapshot.timestamp.format("%Y-%m-%d %H:%M:%S UTC")
-        ));
-        lines.push(String::new());
+        if !config.stable {
+            lines.push(format!(
+                "Generated: {}",
+                snapshot.timestamp.format("%Y-%m-%d %H:%M:%S UTC")
+            ));
+            lines.push(String::new());
+        }
 
         // Project context for AI (crucial for better suggestions)
         if let Some(ref info) = self.project_info {
@@ -488,8 +811,11 @@ impl ExportServiceTrait for ExportService {
         lines.push(format!("- **Info**: {}", summary.info_count));
         lines.push(String::new());
 
+        self.add_markdown_triage(&mut lines);
+        self.add_markdown_noise(&mut lines);
+
         // Only show errors and warnings for Claude (reduce noise)
-        let important_diagnostics: Vec<&Diagnostic> = sorted_diagnostics
+        let mut important_diagnostics: Vec<&Diagnostic> = sorted_diagnostics
             .iter()
             .filter(|d| {
                 matches!(
@@ -499,7 +825,63 @@ impl ExportServiceTrait for ExportService {
             })
             .collect();
 
-        if summary.error_count > 0 {
+        let mut trimmed = 0;
+        let mut remaining_tokens = None;
+        let mut oversized = Vec::new();
+        if let Some(estimator) = &self.token_estimator {
+            let costs: Vec<usize> = important_diagnostics
+                .iter()
+                .map(|d| {
+                    let mut section = Vec::new();
+                    self.export_claude_optimized_section(&mut section, &[(*d).clone()], None);
+                    estimator.estimate(&section.join("\n"))
+                })
+                .collect();
+
+            for (diagnostic, cost) in important_diagnostics.iter().zip(&costs) {
+                if estimator.exceeds_practical_window(*cost) {
+                    tracing::warn!(
+                        "Diagnostic {} needs ~{} tokens, beyond the practical window of {}",
+                        diagnostic.id,
+                        cost,
+                        estimator.profile().family
+                    );
+                    oversized.push(format!(
+                        "{}:{} (~{} tokens)",
+                        diagnostic.file,
+                        diagnostic.range.start.line + 1,
+                        cost
+                    ));
+                }
+            }
+
+            if let Some(max_tokens) = self.max_tokens {
+                let budget = max_tokens.saturating_sub(estimator.estimate(&lines.join("\n")));
+                let keep = estimator.select_within_budget(&important_diagnostics, &costs, budget);
+                trimmed = keep.iter().filter(|k| !**k).count();
+                let spent: usize = costs.iter().zip(&keep).filter(|(_, k)| **k).map(|(c, _)| c).sum();
+                remaining_tokens = Some(budget.saturating_sub(spent));
+                let mut keep = keep.into_iter();
+                important_diagnostics.retain(|_| keep.next().unwrap_or(false));
+            }
+        }
+
+        let error_count = important_diagnostics
+            .iter()
+            .filter(|d| d.severity == DiagnosticSeverity::Error)
+            .count();
+        let warning_count = important_diagnostics.len() - error_count;
+
+        let context = config.include_context.then(|| {
+            let with_code: Vec<&Diagnostic> = important_diagnostics
+                .iter()
+                .copied()
+                .filter(|d| !is_licensed(d, LicenseAction::Redact))
+                .collect();
+            self.select_context(&with_code, config, remaining_tokens)
+        });
+
+        if error_count > 0 {
             lines.push("## Errors".to_string());
             lines.push(String::new());
             let errors: Vec<Diagnostic> = important_diagnostics
@@ -507,10 +889,10 @@ impl ExportServiceTrait for ExportService {
                 .filter(|d| d.severity == DiagnosticSeverity::Error)
                 .map(|d| (*d).clone())
                 .collect();
-            self.export_claude_optimized_section(&mut lines, &errors, config);
+            self.export_claude_optimized_section(&mut lines, &errors, context.as_ref());
         }
 
-        if summary.warning_count > 0 {
+        if warning_count > 0 {
             lines.push("## Warnings".to_string());
             lines.push(String::new());
             let warnings: Vec<Diagnostic> = important_diagnostics
@@ -518,8 +900,13 @@ impl ExportServiceTrait for ExportService {
                 .filter(|d| d.severity == DiagnosticSeverity::Warning)
                 .map(|d| (*d).clone())
                 .collect();
-            self.export_claude_optimized_section(&mut lines, &warnings, config);
+            self.export_claude_optimized_section(&mut lines, &warnings, context.as_ref());
+        }
+
+        if let Some(context) = &context {
+            self.add_context_blocks(&mut lines, context);
         }
+        self.add_license_exclusions(&mut lines, &exclusions, &snapshot.diagnostics);
 
         // Add helpful context for Claude
         if !important_diagnostics.is_empty() {
@@ -527,7 +914,9 @@ impl ExportServiceTrait for ExportService {
             lines.push(String::new());
             lines.push("This diagnostic report contains:".to_string());
 
-            for (source, count) in &summary.source_breakdown {
+            let mut sources: Vec<_> = summary.source_breakdown.iter().collect();
+            sources.sort();
+            for (source, count) in sources {
                 lines.push(format!("- {count} diagnostic(s) from {source}"));
             }
             lines.push(String::new());
@@ -536,6 +925,10 @@ impl ExportServiceTrait for ExportService {
             );
         }
 
+        if let Some(estimator) = &self.token_estimator {
+            self.add_token_estimate(&mut lines, estimator, trimmed, &oversized);
+        }
+
         Ok(lines.join("\n"))
     }
 
@@ -578,3 +971,24 @@ impl Default for ExportService {
         Self::new()
     }
 }
+
+/// Where a deduplicated diagnostic also occurred
+fn duplicates_note(duplicates: &DuplicateInfo) -> String {
+    let mut note = format!("_Seen {} times in {} file(s)", duplicates.occurrences, duplicates.files);
+    if duplicates.origins.len() > 1 {
+        note.push_str(&format!(" across {}", duplicates.origins.join(", ")));
+    }
+    note.push('_');
+    note
+}
+
+/// Callout for a diagnostic whose location shows up in crash reports
+fn crash_note(crash: &CrashCorrelation) -> String {
+    let place = if crash.at_crash_site { "crashes here" } else { "is on the stack of a crash" };
+    let times = if crash.occurrences == 1 {
+        "once".to_string()
+    } else {
+        format!("{} times", crash.occurrences)
+    };
+    format!("> **Crashes in production:** this line {place} ({}, seen {times}): {}", crash.kind, crash.message)
+}
@@ -0,0 +1,151 @@
+//! GitHub Actions annotation export
+//!
+//! [`GithubActionsExporter`] writes one workflow command per diagnostic,
+//! such as `::error file=src/lib.rs,line=3,col=5,title=rustc E0308::mismatched types`.
+//! Printed from a workflow step, GitHub turns each command into an
+//! annotation shown inline on the pull request diff, so CI jobs need no
+//! extra tooling to surface diagnostics.
+//!
+//! Errors become `::error`, warnings `::warning` and everything else
+//! `::notice`. Paths inside the workspace are written relative to its root,
+//! which is how GitHub matches annotations to files in the checkout.
+
+use super::multi_format::DiagnosticWriter;
+use crate::core::errors::ExportError;
+use crate::core::{Diagnostic, DiagnosticSeverity, DiagnosticSnapshot, ExportFormat};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Streams diagnostics as GitHub Actions workflow commands
+#[derive(Debug, Default)]
+pub struct GithubActionsExporter {
+    root: Option<PathBuf>,
+    output: String,
+}
+
+impl GithubActionsExporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write paths relative to this directory instead of the snapshot's workspace root
+    pub fn with_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = Some(root.into());
+        self
+    }
+
+    /// Path as GitHub expects it: relative to the checkout when inside it
+    fn path<'a>(&self, file: &'a str) -> std::borrow::Cow<'a, str> {
+        let file = file.strip_prefix("file://").unwrap_or(file);
+        match self.root.as_ref().and_then(|root| Path::new(file).strip_prefix(root).ok()) {
+            Some(relative) => relative.to_string_lossy().into_owned().into(),
+            None => file.into(),
+        }
+    }
+}
+
+impl DiagnosticWriter for GithubActionsExporter {
+    fn format(&self) -> ExportFormat {
+        ExportFormat::GithubActions
+    }
+
+    fn begin(&mut self, snapshot: &DiagnosticSnapshot) -> Result<(), ExportError> {
+        if self.root.is_none() && !snapshot.workspace.root_path.is_empty() {
+            self.root = Some(PathBuf::from(&snapshot.workspace.root_path));
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, diagnostic: &Diagnostic) -> Result<(), ExportError> {
+        let command = match diagnostic.severity {
+            DiagnosticSeverity::Error => "error",
+            DiagnosticSeverity::Warning => "warning",
+            DiagnosticSeverity::Information | DiagnosticSeverity::Hint => "notice",
+        };
+        let (start, end) = (&diagnostic.range.start, &diagnostic.range.end);
+
+        let mut properties = format!("file={},line={}", escape_property(&self.path(&diagnostic.file)), start.line + 1);
+        if end.line > start.line {
+            let _ = write!(properties, ",endLine={}", end.line + 1);
+        } else {
+            // Columns are only shown for single-line annotations
+            let _ = write!(properties, ",col={}", start.character + 1);
+            if end.character > start.character {
+                let _ = write!(properties, ",endColumn={}", end.character + 1);
+            }
+        }
+        let title = match &diagnostic.code {
+            Some(code) => format!("{} {code}", diagnostic.source),
+            None => diagnostic.source.clone(),
+        };
+        if !title.trim().is_empty() {
+            let _ = write!(properties, ",title={}", escape_property(&title));
+        }
+
+        let _ = writeln!(self.output, "::{command} {properties}::{}", escape_data(&diagnostic.message));
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<String, ExportError> {
+        Ok(std::mem::take(&mut self.output))
+    }
+}
+
+/// Escape a command's message so line breaks and `%` survive
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Escape a property value, which additionally cannot contain `:` or `,`
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Position, Range};
+
+    fn diagnostic(file: &str, severity: DiagnosticSeverity, range: (u32, u32, u32, u32), message: &str) -> Diagnostic {
+        let mut diagnostic = Diagnostic::new(
+            file.to_string(),
+            Range {
+                start: Position { line: range.0, character: range.1 },
+                end: Position { line: range.2, character: range.3 },
+            },
+            severity,
+            message.to_string(),
+            "rustc".to_string(),
+        );
+        diagnostic.code = Some("E0308".to_string());
+        diagnostic
+    }
+
+    #[test]
+    fn test_workflow_commands() {
+        let mut exporter = GithubActionsExporter::new().with_root("/work/demo");
+        exporter
+            .write(&diagnostic(
+                "file:///work/demo/src/lib.rs",
+                DiagnosticSeverity::Error,
+                (2, 4, 2, 9),
+                "mismatched types: expected `u32`, found `&str`",
+            ))
+            .unwrap();
+        let mut multiline = diagnostic("/elsewhere/main.rs", DiagnosticSeverity::Hint, (0, 0, 3, 1), "100% unused\nremove it");
+        multiline.code = None;
+        exporter.write(&multiline).unwrap();
+
+        let output = exporter.finish().unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(
+            lines[0],
+            "::error file=src/lib.rs,line=3,col=5,endColumn=10,title=rustc E0308::mismatched types: expected `u32`, found `&str`"
+        );
+        assert_eq!(
+            lines[1],
+            "::notice file=/elsewhere/main.rs,line=1,endLine=4,title=rustc::100%25 unused%0Aremove it"
+        );
+        assert_eq!(escape_property("a:b,c"), "a%3Ab%2Cc");
+    }
+}
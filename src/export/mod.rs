@@ -1,3 +1,15 @@
+pub mod archive;
 pub mod export_service;
+pub mod github_actions;
+pub mod multi_format;
+pub mod parquet;
+pub mod routing;
+pub mod sarif;
 
+pub use archive::{BundleEntry, BundleManifest, Compression, ExportBundle};
 pub use export_service::ExportService;
+pub use github_actions::GithubActionsExporter;
+pub use multi_format::{DiagnosticWriter, ExportOutput};
+pub use parquet::{ParquetWriter, DEFAULT_ROW_GROUP_SIZE};
+pub use routing::{RoutedExport, RoutedExportSet};
+pub use sarif::{SarifExporter, SarifLog};
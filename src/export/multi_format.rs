@@ -0,0 +1,460 @@
+//! Multi-format export in a single pass
+//!
+//! CI pipelines often need several reports from the same capture (SARIF for
+//! code scanning, HTML for humans, a Claude export for an assistant).
+//! [`ExportService::export_multi`] sorts the snapshot once and streams each
+//! diagnostic to a set of [`DiagnosticWriter`]s, one per requested format.
+//!
+//! [`ExportConfig::region`] narrows every format to the diagnostics
+//! overlapping a range of lines, such as an editor selection.
+//!
+//! With [`ExportConfig::stable`] every format gets the same guarantees, so
+//! exports can be committed and diffed: diagnostics in canonical order (path,
+//! range, code), ids derived from content, and no capture timestamps.
+
+use super::{ExportService, GithubActionsExporter, SarifExporter};
+use crate::core::errors::ExportError;
+use crate::core::{
+    Diagnostic, DiagnosticSeverity, DiagnosticSnapshot, ExportConfig, ExportFormat,
+    ExportService as ExportServiceTrait, SortBy,
+};
+use std::fmt::Write as _;
+
+/// A sink that receives the diagnostics of one export pass
+pub trait DiagnosticWriter {
+    /// Format produced by this writer
+    fn format(&self) -> ExportFormat;
+
+    /// Called once before any diagnostic is written
+    fn begin(&mut self, _snapshot: &DiagnosticSnapshot) -> Result<(), ExportError> {
+        Ok(())
+    }
+
+    /// Called for every diagnostic, in export order
+    fn write(&mut self, diagnostic: &Diagnostic) -> Result<(), ExportError>;
+
+    /// Produce the final document
+    fn finish(&mut self) -> Result<String, ExportError>;
+}
+
+/// The rendered document for one format
+#[derive(Debug, Clone)]
+pub struct ExportOutput {
+    pub format: ExportFormat,
+    pub content: String,
+}
+
+impl ExportOutput {
+    /// Default file name for this output, e.g. `diagnostics.sarif`
+    pub fn file_name(&self) -> String {
+        format!("diagnostics.{}", self.format.file_extension())
+    }
+}
+
+impl ExportFormat {
+    /// File extension conventionally used for this format
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Markdown => "md",
+            ExportFormat::ClaudeOptimized => "claude.md",
+            ExportFormat::Sarif => "sarif",
+            ExportFormat::Html => "html",
+            ExportFormat::GithubActions => "github-actions.txt",
+        }
+    }
+}
+
+impl ExportService {
+    /// Export a snapshot in a single format
+    pub fn export(
+        &self,
+        snapshot: &DiagnosticSnapshot,
+        config: &ExportConfig,
+    ) -> Result<String, ExportError> {
+        let mut outputs = self.export_multi(snapshot, config, std::slice::from_ref(&config.format))?;
+        Ok(outputs.remove(0).content)
+    }
+
+    /// Export a snapshot in several formats, iterating the diagnostics once
+    pub fn export_multi(
+        &self,
+        snapshot: &DiagnosticSnapshot,
+        config: &ExportConfig,
+        formats: &[ExportFormat],
+    ) -> Result<Vec<ExportOutput>, ExportError> {
+        let scoped;
+        let (snapshot, config) = match &config.region {
+            Some(region) => {
+                scoped = (
+                    DiagnosticSnapshot {
+                        diagnostics: snapshot
+                            .diagnostics
+                            .iter()
+                            .filter(|diagnostic| region.intersects(diagnostic))
+                            .cloned()
+                            .collect(),
+                        ..snapshot.clone()
+                    },
+                    ExportConfig { include_context: true, ..config.clone() },
+                );
+                (&scoped.0, &scoped.1)
+            }
+            None => (snapshot, config),
+        };
+
+        let stable;
+        let (snapshot, config) = if config.stable {
+            stable = (stabilize(snapshot), ExportConfig { sort_by: SortBy::File, ..config.clone() });
+            (&stable.0, &stable.1)
+        } else {
+            (snapshot, config)
+        };
+
+        let mut writers: Vec<Box<dyn DiagnosticWriter + '_>> = formats
+            .iter()
+            .map(|format| self.writer_for(format.clone(), config))
+            .collect();
+
+        for writer in writers.iter_mut() {
+            writer.begin(snapshot)?;
+        }
+        for diagnostic in self.sort_diagnostics(&snapshot.diagnostics, &config.sort_by) {
+            for writer in writers.iter_mut() {
+                writer.write(&diagnostic)?;
+            }
+        }
+
+        writers
+            .iter_mut()
+            .map(|writer| {
+                Ok(ExportOutput {
+                    format: writer.format(),
+                    content: writer.finish()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Create the writer for a format
+    pub fn writer_for<'a>(
+        &'a self,
+        format: ExportFormat,
+        config: &ExportConfig,
+    ) -> Box<dyn DiagnosticWriter + 'a> {
+        match format {
+            ExportFormat::Sarif => Box::new(SarifExporter::new()),
+            ExportFormat::Html => Box::new(HtmlWriter::new(config.stable)),
+            ExportFormat::GithubActions => Box::new(GithubActionsExporter::new()),
+            format => Box::new(ServiceWriter {
+                service: self,
+                format,
+                config: config.clone(),
+                snapshot: None,
+            }),
+        }
+    }
+}
+
+/// Copy of a snapshot with content-derived diagnostic ids
+fn stabilize(snapshot: &DiagnosticSnapshot) -> DiagnosticSnapshot {
+    let mut stable = snapshot.clone();
+    for diagnostic in &mut stable.diagnostics {
+        diagnostic.id = diagnostic.content_id();
+    }
+    stable
+}
+
+/// Adapts the document-oriented exports (JSON, Markdown, Claude) to the writer interface
+///
+/// These formats need the whole snapshot for summaries, triage and token
+/// budgeting, so diagnostics are collected and rendered in `finish`.
+struct ServiceWriter<'a> {
+    service: &'a ExportService,
+    format: ExportFormat,
+    config: ExportConfig,
+    snapshot: Option<DiagnosticSnapshot>,
+}
+
+impl DiagnosticWriter for ServiceWriter<'_> {
+    fn format(&self) -> ExportFormat {
+        self.format.clone()
+    }
+
+    fn begin(&mut self, snapshot: &DiagnosticSnapshot) -> Result<(), ExportError> {
+        self.snapshot = Some(DiagnosticSnapshot {
+            id: snapshot.id,
+            timestamp: snapshot.timestamp,
+            workspace: snapshot.workspace.clone(),
+            diagnostics: Vec::with_capacity(snapshot.diagnostics.len()),
+            metadata: snapshot.metadata.clone(),
+            code_lenses: snapshot.code_lenses.clone(),
+        });
+        Ok(())
+    }
+
+    fn write(&mut self, diagnostic: &Diagnostic) -> Result<(), ExportError> {
+        if let Some(snapshot) = &mut self.snapshot {
+            snapshot.diagnostics.push(diagnostic.clone());
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<String, ExportError> {
+        let snapshot = self.snapshot.take().ok_or_else(|| ExportError::InsufficientData {
+            reason: "export finished before it began".to_string(),
+        })?;
+        match self.format {
+            ExportFormat::Json => self.service.export_to_json(&snapshot, &self.config),
+            ExportFormat::Markdown => self.service.export_to_markdown(&snapshot, &self.config),
+            ExportFormat::ClaudeOptimized => {
+                self.service.export_to_claude_optimized(&snapshot, &self.config)
+            }
+            ExportFormat::Sarif | ExportFormat::Html | ExportFormat::GithubActions => Err(ExportError::UnsupportedFormat {
+                format: format!("{:?}", self.format),
+            }),
+        }
+    }
+}
+
+/// Streams diagnostics into a standalone HTML report
+struct HtmlWriter {
+    title: String,
+    /// Capture time line; left out of stable exports
+    generated: Option<String>,
+    stable: bool,
+    rows: String,
+    counts: [usize; 4],
+}
+
+impl HtmlWriter {
+    fn new(stable: bool) -> Self {
+        Self {
+            title: String::new(),
+            generated: None,
+            stable,
+            rows: String::new(),
+            counts: [0; 4],
+        }
+    }
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+const HTML_STYLE: &str = "body{font-family:system-ui,sans-serif;margin:2rem;color:#222}\
+table{border-collapse:collapse;width:100%}\
+th,td{border-bottom:1px solid #ddd;padding:.4rem .6rem;text-align:left;vertical-align:top}\
+.error{color:#b00020}.warning{color:#a15c00}.information{color:#0b5cad}.hint{color:#555}\
+.summary span{margin-right:1.5rem}code{font-size:.9em}";
+
+impl DiagnosticWriter for HtmlWriter {
+    fn format(&self) -> ExportFormat {
+        ExportFormat::Html
+    }
+
+    fn begin(&mut self, snapshot: &DiagnosticSnapshot) -> Result<(), ExportError> {
+        self.title = format!("Diagnostics Report - {}", snapshot.workspace.name);
+        if !self.stable {
+            let timestamp = snapshot.timestamp.format("%Y-%m-%d %H:%M:%S UTC");
+            self.generated = Some(format!("<p>Generated: {timestamp}</p>\n"));
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, diagnostic: &Diagnostic) -> Result<(), ExportError> {
+        let (index, class) = match diagnostic.severity {
+            DiagnosticSeverity::Error => (0, "error"),
+            DiagnosticSeverity::Warning => (1, "warning"),
+            DiagnosticSeverity::Information => (2, "information"),
+            DiagnosticSeverity::Hint => (3, "hint"),
+        };
+        self.counts[index] += 1;
+
+        let _ = writeln!(
+            self.rows,
+            "<tr><td class=\"{class}\">{class}</td><td><code>{}:{}:{}</code></td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape_html(&diagnostic.file),
+            diagnostic.range.start.line + 1,
+            diagnostic.range.start.character + 1,
+            escape_html(&diagnostic.source),
+            escape_html(diagnostic.code.as_deref().unwrap_or("")),
+            escape_html(&diagnostic.message),
+        );
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<String, ExportError> {
+        let [errors, warnings, info, hints] = self.counts;
+        let title = escape_html(&self.title);
+        Ok(format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>{title}</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n\
+             <h1>{title}</h1>\n{}\
+             <p class=\"summary\"><span class=\"error\">Errors: {errors}</span>\
+             <span class=\"warning\">Warnings: {warnings}</span>\
+             <span class=\"information\">Info: {info}</span>\
+             <span class=\"hint\">Hints: {hints}</span></p>\n\
+             <table>\n<thead><tr><th>Severity</th><th>Location</th><th>Source</th><th>Code</th><th>Message</th></tr></thead>\n\
+             <tbody>\n{}</tbody>\n</table>\n</body>\n</html>\n",
+            self.generated.take().unwrap_or_default(),
+            std::mem::take(&mut self.rows),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{DiagnosticRegion, Position, Range, WorkspaceInfo};
+
+    fn snapshot() -> DiagnosticSnapshot {
+        let mut error = Diagnostic::new(
+            "src/main.rs".to_string(),
+            Range {
+                start: Position { line: 4, character: 2 },
+                end: Position { line: 4, character: 9 },
+            },
+            DiagnosticSeverity::Error,
+            "expected `u32`, found `<&str>`".to_string(),
+            "rustc".to_string(),
+        );
+        error.code = Some("E0308".to_string());
+        let warning = Diagnostic::new(
+            "src/lib.rs".to_string(),
+            Range {
+                start: Position { line: 0, character: 0 },
+                end: Position { line: 0, character: 3 },
+            },
+            DiagnosticSeverity::Warning,
+            "unused import".to_string(),
+            "rustc".to_string(),
+        );
+
+        DiagnosticSnapshot::new(
+            WorkspaceInfo {
+                name: "demo".to_string(),
+                root_path: "/work/demo".to_string(),
+                language: Some("rust".to_string()),
+                version: None,
+                roots: vec![],
+            },
+            vec![warning, error],
+        )
+    }
+
+    #[test]
+    fn test_multi_format_export_produces_every_format() {
+        let service = ExportService::new();
+        let formats = [ExportFormat::Sarif, ExportFormat::Html, ExportFormat::ClaudeOptimized];
+        let outputs = service
+            .export_multi(&snapshot(), &ExportConfig::default(), &formats)
+            .unwrap();
+
+        assert_eq!(outputs.len(), 3);
+        assert_eq!(outputs[0].file_name(), "diagnostics.sarif");
+        assert_eq!(outputs[2].file_name(), "diagnostics.claude.md");
+        assert!(outputs[2].content.contains("# Diagnostics Report - demo"));
+
+        let html = &outputs[1].content;
+        assert!(html.contains("Errors: 1"));
+        assert!(html.contains("&lt;&amp;str&gt;"));
+    }
+
+    #[test]
+    fn test_sarif_results_and_rules() {
+        let service = ExportService::new();
+        let sarif = service
+            .export_multi(&snapshot(), &ExportConfig::default(), &[ExportFormat::Sarif])
+            .unwrap()
+            .remove(0);
+        let log: serde_json::Value = serde_json::from_str(&sarif.content).unwrap();
+
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 2);
+
+        // Sorted by severity, so the error comes first
+        let result = &run["results"][0];
+        assert_eq!(result["ruleId"], "rustc/E0308");
+        assert_eq!(result["level"], "error");
+        let region = &result["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(region["startLine"], 5);
+        assert_eq!(region["startColumn"], 3);
+    }
+
+    #[test]
+    fn test_region_export_keeps_overlapping_diagnostics() {
+        let service = ExportService::new();
+        let export = |region: DiagnosticRegion| {
+            let config = ExportConfig {
+                region: Some(region),
+                ..ExportConfig::default()
+            };
+            let json = service
+                .export_multi(&snapshot(), &config, &[ExportFormat::Json])
+                .unwrap()
+                .remove(0)
+                .content;
+            let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+            value["diagnostics"].as_array().unwrap().len()
+        };
+
+        // The error is on line 5 of src/main.rs
+        assert_eq!(export(DiagnosticRegion::lines("src/main.rs", "3-5").unwrap()), 1);
+        assert_eq!(export(DiagnosticRegion::lines("/work/demo/src/main.rs", "5").unwrap()), 1);
+        assert_eq!(export(DiagnosticRegion::lines("other/src/main.rs", "5").unwrap()), 0);
+        assert_eq!(export(DiagnosticRegion::lines("src/main.rs", "6-9").unwrap()), 0);
+        assert_eq!(export(DiagnosticRegion::file("./src/lib.rs")), 1);
+        assert!(DiagnosticRegion::lines("src/main.rs", "9-3").is_err());
+        assert!(DiagnosticRegion::lines("src/main.rs", "0").is_err());
+    }
+
+    #[test]
+    fn test_stable_exports_are_identical_across_captures() {
+        let service = ExportService::new();
+        let config = ExportConfig {
+            stable: true,
+            group_by_file: true,
+            ..ExportConfig::default()
+        };
+        let formats = [
+            ExportFormat::Json,
+            ExportFormat::Markdown,
+            ExportFormat::ClaudeOptimized,
+            ExportFormat::Sarif,
+            ExportFormat::Html,
+        ];
+
+        let first = snapshot();
+        // A later capture: new ids, new timestamp, diagnostics in another order
+        let mut second = snapshot();
+        second.diagnostics.reverse();
+        second.timestamp += chrono::Duration::minutes(5);
+
+        let a = service.export_multi(&first, &config, &formats).unwrap();
+        let b = service.export_multi(&second, &config, &formats).unwrap();
+        for (a, b) in a.iter().zip(&b) {
+            assert_eq!(a.content, b.content, "{:?} output differs", a.format);
+            assert!(!a.content.contains("Generated:"));
+        }
+
+        // Canonical order is by path, so src/lib.rs comes before src/main.rs
+        let json: serde_json::Value = serde_json::from_str(&a[0].content).unwrap();
+        assert_eq!(json["diagnostics"][0]["file"], "src/lib.rs");
+        assert!(json.get("timestamp").is_none());
+        assert_eq!(json["diagnostics"][0]["id"], first.diagnostics[0].content_id());
+    }
+}
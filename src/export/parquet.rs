@@ -0,0 +1,300 @@
+//! Streaming Parquet export
+//!
+//! [`ParquetWriter`] writes diagnostics to a Parquet file one row group at a
+//! time. Rows are buffered in Arrow column builders until the row group is
+//! full, then handed to the `parquet` crate's [`ArrowWriter`] as one record
+//! batch, which writes the row group out, so memory stays bounded by the
+//! row group size no matter how many diagnostics are exported.
+//!
+//! The file has a flat schema ([`diagnostic_schema`]), optionally
+//! compressed with gzip or zstd, and reads directly in DuckDB, Polars,
+//! pandas and Spark.
+
+use anyhow::{Context, Result};
+use arrow_array::builder::{Int32Builder, StringBuilder, TimestampMillisecondBuilder};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{DateTime, Utc};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::{Compression as ParquetCompression, GzipLevel, ZstdLevel};
+use parquet::file::properties::WriterProperties;
+use std::io::Write;
+use std::sync::Arc;
+
+use super::Compression;
+use crate::core::{Diagnostic, DiagnosticSeverity};
+
+/// Rows per row group unless configured otherwise
+pub const DEFAULT_ROW_GROUP_SIZE: usize = 65_536;
+
+/// Arrow schema of a diagnostics Parquet file
+///
+/// `captured_at` is set for diagnostics exported from history and `code`
+/// when the diagnostic has one.
+pub fn diagnostic_schema() -> SchemaRef {
+    let utf8 = |name: &str| Field::new(name, DataType::Utf8, false);
+    let int32 = |name: &str| Field::new(name, DataType::Int32, false);
+    Arc::new(Schema::new(vec![
+        Field::new("captured_at", DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())), true),
+        utf8("file"),
+        int32("start_line"),
+        int32("start_character"),
+        int32("end_line"),
+        int32("end_character"),
+        utf8("severity"),
+        utf8("source"),
+        Field::new("code", DataType::Utf8, true),
+        utf8("message"),
+        utf8("id"),
+    ]))
+}
+
+/// Builders of the row group being filled, one per column of [`diagnostic_schema`]
+struct RowGroupBuilder {
+    captured_at: TimestampMillisecondBuilder,
+    file: StringBuilder,
+    start_line: Int32Builder,
+    start_character: Int32Builder,
+    end_line: Int32Builder,
+    end_character: Int32Builder,
+    severity: StringBuilder,
+    source: StringBuilder,
+    code: StringBuilder,
+    message: StringBuilder,
+    id: StringBuilder,
+}
+
+impl RowGroupBuilder {
+    fn new() -> Self {
+        Self {
+            captured_at: TimestampMillisecondBuilder::new().with_timezone("UTC"),
+            file: StringBuilder::new(),
+            start_line: Int32Builder::new(),
+            start_character: Int32Builder::new(),
+            end_line: Int32Builder::new(),
+            end_character: Int32Builder::new(),
+            severity: StringBuilder::new(),
+            source: StringBuilder::new(),
+            code: StringBuilder::new(),
+            message: StringBuilder::new(),
+            id: StringBuilder::new(),
+        }
+    }
+
+    fn append(&mut self, diagnostic: &Diagnostic, captured_at: Option<DateTime<Utc>>) {
+        let range = &diagnostic.range;
+        self.captured_at.append_option(captured_at.map(|time| time.timestamp_millis()));
+        self.file.append_value(&diagnostic.file);
+        self.start_line.append_value(range.start.line as i32);
+        self.start_character.append_value(range.start.character as i32);
+        self.end_line.append_value(range.end.line as i32);
+        self.end_character.append_value(range.end.character as i32);
+        self.severity.append_value(severity_name(diagnostic.severity));
+        self.source.append_value(&diagnostic.source);
+        self.code.append_option(diagnostic.code.as_deref());
+        self.message.append_value(&diagnostic.message);
+        self.id.append_value(&diagnostic.id);
+    }
+
+    /// The buffered rows as a record batch, leaving the builders empty
+    fn finish(&mut self, schema: SchemaRef) -> Result<RecordBatch> {
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(self.captured_at.finish()),
+            Arc::new(self.file.finish()),
+            Arc::new(self.start_line.finish()),
+            Arc::new(self.start_character.finish()),
+            Arc::new(self.end_line.finish()),
+            Arc::new(self.end_character.finish()),
+            Arc::new(self.severity.finish()),
+            Arc::new(self.source.finish()),
+            Arc::new(self.code.finish()),
+            Arc::new(self.message.finish()),
+            Arc::new(self.id.finish()),
+        ];
+        RecordBatch::try_new(schema, columns).context("Failed to build Parquet row group")
+    }
+}
+
+/// Writes diagnostics to a Parquet file, one row group at a time
+pub struct ParquetWriter<W: Write + Send> {
+    /// Created on the first write, once the row group size and compression are final
+    writer: Option<ArrowWriter<W>>,
+    output: Option<W>,
+    schema: SchemaRef,
+    row_group_size: usize,
+    compression: Option<Compression>,
+    rows: RowGroupBuilder,
+    buffered: usize,
+    written: u64,
+}
+
+impl<W: Write + Send> ParquetWriter<W> {
+    pub fn new(output: W) -> Result<Self> {
+        Ok(Self {
+            writer: None,
+            output: Some(output),
+            schema: diagnostic_schema(),
+            row_group_size: DEFAULT_ROW_GROUP_SIZE,
+            compression: None,
+            rows: RowGroupBuilder::new(),
+            buffered: 0,
+            written: 0,
+        })
+    }
+
+    /// Rows buffered before a row group is written; larger groups compress better but use more memory
+    pub fn with_row_group_size(mut self, rows: usize) -> Self {
+        self.row_group_size = rows.max(1);
+        self
+    }
+
+    /// Compress every data page
+    pub fn with_compression(mut self, compression: Option<Compression>) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Rows written so far, including those still buffered
+    pub fn rows_written(&self) -> u64 {
+        self.written + self.buffered as u64
+    }
+
+    /// Append one diagnostic, captured at `captured_at` when it comes from history
+    pub fn write(&mut self, diagnostic: &Diagnostic, captured_at: Option<DateTime<Utc>>) -> Result<()> {
+        self.rows.append(diagnostic, captured_at);
+        self.buffered += 1;
+        if self.buffered >= self.row_group_size {
+            self.flush_row_group()?;
+        }
+        Ok(())
+    }
+
+    /// Write the buffered rows and the footer, returning the underlying writer
+    pub fn finish(mut self) -> Result<W> {
+        if self.buffered > 0 || self.writer.is_none() {
+            self.flush_row_group()?;
+        }
+        let writer = self.writer.take().expect("the writer is created with the first row group");
+        let mut output = writer.into_inner().context("Failed to finish Parquet file")?;
+        output.flush()?;
+        Ok(output)
+    }
+
+    fn flush_row_group(&mut self) -> Result<()> {
+        let batch = self.rows.finish(self.schema.clone())?;
+        if let Some(output) = self.output.take() {
+            let writer = ArrowWriter::try_new(output, self.schema.clone(), Some(self.properties()))
+                .context("Failed to start Parquet file")?;
+            self.writer = Some(writer);
+        }
+        let writer = self.writer.as_mut().expect("the writer is created with the first row group");
+        if batch.num_rows() > 0 {
+            writer.write(&batch).context("Failed to write Parquet row group")?;
+            writer.flush().context("Failed to write Parquet row group")?;
+        }
+        self.written += std::mem::take(&mut self.buffered) as u64;
+        Ok(())
+    }
+
+    fn properties(&self) -> WriterProperties {
+        let compression = match self.compression {
+            None => ParquetCompression::UNCOMPRESSED,
+            Some(Compression::Gzip) => ParquetCompression::GZIP(GzipLevel::default()),
+            Some(Compression::Zstd) => ParquetCompression::ZSTD(ZstdLevel::default()),
+        };
+        WriterProperties::builder()
+            .set_max_row_group_size(self.row_group_size)
+            .set_compression(compression)
+            .set_created_by(concat!("lspbridge version ", env!("CARGO_PKG_VERSION")).to_string())
+            .build()
+    }
+}
+
+fn severity_name(severity: DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::Error => "error",
+        DiagnosticSeverity::Warning => "warning",
+        DiagnosticSeverity::Information => "info",
+        DiagnosticSeverity::Hint => "hint",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{DiagnosticSeverity, Position, Range};
+    use arrow_array::{Array, Int32Array, StringArray, TimestampMillisecondArray};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    fn diagnostic(line: u32, code: Option<&str>) -> Diagnostic {
+        let mut diagnostic = Diagnostic::new(
+            "src/lib.rs".to_string(),
+            Range {
+                start: Position { line, character: 0 },
+                end: Position { line, character: 4 },
+            },
+            DiagnosticSeverity::Warning,
+            "unused variable".to_string(),
+            "rustc".to_string(),
+        );
+        diagnostic.code = code.map(str::to_string);
+        diagnostic
+    }
+
+    /// `file` in a temporary file the reader can seek in
+    fn reopen(file: Vec<u8>) -> Result<std::fs::File> {
+        let mut temp = tempfile::tempfile()?;
+        temp.write_all(&file)?;
+        Ok(temp)
+    }
+
+    #[test]
+    fn test_row_groups_round_trip() -> Result<()> {
+        for compression in [None, Some(Compression::Gzip), Some(Compression::Zstd)] {
+            let captured_at = DateTime::from_timestamp_millis(1_700_000_000_123).unwrap();
+            let mut writer = ParquetWriter::new(Vec::new())?
+                .with_row_group_size(2)
+                .with_compression(compression);
+            writer.write(&diagnostic(1, Some("E0308")), None)?;
+            writer.write(&diagnostic(2, None), Some(captured_at))?;
+            // The first row group is written as soon as it is full
+            let after_first_group = writer.writer.as_ref().unwrap().bytes_written();
+            assert!(after_first_group > 0);
+            writer.write(&diagnostic(3, None), None)?;
+            assert_eq!(writer.rows_written(), 3);
+            let file = writer.finish()?;
+
+            let reader = ParquetRecordBatchReaderBuilder::try_new(reopen(file)?)?;
+            assert_eq!(reader.metadata().num_row_groups(), 2);
+            assert_eq!(reader.schema(), &diagnostic_schema());
+            let batches = reader.build()?.collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(batches.len(), 1);
+            let batch = &batches[0];
+            assert_eq!(batch.num_rows(), 3);
+
+            let column = |name: &str| batch.column_by_name(name).unwrap().clone();
+            let lines = column("start_line");
+            let lines = lines.as_any().downcast_ref::<Int32Array>().unwrap();
+            assert_eq!(lines.values(), &[1, 2, 3]);
+            let codes = column("code");
+            let codes = codes.as_any().downcast_ref::<StringArray>().unwrap();
+            assert_eq!((codes.value(0), codes.is_null(1)), ("E0308", true));
+            let captured = column("captured_at");
+            let captured = captured.as_any().downcast_ref::<TimestampMillisecondArray>().unwrap();
+            assert!(captured.is_null(0));
+            assert_eq!(captured.value(1), captured_at.timestamp_millis());
+            let severities = column("severity");
+            let severities = severities.as_any().downcast_ref::<StringArray>().unwrap();
+            assert_eq!(severities.value(2), "warning");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_export_is_a_valid_file() -> Result<()> {
+        let file = ParquetWriter::new(Vec::new())?.finish()?;
+        let reader = ParquetRecordBatchReaderBuilder::try_new(reopen(file)?)?;
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+//! Routed export over the multi-format pass
+//!
+//! [`ExportService::export_routed`] splits a snapshot between the routes of an
+//! [`ExportRoutingConfig`], applies each route's privacy level to its share
+//! and runs [`ExportService::export_multi`] over it in the route's formats.
+
+use super::{ExportOutput, ExportService};
+use crate::core::errors::ExportError;
+use crate::core::PrivacyFilter as _;
+use crate::core::{DiagnosticSnapshot, ExportConfig, ExportRoutingConfig, PrivacyPolicy};
+use crate::privacy::PrivacyFilter;
+use std::path::{Path, PathBuf};
+
+/// The documents produced for one route
+#[derive(Debug, Clone)]
+pub struct RoutedExport {
+    pub route: String,
+    /// The route's own output directory, if it sets one
+    pub out_dir: Option<PathBuf>,
+    /// Diagnostics exported after the route's privacy filter
+    pub diagnostics: usize,
+    pub outputs: Vec<ExportOutput>,
+}
+
+impl RoutedExport {
+    /// File name for one of the route's outputs, e.g. `security.sarif`
+    pub fn file_name(&self, output: &ExportOutput) -> String {
+        format!("{}.{}", self.route, output.format.file_extension())
+    }
+}
+
+/// Every route's documents from one export
+#[derive(Debug, Clone)]
+pub struct RoutedExportSet {
+    /// In route order
+    pub routes: Vec<RoutedExport>,
+    /// Diagnostics no route matched
+    pub unrouted: usize,
+}
+
+impl ExportService {
+    /// Export each route's share of a snapshot in its own formats and privacy level
+    pub fn export_routed(
+        &self,
+        snapshot: &DiagnosticSnapshot,
+        config: &ExportConfig,
+        routing: &ExportRoutingConfig,
+    ) -> Result<RoutedExportSet, ExportError> {
+        routing.validate()?;
+        let routed = routing.partition(snapshot.diagnostics.clone(), snapshot.timestamp);
+
+        let mut routes = Vec::with_capacity(routing.routes.len());
+        for (route, diagnostics) in routing.routes.iter().zip(routed.routes) {
+            let mut filter = PrivacyFilter::new(PrivacyPolicy::for_level(&route.privacy));
+            if let Some(audit) = &self.privacy_audit {
+                filter = filter.with_audit(audit.clone());
+            }
+            let diagnostics = filter.apply(diagnostics)?;
+            // Lenses go only to routes that received diagnostics of their file
+            let code_lenses = snapshot
+                .code_lenses
+                .iter()
+                .filter(|(file, _)| diagnostics.iter().any(|d| Path::new(&d.file) == file.as_path()))
+                .map(|(file, lenses)| (file.clone(), lenses.clone()))
+                .collect();
+            let share = DiagnosticSnapshot {
+                id: snapshot.id,
+                timestamp: snapshot.timestamp,
+                workspace: snapshot.workspace.clone(),
+                diagnostics,
+                metadata: snapshot.metadata.clone(),
+                code_lenses,
+            };
+            routes.push(RoutedExport {
+                route: route.name.clone(),
+                out_dir: route.out_dir.clone(),
+                diagnostics: share.diagnostics.len(),
+                outputs: self.export_multi(&share, config, &route.formats)?,
+            });
+        }
+
+        Ok(RoutedExportSet {
+            routes,
+            unrouted: routed.unrouted.len(),
+        })
+    }
+}
@@ -0,0 +1,713 @@
+//! SARIF 2.1.0 export
+//!
+//! [`SarifExporter`] writes diagnostics as a SARIF log that GitHub Code
+//! Scanning and other SARIF consumers accept: one rule per `source/code`
+//! pair with its level and description, results pointing at their rule by
+//! id and index, and paths inside the workspace written relative to
+//! `%SRCROOT%` so uploads from different checkouts match up.
+//!
+//! Every result carries a fingerprint that ignores its line, so code
+//! scanning can follow an alert when the code around it moves. [`SarifLog`]
+//! reads the logs back, which lets SARIF from other tools be imported as
+//! diagnostics.
+
+use super::multi_format::DiagnosticWriter;
+use crate::core::errors::ExportError;
+use crate::core::{
+    Diagnostic, DiagnosticSeverity, DiagnosticSnapshot, ExportFormat, Location, Position, Range,
+    RelatedInformation,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// SARIF version written and expected when reading
+pub const SARIF_VERSION: &str = "2.1.0";
+
+/// Schema URI referenced by exported logs
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Base id that workspace-relative artifact URIs resolve against
+pub const SRCROOT: &str = "%SRCROOT%";
+
+/// Key of the line-independent fingerprint in `partialFingerprints`
+pub const FINGERPRINT_KEY: &str = "lspbridge/v1";
+
+/// A SARIF log: the top-level document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SarifLog {
+    #[serde(rename = "$schema", default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    pub version: String,
+    pub runs: Vec<SarifRun>,
+}
+
+/// Results of one analysis tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRun {
+    pub tool: SarifTool,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub original_uri_base_ids: BTreeMap<String, SarifArtifactLocation>,
+    #[serde(default)]
+    pub results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SarifTool {
+    pub driver: SarifDriver,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifDriver {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub information_uri: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<SarifRule>,
+}
+
+/// Rule metadata (a SARIF `reportingDescriptor`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRule {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub short_description: Option<SarifMessage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_configuration: Option<SarifRuleConfiguration>,
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub properties: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SarifRuleConfiguration {
+    pub level: SarifLevel,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SarifMessage {
+    pub text: String,
+}
+
+/// Result level, ordered from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SarifLevel {
+    None,
+    Note,
+    Warning,
+    Error,
+}
+
+impl From<DiagnosticSeverity> for SarifLevel {
+    fn from(severity: DiagnosticSeverity) -> Self {
+        match severity {
+            DiagnosticSeverity::Error => SarifLevel::Error,
+            DiagnosticSeverity::Warning => SarifLevel::Warning,
+            DiagnosticSeverity::Information | DiagnosticSeverity::Hint => SarifLevel::Note,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifResult {
+    pub rule_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule_index: Option<usize>,
+    #[serde(default = "default_level")]
+    pub level: SarifLevel,
+    pub message: SarifMessage,
+    #[serde(default)]
+    pub locations: Vec<SarifLocation>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related_locations: Vec<SarifLocation>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub partial_fingerprints: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub properties: serde_json::Map<String, serde_json::Value>,
+}
+
+/// The level SARIF assumes when a result doesn't state one
+fn default_level() -> SarifLevel {
+    SarifLevel::Warning
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifLocation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<usize>,
+    pub physical_location: SarifPhysicalLocation,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<SarifMessage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifPhysicalLocation {
+    pub artifact_location: SarifArtifactLocation,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<SarifRegion>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifArtifactLocation {
+    pub uri: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri_base_id: Option<String>,
+}
+
+/// One-based line and column span
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRegion {
+    pub start_line: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_column: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_column: Option<u32>,
+}
+
+impl From<&Range> for SarifRegion {
+    fn from(range: &Range) -> Self {
+        Self {
+            start_line: range.start.line + 1,
+            start_column: Some(range.start.character + 1),
+            end_line: Some(range.end.line + 1),
+            end_column: Some(range.end.character + 1),
+        }
+    }
+}
+
+impl From<&SarifRegion> for Range {
+    fn from(region: &SarifRegion) -> Self {
+        let start = Position {
+            line: region.start_line.saturating_sub(1),
+            character: region.start_column.unwrap_or(1).saturating_sub(1),
+        };
+        let end = Position {
+            line: region.end_line.map_or(start.line, |line| line.saturating_sub(1)),
+            character: region.end_column.map_or(start.character, |column| column.saturating_sub(1)),
+        };
+        Range { start, end }
+    }
+}
+
+impl SarifLog {
+    pub fn parse(content: &str) -> Result<Self, ExportError> {
+        let log: Self = serde_json::from_str(content).map_err(|e| ExportError::DataTransformation {
+            from_format: "SARIF".to_string(),
+            to_format: "DiagnosticSnapshot".to_string(),
+            reason: e.to_string(),
+        })?;
+        if log.version != SARIF_VERSION {
+            return Err(ExportError::UnsupportedFormat {
+                format: format!("SARIF {}", log.version),
+            });
+        }
+        Ok(log)
+    }
+
+    /// Diagnostics for the results of every run
+    ///
+    /// Paths relative to a base id such as `%SRCROOT%` stay relative. The
+    /// source comes from the rule's `source` property when the log was
+    /// written by [`SarifExporter`], and from the tool name otherwise.
+    pub fn to_diagnostics(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for run in &self.runs {
+            let driver = &run.tool.driver;
+            for result in &run.results {
+                let Some(location) = result.locations.first() else {
+                    continue;
+                };
+                let rule = result
+                    .rule_index
+                    .and_then(|index| driver.rules.get(index))
+                    .filter(|rule| rule.id == result.rule_id)
+                    .or_else(|| driver.rules.iter().find(|rule| rule.id == result.rule_id));
+                let source = rule
+                    .and_then(|rule| rule.properties.get("source"))
+                    .and_then(|source| source.as_str())
+                    .unwrap_or(&driver.name)
+                    .to_string();
+                let code = match result.rule_id.strip_prefix(&format!("{source}/")) {
+                    Some(code) => Some(code.to_string()),
+                    None if result.rule_id == source => None,
+                    None => Some(result.rule_id.clone()),
+                };
+                let severity = result
+                    .properties
+                    .get("severity")
+                    .and_then(|severity| serde_json::from_value(severity.clone()).ok())
+                    .unwrap_or(match result.level {
+                        SarifLevel::Error => DiagnosticSeverity::Error,
+                        SarifLevel::Warning => DiagnosticSeverity::Warning,
+                        SarifLevel::Note | SarifLevel::None => DiagnosticSeverity::Information,
+                    });
+
+                let (file, range) = location.to_path_and_range();
+                let mut diagnostic = Diagnostic::new(file, range, severity, result.message.text.clone(), source);
+                diagnostic.code = code;
+                if !result.related_locations.is_empty() {
+                    diagnostic.related_information = Some(
+                        result
+                            .related_locations
+                            .iter()
+                            .map(|related| {
+                                let (uri, range) = related.to_path_and_range();
+                                RelatedInformation {
+                                    location: Location { uri, range },
+                                    message: related.message.as_ref().map(|m| m.text.clone()).unwrap_or_default(),
+                                }
+                            })
+                            .collect(),
+                    );
+                }
+                diagnostics.push(diagnostic);
+            }
+        }
+        diagnostics
+    }
+}
+
+impl SarifLocation {
+    fn to_path_and_range(&self) -> (String, Range) {
+        let artifact = &self.physical_location.artifact_location;
+        let uri = match &artifact.uri_base_id {
+            Some(_) => artifact.uri.as_str(),
+            None => artifact.uri.strip_prefix("file://").unwrap_or(&artifact.uri),
+        };
+        let range = self
+            .physical_location
+            .region
+            .as_ref()
+            .map(Range::from)
+            .unwrap_or(Range {
+                start: Position { line: 0, character: 0 },
+                end: Position { line: 0, character: 0 },
+            });
+        (decode_uri(uri), range)
+    }
+}
+
+/// Rule identifier for a diagnostic: `source/code`, or the source alone
+pub fn rule_id(diagnostic: &Diagnostic) -> String {
+    match &diagnostic.code {
+        Some(code) => format!("{}/{}", diagnostic.source, code),
+        None => diagnostic.source.clone(),
+    }
+}
+
+/// Streams diagnostics into a SARIF 2.1.0 log
+#[derive(Debug, Default)]
+pub struct SarifExporter {
+    root: Option<PathBuf>,
+    rules: BTreeMap<String, SarifRule>,
+    results: Vec<SarifResult>,
+}
+
+impl SarifExporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write paths under `root` relative to `%SRCROOT%`
+    ///
+    /// Defaults to the workspace root of the exported snapshot.
+    pub fn with_source_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = Some(root.into());
+        self
+    }
+
+    /// Export a whole snapshot in its current order
+    pub fn export(mut self, snapshot: &DiagnosticSnapshot) -> Result<String, ExportError> {
+        self.begin(snapshot)?;
+        for diagnostic in &snapshot.diagnostics {
+            self.write(diagnostic)?;
+        }
+        self.finish()
+    }
+
+    fn artifact(&self, path: &str) -> SarifArtifactLocation {
+        let path = path.strip_prefix("file://").unwrap_or(path);
+        let relative = if Path::new(path).is_relative() {
+            Some(Path::new(path.trim_start_matches("./")))
+        } else {
+            self.root.as_ref().and_then(|root| Path::new(path).strip_prefix(root).ok())
+        };
+        match relative {
+            Some(relative) => SarifArtifactLocation {
+                uri: encode_uri(&relative.to_string_lossy()),
+                uri_base_id: Some(SRCROOT.to_string()),
+            },
+            None => SarifArtifactLocation {
+                uri: format!("file://{}", encode_uri(path)),
+                uri_base_id: None,
+            },
+        }
+    }
+
+    fn location(&self, path: &str, range: &Range, message: Option<&str>) -> SarifLocation {
+        SarifLocation {
+            id: None,
+            physical_location: SarifPhysicalLocation {
+                artifact_location: self.artifact(path),
+                region: Some(SarifRegion::from(range)),
+            },
+            message: message.map(|text| SarifMessage { text: text.to_string() }),
+        }
+    }
+
+    /// Log of everything written so far
+    fn log(&mut self) -> SarifLog {
+        let rules: Vec<SarifRule> = std::mem::take(&mut self.rules).into_values().collect();
+        let mut results = std::mem::take(&mut self.results);
+        for result in &mut results {
+            result.rule_index = rules.iter().position(|rule| rule.id == result.rule_id);
+        }
+
+        let mut original_uri_base_ids = BTreeMap::new();
+        if let Some(root) = &self.root {
+            let root = encode_uri(&root.to_string_lossy());
+            original_uri_base_ids.insert(
+                SRCROOT.to_string(),
+                SarifArtifactLocation {
+                    uri: format!("file://{}/", root.trim_end_matches('/')),
+                    uri_base_id: None,
+                },
+            );
+        }
+
+        SarifLog {
+            schema: Some(SARIF_SCHEMA.to_string()),
+            version: SARIF_VERSION.to_string(),
+            runs: vec![SarifRun {
+                tool: SarifTool {
+                    driver: SarifDriver {
+                        name: "LSPbridge".to_string(),
+                        version: Some(env!("CARGO_PKG_VERSION").to_string()),
+                        information_uri: Some("https://github.com/Hydepwns/LSPbridge".to_string()),
+                        rules,
+                    },
+                },
+                original_uri_base_ids,
+                results,
+            }],
+        }
+    }
+}
+
+impl DiagnosticWriter for SarifExporter {
+    fn format(&self) -> ExportFormat {
+        ExportFormat::Sarif
+    }
+
+    fn begin(&mut self, snapshot: &DiagnosticSnapshot) -> Result<(), ExportError> {
+        if self.root.is_none() && !snapshot.workspace.root_path.is_empty() {
+            self.root = Some(PathBuf::from(&snapshot.workspace.root_path));
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, diagnostic: &Diagnostic) -> Result<(), ExportError> {
+        let rule_id = rule_id(diagnostic);
+        let level = SarifLevel::from(diagnostic.severity);
+
+        let rule = self.rules.entry(rule_id.clone()).or_insert_with(|| new_rule(&rule_id, diagnostic));
+        let configuration = rule.default_configuration.get_or_insert(SarifRuleConfiguration { level });
+        configuration.level = configuration.level.max(level);
+
+        let location = self.location(&diagnostic.file, &diagnostic.range, None);
+        let related_locations = diagnostic
+            .related_information
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(|(index, related)| SarifLocation {
+                id: Some(index),
+                ..self.location(&related.location.uri, &related.location.range, Some(&related.message))
+            })
+            .collect();
+
+        let mut partial_fingerprints = BTreeMap::new();
+        partial_fingerprints.insert(
+            FINGERPRINT_KEY.to_string(),
+            fingerprint(&location.physical_location.artifact_location.uri, &rule_id, &diagnostic.message),
+        );
+        let mut properties = serde_json::Map::new();
+        properties.insert("severity".to_string(), serde_json::json!(diagnostic.severity));
+
+        self.results.push(SarifResult {
+            rule_id,
+            rule_index: None,
+            level,
+            message: SarifMessage {
+                text: diagnostic.message.clone(),
+            },
+            locations: vec![location],
+            related_locations,
+            partial_fingerprints,
+            properties,
+        });
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<String, ExportError> {
+        serde_json::to_string_pretty(&self.log()).map_err(|e| ExportError::DataTransformation {
+            from_format: "DiagnosticSnapshot".to_string(),
+            to_format: "SARIF".to_string(),
+            reason: e.to_string(),
+        })
+    }
+}
+
+/// Rule metadata from the first diagnostic reported under `id`
+///
+/// Diagnostics sharing a code share a meaning, so the first message
+/// describes the rule; without a code the rule covers a whole tool.
+fn new_rule(id: &str, diagnostic: &Diagnostic) -> SarifRule {
+    let description = match &diagnostic.code {
+        Some(_) => diagnostic.message.clone(),
+        None => format!("Diagnostics reported by {}", diagnostic.source),
+    };
+    let mut properties = serde_json::Map::new();
+    properties.insert("source".to_string(), diagnostic.source.clone().into());
+    properties.insert("tags".to_string(), serde_json::json!([diagnostic.source]));
+    SarifRule {
+        id: id.to_string(),
+        name: Some(diagnostic.code.clone().unwrap_or_else(|| diagnostic.source.clone())),
+        short_description: Some(SarifMessage { text: description }),
+        default_configuration: None,
+        properties,
+    }
+}
+
+/// Hash of where and what, leaving out the line so moved code keeps its alert
+fn fingerprint(uri: &str, rule_id: &str, message: &str) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    for part in [uri, rule_id, message] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hasher.finalize().iter().take(8).map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Percent-encode a path for use in a URI, keeping `/` separators
+fn encode_uri(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.replace('\\', "/").bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+fn decode_uri(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| uri.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::WorkspaceInfo;
+    use serde_json::Value;
+
+    fn diagnostic(file: &str, line: u32, severity: DiagnosticSeverity, code: Option<&str>, source: &str) -> Diagnostic {
+        let mut diagnostic = Diagnostic::new(
+            file.to_string(),
+            Range {
+                start: Position { line, character: 4 },
+                end: Position { line, character: 10 },
+            },
+            severity,
+            format!("problem on line {line}"),
+            source.to_string(),
+        );
+        diagnostic.code = code.map(str::to_string);
+        diagnostic
+    }
+
+    fn snapshot(diagnostics: Vec<Diagnostic>) -> DiagnosticSnapshot {
+        DiagnosticSnapshot::new(
+            WorkspaceInfo {
+                name: "demo".to_string(),
+                root_path: "/work/demo".to_string(),
+                language: Some("rust".to_string()),
+                version: None,
+                roots: vec![],
+            },
+            diagnostics,
+        )
+    }
+
+    /// Properties the SARIF 2.1.0 schema requires or constrains for what we write
+    fn assert_valid_sarif(log: &Value) {
+        assert_eq!(log["version"], SARIF_VERSION);
+        assert_eq!(log["$schema"], SARIF_SCHEMA);
+        for run in log["runs"].as_array().expect("runs is an array") {
+            let driver = &run["tool"]["driver"];
+            assert!(driver["name"].is_string());
+            let rules = driver["rules"].as_array().expect("rules is an array");
+            for rule in rules {
+                assert!(rule["id"].is_string());
+                assert!(rule["shortDescription"]["text"].is_string());
+            }
+            for result in run["results"].as_array().expect("results is an array") {
+                assert!(result["message"]["text"].is_string());
+                assert!(["none", "note", "warning", "error"].contains(&result["level"].as_str().unwrap()));
+                let index = result["ruleIndex"].as_u64().unwrap() as usize;
+                assert_eq!(rules[index]["id"], result["ruleId"]);
+                for location in result["locations"].as_array().unwrap() {
+                    let physical = &location["physicalLocation"];
+                    if let Some(base) = physical["artifactLocation"]["uriBaseId"].as_str() {
+                        assert!(run["originalUriBaseIds"][base]["uri"].as_str().unwrap().ends_with('/'));
+                    }
+                    let region = &physical["region"];
+                    assert!(region["startLine"].as_u64().unwrap() >= 1);
+                    assert!(region["startColumn"].as_u64().unwrap() >= 1);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sarif_export_round_trips() {
+        let mut error = diagnostic("/work/demo/src/main.rs", 4, DiagnosticSeverity::Error, Some("E0308"), "rustc");
+        error.related_information = Some(vec![RelatedInformation {
+            location: Location {
+                uri: "file:///work/demo/src/lib.rs".to_string(),
+                range: error.range.clone(),
+            },
+            message: "expected due to this".to_string(),
+        }]);
+        let diagnostics = vec![
+            error,
+            diagnostic("src/my file.rs", 0, DiagnosticSeverity::Hint, Some("E0308"), "rustc"),
+            diagnostic("/elsewhere/gen.rs", 2, DiagnosticSeverity::Warning, None, "clippy"),
+        ];
+
+        let content = SarifExporter::new().export(&snapshot(diagnostics.clone())).unwrap();
+        let value: Value = serde_json::from_str(&content).unwrap();
+        assert_valid_sarif(&value);
+
+        let run = &value["runs"][0];
+        assert_eq!(run["originalUriBaseIds"][SRCROOT]["uri"], "file:///work/demo/");
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[1]["id"], "rustc/E0308");
+        assert_eq!(rules[1]["defaultConfiguration"]["level"], "error");
+        assert_eq!(rules[0]["shortDescription"]["text"], "Diagnostics reported by clippy");
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"], "src/main.rs");
+        assert_eq!(results[1]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"], "src/my%20file.rs");
+        assert_eq!(results[1]["level"], "note");
+        assert_eq!(results[2]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"], "file:///elsewhere/gen.rs");
+
+        // Reading the log back gives the same diagnostics, with workspace paths relative
+        let log = SarifLog::parse(&content).unwrap();
+        assert_eq!(serde_json::to_value(&log).unwrap(), value);
+        let read = log.to_diagnostics();
+        assert_eq!(read.len(), 3);
+        for (read, original) in read.iter().zip(&diagnostics) {
+            assert_eq!(read.range, original.range);
+            assert_eq!(read.severity, original.severity);
+            assert_eq!(read.message, original.message);
+            assert_eq!(read.code, original.code);
+            assert_eq!(read.source, original.source);
+        }
+        assert_eq!(read[0].file, "src/main.rs");
+        assert_eq!(read[1].file, "src/my file.rs");
+        assert_eq!(read[2].file, "/elsewhere/gen.rs");
+        let related = &read[0].related_information.as_ref().unwrap()[0];
+        assert_eq!(related.location.uri, "src/lib.rs");
+        assert_eq!(related.message, "expected due to this");
+    }
+
+    #[test]
+    fn test_fingerprints_ignore_line_moves() {
+        let fingerprint = |line| {
+            let content = SarifExporter::new()
+                .export(&snapshot(vec![diagnostic("src/main.rs", line, DiagnosticSeverity::Warning, None, "clippy")]))
+                .unwrap();
+            let value: Value = serde_json::from_str(&content).unwrap();
+            value["runs"][0]["results"][0]["partialFingerprints"][FINGERPRINT_KEY].clone()
+        };
+        // The message mentions the line, so only an unchanged message keeps the fingerprint
+        assert_ne!(fingerprint(1), fingerprint(2));
+
+        let moved = |line| {
+            let mut moved = diagnostic("src/main.rs", line, DiagnosticSeverity::Warning, None, "clippy");
+            moved.message = "unused variable".to_string();
+            let content = SarifExporter::new().export(&snapshot(vec![moved])).unwrap();
+            let value: Value = serde_json::from_str(&content).unwrap();
+            value["runs"][0]["results"][0]["partialFingerprints"][FINGERPRINT_KEY].clone()
+        };
+        assert_eq!(moved(1), moved(40));
+    }
+
+    #[test]
+    fn test_foreign_sarif_imports() {
+        let content = r#"{
+            "version": "2.1.0",
+            "runs": [{
+                "tool": { "driver": { "name": "semgrep" } },
+                "results": [{
+                    "ruleId": "python.lang.security.eval",
+                    "message": { "text": "Avoid eval" },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": "app/views.py" },
+                            "region": { "startLine": 12 }
+                        }
+                    }]
+                }]
+            }]
+        }"#;
+        let diagnostics = SarifLog::parse(content).unwrap().to_diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].source, "semgrep");
+        assert_eq!(diagnostics[0].code.as_deref(), Some("python.lang.security.eval"));
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Warning);
+        assert_eq!(diagnostics[0].range.start.line, 11);
+        assert_eq!(diagnostics[0].file, "app/views.py");
+
+        assert!(SarifLog::parse(r#"{"version": "1.0.0", "runs": []}"#).is_err());
+    }
+}
@@ -0,0 +1,544 @@
+//! Shared, relevance-ranked code context for multi-diagnostic exports
+//!
+//! A file with thirty diagnostics inside the same few functions would
+//! otherwise repeat the same surrounding code thirty times. The
+//! [`ContextSelector`] groups diagnostics by their enclosing function or
+//! class so each block of code is emitted once, and falls back to merged
+//! line windows when no enclosing declaration can be found.
+//!
+//! Blocks are ranked by the aggregate relevance of the diagnostics they
+//! contain (severity-weighted) and picked greedily under an optional token
+//! budget, so the most important code survives when space is short.
+//!
+//! Block detection is a lightweight text heuristic: declarations are matched
+//! by keyword and bodies are delimited by brace depth or, for
+//! indentation-based languages, by indentation.
+
+use super::token_estimator::{ModelFamily, TokenEstimator};
+use crate::core::{Diagnostic, DiagnosticSeverity, FileError, FileGuard, SkippedFile};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Blocks longer than this fall back to a line window
+const DEFAULT_MAX_BLOCK_LINES: usize = 80;
+
+static FUNCTION_DECL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^\s*(?:(?:pub(?:\([^)]*\))?|export|default|async|unsafe|const|static|public|private|protected|override|final)\s+)*(?:fn|def|func|function)\s+([A-Za-z_$][\w$]*)",
+    )
+    .expect("valid function regex")
+});
+
+static CLASS_DECL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^\s*(?:(?:pub(?:\([^)]*\))?|export|default|abstract|public|private|final|data)\s+)*(?:class|struct|enum|trait|interface|impl(?:<[^>]*>)?)\s+([A-Za-z_$][\w$:<>, ]*?)\s*(?:[{:(<]|where\b|for\b|extends\b|implements\b|$)",
+    )
+    .expect("valid class regex")
+});
+
+/// What a context block covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockKind {
+    Function,
+    Class,
+    /// Lines around one or more diagnostics outside any recognised declaration
+    Window,
+}
+
+/// A piece of source shared by every diagnostic it contains
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextBlock {
+    pub file: String,
+    pub kind: BlockKind,
+    /// Function or class name, if the block is a declaration
+    pub name: Option<String>,
+    /// First line of the block (0-based)
+    pub start_line: usize,
+    /// Last line of the block (0-based, inclusive)
+    pub end_line: usize,
+    pub code: String,
+    /// Diagnostics located inside the block
+    pub diagnostic_ids: Vec<String>,
+    /// Severity-weighted sum over the contained diagnostics
+    pub relevance: f32,
+    pub tokens: usize,
+}
+
+impl ContextBlock {
+    /// Short human-readable label, e.g. `fn parse` or `lines 10-24`
+    pub fn label(&self) -> String {
+        match (&self.kind, &self.name) {
+            (BlockKind::Function, Some(name)) => format!("fn {name}"),
+            (BlockKind::Class, Some(name)) => format!("type {name}"),
+            _ => format!("lines {}-{}", self.start_line + 1, self.end_line + 1),
+        }
+    }
+}
+
+/// Result of a selection pass
+#[derive(Debug, Clone, Default)]
+pub struct ContextSelection {
+    /// Selected blocks, ordered by file and line
+    pub blocks: Vec<ContextBlock>,
+    /// Blocks dropped to stay within the budget
+    pub omitted: usize,
+    /// Files left out for exceeding the size limit or looking binary
+    pub skipped: Vec<SkippedFile>,
+    assignments: HashMap<String, usize>,
+}
+
+impl ContextSelection {
+    /// Index into `blocks` of the context shared by a diagnostic
+    pub fn block_for(&self, diagnostic_id: &str) -> Option<usize> {
+        self.assignments.get(diagnostic_id).copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Total estimated tokens of the selected blocks
+    pub fn total_tokens(&self) -> usize {
+        self.blocks.iter().map(|b| b.tokens).sum()
+    }
+}
+
+/// Groups diagnostics into shared context blocks and ranks them under a budget
+#[derive(Debug, Clone)]
+pub struct ContextSelector {
+    context_lines: usize,
+    max_block_lines: usize,
+    budget: Option<usize>,
+    estimator: TokenEstimator,
+    file_guard: FileGuard,
+}
+
+impl ContextSelector {
+    /// Create a selector that uses `context_lines` around diagnostics outside any declaration
+    pub fn new(context_lines: usize) -> Self {
+        Self {
+            context_lines,
+            max_block_lines: DEFAULT_MAX_BLOCK_LINES,
+            budget: None,
+            estimator: TokenEstimator::new(ModelFamily::Claude),
+            file_guard: FileGuard::default(),
+        }
+    }
+
+    /// Limit the total size of the selected blocks, in estimated tokens
+    pub fn with_budget(mut self, tokens: usize) -> Self {
+        self.budget = Some(tokens);
+        self
+    }
+
+    /// Estimate block sizes with a specific model's tokenizer profile
+    pub fn with_estimator(mut self, estimator: TokenEstimator) -> Self {
+        self.estimator = estimator;
+        self
+    }
+
+    /// Declarations longer than this are replaced by a line window
+    pub fn with_max_block_lines(mut self, lines: usize) -> Self {
+        self.max_block_lines = lines.max(1);
+        self
+    }
+
+    /// Size and binary limits for source files read by [`Self::select`]
+    pub fn with_file_guard(mut self, file_guard: FileGuard) -> Self {
+        self.file_guard = file_guard;
+        self
+    }
+
+    /// Select context for diagnostics, reading their files from disk
+    ///
+    /// Files rejected by the file guard are listed in the selection's `skipped`.
+    pub fn select(&self, diagnostics: &[&Diagnostic]) -> ContextSelection {
+        let mut skipped = Vec::new();
+        let mut selection = self.select_with(diagnostics, |file| {
+            match self.file_guard.read_to_string(Path::new(file)) {
+                Ok(source) => Some(source),
+                Err(FileError::Skipped { path, reason }) => {
+                    skipped.push(SkippedFile { path, reason });
+                    None
+                }
+                Err(_) => None,
+            }
+        });
+        selection.skipped = skipped;
+        selection
+    }
+
+    /// Select context for diagnostics, loading sources through `read`
+    ///
+    /// Files that cannot be read contribute no blocks.
+    pub fn select_with<F>(&self, diagnostics: &[&Diagnostic], mut read: F) -> ContextSelection
+    where
+        F: FnMut(&str) -> Option<String>,
+    {
+        let mut by_file: Vec<(&str, Vec<&Diagnostic>)> = Vec::new();
+        for diagnostic in diagnostics {
+            match by_file.iter_mut().find(|(file, _)| *file == diagnostic.file) {
+                Some((_, group)) => group.push(diagnostic),
+                None => by_file.push((&diagnostic.file, vec![diagnostic])),
+            }
+        }
+
+        let mut candidates = Vec::new();
+        for (file, group) in by_file {
+            if let Some(source) = read(file) {
+                candidates.extend(self.blocks_for_file(file, &source, &group));
+            }
+        }
+
+        self.rank(candidates)
+    }
+
+    fn blocks_for_file(
+        &self,
+        file: &str,
+        source: &str,
+        diagnostics: &[&Diagnostic],
+    ) -> Vec<ContextBlock> {
+        let lines: Vec<&str> = source.lines().collect();
+        if lines.is_empty() {
+            return Vec::new();
+        }
+
+        let mut spans: Vec<Span> = Vec::new();
+        let mut windows: Vec<(usize, usize, Vec<&Diagnostic>)> = Vec::new();
+
+        for diagnostic in diagnostics {
+            let line = (diagnostic.range.start.line as usize).min(lines.len() - 1);
+            match enclosing_block(&lines, line, self.max_block_lines) {
+                Some((kind, name, start, end)) => {
+                    match spans.iter_mut().find(|s| s.start == start && s.end == end) {
+                        Some(span) => span.diagnostics.push(diagnostic),
+                        None => spans.push(Span {
+                            kind,
+                            name: Some(name),
+                            start,
+                            end,
+                            diagnostics: vec![diagnostic],
+                        }),
+                    }
+                }
+                None => {
+                    let start = line.saturating_sub(self.context_lines);
+                    let end = (line + self.context_lines).min(lines.len() - 1);
+                    windows.push((start, end, vec![diagnostic]));
+                }
+            }
+        }
+
+        // Merge overlapping or touching windows so nearby diagnostics share one
+        windows.sort_by_key(|w| w.0);
+        let mut merged: Vec<(usize, usize, Vec<&Diagnostic>)> = Vec::new();
+        for window in windows {
+            match merged.last_mut() {
+                Some(last) if window.0 <= last.1 + 1 => {
+                    last.1 = last.1.max(window.1);
+                    last.2.extend(window.2);
+                }
+                _ => merged.push(window),
+            }
+        }
+        spans.extend(merged.into_iter().map(|(start, end, diagnostics)| Span {
+            kind: BlockKind::Window,
+            name: None,
+            start,
+            end,
+            diagnostics,
+        }));
+
+        spans
+            .into_iter()
+            .map(|span| {
+                let code = lines[span.start..=span.end].join("\n");
+                ContextBlock {
+                    file: file.to_string(),
+                    kind: span.kind,
+                    name: span.name,
+                    start_line: span.start,
+                    end_line: span.end,
+                    tokens: self.estimator.estimate(&code),
+                    code,
+                    diagnostic_ids: span.diagnostics.iter().map(|d| d.id.clone()).collect(),
+                    relevance: span
+                        .diagnostics
+                        .iter()
+                        .map(|d| severity_weight(d.severity))
+                        .sum(),
+                }
+            })
+            .collect()
+    }
+
+    fn rank(&self, mut candidates: Vec<ContextBlock>) -> ContextSelection {
+        candidates.sort_by(|a, b| {
+            b.relevance
+                .partial_cmp(&a.relevance)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.tokens.cmp(&b.tokens))
+        });
+
+        let mut selected = Vec::new();
+        let mut omitted = 0;
+        let mut used = 0usize;
+        for block in candidates {
+            match self.budget {
+                Some(budget) if used + block.tokens > budget => omitted += 1,
+                _ => {
+                    used += block.tokens;
+                    selected.push(block);
+                }
+            }
+        }
+
+        selected.sort_by(|a, b| {
+            a.file
+                .cmp(&b.file)
+                .then(a.start_line.cmp(&b.start_line))
+        });
+
+        let mut assignments = HashMap::new();
+        for (index, block) in selected.iter().enumerate() {
+            for id in &block.diagnostic_ids {
+                assignments.insert(id.clone(), index);
+            }
+        }
+
+        ContextSelection {
+            blocks: selected,
+            omitted,
+            skipped: Vec::new(),
+            assignments,
+        }
+    }
+}
+
+/// Diagnostics grouped under one block before its code is extracted
+struct Span<'a> {
+    kind: BlockKind,
+    name: Option<String>,
+    start: usize,
+    end: usize,
+    diagnostics: Vec<&'a Diagnostic>,
+}
+
+fn severity_weight(severity: DiagnosticSeverity) -> f32 {
+    match severity {
+        DiagnosticSeverity::Error => 1.0,
+        DiagnosticSeverity::Warning => 0.5,
+        DiagnosticSeverity::Information => 0.2,
+        DiagnosticSeverity::Hint => 0.1,
+    }
+}
+
+fn declaration(line: &str) -> Option<(BlockKind, String)> {
+    if let Some(caps) = FUNCTION_DECL.captures(line) {
+        return Some((BlockKind::Function, caps[1].to_string()));
+    }
+    CLASS_DECL
+        .captures(line)
+        .map(|caps| (BlockKind::Class, caps[1].trim().to_string()))
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Innermost declaration enclosing `line`, as (kind, name, start, end)
+fn enclosing_block(
+    lines: &[&str],
+    line: usize,
+    max_lines: usize,
+) -> Option<(BlockKind, String, usize, usize)> {
+    let lowest = line.saturating_sub(max_lines);
+    for start in (lowest..=line).rev() {
+        let Some((kind, name)) = declaration(lines[start]) else {
+            continue;
+        };
+        let Some(end) = block_end(lines, start) else {
+            continue;
+        };
+        if end >= line {
+            if end - start + 1 > max_lines {
+                return None;
+            }
+            return Some((kind, name, start, end));
+        }
+    }
+    None
+}
+
+/// Last line of the declaration starting at `start`
+fn block_end(lines: &[&str], start: usize) -> Option<usize> {
+    // Brace-delimited body: the opening brace must appear before the body starts
+    let mut depth = 0i32;
+    let mut opened = false;
+    for (offset, text) in lines[start..].iter().enumerate() {
+        for ch in text.chars() {
+            match ch {
+                '{' => {
+                    depth += 1;
+                    opened = true;
+                }
+                '}' => depth -= 1,
+                ';' if !opened && depth == 0 => return Some(start + offset),
+                _ => {}
+            }
+        }
+        if opened && depth <= 0 {
+            return Some(start + offset);
+        }
+        if !opened && text.trim_end().ends_with(':') {
+            return indented_block_end(lines, start);
+        }
+    }
+    None
+}
+
+/// Last line of an indentation-delimited body (Python and similar)
+fn indented_block_end(lines: &[&str], start: usize) -> Option<usize> {
+    let base = indentation(lines[start]);
+    let mut end = start;
+    for (offset, text) in lines[start + 1..].iter().enumerate() {
+        if text.trim().is_empty() {
+            continue;
+        }
+        if indentation(text) <= base {
+            break;
+        }
+        end = start + 1 + offset;
+    }
+    (end > start).then_some(end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Position, Range};
+
+    const SOURCE: &str = "\
+use std::io;
+
+fn parse(input: &str) -> u32 {
+    let a = input.len();
+    let b = a + 1;
+    b as u32
+}
+
+struct Config {
+    name: String,
+}
+
+const LIMIT: usize = 10;
+const OTHER: usize = 20;
+";
+
+    fn diagnostic(line: u32, severity: DiagnosticSeverity) -> Diagnostic {
+        Diagnostic::new(
+            "src/lib.rs".to_string(),
+            Range {
+                start: Position { line, character: 0 },
+                end: Position { line, character: 1 },
+            },
+            severity,
+            "problem".to_string(),
+            "rustc".to_string(),
+        )
+    }
+
+    fn select(selector: &ContextSelector, diagnostics: &[Diagnostic]) -> ContextSelection {
+        let refs: Vec<&Diagnostic> = diagnostics.iter().collect();
+        selector.select_with(&refs, |_| Some(SOURCE.to_string()))
+    }
+
+    #[test]
+    fn test_diagnostics_in_one_function_share_a_block() {
+        let diagnostics = vec![
+            diagnostic(3, DiagnosticSeverity::Error),
+            diagnostic(4, DiagnosticSeverity::Warning),
+            diagnostic(5, DiagnosticSeverity::Error),
+            diagnostic(9, DiagnosticSeverity::Warning),
+            diagnostic(12, DiagnosticSeverity::Hint),
+            diagnostic(13, DiagnosticSeverity::Hint),
+        ];
+        let selection = select(&ContextSelector::new(0), &diagnostics);
+
+        assert_eq!(selection.blocks.len(), 3);
+        let function = &selection.blocks[0];
+        assert_eq!(function.kind, BlockKind::Function);
+        assert_eq!(function.label(), "fn parse");
+        assert_eq!((function.start_line, function.end_line), (2, 6));
+        assert_eq!(function.diagnostic_ids.len(), 3);
+        assert!((function.relevance - 2.5).abs() < f32::EPSILON);
+
+        assert_eq!(selection.blocks[1].name.as_deref(), Some("Config"));
+        // Adjacent windows merge into one
+        assert_eq!(selection.blocks[2].kind, BlockKind::Window);
+        assert_eq!(selection.blocks[2].diagnostic_ids.len(), 2);
+
+        for d in &diagnostics[..3] {
+            assert_eq!(selection.block_for(&d.id), Some(0));
+        }
+    }
+
+    #[test]
+    fn test_budget_keeps_most_relevant_blocks() {
+        let diagnostics = vec![
+            diagnostic(3, DiagnosticSeverity::Error),
+            diagnostic(9, DiagnosticSeverity::Hint),
+            diagnostic(12, DiagnosticSeverity::Warning),
+        ];
+        let unlimited = select(&ContextSelector::new(0), &diagnostics);
+        let function_tokens = unlimited.blocks[0].tokens;
+
+        let selection = select(
+            &ContextSelector::new(0).with_budget(function_tokens),
+            &diagnostics,
+        );
+        assert_eq!(selection.blocks.len(), 1);
+        assert_eq!(selection.blocks[0].label(), "fn parse");
+        assert_eq!(selection.omitted, 2);
+        assert_eq!(selection.block_for(&diagnostics[1].id), None);
+    }
+
+    #[test]
+    fn test_indented_blocks_and_oversized_fallback() {
+        let python = "class Greeter:\n    def greet(self):\n        return 1\n\nx = 1\n";
+        let lines: Vec<&str> = python.lines().collect();
+        let (kind, name, start, end) = enclosing_block(&lines, 2, 80).unwrap();
+        assert_eq!((kind, name.as_str(), start, end), (BlockKind::Function, "greet", 1, 2));
+        assert!(enclosing_block(&lines, 4, 80).is_none());
+
+        let source: Vec<&str> = SOURCE.lines().collect();
+        assert!(enclosing_block(&source, 4, 3).is_none());
+    }
+
+    #[test]
+    fn test_oversized_files_are_reported_as_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lib.rs");
+        std::fs::write(&path, SOURCE).unwrap();
+
+        let mut diagnostic = diagnostic(3, DiagnosticSeverity::Error);
+        diagnostic.file = path.to_string_lossy().to_string();
+
+        let selection = ContextSelector::new(0).select(&[&diagnostic]);
+        assert_eq!(selection.blocks.len(), 1);
+        assert!(selection.skipped.is_empty());
+
+        let guard = FileGuard::with_max_bytes(16);
+        let selection = ContextSelector::new(0)
+            .with_file_guard(guard.clone())
+            .select(&[&diagnostic]);
+        assert!(selection.is_empty());
+        assert_eq!(selection.skipped.len(), 1);
+        assert_eq!(guard.skipped(), selection.skipped);
+    }
+}
@@ -58,7 +58,12 @@ impl GenericLSPConverter {
             severity,
             message,
             code,
-            source: source.to_string(),
+            // Prefer the diagnostic's own source (e.g. `rustc`, `eslint`) when the server sets one
+            source: d
+                .get("source")
+                .and_then(|s| s.as_str())
+                .unwrap_or(source)
+                .to_string(),
             related_information: None,
             tags: None,
             data: None,
@@ -0,0 +1,318 @@
+//! Converters for Go analyzers: `go vet -json` and `staticcheck -f json`
+
+use crate::core::errors::ParseError;
+use crate::core::{Diagnostic, DiagnosticSeverity, Location, Position, Range, RawDiagnostics, RelatedInformation};
+use crate::format::format_converter::types::SpecificFormatConverter;
+use crate::format::format_converter::utils::{generate_id, normalize_file_path};
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Converter for `go vet -json` output
+///
+/// Vet prints one object per package, `{"<pkg>": {"<analyzer>": [{"posn": "file.go:12:3",
+/// "message": ...}]}}`; the analyzer name becomes the diagnostic code.
+pub struct GoVetConverter;
+
+impl GoVetConverter {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Whether the data has the shape of vet's JSON output
+    pub fn matches(data: &Value) -> bool {
+        packages(data).any(|package| {
+            package
+                .as_object()
+                .into_iter()
+                .flat_map(|analyzers| analyzers.values())
+                .filter_map(Value::as_array)
+                .any(|findings| findings.first().is_some_and(|f| f.get("posn").is_some()))
+        })
+    }
+}
+
+#[async_trait]
+impl SpecificFormatConverter for GoVetConverter {
+    async fn convert(&self, raw: &RawDiagnostics) -> Result<Vec<Diagnostic>, ParseError> {
+        if !raw.data.is_object() && !raw.data.is_array() {
+            return Err(ParseError::InvalidFormat {
+                context: "go vet output".to_string(),
+                expected: "object of packages from `go vet -json`".to_string(),
+                found: format!("{:?}", raw.data),
+            });
+        }
+
+        let mut diagnostics = Vec::new();
+        for package in packages(&raw.data) {
+            let Some(analyzers) = package.as_object() else {
+                continue;
+            };
+            // Packages that fail to type-check report `{"error": ...}` instead of findings
+            for (analyzer, findings) in analyzers {
+                for finding in findings.as_array().into_iter().flatten() {
+                    let Some((file, start)) = finding.get("posn").and_then(Value::as_str).and_then(parse_posn)
+                    else {
+                        continue;
+                    };
+                    let end = finding
+                        .get("end")
+                        .and_then(Value::as_str)
+                        .and_then(parse_posn)
+                        .map(|(_, end)| end)
+                        .unwrap_or(start.clone());
+
+                    diagnostics.push(Diagnostic {
+                        id: generate_id("go-vet", diagnostics.len()),
+                        file: normalize_file_path(&file),
+                        range: Range { start, end },
+                        severity: DiagnosticSeverity::Warning,
+                        message: finding.get("message").and_then(Value::as_str).unwrap_or("").to_string(),
+                        code: Some(analyzer.clone()),
+                        source: "go vet".to_string(),
+                        related_information: None,
+                        tags: None,
+                        data: None,
+                    });
+                }
+            }
+        }
+        Ok(diagnostics)
+    }
+
+    fn can_handle(&self, source: &str) -> bool {
+        let source = source.to_lowercase();
+        source.contains("go vet") || source.contains("govet") || source.contains("go-vet")
+    }
+
+    fn name(&self) -> &'static str {
+        "go vet"
+    }
+}
+
+impl Default for GoVetConverter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Converter for `staticcheck -f json` output
+///
+/// Staticcheck prints one object per line with `code`, `severity`,
+/// `location`/`end` (one-based line and column) and optional `related` notes.
+pub struct StaticcheckConverter;
+
+impl StaticcheckConverter {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Whether the data has the shape of staticcheck's JSON output
+    pub fn matches(data: &Value) -> bool {
+        findings(data)
+            .first()
+            .is_some_and(|f| f.get("code").is_some() && f.get("location").and_then(|l| l.get("file")).is_some())
+    }
+}
+
+#[async_trait]
+impl SpecificFormatConverter for StaticcheckConverter {
+    async fn convert(&self, raw: &RawDiagnostics) -> Result<Vec<Diagnostic>, ParseError> {
+        let findings = findings(&raw.data);
+        if findings.is_empty() && !raw.data.is_array() {
+            return Err(ParseError::InvalidFormat {
+                context: "staticcheck output".to_string(),
+                expected: "objects from `staticcheck -f json`".to_string(),
+                found: format!("{:?}", raw.data),
+            });
+        }
+
+        let mut diagnostics = Vec::new();
+        for finding in findings {
+            let severity = match finding.get("severity").and_then(Value::as_str) {
+                Some("error") => DiagnosticSeverity::Error,
+                Some("warning") => DiagnosticSeverity::Warning,
+                // Findings silenced by a `//lint:ignore` directive
+                Some("ignored") => continue,
+                _ => DiagnosticSeverity::Information,
+            };
+            let Some((file, start)) = finding.get("location").and_then(location) else {
+                continue;
+            };
+            let end = finding
+                .get("end")
+                .and_then(location)
+                .map(|(_, end)| end)
+                .unwrap_or(start.clone());
+
+            let related: Vec<RelatedInformation> = finding
+                .get("related")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|note| {
+                    let (uri, start) = note.get("location").and_then(location)?;
+                    let end = note.get("end").and_then(location).map(|(_, end)| end).unwrap_or(start.clone());
+                    Some(RelatedInformation {
+                        location: Location {
+                            uri: normalize_file_path(&uri),
+                            range: Range { start, end },
+                        },
+                        message: note.get("message").and_then(Value::as_str).unwrap_or("").to_string(),
+                    })
+                })
+                .collect();
+
+            diagnostics.push(Diagnostic {
+                id: generate_id("staticcheck", diagnostics.len()),
+                file: normalize_file_path(&file),
+                range: Range { start, end },
+                severity,
+                message: finding.get("message").and_then(Value::as_str).unwrap_or("").to_string(),
+                code: finding.get("code").and_then(Value::as_str).map(str::to_string),
+                source: "staticcheck".to_string(),
+                related_information: (!related.is_empty()).then_some(related),
+                tags: None,
+                data: None,
+            });
+        }
+        Ok(diagnostics)
+    }
+
+    fn can_handle(&self, source: &str) -> bool {
+        source.to_lowercase().contains("staticcheck")
+    }
+
+    fn name(&self) -> &'static str {
+        "staticcheck"
+    }
+}
+
+impl Default for StaticcheckConverter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Per-package objects of vet output: one object, or several when read as a stream
+fn packages(data: &Value) -> impl Iterator<Item = &Value> {
+    let objects: Vec<&Value> = match data {
+        Value::Array(items) => items.iter().collect(),
+        object => vec![object],
+    };
+    objects
+        .into_iter()
+        .filter_map(Value::as_object)
+        .flat_map(|packages| packages.values())
+}
+
+/// Staticcheck findings: one object, or an array of them when read as a stream
+fn findings(data: &Value) -> Vec<&Value> {
+    match data {
+        Value::Array(items) => items.iter().filter(|item| item.is_object()).collect(),
+        Value::Object(_) if data.get("location").is_some() => vec![data],
+        _ => Vec::new(),
+    }
+}
+
+/// `path/file.go:12:3` (one-based) to a file and zero-based position
+fn parse_posn(posn: &str) -> Option<(String, Position)> {
+    let mut parts = posn.rsplitn(3, ':');
+    let last = parts.next()?;
+    let middle = parts.next()?;
+    let (file, line, column) = match (parts.next(), middle.parse::<u32>()) {
+        (Some(file), Ok(line)) => (file, line, last.parse::<u32>().ok()?),
+        // No column: `file.go:12`
+        _ => (middle, last.parse::<u32>().ok()?, 1),
+    };
+    Some((file.to_string(), position(line, column)))
+}
+
+/// A staticcheck `{"file", "line", "column"}` location
+fn location(value: &Value) -> Option<(String, Position)> {
+    let file = value.get("file").and_then(Value::as_str)?;
+    let line = value.get("line").and_then(Value::as_u64)? as u32;
+    // Unknown end positions are reported as line 0
+    if line == 0 {
+        return None;
+    }
+    let column = value.get("column").and_then(Value::as_u64).unwrap_or(1) as u32;
+    Some((file.to_string(), position(line, column)))
+}
+
+fn position(line: u32, column: u32) -> Position {
+    Position {
+        line: line.saturating_sub(1),
+        character: column.saturating_sub(1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::format_converter::utils::parse_json_stream;
+
+    fn raw(source: &str, data: Value) -> RawDiagnostics {
+        RawDiagnostics {
+            source: source.to_string(),
+            data,
+            timestamp: chrono::Utc::now(),
+            workspace: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_go_vet_stream() {
+        let output = r#"# example.com/shop/api
+{
+	"example.com/shop/api": {
+		"printf": [
+			{
+				"posn": "/repo/api/main.go:12:3",
+				"message": "fmt.Sprintf format %d has arg name of wrong type string"
+			}
+		]
+	}
+}
+# example.com/shop/lib
+{
+	"example.com/shop/lib": {
+		"unusedresult": [
+			{ "posn": "/repo/lib/util.go:40:2", "message": "result of fmt.Sprint call not used" }
+		]
+	}
+}
+"#;
+        let data = parse_json_stream(output).unwrap();
+        assert!(GoVetConverter::matches(&data));
+        assert!(!StaticcheckConverter::matches(&data));
+
+        let diagnostics = GoVetConverter.convert(&raw("stdin", data)).await.unwrap();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].file, "/repo/api/main.go");
+        assert_eq!(diagnostics[0].range.start, Position { line: 11, character: 2 });
+        assert_eq!(diagnostics[0].code.as_deref(), Some("printf"));
+        assert_eq!(diagnostics[1].source, "go vet");
+    }
+
+    #[tokio::test]
+    async fn test_staticcheck_json_lines() {
+        let output = concat!(
+            r#"{"code":"SA4006","severity":"error","location":{"file":"/repo/api/main.go","line":20,"column":2},"#,
+            r#""end":{"file":"/repo/api/main.go","line":20,"column":5},"message":"this value of err is never used","#,
+            r#""related":[{"location":{"file":"/repo/api/main.go","line":18,"column":2},"end":{"file":"","line":0,"column":0},"message":"assigned here"}]}"#,
+            "\n",
+            r#"{"code":"ST1003","severity":"ignored","location":{"file":"/repo/api/main.go","line":3,"column":6},"end":{"file":"","line":0,"column":0},"message":"should not use underscores"}"#,
+            "\n",
+        );
+        let data = parse_json_stream(output).unwrap();
+        assert!(StaticcheckConverter::matches(&data));
+
+        let diagnostics = StaticcheckConverter.convert(&raw("stdin", data)).await.unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.severity, DiagnosticSeverity::Error);
+        assert_eq!(diagnostic.range.end, Position { line: 19, character: 4 });
+        let related = diagnostic.related_information.as_ref().unwrap();
+        assert_eq!(related[0].location.range.start, Position { line: 17, character: 1 });
+    }
+}
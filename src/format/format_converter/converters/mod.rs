@@ -2,10 +2,12 @@
 
 pub mod eslint;
 pub mod generic_lsp;
+pub mod go;
 pub mod rust_analyzer;
 pub mod typescript;
 
 pub use eslint::ESLintConverter;
 pub use generic_lsp::GenericLSPConverter;
+pub use go::{GoVetConverter, StaticcheckConverter};
 pub use rust_analyzer::RustAnalyzerConverter;
 pub use typescript::TypeScriptConverter;
\ No newline at end of file
@@ -1,7 +1,8 @@
 //! Factory for creating format-specific converters
 
 use crate::format::format_converter::converters::{
-    ESLintConverter, GenericLSPConverter, RustAnalyzerConverter, TypeScriptConverter,
+    ESLintConverter, GenericLSPConverter, GoVetConverter, RustAnalyzerConverter, StaticcheckConverter,
+    TypeScriptConverter,
 };
 use crate::format::format_converter::types::{SourceType, SpecificFormatConverter};
 use serde_json::Value;
@@ -12,6 +13,8 @@ pub struct ConverterFactory {
     typescript_converter: Arc<dyn SpecificFormatConverter>,
     rust_converter: Arc<dyn SpecificFormatConverter>,
     eslint_converter: Arc<dyn SpecificFormatConverter>,
+    go_vet_converter: Arc<dyn SpecificFormatConverter>,
+    staticcheck_converter: Arc<dyn SpecificFormatConverter>,
     generic_converter: Arc<dyn SpecificFormatConverter>,
 }
 
@@ -22,6 +25,8 @@ impl ConverterFactory {
             typescript_converter: Arc::new(TypeScriptConverter::new()),
             rust_converter: Arc::new(RustAnalyzerConverter::new()),
             eslint_converter: Arc::new(ESLintConverter::new()),
+            go_vet_converter: Arc::new(GoVetConverter::new()),
+            staticcheck_converter: Arc::new(StaticcheckConverter::new()),
             generic_converter: Arc::new(GenericLSPConverter::new()),
         }
     }
@@ -32,6 +37,8 @@ impl ConverterFactory {
             SourceType::TypeScript => self.typescript_converter.clone(),
             SourceType::RustAnalyzer => self.rust_converter.clone(),
             SourceType::ESLint => self.eslint_converter.clone(),
+            SourceType::GoVet => self.go_vet_converter.clone(),
+            SourceType::Staticcheck => self.staticcheck_converter.clone(),
             SourceType::GenericLSP(_) => self.generic_converter.clone(),
         }
     }
@@ -40,6 +47,14 @@ impl ConverterFactory {
     pub fn get_converter_by_source(&self, source: &str) -> Arc<dyn SpecificFormatConverter> {
         let source_lower = source.to_lowercase();
 
+        // Before TypeScript, whose match on "ts" is loose
+        if self.staticcheck_converter.can_handle(&source_lower) {
+            return self.staticcheck_converter.clone();
+        }
+        if self.go_vet_converter.can_handle(&source_lower) {
+            return self.go_vet_converter.clone();
+        }
+
         if self.typescript_converter.can_handle(&source_lower) {
             self.typescript_converter.clone()
         } else if self.rust_converter.can_handle(&source_lower) {
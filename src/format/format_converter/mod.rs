@@ -33,6 +33,8 @@ impl FormatConverter {
             SourceType::TypeScript => "typescript".to_string(),
             SourceType::RustAnalyzer => "rust-analyzer".to_string(),
             SourceType::ESLint => "eslint".to_string(),
+            SourceType::GoVet => "go vet".to_string(),
+            SourceType::Staticcheck => "staticcheck".to_string(),
             SourceType::GenericLSP(_) => "lsp-generic".to_string(),
         }
     }
@@ -41,7 +43,11 @@ impl FormatConverter {
 #[async_trait]
 impl FormatConverterTrait for FormatConverter {
     async fn normalize(&self, raw: RawDiagnostics) -> Result<Vec<Diagnostic>, ParseError> {
-        let converter = self.factory.get_converter_by_source(&raw.source);
+        // Go analyzer output piped in on stdin is recognised by its shape
+        let converter = match self.factory.detect_source_type(&raw.data, &raw.source) {
+            source_type @ (SourceType::GoVet | SourceType::Staticcheck) => self.factory.get_converter(&source_type),
+            _ => self.factory.get_converter_by_source(&raw.source),
+        };
         converter.convert(&raw).await
     }
 
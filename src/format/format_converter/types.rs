@@ -2,6 +2,7 @@
 
 use crate::core::errors::ParseError;
 use crate::core::{Diagnostic, RawDiagnostics};
+use crate::format::format_converter::converters::{GoVetConverter, StaticcheckConverter};
 use async_trait::async_trait;
 use serde_json::Value;
 
@@ -24,6 +25,8 @@ pub enum SourceType {
     TypeScript,
     RustAnalyzer,
     ESLint,
+    GoVet,
+    Staticcheck,
     GenericLSP(String), // Contains the actual source name
 }
 
@@ -32,6 +35,14 @@ impl SourceType {
     pub fn detect(data: &Value, source: &str) -> Self {
         // First check explicit source string
         let source_lower = source.to_lowercase();
+
+        if source_lower.contains("staticcheck") {
+            return SourceType::Staticcheck;
+        }
+
+        if source_lower.contains("go vet") || source_lower.contains("govet") || source_lower.contains("go-vet") {
+            return SourceType::GoVet;
+        }
         
         if source_lower.contains("typescript") || source_lower.contains("ts") {
             return SourceType::TypeScript;
@@ -46,6 +57,13 @@ impl SourceType {
         }
         
         // Then try to detect from data structure
+        if StaticcheckConverter::matches(data) {
+            return SourceType::Staticcheck;
+        }
+        if GoVetConverter::matches(data) {
+            return SourceType::GoVet;
+        }
+
         if let Some(obj) = data.as_object() {
             if obj.contains_key("diagnostics") {
                 if let Some(first) = obj["diagnostics"].as_array().and_then(|arr| arr.first()) {
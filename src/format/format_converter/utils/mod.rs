@@ -6,8 +6,36 @@ pub mod severity_converter;
 pub use range_converter::RangeConverter;
 pub use severity_converter::SeverityConverter;
 
+use serde_json::Value;
 use uuid::Uuid;
 
+/// Parse analyzer output that may be one JSON document or a stream of them
+///
+/// Tools such as `staticcheck -f json` print one object per line, and
+/// `go vet -json` prints one object per package after a `# package` comment
+/// line. A single document is returned as is; a stream becomes an array.
+pub fn parse_json_stream(input: &str) -> serde_json::Result<Value> {
+    match serde_json::from_str(input) {
+        Ok(value) => Ok(value),
+        Err(single_error) => {
+            let stripped: String = input
+                .lines()
+                .filter(|line| !line.trim_start().starts_with('#'))
+                .map(|line| format!("{line}\n"))
+                .collect();
+            let values = serde_json::Deserializer::from_str(&stripped)
+                .into_iter::<Value>()
+                .collect::<serde_json::Result<Vec<_>>>()
+                .map_err(|_| single_error)?;
+            match values.len() {
+                0 => serde_json::from_str(input),
+                1 => Ok(values.into_iter().next().unwrap_or_default()),
+                _ => Ok(Value::Array(values)),
+            }
+        }
+    }
+}
+
 /// Generate a unique ID for a diagnostic
 pub fn generate_id(source: &str, _index: usize) -> String {
     format!("{}_{}", source, Uuid::new_v4())
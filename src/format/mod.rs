@@ -1,3 +1,8 @@
+pub mod context_selection;
 pub mod format_converter;
+pub mod token_estimator;
 
+pub use context_selection::{BlockKind, ContextBlock, ContextSelection, ContextSelector};
+pub use format_converter::utils::parse_json_stream;
 pub use format_converter::FormatConverter;
+pub use token_estimator::{ModelFamily, TokenEstimate, TokenEstimator, TokenizerProfile};
@@ -0,0 +1,263 @@
+//! Token and cost estimation for AI-oriented exports
+//!
+//! Exact token counts require each vendor's tokenizer, which is far too heavy
+//! to ship. Instead every model family gets a [`TokenizerProfile`] describing
+//! how many characters a token covers for prose and for code, its context
+//! window and its input price. Estimates are typically within 10-15% of the
+//! real tokenizer, which is enough for budgeting and cost warnings.
+//!
+//! Code is denser in tokens than prose (identifiers are split, punctuation is
+//! usually its own token), so text with a high symbol ratio uses the profile's
+//! code ratio.
+
+use crate::core::{Diagnostic, DiagnosticSeverity};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Share of non-whitespace characters that must be symbols for text to be treated as code
+const CODE_SYMBOL_RATIO: f64 = 0.12;
+
+/// Model families with known tokenizer characteristics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ModelFamily {
+    /// Anthropic Claude models
+    Claude,
+    /// OpenAI GPT-4o / o-series (o200k tokenizer)
+    Gpt4o,
+    /// OpenAI GPT-4 / GPT-3.5 (cl100k tokenizer)
+    Gpt4,
+    /// Google Gemini models
+    Gemini,
+    /// Llama and other SentencePiece-based open models
+    Llama,
+}
+
+impl fmt::Display for ModelFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ModelFamily::Claude => "claude",
+            ModelFamily::Gpt4o => "gpt-4o",
+            ModelFamily::Gpt4 => "gpt-4",
+            ModelFamily::Gemini => "gemini",
+            ModelFamily::Llama => "llama",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl ModelFamily {
+    /// Default tokenizer profile for this family
+    pub fn profile(self) -> TokenizerProfile {
+        let (prose, code, window, cost) = match self {
+            ModelFamily::Claude => (3.5, 2.8, 200_000, 3.0),
+            ModelFamily::Gpt4o => (4.0, 3.2, 128_000, 2.5),
+            ModelFamily::Gpt4 => (3.8, 3.0, 128_000, 10.0),
+            ModelFamily::Gemini => (4.0, 3.0, 1_000_000, 1.25),
+            ModelFamily::Llama => (3.6, 2.7, 128_000, 0.0),
+        };
+        TokenizerProfile {
+            family: self,
+            prose_chars_per_token: prose,
+            code_chars_per_token: code,
+            context_window: window,
+            // Quality degrades well before the hard limit; warn at a quarter of it
+            practical_window: window / 4,
+            input_cost_per_million: cost,
+        }
+    }
+}
+
+/// Tokenizer approximation for a model family
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenizerProfile {
+    /// Model family this profile describes
+    pub family: ModelFamily,
+    /// Average characters per token for natural language
+    pub prose_chars_per_token: f64,
+    /// Average characters per token for source code
+    pub code_chars_per_token: f64,
+    /// Hard context window, in tokens
+    pub context_window: usize,
+    /// Size beyond which a single item is unlikely to be used well, in tokens
+    pub practical_window: usize,
+    /// Input price in USD per million tokens
+    pub input_cost_per_million: f64,
+}
+
+/// Token and cost estimate for a piece of output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenEstimate {
+    /// Model family used for the estimate
+    pub model: ModelFamily,
+    /// Estimated number of tokens
+    pub tokens: usize,
+    /// Estimated input cost in USD
+    pub estimated_cost_usd: f64,
+    /// Context window of the model, in tokens
+    pub context_window: usize,
+}
+
+/// Estimates tokens and cost for a model family
+#[derive(Debug, Clone)]
+pub struct TokenEstimator {
+    profile: TokenizerProfile,
+}
+
+impl TokenEstimator {
+    /// Create an estimator with the default profile for a model family
+    pub fn new(family: ModelFamily) -> Self {
+        Self {
+            profile: family.profile(),
+        }
+    }
+
+    /// Create an estimator from a custom profile
+    pub fn with_profile(profile: TokenizerProfile) -> Self {
+        Self { profile }
+    }
+
+    /// The tokenizer profile in use
+    pub fn profile(&self) -> &TokenizerProfile {
+        &self.profile
+    }
+
+    /// Estimate the number of tokens in a piece of text
+    pub fn estimate(&self, text: &str) -> usize {
+        let mut visible = 0usize;
+        let mut symbols = 0usize;
+        let mut newlines = 0usize;
+        for c in text.chars() {
+            if c == '\n' {
+                newlines += 1;
+            } else if !c.is_whitespace() {
+                visible += 1;
+                if !c.is_alphanumeric() {
+                    symbols += 1;
+                }
+            }
+        }
+        if visible == 0 {
+            return newlines;
+        }
+
+        let chars_per_token = if symbols as f64 / visible as f64 >= CODE_SYMBOL_RATIO {
+            self.profile.code_chars_per_token
+        } else {
+            self.profile.prose_chars_per_token
+        };
+        // Spaces are mostly merged into the following token; newlines are not
+        (visible as f64 / chars_per_token).ceil() as usize + newlines
+    }
+
+    /// Estimated input cost of a number of tokens, in USD
+    pub fn cost(&self, tokens: usize) -> f64 {
+        tokens as f64 * self.profile.input_cost_per_million / 1_000_000.0
+    }
+
+    /// Full estimate for a piece of text
+    pub fn estimate_text(&self, text: &str) -> TokenEstimate {
+        let tokens = self.estimate(text);
+        TokenEstimate {
+            model: self.profile.family,
+            tokens,
+            estimated_cost_usd: self.cost(tokens),
+            context_window: self.profile.context_window,
+        }
+    }
+
+    /// Whether a single item exceeds the model's practical window
+    pub fn exceeds_practical_window(&self, tokens: usize) -> bool {
+        tokens > self.profile.practical_window
+    }
+
+    /// Pick which diagnostics fit in a token budget, most relevant first
+    ///
+    /// Relevance is severity first, then the given order (so callers can
+    /// pre-sort by priority). Items that do not fit are skipped so smaller,
+    /// less severe diagnostics can still use the remaining budget. Returns a
+    /// keep flag per input diagnostic.
+    pub fn select_within_budget(
+        &self,
+        diagnostics: &[&Diagnostic],
+        costs: &[usize],
+        budget: usize,
+    ) -> Vec<bool> {
+        let mut order: Vec<usize> = (0..diagnostics.len()).collect();
+        order.sort_by_key(|&i| severity_rank(diagnostics[i].severity));
+
+        let mut keep = vec![false; diagnostics.len()];
+        let mut used = 0usize;
+        for i in order {
+            if used + costs[i] <= budget {
+                used += costs[i];
+                keep[i] = true;
+            }
+        }
+        keep
+    }
+}
+
+fn severity_rank(severity: DiagnosticSeverity) -> u8 {
+    match severity {
+        DiagnosticSeverity::Error => 0,
+        DiagnosticSeverity::Warning => 1,
+        DiagnosticSeverity::Information => 2,
+        DiagnosticSeverity::Hint => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Position, Range};
+
+    fn diagnostic(severity: DiagnosticSeverity) -> Diagnostic {
+        Diagnostic::new(
+            "src/lib.rs".to_string(),
+            Range {
+                start: Position { line: 0, character: 0 },
+                end: Position { line: 0, character: 1 },
+            },
+            severity,
+            "message".to_string(),
+            "rustc".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_code_is_denser_than_prose() {
+        let estimator = TokenEstimator::new(ModelFamily::Claude);
+        let prose = "the quick brown fox jumps over the lazy dog again and again";
+        let code = "fn f(x:&mut Vec<u8>)->Result<(),E>{x.push(1);Ok(())}";
+
+        let prose_ratio = prose.len() as f64 / estimator.estimate(prose) as f64;
+        let code_ratio = code.len() as f64 / estimator.estimate(code) as f64;
+        assert!(code_ratio < prose_ratio);
+        assert_eq!(estimator.estimate(""), 0);
+    }
+
+    #[test]
+    fn test_cost_scales_with_model() {
+        let text = "error[E0308]: mismatched types\n".repeat(100);
+        let claude = TokenEstimator::new(ModelFamily::Claude).estimate_text(&text);
+        let llama = TokenEstimator::new(ModelFamily::Llama).estimate_text(&text);
+
+        assert!(claude.estimated_cost_usd > 0.0);
+        assert_eq!(llama.estimated_cost_usd, 0.0);
+        assert_eq!(claude.context_window, 200_000);
+    }
+
+    #[test]
+    fn test_budget_prefers_errors_and_fills_remaining_space() {
+        let estimator = TokenEstimator::new(ModelFamily::Claude);
+        let warning = diagnostic(DiagnosticSeverity::Warning);
+        let error = diagnostic(DiagnosticSeverity::Error);
+        let hint = diagnostic(DiagnosticSeverity::Hint);
+        let diagnostics = vec![&warning, &error, &hint];
+
+        let keep = estimator.select_within_budget(&diagnostics, &[60, 50, 10], 65);
+        assert_eq!(keep, vec![false, true, true]);
+    }
+}
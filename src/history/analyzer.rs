@@ -1,10 +1,12 @@
-use crate::core::DiagnosticSeverity;
+use crate::analyzers::DiagnosticTaxonomy;
+use crate::core::{Diagnostic, DiagnosticFilter, DiagnosticSeverity, Position, Range};
 use crate::history::storage::{
-    DiagnosticSnapshot, HistoricalErrorPattern, HistoryStorage, TimeSeriesPoint,
+    AnnotationMode, DiagnosticSnapshot, HistoricalErrorPattern, HistoryAnnotation, HistoryStorage,
+    TimeSeriesPoint,
 };
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
@@ -19,6 +21,25 @@ pub struct TrendAnalysis {
     pub fix_time_estimates: HashMap<DiagnosticCategory, Duration>,
     pub trend_direction: TrendDirection,
     pub health_score: f32, // 0.0 (worst) to 1.0 (best)
+    /// Recurring diagnostics grouped by shared taxonomy, most frequent first
+    #[serde(default)]
+    pub taxonomy_breakdown: Vec<TaxonomyTrend>,
+    /// Annotated windows overlapping the analysed period
+    #[serde(default)]
+    pub annotations: Vec<HistoryAnnotation>,
+    /// Hourly buckets left out because they fall inside an annotated window
+    #[serde(default)]
+    pub excluded_buckets: usize,
+}
+
+/// Recurring diagnostics in one taxonomy bucket, split by diagnostic source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxonomyTrend {
+    pub taxonomy: DiagnosticTaxonomy,
+    pub patterns: usize,
+    pub occurrences: usize,
+    /// Occurrences per diagnostic source (e.g. `rustc`, `typescript`)
+    pub by_source: BTreeMap<String, usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,6 +80,29 @@ pub enum DiagnosticCategory {
     Other,
 }
 
+/// What a trend analysis or forecast covers
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrendOptions {
+    /// Whether annotated windows are left out or only marked
+    #[serde(default)]
+    pub annotations: AnnotationMode,
+    /// Only count recorded diagnostics that pass this filter
+    #[serde(default)]
+    pub filter: DiagnosticFilter,
+}
+
+impl TrendOptions {
+    pub fn with_annotations(mut self, annotations: AnnotationMode) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    pub fn with_filter(mut self, filter: DiagnosticFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
 pub struct TrendAnalyzer {
     storage: Arc<HistoryStorage>,
 }
@@ -72,26 +116,55 @@ impl TrendAnalyzer {
         &self,
         time_window: Duration,
         min_samples: usize,
+    ) -> Result<TrendAnalysis> {
+        self.analyze_trends_with(time_window, min_samples, &TrendOptions::default())
+            .await
+    }
+
+    /// Analyze trends over filtered diagnostics, excluding or only marking
+    /// annotated windows
+    pub async fn analyze_trends_with(
+        &self,
+        time_window: Duration,
+        min_samples: usize,
+        options: &TrendOptions,
     ) -> Result<TrendAnalysis> {
         let end_time = SystemTime::now();
         let start_time = end_time - time_window;
 
         // Get time series data
         let interval = Duration::from_secs(3600); // 1 hour buckets
-        let time_series = self
-            .storage
-            .get_time_series_data(start_time, end_time, interval)
-            .await?;
+        let mut time_series = if options.filter.is_empty() {
+            self.storage
+                .get_time_series_data(start_time, end_time, interval)
+                .await?
+        } else {
+            // Counts have to be recomputed from the recorded diagnostics
+            let snapshots = self.storage.get_snapshots_between(start_time, end_time).await?;
+            filtered_time_series(&snapshots, &options.filter, interval)
+        };
+
+        let annotations = self.storage.get_annotations_between(start_time, end_time).await?;
+        let mut excluded_buckets = 0;
+        if options.annotations == AnnotationMode::Exclude {
+            time_series = exclude_annotated(time_series, &annotations, interval);
+            excluded_buckets = annotated_buckets(start_time, end_time, &annotations, interval);
+        }
 
-        // Calculate velocities
+        // Calculate velocities over the time that is still counted
+        let counted_window = time_window
+            .saturating_sub(interval * excluded_buckets as u32)
+            .max(interval);
         let (error_velocity, warning_velocity) =
-            self.calculate_velocities(&time_series, time_window);
+            self.calculate_velocities(&time_series, counted_window);
 
         // Find hot spots
         let hot_spots = self.identify_hot_spots(start_time, end_time).await?;
 
         // Analyze recurring patterns
-        let recurring_issues = self.analyze_recurring_patterns(min_samples).await?;
+        let error_patterns = self.storage.get_recurring_patterns(min_samples).await?;
+        let taxonomy_breakdown = taxonomy_breakdown(&error_patterns);
+        let recurring_issues = self.analyze_recurring_patterns(error_patterns);
 
         // Estimate fix times
         let fix_time_estimates = self.estimate_fix_times(&time_series).await?;
@@ -110,6 +183,9 @@ impl TrendAnalyzer {
             fix_time_estimates,
             trend_direction,
             health_score,
+            taxonomy_breakdown,
+            annotations,
+            excluded_buckets,
         })
     }
 
@@ -118,11 +194,36 @@ impl TrendAnalyzer {
         file_path: &Path,
         time_window: Duration,
     ) -> Result<FileTrendReport> {
-        let snapshots = self
+        self.analyze_file_trends_with(file_path, time_window, &TrendOptions::default())
+            .await
+    }
+
+    /// Analyze and forecast a file's trend over filtered diagnostics,
+    /// excluding or only marking annotated windows
+    pub async fn analyze_file_trends_with(
+        &self,
+        file_path: &Path,
+        time_window: Duration,
+        options: &TrendOptions,
+    ) -> Result<FileTrendReport> {
+        let end_time = SystemTime::now();
+        let start_time = end_time - time_window;
+        let mut snapshots = self
             .storage
-            .get_snapshots_for_file(file_path, Some(SystemTime::now() - time_window), None)
+            .get_snapshots_for_file(file_path, Some(start_time), None)
             .await?;
 
+        let annotations = self.storage.get_annotations_between(start_time, end_time).await?;
+        if options.annotations == AnnotationMode::Exclude {
+            snapshots.retain(|snapshot| !annotations.iter().any(|a| a.contains(snapshot.timestamp)));
+        }
+        if !options.filter.is_empty() {
+            snapshots = snapshots
+                .into_iter()
+                .filter_map(|snapshot| filter_snapshot(snapshot, &options.filter))
+                .collect();
+        }
+
         if snapshots.is_empty() {
             return Ok(FileTrendReport {
                 file_path: file_path.to_path_buf(),
@@ -131,6 +232,7 @@ impl TrendAnalyzer {
                 warning_trend: Vec::new(),
                 volatility: 0.0,
                 predictions: FilePredictions::default(),
+                annotations,
             });
         }
 
@@ -159,6 +261,7 @@ impl TrendAnalyzer {
             warning_trend,
             volatility,
             predictions,
+            annotations,
         })
     }
 
@@ -289,10 +392,8 @@ impl TrendAnalyzer {
         Ok(hot_spots)
     }
 
-    async fn analyze_recurring_patterns(&self, min_occurrences: usize) -> Result<Vec<Pattern>> {
-        let error_patterns = self.storage.get_recurring_patterns(min_occurrences).await?;
-
-        let patterns: Vec<Pattern> = error_patterns
+    fn analyze_recurring_patterns(&self, error_patterns: Vec<HistoricalErrorPattern>) -> Vec<Pattern> {
+        error_patterns
             .into_iter()
             .map(|ep| {
                 let days_active = ep
@@ -313,9 +414,7 @@ impl TrendAnalyzer {
                     suggested_action,
                 }
             })
-            .collect();
-
-        Ok(patterns)
+            .collect()
     }
 
     async fn estimate_fix_times(
@@ -382,18 +481,7 @@ impl TrendAnalyzer {
         }
 
         let latest = time_series.last().unwrap();
-
-        // Factor 1: Error density (0.0 to 1.0, inverted)
-        let error_factor = 1.0 / (1.0 + latest.avg_errors as f32 / 10.0);
-
-        // Factor 2: Warning density (0.0 to 1.0, inverted)
-        let warning_factor = 1.0 / (1.0 + latest.avg_warnings as f32 / 20.0);
-
-        // Factor 3: Hot spot count (0.0 to 1.0, inverted)
-        let hot_spot_factor = 1.0 / (1.0 + hot_spots.len() as f32 / 10.0);
-
-        // Weighted average
-        (error_factor * 0.5 + warning_factor * 0.3 + hot_spot_factor * 0.2).clamp(0.0, 1.0)
+        health_score(latest.avg_errors, latest.avg_warnings, hot_spots.len())
     }
 
     fn calculate_volatility(&self, trend: &[(SystemTime, usize)]) -> f32 {
@@ -547,6 +635,9 @@ pub struct FileTrendReport {
     pub warning_trend: Vec<(SystemTime, usize)>,
     pub volatility: f32,
     pub predictions: FilePredictions,
+    /// Annotated windows overlapping the analysed period
+    #[serde(default)]
+    pub annotations: Vec<HistoryAnnotation>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -567,6 +658,151 @@ pub struct HotSpot {
     pub recommendation: String,
 }
 
+/// Keep only a snapshot's diagnostics that pass the filter, recounting
+/// severities; `None` when the snapshot falls outside the filter's time range
+fn filter_snapshot(mut snapshot: DiagnosticSnapshot, filter: &DiagnosticFilter) -> Option<DiagnosticSnapshot> {
+    let captured_at: chrono::DateTime<chrono::Utc> = snapshot.timestamp.into();
+    if !filter.matches_time(captured_at) {
+        return None;
+    }
+
+    snapshot
+        .diagnostics
+        .retain(|diagnostic| filter.matches(diagnostic, captured_at));
+    let count = |severity| {
+        snapshot
+            .diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .count()
+    };
+    snapshot.error_count = count(DiagnosticSeverity::Error);
+    snapshot.warning_count = count(DiagnosticSeverity::Warning);
+    snapshot.info_count = count(DiagnosticSeverity::Information);
+    snapshot.hint_count = count(DiagnosticSeverity::Hint);
+    Some(snapshot)
+}
+
+/// Time series over filtered snapshots, bucketed like
+/// [`HistoryStorage::get_time_series_data`]
+fn filtered_time_series(
+    snapshots: &[DiagnosticSnapshot],
+    filter: &DiagnosticFilter,
+    interval: Duration,
+) -> Vec<TimeSeriesPoint> {
+    let step = interval.as_secs().max(1);
+    let mut buckets: BTreeMap<u64, Vec<DiagnosticSnapshot>> = BTreeMap::new();
+    for snapshot in snapshots {
+        if let Some(filtered) = filter_snapshot(snapshot.clone(), filter) {
+            let secs = filtered
+                .timestamp
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            buckets.entry(secs / step * step).or_default().push(filtered);
+        }
+    }
+
+    buckets
+        .into_iter()
+        .map(|(bucket, snapshots)| {
+            TimeSeriesPoint::from_snapshots(std::time::UNIX_EPOCH + Duration::from_secs(bucket), &snapshots)
+        })
+        .collect()
+}
+
+/// Drop time series buckets that overlap an annotated window
+fn exclude_annotated(
+    points: Vec<TimeSeriesPoint>,
+    annotations: &[HistoryAnnotation],
+    interval: Duration,
+) -> Vec<TimeSeriesPoint> {
+    points
+        .into_iter()
+        .filter(|point| {
+            !annotations
+                .iter()
+                .any(|a| a.overlaps(point.timestamp, point.timestamp + interval))
+        })
+        .collect()
+}
+
+/// Group recurring patterns by shared taxonomy so trends compare across languages
+/// Number of `interval`-aligned buckets in `[start, end)` that overlap an annotated window
+fn annotated_buckets(
+    start: SystemTime,
+    end: SystemTime,
+    annotations: &[HistoryAnnotation],
+    interval: Duration,
+) -> usize {
+    let secs = |time: SystemTime| {
+        time.duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+    };
+    let step = interval.as_secs().max(1);
+    let end = secs(end);
+    (secs(start) / step * step..end)
+        .step_by(step as usize)
+        .filter(|bucket| {
+            let bucket_start = std::time::UNIX_EPOCH + Duration::from_secs(*bucket);
+            annotations
+                .iter()
+                .any(|a| a.overlaps(bucket_start, bucket_start + interval))
+        })
+        .count()
+}
+
+pub fn taxonomy_breakdown(patterns: &[HistoricalErrorPattern]) -> Vec<TaxonomyTrend> {
+    let mut trends: HashMap<DiagnosticTaxonomy, TaxonomyTrend> = HashMap::new();
+
+    for pattern in patterns {
+        let source = pattern.source.clone().unwrap_or_else(|| "unknown".to_string());
+        let mut diagnostic = Diagnostic::new(
+            String::new(),
+            Range {
+                start: Position { line: 0, character: 0 },
+                end: Position { line: 0, character: 0 },
+            },
+            DiagnosticSeverity::Error,
+            pattern.error_message.clone(),
+            source.clone(),
+        );
+        diagnostic.code = pattern.error_code.clone();
+        let taxonomy = DiagnosticTaxonomy::classify(&diagnostic);
+
+        let trend = trends.entry(taxonomy).or_insert_with(|| TaxonomyTrend {
+            taxonomy,
+            patterns: 0,
+            occurrences: 0,
+            by_source: BTreeMap::new(),
+        });
+        trend.patterns += 1;
+        trend.occurrences += pattern.occurrence_count;
+        *trend.by_source.entry(source).or_default() += pattern.occurrence_count;
+    }
+
+    let mut trends: Vec<_> = trends.into_values().collect();
+    trends.sort_by(|a, b| b.occurrences.cmp(&a.occurrences).then(a.taxonomy.cmp(&b.taxonomy)));
+    trends
+}
+
+/// Health score from 0.0 (worst) to 1.0 (best) for per-file error and warning
+/// averages and a hot spot count
+pub fn health_score(avg_errors: f64, avg_warnings: f64, hot_spots: usize) -> f32 {
+    // Factor 1: Error density (0.0 to 1.0, inverted)
+    let error_factor = 1.0 / (1.0 + avg_errors as f32 / 10.0);
+
+    // Factor 2: Warning density (0.0 to 1.0, inverted)
+    let warning_factor = 1.0 / (1.0 + avg_warnings as f32 / 20.0);
+
+    // Factor 3: Hot spot count (0.0 to 1.0, inverted)
+    let hot_spot_factor = 1.0 / (1.0 + hot_spots as f32 / 10.0);
+
+    // Weighted average
+    (error_factor * 0.5 + warning_factor * 0.3 + hot_spot_factor * 0.2).clamp(0.0, 1.0)
+}
+
 #[derive(Debug, Clone)]
 struct FixData {
     pub fix_duration: Duration,
@@ -603,4 +839,159 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_annotated_windows_are_excluded() -> Result<()> {
+        use crate::core::FileHash;
+
+        let temp_dir = TempDir::new()?;
+        let config = HistoryConfig {
+            db_path: temp_dir.path().join("test_history.db"),
+            ..Default::default()
+        };
+        let storage = Arc::new(HistoryStorage::new(config).await?);
+        let now = SystemTime::now();
+        let file = PathBuf::from("/repo/src/generated.rs");
+
+        for (hours_ago, errors) in [(30, 1), (5, 40), (1, 2)] {
+            storage
+                .record_snapshot(DiagnosticSnapshot {
+                    id: 0,
+                    timestamp: now - Duration::from_secs(hours_ago * 3600),
+                    file_path: file.clone(),
+                    file_hash: FileHash::new(b"generated"),
+                    diagnostics: vec![],
+                    error_count: errors,
+                    warning_count: 0,
+                    info_count: 0,
+                    hint_count: 0,
+                })
+                .await?;
+        }
+        // Exactly the hourly bucket holding the spike
+        let spike = (now - Duration::from_secs(5 * 3600))
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let bucket = std::time::UNIX_EPOCH + Duration::from_secs(spike / 3600 * 3600);
+        let codegen = HistoryAnnotation::new(bucket, bucket + Duration::from_secs(3599), "codegen migration");
+        storage.add_annotation(&codegen).await?;
+
+        let analyzer = TrendAnalyzer::new(storage.clone());
+        let window = Duration::from_secs(24 * 3600);
+
+        let marked = analyzer.analyze_trends(window, 5).await?;
+        assert_eq!(marked.annotations.len(), 1);
+        assert_eq!(marked.annotations[0].label, "codegen migration");
+        assert_eq!(marked.excluded_buckets, 0);
+        assert!((marked.error_velocity - 42.0 / 24.0).abs() < 1e-4);
+
+        let exclude = TrendOptions::default().with_annotations(AnnotationMode::Exclude);
+        let excluded = analyzer.analyze_trends_with(window, 5, &exclude).await?;
+        assert_eq!(excluded.excluded_buckets, 1);
+        assert!((excluded.error_velocity - 2.0 / 23.0).abs() < 1e-4);
+
+        let report = analyzer
+            .analyze_file_trends_with(&file, Duration::from_secs(48 * 3600), &exclude)
+            .await?;
+        let counts: Vec<usize> = report.error_trend.iter().map(|(_, count)| *count).collect();
+        assert_eq!(counts, vec![2, 1]);
+        assert_eq!(report.annotations.len(), 1);
+
+        // Annotations entirely outside the window are not reported
+        let recent = analyzer.analyze_trends(Duration::from_secs(3 * 3600), 5).await?;
+        assert!(recent.annotations.is_empty());
+
+        assert!(storage.delete_annotation(storage.get_annotations().await?[0].id).await?);
+        assert!(storage.get_annotations().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_trends_count_only_filtered_diagnostics() -> Result<()> {
+        use crate::core::FileHash;
+
+        let temp_dir = TempDir::new()?;
+        let config = HistoryConfig {
+            db_path: temp_dir.path().join("test_history.db"),
+            ..Default::default()
+        };
+        let storage = Arc::new(HistoryStorage::new(config).await?);
+        let file = PathBuf::from("/repo/src/lib.rs");
+        let diagnostic = |source: &str, severity| {
+            Diagnostic::new(
+                file.to_string_lossy().to_string(),
+                Range {
+                    start: Position { line: 0, character: 0 },
+                    end: Position { line: 0, character: 1 },
+                },
+                severity,
+                "message".to_string(),
+                source.to_string(),
+            )
+        };
+        let diagnostics = vec![
+            diagnostic("rustc", DiagnosticSeverity::Error),
+            diagnostic("clippy", DiagnosticSeverity::Warning),
+            diagnostic("clippy", DiagnosticSeverity::Warning),
+        ];
+        storage
+            .record_snapshot(DiagnosticSnapshot {
+                id: 0,
+                timestamp: SystemTime::now() - Duration::from_secs(60),
+                file_path: file.clone(),
+                file_hash: FileHash::new(b"lib"),
+                diagnostics,
+                error_count: 1,
+                warning_count: 2,
+                info_count: 0,
+                hint_count: 0,
+            })
+            .await?;
+
+        let analyzer = TrendAnalyzer::new(storage);
+        let window = Duration::from_secs(24 * 3600);
+        let clippy = TrendOptions::default().with_filter(DiagnosticFilter {
+            sources: Some(vec!["clippy".to_string()]),
+            ..Default::default()
+        });
+
+        let all = analyzer.analyze_trends(window, 5).await?;
+        let filtered = analyzer.analyze_trends_with(window, 5, &clippy).await?;
+        assert!((all.error_velocity - 1.0 / 24.0).abs() < 1e-4);
+        assert_eq!(filtered.error_velocity, 0.0);
+        assert!((filtered.warning_velocity - 2.0 / 24.0).abs() < 1e-4);
+
+        let report = analyzer.analyze_file_trends_with(&file, window, &clippy).await?;
+        assert_eq!(report.error_trend[0].1, 0);
+        assert_eq!(report.warning_trend[0].1, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_taxonomy_breakdown_spans_languages() {
+        let pattern = |message: &str, code: &str, source: &str, occurrences: usize| HistoricalErrorPattern {
+            pattern_hash: message.to_string(),
+            first_seen: SystemTime::now(),
+            last_seen: SystemTime::now(),
+            occurrence_count: occurrences,
+            files_affected: 1,
+            error_message: message.to_string(),
+            error_code: Some(code.to_string()),
+            source: Some(source.to_string()),
+        };
+
+        let breakdown = taxonomy_breakdown(&[
+            pattern("mismatched types", "E0308", "rustc", 4),
+            pattern("Type 'string' is not assignable to type 'number'.", "2322", "typescript", 3),
+            pattern("use of moved value: `buf`", "E0382", "rustc", 2),
+        ]);
+
+        assert_eq!(breakdown[0].taxonomy, DiagnosticTaxonomy::Typing);
+        assert_eq!(breakdown[0].occurrences, 7);
+        assert_eq!(breakdown[0].by_source.get("typescript"), Some(&3));
+        assert_eq!(breakdown[1].taxonomy, DiagnosticTaxonomy::MemorySafety);
+    }
 }
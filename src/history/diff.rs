@@ -0,0 +1,271 @@
+//! Diagnostic-level comparison of two points in history
+//!
+//! Answers "did my change make things worse": every file recorded at either
+//! point is compared diagnostic by diagnostic. Diagnostics are matched on
+//! severity, source, code and message rather than position, so edits that
+//! only shift lines around don't show up as fixed and reintroduced issues.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use super::storage::{AsOf, DiagnosticSnapshot};
+use crate::core::{Diagnostic, DiagnosticSeverity};
+
+/// How one file's diagnostics differ between two points in history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileDiff {
+    pub file_path: PathBuf,
+    /// Diagnostics only present at the later point
+    pub added: Vec<Diagnostic>,
+    /// Diagnostics only present at the earlier point
+    pub removed: Vec<Diagnostic>,
+    /// Diagnostics present at both points, as recorded at the later one
+    pub persisting: Vec<Diagnostic>,
+}
+
+impl FileDiff {
+    pub fn is_unchanged(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Per-file added, removed and persisting diagnostics between two points
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotDiff {
+    pub from: AsOf,
+    pub to: AsOf,
+    /// Every file recorded at either point, ordered by path
+    pub files: Vec<FileDiff>,
+    pub added: usize,
+    pub removed: usize,
+    pub persisting: usize,
+    /// Errors added minus errors removed
+    pub error_delta: i64,
+}
+
+impl SnapshotDiff {
+    /// Compare the per-file snapshots reconstructed at `from` and `to`
+    pub fn between(from: AsOf, before: &[DiagnosticSnapshot], to: AsOf, after: &[DiagnosticSnapshot]) -> Self {
+        let before: BTreeMap<&PathBuf, &DiagnosticSnapshot> = before.iter().map(|s| (&s.file_path, s)).collect();
+        let after: BTreeMap<&PathBuf, &DiagnosticSnapshot> = after.iter().map(|s| (&s.file_path, s)).collect();
+        let paths: BTreeSet<&PathBuf> = before.keys().chain(after.keys()).copied().collect();
+
+        let files: Vec<FileDiff> = paths
+            .into_iter()
+            .map(|path| {
+                let before = before.get(path).map_or(&[][..], |s| s.diagnostics.as_slice());
+                let after = after.get(path).map_or(&[][..], |s| s.diagnostics.as_slice());
+                diff_file(path.clone(), before, after)
+            })
+            .collect();
+
+        let errors = |diagnostics: &[Diagnostic]| {
+            diagnostics
+                .iter()
+                .filter(|d| d.severity == DiagnosticSeverity::Error)
+                .count() as i64
+        };
+        Self {
+            from,
+            to,
+            added: files.iter().map(|f| f.added.len()).sum(),
+            removed: files.iter().map(|f| f.removed.len()).sum(),
+            persisting: files.iter().map(|f| f.persisting.len()).sum(),
+            error_delta: files.iter().map(|f| errors(&f.added) - errors(&f.removed)).sum(),
+            files,
+        }
+    }
+
+    /// Files with added or removed diagnostics
+    pub fn changed_files(&self) -> impl Iterator<Item = &FileDiff> {
+        self.files.iter().filter(|file| !file.is_unchanged())
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# History Diff ({} → {})\n\n", describe(&self.from), describe(&self.to));
+        let _ = writeln!(
+            out,
+            "**Added**: {} | **Removed**: {} | **Persisting**: {} | **Errors**: {:+}\n",
+            self.added, self.removed, self.persisting, self.error_delta
+        );
+
+        let mut changed = self.changed_files().peekable();
+        if changed.peek().is_none() {
+            out.push_str("_No diagnostics added or removed._\n");
+            return out;
+        }
+        for file in changed {
+            let _ = writeln!(
+                out,
+                "## {} (+{} / -{}, {} persisting)\n",
+                file.file_path.display(),
+                file.added.len(),
+                file.removed.len(),
+                file.persisting.len()
+            );
+            for (marker, diagnostic) in file
+                .added
+                .iter()
+                .map(|d| ('+', d))
+                .chain(file.removed.iter().map(|d| ('-', d)))
+            {
+                let _ = writeln!(
+                    out,
+                    "- {marker} {:?} line {}: {} ({}{})",
+                    diagnostic.severity,
+                    diagnostic.range.start.line + 1,
+                    diagnostic.message,
+                    diagnostic.source,
+                    diagnostic.code.as_deref().map(|code| format!(" {code}")).unwrap_or_default()
+                );
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// What identifies a diagnostic across snapshots, independent of position
+type MatchKey<'a> = (DiagnosticSeverity, &'a str, Option<&'a str>, &'a str);
+
+fn match_key(diagnostic: &Diagnostic) -> MatchKey<'_> {
+    (
+        diagnostic.severity,
+        &diagnostic.source,
+        diagnostic.code.as_deref(),
+        &diagnostic.message,
+    )
+}
+
+/// Pair diagnostics with equal keys in line order; the rest are added or removed
+fn diff_file(file_path: PathBuf, before: &[Diagnostic], after: &[Diagnostic]) -> FileDiff {
+    let mut unmatched: HashMap<MatchKey<'_>, usize> = HashMap::new();
+    for diagnostic in before {
+        *unmatched.entry(match_key(diagnostic)).or_default() += 1;
+    }
+
+    let mut added = Vec::new();
+    let mut persisting = Vec::new();
+    let mut matched: HashMap<MatchKey<'_>, usize> = HashMap::new();
+    for diagnostic in by_line(after) {
+        let key = match_key(diagnostic);
+        match unmatched.get_mut(&key) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                *matched.entry(key).or_default() += 1;
+                persisting.push(diagnostic.clone());
+            }
+            _ => added.push(diagnostic.clone()),
+        }
+    }
+
+    // The earliest occurrences of a key are the ones that persisted
+    let mut removed = Vec::new();
+    for diagnostic in by_line(before) {
+        match matched.get_mut(&match_key(diagnostic)) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => removed.push(diagnostic.clone()),
+        }
+    }
+
+    FileDiff {
+        file_path,
+        added,
+        removed,
+        persisting,
+    }
+}
+
+fn by_line(diagnostics: &[Diagnostic]) -> Vec<&Diagnostic> {
+    let mut sorted: Vec<&Diagnostic> = diagnostics.iter().collect();
+    sorted.sort_by_key(|d| (d.range.start.line, d.range.start.character));
+    sorted
+}
+
+fn describe(as_of: &AsOf) -> String {
+    match as_of {
+        AsOf::Snapshot(id) => format!("snapshot {id}"),
+        AsOf::Time(time) => {
+            let time: chrono::DateTime<chrono::Utc> = (*time).into();
+            time.format("%Y-%m-%d %H:%M UTC").to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{FileHash, Position, Range};
+    use std::time::SystemTime;
+
+    fn diagnostic(line: u32, severity: DiagnosticSeverity, message: &str) -> Diagnostic {
+        Diagnostic::new(
+            "/repo/src/lib.rs".to_string(),
+            Range {
+                start: Position { line, character: 0 },
+                end: Position { line, character: 1 },
+            },
+            severity,
+            message.to_string(),
+            "rustc".to_string(),
+        )
+    }
+
+    fn snapshot(path: &str, diagnostics: Vec<Diagnostic>) -> DiagnosticSnapshot {
+        DiagnosticSnapshot {
+            id: 0,
+            timestamp: SystemTime::now(),
+            file_path: PathBuf::from(path),
+            file_hash: FileHash::new(b""),
+            error_count: 0,
+            warning_count: 0,
+            info_count: 0,
+            hint_count: 0,
+            diagnostics,
+        }
+    }
+
+    #[test]
+    fn test_moved_diagnostics_persist() {
+        use DiagnosticSeverity::{Error, Warning};
+
+        let before = vec![
+            snapshot(
+                "/repo/src/lib.rs",
+                vec![
+                    diagnostic(3, Error, "mismatched types"),
+                    diagnostic(9, Warning, "unused variable"),
+                    diagnostic(12, Warning, "unused variable"),
+                ],
+            ),
+            snapshot("/repo/src/old.rs", vec![diagnostic(1, Error, "cannot find value")]),
+        ];
+        let after = vec![snapshot(
+            "/repo/src/lib.rs",
+            vec![
+                diagnostic(5, Error, "mismatched types"),
+                diagnostic(14, Warning, "unused variable"),
+                diagnostic(20, Error, "borrow of moved value"),
+            ],
+        )];
+
+        let diff = SnapshotDiff::between(AsOf::Snapshot(1), &before, AsOf::Snapshot(2), &after);
+        assert_eq!((diff.added, diff.removed, diff.persisting), (1, 2, 2));
+        assert_eq!(diff.error_delta, 0);
+
+        let lib = &diff.files[0];
+        assert_eq!(lib.added[0].message, "borrow of moved value");
+        assert_eq!(lib.persisting[0].range.start.line, 5);
+        // Of two identical warnings only one persisted; the later one is reported removed
+        assert_eq!(lib.removed.len(), 1);
+        assert_eq!(lib.removed[0].range.start.line, 12);
+
+        let old = &diff.files[1];
+        assert_eq!(old.file_path, PathBuf::from("/repo/src/old.rs"));
+        assert_eq!(old.removed.len(), 1);
+        assert_eq!(diff.changed_files().count(), 2);
+        assert!(diff.to_markdown().contains("- + Error line 21: borrow of moved value (rustc)"));
+    }
+}
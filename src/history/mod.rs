@@ -1,15 +1,31 @@
 pub mod analyzer;
+pub mod diff;
+pub mod owners;
+pub mod pruning;
+pub mod refresh;
+pub mod report;
+pub mod service;
 pub mod storage;
 pub mod visualization;
 
+pub use diff::{FileDiff, SnapshotDiff};
+pub use owners::{OwnerHotSpot, OwnerHotSpotReport};
+pub use pruning::{CleanPreview, HistoryBackup, SnapshotRef, TrendImpact, WindowImpact};
+pub use report::{
+    CommandSummaryProvider, FileChange, ReportAction, ReportFormat, SummaryProvider, WeeklyReport, WeeklyReportArgs,
+};
+pub use service::{HistoryControlHandler, HistoryRequest, HistoryService};
+pub use refresh::{FileReanalyzer, RefreshSummary, StaleFile, StaleFileRefresher, StaleReason};
+
 pub use storage::{
-    DiagnosticSnapshot, FileHistoryStats, HistoricalErrorPattern, HistoryConfig, HistoryStorage,
-    MLDataPoint, TimeSeriesPoint,
+    AnnotationMode, AsOf, CleanupSummary, DiagnosticSnapshot, FileHistoryStats, HistoricalErrorPattern, HistoryAnnotation, HistoryConfig,
+    HistoryStorage, MLDataPoint, MessageMatch, MessageSearch, SearchField, TimeSeriesPoint,
 };
 
 pub use analyzer::{
-    DiagnosticCategory, FilePredictions, FileStats, FileTrendReport, HotSpot, Pattern,
-    TrendAnalysis, TrendAnalyzer, TrendDirection,
+    health_score, taxonomy_breakdown, DiagnosticCategory, FilePredictions, FileStats,
+    FileTrendReport, HotSpot, Pattern, TaxonomyTrend, TrendAnalysis, TrendAnalyzer,
+    TrendDirection, TrendOptions,
 };
 
 pub use visualization::{
@@ -27,6 +43,12 @@ pub enum HistoryAction {
         /// Number of hours to analyze
         #[arg(short = 'h', long, default_value = "24")]
         hours: u64,
+        /// Whether annotated windows are left out of the analysis or only marked
+        #[arg(long, value_enum, default_value = "mark")]
+        annotations: AnnotationMode,
+        /// Only count recorded diagnostics matching these filters
+        #[command(flatten)]
+        filter: crate::cli::DiagnosticFilterArgs,
         /// Output format
         #[arg(short, long, value_enum, default_value = "markdown")]
         format: crate::cli::OutputFormat,
@@ -36,9 +58,15 @@ pub enum HistoryAction {
         /// Maximum number of hot spots to show
         #[arg(short, long, default_value = "10")]
         limit: usize,
+        /// Group the hottest files by their CODEOWNERS owners, with each owner's trend
+        #[arg(long)]
+        by_owner: bool,
+        /// Days of history an owner's trend covers
+        #[arg(long, default_value = "7", requires = "by_owner")]
+        days: u64,
         /// Output format
         #[arg(short, long, value_enum, default_value = "markdown")]
-        format: crate::cli::OutputFormat,
+        format: HotSpotFormat,
     },
     /// Get history for a specific file
     File {
@@ -47,6 +75,44 @@ pub enum HistoryAction {
         /// Number of hours to analyze
         #[arg(short = 'h', long, default_value = "24")]
         hours: u64,
+        /// Whether annotated windows are left out of the trend and forecast or only marked
+        #[arg(long, value_enum, default_value = "mark")]
+        annotations: AnnotationMode,
+        /// Only count recorded diagnostics matching these filters
+        #[command(flatten)]
+        filter: crate::cli::DiagnosticFilterArgs,
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "markdown")]
+        format: crate::cli::OutputFormat,
+    },
+    /// Compare diagnostics at two points in history, file by file
+    Diff {
+        /// Earlier point: a snapshot id, an RFC 3339 timestamp or YYYY-MM-DD (end of day)
+        from: String,
+        /// Later point, in the same forms
+        to: String,
+        /// Exit with an error when the later point has diagnostics the earlier one didn't
+        #[arg(long)]
+        fail_on_added: bool,
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "markdown")]
+        format: crate::cli::OutputFormat,
+    },
+    /// Label a window of history, such as a codegen run, so trends can exclude or mark it
+    Annotate {
+        /// Time range as START..END; each end is an RFC 3339 timestamp or YYYY-MM-DD,
+        /// and an empty END means now
+        #[arg(long)]
+        range: String,
+        /// What happened in the window
+        #[arg(long)]
+        label: String,
+    },
+    /// List annotated windows
+    Annotations {
+        /// Remove the annotation with this id instead of listing
+        #[arg(long)]
+        remove: Option<i64>,
         /// Output format
         #[arg(short, long, value_enum, default_value = "markdown")]
         format: crate::cli::OutputFormat,
@@ -56,9 +122,81 @@ pub enum HistoryAction {
         /// Delete data older than this many days
         #[arg(long, default_value = "30")]
         older_than_days: u32,
+        /// Report what would be removed and the impact on trends without deleting anything
+        #[arg(long)]
+        preview: bool,
+        /// Export the snapshots being removed to a JSON backup before deleting
+        #[arg(long)]
+        backup: bool,
+        /// Backup file path (defaults to the data directory)
+        #[arg(long, requires = "backup")]
+        backup_path: Option<PathBuf>,
+        /// Output format for the preview
+        #[arg(short, long, value_enum, default_value = "markdown")]
+        format: crate::cli::OutputFormat,
+    },
+    /// Take a full backup of a database now, or list its backups
+    Backup {
+        /// Database to back up
+        #[arg(long, value_enum, default_value = "history")]
+        database: BackupDatabase,
+        /// List the backup generations instead of taking one
+        #[arg(long)]
+        list: bool,
+        /// Output format for the list
+        #[arg(short, long, value_enum, default_value = "markdown")]
+        format: crate::cli::OutputFormat,
+    },
+    /// Restore a database to its state at a point in time from its backups
+    Restore {
+        /// RFC 3339 timestamp, or YYYY-MM-DD for the end of that day
+        #[arg(long)]
+        to: String,
+        /// Database to restore
+        #[arg(long, value_enum, default_value = "history")]
+        database: BackupDatabase,
+        /// Show which backup would be used without restoring
+        #[arg(long)]
+        dry_run: bool,
     },
 }
 
+/// Output formats of `history hot-spots`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum HotSpotFormat {
+    #[value(alias = "claude")]
+    Markdown,
+    Json,
+    Csv,
+}
+
+/// Databases covered by automated backups
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BackupDatabase {
+    /// The diagnostic history database
+    History,
+    /// The team collaboration database (`multi_repo.team_db_path`)
+    Team,
+}
+
+impl BackupDatabase {
+    /// Name of the database's directory in the backup destination
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::History => "history",
+            Self::Team => "team",
+        }
+    }
+
+    /// Location of the database, if one is configured
+    pub fn path(self, config: &crate::core::config::UnifiedConfig) -> Option<PathBuf> {
+        match self {
+            Self::History => Some(HistoryConfig::default().db_path),
+            Self::Team => config.multi_repo.team_db_path.clone(),
+        }
+    }
+}
+
 use crate::core::{Diagnostic, FileHash};
 use anyhow::Result;
 use std::path::Path;
@@ -79,6 +217,17 @@ impl HistoryManager {
         Ok(Self { storage, analyzer })
     }
 
+    /// Manager over storage already opened by this process
+    pub fn from_storage(storage: Arc<HistoryStorage>) -> Self {
+        let analyzer = TrendAnalyzer::new(storage.clone());
+        Self { storage, analyzer }
+    }
+
+    /// Storage this manager reads and writes
+    pub fn storage(&self) -> &Arc<HistoryStorage> {
+        &self.storage
+    }
+
     /// Record a new diagnostic snapshot
     pub async fn record_diagnostics(
         &self,
@@ -115,8 +264,8 @@ impl HistoryManager {
     }
 
     /// Get trend analysis for the specified time window
-    pub async fn get_trends(&self, time_window: Duration) -> Result<TrendAnalysis> {
-        self.analyzer.analyze_trends(time_window, 5).await
+    pub async fn get_trends(&self, time_window: Duration, options: &TrendOptions) -> Result<TrendAnalysis> {
+        self.analyzer.analyze_trends_with(time_window, 5, options).await
     }
 
     /// Get file-specific trend analysis
@@ -124,17 +273,67 @@ impl HistoryManager {
         &self,
         file_path: &Path,
         time_window: Duration,
+        options: &TrendOptions,
     ) -> Result<FileTrendReport> {
         self.analyzer
-            .analyze_file_trends(file_path, time_window)
+            .analyze_file_trends_with(file_path, time_window, options)
             .await
     }
 
+    /// Label a window of history, returning the annotation id
+    pub async fn annotate(&self, start: SystemTime, end: SystemTime, label: &str) -> Result<i64> {
+        if end < start {
+            return Err(anyhow::anyhow!("Annotation ends before it starts"));
+        }
+        if label.trim().is_empty() {
+            return Err(anyhow::anyhow!("Annotation label must not be empty"));
+        }
+        Ok(self
+            .storage
+            .add_annotation(&HistoryAnnotation::new(start, end, label.trim()))
+            .await?)
+    }
+
+    /// All annotated windows, oldest first
+    pub async fn get_annotations(&self) -> Result<Vec<HistoryAnnotation>> {
+        Ok(self.storage.get_annotations().await?)
+    }
+
+    /// Remove an annotation, returning whether it existed
+    pub async fn remove_annotation(&self, id: i64) -> Result<bool> {
+        Ok(self.storage.delete_annotation(id).await?)
+    }
+
     /// Get the current hot spots (problem files)
     pub async fn get_hot_spots(&self, limit: usize) -> Result<Vec<HotSpot>> {
         self.analyzer.get_hot_spots(limit).await
     }
 
+    /// Per-file diagnostic counts at `start` and at `end`
+    pub async fn get_file_changes(&self, start: SystemTime, end: SystemTime) -> Result<Vec<FileChange>> {
+        let before = self.storage.reconstruct(AsOf::Time(start)).await?;
+        let after = self.storage.reconstruct(AsOf::Time(end)).await?;
+        Ok(report::file_changes(&before, &after))
+    }
+
+    /// Diagnostics added, removed and persisting per file between two points
+    ///
+    /// A time before anything was recorded counts as no diagnostics; a
+    /// snapshot id that doesn't exist is an error.
+    pub async fn diff_snapshots(&self, from: AsOf, to: AsOf) -> Result<SnapshotDiff> {
+        let before = self.storage.reconstruct(from).await?;
+        let after = self.storage.reconstruct(to).await?;
+        for (as_of, snapshots) in [(from, &before), (to, &after)] {
+            if let (AsOf::Snapshot(id), true) = (as_of, snapshots.is_empty()) {
+                return Err(anyhow::anyhow!("No history snapshot with id {id}"));
+            }
+        }
+        if before.is_empty() && after.is_empty() {
+            return Err(anyhow::anyhow!("No diagnostics history recorded at either point"));
+        }
+        Ok(SnapshotDiff::between(from, &before, to, &after))
+    }
+
     /// Predict fix time for a category of diagnostics
     pub async fn predict_fix_time(&self, category: DiagnosticCategory) -> Result<Duration> {
         self.analyzer.predict_fix_time(category).await
@@ -181,10 +380,27 @@ impl HistoryManager {
     }
 
     /// Clean old data from the history storage
-    pub async fn clean_old_data(&self, _cutoff_date: chrono::DateTime<chrono::Utc>) -> Result<usize> {
-        // For now, we don't have a specific method in HistoryStorage for this
-        // This would typically be implemented in the storage layer
-        Ok(0)
+    ///
+    /// Returns the number of snapshots deleted.
+    pub async fn clean_old_data(&self, cutoff_date: chrono::DateTime<chrono::Utc>) -> Result<usize> {
+        let summary = self.storage.delete_before(cutoff_date.into()).await?;
+        Ok(summary.snapshots_deleted)
+    }
+
+    /// Report what `clean_old_data` would remove, without deleting anything
+    pub async fn preview_clean(&self, cutoff_date: chrono::DateTime<chrono::Utc>) -> Result<CleanPreview> {
+        pruning::preview_clean(&self.storage, cutoff_date.into()).await
+    }
+
+    /// Back up the snapshots older than the cutoff to a JSON file
+    ///
+    /// Returns the number of snapshots written.
+    pub async fn backup_before(
+        &self,
+        cutoff_date: chrono::DateTime<chrono::Utc>,
+        path: &Path,
+    ) -> Result<usize> {
+        pruning::backup_before(&self.storage, cutoff_date.into(), path).await
     }
 
     /// Export visualization data
@@ -201,14 +417,18 @@ impl HistoryManager {
         let time_series = self
             .get_time_series(start, end, Duration::from_secs(3600))
             .await?;
-        let trends = self.get_trends(time_window).await?;
+        let trends = self.get_trends(time_window, &TrendOptions::default()).await?;
         let hot_spots = self.get_hot_spots(10).await?;
 
         // Create visualization data
         let mut all_charts = Vec::new();
 
         // Add time series visualization
-        let ts_viz = VisualizationExporter::export_time_series(&time_series, "Diagnostic Trends")?;
+        let ts_viz = VisualizationExporter::export_time_series_with_annotations(
+            &time_series,
+            "Diagnostic Trends",
+            &trends.annotations,
+        )?;
         all_charts.extend(ts_viz.charts);
 
         // Add hot spots visualization
@@ -299,7 +519,7 @@ mod tests {
 
         // Test getting trends
         let trends = manager
-            .get_trends(Duration::from_secs(24 * 60 * 60))
+            .get_trends(Duration::from_secs(24 * 60 * 60), &TrendOptions::default())
             .await?;
         assert_eq!(trends.error_velocity, 0.0);
 
@@ -0,0 +1,273 @@
+//! Hot spots grouped by code owner
+//!
+//! Overlays the repository's CODEOWNERS on the files with the most open
+//! diagnostics, so tech-debt work can be staffed by the teams whose files
+//! are hottest. Each owner's trend compares all of their files at both ends
+//! of the window, not just the hot ones.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::SystemTime;
+
+use super::analyzer::TrendDirection;
+use super::report::FileChange;
+use crate::core::OwnershipMap;
+
+/// Owner name used for files no CODEOWNERS rule matches
+pub const UNOWNED: &str = "(unowned)";
+
+/// Relative score change below which an owner's files count as stable
+const STABLE_CHANGE: f64 = 0.1;
+
+/// One owner's share of the hot spots
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OwnerHotSpot {
+    /// CODEOWNERS owner (`@org/team`, `@user` or an email), or [`UNOWNED`]
+    pub owner: String,
+    /// The owner's files among the hottest, hottest first
+    pub hot_files: Vec<FileChange>,
+    /// Totals over every file the owner has history for
+    pub errors_before: usize,
+    pub errors_after: usize,
+    pub warnings_before: usize,
+    pub warnings_after: usize,
+    pub trend: TrendDirection,
+}
+
+impl OwnerHotSpot {
+    /// Weighted score of the owner's hot files, errors counting double
+    pub fn hot_score(&self) -> usize {
+        self.hot_files.iter().map(FileChange::score).sum()
+    }
+
+    fn new(owner: &str) -> Self {
+        Self {
+            owner: owner.to_string(),
+            hot_files: Vec::new(),
+            errors_before: 0,
+            errors_after: 0,
+            warnings_before: 0,
+            warnings_after: 0,
+            trend: TrendDirection::Stable,
+        }
+    }
+}
+
+/// Hot spots over a window, grouped by the owners of the files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OwnerHotSpotReport {
+    pub start: SystemTime,
+    pub end: SystemTime,
+    /// Number of hot files considered
+    pub hot_files: usize,
+    /// Owners of at least one hot file, highest hot score first
+    pub owners: Vec<OwnerHotSpot>,
+}
+
+impl OwnerHotSpotReport {
+    /// Group the `limit` hottest files of `changes` by owner
+    ///
+    /// A file with several owners counts toward each of them.
+    pub fn build(
+        start: SystemTime,
+        end: SystemTime,
+        changes: &[FileChange],
+        ownership: &OwnershipMap,
+        limit: usize,
+    ) -> Self {
+        let mut hottest: Vec<&FileChange> = changes.iter().filter(|change| change.score() > 0).collect();
+        hottest.sort_by_key(|change| (std::cmp::Reverse(change.score()), change.file_path.clone()));
+        hottest.truncate(limit);
+
+        let owners_of = |change: &FileChange| {
+            let owners = ownership.owners_for(&change.file_path);
+            if owners.is_empty() {
+                vec![UNOWNED.to_string()]
+            } else {
+                owners
+            }
+        };
+
+        let mut by_owner: BTreeMap<String, OwnerHotSpot> = BTreeMap::new();
+        for change in &hottest {
+            for owner in owners_of(change) {
+                by_owner
+                    .entry(owner.clone())
+                    .or_insert_with(|| OwnerHotSpot::new(&owner))
+                    .hot_files
+                    .push((*change).clone());
+            }
+        }
+
+        // Trends take in every file of an owner with a hot file
+        for change in changes {
+            for owner in owners_of(change) {
+                if let Some(entry) = by_owner.get_mut(&owner) {
+                    entry.errors_before += change.errors_before;
+                    entry.errors_after += change.errors_after;
+                    entry.warnings_before += change.warnings_before;
+                    entry.warnings_after += change.warnings_after;
+                }
+            }
+        }
+
+        let mut owners: Vec<OwnerHotSpot> = by_owner
+            .into_values()
+            .map(|mut entry| {
+                entry.trend = trend(
+                    entry.errors_before * 2 + entry.warnings_before,
+                    entry.errors_after * 2 + entry.warnings_after,
+                );
+                entry
+            })
+            .collect();
+        owners.sort_by_key(|entry| std::cmp::Reverse(entry.hot_score()));
+
+        Self {
+            start,
+            end,
+            hot_files: hottest.len(),
+            owners,
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("# Hot Spots by Owner\n\n");
+        if self.owners.is_empty() {
+            out.push_str("_No open diagnostics._\n");
+            return out;
+        }
+
+        let _ = writeln!(out, "{} hot file(s) across {} owner(s).\n", self.hot_files, self.owners.len());
+        out.push_str("| Owner | Hot files | Hot score | Errors | Warnings | Trend |\n");
+        out.push_str("|-------|-----------|-----------|--------|----------|-------|\n");
+        for entry in &self.owners {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} ({:+}) | {} ({:+}) | {:?} |",
+                entry.owner,
+                entry.hot_files.len(),
+                entry.hot_score(),
+                entry.errors_after,
+                entry.errors_after as i64 - entry.errors_before as i64,
+                entry.warnings_after,
+                entry.warnings_after as i64 - entry.warnings_before as i64,
+                entry.trend
+            );
+        }
+
+        for entry in &self.owners {
+            let _ = writeln!(out, "\n## {}\n", entry.owner);
+            for file in &entry.hot_files {
+                let _ = writeln!(
+                    out,
+                    "- `{}`: {} errors ({:+}), {} warnings ({:+})",
+                    file.file_path.display(),
+                    file.errors_after,
+                    file.error_delta(),
+                    file.warnings_after,
+                    file.warning_delta()
+                );
+            }
+        }
+        out
+    }
+
+    /// One row per owner and hot file
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(
+            "owner,owner_trend,owner_errors,owner_warnings,file,errors,errors_before,warnings,warnings_before\n",
+        );
+        for entry in &self.owners {
+            for file in &entry.hot_files {
+                let _ = writeln!(
+                    out,
+                    "{},{:?},{},{},{},{},{},{},{}",
+                    csv_field(&entry.owner),
+                    entry.trend,
+                    entry.errors_after,
+                    entry.warnings_after,
+                    csv_field(&file.file_path.to_string_lossy()),
+                    file.errors_after,
+                    file.errors_before,
+                    file.warnings_after,
+                    file.warnings_before
+                );
+            }
+        }
+        out
+    }
+}
+
+fn trend(before: usize, after: usize) -> TrendDirection {
+    let change = after as f64 - before as f64;
+    if change.abs() <= before as f64 * STABLE_CHANGE {
+        TrendDirection::Stable
+    } else if change > 0.0 {
+        TrendDirection::Degrading
+    } else {
+        TrendDirection::Improving
+    }
+}
+
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains(',') || value.contains('"') || value.contains('\n') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::{Path, PathBuf};
+
+    fn change(file: &str, before: (usize, usize), after: (usize, usize)) -> FileChange {
+        FileChange {
+            file_path: PathBuf::from(file),
+            errors_before: before.0,
+            errors_after: after.0,
+            warnings_before: before.1,
+            warnings_after: after.1,
+        }
+    }
+
+    #[test]
+    fn test_hot_files_grouped_by_owner_with_trends() {
+        let ownership = OwnershipMap::parse(
+            Path::new("/repo"),
+            "/api/ @shop/backend\n/ui/ @shop/frontend\n/shared/ @shop/backend @shop/frontend\n",
+        );
+        let changes = vec![
+            change("/repo/api/orders.rs", (1, 0), (4, 2)),
+            change("/repo/api/users.rs", (0, 0), (0, 1)),
+            change("/repo/ui/cart.tsx", (6, 2), (2, 1)),
+            change("/repo/shared/money.rs", (1, 1), (1, 1)),
+            change("/repo/scripts/gen.py", (0, 0), (1, 0)),
+            change("/repo/ui/fixed.tsx", (3, 0), (0, 0)),
+        ];
+        let now = SystemTime::now();
+        let report = OwnerHotSpotReport::build(now, now, &changes, &ownership, 4);
+
+        assert_eq!(report.hot_files, 4);
+        let owners: Vec<_> = report.owners.iter().map(|o| o.owner.as_str()).collect();
+        assert_eq!(owners, vec!["@shop/backend", "@shop/frontend", UNOWNED]);
+
+        let backend = &report.owners[0];
+        // orders.rs and money.rs are hot; users.rs (score 1) fell outside the top 4
+        assert_eq!(backend.hot_files.len(), 2);
+        assert_eq!(backend.errors_after, 5);
+        assert_eq!(backend.trend, TrendDirection::Degrading);
+
+        // The fixed file still counts toward the frontend trend
+        let frontend = &report.owners[1];
+        assert_eq!(frontend.errors_before, 10);
+        assert_eq!(frontend.trend, TrendDirection::Improving);
+
+        let csv = report.to_csv();
+        assert!(csv.lines().any(|line| line.starts_with("@shop/frontend,Improving,3,2,/repo/ui/cart.tsx,2,6,1,2")));
+        assert!(report.to_markdown().contains("| @shop/backend | 2 | 13 |"));
+    }
+}
@@ -0,0 +1,335 @@
+//! Preview and backup support for history cleanup
+//!
+//! `history clean` permanently deletes snapshots. Before doing so, a
+//! [`CleanPreview`] lists exactly what would be removed (snapshots, file
+//! statistics, error patterns and daily time-series buckets) and estimates
+//! the impact on trend analysis: how much of each standard trend window
+//! would still be covered by data afterwards.
+//!
+//! [`backup_before`] writes the snapshots that a cleanup would delete to a
+//! JSON file so they can be inspected or re-imported later.
+
+use super::storage::{DiagnosticSnapshot, HistoryStorage};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Length of a time-series bucket in the preview
+const BUCKET: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Trend windows reported in the impact estimate, in days
+const TREND_WINDOWS_DAYS: &[u64] = &[1, 7, 30, 90];
+
+/// A snapshot that a cleanup would delete
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotRef {
+    pub id: i64,
+    pub timestamp: SystemTime,
+    pub file_path: PathBuf,
+    pub error_count: usize,
+    pub warning_count: usize,
+}
+
+/// Coverage of a trend window before and after cleanup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowImpact {
+    /// Window length in days
+    pub window_days: u64,
+    /// Fraction of the window covered by history before cleanup (0.0 - 1.0)
+    pub coverage_before: f64,
+    /// Fraction of the window covered by history after cleanup (0.0 - 1.0)
+    pub coverage_after: f64,
+}
+
+impl WindowImpact {
+    /// Whether cleanup introduces a gap into this window
+    pub fn has_gap(&self) -> bool {
+        self.coverage_after + f64::EPSILON < self.coverage_before
+    }
+}
+
+/// Estimated impact of a cleanup on trend accuracy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrendImpact {
+    /// Days of history before cleanup
+    pub span_days_before: f64,
+    /// Days of history remaining after cleanup
+    pub span_days_after: f64,
+    /// Fraction of all snapshots that would be removed
+    pub removed_fraction: f64,
+    /// Per trend window coverage
+    pub windows: Vec<WindowImpact>,
+}
+
+/// Everything a cleanup with a given cutoff would remove
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanPreview {
+    /// Snapshots recorded before this time are removed
+    pub cutoff: SystemTime,
+    /// Snapshots that would be deleted, oldest first
+    pub snapshots: Vec<SnapshotRef>,
+    /// Files whose entire history (and file statistics) would be deleted
+    pub files_removed: Vec<PathBuf>,
+    /// Files that keep some history but lose older snapshots
+    pub files_truncated: Vec<PathBuf>,
+    /// Error patterns last seen before the cutoff
+    pub patterns: Vec<String>,
+    /// Daily time-series buckets that would disappear entirely
+    pub time_series_buckets: Vec<SystemTime>,
+    /// Impact on trend analysis
+    pub impact: TrendImpact,
+}
+
+impl CleanPreview {
+    /// Check whether the cleanup would remove anything
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty() && self.patterns.is_empty()
+    }
+}
+
+/// Compute what a cleanup with `cutoff` would remove without deleting anything
+pub async fn preview_clean(storage: &HistoryStorage, cutoff: SystemTime) -> Result<CleanPreview> {
+    let removed = storage.get_snapshots_before(cutoff).await?;
+    let remaining_files: HashSet<PathBuf> =
+        storage.get_files_since(cutoff).await?.into_iter().collect();
+    let time_range = storage.get_time_range().await?;
+
+    let removed_files: BTreeSet<&PathBuf> = removed.iter().map(|s| &s.file_path).collect();
+    let (files_truncated, files_removed): (Vec<PathBuf>, Vec<PathBuf>) = removed_files
+        .into_iter()
+        .cloned()
+        .partition(|file| remaining_files.contains(file));
+
+    let patterns = storage
+        .get_recurring_patterns(1)
+        .await?
+        .into_iter()
+        .filter(|p| p.last_seen < cutoff)
+        .map(|p| p.pattern_hash)
+        .collect();
+
+    let (impact, time_series_buckets) = match time_range {
+        Some((first, last)) => {
+            let total = storage
+                .get_time_series_data(first, last, BUCKET)
+                .await?
+                .iter()
+                .map(|p| p.snapshot_count)
+                .sum::<usize>();
+            let earliest_remaining = if removed.len() < total {
+                Some(cutoff.max(first))
+            } else {
+                None
+            };
+            (
+                estimate_impact(first, last, earliest_remaining, removed.len(), total),
+                emptied_buckets(&removed, cutoff),
+            )
+        }
+        None => (estimate_impact(cutoff, cutoff, None, 0, 0), Vec::new()),
+    };
+
+    Ok(CleanPreview {
+        cutoff,
+        snapshots: removed.iter().map(snapshot_ref).collect(),
+        files_removed,
+        files_truncated,
+        patterns,
+        time_series_buckets,
+        impact,
+    })
+}
+
+/// Write the snapshots a cleanup would delete to a JSON file
+///
+/// Returns the number of snapshots written.
+pub async fn backup_before(
+    storage: &HistoryStorage,
+    cutoff: SystemTime,
+    path: &Path,
+) -> Result<usize> {
+    let snapshots = storage.get_snapshots_before(cutoff).await?;
+    let backup = HistoryBackup {
+        created_at: SystemTime::now(),
+        cutoff,
+        snapshots,
+    };
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create backup directory {}", parent.display()))?;
+    }
+    let content = serde_json::to_string_pretty(&backup)?;
+    std::fs::write(path, content)
+        .with_context(|| format!("Failed to write history backup {}", path.display()))?;
+
+    Ok(backup.snapshots.len())
+}
+
+/// Default location for a pre-clean backup
+pub fn default_backup_path() -> PathBuf {
+    let stamp = chrono::Utc::now().format("%Y%m%d-%H%M%S");
+    crate::config::data_dir()
+        .unwrap_or_else(|_| std::env::temp_dir().join("lspbridge"))
+        .join("backups")
+        .join(format!("history-clean-{stamp}.json"))
+}
+
+/// Snapshots exported before a cleanup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryBackup {
+    pub created_at: SystemTime,
+    pub cutoff: SystemTime,
+    pub snapshots: Vec<DiagnosticSnapshot>,
+}
+
+fn snapshot_ref(snapshot: &DiagnosticSnapshot) -> SnapshotRef {
+    SnapshotRef {
+        id: snapshot.id,
+        timestamp: snapshot.timestamp,
+        file_path: snapshot.file_path.clone(),
+        error_count: snapshot.error_count,
+        warning_count: snapshot.warning_count,
+    }
+}
+
+fn bucket_start(time: SystemTime) -> u64 {
+    let secs = time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    secs - secs % BUCKET.as_secs()
+}
+
+/// Daily buckets whose every snapshot would be removed
+///
+/// Buckets entirely before the cutoff lose all data; the bucket containing
+/// the cutoff keeps its later snapshots and is not listed.
+fn emptied_buckets(removed: &[DiagnosticSnapshot], cutoff: SystemTime) -> Vec<SystemTime> {
+    let cutoff_bucket = bucket_start(cutoff);
+    removed
+        .iter()
+        .map(|s| bucket_start(s.timestamp))
+        .filter(|&bucket| bucket < cutoff_bucket)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+        .collect()
+}
+
+fn days_between(start: SystemTime, end: SystemTime) -> f64 {
+    end.duration_since(start).unwrap_or_default().as_secs_f64() / 86_400.0
+}
+
+fn estimate_impact(
+    first: SystemTime,
+    last: SystemTime,
+    earliest_remaining: Option<SystemTime>,
+    removed: usize,
+    total: usize,
+) -> TrendImpact {
+    let now = SystemTime::now();
+    let coverage = |start: Option<SystemTime>, window_days: u64| -> f64 {
+        let Some(start) = start else {
+            return 0.0;
+        };
+        let covered = days_between(start, now).min(window_days as f64);
+        (covered / window_days as f64).clamp(0.0, 1.0)
+    };
+    let has_data = total > 0;
+
+    TrendImpact {
+        span_days_before: if has_data { days_between(first, last) } else { 0.0 },
+        span_days_after: earliest_remaining.map_or(0.0, |start| days_between(start, last)),
+        removed_fraction: if has_data { removed as f64 / total as f64 } else { 0.0 },
+        windows: TREND_WINDOWS_DAYS
+            .iter()
+            .map(|&window_days| WindowImpact {
+                window_days,
+                coverage_before: coverage(has_data.then_some(first), window_days),
+                coverage_after: coverage(earliest_remaining, window_days),
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::FileHash;
+
+    fn snapshot(days_ago: u64) -> DiagnosticSnapshot {
+        DiagnosticSnapshot {
+            id: days_ago as i64,
+            timestamp: SystemTime::now() - Duration::from_secs(days_ago * 86_400),
+            file_path: PathBuf::from("src/lib.rs"),
+            file_hash: FileHash::new(b"content"),
+            diagnostics: vec![],
+            error_count: 1,
+            warning_count: 0,
+            info_count: 0,
+            hint_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_emptied_buckets_exclude_cutoff_bucket() {
+        let cutoff = SystemTime::now() - Duration::from_secs(10 * 86_400);
+        let removed = vec![snapshot(40), snapshot(40), snapshot(20)];
+
+        let buckets = emptied_buckets(&removed, cutoff);
+        assert_eq!(buckets.len(), 2);
+        assert!(buckets[0] < buckets[1]);
+    }
+
+    #[test]
+    fn test_impact_reports_gaps_in_long_windows() {
+        let now = SystemTime::now();
+        let first = now - Duration::from_secs(60 * 86_400);
+        let cutoff = now - Duration::from_secs(14 * 86_400);
+
+        let impact = estimate_impact(first, now, Some(cutoff), 75, 100);
+        assert!((impact.removed_fraction - 0.75).abs() < 1e-9);
+        assert!(impact.span_days_after < impact.span_days_before);
+
+        let week = impact.windows.iter().find(|w| w.window_days == 7).unwrap();
+        assert!(!week.has_gap());
+        let month = impact.windows.iter().find(|w| w.window_days == 30).unwrap();
+        assert!(month.has_gap());
+        assert!((month.coverage_after - 14.0 / 30.0).abs() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn test_preview_matches_cleanup() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let config = crate::history::HistoryConfig {
+            db_path: temp_dir.path().join("history.db"),
+            retention_days: 30,
+            max_snapshots_per_file: 100,
+            auto_cleanup_interval: Duration::from_secs(3600),
+            min_connections: 1,
+            max_connections: 2,
+            connection_timeout_secs: 5,
+        };
+        let storage = HistoryStorage::new(config).await?;
+
+        let mut old_only = snapshot(40);
+        old_only.file_path = PathBuf::from("src/old.rs");
+        for snapshot in [old_only, snapshot(40), snapshot(1)] {
+            storage.record_snapshot(snapshot).await?;
+        }
+
+        let cutoff = SystemTime::now() - Duration::from_secs(10 * 86_400);
+        let preview = preview_clean(&storage, cutoff).await?;
+        assert_eq!(preview.snapshots.len(), 2);
+        assert_eq!(preview.files_removed, vec![PathBuf::from("src/old.rs")]);
+        assert_eq!(preview.files_truncated, vec![PathBuf::from("src/lib.rs")]);
+
+        let backup_path = temp_dir.path().join("backup.json");
+        assert_eq!(backup_before(&storage, cutoff, &backup_path).await?, 2);
+
+        let summary = storage.delete_before(cutoff).await?;
+        assert_eq!(summary.snapshots_deleted, preview.snapshots.len());
+        assert!(preview_clean(&storage, cutoff).await?.snapshots.is_empty());
+        Ok(())
+    }
+}
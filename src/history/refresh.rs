@@ -0,0 +1,380 @@
+//! Background re-analysis of stale history
+//!
+//! A file's history goes stale when its content changed since the last
+//! capture, or when the last capture is older than a maximum age. The
+//! [`StaleFileRefresher`] finds such files and re-analyzes a small batch at a
+//! time, pausing between files so it stays out of the way of interactive
+//! work. Only files that already have history are refreshed; a full capture
+//! is still what brings new files in.
+//!
+//! Refreshing replaces the diagnostics of the reanalyzer's own source.
+//! `watch --refresh-stale-days` uses the [`StaticScanner`], so diagnostics
+//! from language servers can't be recomputed: an expired file keeps them,
+//! since its content is unchanged, but a changed file drops them, as their
+//! ranges point into content that no longer exists. The next full capture
+//! re-runs the servers.
+
+use super::storage::{AsOf, DiagnosticSnapshot, HistoryStorage};
+use crate::analyzers::SECURITY_SOURCE;
+use crate::core::{Diagnostic, DiagnosticSeverity, FileHash, StaticScanner, SCAN_SOURCE};
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::task::JoinHandle;
+
+/// Re-computes the diagnostics of a single file
+#[async_trait]
+pub trait FileReanalyzer: Send + Sync {
+    /// Diagnostic source produced by this analyzer
+    ///
+    /// On refresh, previous diagnostics from this source are replaced.
+    /// Diagnostics from other sources are carried over until the next full
+    /// capture when the file is unchanged, and dropped when it changed.
+    fn source(&self) -> &str;
+
+    /// Whether a previous diagnostic is replaced by the results of [`Self::reanalyze`]
+    fn replaces(&self, diagnostic: &Diagnostic) -> bool {
+        diagnostic.source == self.source()
+    }
+
+    async fn reanalyze(&self, path: &Path) -> Result<Vec<Diagnostic>>;
+}
+
+#[async_trait]
+impl FileReanalyzer for StaticScanner {
+    fn source(&self) -> &str {
+        SCAN_SOURCE
+    }
+
+    /// Scans report security findings under their own source
+    fn replaces(&self, diagnostic: &Diagnostic) -> bool {
+        diagnostic.source == SCAN_SOURCE || diagnostic.source == SECURITY_SOURCE
+    }
+
+    async fn reanalyze(&self, path: &Path) -> Result<Vec<Diagnostic>> {
+        self.scan_file(path).await
+    }
+}
+
+/// Why a file's history needs refreshing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StaleReason {
+    /// Content differs from the last capture
+    Changed,
+    /// Last capture is older than the maximum age
+    Expired,
+}
+
+/// A file whose last snapshot is stale
+#[derive(Debug, Clone)]
+pub struct StaleFile {
+    pub path: PathBuf,
+    pub reason: StaleReason,
+    pub last_snapshot: DiagnosticSnapshot,
+}
+
+/// Outcome of one refresh pass
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RefreshSummary {
+    pub stale: usize,
+    pub refreshed: usize,
+    pub failed: usize,
+}
+
+/// Finds stale files in history and re-analyzes them a batch at a time
+pub struct StaleFileRefresher {
+    storage: Arc<HistoryStorage>,
+    analyzer: Arc<dyn FileReanalyzer>,
+    root: PathBuf,
+    max_age: Duration,
+    batch_size: usize,
+    pause: Duration,
+    interval: Duration,
+}
+
+impl StaleFileRefresher {
+    /// Refresher for files under `root`; relative history paths resolve against it
+    pub fn new(storage: Arc<HistoryStorage>, analyzer: Arc<dyn FileReanalyzer>, root: impl Into<PathBuf>) -> Self {
+        Self {
+            storage,
+            analyzer,
+            root: root.into(),
+            max_age: Duration::from_secs(7 * 24 * 60 * 60),
+            batch_size: 10,
+            pause: Duration::from_millis(250),
+            interval: Duration::from_secs(5 * 60),
+        }
+    }
+
+    /// Re-analyze unchanged files whose last capture is older than this
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Maximum files re-analyzed per pass
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Delay between files within a pass
+    pub fn with_pause(mut self, pause: Duration) -> Self {
+        self.pause = pause;
+        self
+    }
+
+    /// Delay between passes when running in the background
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Files whose latest snapshot is stale, changed files first, then oldest first
+    ///
+    /// Files that no longer exist are skipped.
+    pub async fn find_stale(&self) -> Result<Vec<StaleFile>> {
+        let now = SystemTime::now();
+        let snapshots = self.storage.reconstruct(AsOf::Time(now)).await?;
+
+        let mut stale = Vec::new();
+        for snapshot in snapshots {
+            let path = self.resolve(&snapshot.file_path);
+            let Ok(content) = tokio::fs::read(&path).await else {
+                continue;
+            };
+
+            let reason = if FileHash::new(&content) != snapshot.file_hash {
+                StaleReason::Changed
+            } else if now.duration_since(snapshot.timestamp).unwrap_or_default() > self.max_age {
+                StaleReason::Expired
+            } else {
+                continue;
+            };
+            stale.push(StaleFile {
+                path,
+                reason,
+                last_snapshot: snapshot,
+            });
+        }
+
+        stale.sort_by_key(|file| (file.reason != StaleReason::Changed, file.last_snapshot.timestamp));
+        Ok(stale)
+    }
+
+    /// Re-analyze up to one batch of stale files and record fresh snapshots
+    pub async fn refresh(&self) -> Result<RefreshSummary> {
+        let stale = self.find_stale().await?;
+        let mut summary = RefreshSummary {
+            stale: stale.len(),
+            ..Default::default()
+        };
+
+        for (index, file) in stale.into_iter().take(self.batch_size).enumerate() {
+            if index > 0 {
+                tokio::time::sleep(self.pause).await;
+            }
+            match self.refresh_file(file).await {
+                Ok(()) => summary.refreshed += 1,
+                Err(e) => {
+                    tracing::debug!("Stale file refresh failed: {}", e);
+                    summary.failed += 1;
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Run refresh passes in the background until the handle is aborted
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(self.interval).await;
+                match self.refresh().await {
+                    Ok(summary) if summary.refreshed > 0 || summary.failed > 0 => tracing::info!(
+                        "Refreshed {} of {} stale files ({} failed)",
+                        summary.refreshed,
+                        summary.stale,
+                        summary.failed
+                    ),
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Stale file refresh pass failed: {}", e),
+                }
+            }
+        })
+    }
+
+    async fn refresh_file(&self, file: StaleFile) -> Result<()> {
+        let content = tokio::fs::read(&file.path).await?;
+        let fresh = self.analyzer.reanalyze(&file.path).await?;
+
+        // Other sources' diagnostics only stay valid while the content is unchanged
+        let mut diagnostics: Vec<Diagnostic> = match file.reason {
+            StaleReason::Changed => Vec::new(),
+            StaleReason::Expired => file
+                .last_snapshot
+                .diagnostics
+                .into_iter()
+                .filter(|d| !self.analyzer.replaces(d))
+                .collect(),
+        };
+        diagnostics.extend(fresh);
+
+        let count = |severity: DiagnosticSeverity| diagnostics.iter().filter(|d| d.severity == severity).count();
+        let snapshot = DiagnosticSnapshot {
+            id: 0,
+            timestamp: SystemTime::now(),
+            file_path: file.last_snapshot.file_path,
+            file_hash: FileHash::new(&content),
+            error_count: count(DiagnosticSeverity::Error),
+            warning_count: count(DiagnosticSeverity::Warning),
+            info_count: count(DiagnosticSeverity::Information),
+            hint_count: count(DiagnosticSeverity::Hint),
+            diagnostics,
+        };
+        self.storage.record_snapshot(snapshot).await?;
+        Ok(())
+    }
+
+    fn resolve(&self, path: &Path) -> PathBuf {
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.root.join(path)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Position, Range};
+    use crate::history::HistoryConfig;
+
+    struct FixedAnalyzer;
+
+    #[async_trait]
+    impl FileReanalyzer for FixedAnalyzer {
+        fn source(&self) -> &str {
+            "fixed"
+        }
+
+        async fn reanalyze(&self, path: &Path) -> Result<Vec<Diagnostic>> {
+            Ok(vec![Diagnostic::new(
+                path.to_string_lossy().to_string(),
+                Range {
+                    start: Position { line: 0, character: 0 },
+                    end: Position { line: 0, character: 1 },
+                },
+                DiagnosticSeverity::Warning,
+                "fresh".to_string(),
+                "fixed".to_string(),
+            )])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_changed_files_are_refreshed() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let storage = Arc::new(
+            HistoryStorage::new(HistoryConfig {
+                db_path: dir.path().join("history.db"),
+                ..Default::default()
+            })
+            .await?,
+        );
+        std::fs::write(dir.path().join("changed.rs"), "fn changed() {}")?;
+        std::fs::write(dir.path().join("fresh.rs"), "fn fresh() {}")?;
+
+        for (file, content) in [("changed.rs", "fn old() {}"), ("fresh.rs", "fn fresh() {}")] {
+            storage
+                .record_snapshot(DiagnosticSnapshot {
+                    id: 0,
+                    timestamp: SystemTime::now(),
+                    file_path: PathBuf::from(file),
+                    file_hash: FileHash::new(content.as_bytes()),
+                    diagnostics: Vec::new(),
+                    error_count: 0,
+                    warning_count: 0,
+                    info_count: 0,
+                    hint_count: 0,
+                })
+                .await?;
+        }
+
+        let refresher = StaleFileRefresher::new(storage.clone(), Arc::new(FixedAnalyzer), dir.path())
+            .with_pause(Duration::ZERO);
+        let stale = refresher.find_stale().await?;
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].reason, StaleReason::Changed);
+
+        let summary = refresher.refresh().await?;
+        assert_eq!((summary.stale, summary.refreshed), (1, 1));
+        assert!(refresher.find_stale().await?.is_empty());
+
+        let snapshots = storage.get_snapshots_for_file(Path::new("changed.rs"), None, None).await?;
+        let latest = snapshots.iter().max_by_key(|snapshot| snapshot.id).unwrap();
+        assert_eq!(latest.warning_count, 1);
+        assert_eq!(latest.file_hash, FileHash::new(b"fn changed() {}"));
+
+        // Everything counts as expired once the maximum age is zero
+        let refresher = refresher.with_max_age(Duration::ZERO);
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        let stale = refresher.find_stale().await?;
+        assert_eq!(stale.len(), 2);
+        assert!(stale.iter().all(|file| file.reason == StaleReason::Expired));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_changed_file_drops_language_server_diagnostics() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let storage = Arc::new(
+            HistoryStorage::new(HistoryConfig {
+                db_path: dir.path().join("history.db"),
+                ..Default::default()
+            })
+            .await?,
+        );
+        std::fs::write(dir.path().join("lib.rs"), "fn new() {}")?;
+        let range = Range {
+            start: Position { line: 3, character: 4 },
+            end: Position { line: 3, character: 9 },
+        };
+        let lsp = Diagnostic::new(
+            "lib.rs".to_string(),
+            range,
+            DiagnosticSeverity::Error,
+            "mismatched types".to_string(),
+            "rust-analyzer".to_string(),
+        );
+        storage
+            .record_snapshot(DiagnosticSnapshot {
+                id: 0,
+                timestamp: SystemTime::now(),
+                file_path: PathBuf::from("lib.rs"),
+                file_hash: FileHash::new(b"fn old() {}"),
+                diagnostics: vec![lsp],
+                error_count: 1,
+                warning_count: 0,
+                info_count: 0,
+                hint_count: 0,
+            })
+            .await?;
+
+        let refresher = StaleFileRefresher::new(storage.clone(), Arc::new(FixedAnalyzer), dir.path())
+            .with_pause(Duration::ZERO);
+        assert_eq!(refresher.refresh().await?.refreshed, 1);
+
+        let snapshots = storage.get_snapshots_for_file(Path::new("lib.rs"), None, None).await?;
+        let latest = snapshots.iter().max_by_key(|snapshot| snapshot.id).unwrap();
+        assert_eq!(latest.file_hash, FileHash::new(b"fn new() {}"));
+        assert!(latest.diagnostics.iter().all(|d| d.source == "fixed"));
+        assert_eq!((latest.error_count, latest.warning_count), (0, 1));
+        Ok(())
+    }
+}
@@ -0,0 +1,642 @@
+//! Weekly narrative reports
+//!
+//! A [`WeeklyReport`] compiles a window of recorded history into the numbers
+//! a team cares about at the end of a week: how error and warning counts
+//! moved, which files regressed, which were fixed and where problems remain
+//! concentrated. Reports render to Markdown or HTML through a small
+//! `{{placeholder}}` template, so teams can reshape the layout for the
+//! channel they post to. An optional executive summary is written by a
+//! [`SummaryProvider`], such as an LLM command line tool.
+
+use super::analyzer::{TrendAnalyzer, TrendDirection};
+use super::storage::{AsOf, DiagnosticSnapshot, HistoryStorage, TimeSeriesPoint};
+use crate::core::{CalendarConfig, CalendarUnit};
+use crate::export::multi_format::escape_html;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use clap::{Args, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::io::AsyncWriteExt;
+
+/// Default Markdown layout
+pub const MARKDOWN_TEMPLATE: &str = "# {{title}}
+
+_{{period}}_
+
+{{executive_summary}}
+
+## Summary
+
+{{narrative}}
+
+## Daily trend
+
+{{trend}}
+
+## Top regressions
+
+{{regressions}}
+
+## Top fixes
+
+{{fixes}}
+
+## Hot spots
+
+{{hot_spots}}
+";
+
+/// Default HTML layout
+pub const HTML_TEMPLATE: &str = "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{{title}}</title>
+<style>
+body { font-family: system-ui, sans-serif; max-width: 60rem; margin: 2rem auto; color: #222; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 0.25rem 0.6rem; text-align: left; }
+.summary { background: #f5f5f5; padding: 0.75rem 1rem; border-left: 4px solid #555; }
+</style>
+</head>
+<body>
+<h1>{{title}}</h1>
+<p><em>{{period}}</em></p>
+{{executive_summary}}
+<h2>Summary</h2>
+{{narrative}}
+<h2>Daily trend</h2>
+{{trend}}
+<h2>Top regressions</h2>
+{{regressions}}
+<h2>Top fixes</h2>
+{{fixes}}
+<h2>Hot spots</h2>
+{{hot_spots}}
+</body>
+</html>
+";
+
+/// Actions for generating reports
+#[derive(Debug, Clone, Subcommand)]
+pub enum ReportAction {
+    /// Narrative report of the last week of diagnostic history
+    Weekly(WeeklyReportArgs),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct WeeklyReportArgs {
+    /// Report format
+    #[arg(short, long, value_enum, default_value = "markdown")]
+    pub format: ReportFormat,
+
+    /// Output file (default: stdout)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Template file with `{{placeholder}}` sections replacing the built-in layout
+    #[arg(long)]
+    pub template: Option<PathBuf>,
+
+    /// Number of days covered by the report
+    #[arg(long, default_value = "7")]
+    pub days: u64,
+
+    /// Maximum files listed per section
+    #[arg(long, default_value = "5")]
+    pub limit: usize,
+
+    /// Command that writes an executive summary; it receives the report on stdin
+    #[arg(long)]
+    pub summary_command: Option<String>,
+}
+
+/// How a report is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+/// How one file's diagnostics moved over the report window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileChange {
+    pub file_path: PathBuf,
+    pub errors_before: usize,
+    pub errors_after: usize,
+    pub warnings_before: usize,
+    pub warnings_after: usize,
+}
+
+impl FileChange {
+    pub fn error_delta(&self) -> i64 {
+        self.errors_after as i64 - self.errors_before as i64
+    }
+
+    pub fn warning_delta(&self) -> i64 {
+        self.warnings_after as i64 - self.warnings_before as i64
+    }
+
+    /// Weighted problem score at the end of the window, errors counting double
+    pub fn score(&self) -> usize {
+        self.errors_after * 2 + self.warnings_after
+    }
+}
+
+/// A week (or other window) of diagnostic history, ready to render
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyReport {
+    pub start: SystemTime,
+    pub end: SystemTime,
+    pub errors_before: usize,
+    pub errors_after: usize,
+    pub warnings_before: usize,
+    pub warnings_after: usize,
+    /// Files with at least one error at the end of the window
+    pub files_with_errors: usize,
+    /// 0.0 (worst) to 1.0 (best)
+    pub health_score: f32,
+    pub trend: TrendDirection,
+    /// One point per day
+    pub daily: Vec<TimeSeriesPoint>,
+    /// Files whose error count rose the most
+    pub regressions: Vec<FileChange>,
+    /// Files whose error count fell the most
+    pub fixes: Vec<FileChange>,
+    /// Files with the most diagnostics at the end of the window
+    pub hot_spots: Vec<FileChange>,
+    pub executive_summary: Option<String>,
+    /// Zone the daily buckets and dates are in
+    #[serde(default)]
+    pub calendar: CalendarConfig,
+}
+
+impl WeeklyReport {
+    /// Compile the report for the `window` ending now, listing up to `limit` files per section
+    pub async fn compile(storage: Arc<HistoryStorage>, window: Duration, limit: usize) -> Result<Self> {
+        Self::compile_with(storage, window, limit, CalendarConfig::default()).await
+    }
+
+    /// Compile the report with days split at midnight in the calendar's time zone
+    ///
+    /// Whole-day windows start at the same local time of day `window` ago, so
+    /// a week spanning a daylight saving change is still seven calendar days.
+    pub async fn compile_with(
+        storage: Arc<HistoryStorage>,
+        window: Duration,
+        limit: usize,
+        calendar: CalendarConfig,
+    ) -> Result<Self> {
+        let end = SystemTime::now();
+        let start = match window.as_secs() {
+            secs if secs > 0 && secs % 86_400 == 0 => calendar
+                .units_before(end.into(), CalendarUnit::Day, (secs / 86_400) as u32)
+                .into(),
+            _ => end - window,
+        };
+
+        let before = storage.reconstruct(AsOf::Time(start)).await?;
+        let after = storage.reconstruct(AsOf::Time(end)).await?;
+        let changes = file_changes(&before, &after);
+
+        let mut regressions: Vec<FileChange> = changes.iter().filter(|c| c.error_delta() > 0).cloned().collect();
+        regressions.sort_by_key(|c| (std::cmp::Reverse(c.error_delta()), c.file_path.clone()));
+        regressions.truncate(limit);
+
+        let mut fixes: Vec<FileChange> = changes.iter().filter(|c| c.error_delta() < 0).cloned().collect();
+        fixes.sort_by_key(|c| (c.error_delta(), c.file_path.clone()));
+        fixes.truncate(limit);
+
+        let mut hot_spots: Vec<FileChange> = changes.iter().filter(|c| c.score() > 0).cloned().collect();
+        hot_spots.sort_by_key(|c| (std::cmp::Reverse(c.score()), c.file_path.clone()));
+        hot_spots.truncate(limit);
+
+        let daily = storage
+            .get_calendar_series(start, end, CalendarUnit::Day, &calendar)
+            .await?;
+        let trends = TrendAnalyzer::new(storage).analyze_trends(window, 5).await?;
+
+        Ok(Self {
+            start,
+            end,
+            errors_before: changes.iter().map(|c| c.errors_before).sum(),
+            errors_after: changes.iter().map(|c| c.errors_after).sum(),
+            warnings_before: changes.iter().map(|c| c.warnings_before).sum(),
+            warnings_after: changes.iter().map(|c| c.warnings_after).sum(),
+            files_with_errors: changes.iter().filter(|c| c.errors_after > 0).count(),
+            health_score: trends.health_score,
+            trend: trends.trend_direction,
+            daily,
+            regressions,
+            fixes,
+            hot_spots,
+            executive_summary: None,
+            calendar,
+        })
+    }
+
+    /// Ask `provider` for an executive summary of the rendered report
+    pub async fn summarize(&mut self, provider: &dyn SummaryProvider) -> Result<()> {
+        let prompt = format!(
+            "Write a three to five sentence executive summary of this weekly code health report \
+             for an engineering team channel. Mention the overall direction, the most important \
+             regressions and fixes, and one suggested focus for next week. Reply with the summary only.\n\n{}",
+            self.render(ReportFormat::Markdown, None)
+        );
+        let summary = provider.summarize(&prompt).await?;
+        let summary = summary.trim();
+        if summary.is_empty() {
+            return Err(anyhow!("Summary provider returned an empty summary"));
+        }
+        self.executive_summary = Some(summary.to_string());
+        Ok(())
+    }
+
+    /// Plain-language summary of the numbers, one sentence per fact
+    pub fn narrative(&self) -> Vec<String> {
+        let mut sentences = vec![format!(
+            "Errors went from {} to {} ({}) and warnings from {} to {} ({}).",
+            self.errors_before,
+            self.errors_after,
+            percent_change(self.errors_before, self.errors_after),
+            self.warnings_before,
+            self.warnings_after,
+            percent_change(self.warnings_before, self.warnings_after)
+        )];
+
+        sentences.push(format!(
+            "Overall health is {:.0}% and {}.",
+            self.health_score * 100.0,
+            match self.trend {
+                TrendDirection::Improving => "improving",
+                TrendDirection::Stable => "holding steady",
+                TrendDirection::Degrading => "degrading",
+            }
+        ));
+
+        if self.files_with_errors > 0 {
+            sentences.push(format!("{} file(s) still have errors.", self.files_with_errors));
+        } else {
+            sentences.push("No file has errors at the end of the period.".to_string());
+        }
+
+        if let Some(worst) = self.regressions.first() {
+            sentences.push(format!(
+                "The largest regression was {} with {} new error(s).",
+                worst.file_path.display(),
+                worst.error_delta()
+            ));
+        }
+        if let Some(best) = self.fixes.first() {
+            sentences.push(format!(
+                "The largest fix was {}, down {} error(s).",
+                best.file_path.display(),
+                -best.error_delta()
+            ));
+        }
+
+        sentences
+    }
+
+    /// Render with the built-in layout for `format`, or with `template`
+    ///
+    /// Templates may use `{{title}}`, `{{period}}`, `{{executive_summary}}`,
+    /// `{{narrative}}`, `{{trend}}`, `{{regressions}}`, `{{fixes}}` and
+    /// `{{hot_spots}}`; unknown placeholders are left as they are.
+    pub fn render(&self, format: ReportFormat, template: Option<&str>) -> String {
+        let template = template.unwrap_or(match format {
+            ReportFormat::Markdown => MARKDOWN_TEMPLATE,
+            ReportFormat::Html => HTML_TEMPLATE,
+        });
+
+        let sections = match format {
+            ReportFormat::Markdown => self.markdown_sections(),
+            ReportFormat::Html => self.html_sections(),
+        };
+
+        let mut rendered = template.to_string();
+        for (name, value) in sections {
+            rendered = rendered.replace(&format!("{{{{{name}}}}}"), &value);
+        }
+        rendered
+    }
+
+    fn format_date(&self, time: SystemTime) -> String {
+        self.calendar.format(time.into(), "%Y-%m-%d")
+    }
+
+    fn title(&self) -> String {
+        format!("Weekly diagnostics report: {}", self.format_date(self.end))
+    }
+
+    fn period(&self) -> String {
+        format!("{} to {}", self.format_date(self.start), self.format_date(self.end))
+    }
+
+    fn markdown_sections(&self) -> BTreeMap<&'static str, String> {
+        let mut sections = BTreeMap::new();
+        sections.insert("title", self.title());
+        sections.insert("period", self.period());
+        sections.insert(
+            "executive_summary",
+            self.executive_summary
+                .as_ref()
+                .map(|summary| {
+                    summary
+                        .lines()
+                        .map(|line| format!("> {line}"))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .unwrap_or_default(),
+        );
+        sections.insert("narrative", self.narrative().join(" "));
+
+        sections.insert(
+            "trend",
+            if self.daily.is_empty() {
+                "_No snapshots recorded._".to_string()
+            } else {
+                let mut table = vec![
+                    "| Day | Snapshots | Errors | Warnings |".to_string(),
+                    "|-----|-----------|--------|----------|".to_string(),
+                ];
+                table.extend(self.daily.iter().map(|point| {
+                    format!(
+                        "| {} | {} | {} | {} |",
+                        self.format_date(point.timestamp),
+                        point.snapshot_count,
+                        point.total_errors,
+                        point.total_warnings
+                    )
+                }));
+                table.join("\n")
+            },
+        );
+
+        let changes = |changes: &[FileChange], empty: &str| {
+            if changes.is_empty() {
+                return format!("_{empty}_");
+            }
+            let mut table = vec![
+                "| File | Errors | Warnings |".to_string(),
+                "|------|--------|----------|".to_string(),
+            ];
+            table.extend(changes.iter().map(|c| {
+                format!(
+                    "| `{}` | {} → {} ({:+}) | {} → {} ({:+}) |",
+                    c.file_path.display(),
+                    c.errors_before,
+                    c.errors_after,
+                    c.error_delta(),
+                    c.warnings_before,
+                    c.warnings_after,
+                    c.warning_delta()
+                )
+            }));
+            table.join("\n")
+        };
+        sections.insert("regressions", changes(&self.regressions, "No files regressed."));
+        sections.insert("fixes", changes(&self.fixes, "No errors were fixed."));
+        sections.insert("hot_spots", changes(&self.hot_spots, "No open diagnostics."));
+        sections
+    }
+
+    fn html_sections(&self) -> BTreeMap<&'static str, String> {
+        let mut sections = BTreeMap::new();
+        sections.insert("title", escape_html(&self.title()));
+        sections.insert("period", escape_html(&self.period()));
+        sections.insert(
+            "executive_summary",
+            self.executive_summary
+                .as_ref()
+                .map(|summary| format!("<p class=\"summary\">{}</p>", escape_html(summary)))
+                .unwrap_or_default(),
+        );
+        sections.insert(
+            "narrative",
+            format!("<p>{}</p>", escape_html(&self.narrative().join(" "))),
+        );
+
+        sections.insert(
+            "trend",
+            if self.daily.is_empty() {
+                "<p><em>No snapshots recorded.</em></p>".to_string()
+            } else {
+                let rows: String = self
+                    .daily
+                    .iter()
+                    .map(|point| {
+                        format!(
+                            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                            self.format_date(point.timestamp),
+                            point.snapshot_count,
+                            point.total_errors,
+                            point.total_warnings
+                        )
+                    })
+                    .collect();
+                format!(
+                    "<table>\n<tr><th>Day</th><th>Snapshots</th><th>Errors</th><th>Warnings</th></tr>\n{rows}</table>"
+                )
+            },
+        );
+
+        let changes = |changes: &[FileChange], empty: &str| {
+            if changes.is_empty() {
+                return format!("<p><em>{empty}</em></p>");
+            }
+            let rows: String = changes
+                .iter()
+                .map(|c| {
+                    format!(
+                        "<tr><td><code>{}</code></td><td>{} &rarr; {} ({:+})</td><td>{} &rarr; {} ({:+})</td></tr>\n",
+                        escape_html(&c.file_path.display().to_string()),
+                        c.errors_before,
+                        c.errors_after,
+                        c.error_delta(),
+                        c.warnings_before,
+                        c.warnings_after,
+                        c.warning_delta()
+                    )
+                })
+                .collect();
+            format!("<table>\n<tr><th>File</th><th>Errors</th><th>Warnings</th></tr>\n{rows}</table>")
+        };
+        sections.insert("regressions", changes(&self.regressions, "No files regressed."));
+        sections.insert("fixes", changes(&self.fixes, "No errors were fixed."));
+        sections.insert("hot_spots", changes(&self.hot_spots, "No open diagnostics."));
+        sections
+    }
+}
+
+/// Writes executive summaries for reports
+#[async_trait]
+pub trait SummaryProvider: Send + Sync {
+    /// Answer `prompt` with a short summary
+    async fn summarize(&self, prompt: &str) -> Result<String>;
+}
+
+/// Summary provider that runs a command, writing the prompt to its stdin
+///
+/// Works with any LLM command line tool that reads a prompt from stdin and
+/// prints the answer, e.g. `llm -m claude-3-haiku`.
+pub struct CommandSummaryProvider {
+    program: String,
+    args: Vec<String>,
+}
+
+impl CommandSummaryProvider {
+    /// Provider for a whitespace-separated command line
+    pub fn parse(command_line: &str) -> Result<Self> {
+        let mut parts = command_line.split_whitespace().map(String::from);
+        let program = parts.next().ok_or_else(|| anyhow!("Summary command is empty"))?;
+        Ok(Self {
+            program,
+            args: parts.collect(),
+        })
+    }
+}
+
+#[async_trait]
+impl SummaryProvider for CommandSummaryProvider {
+    async fn summarize(&self, prompt: &str) -> Result<String> {
+        // The command is usually a hosted model's client, so it counts as network access
+        crate::core::NetworkGuard::global().check(format!("summary command {}", self.program))?;
+
+        let mut child = tokio::process::Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow!("Failed to run summary command {}: {}", self.program, e))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(prompt.as_bytes()).await?;
+        }
+
+        let output = child.wait_with_output().await?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Summary command {} failed: {}",
+                self.program,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+/// Pair up the latest snapshots per file at both ends of the window
+pub(crate) fn file_changes(before: &[DiagnosticSnapshot], after: &[DiagnosticSnapshot]) -> Vec<FileChange> {
+    let before: BTreeMap<&PathBuf, &DiagnosticSnapshot> = before.iter().map(|s| (&s.file_path, s)).collect();
+    let after: BTreeMap<&PathBuf, &DiagnosticSnapshot> = after.iter().map(|s| (&s.file_path, s)).collect();
+    let files: BTreeSet<&PathBuf> = before.keys().chain(after.keys()).copied().collect();
+
+    files
+        .into_iter()
+        .map(|file| {
+            let counts = |snapshot: Option<&&DiagnosticSnapshot>| {
+                snapshot.map_or((0, 0), |s| (s.error_count, s.warning_count))
+            };
+            let (errors_before, warnings_before) = counts(before.get(file));
+            let (errors_after, warnings_after) = counts(after.get(file));
+            FileChange {
+                file_path: file.clone(),
+                errors_before,
+                errors_after,
+                warnings_before,
+                warnings_after,
+            }
+        })
+        .collect()
+}
+
+fn percent_change(before: usize, after: usize) -> String {
+    if before == 0 {
+        return if after == 0 { "no change".to_string() } else { "new".to_string() };
+    }
+    format!("{:+.0}%", (after as f64 - before as f64) / before as f64 * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::FileHash;
+    use crate::history::HistoryConfig;
+
+    struct FixedSummary;
+
+    #[async_trait]
+    impl SummaryProvider for FixedSummary {
+        async fn summarize(&self, prompt: &str) -> Result<String> {
+            assert!(prompt.contains("## Top regressions"));
+            Ok("Errors are trending down.\n".to_string())
+        }
+    }
+
+    fn snapshot(file: &str, timestamp: SystemTime, errors: usize, warnings: usize) -> DiagnosticSnapshot {
+        DiagnosticSnapshot {
+            id: 0,
+            timestamp,
+            file_path: PathBuf::from(file),
+            file_hash: FileHash::new(file.as_bytes()),
+            diagnostics: Vec::new(),
+            error_count: errors,
+            warning_count: warnings,
+            info_count: 0,
+            hint_count: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_weekly_report_ranks_regressions_and_fixes() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let storage = Arc::new(
+            HistoryStorage::new(HistoryConfig {
+                db_path: dir.path().join("history.db"),
+                ..Default::default()
+            })
+            .await?,
+        );
+
+        let now = SystemTime::now();
+        let last_week = now - Duration::from_secs(9 * 24 * 60 * 60);
+        let yesterday = now - Duration::from_secs(24 * 60 * 60);
+        for snapshot in [
+            snapshot("src/fixed.rs", last_week, 4, 1),
+            snapshot("src/broken.rs", last_week, 0, 0),
+            snapshot("src/fixed.rs", yesterday, 1, 1),
+            snapshot("src/broken.rs", yesterday, 3, 2),
+            snapshot("src/new.rs", yesterday, 1, 0),
+        ] {
+            storage.record_snapshot(snapshot).await?;
+        }
+
+        let mut report = WeeklyReport::compile(storage, Duration::from_secs(7 * 24 * 60 * 60), 5).await?;
+        assert_eq!((report.errors_before, report.errors_after), (4, 5));
+        assert_eq!(report.files_with_errors, 3);
+        let regressed: Vec<_> = report.regressions.iter().map(|c| c.file_path.to_str().unwrap()).collect();
+        assert_eq!(regressed, ["src/broken.rs", "src/new.rs"]);
+        assert_eq!(report.fixes[0].error_delta(), -3);
+        assert_eq!(report.hot_spots[0].file_path, PathBuf::from("src/broken.rs"));
+
+        report.summarize(&FixedSummary).await?;
+        let markdown = report.render(ReportFormat::Markdown, None);
+        assert!(markdown.contains("> Errors are trending down."));
+        assert!(markdown.contains("Errors went from 4 to 5 (+25%)"));
+        assert!(markdown.contains("| `src/fixed.rs` | 4 → 1 (-3) | 1 → 1 (+0) |"));
+
+        let html = report.render(ReportFormat::Html, Some("<h1>{{title}}</h1>{{fixes}}{{unknown}}"));
+        assert!(html.starts_with("<h1>Weekly diagnostics report"));
+        assert!(html.contains("<code>src/fixed.rs</code>"));
+        assert!(html.ends_with("{{unknown}}"));
+        Ok(())
+    }
+}
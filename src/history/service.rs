@@ -0,0 +1,374 @@
+//! History access that respects a running daemon
+//!
+//! CLI commands use [`HistoryService`] instead of opening the history
+//! database themselves. When a daemon holds the store lock, each operation
+//! is sent over its control socket as a [`HistoryRequest`] and performed by
+//! the daemon's [`HistoryControlHandler`]; otherwise the command takes the
+//! lock and works on the database directly. See [`crate::core::daemon`].
+
+use super::{
+    AsOf, CleanPreview, DiagnosticSnapshot, FileChange, FileTrendReport, HistoryAnnotation, HistoryConfig, HistoryManager,
+    HistoryStorage, HotSpot, SnapshotDiff, TrendAnalysis, TrendOptions, WeeklyReport,
+};
+use crate::capture::importers::ImportReport;
+use crate::core::daemon::{ControlHandler, DaemonClient, LockRole, StoreLock};
+use crate::core::{CalendarConfig, Diagnostic, ErrorRecoverySystem, Subsystem};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// How long a CLI command waits for another command's lock
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// History operation sent to a daemon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum HistoryRequest {
+    Trends {
+        window_secs: u64,
+        #[serde(default)]
+        options: TrendOptions,
+    },
+    HotSpots { limit: usize },
+    FileChanges { start: SystemTime, end: SystemTime },
+    Diff { from: AsOf, to: AsOf },
+    FileTrends {
+        path: PathBuf,
+        window_secs: u64,
+        #[serde(default)]
+        options: TrendOptions,
+    },
+    Annotate { start: SystemTime, end: SystemTime, label: String },
+    Annotations,
+    RemoveAnnotation { id: i64 },
+    PreviewClean { cutoff: DateTime<Utc> },
+    Backup { cutoff: DateTime<Utc>, path: PathBuf },
+    Clean { cutoff: DateTime<Utc> },
+    Reconstruct { as_of: AsOf },
+    FilesSince { cutoff: SystemTime },
+    FileSnapshots {
+        path: PathBuf,
+        since: Option<SystemTime>,
+        limit: Option<usize>,
+    },
+    Import { diagnostics: Vec<Diagnostic> },
+    WeeklyReport {
+        window_secs: u64,
+        limit: usize,
+        #[serde(default)]
+        calendar: CalendarConfig,
+    },
+}
+
+/// History storage, local or behind a running daemon
+pub enum HistoryService {
+    /// Direct database access while holding the store lock
+    Local {
+        manager: HistoryManager,
+        _lock: StoreLock,
+    },
+    /// Operations are forwarded to the daemon
+    Daemon(DaemonClient),
+}
+
+impl HistoryService {
+    /// Route through the daemon if one is running, else lock and open the database
+    ///
+    /// Opening runs under the storage circuit breaker.
+    pub async fn connect(config: HistoryConfig, command: &str) -> Result<Self> {
+        let data_dir = crate::config::data_dir()?;
+        if let Some(client) = DaemonClient::detect(&data_dir)? {
+            tracing::debug!("Routing history operations through {}", client.owner());
+            return Ok(Self::Daemon(client));
+        }
+
+        let lock = StoreLock::acquire(&data_dir, LockRole::Cli, command, LOCK_TIMEOUT).await?;
+        let manager = ErrorRecoverySystem::persistent()
+            .execute_in(Subsystem::Storage, || HistoryManager::new(config.clone()))
+            .await?;
+        Ok(Self::Local { manager, _lock: lock })
+    }
+
+    /// Whether operations go through a daemon
+    pub fn is_remote(&self) -> bool {
+        matches!(self, Self::Daemon(_))
+    }
+
+    /// The database opened under the store lock, or `None` when a daemon owns it
+    ///
+    /// For callers that need the storage itself rather than one of the
+    /// operations below. The handle must not outlive this service, which
+    /// holds the lock.
+    pub fn storage(&self) -> Option<Arc<HistoryStorage>> {
+        match self {
+            Self::Local { manager, .. } => Some(manager.storage().clone()),
+            Self::Daemon(_) => None,
+        }
+    }
+
+    /// The process serving history when this service forwards to a daemon
+    pub fn daemon(&self) -> Option<&DaemonClient> {
+        match self {
+            Self::Local { .. } => None,
+            Self::Daemon(client) => Some(client),
+        }
+    }
+
+    pub async fn get_trends(&self, window: Duration, options: &TrendOptions) -> Result<TrendAnalysis> {
+        match self {
+            Self::Local { manager, .. } => manager.get_trends(window, options).await,
+            Self::Daemon(client) => {
+                let request = HistoryRequest::Trends {
+                    window_secs: window.as_secs(),
+                    options: options.clone(),
+                };
+                client.request(&request).await
+            }
+        }
+    }
+
+    pub async fn get_hot_spots(&self, limit: usize) -> Result<Vec<HotSpot>> {
+        match self {
+            Self::Local { manager, .. } => manager.get_hot_spots(limit).await,
+            Self::Daemon(client) => client.request(&HistoryRequest::HotSpots { limit }).await,
+        }
+    }
+
+    pub async fn get_file_changes(&self, start: SystemTime, end: SystemTime) -> Result<Vec<FileChange>> {
+        match self {
+            Self::Local { manager, .. } => manager.get_file_changes(start, end).await,
+            Self::Daemon(client) => client.request(&HistoryRequest::FileChanges { start, end }).await,
+        }
+    }
+
+    pub async fn diff_snapshots(&self, from: AsOf, to: AsOf) -> Result<SnapshotDiff> {
+        match self {
+            Self::Local { manager, .. } => manager.diff_snapshots(from, to).await,
+            Self::Daemon(client) => client.request(&HistoryRequest::Diff { from, to }).await,
+        }
+    }
+
+    pub async fn get_file_trends(
+        &self,
+        path: &Path,
+        window: Duration,
+        options: &TrendOptions,
+    ) -> Result<FileTrendReport> {
+        match self {
+            Self::Local { manager, .. } => manager.get_file_trends(path, window, options).await,
+            Self::Daemon(client) => {
+                let request = HistoryRequest::FileTrends {
+                    path: absolute(path)?,
+                    window_secs: window.as_secs(),
+                    options: options.clone(),
+                };
+                client.request(&request).await
+            }
+        }
+    }
+
+    pub async fn annotate(&self, start: SystemTime, end: SystemTime, label: &str) -> Result<i64> {
+        match self {
+            Self::Local { manager, .. } => manager.annotate(start, end, label).await,
+            Self::Daemon(client) => {
+                let request = HistoryRequest::Annotate {
+                    start,
+                    end,
+                    label: label.to_string(),
+                };
+                client.request(&request).await
+            }
+        }
+    }
+
+    pub async fn get_annotations(&self) -> Result<Vec<HistoryAnnotation>> {
+        match self {
+            Self::Local { manager, .. } => manager.get_annotations().await,
+            Self::Daemon(client) => client.request(&HistoryRequest::Annotations).await,
+        }
+    }
+
+    pub async fn remove_annotation(&self, id: i64) -> Result<bool> {
+        match self {
+            Self::Local { manager, .. } => manager.remove_annotation(id).await,
+            Self::Daemon(client) => client.request(&HistoryRequest::RemoveAnnotation { id }).await,
+        }
+    }
+
+    pub async fn preview_clean(&self, cutoff: DateTime<Utc>) -> Result<CleanPreview> {
+        match self {
+            Self::Local { manager, .. } => manager.preview_clean(cutoff).await,
+            Self::Daemon(client) => client.request(&HistoryRequest::PreviewClean { cutoff }).await,
+        }
+    }
+
+    /// Back up snapshots older than the cutoff; the daemon writes the file itself
+    pub async fn backup_before(&self, cutoff: DateTime<Utc>, path: &Path) -> Result<usize> {
+        match self {
+            Self::Local { manager, .. } => manager.backup_before(cutoff, path).await,
+            Self::Daemon(client) => {
+                let request = HistoryRequest::Backup {
+                    cutoff,
+                    path: absolute(path)?,
+                };
+                client.request(&request).await
+            }
+        }
+    }
+
+    pub async fn clean_old_data(&self, cutoff: DateTime<Utc>) -> Result<usize> {
+        match self {
+            Self::Local { manager, .. } => manager.clean_old_data(cutoff).await,
+            Self::Daemon(client) => client.request(&HistoryRequest::Clean { cutoff }).await,
+        }
+    }
+
+    /// Newest snapshot of every file at `as_of`, see [`HistoryStorage::reconstruct`]
+    pub async fn reconstruct(&self, as_of: AsOf) -> Result<Vec<DiagnosticSnapshot>> {
+        match self {
+            Self::Local { manager, .. } => Ok(manager.storage().reconstruct(as_of).await?),
+            Self::Daemon(client) => client.request(&HistoryRequest::Reconstruct { as_of }).await,
+        }
+    }
+
+    pub async fn get_files_since(&self, cutoff: SystemTime) -> Result<Vec<PathBuf>> {
+        match self {
+            Self::Local { manager, .. } => Ok(manager.storage().get_files_since(cutoff).await?),
+            Self::Daemon(client) => client.request(&HistoryRequest::FilesSince { cutoff }).await,
+        }
+    }
+
+    pub async fn get_snapshots_for_file(
+        &self,
+        path: &Path,
+        since: Option<SystemTime>,
+        limit: Option<usize>,
+    ) -> Result<Vec<DiagnosticSnapshot>> {
+        match self {
+            Self::Local { manager, .. } => Ok(manager.storage().get_snapshots_for_file(path, since, limit).await?),
+            Self::Daemon(client) => {
+                let request = HistoryRequest::FileSnapshots {
+                    path: path.to_path_buf(),
+                    since,
+                    limit,
+                };
+                client.request(&request).await
+            }
+        }
+    }
+
+    /// Record imported diagnostics, see [`ImportReport::record`]; returns the number of files recorded
+    pub async fn record_import(&self, report: &ImportReport) -> Result<usize> {
+        match self {
+            Self::Local { manager, .. } => report.record(manager.storage()).await,
+            Self::Daemon(client) => {
+                let request = HistoryRequest::Import {
+                    diagnostics: report.diagnostics.clone(),
+                };
+                client.request(&request).await
+            }
+        }
+    }
+
+    /// Compile the weekly report, see [`WeeklyReport::compile_with`]
+    pub async fn weekly_report(&self, window: Duration, limit: usize, calendar: CalendarConfig) -> Result<WeeklyReport> {
+        match self {
+            Self::Local { manager, .. } => {
+                WeeklyReport::compile_with(manager.storage().clone(), window, limit, calendar).await
+            }
+            Self::Daemon(client) => {
+                let request = HistoryRequest::WeeklyReport {
+                    window_secs: window.as_secs(),
+                    limit,
+                    calendar,
+                };
+                client.request(&request).await
+            }
+        }
+    }
+}
+
+/// The daemon runs in another directory, so relative paths are resolved here
+fn absolute(path: &Path) -> Result<PathBuf> {
+    if path.is_absolute() {
+        Ok(path.to_path_buf())
+    } else {
+        Ok(std::env::current_dir()?.join(path))
+    }
+}
+
+/// Serves [`HistoryRequest`]s inside the daemon
+pub struct HistoryControlHandler {
+    manager: HistoryManager,
+}
+
+impl HistoryControlHandler {
+    pub fn new(manager: HistoryManager) -> Self {
+        Self { manager }
+    }
+}
+
+#[async_trait]
+impl ControlHandler for HistoryControlHandler {
+    async fn handle(&self, request: serde_json::Value) -> Result<serde_json::Value> {
+        let request: HistoryRequest = serde_json::from_value(request)?;
+        let manager = &self.manager;
+        Ok(match request {
+            HistoryRequest::Trends { window_secs, options } => serde_json::to_value(
+                manager
+                    .get_trends(Duration::from_secs(window_secs), &options)
+                    .await?,
+            )?,
+            HistoryRequest::HotSpots { limit } => serde_json::to_value(manager.get_hot_spots(limit).await?)?,
+            HistoryRequest::FileChanges { start, end } => {
+                serde_json::to_value(manager.get_file_changes(start, end).await?)?
+            }
+            HistoryRequest::Diff { from, to } => serde_json::to_value(manager.diff_snapshots(from, to).await?)?,
+            HistoryRequest::FileTrends {
+                path,
+                window_secs,
+                options,
+            } => serde_json::to_value(
+                manager
+                    .get_file_trends(&path, Duration::from_secs(window_secs), &options)
+                    .await?,
+            )?,
+            HistoryRequest::Annotate { start, end, label } => {
+                serde_json::to_value(manager.annotate(start, end, &label).await?)?
+            }
+            HistoryRequest::Annotations => serde_json::to_value(manager.get_annotations().await?)?,
+            HistoryRequest::RemoveAnnotation { id } => serde_json::to_value(manager.remove_annotation(id).await?)?,
+            HistoryRequest::PreviewClean { cutoff } => serde_json::to_value(manager.preview_clean(cutoff).await?)?,
+            HistoryRequest::Backup { cutoff, path } => {
+                serde_json::to_value(manager.backup_before(cutoff, &path).await?)?
+            }
+            HistoryRequest::Clean { cutoff } => serde_json::to_value(manager.clean_old_data(cutoff).await?)?,
+            HistoryRequest::Reconstruct { as_of } => serde_json::to_value(manager.storage().reconstruct(as_of).await?)?,
+            HistoryRequest::FilesSince { cutoff } => {
+                serde_json::to_value(manager.storage().get_files_since(cutoff).await?)?
+            }
+            HistoryRequest::FileSnapshots { path, since, limit } => {
+                serde_json::to_value(manager.storage().get_snapshots_for_file(&path, since, limit).await?)?
+            }
+            HistoryRequest::Import { diagnostics } => {
+                let report = ImportReport {
+                    diagnostics,
+                    skipped: Vec::new(),
+                };
+                serde_json::to_value(report.record(manager.storage()).await?)?
+            }
+            HistoryRequest::WeeklyReport {
+                window_secs,
+                limit,
+                calendar,
+            } => serde_json::to_value(
+                WeeklyReport::compile_with(manager.storage().clone(), Duration::from_secs(window_secs), limit, calendar)
+                    .await?,
+            )?,
+        })
+    }
+}
@@ -1,6 +1,7 @@
 use super::traits::StorageBackend;
 use crate::core::errors::DatabaseError;
 use crate::core::{DatabasePool, DatabasePoolBuilder, FileHash};
+use crate::history::storage::migrations::MigrationRunner;
 use crate::history::storage::types::*;
 use async_trait::async_trait;
 use rusqlite::{params, Connection, OptionalExtension};
@@ -9,6 +10,10 @@ use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tracing::{debug, info};
 
+/// Drop index rows whose snapshot was deleted
+const SWEEP_FTS: &str =
+    "DELETE FROM diagnostic_messages_fts WHERE snapshot_id NOT IN (SELECT id FROM diagnostic_snapshots)";
+
 pub struct SqliteBackend {
     pool: Arc<DatabasePool>,
     config: HistoryConfig,
@@ -44,17 +49,28 @@ impl SqliteBackend {
     }
 
     pub(crate) fn init_schema(conn: &mut Connection) -> anyhow::Result<()> {
-        conn.execute_batch(include_str!("../migrations/v1_initial.sql"))?;
-        
-        // Set schema version
-        conn.execute(
-            "INSERT OR REPLACE INTO metadata (key, value) VALUES (?, ?)",
-            params!["schema_version", "1.0"],
-        )?;
-        
+        MigrationRunner::new().run_migrations(conn)?;
         Ok(())
     }
 
+    /// Map a `diagnostic_snapshots` row selected in the standard column order
+    fn snapshot_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<DiagnosticSnapshot> {
+        let timestamp_secs: i64 = row.get(1)?;
+        let diagnostics_json: String = row.get(8)?;
+
+        Ok(DiagnosticSnapshot {
+            id: row.get(0)?,
+            timestamp: UNIX_EPOCH + Duration::from_secs(timestamp_secs as u64),
+            file_path: PathBuf::from(row.get::<_, String>(2)?),
+            file_hash: FileHash::from_hex(&row.get::<_, String>(3)?),
+            diagnostics: serde_json::from_str(&diagnostics_json).unwrap_or_default(),
+            error_count: row.get(4)?,
+            warning_count: row.get(5)?,
+            info_count: row.get(6)?,
+            hint_count: row.get(7)?,
+        })
+    }
+
     fn convert_timestamp_to_secs(time: SystemTime) -> Result<i64, DatabaseError> {
         time.duration_since(UNIX_EPOCH)
             .map_err(|e| DatabaseError::Serialization {
@@ -105,7 +121,7 @@ impl StorageBackend for SqliteBackend {
                 params![
                     timestamp,
                     snapshot.file_path.to_string_lossy(),
-                    format!("{:?}", snapshot.file_hash),
+                    snapshot.file_hash.as_str(),
                     snapshot.error_count,
                     snapshot.warning_count,
                     snapshot.info_count,
@@ -156,7 +172,8 @@ impl StorageBackend for SqliteBackend {
                 query.push_str(&format!(" AND timestamp >= {since_timestamp}"));
             }
 
-            query.push_str(" ORDER BY timestamp DESC");
+            // Snapshots within the same second are ordered by id, like reconstruction
+            query.push_str(" ORDER BY timestamp DESC, id DESC");
 
             if let Some(limit_value) = limit {
                 query.push_str(&format!(" LIMIT {limit_value}"));
@@ -164,22 +181,7 @@ impl StorageBackend for SqliteBackend {
 
             let mut stmt = conn.prepare(&query)?;
             let snapshots = stmt
-                .query_map([&file_path_str], |row| {
-                    let timestamp_secs: i64 = row.get(1)?;
-                    let diagnostics_json: String = row.get(8)?;
-
-                    Ok(DiagnosticSnapshot {
-                        id: row.get(0)?,
-                        timestamp: UNIX_EPOCH + Duration::from_secs(timestamp_secs as u64),
-                        file_path: PathBuf::from(row.get::<_, String>(2)?),
-                        file_hash: FileHash::new(row.get::<_, String>(3)?.as_bytes()),
-                        diagnostics: serde_json::from_str(&diagnostics_json).unwrap_or_default(),
-                        error_count: row.get(4)?,
-                        warning_count: row.get(5)?,
-                        info_count: row.get(6)?,
-                        hint_count: row.get(7)?,
-                    })
-                })?
+                .query_map([&file_path_str], Self::snapshot_from_row)?
                 .collect::<rusqlite::Result<Vec<_>>>()?;
 
             Ok(snapshots)
@@ -356,6 +358,7 @@ impl StorageBackend for SqliteBackend {
                     "DELETE FROM file_stats WHERE file_path NOT IN (SELECT DISTINCT file_path FROM diagnostic_snapshots)",
                     [],
                 )?;
+                conn.execute(SWEEP_FTS, [])?;
             }
 
             Ok(deleted)
@@ -376,6 +379,344 @@ impl StorageBackend for SqliteBackend {
         Ok(deleted)
     }
 
+    async fn get_snapshots_before(
+        &self,
+        cutoff: SystemTime,
+    ) -> Result<Vec<DiagnosticSnapshot>, DatabaseError> {
+        let cutoff_ts = Self::convert_timestamp_to_secs(cutoff)?;
+
+        self.pool.with_read_connection(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, timestamp, file_path, file_hash, error_count, warning_count,
+                 info_count, hint_count, diagnostics_json
+                 FROM diagnostic_snapshots
+                 WHERE timestamp < ?
+                 ORDER BY timestamp ASC",
+            )?;
+            let snapshots = stmt
+                .query_map([cutoff_ts], Self::snapshot_from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(snapshots)
+        }).await.map_err(|e| DatabaseError::Sqlite {
+            operation: "get_snapshots_before".to_string(),
+            message: e.to_string(),
+            source: rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CANTOPEN),
+                Some(e.to_string()),
+            ),
+        })
+    }
+
+    async fn get_snapshots_between(
+        &self,
+        start: SystemTime,
+        end: SystemTime,
+    ) -> Result<Vec<DiagnosticSnapshot>, DatabaseError> {
+        let start_ts = Self::convert_timestamp_to_secs(start)?;
+        let end_ts = Self::convert_timestamp_to_secs(end)?;
+
+        self.pool.with_read_connection(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, timestamp, file_path, file_hash, error_count, warning_count,
+                 info_count, hint_count, diagnostics_json
+                 FROM diagnostic_snapshots
+                 WHERE timestamp >= ? AND timestamp <= ?
+                 ORDER BY timestamp ASC",
+            )?;
+            let snapshots = stmt
+                .query_map([start_ts, end_ts], Self::snapshot_from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(snapshots)
+        }).await.map_err(|e| DatabaseError::Sqlite {
+            operation: "get_snapshots_between".to_string(),
+            message: e.to_string(),
+            source: rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CANTOPEN),
+                Some(e.to_string()),
+            ),
+        })
+    }
+
+    async fn get_snapshots_as_of(&self, as_of: AsOf) -> Result<Vec<DiagnosticSnapshot>, DatabaseError> {
+        let time_cutoff = match as_of {
+            AsOf::Time(time) => Some(Self::convert_timestamp_to_secs(time)?),
+            AsOf::Snapshot(_) => None,
+        };
+
+        self.pool.with_read_connection(move |conn| {
+            // Snapshots sharing a timestamp are ordered by id, so pinning to a
+            // snapshot excludes anything recorded after it in the same second
+            let (cutoff_ts, max_id) = match (as_of, time_cutoff) {
+                (AsOf::Snapshot(id), _) => {
+                    let timestamp: Option<i64> = conn
+                        .query_row(
+                            "SELECT timestamp FROM diagnostic_snapshots WHERE id = ?",
+                            [id],
+                            |row| row.get(0),
+                        )
+                        .optional()?;
+                    match timestamp {
+                        Some(timestamp) => (timestamp, id),
+                        None => return Ok(Vec::new()),
+                    }
+                }
+                (AsOf::Time(_), cutoff) => (cutoff.unwrap_or_default(), i64::MAX),
+            };
+
+            let mut stmt = conn.prepare(
+                "SELECT s.id, s.timestamp, s.file_path, s.file_hash, s.error_count, s.warning_count,
+                 s.info_count, s.hint_count, s.diagnostics_json
+                 FROM diagnostic_snapshots s
+                 WHERE s.id = (
+                     SELECT latest.id FROM diagnostic_snapshots latest
+                     WHERE latest.file_path = s.file_path
+                       AND (latest.timestamp < ?1 OR (latest.timestamp = ?1 AND latest.id <= ?2))
+                     ORDER BY latest.timestamp DESC, latest.id DESC
+                     LIMIT 1
+                 )
+                 ORDER BY s.file_path ASC",
+            )?;
+            let snapshots = stmt
+                .query_map(params![cutoff_ts, max_id], Self::snapshot_from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(snapshots)
+        }).await.map_err(|e| DatabaseError::Sqlite {
+            operation: "get_snapshots_as_of".to_string(),
+            message: e.to_string(),
+            source: rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_ERROR),
+                Some(e.to_string()),
+            ),
+        })
+    }
+
+    async fn get_files_since(&self, cutoff: SystemTime) -> Result<Vec<PathBuf>, DatabaseError> {
+        let cutoff_ts = Self::convert_timestamp_to_secs(cutoff)?;
+
+        self.pool.with_read_connection(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT DISTINCT file_path FROM diagnostic_snapshots WHERE timestamp >= ?",
+            )?;
+            let files = stmt
+                .query_map([cutoff_ts], |row| Ok(PathBuf::from(row.get::<_, String>(0)?)))?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(files)
+        }).await.map_err(|e| DatabaseError::Sqlite {
+            operation: "get_files_since".to_string(),
+            message: e.to_string(),
+            source: rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CANTOPEN),
+                Some(e.to_string()),
+            ),
+        })
+    }
+
+    async fn get_time_range(&self) -> Result<Option<(SystemTime, SystemTime)>, DatabaseError> {
+        let range = self.pool.with_read_connection(|conn| {
+            Ok(conn.query_row(
+                "SELECT MIN(timestamp), MAX(timestamp) FROM diagnostic_snapshots",
+                [],
+                |row| Ok((row.get::<_, Option<i64>>(0)?, row.get::<_, Option<i64>>(1)?)),
+            )?)
+        }).await.map_err(|e| DatabaseError::Sqlite {
+            operation: "get_time_range".to_string(),
+            message: e.to_string(),
+            source: rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CANTOPEN),
+                Some(e.to_string()),
+            ),
+        })?;
+
+        Ok(match range {
+            (Some(min), Some(max)) => Some((
+                UNIX_EPOCH + Duration::from_secs(min as u64),
+                UNIX_EPOCH + Duration::from_secs(max as u64),
+            )),
+            _ => None,
+        })
+    }
+
+    async fn search_messages(
+        &self,
+        search: &MessageSearch,
+    ) -> Result<Vec<MessageMatch>, DatabaseError> {
+        let Some(expression) = search.match_expression() else {
+            return Ok(Vec::new());
+        };
+        let since_ts = search.since.map(Self::convert_timestamp_to_secs).transpose()?;
+        let until_ts = search.until.map(Self::convert_timestamp_to_secs).transpose()?;
+        let limit = search.limit;
+
+        let matches = self.pool.with_read_connection(move |conn| {
+            let mut query = String::from(
+                "SELECT snapshot_id, timestamp, file_path, line, severity, code, message
+                 FROM diagnostic_messages_fts
+                 WHERE diagnostic_messages_fts MATCH ?",
+            );
+            if let Some(since_timestamp) = since_ts {
+                query.push_str(&format!(" AND timestamp >= {since_timestamp}"));
+            }
+            if let Some(until_timestamp) = until_ts {
+                query.push_str(&format!(" AND timestamp < {until_timestamp}"));
+            }
+            query.push_str(" ORDER BY timestamp DESC");
+            if let Some(limit_value) = limit {
+                query.push_str(&format!(" LIMIT {limit_value}"));
+            }
+
+            let mut stmt = conn.prepare(&query)?;
+            let matches = stmt
+                .query_map([&expression], |row| {
+                    let timestamp_secs: i64 = row.get(1)?;
+                    let code: String = row.get(5)?;
+                    Ok(MessageMatch {
+                        snapshot_id: row.get(0)?,
+                        timestamp: UNIX_EPOCH + Duration::from_secs(timestamp_secs as u64),
+                        file_path: PathBuf::from(row.get::<_, String>(2)?),
+                        line: row.get(3)?,
+                        severity: row.get(4)?,
+                        code: (!code.is_empty()).then_some(code),
+                        message: row.get(6)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            Ok(matches)
+        }).await.map_err(|e| DatabaseError::Sqlite {
+            operation: "search_messages".to_string(),
+            message: e.to_string(),
+            source: rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_ERROR),
+                Some(e.to_string()),
+            ),
+        })?;
+
+        Ok(matches)
+    }
+
+    async fn delete_before(&self, cutoff: SystemTime) -> Result<CleanupSummary, DatabaseError> {
+        let cutoff_ts = Self::convert_timestamp_to_secs(cutoff)?;
+
+        let summary = self.pool.with_connection(move |conn| {
+            let tx = conn.transaction()?;
+            let snapshots_deleted = tx.execute(
+                "DELETE FROM diagnostic_snapshots WHERE timestamp < ?",
+                [cutoff_ts],
+            )?;
+            let file_stats_deleted = tx.execute(
+                "DELETE FROM file_stats WHERE file_path NOT IN (SELECT DISTINCT file_path FROM diagnostic_snapshots)",
+                [],
+            )?;
+            let patterns_deleted = tx.execute(
+                "DELETE FROM error_patterns WHERE last_seen < ?",
+                [cutoff_ts],
+            )?;
+            if snapshots_deleted > 0 {
+                tx.execute(SWEEP_FTS, [])?;
+            }
+            tx.commit()?;
+
+            Ok(CleanupSummary {
+                snapshots_deleted,
+                file_stats_deleted,
+                patterns_deleted,
+            })
+        }).await.map_err(|e| DatabaseError::Sqlite {
+            operation: "delete_before".to_string(),
+            message: e.to_string(),
+            source: rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CANTOPEN),
+                Some(e.to_string()),
+            ),
+        })?;
+
+        info!(
+            "Deleted {} snapshots, {} file stats and {} patterns before cutoff",
+            summary.snapshots_deleted, summary.file_stats_deleted, summary.patterns_deleted
+        );
+        Ok(summary)
+    }
+
+    async fn add_annotation(&self, annotation: &HistoryAnnotation) -> Result<i64, DatabaseError> {
+        let start_ts = Self::convert_timestamp_to_secs(annotation.start)?;
+        let end_ts = Self::convert_timestamp_to_secs(annotation.end)?;
+        let created_at = Self::convert_timestamp_to_secs(SystemTime::now())?;
+        let label = annotation.label.clone();
+
+        self.pool.with_connection(move |conn| {
+            Ok(conn.query_row(
+                "INSERT INTO annotations (start_time, end_time, label, created_at)
+                 VALUES (?, ?, ?, ?) RETURNING id",
+                params![start_ts, end_ts, label, created_at],
+                |row| row.get(0),
+            )?)
+        }).await.map_err(|e| DatabaseError::Sqlite {
+            operation: "add_annotation".to_string(),
+            message: e.to_string(),
+            source: rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CONSTRAINT),
+                Some(e.to_string()),
+            ),
+        })
+    }
+
+    async fn get_annotations(
+        &self,
+        start: Option<SystemTime>,
+        end: Option<SystemTime>,
+    ) -> Result<Vec<HistoryAnnotation>, DatabaseError> {
+        let start_ts = start.map(Self::convert_timestamp_to_secs).transpose()?;
+        let end_ts = end.map(Self::convert_timestamp_to_secs).transpose()?;
+
+        self.pool.with_read_connection(move |conn| {
+            let mut query = String::from("SELECT id, start_time, end_time, label FROM annotations WHERE 1 = 1");
+            if let Some(start_timestamp) = start_ts {
+                query.push_str(&format!(" AND end_time >= {start_timestamp}"));
+            }
+            if let Some(end_timestamp) = end_ts {
+                query.push_str(&format!(" AND start_time < {end_timestamp}"));
+            }
+            query.push_str(" ORDER BY start_time, id");
+
+            let mut stmt = conn.prepare(&query)?;
+            let annotations = stmt
+                .query_map([], |row| {
+                    let start_secs: i64 = row.get(1)?;
+                    let end_secs: i64 = row.get(2)?;
+                    Ok(HistoryAnnotation {
+                        id: row.get(0)?,
+                        start: UNIX_EPOCH + Duration::from_secs(start_secs as u64),
+                        end: UNIX_EPOCH + Duration::from_secs(end_secs as u64),
+                        label: row.get(3)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(annotations)
+        }).await.map_err(|e| DatabaseError::Sqlite {
+            operation: "get_annotations".to_string(),
+            message: e.to_string(),
+            source: rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CANTOPEN),
+                Some(e.to_string()),
+            ),
+        })
+    }
+
+    async fn delete_annotation(&self, id: i64) -> Result<bool, DatabaseError> {
+        let deleted = self.pool.with_connection(move |conn| {
+            Ok(conn.execute("DELETE FROM annotations WHERE id = ?", [id])?)
+        }).await.map_err(|e| DatabaseError::Sqlite {
+            operation: "delete_annotation".to_string(),
+            message: e.to_string(),
+            source: rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CANTOPEN),
+                Some(e.to_string()),
+            ),
+        })?;
+
+        Ok(deleted > 0)
+    }
+
     async fn export_ml_ready_data(&self, output_path: &Path) -> Result<(), DatabaseError> {
         let query = r#"
             SELECT 
@@ -1,7 +1,7 @@
 use crate::core::errors::DatabaseError;
 use crate::history::storage::types::*;
 use async_trait::async_trait;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
 #[async_trait]
@@ -46,6 +46,54 @@ pub trait StorageBackend: Send + Sync {
     /// Clean up old data based on retention policy
     async fn cleanup_old_data(&self, retention_days: u64) -> Result<usize, DatabaseError>;
 
+    /// Get all snapshots recorded before a cutoff, oldest first
+    async fn get_snapshots_before(
+        &self,
+        cutoff: SystemTime,
+    ) -> Result<Vec<DiagnosticSnapshot>, DatabaseError>;
+
+    /// Get all snapshots recorded in `[start, end]`, oldest first
+    async fn get_snapshots_between(
+        &self,
+        start: SystemTime,
+        end: SystemTime,
+    ) -> Result<Vec<DiagnosticSnapshot>, DatabaseError>;
+
+    /// Get the latest snapshot of every file as of a point in history,
+    /// ordered by file path
+    async fn get_snapshots_as_of(&self, as_of: AsOf) -> Result<Vec<DiagnosticSnapshot>, DatabaseError>;
+
+    /// Get the distinct files that have snapshots at or after a cutoff
+    async fn get_files_since(&self, cutoff: SystemTime) -> Result<Vec<PathBuf>, DatabaseError>;
+
+    /// Get the timestamps of the oldest and newest snapshots
+    async fn get_time_range(&self) -> Result<Option<(SystemTime, SystemTime)>, DatabaseError>;
+
+    /// Find recorded diagnostics whose message or code contains every search term,
+    /// newest first
+    async fn search_messages(
+        &self,
+        search: &MessageSearch,
+    ) -> Result<Vec<MessageMatch>, DatabaseError>;
+
+    /// Delete snapshots before a cutoff, along with orphaned file stats and
+    /// error patterns last seen before the cutoff
+    async fn delete_before(&self, cutoff: SystemTime) -> Result<CleanupSummary, DatabaseError>;
+
+    /// Store an annotated window, returning its id
+    async fn add_annotation(&self, annotation: &HistoryAnnotation) -> Result<i64, DatabaseError>;
+
+    /// Get annotations overlapping `[start, end)`, or all of them when unbounded,
+    /// ordered by start time
+    async fn get_annotations(
+        &self,
+        start: Option<SystemTime>,
+        end: Option<SystemTime>,
+    ) -> Result<Vec<HistoryAnnotation>, DatabaseError>;
+
+    /// Delete an annotation, returning whether it existed
+    async fn delete_annotation(&self, id: i64) -> Result<bool, DatabaseError>;
+
     /// Export data in ML-ready format
     async fn export_ml_ready_data(&self, output_path: &Path) -> Result<(), DatabaseError>;
 
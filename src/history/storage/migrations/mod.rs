@@ -2,6 +2,25 @@ use crate::core::errors::DatabaseError;
 use rusqlite::Connection;
 use std::collections::HashMap;
 
+/// Schema version written by the latest migration
+pub const SCHEMA_VERSION: &str = "3.0";
+
+/// Index snapshots recorded before the full-text table existed
+const FTS_BACKFILL: &str = r#"
+DELETE FROM diagnostic_messages_fts;
+INSERT INTO diagnostic_messages_fts
+    (message, code, file_path, snapshot_id, timestamp, severity, line)
+SELECT
+    json_extract(d.value, '$.message'),
+    COALESCE(json_extract(d.value, '$.code'), ''),
+    s.file_path,
+    s.id,
+    s.timestamp,
+    json_extract(d.value, '$.severity'),
+    json_extract(d.value, '$.range.start.line')
+FROM diagnostic_snapshots AS s, json_each(s.diagnostics_json) AS d;
+"#;
+
 pub struct MigrationRunner {
     migrations: HashMap<&'static str, &'static str>,
 }
@@ -10,29 +29,54 @@ impl MigrationRunner {
     pub fn new() -> Self {
         let mut migrations = HashMap::new();
         migrations.insert("1.0", include_str!("v1_initial.sql"));
+        migrations.insert("2.0", include_str!("v2_fts.sql"));
+        migrations.insert("3.0", include_str!("v3_annotations.sql"));
         
         Self { migrations }
     }
 
     pub fn run_migrations(&self, conn: &mut Connection) -> Result<(), DatabaseError> {
         // Get current schema version
-        let current_version = self.get_schema_version(conn)?;
+        let mut version = self.get_schema_version(conn)?;
         
-        // For now, we only have one migration, so we just ensure it's applied
-        if current_version.is_none() {
-            conn.execute_batch(self.migrations["1.0"])
+        if version.is_none() {
+            self.apply(conn, "1.0")?;
+            self.set_schema_version(conn, "1.0")?;
+            version = Some("1.0".to_string());
+        }
+
+        // Full-text index over messages; databases from 1.0 need their
+        // existing snapshots indexed once
+        if version.as_deref() == Some("1.0") {
+            self.apply(conn, "2.0")?;
+            conn.execute_batch(FTS_BACKFILL)
                 .map_err(|e| DatabaseError::Sqlite {
-                    operation: "run_migrations".to_string(),
-                    message: format!("Failed to run initial migration: {e}"),
+                    operation: "backfill_fts".to_string(),
+                    message: format!("Failed to index existing snapshots: {e}"),
                     source: e,
                 })?;
-            
-            self.set_schema_version(conn, "1.0")?;
+            self.set_schema_version(conn, "2.0")?;
+            version = Some("2.0".to_string());
+        }
+
+        // Annotated windows for trend analysis
+        if version.as_deref() == Some("2.0") {
+            self.apply(conn, "3.0")?;
+            self.set_schema_version(conn, SCHEMA_VERSION)?;
         }
         
         Ok(())
     }
 
+    fn apply(&self, conn: &Connection, version: &str) -> Result<(), DatabaseError> {
+        conn.execute_batch(self.migrations[version])
+            .map_err(|e| DatabaseError::Sqlite {
+                operation: "run_migrations".to_string(),
+                message: format!("Failed to run migration {version}: {e}"),
+                source: e,
+            })
+    }
+
     fn get_schema_version(&self, conn: &Connection) -> Result<Option<String>, DatabaseError> {
         // Check if metadata table exists
         let table_exists: bool = conn
@@ -0,0 +1,28 @@
+-- Full-text index over diagnostic messages and codes, one row per diagnostic.
+-- Rows are added by trigger on snapshot insert; deletions are swept in bulk by
+-- the backend after retention cleanup, since FTS5 can only find them by scanning.
+CREATE VIRTUAL TABLE IF NOT EXISTS diagnostic_messages_fts USING fts5(
+    message,
+    code,
+    file_path UNINDEXED,
+    snapshot_id UNINDEXED,
+    timestamp UNINDEXED,
+    severity UNINDEXED,
+    line UNINDEXED
+);
+
+CREATE TRIGGER IF NOT EXISTS diagnostic_snapshots_fts_insert
+AFTER INSERT ON diagnostic_snapshots
+BEGIN
+    INSERT INTO diagnostic_messages_fts
+        (message, code, file_path, snapshot_id, timestamp, severity, line)
+    SELECT
+        json_extract(d.value, '$.message'),
+        COALESCE(json_extract(d.value, '$.code'), ''),
+        new.file_path,
+        new.id,
+        new.timestamp,
+        json_extract(d.value, '$.severity'),
+        json_extract(d.value, '$.range.start.line')
+    FROM json_each(new.diagnostics_json) AS d;
+END;
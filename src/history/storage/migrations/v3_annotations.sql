@@ -0,0 +1,13 @@
+-- Labelled windows of history (codegen runs, large refactors) whose
+-- diagnostic churn trend analysis can exclude or mark. Timestamps are
+-- seconds since the epoch, both ends inclusive.
+CREATE TABLE IF NOT EXISTS annotations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    start_time INTEGER NOT NULL,
+    end_time INTEGER NOT NULL,
+    label TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    CHECK (end_time >= start_time)
+);
+
+CREATE INDEX IF NOT EXISTS idx_annotations_time ON annotations(start_time, end_time);
@@ -4,11 +4,14 @@ pub mod migrations;
 pub mod types;
 
 use crate::core::config::ConfigDefaults;
+use crate::core::{CalendarConfig, CalendarUnit};
 use crate::core::errors::DatabaseError;
 use crate::impl_config_defaults;
 use backend::{sqlite::SqliteBackend, StorageBackend};
 use cache::QueryCache;
-use std::path::Path;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
 pub use types::*;
@@ -133,6 +136,93 @@ impl HistoryStorage {
         self.backend.get_time_series_data(start, end, interval).await
     }
 
+    /// Time series in calendar buckets, e.g. days split at the configured
+    /// zone's midnight rather than UTC's
+    pub async fn get_calendar_series(
+        &self,
+        start: SystemTime,
+        end: SystemTime,
+        unit: CalendarUnit,
+        calendar: &CalendarConfig,
+    ) -> Result<Vec<TimeSeriesPoint>, DatabaseError> {
+        let mut buckets: BTreeMap<DateTime<Utc>, Vec<DiagnosticSnapshot>> = BTreeMap::new();
+        for snapshot in self.get_snapshots_between(start, end).await? {
+            let bucket = calendar.bucket_start(snapshot.timestamp.into(), unit);
+            buckets.entry(bucket).or_default().push(snapshot);
+        }
+        Ok(buckets
+            .into_iter()
+            .map(|(bucket, snapshots)| TimeSeriesPoint::from_snapshots(bucket.into(), &snapshots))
+            .collect())
+    }
+
+    pub async fn get_snapshots_before(
+        &self,
+        cutoff: SystemTime,
+    ) -> Result<Vec<DiagnosticSnapshot>, DatabaseError> {
+        self.backend.get_snapshots_before(cutoff).await
+    }
+
+    pub async fn get_snapshots_between(
+        &self,
+        start: SystemTime,
+        end: SystemTime,
+    ) -> Result<Vec<DiagnosticSnapshot>, DatabaseError> {
+        self.backend.get_snapshots_between(start, end).await
+    }
+
+    /// Reconstruct the workspace's diagnostics at a point in history
+    ///
+    /// Returns the newest snapshot of every file recorded at or before
+    /// `as_of`, ordered by path, so repeated reconstructions of the same
+    /// point are identical.
+    pub async fn reconstruct(&self, as_of: AsOf) -> Result<Vec<DiagnosticSnapshot>, DatabaseError> {
+        self.backend.get_snapshots_as_of(as_of).await
+    }
+
+    pub async fn get_files_since(&self, cutoff: SystemTime) -> Result<Vec<PathBuf>, DatabaseError> {
+        self.backend.get_files_since(cutoff).await
+    }
+
+    pub async fn get_time_range(&self) -> Result<Option<(SystemTime, SystemTime)>, DatabaseError> {
+        self.backend.get_time_range().await
+    }
+
+    pub async fn search_messages(
+        &self,
+        search: &MessageSearch,
+    ) -> Result<Vec<MessageMatch>, DatabaseError> {
+        self.backend.search_messages(search).await
+    }
+
+    pub async fn delete_before(&self, cutoff: SystemTime) -> Result<CleanupSummary, DatabaseError> {
+        let summary = self.backend.delete_before(cutoff).await?;
+        self.cache.invalidate_all().await;
+        Ok(summary)
+    }
+
+    /// Record an annotated window, returning its id
+    pub async fn add_annotation(&self, annotation: &HistoryAnnotation) -> Result<i64, DatabaseError> {
+        self.backend.add_annotation(annotation).await
+    }
+
+    /// Annotations overlapping `[start, end)`, ordered by start time
+    pub async fn get_annotations_between(
+        &self,
+        start: SystemTime,
+        end: SystemTime,
+    ) -> Result<Vec<HistoryAnnotation>, DatabaseError> {
+        self.backend.get_annotations(Some(start), Some(end)).await
+    }
+
+    pub async fn get_annotations(&self) -> Result<Vec<HistoryAnnotation>, DatabaseError> {
+        self.backend.get_annotations(None, None).await
+    }
+
+    pub async fn delete_annotation(&self, id: i64) -> Result<bool, DatabaseError> {
+        self.backend.delete_annotation(id).await
+    }
+
     pub async fn export_ml_ready_data(&self, output_path: &Path) -> Result<(), DatabaseError> {
         self.backend.export_ml_ready_data(output_path).await
     }
@@ -143,7 +233,6 @@ mod tests {
     use super::*;
     use crate::core::FileHash;
     use tempfile::TempDir;
-    use std::path::PathBuf;
 
     #[tokio::test]
     #[ignore] // TODO: Fix file stats updating - requires refactoring connection pool usage
@@ -194,4 +283,174 @@ mod tests {
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    fn test_config(dir: &Path) -> HistoryConfig {
+        HistoryConfig {
+            db_path: dir.join("test_history.db"),
+            retention_days: 30,
+            max_snapshots_per_file: 100,
+            auto_cleanup_interval: Duration::from_secs(3600),
+            min_connections: 1,
+            max_connections: 5,
+            connection_timeout_secs: 5,
+        }
+    }
+
+    fn snapshot_with(file: &str, timestamp: SystemTime, messages: &[(&str, Option<&str>)]) -> DiagnosticSnapshot {
+        use crate::core::{Diagnostic, DiagnosticSeverity, Position, Range};
+
+        let diagnostics: Vec<Diagnostic> = messages
+            .iter()
+            .enumerate()
+            .map(|(line, (message, code))| {
+                let mut diagnostic = Diagnostic::new(
+                    file.to_string(),
+                    Range {
+                        start: Position { line: line as u32, character: 0 },
+                        end: Position { line: line as u32, character: 4 },
+                    },
+                    DiagnosticSeverity::Error,
+                    message.to_string(),
+                    "rustc".to_string(),
+                );
+                diagnostic.code = code.map(str::to_string);
+                diagnostic
+            })
+            .collect();
+        DiagnosticSnapshot {
+            id: 0,
+            timestamp,
+            file_path: PathBuf::from(file),
+            file_hash: FileHash::new(file.as_bytes()),
+            error_count: diagnostics.len(),
+            diagnostics,
+            warning_count: 0,
+            info_count: 0,
+            hint_count: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_message_search_uses_fts_index() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new()?;
+        let storage = HistoryStorage::new(test_config(temp_dir.path())).await?;
+        let now = SystemTime::now();
+
+        storage
+            .record_snapshot(snapshot_with(
+                "/repo/src/main.rs",
+                now - Duration::from_secs(3600),
+                &[
+                    ("borrow of moved value: `config`", Some("E0382")),
+                    ("unused variable: `x`", None),
+                ],
+            ))
+            .await?;
+        storage
+            .record_snapshot(snapshot_with(
+                "/repo/src/lib.rs",
+                now,
+                &[("cannot borrow `self` as mutable", Some("E0596"))],
+            ))
+            .await?;
+
+        let found = storage.search_messages(&MessageSearch::new("Moved VALUE")).await?;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].file_path, PathBuf::from("/repo/src/main.rs"));
+        assert_eq!(found[0].code.as_deref(), Some("E0382"));
+        assert_eq!(found[0].line, Some(0));
+
+        // Newest first, limited and bounded in time
+        let borrow = storage.search_messages(&MessageSearch::new("borrow")).await?;
+        assert_eq!(borrow.len(), 2);
+        assert_eq!(borrow[0].file_path, PathBuf::from("/repo/src/lib.rs"));
+        let recent = MessageSearch::new("borrow").with_time_range(Some(now - Duration::from_secs(60)), None);
+        assert_eq!(storage.search_messages(&recent).await?.len(), 1);
+        assert_eq!(storage.search_messages(&MessageSearch::new("borrow").with_limit(1)).await?.len(), 1);
+
+        let by_code = MessageSearch::new("e0596").with_field(SearchField::Code);
+        assert_eq!(storage.search_messages(&by_code).await?.len(), 1);
+        let code_in_message = MessageSearch::new("e0596").with_field(SearchField::Message);
+        assert!(storage.search_messages(&code_in_message).await?.is_empty());
+
+        // FTS syntax in user input is searched literally
+        assert!(storage.search_messages(&MessageSearch::new("\"x\" OR NEAR(*")).await?.is_empty());
+        assert!(storage.search_messages(&MessageSearch::new("  ")).await?.is_empty());
+
+        // Deleted snapshots leave the index
+        storage.delete_before(now - Duration::from_secs(60)).await?;
+        assert!(storage.search_messages(&MessageSearch::new("moved")).await?.is_empty());
+        assert_eq!(storage.search_messages(&MessageSearch::new("borrow")).await?.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_indexes_existing_snapshots() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new()?;
+        let config = test_config(temp_dir.path());
+        {
+            let conn = rusqlite::Connection::open(&config.db_path).unwrap();
+            conn.execute_batch(include_str!("migrations/v1_initial.sql")).unwrap();
+            conn.execute(
+                "INSERT INTO metadata (key, value) VALUES ('schema_version', '1.0')",
+                [],
+            )
+            .unwrap();
+            let diagnostics = serde_json::to_string(
+                &snapshot_with("/repo/old.rs", SystemTime::now(), &[("mismatched types", Some("E0308"))])
+                    .diagnostics,
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO diagnostic_snapshots (timestamp, file_path, file_hash, error_count, warning_count,
+                 info_count, hint_count, diagnostics_json, created_at) VALUES (1, '/repo/old.rs', 'h', 1, 0, 0, 0, ?, 1)",
+                [diagnostics],
+            )
+            .unwrap();
+        }
+
+        let storage = HistoryStorage::new(config).await?;
+        let found = storage.search_messages(&MessageSearch::new("mismatched")).await?;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].file_path, PathBuf::from("/repo/old.rs"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reconstruct_latest_snapshot_per_file() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new()?;
+        let storage = HistoryStorage::new(test_config(temp_dir.path())).await?;
+        let now = SystemTime::now();
+        let earlier = now - Duration::from_secs(3600);
+
+        let first = storage
+            .record_snapshot(snapshot_with("/repo/b.rs", earlier, &[("old error", None)]))
+            .await?;
+        storage
+            .record_snapshot(snapshot_with("/repo/a.rs", earlier, &[("a error", None)]))
+            .await?;
+        let fixed = storage
+            .record_snapshot(snapshot_with("/repo/b.rs", now, &[]))
+            .await?;
+
+        let at_first = storage.reconstruct(AsOf::Snapshot(first)).await?;
+        assert_eq!(at_first.len(), 1);
+        assert_eq!(at_first[0].file_path, PathBuf::from("/repo/b.rs"));
+
+        let before_fix = storage.reconstruct(AsOf::Time(earlier + Duration::from_secs(1))).await?;
+        let files: Vec<_> = before_fix.iter().map(|s| s.file_path.clone()).collect();
+        assert_eq!(files, vec![PathBuf::from("/repo/a.rs"), PathBuf::from("/repo/b.rs")]);
+        assert_eq!(before_fix[1].diagnostics[0].message, "old error");
+
+        let latest = storage.reconstruct(AsOf::Snapshot(fixed)).await?;
+        assert_eq!(latest.len(), 2);
+        assert!(latest[1].diagnostics.is_empty());
+
+        assert!(storage.reconstruct(AsOf::Snapshot(fixed + 100)).await?.is_empty());
+        assert!(storage.reconstruct(AsOf::Time(earlier - Duration::from_secs(60))).await?.is_empty());
+
+        Ok(())
+    }
+}
@@ -81,6 +81,25 @@ pub struct TimeSeriesPoint {
     pub unique_files: usize,
 }
 
+impl TimeSeriesPoint {
+    /// Aggregate the snapshots recorded in the bucket starting at `timestamp`
+    pub fn from_snapshots(timestamp: SystemTime, snapshots: &[DiagnosticSnapshot]) -> Self {
+        let total_errors: usize = snapshots.iter().map(|s| s.error_count).sum();
+        let total_warnings: usize = snapshots.iter().map(|s| s.warning_count).sum();
+        let files: std::collections::HashSet<_> = snapshots.iter().map(|s| &s.file_path).collect();
+        let count = snapshots.len().max(1) as f64;
+        Self {
+            timestamp,
+            snapshot_count: snapshots.len(),
+            total_errors,
+            total_warnings,
+            avg_errors: total_errors as f64 / count,
+            avg_warnings: total_warnings as f64 / count,
+            unique_files: files.len(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MLDataPoint {
     pub timestamp: i64,
@@ -89,4 +108,147 @@ pub struct MLDataPoint {
     pub historical_avg_errors: f64,
     pub historical_avg_warnings: f64,
     pub file_complexity_score: f64,
-}
\ No newline at end of file
+}
+/// Rows removed by a cutoff-based cleanup
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CleanupSummary {
+    pub snapshots_deleted: usize,
+    pub file_stats_deleted: usize,
+    pub patterns_deleted: usize,
+}
+
+/// Labelled window of history, such as a codegen run or a large refactor,
+/// whose diagnostic churn should not be read as a trend
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryAnnotation {
+    pub id: i64,
+    pub start: SystemTime,
+    /// Inclusive end of the window
+    pub end: SystemTime,
+    pub label: String,
+}
+
+impl HistoryAnnotation {
+    pub fn new(start: SystemTime, end: SystemTime, label: impl Into<String>) -> Self {
+        Self {
+            id: 0, // Will be assigned by database
+            start,
+            end,
+            label: label.into(),
+        }
+    }
+
+    pub fn contains(&self, time: SystemTime) -> bool {
+        self.start <= time && time <= self.end
+    }
+
+    /// Whether any part of `[start, end)` falls inside the window
+    pub fn overlaps(&self, start: SystemTime, end: SystemTime) -> bool {
+        self.start < end && start <= self.end
+    }
+}
+
+/// How trend analysis and forecasts treat annotated windows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnotationMode {
+    /// Keep the data and report which windows it overlaps
+    #[default]
+    Mark,
+    /// Leave data recorded inside annotated windows out of the analysis
+    Exclude,
+}
+
+/// Point in recorded history to reconstruct workspace diagnostics at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AsOf {
+    /// Everything recorded up to and including this snapshot id
+    Snapshot(i64),
+    /// Everything recorded at or before this time
+    Time(SystemTime),
+}
+
+/// Columns searched by a [`MessageSearch`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SearchField {
+    Message,
+    Code,
+    /// Message or code
+    #[default]
+    Any,
+}
+
+/// Full-text search over recorded diagnostic messages and codes
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MessageSearch {
+    /// Lowercased words that must all appear
+    pub terms: Vec<String>,
+    pub field: SearchField,
+    pub since: Option<SystemTime>,
+    pub until: Option<SystemTime>,
+    pub limit: Option<usize>,
+}
+
+impl MessageSearch {
+    /// Search for diagnostics containing every word of `text`
+    pub fn new(text: &str) -> Self {
+        Self {
+            terms: text
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .map(str::to_lowercase)
+                .collect(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_field(mut self, field: SearchField) -> Self {
+        self.field = field;
+        self
+    }
+
+    /// Only match snapshots recorded in `[since, until)`
+    pub fn with_time_range(mut self, since: Option<SystemTime>, until: Option<SystemTime>) -> Self {
+        self.since = since;
+        self.until = until;
+        self
+    }
+
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// FTS5 query for the search, or `None` when there is nothing to search for.
+    /// Terms are quoted so user input can never inject FTS operators.
+    pub fn match_expression(&self) -> Option<String> {
+        if self.terms.is_empty() {
+            return None;
+        }
+        let columns = match self.field {
+            SearchField::Message => "message",
+            SearchField::Code => "code",
+            SearchField::Any => "{message code}",
+        };
+        Some(
+            self.terms
+                .iter()
+                .map(|term| format!("{columns} : \"{}\"", term.replace('"', "\"\"")))
+                .collect::<Vec<_>>()
+                .join(" AND "),
+        )
+    }
+}
+
+/// A recorded diagnostic found by a [`MessageSearch`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageMatch {
+    pub snapshot_id: i64,
+    pub timestamp: SystemTime,
+    pub file_path: PathBuf,
+    /// Zero-based start line
+    pub line: Option<u32>,
+    pub severity: Option<String>,
+    pub code: Option<String>,
+    pub message: String,
+}
@@ -1,4 +1,4 @@
-use crate::history::{HotSpot, TimeSeriesPoint, TrendAnalysis};
+use crate::history::{HistoryAnnotation, HotSpot, TimeSeriesPoint, TrendAnalysis};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::time::SystemTime;
@@ -40,6 +40,26 @@ pub struct TimeSeriesChart {
     pub x_label: String,
     pub y_label: String,
     pub series: Vec<Series>,
+    /// Annotated windows, drawn as shaded regions behind the series
+    #[serde(default)]
+    pub shaded_regions: Vec<ShadedRegion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShadedRegion {
+    pub start: f64, // Unix timestamp
+    pub end: f64,
+    pub label: String,
+}
+
+impl ShadedRegion {
+    fn from_annotation(annotation: &HistoryAnnotation) -> Result<Self> {
+        Ok(Self {
+            start: annotation.start.duration_since(std::time::UNIX_EPOCH)?.as_secs() as f64,
+            end: annotation.end.duration_since(std::time::UNIX_EPOCH)?.as_secs() as f64,
+            label: annotation.label.clone(),
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -117,6 +137,15 @@ impl VisualizationExporter {
     pub fn export_time_series(
         points: &[TimeSeriesPoint],
         title: &str,
+    ) -> Result<VisualizationData> {
+        Self::export_time_series_with_annotations(points, title, &[])
+    }
+
+    /// Export time series data with annotated windows shaded
+    pub fn export_time_series_with_annotations(
+        points: &[TimeSeriesPoint],
+        title: &str,
+        annotations: &[HistoryAnnotation],
     ) -> Result<VisualizationData> {
         let mut error_data = Vec::new();
         let mut warning_data = Vec::new();
@@ -154,6 +183,10 @@ impl VisualizationExporter {
                     color: Some("#ffd93d".to_string()),
                 },
             ],
+            shaded_regions: annotations
+                .iter()
+                .map(ShadedRegion::from_annotation)
+                .collect::<Result<_>>()?,
         };
 
         let time_range = if let (Some(first), Some(last)) = (points.first(), points.last()) {
@@ -297,6 +330,27 @@ impl VisualizationExporter {
             charts.push(ChartData::Bar(fix_time_chart));
         }
 
+        // 4. Recurring issues by taxonomy, comparable across languages
+        if !trends.taxonomy_breakdown.is_empty() {
+            let taxonomy_chart = BarChart {
+                title: "Recurring Issues by Taxonomy".to_string(),
+                x_label: "Taxonomy".to_string(),
+                y_label: "Occurrences".to_string(),
+                categories: trends
+                    .taxonomy_breakdown
+                    .iter()
+                    .map(|trend| trend.taxonomy.to_string())
+                    .collect(),
+                values: trends
+                    .taxonomy_breakdown
+                    .iter()
+                    .map(|trend| trend.occurrences as f64)
+                    .collect(),
+                colors: None,
+            };
+            charts.push(ChartData::Bar(taxonomy_chart));
+        }
+
         Ok(VisualizationData {
             charts,
             metadata: VisualizationMetadata {
@@ -332,12 +386,33 @@ impl VisualizationExporter {
             "layout": {
                 "title": data.metadata.title,
                 "showlegend": true,
+                "shapes": [],
             }
         });
 
         for chart in &data.charts {
             match chart {
                 ChartData::TimeSeries(ts) => {
+                    for region in &ts.shaded_regions {
+                        let shape = serde_json::json!({
+                            "type": "rect",
+                            "xref": "x",
+                            "yref": "paper",
+                            "x0": region.start,
+                            "x1": region.end,
+                            "y0": 0,
+                            "y1": 1,
+                            "fillcolor": "#a0a0a0",
+                            "opacity": 0.25,
+                            "line": { "width": 0 },
+                            "layer": "below",
+                            "label": { "text": region.label, "textposition": "top center" },
+                        });
+                        plotly_data["layout"]["shapes"]
+                            .as_array_mut()
+                            .unwrap()
+                            .push(shape);
+                    }
                     for series in &ts.series {
                         let trace = serde_json::json!({
                             "type": "scatter",
@@ -395,6 +470,21 @@ impl VisualizationExporter {
                                 "display": true,
                                 "text": ts.title,
                             },
+                            // Rendered by chartjs-plugin-annotation
+                            "plugins": {
+                                "annotation": {
+                                    "annotations": ts.shaded_regions.iter().map(|region| {
+                                        serde_json::json!({
+                                            "type": "box",
+                                            "xMin": region.start,
+                                            "xMax": region.end,
+                                            "backgroundColor": "rgba(160, 160, 160, 0.25)",
+                                            "borderWidth": 0,
+                                            "label": { "display": true, "content": region.label },
+                                        })
+                                    }).collect::<Vec<_>>(),
+                                },
+                            },
                         }
                     });
                     chartjs_configs.push(config);
@@ -422,9 +512,7 @@ impl VisualizationExporter {
         for chart in &data.charts {
             match chart {
                 ChartData::TimeSeries(ts) => {
-                    let spec = serde_json::json!({
-                        "$schema": "https://vega.github.io/schema/vega-lite/v5.json",
-                        "title": ts.title,
+                    let line = serde_json::json!({
                         "data": {
                             "values": ts.series.iter().flat_map(|s| {
                                 s.data.iter().map(|p| {
@@ -454,6 +542,27 @@ impl VisualizationExporter {
                             }
                         }
                     });
+                    let spec = if ts.shaded_regions.is_empty() {
+                        let mut spec = line;
+                        spec["$schema"] = "https://vega.github.io/schema/vega-lite/v5.json".into();
+                        spec["title"] = ts.title.clone().into();
+                        spec
+                    } else {
+                        let shading = serde_json::json!({
+                            "data": { "values": ts.shaded_regions },
+                            "mark": { "type": "rect", "color": "#a0a0a0", "opacity": 0.25 },
+                            "encoding": {
+                                "x": { "field": "start", "type": "temporal" },
+                                "x2": { "field": "end" },
+                                "tooltip": { "field": "label", "type": "nominal" },
+                            }
+                        });
+                        serde_json::json!({
+                            "$schema": "https://vega.github.io/schema/vega-lite/v5.json",
+                            "title": ts.title,
+                            "layer": [shading, line],
+                        })
+                    };
                     vega_specs.push(spec);
                 }
                 _ => {} // Add more chart types as needed
@@ -545,7 +654,11 @@ pub fn generate_html_dashboard(data: &VisualizationData) -> Result<String> {
             div.id = 'chart' + index;
             chartsDiv.appendChild(div);
             
-            Plotly.newPlot(div.id, [trace], plotlyConfig.layout || {{}});
+            // Annotated windows only make sense on the time axis
+            const layout = Object.assign({{}}, plotlyConfig.layout, {{
+                shapes: trace.type === 'scatter' ? plotlyConfig.layout.shapes : [],
+            }});
+            Plotly.newPlot(div.id, [trace], layout);
         }});
     </script>
 </body>
@@ -589,4 +702,41 @@ mod tests {
             panic!("Expected TimeSeries chart");
         }
     }
+
+    #[test]
+    fn test_annotations_are_shaded() {
+        let points = vec![TimeSeriesPoint {
+            timestamp: UNIX_EPOCH + std::time::Duration::from_secs(3600),
+            snapshot_count: 1,
+            total_errors: 50,
+            total_warnings: 0,
+            avg_errors: 50.0,
+            avg_warnings: 0.0,
+            unique_files: 1,
+        }];
+        let annotation = HistoryAnnotation::new(
+            UNIX_EPOCH + std::time::Duration::from_secs(3000),
+            UNIX_EPOCH + std::time::Duration::from_secs(4000),
+            "codegen migration",
+        );
+
+        let data = VisualizationExporter::export_time_series_with_annotations(&points, "Trends", &[annotation])
+            .unwrap();
+        let ChartData::TimeSeries(ts) = &data.charts[0] else {
+            panic!("Expected TimeSeries chart");
+        };
+        assert_eq!(ts.shaded_regions.len(), 1);
+        assert_eq!(ts.shaded_regions[0].start, 3000.0);
+
+        let plotly: serde_json::Value =
+            serde_json::from_str(&VisualizationExporter::export_for_library(&data, VisualizationLibrary::Plotly).unwrap())
+                .unwrap();
+        assert_eq!(plotly["layout"]["shapes"][0]["x1"], 4000.0);
+        assert_eq!(plotly["layout"]["shapes"][0]["label"]["text"], "codegen migration");
+
+        let vega: serde_json::Value =
+            serde_json::from_str(&VisualizationExporter::export_for_library(&data, VisualizationLibrary::Vega).unwrap())
+                .unwrap();
+        assert_eq!(vega[0]["layer"][0]["mark"]["type"], "rect");
+    }
 }
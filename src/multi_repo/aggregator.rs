@@ -1,16 +1,77 @@
 //! Diagnostic aggregation across multiple repositories
-
-use anyhow::Result;
+//!
+//! Analyzing hundreds of repositories takes long enough that runs get
+//! interrupted, so [`DiagnosticAggregator::analyze_with_checkpoints`] records
+//! each finished repository in the registry and can pick up where a run
+//! stopped. A [`Shard`] splits the repositories between parallel CI jobs;
+//! relations between diagnostics are only found within a shard.
+
+use anyhow::{anyhow, Result};
 use futures::future::join_all;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
 use tokio::sync::{Mutex, Semaphore};
 
-use super::registry::RepositoryInfo;
+use super::registry::{RepositoryInfo, RepositoryRegistry};
 use crate::core::types::{Diagnostic, DiagnosticSeverity};
 
+/// One of `count` slices of the registered repositories, written `index/count`
+///
+/// Repositories are assigned by a hash of their id, so each keeps its shard
+/// when others are registered or removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shard {
+    /// One-based
+    pub index: usize,
+    pub count: usize,
+}
+
+impl Shard {
+    pub fn includes(&self, repo_id: &str) -> bool {
+        let digest = Sha256::digest(repo_id.as_bytes());
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&digest[..8]);
+        (u64::from_be_bytes(bytes) % self.count as u64) as usize == self.index - 1
+    }
+}
+
+impl FromStr for Shard {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let parsed = value
+            .split_once('/')
+            .and_then(|(index, count)| Some((index.trim().parse().ok()?, count.trim().parse().ok()?)));
+        match parsed {
+            Some((index, count)) if count > 0 && (1..=count).contains(&index) => Ok(Self { index, count }),
+            _ => Err(anyhow!("Invalid shard '{value}'; expected INDEX/COUNT such as 2/4")),
+        }
+    }
+}
+
+impl fmt::Display for Shard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.index, self.count)
+    }
+}
+
+/// Outcome of a checkpointed analysis run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnalysisRun {
+    pub diagnostics: Vec<AggregatedDiagnostic>,
+    /// Repositories analyzed in this run
+    pub analyzed: usize,
+    /// Repositories taken from an earlier run's checkpoints
+    pub resumed: usize,
+    /// Repositories that failed, with the error; a resumed run retries them
+    pub failed: Vec<(String, String)>,
+}
+
 /// Aggregated diagnostic across repositories
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AggregatedDiagnostic {
@@ -85,56 +146,126 @@ impl DiagnosticAggregator {
         &self,
         repositories: Vec<RepositoryInfo>,
     ) -> Result<Vec<AggregatedDiagnostic>> {
-        // Collect diagnostics from all repositories in parallel
+        let mut collected = Vec::with_capacity(repositories.len());
+        for (repo, result) in self.collect_all(repositories, None).await {
+            match result {
+                Ok(diagnostics) => collected.push((repo, diagnostics)),
+                Err(e) => eprintln!("Failed to collect diagnostics: {e}"),
+            }
+        }
+        self.aggregate(collected).await
+    }
+
+    /// Analyze repositories, recording each one finished in the registry under `run_key`
+    ///
+    /// With `resume`, repositories already finished under `run_key` are not
+    /// collected again; otherwise the run's old checkpoints are discarded.
+    pub async fn analyze_with_checkpoints(
+        &self,
+        repositories: Vec<RepositoryInfo>,
+        registry: &RepositoryRegistry,
+        run_key: &str,
+        resume: bool,
+    ) -> Result<AnalysisRun> {
+        let mut finished = if resume {
+            registry.load_checkpoints(run_key).await?
+        } else {
+            registry.clear_checkpoints(run_key).await?;
+            HashMap::new()
+        };
+
+        let mut run = AnalysisRun::default();
+        let mut collected = Vec::with_capacity(repositories.len());
+        let mut pending = Vec::new();
+        for repo in repositories {
+            match finished.remove(&repo.id) {
+                Some(diagnostics) => {
+                    run.resumed += 1;
+                    collected.push((repo, diagnostics));
+                }
+                None => pending.push(repo),
+            }
+        }
+
+        for (repo, result) in self.collect_all(pending, Some((registry, run_key))).await {
+            match result {
+                Ok(diagnostics) => {
+                    run.analyzed += 1;
+                    collected.push((repo, diagnostics));
+                }
+                Err(e) => run.failed.push((repo.id, e.to_string())),
+            }
+        }
+
+        run.diagnostics = self.aggregate(collected).await?;
+        Ok(run)
+    }
+
+    /// Collect diagnostics from the repositories in parallel, saving a checkpoint for each if asked
+    async fn collect_all(
+        &self,
+        repositories: Vec<RepositoryInfo>,
+        checkpoint: Option<(&RepositoryRegistry, &str)>,
+    ) -> Vec<(RepositoryInfo, Result<Vec<Diagnostic>>)> {
         let mut tasks = Vec::with_capacity(repositories.len());
 
         for repo in &repositories {
             let repo = repo.clone();
             let semaphore = self.semaphore.clone();
             let cache = self.cache.clone();
+            let checkpoint = checkpoint.map(|(registry, run_key)| (registry.clone(), run_key.to_string()));
 
             tasks.push(tokio::spawn(async move {
-                let _permit = semaphore.acquire().await.unwrap();
+                let _permit = semaphore.acquire().await?;
                 let diagnostics = Self::collect_diagnostics(&repo).await?;
 
+                // Checkpoint as soon as a repository finishes, so a crash loses as little as possible
+                if let Some((registry, run_key)) = checkpoint {
+                    registry.save_checkpoint(&run_key, &repo.id, &diagnostics).await?;
+                }
+
                 // Cache the results
                 let mut cache_guard = cache.lock().await;
                 cache_guard.insert(repo.id.clone(), diagnostics.clone());
 
-                Ok::<(RepositoryInfo, Vec<Diagnostic>), anyhow::Error>((repo, diagnostics))
+                Ok::<Vec<Diagnostic>, anyhow::Error>(diagnostics)
             }));
         }
 
         // Wait for all collections to complete
         let results = join_all(tasks).await;
 
-        // Pre-allocate based on expected diagnostics (estimate ~10 per repo)
-        let mut all_diagnostics = Vec::with_capacity(repositories.len() * 10);
-        let mut repo_diagnostics_map = HashMap::with_capacity(repositories.len());
+        repositories
+            .into_iter()
+            .zip(results)
+            .map(|(repo, result)| {
+                let result = result.unwrap_or_else(|e| Err(anyhow!("Task failed: {e}")));
+                (repo, result)
+            })
+            .collect()
+    }
 
-        for result in results {
-            match result {
-                Ok(Ok((repo, diagnostics))) => {
-                    repo_diagnostics_map
-                        .insert(repo.id.clone(), (repo.clone(), diagnostics.clone()));
-
-                    // Convert to aggregated diagnostics
-                    for diagnostic in diagnostics {
-                        let relative_path = PathBuf::from(&diagnostic.file);
-
-                        all_diagnostics.push(AggregatedDiagnostic {
-                            diagnostic: diagnostic.clone(),
-                            repository_id: repo.id.clone(),
-                            repository_name: repo.name.clone(),
-                            relative_path,
-                            cross_repo_impact: 0.0, // Will be calculated
-                            related_diagnostics: Vec::new(), // Will be populated
-                        });
-                    }
-                }
-                Ok(Err(e)) => eprintln!("Failed to collect diagnostics: {e}"),
-                Err(e) => eprintln!("Task failed: {e}"),
+    /// Relate and score the diagnostics collected from each repository
+    async fn aggregate(&self, collected: Vec<(RepositoryInfo, Vec<Diagnostic>)>) -> Result<Vec<AggregatedDiagnostic>> {
+        // Pre-allocate based on expected diagnostics (estimate ~10 per repo)
+        let mut all_diagnostics = Vec::with_capacity(collected.len() * 10);
+        let mut repo_diagnostics_map = HashMap::with_capacity(collected.len());
+
+        for (repo, diagnostics) in collected {
+            // Convert to aggregated diagnostics
+            for diagnostic in &diagnostics {
+                let relative_path = PathBuf::from(&diagnostic.file);
+
+                all_diagnostics.push(AggregatedDiagnostic {
+                    diagnostic: diagnostic.clone(),
+                    repository_id: repo.id.clone(),
+                    repository_name: repo.name.clone(),
+                    relative_path,
+                    cross_repo_impact: 0.0, // Will be calculated
+                    related_diagnostics: Vec::new(), // Will be populated
+                });
             }
+            repo_diagnostics_map.insert(repo.id.clone(), (repo, diagnostics));
         }
 
         // Find relationships between diagnostics
@@ -328,3 +459,80 @@ impl DiagnosticAggregator {
         cache.clear();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{Position, Range};
+
+    fn repo(id: &str) -> RepositoryInfo {
+        RepositoryInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            path: PathBuf::from(format!("/src/{id}")),
+            remote_url: None,
+            primary_language: None,
+            build_system: None,
+            is_monorepo_member: false,
+            monorepo_id: None,
+            tags: Vec::new(),
+            active: true,
+            last_diagnostic_run: None,
+            metadata: serde_json::json!({}),
+        }
+    }
+
+    #[test]
+    fn test_shards_partition_repositories() {
+        assert!("0/4".parse::<Shard>().is_err());
+        assert!("5/4".parse::<Shard>().is_err());
+        let shards: Vec<Shard> = (1..=4).map(|index| format!("{index}/4").parse().unwrap()).collect();
+        assert_eq!(shards[1].to_string(), "2/4");
+
+        let ids: Vec<String> = (0..200).map(|n| format!("repo-{n}")).collect();
+        for id in &ids {
+            assert_eq!(shards.iter().filter(|shard| shard.includes(id)).count(), 1);
+        }
+        // Every shard gets a fair share
+        assert!(shards.iter().all(|shard| ids.iter().filter(|id| shard.includes(id)).count() > 25));
+    }
+
+    #[tokio::test]
+    async fn test_resume_skips_checkpointed_repositories() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let registry = RepositoryRegistry::load_or_create(&dir.path().join("repos.db")).await?;
+        for id in ["api", "web", "jobs"] {
+            registry.register(repo(id)).await?;
+        }
+        let aggregator = DiagnosticAggregator::new(2);
+
+        // A run that got through `api` before dying
+        let diagnostic = Diagnostic::new(
+            "src/lib.rs".to_string(),
+            Range {
+                start: Position { line: 1, character: 0 },
+                end: Position { line: 1, character: 4 },
+            },
+            DiagnosticSeverity::Error,
+            "mismatched types".to_string(),
+            "rustc".to_string(),
+        );
+        registry.save_checkpoint("all#1/1", "api", &[diagnostic]).await?;
+
+        let run = aggregator
+            .analyze_with_checkpoints(registry.list_active().await?, &registry, "all#1/1", true)
+            .await?;
+        assert_eq!((run.resumed, run.analyzed), (1, 2));
+        assert_eq!(run.diagnostics.len(), 1);
+        assert_eq!(run.diagnostics[0].repository_id, "api");
+        assert_eq!(registry.load_checkpoints("all#1/1").await?.len(), 3);
+
+        // Without --resume the old checkpoints are dropped and everything is collected again
+        let run = aggregator
+            .analyze_with_checkpoints(registry.list_active().await?, &registry, "all#1/1", false)
+            .await?;
+        assert_eq!((run.resumed, run.analyzed), (0, 3));
+        assert!(run.diagnostics.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,176 @@
+//! Storage backends for the repository registry and team database
+//!
+//! [`RepositoryRegistry`](super::RepositoryRegistry) and the assignment
+//! commands talk to storage through [`RegistryBackend`] and [`TeamBackend`].
+//! A local SQLite file is the default; with the `postgres` feature and
+//! `multi_repo.registry_url` set, a team shares one PostgreSQL database for
+//! both across machines.
+//!
+//! Every repository row carries a version that each write increments.
+//! Writers that read a repository, change it and write it back pass the
+//! version they read as [`ExpectedVersion::Version`]; if another machine
+//! wrote in between, the write fails with a [`RegistryConflict`] instead of
+//! silently discarding the other change.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use super::collaboration::{AssignmentStatus, DiagnosticAssignment, TeamDatabase, TeamMember, TeamMetrics};
+use super::registry::{Fleet, RepositoryInfo, RepositoryRelation, SqliteRegistry};
+use crate::core::config::MultiRepoConfig;
+use crate::core::types::Diagnostic;
+
+/// What a repository write expects to find
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExpectedVersion {
+    /// Write unconditionally; the last writer wins
+    Any,
+    /// The repository must not be registered yet
+    New,
+    /// The repository must still be at this version
+    Version(u64),
+}
+
+impl fmt::Display for ExpectedVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Any => f.write_str("any version"),
+            Self::New => f.write_str("no existing registration"),
+            Self::Version(version) => write!(f, "version {version}"),
+        }
+    }
+}
+
+/// A value together with the version it was read at
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Versioned<T> {
+    pub value: T,
+    pub version: u64,
+}
+
+/// A repository write lost a race with another writer
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Repository '{id}' was changed concurrently: expected {expected}, found {}", describe_actual(*.actual))]
+pub struct RegistryConflict {
+    pub id: String,
+    pub expected: ExpectedVersion,
+    /// Version found instead, or `None` when the repository isn't registered
+    pub actual: Option<u64>,
+}
+
+fn describe_actual(actual: Option<u64>) -> String {
+    actual.map_or_else(|| "no registration".to_string(), |version| format!("version {version}"))
+}
+
+/// Storage of repositories, their relations, fleets and analysis checkpoints
+#[async_trait]
+pub trait RegistryBackend: Send + Sync {
+    /// Insert or update a repository, returning its new version
+    ///
+    /// Fails with a [`RegistryConflict`] when `expected` doesn't hold.
+    async fn register(&self, info: &RepositoryInfo, expected: ExpectedVersion) -> Result<u64>;
+
+    async fn get(&self, id: &str) -> Result<Option<Versioned<RepositoryInfo>>>;
+
+    /// Repositories ordered by name
+    async fn list(&self, include_inactive: bool) -> Result<Vec<RepositoryInfo>>;
+
+    /// Record `at` as a repository's last diagnostic run
+    async fn touch_diagnostic_run(&self, repo_id: &str, at: DateTime<Utc>) -> Result<()>;
+
+    /// Add or replace a relation between two repositories
+    async fn add_relation(&self, relation: &RepositoryRelation) -> Result<()>;
+
+    /// Relations with `repo_id` at either end, or every relation
+    async fn relations(&self, repo_id: Option<&str>) -> Result<Vec<RepositoryRelation>>;
+
+    /// Save a fleet, replacing any fleet with the same name
+    async fn save_fleet(&self, fleet: &Fleet) -> Result<()>;
+
+    async fn get_fleet(&self, name: &str) -> Result<Option<Fleet>>;
+
+    /// Fleets ordered by name
+    async fn list_fleets(&self) -> Result<Vec<Fleet>>;
+
+    /// Delete a fleet; returns whether it existed
+    async fn delete_fleet(&self, name: &str) -> Result<bool>;
+
+    /// Record that `repo_id` finished analysis in the run `run_key`
+    async fn save_checkpoint(&self, run_key: &str, repo_id: &str, diagnostics: &[Diagnostic]) -> Result<()>;
+
+    /// Diagnostics of the repositories already finished in the run `run_key`
+    async fn load_checkpoints(&self, run_key: &str) -> Result<HashMap<String, Vec<Diagnostic>>>;
+
+    /// Forget the checkpoints of the run `run_key`; returns how many were removed
+    async fn clear_checkpoints(&self, run_key: &str) -> Result<usize>;
+}
+
+/// Storage of team members and their diagnostic assignments
+#[async_trait]
+pub trait TeamBackend: Send + Sync {
+    async fn add_member(&self, member: TeamMember) -> Result<()>;
+
+    async fn get_member(&self, id: &str) -> Result<Option<TeamMember>>;
+
+    /// Active members ordered by name
+    async fn list_members(&self) -> Result<Vec<TeamMember>>;
+
+    async fn create_assignment(&self, assignment: DiagnosticAssignment) -> Result<()>;
+
+    async fn update_assignment_status(
+        &self,
+        assignment_id: &str,
+        new_status: AssignmentStatus,
+        updated_by: &str,
+    ) -> Result<()>;
+
+    async fn get_member_assignments(
+        &self,
+        member_id: &str,
+        status_filter: Option<AssignmentStatus>,
+    ) -> Result<Vec<DiagnosticAssignment>>;
+
+    /// Active members with their resolved count and average resolution time
+    async fn get_team_metrics(&self) -> Result<TeamMetrics>;
+}
+
+/// The backends a `multi_repo` configuration selects
+pub struct Backends {
+    pub registry: Arc<dyn RegistryBackend>,
+    /// `None` when no team database is configured
+    pub team: Option<Arc<dyn TeamBackend>>,
+}
+
+/// Open the shared database at `registry_url`, or else the SQLite files at
+/// `registry_path` and `team_db_path`
+pub async fn open(config: &MultiRepoConfig) -> Result<Backends> {
+    if let Some(url) = &config.registry_url {
+        return open_shared(url).await;
+    }
+
+    let registry = Arc::new(SqliteRegistry::open(&config.registry_path).await?);
+    let team = match &config.team_db_path {
+        Some(path) => Some(Arc::new(TeamDatabase::connect(path).await?) as Arc<dyn TeamBackend>),
+        None => None,
+    };
+    Ok(Backends { registry, team })
+}
+
+#[cfg(feature = "postgres")]
+async fn open_shared(url: &str) -> Result<Backends> {
+    let backend = Arc::new(super::postgres::PostgresBackend::connect(url).await?);
+    Ok(Backends {
+        registry: backend.clone(),
+        team: Some(backend),
+    })
+}
+
+#[cfg(not(feature = "postgres"))]
+async fn open_shared(_url: &str) -> Result<Backends> {
+    anyhow::bail!("multi_repo.registry_url is set, but lspbridge was built without the `postgres` feature")
+}
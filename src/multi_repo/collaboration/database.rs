@@ -1,11 +1,14 @@
 use anyhow::{Context, Result};
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection, OptionalExtension};
 use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+use super::notifications::{NotificationChannel, NotificationPreferences};
 use super::types::{TeamMember, TeamRole, DiagnosticAssignment, AssignmentStatus, Priority, TeamMetrics};
+use crate::multi_repo::backend::TeamBackend;
 
 /// Team collaboration database
 pub struct TeamDatabase {
@@ -79,6 +82,16 @@ impl TeamDatabase {
                 UNIQUE(member_id, repository_id)
             );
             
+            CREATE TABLE IF NOT EXISTS notification_preferences (
+                member_id TEXT PRIMARY KEY,
+                channels TEXT NOT NULL,
+                slack_user TEXT,
+                on_created BOOLEAN NOT NULL,
+                on_resolved BOOLEAN NOT NULL,
+                updated_at INTEGER NOT NULL,
+                FOREIGN KEY (member_id) REFERENCES team_members(id)
+            );
+            
             CREATE INDEX IF NOT EXISTS idx_assignments_assignee ON diagnostic_assignments(assignee_id);
             CREATE INDEX IF NOT EXISTS idx_assignments_status ON diagnostic_assignments(status);
             CREATE INDEX IF NOT EXISTS idx_assignments_repo ON diagnostic_assignments(repository_id);
@@ -269,6 +282,21 @@ impl TeamDatabase {
         Ok(())
     }
 
+    /// Get an assignment by ID
+    pub async fn get_assignment(&self, id: &str) -> Result<Option<DiagnosticAssignment>> {
+        let conn = self.conn.lock().await;
+
+        let assignment = conn
+            .query_row(
+                "SELECT * FROM diagnostic_assignments WHERE id = ?1",
+                params![id],
+                Self::map_assignment_row,
+            )
+            .optional()?;
+
+        Ok(assignment)
+    }
+
     /// Get assignments for a team member
     pub async fn get_member_assignments(
         &self,
@@ -367,6 +395,69 @@ impl TeamDatabase {
         Ok(())
     }
 
+    /// Store a member's notification preferences, replacing earlier ones
+    pub async fn set_notification_preferences(&self, preferences: &NotificationPreferences) -> Result<()> {
+        let conn = self.conn.lock().await;
+        let now = Utc::now().timestamp();
+
+        let channels = preferences
+            .channels
+            .iter()
+            .map(NotificationChannel::as_str)
+            .collect::<Vec<_>>()
+            .join(",");
+
+        conn.execute(
+            r#"
+            INSERT INTO notification_preferences
+            (member_id, channels, slack_user, on_created, on_resolved, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            ON CONFLICT(member_id) DO UPDATE SET
+                channels = ?2, slack_user = ?3, on_created = ?4, on_resolved = ?5, updated_at = ?6
+            "#,
+            params![
+                preferences.member_id,
+                channels,
+                preferences.slack_user,
+                preferences.on_created,
+                preferences.on_resolved,
+                now,
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Get a member's notification preferences, if they stored any
+    pub async fn get_notification_preferences(&self, member_id: &str) -> Result<Option<NotificationPreferences>> {
+        let conn = self.conn.lock().await;
+
+        let preferences = conn
+            .query_row(
+                r#"
+                SELECT member_id, channels, slack_user, on_created, on_resolved
+                FROM notification_preferences WHERE member_id = ?1
+                "#,
+                params![member_id],
+                |row| {
+                    Ok(NotificationPreferences {
+                        member_id: row.get(0)?,
+                        channels: row
+                            .get::<_, String>(1)?
+                            .split(',')
+                            .filter_map(NotificationChannel::parse)
+                            .collect(),
+                        slack_user: row.get(2)?,
+                        on_created: row.get(3)?,
+                        on_resolved: row.get(4)?,
+                    })
+                },
+            )
+            .optional()?;
+
+        Ok(preferences)
+    }
+
     /// Update member metrics when assignment is resolved
     async fn update_member_metrics(&self, assignment_id: &str) -> Result<()> {
         let conn = self.conn.lock().await;
@@ -417,8 +508,48 @@ impl TeamDatabase {
     }
 }
 
+#[async_trait]
+impl TeamBackend for TeamDatabase {
+    async fn add_member(&self, member: TeamMember) -> Result<()> {
+        TeamDatabase::add_member(self, member).await
+    }
+
+    async fn get_member(&self, id: &str) -> Result<Option<TeamMember>> {
+        TeamDatabase::get_member(self, id).await
+    }
+
+    async fn list_members(&self) -> Result<Vec<TeamMember>> {
+        TeamDatabase::list_members(self).await
+    }
+
+    async fn create_assignment(&self, assignment: DiagnosticAssignment) -> Result<()> {
+        TeamDatabase::create_assignment(self, assignment).await
+    }
+
+    async fn update_assignment_status(
+        &self,
+        assignment_id: &str,
+        new_status: AssignmentStatus,
+        updated_by: &str,
+    ) -> Result<()> {
+        TeamDatabase::update_assignment_status(self, assignment_id, new_status, updated_by).await
+    }
+
+    async fn get_member_assignments(
+        &self,
+        member_id: &str,
+        status_filter: Option<AssignmentStatus>,
+    ) -> Result<Vec<DiagnosticAssignment>> {
+        TeamDatabase::get_member_assignments(self, member_id, status_filter).await
+    }
+
+    async fn get_team_metrics(&self) -> Result<TeamMetrics> {
+        TeamDatabase::get_team_metrics(self).await
+    }
+}
+
 // Helper functions for type conversion
-fn role_to_string(role: &TeamRole) -> String {
+pub(crate) fn role_to_string(role: &TeamRole) -> String {
     match role {
         TeamRole::Viewer => "viewer",
         TeamRole::Developer => "developer",
@@ -429,7 +560,7 @@ fn role_to_string(role: &TeamRole) -> String {
     }.to_string()
 }
 
-fn string_to_role(s: &str) -> TeamRole {
+pub(crate) fn string_to_role(s: &str) -> TeamRole {
     match s {
         "viewer" => TeamRole::Viewer,
         "developer" => TeamRole::Developer,
@@ -441,7 +572,7 @@ fn string_to_role(s: &str) -> TeamRole {
     }
 }
 
-fn status_to_string(status: &AssignmentStatus) -> String {
+pub(crate) fn status_to_string(status: &AssignmentStatus) -> String {
     match status {
         AssignmentStatus::Open => "open",
         AssignmentStatus::InProgress => "in_progress",
@@ -451,7 +582,7 @@ fn status_to_string(status: &AssignmentStatus) -> String {
     }.to_string()
 }
 
-fn string_to_status(s: &str) -> AssignmentStatus {
+pub(crate) fn string_to_status(s: &str) -> AssignmentStatus {
     match s {
         "open" => AssignmentStatus::Open,
         "in_progress" => AssignmentStatus::InProgress,
@@ -462,7 +593,7 @@ fn string_to_status(s: &str) -> AssignmentStatus {
     }
 }
 
-fn priority_to_string(priority: &Priority) -> String {
+pub(crate) fn priority_to_string(priority: &Priority) -> String {
     match priority {
         Priority::Critical => "critical",
         Priority::High => "high",
@@ -471,7 +602,7 @@ fn priority_to_string(priority: &Priority) -> String {
     }.to_string()
 }
 
-fn string_to_priority(s: &str) -> Priority {
+pub(crate) fn string_to_priority(s: &str) -> Priority {
     match s {
         "critical" => Priority::Critical,
         "high" => Priority::High,
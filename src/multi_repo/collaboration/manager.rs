@@ -3,11 +3,13 @@ use std::path::Path;
 use tracing::{debug, info};
 
 use super::database::TeamDatabase;
+use super::notifications::{AssignmentEvent, AssignmentNotifier, NotificationPreferences};
 use super::types::{TeamMember, DiagnosticAssignment, AssignmentStatus, TeamMetrics};
 
 /// Manages team collaboration features
 pub struct CollaborationManager {
     database: TeamDatabase,
+    notifier: Option<AssignmentNotifier>,
 }
 
 impl CollaborationManager {
@@ -17,7 +19,16 @@ impl CollaborationManager {
         
         info!("Collaboration manager initialized with database at {:?}", db_path);
         
-        Ok(Self { database })
+        Ok(Self {
+            database,
+            notifier: None,
+        })
+    }
+
+    /// Notify members when assignments are created or resolved
+    pub fn with_notifier(mut self, notifier: AssignmentNotifier) -> Self {
+        self.notifier = Some(notifier);
+        self
     }
 
     /// Add a new team member
@@ -44,7 +55,14 @@ impl CollaborationManager {
             "Creating assignment {} for {} in repository {}",
             assignment.id, assignment.assignee_id, assignment.repository_id
         );
-        self.database.create_assignment(assignment).await
+        self.database.create_assignment(assignment.clone()).await?;
+
+        if let Some(notifier) = &self.notifier {
+            notifier
+                .notify(&self.database, AssignmentEvent::Created, &assignment, &assignment.assigned_by)
+                .await;
+        }
+        Ok(())
     }
 
     /// Update assignment status
@@ -58,9 +76,35 @@ impl CollaborationManager {
             "Updating assignment {} status to {:?} by {}",
             assignment_id, new_status, updated_by
         );
+        let resolved = new_status == AssignmentStatus::Resolved;
         self.database
             .update_assignment_status(assignment_id, new_status, updated_by)
-            .await
+            .await?;
+
+        if let (true, Some(notifier)) = (resolved, &self.notifier) {
+            if let Some(assignment) = self.database.get_assignment(assignment_id).await? {
+                notifier
+                    .notify(&self.database, AssignmentEvent::Resolved, &assignment, updated_by)
+                    .await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Store which channels and events a member is notified about
+    pub async fn set_notification_preferences(&self, preferences: NotificationPreferences) -> Result<()> {
+        info!("Updating notification preferences of {}", preferences.member_id);
+        self.database.set_notification_preferences(&preferences).await
+    }
+
+    /// A member's notification preferences; every channel and event unless they stored their own
+    pub async fn get_notification_preferences(&self, member_id: &str) -> Result<NotificationPreferences> {
+        debug!("Fetching notification preferences of {}", member_id);
+        Ok(self
+            .database
+            .get_notification_preferences(member_id)
+            .await?
+            .unwrap_or_else(|| NotificationPreferences::new(member_id)))
     }
 
     /// Get assignments for a team member
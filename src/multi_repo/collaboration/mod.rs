@@ -1,6 +1,7 @@
 pub mod types;
 pub mod database;
 pub mod manager;
+pub mod notifications;
 pub mod sync;
 
 // Re-export main types and functionality
@@ -9,6 +10,10 @@ pub use types::{
 };
 pub use database::TeamDatabase;
 pub use manager::CollaborationManager;
+pub use notifications::{
+    AssignmentEvent, AssignmentNotification, AssignmentNotifier, EmailNotifier, NotificationChannel,
+    NotificationConfig, NotificationPreferences, Notifier, SlackNotifier, SmtpConfig, SmtpSecurity,
+};
 pub use sync::{AssignmentSynchronizer, SyncResult, AssignmentConflict, ConflictType};
 
 #[cfg(test)]
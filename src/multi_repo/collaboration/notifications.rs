@@ -0,0 +1,641 @@
+//! Telling team members about their assignments
+//!
+//! When a diagnostic is assigned, the assignee hears about it; when it is
+//! resolved, whoever assigned it does. Each [`Notifier`] delivers to one
+//! kind of channel: [`EmailNotifier`] over SMTP (needs the `email` feature)
+//! and [`SlackNotifier`] to an incoming webhook. Which channels and events a
+//! member wants is stored per member in the [`TeamDatabase`] as
+//! [`NotificationPreferences`]; members without stored preferences get every
+//! configured channel.
+//!
+//! Team-wide settings live under `[multi_repo.notifications]`:
+//!
+//! ```toml
+//! [multi_repo.notifications]
+//! slack_webhook_url = "https://hooks.slack.com/services/T000/B000/XXXX"
+//!
+//! [multi_repo.notifications.smtp]
+//! host = "smtp.example.com"
+//! from = "LSPbridge <lspbridge@example.com>"
+//! username = "lspbridge"
+//! password_env = "LSPBRIDGE_SMTP_PASSWORD"
+//! ```
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use super::database::TeamDatabase;
+use super::types::{DiagnosticAssignment, TeamMember};
+use crate::core::net::{post_json, NetError, NetworkGuard, RetryPolicy};
+
+/// How long a single delivery may take
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What happened to an assignment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AssignmentEvent {
+    /// The diagnostic was assigned; the assignee is notified
+    Created,
+    /// The assignment was resolved; the member who assigned it is notified
+    Resolved,
+}
+
+/// Kind of channel a member can be notified on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationChannel {
+    Email,
+    Slack,
+}
+
+impl NotificationChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Email => "email",
+            Self::Slack => "slack",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "email" => Some(Self::Email),
+            "slack" => Some(Self::Slack),
+            _ => None,
+        }
+    }
+}
+
+/// Channels and events a member wants to be notified about
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationPreferences {
+    pub member_id: String,
+    /// Channels to notify on; none turns notifications off
+    pub channels: Vec<NotificationChannel>,
+    /// Slack user ID mentioned in Slack messages, e.g. `U024BE7LH`
+    pub slack_user: Option<String>,
+    /// Notify when a diagnostic is assigned to the member
+    pub on_created: bool,
+    /// Notify when an assignment the member created is resolved
+    pub on_resolved: bool,
+}
+
+impl NotificationPreferences {
+    /// Every channel and event, which members get until they store their own
+    pub fn new(member_id: impl Into<String>) -> Self {
+        Self {
+            member_id: member_id.into(),
+            channels: vec![NotificationChannel::Email, NotificationChannel::Slack],
+            slack_user: None,
+            on_created: true,
+            on_resolved: true,
+        }
+    }
+
+    pub fn wants(&self, event: AssignmentEvent) -> bool {
+        match event {
+            AssignmentEvent::Created => self.on_created,
+            AssignmentEvent::Resolved => self.on_resolved,
+        }
+    }
+}
+
+/// A message about one assignment for one member
+#[derive(Debug, Clone)]
+pub struct AssignmentNotification {
+    pub event: AssignmentEvent,
+    pub assignment: DiagnosticAssignment,
+    pub recipient: TeamMember,
+    /// Member who assigned or resolved it, when known
+    pub actor: Option<TeamMember>,
+    pub slack_user: Option<String>,
+}
+
+impl AssignmentNotification {
+    pub fn subject(&self) -> String {
+        let assignment = &self.assignment;
+        match self.event {
+            AssignmentEvent::Created => format!(
+                "[{}] Diagnostic assigned to you in {}",
+                assignment.repository_id, assignment.file_path
+            ),
+            AssignmentEvent::Resolved => format!(
+                "[{}] Assignment resolved in {}",
+                assignment.repository_id, assignment.file_path
+            ),
+        }
+    }
+
+    pub fn text(&self) -> String {
+        let assignment = &self.assignment;
+        let actor = self
+            .actor
+            .as_ref()
+            .map_or_else(|| "Someone".to_string(), |actor| actor.name.clone());
+        let mut text = match self.event {
+            AssignmentEvent::Created => format!(
+                "{actor} assigned you diagnostic {} in {}/{} ({:?} priority).",
+                assignment.diagnostic_hash, assignment.repository_id, assignment.file_path, assignment.priority
+            ),
+            AssignmentEvent::Resolved => format!(
+                "{actor} resolved diagnostic {} in {}/{} that you assigned.",
+                assignment.diagnostic_hash, assignment.repository_id, assignment.file_path
+            ),
+        };
+        if self.event == AssignmentEvent::Created {
+            if let Some(due) = assignment.due_date {
+                text.push_str(&format!("\nDue: {}", due.format("%Y-%m-%d")));
+            }
+        }
+        if let Some(notes) = &assignment.notes {
+            text.push_str(&format!("\nNotes: {notes}"));
+        }
+        text.push_str(&format!("\nAssignment: {}", assignment.id));
+        text
+    }
+}
+
+/// A way of delivering assignment notifications
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    fn channel(&self) -> NotificationChannel;
+
+    /// Deliver `notification` once
+    async fn send(&self, notification: &AssignmentNotification) -> Result<(), NetError>;
+}
+
+/// Slack incoming webhook shared by the team; recipients are mentioned by Slack user ID
+pub struct SlackNotifier {
+    webhook_url: String,
+    /// Overrides the webhook's default channel
+    channel: Option<String>,
+}
+
+impl SlackNotifier {
+    pub fn new(webhook_url: impl Into<String>) -> Self {
+        Self {
+            webhook_url: webhook_url.into(),
+            channel: None,
+        }
+    }
+
+    pub fn with_channel(mut self, channel: impl Into<String>) -> Self {
+        self.channel = Some(channel.into());
+        self
+    }
+
+    pub fn payload(&self, notification: &AssignmentNotification) -> Value {
+        let mention = notification
+            .slack_user
+            .as_ref()
+            .map_or_else(|| notification.recipient.name.clone(), |user| format!("<@{user}>"));
+        let mut body = json!({
+            "text": format!("{mention}: {}\n{}", notification.subject(), notification.text()),
+        });
+        if let Some(channel) = &self.channel {
+            body["channel"] = Value::String(channel.clone());
+        }
+        body
+    }
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    fn channel(&self) -> NotificationChannel {
+        NotificationChannel::Slack
+    }
+
+    async fn send(&self, notification: &AssignmentNotification) -> Result<(), NetError> {
+        post_json(&self.webhook_url, &[], &self.payload(notification), REQUEST_TIMEOUT).await
+    }
+}
+
+/// How the SMTP connection is secured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// TLS from the start, port 465 by default
+    Tls,
+    /// Upgrade with STARTTLS, port 587 by default
+    #[default]
+    StartTls,
+    /// Unencrypted, port 25 by default; only for local relays
+    None,
+}
+
+/// SMTP server notifications are emailed through
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SmtpConfig {
+    pub host: String,
+    /// Port; the default of `security` when unset
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub security: SmtpSecurity,
+    /// Sender address, e.g. `LSPbridge <lspbridge@example.com>`
+    pub from: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Environment variable holding the password, so it stays out of config files
+    #[serde(default)]
+    pub password_env: Option<String>,
+}
+
+/// Email over SMTP to the member's address
+pub struct EmailNotifier {
+    #[cfg_attr(not(feature = "email"), allow(dead_code))]
+    config: SmtpConfig,
+}
+
+impl EmailNotifier {
+    pub fn new(config: SmtpConfig) -> Self {
+        Self { config }
+    }
+
+    #[cfg(feature = "email")]
+    fn transport(&self) -> Result<lettre::AsyncSmtpTransport<lettre::Tokio1Executor>, NetError> {
+        use lettre::transport::smtp::authentication::Credentials;
+        use lettre::{AsyncSmtpTransport, Tokio1Executor};
+
+        let config = &self.config;
+        let builder = match config.security {
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host),
+            SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host),
+            SmtpSecurity::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)),
+        }
+        .map_err(|e| NetError::Permanent(format!("invalid SMTP host {}: {e}", config.host)))?;
+        let mut builder = builder.timeout(Some(REQUEST_TIMEOUT));
+        if let Some(port) = config.port {
+            builder = builder.port(port);
+        }
+        if let Some(username) = &config.username {
+            let password = match &config.password_env {
+                Some(var) => std::env::var(var)
+                    .map_err(|_| NetError::Permanent(format!("SMTP password variable {var} is not set")))?,
+                None => String::new(),
+            };
+            builder = builder.credentials(Credentials::new(username.clone(), password));
+        }
+        Ok(builder.build())
+    }
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    fn channel(&self) -> NotificationChannel {
+        NotificationChannel::Email
+    }
+
+    /// Needs the `email` feature, without which every call fails permanently
+    async fn send(&self, notification: &AssignmentNotification) -> Result<(), NetError> {
+        #[cfg(feature = "email")]
+        {
+            use lettre::message::{header::ContentType, Mailbox};
+            use lettre::{AsyncTransport, Message};
+
+            let parse = |address: &str| {
+                address
+                    .parse::<Mailbox>()
+                    .map_err(|e| NetError::Permanent(format!("invalid email address {address}: {e}")))
+            };
+            let recipient = Mailbox::new(
+                Some(notification.recipient.name.clone()),
+                parse(&notification.recipient.email)?.email,
+            );
+            let message = Message::builder()
+                .from(parse(&self.config.from)?)
+                .to(recipient)
+                .subject(notification.subject())
+                .header(ContentType::TEXT_PLAIN)
+                .body(notification.text())
+                .map_err(|e| NetError::Permanent(e.to_string()))?;
+
+            self.transport()?.send(message).await.map(|_| ()).map_err(|e| {
+                if e.is_timeout() {
+                    NetError::Timeout(REQUEST_TIMEOUT)
+                } else if e.is_permanent() {
+                    NetError::Permanent(e.to_string())
+                } else {
+                    NetError::Unreachable(e.to_string())
+                }
+            })
+        }
+        #[cfg(not(feature = "email"))]
+        {
+            Err(NetError::Permanent(format!(
+                "cannot email {}: lspbridge was built without the `email` feature",
+                notification.recipient.email
+            )))
+        }
+    }
+}
+
+/// Team-wide notification settings under `[multi_repo.notifications]`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    /// Email notifications are off when unset
+    pub smtp: Option<SmtpConfig>,
+    /// Slack notifications are off when unset
+    pub slack_webhook_url: Option<String>,
+    /// Overrides the Slack webhook's default channel
+    pub slack_channel: Option<String>,
+}
+
+/// Sends assignment notifications to the members who want them
+pub struct AssignmentNotifier {
+    notifiers: Vec<Arc<dyn Notifier>>,
+    policy: RetryPolicy,
+}
+
+impl AssignmentNotifier {
+    pub fn new(policy: RetryPolicy) -> Self {
+        Self {
+            notifiers: Vec::new(),
+            policy,
+        }
+    }
+
+    /// Notifier for every channel `config` sets up
+    pub fn from_config(config: &NotificationConfig, policy: RetryPolicy) -> Self {
+        let mut notifier = Self::new(policy);
+        if let Some(smtp) = &config.smtp {
+            notifier = notifier.with_notifier(Arc::new(EmailNotifier::new(smtp.clone())));
+        }
+        if let Some(url) = &config.slack_webhook_url {
+            let mut slack = SlackNotifier::new(url.clone());
+            if let Some(channel) = &config.slack_channel {
+                slack = slack.with_channel(channel.clone());
+            }
+            notifier = notifier.with_notifier(Arc::new(slack));
+        }
+        notifier
+    }
+
+    pub fn with_notifier(mut self, notifier: Arc<dyn Notifier>) -> Self {
+        self.notifiers.push(notifier);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.notifiers.is_empty()
+    }
+
+    /// Notify whoever `event` concerns on the channels they chose
+    ///
+    /// The assignee hears about new assignments and the assigner about
+    /// resolved ones; nobody is told about their own action. Failures are
+    /// logged rather than returned so an unreachable mail server never
+    /// fails the assignment itself. Returns how many messages were sent.
+    pub async fn notify(
+        &self,
+        database: &TeamDatabase,
+        event: AssignmentEvent,
+        assignment: &DiagnosticAssignment,
+        actor_id: &str,
+    ) -> usize {
+        if self.is_empty() {
+            return 0;
+        }
+        let recipient_id = match event {
+            AssignmentEvent::Created => &assignment.assignee_id,
+            AssignmentEvent::Resolved => &assignment.assigned_by,
+        };
+        if recipient_id == actor_id {
+            return 0;
+        }
+
+        let notification = match self.notification(database, event, assignment, recipient_id, actor_id).await {
+            Ok(Some(notification)) => notification,
+            Ok(None) => return 0,
+            Err(e) => {
+                warn!("Assignment {} notification skipped: {:#}", assignment.id, e);
+                return 0;
+            }
+        };
+        let preferences = match database.get_notification_preferences(recipient_id).await {
+            Ok(preferences) => preferences.unwrap_or_else(|| NotificationPreferences::new(recipient_id.as_str())),
+            Err(e) => {
+                warn!("Notification preferences of {} unavailable: {:#}", recipient_id, e);
+                return 0;
+            }
+        };
+        if !preferences.wants(event) {
+            return 0;
+        }
+        let notification = AssignmentNotification {
+            slack_user: preferences.slack_user.clone(),
+            ..notification
+        };
+
+        if let Err(e) = NetworkGuard::global().check("assignment notification") {
+            warn!("Assignment {} notification skipped: {}", assignment.id, e);
+            return 0;
+        }
+        let mut sent = 0;
+        for notifier in &self.notifiers {
+            let channel = notifier.channel();
+            if !preferences.channels.contains(&channel) {
+                continue;
+            }
+            match self.policy.retry(|_| notifier.send(&notification)).await {
+                Ok(()) => {
+                    debug!("Notified {} of assignment {} by {}", recipient_id, assignment.id, channel.as_str());
+                    sent += 1;
+                }
+                Err(e) => warn!(
+                    "Could not notify {} of assignment {} by {}: {}",
+                    recipient_id,
+                    assignment.id,
+                    channel.as_str(),
+                    e
+                ),
+            }
+        }
+        sent
+    }
+
+    async fn notification(
+        &self,
+        database: &TeamDatabase,
+        event: AssignmentEvent,
+        assignment: &DiagnosticAssignment,
+        recipient_id: &str,
+        actor_id: &str,
+    ) -> anyhow::Result<Option<AssignmentNotification>> {
+        let Some(recipient) = database.get_member(recipient_id).await? else {
+            return Ok(None);
+        };
+        if !recipient.active {
+            return Ok(None);
+        }
+        Ok(Some(AssignmentNotification {
+            event,
+            assignment: assignment.clone(),
+            recipient,
+            actor: database.get_member(actor_id).await?,
+            slack_user: None,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multi_repo::collaboration::{CollaborationManager, Priority, TeamRole};
+    use chrono::Utc;
+    use std::sync::Mutex;
+
+    /// Records who it notified, and of what
+    struct RecordingNotifier {
+        channel: NotificationChannel,
+        sent: Mutex<Vec<(String, AssignmentEvent)>>,
+    }
+
+    impl RecordingNotifier {
+        fn new(channel: NotificationChannel) -> Arc<Self> {
+            Arc::new(Self {
+                channel,
+                sent: Mutex::new(Vec::new()),
+            })
+        }
+
+        fn sent(&self) -> Vec<(String, AssignmentEvent)> {
+            self.sent.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl Notifier for RecordingNotifier {
+        fn channel(&self) -> NotificationChannel {
+            self.channel
+        }
+
+        async fn send(&self, notification: &AssignmentNotification) -> Result<(), NetError> {
+            self.sent
+                .lock()
+                .unwrap()
+                .push((notification.recipient.id.clone(), notification.event));
+            Ok(())
+        }
+    }
+
+    fn member(id: &str, role: TeamRole) -> TeamMember {
+        TeamMember {
+            id: id.to_string(),
+            name: id.to_string(),
+            email: format!("{id}@example.com"),
+            role,
+            active: true,
+            last_activity: Some(Utc::now()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_assignment_events_notify_by_preference() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let email = RecordingNotifier::new(NotificationChannel::Email);
+        let slack = RecordingNotifier::new(NotificationChannel::Slack);
+        let notifier = AssignmentNotifier::new(RetryPolicy::no_retry())
+            .with_notifier(email.clone())
+            .with_notifier(slack.clone());
+        let manager = CollaborationManager::new(&temp_dir.path().join("team.db"))
+            .await
+            .unwrap()
+            .with_notifier(notifier);
+
+        manager.add_team_member(member("dev", TeamRole::Developer)).await.unwrap();
+        manager.add_team_member(member("lead", TeamRole::Lead)).await.unwrap();
+        manager
+            .set_notification_preferences(NotificationPreferences {
+                channels: vec![NotificationChannel::Slack],
+                slack_user: Some("U024BE7LH".to_string()),
+                ..NotificationPreferences::new("dev")
+            })
+            .await
+            .unwrap();
+        manager
+            .set_notification_preferences(NotificationPreferences {
+                on_created: false,
+                ..NotificationPreferences::new("lead")
+            })
+            .await
+            .unwrap();
+
+        let id = manager
+            .assign_diagnostic(
+                "repo".to_string(),
+                "src/lib.rs".to_string(),
+                "hash".to_string(),
+                "dev".to_string(),
+                "lead".to_string(),
+                Priority::High,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        manager.start_assignment(&id, "dev").await.unwrap();
+        manager.resolve_assignment(&id, "dev").await.unwrap();
+
+        // Self-assignments are nobody's news
+        manager
+            .assign_diagnostic(
+                "repo".to_string(),
+                "src/lib.rs".to_string(),
+                "other".to_string(),
+                "lead".to_string(),
+                "lead".to_string(),
+                Priority::Low,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(slack.sent(), vec![
+            ("dev".to_string(), AssignmentEvent::Created),
+            ("lead".to_string(), AssignmentEvent::Resolved),
+        ]);
+        assert_eq!(email.sent(), vec![("lead".to_string(), AssignmentEvent::Resolved)]);
+    }
+
+    #[test]
+    fn test_slack_payload_mentions_recipient() {
+        let notification = AssignmentNotification {
+            event: AssignmentEvent::Created,
+            assignment: DiagnosticAssignment {
+                id: "assign_1".to_string(),
+                repository_id: "api".to_string(),
+                file_path: "src/main.rs".to_string(),
+                diagnostic_hash: "abc123".to_string(),
+                assignee_id: "dev".to_string(),
+                assigned_by: "lead".to_string(),
+                assigned_at: Utc::now(),
+                due_date: None,
+                status: crate::multi_repo::collaboration::AssignmentStatus::Open,
+                priority: Priority::High,
+                notes: Some("Blocks the release".to_string()),
+            },
+            recipient: member("dev", TeamRole::Developer),
+            actor: Some(member("lead", TeamRole::Lead)),
+            slack_user: Some("U024BE7LH".to_string()),
+        };
+
+        let body = SlackNotifier::new("http://localhost/slack")
+            .with_channel("#triage")
+            .payload(&notification);
+        assert_eq!(body["channel"], "#triage");
+        assert_eq!(
+            body["text"],
+            "<@U024BE7LH>: [api] Diagnostic assigned to you in src/main.rs\n\
+             lead assigned you diagnostic abc123 in api/src/main.rs (High priority).\n\
+             Notes: Blocks the release\n\
+             Assignment: assign_1"
+        );
+    }
+}
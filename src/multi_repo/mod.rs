@@ -7,22 +7,27 @@
 #![allow(deprecated)]
 
 pub mod aggregator;
+pub mod backend;
 pub mod collaboration;
 pub mod cross_repo;
 pub mod monorepo;
+#[cfg(feature = "postgres")]
+pub mod postgres;
 pub mod registry;
 
-pub use aggregator::{AggregatedDiagnostic, DiagnosticAggregator};
+pub use aggregator::{AggregatedDiagnostic, AnalysisRun, DiagnosticAggregator, Shard};
+pub use backend::{ExpectedVersion, RegistryBackend, RegistryConflict, TeamBackend, Versioned};
 pub use collaboration::{DiagnosticAssignment, TeamDatabase, TeamMember};
 pub use cross_repo::CrossRepoAnalyzer;
 pub use cross_repo::types::TypeReference;
 pub use monorepo::{MonorepoDetector, SubprojectInfo, WorkspaceLayout, WorkspaceType};
-pub use registry::{RepositoryInfo, RepositoryRegistry, RepositoryRelation};
+pub use registry::{tag_matches, Fleet, RepositoryInfo, RepositoryRegistry, RepositoryRelation, SqliteRegistry};
 
 use crate::core::config::ConfigDefaults;
 use crate::impl_config_defaults;
 use anyhow::Result;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 /// Multi-repository configuration
 ///
@@ -87,10 +92,12 @@ impl MultiRepoConfig {
         crate::core::config::MultiRepoConfig {
             registry_path: self.registry_path.clone(),
             team_db_path: self.team_db_path.clone(),
+            registry_url: None,
             auto_detect_monorepo: self.auto_detect_monorepo,
             enable_cross_repo_types: self.enable_cross_repo_types,
             max_concurrent_repos: self.max_concurrent_repos,
             cache_dir: self.cache_dir.clone(),
+            notifications: Default::default(),
         }
     }
 
@@ -113,22 +120,17 @@ pub struct MultiRepoContext {
     registry: RepositoryRegistry,
     aggregator: DiagnosticAggregator,
     analyzer: CrossRepoAnalyzer,
-    #[allow(dead_code)]
-    team_db: Option<TeamDatabase>,
+    team_db: Option<Arc<dyn TeamBackend>>,
 }
 
 impl MultiRepoContext {
     /// Create a new multi-repository context
     pub async fn new(config: crate::core::config::MultiRepoConfig) -> Result<Self> {
-        let registry = RepositoryRegistry::load_or_create(&config.registry_path).await?;
+        let backends = backend::open(&config).await?;
+        let registry = RepositoryRegistry::with_backend(backends.registry);
         let aggregator = DiagnosticAggregator::new(config.max_concurrent_repos);
         let analyzer = CrossRepoAnalyzer::new(config.enable_cross_repo_types);
-
-        let team_db = if let Some(db_path) = &config.team_db_path {
-            Some(TeamDatabase::connect(db_path).await?)
-        } else {
-            None
-        };
+        let team_db = backends.team;
 
         Ok(Self {
             config,
@@ -150,6 +152,46 @@ impl MultiRepoContext {
         self.aggregator.analyze_repositories(repos).await
     }
 
+    /// Analyze diagnostics across the repositories of a saved fleet
+    pub async fn analyze_fleet(&mut self, fleet: &str) -> Result<Vec<AggregatedDiagnostic>> {
+        let repos = self.registry.fleet_members(fleet).await?;
+        self.aggregator.analyze_repositories(repos).await
+    }
+
+    /// Analyze one shard of the active repositories (or of a fleet), checkpointing each one
+    ///
+    /// Checkpoints are kept per fleet and shard, so `resume` continues the
+    /// last run of the same selection.
+    pub async fn analyze_checkpointed(
+        &mut self,
+        fleet: Option<&str>,
+        shard: Option<Shard>,
+        resume: bool,
+    ) -> Result<AnalysisRun> {
+        let repos = match fleet {
+            Some(fleet) => self.registry.fleet_members(fleet).await?,
+            None => self.registry.list_active().await?,
+        };
+        let shard = shard.unwrap_or(Shard { index: 1, count: 1 });
+        let repos = repos.into_iter().filter(|repo| shard.includes(&repo.id)).collect();
+
+        let selection = fleet.map_or_else(|| "all".to_string(), |fleet| format!("fleet:{fleet}"));
+        let run_key = format!("{selection}#{shard}");
+        self.aggregator
+            .analyze_with_checkpoints(repos, &self.registry, &run_key, resume)
+            .await
+    }
+
+    /// The repository registry, for tag and fleet management
+    pub fn registry(&self) -> &RepositoryRegistry {
+        &self.registry
+    }
+
+    /// The team database, if one is configured
+    pub fn team_db(&self) -> Option<&dyn TeamBackend> {
+        self.team_db.as_deref()
+    }
+
     /// Find cross-repository type references
     pub async fn find_cross_repo_types(&mut self) -> Result<Vec<TypeReference>> {
         self.analyzer.analyze_type_references(&self.registry).await
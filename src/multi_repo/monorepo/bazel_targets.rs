@@ -0,0 +1,480 @@
+//! Bazel target mapping for diagnostics
+//!
+//! Maps source files to the Bazel targets that own them by reading the
+//! `BUILD` / `BUILD.bazel` files of a workspace. Each rule with a `name`
+//! attribute becomes a target whose sources are taken from its `srcs` and
+//! `hdrs` attributes; literal file names and `glob([...])` patterns are both
+//! supported.
+//!
+//! The mapping is computed statically so it works without a Bazel server.
+//! Macro-generated rules and `select()` branches are not expanded.
+
+use anyhow::{Context, Result};
+use glob::{MatchOptions, Pattern};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// File names recognised as Bazel build files, in lookup order
+pub const BUILD_FILE_NAMES: &[&str] = &["BUILD.bazel", "BUILD"];
+
+/// Files marking the root of a Bazel workspace
+pub const WORKSPACE_MARKERS: &[&str] = &["MODULE.bazel", "WORKSPACE.bazel", "WORKSPACE"];
+
+/// Source attributes that associate files with a target
+const SOURCE_ATTRIBUTES: &[&str] = &["srcs", "hdrs"];
+
+/// A Bazel rule and the sources it owns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BazelTarget {
+    /// Fully qualified label, e.g. `//src/query:query`
+    pub label: String,
+    /// Rule kind, e.g. `rust_library`
+    pub kind: String,
+    /// Package path relative to the workspace root (empty for the root package)
+    pub package: String,
+    /// Literal source files relative to the package
+    pub srcs: Vec<String>,
+    /// Glob patterns relative to the package
+    pub globs: Vec<String>,
+    /// Patterns excluded from the globs
+    pub excludes: Vec<String>,
+}
+
+impl BazelTarget {
+    /// Check whether a package-relative file belongs to this target
+    fn owns(&self, file: &str) -> bool {
+        let options = MatchOptions {
+            case_sensitive: true,
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        };
+
+        let matches = |pattern: &String| {
+            Pattern::new(pattern)
+                .map(|p| p.matches_with(file, options))
+                .unwrap_or(false)
+        };
+
+        self.srcs.iter().any(|src| src == file)
+            || (self.globs.iter().any(matches) && !self.excludes.iter().any(matches))
+    }
+}
+
+/// Map from source files to the Bazel targets that own them
+#[derive(Debug, Clone, Default)]
+pub struct BazelTargetMap {
+    root: PathBuf,
+    packages: HashMap<String, Vec<BazelTarget>>,
+}
+
+impl BazelTargetMap {
+    /// Create an empty target map for a workspace root
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            packages: HashMap::new(),
+        }
+    }
+
+    /// Check whether a directory is the root of a Bazel workspace
+    pub fn is_workspace(root: &Path) -> bool {
+        WORKSPACE_MARKERS.iter().any(|marker| root.join(marker).is_file())
+    }
+
+    /// Load every BUILD file under the workspace root
+    ///
+    /// Bazel output trees (`bazel-*`) and hidden directories are skipped.
+    pub fn load(root: &Path) -> Result<Self> {
+        let mut map = Self::new(root);
+
+        let walker = WalkDir::new(root).into_iter().filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            entry.depth() == 0 || !(name.starts_with('.') || name.starts_with("bazel-"))
+        });
+
+        for entry in walker.filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy();
+            if !BUILD_FILE_NAMES.contains(&name.as_ref()) {
+                continue;
+            }
+            // BUILD.bazel takes precedence when both exist
+            let dir = entry.path().parent().unwrap_or(root);
+            if name == "BUILD" && dir.join("BUILD.bazel").is_file() {
+                continue;
+            }
+
+            let content = std::fs::read_to_string(entry.path())
+                .with_context(|| format!("Failed to read {}", entry.path().display()))?;
+            let package = dir
+                .strip_prefix(root)
+                .unwrap_or(dir)
+                .to_string_lossy()
+                .replace('\\', "/");
+            map.add_build_file(&package, &content);
+        }
+
+        Ok(map)
+    }
+
+    /// Parse the contents of a BUILD file for the given package
+    pub fn add_build_file(&mut self, package: &str, content: &str) {
+        let targets = parse_rules(content)
+            .into_iter()
+            .filter_map(|(kind, body)| {
+                let name = string_attribute(&body, "name")?;
+                let mut srcs = Vec::new();
+                let mut globs = Vec::new();
+                let mut excludes = Vec::new();
+                for attribute in SOURCE_ATTRIBUTES {
+                    if let Some(value) = attribute_value(&body, attribute) {
+                        collect_sources(&value, &mut srcs, &mut globs, &mut excludes);
+                    }
+                }
+                Some(BazelTarget {
+                    label: format!("//{package}:{name}"),
+                    kind,
+                    package: package.to_string(),
+                    srcs,
+                    globs,
+                    excludes,
+                })
+            })
+            .collect();
+
+        self.packages.insert(package.to_string(), targets);
+    }
+
+    /// Find the target that owns a file
+    ///
+    /// The nearest enclosing package is searched first, mirroring Bazel's
+    /// package boundaries; files not listed by any rule have no target.
+    pub fn target_for(&self, path: &Path) -> Option<&BazelTarget> {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        let relative = relative.trim_start_matches("./");
+
+        let mut package = relative;
+        loop {
+            package = match package.rfind('/') {
+                Some(index) => &package[..index],
+                None => "",
+            };
+
+            if let Some(targets) = self.packages.get(package) {
+                let file = if package.is_empty() {
+                    relative
+                } else {
+                    &relative[package.len() + 1..]
+                };
+                return targets.iter().find(|target| target.owns(file));
+            }
+
+            if package.is_empty() {
+                return None;
+            }
+        }
+    }
+
+    /// Label of the target owning a file
+    pub fn label_for(&self, path: &Path) -> Option<String> {
+        self.target_for(path).map(|target| target.label.clone())
+    }
+
+    /// Sorted, de-duplicated labels of the targets owning any of `files`
+    ///
+    /// Useful for building only what a change touches, e.g. `bazel build <labels>`.
+    pub fn affected_targets(&self, files: &[PathBuf]) -> Vec<String> {
+        files
+            .iter()
+            .filter_map(|file| self.label_for(file))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// All known targets
+    pub fn targets(&self) -> impl Iterator<Item = &BazelTarget> {
+        self.packages.values().flatten()
+    }
+
+    /// Workspace root
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Check whether any targets were found
+    pub fn is_empty(&self) -> bool {
+        self.packages.values().all(|targets| targets.is_empty())
+    }
+}
+
+/// Check whether a label matches a target pattern
+///
+/// Supports exact labels, `//pkg:all` / `//pkg:*` for every target in a
+/// package, and `//pkg/...` for every target in a package subtree.
+pub fn label_matches(label: &str, pattern: &str) -> bool {
+    if let Some(prefix) = pattern.strip_suffix("/...").or_else(|| pattern.strip_suffix("...")) {
+        let package = label.split(':').next().unwrap_or(label);
+        let prefix = prefix.trim_end_matches('/');
+        return prefix == "/"
+            || prefix == "//"
+            || package == prefix
+            || package.starts_with(&format!("{prefix}/"));
+    }
+    if let Some(package) = pattern
+        .strip_suffix(":all")
+        .or_else(|| pattern.strip_suffix(":*"))
+    {
+        return label.split(':').next() == Some(package);
+    }
+    label == pattern
+}
+
+/// Split BUILD file content into top-level `kind(body)` calls
+fn parse_rules(content: &str) -> Vec<(String, String)> {
+    let mut rules = Vec::new();
+    let chars: Vec<char> = strip_comments(content).chars().collect();
+    let mut index = 0;
+
+    while index < chars.len() {
+        if !(chars[index].is_ascii_alphabetic() || chars[index] == '_') {
+            index += 1;
+            continue;
+        }
+
+        let start = index;
+        while index < chars.len() && (chars[index].is_ascii_alphanumeric() || chars[index] == '_') {
+            index += 1;
+        }
+        let kind: String = chars[start..index].iter().collect();
+
+        let mut open = index;
+        while open < chars.len() && chars[open].is_whitespace() {
+            open += 1;
+        }
+        if open >= chars.len() || chars[open] != '(' {
+            continue;
+        }
+
+        match matching_paren(&chars, open) {
+            Some(close) => {
+                rules.push((kind, chars[open + 1..close].iter().collect()));
+                index = close + 1;
+            }
+            None => break,
+        }
+    }
+
+    rules
+}
+
+/// Remove `#` comments outside of string literals
+fn strip_comments(content: &str) -> String {
+    content
+        .lines()
+        .map(|line| {
+            let mut quote = None;
+            for (i, c) in line.char_indices() {
+                match (quote, c) {
+                    (None, '"' | '\'') => quote = Some(c),
+                    (Some(q), _) if c == q => quote = None,
+                    (None, '#') => return &line[..i],
+                    _ => {}
+                }
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Find the index of the parenthesis closing the one at `open`
+fn matching_paren(chars: &[char], open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut quote = None;
+
+    for (i, &c) in chars.iter().enumerate().skip(open) {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '(' | '[' | '{') => depth += 1,
+            (None, ')' | ']' | '}') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Raw text of a top-level `attr = value` in a rule body
+fn attribute_value(body: &str, attribute: &str) -> Option<String> {
+    let re = Regex::new(&format!(r"(?:^|[\s,]){}\s*=\s*", regex::escape(attribute))).ok()?;
+    let start = re.find(body)?.end();
+    let chars: Vec<char> = body[start..].chars().collect();
+
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut end = chars.len();
+    for (i, &c) in chars.iter().enumerate() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '(' | '[' | '{') => depth += 1,
+            (None, ')' | ']' | '}') => depth = depth.saturating_sub(1),
+            (None, ',') if depth == 0 => {
+                end = i;
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    Some(chars[..end].iter().collect::<String>().trim().to_string())
+}
+
+/// Value of a string attribute such as `name = "lib"`
+fn string_attribute(body: &str, attribute: &str) -> Option<String> {
+    let value = attribute_value(body, attribute)?;
+    string_literals(&value).into_iter().next()
+}
+
+/// All string literals in an expression
+fn string_literals(expression: &str) -> Vec<String> {
+    static LITERAL: once_cell::sync::Lazy<Regex> =
+        once_cell::sync::Lazy::new(|| Regex::new(r#""([^"]*)"|'([^']*)'"#).unwrap());
+
+    LITERAL
+        .captures_iter(expression)
+        .filter_map(|c| c.get(1).or_else(|| c.get(2)))
+        .map(|m| m.as_str().to_string())
+        .collect()
+}
+
+/// Split a sources expression into literal files and glob patterns
+///
+/// Labels (`:gen`, `//other:pkg`) refer to other targets and are ignored.
+fn collect_sources(
+    expression: &str,
+    srcs: &mut Vec<String>,
+    globs: &mut Vec<String>,
+    excludes: &mut Vec<String>,
+) {
+    let mut remaining = expression.to_string();
+
+    while let Some(start) = remaining.find("glob(") {
+        let chars: Vec<char> = remaining.chars().collect();
+        let start = remaining[..start].chars().count();
+        let open = start + "glob".len();
+        let Some(close) = matching_paren(&chars, open) else {
+            break;
+        };
+        let call: String = chars[open + 1..close].iter().collect();
+        match call.find("exclude") {
+            Some(index) => {
+                globs.extend(string_literals(&call[..index]));
+                excludes.extend(string_literals(&call[index..]));
+            }
+            None => globs.extend(string_literals(&call)),
+        }
+
+        let before: String = chars[..start].iter().collect();
+        let after: String = chars[close + 1..].iter().collect();
+        remaining = format!("{before}{after}");
+    }
+
+    srcs.extend(
+        string_literals(&remaining)
+            .into_iter()
+            .filter(|src| !src.starts_with(':') && !src.starts_with("//") && !src.starts_with('@')),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUILD: &str = r#"
+load("@rules_rust//rust:defs.bzl", "rust_library", "rust_test")
+
+# Query engine
+rust_library(
+    name = "query",
+    srcs = glob(
+        ["src/**/*.rs"],
+        exclude = ["src/**/*_test.rs"],
+    ),
+    deps = ["//core:types"],
+)
+
+rust_test(
+    name = "query_test",
+    srcs = ["src/parser_test.rs", ":generated"],
+)
+"#;
+
+    fn sample_map() -> BazelTargetMap {
+        let mut map = BazelTargetMap::new("/ws");
+        map.add_build_file("query", BUILD);
+        map.add_build_file("", "filegroup(name = 'docs', srcs = ['README.md'])");
+        map
+    }
+
+    #[test]
+    fn test_parse_rules_and_labels() {
+        let map = sample_map();
+        let mut labels: Vec<_> = map.targets().map(|t| t.label.clone()).collect();
+        labels.sort();
+        assert_eq!(labels, vec!["//:docs", "//query:query", "//query:query_test"]);
+
+        let test = map.targets().find(|t| t.label == "//query:query_test").unwrap();
+        assert_eq!(test.kind, "rust_test");
+        assert_eq!(test.srcs, vec!["src/parser_test.rs"]);
+    }
+
+    #[test]
+    fn test_target_for_file() {
+        let map = sample_map();
+        assert_eq!(
+            map.label_for(Path::new("/ws/query/src/executor/mod.rs")).as_deref(),
+            Some("//query:query")
+        );
+        assert_eq!(
+            map.label_for(Path::new("query/src/parser_test.rs")).as_deref(),
+            Some("//query:query_test")
+        );
+        assert_eq!(map.label_for(Path::new("README.md")).as_deref(), Some("//:docs"));
+        assert_eq!(map.label_for(Path::new("query/Cargo.toml")), None);
+    }
+
+    #[test]
+    fn test_affected_targets_are_deduplicated() {
+        let map = sample_map();
+        let files = vec![
+            PathBuf::from("query/src/lib.rs"),
+            PathBuf::from("query/src/main.rs"),
+            PathBuf::from("README.md"),
+        ];
+        assert_eq!(map.affected_targets(&files), vec!["//:docs", "//query:query"]);
+    }
+
+    #[test]
+    fn test_label_patterns() {
+        assert!(label_matches("//query:query", "//query:query"));
+        assert!(label_matches("//query:query", "//query:all"));
+        assert!(label_matches("//query/sub:x", "//query/..."));
+        assert!(label_matches("//query:x", "//..."));
+        assert!(!label_matches("//querying:x", "//query/..."));
+        assert!(!label_matches("//query/sub:x", "//query:*"));
+    }
+}
@@ -0,0 +1,87 @@
+//! Go workspace detector (go.work or several go.mod modules)
+
+use super::super::types::{SubprojectInfo, WorkspaceConfig, WorkspaceLayout, WorkspaceType};
+use super::super::utils::find_shared_configs;
+use super::WorkspaceDetector;
+use crate::core::constants::{build_systems, languages};
+use crate::project::GoWorkspace;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::path::Path;
+
+/// Detector for Go workspaces and multi-module repositories
+pub struct GoDetector;
+
+#[async_trait]
+impl WorkspaceDetector for GoDetector {
+    async fn detect(&self, root: &Path) -> Result<Option<WorkspaceLayout>> {
+        // A single module is a plain project, not a workspace
+        let root = root.to_path_buf();
+        let Some(workspace) = tokio::task::spawn_blocking(move || GoWorkspace::discover(&root)).await?? else {
+            return Ok(None);
+        };
+        if !workspace.is_multi_module() {
+            return Ok(None);
+        }
+
+        let mut layout = layout(workspace);
+        layout.shared_configs = find_shared_configs(
+            &layout.root,
+            &["go.work", "go.work.sum", "staticcheck.conf", ".golangci.yml", ".golangci.yaml"],
+        )
+        .await?;
+        Ok(Some(layout))
+    }
+
+    fn workspace_type(&self) -> &'static str {
+        "go"
+    }
+}
+
+fn layout(workspace: GoWorkspace) -> WorkspaceLayout {
+    let subprojects = workspace
+        .modules
+        .iter()
+        .map(|module| SubprojectInfo {
+            name: module.path().to_string(),
+            relative_path: module.dir.clone(),
+            absolute_path: workspace.root.join(&module.dir),
+            language: Some(languages::GO.to_string()),
+            build_system: Some(build_systems::GO_BUILD.to_string()),
+            internal_deps: workspace
+                .internal_requires(module)
+                .map(|req| req.path.clone())
+                .collect(),
+            external_deps: module
+                .go_mod
+                .requires
+                .iter()
+                .filter(|req| !workspace.modules.iter().any(|other| other.path() == req.path))
+                .map(|req| req.path.clone())
+                .collect(),
+            package_config: serde_json::to_value(&module.go_mod).ok(),
+        })
+        .collect();
+
+    // Workspace-level dependencies are the pinned versions of every direct requirement
+    let dependencies = workspace
+        .modules
+        .iter()
+        .flat_map(|module| &module.go_mod.requires)
+        .filter(|req| !req.indirect)
+        .map(|req| (req.path.clone(), req.version.clone()))
+        .collect();
+
+    WorkspaceLayout {
+        root: workspace.root.clone(),
+        workspace_type: WorkspaceType::GoWorkspace,
+        subprojects,
+        config: WorkspaceConfig {
+            patterns: workspace.modules.iter().map(|module| module.display_dir()).collect(),
+            excludes: vec![],
+            dependencies,
+            build_config: serde_json::to_value(&workspace).ok(),
+        },
+        shared_configs: vec![],
+    }
+}
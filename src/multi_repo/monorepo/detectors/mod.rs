@@ -8,6 +8,7 @@ use std::path::Path;
 pub mod bazel;
 pub mod cargo;
 pub mod custom;
+pub mod go;
 pub mod lerna;
 pub mod npm;
 pub mod nx;
@@ -40,6 +41,7 @@ impl DetectorRegistry {
             Box::new(nx::NxDetector),
             Box::new(rush::RushDetector),
             Box::new(bazel::BazelDetector),
+            Box::new(go::GoDetector),
             Box::new(custom::CustomDetector),
         ];
 
@@ -1,13 +1,15 @@
 //! Monorepo detection and analysis
 //! 
 //! This module provides comprehensive monorepo detection capabilities for various
-//! workspace types including npm, pnpm, Lerna, Cargo, Nx, Rush, Bazel, and custom structures.
+//! workspace types including npm, pnpm, Lerna, Cargo, Nx, Rush, Bazel, Go, and custom structures.
 
+pub mod bazel_targets;
 pub mod detectors;
 pub mod types;
 pub mod utils;
 
 // Re-export main types for convenience
+pub use bazel_targets::{BazelTarget, BazelTargetMap};
 pub use types::{SubprojectInfo, WorkspaceConfig, WorkspaceLayout, WorkspaceType};
 
 use anyhow::Result;
@@ -84,6 +86,7 @@ impl MonorepoDetector {
             "nx",
             "rush",
             "bazel",
+            "go",
             "custom"
         ]
     }
@@ -177,6 +180,33 @@ edition = "2021"
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_go_multi_module_detection() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+
+        // Two modules without a go.work; the vendored one is ignored
+        fs::create_dir_all(root.join("services/api")).await?;
+        fs::create_dir_all(root.join("lib/vendor/dep")).await?;
+        fs::write(
+            root.join("services/api/go.mod"),
+            "module example.com/api\n\nrequire example.com/lib v0.1.0\n",
+        )
+        .await?;
+        fs::write(root.join("lib/go.mod"), "module example.com/lib\n").await?;
+        fs::write(root.join("lib/vendor/dep/go.mod"), "module example.com/dep\n").await?;
+
+        let detector = MonorepoDetector::new();
+        let layout = detector.detect(root).await?.unwrap();
+
+        assert_eq!(layout.workspace_type, WorkspaceType::GoWorkspace);
+        let names: Vec<_> = layout.subprojects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["example.com/lib", "example.com/api"]);
+        assert_eq!(layout.subprojects[1].internal_deps, vec!["example.com/lib"]);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_no_workspace_detected() -> Result<()> {
         let temp_dir = TempDir::new()?;
@@ -75,6 +75,9 @@ pub enum WorkspaceType {
     /// Rush monorepo
     Rush,
 
+    /// Go workspace (`go.work`) or repository with several Go modules
+    GoWorkspace,
+
     /// Custom/Unknown
     Custom,
 }
@@ -0,0 +1,683 @@
+//! PostgreSQL registry and team database shared across machines
+//!
+//! Selected by setting `multi_repo.registry_url`, e.g.
+//! `postgres://lspbridge@db.internal/lspbridge`. The schema mirrors the
+//! SQLite registry and team database and is created on first connect.
+//! Repository writes are versioned the same way, so two machines updating
+//! one repository can't silently overwrite each other.
+//!
+//! Connections use TLS, verified against the system's trusted roots. TLS is
+//! required unless the URL sets `sslmode` itself, e.g. `sslmode=disable` for
+//! a database on the same machine.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio_postgres::types::ToSql;
+use tokio_postgres::config::SslMode;
+use tokio_postgres::{Client, Config, Row};
+
+use super::backend::{ExpectedVersion, RegistryBackend, RegistryConflict, TeamBackend, Versioned};
+use super::collaboration::database::{
+    priority_to_string, role_to_string, status_to_string, string_to_priority, string_to_role, string_to_status,
+};
+use super::collaboration::{AssignmentStatus, DiagnosticAssignment, TeamMember, TeamMetrics};
+use super::registry::{parse_checkpoints, relation_type_from_str, Fleet, RepositoryInfo, RepositoryRelation};
+use crate::core::types::Diagnostic;
+use crate::core::NetworkGuard;
+
+const SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS repositories (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        path TEXT NOT NULL,
+        remote_url TEXT,
+        primary_language TEXT,
+        build_system TEXT,
+        is_monorepo_member BOOLEAN NOT NULL DEFAULT FALSE,
+        monorepo_id TEXT,
+        tags TEXT,
+        active BOOLEAN NOT NULL DEFAULT TRUE,
+        last_diagnostic_run BIGINT,
+        metadata TEXT NOT NULL DEFAULT '{}',
+        created_at BIGINT NOT NULL,
+        updated_at BIGINT NOT NULL,
+        version BIGINT NOT NULL DEFAULT 1
+    );
+
+    CREATE TABLE IF NOT EXISTS repository_relations (
+        source_id TEXT NOT NULL REFERENCES repositories(id),
+        target_id TEXT NOT NULL REFERENCES repositories(id),
+        relation_type TEXT NOT NULL,
+        data TEXT NOT NULL DEFAULT '{}',
+        created_at BIGINT NOT NULL,
+        PRIMARY KEY (source_id, target_id, relation_type)
+    );
+
+    CREATE TABLE IF NOT EXISTS fleets (
+        name TEXT PRIMARY KEY,
+        description TEXT,
+        tags TEXT NOT NULL DEFAULT '[]',
+        repositories TEXT NOT NULL DEFAULT '[]',
+        created_at BIGINT NOT NULL,
+        updated_at BIGINT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS analysis_checkpoints (
+        run_key TEXT NOT NULL,
+        repo_id TEXT NOT NULL,
+        diagnostics TEXT NOT NULL DEFAULT '[]',
+        completed_at BIGINT NOT NULL,
+        PRIMARY KEY (run_key, repo_id)
+    );
+
+    CREATE TABLE IF NOT EXISTS team_members (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        email TEXT NOT NULL UNIQUE,
+        role TEXT NOT NULL,
+        active BOOLEAN NOT NULL DEFAULT TRUE,
+        last_activity BIGINT,
+        created_at BIGINT NOT NULL,
+        updated_at BIGINT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS diagnostic_assignments (
+        id TEXT PRIMARY KEY,
+        repository_id TEXT NOT NULL,
+        file_path TEXT NOT NULL,
+        diagnostic_hash TEXT NOT NULL,
+        assignee_id TEXT NOT NULL REFERENCES team_members(id),
+        assigned_by TEXT NOT NULL REFERENCES team_members(id),
+        assigned_at BIGINT NOT NULL,
+        due_date BIGINT,
+        status TEXT NOT NULL,
+        priority TEXT NOT NULL,
+        notes TEXT,
+        completed_at BIGINT,
+        updated_at BIGINT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS assignment_history (
+        id BIGSERIAL PRIMARY KEY,
+        assignment_id TEXT NOT NULL REFERENCES diagnostic_assignments(id),
+        member_id TEXT NOT NULL REFERENCES team_members(id),
+        action TEXT NOT NULL,
+        old_value TEXT,
+        new_value TEXT,
+        timestamp BIGINT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS team_metrics (
+        member_id TEXT NOT NULL REFERENCES team_members(id),
+        repository_id TEXT NOT NULL,
+        resolved_count BIGINT NOT NULL DEFAULT 0,
+        avg_resolution_time BIGINT,
+        last_updated BIGINT NOT NULL,
+        PRIMARY KEY (member_id, repository_id)
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_repos_active ON repositories(active);
+    CREATE INDEX IF NOT EXISTS idx_repos_monorepo ON repositories(monorepo_id);
+    CREATE INDEX IF NOT EXISTS idx_relations_target ON repository_relations(target_id);
+    CREATE INDEX IF NOT EXISTS idx_assignments_assignee ON diagnostic_assignments(assignee_id);
+    CREATE INDEX IF NOT EXISTS idx_assignments_status ON diagnostic_assignments(status);
+    CREATE INDEX IF NOT EXISTS idx_assignments_repo ON diagnostic_assignments(repository_id);
+    CREATE INDEX IF NOT EXISTS idx_history_assignment ON assignment_history(assignment_id);
+"#;
+
+/// Columns read by [`repository_from_row`], in order
+const REPOSITORY_COLUMNS: &str = "id, name, path, remote_url, primary_language, build_system, \
+     is_monorepo_member, monorepo_id, tags, active, last_diagnostic_run, metadata, version";
+
+const MEMBER_COLUMNS: &str = "id, name, email, role, active, last_activity";
+
+const ASSIGNMENT_COLUMNS: &str = "id, repository_id, file_path, diagnostic_hash, assignee_id, \
+     assigned_by, assigned_at, due_date, status, priority, notes";
+
+fn timestamp(seconds: i64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(seconds, 0)
+}
+
+fn repository_from_row(row: &Row) -> Versioned<RepositoryInfo> {
+    let value = RepositoryInfo {
+        id: row.get(0),
+        name: row.get(1),
+        path: PathBuf::from(row.get::<_, String>(2)),
+        remote_url: row.get(3),
+        primary_language: row.get(4),
+        build_system: row.get(5),
+        is_monorepo_member: row.get(6),
+        monorepo_id: row.get(7),
+        tags: row
+            .get::<_, Option<String>>(8)
+            .and_then(|tags| serde_json::from_str(&tags).ok())
+            .unwrap_or_default(),
+        active: row.get(9),
+        last_diagnostic_run: row.get::<_, Option<i64>>(10).and_then(timestamp),
+        metadata: serde_json::from_str(row.get(11)).unwrap_or_default(),
+    };
+    Versioned {
+        value,
+        version: row.get::<_, i64>(12) as u64,
+    }
+}
+
+fn fleet_from_row(row: &Row) -> Fleet {
+    Fleet {
+        name: row.get(0),
+        description: row.get(1),
+        tags: serde_json::from_str(row.get(2)).unwrap_or_default(),
+        repositories: serde_json::from_str(row.get(3)).unwrap_or_default(),
+    }
+}
+
+fn member_from_row(row: &Row) -> TeamMember {
+    TeamMember {
+        id: row.get(0),
+        name: row.get(1),
+        email: row.get(2),
+        role: string_to_role(row.get(3)),
+        active: row.get(4),
+        last_activity: row.get::<_, Option<i64>>(5).and_then(timestamp),
+    }
+}
+
+fn assignment_from_row(row: &Row) -> DiagnosticAssignment {
+    DiagnosticAssignment {
+        id: row.get(0),
+        repository_id: row.get(1),
+        file_path: row.get(2),
+        diagnostic_hash: row.get(3),
+        assignee_id: row.get(4),
+        assigned_by: row.get(5),
+        assigned_at: timestamp(row.get(6)).unwrap_or_default(),
+        due_date: row.get::<_, Option<i64>>(7).and_then(timestamp),
+        status: string_to_status(row.get(8)),
+        priority: string_to_priority(row.get(9)),
+        notes: row.get(10),
+    }
+}
+
+/// Registry and team database in PostgreSQL
+pub struct PostgresBackend {
+    client: Client,
+}
+
+impl PostgresBackend {
+    /// Connect to `url` and create the schema if needed
+    pub async fn connect(url: &str) -> Result<Self> {
+        let mut config: Config = url.parse().context("Invalid shared registry URL")?;
+        if !url.contains("sslmode") {
+            config.ssl_mode(SslMode::Require);
+        }
+        NetworkGuard::global().check("shared registry database")?;
+
+        let connector = native_tls::TlsConnector::new().context("Failed to set up TLS for the shared registry")?;
+        let (client, connection) = config
+            .connect(postgres_native_tls::MakeTlsConnector::new(connector))
+            .await
+            .context("Failed to connect to the shared registry database")?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                tracing::error!("Shared registry database connection failed: {}", e);
+            }
+        });
+
+        client
+            .batch_execute(SCHEMA)
+            .await
+            .context("Failed to initialize the shared registry schema")?;
+
+        Ok(Self { client })
+    }
+
+    async fn add_history(
+        &self,
+        assignment_id: &str,
+        member_id: &str,
+        action: &str,
+        old_value: Option<&str>,
+        new_value: Option<&str>,
+    ) -> Result<()> {
+        self.client
+            .execute(
+                "INSERT INTO assignment_history
+                 (assignment_id, member_id, action, old_value, new_value, timestamp)
+                 VALUES ($1, $2, $3, $4, $5, $6)",
+                &[&assignment_id, &member_id, &action, &old_value, &new_value, &Utc::now().timestamp()],
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RegistryBackend for PostgresBackend {
+    async fn register(&self, info: &RepositoryInfo, expected: ExpectedVersion) -> Result<u64> {
+        let now = Utc::now().timestamp();
+        let path = info.path.to_string_lossy().into_owned();
+        let tags = serde_json::to_string(&info.tags)?;
+        let last_diagnostic_run = info.last_diagnostic_run.map(|dt| dt.timestamp());
+        let metadata = serde_json::to_string(&info.metadata)?;
+        let mut values: Vec<&(dyn ToSql + Sync)> = vec![
+            &info.id,
+            &info.name,
+            &path,
+            &info.remote_url,
+            &info.primary_language,
+            &info.build_system,
+            &info.is_monorepo_member,
+            &info.monorepo_id,
+            &tags,
+            &info.active,
+            &last_diagnostic_run,
+            &metadata,
+            &now,
+        ];
+
+        const SET: &str = "name = $2, path = $3, remote_url = $4, primary_language = $5, build_system = $6,
+            is_monorepo_member = $7, monorepo_id = $8, tags = $9, active = $10, last_diagnostic_run = $11,
+            metadata = $12, updated_at = $13, version = repositories.version + 1";
+        let expected_version;
+        let sql = match expected {
+            ExpectedVersion::Any | ExpectedVersion::New => {
+                let on_conflict = if expected == ExpectedVersion::New {
+                    "DO NOTHING".to_string()
+                } else {
+                    format!("DO UPDATE SET {SET}")
+                };
+                format!(
+                    "INSERT INTO repositories
+                     (id, name, path, remote_url, primary_language, build_system,
+                      is_monorepo_member, monorepo_id, tags, active, last_diagnostic_run,
+                      metadata, created_at, updated_at, version)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $13, 1)
+                     ON CONFLICT (id) {on_conflict}
+                     RETURNING version"
+                )
+            }
+            ExpectedVersion::Version(version) => {
+                expected_version = version as i64;
+                values.push(&expected_version);
+                format!("UPDATE repositories SET {SET} WHERE id = $1 AND version = $14 RETURNING version")
+            }
+        };
+
+        match self.client.query_opt(&sql, &values).await? {
+            Some(row) => Ok(row.get::<_, i64>(0) as u64),
+            None => {
+                let actual = self
+                    .client
+                    .query_opt("SELECT version FROM repositories WHERE id = $1", &[&info.id])
+                    .await?
+                    .map(|row| row.get::<_, i64>(0) as u64);
+                Err(RegistryConflict {
+                    id: info.id.clone(),
+                    expected,
+                    actual,
+                }
+                .into())
+            }
+        }
+    }
+
+    async fn get(&self, id: &str) -> Result<Option<Versioned<RepositoryInfo>>> {
+        let row = self
+            .client
+            .query_opt(&format!("SELECT {REPOSITORY_COLUMNS} FROM repositories WHERE id = $1"), &[&id])
+            .await?;
+        Ok(row.as_ref().map(repository_from_row))
+    }
+
+    async fn list(&self, include_inactive: bool) -> Result<Vec<RepositoryInfo>> {
+        let filter = if include_inactive { "" } else { "WHERE active" };
+        let rows = self
+            .client
+            .query(&format!("SELECT {REPOSITORY_COLUMNS} FROM repositories {filter} ORDER BY name"), &[])
+            .await?;
+        Ok(rows.iter().map(|row| repository_from_row(row).value).collect())
+    }
+
+    async fn touch_diagnostic_run(&self, repo_id: &str, at: DateTime<Utc>) -> Result<()> {
+        self.client
+            .execute(
+                "UPDATE repositories SET last_diagnostic_run = $1, updated_at = $2, version = version + 1 WHERE id = $3",
+                &[&at.timestamp(), &Utc::now().timestamp(), &repo_id],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn add_relation(&self, relation: &RepositoryRelation) -> Result<()> {
+        self.client
+            .execute(
+                "INSERT INTO repository_relations (source_id, target_id, relation_type, data, created_at)
+                 VALUES ($1, $2, $3, $4, $5)
+                 ON CONFLICT (source_id, target_id, relation_type) DO UPDATE SET
+                     data = excluded.data,
+                     created_at = excluded.created_at",
+                &[
+                    &relation.source_id,
+                    &relation.target_id,
+                    &relation.relation_type.as_str(),
+                    &serde_json::to_string(&relation.data)?,
+                    &Utc::now().timestamp(),
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn relations(&self, repo_id: Option<&str>) -> Result<Vec<RepositoryRelation>> {
+        let rows = self
+            .client
+            .query(
+                "SELECT source_id, target_id, relation_type, data
+                 FROM repository_relations
+                 WHERE $1::TEXT IS NULL OR source_id = $1 OR target_id = $1
+                 ORDER BY source_id, target_id",
+                &[&repo_id],
+            )
+            .await?;
+        Ok(rows
+            .iter()
+            .map(|row| RepositoryRelation {
+                source_id: row.get(0),
+                target_id: row.get(1),
+                relation_type: relation_type_from_str(row.get(2)),
+                data: serde_json::from_str(row.get(3)).unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    async fn save_fleet(&self, fleet: &Fleet) -> Result<()> {
+        self.client
+            .execute(
+                "INSERT INTO fleets (name, description, tags, repositories, created_at, updated_at)
+                 VALUES ($1, $2, $3, $4, $5, $5)
+                 ON CONFLICT (name) DO UPDATE SET
+                     description = excluded.description,
+                     tags = excluded.tags,
+                     repositories = excluded.repositories,
+                     updated_at = excluded.updated_at",
+                &[
+                    &fleet.name,
+                    &fleet.description,
+                    &serde_json::to_string(&fleet.tags)?,
+                    &serde_json::to_string(&fleet.repositories)?,
+                    &Utc::now().timestamp(),
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn get_fleet(&self, name: &str) -> Result<Option<Fleet>> {
+        let row = self
+            .client
+            .query_opt("SELECT name, description, tags, repositories FROM fleets WHERE name = $1", &[&name])
+            .await?;
+        Ok(row.as_ref().map(fleet_from_row))
+    }
+
+    async fn list_fleets(&self) -> Result<Vec<Fleet>> {
+        let rows = self
+            .client
+            .query("SELECT name, description, tags, repositories FROM fleets ORDER BY name", &[])
+            .await?;
+        Ok(rows.iter().map(fleet_from_row).collect())
+    }
+
+    async fn delete_fleet(&self, name: &str) -> Result<bool> {
+        let deleted = self.client.execute("DELETE FROM fleets WHERE name = $1", &[&name]).await?;
+        Ok(deleted > 0)
+    }
+
+    async fn save_checkpoint(&self, run_key: &str, repo_id: &str, diagnostics: &[Diagnostic]) -> Result<()> {
+        self.client
+            .execute(
+                "INSERT INTO analysis_checkpoints (run_key, repo_id, diagnostics, completed_at)
+                 VALUES ($1, $2, $3, $4)
+                 ON CONFLICT (run_key, repo_id) DO UPDATE SET
+                     diagnostics = excluded.diagnostics,
+                     completed_at = excluded.completed_at",
+                &[&run_key, &repo_id, &serde_json::to_string(diagnostics)?, &Utc::now().timestamp()],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn load_checkpoints(&self, run_key: &str) -> Result<HashMap<String, Vec<Diagnostic>>> {
+        let rows = self
+            .client
+            .query("SELECT repo_id, diagnostics FROM analysis_checkpoints WHERE run_key = $1", &[&run_key])
+            .await?;
+        parse_checkpoints(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+    }
+
+    async fn clear_checkpoints(&self, run_key: &str) -> Result<usize> {
+        let removed = self
+            .client
+            .execute("DELETE FROM analysis_checkpoints WHERE run_key = $1", &[&run_key])
+            .await?;
+        Ok(removed as usize)
+    }
+}
+
+#[async_trait]
+impl TeamBackend for PostgresBackend {
+    async fn add_member(&self, member: TeamMember) -> Result<()> {
+        let now = Utc::now().timestamp();
+        self.client
+            .execute(
+                "INSERT INTO team_members (id, name, email, role, active, last_activity, created_at, updated_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $7)",
+                &[
+                    &member.id,
+                    &member.name,
+                    &member.email,
+                    &role_to_string(&member.role),
+                    &member.active,
+                    &member.last_activity.map(|dt| dt.timestamp()),
+                    &now,
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn get_member(&self, id: &str) -> Result<Option<TeamMember>> {
+        let row = self
+            .client
+            .query_opt(&format!("SELECT {MEMBER_COLUMNS} FROM team_members WHERE id = $1"), &[&id])
+            .await?;
+        Ok(row.as_ref().map(member_from_row))
+    }
+
+    async fn list_members(&self) -> Result<Vec<TeamMember>> {
+        let rows = self
+            .client
+            .query(&format!("SELECT {MEMBER_COLUMNS} FROM team_members WHERE active ORDER BY name"), &[])
+            .await?;
+        Ok(rows.iter().map(member_from_row).collect())
+    }
+
+    async fn create_assignment(&self, assignment: DiagnosticAssignment) -> Result<()> {
+        self.client
+            .execute(
+                "INSERT INTO diagnostic_assignments
+                 (id, repository_id, file_path, diagnostic_hash, assignee_id, assigned_by,
+                  assigned_at, due_date, status, priority, notes, updated_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+                &[
+                    &assignment.id,
+                    &assignment.repository_id,
+                    &assignment.file_path,
+                    &assignment.diagnostic_hash,
+                    &assignment.assignee_id,
+                    &assignment.assigned_by,
+                    &assignment.assigned_at.timestamp(),
+                    &assignment.due_date.map(|dt| dt.timestamp()),
+                    &status_to_string(&assignment.status),
+                    &priority_to_string(&assignment.priority),
+                    &assignment.notes,
+                    &Utc::now().timestamp(),
+                ],
+            )
+            .await?;
+
+        self.add_history(
+            &assignment.id,
+            &assignment.assigned_by,
+            "created",
+            None,
+            Some(&format!("Assigned to {}", assignment.assignee_id)),
+        )
+        .await
+    }
+
+    async fn update_assignment_status(
+        &self,
+        assignment_id: &str,
+        new_status: AssignmentStatus,
+        updated_by: &str,
+    ) -> Result<()> {
+        let now = Utc::now().timestamp();
+        let new_status_str = status_to_string(&new_status);
+        let completed_at = (new_status == AssignmentStatus::Resolved).then_some(now);
+
+        // Read the old status in the same statement so concurrent updates each
+        // record the status they actually replaced
+        let row = self
+            .client
+            .query_one(
+                "UPDATE diagnostic_assignments AS a
+                 SET status = $1, updated_at = $2, completed_at = $3
+                 FROM (SELECT id, status FROM diagnostic_assignments WHERE id = $4 FOR UPDATE) AS old
+                 WHERE a.id = old.id
+                 RETURNING old.status, a.assignee_id, a.repository_id, a.assigned_at",
+                &[&new_status_str, &now, &completed_at, &assignment_id],
+            )
+            .await
+            .with_context(|| format!("Unknown assignment '{assignment_id}'"))?;
+        let old_status: String = row.get(0);
+
+        self.add_history(
+            assignment_id,
+            updated_by,
+            "status_changed",
+            Some(&old_status),
+            Some(&new_status_str),
+        )
+        .await?;
+
+        if new_status == AssignmentStatus::Resolved {
+            let (assignee_id, repo_id, assigned_at): (String, String, i64) = (row.get(1), row.get(2), row.get(3));
+            self.client
+                .execute(
+                    "INSERT INTO team_metrics (member_id, repository_id, resolved_count, avg_resolution_time, last_updated)
+                     VALUES ($1, $2, 1, $3, $4)
+                     ON CONFLICT (member_id, repository_id) DO UPDATE SET
+                         resolved_count = team_metrics.resolved_count + 1,
+                         avg_resolution_time = (COALESCE(team_metrics.avg_resolution_time, 0) * team_metrics.resolved_count + $3)
+                             / (team_metrics.resolved_count + 1),
+                         last_updated = $4",
+                    &[&assignee_id, &repo_id, &(now - assigned_at), &now],
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn get_member_assignments(
+        &self,
+        member_id: &str,
+        status_filter: Option<AssignmentStatus>,
+    ) -> Result<Vec<DiagnosticAssignment>> {
+        let status = status_filter.map(|status| status_to_string(&status));
+        let rows = self
+            .client
+            .query(
+                &format!(
+                    "SELECT {ASSIGNMENT_COLUMNS} FROM diagnostic_assignments
+                     WHERE assignee_id = $1 AND ($2::TEXT IS NULL OR status = $2)
+                     ORDER BY priority, assigned_at"
+                ),
+                &[&member_id, &status],
+            )
+            .await?;
+        Ok(rows.iter().map(assignment_from_row).collect())
+    }
+
+    async fn get_team_metrics(&self) -> Result<TeamMetrics> {
+        let rows = self
+            .client
+            .query(
+                "SELECT m.id, m.name, m.email, m.role, m.active, m.last_activity,
+                        COALESCE(SUM(tm.resolved_count), 0)::BIGINT AS total_resolved,
+                        AVG(tm.avg_resolution_time)::BIGINT
+                 FROM team_members m
+                 LEFT JOIN team_metrics tm ON m.id = tm.member_id
+                 WHERE m.active
+                 GROUP BY m.id
+                 ORDER BY total_resolved DESC",
+                &[],
+            )
+            .await?;
+        Ok(rows
+            .iter()
+            .map(|row| (member_from_row(row), row.get::<_, i64>(6) as u32, row.get(7)))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Set to a scratch database to run these tests, e.g.
+    /// `postgres://postgres@localhost/lspbridge_test`
+    const URL_VAR: &str = "LSPBRIDGE_TEST_POSTGRES_URL";
+
+    #[tokio::test]
+    #[ignore = "needs a PostgreSQL database in LSPBRIDGE_TEST_POSTGRES_URL"]
+    async fn test_concurrent_updates_conflict() -> Result<()> {
+        let url = std::env::var(URL_VAR).context("LSPBRIDGE_TEST_POSTGRES_URL is not set")?;
+        let first = PostgresBackend::connect(&url).await?;
+        let second = PostgresBackend::connect(&url).await?;
+
+        let mut repo = RepositoryInfo {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: "shared".to_string(),
+            path: PathBuf::from("/srv/shared"),
+            remote_url: None,
+            primary_language: Some("rust".to_string()),
+            build_system: None,
+            is_monorepo_member: false,
+            monorepo_id: None,
+            tags: vec!["team/core".to_string()],
+            active: true,
+            last_diagnostic_run: None,
+            metadata: serde_json::json!({}),
+        };
+        assert_eq!(first.register(&repo, ExpectedVersion::New).await?, 1);
+        assert!(second.register(&repo, ExpectedVersion::New).await.is_err());
+
+        repo.name = "renamed".to_string();
+        assert_eq!(first.register(&repo, ExpectedVersion::Version(1)).await?, 2);
+        let conflict = second
+            .register(&repo, ExpectedVersion::Version(1))
+            .await
+            .unwrap_err()
+            .downcast::<RegistryConflict>()?;
+        assert_eq!(conflict.actual, Some(2));
+
+        let stored = second.get(&repo.id).await?.context("repository missing")?;
+        assert_eq!((stored.value.name.as_str(), stored.version), ("renamed", 2));
+        Ok(())
+    }
+}
@@ -1,9 +1,29 @@
 //! Repository registry for tracking related repositories
-
+//!
+//! Repositories carry hierarchical tags such as `team/payments/billing`; a
+//! tag selects every repository tagged with it or with a tag below it, so
+//! `team/payments` also matches `team/payments/billing`. Saved [`Fleet`]s
+//! name a set of repositories by tags and explicit members, so cross-repo
+//! commands can target business groupings with `--fleet <name>`.
+//!
+//! The registry also keeps analysis checkpoints: each repository finished by
+//! a multi-repo analysis run is recorded with its diagnostics, so a run that
+//! dies halfway can be resumed without collecting the finished ones again.
+//!
+//! Storage is pluggable through [`RegistryBackend`]: [`SqliteRegistry`] keeps
+//! the registry in a local file, and with the `postgres` feature a team can
+//! share one database (see [`super::backend`]).
+
+use super::backend::{ExpectedVersion, RegistryBackend, RegistryConflict, Versioned};
+use crate::core::config::MultiRepoConfig;
+use crate::core::graph::RelationGraph;
+use crate::core::types::Diagnostic;
 use anyhow::{Context, Result};
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -35,7 +55,7 @@ pub struct RepositoryInfo {
     /// Parent monorepo ID (if applicable)
     pub monorepo_id: Option<String>,
 
-    /// Tags for categorization
+    /// Tags for categorization; `/` separates levels, e.g. `team/payments`
     pub tags: Vec<String>,
 
     /// Whether the repository is active
@@ -86,14 +106,128 @@ pub enum RelationType {
     Custom(String),
 }
 
-/// Repository registry for managing multiple repositories
-pub struct RepositoryRegistry {
-    conn: Arc<Mutex<Connection>>,
+impl RelationType {
+    /// Name stored in the registry
+    pub fn as_str(&self) -> &str {
+        match self {
+            RelationType::SharedTypes => "shared_types",
+            RelationType::Dependency => "dependency",
+            RelationType::DevDependency => "dev_dependency",
+            RelationType::MonorepoSibling => "monorepo_sibling",
+            RelationType::ApiRelation => "api_relation",
+            RelationType::Custom(name) => name,
+        }
+    }
 }
 
-impl RepositoryRegistry {
-    /// Load existing registry or create a new one
-    pub async fn load_or_create(path: &Path) -> Result<Self> {
+/// A saved, named set of repositories, e.g. `payments-services`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fleet {
+    /// Unique fleet name
+    pub name: String,
+
+    /// What the fleet is for
+    pub description: Option<String>,
+
+    /// Repositories with any of these tags, or a tag below them, are members
+    pub tags: Vec<String>,
+
+    /// Repositories that are members regardless of tags, by ID or name
+    pub repositories: Vec<String>,
+}
+
+impl Fleet {
+    /// Whether a repository belongs to the fleet
+    pub fn includes(&self, repo: &RepositoryInfo) -> bool {
+        self.repositories.iter().any(|r| *r == repo.id || *r == repo.name)
+            || self
+                .tags
+                .iter()
+                .any(|selector| repo.tags.iter().any(|tag| tag_matches(tag, selector)))
+    }
+}
+
+/// Whether `tag` is `selector` or lies below it in the tag hierarchy
+///
+/// Matching is case-insensitive and ignores surrounding slashes, so
+/// `team/payments/billing` matches `team/payments` but not `team/pay`.
+pub fn tag_matches(tag: &str, selector: &str) -> bool {
+    let tag = tag.trim().trim_matches('/').to_lowercase();
+    let selector = selector.trim().trim_matches('/').to_lowercase();
+    !selector.is_empty()
+        && tag
+            .strip_prefix(&selector)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Columns read by [`repository_from_row`], in order
+const REPOSITORY_COLUMNS: &str = "id, name, path, remote_url, primary_language, build_system, \
+     is_monorepo_member, monorepo_id, tags, active, last_diagnostic_run, metadata, version";
+
+fn repository_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Versioned<RepositoryInfo>> {
+    let value = RepositoryInfo {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        path: PathBuf::from(row.get::<_, String>(2)?),
+        remote_url: row.get(3)?,
+        primary_language: row.get(4)?,
+        build_system: row.get(5)?,
+        is_monorepo_member: row.get(6)?,
+        monorepo_id: row.get(7)?,
+        tags: row
+            .get::<_, Option<String>>(8)?
+            .and_then(|tags| serde_json::from_str(&tags).ok())
+            .unwrap_or_default(),
+        active: row.get(9)?,
+        last_diagnostic_run: row
+            .get::<_, Option<i64>>(10)?
+            .and_then(|ts| DateTime::from_timestamp(ts, 0)),
+        metadata: serde_json::from_str(&row.get::<_, String>(11)?).unwrap_or_default(),
+    };
+    Ok(Versioned {
+        value,
+        version: row.get::<_, i64>(12)? as u64,
+    })
+}
+
+/// Relation type from its stored name
+pub(crate) fn relation_type_from_str(name: &str) -> RelationType {
+    match name {
+        "shared_types" => RelationType::SharedTypes,
+        "dependency" => RelationType::Dependency,
+        "dev_dependency" => RelationType::DevDependency,
+        "monorepo_sibling" => RelationType::MonorepoSibling,
+        "api_relation" => RelationType::ApiRelation,
+        other => RelationType::Custom(other.to_string()),
+    }
+}
+
+fn relation_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<RepositoryRelation> {
+    Ok(RepositoryRelation {
+        source_id: row.get(0)?,
+        target_id: row.get(1)?,
+        relation_type: relation_type_from_str(&row.get::<_, String>(2)?),
+        data: serde_json::from_str(&row.get::<_, String>(3)?).unwrap_or_default(),
+    })
+}
+
+fn fleet_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Fleet> {
+    Ok(Fleet {
+        name: row.get(0)?,
+        description: row.get(1)?,
+        tags: serde_json::from_str(&row.get::<_, String>(2)?).unwrap_or_default(),
+        repositories: serde_json::from_str(&row.get::<_, String>(3)?).unwrap_or_default(),
+    })
+}
+
+/// Registry stored in a local SQLite file
+pub struct SqliteRegistry {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteRegistry {
+    /// Open the registry at `path`, creating it if needed
+    pub async fn open(path: &Path) -> Result<Self> {
         // Ensure parent directory exists
         if let Some(parent) = path.parent() {
             tokio::fs::create_dir_all(parent)
@@ -120,7 +254,8 @@ impl RepositoryRegistry {
                 last_diagnostic_run INTEGER,
                 metadata TEXT NOT NULL DEFAULT '{}',
                 created_at INTEGER NOT NULL,
-                updated_at INTEGER NOT NULL
+                updated_at INTEGER NOT NULL,
+                version INTEGER NOT NULL DEFAULT 1
             );
             
             CREATE TABLE IF NOT EXISTS repository_relations (
@@ -135,6 +270,23 @@ impl RepositoryRegistry {
                 UNIQUE(source_id, target_id, relation_type)
             );
             
+            CREATE TABLE IF NOT EXISTS fleets (
+                name TEXT PRIMARY KEY,
+                description TEXT,
+                tags TEXT NOT NULL DEFAULT '[]',
+                repositories TEXT NOT NULL DEFAULT '[]',
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS analysis_checkpoints (
+                run_key TEXT NOT NULL,
+                repo_id TEXT NOT NULL,
+                diagnostics TEXT NOT NULL DEFAULT '[]',
+                completed_at INTEGER NOT NULL,
+                PRIMARY KEY (run_key, repo_id)
+            );
+
             CREATE INDEX IF NOT EXISTS idx_repos_active ON repositories(active);
             CREATE INDEX IF NOT EXISTS idx_repos_monorepo ON repositories(monorepo_id);
             CREATE INDEX IF NOT EXISTS idx_relations_source ON repository_relations(source_id);
@@ -142,152 +294,136 @@ impl RepositoryRegistry {
             "#,
         )?;
 
-        Ok(Self {
-            conn: Arc::new(Mutex::new(conn)),
-        })
+        // Registries created before repositories were versioned
+        let versioned: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('repositories') WHERE name = 'version'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !versioned {
+            conn.execute_batch("ALTER TABLE repositories ADD COLUMN version INTEGER NOT NULL DEFAULT 1")?;
+        }
+
+        Ok(Self { conn: Mutex::new(conn) })
     }
+}
 
-    /// Register a new repository
-    pub async fn register(&self, info: RepositoryInfo) -> Result<()> {
+#[async_trait]
+impl RegistryBackend for SqliteRegistry {
+    async fn register(&self, info: &RepositoryInfo, expected: ExpectedVersion) -> Result<u64> {
         let conn = self.conn.lock().await;
         let now = Utc::now().timestamp();
+        let path = info.path.to_string_lossy();
+        let tags = serde_json::to_string(&info.tags)?;
+        let last_diagnostic_run = info.last_diagnostic_run.map(|dt| dt.timestamp());
+        let metadata = serde_json::to_string(&info.metadata)?;
+        let mut values: Vec<&dyn rusqlite::ToSql> = vec![
+            &info.id,
+            &info.name,
+            &path,
+            &info.remote_url,
+            &info.primary_language,
+            &info.build_system,
+            &info.is_monorepo_member,
+            &info.monorepo_id,
+            &tags,
+            &info.active,
+            &last_diagnostic_run,
+            &metadata,
+            &now,
+        ];
+
+        const SET: &str = "name = ?2, path = ?3, remote_url = ?4, primary_language = ?5, build_system = ?6,
+            is_monorepo_member = ?7, monorepo_id = ?8, tags = ?9, active = ?10, last_diagnostic_run = ?11,
+            metadata = ?12, updated_at = ?13, version = repositories.version + 1";
+        let expected_version;
+        let sql = match expected {
+            ExpectedVersion::Any | ExpectedVersion::New => {
+                let on_conflict = if expected == ExpectedVersion::New {
+                    "DO NOTHING".to_string()
+                } else {
+                    format!("DO UPDATE SET {SET}")
+                };
+                format!(
+                    "INSERT INTO repositories
+                     (id, name, path, remote_url, primary_language, build_system,
+                      is_monorepo_member, monorepo_id, tags, active, last_diagnostic_run,
+                      metadata, created_at, updated_at, version)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?13, 1)
+                     ON CONFLICT(id) {on_conflict}
+                     RETURNING version"
+                )
+            }
+            ExpectedVersion::Version(version) => {
+                expected_version = version as i64;
+                values.push(&expected_version);
+                format!("UPDATE repositories SET {SET} WHERE id = ?1 AND version = ?14 RETURNING version")
+            }
+        };
 
-        conn.execute(
-            r#"
-            INSERT OR REPLACE INTO repositories 
-            (id, name, path, remote_url, primary_language, build_system,
-             is_monorepo_member, monorepo_id, tags, active, last_diagnostic_run,
-             metadata, created_at, updated_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
-            "#,
-            params![
-                info.id,
-                info.name,
-                info.path.to_string_lossy(),
-                info.remote_url,
-                info.primary_language,
-                info.build_system,
-                info.is_monorepo_member,
-                info.monorepo_id,
-                serde_json::to_string(&info.tags)?,
-                info.active,
-                info.last_diagnostic_run.map(|dt| dt.timestamp()),
-                serde_json::to_string(&info.metadata)?,
-                now,
-                now,
-            ],
-        )?;
-
-        Ok(())
+        let version = conn
+            .query_row(&sql, values.as_slice(), |row| row.get::<_, i64>(0))
+            .optional()?;
+        match version {
+            Some(version) => Ok(version as u64),
+            None => {
+                let actual = conn
+                    .query_row("SELECT version FROM repositories WHERE id = ?1", params![info.id], |row| {
+                        row.get::<_, i64>(0)
+                    })
+                    .optional()?;
+                Err(RegistryConflict {
+                    id: info.id.clone(),
+                    expected,
+                    actual: actual.map(|version| version as u64),
+                }
+                .into())
+            }
+        }
     }
 
-    /// Get repository by ID
-    pub async fn get(&self, id: &str) -> Result<Option<RepositoryInfo>> {
+    async fn get(&self, id: &str) -> Result<Option<Versioned<RepositoryInfo>>> {
         let conn = self.conn.lock().await;
 
         let result = conn
             .query_row(
-                "SELECT * FROM repositories WHERE id = ?1",
+                &format!("SELECT {REPOSITORY_COLUMNS} FROM repositories WHERE id = ?1"),
                 params![id],
-                |row| {
-                    Ok(RepositoryInfo {
-                        id: row.get(0)?,
-                        name: row.get(1)?,
-                        path: PathBuf::from(row.get::<_, String>(2)?),
-                        remote_url: row.get(3)?,
-                        primary_language: row.get(4)?,
-                        build_system: row.get(5)?,
-                        is_monorepo_member: row.get(6)?,
-                        monorepo_id: row.get(7)?,
-                        tags: serde_json::from_str(&row.get::<_, String>(8)?).unwrap_or_default(),
-                        active: row.get(9)?,
-                        last_diagnostic_run: row
-                            .get::<_, Option<i64>>(10)?
-                            .map(|ts| DateTime::from_timestamp(ts, 0).unwrap()),
-                        metadata: serde_json::from_str(&row.get::<_, String>(11)?)
-                            .unwrap_or_default(),
-                    })
-                },
+                repository_from_row,
             )
             .optional()?;
 
         Ok(result)
     }
 
-    /// List all active repositories
-    pub async fn list_active(&self) -> Result<Vec<RepositoryInfo>> {
+    async fn list(&self, include_inactive: bool) -> Result<Vec<RepositoryInfo>> {
         let conn = self.conn.lock().await;
 
-        let mut stmt = conn.prepare("SELECT * FROM repositories WHERE active = 1 ORDER BY name")?;
+        let filter = if include_inactive { "" } else { "WHERE active = 1" };
+        let mut stmt = conn.prepare(&format!("SELECT {REPOSITORY_COLUMNS} FROM repositories {filter} ORDER BY name"))?;
 
         let repos = stmt
-            .query_map([], |row| {
-                Ok(RepositoryInfo {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    path: PathBuf::from(row.get::<_, String>(2)?),
-                    remote_url: row.get(3)?,
-                    primary_language: row.get(4)?,
-                    build_system: row.get(5)?,
-                    is_monorepo_member: row.get(6)?,
-                    monorepo_id: row.get(7)?,
-                    tags: serde_json::from_str(&row.get::<_, String>(8)?).unwrap_or_default(),
-                    active: row.get(9)?,
-                    last_diagnostic_run: row
-                        .get::<_, Option<i64>>(10)?
-                        .map(|ts| DateTime::from_timestamp(ts, 0).unwrap()),
-                    metadata: serde_json::from_str(&row.get::<_, String>(11)?).unwrap_or_default(),
-                })
-            })?
+            .query_map([], |row| repository_from_row(row).map(|repo| repo.value))?
             .collect::<rusqlite::Result<Vec<_>>>()?;
 
         Ok(repos)
     }
 
-    /// List all repositories (including inactive)
-    pub async fn list_all(&self) -> Result<Vec<RepositoryInfo>> {
+    async fn touch_diagnostic_run(&self, repo_id: &str, at: DateTime<Utc>) -> Result<()> {
         let conn = self.conn.lock().await;
 
-        let mut stmt = conn.prepare("SELECT * FROM repositories ORDER BY name")?;
-
-        let repos = stmt
-            .query_map([], |row| {
-                Ok(RepositoryInfo {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    path: PathBuf::from(row.get::<_, String>(2)?),
-                    remote_url: row.get(3)?,
-                    primary_language: row.get(4)?,
-                    build_system: row.get(5)?,
-                    is_monorepo_member: row.get(6)?,
-                    monorepo_id: row.get(7)?,
-                    tags: row
-                        .get::<_, Option<String>>(8)?
-                        .map(|s| s.split(',').map(String::from).collect())
-                        .unwrap_or_default(),
-                    active: row.get(9)?,
-                    last_diagnostic_run: row.get(10)?,
-                    metadata: serde_json::from_str(&row.get::<_, String>(11)?).ok().unwrap_or_default(),
-                })
-            })?
-            .collect::<rusqlite::Result<Vec<_>>>()?;
+        conn.execute(
+            "UPDATE repositories SET last_diagnostic_run = ?1, updated_at = ?2, version = version + 1 WHERE id = ?3",
+            params![at.timestamp(), Utc::now().timestamp(), repo_id],
+        )?;
 
-        Ok(repos)
+        Ok(())
     }
 
-    /// Add a relationship between repositories
-    pub async fn add_relation(&self, relation: RepositoryRelation) -> Result<()> {
+    async fn add_relation(&self, relation: &RepositoryRelation) -> Result<()> {
         let conn = self.conn.lock().await;
         let now = Utc::now().timestamp();
-
-        let relation_type = match &relation.relation_type {
-            RelationType::SharedTypes => "shared_types",
-            RelationType::Dependency => "dependency",
-            RelationType::DevDependency => "dev_dependency",
-            RelationType::MonorepoSibling => "monorepo_sibling",
-            RelationType::ApiRelation => "api_relation",
-            RelationType::Custom(name) => name,
-        };
+        let relation_type = relation.relation_type.as_str();
 
         conn.execute(
             r#"
@@ -307,84 +443,390 @@ impl RepositoryRegistry {
         Ok(())
     }
 
-    /// Get relationships for a repository
-    pub async fn get_relations(&self, repo_id: &str) -> Result<Vec<RepositoryRelation>> {
+    async fn relations(&self, repo_id: Option<&str>) -> Result<Vec<RepositoryRelation>> {
         let conn = self.conn.lock().await;
 
         let mut stmt = conn.prepare(
             r#"
             SELECT source_id, target_id, relation_type, data 
             FROM repository_relations 
-            WHERE source_id = ?1 OR target_id = ?1
+            WHERE ?1 IS NULL OR source_id = ?1 OR target_id = ?1
+            ORDER BY source_id, target_id
             "#,
         )?;
 
         let relations = stmt
-            .query_map(params![repo_id], |row| {
-                let relation_type_str: String = row.get(2)?;
-                let relation_type = match relation_type_str.as_str() {
-                    "shared_types" => RelationType::SharedTypes,
-                    "dependency" => RelationType::Dependency,
-                    "dev_dependency" => RelationType::DevDependency,
-                    "monorepo_sibling" => RelationType::MonorepoSibling,
-                    "api_relation" => RelationType::ApiRelation,
-                    other => RelationType::Custom(other.to_string()),
-                };
-
-                Ok(RepositoryRelation {
-                    source_id: row.get(0)?,
-                    target_id: row.get(1)?,
-                    relation_type,
-                    data: serde_json::from_str(&row.get::<_, String>(3)?).unwrap_or_default(),
-                })
-            })?
+            .query_map(params![repo_id], relation_from_row)?
             .collect::<rusqlite::Result<Vec<_>>>()?;
 
         Ok(relations)
     }
 
-    /// Find repositories by tag
-    pub async fn find_by_tag(&self, tag: &str) -> Result<Vec<RepositoryInfo>> {
+    async fn save_fleet(&self, fleet: &Fleet) -> Result<()> {
         let conn = self.conn.lock().await;
+        let now = Utc::now().timestamp();
 
-        let mut stmt =
-            conn.prepare("SELECT * FROM repositories WHERE tags LIKE ?1 AND active = 1")?;
+        conn.execute(
+            r#"
+            INSERT INTO fleets (name, description, tags, repositories, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+            ON CONFLICT(name) DO UPDATE SET
+                description = excluded.description,
+                tags = excluded.tags,
+                repositories = excluded.repositories,
+                updated_at = excluded.updated_at
+            "#,
+            params![
+                fleet.name,
+                fleet.description,
+                serde_json::to_string(&fleet.tags)?,
+                serde_json::to_string(&fleet.repositories)?,
+                now,
+            ],
+        )?;
 
-        let pattern = format!("%\"{tag}\"% ");
-        let repos = stmt
-            .query_map(params![pattern], |row| {
-                Ok(RepositoryInfo {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    path: PathBuf::from(row.get::<_, String>(2)?),
-                    remote_url: row.get(3)?,
-                    primary_language: row.get(4)?,
-                    build_system: row.get(5)?,
-                    is_monorepo_member: row.get(6)?,
-                    monorepo_id: row.get(7)?,
-                    tags: serde_json::from_str(&row.get::<_, String>(8)?).unwrap_or_default(),
-                    active: row.get(9)?,
-                    last_diagnostic_run: row
-                        .get::<_, Option<i64>>(10)?
-                        .map(|ts| DateTime::from_timestamp(ts, 0).unwrap()),
-                    metadata: serde_json::from_str(&row.get::<_, String>(11)?).unwrap_or_default(),
-                })
-            })?
+        Ok(())
+    }
+
+    async fn get_fleet(&self, name: &str) -> Result<Option<Fleet>> {
+        let conn = self.conn.lock().await;
+
+        let fleet = conn
+            .query_row(
+                "SELECT name, description, tags, repositories FROM fleets WHERE name = ?1",
+                params![name],
+                fleet_from_row,
+            )
+            .optional()?;
+
+        Ok(fleet)
+    }
+
+    async fn list_fleets(&self) -> Result<Vec<Fleet>> {
+        let conn = self.conn.lock().await;
+
+        let mut stmt =
+            conn.prepare("SELECT name, description, tags, repositories FROM fleets ORDER BY name")?;
+        let fleets = stmt
+            .query_map([], fleet_from_row)?
             .collect::<rusqlite::Result<Vec<_>>>()?;
 
-        Ok(repos)
+        Ok(fleets)
     }
 
-    /// Update last diagnostic run timestamp
-    pub async fn update_diagnostic_timestamp(&self, repo_id: &str) -> Result<()> {
+    async fn delete_fleet(&self, name: &str) -> Result<bool> {
         let conn = self.conn.lock().await;
-        let now = Utc::now();
+        let deleted = conn.execute("DELETE FROM fleets WHERE name = ?1", params![name])?;
+        Ok(deleted > 0)
+    }
+
+    async fn save_checkpoint(&self, run_key: &str, repo_id: &str, diagnostics: &[Diagnostic]) -> Result<()> {
+        let conn = self.conn.lock().await;
+        let now = Utc::now().timestamp();
 
         conn.execute(
-            "UPDATE repositories SET last_diagnostic_run = ?1, updated_at = ?2 WHERE id = ?3",
-            params![now.timestamp(), now.timestamp(), repo_id],
+            "INSERT OR REPLACE INTO analysis_checkpoints (run_key, repo_id, diagnostics, completed_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![run_key, repo_id, serde_json::to_string(diagnostics)?, now],
         )?;
 
         Ok(())
     }
+
+    async fn load_checkpoints(&self, run_key: &str) -> Result<HashMap<String, Vec<Diagnostic>>> {
+        let conn = self.conn.lock().await;
+
+        let mut stmt = conn.prepare("SELECT repo_id, diagnostics FROM analysis_checkpoints WHERE run_key = ?1")?;
+        let rows = stmt
+            .query_map(params![run_key], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        parse_checkpoints(rows)
+    }
+
+    async fn clear_checkpoints(&self, run_key: &str) -> Result<usize> {
+        let conn = self.conn.lock().await;
+        let removed = conn.execute("DELETE FROM analysis_checkpoints WHERE run_key = ?1", params![run_key])?;
+        Ok(removed)
+    }
+}
+
+/// Decode stored `(repo_id, diagnostics JSON)` checkpoints
+pub(crate) fn parse_checkpoints(rows: Vec<(String, String)>) -> Result<HashMap<String, Vec<Diagnostic>>> {
+    rows.into_iter()
+        .map(|(repo_id, diagnostics)| {
+            let diagnostics = serde_json::from_str(&diagnostics)
+                .with_context(|| format!("Corrupt analysis checkpoint for {repo_id}"))?;
+            Ok((repo_id, diagnostics))
+        })
+        .collect()
+}
+
+/// Repository registry for managing multiple repositories
+#[derive(Clone)]
+pub struct RepositoryRegistry {
+    backend: Arc<dyn RegistryBackend>,
+}
+
+impl RepositoryRegistry {
+    /// Load existing registry or create a new one
+    pub async fn load_or_create(path: &Path) -> Result<Self> {
+        Ok(Self::with_backend(Arc::new(SqliteRegistry::open(path).await?)))
+    }
+
+    /// Registry configured by `multi_repo`: the shared database at
+    /// `registry_url` when set, otherwise the SQLite file at `registry_path`
+    pub async fn connect(config: &MultiRepoConfig) -> Result<Self> {
+        Ok(Self::with_backend(super::backend::open(config).await?.registry))
+    }
+
+    pub fn with_backend(backend: Arc<dyn RegistryBackend>) -> Self {
+        Self { backend }
+    }
+
+    /// Register a repository, replacing any registration with the same ID
+    pub async fn register(&self, info: RepositoryInfo) -> Result<()> {
+        self.backend.register(&info, ExpectedVersion::Any).await?;
+        Ok(())
+    }
+
+    /// Register a repository that must not be registered yet, returning its version
+    ///
+    /// Fails with a [`RegistryConflict`] if the ID is taken.
+    pub async fn register_new(&self, info: &RepositoryInfo) -> Result<u64> {
+        self.backend.register(info, ExpectedVersion::New).await
+    }
+
+    /// Write back a repository read at `version`, returning its new version
+    ///
+    /// Fails with a [`RegistryConflict`] if it was changed since; re-read it
+    /// with [`get_versioned`](Self::get_versioned) and apply the change again.
+    pub async fn update(&self, info: &RepositoryInfo, version: u64) -> Result<u64> {
+        self.backend.register(info, ExpectedVersion::Version(version)).await
+    }
+
+    /// Get repository by ID
+    pub async fn get(&self, id: &str) -> Result<Option<RepositoryInfo>> {
+        Ok(self.backend.get(id).await?.map(|repo| repo.value))
+    }
+
+    /// Get a repository with the version to pass to [`update`](Self::update)
+    pub async fn get_versioned(&self, id: &str) -> Result<Option<Versioned<RepositoryInfo>>> {
+        self.backend.get(id).await
+    }
+
+    /// List all active repositories
+    pub async fn list_active(&self) -> Result<Vec<RepositoryInfo>> {
+        self.backend.list(false).await
+    }
+
+    /// List all repositories (including inactive)
+    pub async fn list_all(&self) -> Result<Vec<RepositoryInfo>> {
+        self.backend.list(true).await
+    }
+
+    /// Add a relationship between repositories
+    pub async fn add_relation(&self, relation: RepositoryRelation) -> Result<()> {
+        self.backend.add_relation(&relation).await
+    }
+
+    /// Get relationships for a repository
+    pub async fn get_relations(&self, repo_id: &str) -> Result<Vec<RepositoryRelation>> {
+        self.backend.relations(Some(repo_id)).await
+    }
+
+    /// Every relationship in the registry
+    pub async fn list_relations(&self) -> Result<Vec<RepositoryRelation>> {
+        self.backend.relations(None).await
+    }
+
+    /// Graph of active repositories and the relationships between them
+    ///
+    /// Repositories are grouped by primary language.
+    pub async fn dependency_graph(&self) -> Result<RelationGraph> {
+        let repos = self.list_active().await?;
+        let mut graph = RelationGraph::new("repo-deps");
+        for repo in &repos {
+            graph.add_node(&repo.id, &repo.name, repo.primary_language.clone());
+        }
+        for relation in self.list_relations().await? {
+            let known = |id: &str| repos.iter().any(|repo| repo.id == id);
+            if known(&relation.source_id) && known(&relation.target_id) {
+                graph.add_edge(
+                    relation.source_id,
+                    relation.target_id,
+                    Some(relation.relation_type.as_str().to_string()),
+                );
+            }
+        }
+        Ok(graph)
+    }
+
+    /// Find active repositories with a tag, or a tag below it in the hierarchy
+    pub async fn find_by_tag(&self, tag: &str) -> Result<Vec<RepositoryInfo>> {
+        let repos = self.list_active().await?;
+        Ok(repos
+            .into_iter()
+            .filter(|repo| repo.tags.iter().any(|t| tag_matches(t, tag)))
+            .collect())
+    }
+
+    /// Save a fleet, replacing any fleet with the same name
+    pub async fn save_fleet(&self, fleet: &Fleet) -> Result<()> {
+        self.backend.save_fleet(fleet).await
+    }
+
+    /// Get a fleet by name
+    pub async fn get_fleet(&self, name: &str) -> Result<Option<Fleet>> {
+        self.backend.get_fleet(name).await
+    }
+
+    /// List saved fleets
+    pub async fn list_fleets(&self) -> Result<Vec<Fleet>> {
+        self.backend.list_fleets().await
+    }
+
+    /// Delete a fleet; returns whether it existed
+    pub async fn delete_fleet(&self, name: &str) -> Result<bool> {
+        self.backend.delete_fleet(name).await
+    }
+
+    /// Active repositories in a saved fleet
+    pub async fn fleet_members(&self, name: &str) -> Result<Vec<RepositoryInfo>> {
+        let fleet = self
+            .get_fleet(name)
+            .await?
+            .with_context(|| format!("Unknown fleet '{name}'"))?;
+        let repos = self.list_active().await?;
+        Ok(repos.into_iter().filter(|repo| fleet.includes(repo)).collect())
+    }
+
+    /// Update last diagnostic run timestamp
+    pub async fn update_diagnostic_timestamp(&self, repo_id: &str) -> Result<()> {
+        self.backend.touch_diagnostic_run(repo_id, Utc::now()).await
+    }
+
+    /// Record that `repo_id` finished analysis in the run `run_key`
+    ///
+    /// Also updates the repository's last diagnostic run.
+    pub async fn save_checkpoint(&self, run_key: &str, repo_id: &str, diagnostics: &[Diagnostic]) -> Result<()> {
+        self.backend.save_checkpoint(run_key, repo_id, diagnostics).await?;
+        self.backend.touch_diagnostic_run(repo_id, Utc::now()).await
+    }
+
+    /// Diagnostics of the repositories already finished in the run `run_key`
+    pub async fn load_checkpoints(&self, run_key: &str) -> Result<HashMap<String, Vec<Diagnostic>>> {
+        self.backend.load_checkpoints(run_key).await
+    }
+
+    /// Forget the checkpoints of the run `run_key`; returns how many were removed
+    pub async fn clear_checkpoints(&self, run_key: &str) -> Result<usize> {
+        self.backend.clear_checkpoints(run_key).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(id: &str, tags: &[&str]) -> RepositoryInfo {
+        RepositoryInfo {
+            id: id.to_string(),
+            name: format!("{id}-service"),
+            path: PathBuf::from(format!("/src/{id}")),
+            remote_url: None,
+            primary_language: None,
+            build_system: None,
+            is_monorepo_member: false,
+            monorepo_id: None,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            active: true,
+            last_diagnostic_run: None,
+            metadata: serde_json::json!({}),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hierarchical_tags_and_fleets() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let registry = RepositoryRegistry::load_or_create(&dir.path().join("repos.db")).await?;
+        registry.register(repo("billing", &["team/payments/billing"])).await?;
+        registry.register(repo("ledger", &["Team/Payments"])).await?;
+        registry.register(repo("checkout", &["team/pay"])).await?;
+        registry.register(repo("search", &["team/discovery"])).await?;
+
+        let ids = |repos: Vec<RepositoryInfo>| repos.into_iter().map(|r| r.id).collect::<Vec<_>>();
+        assert_eq!(ids(registry.find_by_tag("team/payments").await?), ["billing", "ledger"]);
+        assert_eq!(ids(registry.find_by_tag("team").await?).len(), 4);
+
+        let fleet = Fleet {
+            name: "payments-services".to_string(),
+            description: None,
+            tags: vec!["team/payments".to_string()],
+            repositories: vec!["search-service".to_string()],
+        };
+        registry.save_fleet(&fleet).await?;
+        assert_eq!(registry.get_fleet("payments-services").await?, Some(fleet));
+        assert_eq!(
+            ids(registry.fleet_members("payments-services").await?),
+            ["billing", "ledger", "search"]
+        );
+        assert!(registry.fleet_members("unknown").await.is_err());
+
+        assert!(registry.delete_fleet("payments-services").await?);
+        assert!(registry.list_fleets().await?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_versioned_writes_detect_conflicts() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let registry = RepositoryRegistry::load_or_create(&dir.path().join("repos.db")).await?;
+        let mut billing = repo("billing", &[]);
+        assert_eq!(registry.register_new(&billing).await?, 1);
+        let conflict = registry.register_new(&billing).await.unwrap_err().downcast::<RegistryConflict>()?;
+        assert_eq!(conflict.actual, Some(1));
+
+        // Two writers read version 1; only the first write lands
+        billing.tags = vec!["team/payments".to_string()];
+        assert_eq!(registry.update(&billing, 1).await?, 2);
+        billing.tags = vec!["team/ledger".to_string()];
+        let conflict = registry.update(&billing, 1).await.unwrap_err().downcast::<RegistryConflict>()?;
+        assert_eq!((conflict.expected, conflict.actual), (ExpectedVersion::Version(1), Some(2)));
+
+        registry.update_diagnostic_timestamp("billing").await?;
+        let stored = registry.get_versioned("billing").await?.expect("registered");
+        assert_eq!(stored.version, 3);
+        assert_eq!(stored.value.tags, ["team/payments"]);
+        assert!(stored.value.last_diagnostic_run.is_some());
+
+        let missing = registry.update(&repo("search", &[]), 1).await.unwrap_err().downcast::<RegistryConflict>()?;
+        assert_eq!(missing.actual, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dependency_graph() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let registry = RepositoryRegistry::load_or_create(&dir.path().join("repos.db")).await?;
+        registry.register(repo("web", &[])).await?;
+        registry.register(repo("api", &[])).await?;
+        registry.register(RepositoryInfo { active: false, ..repo("legacy", &[]) }).await?;
+        for (target, relation_type) in [("api", RelationType::Dependency), ("legacy", RelationType::SharedTypes)] {
+            registry
+                .add_relation(RepositoryRelation {
+                    source_id: "web".to_string(),
+                    target_id: target.to_string(),
+                    relation_type,
+                    data: serde_json::json!({}),
+                })
+                .await?;
+        }
+
+        let graph = registry.dependency_graph().await?;
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(graph.edges.len(), 1);
+        assert_eq!(graph.edges[0].label.as_deref(), Some("dependency"));
+        Ok(())
+    }
 }
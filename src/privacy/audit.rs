@@ -0,0 +1,147 @@
+//! Audit trail of privacy filter decisions
+//!
+//! With auditing on, every diagnostic the [`PrivacyFilter`] drops and every
+//! field it rewrites is appended to a JSON-lines [`AuditLog`]: which rule
+//! fired, SHA-256 hashes of the original value and of each removed span,
+//! and how many bytes were removed. The sensitive text itself is never
+//! written, yet a security team can hash a secret they know about and
+//! check that a rule caught it before anything was exported.
+//!
+//! [`PrivacyFilter`]: super::PrivacyFilter
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+
+use crate::core::{AuditLog, Diagnostic};
+
+/// Audit event name of a single filter decision
+pub const REDACTION_EVENT: &str = "privacy_redaction";
+
+/// Audit event name of the per-batch summary
+pub const FILTER_SUMMARY_EVENT: &str = "privacy_filter_applied";
+
+/// The privacy rule behind a decision
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RedactionRule {
+    /// The file is ignored by the workspace's `.gitignore`-aware filter
+    WorkspaceFilter,
+    /// The file lies outside every root of a multi-root workspace
+    OutsideWorkspaceRoots,
+    /// The file matches one of the policy's `exclude_patterns`
+    ExcludePattern { pattern: String },
+    /// The policy only keeps errors
+    ErrorsOnly,
+    /// The file already had `max_diagnostics_per_file` more severe diagnostics
+    PerFileLimit { max: usize },
+    /// String literals were replaced by `sanitize_strings`
+    StringLiterals,
+    /// Comments were replaced by `sanitize_comments`
+    Comments,
+    /// Matches of one of the policy's `redaction_patterns` were replaced
+    RedactionPattern { pattern: String },
+    /// The directory of a path was replaced by `anonymize_file_paths`
+    AnonymizedPath,
+}
+
+/// One decision of the privacy filter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedactionRecord {
+    /// Id of the diagnostic the decision applies to
+    pub diagnostic_id: String,
+    pub rule: RedactionRule,
+    /// The rewritten field, e.g. `message` or `related_information[0].message`;
+    /// `None` when the whole diagnostic was dropped
+    pub field: Option<String>,
+    /// SHA-256 of the original field, or of the whole diagnostic as JSON when dropped
+    pub original_sha256: String,
+    /// SHA-256 of each span the rule removed
+    pub removed_sha256: Vec<String>,
+    pub removed_bytes: usize,
+}
+
+impl RedactionRecord {
+    /// A diagnostic dropped from the output entirely
+    pub fn excluded(diagnostic: &Diagnostic, rule: RedactionRule) -> Self {
+        let original = serde_json::to_string(diagnostic).unwrap_or_default();
+        Self {
+            diagnostic_id: diagnostic.id.clone(),
+            rule,
+            field: None,
+            original_sha256: sha256_hex(&original),
+            removed_sha256: vec![sha256_hex(&original)],
+            removed_bytes: original.len(),
+        }
+    }
+
+    /// A field of a diagnostic from which `removed` spans were replaced
+    pub fn rewritten(diagnostic_id: &str, field: &str, original: &str, rule: RedactionRule, removed: &[String]) -> Self {
+        Self {
+            diagnostic_id: diagnostic_id.to_string(),
+            rule,
+            field: Some(field.to_string()),
+            original_sha256: sha256_hex(original),
+            removed_sha256: removed.iter().map(|span| sha256_hex(span)).collect(),
+            removed_bytes: removed.iter().map(String::len).sum(),
+        }
+    }
+}
+
+/// Counts of one batch the filter processed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilterSummary {
+    pub received: usize,
+    pub excluded: usize,
+    /// Diagnostics that passed with at least one field rewritten
+    pub redacted: usize,
+    pub passed: usize,
+}
+
+/// Writes privacy filter decisions to an append-only audit log
+#[derive(Debug, Clone)]
+pub struct RedactionAudit {
+    log: AuditLog,
+}
+
+impl RedactionAudit {
+    pub fn new(log: AuditLog) -> Self {
+        Self { log }
+    }
+
+    /// Audit into `path`, the `privacy.audit_log` setting
+    pub fn at(path: impl Into<PathBuf>) -> Self {
+        Self::new(AuditLog::new(path))
+    }
+
+    pub fn log(&self) -> &AuditLog {
+        &self.log
+    }
+
+    /// Append a batch's decisions followed by its summary
+    pub fn record(&self, records: &[RedactionRecord], summary: FilterSummary) -> Result<()> {
+        let mut events = Vec::with_capacity(records.len() + 1);
+        for record in records {
+            events.push((REDACTION_EVENT, serde_json::to_value(record)?));
+        }
+        events.push((FILTER_SUMMARY_EVENT, serde_json::to_value(summary)?));
+        self.log.record_all(events)
+    }
+
+    /// Every decision recorded so far, oldest first
+    pub fn records(&self) -> Result<Vec<RedactionRecord>> {
+        Ok(self
+            .log
+            .read_events()?
+            .into_iter()
+            .filter(|event| event.event == REDACTION_EVENT)
+            .filter_map(|event| serde_json::from_value(event.details).ok())
+            .collect())
+    }
+}
+
+/// Lowercase hex SHA-256 of `value`
+pub fn sha256_hex(value: &str) -> String {
+    Sha256::digest(value.as_bytes()).iter().map(|byte| format!("{byte:02x}")).collect()
+}
@@ -1,6 +1,10 @@
+pub mod audit;
+pub mod preview;
 pub mod privacy_filter;
 pub mod workspace_filter;
 
+pub use audit::{FilterSummary, RedactionAudit, RedactionRecord, RedactionRule};
+pub use preview::{PreviewEntry, PreviewStyle, RedactionPreview};
 pub use privacy_filter::PrivacyFilter;
 pub use workspace_filter::WorkspaceFilter;
 
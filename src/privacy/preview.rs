@@ -0,0 +1,237 @@
+//! Preview of what privacy filtering changes before an export
+//!
+//! A [`RedactionPreview`] pairs a sample of captured diagnostics with their
+//! privacy-filtered counterparts and renders them as a side-by-side or
+//! unified diff, so users can check exactly what an assistant will see.
+//! Diagnostics that filtering changes or drops are sampled first.
+
+use crate::core::{Diagnostic, PrivacyFilter as PrivacyFilterTrait};
+use anyhow::Result;
+use clap::ValueEnum;
+use std::collections::HashMap;
+
+/// How original and filtered diagnostics are laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PreviewStyle {
+    /// Original on the left, filtered on the right
+    SideBySide,
+    /// `-`/`+` lines, one diagnostic after another
+    Unified,
+}
+
+/// One sampled diagnostic and what survives filtering
+#[derive(Debug, Clone)]
+pub struct PreviewEntry {
+    pub original: Diagnostic,
+    /// `None` when the privacy policy excludes the diagnostic
+    pub filtered: Option<Diagnostic>,
+}
+
+impl PreviewEntry {
+    pub fn is_changed(&self) -> bool {
+        match &self.filtered {
+            Some(filtered) => describe(filtered) != describe(&self.original),
+            None => true,
+        }
+    }
+}
+
+/// Original vs privacy-filtered view of a sample of diagnostics
+#[derive(Debug, Clone)]
+pub struct RedactionPreview {
+    pub entries: Vec<PreviewEntry>,
+    pub total: usize,
+    pub changed: usize,
+    pub excluded: usize,
+}
+
+impl RedactionPreview {
+    /// Run `filter` over `diagnostics` and keep up to `sample` entries
+    pub fn build(filter: &dyn PrivacyFilterTrait, diagnostics: Vec<Diagnostic>, sample: usize) -> Result<Self> {
+        let mut filtered: HashMap<String, Diagnostic> = filter
+            .apply(diagnostics.clone())?
+            .into_iter()
+            .map(|d| (d.id.clone(), d))
+            .collect();
+
+        let entries: Vec<PreviewEntry> = diagnostics
+            .into_iter()
+            .map(|original| PreviewEntry {
+                filtered: filtered.remove(&original.id),
+                original,
+            })
+            .collect();
+
+        let total = entries.len();
+        let excluded = entries.iter().filter(|e| e.filtered.is_none()).count();
+        let changed = entries.iter().filter(|e| e.is_changed()).count();
+
+        // Changed entries first, otherwise in capture order
+        let mut indexed: Vec<(usize, PreviewEntry)> = entries.into_iter().enumerate().collect();
+        indexed.sort_by_key(|(index, entry)| (!entry.is_changed(), *index));
+        indexed.truncate(sample);
+        indexed.sort_by_key(|(index, _)| *index);
+
+        Ok(Self {
+            entries: indexed.into_iter().map(|(_, entry)| entry).collect(),
+            total,
+            changed,
+            excluded,
+        })
+    }
+
+    /// Render the preview; `width` is the total line width for side-by-side output
+    pub fn render(&self, style: PreviewStyle, width: usize) -> String {
+        let mut out = Vec::new();
+        out.push(format!(
+            "Redaction preview: {} of {} diagnostic(s) shown, {} changed by filtering, {} excluded",
+            self.entries.len(),
+            self.total,
+            self.changed,
+            self.excluded
+        ));
+        out.push(String::new());
+
+        for entry in &self.entries {
+            let original = describe(&entry.original);
+            let filtered = match &entry.filtered {
+                Some(filtered) => describe(filtered),
+                None => vec!["(excluded by privacy policy)".to_string()],
+            };
+            match style {
+                PreviewStyle::SideBySide => render_side_by_side(&mut out, &original, &filtered, width),
+                PreviewStyle::Unified => render_unified(&mut out, &original, &filtered),
+            }
+            out.push(String::new());
+        }
+
+        out.join("\n")
+    }
+}
+
+/// The fields of a diagnostic that reach an export, one per line
+fn describe(diagnostic: &Diagnostic) -> Vec<String> {
+    let mut lines = vec![
+        format!(
+            "{}:{}:{}",
+            diagnostic.file,
+            diagnostic.range.start.line + 1,
+            diagnostic.range.start.character + 1
+        ),
+        match &diagnostic.code {
+            Some(code) => format!("{:?} [{} {}]", diagnostic.severity, diagnostic.source, code),
+            None => format!("{:?} [{}]", diagnostic.severity, diagnostic.source),
+        },
+    ];
+    lines.extend(diagnostic.message.lines().map(String::from));
+    for info in diagnostic.related_information.iter().flatten() {
+        lines.push(format!("related: {} {}", info.location.uri, info.message));
+    }
+    lines
+}
+
+fn render_unified(out: &mut Vec<String>, original: &[String], filtered: &[String]) {
+    if original == filtered {
+        out.extend(original.iter().map(|line| format!("  {line}")));
+        return;
+    }
+    out.push("@@ original / filtered @@".to_string());
+    for index in 0..original.len().max(filtered.len()) {
+        match (original.get(index), filtered.get(index)) {
+            (Some(left), Some(right)) if left == right => out.push(format!("  {left}")),
+            (left, right) => {
+                if let Some(left) = left {
+                    out.push(format!("- {left}"));
+                }
+                if let Some(right) = right {
+                    out.push(format!("+ {right}"));
+                }
+            }
+        }
+    }
+}
+
+/// Two columns with `diff -y` style gutter markers: `|` changed, `<` only original, `>` only filtered
+fn render_side_by_side(out: &mut Vec<String>, original: &[String], filtered: &[String], width: usize) {
+    let column = (width.saturating_sub(3) / 2).max(20);
+    out.push(format!("{:<column$} | {}", "ORIGINAL", "FILTERED"));
+    out.push(format!("{}-+-{}", "-".repeat(column), "-".repeat(column)));
+
+    for index in 0..original.len().max(filtered.len()) {
+        let left = original.get(index);
+        let right = filtered.get(index);
+        let marker = match (left, right) {
+            (Some(l), Some(r)) if l == r => ' ',
+            (Some(_), Some(_)) => '|',
+            (Some(_), None) => '<',
+            _ => '>',
+        };
+        let left = wrap(left.map(String::as_str).unwrap_or_default(), column);
+        let right = wrap(right.map(String::as_str).unwrap_or_default(), column);
+        for row in 0..left.len().max(right.len()) {
+            let l = left.get(row).map(String::as_str).unwrap_or_default();
+            let r = right.get(row).map(String::as_str).unwrap_or_default();
+            out.push(format!("{l:<column$} {marker} {r}").trim_end().to_string());
+        }
+    }
+}
+
+/// Split a line into chunks of at most `width` characters
+fn wrap(line: &str, width: usize) -> Vec<String> {
+    let chars: Vec<char> = line.chars().collect();
+    if chars.is_empty() {
+        return vec![String::new()];
+    }
+    chars.chunks(width).map(|chunk| chunk.iter().collect()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{DiagnosticSeverity, Position, PrivacyPolicy, Range};
+    use crate::privacy::PrivacyFilter;
+
+    fn diagnostic(file: &str, severity: DiagnosticSeverity, message: &str) -> Diagnostic {
+        Diagnostic::new(
+            file.to_string(),
+            Range {
+                start: Position { line: 4, character: 2 },
+                end: Position { line: 4, character: 8 },
+            },
+            severity,
+            message.to_string(),
+            "tsc".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_changed_and_excluded_diagnostics_are_sampled_first() {
+        let policy = PrivacyPolicy {
+            include_only_errors: true,
+            sanitize_strings: true,
+            ..PrivacyPolicy::default()
+        };
+        let filter = PrivacyFilter::new(policy);
+        let diagnostics = vec![
+            diagnostic("a.ts", DiagnosticSeverity::Error, "Missing semicolon"),
+            diagnostic("b.ts", DiagnosticSeverity::Warning, "Unused variable"),
+            diagnostic("c.ts", DiagnosticSeverity::Error, "Expected \"sk-live-1234\" to be a number"),
+        ];
+
+        let preview = RedactionPreview::build(&filter, diagnostics, 2).unwrap();
+        assert_eq!((preview.total, preview.excluded), (3, 1));
+        assert_eq!(preview.entries.len(), 2);
+        assert_eq!(preview.entries[0].original.file, "b.ts");
+        assert!(preview.entries[0].filtered.is_none());
+        assert_eq!(preview.entries[1].original.file, "c.ts");
+
+        let unified = preview.render(PreviewStyle::Unified, 100);
+        assert!(unified.contains("+ (excluded by privacy policy)"));
+        assert!(unified.contains("- Expected \"sk-live-1234\" to be a number"));
+        assert!(!unified.contains("+ Expected \"sk-live-1234\""));
+
+        let side_by_side = preview.render(PreviewStyle::SideBySide, 60);
+        assert!(side_by_side.lines().any(|line| line.starts_with("Expected") && line.contains(" | ")));
+        assert!(side_by_side.lines().all(|line| line.chars().count() <= 60 || line.starts_with("Redaction")));
+    }
+}
@@ -1,8 +1,10 @@
+use super::audit::{FilterSummary, RedactionAudit, RedactionRecord, RedactionRule};
 use super::workspace_filter::WorkspaceFilter;
 use crate::core::{
     Diagnostic, DiagnosticSeverity, PrivacyFilter as PrivacyFilterTrait, PrivacyPolicy,
+    WorkspaceRoot,
 };
-use anyhow::Result;
+use anyhow::{Context, Result};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::collections::HashMap;
@@ -28,17 +30,37 @@ static HASH_COMMENT_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"#.*$").expect("Failed to compile hash comment regex")
 });
 
+/// Workspace filter and optional policy override for one root of a
+/// multi-root workspace
+struct RootPrivacy {
+    root: WorkspaceRoot,
+    filter: WorkspaceFilter,
+    policy: Option<PrivacyPolicy>,
+}
+
 pub struct PrivacyFilter {
     policy: PrivacyPolicy,
     workspace_filter: Option<WorkspaceFilter>,
+    roots: Vec<RootPrivacy>,
+    /// Compiled `redaction_patterns` of every policy, keyed by pattern
+    redactions: HashMap<String, Regex>,
+    audit: Option<RedactionAudit>,
 }
 
+/// Rules that fired on one field, with the spans each removed
+type Fired = Vec<(RedactionRule, Vec<String>)>;
+
 impl PrivacyFilter {
     pub fn new(policy: PrivacyPolicy) -> Self {
-        Self {
+        let mut filter = Self {
             policy,
             workspace_filter: None,
-        }
+            roots: Vec::new(),
+            redactions: HashMap::new(),
+            audit: None,
+        };
+        filter.compile_redactions();
+        filter
     }
 
     pub fn with_default_policy() -> Self {
@@ -58,8 +80,133 @@ impl PrivacyFilter {
         self
     }
 
+    /// Filter against the folders of a multi-root workspace.
+    ///
+    /// Each root gets its own `.gitignore`-aware workspace filter, and files
+    /// outside every root are excluded. Takes precedence over
+    /// [`with_workspace`](Self::with_workspace).
+    pub fn with_workspace_roots(mut self, roots: &[WorkspaceRoot]) -> Self {
+        self.roots = roots
+            .iter()
+            .map(|root| RootPrivacy {
+                root: root.clone(),
+                filter: WorkspaceFilter::new(PathBuf::from(&root.path)),
+                policy: None,
+            })
+            .collect();
+        self
+    }
+
+    /// Record every exclusion and redaction in an audit log.
+    ///
+    /// Writing the log is part of filtering: if it fails, so does
+    /// [`apply`](PrivacyFilterTrait::apply), and nothing unaudited is exported.
+    pub fn with_audit(mut self, audit: RedactionAudit) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// Override the policy for diagnostics in one workspace root.
+    ///
+    /// Roots without an override use the filter's default policy.
+    pub fn with_root_policy(mut self, root_name: &str, policy: PrivacyPolicy) -> Self {
+        match self.roots.iter_mut().find(|r| r.root.name == root_name) {
+            Some(root) => root.policy = Some(policy),
+            None => tracing::warn!("No workspace root named '{}' for privacy policy", root_name),
+        }
+        self.compile_redactions();
+        self
+    }
+
+    /// Resolve the policy that applies to a file
+    pub fn policy_for(&self, file: &str) -> &PrivacyPolicy {
+        self.root_for(file)
+            .and_then(|root| root.policy.as_ref())
+            .unwrap_or(&self.policy)
+    }
+
+    fn root_for(&self, file: &str) -> Option<&RootPrivacy> {
+        let file = Path::new(file);
+        self.roots
+            .iter()
+            .filter(|r| file.starts_with(&r.root.path))
+            .max_by_key(|r| r.root.path.len())
+    }
+
     pub fn update_policy(&mut self, policy: PrivacyPolicy) {
         self.policy = policy;
+        self.compile_redactions();
+    }
+
+    /// Compile the redaction patterns of all policies; invalid patterns are skipped
+    fn compile_redactions(&mut self) {
+        let patterns: Vec<String> = std::iter::once(&self.policy)
+            .chain(self.roots.iter().filter_map(|r| r.policy.as_ref()))
+            .flat_map(|policy| policy.redaction_patterns.iter().cloned())
+            .collect();
+
+        self.redactions.retain(|pattern, _| patterns.contains(pattern));
+        for pattern in patterns {
+            if self.redactions.contains_key(&pattern) {
+                continue;
+            }
+            match Regex::new(&pattern) {
+                Ok(regex) => {
+                    self.redactions.insert(pattern, regex);
+                }
+                Err(e) => tracing::warn!("Ignoring invalid redaction pattern '{}': {}", pattern, e),
+            }
+        }
+    }
+
+    /// Apply the policy's message rules to `text`; comments are only
+    /// sanitized when `comments` is set
+    fn sanitize_text(&self, policy: &PrivacyPolicy, text: &str, comments: bool) -> (String, Fired) {
+        let mut text = text.to_string();
+        let mut fired = Vec::new();
+        let mut removed = Vec::new();
+
+        if policy.sanitize_strings {
+            text = self.sanitize_string_literals(&text, &mut removed);
+            fired.push((RedactionRule::StringLiterals, std::mem::take(&mut removed)));
+        }
+        if comments && policy.sanitize_comments {
+            text = self.sanitize_comments(&text, &mut removed);
+            fired.push((RedactionRule::Comments, std::mem::take(&mut removed)));
+        }
+        // Replace matches of the policy's redaction patterns
+        for pattern in &policy.redaction_patterns {
+            if let Some(regex) = self.redactions.get(pattern) {
+                text = replace_all(regex, &text, "[REDACTED]", &mut removed);
+                let rule = RedactionRule::RedactionPattern { pattern: pattern.clone() };
+                fired.push((rule, std::mem::take(&mut removed)));
+            }
+        }
+
+        fired.retain(|(_, removed)| !removed.is_empty());
+        (text, fired)
+    }
+
+    fn anonymize_path(&self, path: &str) -> (String, Fired) {
+        let fired = match path.rsplit_once('/') {
+            Some((dir, _)) if !dir.is_empty() => vec![(RedactionRule::AnonymizedPath, vec![dir.to_string()])],
+            _ => Vec::new(),
+        };
+        (self.anonymize_file_path(path), fired)
+    }
+
+    /// Record a decision when auditing
+    fn note(&self, records: &mut Vec<RedactionRecord>, record: impl FnOnce() -> RedactionRecord) {
+        if self.audit.is_some() {
+            records.push(record());
+        }
+    }
+
+    /// Record the rules that rewrote one field of a diagnostic when auditing
+    fn note_rewrites(&self, records: &mut Vec<RedactionRecord>, id: &str, field: &str, original: &str, fired: Fired) {
+        for (rule, removed) in fired {
+            self.note(records, || RedactionRecord::rewritten(id, field, original, rule, &removed));
+        }
     }
 
     pub fn get_policy(&self) -> &PrivacyPolicy {
@@ -74,7 +221,7 @@ impl PrivacyFilter {
     /// 
     /// This function replaces string literals with placeholder text while preserving
     /// the overall structure of the message for debugging purposes.
-    fn sanitize_string_literals(&self, message: &str) -> String {
+    fn sanitize_string_literals(&self, message: &str, removed: &mut Vec<String>) -> String {
         if message.is_empty() {
             return message.to_string();
         }
@@ -89,19 +236,13 @@ impl PrivacyFilter {
         let mut result = message.to_string();
 
         // Replace double quotes using pre-compiled regex
-        result = DOUBLE_QUOTE_REGEX
-            .replace_all(&result, r#""[STRING]""#)
-            .to_string();
+        result = replace_all(&DOUBLE_QUOTE_REGEX, &result, r#""[STRING]""#, removed);
 
         // Replace single quotes using pre-compiled regex
-        result = SINGLE_QUOTE_REGEX
-            .replace_all(&result, "'[STRING]'")
-            .to_string();
+        result = replace_all(&SINGLE_QUOTE_REGEX, &result, "'[STRING]'", removed);
 
         // Replace template literals using pre-compiled regex
-        result = TEMPLATE_LITERAL_REGEX
-            .replace_all(&result, "`[STRING]`")
-            .to_string();
+        result = replace_all(&TEMPLATE_LITERAL_REGEX, &result, "`[STRING]`", removed);
 
         result
     }
@@ -110,7 +251,7 @@ impl PrivacyFilter {
     /// 
     /// This function replaces comments with placeholder text while preserving
     /// the comment structure for context.
-    fn sanitize_comments(&self, message: &str) -> String {
+    fn sanitize_comments(&self, message: &str, removed: &mut Vec<String>) -> String {
         if message.is_empty() {
             return message.to_string();
         }
@@ -125,19 +266,13 @@ impl PrivacyFilter {
         let mut result = message.to_string();
 
         // Remove line comments using pre-compiled regex
-        result = LINE_COMMENT_REGEX
-            .replace_all(&result, "// [COMMENT]")
-            .to_string();
+        result = replace_all(&LINE_COMMENT_REGEX, &result, "// [COMMENT]", removed);
 
         // Remove block comments using pre-compiled regex
-        result = BLOCK_COMMENT_REGEX
-            .replace_all(&result, "/* [COMMENT] */")
-            .to_string();
+        result = replace_all(&BLOCK_COMMENT_REGEX, &result, "/* [COMMENT] */", removed);
 
         // Remove hash comments using pre-compiled regex
-        result = HASH_COMMENT_REGEX
-            .replace_all(&result, "# [COMMENT]")
-            .to_string();
+        result = replace_all(&HASH_COMMENT_REGEX, &result, "# [COMMENT]", removed);
 
         result
     }
@@ -219,11 +354,11 @@ impl PrivacyFilter {
         })
     }
 
-    fn limit_diagnostics_per_file(&self, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
-        if self.policy.max_diagnostics_per_file == 0 {
-            return diagnostics;
-        }
-
+    fn limit_diagnostics_per_file(
+        &self,
+        diagnostics: Vec<Diagnostic>,
+        records: &mut Vec<RedactionRecord>,
+    ) -> Vec<Diagnostic> {
         let mut file_groups: HashMap<String, Vec<Diagnostic>> = HashMap::new();
 
         // Group by file
@@ -236,56 +371,56 @@ impl PrivacyFilter {
 
         // Limit each group and prioritize by severity
         let mut limited = Vec::new();
-        for (_, mut file_diagnostics) in file_groups {
-            // Sort by severity (errors first)
-            file_diagnostics.sort_by_key(|d| d.severity as u8);
-
-            // Take only the allowed number
-            file_diagnostics.truncate(self.policy.max_diagnostics_per_file);
+        for (file, mut file_diagnostics) in file_groups {
+            // A limit of 0 means unlimited
+            let max = self.policy_for(&file).max_diagnostics_per_file;
+            if max > 0 {
+                // Sort by severity (errors first)
+                file_diagnostics.sort_by_key(|d| d.severity as u8);
+
+                // Take only the allowed number
+                for dropped in file_diagnostics.split_off(max.min(file_diagnostics.len())) {
+                    self.note(records, || RedactionRecord::excluded(&dropped, RedactionRule::PerFileLimit { max }));
+                }
+            }
             limited.extend(file_diagnostics);
         }
 
         limited
     }
-}
-
-impl PrivacyFilterTrait for PrivacyFilter {
-    fn apply(&self, diagnostics: Vec<Diagnostic>) -> Result<Vec<Diagnostic>> {
-        let mut filtered: Vec<Diagnostic> = diagnostics
-            .into_iter()
-            .filter(|d| self.should_include_diagnostic(d))
-            .map(|d| self.sanitize_diagnostic(d))
-            .collect();
-
-        // Apply per-file limits
-        if self.policy.max_diagnostics_per_file > 0 {
-            filtered = self.limit_diagnostics_per_file(filtered);
-        }
-
-        Ok(filtered)
-    }
 
-    fn get_policy(&self) -> &PrivacyPolicy {
-        &self.policy
-    }
-
-    fn should_include_diagnostic(&self, diagnostic: &Diagnostic) -> bool {
-        // First check workspace filter if available
-        if let Some(ref workspace_filter) = self.workspace_filter {
+    /// The rule that keeps a diagnostic out of the output, if any
+    fn exclusion_rule(&self, diagnostic: &Diagnostic) -> Option<RedactionRule> {
+        // Multi-root workspaces: the file must belong to a root and pass its filter
+        if !self.roots.is_empty() {
+            match self.root_for(&diagnostic.file) {
+                Some(root) => {
+                    if !root.filter.should_include_file(Path::new(&diagnostic.file)) {
+                        return Some(RedactionRule::WorkspaceFilter);
+                    }
+                }
+                None => return Some(RedactionRule::OutsideWorkspaceRoots),
+            }
+        } else if let Some(ref workspace_filter) = self.workspace_filter {
+            // Otherwise check the single workspace filter if available
             let file_path = Path::new(&diagnostic.file);
             if !workspace_filter.should_include_file(file_path) {
-                return false;
+                return Some(RedactionRule::WorkspaceFilter);
             }
         }
 
+        let policy = self.policy_for(&diagnostic.file);
+
         // Check against exclusion patterns with proper validation
-        for pattern in &self.policy.exclude_patterns {
+        for pattern in &policy.exclude_patterns {
             // Validate pattern before using it to prevent regex injection
             if self.is_safe_glob_pattern(pattern) {
                 match glob::Pattern::new(pattern) {
                     Ok(p) => {
                         if p.matches(&diagnostic.file) {
-                            return false;
+                            return Some(RedactionRule::ExcludePattern {
+                                pattern: pattern.clone(),
+                            });
                         }
                     }
                     Err(_) => {
@@ -302,37 +437,44 @@ impl PrivacyFilterTrait for PrivacyFilter {
         }
 
         // Check severity filters
-        if self.policy.include_only_errors && diagnostic.severity != DiagnosticSeverity::Error {
-            return false;
+        if policy.include_only_errors && diagnostic.severity != DiagnosticSeverity::Error {
+            return Some(RedactionRule::ErrorsOnly);
         }
 
-        true
+        None
     }
 
-    fn sanitize_diagnostic(&self, mut diagnostic: Diagnostic) -> Diagnostic {
-        // Sanitize message content
-        if self.policy.sanitize_strings {
-            diagnostic.message = self.sanitize_string_literals(&diagnostic.message);
-        }
+    /// Sanitize a diagnostic, recording the rules that fired when auditing
+    fn sanitize(&self, mut diagnostic: Diagnostic, records: &mut Vec<RedactionRecord>) -> Diagnostic {
+        // Resolve the policy before the file path is anonymized
+        let policy = self.policy_for(&diagnostic.file);
+        let id = diagnostic.id.clone();
 
-        if self.policy.sanitize_comments {
-            diagnostic.message = self.sanitize_comments(&diagnostic.message);
-        }
+        // Sanitize message content
+        let (message, fired) = self.sanitize_text(policy, &diagnostic.message, true);
+        self.note_rewrites(records, &id, "message", &diagnostic.message, fired);
+        diagnostic.message = message;
 
         // Anonymize file paths if requested
-        if self.policy.anonymize_file_paths {
-            diagnostic.file = self.anonymize_file_path(&diagnostic.file);
+        if policy.anonymize_file_paths {
+            let (file, fired) = self.anonymize_path(&diagnostic.file);
+            self.note_rewrites(records, &id, "file", &diagnostic.file, fired);
+            diagnostic.file = file;
         }
 
         // Sanitize related information
         if let Some(related_info) = &mut diagnostic.related_information {
-            for info in related_info.iter_mut() {
-                if self.policy.sanitize_strings {
-                    info.message = self.sanitize_string_literals(&info.message);
-                }
-
-                if self.policy.anonymize_file_paths {
-                    info.location.uri = self.anonymize_file_path(&info.location.uri);
+            for (index, info) in related_info.iter_mut().enumerate() {
+                let (message, fired) = self.sanitize_text(policy, &info.message, false);
+                let field = format!("related_information[{index}].message");
+                self.note_rewrites(records, &id, &field, &info.message, fired);
+                info.message = message;
+
+                if policy.anonymize_file_paths {
+                    let (uri, fired) = self.anonymize_path(&info.location.uri);
+                    let field = format!("related_information[{index}].location.uri");
+                    self.note_rewrites(records, &id, &field, &info.location.uri, fired);
+                    info.location.uri = uri;
                 }
             }
         }
@@ -341,5 +483,198 @@ impl PrivacyFilterTrait for PrivacyFilter {
     }
 }
 
+/// Replace every match of `regex`, collecting the replaced spans in `removed`
+fn replace_all(regex: &Regex, text: &str, replacement: &str, removed: &mut Vec<String>) -> String {
+    removed.extend(regex.find_iter(text).map(|m| m.as_str().to_string()));
+    regex.replace_all(text, replacement).into_owned()
+}
+
+impl PrivacyFilterTrait for PrivacyFilter {
+    fn apply(&self, diagnostics: Vec<Diagnostic>) -> Result<Vec<Diagnostic>> {
+        let received = diagnostics.len();
+        let mut records = Vec::new();
+        let mut filtered: Vec<Diagnostic> = diagnostics
+            .into_iter()
+            .filter(|d| match self.exclusion_rule(d) {
+                Some(rule) => {
+                    self.note(&mut records, || RedactionRecord::excluded(d, rule));
+                    false
+                }
+                None => true,
+            })
+            .collect();
+
+        // Apply per-file limits before sanitizing, while paths still resolve to roots
+        let has_limits = self.policy.max_diagnostics_per_file > 0
+            || self
+                .roots
+                .iter()
+                .any(|r| r.policy.as_ref().is_some_and(|p| p.max_diagnostics_per_file > 0));
+        if has_limits {
+            filtered = self.limit_diagnostics_per_file(filtered, &mut records);
+        }
+
+        let excluded = received - filtered.len();
+        let mut redacted = 0;
+        let sanitized: Vec<Diagnostic> = filtered
+            .into_iter()
+            .map(|d| {
+                let before = records.len();
+                let d = self.sanitize(d, &mut records);
+                if records.len() > before {
+                    redacted += 1;
+                }
+                d
+            })
+            .collect();
+
+        if let Some(audit) = &self.audit {
+            let summary = FilterSummary {
+                received,
+                excluded,
+                redacted,
+                passed: sanitized.len(),
+            };
+            audit
+                .record(&records, summary)
+                .with_context(|| format!("Failed to write privacy audit log {}", audit.log().path().display()))?;
+        }
+        Ok(sanitized)
+    }
+
+    fn get_policy(&self) -> &PrivacyPolicy {
+        &self.policy
+    }
+
+    fn update_policy(&mut self, policy: PrivacyPolicy) {
+        PrivacyFilter::update_policy(self, policy);
+    }
+
+    fn should_include_diagnostic(&self, diagnostic: &Diagnostic) -> bool {
+        self.exclusion_rule(diagnostic).is_none()
+    }
+
+    fn sanitize_diagnostic(&self, diagnostic: Diagnostic) -> Diagnostic {
+        self.sanitize(diagnostic, &mut Vec::new())
+    }
+}
+
 // Add regex to dependencies
 // In Cargo.toml, add: regex = "1.0"
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Position, Range};
+    use tempfile::TempDir;
+
+    fn diagnostic_in(file: &Path) -> Diagnostic {
+        Diagnostic::new(
+            file.to_string_lossy().to_string(),
+            Range {
+                start: Position { line: 0, character: 0 },
+                end: Position { line: 0, character: 1 },
+            },
+            DiagnosticSeverity::Warning,
+            "unused variable \"secret\"".to_string(),
+            "rustc".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_multi_root_filtering_and_policies() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = temp_dir.path().join("backend");
+        let frontend = temp_dir.path().join("frontend");
+        let outside = temp_dir.path().join("scratch");
+        for dir in [&backend, &frontend, &outside] {
+            std::fs::create_dir_all(dir).unwrap();
+            std::fs::write(dir.join("main.rs"), "fn main() {}").unwrap();
+        }
+
+        let roots = crate::project::roots_from_paths(&[backend.clone(), frontend.clone()]);
+        let filter = PrivacyFilter::new(PrivacyPolicy::permissive())
+            .with_workspace_roots(&roots)
+            .with_root_policy("backend", PrivacyPolicy::strict());
+
+        let filtered = filter
+            .apply(vec![
+                diagnostic_in(&backend.join("main.rs")),
+                diagnostic_in(&frontend.join("main.rs")),
+                diagnostic_in(&outside.join("main.rs")),
+            ])
+            .unwrap();
+
+        // Strict policy (errors only) drops the backend warning; scratch is outside all roots
+        assert_eq!(filtered.len(), 1);
+        assert!(filtered[0].file.contains("frontend"));
+        assert!(filtered[0].message.contains("secret"));
+        assert!(filter.policy_for(&backend.join("x.rs").to_string_lossy()).include_only_errors);
+    }
+
+    #[test]
+    fn test_redaction_patterns_follow_policy_updates() {
+        let mut filter = PrivacyFilter::new(PrivacyPolicy::permissive());
+        let mut diagnostic = diagnostic_in(Path::new("/repo/src/main.rs"));
+        diagnostic.message = "invalid key sk-12345 for user".to_string();
+
+        let unchanged = filter.apply(vec![diagnostic.clone()]).unwrap();
+        assert_eq!(unchanged[0].message, "invalid key sk-12345 for user");
+
+        let mut policy = PrivacyPolicy::permissive();
+        policy.redaction_patterns = vec![r"sk-\d+".to_string(), "(unclosed".to_string()];
+        PrivacyFilterTrait::update_policy(&mut filter, policy);
+
+        let redacted = filter.apply(vec![diagnostic]).unwrap();
+        assert_eq!(redacted[0].message, "invalid key [REDACTED] for user");
+    }
+
+    #[test]
+    fn test_audit_records_every_decision_without_plaintext() {
+        use crate::privacy::audit::sha256_hex;
+
+        let temp_dir = TempDir::new().unwrap();
+        let log_path = temp_dir.path().join("privacy-audit.log");
+        let mut policy = PrivacyPolicy::default();
+        policy.redaction_patterns = vec![r"sk-\d+".to_string()];
+        let filter = PrivacyFilter::new(policy).with_audit(RedactionAudit::at(&log_path));
+
+        let mut leaky = diagnostic_in(Path::new("/repo/src/main.rs"));
+        leaky.message = "invalid key sk-12345 in \"prod\"".to_string();
+        let env = diagnostic_in(Path::new("/repo/.env.local"));
+        let exported = filter.apply(vec![leaky.clone(), env.clone()]).unwrap();
+        assert_eq!(exported.len(), 1);
+
+        let records = RedactionAudit::at(&log_path).records().unwrap();
+        let rules: Vec<&RedactionRule> = records.iter().map(|r| &r.rule).collect();
+        assert_eq!(
+            rules,
+            vec![
+                &RedactionRule::ExcludePattern { pattern: "**/.env*".to_string() },
+                &RedactionRule::StringLiterals,
+                &RedactionRule::RedactionPattern { pattern: r"sk-\d+".to_string() },
+            ]
+        );
+        assert_eq!(records[0].diagnostic_id, env.id);
+        assert_eq!(records[0].field, None);
+        assert_eq!(records[2].field.as_deref(), Some("message"));
+        assert_eq!(records[2].original_sha256, sha256_hex(&leaky.message));
+        assert_eq!(records[2].removed_sha256, vec![sha256_hex("sk-12345")]);
+        assert_eq!(records[2].removed_bytes, 8);
+
+        let log = std::fs::read_to_string(&log_path).unwrap();
+        assert!(!log.contains("sk-12345") && !log.contains("prod"));
+        let events = RedactionAudit::at(&log_path).log().read_events().unwrap();
+        let summary: FilterSummary = serde_json::from_value(events[3].details.clone()).unwrap();
+        assert_eq!(events[3].event, "privacy_filter_applied");
+        assert_eq!(
+            summary,
+            FilterSummary {
+                received: 2,
+                excluded: 1,
+                redacted: 1,
+                passed: 1,
+            }
+        );
+    }
+}
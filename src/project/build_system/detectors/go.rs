@@ -1,9 +1,259 @@
 use anyhow::Result;
-use std::path::Path;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
 
 use crate::project::build_system::types::{BuildCommands, BuildConfig, BuildSystem};
 use super::{BuildSystemDetector, utils};
 
+/// Directories never searched for nested `go.mod` files
+const SKIPPED_DIRS: &[&str] = &["vendor", "testdata", "node_modules", "target"];
+
+/// How deep below the root nested modules are looked for without a `go.work`
+const MODULE_SEARCH_DEPTH: usize = 5;
+
+/// A `require` line of a `go.mod`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoRequirement {
+    pub path: String,
+    pub version: String,
+    /// Marked `// indirect`
+    pub indirect: bool,
+}
+
+/// The parts of a `go.mod` used for analysis
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoModFile {
+    /// Module path from the `module` directive
+    pub module: String,
+    pub go_version: Option<String>,
+    pub requires: Vec<GoRequirement>,
+    /// `replace` directives as (module path, replacement path or module)
+    pub replaces: Vec<(String, String)>,
+}
+
+impl GoModFile {
+    pub fn parse(content: &str) -> Self {
+        let mut go_mod = Self::default();
+        for directive in directives(content) {
+            match (directive.verb.as_str(), directive.args.as_slice()) {
+                ("module", [path, ..]) => go_mod.module = path.clone(),
+                ("go", [version, ..]) => go_mod.go_version = Some(version.clone()),
+                ("require", [path, version, ..]) => go_mod.requires.push(GoRequirement {
+                    path: path.clone(),
+                    version: version.clone(),
+                    indirect: directive.comment.as_deref() == Some("indirect"),
+                }),
+                ("replace", args) => {
+                    if let Some(arrow) = args.iter().position(|arg| arg == "=>") {
+                        if let (Some(from), Some(to)) = (args.first(), args.get(arrow + 1)) {
+                            go_mod.replaces.push((from.clone(), to.clone()));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        go_mod
+    }
+}
+
+/// One module of a Go workspace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoModule {
+    /// Module directory relative to the workspace root; empty for the root
+    pub dir: PathBuf,
+    pub go_mod: GoModFile,
+}
+
+impl GoModule {
+    /// Module path, e.g. `example.com/shop/api`
+    pub fn path(&self) -> &str {
+        &self.go_mod.module
+    }
+
+    /// Directory in the form used for command names and `go -C`, `.` for the root
+    pub fn display_dir(&self) -> String {
+        if self.dir.as_os_str().is_empty() {
+            ".".to_string()
+        } else {
+            self.dir.to_string_lossy().replace('\\', "/")
+        }
+    }
+}
+
+/// The modules of a Go repository, from `go.work` or nested `go.mod` files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoWorkspace {
+    pub root: PathBuf,
+    /// Whether the modules come from a `go.work` file
+    pub go_work: bool,
+    pub go_version: Option<String>,
+    /// Sorted by directory
+    pub modules: Vec<GoModule>,
+}
+
+impl GoWorkspace {
+    /// Find the modules under `root`
+    ///
+    /// With a `go.work`, its `use` directives list the modules; otherwise
+    /// every `go.mod` below the root counts, skipping vendored and test data
+    /// directories. `None` when the root has neither.
+    pub fn discover(root: &Path) -> Result<Option<Self>> {
+        let go_work_path = root.join("go.work");
+        let (go_work, go_version, dirs) = if go_work_path.exists() {
+            let content = utils::read_file(&go_work_path)?;
+            let mut go_version = None;
+            let mut dirs = Vec::new();
+            for directive in directives(&content) {
+                match (directive.verb.as_str(), directive.args.first()) {
+                    ("go", Some(version)) => go_version = Some(version.clone()),
+                    ("use", Some(dir)) => dirs.push(clean_relative(dir)),
+                    _ => {}
+                }
+            }
+            (true, go_version, dirs)
+        } else {
+            (false, None, find_module_dirs(root))
+        };
+
+        let mut modules = Vec::new();
+        for dir in dirs {
+            let go_mod_path = root.join(&dir).join("go.mod");
+            match utils::read_file(&go_mod_path) {
+                Ok(content) => modules.push(GoModule {
+                    dir,
+                    go_mod: GoModFile::parse(&content),
+                }),
+                Err(e) => tracing::warn!("Skipping Go module {}: {}", go_mod_path.display(), e),
+            }
+        }
+        if modules.is_empty() && !go_work {
+            return Ok(None);
+        }
+        modules.sort_by(|a, b| a.dir.cmp(&b.dir));
+        modules.dedup_by(|a, b| a.dir == b.dir);
+
+        let go_version = go_version.or_else(|| modules.iter().find_map(|m| m.go_mod.go_version.clone()));
+        Ok(Some(Self {
+            root: root.to_path_buf(),
+            go_work,
+            go_version,
+            modules,
+        }))
+    }
+
+    /// Whether the repository has more than one module or a `go.work`
+    pub fn is_multi_module(&self) -> bool {
+        self.go_work || self.modules.len() > 1
+    }
+
+    /// The module owning a file: the one whose directory is its longest prefix
+    ///
+    /// `file` may be absolute or relative to the workspace root.
+    pub fn module_for(&self, file: &Path) -> Option<&GoModule> {
+        let file = file.strip_prefix(&self.root).unwrap_or(file);
+        self.modules
+            .iter()
+            .filter(|module| file.starts_with(&module.dir))
+            .max_by_key(|module| module.dir.components().count())
+    }
+
+    /// Requirements of `module` that are other modules of this workspace
+    pub fn internal_requires<'a>(&'a self, module: &'a GoModule) -> impl Iterator<Item = &'a GoRequirement> {
+        module
+            .go_mod
+            .requires
+            .iter()
+            .filter(move |req| self.modules.iter().any(|other| other.path() == req.path))
+    }
+
+    /// Direct requirements outside the workspace, deduplicated across modules
+    pub fn external_requires(&self) -> Vec<String> {
+        let local: BTreeSet<&str> = self.modules.iter().map(|module| module.path()).collect();
+        self.modules
+            .iter()
+            .flat_map(|module| &module.go_mod.requires)
+            .filter(|req| !req.indirect && !local.contains(req.path.as_str()))
+            .map(|req| req.path.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+}
+
+/// One directive of a `go.mod` or `go.work`, with block entries flattened
+struct GoDirective {
+    verb: String,
+    args: Vec<String>,
+    /// Trailing `//` comment, e.g. `indirect`
+    comment: Option<String>,
+}
+
+fn directives(content: &str) -> Vec<GoDirective> {
+    let mut result = Vec::new();
+    let mut block: Option<String> = None;
+
+    for line in content.lines() {
+        let (code, comment) = match line.find("//") {
+            Some(index) => (&line[..index], Some(line[index + 2..].trim().to_string())),
+            None => (line, None),
+        };
+        let mut tokens: Vec<String> = code
+            .split_whitespace()
+            .map(|token| token.trim_matches(|c| c == '"' || c == '`').to_string())
+            .collect();
+        if tokens.is_empty() {
+            continue;
+        }
+
+        match &block {
+            Some(_) if tokens[0] == ")" => block = None,
+            Some(verb) => result.push(GoDirective {
+                verb: verb.clone(),
+                args: tokens,
+                comment,
+            }),
+            None if tokens.len() == 2 && tokens[1] == "(" => block = Some(tokens.remove(0)),
+            None => {
+                let verb = tokens.remove(0);
+                result.push(GoDirective {
+                    verb,
+                    args: tokens,
+                    comment,
+                });
+            }
+        }
+    }
+    result
+}
+
+/// `./svc/api` → `svc/api`, `.` → empty
+fn clean_relative(dir: &str) -> PathBuf {
+    Path::new(dir)
+        .components()
+        .filter(|component| !matches!(component, std::path::Component::CurDir))
+        .collect()
+}
+
+fn find_module_dirs(root: &Path) -> Vec<PathBuf> {
+    WalkDir::new(root)
+        .max_depth(MODULE_SEARCH_DEPTH)
+        .into_iter()
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            entry.depth() == 0 || !(name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref()))
+        })
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file() && entry.file_name() == "go.mod")
+        .filter_map(|entry| {
+            let dir = entry.path().parent()?;
+            Some(dir.strip_prefix(root).unwrap_or(dir).to_path_buf())
+        })
+        .collect()
+}
+
 pub struct GoDetector;
 
 impl BuildSystemDetector for GoDetector {
@@ -12,33 +262,70 @@ impl BuildSystemDetector for GoDetector {
     }
 
     fn can_detect(&self, project_root: &Path) -> bool {
-        utils::has_file(project_root, "go.mod")
+        utils::has_file(project_root, "go.mod") || utils::has_file(project_root, "go.work")
     }
 
     fn detect(&self, project_root: &Path) -> Result<BuildConfig> {
-        let go_mod_path = utils::get_file_path(project_root, "go.mod");
-        let content = utils::read_file(&go_mod_path)?;
+        let workspace = GoWorkspace::discover(project_root)?;
+        let modules = workspace.as_ref().map(|ws| ws.modules.as_slice()).unwrap_or_default();
+        let has_root_module = modules.iter().any(|module| module.dir.as_os_str().is_empty());
 
         let mut commands = BuildCommands::default();
-        commands.build = Some("go build ./...".to_string());
-        commands.test = Some("go test ./...".to_string());
-        commands.run = Some("go run .".to_string());
+        match &workspace {
+            // With go.work, relative patterns into any workspace module build from the root
+            Some(ws) if ws.go_work && !modules.is_empty() => {
+                let patterns: Vec<String> = modules
+                    .iter()
+                    .map(|module| match module.display_dir().as_str() {
+                        "." => "./...".to_string(),
+                        dir => format!("./{dir}/..."),
+                    })
+                    .collect();
+                let patterns = patterns.join(" ");
+                commands.build = Some(format!("go build {patterns}"));
+                commands.test = Some(format!("go test {patterns}"));
+                commands.lint = Some(format!("go vet {patterns}"));
+                commands.format = Some(format!("go fmt {patterns}"));
+            }
+            _ if has_root_module => {
+                commands.build = Some("go build ./...".to_string());
+                commands.test = Some("go test ./...".to_string());
+                commands.run = Some("go run .".to_string());
+                commands.format = Some("go fmt ./...".to_string());
+                commands.lint = Some("go vet ./...".to_string());
+            }
+            _ => {}
+        }
         commands.clean = Some("go clean".to_string());
-        commands.format = Some("go fmt ./...".to_string());
-        commands.lint = Some("go vet ./...".to_string());
+
+        // Per-module commands, e.g. `test:services/api`, for scoping to one module
+        if workspace.as_ref().is_some_and(|ws| ws.is_multi_module()) {
+            for module in modules {
+                let dir = module.display_dir();
+                commands.custom.insert(format!("build:{dir}"), format!("go -C {dir} build ./..."));
+                commands.custom.insert(format!("test:{dir}"), format!("go -C {dir} test ./..."));
+                commands.custom.insert(format!("vet:{dir}"), format!("go -C {dir} vet ./..."));
+            }
+        }
 
         // Additional common Go commands
-        commands.custom.insert("mod-download".to_string(), "go mod download".to_string());
-        commands.custom.insert("mod-tidy".to_string(), "go mod tidy".to_string());
-        commands.custom.insert("mod-vendor".to_string(), "go mod vendor".to_string());
-        commands.custom.insert("mod-verify".to_string(), "go mod verify".to_string());
-        commands.custom.insert("test-race".to_string(), "go test -race ./...".to_string());
-        commands.custom.insert("test-cover".to_string(), "go test -cover ./...".to_string());
-        commands.custom.insert("bench".to_string(), "go test -bench=. ./...".to_string());
+        if has_root_module {
+            commands.custom.insert("mod-download".to_string(), "go mod download".to_string());
+            commands.custom.insert("mod-tidy".to_string(), "go mod tidy".to_string());
+            commands.custom.insert("mod-vendor".to_string(), "go mod vendor".to_string());
+            commands.custom.insert("mod-verify".to_string(), "go mod verify".to_string());
+            commands.custom.insert("test-race".to_string(), "go test -race ./...".to_string());
+            commands.custom.insert("test-cover".to_string(), "go test -cover ./...".to_string());
+            commands.custom.insert("bench".to_string(), "go test -bench=. ./...".to_string());
+        }
+
+        // Machine-readable analyzer output that `lspbridge export` can ingest from stdin
+        commands.custom.insert("vet-json".to_string(), "go vet -json ./...".to_string());
+        commands.custom.insert("staticcheck".to_string(), "staticcheck -f json ./...".to_string());
 
         // Check for common Go tools
-        if utils::has_file(project_root, ".golangci.yml") || 
-           utils::has_file(project_root, ".golangci.yaml") || 
+        if utils::has_file(project_root, ".golangci.yml") ||
+           utils::has_file(project_root, ".golangci.yaml") ||
            utils::has_file(project_root, ".golangci.toml") {
             commands.lint = Some("golangci-lint run".to_string());
         }
@@ -54,45 +341,26 @@ impl BuildSystemDetector for GoDetector {
             }
         }
 
-        // Extract module name and dependencies
-        let mut _module_name = String::new();
-        let mut dependencies = vec![];
-
-        for line in content.lines() {
-            let line = line.trim();
-            if line.starts_with("module ") {
-                _module_name = line[7..].trim().to_string();
-            } else if line.starts_with("require ") && line.contains("(") {
-                // Multi-line require block
-                continue;
-            } else if line.starts_with("require ") {
-                // Single line require
-                if let Some(dep) = line[8..].split_whitespace().next() {
-                    dependencies.push(dep.to_string());
-                }
-            } else if !line.starts_with("module") && !line.starts_with("go ") && 
-                      !line.starts_with("require") && !line.starts_with("replace") && 
-                      !line.starts_with("exclude") && !line.starts_with("//") &&
-                      !line.is_empty() && !line.starts_with(")") {
-                // Inside a require block
-                if let Some(dep) = line.split_whitespace().next() {
-                    dependencies.push(dep.to_string());
-                }
-            }
-        }
+        let dependencies = workspace.as_ref().map(|ws| ws.external_requires()).unwrap_or_default();
+
+        let mut config_files: Vec<PathBuf> = modules
+            .iter()
+            .map(|module| project_root.join(&module.dir).join("go.mod"))
+            .collect();
 
-        let mut config_files = vec![go_mod_path];
-        
         if utils::has_file(project_root, "go.sum") {
             config_files.push(utils::get_file_path(project_root, "go.sum"));
         }
-        
+
         if utils::has_file(project_root, "go.work") {
             config_files.push(utils::get_file_path(project_root, "go.work"));
             // For workspaces
             commands.custom.insert("work-sync".to_string(), "go work sync".to_string());
         }
-        
+        if utils::has_file(project_root, "go.work.sum") {
+            config_files.push(utils::get_file_path(project_root, "go.work.sum"));
+        }
+
         if utils::has_file(project_root, ".golangci.yml") {
             config_files.push(utils::get_file_path(project_root, ".golangci.yml"));
         } else if utils::has_file(project_root, ".golangci.yaml") {
@@ -101,6 +369,10 @@ impl BuildSystemDetector for GoDetector {
             config_files.push(utils::get_file_path(project_root, ".golangci.toml"));
         }
 
+        if utils::has_file(project_root, "staticcheck.conf") {
+            config_files.push(utils::get_file_path(project_root, "staticcheck.conf"));
+        }
+
         // Check for tools.go (common pattern for tool dependencies)
         if utils::has_file(project_root, "tools.go") {
             config_files.push(utils::get_file_path(project_root, "tools.go"));
@@ -115,4 +387,70 @@ impl BuildSystemDetector for GoDetector {
             dev_dependencies: vec![], // Go doesn't distinguish dev dependencies in go.mod
         })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_go_mod_parsing() {
+        let go_mod = GoModFile::parse(
+            r#"
+module example.com/shop/api // the API
+
+go 1.22
+
+require example.com/shop/lib v0.0.0
+
+require (
+    github.com/go-chi/chi/v5 v5.0.12
+    golang.org/x/text v0.14.0 // indirect
+)
+
+replace example.com/shop/lib => ../lib
+"#,
+        );
+        assert_eq!(go_mod.module, "example.com/shop/api");
+        assert_eq!(go_mod.go_version.as_deref(), Some("1.22"));
+        assert_eq!(go_mod.requires.len(), 3);
+        assert!(go_mod.requires[2].indirect);
+        assert!(!go_mod.requires[1].indirect);
+        assert_eq!(go_mod.replaces, vec![("example.com/shop/lib".to_string(), "../lib".to_string())]);
+    }
+
+    #[test]
+    fn test_go_work_modules_and_commands() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("go.work"), "go 1.22\n\nuse (\n    ./services/api\n    ./lib\n)\n").unwrap();
+        fs::create_dir_all(root.join("services/api")).unwrap();
+        fs::create_dir_all(root.join("lib")).unwrap();
+        fs::write(
+            root.join("services/api/go.mod"),
+            "module example.com/shop/api\n\ngo 1.22\n\nrequire (\n\texample.com/shop/lib v0.0.0\n\tgithub.com/go-chi/chi/v5 v5.0.12\n)\n",
+        )
+        .unwrap();
+        fs::write(root.join("lib/go.mod"), "module example.com/shop/lib\n\ngo 1.22\n").unwrap();
+
+        let workspace = GoWorkspace::discover(root).unwrap().unwrap();
+        assert!(workspace.go_work && workspace.is_multi_module());
+        let paths: Vec<_> = workspace.modules.iter().map(|m| m.path()).collect();
+        assert_eq!(paths, vec!["example.com/shop/lib", "example.com/shop/api"]);
+
+        let api = workspace.module_for(Path::new("services/api/handlers/user.go")).unwrap();
+        assert_eq!(api.path(), "example.com/shop/api");
+        assert!(workspace.module_for(&root.join("tools/gen.go")).is_none());
+        let internal: Vec<_> = workspace.internal_requires(api).map(|req| req.path.as_str()).collect();
+        assert_eq!(internal, vec!["example.com/shop/lib"]);
+        assert_eq!(workspace.external_requires(), vec!["github.com/go-chi/chi/v5"]);
+
+        let config = GoDetector.detect(root).unwrap();
+        assert_eq!(config.system, BuildSystem::Go);
+        assert_eq!(config.get_command("test"), Some("go test ./lib/... ./services/api/..."));
+        assert_eq!(config.get_command("test:services/api"), Some("go -C services/api test ./..."));
+        assert!(config.supports_feature("workspace"));
+    }
+}
@@ -9,7 +9,7 @@
 //! - **Npm/Yarn/Pnpm** - Node.js projects using package.json
 //! - **Poetry/Pip** - Python projects using pyproject.toml or requirements.txt
 //! - **Maven/Gradle** - Java projects using pom.xml or build.gradle
-//! - **Go** - Go projects using go.mod
+//! - **Go** - Go projects using go.mod, including multi-module repos and go.work workspaces
 //! - **Make** - Projects using Makefile
 //!
 //! ## Key Components
@@ -22,6 +22,7 @@
 pub mod detectors;
 pub mod types;
 
+pub use detectors::go::{GoModFile, GoModule, GoRequirement, GoWorkspace};
 pub use types::*;
 
 use anyhow::Result;
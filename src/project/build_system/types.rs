@@ -102,6 +102,10 @@ impl BuildConfig {
                 .config_files
                 .iter()
                 .any(|f| f.file_name() == Some("package.json".as_ref())),
+            (BuildSystem::Go, "workspace") => self
+                .config_files
+                .iter()
+                .any(|f| f.file_name() == Some("go.work".as_ref())),
             (BuildSystem::Poetry, "virtualenv") => true,
             _ => false,
         }
@@ -1,7 +1,9 @@
 pub mod build_system;
+pub mod multi_root;
 mod structure_analyzer;
 
-pub use build_system::{BuildCommands, BuildConfig, BuildSystem, BuildSystemDetector};
+pub use build_system::{BuildCommands, BuildConfig, BuildSystem, BuildSystemDetector, GoModule, GoWorkspace};
+pub use multi_root::{load_code_workspace, parse_code_workspace, roots_from_paths};
 pub use structure_analyzer::{DirectoryNode, ProjectStructure, StructureAnalyzer};
 
 /// Project type detection based on files and structure
@@ -60,24 +62,39 @@ impl ProjectAnalyzer {
         self.analyze(project_root)
     }
     
+    /// Analyze every root of a multi-root workspace independently
+    ///
+    /// Roots that cannot be analyzed (e.g. missing folders) are skipped with a
+    /// warning so one broken folder does not hide the others. Each root's
+    /// `language` is filled in from its detected main language.
+    pub fn analyze_roots(&self, roots: &[WorkspaceRoot]) -> Vec<RootProjectInfo> {
+        roots
+            .iter()
+            .filter_map(|root| match self.analyze(Path::new(&root.path)) {
+                Ok(info) => {
+                    let mut root = root.clone();
+                    if root.language.is_none() {
+                        root.language = info.structure.get_main_language();
+                    }
+                    Some(RootProjectInfo { root, info })
+                }
+                Err(e) => {
+                    tracing::warn!("Skipping workspace root {}: {}", root.path, e);
+                    None
+                }
+            })
+            .collect()
+    }
+
     /// Detect the primary language of a file
+    ///
+    /// Falls back to the file's shebang or, for Vue, Svelte and MDX files,
+    /// its script blocks when the extension does not name a language.
     pub fn detect_language(&self, file_path: &Path) -> Result<String> {
-        let extension = file_path
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("");
-            
-        let language = match extension {
-            "rs" => "rust",
-            "ts" | "tsx" => "typescript", 
-            "js" | "jsx" => "javascript",
-            "py" => "python",
-            "go" => "go",
-            "java" => "java",
-            "cpp" | "cc" | "cxx" => "cpp",
-            _ => "unknown",
-        };
-        
+        let language = crate::core::detect_file_language(file_path)
+            .map(|language| language.as_str())
+            .unwrap_or("unknown");
+
         Ok(language.to_string())
     }
 }
@@ -88,10 +105,18 @@ impl Default for ProjectAnalyzer {
     }
 }
 
+use crate::core::WorkspaceRoot;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
+/// Project information for one root of a multi-root workspace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RootProjectInfo {
+    pub root: WorkspaceRoot,
+    pub info: ProjectInfo,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectInfo {
     pub build_config: BuildConfig,
@@ -115,6 +140,7 @@ impl ProjectInfo {
                 // TODO: Distinguish between TypeScript and JavaScript
                 ProjectType::TypeScript
             }
+            BuildSystem::Go => ProjectType::Go,
             _ => ProjectType::Unknown,
         };
 
@@ -0,0 +1,201 @@
+//! Multi-root workspace discovery
+//!
+//! Loads the folder list of VS Code style `.code-workspace` files, which are
+//! JSON with comments and trailing commas:
+//!
+//! ```jsonc
+//! {
+//!   "folders": [
+//!     { "path": "backend" },
+//!     { "name": "Web UI", "path": "../frontend" },
+//!   ],
+//! }
+//! ```
+//!
+//! Relative folder paths are resolved against the directory containing the
+//! workspace file, and folders without a name use their directory name.
+
+use crate::core::WorkspaceRoot;
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Deserialize)]
+struct CodeWorkspaceFile {
+    #[serde(default)]
+    folders: Vec<CodeWorkspaceFolder>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CodeWorkspaceFolder {
+    path: Option<String>,
+    uri: Option<String>,
+    name: Option<String>,
+}
+
+/// Load the roots of a `.code-workspace` file
+pub fn load_code_workspace(path: &Path) -> Result<Vec<WorkspaceRoot>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read workspace file {}", path.display()))?;
+    let base = path.parent().unwrap_or_else(|| Path::new("."));
+    parse_code_workspace(&content, base)
+        .with_context(|| format!("Invalid workspace file {}", path.display()))
+}
+
+/// Parse `.code-workspace` content, resolving relative folders against `base`
+pub fn parse_code_workspace(content: &str, base: &Path) -> Result<Vec<WorkspaceRoot>> {
+    let workspace: CodeWorkspaceFile = serde_json::from_str(&strip_jsonc(content))?;
+
+    workspace
+        .folders
+        .into_iter()
+        .map(|folder| {
+            let raw = match (folder.path, folder.uri) {
+                (Some(path), _) => PathBuf::from(path),
+                (None, Some(uri)) => PathBuf::from(
+                    uri.strip_prefix("file://")
+                        .ok_or_else(|| anyhow!("Unsupported folder URI: {uri}"))?,
+                ),
+                (None, None) => return Err(anyhow!("Workspace folder has no path")),
+            };
+            let resolved = if raw.is_absolute() { raw } else { base.join(raw) };
+            Ok(root_from_path(&normalize(&resolved), folder.name))
+        })
+        .collect()
+}
+
+/// Build roots from plain folder paths (e.g. several `--root` arguments)
+pub fn roots_from_paths(paths: &[PathBuf]) -> Vec<WorkspaceRoot> {
+    paths
+        .iter()
+        .map(|path| root_from_path(&normalize(path), None))
+        .collect()
+}
+
+fn root_from_path(path: &Path, name: Option<String>) -> WorkspaceRoot {
+    let name = name.unwrap_or_else(|| {
+        path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string_lossy().to_string())
+    });
+    WorkspaceRoot {
+        name,
+        path: path.to_string_lossy().to_string(),
+        language: None,
+    }
+}
+
+/// Resolve `.` and `..` components without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Remove `//` and `/* */` comments and trailing commas from JSONC
+fn strip_jsonc(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            match c {
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        output.push(escaped);
+                    }
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                output.push(c);
+            }
+            ('/', Some('/')) => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        output.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = '\0';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            (',', _) => {
+                // Drop the comma if the next significant character closes a container
+                let rest: String = chars.clone().collect();
+                let next = rest.trim_start().chars().next();
+                if !matches!(next, Some(']') | Some('}')) {
+                    output.push(c);
+                }
+            }
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_code_workspace_with_comments() {
+        let content = r#"{
+            // Backend services
+            "folders": [
+                { "path": "backend" },
+                { "name": "Web UI", "path": "../frontend" }, /* shared UI */
+                { "path": "/opt/tools" },
+            ],
+            "settings": { "url": "http://example.com" },
+        }"#;
+
+        let roots = parse_code_workspace(content, Path::new("/work/project")).unwrap();
+        assert_eq!(roots.len(), 3);
+        assert_eq!(roots[0].name, "backend");
+        assert_eq!(roots[0].path, "/work/project/backend");
+        assert_eq!(roots[1].name, "Web UI");
+        assert_eq!(roots[1].path, "/work/frontend");
+        assert_eq!(roots[2].path, "/opt/tools");
+    }
+
+    #[test]
+    fn test_root_lookup_is_component_aware() {
+        let roots = roots_from_paths(&[
+            PathBuf::from("/work/api"),
+            PathBuf::from("/work/api-client"),
+            PathBuf::from("/work/api/vendor"),
+        ]);
+
+        let root = crate::core::root_for_path(&roots, "/work/api-client/src/lib.rs").unwrap();
+        assert_eq!(root.name, "api-client");
+        let root = crate::core::root_for_path(&roots, "/work/api/vendor/x.rs").unwrap();
+        assert_eq!(root.name, "vendor");
+        assert!(crate::core::root_for_path(&roots, "/elsewhere/main.rs").is_none());
+    }
+}
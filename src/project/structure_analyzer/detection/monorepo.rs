@@ -27,6 +27,10 @@ impl MonorepoDetector {
             return true;
         }
 
+        if root.join("go.work").exists() {
+            return true;
+        }
+
         // Check for yarn workspaces
         if let Ok(content) = fs::read_to_string(root.join("package.json")) {
             if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
@@ -0,0 +1,395 @@
+//! Ownership-aware authorization for the shared query server
+//!
+//! When LSPbridge serves a whole team, a developer should only see
+//! diagnostics for the code they own. [`OwnershipAuthorizer`] resolves the
+//! caller from the request's API key and intersects query and export results
+//! with CODEOWNERS: a file is visible when one of its owners is the caller or
+//! a team the caller belongs to. Admins bypass the filter.
+//!
+//! Principals and team membership come from a TOML access file:
+//!
+//! ```toml
+//! # Hide files without a CODEOWNERS entry from non-admins
+//! restrict_unowned = true
+//!
+//! [teams]
+//! "@acme/backend" = ["@alice", "@bob"]
+//!
+//! [[principals]]
+//! api_key = "alice-key"
+//! name = "@alice"
+//!
+//! [[principals]]
+//! api_key = "ops-key"
+//! name = "@ops"
+//! role = "admin"
+//! ```
+
+use super::types::ClientInfo;
+use crate::core::{
+    Diagnostic, DiagnosticResult, DiagnosticSeverity, DiagnosticSnapshot, OwnershipMap,
+};
+use crate::history::TrendAnalysis;
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Access level of a caller
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Sees diagnostics for files they (or their teams) own
+    #[default]
+    Developer,
+    /// Sees everything
+    Admin,
+}
+
+/// An authenticated caller
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Principal {
+    /// Owner handle or email as written in CODEOWNERS (e.g. `@alice`)
+    pub name: String,
+    /// Teams the caller belongs to, in addition to the `[teams]` mapping
+    #[serde(default)]
+    pub teams: Vec<String>,
+    #[serde(default)]
+    pub role: Role,
+}
+
+impl Principal {
+    pub fn is_admin(&self) -> bool {
+        self.role == Role::Admin
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PrincipalEntry {
+    api_key: String,
+    #[serde(flatten)]
+    principal: Principal,
+}
+
+/// Contents of an access file
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AccessConfig {
+    /// Hide files without CODEOWNERS owners from non-admins
+    #[serde(default)]
+    pub restrict_unowned: bool,
+    /// Team handle to member handles
+    #[serde(default)]
+    pub teams: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    principals: Vec<PrincipalEntry>,
+}
+
+impl AccessConfig {
+    /// Load an access file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read access file {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Invalid access file {}", path.display()))
+    }
+}
+
+/// Filters query and export results by CODEOWNERS ownership
+#[derive(Debug, Clone)]
+pub struct OwnershipAuthorizer {
+    ownership: OwnershipMap,
+    principals: HashMap<String, Principal>,
+    team_members: HashMap<String, HashSet<String>>,
+    restrict_unowned: bool,
+}
+
+impl OwnershipAuthorizer {
+    /// Create an authorizer with no known principals
+    pub fn new(ownership: OwnershipMap) -> Self {
+        Self {
+            ownership,
+            principals: HashMap::new(),
+            team_members: HashMap::new(),
+            restrict_unowned: false,
+        }
+    }
+
+    /// Create an authorizer from an access file's contents
+    pub fn from_config(ownership: OwnershipMap, config: AccessConfig) -> Self {
+        let mut authorizer = Self::new(ownership).with_restrict_unowned(config.restrict_unowned);
+        for (team, members) in config.teams {
+            authorizer = authorizer.with_team(team, members);
+        }
+        for entry in config.principals {
+            authorizer = authorizer.with_principal(entry.api_key, entry.principal);
+        }
+        authorizer
+    }
+
+    /// Register the principal authenticated by an API key
+    pub fn with_principal(mut self, api_key: impl Into<String>, principal: Principal) -> Self {
+        self.principals.insert(api_key.into(), principal);
+        self
+    }
+
+    /// Register the members of a team
+    pub fn with_team(mut self, team: impl Into<String>, members: Vec<String>) -> Self {
+        self.team_members
+            .entry(team.into())
+            .or_default()
+            .extend(members);
+        self
+    }
+
+    /// Hide files without CODEOWNERS owners from non-admins
+    pub fn with_restrict_unowned(mut self, restrict: bool) -> Self {
+        self.restrict_unowned = restrict;
+        self
+    }
+
+    /// Resolve the caller of a request
+    pub fn authenticate(&self, client: Option<&ClientInfo>) -> Result<Principal> {
+        let api_key = client
+            .and_then(|c| c.api_key.as_deref())
+            .ok_or_else(|| anyhow!("Unauthorized: an API key is required"))?;
+        self.principals
+            .get(api_key)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unauthorized: unknown API key"))
+    }
+
+    /// Handles a file owner must match for the principal to see it
+    fn identities(&self, principal: &Principal) -> HashSet<String> {
+        let mut identities: HashSet<String> = principal.teams.iter().cloned().collect();
+        identities.insert(principal.name.clone());
+        for (team, members) in &self.team_members {
+            if members.contains(&principal.name) {
+                identities.insert(team.clone());
+            }
+        }
+        identities
+    }
+
+    /// Whether the principal may see diagnostics for a file
+    pub fn is_entitled(&self, principal: &Principal, path: &Path) -> bool {
+        if principal.is_admin() {
+            return true;
+        }
+        self.entitled_with(&self.identities(principal), path)
+    }
+
+    fn entitled_with(&self, identities: &HashSet<String>, path: &Path) -> bool {
+        let owners = self.ownership.owners_for(path);
+        if owners.is_empty() {
+            return !self.restrict_unowned;
+        }
+        owners.iter().any(|owner| identities.contains(owner))
+    }
+
+    /// Build a path predicate for a principal
+    pub fn scope<'a>(&'a self, principal: &Principal) -> impl Fn(&Path) -> bool + Send + Sync + 'a {
+        let admin = principal.is_admin();
+        let identities = self.identities(principal);
+        move |path| admin || self.entitled_with(&identities, path)
+    }
+
+    /// Restrict loaded diagnostics to the files a principal may see
+    pub fn filter_diagnostics(&self, principal: &Principal, result: &DiagnosticResult) -> DiagnosticResult {
+        restrict_diagnostics(result, &self.scope(principal))
+    }
+
+    /// Restrict an export snapshot to the files a principal may see
+    pub fn filter_snapshot(&self, principal: &Principal, mut snapshot: DiagnosticSnapshot) -> DiagnosticSnapshot {
+        let scope = self.scope(principal);
+        snapshot
+            .diagnostics
+            .retain(|d| scope(Path::new(&d.file)));
+        snapshot
+    }
+
+    /// Restrict trend analysis to the files a principal may see
+    ///
+    /// Hot spots outside the principal's files are dropped, as are recurring
+    /// issues once none of their affected files remain.
+    pub fn filter_trends(&self, principal: &Principal, mut trends: TrendAnalysis) -> TrendAnalysis {
+        let scope = self.scope(principal);
+        trends.hot_spots.retain(|stats| scope(&stats.file_path));
+        trends.recurring_issues.retain_mut(|pattern| {
+            pattern.affected_files.retain(|file| scope(file));
+            !pattern.affected_files.is_empty()
+        });
+        trends
+    }
+}
+
+/// Copy of `result` containing only files accepted by `allow`, with a recomputed summary
+pub fn restrict_diagnostics(
+    result: &DiagnosticResult,
+    allow: &(dyn Fn(&Path) -> bool + Send + Sync),
+) -> DiagnosticResult {
+    let mut restricted = DiagnosticResult::new();
+    restricted.timestamp = result.timestamp;
+
+    for (path, diagnostics) in &result.diagnostics {
+        if !allow(path) {
+            continue;
+        }
+        restricted.summary.file_count += 1;
+        for diagnostic in diagnostics {
+            count(&mut restricted, diagnostic);
+        }
+        restricted.diagnostics.insert(path.clone(), diagnostics.clone());
+    }
+    restricted.code_lenses = result
+        .code_lenses
+        .iter()
+        .filter(|(path, _)| allow(path))
+        .map(|(path, lenses)| (path.clone(), lenses.clone()))
+        .collect();
+    restricted
+}
+
+fn count(result: &mut DiagnosticResult, diagnostic: &Diagnostic) {
+    let summary = &mut result.summary;
+    summary.total_diagnostics += 1;
+    match diagnostic.severity {
+        DiagnosticSeverity::Error => summary.error_count += 1,
+        DiagnosticSeverity::Warning => summary.warning_count += 1,
+        DiagnosticSeverity::Information => summary.info_count += 1,
+        DiagnosticSeverity::Hint => summary.hint_count += 1,
+    }
+    *summary
+        .source_breakdown
+        .entry(diagnostic.source.clone())
+        .or_insert(0) += 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Position, Range};
+    use std::path::PathBuf;
+
+    fn authorizer() -> OwnershipAuthorizer {
+        let ownership = OwnershipMap::parse(
+            Path::new("/repo"),
+            "/backend/ @acme/backend\n/web/ @carol\n",
+        );
+        let config: AccessConfig = toml::from_str(
+            r#"
+            [teams]
+            "@acme/backend" = ["@alice"]
+
+            [[principals]]
+            api_key = "alice-key"
+            name = "@alice"
+
+            [[principals]]
+            api_key = "ops-key"
+            name = "@ops"
+            role = "admin"
+            "#,
+        )
+        .unwrap();
+        OwnershipAuthorizer::from_config(ownership, config)
+    }
+
+    fn client(api_key: &str) -> ClientInfo {
+        ClientInfo {
+            ip: None,
+            user_agent: None,
+            api_key: Some(api_key.to_string()),
+        }
+    }
+
+    fn diagnostics() -> DiagnosticResult {
+        let mut result = DiagnosticResult::new();
+        for file in ["/repo/backend/db.rs", "/repo/web/app.ts", "/repo/README.md"] {
+            let diagnostic = Diagnostic::new(
+                file.to_string(),
+                Range {
+                    start: Position { line: 0, character: 0 },
+                    end: Position { line: 0, character: 1 },
+                },
+                DiagnosticSeverity::Error,
+                "broken".to_string(),
+                "lsp".to_string(),
+            );
+            result.diagnostics.insert(PathBuf::from(file), vec![diagnostic]);
+        }
+        result
+    }
+
+    #[test]
+    fn test_developer_sees_team_files_only() {
+        let authorizer = authorizer();
+        let alice = authorizer.authenticate(Some(&client("alice-key"))).unwrap();
+
+        let visible = authorizer.filter_diagnostics(&alice, &diagnostics());
+        assert!(visible.diagnostics.contains_key(Path::new("/repo/backend/db.rs")));
+        assert!(!visible.diagnostics.contains_key(Path::new("/repo/web/app.ts")));
+        // Unowned files stay visible unless restricted
+        assert!(visible.diagnostics.contains_key(Path::new("/repo/README.md")));
+        assert_eq!(visible.summary.error_count, 2);
+
+        let strict = authorizer.with_restrict_unowned(true);
+        let visible = strict.filter_diagnostics(&alice, &diagnostics());
+        assert_eq!(visible.diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn test_admin_bypass_and_unknown_keys() {
+        let authorizer = authorizer();
+        let ops = authorizer.authenticate(Some(&client("ops-key"))).unwrap();
+        assert_eq!(authorizer.filter_diagnostics(&ops, &diagnostics()).diagnostics.len(), 3);
+
+        assert!(authorizer.authenticate(Some(&client("stolen"))).is_err());
+        assert!(authorizer.authenticate(None).is_err());
+    }
+
+    #[test]
+    fn test_trends_scoped_to_owned_files() {
+        use crate::history::{FileStats, Pattern, TrendDirection};
+
+        let stats = |file: &str| FileStats {
+            file_path: PathBuf::from(file),
+            error_density: 1.0,
+            warning_density: 0.0,
+            volatility_score: 0.0,
+            recent_trend: TrendDirection::Stable,
+            last_error_count: 1,
+            last_warning_count: 0,
+        };
+        let pattern = |files: &[&str]| Pattern {
+            pattern_id: files.join(","),
+            description: "broken".to_string(),
+            occurrence_rate: 1.0,
+            affected_files: files.iter().map(PathBuf::from).collect(),
+            severity: DiagnosticSeverity::Error,
+            suggested_action: String::new(),
+        };
+        let trends = TrendAnalysis {
+            error_velocity: 0.0,
+            warning_velocity: 0.0,
+            hot_spots: vec![stats("/repo/backend/db.rs"), stats("/repo/web/app.ts")],
+            recurring_issues: vec![
+                pattern(&["/repo/web/app.ts"]),
+                pattern(&["/repo/backend/db.rs", "/repo/web/app.ts"]),
+            ],
+            fix_time_estimates: HashMap::new(),
+            trend_direction: TrendDirection::Stable,
+            health_score: 1.0,
+            taxonomy_breakdown: Vec::new(),
+            annotations: Vec::new(),
+            excluded_buckets: 0,
+        };
+
+        let authorizer = authorizer();
+        let alice = authorizer.authenticate(Some(&client("alice-key"))).unwrap();
+        let visible = authorizer.filter_trends(&alice, trends);
+        assert_eq!(visible.hot_spots.len(), 1);
+        assert_eq!(visible.hot_spots[0].file_path, Path::new("/repo/backend/db.rs"));
+        assert_eq!(visible.recurring_issues.len(), 1);
+        assert_eq!(visible.recurring_issues[0].affected_files, vec![PathBuf::from("/repo/backend/db.rs")]);
+    }
+}
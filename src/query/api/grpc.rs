@@ -0,0 +1,328 @@
+//! gRPC service for the query API
+//!
+//! [`QueryGrpcService`] serves `lspbridge.query.v1.QueryService`, defined in
+//! `proto/lspbridge/query/v1/query.proto`. The proto file ships with the
+//! crate so IDE plugins and other tools can generate clients in their own
+//! language; Rust clients can use [`proto::query_service_client`].
+//!
+//! Queries go through [`QueryApi::handle_request`], so rate limits, API key
+//! authorization and quotas apply exactly as for JSON-RPC callers. The
+//! caller's rate limit state is returned in the response headers (or the
+//! trailers of a failed call), see [`RATE_LIMITED_HEADER`] and friends.
+
+/// Types and service stubs generated from the shipped proto definitions
+pub mod proto {
+    tonic::include_proto!("lspbridge.query.v1");
+}
+
+use super::types::{ClientInfo, QueryRequest, QueryResponse, RateLimitStatus, ResponseFormat};
+use super::QueryApi;
+use crate::query::executor::{QueryResult, Row, Value};
+use anyhow::{anyhow, Result};
+use futures::stream::{self, BoxStream, StreamExt};
+use proto::query_service_server::{QueryService, QueryServiceServer};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tonic::metadata::{MetadataMap, MetadataValue};
+use tonic::transport::server::TcpIncoming;
+use tonic::{Code, Request, Response, Status};
+
+/// Header carrying the API key used for authorization and quotas
+pub const API_KEY_HEADER: &str = "x-api-key";
+/// `"true"` when the request was rejected by a rate limit or quota
+pub const RATE_LIMITED_HEADER: &str = "x-ratelimit-limited";
+/// Requests left in the current rate limit window
+pub const RATE_LIMIT_REMAINING_HEADER: &str = "x-ratelimit-remaining";
+/// Seconds to wait before retrying a rate limited request
+pub const RETRY_AFTER_HEADER: &str = "retry-after";
+
+/// Rows per streamed batch when the request doesn't ask for a size
+pub const DEFAULT_BATCH_SIZE: usize = 500;
+
+/// Query timeout when the request doesn't set one
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// gRPC front end for a [`QueryApi`]
+pub struct QueryGrpcService {
+    api: Arc<QueryApi>,
+}
+
+impl QueryGrpcService {
+    pub fn new(api: Arc<QueryApi>) -> Self {
+        Self { api }
+    }
+
+    /// Wrap the service for a tonic router
+    pub fn into_server(self) -> QueryServiceServer<Self> {
+        QueryServiceServer::new(self)
+    }
+
+    /// Run a query on behalf of the request's caller
+    async fn run(&self, request: Request<proto::QueryRequest>) -> Result<(QueryResult, MetadataMap), Status> {
+        let client_info = client_info(&request);
+        let request = request.into_inner();
+        let timeout = request.timeout_ms.map_or(DEFAULT_TIMEOUT, Duration::from_millis);
+        let request = QueryRequest {
+            query: request.query,
+            format: Some(ResponseFormat::Json),
+            timeout_ms: Some(timeout.as_millis() as u64),
+            client_info: Some(client_info),
+        };
+
+        let response = tokio::time::timeout(timeout, self.api.handle_request(request))
+            .await
+            .map_err(|_| Status::deadline_exceeded(format!("Query timed out after {}ms", timeout.as_millis())))?;
+
+        let mut metadata = MetadataMap::new();
+        if let Some(status) = &response.rate_limit_status {
+            insert_rate_limit_headers(&mut metadata, status);
+        }
+        match response {
+            QueryResponse {
+                success: true,
+                result: Some(result),
+                ..
+            } => Ok((result, metadata)),
+            response => Err(error_status(response, metadata)),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl QueryService for QueryGrpcService {
+    type ExecuteStreamStream = BoxStream<'static, Result<proto::QueryResultBatch, Status>>;
+
+    async fn execute(
+        &self,
+        request: Request<proto::QueryRequest>,
+    ) -> Result<Response<proto::QueryResult>, Status> {
+        let (result, metadata) = self.run(request).await?;
+        let stats = stats(&result);
+        let reply = proto::QueryResult {
+            columns: result.columns,
+            rows: result.rows.into_iter().map(proto::Row::from).collect(),
+            stats: Some(stats),
+        };
+        Ok(Response::from_parts(metadata, reply, Default::default()))
+    }
+
+    async fn execute_stream(
+        &self,
+        request: Request<proto::QueryRequest>,
+    ) -> Result<Response<Self::ExecuteStreamStream>, Status> {
+        let batch_size = match request.get_ref().batch_size {
+            0 => DEFAULT_BATCH_SIZE,
+            size => size as usize,
+        };
+        let (result, metadata) = self.run(request).await?;
+        let batches = stream::iter(batches(result, batch_size).map(Ok)).boxed();
+        Ok(Response::from_parts(metadata, batches, Default::default()))
+    }
+
+    async fn explain(&self, request: Request<proto::ExplainRequest>) -> Result<Response<proto::QueryPlan>, Status> {
+        let plan = self
+            .api
+            .explain(&request.into_inner().query)
+            .await
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        Ok(Response::new(proto::QueryPlan {
+            query: plan.query,
+            estimated_rows: plan.estimated_rows.map(|rows| rows as u64),
+            indexes_used: plan.indexes_used,
+            optimization_hints: plan.optimization_hints,
+            data_source: plan.data_source,
+            engine: plan.engine,
+            estimated_rows_scanned: plan.estimated_rows_scanned.map(|rows| rows as u64),
+            estimated_cost: plan.estimated_cost,
+            uses_cache: plan.uses_cache,
+            optimizations: plan.optimizations,
+            steps: plan
+                .steps
+                .into_iter()
+                .map(|step| proto::QueryPlanStep {
+                    data_source: step.data_source,
+                    engine: step.engine,
+                    estimated_rows: step.estimated_rows.map(|rows| rows as u64),
+                    indexes_used: step.indexes_used,
+                    optimizations: step.optimizations,
+                })
+                .collect(),
+        }))
+    }
+}
+
+/// Bind `addr` and return the future serving `api` on it
+///
+/// Binding happens before this returns, so an address already in use is
+/// reported to the caller rather than from inside a spawned task.
+pub fn serve(api: Arc<QueryApi>, addr: SocketAddr) -> Result<impl Future<Output = Result<()>>> {
+    let incoming = TcpIncoming::new(addr, true, None).map_err(|e| anyhow!("Failed to bind gRPC server to {addr}: {e}"))?;
+    let router = tonic::transport::Server::builder().add_service(QueryGrpcService::new(api).into_server());
+    Ok(async move {
+        router.serve_with_incoming(incoming).await?;
+        Ok(())
+    })
+}
+
+fn client_info<T>(request: &Request<T>) -> ClientInfo {
+    let header = |name: &str| {
+        request
+            .metadata()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from)
+    };
+    ClientInfo {
+        ip: request.remote_addr().map(|addr| addr.ip()),
+        user_agent: header("user-agent"),
+        api_key: header(API_KEY_HEADER),
+    }
+}
+
+fn insert_rate_limit_headers(metadata: &mut MetadataMap, status: &RateLimitStatus) {
+    let limited = if status.limited { "true" } else { "false" };
+    metadata.insert(RATE_LIMITED_HEADER, MetadataValue::from_static(limited));
+    if let Some(remaining) = status.requests_remaining {
+        metadata.insert(RATE_LIMIT_REMAINING_HEADER, MetadataValue::from(remaining));
+    }
+    if let Some(retry_after) = status.retry_after_secs {
+        metadata.insert(RETRY_AFTER_HEADER, MetadataValue::from(retry_after));
+    }
+}
+
+/// Status for a failed query, carrying the rate limit headers as trailers
+fn error_status(response: QueryResponse, metadata: MetadataMap) -> Status {
+    let message = response.error.unwrap_or_else(|| "Query failed".to_string());
+    let code = if response.rate_limit_status.is_some_and(|status| status.limited) {
+        Code::ResourceExhausted
+    } else if message.starts_with("Unauthorized") {
+        Code::Unauthenticated
+    } else {
+        Code::InvalidArgument
+    };
+    Status::with_metadata(code, message, metadata)
+}
+
+fn stats(result: &QueryResult) -> proto::QueryStats {
+    proto::QueryStats {
+        total_count: result.total_count as u64,
+        query_time_ms: result.query_time_ms,
+        data_source: result.metadata.data_source.clone(),
+        filters_applied: result.metadata.filters_applied as u64,
+        rows_scanned: result.metadata.rows_scanned as u64,
+        cache_hit: result.metadata.cache_hit,
+    }
+}
+
+/// Split a result into batches: columns on the first, statistics on the last
+///
+/// An empty result still yields one batch, so clients always see the columns.
+fn batches(result: QueryResult, batch_size: usize) -> impl Iterator<Item = proto::QueryResultBatch> + Send {
+    let mut stats = Some(stats(&result));
+    let mut columns = Some(result.columns);
+    let mut rows = result.rows.into_iter().peekable();
+    std::iter::from_fn(move || {
+        stats.as_ref()?;
+        let batch = rows.by_ref().take(batch_size).map(proto::Row::from).collect();
+        Some(proto::QueryResultBatch {
+            columns: columns.take().unwrap_or_default(),
+            rows: batch,
+            stats: if rows.peek().is_none() { stats.take() } else { None },
+        })
+    })
+}
+
+impl From<Row> for proto::Row {
+    fn from(row: Row) -> Self {
+        Self {
+            values: row.values.into_iter().map(proto::Value::from).collect(),
+        }
+    }
+}
+
+impl From<Value> for proto::Value {
+    fn from(value: Value) -> Self {
+        use proto::value::Kind;
+
+        let kind = match value {
+            Value::String(s) => Kind::StringValue(s),
+            Value::Integer(i) => Kind::IntegerValue(i),
+            Value::Number(n) => Kind::NumberValue(n),
+            Value::Boolean(b) => Kind::BoolValue(b),
+            Value::Path(path) => Kind::PathValue(path.display().to_string()),
+            Value::Severity(severity) => Kind::SeverityValue(format!("{severity:?}").to_lowercase()),
+            Value::Array(values) => Kind::ArrayValue(proto::ValueList {
+                values: values.into_iter().map(proto::Value::from).collect(),
+            }),
+            Value::Null => Kind::NullValue(true),
+        };
+        Self { kind: Some(kind) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Diagnostic, DiagnosticResult, DiagnosticSeverity, Position, Range, RateLimitConfig};
+
+    async fn service(max_requests: u32) -> QueryGrpcService {
+        let api = QueryApi::with_rate_limiting(RateLimitConfig {
+            max_requests,
+            ..RateLimitConfig::default()
+        });
+        let diagnostics = (0..5)
+            .map(|line| {
+                Diagnostic::new(
+                    "/repo/src/lib.rs".to_string(),
+                    Range {
+                        start: Position { line, character: 0 },
+                        end: Position { line, character: 1 },
+                    },
+                    DiagnosticSeverity::Error,
+                    format!("error {line}"),
+                    "rustc".to_string(),
+                )
+            })
+            .collect();
+        api.with_diagnostics(DiagnosticResult::from_diagnostics(diagnostics))
+            .await
+            .unwrap();
+        QueryGrpcService::new(Arc::new(api))
+    }
+
+    fn request(batch_size: u32) -> Request<proto::QueryRequest> {
+        Request::new(proto::QueryRequest {
+            query: "SELECT * FROM diagnostics".to_string(),
+            timeout_ms: None,
+            batch_size,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_streams_rows_in_batches() {
+        let service = service(10).await;
+        let response = service.execute_stream(request(2)).await.unwrap();
+        assert_eq!(response.metadata().get(RATE_LIMITED_HEADER).unwrap(), "false");
+        assert_eq!(response.metadata().get(RATE_LIMIT_REMAINING_HEADER).unwrap(), "9");
+
+        let batches: Vec<_> = response.into_inner().map(Result::unwrap).collect().await;
+        assert_eq!(batches.iter().map(|batch| batch.rows.len()).collect::<Vec<_>>(), vec![2, 2, 1]);
+        assert!(!batches[0].columns.is_empty());
+        assert!(batches[1].columns.is_empty());
+        assert!(batches[..2].iter().all(|batch| batch.stats.is_none()));
+        assert_eq!(batches[2].stats.as_ref().unwrap().total_count, 5);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_requests_are_resource_exhausted() {
+        let service = service(1).await;
+        let response = service.execute(request(0)).await.unwrap();
+        assert_eq!(response.get_ref().rows.len(), 5);
+
+        let status = service.execute(request(0)).await.unwrap_err();
+        assert_eq!(status.code(), Code::ResourceExhausted);
+        assert_eq!(status.metadata().get(RATE_LIMITED_HEADER).unwrap(), "true");
+    }
+}
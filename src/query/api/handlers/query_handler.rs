@@ -1,6 +1,7 @@
-use crate::core::{RateLimiter, RateLimitResult, extract_client_id};
+use crate::core::{RateLimiter, RateLimitResult, UsageAccounting, extract_client_id};
 use crate::query::{QueryExecutor, QueryResult};
-use crate::query::api::types::{QueryRequest, QueryResponse, RateLimitStatus};
+use crate::query::api::authorization::OwnershipAuthorizer;
+use crate::query::api::types::{ClientInfo, QueryRequest, QueryResponse, RateLimitStatus};
 use crate::query::api::validation::QueryValidator;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -10,6 +11,8 @@ pub struct QueryHandler {
     executor: Arc<RwLock<QueryExecutor>>,
     rate_limiter: Arc<RateLimiter>,
     validator: QueryValidator,
+    authorizer: Option<Arc<OwnershipAuthorizer>>,
+    usage: Option<Arc<UsageAccounting>>,
 }
 
 impl QueryHandler {
@@ -18,9 +21,23 @@ impl QueryHandler {
             executor,
             rate_limiter,
             validator: QueryValidator::new(),
+            authorizer: None,
+            usage: None,
         }
     }
 
+    /// Restrict results to the files each caller owns
+    pub fn with_authorizer(mut self, authorizer: Arc<OwnershipAuthorizer>) -> Self {
+        self.authorizer = Some(authorizer);
+        self
+    }
+
+    /// Record per-client usage and enforce monthly quotas
+    pub fn with_usage(mut self, usage: Arc<UsageAccounting>) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
     /// Handle a query request with full rate limiting and error handling
     pub async fn handle_request(&self, request: QueryRequest) -> QueryResponse {
         let start_time = std::time::Instant::now();
@@ -76,11 +93,45 @@ impl QueryHandler {
             };
         }
 
+        // Check monthly quota
+        if let Some(usage) = &self.usage {
+            let rejection = match usage.check(&client_id).await {
+                Ok(exceeded) => exceeded,
+                Err(e) => Some(format!("Quota check failed: {e}")),
+            };
+            if let Some(error) = rejection {
+                return QueryResponse {
+                    success: false,
+                    result: None,
+                    error: Some(error),
+                    query_time_ms: start_time.elapsed().as_millis() as u64,
+                    rate_limit_status: Some(RateLimitStatus {
+                        limited: true,
+                        retry_after_secs: None,
+                        requests_remaining: Some(0),
+                    }),
+                };
+            }
+        }
+
         // Validate and execute the query
-        match self.validate_and_execute(&request.query).await {
+        match self
+            .validate_and_execute(&request.query, request.client_info.as_ref())
+            .await
+        {
             Ok(mut result) => {
                 result.query_time_ms = start_time.elapsed().as_millis() as u64;
 
+                if let Some(usage) = &self.usage {
+                    let bytes_exported = serde_json::to_vec(&result).map(|b| b.len()).unwrap_or(0);
+                    if let Err(e) = usage
+                        .record(&client_id, result.metadata.rows_scanned as u64, bytes_exported as u64)
+                        .await
+                    {
+                        tracing::warn!("Failed to record API usage for {client_id}: {e}");
+                    }
+                }
+
                 QueryResponse {
                     success: true,
                     result: Some(result),
@@ -89,7 +140,7 @@ impl QueryHandler {
                     rate_limit_status: Some(RateLimitStatus {
                         limited: false,
                         retry_after_secs: None,
-                        requests_remaining: None, // Would need to track this for precise counts
+                        requests_remaining: self.rate_limiter.remaining(&client_id).await,
                     }),
                 }
             }
@@ -101,19 +152,36 @@ impl QueryHandler {
                 rate_limit_status: Some(RateLimitStatus {
                     limited: false,
                     retry_after_secs: None,
-                    requests_remaining: None,
+                    requests_remaining: self.rate_limiter.remaining(&client_id).await,
                 }),
             },
         }
     }
 
     /// Validate and execute a query
-    async fn validate_and_execute(&self, query_str: &str) -> anyhow::Result<QueryResult> {
+    async fn validate_and_execute(
+        &self,
+        query_str: &str,
+        client_info: Option<&ClientInfo>,
+    ) -> anyhow::Result<QueryResult> {
+        // Resolve the caller before doing any work
+        let principal = match &self.authorizer {
+            Some(authorizer) => Some(authorizer.authenticate(client_info)?),
+            None => None,
+        };
+
         // Validate query
         let query = self.validator.validate_query(query_str)?;
         
-        // Execute query
-        let mut executor = self.executor.write().await;
-        executor.execute(&query).await
+        // Execute query, scoped to the caller's files unless they are an admin.
+        // Queries share the read lock; only loading new data takes the write lock.
+        let executor = self.executor.read().await;
+        match (&self.authorizer, principal) {
+            (Some(authorizer), Some(principal)) if !principal.is_admin() => {
+                let scope = authorizer.scope(&principal);
+                executor.execute_restricted(&query, &scope).await
+            }
+            _ => executor.execute(&query).await,
+        }
     }
 }
\ No newline at end of file
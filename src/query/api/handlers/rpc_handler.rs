@@ -1,7 +1,39 @@
-use crate::query::api::{QueryApi, types::QueryRequest};
+use crate::core::TriageEngine;
+use crate::quick_fix::{FixOutcomeReport, SuggestFixesRequest};
+use crate::query::api::{QueryApi, types::{ClientInfo, QueryRequest}};
 use anyhow::Result;
+use serde::Deserialize;
+use std::path::PathBuf;
 use std::sync::Arc;
 
+/// Parameters for the `triage.suggest` method
+#[derive(Debug, Default, Deserialize)]
+struct TriageParams {
+    /// Repository root used to locate CODEOWNERS
+    #[serde(default)]
+    project_root: Option<PathBuf>,
+}
+
+/// Parameters for the `fixes.suggest` method
+#[derive(Debug, Deserialize)]
+struct SuggestFixesParams {
+    #[serde(flatten)]
+    request: SuggestFixesRequest,
+    /// Caller, carrying the API key when authorization is enabled
+    #[serde(default)]
+    client_info: Option<ClientInfo>,
+}
+
+/// Parameters for the `fixes.outcome` method
+#[derive(Debug, Deserialize)]
+struct FixOutcomeParams {
+    #[serde(flatten)]
+    report: FixOutcomeReport,
+    /// Caller, carrying the API key when authorization is enabled
+    #[serde(default)]
+    client_info: Option<ClientInfo>,
+}
+
 /// JSON-RPC handler for query API
 pub struct QueryRpcHandler {
     api: Arc<QueryApi>,
@@ -25,9 +57,37 @@ impl QueryRpcHandler {
             }
             "query.explain" => {
                 let query_str: String = serde_json::from_value(params)?;
-                let plan = self.api.explain(&query_str)?;
+                let plan = self.api.explain(&query_str).await?;
                 Ok(serde_json::to_value(plan)?)
             }
+            "triage.suggest" => {
+                let params: TriageParams = if params.is_null() {
+                    TriageParams::default()
+                } else {
+                    serde_json::from_value(params)?
+                };
+                let mut engine = TriageEngine::new();
+                if let Some(root) = &params.project_root {
+                    engine = engine.with_project_root(root);
+                }
+                let suggestions = self.api.triage(&engine).await;
+                Ok(serde_json::to_value(suggestions)?)
+            }
+            "fixes.suggest" => {
+                let params: SuggestFixesParams = serde_json::from_value(params)?;
+                let response = self
+                    .api
+                    .suggest_fixes(params.client_info.as_ref(), &params.request.file, &params.request.range)
+                    .await?;
+                Ok(serde_json::to_value(response)?)
+            }
+            "fixes.outcome" => {
+                let params: FixOutcomeParams = serde_json::from_value(params)?;
+                self.api
+                    .record_fix_outcome(params.client_info.as_ref(), &params.report)
+                    .await?;
+                Ok(serde_json::Value::Null)
+            }
             _ => Err(anyhow::anyhow!("Unknown method: {}", method)),
         }
     }
@@ -0,0 +1,864 @@
+//! HTTP REST server for diagnostics, queries and history
+//!
+//! [`HttpService`] serves the following endpoints, each handler carrying
+//! the `#[utoipa::path]` documentation [`super::openapi`] collects:
+//!
+//! - `GET /diagnostics` - current diagnostics, optionally narrowed by
+//!   `?severity=error` and `?file=<path fragment>`
+//! - `POST /query` and `POST /query/explain` - the query API; a query with
+//!   `"format": "Arrow"` is answered with an Arrow IPC file instead of JSON
+//! - `GET /history/trends?hours=24` - trend analysis of recorded history,
+//!   with hot spots and recurring issues limited to the caller's files
+//! - `POST /quick-fix/suggest` - ranked fixes for the diagnostics at a
+//!   cursor location, backing editor code actions
+//! - `POST /quick-fix/outcome` - whether a suggested fix was accepted,
+//!   modified or rejected, feeding `quick-fix stats` and fix confidence
+//! - `POST /export` - the caller's diagnostics rendered in an export
+//!   format, optionally limited to a region of lines and always passed
+//!   through a privacy filter
+//! - `GET /health` - overall and per-component health
+//! - `GET /openapi.json` - the OpenAPI 3.1 document for these endpoints
+//!
+//! Every route is rate limited per client IP by the query API's
+//! [`RateLimiter`]; `/query` goes through [`QueryApi::handle_request`],
+//! which keys callers presenting an `x-api-key` header by their key
+//! instead. Rejected requests get `429 Too Many Requests` with
+//! `Retry-After`, and every limited response carries
+//! `X-RateLimit-Remaining` when the remaining budget is known.
+
+use super::openapi::{self, HealthResponse};
+use super::types::{ClientInfo, QueryPlan, QueryRequest, QueryResponse, RateLimitStatus, ResponseFormat};
+use super::QueryApi;
+use crate::core::PrivacyFilter as _;
+use crate::core::{
+    extract_client_id, Diagnostic, DiagnosticRegion, DiagnosticResult, DiagnosticSeverity, DiagnosticSnapshot,
+    DiagnosticSummary, ExportConfig, ExportFormat, HealthMonitor, PrivacyLevel, PrivacyPolicy, RateLimitResult,
+    RateLimiter, SystemHealthStatus, WorkspaceInfo,
+};
+use crate::export::ExportService;
+use crate::privacy::PrivacyFilter;
+use crate::history::{AnnotationMode, HistoryManager, TrendOptions};
+use crate::quick_fix::{FixOutcomeReport, FixSuggestionsResponse, SuggestFixesRequest};
+use anyhow::{anyhow, Result};
+use axum::extract::{ConnectInfo, Query, State};
+use axum::http::{HeaderMap, HeaderValue, Request, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use utoipa::ToSchema;
+
+/// Header carrying the API key used for authorization and quotas
+pub const API_KEY_HEADER: &str = "x-api-key";
+/// Requests left in the current rate limit window
+pub const RATE_LIMIT_REMAINING_HEADER: &str = "x-ratelimit-remaining";
+
+/// Response body of `GET /diagnostics`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DiagnosticsResponse {
+    /// Matching diagnostics, ordered by file and position
+    pub diagnostics: Vec<Diagnostic>,
+    /// Counts over the matching diagnostics
+    pub summary: DiagnosticSummary,
+    /// When the diagnostics were captured
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Query parameters of `GET /diagnostics`
+#[derive(Debug, Default, Deserialize)]
+struct DiagnosticsParams {
+    /// Only diagnostics of this severity
+    severity: Option<String>,
+    /// Only diagnostics in files whose path contains this
+    file: Option<String>,
+}
+
+/// Query parameters of `GET /history/trends`
+#[derive(Debug, Deserialize)]
+struct TrendParams {
+    /// Hours of history to analyze
+    #[serde(default = "default_trend_hours")]
+    hours: u64,
+    #[serde(default)]
+    annotations: AnnotationMode,
+}
+
+fn default_trend_hours() -> u64 {
+    24
+}
+
+/// Request body of `POST /export`
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ExportRequest {
+    /// Export format (default JSON)
+    #[serde(default = "default_export_format")]
+    pub format: ExportFormat,
+    /// Export only diagnostics overlapping this region, with their code context
+    #[serde(default)]
+    pub region: Option<DiagnosticRegion>,
+    /// Privacy level applied to the caller's diagnostics before export
+    #[serde(default)]
+    pub privacy: PrivacyLevel,
+    /// Canonical ordering and content-derived ids, for exports that are diffed
+    #[serde(default)]
+    pub stable: bool,
+}
+
+fn default_export_format() -> ExportFormat {
+    ExportFormat::Json
+}
+
+/// Error body of every endpoint except `/query`, which answers with a [`QueryResponse`]
+#[derive(Debug, Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+/// HTTP front end for a [`QueryApi`], its health and recorded history
+pub struct HttpService {
+    api: Arc<QueryApi>,
+    health: Arc<HealthMonitor>,
+    history: Option<Arc<HistoryManager>>,
+    workspace_root: Option<PathBuf>,
+    rate_limiter: Arc<RateLimiter>,
+}
+
+impl HttpService {
+    pub fn new(api: Arc<QueryApi>, health: Arc<HealthMonitor>) -> Self {
+        let rate_limiter = api.rate_limiter();
+        Self {
+            api,
+            health,
+            history: None,
+            workspace_root: None,
+            rate_limiter,
+        }
+    }
+
+    /// Serve `/history/trends` from recorded history
+    pub fn with_history(mut self, history: Arc<HistoryManager>) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Name `root` as the workspace of `/export` documents
+    pub fn with_workspace_root(mut self, root: PathBuf) -> Self {
+        self.workspace_root = Some(root);
+        self
+    }
+
+    pub fn into_router(self) -> Router {
+        let state = Arc::new(self);
+        Router::new()
+            .route("/diagnostics", get(diagnostics))
+            .route("/query/explain", post(explain))
+            .route("/history/trends", get(trends))
+            .route("/quick-fix/suggest", post(suggest_fixes))
+            .route("/quick-fix/outcome", post(fix_outcome))
+            .route("/export", post(export))
+            .route("/health", get(health))
+            .route("/openapi.json", get(openapi_json))
+            .route_layer(middleware::from_fn_with_state(state.clone(), rate_limit))
+            // Rate limited by the query API itself
+            .route("/query", post(query))
+            .with_state(state)
+    }
+}
+
+/// Bind `addr` and return the future serving `service` on it
+///
+/// Binding happens before this returns, so an address already in use is
+/// reported to the caller rather than from inside a spawned task.
+pub fn serve(service: HttpService, addr: SocketAddr) -> Result<impl Future<Output = Result<()>>> {
+    let server = axum::Server::try_bind(&addr).map_err(|e| anyhow!("Failed to bind HTTP server to {addr}: {e}"))?;
+    let app = service.into_router().into_make_service_with_connect_info::<SocketAddr>();
+    Ok(async move {
+        server.serve(app).await?;
+        Ok(())
+    })
+}
+
+async fn rate_limit<B>(
+    State(service): State<Arc<HttpService>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let client_id = extract_client_id(Some(peer.ip()), None, None);
+    let status = match service.rate_limiter.check_request(&client_id).await {
+        Ok(RateLimitResult::Allowed) => RateLimitStatus {
+            limited: false,
+            retry_after_secs: None,
+            requests_remaining: service.rate_limiter.remaining(&client_id).await,
+        },
+        Ok(RateLimitResult::ClientLimitExceeded { retry_after }) => RateLimitStatus {
+            limited: true,
+            retry_after_secs: retry_after.map(|d| d.as_secs().max(1)),
+            requests_remaining: Some(0),
+        },
+        Ok(RateLimitResult::GlobalLimitExceeded) => RateLimitStatus {
+            limited: true,
+            retry_after_secs: None,
+            requests_remaining: None,
+        },
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, format!("Rate limit check failed: {e}")),
+    };
+
+    let mut response = if status.limited {
+        error(StatusCode::TOO_MANY_REQUESTS, "Rate limit exceeded".to_string())
+    } else {
+        next.run(request).await
+    };
+    insert_rate_limit_headers(response.headers_mut(), &status);
+    response
+}
+
+/// List the current diagnostics
+#[utoipa::path(
+    get,
+    path = "/diagnostics",
+    tag = "diagnostics",
+    params(
+        ("severity" = Option<String>, Query, description = "Only this severity: error, warning, information or hint"),
+        ("file" = Option<String>, Query, description = "Only files whose path contains this")
+    ),
+    responses(
+        (status = 200, description = "Matching diagnostics", body = DiagnosticsResponse),
+        (status = 400, description = "Unknown severity"),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 429, description = "Rate limit exceeded")
+    )
+)]
+async fn diagnostics(
+    State(service): State<Arc<HttpService>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Query(params): Query<DiagnosticsParams>,
+) -> Response {
+    let severity = match params.severity.as_deref().map(str::parse::<DiagnosticSeverity>) {
+        Some(Err(e)) => return error(StatusCode::BAD_REQUEST, e),
+        Some(Ok(severity)) => Some(severity),
+        None => None,
+    };
+    let client_info = client_info(peer, &headers, None);
+    let result = match service.api.diagnostics_for(Some(&client_info)).await {
+        Ok(result) => result,
+        Err(e) => return error(StatusCode::UNAUTHORIZED, e.to_string()),
+    };
+
+    let mut diagnostics: Vec<_> = result
+        .diagnostics
+        .into_values()
+        .flatten()
+        .filter(|d| severity.map_or(true, |severity| d.severity == severity))
+        .filter(|d| params.file.as_deref().map_or(true, |file| d.file.contains(file)))
+        .collect();
+    diagnostics.sort_by(|a, b| {
+        (&a.file, a.range.start.line, a.range.start.character).cmp(&(&b.file, b.range.start.line, b.range.start.character))
+    });
+    let summary = DiagnosticResult::from_diagnostics(diagnostics.clone()).summary;
+
+    Json(DiagnosticsResponse {
+        diagnostics,
+        summary,
+        timestamp: result.timestamp,
+    })
+    .into_response()
+}
+
+/// Execute a diagnostic query
+#[utoipa::path(
+    post,
+    path = "/query",
+    tag = "query",
+    request_body = QueryRequest,
+    responses(
+        (status = 200, description = "Query executed; an Arrow IPC file when `format` is `Arrow`", content(
+            (QueryResponse = "application/json"),
+            (Vec<u8> = "application/vnd.apache.arrow.file")
+        )),
+        (status = 400, description = "Invalid query", body = QueryResponse),
+        (status = 401, description = "Missing or unknown API key", body = QueryResponse),
+        (status = 429, description = "Rate limit exceeded", body = QueryResponse)
+    )
+)]
+async fn query(
+    State(service): State<Arc<HttpService>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(mut request): Json<QueryRequest>,
+) -> Response {
+    // The peer address is authoritative; only the API key may come from the body
+    let body_key = request.client_info.take().and_then(|client| client.api_key);
+    request.client_info = Some(client_info(peer, &headers, body_key));
+
+    let format = request.format;
+    let response: QueryResponse = service.api.handle_request(request).await;
+    let status = if response.success {
+        StatusCode::OK
+    } else if response.rate_limit_status.as_ref().is_some_and(|status| status.limited) {
+        StatusCode::TOO_MANY_REQUESTS
+    } else if response.error.as_deref().is_some_and(|e| e.starts_with("Unauthorized")) {
+        StatusCode::UNAUTHORIZED
+    } else {
+        StatusCode::BAD_REQUEST
+    };
+
+    let rate_limit_status = response.rate_limit_status.clone();
+    let mut response = match (format, &response.result) {
+        // Errors stay JSON so clients can always read them
+        (Some(ResponseFormat::Arrow), Some(result)) if response.success => match result.to_arrow_ipc() {
+            Ok(bytes) => (
+                status,
+                [(axum::http::header::CONTENT_TYPE, ResponseFormat::ARROW_CONTENT_TYPE)],
+                bytes,
+            )
+                .into_response(),
+            Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, format!("Arrow conversion failed: {e}")),
+        },
+        _ => (status, Json(response)).into_response(),
+    };
+    if let Some(rate_limit_status) = &rate_limit_status {
+        insert_rate_limit_headers(response.headers_mut(), rate_limit_status);
+    }
+    response
+}
+
+/// Explain how a query would be executed
+#[utoipa::path(
+    post,
+    path = "/query/explain",
+    tag = "query",
+    request_body(content = String, description = "Query string"),
+    responses(
+        (status = 200, description = "Execution plan", body = QueryPlan),
+        (status = 400, description = "Invalid query"),
+        (status = 429, description = "Rate limit exceeded")
+    )
+)]
+async fn explain(State(service): State<Arc<HttpService>>, query: String) -> Response {
+    match service.api.explain(&query).await {
+        Ok(plan) => Json(plan).into_response(),
+        Err(e) => error(StatusCode::BAD_REQUEST, e.to_string()),
+    }
+}
+
+/// Analyze trends in recorded history
+#[utoipa::path(
+    get,
+    path = "/history/trends",
+    tag = "history",
+    params(
+        ("hours" = Option<u64>, Query, description = "Hours of history to analyze (default 24)"),
+        ("annotations" = Option<String>, Query, description = "`mark` or `exclude` annotated windows")
+    ),
+    responses(
+        (status = 200, description = "Velocities, hot spots, recurring issues and health score"),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 429, description = "Rate limit exceeded"),
+        (status = 503, description = "History is not recorded by this server")
+    )
+)]
+async fn trends(
+    State(service): State<Arc<HttpService>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Query(params): Query<TrendParams>,
+) -> Response {
+    let principal = match service.api.authenticate(Some(&client_info(peer, &headers, None))) {
+        Ok(principal) => principal,
+        Err(e) => return error(StatusCode::UNAUTHORIZED, e.to_string()),
+    };
+    let Some(history) = &service.history else {
+        return error(StatusCode::SERVICE_UNAVAILABLE, "History is not recorded by this server".to_string());
+    };
+    let window = Duration::from_secs(params.hours.max(1) * 3600);
+    let options = TrendOptions::default().with_annotations(params.annotations);
+    match history.get_trends(window, &options).await {
+        Ok(trends) => Json(service.api.scope_trends(principal.as_ref(), trends)).into_response(),
+        Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+/// Ranked fixes for the diagnostics at a cursor location
+#[utoipa::path(
+    post,
+    path = "/quick-fix/suggest",
+    tag = "quick-fix",
+    request_body = SuggestFixesRequest,
+    responses(
+        (status = 200, description = "Fixes, best first", body = FixSuggestionsResponse),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 429, description = "Rate limit exceeded")
+    )
+)]
+async fn suggest_fixes(
+    State(service): State<Arc<HttpService>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(request): Json<SuggestFixesRequest>,
+) -> Response {
+    let client_info = client_info(peer, &headers, None);
+    match service
+        .api
+        .suggest_fixes(Some(&client_info), &request.file, &request.range)
+        .await
+    {
+        Ok(response) => Json(response).into_response(),
+        Err(e) => error(StatusCode::UNAUTHORIZED, e.to_string()),
+    }
+}
+
+/// Report whether a suggested fix was accepted, modified or rejected
+#[utoipa::path(
+    post,
+    path = "/quick-fix/outcome",
+    tag = "quick-fix",
+    request_body = FixOutcomeReport,
+    responses(
+        (status = 204, description = "Outcome recorded"),
+        (status = 400, description = "Invalid report or outcomes are not recorded"),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 429, description = "Rate limit exceeded")
+    )
+)]
+async fn fix_outcome(
+    State(service): State<Arc<HttpService>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(report): Json<FixOutcomeReport>,
+) -> Response {
+    let client_info = client_info(peer, &headers, None);
+    match service.api.record_fix_outcome(Some(&client_info), &report).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) if e.to_string().starts_with("Unauthorized") => error(StatusCode::UNAUTHORIZED, e.to_string()),
+        Err(e) => error(StatusCode::BAD_REQUEST, e.to_string()),
+    }
+}
+
+/// Export the caller's diagnostics
+#[utoipa::path(
+    post,
+    path = "/export",
+    tag = "export",
+    request_body = ExportRequest,
+    responses(
+        (status = 200, description = "The export document, in the content type of its format", content(
+            (String = "application/json"),
+            (String = "application/sarif+json"),
+            (String = "text/markdown"),
+            (String = "text/html"),
+            (String = "text/plain")
+        )),
+        (status = 401, description = "Missing or unknown API key"),
+        (status = 429, description = "Rate limit exceeded")
+    )
+)]
+async fn export(
+    State(service): State<Arc<HttpService>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(request): Json<ExportRequest>,
+) -> Response {
+    let client_info = client_info(peer, &headers, None);
+    let result = match service.api.diagnostics_for(Some(&client_info)).await {
+        Ok(result) => result,
+        Err(e) => return error(StatusCode::UNAUTHORIZED, e.to_string()),
+    };
+    let diagnostics = match PrivacyFilter::new(PrivacyPolicy::for_level(&request.privacy))
+        .apply(result.diagnostics.into_values().flatten().collect())
+    {
+        Ok(diagnostics) => diagnostics,
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, format!("Privacy filter failed: {e}")),
+    };
+
+    let root = service.workspace_root.as_deref();
+    let workspace = WorkspaceInfo {
+        name: root
+            .and_then(|root| root.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "workspace".to_string()),
+        root_path: root.map(|root| root.to_string_lossy().to_string()).unwrap_or_default(),
+        language: None,
+        version: None,
+        roots: Vec::new(),
+    };
+    let mut snapshot = DiagnosticSnapshot::new(workspace, diagnostics);
+    snapshot.timestamp = result.timestamp;
+    snapshot.code_lenses = result.code_lenses;
+
+    let content_type = export_content_type(&request.format);
+    let config = ExportConfig {
+        format: request.format,
+        stable: request.stable,
+        region: request.region,
+        ..ExportConfig::default()
+    };
+    match ExportService::new().export(&snapshot, &config) {
+        Ok(document) => ([(axum::http::header::CONTENT_TYPE, content_type)], document).into_response(),
+        Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, format!("Export failed: {e}")),
+    }
+}
+
+fn export_content_type(format: &ExportFormat) -> &'static str {
+    match format {
+        ExportFormat::Json => "application/json",
+        ExportFormat::Sarif => "application/sarif+json",
+        ExportFormat::Markdown | ExportFormat::ClaudeOptimized => "text/markdown",
+        ExportFormat::Html => "text/html",
+        ExportFormat::GithubActions => "text/plain",
+    }
+}
+
+/// Report system health
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses(
+        (status = 200, description = "System is healthy or degraded", body = HealthResponse),
+        (status = 503, description = "System is unhealthy", body = HealthResponse),
+        (status = 429, description = "Rate limit exceeded")
+    )
+)]
+async fn health(State(service): State<Arc<HttpService>>) -> Response {
+    let dashboard = service.health.get_dashboard().await;
+    let status = match dashboard.overall_status {
+        SystemHealthStatus::Unhealthy | SystemHealthStatus::Critical => StatusCode::SERVICE_UNAVAILABLE,
+        _ => StatusCode::OK,
+    };
+    let mut components: Vec<_> = dashboard.components.into_values().collect();
+    components.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let body = HealthResponse {
+        status: dashboard.overall_status,
+        components,
+    };
+    (status, Json(body)).into_response()
+}
+
+/// This document
+#[utoipa::path(
+    get,
+    path = "/openapi.json",
+    tag = "health",
+    responses((status = 200, description = "OpenAPI 3.1 document for this server"))
+)]
+async fn openapi_json() -> Response {
+    Json(openapi::openapi()).into_response()
+}
+
+fn client_info(peer: SocketAddr, headers: &HeaderMap, fallback_key: Option<String>) -> ClientInfo {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok()).map(String::from);
+    ClientInfo {
+        ip: Some(peer.ip()),
+        user_agent: header("user-agent"),
+        api_key: header(API_KEY_HEADER).or(fallback_key),
+    }
+}
+
+fn insert_rate_limit_headers(headers: &mut HeaderMap, status: &RateLimitStatus) {
+    if let Some(remaining) = status.requests_remaining {
+        headers.insert(RATE_LIMIT_REMAINING_HEADER, HeaderValue::from(remaining));
+    }
+    if let Some(retry_after) = status.retry_after_secs {
+        headers.insert(axum::http::header::RETRY_AFTER, HeaderValue::from(retry_after));
+    }
+}
+
+fn error(status: StatusCode, error: String) -> Response {
+    (status, Json(ErrorBody { error })).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{OwnershipAuthorizer, Principal, Role};
+    use crate::core::{OwnershipMap, Position, Range, RateLimitConfig, SimpleEnhancedConfig, SimpleEnhancedProcessor};
+    use crate::quick_fix::AcceptanceStore;
+    use axum::body::Body;
+    use std::path::Path;
+    use tempfile::TempDir;
+    use tower::ServiceExt;
+
+    async fn router(max_requests: u32, cache_dir: &TempDir) -> Router {
+        let api = QueryApi::with_rate_limiting(RateLimitConfig {
+            max_requests,
+            ..RateLimitConfig::default()
+        });
+        let diagnostics = [("/repo/src/lib.rs", DiagnosticSeverity::Error), ("/repo/src/main.rs", DiagnosticSeverity::Warning)]
+            .into_iter()
+            .map(|(file, severity)| {
+                Diagnostic::new(
+                    file.to_string(),
+                    Range {
+                        start: Position { line: 1, character: 0 },
+                        end: Position { line: 1, character: 4 },
+                    },
+                    severity,
+                    "broken".to_string(),
+                    "rustc".to_string(),
+                )
+            })
+            .collect();
+        api.with_diagnostics(DiagnosticResult::from_diagnostics(diagnostics))
+            .await
+            .unwrap();
+
+        HttpService::new(Arc::new(api), monitor(cache_dir).await).into_router()
+    }
+
+    async fn monitor(cache_dir: &TempDir) -> Arc<HealthMonitor> {
+        let processor = SimpleEnhancedProcessor::new(SimpleEnhancedConfig {
+            cache_dir: cache_dir.path().to_path_buf(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        Arc::new(HealthMonitor::new(Arc::new(processor), None).await.unwrap())
+    }
+
+    fn request(method: &str, uri: &str, body: Body) -> Request<Body> {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body)
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 40000))));
+        request
+    }
+
+    async fn json(response: Response) -> serde_json::Value {
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_diagnostics_endpoint_filters() {
+        let cache = TempDir::new().unwrap();
+        let router = router(10, &cache).await;
+
+        let response = router
+            .clone()
+            .oneshot(request("GET", "/diagnostics?severity=error", Body::empty()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[RATE_LIMIT_REMAINING_HEADER], "9");
+        let body = json(response).await;
+        assert_eq!(body["diagnostics"].as_array().unwrap().len(), 1);
+        assert_eq!(body["summary"]["error_count"], 1);
+
+        let response = router
+            .oneshot(request("GET", "/diagnostics?severity=fatal", Body::empty()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_quick_fix_suggest_endpoint() {
+        let cache = TempDir::new().unwrap();
+        let router = router(10, &cache).await;
+
+        let body = serde_json::json!({
+            "file": "/repo/src/lib.rs",
+            "range": { "start": { "line": 1, "character": 2 }, "end": { "line": 1, "character": 2 } }
+        });
+        let response = router
+            .oneshot(request("POST", "/quick-fix/suggest", Body::from(body.to_string())))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json(response).await;
+        assert_eq!(body["file"], "/repo/src/lib.rs");
+        assert!(body["fixes"].is_array());
+    }
+
+    #[tokio::test]
+    async fn test_quick_fix_outcome_endpoint() {
+        let cache = TempDir::new().unwrap();
+        let body = serde_json::json!({ "fingerprint": "rust:E0308", "outcome": "accepted", "file": "src/lib.rs" });
+
+        // Without an outcome store there is nowhere to record it
+        let response = router(10, &cache)
+            .await
+            .oneshot(request("POST", "/quick-fix/outcome", Body::from(body.to_string())))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let api = QueryApi::new()
+            .with_fix_outcomes(AcceptanceStore::in_memory().unwrap())
+            .await
+            .unwrap();
+        let router = HttpService::new(Arc::new(api), monitor(&cache).await).into_router();
+        let response = router
+            .oneshot(request("POST", "/quick-fix/outcome", Body::from(body.to_string())))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn test_export_endpoint_applies_region_privacy_and_ownership() {
+        let cache = TempDir::new().unwrap();
+        let router = router(10, &cache).await;
+        let export = |body: serde_json::Value| request("POST", "/export", Body::from(body.to_string()));
+        let exported = |body: serde_json::Value| body["diagnostics"].as_array().unwrap().len();
+
+        let response = router
+            .clone()
+            .oneshot(export(serde_json::json!({ "privacy": "minimal" })))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "application/json");
+        assert_eq!(exported(json(response).await), 2);
+
+        let region = serde_json::json!({ "file": "/repo/src/main.rs", "start_line": 1, "end_line": 5 });
+        let response = router
+            .clone()
+            .oneshot(export(serde_json::json!({ "privacy": "minimal", "region": region })))
+            .await
+            .unwrap();
+        let body = json(response).await;
+        assert_eq!(exported(body.clone()), 1);
+        assert_eq!(body["diagnostics"][0]["file"], "/repo/src/main.rs");
+
+        // Strict privacy exports errors only
+        let response = router
+            .clone()
+            .oneshot(export(serde_json::json!({ "privacy": "strict" })))
+            .await
+            .unwrap();
+        assert_eq!(exported(json(response).await), 1);
+
+        let response = router
+            .oneshot(export(serde_json::json!({ "format": "Sarif" })))
+            .await
+            .unwrap();
+        assert_eq!(response.headers()["content-type"], "application/sarif+json");
+
+        // With authorization, callers export only the files they own
+        let ownership = OwnershipMap::parse(Path::new("/repo"), "/src/lib.rs @alice\n/src/main.rs @bob\n");
+        let authorizer = OwnershipAuthorizer::new(ownership).with_principal(
+            "alice-key",
+            Principal {
+                name: "@alice".to_string(),
+                teams: vec![],
+                role: Role::Developer,
+            },
+        );
+        let api = QueryApi::new().with_authorization(authorizer);
+        let diagnostics = ["/repo/src/lib.rs", "/repo/src/main.rs"]
+            .into_iter()
+            .map(|file| {
+                Diagnostic::new(
+                    file.to_string(),
+                    Range {
+                        start: Position { line: 1, character: 0 },
+                        end: Position { line: 1, character: 4 },
+                    },
+                    DiagnosticSeverity::Error,
+                    "broken".to_string(),
+                    "rustc".to_string(),
+                )
+            })
+            .collect();
+        api.with_diagnostics(DiagnosticResult::from_diagnostics(diagnostics))
+            .await
+            .unwrap();
+        let router = HttpService::new(Arc::new(api), monitor(&cache).await).into_router();
+
+        let response = router
+            .clone()
+            .oneshot(export(serde_json::json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let mut request = export(serde_json::json!({ "privacy": "minimal" }));
+        request.headers_mut().insert(API_KEY_HEADER, HeaderValue::from_static("alice-key"));
+        let body = json(router.oneshot(request).await.unwrap()).await;
+        assert_eq!(exported(body.clone()), 1);
+        assert_eq!(body["diagnostics"][0]["file"], "/repo/src/lib.rs");
+    }
+
+    #[tokio::test]
+    async fn test_documented_paths_are_served() {
+        let cache = TempDir::new().unwrap();
+        let router = router(100, &cache).await;
+
+        for (path, item) in openapi::openapi().paths.paths {
+            let method = if item.get.is_some() { "GET" } else { "POST" };
+            let response = router.clone().oneshot(request(method, &path, Body::empty())).await.unwrap();
+            assert_ne!(response.status(), StatusCode::NOT_FOUND, "{method} {path} is not routed");
+            assert_ne!(response.status(), StatusCode::METHOD_NOT_ALLOWED, "{method} {path} is not routed");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_per_client_ip() {
+        let cache = TempDir::new().unwrap();
+        let router = router(1, &cache).await;
+
+        let response = router.clone().oneshot(request("GET", "/health", Body::empty())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(json(response).await["status"], "Healthy");
+
+        let response = router.clone().oneshot(request("GET", "/health", Body::empty())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(axum::http::header::RETRY_AFTER));
+
+        // History isn't configured, but the limit applies first
+        let response = router.oneshot(request("GET", "/history/trends", Body::empty())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn test_query_endpoint() {
+        let cache = TempDir::new().unwrap();
+        let router = router(10, &cache).await;
+
+        let body = serde_json::json!({ "query": "SELECT * FROM diagnostics WHERE severity = warning" });
+        let response = router
+            .clone()
+            .oneshot(request("POST", "/query", Body::from(body.to_string())))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json(response).await;
+        assert_eq!(body["success"], true);
+        assert_eq!(body["result"]["rows"].as_array().unwrap().len(), 1);
+
+        let body = serde_json::json!({ "query": "SELECT * FROM diagnostics", "format": "Arrow" });
+        let response = router
+            .clone()
+            .oneshot(request("POST", "/query", Body::from(body.to_string())))
+            .await
+            .unwrap();
+        assert_eq!(response.headers()["content-type"], ResponseFormat::ARROW_CONTENT_TYPE);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let reader = arrow_ipc::reader::FileReader::try_new(std::io::Cursor::new(body.to_vec()), None).unwrap();
+        let batches: Vec<_> = reader.collect::<Result<_, _>>().unwrap();
+        assert_eq!(batches[0].num_rows(), 2);
+
+        let body = serde_json::json!({ "query": "SELECT FROM" });
+        let response = router
+            .oneshot(request("POST", "/query", Body::from(body.to_string())))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
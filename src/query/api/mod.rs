@@ -1,16 +1,32 @@
 pub mod types;
+pub mod authorization;
+pub mod grpc;
 pub mod handlers;
+pub mod http;
+pub mod openapi;
 pub mod validation;
 pub mod router;
 
 pub use types::{
     QueryRequest, QueryResponse, ClientInfo, ResponseFormat, 
-    RateLimitStatus, QueryPlan
+    RateLimitStatus, QueryPlan, QueryPlanStep
 };
+pub use authorization::{AccessConfig, OwnershipAuthorizer, Principal, Role};
+pub use grpc::QueryGrpcService;
 pub use handlers::{QueryRpcHandler, QuerySubscription};
+pub use http::HttpService;
 
-use crate::core::{DiagnosticResult, RateLimiter, RateLimitConfig};
-use crate::history::HistoryStorage;
+use crate::core::{
+    CalendarConfig, DiagnosticResult, RateLimiter, RateLimitConfig, Range, SymbolIndex, TriageEngine, TriageSuggestion,
+    UsageAccounting,
+};
+use crate::core::config::EnvironmentSnapshot;
+use crate::history::{HistoryStorage, TrendAnalysis};
+use crate::multi_repo::monorepo::BazelTargetMap;
+use crate::quick_fix::{
+    AcceptanceStore, FixConfidenceScorer, FixOutcomeReport, FixSuggestionService, FixSuggestionsResponse,
+};
+use crate::query::executor::Value;
 use crate::query::{QueryParser, QueryExecutor, Query, QueryResult};
 use anyhow::Result;
 use std::sync::Arc;
@@ -60,7 +76,11 @@ pub struct QueryApi {
     executor: Arc<RwLock<QueryExecutor>>,
     rate_limiter: Arc<RateLimiter>,
     handler: handlers::QueryHandler,
+    usage: Option<Arc<UsageAccounting>>,
     router: router::QueryRouter,
+    fixes: Arc<FixSuggestionService>,
+    fix_outcomes: Option<Arc<AcceptanceStore>>,
+    authorizer: Option<Arc<OwnershipAuthorizer>>,
 }
 
 impl Default for QueryApi {
@@ -91,7 +111,11 @@ impl QueryApi {
             executor: executor.clone(),
             rate_limiter: rate_limiter.clone(),
             handler: handlers::QueryHandler::new(executor.clone(), rate_limiter.clone()),
+            usage: None,
             router: router::QueryRouter::new(executor.clone()),
+            fixes: Arc::new(FixSuggestionService::new()),
+            fix_outcomes: None,
+            authorizer: None,
         }
     }
 
@@ -124,10 +148,62 @@ impl QueryApi {
             executor: executor.clone(),
             rate_limiter: rate_limiter.clone(),
             handler: handlers::QueryHandler::new(executor.clone(), rate_limiter.clone()),
+            usage: None,
             router: router::QueryRouter::new(executor.clone()),
+            fixes: Arc::new(FixSuggestionService::new()),
+            fix_outcomes: None,
+            authorizer: None,
         }
     }
 
+    /// Restrict query results to the files each caller owns.
+    /// 
+    /// Requests must then carry an API key known to the authorizer; callers
+    /// with the admin role see every file.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `authorizer` - Ownership map and principals used to scope results
+    pub fn with_authorization(mut self, authorizer: OwnershipAuthorizer) -> Self {
+        let authorizer = Arc::new(authorizer);
+        self.handler = self.handler.with_authorizer(authorizer.clone());
+        self.authorizer = Some(authorizer);
+        self
+    }
+
+    /// Account per-client usage and enforce monthly quotas.
+    /// 
+    /// Every successful request adds to the caller's query count, rows
+    /// scanned and bytes returned for the current month; requests from
+    /// clients that have exhausted a quota are rejected.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `usage` - Usage store and the quotas to enforce
+    pub fn with_usage_accounting(mut self, usage: UsageAccounting) -> Self {
+        let usage = Arc::new(usage);
+        self.handler = self.handler.with_usage(usage.clone());
+        self.usage = Some(usage);
+        self
+    }
+
+    /// Record what editors do with suggested fixes and rank fixes by it.
+    /// 
+    /// Outcomes reported through [`QueryApi::record_fix_outcome`] are stored
+    /// in `store`, and the outcomes already stored calibrate the confidence
+    /// of fix suggestions.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `store` - Fix outcome database
+    pub async fn with_fix_outcomes(mut self, store: AcceptanceStore) -> Result<Self> {
+        let stats = store.stats(None).await?;
+        let scorer = FixConfidenceScorer::new().with_acceptance(&stats);
+        self.fixes = Arc::new(FixSuggestionService::new().with_scorer(scorer));
+        self.fix_outcomes = Some(Arc::new(store));
+        Ok(self)
+    }
+
     /// Load diagnostic data for querying.
     /// 
     /// Provides the query executor with diagnostic data to search through.
@@ -150,6 +226,7 @@ impl QueryApi {
     pub async fn with_diagnostics(&self, diagnostics: DiagnosticResult) -> Result<()> {
         let mut executor = self.executor.write().await;
         executor.with_diagnostics(diagnostics);
+        self.fixes.clear();
         Ok(())
     }
 
@@ -167,6 +244,73 @@ impl QueryApi {
         Ok(())
     }
 
+    /// Query history through a storage handle shared with its owner.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `history` - Storage the caller keeps using, e.g. the daemon's connection
+    pub async fn with_shared_history(&self, history: Arc<HistoryStorage>) -> Result<()> {
+        let mut executor = self.executor.write().await;
+        executor.with_shared_history(history);
+        Ok(())
+    }
+
+    /// Replace the diagnostics with a snapshot shared with the caller.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `diagnostics` - Latest diagnostics, kept without copying
+    pub async fn with_shared_diagnostics(&self, diagnostics: Arc<DiagnosticResult>) -> Result<()> {
+        let mut executor = self.executor.write().await;
+        executor.with_shared_diagnostics(diagnostics);
+        self.fixes.clear();
+        Ok(())
+    }
+
+    /// Map diagnostics to Bazel targets, enabling the `target` field.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `targets` - Target map loaded from the workspace's BUILD files
+    pub async fn with_bazel_targets(&self, targets: BazelTargetMap) -> Result<()> {
+        let mut executor = self.executor.write().await;
+        executor.with_bazel_targets(targets);
+        Ok(())
+    }
+
+    /// Resolve relative time ranges on a calendar in the configured time zone.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `calendar` - Time zone and week start, usually from `[calendar]` in `lspbridge.toml`
+    pub async fn with_calendar(&self, calendar: CalendarConfig) -> Result<()> {
+        let mut executor = self.executor.write().await;
+        executor.with_calendar(calendar);
+        Ok(())
+    }
+
+    /// Expose the effective configuration and environment as the `config` source.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `environment` - Snapshot taken with [`EnvironmentSnapshot::capture`]
+    pub async fn with_environment(&self, environment: EnvironmentSnapshot) -> Result<()> {
+        let mut executor = self.executor.write().await;
+        executor.with_environment(environment);
+        Ok(())
+    }
+
+    /// Answer `symbols` queries from a workspace symbol index.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `index` - Usually synced through [`SymbolStore::sync`](crate::core::SymbolStore::sync)
+    pub async fn with_symbol_index(&self, index: Arc<SymbolIndex>) -> Result<()> {
+        let mut executor = self.executor.write().await;
+        executor.with_symbol_index(index);
+        Ok(())
+    }
+
     /// Execute a query string directly and return the raw result.
     /// 
     /// This is a lower-level method that bypasses rate limiting and formatting.
@@ -195,6 +339,31 @@ impl QueryApi {
         self.router.execute(query_str).await
     }
 
+    /// Execute a query with `?` placeholders bound to `params` in order.
+    ///
+    /// Use this instead of formatting user-provided file paths or messages
+    /// into the query string: each parameter is bound as a single literal
+    /// after tokenizing, so it cannot change the structure of the query.
+    /// An array binds a list of literals for `IN (?)`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lspbridge::query::api::QueryApi;
+    /// use lspbridge::query::executor::Value;
+    ///
+    /// let api = QueryApi::new();
+    /// let result = api
+    ///     .execute_with_params(
+    ///         "SELECT * FROM diagnostics WHERE file = ? AND message CONTAINS_TEXT ?",
+    ///         &[Value::String(path), Value::String(search)],
+    ///     )
+    ///     .await?;
+    /// ```
+    pub async fn execute_with_params(&self, query: &str, params: &[Value]) -> Result<QueryResult> {
+        self.router.execute_with_params(query, params).await
+    }
+
     /// Execute a query request with full rate limiting and error handling.
     /// 
     /// This is the recommended method for production use. It provides:
@@ -252,8 +421,152 @@ impl QueryApi {
     }
 
     /// Get query execution plan (for debugging/optimization)
-    pub fn explain(&self, query_str: &str) -> Result<QueryPlan> {
-        self.router.explain(query_str)
+    pub async fn explain(&self, query_str: &str) -> Result<QueryPlan> {
+        self.router.explain(query_str).await
+    }
+
+    /// The loaded diagnostics a caller may see.
+    /// 
+    /// With authorization enabled the caller must present a known API key,
+    /// and only diagnostics for files they own are returned. Returns an empty
+    /// result when no diagnostics have been loaded.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `client_info` - Caller of the request, carrying its API key
+    pub async fn diagnostics_for(&self, client_info: Option<&ClientInfo>) -> Result<DiagnosticResult> {
+        let principal = match &self.authorizer {
+            Some(authorizer) => Some(authorizer.authenticate(client_info)?),
+            None => None,
+        };
+        let executor = self.executor.read().await;
+        let Some(result) = executor.diagnostics() else {
+            return Ok(DiagnosticResult::new());
+        };
+        Ok(match (&self.authorizer, principal) {
+            (Some(authorizer), Some(principal)) => authorizer.filter_diagnostics(&principal, result),
+            _ => result.clone(),
+        })
+    }
+
+    /// Resolve the caller of a request, or `None` when authorization is disabled
+    /// 
+    /// # Arguments
+    /// 
+    /// * `client_info` - Caller of the request, carrying its API key
+    pub fn authenticate(&self, client_info: Option<&ClientInfo>) -> Result<Option<Principal>> {
+        self.authorizer
+            .as_ref()
+            .map(|authorizer| authorizer.authenticate(client_info))
+            .transpose()
+    }
+
+    /// Restrict trend analysis to the files a caller may see
+    /// 
+    /// # Arguments
+    /// 
+    /// * `principal` - Caller resolved by [`QueryApi::authenticate`]
+    /// * `trends` - Trends over all recorded history
+    pub fn scope_trends(&self, principal: Option<&Principal>, trends: TrendAnalysis) -> TrendAnalysis {
+        match (&self.authorizer, principal) {
+            (Some(authorizer), Some(principal)) => authorizer.filter_trends(principal, trends),
+            _ => trends,
+        }
+    }
+
+    /// Produce triage suggestions for the loaded diagnostics
+    ///
+    /// Returns an empty list when no diagnostics have been loaded.
+    pub async fn triage(&self, engine: &TriageEngine) -> Vec<TriageSuggestion> {
+        let diagnostics: Vec<_> = {
+            let executor = self.executor.read().await;
+            match executor.diagnostics() {
+                Some(result) => result.diagnostics.values().flatten().cloned().collect(),
+                None => return Vec::new(),
+            }
+        };
+        engine.triage(&diagnostics).await
+    }
+
+    /// Ranked fix suggestions for the diagnostics at a cursor location.
+    /// 
+    /// Backs editor lightbulb actions: only diagnostics in `file` that overlap
+    /// `range` are considered, and suggestions are cached per diagnostic so
+    /// repeated requests return in well under 100ms. With authorization
+    /// enabled the caller must present an API key and only gets fixes for
+    /// files they may see.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `client_info` - Caller of the request, carrying its API key
+    /// * `file` - File the cursor is in, absolute or relative to the workspace
+    /// * `range` - Cursor position or selection
+    pub async fn suggest_fixes(
+        &self,
+        client_info: Option<&ClientInfo>,
+        file: &str,
+        range: &Range,
+    ) -> Result<FixSuggestionsResponse> {
+        let principal = match &self.authorizer {
+            Some(authorizer) => Some(authorizer.authenticate(client_info)?),
+            None => None,
+        };
+        let executor = self.executor.read().await;
+        let diagnostics: Vec<_> = executor
+            .diagnostics()
+            .map(|result| {
+                result
+                    .diagnostics
+                    .values()
+                    .flatten()
+                    .filter(|d| match (&self.authorizer, &principal) {
+                        (Some(authorizer), Some(principal)) => {
+                            authorizer.is_entitled(principal, std::path::Path::new(&d.file))
+                        }
+                        _ => true,
+                    })
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(self.fixes.suggest_fixes(&diagnostics, file, range))
+    }
+
+    /// Record whether a suggested fix was accepted, modified or rejected.
+    /// 
+    /// Requires fix outcome tracking, enabled with [`QueryApi::with_fix_outcomes`].
+    /// With authorization enabled the caller must present a known API key.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `client_info` - Caller of the request, carrying its API key
+    /// * `report` - Outcome reported by the editor, keyed by the fix's fingerprint
+    pub async fn record_fix_outcome(&self, client_info: Option<&ClientInfo>, report: &FixOutcomeReport) -> Result<()> {
+        if let Some(authorizer) = &self.authorizer {
+            authorizer.authenticate(client_info)?;
+        }
+        let store = self
+            .fix_outcomes
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Fix outcome tracking is not enabled"))?;
+        store.record(report).await
+    }
+
+    /// Precompute fix suggestions for every loaded diagnostic
+    ///
+    /// Called after new diagnostics are loaded so the first lightbulb
+    /// request for each is already cached.
+    pub async fn warm_fix_cache(&self) {
+        let diagnostics: Vec<_> = match self.executor.read().await.diagnostics() {
+            Some(result) => result.diagnostics.values().flatten().cloned().collect(),
+            None => return,
+        };
+        self.fixes.warm(&diagnostics);
+    }
+
+    /// Rate limiter applied to query requests, for front ends limiting other routes
+    pub fn rate_limiter(&self) -> Arc<RateLimiter> {
+        self.rate_limiter.clone()
     }
 
     /// Get rate limiting statistics
@@ -261,6 +574,11 @@ impl QueryApi {
         self.rate_limiter.get_stats().await
     }
 
+    /// Usage accounting, when enabled with [`QueryApi::with_usage_accounting`]
+    pub fn usage(&self) -> Option<&UsageAccounting> {
+        self.usage.as_deref()
+    }
+
     /// Reset rate limiting state (useful for testing)
     pub async fn reset_rate_limits(&self) {
         self.rate_limiter.reset().await;
@@ -299,6 +617,107 @@ mod tests {
         assert!(response.success || response.error.is_some());
     }
 
+    #[tokio::test]
+    async fn test_authorized_queries_are_scoped_to_owned_files() {
+        use crate::core::{Diagnostic, DiagnosticSeverity, OwnershipMap, Position, Range};
+        use std::path::{Path, PathBuf};
+
+        let ownership = OwnershipMap::parse(Path::new("/repo"), "/api/ @alice\n/ui/ @bob\n");
+        let authorizer = OwnershipAuthorizer::new(ownership).with_principal(
+            "alice-key",
+            Principal {
+                name: "@alice".to_string(),
+                teams: vec![],
+                role: Role::Developer,
+            },
+        );
+        let api = QueryApi::new().with_authorization(authorizer);
+
+        let mut diagnostics = DiagnosticResult::new();
+        for file in ["/repo/api/lib.rs", "/repo/ui/app.ts"] {
+            let diagnostic = Diagnostic::new(
+                file.to_string(),
+                Range {
+                    start: Position { line: 0, character: 0 },
+                    end: Position { line: 0, character: 1 },
+                },
+                DiagnosticSeverity::Error,
+                "broken".to_string(),
+                "lsp".to_string(),
+            );
+            diagnostics.diagnostics.insert(PathBuf::from(file), vec![diagnostic]);
+        }
+        api.with_diagnostics(diagnostics).await.unwrap();
+
+        let request = |api_key: Option<&str>| QueryRequest {
+            query: "SELECT * FROM diagnostics".to_string(),
+            format: Some(ResponseFormat::Json),
+            timeout_ms: None,
+            client_info: Some(ClientInfo {
+                ip: None,
+                user_agent: None,
+                api_key: api_key.map(String::from),
+            }),
+        };
+
+        let response = api.handle_request(request(Some("alice-key"))).await;
+        assert!(response.success, "{:?}", response.error);
+        assert_eq!(response.result.unwrap().rows.len(), 1);
+
+        let response = api.handle_request(request(None)).await;
+        assert!(!response.success);
+        assert!(response.error.unwrap().contains("Unauthorized"));
+    }
+
+    #[tokio::test]
+    async fn test_usage_quota_rejects_after_limit() {
+        use crate::core::{QuotaConfig, QuotaLimits, UsageStore};
+
+        let quotas = QuotaConfig {
+            default: QuotaLimits {
+                max_queries: Some(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let api = QueryApi::new()
+            .with_usage_accounting(UsageAccounting::new(UsageStore::in_memory().unwrap(), quotas));
+        api.with_diagnostics(DiagnosticResult::new()).await.unwrap();
+
+        let request = QueryRequest {
+            query: "SELECT COUNT(*) FROM diagnostics".to_string(),
+            format: Some(ResponseFormat::Json),
+            timeout_ms: None,
+            client_info: Some(ClientInfo {
+                ip: None,
+                user_agent: None,
+                api_key: Some("team-a".to_string()),
+            }),
+        };
+
+        let response = api.handle_request(request.clone()).await;
+        assert!(response.success, "{:?}", response.error);
+
+        let response = api.handle_request(request).await;
+        assert!(!response.success);
+        assert!(response.error.unwrap().contains("quota"));
+
+        let usage = api.usage().unwrap();
+        let record = usage
+            .store()
+            .usage("api:team-a", &crate::core::usage::current_month())
+            .await
+            .unwrap();
+        assert_eq!(record.queries, 1);
+        assert!(record.bytes_exported > 0);
+    }
+
+    #[tokio::test]
+    async fn test_triage_without_diagnostics() {
+        let api = QueryApi::new();
+        assert!(api.triage(&TriageEngine::new()).await.is_empty());
+    }
+
     #[tokio::test]
     #[ignore] // TODO: Fix QueryExecutor setup for rate limiting test - needs database/data setup
     async fn test_rate_limiting() {
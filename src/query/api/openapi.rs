@@ -0,0 +1,126 @@
+//! OpenAPI 3.1 specification for the HTTP API
+//!
+//! The specification is generated from the Rust request/response types with
+//! `utoipa`, and its paths from the `#[utoipa::path]` attributes on the
+//! handlers [`super::http`] routes, so it cannot drift from what the server
+//! actually serves. It is served at `/openapi.json` and printed by
+//! `serve --openapi`; teams can feed it to any OpenAPI generator to obtain
+//! typed TypeScript or Python clients.
+
+use super::http::{self, DiagnosticsResponse, ExportRequest};
+use super::types::{ClientInfo, QueryPlan, QueryPlanStep, QueryRequest, QueryResponse, RateLimitStatus, ResponseFormat};
+use crate::core::health_dashboard::{ComponentHealth, SystemHealthStatus};
+use crate::core::{Diagnostic, DiagnosticRegion, DiagnosticSummary, ExportFormat, PrivacyLevel};
+use crate::query::executor::{QueryResult, Row};
+use serde::{Deserialize, Serialize};
+use utoipa::{OpenApi, ToSchema};
+
+/// Response body of the health endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HealthResponse {
+    /// Overall system status
+    pub status: SystemHealthStatus,
+    /// Per-component health
+    pub components: Vec<ComponentHealth>,
+}
+
+/// OpenAPI document for the LSPbridge HTTP API
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "LSPbridge API",
+        description = "Diagnostics, query, history, export, health and quick-fix endpoints for IDE diagnostics"
+    ),
+    paths(
+        http::diagnostics,
+        http::query,
+        http::explain,
+        http::trends,
+        http::suggest_fixes,
+        http::fix_outcome,
+        http::export,
+        http::health,
+        http::openapi_json
+    ),
+    components(schemas(
+        QueryRequest,
+        QueryResponse,
+        ClientInfo,
+        ResponseFormat,
+        RateLimitStatus,
+        QueryPlan,
+        QueryPlanStep,
+        QueryResult,
+        Row,
+        Diagnostic,
+        DiagnosticSummary,
+        DiagnosticsResponse,
+        ExportRequest,
+        ExportFormat,
+        DiagnosticRegion,
+        PrivacyLevel,
+        HealthResponse
+    )),
+    tags(
+        (name = "diagnostics", description = "Current diagnostics"),
+        (name = "query", description = "SQL-like diagnostic queries"),
+        (name = "history", description = "Recorded diagnostic history"),
+        (name = "health", description = "System health"),
+        (name = "quick-fix", description = "Automated fixes"),
+        (name = "export", description = "Diagnostic exports")
+    )
+)]
+pub struct ApiDoc;
+
+/// Build the OpenAPI document
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+/// Render the OpenAPI document as pretty-printed JSON
+pub fn to_json() -> serde_json::Result<String> {
+    openapi().to_pretty_json()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_is_openapi_3_1() {
+        let spec: serde_json::Value = serde_json::from_str(&to_json().unwrap()).unwrap();
+        assert!(spec["openapi"].as_str().unwrap().starts_with("3.1"));
+        assert_eq!(spec["info"]["title"], "LSPbridge API");
+    }
+
+    #[test]
+    fn test_spec_covers_endpoints_and_types() {
+        let spec = openapi();
+        for path in [
+            "/diagnostics",
+            "/history/trends",
+            "/query",
+            "/query/explain",
+            "/health",
+            "/quick-fix/suggest",
+            "/quick-fix/outcome",
+            "/export",
+            "/openapi.json",
+        ] {
+            assert!(spec.paths.paths.contains_key(path), "missing path {path}");
+        }
+
+        let schemas = &spec.components.as_ref().unwrap().schemas;
+        for name in [
+            "QueryRequest",
+            "QueryResponse",
+            "Value",
+            "DiagnosticSeverity",
+            "FixSuggestionsResponse",
+            "ExportRequest",
+            "DiagnosticRegion",
+        ] {
+            assert!(schemas.contains_key(name), "missing schema {name}");
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::query::{Query, QueryExecutor, QueryResult};
-use crate::query::api::types::QueryPlan;
+use crate::query::api::types::{QueryPlan, QueryPlanStep};
+use crate::query::executor::{ExecutionPlan, Value};
 use crate::query::api::validation::QueryValidator;
 use anyhow::Result;
 use std::sync::Arc;
@@ -25,9 +26,16 @@ impl QueryRouter {
         self.execute_query(query).await
     }
 
+    /// Execute a query string with its `?` placeholders bound to `params`
+    pub async fn execute_with_params(&self, query_str: &str, params: &[Value]) -> Result<QueryResult> {
+        let query = self.validator.validate_query_with_params(query_str, params)?;
+        self.execute_query(query).await
+    }
+
     /// Execute a pre-parsed query
     pub async fn execute_query(&self, query: Query) -> Result<QueryResult> {
-        let mut executor = self.executor.write().await;
+        // Queries share the read lock and run concurrently
+        let executor = self.executor.read().await;
         executor.execute(&query).await
     }
 
@@ -49,14 +57,45 @@ impl QueryRouter {
     }
 
     /// Get query execution plan (for debugging/optimization)
-    pub fn explain(&self, query_str: &str) -> Result<QueryPlan> {
+    pub async fn explain(&self, query_str: &str) -> Result<QueryPlan> {
         let query = self.validator.validate_query(query_str)?;
+        let plan = self.executor.read().await.plan(&query);
+
+        let mut steps = Vec::new();
+        collect_steps(&plan.inputs, &mut steps);
+        let mut indexes_used = plan.indexes_used;
+        for index in steps.iter().flat_map(|step| &step.indexes_used) {
+            if !indexes_used.contains(index) {
+                indexes_used.push(index.clone());
+            }
+        }
 
         Ok(QueryPlan {
             query: format!("{query:?}"),
-            estimated_rows: None,
-            indexes_used: vec![],
+            data_source: plan.source,
+            engine: plan.engine,
+            estimated_rows: plan.estimated_rows,
+            estimated_rows_scanned: plan.estimated_rows_scanned,
+            estimated_cost: plan.estimated_cost,
+            uses_cache: plan.uses_cache,
+            indexes_used,
+            optimizations: plan.optimizations,
             optimization_hints: self.validator.get_optimization_hints(&query),
+            steps,
         })
     }
+}
+
+/// Flatten plan inputs depth-first, so each step follows the steps it reads
+fn collect_steps(inputs: &[ExecutionPlan], steps: &mut Vec<QueryPlanStep>) {
+    for input in inputs {
+        collect_steps(&input.inputs, steps);
+        steps.push(QueryPlanStep {
+            data_source: input.source.clone(),
+            engine: input.engine.clone(),
+            estimated_rows: input.estimated_rows,
+            indexes_used: input.indexes_used.clone(),
+            optimizations: input.optimizations.clone(),
+        });
+    }
 }
\ No newline at end of file
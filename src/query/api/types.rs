@@ -1,6 +1,7 @@
 use crate::query::QueryResult;
 use serde::{Deserialize, Serialize};
 use std::net::IpAddr;
+use utoipa::ToSchema;
 
 /// Request structure for executing diagnostic queries.
 /// 
@@ -19,7 +20,7 @@ use std::net::IpAddr;
 ///     client_info: None,
 /// };
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct QueryRequest {
     /// The diagnostic query string to execute
     pub query: String,
@@ -35,9 +36,10 @@ pub struct QueryRequest {
 /// 
 /// Used to identify clients for rate limiting purposes and provide
 /// contextual information for query processing.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ClientInfo {
     /// Client IP address for rate limiting
+    #[schema(value_type = Option<String>)]
     pub ip: Option<IpAddr>,
     /// User agent string for client identification
     pub user_agent: Option<String>,
@@ -52,7 +54,8 @@ pub struct ClientInfo {
 /// - `Csv` - Spreadsheet-compatible tabular data
 /// - `Table` - Human-readable console table format
 /// - `Markdown` - Documentation-friendly markup format
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// - `Arrow` - Arrow IPC (Feather) for Polars/pandas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 pub enum ResponseFormat {
     /// JSON format for programmatic processing
     Json,
@@ -62,6 +65,13 @@ pub enum ResponseFormat {
     Table,
     /// Markdown format for documentation
     Markdown,
+    /// Arrow IPC file format (Feather v2) for dataframe libraries
+    Arrow,
+}
+
+impl ResponseFormat {
+    /// Media type of an Arrow IPC file
+    pub const ARROW_CONTENT_TYPE: &'static str = "application/vnd.apache.arrow.file";
 }
 
 /// Response structure containing query results and metadata.
@@ -81,7 +91,7 @@ pub enum ResponseFormat {
 ///     eprintln!("Query failed: {}", error);
 /// }
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct QueryResponse {
     /// Whether the query executed successfully
     pub success: bool,
@@ -99,7 +109,7 @@ pub struct QueryResponse {
 /// 
 /// Provides clients with information about current rate limiting
 /// state and guidance for future requests.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RateLimitStatus {
     /// Whether this request was rate limited
     pub limited: bool,
@@ -110,10 +120,40 @@ pub struct RateLimitStatus {
 }
 
 /// Query execution plan for debugging/optimization
-#[derive(Debug, Serialize, Deserialize)]
+///
+/// Built by [`QueryExecutor::plan`](crate::query::executor::QueryExecutor::plan)
+/// without running the query.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct QueryPlan {
     pub query: String,
+    /// Data source named in FROM
+    pub data_source: String,
+    /// Engine that produces the final rows
+    pub engine: String,
+    /// Estimated result rows; absent when the source has no statistics yet
     pub estimated_rows: Option<usize>,
+    /// Estimated rows read by the engine
+    pub estimated_rows_scanned: Option<usize>,
+    /// Relative cost of the query, as used for performance warnings
+    pub estimated_cost: u32,
+    /// Whether the result cache already holds the answer
+    pub uses_cache: bool,
+    /// Indexes used by any step of the plan
     pub indexes_used: Vec<String>,
+    /// Optimizations the executor applies to the final step
+    pub optimizations: Vec<String>,
+    /// Suggestions for making the query cheaper
     pub optimization_hints: Vec<String>,
+    /// Subqueries and join sides, in the order they run
+    pub steps: Vec<QueryPlanStep>,
+}
+
+/// A step that runs before the final one in a [`QueryPlan`]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct QueryPlanStep {
+    pub data_source: String,
+    pub engine: String,
+    pub estimated_rows: Option<usize>,
+    pub indexes_used: Vec<String>,
+    pub optimizations: Vec<String>,
 }
\ No newline at end of file
@@ -1,3 +1,4 @@
+use crate::query::executor::Value;
 use crate::query::{Query, QueryParser};
 use anyhow::{anyhow, Result};
 
@@ -21,14 +22,7 @@ impl QueryValidator {
 
     /// Validate a query string without executing it
     pub fn validate_query(&self, query_str: &str) -> Result<Query> {
-        // Basic input validation
-        if query_str.is_empty() {
-            return Err(anyhow!("Query string cannot be empty"));
-        }
-
-        if query_str.len() > 10_000 {
-            return Err(anyhow!("Query string too long (max 10KB)"));
-        }
+        self.validate_input(query_str)?;
 
         // Parse and validate query
         let query = self.parser.parse(query_str)?;
@@ -39,6 +33,27 @@ impl QueryValidator {
         Ok(query)
     }
 
+    /// Validate a query string with `?` placeholders bound to `params`
+    pub fn validate_query_with_params(&self, query_str: &str, params: &[Value]) -> Result<Query> {
+        self.validate_input(query_str)?;
+        let query = self.parser.parse_with_params(query_str, params)?;
+        self.validate_semantics(&query)?;
+        Ok(query)
+    }
+
+    /// Basic input validation
+    fn validate_input(&self, query_str: &str) -> Result<()> {
+        if query_str.is_empty() {
+            return Err(anyhow!("Query string cannot be empty"));
+        }
+
+        if query_str.len() > 10_000 {
+            return Err(anyhow!("Query string too long (max 10KB)"));
+        }
+
+        Ok(())
+    }
+
     /// Validate query semantics
     fn validate_semantics(&self, query: &Query) -> Result<()> {
         // Check for conflicting filters
@@ -139,4 +154,17 @@ mod tests {
         let result = validator.validate_query("SELECT * FROM diagnostics WHERE severity = error");
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_parameterized_query_validation() {
+        let validator = QueryValidator::new();
+        let query = "SELECT * FROM diagnostics WHERE file = ?";
+        assert!(validator.validate_query(query).is_err());
+        assert!(validator
+            .validate_query_with_params(query, &[Value::String("src/lib.rs".to_string())])
+            .is_ok());
+
+        let result = validator.validate_query_with_params(query, &[]);
+        assert!(result.unwrap_err().to_string().contains("placeholder"));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,191 @@
+//! Arrow IPC conversion for query results
+//!
+//! Converts a [`QueryResult`] into an Arrow `RecordBatch` and serializes it
+//! in the Arrow IPC file format (a.k.a. Feather v2). The output can be loaded
+//! directly by Polars (`pl.read_ipc`) or pandas (`pd.read_feather`) with
+//! proper column types, avoiding CSV parsing and type guessing.
+//!
+//! Column types are inferred from the values present in each column:
+//!
+//! - All integers → `Int64`
+//! - Integers mixed with floats → `Float64`
+//! - All booleans → `Boolean`
+//! - Anything else (strings, paths, severities, arrays, mixed) → `Utf8`
+//!
+//! `Value::Null` always maps to an Arrow null, and a column that contains only
+//! nulls is emitted as a nullable `Utf8` column.
+
+use super::types::{QueryResult, Value};
+use anyhow::{Context, Result};
+use arrow_array::{
+    ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, RecordBatchOptions, StringArray,
+};
+use arrow_ipc::writer::FileWriter;
+use arrow_schema::{DataType, Field, Schema};
+use std::sync::Arc;
+
+/// Infer the Arrow data type for a column from its values
+fn infer_column_type<'a>(values: impl Iterator<Item = &'a Value>) -> DataType {
+    let mut saw_int = false;
+    let mut saw_float = false;
+    let mut saw_bool = false;
+    let mut saw_other = false;
+
+    for value in values {
+        match value {
+            Value::Integer(_) => saw_int = true,
+            Value::Number(_) => saw_float = true,
+            Value::Boolean(_) => saw_bool = true,
+            Value::Null => {}
+            _ => saw_other = true,
+        }
+    }
+
+    if saw_other || (saw_bool && (saw_int || saw_float)) {
+        DataType::Utf8
+    } else if saw_float {
+        DataType::Float64
+    } else if saw_int {
+        DataType::Int64
+    } else if saw_bool {
+        DataType::Boolean
+    } else {
+        DataType::Utf8
+    }
+}
+
+/// Render a value as text for `Utf8` columns
+fn value_as_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::Severity(severity) => Some(severity.to_string().to_lowercase()),
+        Value::Array(_) => serde_json::to_string(value).ok(),
+        other => Some(other.to_string()),
+    }
+}
+
+/// Build a single Arrow column from the values at `index` in every row
+fn build_column(result: &QueryResult, index: usize, data_type: &DataType) -> ArrayRef {
+    let cells = result.rows.iter().map(|row| row.get(index).unwrap_or(&Value::Null));
+
+    match data_type {
+        DataType::Int64 => Arc::new(Int64Array::from_iter(cells.map(|v| match v {
+            Value::Integer(i) => Some(*i),
+            _ => None,
+        }))),
+        DataType::Float64 => Arc::new(Float64Array::from_iter(cells.map(Value::as_number))),
+        DataType::Boolean => Arc::new(BooleanArray::from_iter(cells.map(|v| match v {
+            Value::Boolean(b) => Some(*b),
+            _ => None,
+        }))),
+        _ => Arc::new(StringArray::from_iter(cells.map(value_as_text))),
+    }
+}
+
+/// Convert a query result into an Arrow record batch
+///
+/// The schema mirrors `result.columns`; every field is nullable since any
+/// query may produce `NULL` cells.
+pub fn to_record_batch(result: &QueryResult) -> Result<RecordBatch> {
+    let mut fields = Vec::with_capacity(result.columns.len());
+    let mut columns = Vec::with_capacity(result.columns.len());
+
+    for (index, name) in result.columns.iter().enumerate() {
+        let data_type =
+            infer_column_type(result.rows.iter().filter_map(|row| row.get(index)));
+        columns.push(build_column(result, index, &data_type));
+        fields.push(Field::new(name, data_type, true));
+    }
+
+    // An explicit row count keeps zero-column results (e.g. empty queries) valid
+    let options = RecordBatchOptions::new().with_row_count(Some(result.rows.len()));
+    let schema = Arc::new(Schema::new(fields));
+    RecordBatch::try_new_with_options(schema, columns, &options)
+        .context("Failed to build Arrow record batch")
+}
+
+/// Serialize a query result to the Arrow IPC file format (Feather v2)
+pub fn to_ipc_bytes(result: &QueryResult) -> Result<Vec<u8>> {
+    let batch = to_record_batch(result)?;
+    let mut buffer = Vec::new();
+    {
+        let mut writer = FileWriter::try_new(&mut buffer, &batch.schema())
+            .context("Failed to create Arrow IPC writer")?;
+        writer.write(&batch).context("Failed to write Arrow record batch")?;
+        writer.finish().context("Failed to finalize Arrow IPC file")?;
+    }
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::DiagnosticSeverity;
+    use crate::query::executor::types::Row;
+    use arrow_array::Array;
+    use arrow_ipc::reader::FileReader;
+    use std::io::Cursor;
+
+    fn sample_result() -> QueryResult {
+        let mut result = QueryResult::empty("files");
+        result.columns = vec![
+            "file".to_string(),
+            "errors".to_string(),
+            "ratio".to_string(),
+            "severity".to_string(),
+        ];
+        result.rows = vec![
+            Row::new(vec![
+                Value::String("src/main.rs".to_string()),
+                Value::Integer(3),
+                Value::Number(0.5),
+                Value::Severity(DiagnosticSeverity::Error),
+            ]),
+            Row::new(vec![
+                Value::String("src/lib.rs".to_string()),
+                Value::Null,
+                Value::Integer(1),
+                Value::Severity(DiagnosticSeverity::Warning),
+            ]),
+        ];
+        result.total_count = 2;
+        result
+    }
+
+    #[test]
+    fn test_column_types_are_inferred() {
+        let batch = to_record_batch(&sample_result()).unwrap();
+        let schema = batch.schema();
+
+        assert_eq!(schema.field(0).data_type(), &DataType::Utf8);
+        assert_eq!(schema.field(1).data_type(), &DataType::Int64);
+        assert_eq!(schema.field(2).data_type(), &DataType::Float64);
+        assert_eq!(schema.field(3).data_type(), &DataType::Utf8);
+        assert_eq!(batch.num_rows(), 2);
+        assert!(batch.column(1).is_null(1));
+    }
+
+    #[test]
+    fn test_ipc_round_trip() {
+        let bytes = to_ipc_bytes(&sample_result()).unwrap();
+        assert!(bytes.starts_with(b"ARROW1"));
+
+        let reader = FileReader::try_new(Cursor::new(bytes), None).unwrap();
+        let batches: Vec<_> = reader.collect::<std::result::Result<_, _>>().unwrap();
+        assert_eq!(batches.len(), 1);
+
+        let severities = batches[0]
+            .column(3)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(severities.value(0), "error");
+    }
+
+    #[test]
+    fn test_empty_result_produces_valid_file() {
+        let bytes = to_ipc_bytes(&QueryResult::empty("diagnostics")).unwrap();
+        let reader = FileReader::try_new(Cursor::new(bytes), None).unwrap();
+        assert_eq!(reader.schema().fields().len(), 0);
+    }
+}
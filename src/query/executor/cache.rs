@@ -5,6 +5,7 @@
 
 use super::types::QueryResult;
 use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
 use std::time::Instant;
 
 /// Cached query result with timestamp
@@ -15,54 +16,89 @@ pub struct CachedResult {
 }
 
 /// Query result cache with TTL (Time To Live) support
+///
+/// All methods take `&self`; entries and settings sit behind a mutex so one
+/// cache can be shared by queries running concurrently. The lock is only held
+/// for map lookups and inserts, never while a query executes.
 pub struct QueryCache {
-    cache: HashMap<String, CachedResult>,
-    default_ttl_secs: u64,
+    state: Mutex<CacheState>,
+}
+
+struct CacheState {
+    entries: HashMap<String, CachedResult>,
+    ttl_secs: u64,
     max_entries: usize,
 }
 
+impl CacheState {
+    /// Remove expired entries from cache
+    fn cleanup_expired(&mut self) {
+        let ttl_secs = self.ttl_secs;
+        self.entries
+            .retain(|_, cached| cached.cached_at.elapsed().as_secs() < ttl_secs);
+    }
+
+    /// Evict the oldest entry when cache is full
+    fn evict_oldest(&mut self) {
+        if let Some(oldest_key) = self
+            .entries
+            .iter()
+            .min_by_key(|(_, cached)| cached.cached_at)
+            .map(|(key, _)| key.clone())
+        {
+            self.entries.remove(&oldest_key);
+        }
+    }
+}
+
 impl QueryCache {
     /// Create a new query cache
     pub fn new() -> Self {
-        Self {
-            cache: HashMap::new(),
-            default_ttl_secs: 300, // 5 minutes default
-            max_entries: 1000,
-        }
+        Self::with_settings(300, 1000) // 5 minutes default
     }
 
     /// Create a cache with custom settings
     pub fn with_settings(ttl_secs: u64, max_entries: usize) -> Self {
         Self {
-            cache: HashMap::new(),
-            default_ttl_secs: ttl_secs,
-            max_entries,
+            state: Mutex::new(CacheState {
+                entries: HashMap::new(),
+                ttl_secs,
+                max_entries,
+            }),
         }
     }
 
+    fn state(&self) -> MutexGuard<'_, CacheState> {
+        // A panic while holding the lock leaves the map consistent, so keep using it
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
     /// Get a cached result if it exists and hasn't expired
-    pub fn get(&mut self, key: &str) -> Option<QueryResult> {
-        self.cleanup_expired();
-        
-        if let Some(cached) = self.cache.get(key) {
-            if cached.cached_at.elapsed().as_secs() < self.default_ttl_secs {
-                let mut result = cached.result.clone();
-                result.metadata.cache_hit = true;
-                return Some(result);
-            } else {
-                // Remove expired entry
-                self.cache.remove(key);
-            }
-        }
-        
-        None
+    pub fn get(&self, key: &str) -> Option<QueryResult> {
+        let mut state = self.state();
+        state.cleanup_expired();
+
+        state.entries.get(key).map(|cached| {
+            let mut result = cached.result.clone();
+            result.metadata.cache_hit = true;
+            result
+        })
+    }
+
+    /// Whether an unexpired result is cached under `key`
+    pub fn contains(&self, key: &str) -> bool {
+        let mut state = self.state();
+        state.cleanup_expired();
+        state.entries.contains_key(key)
     }
 
     /// Store a result in the cache
-    pub fn insert(&mut self, key: String, result: QueryResult) {
+    pub fn insert(&self, key: String, result: QueryResult) {
+        let mut state = self.state();
+
         // Ensure we don't exceed max entries
-        if self.cache.len() >= self.max_entries {
-            self.evict_oldest();
+        if !state.entries.contains_key(&key) && state.entries.len() >= state.max_entries {
+            state.evict_oldest();
         }
 
         let cached_result = CachedResult {
@@ -70,61 +106,37 @@ impl QueryCache {
             cached_at: Instant::now(),
         };
 
-        self.cache.insert(key, cached_result);
+        state.entries.insert(key, cached_result);
     }
 
     /// Clear all cached results
-    pub fn clear(&mut self) {
-        self.cache.clear();
+    pub fn clear(&self) {
+        self.state().entries.clear();
     }
 
     /// Get cache statistics
     pub fn stats(&self) -> CacheStats {
+        let state = self.state();
         CacheStats {
-            total_entries: self.cache.len(),
-            max_entries: self.max_entries,
-            ttl_seconds: self.default_ttl_secs,
-        }
-    }
-
-    /// Remove expired entries from cache
-    fn cleanup_expired(&mut self) {
-        let expired_keys: Vec<String> = self
-            .cache
-            .iter()
-            .filter(|(_, cached)| cached.cached_at.elapsed().as_secs() >= self.default_ttl_secs)
-            .map(|(key, _)| key.clone())
-            .collect();
-
-        for key in expired_keys {
-            self.cache.remove(&key);
-        }
-    }
-
-    /// Evict the oldest entry when cache is full
-    fn evict_oldest(&mut self) {
-        if let Some((oldest_key, _)) = self
-            .cache
-            .iter()
-            .min_by_key(|(_, cached)| cached.cached_at)
-            .map(|(k, v)| (k.clone(), v.clone()))
-        {
-            self.cache.remove(&oldest_key);
+            total_entries: state.entries.len(),
+            max_entries: state.max_entries,
+            ttl_seconds: state.ttl_secs,
         }
     }
 
     /// Update TTL for the cache
-    pub fn set_ttl(&mut self, ttl_secs: u64) {
-        self.default_ttl_secs = ttl_secs;
+    pub fn set_ttl(&self, ttl_secs: u64) {
+        self.state().ttl_secs = ttl_secs;
     }
 
     /// Set maximum number of cache entries
-    pub fn set_max_entries(&mut self, max_entries: usize) {
-        self.max_entries = max_entries;
-        
+    pub fn set_max_entries(&self, max_entries: usize) {
+        let mut state = self.state();
+        state.max_entries = max_entries;
+
         // Evict entries if we're over the new limit
-        while self.cache.len() > max_entries {
-            self.evict_oldest();
+        while state.entries.len() > max_entries {
+            state.evict_oldest();
         }
     }
 }
@@ -160,6 +172,7 @@ impl QueryValidator {
         format!("{:?}", query.order_by).hash(&mut hasher);
         query.limit.hash(&mut hasher);
         format!("{:?}", query.time_range).hash(&mut hasher);
+        format!("{:?}", query.join).hash(&mut hasher);
 
         format!("query_{:x}", hasher.finish())
     }
@@ -216,6 +229,9 @@ impl QueryValidator {
             crate::query::parser::FromClause::Symbols => 20,
             crate::query::parser::FromClause::References => 25,
             crate::query::parser::FromClause::Projects => 30,
+            crate::query::parser::FromClause::Fixes => 40,
+            crate::query::parser::FromClause::Lenses => 20,
+            crate::query::parser::FromClause::Config => 5,
         };
 
         // Filter cost
@@ -307,6 +323,7 @@ impl QueryKeyGenerator {
             crate::query::parser::SelectClause::Count => "select:count",
             crate::query::parser::SelectClause::Fields(_) => "select:fields",
             crate::query::parser::SelectClause::Aggregations(_) => "select:agg",
+            crate::query::parser::SelectClause::Expressions(_) => "select:expr",
         };
         key_parts.push(select_type.to_string());
 
@@ -320,8 +337,11 @@ impl QueryKeyGenerator {
                 crate::query::parser::QueryFilter::Severity(_) => "severity",
                 crate::query::parser::QueryFilter::Category(_) => "category",
                 crate::query::parser::QueryFilter::Message(_) => "message",
+                crate::query::parser::QueryFilter::FullText(_) => "fulltext",
                 crate::query::parser::QueryFilter::TimeRange(_) => "time",
                 crate::query::parser::QueryFilter::FileCount(_) => "filecount",
+                crate::query::parser::QueryFilter::Comparison(_) => "comparison",
+                crate::query::parser::QueryFilter::In(_) => "in",
                 crate::query::parser::QueryFilter::Custom(field, _) => return format!("custom:{field}"),
             };
             filter_types.push(filter_type);
@@ -351,7 +371,7 @@ mod tests {
 
     #[test]
     fn test_cache_basic_operations() {
-        let mut cache = QueryCache::new();
+        let cache = QueryCache::new();
         
         let result = super::super::types::QueryResult::empty("test");
         cache.insert("key1".to_string(), result.clone());
@@ -363,7 +383,7 @@ mod tests {
 
     #[test]
     fn test_cache_expiration() {
-        let mut cache = QueryCache::with_settings(1, 100); // 1 second TTL
+        let cache = QueryCache::with_settings(1, 100); // 1 second TTL
         
         let result = super::super::types::QueryResult::empty("test");
         cache.insert("key1".to_string(), result);
@@ -378,7 +398,7 @@ mod tests {
 
     #[test]
     fn test_cache_eviction() {
-        let mut cache = QueryCache::with_settings(300, 2); // Max 2 entries
+        let cache = QueryCache::with_settings(300, 2); // Max 2 entries
         
         let result = super::super::types::QueryResult::empty("test");
         
@@ -386,7 +406,7 @@ mod tests {
         cache.insert("key2".to_string(), result.clone());
         cache.insert("key3".to_string(), result.clone()); // Should evict oldest
         
-        assert_eq!(cache.cache.len(), 2);
+        assert_eq!(cache.stats().total_entries, 2);
         assert!(cache.get("key1").is_none()); // Should be evicted
         assert!(cache.get("key2").is_some());
         assert!(cache.get("key3").is_some());
@@ -402,6 +422,7 @@ mod tests {
             order_by: None,
             limit: None,
             time_range: None,
+            join: None,
         };
 
         let cost = QueryValidator::estimate_query_cost(&query);
@@ -419,6 +440,7 @@ mod tests {
             order_by: None,
             limit: Some(10),
             time_range: None,
+            join: None,
         };
 
         let key1 = QueryValidator::generate_cache_key(&query);
@@ -444,6 +466,7 @@ mod tests {
             order_by: None,
             limit: Some(10),
             time_range: None,
+            join: None,
         };
 
         let pattern_key = QueryKeyGenerator::generate_pattern_key(&query);
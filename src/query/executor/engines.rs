@@ -1,21 +1,35 @@
 //! Query execution engines for different data sources
 //!
 //! This module provides specialized execution engines for each data source type:
-//! diagnostics, files, history, and trends. Each engine knows how to query its
-//! specific data source and convert results to the common QueryResult format.
+//! diagnostics, files, history, trends, quick-fix candidates and configuration. Each engine
+//! knows how to query its specific data source and convert results to the
+//! common QueryResult format.
 
 use super::filters::FilterEngine;
+use super::processing::AggregationProcessor;
+use crate::analyzers::DiagnosticTaxonomy;
 use crate::query::parser::{FromClause, Query, SelectClause, QueryAggregation};
 use super::types::{FileStatistics, QueryMetadata, QueryResult, Row, Value};
-use crate::core::{Diagnostic, DiagnosticResult};
-use crate::history::HistoryStorage;
+use crate::core::config::{EnvironmentEntry, EnvironmentSnapshot};
+use crate::core::{
+    CalendarConfig, CodeLens, CodeLensKind, Diagnostic, DiagnosticResult, DiagnosticSeverity, SymbolDefinition,
+    SymbolIndex,
+};
+use crate::history::{AsOf, HistoryStorage, MessageSearch, SearchField};
+use crate::multi_repo::monorepo::{bazel_targets, BazelTargetMap};
+use crate::query::parser::{FullTextFilter, QueryFilter, TextField, TimeRange};
+use crate::quick_fix::verification::detect_language_from_files;
+use crate::quick_fix::{FixSuggestionService, RankedFix};
 use anyhow::{anyhow, Result};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
 
 /// Engine for executing queries against diagnostic data
 pub struct DiagnosticsEngine {
     filter_engine: FilterEngine,
+    targets: Option<Arc<BazelTargetMap>>,
 }
 
 impl DiagnosticsEngine {
@@ -23,9 +37,85 @@ impl DiagnosticsEngine {
     pub fn new() -> Self {
         Self {
             filter_engine: FilterEngine::new(),
+            targets: None,
         }
     }
 
+    /// Enable the `target` field using a Bazel target map
+    pub fn set_targets(&mut self, targets: Arc<BazelTargetMap>) {
+        self.targets = Some(targets);
+    }
+
+    /// Bazel label owning a diagnostic's file
+    fn target_label(&self, file_path: &Path) -> Option<String> {
+        self.targets.as_ref()?.label_for(file_path)
+    }
+
+    /// Apply `target = '<pattern>'` filters
+    ///
+    /// Patterns follow Bazel syntax, so `//pkg/...` and `//pkg:all` select
+    /// whole subtrees and packages.
+    fn apply_target_filters(
+        &self,
+        diagnostics: Vec<(PathBuf, Diagnostic)>,
+        filters: &[QueryFilter],
+    ) -> Vec<(PathBuf, Diagnostic)> {
+        let patterns: Vec<&String> = filters
+            .iter()
+            .filter_map(|filter| match filter {
+                QueryFilter::Custom(field, value) if field == "target" => Some(value),
+                _ => None,
+            })
+            .collect();
+
+        if patterns.is_empty() {
+            return diagnostics;
+        }
+
+        diagnostics
+            .into_iter()
+            .filter(|(path, _)| match self.target_label(path) {
+                Some(label) => patterns
+                    .iter()
+                    .all(|pattern| bazel_targets::label_matches(&label, pattern)),
+                None => false,
+            })
+            .collect()
+    }
+
+    /// Apply `taxonomy = '<name>'` filters
+    fn apply_taxonomy_filters(
+        &self,
+        diagnostics: Vec<(PathBuf, Diagnostic)>,
+        filters: &[QueryFilter],
+    ) -> Result<Vec<(PathBuf, Diagnostic)>> {
+        let wanted = filters
+            .iter()
+            .filter_map(|filter| match filter {
+                QueryFilter::Custom(field, value) if field == "taxonomy" => Some(value),
+                _ => None,
+            })
+            .map(|value| {
+                DiagnosticTaxonomy::parse(value).ok_or_else(|| {
+                    let valid: Vec<_> = DiagnosticTaxonomy::ALL.iter().map(|t| t.as_str()).collect();
+                    anyhow!("Unknown taxonomy '{}', expected one of: {}", value, valid.join(", "))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if wanted.is_empty() {
+            return Ok(diagnostics);
+        }
+
+        Ok(diagnostics
+            .into_iter()
+            .filter(|(_, diagnostic)| {
+                let taxonomy = DiagnosticTaxonomy::classify(diagnostic);
+                wanted.iter().all(|wanted| *wanted == taxonomy)
+            })
+            .collect())
+    }
+
     /// Execute a query against diagnostic data
     pub async fn execute(&self, query: &Query, diagnostics: &DiagnosticResult) -> Result<QueryResult> {
         // Convert diagnostics to a flat list
@@ -38,14 +128,18 @@ impl DiagnosticsEngine {
 
         // Apply filters
         let filtered = self.filter_engine.apply_diagnostic_filters(&all_diagnostics, &query.filters)?;
+        let filtered = self.apply_target_filters(filtered, &query.filters);
+        let filtered = self.apply_taxonomy_filters(filtered, &query.filters)?;
         let rows_scanned = all_diagnostics.len();
 
         // Build result based on select clause
-        let (columns, rows) = match &query.select {
-            SelectClause::All => self.build_all_columns_result(&filtered),
-            SelectClause::Count => self.build_count_result(filtered.len()),
-            SelectClause::Fields(fields) => self.build_fields_result(&filtered, fields),
-            SelectClause::Aggregations(aggs) => self.build_aggregation_result(&filtered, aggs)?,
+        let group_by = query.group_by.as_ref().map(|g| g.fields.as_slice()).unwrap_or_default();
+        let (columns, rows) = match (&query.select, AggregationProcessor::output_columns(query)) {
+            (SelectClause::Aggregations(aggs), _) => self.build_aggregation_result(&filtered, aggs, group_by)?,
+            (_, Some(select)) => self.build_grouped_result(&filtered, &select, group_by)?,
+            (SelectClause::All | SelectClause::Expressions(_), None) => self.build_all_columns_result(&filtered),
+            (SelectClause::Count, None) => self.build_count_result(filtered.len()),
+            (SelectClause::Fields(fields), None) => self.build_fields_result(&filtered, fields),
         };
 
         let total_count = rows.len();
@@ -66,8 +160,12 @@ impl DiagnosticsEngine {
     }
 
     /// Build result with all diagnostic columns
+    ///
+    /// A `target` column is appended when a Bazel target map is configured,
+    /// and an `occurrences` column when duplicates were collapsed.
     fn build_all_columns_result(&self, filtered: &[(PathBuf, Diagnostic)]) -> (Vec<String>, Vec<Row>) {
-        let columns = vec![
+        let deduplicated = filtered.iter().any(|(_, diagnostic)| diagnostic.duplicates().is_some());
+        let mut columns = vec![
             "file".to_string(),
             "line".to_string(),
             "column".to_string(),
@@ -75,24 +173,70 @@ impl DiagnosticsEngine {
             "category".to_string(),
             "message".to_string(),
         ];
+        if self.targets.is_some() {
+            columns.push("target".to_string());
+        }
+        if deduplicated {
+            columns.push("occurrences".to_string());
+        }
 
         let mut rows = Vec::new();
         for (file_path, diagnostic) in filtered {
-            rows.push(Row {
-                values: vec![
-                    Value::Path(file_path.clone()),
-                    Value::Integer(diagnostic.range.start.line as i64),
-                    Value::Integer(diagnostic.range.start.character as i64),
-                    Value::Severity(diagnostic.severity),
-                    Value::String(diagnostic.code.clone().unwrap_or_default()),
-                    Value::String(diagnostic.message.clone()),
-                ],
-            });
+            let mut values = vec![
+                Value::Path(file_path.clone()),
+                Value::Integer(diagnostic.range.start.line as i64),
+                Value::Integer(diagnostic.range.start.character as i64),
+                Value::Severity(diagnostic.severity),
+                Value::String(diagnostic.code.clone().unwrap_or_default()),
+                Value::String(diagnostic.message.clone()),
+            ];
+            if self.targets.is_some() {
+                values.push(self.extract_diagnostic_field(file_path, diagnostic, "target"));
+            }
+            if deduplicated {
+                values.push(Value::Integer(diagnostic.occurrences() as i64));
+            }
+            rows.push(Row { values });
         }
 
         (columns, rows)
     }
 
+    /// Build result for a query with GROUP BY or aggregation columns
+    ///
+    /// Extracts the fields the grouping and aggregations need from each
+    /// diagnostic and hands them to [`AggregationProcessor::aggregate_rows`].
+    fn build_grouped_result(
+        &self,
+        filtered: &[(PathBuf, Diagnostic)],
+        select: &[String],
+        group_by: &[String],
+    ) -> Result<(Vec<String>, Vec<Row>)> {
+        let mut fields: Vec<String> = group_by.to_vec();
+        for column in select {
+            let field = match QueryAggregation::from_column(column) {
+                Some(agg) if agg.field() == "*" => continue,
+                Some(agg) => agg.field().to_string(),
+                None => column.clone(),
+            };
+            if !fields.contains(&field) {
+                fields.push(field);
+            }
+        }
+
+        let rows: Vec<Row> = filtered
+            .iter()
+            .map(|(file_path, diagnostic)| Row {
+                values: fields
+                    .iter()
+                    .map(|field| self.extract_diagnostic_field(file_path, diagnostic, field))
+                    .collect(),
+            })
+            .collect();
+
+        AggregationProcessor::aggregate_rows(&fields, &rows, select, group_by)
+    }
+
     /// Build count result
     fn build_count_result(&self, count: usize) -> (Vec<String>, Vec<Row>) {
         let columns = vec!["count".to_string()];
@@ -116,14 +260,16 @@ impl DiagnosticsEngine {
         (fields.to_vec(), rows)
     }
 
-    /// Build aggregation result
-    fn build_aggregation_result(&self, filtered: &[(PathBuf, Diagnostic)], _aggs: &[QueryAggregation]) -> Result<(Vec<String>, Vec<Row>)> {
-        // Simple implementation - just count for now
-        let columns = vec!["count".to_string()];
-        let rows = vec![Row {
-            values: vec![Value::Integer(filtered.len() as i64)],
-        }];
-        Ok((columns, rows))
+    /// Build aggregation result, one row per group
+    fn build_aggregation_result(
+        &self,
+        filtered: &[(PathBuf, Diagnostic)],
+        aggs: &[QueryAggregation],
+        group_by: &[String],
+    ) -> Result<(Vec<String>, Vec<Row>)> {
+        let mut select: Vec<String> = group_by.to_vec();
+        select.extend(aggs.iter().map(ToString::to_string));
+        self.build_grouped_result(filtered, &select, group_by)
     }
 
     /// Extract a specific field value from a diagnostic
@@ -132,10 +278,22 @@ impl DiagnosticsEngine {
             "file" | "path" => Value::Path(file_path.clone()),
             "line" => Value::Integer(diagnostic.range.start.line as i64),
             "column" => Value::Integer(diagnostic.range.start.character as i64),
+            "end_line" => Value::Integer(diagnostic.range.end.line as i64),
+            // Lines the diagnostic's range spans
+            "line_count" => Value::Integer(
+                diagnostic.range.end.line.saturating_sub(diagnostic.range.start.line) as i64 + 1,
+            ),
+            "message_length" => Value::Integer(diagnostic.message.chars().count() as i64),
             "severity" => Value::Severity(diagnostic.severity),
             "category" => Value::String(diagnostic.code.clone().unwrap_or_default()),
             "message" => Value::String(diagnostic.message.clone()),
             "source" => Value::String(diagnostic.source.clone()),
+            "taxonomy" => Value::String(DiagnosticTaxonomy::classify(diagnostic).to_string()),
+            "occurrences" => Value::Integer(diagnostic.occurrences() as i64),
+            "target" => self
+                .target_label(file_path)
+                .map(Value::String)
+                .unwrap_or(Value::Null),
             _ => Value::Null,
         }
     }
@@ -174,7 +332,9 @@ impl FilesEngine {
         // Build result
         let total_count = file_list.len();
         let (columns, rows) = match &query.select {
-            SelectClause::All | SelectClause::Fields(_) => self.build_file_stats_result(&file_list),
+            SelectClause::All | SelectClause::Fields(_) | SelectClause::Expressions(_) => {
+                self.build_file_stats_result(&file_list)
+            }
             SelectClause::Count => self.build_count_result(total_count),
             _ => return Err(anyhow!("Unsupported select clause for files")),
         };
@@ -230,37 +390,203 @@ impl FilesEngine {
 }
 
 /// Engine for executing queries against historical data
-pub struct HistoryEngine;
+pub struct HistoryEngine {
+    calendar: CalendarConfig,
+}
 
 impl HistoryEngine {
     /// Create a new history query engine
     pub fn new() -> Self {
-        Self
+        Self {
+            calendar: CalendarConfig::default(),
+        }
+    }
+
+    /// Resolve relative time ranges in the calendar's time zone
+    pub fn set_calendar(&mut self, calendar: CalendarConfig) {
+        self.calendar = calendar;
     }
 
     /// Execute a query against historical data
-    pub async fn execute(&self, query: &Query, _history: &HistoryStorage) -> Result<QueryResult> {
-        // For now, return a placeholder
-        // This would query the SQLite database based on the query filters
+    ///
+    /// `CONTAINS_TEXT` filters are answered from the history database's
+    /// full-text index, so searching a long history never decodes snapshots.
+    pub async fn execute(&self, query: &Query, history: &HistoryStorage) -> Result<QueryResult> {
+        let text_filters: Vec<&FullTextFilter> = query
+            .filters
+            .iter()
+            .filter_map(|filter| match filter {
+                QueryFilter::FullText(text_filter) => Some(text_filter),
+                _ => None,
+            })
+            .collect();
+
+        let Some((primary, rest)) = text_filters.split_first() else {
+            // For now, return a placeholder
+            // This would query the SQLite database based on the query filters
+            let metadata = QueryMetadata {
+                data_source: "history".to_string(),
+                filters_applied: query.filters.len(),
+                rows_scanned: 0,
+                cache_hit: false,
+            };
+
+            return Ok(QueryResult {
+                columns: HISTORY_COLUMNS.iter().map(|c| c.to_string()).collect(),
+                rows: vec![],
+                total_count: 0,
+                query_time_ms: 0,
+                metadata,
+            });
+        };
+
+        let (since, until) = time_bounds(query.time_range.as_ref(), &self.calendar);
+        let mut search = MessageSearch::new(&primary.text)
+            .with_field(match primary.field {
+                TextField::Message => SearchField::Message,
+                TextField::Code => SearchField::Code,
+                TextField::Any => SearchField::Any,
+            })
+            .with_time_range(since, until);
+        // Aggregations need every match, so LIMIT applies after them
+        let aggregated = AggregationProcessor::output_columns(query).is_some();
+        if rest.is_empty() && query.select != SelectClause::Count && !aggregated {
+            if let Some(limit) = query.limit {
+                search = search.with_limit(limit as usize);
+            }
+        }
+
+        let found = history.search_messages(&search).await?;
+        let rows_scanned = found.len();
+        let mut matches: Vec<_> = found
+            .into_iter()
+            .filter(|m| rest.iter().all(|f| f.matches(&m.message, m.code.as_deref())))
+            .collect();
+        let total_count = matches.len();
+
         let metadata = QueryMetadata {
             data_source: "history".to_string(),
-            filters_applied: query.filters.len(),
-            rows_scanned: 0,
+            filters_applied: text_filters.len(),
+            rows_scanned,
             cache_hit: false,
         };
 
+        if query.select == SelectClause::Count {
+            return Ok(QueryResult {
+                columns: vec!["count".to_string()],
+                rows: vec![Row {
+                    values: vec![Value::Integer(total_count as i64)],
+                }],
+                total_count: 1,
+                query_time_ms: 0,
+                metadata,
+            });
+        }
+
+        if let Some(limit) = query.limit.filter(|_| !aggregated) {
+            matches.truncate(limit as usize);
+        }
+        let now = SystemTime::now();
+        let rows = matches
+            .into_iter()
+            .map(|m| Row {
+                values: vec![
+                    Value::String(chrono::DateTime::<chrono::Utc>::from(m.timestamp).to_rfc3339()),
+                    Value::Path(m.file_path),
+                    m.line.map_or(Value::Null, |line| Value::Integer(line as i64 + 1)),
+                    m.severity.map_or(Value::Null, Value::String),
+                    m.code.map_or(Value::Null, Value::String),
+                    Value::String(m.message),
+                    Value::Number(now.duration_since(m.timestamp).unwrap_or_default().as_secs_f64() / SECONDS_PER_DAY),
+                ],
+            })
+            .collect();
+
         Ok(QueryResult {
-            columns: vec![
-                "timestamp".to_string(),
-                "file".to_string(),
-                "errors".to_string(),
-            ],
-            rows: vec![],
-            total_count: 0,
+            columns: HISTORY_COLUMNS.iter().map(|c| c.to_string()).collect(),
+            rows,
+            total_count,
             query_time_ms: 0,
             metadata,
         })
     }
+
+    /// Latest recorded snapshot of every file, one row per file
+    ///
+    /// This is the history side of a join. Snapshots are taken as of the end
+    /// of the query's time range; comparison filters apply to the count
+    /// columns and file filters to the path.
+    pub async fn latest_snapshots(&self, query: &Query, history: &HistoryStorage) -> Result<QueryResult> {
+        let (_, until) = time_bounds(query.time_range.as_ref(), &self.calendar);
+        let snapshots = history.reconstruct(AsOf::Time(until.unwrap_or_else(SystemTime::now))).await?;
+        let rows_scanned = snapshots.len();
+
+        let columns: Vec<String> = FILE_HISTORY_COLUMNS.iter().map(|c| c.to_string()).collect();
+        let mut rows: Vec<Row> = snapshots
+            .into_iter()
+            .map(|snapshot| {
+                let counts = [snapshot.error_count, snapshot.warning_count, snapshot.info_count, snapshot.hint_count];
+                let mut values = vec![
+                    Value::Path(snapshot.file_path),
+                    Value::String(chrono::DateTime::<chrono::Utc>::from(snapshot.timestamp).to_rfc3339()),
+                ];
+                values.extend(counts.iter().map(|&count| Value::Integer(count as i64)));
+                values.push(Value::Integer(counts.iter().sum::<usize>() as i64));
+                Row::new(values)
+            })
+            .collect();
+
+        for filter in &query.filters {
+            match filter {
+                QueryFilter::File(file) => {
+                    rows.retain(|row| matches!(row.get(0), Some(Value::Path(path)) if path.to_string_lossy().contains(&file.pattern)))
+                }
+                QueryFilter::Comparison(comparison) => {
+                    let index = columns
+                        .iter()
+                        .position(|c| *c == comparison.field)
+                        .ok_or_else(|| anyhow!("Unknown history column: {}", comparison.field))?;
+                    rows.retain(|row| {
+                        row.get(index)
+                            .and_then(Value::as_number)
+                            .is_some_and(|actual| FilterEngine::matches_comparison(actual, comparison))
+                    })
+                }
+                _ => {}
+            }
+        }
+
+        Ok(QueryResult {
+            columns,
+            total_count: rows.len(),
+            rows,
+            query_time_ms: 0,
+            metadata: QueryMetadata {
+                data_source: "history".to_string(),
+                filters_applied: query.filters.len(),
+                rows_scanned,
+                cache_hit: false,
+            },
+        })
+    }
+}
+
+/// Columns of per-file history rows from the latest snapshot of each file
+const FILE_HISTORY_COLUMNS: [&str; 7] =
+    ["file", "timestamp", "error_count", "warning_count", "info_count", "hint_count", "total"];
+
+/// Columns of history rows; `age_days` is the time since the diagnostic was recorded
+const HISTORY_COLUMNS: [&str; 7] = ["timestamp", "file", "line", "severity", "code", "message", "age_days"];
+
+const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+
+/// Absolute bounds of a query time range; commit-relative ranges are not bounded
+fn time_bounds(range: Option<&TimeRange>, calendar: &CalendarConfig) -> (Option<SystemTime>, Option<SystemTime>) {
+    let Some(range) = range else {
+        return (None, None);
+    };
+    let (since, until) = range.resolve(calendar, chrono::Utc::now());
+    (since.map(SystemTime::from), until.map(SystemTime::from))
 }
 
 /// Engine for executing queries against trend data
@@ -338,7 +664,7 @@ impl SymbolsEngine {
 
         // Build result
         let (columns, rows) = match &query.select {
-            SelectClause::All => self.build_all_columns_result(&filtered),
+            SelectClause::All | SelectClause::Expressions(_) => self.build_all_columns_result(&filtered),
             SelectClause::Count => self.build_count_result(filtered.len()),
             SelectClause::Fields(fields) => self.build_fields_result(&filtered, fields),
             SelectClause::Aggregations(aggs) => self.build_aggregation_result(&filtered, aggs)?,
@@ -361,6 +687,120 @@ impl SymbolsEngine {
         })
     }
 
+    /// Execute a query against the workspace symbol index
+    ///
+    /// Rows are definitions: name, kind, file, line, container and in_test.
+    pub async fn execute_index(&self, query: &Query, index: &SymbolIndex) -> Result<QueryResult> {
+        let rows_scanned = index.definitions().len();
+        let mut definitions: Vec<&SymbolDefinition> = index.definitions().iter().collect();
+
+        for filter in &query.filters {
+            definitions = match filter {
+                QueryFilter::Custom(field, pattern) => {
+                    if !SYMBOL_TEXT_COLUMNS.contains(&field.as_str()) {
+                        return Err(anyhow!("Unknown field '{}' for symbols", field));
+                    }
+                    definitions
+                        .into_iter()
+                        .filter(|definition| like_matches(pattern, &Self::text_field(definition, field)))
+                        .collect()
+                }
+                QueryFilter::Symbol(symbol) => definitions
+                    .into_iter()
+                    .filter(|definition| like_matches(&symbol.pattern, &definition.name))
+                    .collect(),
+                QueryFilter::File(file) => definitions
+                    .into_iter()
+                    .filter(|definition| {
+                        let path = definition.file.to_string_lossy();
+                        path.contains(&file.pattern) || like_matches(&file.pattern, &path)
+                    })
+                    .collect(),
+                QueryFilter::Comparison(comparison) if comparison.field == "line" => definitions
+                    .into_iter()
+                    .filter(|definition| FilterEngine::matches_comparison(definition.line as f64, comparison))
+                    .collect(),
+                QueryFilter::In(_) => definitions,
+                _ => return Err(anyhow!("Symbol queries only filter on name, kind, file, container and line")),
+            };
+        }
+
+        let all_columns = || SYMBOL_COLUMNS.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+        let (columns, rows) = match &query.select {
+            SelectClause::All | SelectClause::Expressions(_) => {
+                self.build_index_fields_result(index, &definitions, &all_columns())
+            }
+            SelectClause::Fields(fields) => self.build_index_fields_result(index, &definitions, fields),
+            SelectClause::Count => self.build_count_result(definitions.len()),
+            SelectClause::Aggregations(aggs) => {
+                let mut columns = Vec::new();
+                for agg in aggs {
+                    match agg {
+                        QueryAggregation::Count(field) => columns.push(format!("count_{}", field)),
+                        _ => return Err(anyhow!("Aggregation not supported for symbol queries")),
+                    }
+                }
+                let values = vec![Value::Integer(definitions.len() as i64); columns.len()];
+                (columns, vec![Row { values }])
+            }
+        };
+
+        let metadata = QueryMetadata {
+            data_source: "symbols".to_string(),
+            filters_applied: query.filters.len(),
+            rows_scanned,
+            cache_hit: false,
+        };
+
+        let total_count = rows.len();
+        FilterEngine::retain_membership(
+            QueryResult {
+                columns,
+                rows,
+                total_count,
+                query_time_ms: 0,
+                metadata,
+            },
+            &query.filters,
+        )
+    }
+
+    fn build_index_fields_result(
+        &self,
+        index: &SymbolIndex,
+        definitions: &[&SymbolDefinition],
+        fields: &[String],
+    ) -> (Vec<String>, Vec<Row>) {
+        let rows = definitions
+            .iter()
+            .map(|definition| Row {
+                values: fields
+                    .iter()
+                    .map(|field| match field.as_str() {
+                        "file" => Value::Path(index.root().join(&definition.file)),
+                        "line" => Value::Integer(definition.line as i64),
+                        "in_test" => Value::Boolean(definition.in_test),
+                        "container" => definition.container.clone().map_or(Value::Null, Value::String),
+                        "name" | "kind" => Value::String(Self::text_field(definition, field)),
+                        _ => Value::Null,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        (fields.to_vec(), rows)
+    }
+
+    /// A text column of a definition; `file` and `path` are relative to the index root
+    fn text_field(definition: &SymbolDefinition, field: &str) -> String {
+        match field {
+            "name" => definition.name.clone(),
+            "kind" => definition.kind.as_str().to_string(),
+            "container" => definition.container.clone().unwrap_or_default(),
+            _ => definition.file.to_string_lossy().into_owned(),
+        }
+    }
+
     fn build_all_columns_result(&self, filtered: &[(PathBuf, Diagnostic)]) -> (Vec<String>, Vec<Row>) {
         let columns = vec![
             "file".to_string(),
@@ -431,10 +871,7 @@ impl SymbolsEngine {
                     columns.push(format!("count_{}", field));
                     values.push(Value::Integer(filtered.len() as i64));
                 }
-                QueryAggregation::Sum(_) | 
-                QueryAggregation::Average(_) |
-                QueryAggregation::Min(_) |
-                QueryAggregation::Max(_) => {
+                _ => {
                     return Err(anyhow!("Aggregation not supported for symbol queries"));
                 }
             }
@@ -527,7 +964,7 @@ impl ReferencesEngine {
 
         // Build result
         let (columns, rows) = match &query.select {
-            SelectClause::All => self.build_all_columns_result(&filtered),
+            SelectClause::All | SelectClause::Expressions(_) => self.build_all_columns_result(&filtered),
             SelectClause::Count => self.build_count_result(filtered.len()),
             SelectClause::Fields(fields) => self.build_fields_result(&filtered, fields),
             SelectClause::Aggregations(aggs) => self.build_aggregation_result(&filtered, aggs)?,
@@ -704,7 +1141,7 @@ impl ProjectsEngine {
 
         // Build result
         let (columns, rows) = match &query.select {
-            SelectClause::All => self.build_all_columns_result(&project_stats),
+            SelectClause::All | SelectClause::Expressions(_) => self.build_all_columns_result(&project_stats),
             SelectClause::Count => self.build_count_result(project_stats.len()),
             SelectClause::Fields(fields) => self.build_fields_result(&project_stats, fields),
             SelectClause::Aggregations(aggs) => self.build_aggregation_result(&project_stats, aggs)?,
@@ -827,6 +1264,633 @@ impl ProjectsEngine {
     }
 }
 
+/// A quick-fix candidate paired with the diagnostic it addresses
+struct FixCandidate {
+    file: PathBuf,
+    language: String,
+    diagnostic: Diagnostic,
+    fix: RankedFix,
+}
+
+/// Engine for executing queries against quick-fix candidates
+///
+/// Candidates are produced by running fix generation over the loaded
+/// diagnostics. Diagnostic filters (severity, path, message) narrow the
+/// diagnostics first; `confidence`, `line`, `language` and `automatic`
+/// filters then apply to the generated fixes.
+pub struct FixesEngine {
+    filter_engine: FilterEngine,
+    service: FixSuggestionService,
+}
+
+impl FixesEngine {
+    /// Create a new fixes query engine
+    pub fn new() -> Self {
+        Self {
+            filter_engine: FilterEngine::new(),
+            service: FixSuggestionService::new(),
+        }
+    }
+
+    /// Execute a query against fix candidates
+    pub async fn execute(&self, query: &Query, diagnostics: &DiagnosticResult) -> Result<QueryResult> {
+        let mut all_diagnostics = Vec::new();
+        for (file_path, file_diagnostics) in &diagnostics.diagnostics {
+            for diagnostic in file_diagnostics {
+                all_diagnostics.push((file_path.clone(), diagnostic.clone()));
+            }
+        }
+
+        let filtered = self.filter_engine.apply_diagnostic_filters(&all_diagnostics, &query.filters)?;
+
+        let mut candidates = Vec::new();
+        for (file_path, diagnostic) in filtered {
+            let language = detect_language_from_files(std::slice::from_ref(&file_path));
+            for fix in self.service.fixes(&diagnostic) {
+                candidates.push(FixCandidate {
+                    file: file_path.clone(),
+                    language: language.clone(),
+                    diagnostic: diagnostic.clone(),
+                    fix,
+                });
+            }
+        }
+        let rows_scanned = candidates.len();
+
+        let mut candidates = self.apply_fix_filters(candidates, &query.filters)?;
+        candidates.sort_by(|a, b| {
+            b.fix
+                .confidence
+                .partial_cmp(&a.fix.confidence)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let (columns, rows) = match &query.select {
+            SelectClause::All | SelectClause::Expressions(_) => self.build_all_columns_result(&candidates),
+            SelectClause::Count => self.build_count_result(candidates.len()),
+            SelectClause::Fields(fields) => self.build_fields_result(&candidates, fields),
+            SelectClause::Aggregations(aggs) => self.build_aggregation_result(&candidates, aggs)?,
+        };
+
+        let metadata = QueryMetadata {
+            data_source: "fixes".to_string(),
+            filters_applied: query.filters.len(),
+            rows_scanned,
+            cache_hit: false,
+        };
+
+        let total_count = rows.len();
+        Ok(QueryResult {
+            columns,
+            rows,
+            total_count,
+            query_time_ms: 0,
+            metadata,
+        })
+    }
+
+    /// Apply filters on fix-level fields
+    ///
+    /// Unknown numeric fields are rejected rather than ignored, so a typo in
+    /// `confidence > 0.9` can't select every candidate for automation.
+    fn apply_fix_filters(
+        &self,
+        candidates: Vec<FixCandidate>,
+        filters: &[QueryFilter],
+    ) -> Result<Vec<FixCandidate>> {
+        let mut result = candidates;
+
+        for filter in filters {
+            result = match filter {
+                QueryFilter::Comparison(comparison) => {
+                    let field = comparison.field.as_str();
+                    if !matches!(field, "confidence" | "line") {
+                        return Err(anyhow!("Unknown numeric field '{}' for fixes", field));
+                    }
+                    result
+                        .into_iter()
+                        .filter(|c| {
+                            let actual = match field {
+                                "confidence" => c.fix.confidence as f64,
+                                _ => c.diagnostic.range.start.line as f64,
+                            };
+                            FilterEngine::matches_comparison(actual, comparison)
+                        })
+                        .collect()
+                }
+                QueryFilter::Custom(field, value) if field == "language" => result
+                    .into_iter()
+                    .filter(|c| c.language.eq_ignore_ascii_case(value))
+                    .collect(),
+                QueryFilter::Custom(field, value) if field == "automatic" => {
+                    let automatic = value.eq_ignore_ascii_case("true");
+                    result
+                        .into_iter()
+                        .filter(|c| c.fix.is_automatic == automatic)
+                        .collect()
+                }
+                _ => result,
+            };
+        }
+
+        Ok(result)
+    }
+
+    fn build_all_columns_result(&self, candidates: &[FixCandidate]) -> (Vec<String>, Vec<Row>) {
+        let columns = vec![
+            "file".to_string(),
+            "line".to_string(),
+            "language".to_string(),
+            "severity".to_string(),
+            "diagnostic".to_string(),
+            "title".to_string(),
+            "confidence".to_string(),
+            "automatic".to_string(),
+        ];
+
+        let rows = candidates
+            .iter()
+            .map(|candidate| Row {
+                values: columns
+                    .iter()
+                    .map(|column| self.extract_fix_field(candidate, column))
+                    .collect(),
+            })
+            .collect();
+
+        (columns, rows)
+    }
+
+    fn build_count_result(&self, count: usize) -> (Vec<String>, Vec<Row>) {
+        let columns = vec!["count".to_string()];
+        let rows = vec![Row {
+            values: vec![Value::Integer(count as i64)],
+        }];
+        (columns, rows)
+    }
+
+    fn build_fields_result(&self, candidates: &[FixCandidate], fields: &[String]) -> (Vec<String>, Vec<Row>) {
+        let rows = candidates
+            .iter()
+            .map(|candidate| Row {
+                values: fields
+                    .iter()
+                    .map(|field| self.extract_fix_field(candidate, field))
+                    .collect(),
+            })
+            .collect();
+
+        (fields.to_vec(), rows)
+    }
+
+    fn build_aggregation_result(&self, candidates: &[FixCandidate], aggs: &[QueryAggregation]) -> Result<(Vec<String>, Vec<Row>)> {
+        let mut columns = Vec::new();
+        let mut values = Vec::new();
+        let confidences: Vec<f64> = candidates.iter().map(|c| c.fix.confidence as f64).collect();
+
+        for agg in aggs {
+            match agg {
+                QueryAggregation::Count(field) => {
+                    columns.push(format!("count_{}", field));
+                    values.push(Value::Integer(candidates.len() as i64));
+                }
+                QueryAggregation::Average(field) if field == "confidence" => {
+                    columns.push(format!("avg_{}", field));
+                    values.push(if confidences.is_empty() {
+                        Value::Null
+                    } else {
+                        Value::Number(confidences.iter().sum::<f64>() / confidences.len() as f64)
+                    });
+                }
+                QueryAggregation::Min(field) if field == "confidence" => {
+                    columns.push(format!("min_{}", field));
+                    values.push(
+                        confidences
+                            .iter()
+                            .cloned()
+                            .reduce(f64::min)
+                            .map(Value::Number)
+                            .unwrap_or(Value::Null),
+                    );
+                }
+                QueryAggregation::Max(field) if field == "confidence" => {
+                    columns.push(format!("max_{}", field));
+                    values.push(
+                        confidences
+                            .iter()
+                            .cloned()
+                            .reduce(f64::max)
+                            .map(Value::Number)
+                            .unwrap_or(Value::Null),
+                    );
+                }
+                _ => {
+                    return Err(anyhow!("Aggregation not supported for fix queries"));
+                }
+            }
+        }
+
+        Ok((columns, vec![Row { values }]))
+    }
+
+    /// Extract a specific field value from a fix candidate
+    fn extract_fix_field(&self, candidate: &FixCandidate, field: &str) -> Value {
+        match field {
+            "file" | "path" => Value::Path(candidate.file.clone()),
+            "line" => Value::Integer(candidate.diagnostic.range.start.line as i64),
+            "language" => Value::String(candidate.language.clone()),
+            "severity" => Value::Severity(candidate.diagnostic.severity),
+            "diagnostic_id" => Value::String(candidate.fix.diagnostic_id.clone()),
+            "diagnostic" | "message" => Value::String(candidate.fix.diagnostic_message.clone()),
+            "code" => candidate
+                .diagnostic
+                .code
+                .clone()
+                .map(Value::String)
+                .unwrap_or(Value::Null),
+            "title" => Value::String(candidate.fix.title.clone()),
+            "confidence" => Value::Number(candidate.fix.confidence as f64),
+            "automatic" => Value::Boolean(candidate.fix.is_automatic),
+            "diff" => candidate
+                .fix
+                .diff
+                .clone()
+                .map(Value::String)
+                .unwrap_or(Value::Null),
+            _ => Value::Null,
+        }
+    }
+}
+
+/// A code lens or inlay hint with the diagnostics of the code it annotates
+struct LensRow {
+    file: PathBuf,
+    lens: CodeLens,
+    errors: usize,
+    warnings: usize,
+}
+
+/// Engine for executing queries against code lenses and inlay hints
+///
+/// Each lens is joined with the open diagnostics of the code it annotates:
+/// from its line up to the line of the next code lens in the file, which
+/// approximates the symbol's body. Inlay hints only cover their own line.
+/// `references`, `line`, `error_count` and `warning_count` support numeric
+/// filters, so `SELECT * FROM lenses WHERE references = 0 AND warning_count > 0` lists
+/// unused symbols with open warnings.
+pub struct LensesEngine {
+    filter_engine: FilterEngine,
+}
+
+impl LensesEngine {
+    /// Create a new lenses query engine
+    pub fn new() -> Self {
+        Self {
+            filter_engine: FilterEngine::new(),
+        }
+    }
+
+    /// Execute a query against the lenses collected alongside `diagnostics`
+    pub async fn execute(&self, query: &Query, diagnostics: &DiagnosticResult) -> Result<QueryResult> {
+        let mut lens_rows = Vec::new();
+        for (file_path, lenses) in &diagnostics.code_lenses {
+            let file_diagnostics = diagnostics.diagnostics.get(file_path).map(Vec::as_slice).unwrap_or_default();
+            let mut lens_lines: Vec<u32> = lenses
+                .iter()
+                .filter(|lens| lens.kind == CodeLensKind::CodeLens)
+                .map(|lens| lens.range.start.line)
+                .collect();
+            lens_lines.sort_unstable();
+            lens_lines.dedup();
+
+            for lens in lenses {
+                let start = lens.range.start.line;
+                let end = match lens.kind {
+                    CodeLensKind::CodeLens => lens_lines.iter().copied().find(|line| *line > start).unwrap_or(u32::MAX),
+                    CodeLensKind::InlayHint => start + 1,
+                };
+                let covered = file_diagnostics
+                    .iter()
+                    .filter(|d| (start..end).contains(&d.range.start.line));
+                let (errors, warnings) = covered.fold((0, 0), |(errors, warnings), d| match d.severity {
+                    DiagnosticSeverity::Error => (errors + 1, warnings),
+                    DiagnosticSeverity::Warning => (errors, warnings + 1),
+                    _ => (errors, warnings),
+                });
+                lens_rows.push(LensRow {
+                    file: file_path.clone(),
+                    lens: lens.clone(),
+                    errors,
+                    warnings,
+                });
+            }
+        }
+        lens_rows.sort_by(|a, b| a.file.cmp(&b.file).then(a.lens.range.start.line.cmp(&b.lens.range.start.line)));
+        let rows_scanned = lens_rows.len();
+
+        let lens_rows = self.apply_lens_filters(lens_rows, &query.filters)?;
+
+        let (columns, rows) = match &query.select {
+            SelectClause::All | SelectClause::Expressions(_) => self.build_fields_result(&lens_rows, &Self::all_columns()),
+            SelectClause::Count => (
+                vec!["count".to_string()],
+                vec![Row {
+                    values: vec![Value::Integer(lens_rows.len() as i64)],
+                }],
+            ),
+            SelectClause::Fields(fields) => self.build_fields_result(&lens_rows, fields),
+            SelectClause::Aggregations(aggs) => self.build_aggregation_result(&lens_rows, aggs)?,
+        };
+
+        let metadata = QueryMetadata {
+            data_source: "lenses".to_string(),
+            filters_applied: query.filters.len(),
+            rows_scanned,
+            cache_hit: false,
+        };
+
+        let total_count = rows.len();
+        Ok(QueryResult {
+            columns,
+            rows,
+            total_count,
+            query_time_ms: 0,
+            metadata,
+        })
+    }
+
+    fn all_columns() -> Vec<String> {
+        ["file", "line", "symbol", "kind", "title", "references", "test_status", "error_count", "warning_count"]
+            .iter()
+            .map(|column| column.to_string())
+            .collect()
+    }
+
+    /// Apply path, symbol, numeric and `kind`/`test_status` filters
+    fn apply_lens_filters(&self, lens_rows: Vec<LensRow>, filters: &[QueryFilter]) -> Result<Vec<LensRow>> {
+        let path_filters: Vec<QueryFilter> = filters
+            .iter()
+            .filter(|filter| matches!(filter, QueryFilter::Path(_)))
+            .cloned()
+            .collect();
+        let mut files: Vec<(PathBuf, FileStatistics)> = lens_rows
+            .iter()
+            .map(|row| (row.file.clone(), FileStatistics::default()))
+            .collect();
+        files.dedup_by(|a, b| a.0 == b.0);
+        let allowed: Vec<PathBuf> = self
+            .filter_engine
+            .apply_file_filters(files, &path_filters)?
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        let mut result: Vec<LensRow> = lens_rows.into_iter().filter(|row| allowed.contains(&row.file)).collect();
+
+        for filter in filters {
+            result = match filter {
+                QueryFilter::File(file) => result
+                    .into_iter()
+                    .filter(|row| row.file.to_string_lossy().contains(&file.pattern))
+                    .collect(),
+                QueryFilter::Symbol(symbol) => result
+                    .into_iter()
+                    .filter(|row| row.lens.symbol.as_deref().is_some_and(|s| s.contains(&symbol.pattern)))
+                    .collect(),
+                QueryFilter::Comparison(comparison) => {
+                    let field = comparison.field.as_str();
+                    if !matches!(field, "references" | "line" | "error_count" | "warning_count") {
+                        return Err(anyhow!("Unknown numeric field '{}' for lenses", field));
+                    }
+                    result
+                        .into_iter()
+                        .filter(|row| {
+                            let actual = match field {
+                                // Lenses without a reference count never match a reference comparison
+                                "references" => match row.lens.references {
+                                    Some(references) => references as f64,
+                                    None => return false,
+                                },
+                                "line" => row.lens.range.start.line as f64,
+                                "error_count" => row.errors as f64,
+                                _ => row.warnings as f64,
+                            };
+                            FilterEngine::matches_comparison(actual, comparison)
+                        })
+                        .collect()
+                }
+                QueryFilter::Custom(field, value) if field == "kind" => result
+                    .into_iter()
+                    .filter(|row| row.lens.kind.as_str().eq_ignore_ascii_case(value))
+                    .collect(),
+                QueryFilter::Custom(field, value) if field == "test_status" => result
+                    .into_iter()
+                    .filter(|row| row.lens.test_status.as_deref().is_some_and(|s| s.eq_ignore_ascii_case(value)))
+                    .collect(),
+                _ => result,
+            };
+        }
+
+        Ok(result)
+    }
+
+    fn build_fields_result(&self, lens_rows: &[LensRow], fields: &[String]) -> (Vec<String>, Vec<Row>) {
+        let rows = lens_rows
+            .iter()
+            .map(|row| Row {
+                values: fields.iter().map(|field| self.extract_lens_field(row, field)).collect(),
+            })
+            .collect();
+
+        (fields.to_vec(), rows)
+    }
+
+    fn build_aggregation_result(&self, lens_rows: &[LensRow], aggs: &[QueryAggregation]) -> Result<(Vec<String>, Vec<Row>)> {
+        let mut columns = Vec::new();
+        let mut values = Vec::new();
+
+        for agg in aggs {
+            match agg {
+                QueryAggregation::Count(field) => {
+                    columns.push(format!("count_{}", field));
+                    values.push(Value::Integer(lens_rows.len() as i64));
+                }
+                QueryAggregation::Sum(field) if matches!(field.as_str(), "references" | "error_count" | "warning_count") => {
+                    columns.push(format!("sum_{}", field));
+                    let sum: i64 = lens_rows
+                        .iter()
+                        .map(|row| match self.extract_lens_field(row, field) {
+                            Value::Integer(value) => value,
+                            _ => 0,
+                        })
+                        .sum();
+                    values.push(Value::Integer(sum));
+                }
+                _ => {
+                    return Err(anyhow!("Aggregation not supported for lens queries"));
+                }
+            }
+        }
+
+        Ok((columns, vec![Row { values }]))
+    }
+
+    /// Extract a specific field value from a lens row
+    fn extract_lens_field(&self, row: &LensRow, field: &str) -> Value {
+        match field {
+            "file" | "path" => Value::Path(row.file.clone()),
+            "line" => Value::Integer(row.lens.range.start.line as i64),
+            "symbol" => row.lens.symbol.clone().map(Value::String).unwrap_or(Value::Null),
+            "kind" => Value::String(row.lens.kind.as_str().to_string()),
+            "title" => Value::String(row.lens.title.clone()),
+            "command" => row.lens.command.clone().map(Value::String).unwrap_or(Value::Null),
+            "references" => row
+                .lens
+                .references
+                .map(|references| Value::Integer(references as i64))
+                .unwrap_or(Value::Null),
+            "test_status" => row.lens.test_status.clone().map(Value::String).unwrap_or(Value::Null),
+            "error_count" => Value::Integer(row.errors as i64),
+            "warning_count" => Value::Integer(row.warnings as i64),
+            _ => Value::Null,
+        }
+    }
+}
+
+/// Engine for executing queries against the effective configuration and environment
+///
+/// Rows have `field`, `value` and `category` columns. String filters on
+/// those columns accept `%` and `*` wildcards, as in
+/// `WHERE field LIKE 'cache.%'`.
+pub struct ConfigEngine;
+
+impl ConfigEngine {
+    /// Create a new configuration query engine
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Execute a query against an environment snapshot
+    pub async fn execute(&self, query: &Query, snapshot: &EnvironmentSnapshot) -> Result<QueryResult> {
+        let rows_scanned = snapshot.entries.len();
+        let mut entries: Vec<&EnvironmentEntry> = snapshot.entries.iter().collect();
+
+        for filter in &query.filters {
+            entries = match filter {
+                QueryFilter::Custom(field, pattern) => {
+                    if !matches!(field.as_str(), "field" | "value" | "category") {
+                        return Err(anyhow!("Unknown field '{}' for config", field));
+                    }
+                    entries
+                        .into_iter()
+                        .filter(|entry| like_matches(pattern, &Self::text_field(entry, field)))
+                        .collect()
+                }
+                QueryFilter::Comparison(comparison) if comparison.field == "value" => entries
+                    .into_iter()
+                    .filter(|entry| {
+                        entry
+                            .value
+                            .parse::<f64>()
+                            .is_ok_and(|value| FilterEngine::matches_comparison(value, comparison))
+                    })
+                    .collect(),
+                _ => return Err(anyhow!("Config queries only filter on field, value and category")),
+            };
+        }
+
+        let all_columns = || ["field", "value", "category"].iter().map(|c| c.to_string()).collect::<Vec<_>>();
+        let (columns, rows) = match &query.select {
+            SelectClause::All | SelectClause::Expressions(_) => self.build_fields_result(&entries, &all_columns()),
+            SelectClause::Fields(fields) => self.build_fields_result(&entries, fields),
+            SelectClause::Count => (
+                vec!["count".to_string()],
+                vec![Row {
+                    values: vec![Value::Integer(entries.len() as i64)],
+                }],
+            ),
+            SelectClause::Aggregations(aggs) => {
+                let mut columns = Vec::new();
+                for agg in aggs {
+                    match agg {
+                        QueryAggregation::Count(field) => columns.push(format!("count_{}", field)),
+                        _ => return Err(anyhow!("Only COUNT is supported for config queries")),
+                    }
+                }
+                let values = vec![Value::Integer(entries.len() as i64); columns.len()];
+                (columns, vec![Row { values }])
+            }
+        };
+
+        let metadata = QueryMetadata {
+            data_source: "config".to_string(),
+            filters_applied: query.filters.len(),
+            rows_scanned,
+            cache_hit: false,
+        };
+
+        let total_count = rows.len();
+        Ok(QueryResult {
+            columns,
+            rows,
+            total_count,
+            query_time_ms: 0,
+            metadata,
+        })
+    }
+
+    fn build_fields_result(&self, entries: &[&EnvironmentEntry], fields: &[String]) -> (Vec<String>, Vec<Row>) {
+        let rows = entries
+            .iter()
+            .map(|entry| Row {
+                values: fields
+                    .iter()
+                    .map(|field| match field.as_str() {
+                        "field" | "value" | "category" => Value::String(Self::text_field(entry, field)),
+                        _ => Value::Null,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        (fields.to_vec(), rows)
+    }
+
+    fn text_field(entry: &EnvironmentEntry, field: &str) -> String {
+        match field {
+            "field" => entry.field.clone(),
+            "value" => entry.value.clone(),
+            _ => entry.category.as_str().to_string(),
+        }
+    }
+}
+
+/// Columns of symbol index rows, in `SELECT *` order
+const SYMBOL_COLUMNS: &[&str] = &["name", "kind", "file", "line", "container", "in_test"];
+
+/// Symbol columns that filter as text
+const SYMBOL_TEXT_COLUMNS: &[&str] = &["name", "kind", "file", "path", "container"];
+
+/// SQL `LIKE` matching where `%` and `*` match any run of characters
+fn like_matches(pattern: &str, text: &str) -> bool {
+    let parts: Vec<&str> = pattern.split(['%', '*']).collect();
+    if parts.len() == 1 {
+        return pattern == text;
+    }
+
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !text.starts_with(first) || text.len() < first.len() + last.len() || !text.ends_with(last) {
+        return false;
+    }
+    let mut rest = &text[first.len()..text.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
 /// Factory for creating appropriate execution engines
 pub struct EngineFactory;
 
@@ -840,7 +1904,10 @@ impl EngineFactory {
             FromClause::Trends => Box::new(TrendsEngine::new()),
             FromClause::Symbols => Box::new(SymbolsEngine::new()),
             FromClause::References => Box::new(ReferencesEngine::new()),
-            FromClause::Projects => Box::new(ProjectsEngine::new())
+            FromClause::Projects => Box::new(ProjectsEngine::new()),
+            FromClause::Fixes => Box::new(FixesEngine::new()),
+            FromClause::Lenses => Box::new(LensesEngine::new()),
+            FromClause::Config => Box::new(ConfigEngine::new()),
         }
     }
 }
@@ -916,6 +1983,25 @@ impl QueryEngine for ProjectsEngine {
     }
 }
 
+impl QueryEngine for FixesEngine {
+    fn execute_diagnostics(&self, query: &Query, diagnostics: &DiagnosticResult) -> Result<QueryResult> {
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(self.execute(query, diagnostics))
+        })
+    }
+}
+
+// Config queries read an environment snapshot, which neither entry point provides
+impl QueryEngine for ConfigEngine {}
+
+impl QueryEngine for LensesEngine {
+    fn execute_diagnostics(&self, query: &Query, diagnostics: &DiagnosticResult) -> Result<QueryResult> {
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(self.execute(query, diagnostics))
+        })
+    }
+}
+
 impl Default for DiagnosticsEngine {
     fn default() -> Self {
         Self::new()
@@ -958,6 +2044,24 @@ impl Default for ProjectsEngine {
     }
 }
 
+impl Default for FixesEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for LensesEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for ConfigEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1002,6 +2106,7 @@ mod tests {
             order_by: None,
             limit: None,
             time_range: None,
+            join: None,
         };
 
         let result = engine.execute(&query, &diagnostics).await.unwrap();
@@ -1009,6 +2114,130 @@ mod tests {
         assert_eq!(result.rows[0].values[0], Value::Integer(2));
     }
 
+    #[tokio::test]
+    async fn test_history_engine_contains_text() {
+        use crate::core::FileHash;
+        use crate::history::{DiagnosticSnapshot, HistoryConfig};
+        use crate::query::parser::QueryParser;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let history = HistoryStorage::new(HistoryConfig {
+            db_path: temp_dir.path().join("history.db"),
+            ..HistoryConfig::default()
+        })
+        .await
+        .unwrap();
+        let mut diagnostic = create_test_diagnostic(DiagnosticSeverity::Error, "use of moved value: `buf`");
+        diagnostic.code = Some("E0382".to_string());
+        history
+            .record_snapshot(DiagnosticSnapshot {
+                id: 0,
+                timestamp: SystemTime::now(),
+                file_path: PathBuf::from("test.rs"),
+                file_hash: FileHash::new(b"fn main() {}"),
+                diagnostics: vec![
+                    diagnostic,
+                    create_test_diagnostic(DiagnosticSeverity::Warning, "unused variable: `buf`"),
+                ],
+                error_count: 1,
+                warning_count: 1,
+                info_count: 0,
+                hint_count: 0,
+            })
+            .await
+            .unwrap();
+
+        let parser = QueryParser::new();
+        let query = parser
+            .parse("SELECT * FROM history WHERE message CONTAINS_TEXT 'moved buf' AND LAST 7 DAYS")
+            .unwrap();
+        let result = HistoryEngine::new().execute(&query, &history).await.unwrap();
+        assert_eq!(result.total_count, 1);
+        assert_eq!(result.columns[5], "message");
+        assert_eq!(result.rows[0].values[2], Value::Integer(2));
+        assert_eq!(result.rows[0].values[4], Value::String("E0382".to_string()));
+
+        let query = parser
+            .parse("SELECT COUNT(*) FROM history WHERE text CONTAINS_TEXT 'buf' AND code CONTAINS_TEXT 'E0382'")
+            .unwrap();
+        let result = HistoryEngine::new().execute(&query, &history).await.unwrap();
+        assert_eq!(result.rows[0].values[0], Value::Integer(1));
+    }
+
+    #[tokio::test]
+    async fn test_diagnostics_engine_bazel_targets() {
+        let mut targets = BazelTargetMap::new("/ws");
+        targets.add_build_file("query", r#"rust_library(name = "query", srcs = glob(["**/*.rs"]))"#);
+        targets.add_build_file("core", r#"rust_library(name = "core", srcs = ["lib.rs"])"#);
+
+        let mut engine = DiagnosticsEngine::new();
+        engine.set_targets(Arc::new(targets));
+
+        let mut diagnostics = DiagnosticResult::new();
+        diagnostics.diagnostics.insert(
+            PathBuf::from("/ws/query/src/mod.rs"),
+            vec![
+                create_test_diagnostic(DiagnosticSeverity::Error, "Error 1"),
+                create_test_diagnostic(DiagnosticSeverity::Error, "Error 2"),
+            ],
+        );
+        diagnostics.diagnostics.insert(
+            PathBuf::from("/ws/core/lib.rs"),
+            vec![create_test_diagnostic(DiagnosticSeverity::Warning, "Warning 1")],
+        );
+
+        let mut parser = crate::query::QueryParser::new();
+        let query = parser
+            .parse("SELECT target, COUNT(*) FROM diagnostics GROUP BY target ORDER BY target")
+            .unwrap();
+        let mut result = engine.execute(&query, &diagnostics).await.unwrap();
+        result.rows.sort_by_key(|row| row.values[0].to_string());
+        assert_eq!(result.columns, vec!["target", "COUNT(*)"]);
+        assert_eq!(result.rows[0].values, vec![Value::String("//core:core".to_string()), Value::Integer(1)]);
+        assert_eq!(result.rows[1].values, vec![Value::String("//query:query".to_string()), Value::Integer(2)]);
+
+        let query = parser
+            .parse("SELECT * FROM diagnostics WHERE target = '//query/...'")
+            .unwrap();
+        let result = engine.execute(&query, &diagnostics).await.unwrap();
+        assert_eq!(result.total_count, 2);
+        assert_eq!(result.columns.last().map(String::as_str), Some("target"));
+    }
+
+    #[tokio::test]
+    async fn test_diagnostics_engine_taxonomy() {
+        let engine = DiagnosticsEngine::new();
+        let mut diagnostics = DiagnosticResult::new();
+        diagnostics.diagnostics.insert(
+            PathBuf::from("test.rs"),
+            vec![
+                create_test_diagnostic(DiagnosticSeverity::Error, "mismatched types"),
+                create_test_diagnostic(DiagnosticSeverity::Error, "cannot borrow `x` as mutable more than once at a time"),
+                create_test_diagnostic(DiagnosticSeverity::Warning, "unresolved import `foo`"),
+            ],
+        );
+
+        let mut parser = crate::query::QueryParser::new();
+        let query = parser
+            .parse("SELECT taxonomy, COUNT(*) FROM diagnostics GROUP BY taxonomy")
+            .unwrap();
+        let mut result = engine.execute(&query, &diagnostics).await.unwrap();
+        result.rows.sort_by_key(|row| row.values[0].to_string());
+        let taxonomies: Vec<String> = result.rows.iter().map(|row| row.values[0].to_string()).collect();
+        assert_eq!(taxonomies, vec!["imports", "memory-safety", "typing"]);
+
+        let query = parser
+            .parse("SELECT COUNT(*) FROM diagnostics WHERE taxonomy = 'memory_safety'")
+            .unwrap();
+        let result = engine.execute(&query, &diagnostics).await.unwrap();
+        assert_eq!(result.rows[0].values[0], Value::Integer(1));
+
+        let query = parser
+            .parse("SELECT * FROM diagnostics WHERE taxonomy = 'vibes'")
+            .unwrap();
+        assert!(engine.execute(&query, &diagnostics).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_files_engine() {
         let engine = FilesEngine::new();
@@ -1034,6 +2263,7 @@ mod tests {
             order_by: None,
             limit: None,
             time_range: None,
+            join: None,
         };
 
         let result = engine.execute(&query, &diagnostics).await.unwrap();
@@ -1065,6 +2295,7 @@ mod tests {
             order_by: None,
             limit: None,
             time_range: None,
+            join: None,
         };
 
         let result = engine.execute(&query, &diagnostics).await.unwrap();
@@ -1108,6 +2339,7 @@ mod tests {
             order_by: None,
             limit: None,
             time_range: None,
+            join: None,
         };
 
         let result = engine.execute(&query, &diagnostics).await.unwrap();
@@ -1138,6 +2370,7 @@ mod tests {
             order_by: None,
             limit: None,
             time_range: None,
+            join: None,
         };
 
         let result = engine.execute(&query, &diagnostics).await.unwrap();
@@ -1174,6 +2407,7 @@ mod tests {
             order_by: None,
             limit: None,
             time_range: None,
+            join: None,
         };
 
         let result = engine.execute(&query, &diagnostics).await.unwrap();
@@ -1218,6 +2452,7 @@ mod tests {
             order_by: None,
             limit: None,
             time_range: None,
+            join: None,
         };
 
         let result = engine.execute(&query, &diagnostics).await.unwrap();
@@ -1265,6 +2500,7 @@ mod tests {
             order_by: None,
             limit: None,
             time_range: None,
+            join: None,
         };
 
         let result = engine.execute(&query, &diagnostics).await.unwrap();
@@ -1296,6 +2532,7 @@ mod tests {
             order_by: None,
             limit: None,
             time_range: None,
+            join: None,
         };
 
         let result = engine.execute(&query, &diagnostics).await.unwrap();
@@ -1304,4 +2541,185 @@ mod tests {
         assert_eq!(result.rows[0].values[0], Value::String("struct".to_string()));
         assert_eq!(result.rows[0].values[1], Value::String("Config".to_string()));
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_fixes_engine_filters_candidates() {
+        use crate::query::parser::QueryParser;
+        use std::io::Write;
+
+        let mut file = tempfile::Builder::new().suffix(".rs").tempfile().unwrap();
+        write!(file, "fn main() {{\n    let s = name;\n    takes(s);\n}}\n").unwrap();
+        let rust_path = file.path().to_path_buf();
+
+        let mut mismatch = create_test_diagnostic(
+            DiagnosticSeverity::Error,
+            "mismatched types: expected `String`, found `&str`",
+        );
+        mismatch.id = "mismatch".to_string();
+        mismatch.file = rust_path.to_string_lossy().to_string();
+        mismatch.range = Range {
+            start: Position { line: 1, character: 12 },
+            end: Position { line: 1, character: 16 },
+        };
+        mismatch.code = Some("E0308".to_string());
+        let mut python = create_test_diagnostic(DiagnosticSeverity::Error, "undefined name 'x'");
+        python.id = "python".to_string();
+        python.file = "script.py".to_string();
+
+        let mut diagnostics = DiagnosticResult::new();
+        diagnostics.diagnostics.insert(rust_path.clone(), vec![mismatch]);
+        diagnostics.diagnostics.insert(PathBuf::from("script.py"), vec![python]);
+
+        let parser = QueryParser::new();
+        let engine = FixesEngine::new();
+
+        let query = parser
+            .parse("SELECT * FROM fixes WHERE confidence > 0.5 AND language = 'rust'")
+            .unwrap();
+        let result = engine.execute(&query, &diagnostics).await.unwrap();
+        assert!(result.total_count > 0);
+        assert_eq!(result.columns[6], "confidence");
+        for row in &result.rows {
+            assert_eq!(row.values[0], Value::Path(rust_path.clone()));
+            assert_eq!(row.values[2], Value::String("rust".to_string()));
+            match row.values[6] {
+                Value::Number(confidence) => assert!(confidence > 0.5),
+                ref other => panic!("unexpected confidence {other:?}"),
+            }
+        }
+
+        let query = parser
+            .parse("SELECT COUNT(*) FROM fixes WHERE language = 'python'")
+            .unwrap();
+        let result = engine.execute(&query, &diagnostics).await.unwrap();
+        assert_eq!(result.rows[0].values[0], Value::Integer(0));
+
+        let query = parser
+            .parse("SELECT title FROM fixes WHERE confidence > 1.0")
+            .unwrap();
+        assert_eq!(engine.execute(&query, &diagnostics).await.unwrap().total_count, 0);
+
+        let query = parser.parse("SELECT * FROM fixes WHERE confidnce > 0.8").unwrap();
+        assert!(engine.execute(&query, &diagnostics).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_lenses_engine_joins_open_warnings() {
+        use crate::core::{CodeLens, CodeLensKind};
+        use crate::query::parser::QueryParser;
+
+        let lens = |line: u32, symbol: &str, references: u32| CodeLens {
+            kind: CodeLensKind::CodeLens,
+            range: Range {
+                start: Position { line, character: 3 },
+                end: Position { line, character: 3 + symbol.len() as u32 },
+            },
+            symbol: Some(symbol.to_string()),
+            title: format!("{references} references"),
+            command: None,
+            references: Some(references),
+            test_status: None,
+        };
+        let mut warning = create_test_diagnostic(DiagnosticSeverity::Warning, "unused variable");
+        warning.range.start.line = 12;
+
+        let mut diagnostics = DiagnosticResult::new();
+        let path = PathBuf::from("src/lib.rs");
+        diagnostics.diagnostics.insert(path.clone(), vec![warning]);
+        diagnostics
+            .code_lenses
+            .insert(path.clone(), vec![lens(0, "used", 4), lens(10, "dead", 0), lens(20, "idle", 0)]);
+
+        let parser = QueryParser::new();
+        let engine = LensesEngine::new();
+
+        let query = parser
+            .parse("SELECT file, symbol, warning_count FROM lenses WHERE references = 0 AND warning_count > 0")
+            .unwrap();
+        let result = engine.execute(&query, &diagnostics).await.unwrap();
+        assert_eq!(result.total_count, 1);
+        assert_eq!(
+            result.rows[0].values,
+            vec![Value::Path(path), Value::String("dead".to_string()), Value::Integer(1)]
+        );
+
+        let query = parser.parse("SELECT COUNT(*) FROM lenses WHERE references = 0").unwrap();
+        let result = engine.execute(&query, &diagnostics).await.unwrap();
+        assert_eq!(result.rows[0].values[0], Value::Integer(2));
+
+        let query = parser.parse("SELECT * FROM lenses WHERE refrences = 0").unwrap();
+        assert!(engine.execute(&query, &diagnostics).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_config_engine_matches_like_patterns() {
+        use crate::core::config::{EntryCategory, UnifiedConfig};
+        use crate::query::QueryParser;
+
+        let mut snapshot = EnvironmentSnapshot::from_config(&UnifiedConfig::default()).unwrap();
+        snapshot.entries.push(EnvironmentEntry {
+            field: "tool.gopls".to_string(),
+            value: "golang.org/x/tools/gopls v0.15.3".to_string(),
+            category: EntryCategory::Tool,
+        });
+        let parser = QueryParser::new();
+        let engine = ConfigEngine::new();
+
+        let query = parser.parse("SELECT field, value FROM config WHERE field LIKE 'cache.%'").unwrap();
+        let result = engine.execute(&query, &snapshot).await.unwrap();
+        assert!(result.total_count > 0);
+        assert!(result.rows.iter().all(|row| matches!(&row.values[0], Value::String(f) if f.starts_with("cache."))));
+        assert!(result
+            .rows
+            .iter()
+            .any(|row| row.values[0] == Value::String("cache.max_size_mb".to_string())));
+
+        let query = parser.parse("SELECT * FROM config WHERE category = 'tool' AND value LIKE '*gopls*'").unwrap();
+        let result = engine.execute(&query, &snapshot).await.unwrap();
+        assert_eq!(result.total_count, 1);
+
+        assert!(like_matches("a%c%e", "abcde"));
+        assert!(!like_matches("a%c%e", "ace_"));
+        assert!(!like_matches("ab%ba", "aba"));
+    }
+
+    #[tokio::test]
+    async fn test_symbols_engine_over_index() {
+        use crate::query::QueryParser;
+
+        let mut index = SymbolIndex::new("/work");
+        index.add_file(
+            Path::new("src/parser.rs"),
+            "pub struct Parser;\n\nimpl Parser {\n    pub fn parse_expr(&self) {}\n}\n\npub fn parse_file() {}\n",
+        );
+        index.add_file(Path::new("web/tokens.ts"), "export function parseToken() {}\nconst parsed = 1;\n");
+        let parser = QueryParser::new();
+        let engine = SymbolsEngine::new();
+
+        let query = parser.parse("SELECT * FROM symbols WHERE name LIKE 'parse*'").unwrap();
+        let result = engine.execute_index(&query, &index).await.unwrap();
+        assert_eq!(result.columns, vec!["name", "kind", "file", "line", "container", "in_test"]);
+        let found: Vec<_> = result.rows.iter().map(|row| (row.values[0].to_string(), row.values[1].to_string())).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("parse_expr".to_string(), "method".to_string()),
+                ("parse_file".to_string(), "function".to_string()),
+                ("parseToken".to_string(), "function".to_string()),
+                ("parsed".to_string(), "constant".to_string()),
+            ]
+        );
+        assert_eq!(result.rows[0].values[2], Value::Path(PathBuf::from("/work/src/parser.rs")));
+        assert_eq!(result.rows[0].values[4], Value::String("Parser".to_string()));
+
+        let query = parser
+            .parse("SELECT name FROM symbols WHERE kind = 'function' AND file LIKE 'web/*'")
+            .unwrap();
+        let result = engine.execute_index(&query, &index).await.unwrap();
+        assert_eq!(result.total_count, 1);
+        assert_eq!(result.rows[0].values, vec![Value::String("parseToken".to_string())]);
+
+        let query = parser.parse("SELECT * FROM symbols WHERE severity = 'error'").unwrap();
+        assert!(engine.execute_index(&query, &index).await.is_err());
+    }
+}
@@ -0,0 +1,398 @@
+//! Evaluation of computed SELECT expressions
+//!
+//! Engines answer a computed SELECT with all of their columns; this module
+//! then evaluates each `expr [AS alias]` item per row and projects the result
+//! down to the requested columns. Items may refer to aliases defined earlier
+//! in the same SELECT list, which take precedence over source columns.
+//!
+//! NULL follows SQL semantics: it propagates through arithmetic, string
+//! functions and comparisons, and division or modulo by zero yields NULL
+//! rather than an error.
+
+use super::processing::SortingProcessor;
+use super::types::{QueryResult, Row, Value};
+use crate::core::DiagnosticSeverity;
+use crate::query::parser::{BinaryOperator, Comparison, Expr, ScalarFunction, SelectItem};
+use anyhow::{anyhow, Result};
+use std::cmp::Ordering;
+
+/// Replace a result's columns with the computed SELECT items
+pub fn project(mut result: QueryResult, items: &[SelectItem]) -> Result<QueryResult> {
+    let columns: Vec<String> = items.iter().map(SelectItem::name).collect();
+
+    let rows = result
+        .rows
+        .iter()
+        .map(|row| {
+            let mut scope = RowScope {
+                columns: &result.columns,
+                values: &row.values,
+                computed: Vec::with_capacity(items.len()),
+            };
+            for (item, name) in items.iter().zip(&columns) {
+                let value = evaluate(&item.expr, &scope)?;
+                scope.computed.push((name.clone(), value));
+            }
+            Ok(Row::new(scope.computed.into_iter().map(|(_, value)| value).collect()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    result.columns = columns;
+    result.rows = rows;
+    Ok(result)
+}
+
+/// Column values visible to an expression while evaluating one row
+struct RowScope<'a> {
+    columns: &'a [String],
+    values: &'a [Value],
+    /// Items of the SELECT list evaluated so far, by result column name
+    computed: Vec<(String, Value)>,
+}
+
+impl RowScope<'_> {
+    fn lookup(&self, name: &str) -> Result<Value> {
+        if let Some((_, value)) = self
+            .computed
+            .iter()
+            .rev()
+            .find(|(column, _)| column.eq_ignore_ascii_case(name))
+        {
+            return Ok(value.clone());
+        }
+
+        self.columns
+            .iter()
+            .position(|column| column.eq_ignore_ascii_case(name))
+            .map(|index| self.values.get(index).cloned().unwrap_or(Value::Null))
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unknown column '{name}' in expression (available: {})",
+                    self.columns.join(", ")
+                )
+            })
+    }
+}
+
+fn evaluate(expr: &Expr, scope: &RowScope<'_>) -> Result<Value> {
+    match expr {
+        Expr::Column(name) => scope.lookup(name),
+        Expr::Integer(i) => Ok(Value::Integer(*i)),
+        Expr::Number(n) => Ok(Value::Number(*n)),
+        Expr::String(s) => Ok(Value::String(s.clone())),
+        Expr::Null => Ok(Value::Null),
+        Expr::Negate(inner) => match evaluate(inner, scope)? {
+            Value::Null => Ok(Value::Null),
+            Value::Integer(i) => Ok(Value::Integer(-i)),
+            Value::Number(n) => Ok(Value::Number(-n)),
+            other => Err(anyhow!("Cannot negate non-numeric value '{}'", other.to_string())),
+        },
+        Expr::Binary { op: BinaryOperator::And, left, right } => {
+            // Three-valued logic: FALSE wins over NULL for AND, TRUE for OR
+            let left = truth(&evaluate(left, scope)?);
+            if left == Some(false) {
+                return Ok(Value::Boolean(false));
+            }
+            Ok(match (left, truth(&evaluate(right, scope)?)) {
+                (_, Some(false)) => Value::Boolean(false),
+                (Some(true), Some(true)) => Value::Boolean(true),
+                _ => Value::Null,
+            })
+        }
+        Expr::Binary { op: BinaryOperator::Or, left, right } => {
+            let left = truth(&evaluate(left, scope)?);
+            if left == Some(true) {
+                return Ok(Value::Boolean(true));
+            }
+            Ok(match (left, truth(&evaluate(right, scope)?)) {
+                (_, Some(true)) => Value::Boolean(true),
+                (Some(false), Some(false)) => Value::Boolean(false),
+                _ => Value::Null,
+            })
+        }
+        Expr::Binary { op, left, right } => {
+            let left = evaluate(left, scope)?;
+            let right = evaluate(right, scope)?;
+            binary(op, left, right)
+        }
+        Expr::Function { function, args } => {
+            let args = args
+                .iter()
+                .map(|arg| evaluate(arg, scope))
+                .collect::<Result<Vec<_>>>()?;
+            call(*function, args)
+        }
+        Expr::Case { branches, otherwise } => {
+            for (condition, value) in branches {
+                if truth(&evaluate(condition, scope)?) == Some(true) {
+                    return evaluate(value, scope);
+                }
+            }
+            match otherwise {
+                Some(otherwise) => evaluate(otherwise, scope),
+                None => Ok(Value::Null),
+            }
+        }
+    }
+}
+
+/// Truth value of a condition; `None` is SQL's UNKNOWN
+fn truth(value: &Value) -> Option<bool> {
+    match value {
+        Value::Null => None,
+        Value::Boolean(b) => Some(*b),
+        Value::Integer(i) => Some(*i != 0),
+        Value::Number(n) => Some(*n != 0.0),
+        Value::Array(values) => Some(!values.is_empty()),
+        other => Some(!other.to_string().is_empty()),
+    }
+}
+
+fn binary(op: &BinaryOperator, left: Value, right: Value) -> Result<Value> {
+    if matches!(left, Value::Null) || matches!(right, Value::Null) {
+        return Ok(Value::Null);
+    }
+
+    match op {
+        BinaryOperator::Concat => Ok(Value::String(left.to_string() + &right.to_string())),
+        BinaryOperator::Compare(comparison) => Ok(Value::Boolean(compare(comparison, &left, &right))),
+        _ => arithmetic(op, &left, &right),
+    }
+}
+
+fn arithmetic(op: &BinaryOperator, left: &Value, right: &Value) -> Result<Value> {
+    if let (Value::Integer(a), Value::Integer(b)) = (left, right) {
+        let (a, b) = (*a, *b);
+        let exact = match op {
+            BinaryOperator::Add => a.checked_add(b),
+            BinaryOperator::Subtract => a.checked_sub(b),
+            BinaryOperator::Multiply => a.checked_mul(b),
+            BinaryOperator::Divide | BinaryOperator::Modulo if b == 0 => return Ok(Value::Null),
+            BinaryOperator::Divide => a.checked_div(b),
+            BinaryOperator::Modulo => a.checked_rem(b),
+            _ => None,
+        };
+        if let Some(value) = exact {
+            return Ok(Value::Integer(value));
+        }
+    }
+
+    let operand = |value: &Value| {
+        value
+            .as_number()
+            .ok_or_else(|| anyhow!("Cannot apply '{op}' to non-numeric value '{}'", value.to_string()))
+    };
+    let (a, b) = (operand(left)?, operand(right)?);
+
+    let value = match op {
+        BinaryOperator::Add => a + b,
+        BinaryOperator::Subtract => a - b,
+        BinaryOperator::Multiply => a * b,
+        BinaryOperator::Divide | BinaryOperator::Modulo if b == 0.0 => return Ok(Value::Null),
+        BinaryOperator::Divide => a / b,
+        BinaryOperator::Modulo => a % b,
+        _ => return Err(anyhow!("'{op}' is not an arithmetic operator")),
+    };
+    Ok(Value::Number(value))
+}
+
+fn compare(comparison: &Comparison, left: &Value, right: &Value) -> bool {
+    let ordering = match (left, right) {
+        // Severities compare against the names used in WHERE clauses
+        (Value::Severity(severity), Value::String(name)) => {
+            severity_name(*severity).cmp(name.to_lowercase().as_str())
+        }
+        (Value::String(name), Value::Severity(severity)) => {
+            name.to_lowercase().as_str().cmp(severity_name(*severity))
+        }
+        _ => SortingProcessor::compare_values(left, right),
+    };
+
+    match comparison {
+        Comparison::Equal => ordering == Ordering::Equal,
+        Comparison::NotEqual => ordering != Ordering::Equal,
+        Comparison::GreaterThan => ordering == Ordering::Greater,
+        Comparison::LessThan => ordering == Ordering::Less,
+        Comparison::GreaterThanOrEqual => ordering != Ordering::Less,
+        Comparison::LessThanOrEqual => ordering != Ordering::Greater,
+    }
+}
+
+fn severity_name(severity: DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::Error => "error",
+        DiagnosticSeverity::Warning => "warning",
+        DiagnosticSeverity::Information => "info",
+        DiagnosticSeverity::Hint => "hint",
+    }
+}
+
+fn call(function: ScalarFunction, args: Vec<Value>) -> Result<Value> {
+    let arg = |index: usize| args.get(index).cloned().unwrap_or(Value::Null);
+
+    match function {
+        ScalarFunction::Coalesce => Ok(args
+            .iter()
+            .find(|value| !matches!(value, Value::Null))
+            .cloned()
+            .unwrap_or(Value::Null)),
+        ScalarFunction::Concat => Ok(Value::String(
+            args.iter()
+                .filter(|value| !matches!(value, Value::Null))
+                .map(Value::to_string)
+                .collect(),
+        )),
+        ScalarFunction::NullIf => {
+            let equal = binary(&BinaryOperator::Compare(Comparison::Equal), arg(0), arg(1))?;
+            if equal == Value::Boolean(true) {
+                Ok(Value::Null)
+            } else {
+                Ok(arg(0))
+            }
+        }
+        _ if matches!(arg(0), Value::Null) => Ok(Value::Null),
+        _ => scalar(function, arg(0), arg(1), arg(2)),
+    }
+}
+
+/// Functions that return NULL when their first argument is NULL
+fn scalar(function: ScalarFunction, value: Value, second: Value, third: Value) -> Result<Value> {
+    let numeric = |value: &Value| {
+        value
+            .as_number()
+            .ok_or_else(|| anyhow!("{} expects a number, got '{}'", function.name(), value.to_string()))
+    };
+
+    Ok(match function {
+        ScalarFunction::Upper => Value::String(value.to_string().to_uppercase()),
+        ScalarFunction::Lower => Value::String(value.to_string().to_lowercase()),
+        ScalarFunction::Trim => Value::String(value.to_string().trim().to_string()),
+        ScalarFunction::Length => Value::Integer(value.to_string().chars().count() as i64),
+        ScalarFunction::Substr => {
+            if matches!(second, Value::Null) {
+                return Ok(Value::Null);
+            }
+            // SQL positions are 1-based; positions before the start shorten the length
+            let start = numeric(&second)? as i64;
+            let skip = (start - 1).max(0) as usize;
+            let length = match third {
+                Value::Null => None,
+                length => Some((numeric(&length)? as i64 + start.min(1) - 1).max(0) as usize),
+            };
+            let text = value.to_string();
+            let chars = text.chars().skip(skip);
+            Value::String(match length {
+                Some(length) => chars.take(length).collect(),
+                None => chars.collect(),
+            })
+        }
+        ScalarFunction::Replace => {
+            if matches!(second, Value::Null) || matches!(third, Value::Null) {
+                return Ok(Value::Null);
+            }
+            let from = second.to_string();
+            if from.is_empty() {
+                Value::String(value.to_string())
+            } else {
+                Value::String(value.to_string().replace(&from, &third.to_string()))
+            }
+        }
+        ScalarFunction::Abs => match value {
+            Value::Integer(i) => Value::Integer(i.abs()),
+            other => Value::Number(numeric(&other)?.abs()),
+        },
+        ScalarFunction::Round => {
+            let digits = match second {
+                Value::Null => 0,
+                digits => numeric(&digits)? as i32,
+            };
+            match value {
+                Value::Integer(i) if digits >= 0 => Value::Integer(i),
+                other => {
+                    let scale = 10f64.powi(digits);
+                    Value::Number((numeric(&other)? * scale).round() / scale)
+                }
+            }
+        }
+        ScalarFunction::Coalesce | ScalarFunction::Concat | ScalarFunction::NullIf => {
+            unreachable!("handled by call()")
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::parser::{QueryParser, SelectClause};
+    use std::path::PathBuf;
+
+    fn items(select: &str) -> Vec<SelectItem> {
+        let query = QueryParser::new()
+            .parse_unchecked(&format!("SELECT {select} FROM files"))
+            .unwrap();
+        match query.select {
+            SelectClause::Expressions(items) => items,
+            other => panic!("Expected computed select list, got {other:?}"),
+        }
+    }
+
+    fn file_stats() -> QueryResult {
+        let mut result = QueryResult::empty("files");
+        result.columns = ["file", "errors", "warnings", "total"].map(String::from).to_vec();
+        result.rows = vec![
+            Row::new(vec![
+                Value::Path(PathBuf::from("src/main.rs")),
+                Value::Integer(3),
+                Value::Integer(1),
+                Value::Integer(4),
+            ]),
+            Row::new(vec![
+                Value::Path(PathBuf::from("src/lib.rs")),
+                Value::Integer(0),
+                Value::Integer(0),
+                Value::Integer(0),
+            ]),
+        ];
+        result
+    }
+
+    #[test]
+    fn test_arithmetic_aliases_and_nullif() {
+        let result = project(
+            file_stats(),
+            &items("file, errors + warnings AS total, errors * 1.0 / NULLIF(total, 0) AS error_ratio"),
+        )
+        .unwrap();
+
+        assert_eq!(result.columns, vec!["file", "total", "error_ratio"]);
+        assert_eq!(result.rows[0].values[1], Value::Integer(4));
+        assert_eq!(result.rows[0].values[2], Value::Number(0.75));
+        // Division by zero is NULL rather than an error
+        assert_eq!(result.rows[1].values[2], Value::Null);
+    }
+
+    #[test]
+    fn test_case_and_string_functions() {
+        let result = project(
+            file_stats(),
+            &items(
+                "UPPER(SUBSTR(file, 5, 4)) || '!' AS name, \
+                 CASE WHEN errors > 2 THEN 'hot' WHEN errors > 0 THEN 'warm' ELSE 'cold' END AS heat, \
+                 CASE warnings WHEN 0 THEN 'clean' END AS lint",
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(result.rows[0].values[0], Value::String("MAIN!".to_string()));
+        assert_eq!(result.rows[0].values[1], Value::String("hot".to_string()));
+        assert_eq!(result.rows[0].values[2], Value::Null);
+        assert_eq!(result.rows[1].values[1], Value::String("cold".to_string()));
+        assert_eq!(result.rows[1].values[2], Value::String("clean".to_string()));
+    }
+
+    #[test]
+    fn test_unknown_column_is_an_error() {
+        let error = project(file_stats(), &items("bogus + 1")).unwrap_err();
+        assert!(error.to_string().contains("Unknown column 'bogus'"));
+    }
+}
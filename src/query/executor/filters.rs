@@ -8,9 +8,9 @@ use crate::query::parser::{
     QueryFilter, ComparisonFilter, 
 };
 use crate::query::parser::ast::{
-    CategoryFilter, Comparison, MessageFilter, PathFilter, SeverityFilter,
+    CategoryFilter, Comparison, FullTextFilter, InFilter, MessageFilter, PathFilter, SeverityFilter,
 };
-use super::types::{FileStatistics, Value};
+use super::types::{FileStatistics, QueryResult, Value};
 use crate::core::{Diagnostic, DiagnosticSeverity};
 use anyhow::{anyhow, Result};
 use regex::Regex;
@@ -45,6 +45,10 @@ impl FilterEngine {
                 QueryFilter::Message(message_filter) => {
                     self.filter_diagnostics_by_message(result, message_filter)?
                 }
+                QueryFilter::FullText(text_filter) => {
+                    self.filter_diagnostics_by_text(result, text_filter)?
+                }
+                QueryFilter::In(in_filter) => self.filter_diagnostics_by_membership(result, in_filter)?,
                 _ => result, // Time range and other filters handled elsewhere
             };
         }
@@ -65,9 +69,11 @@ impl FilterEngine {
                 QueryFilter::Path(path_filter) => {
                     self.filter_files_by_path(result, path_filter)?
                 }
-                QueryFilter::FileCount(comparison_filter) => {
+                QueryFilter::FileCount(comparison_filter)
+                | QueryFilter::Comparison(comparison_filter) => {
                     self.filter_files_by_count(result, comparison_filter)?
                 }
+                QueryFilter::In(in_filter) => self.filter_files_by_membership(result, in_filter)?,
                 _ => result, // Other filters not applicable to files
             };
         }
@@ -149,6 +155,105 @@ impl FilterEngine {
         }
     }
 
+    /// Filter diagnostics whose message or code contains every search term
+    fn filter_diagnostics_by_text(
+        &self,
+        diagnostics: Vec<(PathBuf, Diagnostic)>,
+        filter: &FullTextFilter,
+    ) -> Result<Vec<(PathBuf, Diagnostic)>> {
+        Self::validate_pattern_length(&filter.text)?;
+        Ok(diagnostics
+            .into_iter()
+            .filter(|(_, diagnostic)| {
+                filter.matches(&diagnostic.message, diagnostic.code.as_deref())
+            })
+            .collect())
+    }
+
+    /// Filter diagnostics by `IN` list membership
+    fn filter_diagnostics_by_membership(
+        &self,
+        diagnostics: Vec<(PathBuf, Diagnostic)>,
+        filter: &InFilter,
+    ) -> Result<Vec<(PathBuf, Diagnostic)>> {
+        let field = |path: &PathBuf, diagnostic: &Diagnostic| -> Option<String> {
+            Some(match filter.field.as_str() {
+                "file" | "path" => path.display().to_string(),
+                "severity" => format!("{:?}", diagnostic.severity),
+                "category" | "code" => diagnostic.code.clone().unwrap_or_default(),
+                "message" => diagnostic.message.clone(),
+                "source" => diagnostic.source.clone(),
+                "id" => diagnostic.id.clone(),
+                "line" => diagnostic.range.start.line.to_string(),
+                _ => return None,
+            })
+        };
+        if let Some((path, diagnostic)) = diagnostics.first() {
+            if field(path, diagnostic).is_none() {
+                return Err(anyhow!("IN is not supported on diagnostic field: {}", filter.field));
+            }
+        }
+        Ok(diagnostics
+            .into_iter()
+            .filter(|(path, diagnostic)| field(path, diagnostic).is_some_and(|value| filter.matches(&value)))
+            .collect())
+    }
+
+    /// Filter files by `IN` list membership of their path or a count
+    fn filter_files_by_membership(
+        &self,
+        files: Vec<(PathBuf, FileStatistics)>,
+        filter: &InFilter,
+    ) -> Result<Vec<(PathBuf, FileStatistics)>> {
+        let field = |path: &PathBuf, stats: &FileStatistics| -> Option<String> {
+            Some(match filter.field.as_str() {
+                "file" | "path" => path.display().to_string(),
+                field => Self::file_count(stats, field)?.to_string(),
+            })
+        };
+        if !matches!(filter.field.as_str(), "file" | "path")
+            && Self::file_count(&FileStatistics::new(), &filter.field).is_none()
+        {
+            return Err(anyhow!("IN is not supported on file field: {}", filter.field));
+        }
+        Ok(files
+            .into_iter()
+            .filter(|(path, stats)| field(path, stats).is_some_and(|value| filter.matches(&value)))
+            .collect())
+    }
+
+    /// Count column of a file's statistics, by result column or filter name
+    fn file_count(stats: &FileStatistics, field: &str) -> Option<usize> {
+        match field {
+            "errors" | "error_count" => Some(stats.error_count),
+            "warnings" | "warning_count" => Some(stats.warning_count),
+            "total" | "total_count" | "file_count" => Some(stats.total_count),
+            _ => None,
+        }
+    }
+
+    /// Keep rows whose column named by each `IN` filter is in its list
+    ///
+    /// Used for sources whose engines produce rows directly rather than
+    /// filtering diagnostics or file statistics.
+    pub fn retain_membership(mut result: QueryResult, filters: &[QueryFilter]) -> Result<QueryResult> {
+        for filter in filters {
+            let QueryFilter::In(in_filter) = filter else {
+                continue;
+            };
+            let index = result
+                .columns
+                .iter()
+                .position(|column| *column == in_filter.field)
+                .ok_or_else(|| anyhow!("Unknown column for IN: {}", in_filter.field))?;
+            result
+                .rows
+                .retain(|row| row.values.get(index).is_some_and(|value| in_filter.matches(&value.to_string())));
+        }
+        result.total_count = result.rows.len();
+        Ok(result)
+    }
+
     /// Filter files by path pattern
     fn filter_files_by_path(
         &self,
@@ -183,12 +288,7 @@ impl FilterEngine {
         Ok(files
             .into_iter()
             .filter(|(_, stats)| {
-                let actual_count = match filter.field.as_str() {
-                    "error_count" => stats.error_count,
-                    "warning_count" => stats.warning_count,
-                    "total_count" | "file_count" => stats.total_count,
-                    _ => stats.total_count,
-                };
+                let actual_count = Self::file_count(stats, &filter.field).unwrap_or(stats.total_count);
                 
                 Self::compare_numbers(actual_count, target_count, filter.comparison.clone())
             })
@@ -196,7 +296,7 @@ impl FilterEngine {
     }
 
     /// Compare severity levels based on comparison operator
-    fn compare_severity(
+    pub fn compare_severity(
         actual: DiagnosticSeverity,
         target: DiagnosticSeverity,
         comparison: Comparison,
@@ -211,6 +311,18 @@ impl FilterEngine {
         }
     }
 
+    /// Check a floating point value against a numeric comparison filter
+    pub fn matches_comparison(actual: f64, filter: &ComparisonFilter) -> bool {
+        match filter.comparison {
+            Comparison::Equal => (actual - filter.value).abs() < f64::EPSILON,
+            Comparison::NotEqual => (actual - filter.value).abs() >= f64::EPSILON,
+            Comparison::GreaterThan => actual > filter.value,
+            Comparison::LessThan => actual < filter.value,
+            Comparison::GreaterThanOrEqual => actual >= filter.value,
+            Comparison::LessThanOrEqual => actual <= filter.value,
+        }
+    }
+
     /// Compare numeric values based on comparison operator
     fn compare_numbers(actual: usize, target: usize, comparison: Comparison) -> bool {
         match comparison {
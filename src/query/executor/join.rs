@@ -0,0 +1,146 @@
+//! Hash joins between data sources
+//!
+//! Both sides of a JOIN are queried on their own, with the WHERE conditions
+//! the parser attributed to them. The joined source's rows are indexed by
+//! their join column, then every `FROM` row is paired with each matching
+//! row. Result columns are qualified with their side's alias, so `d.file`
+//! and `h.file` stay distinct.
+
+use super::types::{QueryMetadata, QueryResult, Row, Value};
+use crate::query::parser::{JoinClause, JoinKind};
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+
+/// Combine the rows of both sides of `join`
+///
+/// NULL join values never match. A `LEFT JOIN` keeps unmatched `FROM` rows
+/// with NULLs in the joined columns.
+pub fn hash_join(left: QueryResult, right: QueryResult, join: &JoinClause) -> Result<QueryResult> {
+    let left_index = column_index(&left, &join.left_alias, &join.left_column)?;
+    let right_index = column_index(&right, &join.alias, &join.right_column)?;
+
+    let mut matches: HashMap<String, Vec<&Row>> = HashMap::new();
+    for row in &right.rows {
+        if let Some(key) = row.get(right_index).and_then(join_key) {
+            matches.entry(key).or_default().push(row);
+        }
+    }
+
+    let mut rows = Vec::new();
+    for row in &left.rows {
+        let found = row.get(left_index).and_then(join_key).and_then(|key| matches.get(&key));
+        match found {
+            Some(found) => {
+                for other in found {
+                    rows.push(Row::new(row.values.iter().chain(&other.values).cloned().collect()));
+                }
+            }
+            None if join.kind == JoinKind::Left => {
+                let nulls = std::iter::repeat(Value::Null).take(right.columns.len());
+                rows.push(Row::new(row.values.iter().cloned().chain(nulls).collect()));
+            }
+            None => {}
+        }
+    }
+
+    let columns = qualify(&left.columns, &join.left_alias)
+        .chain(qualify(&right.columns, &join.alias))
+        .collect();
+    let metadata = QueryMetadata {
+        data_source: format!("{} JOIN {}", left.metadata.data_source, right.metadata.data_source),
+        filters_applied: left.metadata.filters_applied + right.metadata.filters_applied,
+        rows_scanned: left.metadata.rows_scanned + right.metadata.rows_scanned,
+        cache_hit: false,
+    };
+
+    Ok(QueryResult {
+        columns,
+        total_count: rows.len(),
+        rows,
+        query_time_ms: 0,
+        metadata,
+    })
+}
+
+fn column_index(result: &QueryResult, alias: &str, column: &str) -> Result<usize> {
+    result
+        .columns
+        .iter()
+        .position(|c| c.eq_ignore_ascii_case(column))
+        .ok_or_else(|| {
+            anyhow!(
+                "Unknown join column '{alias}.{column}' (available: {})",
+                result.columns.join(", ")
+            )
+        })
+}
+
+/// Files join whether they were read as paths or strings
+fn join_key(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        value => Some(value.to_string()),
+    }
+}
+
+fn qualify<'a>(columns: &'a [String], alias: &'a str) -> impl Iterator<Item = String> + 'a {
+    columns.iter().map(move |column| format!("{alias}.{column}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::parser::FromClause;
+    use std::path::PathBuf;
+
+    fn result(columns: &[&str], rows: Vec<Vec<Value>>) -> QueryResult {
+        QueryResult {
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            total_count: rows.len(),
+            rows: rows.into_iter().map(Row::new).collect(),
+            query_time_ms: 0,
+            metadata: QueryMetadata {
+                data_source: "test".to_string(),
+                filters_applied: 0,
+                rows_scanned: 0,
+                cache_hit: false,
+            },
+        }
+    }
+
+    #[test]
+    fn test_inner_and_left_join_on_file() -> Result<()> {
+        let left = result(
+            &["file", "message"],
+            vec![
+                vec![Value::Path(PathBuf::from("a.rs")), Value::String("first".to_string())],
+                vec![Value::Path(PathBuf::from("a.rs")), Value::String("second".to_string())],
+                vec![Value::Path(PathBuf::from("b.rs")), Value::String("third".to_string())],
+            ],
+        );
+        let right = result(
+            &["file", "error_count"],
+            vec![vec![Value::String("a.rs".to_string()), Value::Integer(4)]],
+        );
+        let mut join = JoinClause {
+            kind: JoinKind::Inner,
+            left_alias: "d".to_string(),
+            source: FromClause::History,
+            alias: "h".to_string(),
+            left_column: "file".to_string(),
+            right_column: "file".to_string(),
+            filters: Vec::new(),
+        };
+
+        let joined = hash_join(left.clone(), right.clone(), &join)?;
+        assert_eq!(joined.columns, vec!["d.file", "d.message", "h.file", "h.error_count"]);
+        assert_eq!(joined.rows.len(), 2);
+        assert_eq!(joined.rows[1].values[3], Value::Integer(4));
+
+        join.kind = JoinKind::Left;
+        let joined = hash_join(left, right, &join)?;
+        assert_eq!(joined.rows.len(), 3);
+        assert_eq!(joined.rows[2].values[3], Value::Null);
+        Ok(())
+    }
+}
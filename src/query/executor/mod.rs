@@ -12,7 +12,11 @@
 //! - **Engines**: Specialized execution engines for each data source  
 //! - **Filters**: Pattern matching and filtering logic with security validation
 //! - **Processing**: Aggregation, sorting, and grouping utilities
+//! - **Expressions**: Per-row evaluation of computed SELECT columns
+//! - **Join**: Hash joins between diagnostics, files and history
 //! - **Cache**: Result caching with TTL and performance optimization
+//! - **Planner**: Execution plans and row estimates for `EXPLAIN`
+//! - **Arrow**: Arrow IPC serialization of results for dataframe tools
 //!
 //! # Example Usage
 //!
@@ -27,6 +31,9 @@
 //! 
 //! executor.with_diagnostics(diagnostics);
 //!
+//! // Queries take `&self`, so a configured executor can be shared
+//! let executor = std::sync::Arc::new(executor);
+//!
 //! // Execute a query (assuming you have a parsed Query)
 //! // let query = parser.parse("SELECT COUNT(*) FROM diagnostics WHERE severity = 'error'")?;
 //! // let result = executor.execute(&query).await?;
@@ -34,9 +41,13 @@
 //! # }
 //! ```
 
+pub mod arrow;
 pub mod cache;
 pub mod engines;
+pub mod expressions;
 pub mod filters;
+pub mod join;
+pub mod planner;
 pub mod processing;
 pub mod types;
 
@@ -44,15 +55,50 @@ pub mod types;
 pub use types::{FileStatistics, QueryMetadata, QueryResult, Row, Value};
 pub use cache::{CacheStats, QueryCache, QueryCost, CostCategory};
 pub use filters::{FilterEngine, ValueFilter};
-pub use engines::{DiagnosticsEngine, FilesEngine, HistoryEngine, TrendsEngine, EngineFactory, QueryEngine};
+pub use engines::{
+    ConfigEngine, DiagnosticsEngine, FilesEngine, FixesEngine, HistoryEngine, LensesEngine, TrendsEngine,
+    EngineFactory, QueryEngine,
+};
 pub use processing::{AggregationProcessor, SortingProcessor, GroupingProcessor};
+pub use planner::{DiagnosticStatistics, ExecutionPlan};
 
-use crate::core::{DiagnosticResult};
+use crate::core::config::EnvironmentSnapshot;
+use crate::core::telemetry::{self, Operation};
+use crate::core::{CalendarConfig, DiagnosticResult, SymbolIndex};
 use crate::history::HistoryStorage;
-use super::parser::{FromClause, Query};
+use crate::multi_repo::monorepo::BazelTargetMap;
+use super::parser::{Expr, FromClause, InFilter, InList, JoinClause, Query, QueryFilter, SelectClause, SelectItem};
 use anyhow::{anyhow, Result};
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
 use std::time::Instant;
 
+fn loaded(diagnostics: Option<&DiagnosticResult>) -> Result<&DiagnosticResult> {
+    diagnostics.ok_or_else(|| anyhow!("No diagnostics loaded"))
+}
+
+/// Drop rows whose `file` column is not accepted by `allow`
+fn restrict_rows(
+    mut result: QueryResult,
+    allow: &(dyn Fn(&Path) -> bool + Send + Sync),
+) -> Result<QueryResult> {
+    let file_column = result
+        .columns
+        .iter()
+        .position(|c| c == "file" || c == "path")
+        .ok_or_else(|| anyhow!("Forbidden: this query spans files outside your ownership"))?;
+
+    result.rows.retain(|row| match row.values.get(file_column) {
+        Some(Value::Path(path)) => allow(path),
+        Some(Value::String(path)) => allow(Path::new(path)),
+        _ => false,
+    });
+    result.total_count = result.rows.len();
+    Ok(result)
+}
+
 /// Main query executor that coordinates all components
 ///
 /// The QueryExecutor serves as the central coordinator for query execution,
@@ -68,12 +114,20 @@ use std::time::Instant;
 ///
 /// # Thread Safety
 ///
-/// QueryExecutor is not thread-safe by default. If you need to use it across
-/// threads, wrap it in appropriate synchronization primitives.
+/// QueryExecutor is `Send + Sync`. Configuration (`with_*`) needs `&mut self`,
+/// but execution only takes `&self`: loaded data is held in an immutable
+/// `Arc` and the result cache uses interior mutability, so read-only queries
+/// can run concurrently from an `Arc<QueryExecutor>` or under a shared lock.
 pub struct QueryExecutor {
-    diagnostic_cache: Option<DiagnosticResult>,
-    history_storage: Option<HistoryStorage>,
+    diagnostic_cache: Option<Arc<DiagnosticResult>>,
+    history_storage: Option<Arc<HistoryStorage>>,
+    environment: Option<Arc<EnvironmentSnapshot>>,
+    symbol_index: Option<Arc<SymbolIndex>>,
     query_cache: QueryCache,
+    /// Per-file counts of the loaded diagnostics, for the planner
+    statistics: Option<DiagnosticStatistics>,
+    /// Row counts of the last executed query of each shape, for the planner
+    observed_rows: Mutex<HashMap<String, planner::ObservedRows>>,
     diagnostics_engine: DiagnosticsEngine,
     files_engine: FilesEngine,
     history_engine: HistoryEngine,
@@ -88,7 +142,11 @@ impl QueryExecutor {
         Self {
             diagnostic_cache: None,
             history_storage: None,
+            environment: None,
+            symbol_index: None,
             query_cache: QueryCache::new(),
+            statistics: None,
+            observed_rows: Mutex::new(HashMap::new()),
             diagnostics_engine: DiagnosticsEngine::new(),
             files_engine: FilesEngine::new(),
             history_engine: HistoryEngine::new(),
@@ -106,7 +164,11 @@ impl QueryExecutor {
         Self {
             diagnostic_cache: None,
             history_storage: None,
+            environment: None,
+            symbol_index: None,
             query_cache: QueryCache::with_settings(cache_ttl_secs, max_cache_entries),
+            statistics: None,
+            observed_rows: Mutex::new(HashMap::new()),
             diagnostics_engine: DiagnosticsEngine::new(),
             files_engine: FilesEngine::new(),
             history_engine: HistoryEngine::new(),
@@ -118,13 +180,75 @@ impl QueryExecutor {
     ///
     /// This data will be used for diagnostics and files queries.
     pub fn with_diagnostics(&mut self, diagnostics: DiagnosticResult) -> &mut Self {
+        self.with_shared_diagnostics(Arc::new(diagnostics))
+    }
+
+    /// Set diagnostic data shared with other executors or callers
+    ///
+    /// Swapping in a new snapshot invalidates cached results.
+    pub fn with_shared_diagnostics(&mut self, diagnostics: Arc<DiagnosticResult>) -> &mut Self {
+        self.statistics = Some(DiagnosticStatistics::collect(&diagnostics));
         self.diagnostic_cache = Some(diagnostics);
+        self.invalidate();
+        self
+    }
+
+    /// Set the Bazel target map used for the `target` field
+    ///
+    /// Enables filtering (`WHERE target = '//pkg/...'`) and grouping
+    /// (`GROUP BY target`) of diagnostics by their owning Bazel target.
+    pub fn with_bazel_targets(&mut self, targets: BazelTargetMap) -> &mut Self {
+        self.diagnostics_engine.set_targets(Arc::new(targets));
+        self.invalidate();
         self
     }
 
+    /// Diagnostic data currently loaded for queries
+    pub fn diagnostics(&self) -> Option<&DiagnosticResult> {
+        self.diagnostic_cache.as_deref()
+    }
+
+    /// Shared handle to the loaded diagnostic data
+    pub fn shared_diagnostics(&self) -> Option<Arc<DiagnosticResult>> {
+        self.diagnostic_cache.clone()
+    }
+
     /// Set history storage for historical queries
     pub fn with_history(&mut self, history: HistoryStorage) -> &mut Self {
+        self.with_shared_history(Arc::new(history))
+    }
+
+    /// Set history storage shared with its owner, such as a daemon's own connection
+    pub fn with_shared_history(&mut self, history: Arc<HistoryStorage>) -> &mut Self {
         self.history_storage = Some(history);
+        self.invalidate();
+        self
+    }
+
+    /// Set the calendar used to resolve `LAST 7 DAYS` and `THIS WEEK`
+    ///
+    /// Relative ranges start at calendar boundaries in the configured time
+    /// zone rather than at UTC midnight.
+    pub fn with_calendar(&mut self, calendar: CalendarConfig) -> &mut Self {
+        self.history_engine.set_calendar(calendar);
+        self.invalidate();
+        self
+    }
+
+    /// Set the configuration and environment snapshot for `config` queries
+    pub fn with_environment(&mut self, environment: EnvironmentSnapshot) -> &mut Self {
+        self.environment = Some(Arc::new(environment));
+        self.invalidate();
+        self
+    }
+
+    /// Set the workspace symbol index for `symbols` queries
+    ///
+    /// Without one, `symbols` falls back to the symbols mentioned by loaded
+    /// diagnostics.
+    pub fn with_symbol_index(&mut self, index: Arc<SymbolIndex>) -> &mut Self {
+        self.symbol_index = Some(index);
+        self.invalidate();
         self
     }
 
@@ -147,63 +271,256 @@ impl QueryExecutor {
     /// - Results are automatically cached based on query structure
     /// - Expensive queries are identified and can be optimized
     /// - Filter validation prevents regex DoS attacks
-    pub async fn execute(&mut self, query: &Query) -> Result<QueryResult> {
+    #[tracing::instrument(name = "query", skip_all, fields(from = ?query.from))]
+    pub async fn execute(&self, query: &Query) -> Result<QueryResult> {
+        let start_time = Instant::now();
+        record_query(start_time, self.execute_cached(query).await)
+    }
+
+    /// Answer `query` from the result cache, or run it and cache the result
+    async fn execute_cached(&self, query: &Query) -> Result<QueryResult> {
         let start_time = Instant::now();
 
         // Validate query safety
         if let Err(warnings) = cache::QueryValidator::validate_query_safety(query) {
-            println!("Query performance warnings: {:?}", warnings);
+            tracing::warn!("Query performance warnings: {:?}", warnings);
         }
 
         // Check cache first
         let cache_key = cache::QueryValidator::generate_cache_key(query);
         if let Some(cached_result) = self.query_cache.get(&cache_key) {
-            println!("Query cache hit for key: {}", cache_key);
+            tracing::debug!("Query cache hit for key: {}", cache_key);
             return Ok(cached_result);
         }
 
-        // Execute query based on data source
-        let mut result = match &query.from {
-            FromClause::Diagnostics => self.execute_diagnostics_query(query).await?,
-            FromClause::Files => self.execute_files_query(query).await?,
-            FromClause::History => self.execute_history_query(query).await?,
-            FromClause::Trends => self.execute_trends_query(query).await?,
-            FromClause::Symbols => self.execute_symbols_query(query).await?,
-            FromClause::References => self.execute_references_query(query).await?,
-            FromClause::Projects => self.execute_projects_query(query).await?,
-        };
-
-        // Apply post-processing
-        result = self.apply_post_processing(result, query)?;
+        let mut result = self.run(query, self.diagnostic_cache.as_deref()).await?;
 
         // Set execution time
         result.query_time_ms = start_time.elapsed().as_millis() as u64;
 
-        // Cache the result
+        // Cache the result and remember its size for planning
+        if query.join.is_none() {
+            self.observed_rows().insert(
+                cache::QueryKeyGenerator::generate_pattern_key(query),
+                planner::ObservedRows {
+                    scanned: result.metadata.rows_scanned,
+                    returned: result.total_count,
+                },
+            );
+        }
         self.query_cache.insert(cache_key, result.clone());
-        println!("Cached query result with {} rows", result.rows.len());
+        tracing::debug!("Cached query result with {} rows", result.rows.len());
 
         Ok(result)
     }
 
-    /// Execute a query against diagnostic data
-    async fn execute_diagnostics_query(&self, query: &Query) -> Result<QueryResult> {
-        let diagnostics = self
-            .diagnostic_cache
-            .as_ref()
-            .ok_or_else(|| anyhow!("No diagnostics loaded"))?;
+    /// Execute a query over the subset of files accepted by `allow`
+    ///
+    /// Diagnostic-based sources (diagnostics, files, symbols, references,
+    /// projects) run against the restricted data, so aggregates only count
+    /// visible files. History and trend rows are filtered by their `file`
+    /// column; results without one cannot be restricted and are refused.
+    /// Configuration spans the whole install and is refused.
+    /// Restricted results are never cached.
+    #[tracing::instrument(name = "query", skip_all, fields(from = ?query.from, restricted = true))]
+    pub async fn execute_restricted(
+        &self,
+        query: &Query,
+        allow: &(dyn Fn(&Path) -> bool + Send + Sync),
+    ) -> Result<QueryResult> {
+        let start_time = Instant::now();
+        record_query(start_time, self.run_restricted(query, allow).await)
+    }
+
+    async fn run_restricted(
+        &self,
+        query: &Query,
+        allow: &(dyn Fn(&Path) -> bool + Send + Sync),
+    ) -> Result<QueryResult> {
+        let start_time = Instant::now();
+
+        let indexed = self.symbol_index.is_some();
+        let privileged = |source: &FromClause| {
+            matches!(source, FromClause::History | FromClause::Config) || (indexed && *source == FromClause::Symbols)
+        };
+        if query.subqueries().any(|subquery| privileged(&subquery.from)) {
+            return Err(anyhow!(
+                "Forbidden: subqueries over history, configuration or the symbol index require unrestricted access"
+            ));
+        }
 
-        self.diagnostics_engine.execute(query, diagnostics).await
+        let mut result = match &query.from {
+            _ if query.join.is_some() && (query.from == FromClause::History || query.join.as_ref().is_some_and(|j| j.source == FromClause::History)) => {
+                return Err(anyhow!("Forbidden: joins with history require unrestricted access"));
+            }
+            FromClause::Config => {
+                return Err(anyhow!("Forbidden: configuration queries require unrestricted access"));
+            }
+            FromClause::Symbols if indexed => {
+                let result = self.run(query, self.diagnostic_cache.as_deref()).await?;
+                restrict_rows(result, allow)?
+            }
+            FromClause::History | FromClause::Trends => {
+                let result = self.run(query, self.diagnostic_cache.as_deref()).await?;
+                restrict_rows(result, allow)?
+            }
+            _ => {
+                let restricted = self
+                    .diagnostic_cache
+                    .as_deref()
+                    .map(|d| crate::query::api::authorization::restrict_diagnostics(d, allow));
+                self.run(query, restricted.as_ref()).await?
+            }
+        };
+
+        result.query_time_ms = start_time.elapsed().as_millis() as u64;
+        Ok(result)
     }
 
-    /// Execute a query against file statistics
-    async fn execute_files_query(&self, query: &Query) -> Result<QueryResult> {
-        let diagnostics = self
-            .diagnostic_cache
-            .as_ref()
-            .ok_or_else(|| anyhow!("No diagnostics loaded"))?;
+    /// Execute a query against its data source and apply post-processing
+    ///
+    /// Diagnostic-based sources read from `diagnostics` rather than the loaded
+    /// data, so restricted queries can pass a filtered view without touching
+    /// shared state.
+    fn run<'a>(&'a self, query: &'a Query, diagnostics: Option<&'a DiagnosticResult>) -> BoxFuture<'a, Result<QueryResult>> {
+        // Boxed because subqueries run through here too
+        Box::pin(async move {
+            let resolved;
+            let query = if query.subqueries().next().is_some() {
+                resolved = self.resolve_subqueries(query, diagnostics).await?;
+                &resolved
+            } else {
+                query
+            };
+
+            let result = match &query.join {
+                Some(join) => self.run_join(query, join, diagnostics).await?,
+                None => self.run_source(query, diagnostics).await?,
+            };
+            self.apply_post_processing(result, query)
+        })
+    }
 
-        self.files_engine.execute(query, diagnostics).await
+    /// Replace each `IN (SELECT ...)` with the values its subquery returns
+    ///
+    /// Subqueries run against the same data as the outer query and must
+    /// return a single column.
+    async fn resolve_subqueries(&self, query: &Query, diagnostics: Option<&DiagnosticResult>) -> Result<Query> {
+        let mut resolved = query.clone();
+        let join_filters = resolved.join.iter_mut().flat_map(|join| join.filters.iter_mut());
+        for filter in resolved.filters.iter_mut().chain(join_filters) {
+            let QueryFilter::In(InFilter { field, list, .. }) = filter else {
+                continue;
+            };
+            let InList::Subquery(subquery) = list else {
+                continue;
+            };
+            let result = self.run(subquery, diagnostics).await?;
+            if result.columns.len() != 1 {
+                return Err(anyhow!(
+                    "Subquery for {field} IN (...) must select one column, got {}",
+                    result.columns.len()
+                ));
+            }
+            let values = result
+                .rows
+                .iter()
+                .filter_map(|row| row.values.first())
+                .filter(|value| **value != Value::Null)
+                .map(Value::to_string)
+                .collect();
+            *list = InList::Values(values);
+        }
+        Ok(resolved)
+    }
+
+    /// Execute a query against its data source, without post-processing
+    async fn run_source(&self, query: &Query, diagnostics: Option<&DiagnosticResult>) -> Result<QueryResult> {
+        let result = match &query.from {
+            FromClause::Diagnostics => self.diagnostics_engine.execute(query, loaded(diagnostics)?).await?,
+            FromClause::Files => self.files_engine.execute(query, loaded(diagnostics)?).await?,
+            FromClause::History => self.execute_history_query(query).await?,
+            FromClause::Trends => self.execute_trends_query(query).await?,
+            FromClause::Symbols => match &self.symbol_index {
+                Some(index) => engines::SymbolsEngine::new().execute_index(query, index).await?,
+                None => engines::SymbolsEngine::new().execute(query, loaded(diagnostics)?).await?,
+            },
+            FromClause::References => {
+                engines::ReferencesEngine::new().execute(query, loaded(diagnostics)?).await?
+            }
+            FromClause::Projects => engines::ProjectsEngine::new().execute(query, loaded(diagnostics)?).await?,
+            FromClause::Fixes => engines::FixesEngine::new().execute(query, loaded(diagnostics)?).await?,
+            FromClause::Lenses => engines::LensesEngine::new().execute(query, loaded(diagnostics)?).await?,
+            FromClause::Config => {
+                let environment = self
+                    .environment
+                    .as_deref()
+                    .ok_or_else(|| anyhow!("No configuration snapshot loaded"))?;
+                engines::ConfigEngine::new().execute(query, environment).await?
+            }
+        };
+        // Diagnostic and file sources apply IN filters as they filter; the others by result column
+        match query.from {
+            FromClause::Diagnostics | FromClause::Files | FromClause::Symbols | FromClause::References | FromClause::Fixes => {
+                Ok(result)
+            }
+            _ => FilterEngine::retain_membership(result, &query.filters),
+        }
+    }
+
+    /// Execute both sides of a join and combine their rows
+    ///
+    /// Each side runs as `SELECT *` with its own filters; history contributes
+    /// the latest snapshot of each file. `SELECT COUNT(*)` and plain column
+    /// lists are answered here, everything else by post-processing.
+    async fn run_join(&self, query: &Query, join: &JoinClause, diagnostics: Option<&DiagnosticResult>) -> Result<QueryResult> {
+        let left = Query {
+            from: query.from.clone(),
+            filters: query.filters.clone(),
+            time_range: query.time_range.clone(),
+            ..Query::new()
+        };
+        let right = Query {
+            from: join.source.clone(),
+            filters: join.filters.clone(),
+            ..Query::new()
+        };
+        let left = self.run_join_side(&left, diagnostics).await?;
+        let right = self.run_join_side(&right, diagnostics).await?;
+        let result = join::hash_join(left, right, join)?;
+
+        match &query.select {
+            SelectClause::Count => Ok(QueryResult::single_value(
+                &result.metadata.data_source,
+                "count",
+                Value::Integer(result.rows.len() as i64),
+            )),
+            SelectClause::Fields(fields) if processing::AggregationProcessor::output_columns(query).is_none() => {
+                let items: Vec<SelectItem> = fields
+                    .iter()
+                    .map(|field| SelectItem {
+                        expr: Expr::Column(field.clone()),
+                        alias: None,
+                    })
+                    .collect();
+                expressions::project(result, &items)
+            }
+            _ => Ok(result),
+        }
+    }
+
+    async fn run_join_side(&self, query: &Query, diagnostics: Option<&DiagnosticResult>) -> Result<QueryResult> {
+        match query.from {
+            FromClause::History => {
+                let history = self
+                    .history_storage
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("History storage not available"))?;
+                let result = self.history_engine.latest_snapshots(query, history).await?;
+                FilterEngine::retain_membership(result, &query.filters)
+            }
+            _ => self.run_source(query, diagnostics).await,
+        }
     }
 
     /// Execute a query against historical data
@@ -226,41 +543,41 @@ impl QueryExecutor {
         self.trends_engine.execute(query, history).await
     }
 
-    /// Execute a query against symbol data
-    async fn execute_symbols_query(&self, query: &Query) -> Result<QueryResult> {
-        let diagnostics = self
-            .diagnostic_cache
-            .as_ref()
-            .ok_or_else(|| anyhow!("No diagnostics loaded"))?;
-        
-        let engine = engines::SymbolsEngine::new();
-        engine.execute(query, diagnostics).await
-    }
-
-    /// Execute a query against reference data
-    async fn execute_references_query(&self, query: &Query) -> Result<QueryResult> {
-        let diagnostics = self
-            .diagnostic_cache
-            .as_ref()
-            .ok_or_else(|| anyhow!("No diagnostics loaded"))?;
-        
-        let engine = engines::ReferencesEngine::new();
-        engine.execute(query, diagnostics).await
-    }
+    /// Apply post-processing operations (computed columns, sorting, limiting)
+    ///
+    /// Computed columns are evaluated before sorting so `ORDER BY` can use
+    /// their aliases.
+    fn apply_post_processing(&self, mut result: QueryResult, query: &Query) -> Result<QueryResult> {
+        // File, history and joined rows have fixed columns, so they are grouped
+        // and aggregated here; the diagnostics engine aggregates as it extracts fields
+        if matches!(query.from, FromClause::Files | FromClause::History) || query.join.is_some() {
+            if let Some(select) = processing::AggregationProcessor::output_columns(query) {
+                let group_by = query.group_by.as_ref().map(|g| g.fields.as_slice()).unwrap_or_default();
+                let (columns, rows) = processing::AggregationProcessor::aggregate_rows(
+                    &result.columns,
+                    &result.rows,
+                    &select,
+                    group_by,
+                )?;
+                result.columns = columns;
+                result.rows = rows;
+            } else if let (FromClause::Files, SelectClause::Fields(fields)) = (&query.from, &query.select) {
+                // File rows always carry every count, so plain column lists are projected here
+                let items: Vec<SelectItem> = fields
+                    .iter()
+                    .map(|field| SelectItem {
+                        expr: Expr::Column(field.clone()),
+                        alias: None,
+                    })
+                    .collect();
+                result = expressions::project(result, &items)?;
+            }
+        }
 
-    /// Execute a query against project data
-    async fn execute_projects_query(&self, query: &Query) -> Result<QueryResult> {
-        let diagnostics = self
-            .diagnostic_cache
-            .as_ref()
-            .ok_or_else(|| anyhow!("No diagnostics loaded"))?;
-        
-        let engine = engines::ProjectsEngine::new();
-        engine.execute(query, diagnostics).await
-    }
+        if let SelectClause::Expressions(items) = &query.select {
+            result = expressions::project(result, items)?;
+        }
 
-    /// Apply post-processing operations (sorting, limiting)
-    fn apply_post_processing(&self, mut result: QueryResult, query: &Query) -> Result<QueryResult> {
         // Apply sorting if specified
         if let Some(order_by) = &query.order_by {
             processing::SortingProcessor::apply_sorting(&mut result.rows, &result.columns, order_by)?;
@@ -281,13 +598,23 @@ impl QueryExecutor {
         self.query_cache.stats()
     }
 
+    /// Drop cached results and planner observations after the data changed
+    fn invalidate(&self) {
+        self.query_cache.clear();
+        self.observed_rows().clear();
+    }
+
+    fn observed_rows(&self) -> MutexGuard<'_, HashMap<String, planner::ObservedRows>> {
+        self.observed_rows.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
     /// Clear query cache
-    pub fn clear_cache(&mut self) {
+    pub fn clear_cache(&self) {
         self.query_cache.clear();
     }
 
     /// Configure cache settings
-    pub fn configure_cache(&mut self, ttl_secs: u64, max_entries: usize) {
+    pub fn configure_cache(&self, ttl_secs: u64, max_entries: usize) {
         self.query_cache.set_ttl(ttl_secs);
         self.query_cache.set_max_entries(max_entries);
     }
@@ -338,6 +665,13 @@ impl Default for QueryExecutor {
     }
 }
 
+/// Report a finished query to telemetry and pass its result through
+fn record_query(start_time: Instant, result: Result<QueryResult>) -> Result<QueryResult> {
+    let rows = result.as_ref().map_or(0, |result| result.rows.len());
+    telemetry::record_operation(Operation::Query, start_time.elapsed(), rows, result.is_ok());
+    result
+}
+
 /// Convenience function for executing a query
 ///
 /// Creates a temporary executor and executes the query.
@@ -417,6 +751,7 @@ mod tests {
             order_by: None,
             limit: None,
             time_range: None,
+            join: None,
         };
 
         let result = executor.execute(&query).await.unwrap();
@@ -452,6 +787,7 @@ mod tests {
             order_by: None,
             limit: None,
             time_range: None,
+            join: None,
         };
 
         let result = executor.execute(&query).await.unwrap();
@@ -460,6 +796,205 @@ mod tests {
         assert_eq!(result.columns, vec!["file", "errors", "warnings", "total"]);
     }
 
+    #[tokio::test]
+    async fn test_executor_in_subquery() {
+        let mut executor = QueryExecutor::new();
+
+        let mut diagnostics = DiagnosticResult::new();
+        diagnostics.diagnostics.insert(
+            PathBuf::from("a.rs"),
+            vec![create_test_diagnostic(DiagnosticSeverity::Warning, "Warning 1")],
+        );
+        diagnostics.diagnostics.insert(
+            PathBuf::from("b.rs"),
+            vec![
+                create_test_diagnostic(DiagnosticSeverity::Error, "Error 1"),
+                create_test_diagnostic(DiagnosticSeverity::Error, "Error 2"),
+                create_test_diagnostic(DiagnosticSeverity::Warning, "Warning 2"),
+            ],
+        );
+        executor.with_diagnostics(diagnostics);
+        let parser = crate::query::parser::QueryParser::new();
+
+        let query = parser
+            .parse("SELECT file, message FROM diagnostics WHERE file IN (SELECT file FROM files WHERE errors > 1) AND severity = warning")
+            .unwrap();
+        let result = executor.execute(&query).await.unwrap();
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0].values[1], Value::String("Warning 2".to_string()));
+
+        let query = parser
+            .parse("SELECT COUNT(*) FROM diagnostics WHERE file NOT IN (SELECT file FROM files WHERE errors > 1)")
+            .unwrap();
+        let result = executor.execute(&query).await.unwrap();
+        assert_eq!(result.rows[0].values[0], Value::Integer(1));
+
+        let query = parser.parse("SELECT * FROM files WHERE file IN ('a.rs') OR warnings IN (5, 1)").unwrap();
+        let result = executor.execute(&query).await.unwrap();
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0].values[0], Value::Path(PathBuf::from("a.rs")));
+
+        let query = parser
+            .parse("SELECT * FROM diagnostics WHERE file IN (SELECT * FROM files)")
+            .unwrap();
+        assert!(executor.execute(&query).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_executor_computed_columns_order_by_alias() {
+        let mut executor = QueryExecutor::new();
+
+        let mut diagnostics = DiagnosticResult::new();
+        diagnostics.diagnostics.insert(
+            PathBuf::from("a.rs"),
+            vec![create_test_diagnostic(DiagnosticSeverity::Warning, "Warning 1")],
+        );
+        diagnostics.diagnostics.insert(
+            PathBuf::from("b.rs"),
+            vec![
+                create_test_diagnostic(DiagnosticSeverity::Error, "Error 1"),
+                create_test_diagnostic(DiagnosticSeverity::Warning, "Warning 2"),
+            ],
+        );
+        executor.with_diagnostics(diagnostics);
+
+        let query = crate::query::parser::QueryParser::new()
+            .parse("SELECT file, errors * 1.0 / NULLIF(total, 0) AS error_ratio FROM files ORDER BY error_ratio DESC")
+            .unwrap();
+
+        let result = executor.execute(&query).await.unwrap();
+        assert_eq!(result.columns, vec!["file", "error_ratio"]);
+        assert_eq!(result.rows[0].values, vec![Value::Path(PathBuf::from("b.rs")), Value::Number(0.5)]);
+        assert_eq!(result.rows[1].values, vec![Value::Path(PathBuf::from("a.rs")), Value::Number(0.0)]);
+    }
+
+    #[tokio::test]
+    async fn test_executor_joins_diagnostics_with_history() {
+        use crate::core::FileHash;
+        use crate::history::{DiagnosticSnapshot, HistoryConfig};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let history = HistoryStorage::new(HistoryConfig {
+            db_path: temp_dir.path().join("history.db"),
+            ..HistoryConfig::default()
+        })
+        .await
+        .unwrap();
+        for (file, error_count) in [("a.rs", 7), ("b.rs", 1)] {
+            history
+                .record_snapshot(DiagnosticSnapshot {
+                    id: 0,
+                    timestamp: std::time::SystemTime::now(),
+                    file_path: PathBuf::from(file),
+                    file_hash: FileHash::new(file.as_bytes()),
+                    diagnostics: Vec::new(),
+                    error_count,
+                    warning_count: 0,
+                    info_count: 0,
+                    hint_count: 0,
+                })
+                .await
+                .unwrap();
+        }
+
+        let mut diagnostics = DiagnosticResult::new();
+        for file in ["a.rs", "b.rs", "c.rs"] {
+            diagnostics.diagnostics.insert(
+                PathBuf::from(file),
+                vec![create_test_diagnostic(DiagnosticSeverity::Error, "Error 1")],
+            );
+        }
+        let mut executor = QueryExecutor::new();
+        executor.with_diagnostics(diagnostics).with_history(history);
+
+        let parser = crate::query::parser::QueryParser::new();
+        let query = parser
+            .parse("SELECT d.file, h.error_count FROM diagnostics d JOIN history h ON d.file = h.file ORDER BY h.error_count DESC")
+            .unwrap();
+        let result = executor.execute(&query).await.unwrap();
+        assert_eq!(result.columns, vec!["d.file", "h.error_count"]);
+        assert_eq!(result.rows.len(), 2);
+        assert_eq!(result.rows[0].values, vec![Value::Path(PathBuf::from("a.rs")), Value::Integer(7)]);
+
+        let query = parser
+            .parse("SELECT COUNT(*) FROM files f LEFT JOIN history h ON f.file = h.file WHERE h.error_count > 5")
+            .unwrap();
+        let result = executor.execute(&query).await.unwrap();
+        assert_eq!(result.rows[0].values[0], Value::Integer(3));
+
+        let query = parser
+            .parse("SELECT SUM(h.error_count) FROM files f JOIN history h ON f.file = h.file")
+            .unwrap();
+        let result = executor.execute(&query).await.unwrap();
+        assert_eq!(result.rows[0].values[0], Value::Integer(8));
+    }
+
+    #[tokio::test]
+    async fn test_executor_aggregation_functions() {
+        let mut executor = QueryExecutor::new();
+
+        let at_line = |severity, message: &str, line: u32, end: u32| {
+            let mut diagnostic = create_test_diagnostic(severity, message);
+            diagnostic.range.start.line = line;
+            diagnostic.range.end.line = end;
+            diagnostic
+        };
+        let mut diagnostics = DiagnosticResult::new();
+        diagnostics.diagnostics.insert(
+            PathBuf::from("a.rs"),
+            vec![
+                at_line(DiagnosticSeverity::Error, "E1", 10, 12),
+                at_line(DiagnosticSeverity::Error, "E1", 20, 20),
+                at_line(DiagnosticSeverity::Warning, "W1", 30, 33),
+            ],
+        );
+        diagnostics.diagnostics.insert(
+            PathBuf::from("b.rs"),
+            vec![at_line(DiagnosticSeverity::Warning, "W2", 5, 5)],
+        );
+        executor.with_diagnostics(diagnostics);
+        let parser = crate::query::parser::QueryParser::new();
+
+        let query = parser
+            .parse(
+                "SELECT file, count(*), AVG(line), SUM(line_count), MAX(severity), DISTINCT_COUNT(message), \
+                 PERCENTILE(line, 50) FROM diagnostics GROUP BY file ORDER BY COUNT(*) DESC",
+            )
+            .unwrap();
+        let result = executor.execute(&query).await.unwrap();
+        assert_eq!(
+            result.columns,
+            vec!["file", "COUNT(*)", "AVG(line)", "SUM(line_count)", "MAX(severity)", "DISTINCT_COUNT(message)", "PERCENTILE(line, 50)"]
+        );
+        assert_eq!(
+            result.rows[0].values,
+            vec![
+                Value::Path(PathBuf::from("a.rs")),
+                Value::Integer(3),
+                Value::Number(20.0),
+                Value::Integer(8),
+                Value::Severity(DiagnosticSeverity::Warning),
+                Value::Integer(2),
+                Value::Number(20.0),
+            ]
+        );
+
+        // Without GROUP BY everything is one group, and computed columns can wrap aggregates
+        let query = parser
+            .parse("SELECT COUNT(*) AS n, ROUND(AVG(line), 1) AS mean, PERCENTILE(line, 90) FROM diagnostics")
+            .unwrap();
+        let result = executor.execute(&query).await.unwrap();
+        assert_eq!(result.columns, vec!["n", "mean", "PERCENTILE(line, 90)"]);
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0].values[0], Value::Integer(4));
+        assert_eq!(result.rows[0].values[1], Value::Number(16.3));
+        assert_eq!(result.rows[0].values[2], Value::Number(27.0));
+
+        let query = parser.parse("SELECT SUM(errors), AVG(total) FROM files").unwrap();
+        let result = executor.execute(&query).await.unwrap();
+        assert_eq!(result.rows[0].values, vec![Value::Integer(2), Value::Number(2.0)]);
+    }
+
     #[tokio::test]
     async fn test_executor_caching() {
         let mut executor = QueryExecutor::new();
@@ -480,6 +1015,7 @@ mod tests {
             order_by: None,
             limit: None,
             time_range: None,
+            join: None,
         };
 
         // First execution should not be cached
@@ -514,6 +1050,49 @@ mod tests {
         assert_eq!(new_stats.max_entries, 500);
     }
 
+    #[tokio::test]
+    async fn test_shared_executor_runs_queries_concurrently() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<QueryExecutor>();
+
+        let mut diagnostics = DiagnosticResult::new();
+        diagnostics.diagnostics.insert(
+            PathBuf::from("test.rs"),
+            vec![
+                create_test_diagnostic(DiagnosticSeverity::Error, "Error"),
+                create_test_diagnostic(DiagnosticSeverity::Warning, "Warning"),
+            ],
+        );
+        let mut executor = QueryExecutor::new();
+        executor.with_shared_diagnostics(Arc::new(diagnostics));
+        let executor = Arc::new(executor);
+
+        let tasks: Vec<_> = [FromClause::Diagnostics, FromClause::Files, FromClause::Diagnostics]
+            .into_iter()
+            .map(|from| {
+                let executor = executor.clone();
+                tokio::spawn(async move {
+                    let query = Query {
+                        select: SelectClause::Count,
+                        from,
+                        filters: vec![],
+                        group_by: None,
+                        order_by: None,
+                        limit: None,
+                        time_range: None,
+                        join: None,
+                    };
+                    executor.execute(&query).await
+                })
+            })
+            .collect();
+
+        for task in tasks {
+            assert!(task.await.unwrap().is_ok());
+        }
+        assert_eq!(executor.cache_stats().total_entries, 2);
+    }
+
     #[tokio::test]
     async fn test_convenience_functions() {
         let mut diagnostics = DiagnosticResult::new();
@@ -530,6 +1109,7 @@ mod tests {
             order_by: None,
             limit: None,
             time_range: None,
+            join: None,
         };
 
         let result = execute_query(&query, diagnostics).await.unwrap();
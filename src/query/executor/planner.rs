@@ -0,0 +1,503 @@
+//! Execution plans for `EXPLAIN`
+//!
+//! [`QueryExecutor::plan`] describes how a query would run without running
+//! it: which engine answers it, how many rows it reads and returns, whether
+//! the result cache or an index is used, and which optimizations apply.
+//!
+//! Row estimates come from statistics the executor keeps about its data.
+//! Loaded diagnostics are summarized per file and severity when they are
+//! loaded, so path and severity filters are estimated exactly; other
+//! filters are assumed to keep [`DEFAULT_SELECTIVITY`] of their input.
+//! Sources without loaded statistics, such as history, are estimated from
+//! the row counts of the last query of the same shape.
+
+use super::cache::{QueryKeyGenerator, QueryValidator};
+use super::processing::AggregationProcessor;
+use super::types::FileStatistics;
+use super::{FilterEngine, QueryExecutor};
+use crate::core::{DiagnosticResult, DiagnosticSeverity};
+use crate::query::parser::{FromClause, InList, Query, QueryFilter, SelectClause};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// Fraction of rows a filter without statistics is assumed to keep
+pub const DEFAULT_SELECTIVITY: f64 = 0.25;
+
+const SEVERITIES: [DiagnosticSeverity; 4] = [
+    DiagnosticSeverity::Error,
+    DiagnosticSeverity::Warning,
+    DiagnosticSeverity::Information,
+    DiagnosticSeverity::Hint,
+];
+
+/// Counts kept about loaded diagnostics for row estimates
+#[derive(Debug, Clone, Default)]
+pub struct DiagnosticStatistics {
+    /// Diagnostics per file and severity
+    pub files: Vec<(PathBuf, FileStatistics)>,
+    pub total: usize,
+    pub distinct_sources: usize,
+    pub distinct_codes: usize,
+}
+
+impl DiagnosticStatistics {
+    pub fn collect(diagnostics: &DiagnosticResult) -> Self {
+        let mut sources = HashSet::new();
+        let mut codes = HashSet::new();
+        let files = diagnostics
+            .diagnostics
+            .iter()
+            .map(|(path, file_diagnostics)| {
+                let mut stats = FileStatistics::new();
+                for diagnostic in file_diagnostics {
+                    stats.increment_severity(diagnostic.severity);
+                    sources.insert(diagnostic.source.as_str());
+                    if let Some(code) = &diagnostic.code {
+                        codes.insert(code.as_str());
+                    }
+                }
+                (path.clone(), stats)
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            total: files.iter().map(|(_, stats)| stats.total_count).sum(),
+            distinct_sources: sources.len(),
+            distinct_codes: codes.len(),
+            files,
+        }
+    }
+
+    /// Files kept by the path filters among `filters`
+    fn matching_files(&self, filters: &[QueryFilter]) -> Vec<&(PathBuf, FileStatistics)> {
+        let paths: Vec<_> = filters
+            .iter()
+            .filter(|filter| matches!(filter, QueryFilter::Path(_)))
+            .cloned()
+            .collect();
+        let kept: HashSet<PathBuf> = FilterEngine::new()
+            .apply_file_filters(self.files.clone(), &paths)
+            .map(|files| files.into_iter().map(|(path, _)| path).collect())
+            .unwrap_or_default();
+        self.files.iter().filter(|(path, _)| kept.contains(path)).collect()
+    }
+}
+
+/// Row counts seen by the last query of a shape
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct ObservedRows {
+    pub scanned: usize,
+    /// Rows before LIMIT
+    pub returned: usize,
+}
+
+/// How a query will be executed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExecutionPlan {
+    /// Data source, as written in the query
+    pub source: String,
+    /// Engine that produces the rows
+    pub engine: String,
+    /// Rows the engine reads; `None` without statistics for the source
+    pub estimated_rows_scanned: Option<usize>,
+    /// Rows returned after filters, grouping and LIMIT
+    pub estimated_rows: Option<usize>,
+    /// Whether the result cache already holds the answer, so no engine runs
+    pub uses_cache: bool,
+    pub indexes_used: Vec<String>,
+    pub optimizations: Vec<String>,
+    /// Relative cost from [`QueryValidator::estimate_query_cost`]
+    pub estimated_cost: u32,
+    /// Subqueries and join sides, which run before this step
+    pub inputs: Vec<ExecutionPlan>,
+}
+
+impl QueryExecutor {
+    /// Plan `query` against the loaded data without executing it
+    pub fn plan(&self, query: &Query) -> ExecutionPlan {
+        let cache_key = QueryValidator::generate_cache_key(query);
+        let mut plan = self.plan_step(query);
+        plan.uses_cache = self.query_cache.contains(&cache_key);
+        if plan.uses_cache {
+            plan.optimizations.insert(0, "Answered from the result cache".to_string());
+        }
+        plan
+    }
+
+    fn plan_step(&self, query: &Query) -> ExecutionPlan {
+        let mut inputs: Vec<ExecutionPlan> = query.subqueries().map(|subquery| self.plan_step(subquery)).collect();
+        let mut optimizations = Vec::new();
+        if !inputs.is_empty() {
+            optimizations.push(format!(
+                "{} IN subquer{} resolved to value lists before the scan",
+                inputs.len(),
+                if inputs.len() == 1 { "y" } else { "ies" }
+            ));
+        }
+
+        let mut plan = match &query.join {
+            Some(join) => {
+                let left = Query {
+                    from: query.from.clone(),
+                    filters: query.filters.clone(),
+                    time_range: query.time_range.clone(),
+                    ..Query::new()
+                };
+                let right = Query {
+                    from: join.source.clone(),
+                    filters: join.filters.clone(),
+                    ..Query::new()
+                };
+                let left = self.plan_join_side(&left);
+                let right = self.plan_join_side(&right);
+                optimizations.push(format!(
+                    "Hash join on {}.{} = {}.{}",
+                    join.left_alias, join.left_column, join.alias, join.right_column
+                ));
+                if query.select == SelectClause::Count {
+                    optimizations.push("COUNT(*) taken from the join without post-processing".to_string());
+                }
+                // Join keys are mostly unique per side, so the smaller side bounds an inner join
+                // and a left join keeps every left row
+                let joined = match (left.estimated_rows, right.estimated_rows) {
+                    (Some(l), Some(_)) if join.kind == crate::query::parser::JoinKind::Left => Some(l),
+                    (Some(l), Some(r)) => Some(l.min(r)),
+                    _ => None,
+                };
+                let scanned = left.estimated_rows.zip(right.estimated_rows).map(|(l, r)| l + r);
+                inputs.extend([left, right]);
+                ExecutionPlan {
+                    source: query.from.name().to_string(),
+                    engine: "HashJoin".to_string(),
+                    estimated_rows_scanned: scanned,
+                    estimated_rows: joined,
+                    uses_cache: false,
+                    indexes_used: Vec::new(),
+                    optimizations: Vec::new(),
+                    estimated_cost: 0,
+                    inputs: Vec::new(),
+                }
+            }
+            None => self.plan_source(query),
+        };
+
+        plan.estimated_rows = plan.estimated_rows.map(|rows| self.output_rows(query, rows));
+        plan.estimated_cost = QueryValidator::estimate_query_cost(query).total();
+        optimizations.append(&mut plan.optimizations);
+        plan.optimizations = optimizations;
+        inputs.append(&mut plan.inputs);
+        plan.inputs = inputs;
+        plan
+    }
+
+    fn plan_join_side(&self, query: &Query) -> ExecutionPlan {
+        if query.from != FromClause::History {
+            return self.plan_source(query);
+        }
+        let observed = self.observed(query);
+        ExecutionPlan {
+            source: "history".to_string(),
+            engine: "HistoryEngine".to_string(),
+            estimated_rows_scanned: observed.map(|rows| rows.scanned),
+            estimated_rows: observed.map(|rows| rows.returned),
+            uses_cache: false,
+            indexes_used: Vec::new(),
+            optimizations: vec!["Reads only the latest snapshot of each file".to_string()],
+            estimated_cost: QueryValidator::estimate_query_cost(query).total(),
+            inputs: Vec::new(),
+        }
+    }
+
+    /// Plan a single-source query; `estimated_rows` counts rows before grouping and LIMIT
+    fn plan_source(&self, query: &Query) -> ExecutionPlan {
+        let source = query.from.name().to_string();
+        let mut indexes_used = Vec::new();
+        let mut optimizations = Vec::new();
+        let (engine, scanned, rows) = match &query.from {
+            FromClause::Diagnostics => {
+                if AggregationProcessor::output_columns(query).is_some() {
+                    optimizations.push("Aggregates computed while extracting fields".to_string());
+                }
+                let (scanned, rows) = self.estimate_diagnostics(&query.filters);
+                ("DiagnosticsEngine", scanned, rows)
+            }
+            FromClause::Files => {
+                let (scanned, rows) = self.estimate_files(&query.filters);
+                ("FilesEngine", scanned, rows)
+            }
+            FromClause::History => {
+                let text_filters = query
+                    .filters
+                    .iter()
+                    .filter(|filter| matches!(filter, QueryFilter::FullText(_)))
+                    .count();
+                if text_filters > 0 {
+                    indexes_used.push("diagnostic_messages_fts".to_string());
+                    optimizations.push("CONTAINS_TEXT answered from the full-text index".to_string());
+                    let aggregated = AggregationProcessor::output_columns(query).is_some();
+                    if text_filters == 1 && query.limit.is_some() && query.select != SelectClause::Count && !aggregated {
+                        optimizations.push("LIMIT pushed into the full-text search".to_string());
+                    }
+                }
+                self.observed_source("HistoryEngine", query)
+            }
+            FromClause::Symbols => match &self.symbol_index {
+                Some(index) => {
+                    indexes_used.push("symbol index".to_string());
+                    let definitions = index.definitions().len();
+                    let rows = self
+                        .observed(query)
+                        .map_or_else(|| apply_unknown_filters(definitions, &query.filters), |rows| rows.returned);
+                    ("SymbolsEngine", Some(definitions), Some(rows))
+                }
+                None => self.observed_source("SymbolsEngine", query),
+            },
+            FromClause::Trends => self.observed_source("TrendsEngine", query),
+            FromClause::References => self.observed_source("ReferencesEngine", query),
+            FromClause::Projects => self.observed_source("ProjectsEngine", query),
+            FromClause::Fixes => self.observed_source("FixesEngine", query),
+            FromClause::Lenses => self.observed_source("LensesEngine", query),
+            FromClause::Config => self.observed_source("ConfigEngine", query),
+        };
+        if matches!(
+            query.from,
+            FromClause::Diagnostics | FromClause::Files | FromClause::Symbols | FromClause::References | FromClause::Fixes
+        ) && query.filters.iter().any(|filter| matches!(filter, QueryFilter::In(_)))
+        {
+            optimizations.push("IN filters applied during the scan".to_string());
+        }
+
+        ExecutionPlan {
+            source,
+            engine: engine.to_string(),
+            estimated_rows_scanned: scanned,
+            estimated_rows: rows,
+            uses_cache: false,
+            indexes_used,
+            optimizations,
+            estimated_cost: 0,
+            inputs: Vec::new(),
+        }
+    }
+
+    fn observed_source(&self, engine: &'static str, query: &Query) -> (&'static str, Option<usize>, Option<usize>) {
+        let observed = self.observed(query);
+        (engine, observed.map(|rows| rows.scanned), observed.map(|rows| rows.returned))
+    }
+
+    fn observed(&self, query: &Query) -> Option<ObservedRows> {
+        self.observed_rows().get(&QueryKeyGenerator::generate_pattern_key(query)).copied()
+    }
+
+    /// Diagnostics read and kept by `filters`
+    ///
+    /// Path and severity filters are counted from the per-file statistics,
+    /// the rest are assumed to keep [`DEFAULT_SELECTIVITY`].
+    fn estimate_diagnostics(&self, filters: &[QueryFilter]) -> (Option<usize>, Option<usize>) {
+        let Some(stats) = &self.statistics else {
+            return (None, None);
+        };
+        let severities: Vec<_> = SEVERITIES
+            .iter()
+            .copied()
+            .filter(|severity| {
+                filters.iter().all(|filter| match filter {
+                    QueryFilter::Severity(severity_filter) => {
+                        FilterEngine::compare_severity(*severity, severity_filter.severity, severity_filter.comparison.clone())
+                    }
+                    _ => true,
+                })
+            })
+            .collect();
+        let matching: usize = stats
+            .matching_files(filters)
+            .iter()
+            .map(|(_, file)| severities.iter().map(|severity| severity_count(file, *severity)).sum::<usize>())
+            .sum();
+        let others: Vec<_> = filters
+            .iter()
+            .filter(|filter| {
+                matches!(
+                    filter,
+                    QueryFilter::Category(_) | QueryFilter::Message(_) | QueryFilter::FullText(_) | QueryFilter::In(_)
+                )
+            })
+            .cloned()
+            .collect();
+        (Some(stats.total), Some(apply_unknown_filters(matching, &others)))
+    }
+
+    /// Files read and kept by `filters`, counted from the per-file statistics
+    fn estimate_files(&self, filters: &[QueryFilter]) -> (Option<usize>, Option<usize>) {
+        let Some(stats) = &self.statistics else {
+            return (None, None);
+        };
+        // Subqueries have no values until they run
+        let (subqueries, known): (Vec<_>, Vec<_>) = filters.iter().cloned().partition(|filter| {
+            matches!(filter, QueryFilter::In(in_filter) if matches!(in_filter.list, InList::Subquery(_)))
+        });
+        let kept = FilterEngine::new()
+            .apply_file_filters(stats.files.clone(), &known)
+            .map_or_else(|_| apply_unknown_filters(stats.files.len(), &known), |files| files.len());
+        (Some(stats.files.len()), Some(apply_unknown_filters(kept, &subqueries)))
+    }
+
+    /// Rows left after grouping, aggregation and LIMIT
+    fn output_rows(&self, query: &Query, rows: usize) -> usize {
+        let rows = if query.select == SelectClause::Count {
+            1
+        } else if let Some(group_by) = &query.group_by {
+            let groups = group_by
+                .fields
+                .iter()
+                .map(|field| self.distinct_values(query, field).unwrap_or(rows))
+                .fold(1usize, |groups, distinct| groups.saturating_mul(distinct.max(1)));
+            groups.min(rows)
+        } else if AggregationProcessor::output_columns(query).is_some() {
+            1
+        } else {
+            rows
+        };
+        query.limit.map_or(rows, |limit| rows.min(limit as usize))
+    }
+
+    /// Distinct values of a diagnostics column, from the loaded statistics
+    fn distinct_values(&self, query: &Query, field: &str) -> Option<usize> {
+        let stats = self.statistics.as_ref()?;
+        if query.from != FromClause::Diagnostics || query.join.is_some() {
+            return None;
+        }
+        match field {
+            "file" | "path" => Some(stats.matching_files(&query.filters).len()),
+            "severity" => Some(
+                SEVERITIES
+                    .iter()
+                    .filter(|severity| stats.files.iter().any(|(_, file)| severity_count(file, **severity) > 0))
+                    .count(),
+            ),
+            "source" => Some(stats.distinct_sources),
+            "code" | "category" => Some(stats.distinct_codes),
+            _ => None,
+        }
+    }
+}
+
+fn severity_count(stats: &FileStatistics, severity: DiagnosticSeverity) -> usize {
+    match severity {
+        DiagnosticSeverity::Error => stats.error_count,
+        DiagnosticSeverity::Warning => stats.warning_count,
+        DiagnosticSeverity::Information => stats.info_count,
+        DiagnosticSeverity::Hint => stats.hint_count,
+    }
+}
+
+/// `rows` reduced by [`DEFAULT_SELECTIVITY`] for each of `filters`
+fn apply_unknown_filters(rows: usize, filters: &[QueryFilter]) -> usize {
+    let kept = filters
+        .iter()
+        .fold(rows as f64, |rows, _| rows * DEFAULT_SELECTIVITY);
+    kept.ceil() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Diagnostic, Position, Range};
+    use crate::query::parser::QueryParser;
+
+    fn diagnostic(severity: DiagnosticSeverity, source: &str) -> Diagnostic {
+        Diagnostic::new(
+            String::new(),
+            Range {
+                start: Position { line: 0, character: 0 },
+                end: Position { line: 0, character: 1 },
+            },
+            severity,
+            "message".to_string(),
+            source.to_string(),
+        )
+    }
+
+    fn executor() -> QueryExecutor {
+        let mut diagnostics = DiagnosticResult::new();
+        diagnostics.diagnostics.insert(
+            PathBuf::from("src/api/handler.rs"),
+            vec![
+                diagnostic(DiagnosticSeverity::Error, "rustc"),
+                diagnostic(DiagnosticSeverity::Error, "rustc"),
+                diagnostic(DiagnosticSeverity::Warning, "clippy"),
+            ],
+        );
+        diagnostics.diagnostics.insert(
+            PathBuf::from("src/db/pool.rs"),
+            vec![
+                diagnostic(DiagnosticSeverity::Warning, "clippy"),
+                diagnostic(DiagnosticSeverity::Hint, "rustc"),
+            ],
+        );
+        let mut executor = QueryExecutor::new();
+        executor.with_diagnostics(diagnostics);
+        executor
+    }
+
+    fn parse(query: &str) -> Query {
+        QueryParser::new().parse(query).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_plan_estimates_rows_from_statistics() {
+        let executor = executor();
+
+        let plan = executor.plan(&parse("SELECT * FROM diagnostics WHERE severity = error AND path = 'src/api'"));
+        assert_eq!(plan.engine, "DiagnosticsEngine");
+        assert_eq!(plan.estimated_rows_scanned, Some(5));
+        assert_eq!(plan.estimated_rows, Some(2));
+        assert!(!plan.uses_cache);
+
+        let plan = executor.plan(&parse("SELECT severity, COUNT(*) FROM diagnostics GROUP BY severity"));
+        assert_eq!(plan.estimated_rows, Some(3));
+        assert!(plan.optimizations.contains(&"Aggregates computed while extracting fields".to_string()));
+
+        let plan = executor.plan(&parse("SELECT * FROM files WHERE errors > 1"));
+        assert_eq!((plan.engine.as_str(), plan.estimated_rows), ("FilesEngine", Some(1)));
+
+        let query = parse("SELECT file FROM diagnostics WHERE file IN (SELECT file FROM files WHERE errors > 1) LIMIT 1");
+        let plan = executor.plan(&query);
+        assert_eq!(plan.inputs.len(), 1);
+        assert_eq!(plan.inputs[0].estimated_rows, Some(1));
+        assert_eq!(plan.estimated_rows, Some(1));
+
+        // Executed queries are answered from the cache afterwards
+        executor.execute(&query).await.unwrap();
+        let plan = executor.plan(&query);
+        assert!(plan.uses_cache);
+        assert_eq!(plan.optimizations[0], "Answered from the result cache");
+    }
+
+    #[tokio::test]
+    async fn test_plan_uses_observed_rows_without_statistics() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let history = crate::history::HistoryStorage::new(crate::history::HistoryConfig {
+            db_path: temp_dir.path().join("history.db"),
+            ..crate::history::HistoryConfig::default()
+        })
+        .await
+        .unwrap();
+        let mut executor = executor();
+        executor.with_history(history);
+
+        let query = parse("SELECT * FROM history WHERE message CONTAINS_TEXT 'borrow' LIMIT 10");
+        let plan = executor.plan(&query);
+        assert_eq!(plan.engine, "HistoryEngine");
+        assert_eq!(plan.estimated_rows, None);
+        assert_eq!(plan.indexes_used, vec!["diagnostic_messages_fts"]);
+        assert!(plan.optimizations.contains(&"LIMIT pushed into the full-text search".to_string()));
+
+        executor.execute(&query).await.unwrap();
+        executor.clear_cache();
+        let plan = executor.plan(&parse("SELECT * FROM history WHERE message CONTAINS_TEXT 'moved' LIMIT 5"));
+        assert_eq!(plan.estimated_rows, Some(0));
+        assert!(!plan.uses_cache);
+    }
+}
@@ -3,10 +3,10 @@
 //! This module provides utilities for processing query results, including
 //! aggregation functions, sorting operations, and data transformation.
 
-use crate::query::parser::{OrderByClause, OrderDirection, QueryAggregation};
+use crate::query::parser::{OrderByClause, OrderDirection, Query, QueryAggregation, SelectClause};
 use super::types::{Row, Value};
 use anyhow::{anyhow, Result};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Processor for aggregating data based on query specifications
 pub struct AggregationProcessor;
@@ -44,14 +44,138 @@ impl AggregationProcessor {
         Ok((columns, result_rows))
     }
 
+    /// Result columns of a query that aggregates, `None` for per-row queries
+    ///
+    /// A query aggregates when it has a GROUP BY or its SELECT list uses an
+    /// aggregation function. Computed SELECT items contribute the columns
+    /// they reference, so `ROUND(AVG(line), 1) AS avg_line` is evaluated over
+    /// the aggregated `AVG(line)` column.
+    pub fn output_columns(query: &Query) -> Option<Vec<String>> {
+        let columns: Vec<String> = match &query.select {
+            SelectClause::Fields(fields) => fields.clone(),
+            SelectClause::Expressions(items) => {
+                let mut columns: Vec<String> = query
+                    .group_by
+                    .as_ref()
+                    .map(|group_by| group_by.fields.clone())
+                    .unwrap_or_default();
+                for column in items.iter().flat_map(|item| item.expr.columns()) {
+                    if !columns.iter().any(|c| c == column) {
+                        columns.push(column.to_string());
+                    }
+                }
+                columns
+            }
+            SelectClause::Aggregations(aggs) => aggs.iter().map(ToString::to_string).collect(),
+            SelectClause::All | SelectClause::Count => return None,
+        };
+
+        let aggregates = columns.iter().any(|c| QueryAggregation::from_column(c).is_some());
+        (aggregates || query.group_by.is_some()).then_some(columns)
+    }
+
+    /// Group `rows` by the `group_by` columns and compute the `select` columns per group
+    ///
+    /// Aggregation columns (`AVG(line)`) are computed over the group; any
+    /// other column takes the value of the group's first row. Without GROUP
+    /// BY all rows form a single group, which yields one row even when there
+    /// are no input rows. Groups keep the order they first appear in.
+    pub fn aggregate_rows(
+        columns: &[String],
+        rows: &[Row],
+        select: &[String],
+        group_by: &[String],
+    ) -> Result<(Vec<String>, Vec<Row>)> {
+        enum Output {
+            Column(usize),
+            Aggregate(QueryAggregation, Option<usize>),
+        }
+
+        let index = |name: &str| {
+            columns
+                .iter()
+                .position(|c| c == name)
+                .ok_or_else(|| anyhow!("Unknown column: {}", name))
+        };
+        let group_indices = group_by.iter().map(|field| index(field)).collect::<Result<Vec<_>>>()?;
+        let outputs = select
+            .iter()
+            .map(|name| match QueryAggregation::from_column(name) {
+                Some(agg) => {
+                    let source = if agg.field() == "*" { None } else { Some(index(agg.field())?) };
+                    Ok(Output::Aggregate(agg, source))
+                }
+                None => Ok(Output::Column(index(name)?)),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut order: Vec<String> = Vec::new();
+        let mut groups: HashMap<String, Vec<&Row>> = HashMap::new();
+        if group_by.is_empty() {
+            order.push(String::new());
+            groups.insert(String::new(), rows.iter().collect());
+        } else {
+            for row in rows {
+                let key = group_indices
+                    .iter()
+                    .map(|&i| row.get(i).map(Value::to_string).unwrap_or_default())
+                    .collect::<Vec<_>>()
+                    .join("\u{1f}");
+                groups
+                    .entry(key.clone())
+                    .or_insert_with(|| {
+                        order.push(key);
+                        Vec::new()
+                    })
+                    .push(row);
+            }
+        }
+
+        let mut result_rows = Vec::with_capacity(order.len());
+        for key in order {
+            let group = groups.remove(&key).unwrap_or_default();
+            let values = outputs
+                .iter()
+                .map(|output| match output {
+                    Output::Column(i) => Ok(group.first().and_then(|row| row.get(*i)).cloned().unwrap_or(Value::Null)),
+                    Output::Aggregate(agg, source) => {
+                        let values: Vec<Value> = group
+                            .iter()
+                            .map(|row| source.and_then(|i| row.get(i)).cloned().unwrap_or(Value::Null))
+                            .collect();
+                        Self::compute_aggregation(agg, &values)
+                    }
+                })
+                .collect::<Result<Vec<_>>>()?;
+            result_rows.push(Row::new(values));
+        }
+
+        Ok((select.to_vec(), result_rows))
+    }
+
     /// Compute a single aggregation on a set of values
-    fn compute_aggregation(agg: &QueryAggregation, values: &[Value]) -> Result<Value> {
+    ///
+    /// Nulls are skipped, except by `COUNT(*)` which counts every row.
+    /// `SUM` of integers stays an integer; averages and percentiles are numbers.
+    pub fn compute_aggregation(agg: &QueryAggregation, values: &[Value]) -> Result<Value> {
         match agg {
-            QueryAggregation::Count(_) => Ok(Value::Integer(values.len() as i64)),
+            QueryAggregation::Count(field) if field == "*" => Ok(Value::Integer(values.len() as i64)),
+            QueryAggregation::Count(_) => Ok(Value::Integer(
+                values.iter().filter(|v| !matches!(v, Value::Null)).count() as i64,
+            )),
             QueryAggregation::Sum(field) => Self::compute_sum(values, field),
             QueryAggregation::Average(field) => Self::compute_average(values, field),
-            QueryAggregation::Min(field) => Self::compute_min(values, field),
-            QueryAggregation::Max(field) => Self::compute_max(values, field),
+            QueryAggregation::Min(_) => Ok(Self::extreme(values, std::cmp::Ordering::Less)),
+            QueryAggregation::Max(_) => Ok(Self::extreme(values, std::cmp::Ordering::Greater)),
+            QueryAggregation::DistinctCount(_) => {
+                let distinct: HashSet<String> = values
+                    .iter()
+                    .filter(|v| !matches!(v, Value::Null))
+                    .map(Value::to_string)
+                    .collect();
+                Ok(Value::Integer(distinct.len() as i64))
+            }
+            QueryAggregation::Percentile(field, p) => Self::compute_percentile(values, field, *p),
         }
     }
 
@@ -69,105 +193,80 @@ impl AggregationProcessor {
             QueryAggregation::Average(field) => format!("avg_{field}"),
             QueryAggregation::Min(field) => format!("min_{field}"),
             QueryAggregation::Max(field) => format!("max_{field}"),
+            QueryAggregation::DistinctCount(field) => format!("distinct_count_{field}"),
+            QueryAggregation::Percentile(field, p) => format!("p{p}_{field}"),
         }
     }
 
     /// Compute sum of numeric values
-    fn compute_sum(values: &[Value], _field: &str) -> Result<Value> {
-        let mut sum = 0.0;
-        let mut has_values = false;
-
-        for value in values {
-            if let Some(num) = value.as_number() {
-                sum += num;
-                has_values = true;
-            }
+    fn compute_sum(values: &[Value], field: &str) -> Result<Value> {
+        let numbers = Self::numbers(values, field)?;
+        if numbers.is_empty() {
+            return Ok(Value::Null);
         }
 
-        if has_values {
-            Ok(Value::Number(sum))
-        } else {
-            Ok(Value::Null)
-        }
+        let integers: Option<i64> = values
+            .iter()
+            .filter(|v| !matches!(v, Value::Null))
+            .try_fold(0i64, |sum, value| match value {
+                Value::Integer(i) => sum.checked_add(*i),
+                _ => None,
+            });
+        Ok(match integers {
+            Some(sum) => Value::Integer(sum),
+            None => Value::Number(numbers.iter().sum()),
+        })
     }
 
     /// Compute average of numeric values
-    fn compute_average(values: &[Value], _field: &str) -> Result<Value> {
-        let mut sum = 0.0;
-        let mut count = 0;
-
-        for value in values {
-            if let Some(num) = value.as_number() {
-                sum += num;
-                count += 1;
-            }
-        }
-
-        if count > 0 {
-            Ok(Value::Number(sum / count as f64))
-        } else {
+    fn compute_average(values: &[Value], field: &str) -> Result<Value> {
+        let numbers = Self::numbers(values, field)?;
+        if numbers.is_empty() {
             Ok(Value::Null)
+        } else {
+            Ok(Value::Number(numbers.iter().sum::<f64>() / numbers.len() as f64))
         }
     }
 
-    /// Compute minimum value
-    fn compute_min(values: &[Value], _field: &str) -> Result<Value> {
-        let mut min_val: Option<&Value> = None;
-
-        for value in values {
-            match min_val {
-                None => min_val = Some(value),
-                Some(current_min) => {
-                    if Self::is_less_than(value, current_min) {
-                        min_val = Some(value);
-                    }
-                }
-            }
+    /// Compute the `p`-th percentile, interpolating between the closest ranks
+    fn compute_percentile(values: &[Value], field: &str, p: f64) -> Result<Value> {
+        let mut numbers = Self::numbers(values, field)?;
+        if numbers.is_empty() {
+            return Ok(Value::Null);
         }
+        numbers.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
 
-        Ok(min_val.cloned().unwrap_or(Value::Null))
+        let rank = p.clamp(0.0, 100.0) / 100.0 * (numbers.len() - 1) as f64;
+        let lower = rank.floor() as usize;
+        let upper = rank.ceil() as usize;
+        let fraction = rank - lower as f64;
+        Ok(Value::Number(numbers[lower] + (numbers[upper] - numbers[lower]) * fraction))
     }
 
-    /// Compute maximum value
-    fn compute_max(values: &[Value], _field: &str) -> Result<Value> {
-        let mut max_val: Option<&Value> = None;
-
-        for value in values {
-            match max_val {
-                None => max_val = Some(value),
-                Some(current_max) => {
-                    if Self::is_greater_than(value, current_max) {
-                        max_val = Some(value);
-                    }
-                }
-            }
-        }
-
-        Ok(max_val.cloned().unwrap_or(Value::Null))
-    }
-
-    /// Compare if left value is less than right value
-    fn is_less_than(left: &Value, right: &Value) -> bool {
-        match (left, right) {
-            (Value::Integer(a), Value::Integer(b)) => a < b,
-            (Value::Number(a), Value::Number(b)) => a < b,
-            (Value::Integer(a), Value::Number(b)) => (*a as f64) < *b,
-            (Value::Number(a), Value::Integer(b)) => *a < (*b as f64),
-            (Value::String(a), Value::String(b)) => a < b,
-            _ => false,
-        }
+    /// The non-null values as numbers, failing on text and other non-numeric values
+    fn numbers(values: &[Value], field: &str) -> Result<Vec<f64>> {
+        values
+            .iter()
+            .filter(|v| !matches!(v, Value::Null))
+            .map(|value| {
+                value
+                    .as_number()
+                    .ok_or_else(|| anyhow!("Cannot aggregate non-numeric value '{}' of {}", value.to_string(), field))
+            })
+            .collect()
     }
 
-    /// Compare if left value is greater than right value
-    fn is_greater_than(left: &Value, right: &Value) -> bool {
-        match (left, right) {
-            (Value::Integer(a), Value::Integer(b)) => a > b,
-            (Value::Number(a), Value::Number(b)) => a > b,
-            (Value::Integer(a), Value::Number(b)) => (*a as f64) > *b,
-            (Value::Number(a), Value::Integer(b)) => *a > (*b as f64),
-            (Value::String(a), Value::String(b)) => a > b,
-            _ => false,
-        }
+    /// Smallest (`Less`) or largest (`Greater`) non-null value
+    fn extreme(values: &[Value], wanted: std::cmp::Ordering) -> Value {
+        values
+            .iter()
+            .filter(|v| !matches!(v, Value::Null))
+            .fold(None, |best: Option<&Value>, value| match best {
+                Some(best) if SortingProcessor::compare_values(value, best) != wanted => Some(best),
+                _ => Some(value),
+            })
+            .cloned()
+            .unwrap_or(Value::Null)
     }
 }
 
@@ -202,7 +301,7 @@ impl SortingProcessor {
     }
 
     /// Compare two values for sorting
-    fn compare_values(a: &Value, b: &Value) -> std::cmp::Ordering {
+    pub(crate) fn compare_values(a: &Value, b: &Value) -> std::cmp::Ordering {
         use std::cmp::Ordering;
 
         match (a, b) {
@@ -433,6 +532,31 @@ mod tests {
         assert_eq!(result, Value::Number(20.0));
     }
 
+    #[test]
+    fn test_aggregation_typing_and_nulls() {
+        let values = vec![Value::Integer(4), Value::Null, Value::Integer(1), Value::Integer(3), Value::Integer(2)];
+        let compute = |agg: QueryAggregation| AggregationProcessor::compute_aggregation(&agg, &values).unwrap();
+
+        assert_eq!(compute(QueryAggregation::Sum("x".to_string())), Value::Integer(10));
+        assert_eq!(compute(QueryAggregation::Count("*".to_string())), Value::Integer(5));
+        assert_eq!(compute(QueryAggregation::Count("x".to_string())), Value::Integer(4));
+        assert_eq!(compute(QueryAggregation::Min("x".to_string())), Value::Integer(1));
+        assert_eq!(compute(QueryAggregation::Max("x".to_string())), Value::Integer(4));
+        assert_eq!(compute(QueryAggregation::Percentile("x".to_string(), 50.0)), Value::Number(2.5));
+        assert_eq!(compute(QueryAggregation::Percentile("x".to_string(), 100.0)), Value::Number(4.0));
+
+        let words = vec![Value::String("a".to_string()), Value::String("b".to_string()), Value::String("a".to_string())];
+        assert_eq!(
+            AggregationProcessor::compute_aggregation(&QueryAggregation::DistinctCount("w".to_string()), &words).unwrap(),
+            Value::Integer(2)
+        );
+        assert!(AggregationProcessor::compute_aggregation(&QueryAggregation::Average("w".to_string()), &words).is_err());
+        assert_eq!(
+            AggregationProcessor::compute_aggregation(&QueryAggregation::Average("x".to_string()), &[]).unwrap(),
+            Value::Null
+        );
+    }
+
     #[test]
     fn test_sorting() {
         let mut rows = vec![
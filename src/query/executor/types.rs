@@ -7,9 +7,12 @@
 use crate::core::DiagnosticSeverity;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use utoipa::openapi::schema::{ArrayBuilder, ObjectBuilder, OneOfBuilder, Schema, Type};
+use utoipa::openapi::{Ref, RefOr};
+use utoipa::{PartialSchema, ToSchema};
 
 /// A complete query result containing rows, metadata, and timing information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct QueryResult {
     /// Column names for the result set
     pub columns: Vec<String>,
@@ -34,6 +37,28 @@ pub struct Row {
     pub values: Vec<Value>,
 }
 
+// utoipa's derive treats any type named `Value` as `serde_json::Value`, so the
+// row schema is written by hand to reference the query `Value` schema instead.
+impl PartialSchema for Row {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .property(
+                "values",
+                ArrayBuilder::new().items(Ref::from_schema_name(Value::name())),
+            )
+            .required("values")
+            .description(Some("A single row in a query result"))
+            .into()
+    }
+}
+
+impl ToSchema for Row {
+    fn schemas(schemas: &mut Vec<(String, RefOr<Schema>)>) {
+        schemas.push((Value::name().into_owned(), Value::schema()));
+        <Value as ToSchema>::schemas(schemas);
+    }
+}
+
 /// A typed value that can appear in query results
 ///
 /// Supports various data types commonly found in diagnostic and file data,
@@ -42,8 +67,9 @@ pub struct Row {
 #[serde(untagged)]
 pub enum Value {
     String(String),
-    Number(f64),
+    // Before `Number`, so whole numbers read back from JSON stay integers
     Integer(i64),
+    Number(f64),
     Boolean(bool),
     Path(PathBuf),
     Severity(DiagnosticSeverity),
@@ -51,8 +77,34 @@ pub enum Value {
     Null,
 }
 
+/// Values are untagged in JSON, so the schema is a `oneOf` over the
+/// primitive representations rather than a derived enum.
+impl PartialSchema for Value {
+    fn schema() -> RefOr<Schema> {
+        OneOfBuilder::new()
+            .item(ObjectBuilder::new().schema_type(Type::String))
+            .item(ObjectBuilder::new().schema_type(Type::Number))
+            .item(ObjectBuilder::new().schema_type(Type::Integer))
+            .item(ObjectBuilder::new().schema_type(Type::Boolean))
+            .item(Ref::from_schema_name(DiagnosticSeverity::name()))
+            .item(ArrayBuilder::new().items(Ref::from_schema_name(Self::name())))
+            .item(ObjectBuilder::new().schema_type(Type::Null))
+            .description(Some("A query result cell"))
+            .into()
+    }
+}
+
+impl ToSchema for Value {
+    fn schemas(schemas: &mut Vec<(String, RefOr<Schema>)>) {
+        schemas.push((
+            DiagnosticSeverity::name().into_owned(),
+            DiagnosticSeverity::schema(),
+        ));
+    }
+}
+
 /// Metadata about query execution for debugging and optimization
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct QueryMetadata {
     /// Which data source was queried
     pub data_source: String,
@@ -143,6 +195,16 @@ impl QueryResult {
         }
     }
 
+    /// The result as an Arrow record batch, with column types inferred from the values
+    pub fn to_record_batch(&self) -> anyhow::Result<arrow_array::RecordBatch> {
+        super::arrow::to_record_batch(self)
+    }
+
+    /// The result in the Arrow IPC file format (Feather v2)
+    pub fn to_arrow_ipc(&self) -> anyhow::Result<Vec<u8>> {
+        super::arrow::to_ipc_bytes(self)
+    }
+
     /// Create a single-value result (used for COUNT queries)
     pub fn single_value(data_source: &str, column: &str, value: Value) -> Self {
         Self {
@@ -2,11 +2,15 @@ pub mod api;
 pub mod executor;
 pub mod parser;
 pub mod repl;
+pub mod tui;
+pub mod warm;
 
 pub use api::{QueryApi, QueryRequest, QueryResponse};
 pub use executor::{QueryExecutor, QueryResult};
 pub use parser::{Query, QueryAggregation, QueryFilter, QueryParser};
 pub use repl::InteractiveRepl;
+pub use tui::{ResultBrowser, ResultTable};
+pub use warm::{WarmQueryRequest, WarmQueryService};
 
 use anyhow::Result;
 use std::path::PathBuf;
@@ -1,8 +1,9 @@
 //! Abstract Syntax Tree (AST) definitions for query language
 
-use crate::core::DiagnosticSeverity;
+use crate::core::{CalendarConfig, CalendarUnit, DiagnosticSeverity};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 /// Root query structure
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -14,6 +15,40 @@ pub struct Query {
     pub order_by: Option<OrderByClause>,
     pub limit: Option<u32>,
     pub time_range: Option<TimeRange>,
+    /// Second data source joined to `from`
+    #[serde(default)]
+    pub join: Option<JoinClause>,
+}
+
+/// `FROM <source> [alias] [INNER | LEFT] JOIN <source> [alias] ON a.column = b.column`
+///
+/// Joined rows carry every column of both sides, qualified by their alias
+/// (`d.file`, `h.error_count`). The WHERE conditions the parser attributes to
+/// the joined source are kept in `filters` and applied to it before joining;
+/// all others apply to `from`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JoinClause {
+    pub kind: JoinKind,
+    /// Alias of the `FROM` source; its name when none is given
+    pub left_alias: String,
+    pub source: FromClause,
+    /// Alias of the joined source; its name when none is given
+    pub alias: String,
+    /// Unqualified join column of the `FROM` source
+    pub left_column: String,
+    /// Unqualified join column of the joined source
+    pub right_column: String,
+    pub filters: Vec<QueryFilter>,
+}
+
+/// How rows without a match on the other side are treated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum JoinKind {
+    /// Only rows with a match on both sides
+    #[default]
+    Inner,
+    /// Every `FROM` row, with NULLs where the joined source has no match
+    Left,
 }
 
 /// SELECT clause variants
@@ -27,6 +62,235 @@ pub enum SelectClause {
     Fields(Vec<String>),
     /// SELECT aggregation functions
     Aggregations(Vec<QueryAggregation>),
+    /// SELECT with computed columns: `expr [AS alias], ...`
+    Expressions(Vec<SelectItem>),
+}
+
+/// One column of a computed SELECT list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SelectItem {
+    pub expr: Expr,
+    pub alias: Option<String>,
+}
+
+impl SelectItem {
+    /// Result column name: the alias, or the expression as written
+    pub fn name(&self) -> String {
+        match &self.alias {
+            Some(alias) => alias.clone(),
+            None => self.expr.to_string(),
+        }
+    }
+}
+
+/// Scalar expression evaluated once per result row
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Expr {
+    /// A column of the data source, or an alias defined earlier in the SELECT list
+    Column(String),
+    /// Literal without a decimal point; integer operands keep integer arithmetic
+    Integer(i64),
+    Number(f64),
+    String(String),
+    Null,
+    /// Unary minus
+    Negate(Box<Expr>),
+    Binary {
+        op: BinaryOperator,
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+    Function {
+        function: ScalarFunction,
+        args: Vec<Expr>,
+    },
+    /// `CASE WHEN cond THEN value ... [ELSE value] END`
+    Case {
+        branches: Vec<(Expr, Expr)>,
+        otherwise: Option<Box<Expr>>,
+    },
+}
+
+/// Binary operators usable in expressions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BinaryOperator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+    /// String concatenation (`||`)
+    Concat,
+    Compare(Comparison),
+    And,
+    Or,
+}
+
+/// Scalar functions callable in expressions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScalarFunction {
+    Upper,
+    Lower,
+    Length,
+    Trim,
+    Substr,
+    Replace,
+    Concat,
+    Coalesce,
+    NullIf,
+    Abs,
+    Round,
+}
+
+impl ScalarFunction {
+    /// Look up a function by its (case-insensitive) SQL name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "upper" => Some(Self::Upper),
+            "lower" => Some(Self::Lower),
+            "length" | "len" => Some(Self::Length),
+            "trim" => Some(Self::Trim),
+            "substr" | "substring" => Some(Self::Substr),
+            "replace" => Some(Self::Replace),
+            "concat" => Some(Self::Concat),
+            "coalesce" => Some(Self::Coalesce),
+            "nullif" => Some(Self::NullIf),
+            "abs" => Some(Self::Abs),
+            "round" => Some(Self::Round),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Upper => "UPPER",
+            Self::Lower => "LOWER",
+            Self::Length => "LENGTH",
+            Self::Trim => "TRIM",
+            Self::Substr => "SUBSTR",
+            Self::Replace => "REPLACE",
+            Self::Concat => "CONCAT",
+            Self::Coalesce => "COALESCE",
+            Self::NullIf => "NULLIF",
+            Self::Abs => "ABS",
+            Self::Round => "ROUND",
+        }
+    }
+
+    /// Minimum and maximum number of arguments (`None` for variadic)
+    pub fn arity(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::Upper | Self::Lower | Self::Length | Self::Trim | Self::Abs => (1, Some(1)),
+            Self::Substr => (2, Some(3)),
+            Self::Replace => (3, Some(3)),
+            Self::Concat | Self::Coalesce => (1, None),
+            Self::NullIf => (2, Some(2)),
+            Self::Round => (1, Some(2)),
+        }
+    }
+}
+
+impl Expr {
+    /// Columns referenced by this expression, in order of appearance
+    pub fn columns(&self) -> Vec<&str> {
+        let mut columns = Vec::new();
+        self.collect_columns(&mut columns);
+        columns
+    }
+
+    fn collect_columns<'a>(&'a self, columns: &mut Vec<&'a str>) {
+        match self {
+            Expr::Column(name) => columns.push(name),
+            Expr::Integer(_) | Expr::Number(_) | Expr::String(_) | Expr::Null => {}
+            Expr::Negate(inner) => inner.collect_columns(columns),
+            Expr::Binary { left, right, .. } => {
+                left.collect_columns(columns);
+                right.collect_columns(columns);
+            }
+            Expr::Function { args, .. } => args.iter().for_each(|arg| arg.collect_columns(columns)),
+            Expr::Case { branches, otherwise } => {
+                for (condition, value) in branches {
+                    condition.collect_columns(columns);
+                    value.collect_columns(columns);
+                }
+                if let Some(otherwise) = otherwise {
+                    otherwise.collect_columns(columns);
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for BinaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            BinaryOperator::Add => "+",
+            BinaryOperator::Subtract => "-",
+            BinaryOperator::Multiply => "*",
+            BinaryOperator::Divide => "/",
+            BinaryOperator::Modulo => "%",
+            BinaryOperator::Concat => "||",
+            BinaryOperator::Compare(Comparison::Equal) => "=",
+            BinaryOperator::Compare(Comparison::NotEqual) => "!=",
+            BinaryOperator::Compare(Comparison::GreaterThan) => ">",
+            BinaryOperator::Compare(Comparison::LessThan) => "<",
+            BinaryOperator::Compare(Comparison::GreaterThanOrEqual) => ">=",
+            BinaryOperator::Compare(Comparison::LessThanOrEqual) => "<=",
+            BinaryOperator::And => "AND",
+            BinaryOperator::Or => "OR",
+        };
+        f.write_str(symbol)
+    }
+}
+
+/// Renders the expression back to query syntax; nested binary operations are
+/// parenthesized so the text is unambiguous
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn operand(f: &mut fmt::Formatter<'_>, expr: &Expr) -> fmt::Result {
+            match expr {
+                Expr::Binary { .. } => write!(f, "({expr})"),
+                _ => write!(f, "{expr}"),
+            }
+        }
+
+        match self {
+            Expr::Column(name) => f.write_str(name),
+            Expr::Integer(i) => write!(f, "{i}"),
+            Expr::Number(n) => write!(f, "{n:?}"),
+            Expr::String(s) => write!(f, "'{s}'"),
+            Expr::Null => f.write_str("NULL"),
+            Expr::Negate(inner) => {
+                f.write_str("-")?;
+                operand(f, inner)
+            }
+            Expr::Binary { op, left, right } => {
+                operand(f, left)?;
+                write!(f, " {op} ")?;
+                operand(f, right)
+            }
+            Expr::Function { function, args } => {
+                write!(f, "{}(", function.name())?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{arg}")?;
+                }
+                f.write_str(")")
+            }
+            Expr::Case { branches, otherwise } => {
+                f.write_str("CASE")?;
+                for (condition, value) in branches {
+                    write!(f, " WHEN {condition} THEN {value}")?;
+                }
+                if let Some(otherwise) = otherwise {
+                    write!(f, " ELSE {otherwise}")?;
+                }
+                f.write_str(" END")
+            }
+        }
+    }
 }
 
 /// FROM clause data sources
@@ -46,6 +310,30 @@ pub enum FromClause {
     History,
     /// FROM trends
     Trends,
+    /// FROM fixes (quick-fix candidates for loaded diagnostics)
+    Fixes,
+    /// FROM lenses (code lenses and inlay hints collected from the language server)
+    Lenses,
+    /// FROM config (effective configuration and environment of the install)
+    Config,
+}
+
+impl FromClause {
+    /// Name of the data source as written in a query
+    pub fn name(&self) -> &'static str {
+        match self {
+            FromClause::Diagnostics => "diagnostics",
+            FromClause::Files => "files",
+            FromClause::Symbols => "symbols",
+            FromClause::References => "references",
+            FromClause::Projects => "projects",
+            FromClause::History => "history",
+            FromClause::Trends => "trends",
+            FromClause::Fixes => "fixes",
+            FromClause::Lenses => "lenses",
+            FromClause::Config => "config",
+        }
+    }
 }
 
 /// Query filter types
@@ -63,10 +351,16 @@ pub enum QueryFilter {
     Category(CategoryFilter),
     /// Message pattern filter
     Message(MessageFilter),
+    /// Full-text search over messages and codes (`CONTAINS_TEXT`)
+    FullText(FullTextFilter),
     /// Time range filter
     TimeRange(TimeRange),
     /// File count comparison
     FileCount(ComparisonFilter),
+    /// Numeric field comparison (e.g. `confidence > 0.8`)
+    Comparison(ComparisonFilter),
+    /// Membership in a list or subquery (`file IN (SELECT file FROM files WHERE errors > 10)`)
+    In(InFilter),
     /// Custom field filter
     Custom(String, String), // field, value
 }
@@ -98,6 +392,40 @@ pub struct MessageFilter {
     pub is_regex: bool,
 }
 
+/// Field searched by a `CONTAINS_TEXT` filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextField {
+    Message,
+    Code,
+    /// Message or code
+    Any,
+}
+
+/// Full-text filtering: every word of `text` must appear in the field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FullTextFilter {
+    pub field: TextField,
+    pub text: String,
+}
+
+/// `field [NOT] IN (...)` filtering
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InFilter {
+    pub field: String,
+    pub list: InList,
+    /// `NOT IN`
+    pub negated: bool,
+}
+
+/// Values an [`InFilter`] tests against
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum InList {
+    /// Literal values, compared as text
+    Values(Vec<String>),
+    /// A single-column query, replaced by its values before execution
+    Subquery(Box<Query>),
+}
+
 /// File-based filtering
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FileFilter {
@@ -127,6 +455,10 @@ pub enum RelativeTime {
     LastDays(u32),
     /// Last N weeks
     LastWeeks(u32),
+    /// Last N calendar months
+    LastMonths(u32),
+    /// The current calendar hour, day, week or month so far (`THIS WEEK`)
+    Current(CalendarUnit),
     /// Since last commit
     LastCommit,
     /// Since specific commit
@@ -153,6 +485,10 @@ pub enum Comparison {
 }
 
 /// Query aggregation functions
+///
+/// In a SELECT list an aggregation is a result column named by its
+/// [`Display`](fmt::Display) form, e.g. `AVG(line)` or `PERCENTILE(line, 90)`,
+/// which [`QueryAggregation::from_column`] turns back into the function.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum QueryAggregation {
     Count(String),         // COUNT(field)
@@ -160,6 +496,71 @@ pub enum QueryAggregation {
     Average(String),       // AVG(field)
     Min(String),           // MIN(field)
     Max(String),           // MAX(field)
+    DistinctCount(String), // DISTINCT_COUNT(field)
+    /// `PERCENTILE(field, p)` with `p` between 0 and 100
+    Percentile(String, f64),
+}
+
+impl QueryAggregation {
+    /// Build an aggregation from its (case-insensitive) function name
+    pub fn from_name(name: &str, field: String, percentile: Option<f64>) -> Option<Self> {
+        match name.to_uppercase().as_str() {
+            "COUNT" => Some(Self::Count(field)),
+            "SUM" => Some(Self::Sum(field)),
+            "AVG" | "AVERAGE" => Some(Self::Average(field)),
+            "MIN" => Some(Self::Min(field)),
+            "MAX" => Some(Self::Max(field)),
+            "DISTINCT_COUNT" => Some(Self::DistinctCount(field)),
+            "PERCENTILE" => percentile.map(|p| Self::Percentile(field, p)),
+            _ => None,
+        }
+    }
+
+    /// Parse a result column written as an aggregation
+    pub fn from_column(column: &str) -> Option<Self> {
+        let (name, rest) = column.split_once('(')?;
+        let args = rest.strip_suffix(')')?;
+        let (field, percentile) = match args.split_once(',') {
+            Some((field, p)) => (field.trim(), Some(p.trim().parse::<f64>().ok()?)),
+            None => (args.trim(), None),
+        };
+        if field.is_empty() || percentile.is_some() != name.trim().eq_ignore_ascii_case("PERCENTILE") {
+            return None;
+        }
+        Self::from_name(name.trim(), field.to_string(), percentile)
+    }
+
+    /// Field the function is applied to; `*` for `COUNT(*)`
+    pub fn field(&self) -> &str {
+        match self {
+            Self::Count(field)
+            | Self::Sum(field)
+            | Self::Average(field)
+            | Self::Min(field)
+            | Self::Max(field)
+            | Self::DistinctCount(field)
+            | Self::Percentile(field, _) => field,
+        }
+    }
+
+    /// Whether the function only makes sense over numeric values
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, Self::Sum(_) | Self::Average(_) | Self::Percentile(..))
+    }
+}
+
+impl fmt::Display for QueryAggregation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Count(field) => write!(f, "COUNT({field})"),
+            Self::Sum(field) => write!(f, "SUM({field})"),
+            Self::Average(field) => write!(f, "AVG({field})"),
+            Self::Min(field) => write!(f, "MIN({field})"),
+            Self::Max(field) => write!(f, "MAX({field})"),
+            Self::DistinctCount(field) => write!(f, "DISTINCT_COUNT({field})"),
+            Self::Percentile(field, p) => write!(f, "PERCENTILE({field}, {p})"),
+        }
+    }
 }
 
 /// GROUP BY clause
@@ -193,6 +594,7 @@ impl Query {
             order_by: None,
             limit: None,
             time_range: None,
+            join: None,
         }
     }
 
@@ -231,6 +633,18 @@ impl Query {
         self.group_by = Some(GroupByClause { fields });
         self
     }
+
+    /// Subqueries of `IN` filters, on `FROM` and on the joined source
+    pub fn subqueries(&self) -> impl Iterator<Item = &Query> {
+        let join_filters = self.join.iter().flat_map(|join| join.filters.iter());
+        self.filters.iter().chain(join_filters).filter_map(|filter| match filter {
+            QueryFilter::In(InFilter {
+                list: InList::Subquery(query),
+                ..
+            }) => Some(query.as_ref()),
+            _ => None,
+        })
+    }
 }
 
 impl Default for Query {
@@ -275,7 +689,107 @@ impl MessageFilter {
     }
 }
 
+impl TextField {
+    /// Field named on the left of `CONTAINS_TEXT`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "message" => Some(Self::Message),
+            "code" => Some(Self::Code),
+            "text" => Some(Self::Any),
+            _ => None,
+        }
+    }
+}
+
+impl InFilter {
+    /// Whether `value` passes the filter
+    ///
+    /// Severities compare by level, so `'info'` matches `Information`.
+    /// Subqueries must be resolved first; an unresolved one matches nothing.
+    pub fn matches(&self, value: &str) -> bool {
+        let severity = |s: &str| s.parse::<DiagnosticSeverity>().ok();
+        let found = match &self.list {
+            InList::Values(values) if self.field == "severity" => {
+                severity(value).is_some_and(|level| values.iter().any(|v| severity(v) == Some(level)))
+            }
+            InList::Values(values) => values.iter().any(|v| v == value),
+            InList::Subquery(_) => false,
+        };
+        found != self.negated
+    }
+}
+
+impl FullTextFilter {
+    /// Create a full-text filter over a field
+    pub fn new(field: TextField, text: impl Into<String>) -> Self {
+        Self {
+            field,
+            text: text.into(),
+        }
+    }
+
+    /// Lowercased words of the search text, split like the history index tokenizer
+    pub fn terms(&self) -> Vec<String> {
+        tokenize(&self.text)
+    }
+
+    /// Whether a diagnostic's message and code contain every search term
+    pub fn matches(&self, message: &str, code: Option<&str>) -> bool {
+        let terms = self.terms();
+        if terms.is_empty() {
+            return false;
+        }
+        let mut words = match self.field {
+            TextField::Message => tokenize(message),
+            TextField::Code => tokenize(code.unwrap_or_default()),
+            TextField::Any => tokenize(&format!("{message} {}", code.unwrap_or_default())),
+        };
+        words.sort_unstable();
+        terms.iter().all(|term| words.binary_search(term).is_ok())
+    }
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+impl RelativeTime {
+    /// `LAST <count> <unit>`
+    pub fn last(count: u32, unit: CalendarUnit) -> Self {
+        match unit {
+            CalendarUnit::Hour => Self::LastHours(count),
+            CalendarUnit::Day => Self::LastDays(count),
+            CalendarUnit::Week => Self::LastWeeks(count),
+            CalendarUnit::Month => Self::LastMonths(count),
+        }
+    }
+}
+
 impl TimeRange {
+    /// Bounds of the range on `calendar`, with relative ranges ending at `now`
+    ///
+    /// `LAST N DAYS` starts at the same local time of day N calendar days ago
+    /// and `THIS WEEK` at the configured week start, both in the calendar's
+    /// time zone. Commit-relative ranges have no time bounds.
+    pub fn resolve(
+        &self,
+        calendar: &CalendarConfig,
+        now: DateTime<Utc>,
+    ) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+        let since = match &self.relative {
+            Some(RelativeTime::LastHours(count)) => Some(calendar.units_before(now, CalendarUnit::Hour, *count)),
+            Some(RelativeTime::LastDays(count)) => Some(calendar.units_before(now, CalendarUnit::Day, *count)),
+            Some(RelativeTime::LastWeeks(count)) => Some(calendar.units_before(now, CalendarUnit::Week, *count)),
+            Some(RelativeTime::LastMonths(count)) => Some(calendar.units_before(now, CalendarUnit::Month, *count)),
+            Some(RelativeTime::Current(unit)) => Some(calendar.bucket_start(now, *unit)),
+            Some(RelativeTime::LastCommit) | Some(RelativeTime::SinceCommit(_)) | None => self.start,
+        };
+        (since, self.end)
+    }
+
     /// Create a time range from absolute start and end times
     pub fn absolute(start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>) -> Self {
         Self {
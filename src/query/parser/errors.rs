@@ -27,12 +27,51 @@ impl QueryValidator {
         valid_fields.insert("timestamp".to_string());
         valid_fields.insert("file_count".to_string());
         valid_fields.insert("files".to_string());
+        valid_fields.insert("target".to_string());
+        valid_fields.insert("taxonomy".to_string());
+        valid_fields.insert("end_line".to_string());
+        valid_fields.insert("line_count".to_string());
+        valid_fields.insert("message_length".to_string());
+        valid_fields.insert("age_days".to_string());
+        valid_fields.insert("occurrences".to_string());
         
         // File-related fields
         valid_fields.insert("file_path".to_string());
         valid_fields.insert("file_size".to_string());
         valid_fields.insert("file_type".to_string());
         valid_fields.insert("language".to_string());
+
+        // File statistics fields
+        valid_fields.insert("errors".to_string());
+        valid_fields.insert("warnings".to_string());
+        valid_fields.insert("total".to_string());
+        
+        // Quick-fix candidate fields
+        valid_fields.insert("file".to_string());
+        valid_fields.insert("title".to_string());
+        valid_fields.insert("confidence".to_string());
+        valid_fields.insert("automatic".to_string());
+        valid_fields.insert("diagnostic".to_string());
+        valid_fields.insert("diagnostic_id".to_string());
+        valid_fields.insert("diff".to_string());
+
+        // Code lens fields
+        valid_fields.insert("symbol".to_string());
+        valid_fields.insert("kind".to_string());
+        valid_fields.insert("command".to_string());
+        valid_fields.insert("references".to_string());
+        valid_fields.insert("test_status".to_string());
+        valid_fields.insert("error_count".to_string());
+        valid_fields.insert("warning_count".to_string());
+
+        // Symbol index fields
+        valid_fields.insert("name".to_string());
+        valid_fields.insert("container".to_string());
+        valid_fields.insert("in_test".to_string());
+
+        // Configuration fields
+        valid_fields.insert("field".to_string());
+        valid_fields.insert("value".to_string());
         
         // Time-related fields
         valid_fields.insert("time".to_string());
@@ -44,6 +83,9 @@ impl QueryValidator {
         valid_data_sources.insert("files".to_string());
         valid_data_sources.insert("history".to_string());
         valid_data_sources.insert("trends".to_string());
+        valid_data_sources.insert("fixes".to_string());
+        valid_data_sources.insert("lenses".to_string());
+        valid_data_sources.insert("config".to_string());
 
         Self {
             valid_fields,
@@ -133,7 +175,7 @@ impl QueryValidator {
                 if self.is_aggregation_function(field) {
                     continue;
                 }
-                if !self.valid_fields.contains(field) {
+                if !self.is_valid_field(query, field) {
                     errors.push(ParseError::UnknownField {
                         field: field.clone(),
                         available_fields: self.valid_fields.iter().cloned().collect(),
@@ -142,6 +184,27 @@ impl QueryValidator {
             }
         }
 
+        // Check columns referenced by computed SELECT items; earlier aliases are in scope
+        let mut aliases: Vec<&str> = Vec::new();
+        if let super::ast::SelectClause::Expressions(items) = &query.select {
+            for item in items {
+                for column in item.expr.columns() {
+                    let known = aliases.iter().any(|alias| alias.eq_ignore_ascii_case(column))
+                        || self.is_aggregation_function(column)
+                        || self.is_valid_field(query, column);
+                    if !known {
+                        errors.push(ParseError::UnknownField {
+                            field: column.to_string(),
+                            available_fields: self.valid_fields.iter().cloned().collect(),
+                        });
+                    }
+                }
+                if let Some(alias) = &item.alias {
+                    aliases.push(alias);
+                }
+            }
+        }
+
         // Check GROUP BY fields
         if let Some(group_by) = &query.group_by {
             for field in &group_by.fields {
@@ -149,7 +212,7 @@ impl QueryValidator {
                 if self.is_aggregation_function(field) {
                     continue;
                 }
-                if !self.valid_fields.contains(field) {
+                if !self.is_valid_field(query, field) {
                     errors.push(ParseError::UnknownField {
                         field: field.clone(),
                         available_fields: self.valid_fields.iter().cloned().collect(),
@@ -161,7 +224,8 @@ impl QueryValidator {
         // Check ORDER BY field
         if let Some(order_by) = &query.order_by {
             // Allow aggregation functions
-            if !self.is_aggregation_function(&order_by.field) && !self.valid_fields.contains(&order_by.field) {
+            let is_alias = aliases.iter().any(|alias| alias.eq_ignore_ascii_case(&order_by.field));
+            if !is_alias && !self.is_aggregation_function(&order_by.field) && !self.is_valid_field(query, &order_by.field) {
                 errors.push(ParseError::UnknownField {
                     field: order_by.field.clone(),
                     available_fields: self.valid_fields.iter().cloned().collect(),
@@ -183,10 +247,9 @@ impl QueryValidator {
         if let SelectClause::Aggregations(aggregations) = &query.select {
             for aggregation in aggregations {
                 match aggregation {
-                    QueryAggregation::Sum(field) | 
-                    QueryAggregation::Average(field) | 
-                    QueryAggregation::Min(field) | 
-                    QueryAggregation::Max(field) => {
+                    QueryAggregation::Sum(field) |
+                    QueryAggregation::Average(field) |
+                    QueryAggregation::Percentile(field, _) => {
                         if field != "*" && !self.is_numeric_field(field) {
                             return Err(ParseError::InvalidAggregation {
                                 function: format!("{aggregation:?}"),
@@ -195,8 +258,11 @@ impl QueryValidator {
                             });
                         }
                     }
-                    QueryAggregation::Count(_) => {
-                        // COUNT is valid on any field
+                    QueryAggregation::Count(_) |
+                    QueryAggregation::DistinctCount(_) |
+                    QueryAggregation::Min(_) |
+                    QueryAggregation::Max(_) => {
+                        // Counting and ordering work on any field
                     }
                 }
             }
@@ -241,6 +307,11 @@ impl QueryValidator {
                             reason: "Time range cannot be zero weeks".to_string(),
                         });
                     }
+                    RelativeTime::LastMonths(months) if *months == 0 => {
+                        return Err(ParseError::InvalidTimeRange {
+                            reason: "Time range cannot be zero months".to_string(),
+                        });
+                    }
                     RelativeTime::LastHours(hours) if *hours > 8760 => {
                         return Err(ParseError::InvalidTimeRange {
                             reason: "Time range cannot exceed 1 year (8760 hours)".to_string(),
@@ -289,22 +360,25 @@ impl QueryValidator {
         matches!(
             field,
             "line" | "column" | "file_size" | "file_count" | "count" | "duration" | "size"
+                | "confidence" | "references" | "error_count" | "warning_count"
+                | "end_line" | "line_count" | "message_length" | "age_days" | "occurrences"
+                | "errors" | "warnings" | "total"
         )
     }
 
     /// Check if a field name is an aggregation function
+    /// Check a field against the schema; joined fields are qualified with a source alias
+    fn is_valid_field(&self, query: &Query, field: &str) -> bool {
+        let field = match (&query.join, field.split_once('.')) {
+            (Some(join), Some((alias, column))) if alias == join.left_alias || alias == join.alias => column,
+            _ => field,
+        };
+        self.valid_fields.contains(field)
+    }
+
     fn is_aggregation_function(&self, field: &str) -> bool {
-        // Check if it matches aggregation patterns like COUNT(*), SUM(field), etc.
-        field.starts_with("COUNT(") ||
-        field.starts_with("count(") ||
-        field.starts_with("SUM(") ||
-        field.starts_with("sum(") ||
-        field.starts_with("AVG(") ||
-        field.starts_with("avg(") ||
-        field.starts_with("MIN(") ||
-        field.starts_with("min(") ||
-        field.starts_with("MAX(") ||
-        field.starts_with("max(")
+        // Aggregation columns like COUNT(*), SUM(field) or PERCENTILE(field, 90)
+        super::ast::QueryAggregation::from_column(field).is_some()
     }
 
     /// Add a custom field to the validator
@@ -413,6 +487,7 @@ mod tests {
             order_by: None,
             limit: Some(100),
             time_range: None,
+            join: None,
         };
 
         assert!(validator.validate(&query).is_ok());
@@ -430,6 +505,7 @@ mod tests {
             order_by: None,
             limit: None,
             time_range: None,
+            join: None,
         };
 
         assert!(validator.validate(&query).is_err());
@@ -447,6 +523,7 @@ mod tests {
             order_by: None,
             limit: Some(0),
             time_range: None,
+            join: None,
         };
 
         assert!(validator.validate(&query).is_err());
@@ -462,6 +539,7 @@ mod tests {
             order_by: None,
             limit: None,
             time_range: None,
+            join: None,
         };
 
         let suggestions = QueryOptimizer::analyze(&query);
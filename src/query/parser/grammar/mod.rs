@@ -133,6 +133,7 @@ mod tests {
             group_by: None,
             order_by: None,
             limit: None,
+            join: None,
         };
         assert!(GrammarValidator::validate_query(&valid_query).is_ok());
 
@@ -145,6 +146,7 @@ mod tests {
             group_by: Some(GroupByClause { fields: Vec::new() }),
             order_by: None,
             limit: None,
+            join: None,
         };
         assert!(GrammarValidator::validate_query(&invalid_query).is_err());
     }
@@ -5,10 +5,19 @@ use super::utilities::ParserUtilities;
 use super::super::ast::*;
 use super::super::lexer::{Token, TokenType};
 use crate::core::errors::ParseError;
-use crate::core::DiagnosticSeverity;
+use crate::core::{CalendarUnit, DiagnosticSeverity};
 use chrono::{DateTime, Utc};
 use std::str::FromStr;
 
+/// Result of the expression sub-parser
+///
+/// The error is boxed because expressions recurse deeply and `ParseError` is
+/// large; `?` boxes plain `ParseResult` errors automatically.
+type ExprResult<T> = Result<T, Box<ParseError>>;
+
+/// Identifiers that end a data source instead of naming its alias
+const JOIN_KEYWORDS: &[&str] = &["join", "inner", "left", "outer", "on"];
+
 /// Recursive descent parser for the query language
 pub struct Parser {
     state: ParserState,
@@ -41,6 +50,13 @@ impl Parser {
 
     /// Parse the tokens into a Query AST
     pub fn parse(&mut self) -> ParseResult<Query> {
+        if let Some(token) = self.state.tokens.iter().find(|t| t.token_type == TokenType::Placeholder) {
+            return Err(ParseError::UnboundParameter {
+                line: token.line,
+                column: token.column,
+            });
+        }
+
         self.context.enter_rule(ProductionRule::Query);
         let result = self.parse_query();
         self.context.exit_rule();
@@ -51,13 +67,15 @@ impl Parser {
     fn parse_query(&mut self) -> ParseResult<Query> {
         let select = self.parse_select_clause()?;
         let from = self.parse_from_clause()?;
+        let left_alias = self.parse_source_alias().map_err(|e| *e)?.unwrap_or_else(|| from.name().to_string());
+        let mut join = self.parse_join_clause(&left_alias).map_err(|e| *e)?;
         
         let mut filters = Vec::new();
         let mut time_range = None;
         
         // Optional WHERE clause
         if self.state.match_token(&TokenType::Where) {
-            let (parsed_filters, parsed_time_range) = self.parse_where_clause()?;
+            let (parsed_filters, parsed_time_range) = self.parse_where_clause(&left_alias, &mut join).map_err(|e| *e)?;
             filters = parsed_filters;
             time_range = parsed_time_range;
         }
@@ -91,6 +109,7 @@ impl Parser {
             group_by,
             order_by,
             limit,
+            join,
         };
 
         // Validate the parsed query
@@ -108,21 +127,14 @@ impl Parser {
         
         let result = if self.state.match_token(&TokenType::Asterisk) {
             SelectClause::All
-        } else if self.state.check(&TokenType::Count) {
+        } else if self.check_bare_count() {
             self.state.advance(); // consume COUNT
             self.state.consume(TokenType::LeftParen, "Expected '(' after COUNT")?;
             self.state.consume(TokenType::Asterisk, "Expected '*' in COUNT(*)")?;
             self.state.consume(TokenType::RightParen, "Expected ')' after COUNT(*)")?;
             SelectClause::Count
-        } else if self.state.check_identifier() || 
-                  self.state.check(&TokenType::Errors) ||
-                  self.state.check(&TokenType::Warnings) ||
-                  self.state.check(&TokenType::Files) ||
-                  self.state.check(&TokenType::Diagnostics) ||
-                  self.state.check(&TokenType::History) ||
-                  self.state.check(&TokenType::Trends) {
-            let fields = self.parse_field_list()?;
-            SelectClause::Fields(fields)
+        } else if self.check_expression_start() || self.check_aggregation() {
+            self.parse_select_list().map_err(|e| *e)?
         } else {
             return Err(ParseError::UnexpectedToken {
                 expected: "*, COUNT(*), or field list".to_string(),
@@ -142,7 +154,14 @@ impl Parser {
         self.context.expect_token(TokenType::From);
         
         self.state.consume(TokenType::From, "Expected 'FROM'")?;
+        let result = self.parse_source().map_err(|e| *e)?;
         
+        self.context.exit_rule();
+        Ok(result)
+    }
+
+    /// Parse a data source name
+    fn parse_source(&mut self) -> ExprResult<FromClause> {
         // Check for table name - can be a keyword token or identifier
         let result = if self.state.check(&TokenType::Diagnostics) {
             self.state.advance();
@@ -166,27 +185,182 @@ impl Parser {
                 "projects" => FromClause::Projects,
                 "history" => FromClause::History,
                 "trends" => FromClause::Trends,
-                _ => return Err(ParseError::UnknownTable {
+                "fixes" => FromClause::Fixes,
+                "lenses" => FromClause::Lenses,
+                "config" => FromClause::Config,
+                _ => return Err(Box::new(ParseError::UnknownTable {
                     table: token.lexeme.clone(),
                     line: token.line,
                     column: token.column,
-                }),
+                })),
             }
         } else {
-            return Err(ParseError::UnexpectedToken {
+            return Err(Box::new(ParseError::UnexpectedToken {
                 expected: "table name".to_string(),
                 found: self.state.peek().lexeme.clone(),
                 line: self.state.peek().line,
                 column: self.state.peek().column,
-            });
+            }));
         };
-        
-        self.context.exit_rule();
         Ok(result)
     }
 
+    /// Parse an optional `[AS] alias` after a data source
+    fn parse_source_alias(&mut self) -> ExprResult<Option<String>> {
+        if self.state.match_token(&TokenType::As) {
+            return self.parse_alias().map(Some);
+        }
+        let lexeme = &self.state.peek().lexeme;
+        if self.state.check_identifier() && !JOIN_KEYWORDS.iter().any(|k| lexeme.eq_ignore_ascii_case(k)) {
+            return Ok(Some(self.state.advance().lexeme.clone()));
+        }
+        Ok(None)
+    }
+
+    /// Parse an optional `[INNER | LEFT [OUTER]] JOIN <source> [alias] ON a.column = b.column`
+    fn parse_join_clause(&mut self, left_alias: &str) -> ExprResult<Option<JoinClause>> {
+        let kind = if self.match_keyword("left") {
+            self.match_keyword("outer");
+            Some(JoinKind::Left)
+        } else if self.match_keyword("inner") {
+            Some(JoinKind::Inner)
+        } else {
+            None
+        };
+        if kind.is_some() {
+            self.expect_keyword("join")?;
+        } else if !self.match_keyword("join") {
+            return Ok(None);
+        }
+
+        self.context.enter_rule(ProductionRule::JoinClause);
+        let source = self.parse_source()?;
+        let alias_token = self.state.peek().clone();
+        let alias = self.parse_source_alias()?.unwrap_or_else(|| source.name().to_string());
+        if alias == left_alias {
+            return Err(Box::new(ParseError::UnexpectedToken {
+                expected: format!("an alias other than '{left_alias}' for the joined source"),
+                found: alias,
+                line: alias_token.line,
+                column: alias_token.column,
+            }));
+        }
+
+        self.expect_keyword("on")?;
+        let condition_token = self.state.peek().clone();
+        let (first_alias, first) = self.parse_qualified_column()?;
+        self.state.consume(TokenType::Equal, "Expected '=' in JOIN condition")?;
+        let (second_alias, second) = self.parse_qualified_column()?;
+        let (left_column, right_column) = if first_alias == left_alias && second_alias == alias {
+            (first, second)
+        } else if first_alias == alias && second_alias == left_alias {
+            (second, first)
+        } else {
+            return Err(Box::new(ParseError::UnexpectedToken {
+                expected: format!("ON {left_alias}.<column> = {alias}.<column>"),
+                found: format!("{first_alias}.{first} = {second_alias}.{second}"),
+                line: condition_token.line,
+                column: condition_token.column,
+            }));
+        };
+
+        self.context.exit_rule();
+        Ok(Some(JoinClause {
+            kind: kind.unwrap_or_default(),
+            left_alias: left_alias.to_string(),
+            source,
+            alias,
+            left_column,
+            right_column,
+            filters: Vec::new(),
+        }))
+    }
+
+    /// Parse `alias.column` into its two parts
+    fn parse_qualified_column(&mut self) -> ExprResult<(String, String)> {
+        let Some(alias) = self.parse_qualifier() else {
+            return Err(Box::new(ParseError::UnexpectedToken {
+                expected: "qualified column (alias.column)".to_string(),
+                found: self.state.peek().lexeme.clone(),
+                line: self.state.peek().line,
+                column: self.state.peek().column,
+            }));
+        };
+        if self.state.check_identifier() || self.check_keyword_column() {
+            Ok((alias, self.state.advance().lexeme.clone()))
+        } else {
+            Err(Box::new(ParseError::UnexpectedToken {
+                expected: "column name after '.'".to_string(),
+                found: self.state.peek().lexeme.clone(),
+                line: self.state.peek().line,
+                column: self.state.peek().column,
+            }))
+        }
+    }
+
+    /// Consume `alias.` and return the alias, if the current tokens start a qualified name
+    fn parse_qualifier(&mut self) -> Option<String> {
+        let dot_follows = self
+            .state
+            .tokens
+            .get(self.state.current + 1)
+            .is_some_and(|t| t.token_type == TokenType::Dot);
+        if !dot_follows || !(self.state.check_identifier() || self.check_keyword_column()) {
+            return None;
+        }
+        let alias = self.state.advance().lexeme.clone();
+        self.state.advance();
+        Some(alias)
+    }
+
+    /// Extend a column name just consumed into `name.column` when a `.` follows
+    fn parse_qualified_name(&mut self, name: String) -> ExprResult<String> {
+        if !self.state.match_token(&TokenType::Dot) {
+            return Ok(name);
+        }
+        if self.state.check_identifier() || self.check_keyword_column() {
+            Ok(format!("{name}.{}", self.state.advance().lexeme))
+        } else {
+            Err(Box::new(ParseError::UnexpectedToken {
+                expected: "column name after '.'".to_string(),
+                found: self.state.peek().lexeme.clone(),
+                line: self.state.peek().line,
+                column: self.state.peek().column,
+            }))
+        }
+    }
+
+    /// Consume an identifier matching `keyword`, ignoring case
+    fn match_keyword(&mut self, keyword: &str) -> bool {
+        if self.state.check_identifier() && self.state.peek().lexeme.eq_ignore_ascii_case(keyword) {
+            self.state.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> ExprResult<()> {
+        if self.match_keyword(keyword) {
+            return Ok(());
+        }
+        Err(Box::new(ParseError::UnexpectedToken {
+            expected: keyword.to_uppercase(),
+            found: self.state.peek().lexeme.clone(),
+            line: self.state.peek().line,
+            column: self.state.peek().column,
+        }))
+    }
+
     /// Parse WHERE clause
-    fn parse_where_clause(&mut self) -> ParseResult<(Vec<QueryFilter>, Option<TimeRange>)> {
+    ///
+    /// Conditions on a column qualified with the joined source's alias
+    /// (`h.error_count > 3`) go to the join; all others apply to `FROM`.
+    fn parse_where_clause(
+        &mut self,
+        left_alias: &str,
+        join: &mut Option<JoinClause>,
+    ) -> ExprResult<(Vec<QueryFilter>, Option<TimeRange>)> {
         self.context.enter_rule(ProductionRule::WhereClause);
         
         let mut filters = Vec::new();
@@ -194,13 +368,26 @@ impl Parser {
         
         // Parse filters with AND/OR operators
         loop {
+            let qualifier_token = self.state.peek().clone();
+            let qualifier = self.parse_qualifier();
             let filter = self.parse_filter_expression()?;
             
             // Check if this is a time range filter
             if let QueryFilter::TimeRange(ref tr) = filter {
                 time_range = Some(tr.clone());
             } else {
-                filters.push(filter);
+                let target = match (qualifier, join.as_mut()) {
+                    (Some(alias), Some(join)) if alias == join.alias => &mut join.filters,
+                    (Some(alias), _) if alias != left_alias => {
+                        return Err(Box::new(ParseError::UnknownTable {
+                            table: alias,
+                            line: qualifier_token.line,
+                            column: qualifier_token.column,
+                        }));
+                    }
+                    _ => &mut filters,
+                };
+                target.push(filter);
             }
             
             // Check for logical operators
@@ -219,12 +406,27 @@ impl Parser {
     fn parse_filter_expression(&mut self) -> ParseResult<QueryFilter> {
         self.context.enter_rule(ProductionRule::FilterExpression);
         
-        let result = if self.state.check(&TokenType::Last) {
+        let result = if self.state.check(&TokenType::Last) || self.state.peek().lexeme.eq_ignore_ascii_case("this") {
             self.parse_relative_time_filter()
-        } else if self.state.check_identifier() {
+        } else if self.state.check_identifier() || self.check_keyword_column() {
             let field = self.state.advance().lexeme.clone();
             
             match field.as_str() {
+                _ if self.check_in() => self.parse_in_filter(field).map_err(|e| *e),
+                _ if self.state.match_token(&TokenType::ContainsText) => {
+                    let text = self.parse_string_or_identifier()?;
+                    match TextField::from_name(&field) {
+                        Some(text_field) if !text.trim().is_empty() => {
+                            Ok(QueryFilter::FullText(FullTextFilter::new(text_field, text)))
+                        }
+                        _ => Err(ParseError::UnexpectedToken {
+                            expected: "message, code or text CONTAINS_TEXT 'words'".to_string(),
+                            found: format!("{field} CONTAINS_TEXT '{text}'"),
+                            line: self.state.previous().line,
+                            column: self.state.previous().column,
+                        }),
+                    }
+                }
                 "severity" => self.parse_severity_filter(),
                 "file" => self.parse_file_filter(),
                 "symbol" => self.parse_symbol_filter(),
@@ -244,6 +446,48 @@ impl Parser {
         result
     }
 
+    /// Whether the next tokens are `IN` or `NOT IN`
+    fn check_in(&self) -> bool {
+        self.state.check(&TokenType::In)
+            || (self.state.peek().lexeme.eq_ignore_ascii_case("not")
+                && self
+                    .state
+                    .tokens
+                    .get(self.state.current + 1)
+                    .is_some_and(|token| token.token_type == TokenType::In))
+    }
+
+    /// Parse `[NOT] IN ('a', 'b')` or `[NOT] IN (SELECT column FROM ...)` after its field
+    fn parse_in_filter(&mut self, field: String) -> ExprResult<QueryFilter> {
+        let negated = !self.state.check(&TokenType::In);
+        if negated {
+            self.state.advance();
+        }
+        self.state.consume(TokenType::In, "Expected 'IN'")?;
+        self.state.consume(TokenType::LeftParen, "Expected '(' after IN")?;
+
+        let list = if self.state.check(&TokenType::Select) {
+            InList::Subquery(Box::new(self.parse_query()?))
+        } else {
+            let mut values = Vec::new();
+            loop {
+                let value = if self.state.check_number() {
+                    self.state.advance().lexeme.clone()
+                } else {
+                    self.parse_string_or_identifier()?
+                };
+                values.push(value);
+                if !self.state.match_token(&TokenType::Comma) {
+                    break;
+                }
+            }
+            InList::Values(values)
+        };
+        self.state.consume(TokenType::RightParen, "Expected ')' to close IN")?;
+
+        Ok(QueryFilter::In(InFilter { field, list, negated }))
+    }
+
     /// Parse GROUP BY clause
     fn parse_group_by_clause(&mut self) -> ParseResult<GroupByClause> {
         self.context.enter_rule(ProductionRule::GroupByClause);
@@ -265,33 +509,8 @@ impl Parser {
         self.state.consume(TokenType::By, "Expected 'BY' after 'ORDER'")?;
         
         // Parse field or aggregation function
-        let field = if self.state.check(&TokenType::Count) || 
-                       self.state.check(&TokenType::Sum) ||
-                       self.state.check(&TokenType::Avg) ||
-                       self.state.check(&TokenType::Min) ||
-                       self.state.check(&TokenType::Max) {
-            let func = self.state.advance().lexeme.clone();
-            // Handle COUNT(*) and other aggregation functions
-            if self.state.check(&TokenType::LeftParen) {
-                self.state.advance();
-                let arg = if self.state.check(&TokenType::Asterisk) {
-                    self.state.advance();
-                    "*".to_string()
-                } else if self.state.check_identifier() {
-                    self.state.advance().lexeme.clone()
-                } else {
-                    return Err(ParseError::UnexpectedToken {
-                        expected: "field name or *".to_string(),
-                        found: self.state.peek().lexeme.clone(),
-                        line: self.state.peek().line,
-                        column: self.state.peek().column,
-                    });
-                };
-                self.state.consume(TokenType::RightParen, "Expected ')' after aggregation function")?;
-                format!("{func}({arg})")
-            } else {
-                func
-            }
+        let field = if self.check_aggregation() {
+            self.parse_aggregation_field().map_err(|e| *e)?
         } else if self.state.check_identifier() || 
                   self.state.check(&TokenType::Errors) ||
                   self.state.check(&TokenType::Warnings) ||
@@ -299,7 +518,8 @@ impl Parser {
                   self.state.check(&TokenType::Diagnostics) ||
                   self.state.check(&TokenType::History) ||
                   self.state.check(&TokenType::Trends) {
-            self.state.advance().lexeme.clone()
+            let name = self.state.advance().lexeme.clone();
+            self.parse_qualified_name(name).map_err(|e| *e)?
         } else {
             return Err(ParseError::UnexpectedToken {
                 expected: "field name".to_string(),
@@ -353,42 +573,11 @@ impl Parser {
         
         loop {
             // Check for aggregation functions first
-            let field = if self.state.check(&TokenType::Count) || 
-                          self.state.check(&TokenType::Sum) ||
-                          self.state.check(&TokenType::Avg) ||
-                          self.state.check(&TokenType::Min) ||
-                          self.state.check(&TokenType::Max) {
-                let func = self.state.advance().lexeme.clone();
-                // Handle COUNT(*) and other aggregation functions
-                if self.state.check(&TokenType::LeftParen) {
-                    self.state.advance();
-                    let arg = if self.state.check(&TokenType::Asterisk) {
-                        self.state.advance();
-                        "*".to_string()
-                    } else if self.state.check_identifier() {
-                        self.state.advance().lexeme.clone()
-                    } else {
-                        return Err(ParseError::UnexpectedToken {
-                            expected: "field name or *".to_string(),
-                            found: self.state.peek().lexeme.clone(),
-                            line: self.state.peek().line,
-                            column: self.state.peek().column,
-                        });
-                    };
-                    self.state.consume(TokenType::RightParen, "Expected ')' after aggregation function")?;
-                    format!("{func}({arg})")
-                } else {
-                    func
-                }
-            } else if self.state.check_identifier() {
-                self.state.advance().lexeme.clone()
-            } else if self.state.check(&TokenType::Errors) ||
-                      self.state.check(&TokenType::Warnings) ||
-                      self.state.check(&TokenType::Files) ||
-                      self.state.check(&TokenType::Diagnostics) ||
-                      self.state.check(&TokenType::History) ||
-                      self.state.check(&TokenType::Trends) {
-                self.state.advance().lexeme.clone()
+            let field = if self.check_aggregation() {
+                self.parse_aggregation_field().map_err(|e| *e)?
+            } else if self.state.check_identifier() || self.check_keyword_column() {
+                let name = self.state.advance().lexeme.clone();
+                self.parse_qualified_name(name).map_err(|e| *e)?
             } else {
                 return Err(ParseError::UnexpectedToken {
                     expected: "field name".to_string(),
@@ -409,6 +598,367 @@ impl Parser {
         Ok(fields)
     }
 
+    /// Check for a SELECT list that is just `COUNT(*)`
+    ///
+    /// `COUNT(*)` followed by more columns or an alias is an ordinary
+    /// aggregation column instead.
+    fn check_bare_count(&self) -> bool {
+        let ahead = |offset: usize| self.state.tokens.get(self.state.current + offset).map(|t| &t.token_type);
+        self.state.check(&TokenType::Count)
+            && ahead(1) == Some(&TokenType::LeftParen)
+            && ahead(2) == Some(&TokenType::Asterisk)
+            && ahead(3) == Some(&TokenType::RightParen)
+            && !matches!(ahead(4), Some(TokenType::Comma) | Some(TokenType::As))
+    }
+
+    /// Check for an aggregation function keyword
+    fn check_aggregation(&self) -> bool {
+        self.state.check(&TokenType::Count) ||
+            self.state.check(&TokenType::Sum) ||
+            self.state.check(&TokenType::Avg) ||
+            self.state.check(&TokenType::Min) ||
+            self.state.check(&TokenType::Max) ||
+            self.state.check(&TokenType::DistinctCount) ||
+            self.state.check(&TokenType::Percentile)
+    }
+
+    /// Parse `COUNT(*)`, `SUM(field)`, `PERCENTILE(field, p)`, ... (or a bare
+    /// `count` column) into its result column name
+    ///
+    /// Aggregations are named in their canonical upper-case form, so
+    /// `avg(line)` in SELECT and `AVG(line)` in ORDER BY refer to the same column.
+    fn parse_aggregation_field(&mut self) -> ExprResult<String> {
+        let func_token = self.state.advance().clone();
+        let func = func_token.lexeme.clone();
+        if !self.state.check(&TokenType::LeftParen) {
+            return Ok(func);
+        }
+        self.state.advance();
+        let arg = if self.state.check(&TokenType::Asterisk) && func.eq_ignore_ascii_case("count") {
+            self.state.advance();
+            "*".to_string()
+        } else if self.state.check_identifier() || self.check_keyword_column() {
+            let name = self.state.advance().lexeme.clone();
+            self.parse_qualified_name(name)?
+        } else {
+            return Err(Box::new(ParseError::UnexpectedToken {
+                expected: "field name or *".to_string(),
+                found: self.state.peek().lexeme.clone(),
+                line: self.state.peek().line,
+                column: self.state.peek().column,
+            }));
+        };
+
+        let percentile = if matches!(func_token.token_type, TokenType::Percentile) {
+            self.state.consume(TokenType::Comma, "Expected ', <percentile>' in PERCENTILE(field, p)")?;
+            let p = self.parse_number_value()?;
+            if !(0.0..=100.0).contains(&p) {
+                return Err(Box::new(ParseError::InvalidAggregation {
+                    function: "PERCENTILE".to_string(),
+                    field: arg,
+                    reason: format!("percentile must be between 0 and 100, got {p}"),
+                }));
+            }
+            Some(p)
+        } else {
+            None
+        };
+        self.state.consume(TokenType::RightParen, "Expected ')' after aggregation function")?;
+
+        let aggregation = QueryAggregation::from_name(&func, arg, percentile).ok_or_else(|| ParseError::UnexpectedToken {
+            expected: "aggregation function".to_string(),
+            found: func.clone(),
+            line: func_token.line,
+            column: func_token.column,
+        })?;
+        Ok(aggregation.to_string())
+    }
+
+    /// Check for a data source keyword used as a column name (`errors`, `files`, ...)
+    fn check_keyword_column(&self) -> bool {
+        self.state.check(&TokenType::Errors) ||
+            self.state.check(&TokenType::Warnings) ||
+            self.state.check(&TokenType::Files) ||
+            self.state.check(&TokenType::Diagnostics) ||
+            self.state.check(&TokenType::History) ||
+            self.state.check(&TokenType::Trends)
+    }
+
+    /// Check whether the current token can start a SELECT item
+    fn check_expression_start(&self) -> bool {
+        self.state.check_identifier() ||
+            self.state.check_number() ||
+            self.state.check_string() ||
+            self.check_keyword_column() ||
+            self.state.check(&TokenType::Null) ||
+            self.state.check(&TokenType::Case) ||
+            self.state.check(&TokenType::LeftParen) ||
+            self.state.check(&TokenType::Minus)
+    }
+
+    /// Parse a SELECT list of `expr [AS alias]` items
+    ///
+    /// Plain column lists stay `SelectClause::Fields` so engines can project
+    /// them directly; anything computed or renamed becomes
+    /// `SelectClause::Expressions`.
+    fn parse_select_list(&mut self) -> ExprResult<SelectClause> {
+        self.context.enter_rule(ProductionRule::FieldList);
+
+        let mut items = Vec::new();
+        loop {
+            let expr = if self.check_aggregation() {
+                Expr::Column(self.parse_aggregation_field()?)
+            } else {
+                self.parse_expression()?
+            };
+            let alias = if self.state.match_token(&TokenType::As) {
+                Some(self.parse_alias()?)
+            } else {
+                None
+            };
+            items.push(SelectItem { expr, alias });
+
+            if !self.state.match_token(&TokenType::Comma) {
+                break;
+            }
+        }
+
+        self.context.exit_rule();
+
+        let plain = items.iter().all(|item| item.alias.is_none() && matches!(item.expr, Expr::Column(_)));
+        if plain {
+            let fields = items
+                .into_iter()
+                .filter_map(|item| match item.expr {
+                    Expr::Column(name) => Some(name),
+                    _ => None,
+                })
+                .collect();
+            Ok(SelectClause::Fields(fields))
+        } else {
+            Ok(SelectClause::Expressions(items))
+        }
+    }
+
+    /// Parse the name following `AS`
+    fn parse_alias(&mut self) -> ExprResult<String> {
+        if self.state.check_identifier() || self.state.check_string() || self.check_keyword_column() {
+            let token = self.state.advance();
+            Ok(token.lexeme.clone())
+        } else {
+            Err(Box::new(ParseError::UnexpectedToken {
+                expected: "alias after AS".to_string(),
+                found: self.state.peek().lexeme.clone(),
+                line: self.state.peek().line,
+                column: self.state.peek().column,
+            }))
+        }
+    }
+
+    /// Parse an expression: OR binds loosest, then AND, comparisons,
+    /// `+ - ||`, `* / %` and finally unary minus
+    fn parse_expression(&mut self) -> ExprResult<Expr> {
+        let mut left = self.parse_and_expression()?;
+        while self.state.match_token(&TokenType::Or) {
+            let right = self.parse_and_expression()?;
+            left = binary(BinaryOperator::Or, left, right);
+        }
+        Ok(left)
+    }
+
+    fn parse_and_expression(&mut self) -> ExprResult<Expr> {
+        let mut left = self.parse_comparison_expression()?;
+        while self.state.match_token(&TokenType::And) {
+            let right = self.parse_comparison_expression()?;
+            left = binary(BinaryOperator::And, left, right);
+        }
+        Ok(left)
+    }
+
+    fn parse_comparison_expression(&mut self) -> ExprResult<Expr> {
+        let left = self.parse_additive_expression()?;
+        let comparison = match self.state.peek().token_type {
+            TokenType::Equal => Comparison::Equal,
+            TokenType::NotEqual => Comparison::NotEqual,
+            TokenType::GreaterThan => Comparison::GreaterThan,
+            TokenType::LessThan => Comparison::LessThan,
+            TokenType::GreaterThanOrEqual => Comparison::GreaterThanOrEqual,
+            TokenType::LessThanOrEqual => Comparison::LessThanOrEqual,
+            _ => return Ok(left),
+        };
+        self.state.advance();
+        let right = self.parse_additive_expression()?;
+        Ok(binary(BinaryOperator::Compare(comparison), left, right))
+    }
+
+    fn parse_additive_expression(&mut self) -> ExprResult<Expr> {
+        let mut left = self.parse_multiplicative_expression()?;
+        loop {
+            let op = match self.state.peek().token_type {
+                TokenType::Plus => BinaryOperator::Add,
+                TokenType::Minus => BinaryOperator::Subtract,
+                TokenType::Concat => BinaryOperator::Concat,
+                _ => return Ok(left),
+            };
+            self.state.advance();
+            let right = self.parse_multiplicative_expression()?;
+            left = binary(op, left, right);
+        }
+    }
+
+    fn parse_multiplicative_expression(&mut self) -> ExprResult<Expr> {
+        let mut left = self.parse_unary_expression()?;
+        loop {
+            let op = match self.state.peek().token_type {
+                TokenType::Asterisk => BinaryOperator::Multiply,
+                TokenType::Slash => BinaryOperator::Divide,
+                TokenType::Percent => BinaryOperator::Modulo,
+                _ => return Ok(left),
+            };
+            self.state.advance();
+            let right = self.parse_unary_expression()?;
+            left = binary(op, left, right);
+        }
+    }
+
+    fn parse_unary_expression(&mut self) -> ExprResult<Expr> {
+        if self.state.match_token(&TokenType::Minus) {
+            return Ok(match self.parse_unary_expression()? {
+                Expr::Integer(i) => Expr::Integer(-i),
+                Expr::Number(n) => Expr::Number(-n),
+                inner => Expr::Negate(Box::new(inner)),
+            });
+        }
+        self.parse_primary_expression()
+    }
+
+    fn parse_primary_expression(&mut self) -> ExprResult<Expr> {
+        let token = self.state.peek().clone();
+        match &token.token_type {
+            TokenType::Number(_) => {
+                self.state.advance();
+                match token.lexeme.parse::<i64>() {
+                    Ok(integer) => Ok(Expr::Integer(integer)),
+                    Err(_) => Ok(Expr::Number(self.value_parser.parse_number_value(&token.lexeme)?)),
+                }
+            }
+            TokenType::String(_) => {
+                self.state.advance();
+                Ok(Expr::String(self.value_parser.parse_string_value(&token.lexeme)))
+            }
+            TokenType::Null => {
+                self.state.advance();
+                Ok(Expr::Null)
+            }
+            TokenType::LeftParen => {
+                self.state.advance();
+                let expr = self.parse_expression()?;
+                self.state.consume(TokenType::RightParen, "Expected ')' after expression")?;
+                Ok(expr)
+            }
+            TokenType::Case => self.parse_case_expression(),
+            _ if self.check_aggregation() => Ok(Expr::Column(self.parse_aggregation_field()?)),
+            TokenType::Identifier(name) => {
+                self.state.advance();
+                if self.state.check(&TokenType::LeftParen) {
+                    self.parse_function_call(name, &token)
+                } else {
+                    Ok(Expr::Column(self.parse_qualified_name(name.clone())?))
+                }
+            }
+            _ if self.check_keyword_column() => {
+                self.state.advance();
+                Ok(Expr::Column(self.parse_qualified_name(token.lexeme.clone())?))
+            }
+            _ => Err(Box::new(ParseError::UnexpectedToken {
+                expected: "expression".to_string(),
+                found: token.lexeme.clone(),
+                line: token.line,
+                column: token.column,
+            })),
+        }
+    }
+
+    /// Parse `name(arg, ...)`; the name has already been consumed
+    fn parse_function_call(&mut self, name: &str, name_token: &Token) -> ExprResult<Expr> {
+        let function = ScalarFunction::from_name(name).ok_or_else(|| ParseError::UnexpectedToken {
+            expected: "scalar function (UPPER, LOWER, LENGTH, TRIM, SUBSTR, REPLACE, CONCAT, COALESCE, NULLIF, ABS, ROUND)"
+                .to_string(),
+            found: name.to_string(),
+            line: name_token.line,
+            column: name_token.column,
+        })?;
+
+        self.state.consume(TokenType::LeftParen, "Expected '(' after function name")?;
+        let mut args = Vec::new();
+        if !self.state.check(&TokenType::RightParen) {
+            loop {
+                args.push(self.parse_expression()?);
+                if !self.state.match_token(&TokenType::Comma) {
+                    break;
+                }
+            }
+        }
+        self.state.consume(TokenType::RightParen, "Expected ')' after function arguments")?;
+
+        let (min, max) = function.arity();
+        if args.len() < min || max.is_some_and(|max| args.len() > max) {
+            let expected = match max {
+                Some(max) if max == min => format!("{min}"),
+                Some(max) => format!("{min} to {max}"),
+                None => format!("at least {min}"),
+            };
+            return Err(Box::new(ParseError::UnexpectedToken {
+                expected: format!("{expected} argument(s) to {}", function.name()),
+                found: format!("{} argument(s)", args.len()),
+                line: name_token.line,
+                column: name_token.column,
+            }));
+        }
+
+        Ok(Expr::Function { function, args })
+    }
+
+    /// Parse `CASE [operand] WHEN ... THEN ... [ELSE ...] END`
+    ///
+    /// The simple form (`CASE x WHEN 1 THEN ...`) is rewritten into
+    /// equality conditions.
+    fn parse_case_expression(&mut self) -> ExprResult<Expr> {
+        self.state.consume(TokenType::Case, "Expected 'CASE'")?;
+        let operand = if self.state.check(&TokenType::When) {
+            None
+        } else {
+            Some(self.parse_expression()?)
+        };
+
+        let mut branches = Vec::new();
+        while self.state.match_token(&TokenType::When) {
+            let mut condition = self.parse_expression()?;
+            if let Some(operand) = &operand {
+                condition = binary(BinaryOperator::Compare(Comparison::Equal), operand.clone(), condition);
+            }
+            self.state.consume(TokenType::Then, "Expected 'THEN' after WHEN condition")?;
+            branches.push((condition, self.parse_expression()?));
+        }
+        if branches.is_empty() {
+            return Err(Box::new(ParseError::UnexpectedToken {
+                expected: "WHEN".to_string(),
+                found: self.state.peek().lexeme.clone(),
+                line: self.state.peek().line,
+                column: self.state.peek().column,
+            }));
+        }
+
+        let otherwise = if self.state.match_token(&TokenType::Else) {
+            Some(Box::new(self.parse_expression()?))
+        } else {
+            None
+        };
+        self.state.consume(TokenType::End, "Expected 'END' to close CASE")?;
+
+        Ok(Expr::Case { branches, otherwise })
+    }
+
     /// Parse severity filter
     fn parse_severity_filter(&mut self) -> ParseResult<QueryFilter> {
         let comparison = self.parse_comparison_operator()?;
@@ -468,31 +1018,41 @@ impl Parser {
 
     /// Parse custom filter
     fn parse_custom_filter(&mut self, field: String) -> ParseResult<QueryFilter> {
-        let _comparison = self.parse_comparison_operator()?; // Custom filter doesn't use comparison
+        let comparison = self.parse_comparison_operator()?;
+        if self.state.check_number() {
+            let value = self.parse_number_value()?;
+            return Ok(QueryFilter::Comparison(ComparisonFilter { field, comparison, value }));
+        }
+        // String comparisons only support equality
         let value = self.parse_string_or_identifier()?;
         Ok(QueryFilter::Custom(field, value))
     }
 
-    /// Parse relative time filter
+    /// Parse relative time filter (`LAST 7 DAYS`, `THIS WEEK`)
     fn parse_relative_time_filter(&mut self) -> ParseResult<QueryFilter> {
+        if self.state.peek().lexeme.eq_ignore_ascii_case("this") {
+            self.state.advance();
+            let Some(unit) = CalendarUnit::parse(&self.state.peek().lexeme) else {
+                return Err(ParseError::UnexpectedToken {
+                    expected: "time unit after THIS (hour, day, week, month)".to_string(),
+                    found: self.state.peek().lexeme.clone(),
+                    line: self.state.peek().line,
+                    column: self.state.peek().column,
+                });
+            };
+            self.state.advance();
+            return Ok(QueryFilter::TimeRange(TimeRange::relative(RelativeTime::Current(unit))));
+        }
         self.state.consume(TokenType::Last, "Expected 'LAST'")?;
         let value = self.parse_number_value()? as u32;
         
-        let relative_time = match &self.state.peek().token_type {
-            TokenType::Hours => {
+        let relative_time = match CalendarUnit::parse(&self.state.peek().lexeme) {
+            Some(unit) => {
                 self.state.advance();
-                RelativeTime::LastHours(value)
+                RelativeTime::last(value, unit)
             }
-            TokenType::Days => {
-                self.state.advance();
-                RelativeTime::LastDays(value)
-            }
-            TokenType::Weeks => {
-                self.state.advance();
-                RelativeTime::LastWeeks(value)
-            }
-            _ => return Err(ParseError::UnexpectedToken {
-                expected: "time unit (hours, days, weeks)".to_string(),
+            None => return Err(ParseError::UnexpectedToken {
+                expected: "time unit (hours, days, weeks, months)".to_string(),
                 found: self.state.peek().lexeme.clone(),
                 line: self.state.peek().line,
                 column: self.state.peek().column,
@@ -566,6 +1126,14 @@ impl Parser {
     }
 }
 
+fn binary(op: BinaryOperator, left: Expr, right: Expr) -> Expr {
+    Expr::Binary {
+        op,
+        left: Box::new(left),
+        right: Box::new(right),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -619,6 +1187,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_calendar_time_filters() {
+        let query = parse_query("SELECT * FROM history WHERE LAST 2 MONTHS").unwrap();
+        assert_eq!(query.time_range.unwrap().relative, Some(RelativeTime::LastMonths(2)));
+
+        let query = parse_query("SELECT * FROM history WHERE THIS WEEK").unwrap();
+        let range = query.time_range.unwrap();
+        assert_eq!(range.relative, Some(RelativeTime::Current(CalendarUnit::Week)));
+
+        // The week starts at Monday midnight in the configured zone
+        let calendar = crate::core::CalendarConfig::default().with_time_zone("+02:00".parse().unwrap());
+        let now = DateTime::parse_from_rfc3339("2026-03-04T10:00:00Z").unwrap().with_timezone(&Utc);
+        let (since, until) = range.resolve(&calendar, now);
+        assert_eq!(since.unwrap().to_rfc3339(), "2026-03-01T22:00:00+00:00");
+        assert!(until.is_none());
+
+        assert!(parse_query("SELECT * FROM history WHERE THIS fortnight").is_err());
+    }
+
+    #[test]
+    fn test_contains_text_filter() {
+        let query = parse_query("SELECT * FROM history WHERE message CONTAINS_TEXT 'borrowed value'").unwrap();
+        assert_eq!(
+            query.filters,
+            vec![QueryFilter::FullText(FullTextFilter::new(TextField::Message, "borrowed value"))]
+        );
+
+        assert!(parse_query("SELECT * FROM history WHERE file CONTAINS_TEXT 'main'").is_err());
+        assert!(parse_query("SELECT * FROM history WHERE code CONTAINS_TEXT ''").is_err());
+    }
+
+    #[test]
+    fn test_fixes_source_with_numeric_comparison() {
+        let query = parse_query("SELECT * FROM fixes WHERE confidence > 0.8 AND language = 'rust'").unwrap();
+        assert_eq!(query.from, FromClause::Fixes);
+        assert_eq!(
+            query.filters,
+            vec![
+                QueryFilter::Comparison(ComparisonFilter {
+                    field: "confidence".to_string(),
+                    comparison: Comparison::GreaterThan,
+                    value: 0.8,
+                }),
+                QueryFilter::Custom("language".to_string(), "rust".to_string()),
+            ]
+        );
+    }
+
     #[test]
     fn test_order_by_and_limit() {
         let query = parse_query("SELECT * FROM diagnostics ORDER BY severity DESC LIMIT 10").unwrap();
@@ -642,6 +1258,119 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_aggregation_functions() {
+        let query = parse_query(
+            "SELECT file, count(*), avg(line), DISTINCT_COUNT(message), percentile(line, 95.5) FROM diagnostics GROUP BY file ORDER BY Avg(line) DESC",
+        )
+        .unwrap();
+        let SelectClause::Fields(fields) = query.select else {
+            panic!("Expected field list");
+        };
+        assert_eq!(fields, vec!["file", "COUNT(*)", "AVG(line)", "DISTINCT_COUNT(message)", "PERCENTILE(line, 95.5)"]);
+        assert_eq!(query.order_by.unwrap().field, "AVG(line)");
+        assert_eq!(
+            QueryAggregation::from_column(&fields[4]),
+            Some(QueryAggregation::Percentile("line".to_string(), 95.5))
+        );
+
+        // A lone COUNT(*) keeps its own select clause
+        assert_eq!(parse_query("SELECT COUNT(*) FROM diagnostics").unwrap().select, SelectClause::Count);
+
+        assert!(parse_query("SELECT PERCENTILE(line) FROM diagnostics").is_err());
+        assert!(parse_query("SELECT PERCENTILE(line, 101) FROM diagnostics").is_err());
+        assert!(parse_query("SELECT SUM(*) FROM diagnostics").is_err());
+    }
+
+    #[test]
+    fn test_computed_select_expressions() {
+        let query = parse_query(
+            "SELECT file, errors + warnings AS total, errors * 1.0 / NULLIF(total, 0) AS error_ratio FROM files",
+        )
+        .unwrap();
+
+        let SelectClause::Expressions(items) = query.select else {
+            panic!("Expected computed select list");
+        };
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].name(), "file");
+        assert_eq!(items[1].name(), "total");
+        assert_eq!(items[1].expr.to_string(), "errors + warnings");
+        // `*` and `/` are left-associative and bind tighter than `+`
+        assert_eq!(items[2].expr.to_string(), "(errors * 1.0) / NULLIF(total, 0)");
+
+        let query = parse_query(
+            "SELECT CASE WHEN severity = 'error' THEN 'fix now' ELSE UPPER(category) END AS action FROM diagnostics",
+        )
+        .unwrap();
+        let SelectClause::Expressions(items) = query.select else {
+            panic!("Expected computed select list");
+        };
+        assert!(matches!(&items[0].expr, Expr::Case { branches, otherwise: Some(_) } if branches.len() == 1));
+
+        assert!(parse_query("SELECT NOPE(file) FROM files").is_err());
+        assert!(parse_query("SELECT NULLIF(file) FROM files").is_err());
+        assert!(parse_query("SELECT CASE END FROM files").is_err());
+    }
+
+    #[test]
+    fn test_join_with_aliases_and_qualified_columns() {
+        let query = parse_query(
+            "SELECT d.file, h.error_count FROM diagnostics d JOIN history h ON h.file = d.file \
+             WHERE h.error_count > 3 AND d.severity = error ORDER BY h.error_count DESC",
+        )
+        .unwrap();
+        assert_eq!(
+            query.select,
+            SelectClause::Fields(vec!["d.file".to_string(), "h.error_count".to_string()])
+        );
+        let join = query.join.unwrap();
+        assert_eq!((join.kind, join.source, join.alias.as_str()), (JoinKind::Inner, FromClause::History, "h"));
+        assert_eq!((join.left_column.as_str(), join.right_column.as_str()), ("file", "file"));
+        assert!(matches!(&join.filters[..], [QueryFilter::Comparison(c)] if c.field == "error_count"));
+        assert!(matches!(&query.filters[..], [QueryFilter::Severity(_)]));
+        assert_eq!(query.order_by.unwrap().field, "h.error_count");
+
+        let query = parse_query("SELECT * FROM files LEFT JOIN history ON files.file = history.file").unwrap();
+        let join = query.join.unwrap();
+        assert_eq!((join.kind, join.left_alias.as_str(), join.alias.as_str()), (JoinKind::Left, "files", "history"));
+
+        assert!(parse_query("SELECT * FROM diagnostics d JOIN history d ON d.file = d.file").is_err());
+        assert!(parse_query("SELECT * FROM diagnostics d JOIN history h ON d.file = x.file").is_err());
+        assert!(parse_query("SELECT * FROM diagnostics d JOIN history h ON d.file = h.file WHERE x.line > 1").is_err());
+        assert!(parse_query("SELECT * FROM diagnostics d JOIN config c ON d.file = c.file").is_err());
+    }
+
+    #[test]
+    fn test_in_lists_and_subqueries() {
+        let query = parse_query(
+            "SELECT * FROM diagnostics WHERE file IN (SELECT file FROM files WHERE errors > 10) AND severity = error",
+        )
+        .unwrap();
+        assert_eq!(query.filters.len(), 2);
+        let QueryFilter::In(InFilter { field, list: InList::Subquery(subquery), negated: false }) = &query.filters[0] else {
+            panic!("expected an IN subquery, got {:?}", query.filters[0]);
+        };
+        assert_eq!(field, "file");
+        assert_eq!(subquery.from, FromClause::Files);
+        assert_eq!(subquery.select, SelectClause::Fields(vec!["file".to_string()]));
+        assert_eq!(subquery.filters.len(), 1);
+        assert_eq!(query.subqueries().count(), 1);
+
+        let query = parse_query("SELECT * FROM diagnostics WHERE source NOT IN ('clippy', rustc, 3)").unwrap();
+        assert_eq!(
+            query.filters,
+            vec![QueryFilter::In(InFilter {
+                field: "source".to_string(),
+                list: InList::Values(vec!["clippy".to_string(), "rustc".to_string(), "3".to_string()]),
+                negated: true,
+            })]
+        );
+
+        assert!(parse_query("SELECT * FROM diagnostics WHERE file IN (SELECT file FROM files").is_err());
+        assert!(parse_query("SELECT * FROM diagnostics WHERE file IN ()").is_err());
+    }
+
     #[test]
     fn test_error_handling() {
         assert!(parse_query("SELECT").is_err());
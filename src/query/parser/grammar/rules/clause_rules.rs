@@ -100,6 +100,9 @@ impl<'a> ClauseRules for ClauseRuleParser<'a> {
                 "projects" => Ok(FromClause::Projects),
                 "history" => Ok(FromClause::History),
                 "trends" => Ok(FromClause::Trends),
+                "fixes" => Ok(FromClause::Fixes),
+                "lenses" => Ok(FromClause::Lenses),
+                "config" => Ok(FromClause::Config),
                 _ => Err(ParseError::UnknownTable {
                     table: token.lexeme.clone(),
                     line: token.line,
@@ -4,7 +4,7 @@ use super::super::types::{ParserState, ParsingContext, ParseResult, ValueParser,
 use super::super::super::ast::*;
 use super::super::super::lexer::TokenType;
 use crate::core::errors::ParseError;
-use crate::core::DiagnosticSeverity;
+use crate::core::{CalendarUnit, DiagnosticSeverity};
 use chrono::{DateTime, Utc};
 use std::str::FromStr;
 
@@ -63,12 +63,26 @@ impl<'a> FilterRules for FilterRuleParser<'a> {
     /// Parse any filter expression
     /// filter_expression = relative_time_filter | field_filter
     fn parse_filter_expression(&mut self) -> ParseResult<QueryFilter> {
-        if self.state.check(&TokenType::Last) {
+        if self.state.check(&TokenType::Last) || self.state.peek().lexeme.eq_ignore_ascii_case("this") {
             self.parse_relative_time_filter()
         } else if self.state.check_identifier() {
             let field = self.state.advance().lexeme.clone();
             
             match field.as_str() {
+                _ if self.state.match_token(&TokenType::ContainsText) => {
+                    let text = self.parse_string_or_identifier()?;
+                    match TextField::from_name(&field) {
+                        Some(text_field) if !text.trim().is_empty() => {
+                            Ok(QueryFilter::FullText(FullTextFilter::new(text_field, text)))
+                        }
+                        _ => Err(ParseError::UnexpectedToken {
+                            expected: "message, code or text CONTAINS_TEXT 'words'".to_string(),
+                            found: format!("{field} CONTAINS_TEXT '{text}'"),
+                            line: self.state.previous().line,
+                            column: self.state.previous().column,
+                        }),
+                    }
+                }
                 "severity" => self.parse_severity_filter(),
                 "file" => self.parse_file_filter(), 
                 "symbol" => self.parse_symbol_filter(),
@@ -160,8 +174,21 @@ impl<'a> FilterRules for FilterRuleParser<'a> {
     }
     
     /// Parse relative time filter
-    /// relative_time_filter = LAST number time_unit
+    /// relative_time_filter = LAST number time_unit | THIS time_unit
     fn parse_relative_time_filter(&mut self) -> ParseResult<QueryFilter> {
+        if self.state.peek().lexeme.eq_ignore_ascii_case("this") {
+            self.state.advance();
+            let Some(unit) = CalendarUnit::parse(&self.state.peek().lexeme) else {
+                return Err(ParseError::UnexpectedToken {
+                    expected: "time unit after THIS (hour, day, week, month)".to_string(),
+                    found: self.state.peek().lexeme.clone(),
+                    line: self.state.peek().line,
+                    column: self.state.peek().column,
+                });
+            };
+            self.state.advance();
+            return Ok(QueryFilter::TimeRange(TimeRange::relative(RelativeTime::Current(unit))));
+        }
         self.state.consume(TokenType::Last, "Expected 'LAST'")?;
         
         if !self.state.check_number() {
@@ -183,21 +210,13 @@ impl<'a> FilterRules for FilterRuleParser<'a> {
             });
         }
         
-        let relative_time = match &self.state.peek().token_type {
-            TokenType::Hours => {
-                self.state.advance();
-                RelativeTime::LastHours(value)
-            }
-            TokenType::Days => {
-                self.state.advance();
-                RelativeTime::LastDays(value)
-            }
-            TokenType::Weeks => {
+        let relative_time = match CalendarUnit::parse(&self.state.peek().lexeme) {
+            Some(unit) => {
                 self.state.advance();
-                RelativeTime::LastWeeks(value)
+                RelativeTime::last(value, unit)
             }
-            _ => return Err(ParseError::UnexpectedToken {
-                expected: "time unit (hours, days, weeks)".to_string(),
+            None => return Err(ParseError::UnexpectedToken {
+                expected: "time unit (hours, days, weeks, months)".to_string(),
                 found: self.state.peek().lexeme.clone(),
                 line: self.state.peek().line,
                 column: self.state.peek().column,
@@ -210,8 +229,7 @@ impl<'a> FilterRules for FilterRuleParser<'a> {
     /// Parse custom filter
     /// custom_filter = field comparison_operator value
     fn parse_custom_filter(&mut self, field: String) -> ParseResult<QueryFilter> {
-        let _comparison = self.parse_comparison_operator()?; // Not used for custom filter
-        let value = self.parse_string_or_identifier()?;
+        let comparison = self.parse_comparison_operator()?;
         
         if field.is_empty() {
             return Err(ParseError::EmptyFieldName {
@@ -220,6 +238,13 @@ impl<'a> FilterRules for FilterRuleParser<'a> {
             });
         }
         
+        if self.state.check_number() {
+            let value_token = self.state.advance();
+            let value = self.value_parser.parse_number_value(&value_token.lexeme)?;
+            return Ok(QueryFilter::Comparison(ComparisonFilter { field, comparison, value }));
+        }
+        
+        let value = self.parse_string_or_identifier()?;
         Ok(QueryFilter::Custom(field, value))
     }
 }
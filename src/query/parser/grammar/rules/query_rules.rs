@@ -55,12 +55,14 @@ impl<'a> QueryRuleParser<'a> {
     fn validate_required_clauses(&self, query: &Query) -> ParseResult<()> {
         // SELECT and FROM are required
         match query.select {
-            SelectClause::All | SelectClause::Count | SelectClause::Fields(_) | SelectClause::Aggregations(_) => {}
+            SelectClause::All | SelectClause::Count | SelectClause::Fields(_) | SelectClause::Aggregations(_) |
+            SelectClause::Expressions(_) => {}
         }
         
         match query.from {
             FromClause::Diagnostics | FromClause::Files | FromClause::Symbols | 
-            FromClause::References | FromClause::Projects | FromClause::History | FromClause::Trends => {}
+            FromClause::References | FromClause::Projects | FromClause::History | FromClause::Trends |
+            FromClause::Fixes | FromClause::Lenses | FromClause::Config => {}
         }
         
         Ok(())
@@ -78,6 +80,13 @@ impl<'a> QueryRuleParser<'a> {
                         reason: "Cannot use SELECT * with GROUP BY".to_string(),
                     });
                 }
+                SelectClause::Expressions(_) => {
+                    return Err(ParseError::IncompatibleClauses {
+                        clause1: "computed SELECT expressions".to_string(),
+                        clause2: "GROUP BY".to_string(),
+                        reason: "Computed columns are evaluated per row and cannot be grouped".to_string(),
+                    });
+                }
                 SelectClause::Count | SelectClause::Fields(_) | SelectClause::Aggregations(_) => {}
             }
         }
@@ -156,6 +165,18 @@ impl<'a> QueryRuleParser<'a> {
                         });
                     }
                 }
+                RelativeTime::LastMonths(months) => {
+                    if *months == 0 {
+                        return Err(ParseError::InvalidRelativeTime {
+                            value: *months,
+                            unit: "months".to_string(),
+                            reason: "Time value must be greater than 0".to_string(),
+                        });
+                    }
+                }
+                RelativeTime::Current(_unit) => {
+                    // The current period always has a start
+                }
                 RelativeTime::LastCommit => {
                     // LastCommit is always valid
                 }
@@ -186,6 +207,7 @@ mod tests {
             group_by: None,
             order_by: None,
             limit: None,
+            join: None,
         };
         
         let mut lexer = Lexer::new("SELECT * FROM diagnostics");
@@ -209,6 +231,7 @@ mod tests {
             }),
             order_by: None,
             limit: None,
+            join: None,
         };
         
         let mut lexer = Lexer::new("SELECT * FROM diagnostics");
@@ -230,6 +253,7 @@ mod tests {
             group_by: None,
             order_by: None,
             limit: Some(0),
+            join: None,
         };
         
         let mut lexer = Lexer::new("SELECT * FROM diagnostics");
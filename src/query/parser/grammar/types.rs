@@ -119,6 +119,7 @@ pub enum ProductionRule {
     Query,
     SelectClause,
     FromClause,
+    JoinClause,
     WhereClause,
     GroupByClause,
     OrderByClause,
@@ -238,10 +239,28 @@ impl GrammarValidator {
         // Validate that required clauses are present
         Self::validate_select_clause(&query.select)?;
         Self::validate_from_clause(&query.from)?;
+        if let Some(ref join) = query.join {
+            Self::validate_join_clause(&query.from, join).map_err(|e| *e)?;
+        }
         
         // Validate optional clauses if present
         if let Some(ref group_by) = query.group_by {
             Self::validate_group_by_clause(group_by)?;
+            if let SelectClause::Expressions(items) = &query.select {
+                // Computed columns are evaluated once per group, so they may
+                // only use aggregations and the grouped columns
+                let per_row = items.iter().flat_map(|item| item.expr.columns()).any(|column| {
+                    QueryAggregation::from_column(column).is_none() && !group_by.fields.iter().any(|f| f == column)
+                });
+                if per_row {
+                    return Err(ParseError::IncompatibleClauses {
+                        clause1: "computed SELECT expressions".to_string(),
+                        clause2: "GROUP BY".to_string(),
+                        reason: "Computed columns over ungrouped fields are evaluated per row and cannot be grouped"
+                            .to_string(),
+                    });
+                }
+            }
         }
         
         if let Some(ref order_by) = query.order_by {
@@ -263,6 +282,19 @@ impl GrammarValidator {
         Ok(())
     }
 
+    /// Validate join clause: only row sources keyed by file can be joined
+    fn validate_join_clause(from: &FromClause, join: &JoinClause) -> Result<(), Box<ParseError>> {
+        let joinable = |source: &FromClause| matches!(source, FromClause::Diagnostics | FromClause::Files | FromClause::History);
+        if !joinable(from) || !joinable(&join.source) {
+            return Err(Box::new(ParseError::IncompatibleClauses {
+                clause1: format!("FROM {}", from.name()),
+                clause2: format!("JOIN {}", join.source.name()),
+                reason: "Only diagnostics, files and history can be joined".to_string(),
+            }));
+        }
+        Ok(())
+    }
+
     /// Validate group by clause
     fn validate_group_by_clause(group_by: &GroupByClause) -> Result<(), ParseError> {
         if group_by.fields.is_empty() {
@@ -351,6 +383,7 @@ mod tests {
             group_by: None,
             order_by: None,
             limit: None,
+            join: None,
         };
         
         assert!(GrammarValidator::validate_query(&query).is_ok());
@@ -364,6 +397,7 @@ mod tests {
             group_by: Some(GroupByClause { fields: Vec::new() }),
             order_by: None,
             limit: None,
+            join: None,
         };
         
         assert!(GrammarValidator::validate_query(&invalid_query).is_err());
@@ -140,7 +140,7 @@ impl ParserUtilities {
 
     /// Suggest table name corrections
     fn suggest_table_correction(&self, table: &str) -> Option<String> {
-        let valid_tables = ["diagnostics", "files", "symbols", "references", "projects"];
+        let valid_tables = ["diagnostics", "files", "symbols", "references", "projects", "fixes", "lenses", "config"];
         
         // Find closest match using edit distance
         let mut best_match = None;
@@ -242,6 +242,7 @@ impl ParserUtilities {
             ParseError::InvalidSeverity { line, column, .. } => Some((*line, *column)),
             ParseError::InvalidDateTime { line, column, .. } => Some((*line, *column)),
             ParseError::InvalidNumber { line, column, .. } => Some((*line, *column)),
+            ParseError::UnboundParameter { line, column } => Some((*line, *column)),
             _ => None,
         }
     }
@@ -17,6 +17,14 @@ pub enum TokenType {
     By,
     Order,
     Limit,
+    As,
+
+    // Conditional expressions
+    Case,
+    When,
+    Then,
+    Else,
+    End,
 
     // Aggregation functions
     Count,
@@ -24,6 +32,8 @@ pub enum TokenType {
     Avg,
     Min,
     Max,
+    DistinctCount,
+    Percentile,
 
     // Operators
     Equal,
@@ -34,6 +44,14 @@ pub enum TokenType {
     LessThanOrEqual,
     In,
     Like,
+    ContainsText,
+
+    // Arithmetic and string operators
+    Plus,
+    Minus,
+    Slash,
+    Percent,
+    Concat,
 
     // Time keywords
     Last,
@@ -65,6 +83,10 @@ pub enum TokenType {
     Number(f64),
     String(String),
     Identifier(String),
+    Null,
+
+    // Bound to a parameter value before parsing
+    Placeholder,
 
     // Special
     Eof,
@@ -103,6 +125,15 @@ impl Lexer {
         keywords.insert("by".to_string(), TokenType::By);
         keywords.insert("order".to_string(), TokenType::Order);
         keywords.insert("limit".to_string(), TokenType::Limit);
+        keywords.insert("as".to_string(), TokenType::As);
+
+        // Conditional expressions
+        keywords.insert("case".to_string(), TokenType::Case);
+        keywords.insert("when".to_string(), TokenType::When);
+        keywords.insert("then".to_string(), TokenType::Then);
+        keywords.insert("else".to_string(), TokenType::Else);
+        keywords.insert("end".to_string(), TokenType::End);
+        keywords.insert("null".to_string(), TokenType::Null);
 
         // Aggregation functions
         keywords.insert("count".to_string(), TokenType::Count);
@@ -111,10 +142,13 @@ impl Lexer {
         keywords.insert("average".to_string(), TokenType::Avg);
         keywords.insert("min".to_string(), TokenType::Min);
         keywords.insert("max".to_string(), TokenType::Max);
+        keywords.insert("distinct_count".to_string(), TokenType::DistinctCount);
+        keywords.insert("percentile".to_string(), TokenType::Percentile);
 
         // Operators
         keywords.insert("in".to_string(), TokenType::In);
         keywords.insert("like".to_string(), TokenType::Like);
+        keywords.insert("contains_text".to_string(), TokenType::ContainsText);
 
         // Time keywords
         keywords.insert("last".to_string(), TokenType::Last);
@@ -182,7 +216,16 @@ impl Lexer {
             ',' => (TokenType::Comma, ch.to_string()),
             ';' => (TokenType::Semicolon, ch.to_string()),
             '*' => (TokenType::Asterisk, ch.to_string()),
+            '?' => (TokenType::Placeholder, ch.to_string()),
             '.' => (TokenType::Dot, ch.to_string()),
+            '+' => (TokenType::Plus, ch.to_string()),
+            '-' => (TokenType::Minus, ch.to_string()),
+            '/' => (TokenType::Slash, ch.to_string()),
+            '%' => (TokenType::Percent, ch.to_string()),
+            '|' if self.peek() == '|' => {
+                self.advance();
+                (TokenType::Concat, "||".to_string())
+            }
             '=' => (TokenType::Equal, ch.to_string()),
             '!' if self.peek() == '=' => {
                 self.advance();
@@ -356,11 +399,19 @@ impl fmt::Display for TokenType {
             TokenType::By => write!(f, "BY"),
             TokenType::Order => write!(f, "ORDER"),
             TokenType::Limit => write!(f, "LIMIT"),
+            TokenType::As => write!(f, "AS"),
+            TokenType::Case => write!(f, "CASE"),
+            TokenType::When => write!(f, "WHEN"),
+            TokenType::Then => write!(f, "THEN"),
+            TokenType::Else => write!(f, "ELSE"),
+            TokenType::End => write!(f, "END"),
             TokenType::Count => write!(f, "COUNT"),
             TokenType::Sum => write!(f, "SUM"),
             TokenType::Avg => write!(f, "AVG"),
             TokenType::Min => write!(f, "MIN"),
             TokenType::Max => write!(f, "MAX"),
+            TokenType::DistinctCount => write!(f, "DISTINCT_COUNT"),
+            TokenType::Percentile => write!(f, "PERCENTILE"),
             TokenType::Equal => write!(f, "="),
             TokenType::NotEqual => write!(f, "!="),
             TokenType::GreaterThan => write!(f, ">"),
@@ -369,6 +420,12 @@ impl fmt::Display for TokenType {
             TokenType::LessThanOrEqual => write!(f, "<="),
             TokenType::In => write!(f, "IN"),
             TokenType::Like => write!(f, "LIKE"),
+            TokenType::ContainsText => write!(f, "CONTAINS_TEXT"),
+            TokenType::Plus => write!(f, "+"),
+            TokenType::Minus => write!(f, "-"),
+            TokenType::Slash => write!(f, "/"),
+            TokenType::Percent => write!(f, "%"),
+            TokenType::Concat => write!(f, "||"),
             TokenType::Last => write!(f, "LAST"),
             TokenType::Days => write!(f, "DAYS"),
             TokenType::Hours => write!(f, "HOURS"),
@@ -390,6 +447,8 @@ impl fmt::Display for TokenType {
             TokenType::Number(n) => write!(f, "{n}"),
             TokenType::String(s) => write!(f, "\"{s}\""),
             TokenType::Identifier(id) => write!(f, "{id}"),
+            TokenType::Null => write!(f, "NULL"),
+            TokenType::Placeholder => write!(f, "?"),
             TokenType::Eof => write!(f, "EOF"),
         }
     }
@@ -446,4 +505,19 @@ mod tests {
         assert_eq!(tokens[4].token_type, TokenType::GreaterThanOrEqual);
         assert_eq!(tokens[5].token_type, TokenType::LessThanOrEqual);
     }
+
+    #[test]
+    fn test_lexer_expression_operators() {
+        let mut lexer = Lexer::new("a + b - c * d / e % f || 'x' AS total");
+        let tokens = lexer.tokenize().unwrap();
+        let types: Vec<_> = tokens.iter().map(|t| t.token_type.clone()).collect();
+
+        assert_eq!(types[1], TokenType::Plus);
+        assert_eq!(types[3], TokenType::Minus);
+        assert_eq!(types[5], TokenType::Asterisk);
+        assert_eq!(types[7], TokenType::Slash);
+        assert_eq!(types[9], TokenType::Percent);
+        assert_eq!(types[11], TokenType::Concat);
+        assert_eq!(types[13], TokenType::As);
+    }
 }
\ No newline at end of file
@@ -6,8 +6,11 @@
 //!
 //! # Query Language Features
 //!
-//! - **SELECT clauses**: `*`, `COUNT(*)`, field lists, aggregation functions
+//! - **SELECT clauses**: `*`, `COUNT(*)`, field lists, aggregation functions,
+//!   computed columns (`errors + warnings AS total`, `CASE`, string functions)
 //! - **FROM clauses**: `diagnostics`, `files`, `history`, `trends`
+//! - **JOIN**: `diagnostics`, `files` and `history` joined on a column, with
+//!   columns qualified by alias (`d.file`, `h.error_count`)
 //! - **WHERE clauses**: Field filters, time ranges, severity filters
 //! - **GROUP BY**: Grouping by multiple fields
 //! - **ORDER BY**: Sorting with ASC/DESC
@@ -45,26 +48,37 @@
 //!
 //! -- Get trending diagnostic categories
 //! SELECT category, COUNT(*) FROM trends WHERE LAST 30 DAYS GROUP BY category
+//!
+//! -- Rank files by their share of errors
+//! SELECT file, errors + warnings AS total, errors * 1.0 / NULLIF(total, 0) AS error_ratio
+//!   FROM files ORDER BY error_ratio DESC
+//!
+//! -- Compare current diagnostics with each file's latest recorded snapshot
+//! SELECT d.file, d.message, h.error_count
+//!   FROM diagnostics d JOIN history h ON d.file = h.file WHERE h.error_count > 5
 //! ```
 
 pub mod ast;
 pub mod errors;
 pub mod grammar;
 pub mod lexer;
+pub mod params;
 
 // Re-export main types for convenience
 pub use ast::{
-    Comparison, ComparisonFilter, FromClause, GroupByClause, MessageFilter, OrderByClause,
-    OrderDirection, PathFilter, Query, QueryAggregation, QueryFilter, RelativeTime, SelectClause,
-    SeverityFilter, TimeRange,
+    BinaryOperator, Comparison, ComparisonFilter, Expr, FromClause, FullTextFilter, GroupByClause,
+    InFilter, InList, JoinClause, JoinKind, MessageFilter, OrderByClause, OrderDirection, PathFilter, Query, QueryAggregation, QueryFilter,
+    RelativeTime, ScalarFunction, SelectClause, SelectItem, SeverityFilter, TextField, TimeRange,
 };
 pub use errors::{
     OptimizationSuggestion, QueryOptimizer, QueryValidator, SuggestionSeverity, SuggestionType,
 };
 pub use grammar::Parser;
 pub use lexer::{Lexer, Token, TokenType};
+pub use params::bind_parameters;
 
 use crate::core::errors::ParseError;
+use crate::query::executor::Value;
 
 /// Main query parser providing a simple interface for parsing query strings
 ///
@@ -154,6 +168,37 @@ impl QueryParser {
         Ok(query)
     }
 
+    /// Parse a query string, binding its `?` placeholders to `params` in order
+    ///
+    /// Each parameter becomes a single literal token (arrays become a
+    /// comma-separated list for `IN (?)`), so user-provided file paths and
+    /// messages cannot change the structure of the query.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use lsp_bridge::query::executor::Value;
+    /// use lsp_bridge::query::parser::QueryParser;
+    ///
+    /// let parser = QueryParser::new();
+    /// let query = parser.parse_with_params(
+    ///     "SELECT * FROM diagnostics WHERE file = ?",
+    ///     &[Value::String("src/main.rs".to_string())],
+    /// )?;
+    /// assert_eq!(query.filters.len(), 1);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn parse_with_params(&self, input: &str, params: &[Value]) -> Result<Query, Box<ParseError>> {
+        let tokens = bind_parameters(Lexer::new(input).tokenize()?, params)?;
+        let query = Parser::new(tokens).parse()?;
+
+        if let Err(errors) = self.validator.validate(&query) {
+            return Err(Box::new(errors.into_iter().next().unwrap()));
+        }
+
+        Ok(query)
+    }
+
     /// Parse a query string without validation
     ///
     /// This method skips semantic validation and returns the raw parsed AST.
@@ -0,0 +1,130 @@
+//! Binding of `?` placeholders to parameter values
+//!
+//! Parameters are substituted into the token stream after lexing, so a
+//! value always becomes a single literal (or a comma-separated list of
+//! literals for arrays) and can never change the structure of the query.
+
+use super::lexer::{Token, TokenType};
+use crate::core::errors::ParseError;
+use crate::core::DiagnosticSeverity;
+use crate::query::executor::Value;
+
+/// Replace each placeholder token with the literal for its parameter, in order
+///
+/// The error is boxed like the expression parser's, as `ParseError` is large.
+pub fn bind_parameters(tokens: Vec<Token>, params: &[Value]) -> Result<Vec<Token>, Box<ParseError>> {
+    let expected = tokens.iter().filter(|t| t.token_type == TokenType::Placeholder).count();
+    if expected != params.len() {
+        return Err(Box::new(ParseError::ParameterCount {
+            expected,
+            found: params.len(),
+        }));
+    }
+
+    let mut params = params.iter().enumerate();
+    let mut bound = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        if token.token_type != TokenType::Placeholder {
+            bound.push(token);
+            continue;
+        }
+        let (index, value) = params.next().expect("placeholder count checked above");
+        let invalid = |reason: String| Box::new(ParseError::InvalidParameter { index, reason });
+        match value {
+            Value::Array(values) if values.is_empty() => {
+                return Err(invalid("empty arrays cannot be bound".to_string()));
+            }
+            Value::Array(values) => {
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        bound.push(literal(&token, TokenType::Comma, ",".to_string()));
+                    }
+                    bound.push(scalar_token(&token, value).map_err(invalid)?);
+                }
+            }
+            value => bound.push(scalar_token(&token, value).map_err(invalid)?),
+        }
+    }
+    Ok(bound)
+}
+
+/// The literal token for a single value, or why it cannot be bound
+fn scalar_token(placeholder: &Token, value: &Value) -> Result<Token, String> {
+    let string = |s: String| literal(placeholder, TokenType::String(s.clone()), s);
+    Ok(match value {
+        Value::String(s) => string(s.clone()),
+        Value::Path(path) => string(path.to_string_lossy().into_owned()),
+        Value::Boolean(b) => string(b.to_string()),
+        Value::Severity(severity) => string(severity_name(severity).to_string()),
+        Value::Integer(n) => literal(placeholder, TokenType::Number(*n as f64), n.to_string()),
+        Value::Number(n) if n.is_finite() => literal(placeholder, TokenType::Number(*n), n.to_string()),
+        Value::Number(n) => return Err(format!("{n} is not a finite number")),
+        Value::Null => literal(placeholder, TokenType::Null, "null".to_string()),
+        Value::Array(_) => return Err("nested arrays cannot be bound".to_string()),
+    })
+}
+
+/// Severity as the parser spells it
+fn severity_name(severity: &DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::Error => "error",
+        DiagnosticSeverity::Warning => "warning",
+        DiagnosticSeverity::Information => "info",
+        DiagnosticSeverity::Hint => "hint",
+    }
+}
+
+/// A token at the placeholder's position, so errors point at the `?`
+fn literal(placeholder: &Token, token_type: TokenType, lexeme: String) -> Token {
+    Token {
+        token_type,
+        lexeme,
+        line: placeholder.line,
+        column: placeholder.column,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{FullTextFilter, Lexer, QueryFilter, QueryParser, TextField};
+    use super::*;
+
+    #[test]
+    fn test_bind_parameters() {
+        let parser = QueryParser::new();
+        let query = parser
+            .parse_with_params(
+                "SELECT * FROM diagnostics WHERE file = ? AND message CONTAINS_TEXT ?",
+                &[Value::String("src/main.rs".into()), Value::String("x' OR file = '".into())],
+            )
+            .unwrap();
+        assert_eq!(
+            query.filters[1],
+            QueryFilter::FullText(FullTextFilter::new(TextField::Message, "x' OR file = '"))
+        );
+
+        let query = parser
+            .parse_with_params(
+                "SELECT * FROM diagnostics WHERE severity IN (?)",
+                &[Value::Array(vec![
+                    Value::Severity(DiagnosticSeverity::Error),
+                    Value::Severity(DiagnosticSeverity::Warning),
+                ])],
+            )
+            .unwrap();
+        let QueryFilter::In(filter) = &query.filters[0] else {
+            panic!("expected IN filter, got {:?}", query.filters[0]);
+        };
+        assert!(filter.matches("warning"));
+
+        let tokens = Lexer::new("SELECT * FROM files WHERE file = ?").tokenize().unwrap();
+        assert!(matches!(
+            bind_parameters(tokens, &[]),
+            Err(e) if matches!(*e, ParseError::ParameterCount { expected: 1, found: 0 })
+        ));
+        assert!(matches!(
+            parser.parse("SELECT * FROM files WHERE file = ?"),
+            Err(ParseError::UnboundParameter { line: 1, .. })
+        ));
+    }
+}
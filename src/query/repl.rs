@@ -1,6 +1,7 @@
 use super::executor::Value;
 use super::{QueryExecutor, QueryParser, QueryResult};
-use crate::core::DiagnosticResult;
+use crate::core::config::EnvironmentSnapshot;
+use crate::core::{CalendarConfig, DiagnosticResult};
 use crate::history::HistoryStorage;
 use anyhow::Result;
 use colored::*;
@@ -10,6 +11,7 @@ use crossterm::{
 };
 use std::collections::VecDeque;
 use std::io::{self, Write};
+use std::sync::Arc;
 
 pub struct InteractiveRepl {
     parser: QueryParser,
@@ -56,6 +58,22 @@ impl InteractiveRepl {
         self
     }
 
+    /// Use history storage opened by the caller, such as one held under the store lock
+    pub fn with_shared_history(mut self, history: Arc<HistoryStorage>) -> Self {
+        self.executor.with_shared_history(history);
+        self
+    }
+
+    pub fn with_calendar(mut self, calendar: CalendarConfig) -> Self {
+        self.executor.with_calendar(calendar);
+        self
+    }
+
+    pub fn with_environment(mut self, environment: EnvironmentSnapshot) -> Self {
+        self.executor.with_environment(environment);
+        self
+    }
+
     pub async fn run(&mut self) -> Result<()> {
         self.print_welcome();
 
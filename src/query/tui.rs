@@ -0,0 +1,740 @@
+//! Full-screen browser for query results
+//!
+//! `lsp-bridge query --tui` shows a result as a table that can be sorted by
+//! any column and filtered by text. Enter on a row that has `file` and
+//! `line` columns opens the matching diagnostic with a code frame and the
+//! ranked fix suggestions; `e` opens the location in `$EDITOR`.
+
+use super::executor::processing::SortingProcessor;
+use super::executor::{QueryResult, Row, Value};
+use crate::core::{Diagnostic, DiagnosticResult};
+use crate::quick_fix::FixSuggestionService;
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use std::cmp::Ordering;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Widest a column is drawn before values are cut
+const MAX_COLUMN_WIDTH: usize = 40;
+
+/// Source lines shown on each side of a diagnostic
+const FRAME_CONTEXT_LINES: usize = 3;
+
+/// Rows of a result with the current sort, filter and selection
+#[derive(Debug, Clone)]
+pub struct ResultTable {
+    columns: Vec<String>,
+    rows: Vec<Row>,
+    /// Indices into `rows` that pass the filter, in display order
+    visible: Vec<usize>,
+    /// Sorted column and whether the order is descending
+    sort: Option<(usize, bool)>,
+    filter: String,
+    selected: usize,
+}
+
+impl ResultTable {
+    pub fn new(result: &QueryResult) -> Self {
+        let mut table = Self {
+            columns: result.columns.clone(),
+            rows: result.rows.clone(),
+            visible: Vec::new(),
+            sort: None,
+            filter: String::new(),
+            selected: 0,
+        };
+        table.refresh();
+        table
+    }
+
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Rows passing the filter, in display order
+    pub fn visible_rows(&self) -> impl Iterator<Item = &Row> {
+        self.visible.iter().map(|&index| &self.rows[index])
+    }
+
+    pub fn visible_len(&self) -> usize {
+        self.visible.len()
+    }
+
+    pub fn total_len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn sort(&self) -> Option<(usize, bool)> {
+        self.sort
+    }
+
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    /// Position of the selected row among the visible ones
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn selected_row(&self) -> Option<&Row> {
+        self.visible.get(self.selected).map(|&index| &self.rows[index])
+    }
+
+    /// Sort by `column`, reversing the order if it is already sorted by it
+    pub fn sort_by(&mut self, column: usize) {
+        if column >= self.columns.len() {
+            return;
+        }
+        let descending = matches!(self.sort, Some((current, false)) if current == column);
+        self.sort = Some((column, descending));
+        self.refresh();
+    }
+
+    /// Show only rows with a cell containing `filter`, ignoring case
+    pub fn set_filter(&mut self, filter: &str) {
+        self.filter = filter.to_string();
+        self.refresh();
+    }
+
+    /// Move the selection by `delta` rows, stopping at either end
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.visible.len().saturating_sub(1) as isize;
+        self.selected = (self.selected as isize + delta).clamp(0, last) as usize;
+    }
+
+    fn refresh(&mut self) {
+        let needle = self.filter.to_lowercase();
+        self.visible = (0..self.rows.len())
+            .filter(|&index| {
+                needle.is_empty()
+                    || self.rows[index]
+                        .values
+                        .iter()
+                        .any(|value| value.to_string().to_lowercase().contains(&needle))
+            })
+            .collect();
+
+        if let Some((column, descending)) = self.sort {
+            let rows = &self.rows;
+            let cell = |index: usize| rows[index].values.get(column).unwrap_or(&Value::Null);
+            // Stable, so equal cells keep the query's order
+            self.visible.sort_by(|&a, &b| {
+                let order = SortingProcessor::compare_values(cell(a), cell(b));
+                if descending {
+                    order.reverse()
+                } else {
+                    order
+                }
+            });
+        }
+        self.move_selection(0);
+    }
+}
+
+/// Where a result row points in the source
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowLocation {
+    pub file: PathBuf,
+    /// Zero-based, like the `line` column
+    pub line: u32,
+    pub message: Option<String>,
+}
+
+impl RowLocation {
+    /// Location of a row with `file` (or `path`) and `line` columns
+    pub fn of(columns: &[String], row: &Row) -> Option<Self> {
+        let cell = |name: &str| {
+            columns
+                .iter()
+                .position(|column| column.eq_ignore_ascii_case(name))
+                .and_then(|index| row.values.get(index))
+                .filter(|value| !matches!(value, Value::Null))
+        };
+        let file = cell("file").or_else(|| cell("path"))?.to_string();
+        let line = cell("line")?.as_number()?;
+        Some(Self {
+            file: PathBuf::from(file),
+            line: line.max(0.0) as u32,
+            message: cell("message").map(Value::to_string),
+        })
+    }
+
+    /// The diagnostic the row came from, if it is still loaded
+    pub fn find<'a>(&self, diagnostics: &'a DiagnosticResult) -> Option<&'a Diagnostic> {
+        let on_line = diagnostics
+            .diagnostics
+            .values()
+            .flatten()
+            .filter(|diagnostic| {
+                diagnostic.range.start.line == self.line && same_file(Path::new(&diagnostic.file), &self.file)
+            });
+        let mut first = None;
+        for diagnostic in on_line {
+            match &self.message {
+                Some(message) if diagnostic.message == *message => return Some(diagnostic),
+                _ => first = first.or(Some(diagnostic)),
+            }
+        }
+        first
+    }
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    a == b || a.ends_with(b) || b.ends_with(a)
+}
+
+/// Numbered source lines around `line`, marking it and underlining `columns`
+pub fn code_frame(source: &str, line: u32, columns: Option<(u32, u32)>) -> Vec<String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let line = line as usize;
+    if line >= lines.len() {
+        return Vec::new();
+    }
+    let first = line.saturating_sub(FRAME_CONTEXT_LINES);
+    let last = (line + FRAME_CONTEXT_LINES).min(lines.len() - 1);
+    let gutter = (last + 1).to_string().len();
+
+    let mut frame = Vec::new();
+    for (index, text) in lines.iter().enumerate().take(last + 1).skip(first) {
+        let marker = if index == line { '>' } else { ' ' };
+        frame.push(format!("{marker} {:>gutter$} │ {text}", index + 1));
+        if index == line {
+            if let Some((start, end)) = columns {
+                let width = end.saturating_sub(start).max(1) as usize;
+                frame.push(format!(
+                    "  {:gutter$} │ {}{}",
+                    "",
+                    " ".repeat(start as usize),
+                    "^".repeat(width)
+                ));
+            }
+        }
+    }
+    frame
+}
+
+/// Program and arguments that open `file` at a one-based line and column
+///
+/// Understands the `file:line:col` style of VS Code, Sublime Text, Zed and
+/// Helix; anything else gets the `+line file` convention of vi, Emacs and nano.
+pub fn editor_command(editor: &str, file: &Path, line: u32, column: u32) -> Option<(String, Vec<String>)> {
+    let mut parts = editor.split_whitespace().map(String::from);
+    let program = parts.next()?;
+    let mut args: Vec<String> = parts.collect();
+    let name = Path::new(&program)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(&program)
+        .to_string();
+    let located = format!("{}:{line}:{column}", file.display());
+
+    match name.as_str() {
+        "code" | "code-insiders" | "codium" | "cursor" => args.extend(["-g".to_string(), located]),
+        "subl" | "zed" | "hx" | "helix" => args.push(located),
+        _ => args.extend([format!("+{line}"), file.display().to_string()]),
+    }
+    Some((program, args))
+}
+
+enum Screen {
+    Table,
+    /// Drill-down of the selected row, with its scroll offset
+    Detail(usize),
+}
+
+/// Interactive result browser
+pub struct ResultBrowser {
+    table: ResultTable,
+    diagnostics: DiagnosticResult,
+    fixes: FixSuggestionService,
+    query: String,
+}
+
+impl ResultBrowser {
+    pub fn new(query: &str, result: &QueryResult, diagnostics: DiagnosticResult) -> Self {
+        Self {
+            table: ResultTable::new(result),
+            diagnostics,
+            fixes: FixSuggestionService::new(),
+            query: query.to_string(),
+        }
+    }
+
+    /// Run until the user quits
+    ///
+    /// In the table `↑`/`↓` (or `j`/`k`) move, `←`/`→` pick a column, `s`
+    /// sorts by it (again to reverse), `/` filters, Enter drills down, `e`
+    /// opens the editor and `q` quits. In the detail view `Esc` goes back.
+    pub fn run(mut self) -> Result<()> {
+        let mut terminal = RawTerminal::enter()?;
+        let mut screen = Screen::Table;
+        let mut column = 0usize;
+        let mut offset = 0usize;
+        let mut editing: Option<String> = None;
+        let mut status = String::new();
+
+        loop {
+            let (width, height) = crossterm::terminal::size().unwrap_or((80, 24));
+            let (width, height) = (width as usize, height as usize);
+            let lines = match screen {
+                Screen::Table => {
+                    let room = height.saturating_sub(5).max(1);
+                    if self.table.selected() < offset {
+                        offset = self.table.selected();
+                    } else if self.table.selected() >= offset + room {
+                        offset = self.table.selected() + 1 - room;
+                    }
+                    self.draw_table(column, offset, room, width, editing.as_deref(), &status)
+                }
+                Screen::Detail(scroll) => self.draw_detail(scroll, width, height, &status),
+            };
+            draw(&lines)?;
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind == KeyEventKind::Release {
+                continue;
+            }
+            if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                break;
+            }
+
+            if let Some(filter) = editing.as_mut() {
+                match key.code {
+                    KeyCode::Enter => editing = None,
+                    KeyCode::Esc => {
+                        self.table.set_filter("");
+                        editing = None;
+                    }
+                    KeyCode::Backspace => {
+                        filter.pop();
+                        self.table.set_filter(filter);
+                    }
+                    KeyCode::Char(c) => {
+                        filter.push(c);
+                        self.table.set_filter(filter);
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+
+            status.clear();
+            match (&mut screen, key.code) {
+                (_, KeyCode::Char('e')) => status = self.open_editor(&mut terminal),
+                (Screen::Table, KeyCode::Char('q') | KeyCode::Esc) => break,
+                (Screen::Table, KeyCode::Char('j') | KeyCode::Down) => self.table.move_selection(1),
+                (Screen::Table, KeyCode::Char('k') | KeyCode::Up) => self.table.move_selection(-1),
+                (Screen::Table, KeyCode::PageDown) => self.table.move_selection(height as isize / 2),
+                (Screen::Table, KeyCode::PageUp) => self.table.move_selection(-(height as isize / 2)),
+                (Screen::Table, KeyCode::Char('g') | KeyCode::Home) => self.table.move_selection(isize::MIN / 2),
+                (Screen::Table, KeyCode::Char('G') | KeyCode::End) => self.table.move_selection(isize::MAX / 2),
+                (Screen::Table, KeyCode::Char('h') | KeyCode::Left) => column = column.saturating_sub(1),
+                (Screen::Table, KeyCode::Char('l') | KeyCode::Right) => {
+                    column = (column + 1).min(self.table.columns().len().saturating_sub(1))
+                }
+                (Screen::Table, KeyCode::Char('s')) => self.table.sort_by(column),
+                (Screen::Table, KeyCode::Char('/')) => editing = Some(self.table.filter().to_string()),
+                (Screen::Table, KeyCode::Enter) if self.table.selected_row().is_some() => {
+                    screen = Screen::Detail(0)
+                }
+                (Screen::Detail(_), KeyCode::Char('q') | KeyCode::Esc | KeyCode::Backspace) => {
+                    screen = Screen::Table
+                }
+                (Screen::Detail(scroll), KeyCode::Char('j') | KeyCode::Down) => *scroll += 1,
+                (Screen::Detail(scroll), KeyCode::Char('k') | KeyCode::Up) => *scroll = scroll.saturating_sub(1),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn draw_table(
+        &self,
+        column: usize,
+        offset: usize,
+        room: usize,
+        width: usize,
+        editing: Option<&str>,
+        status: &str,
+    ) -> Vec<String> {
+        let table = &self.table;
+        let mut widths: Vec<usize> = table.columns().iter().map(|name| name.chars().count() + 2).collect();
+        for row in table.visible_rows().skip(offset).take(room) {
+            for (index, value) in row.values.iter().enumerate().take(widths.len()) {
+                widths[index] = widths[index].max(value.to_string().chars().count().min(MAX_COLUMN_WIDTH));
+            }
+        }
+
+        let header: Vec<String> = table
+            .columns()
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let arrow = match table.sort() {
+                    Some((sorted, false)) if sorted == index => " ▲",
+                    Some((sorted, true)) if sorted == index => " ▼",
+                    _ => "",
+                };
+                let cell = pad(&format!("{name}{arrow}"), widths[index]);
+                if index == column {
+                    cell.underline().bold().to_string()
+                } else {
+                    cell.bold().to_string()
+                }
+            })
+            .collect();
+
+        let mut lines = vec![
+            fit(&format!("Query: {}", self.query), width),
+            fit_styled(&header.join(" "), width),
+        ];
+        for (index, row) in table.visible_rows().enumerate().skip(offset).take(room) {
+            let cells: Vec<String> = row
+                .values
+                .iter()
+                .enumerate()
+                .map(|(column, value)| pad(&value.to_string(), widths.get(column).copied().unwrap_or(0)))
+                .collect();
+            let line = fit(&cells.join(" "), width);
+            lines.push(if index == table.selected() {
+                line.reversed().to_string()
+            } else {
+                line
+            });
+        }
+        while lines.len() < room + 2 {
+            lines.push(String::new());
+        }
+
+        let counts = if table.filter().is_empty() {
+            format!("{} rows", table.total_len())
+        } else {
+            format!("{} of {} rows match '{}'", table.visible_len(), table.total_len(), table.filter())
+        };
+        lines.push(match editing {
+            Some(filter) => format!("Filter: {filter}▏").yellow().to_string(),
+            None => fit(&counts, width),
+        });
+        lines.push(
+            fit(
+                "[↑↓] move [←→] column [s] sort [/] filter [Enter] details [e] edit [q] quit",
+                width,
+            )
+            .dimmed()
+            .to_string(),
+        );
+        lines.push(status.green().to_string());
+        lines
+    }
+
+    fn draw_detail(&self, scroll: usize, width: usize, height: usize, status: &str) -> Vec<String> {
+        let body = self.detail_lines();
+        let room = height.saturating_sub(2).max(1);
+        let scroll = scroll.min(body.len().saturating_sub(room));
+        let mut lines: Vec<String> = body.into_iter().skip(scroll).take(room).map(|line| fit_styled(&line, width)).collect();
+        while lines.len() < room {
+            lines.push(String::new());
+        }
+        lines.push(fit("[j/k] scroll [e] edit [Esc] back", width).dimmed().to_string());
+        lines.push(status.green().to_string());
+        lines
+    }
+
+    /// The selected row's diagnostic, code frame and fixes
+    fn detail_lines(&self) -> Vec<String> {
+        let Some(row) = self.table.selected_row() else {
+            return Vec::new();
+        };
+        let columns = self.table.columns();
+        let location = RowLocation::of(columns, row);
+        let diagnostic = location.as_ref().and_then(|location| location.find(&self.diagnostics));
+
+        let mut lines = Vec::new();
+        match diagnostic {
+            Some(diagnostic) => {
+                let code = diagnostic.code.as_deref().map(|code| format!(" [{code}]")).unwrap_or_default();
+                lines.push(format!("{}{code} from {}", diagnostic.severity, diagnostic.source).bold().to_string());
+                lines.extend(diagnostic.message.lines().map(String::from));
+                lines.push(format!(
+                    "{}:{}:{}",
+                    diagnostic.file,
+                    diagnostic.range.start.line + 1,
+                    diagnostic.range.start.character + 1
+                ));
+                for related in diagnostic.related_information.iter().flatten() {
+                    lines.push(format!(
+                        "  note: {} ({}:{})",
+                        related.message,
+                        related.location.uri,
+                        related.location.range.start.line + 1
+                    ));
+                }
+            }
+            None => {
+                for (name, value) in columns.iter().zip(&row.values) {
+                    lines.push(format!("{}: {}", name.bold(), value.to_string()));
+                }
+            }
+        }
+
+        if let Some(location) = &location {
+            let columns = diagnostic
+                .filter(|d| d.range.end.line == d.range.start.line)
+                .map(|d| (d.range.start.character, d.range.end.character));
+            match std::fs::read_to_string(&location.file) {
+                Ok(source) => {
+                    lines.push(String::new());
+                    lines.extend(code_frame(&source, location.line, columns));
+                }
+                Err(e) => lines.push(format!("(source unavailable: {e})").dimmed().to_string()),
+            }
+        }
+
+        if let Some(diagnostic) = diagnostic {
+            let fixes = self.fixes.fixes(diagnostic);
+            lines.push(String::new());
+            if fixes.is_empty() {
+                lines.push("No suggested fixes".dimmed().to_string());
+            }
+            for fix in fixes {
+                lines.push(
+                    format!("Fix: {} ({:.0}% confidence)", fix.title, fix.confidence * 100.0)
+                        .cyan()
+                        .to_string(),
+                );
+                let preview = fix.diff.or(fix.code_snippet).unwrap_or_default();
+                for line in preview.lines() {
+                    lines.push(match line.chars().next() {
+                        Some('+') if !line.starts_with("+++") => line.green().to_string(),
+                        Some('-') if !line.starts_with("---") => line.red().to_string(),
+                        _ => format!("  {line}"),
+                    });
+                }
+            }
+        }
+        lines
+    }
+
+    /// Open the selected row's location in `$VISUAL` or `$EDITOR`, returning a status line
+    fn open_editor(&self, terminal: &mut RawTerminal) -> String {
+        let location = self
+            .table
+            .selected_row()
+            .and_then(|row| RowLocation::of(self.table.columns(), row));
+        let Some(location) = location else {
+            return "This row has no file and line to open".to_string();
+        };
+        let column = location
+            .find(&self.diagnostics)
+            .map_or(1, |diagnostic| diagnostic.range.start.character + 1);
+        let editor = std::env::var("VISUAL")
+            .or_else(|_| std::env::var("EDITOR"))
+            .unwrap_or_else(|_| "vi".to_string());
+        let Some((program, args)) = editor_command(&editor, &location.file, location.line + 1, column) else {
+            return "$EDITOR is empty".to_string();
+        };
+
+        terminal.suspend();
+        let outcome = std::process::Command::new(&program).args(&args).status();
+        if let Err(e) = terminal.resume() {
+            return format!("Failed to restore the terminal: {e}");
+        }
+        match outcome {
+            Ok(status) if status.success() => String::new(),
+            Ok(status) => format!("{program} exited with {status}"),
+            Err(e) => format!("Failed to run {program}: {e}"),
+        }
+    }
+}
+
+/// Raw mode on the alternate screen, restored on drop
+struct RawTerminal {
+    active: bool,
+}
+
+impl RawTerminal {
+    fn enter() -> Result<Self> {
+        if !atty::is(atty::Stream::Stdout) {
+            return Err(anyhow!("--tui needs a terminal; use --format to write results elsewhere"));
+        }
+        let mut terminal = Self { active: false };
+        terminal.resume()?;
+        Ok(terminal)
+    }
+
+    /// Hand the terminal back, e.g. to an editor
+    fn suspend(&mut self) {
+        if self.active {
+            let _ = crossterm::execute!(
+                std::io::stdout(),
+                crossterm::cursor::Show,
+                crossterm::terminal::LeaveAlternateScreen
+            );
+            let _ = crossterm::terminal::disable_raw_mode();
+            self.active = false;
+        }
+    }
+
+    fn resume(&mut self) -> Result<()> {
+        crossterm::terminal::enable_raw_mode()?;
+        crossterm::execute!(
+            std::io::stdout(),
+            crossterm::terminal::EnterAlternateScreen,
+            crossterm::cursor::Hide
+        )?;
+        self.active = true;
+        Ok(())
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        self.suspend();
+    }
+}
+
+fn draw(lines: &[String]) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    crossterm::queue!(
+        stdout,
+        crossterm::terminal::Clear(crossterm::terminal::ClearType::All),
+        crossterm::cursor::MoveTo(0, 0)
+    )?;
+    // No trailing newline, which would scroll the screen when it is full
+    for (index, line) in lines.iter().enumerate() {
+        let end = if index + 1 < lines.len() { "\r\n" } else { "" };
+        write!(stdout, "{line}{end}")?;
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Cut or pad plain text to exactly `width` characters
+fn pad(text: &str, width: usize) -> String {
+    let count = text.chars().count();
+    match count.cmp(&width) {
+        Ordering::Greater if width > 1 => format!("{}…", text.chars().take(width - 1).collect::<String>()),
+        Ordering::Greater => text.chars().take(width).collect(),
+        _ => format!("{text}{}", " ".repeat(width - count)),
+    }
+}
+
+/// Cut plain text to the screen width
+fn fit(text: &str, width: usize) -> String {
+    text.chars().take(width).collect()
+}
+
+/// Cut text that may contain color codes, which take no screen space
+fn fit_styled(text: &str, width: usize) -> String {
+    let mut out = String::new();
+    let mut shown = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            out.push(c);
+            for code in chars.by_ref() {
+                out.push(code);
+                if code.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else if shown < width {
+            out.push(c);
+            shown += 1;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{DiagnosticSeverity, Position, Range};
+    use crate::query::executor::QueryMetadata;
+
+    fn result() -> QueryResult {
+        let row = |file: &str, line: i64, message: &str| Row {
+            values: vec![
+                Value::Path(PathBuf::from(file)),
+                Value::Integer(line),
+                Value::String(message.to_string()),
+            ],
+        };
+        QueryResult {
+            columns: vec!["file".to_string(), "line".to_string(), "message".to_string()],
+            rows: vec![
+                row("src/b.rs", 4, "unused variable"),
+                row("src/a.rs", 10, "mismatched types"),
+                row("src/c.rs", 2, "unused import"),
+            ],
+            total_count: 3,
+            query_time_ms: 0,
+            metadata: QueryMetadata {
+                data_source: "diagnostics".to_string(),
+                filters_applied: 0,
+                rows_scanned: 3,
+                cache_hit: false,
+            },
+        }
+    }
+
+    #[test]
+    fn test_sort_filter_and_locate_rows() {
+        let mut table = ResultTable::new(&result());
+        table.sort_by(1);
+        let lines: Vec<_> = table.visible_rows().map(|row| row.values[1].to_string()).collect();
+        assert_eq!(lines, vec!["2", "4", "10"]);
+        table.sort_by(1);
+        assert_eq!(table.sort(), Some((1, true)));
+        assert_eq!(table.selected_row().unwrap().values[1], Value::Integer(10));
+
+        table.move_selection(5);
+        table.set_filter("UNUSED");
+        assert_eq!(table.visible_len(), 2);
+        assert_eq!(table.selected(), 1);
+
+        let mut diagnostics = DiagnosticResult::new();
+        let mut diagnostic = Diagnostic::new(
+            "/work/src/c.rs".to_string(),
+            Range {
+                start: Position { line: 2, character: 4 },
+                end: Position { line: 2, character: 9 },
+            },
+            DiagnosticSeverity::Warning,
+            "unused import".to_string(),
+            "rustc".to_string(),
+        );
+        diagnostic.code = Some("unused_imports".to_string());
+        diagnostics.diagnostics.insert(PathBuf::from("/work/src/c.rs"), vec![diagnostic]);
+
+        let location = RowLocation::of(table.columns(), table.selected_row().unwrap()).unwrap();
+        assert_eq!(location.line, 2);
+        let found = location.find(&diagnostics).unwrap();
+        assert_eq!(found.code.as_deref(), Some("unused_imports"));
+    }
+
+    #[test]
+    fn test_code_frame_and_editor_commands() {
+        let source = "fn main() {\n    let x = 1;\n}\n";
+        let frame = code_frame(source, 1, Some((8, 9)));
+        assert_eq!(frame[1], "> 2 │     let x = 1;");
+        assert_eq!(frame[2], "    │         ^");
+        assert_eq!(frame.len(), 4);
+
+        let file = Path::new("src/main.rs");
+        assert_eq!(
+            editor_command("code --wait", file, 2, 9),
+            Some(("code".to_string(), vec!["--wait".to_string(), "-g".to_string(), "src/main.rs:2:9".to_string()]))
+        );
+        assert_eq!(
+            editor_command("/usr/bin/nvim", file, 2, 9),
+            Some(("/usr/bin/nvim".to_string(), vec!["+2".to_string(), "src/main.rs".to_string()]))
+        );
+        assert_eq!(editor_command("  ", file, 1, 1), None);
+    }
+}
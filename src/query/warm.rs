@@ -0,0 +1,236 @@
+//! Warm queries served by the daemon
+//!
+//! A cold `lspbridge query` captures and normalizes diagnostics, opens the
+//! history database and parses the query on every invocation. While a
+//! daemon runs, it keeps all of that loaded in a [`WarmQueryService`]: the
+//! latest diagnostics from its capture loop, its own history connection and
+//! a workspace symbol index. The `query` command sends its query over the
+//! control socket as a [`WarmQueryRequest`] and only formats the result, and
+//! falls back to running the query itself whenever no daemon answers.
+
+use crate::core::config::{EnvironmentSnapshot, UnifiedConfig};
+use crate::core::daemon::{ControlHandler, DaemonClient};
+use crate::core::{CalendarConfig, Diagnostic, DiagnosticFilter, DiagnosticResult, SymbolIndex, SymbolOccurrence};
+use crate::history::HistoryStorage;
+use crate::query::parser::FromClause;
+use crate::query::{QueryApi, QueryParser, QueryResult};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Query operation sent to a daemon
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum WarmQueryRequest {
+    /// Run a query against the daemon's diagnostics, narrowed by `filter` first
+    Query {
+        query: String,
+        #[serde(default)]
+        filter: DiagnosticFilter,
+    },
+    /// Occurrences of an identifier in the symbol index
+    References { name: String },
+}
+
+impl WarmQueryRequest {
+    /// Control socket operations handled by [`WarmQueryService`]
+    pub const OPS: &'static [&'static str] = &["query", "references"];
+}
+
+/// Diagnostics, history and symbols kept loaded for queries
+pub struct WarmQueryService {
+    /// Executor over the latest unfiltered diagnostics, keeping its result cache between queries
+    api: QueryApi,
+    diagnostics: RwLock<(Arc<DiagnosticResult>, DateTime<Utc>)>,
+    history: Option<Arc<HistoryStorage>>,
+    calendar: CalendarConfig,
+    symbols: RwLock<Option<Arc<SymbolIndex>>>,
+    /// Captured on the first `FROM config` query
+    environment: RwLock<Option<EnvironmentSnapshot>>,
+}
+
+impl WarmQueryService {
+    pub async fn new(calendar: CalendarConfig) -> Result<Self> {
+        let api = QueryApi::new();
+        api.with_calendar(calendar).await?;
+        Ok(Self {
+            api,
+            diagnostics: RwLock::new((Arc::new(DiagnosticResult::new()), Utc::now())),
+            history: None,
+            calendar,
+            symbols: RwLock::new(None),
+            environment: RwLock::new(None),
+        })
+    }
+
+    /// Serve `FROM history` through the daemon's own storage
+    pub async fn with_history(mut self, history: Arc<HistoryStorage>) -> Result<Self> {
+        self.api.with_shared_history(history.clone()).await?;
+        self.history = Some(history);
+        Ok(self)
+    }
+
+    /// Replace the diagnostics queries run against
+    pub async fn update(&self, diagnostics: Vec<Diagnostic>, captured_at: DateTime<Utc>) -> Result<()> {
+        let diagnostics = Arc::new(DiagnosticResult::from_diagnostics(diagnostics));
+        self.api.with_shared_diagnostics(diagnostics.clone()).await?;
+        *self.diagnostics.write().await = (diagnostics, captured_at);
+        Ok(())
+    }
+
+    /// Index the workspace at `root` in the background
+    ///
+    /// Once built, the index also answers `FROM symbols` queries.
+    pub fn index_symbols(self: &Arc<Self>, root: &Path) -> tokio::task::JoinHandle<()> {
+        let service = self.clone();
+        let root = root.to_path_buf();
+        tokio::spawn(async move {
+            match tokio::task::spawn_blocking(move || SymbolIndex::build(&root)).await {
+                Ok(Ok(index)) => {
+                    let index = Arc::new(index);
+                    if let Err(e) = service.api.with_symbol_index(index.clone()).await {
+                        tracing::warn!("Failed to serve the symbol index: {}", e);
+                    }
+                    *service.symbols.write().await = Some(index);
+                }
+                Ok(Err(e)) => tracing::warn!("Failed to build the symbol index: {}", e),
+                Err(e) => tracing::warn!("Symbol indexing stopped: {}", e),
+            }
+        })
+    }
+
+    pub async fn execute(&self, query: &str, filter: &DiagnosticFilter) -> Result<QueryResult> {
+        let api = if *filter == DiagnosticFilter::default() {
+            None
+        } else {
+            // Filtered diagnostics get a one-off executor; everything else stays loaded
+            let (diagnostics, captured_at) = self.diagnostics.read().await.clone();
+            let filtered = filter.apply(diagnostics.diagnostics.values().flatten().cloned().collect(), captured_at);
+            let api = QueryApi::new();
+            api.with_diagnostics(DiagnosticResult::from_diagnostics(filtered)).await?;
+            api.with_calendar(self.calendar).await?;
+            if let Some(history) = &self.history {
+                api.with_shared_history(history.clone()).await?;
+            }
+            if let Some(index) = self.symbols.read().await.as_ref() {
+                api.with_symbol_index(index.clone()).await?;
+            }
+            Some(api)
+        };
+        let api = api.as_ref().unwrap_or(&self.api);
+
+        if QueryParser::new().parse(query).is_ok_and(|parsed| parsed.from == FromClause::Config) {
+            api.with_environment(self.environment().await?).await?;
+        }
+        api.execute(query).await
+    }
+
+    pub async fn references(&self, name: &str) -> Result<Vec<SymbolOccurrence>> {
+        match self.symbols.read().await.as_ref() {
+            Some(index) => Ok(index.references(name).to_vec()),
+            None => Err(anyhow!("The symbol index is still being built")),
+        }
+    }
+
+    async fn environment(&self) -> Result<EnvironmentSnapshot> {
+        if let Some(environment) = self.environment.read().await.as_ref() {
+            return Ok(environment.clone());
+        }
+        let config = UnifiedConfig::load_or_default(Path::new("lspbridge.toml"))
+            .await
+            .unwrap_or_default();
+        let environment = EnvironmentSnapshot::capture(&config).await?;
+        *self.environment.write().await = Some(environment.clone());
+        Ok(environment)
+    }
+}
+
+#[async_trait]
+impl ControlHandler for WarmQueryService {
+    async fn handle(&self, request: serde_json::Value) -> Result<serde_json::Value> {
+        Ok(match serde_json::from_value(request)? {
+            WarmQueryRequest::Query { query, filter } => serde_json::to_value(self.execute(&query, &filter).await?)?,
+            WarmQueryRequest::References { name } => serde_json::to_value(self.references(&name).await?)?,
+        })
+    }
+}
+
+/// Run `query` in a running daemon, if there is one
+///
+/// Returns `None` when no daemon is reachable or it doesn't serve queries,
+/// so the caller can run the query itself.
+pub async fn query_daemon(data_dir: &Path, query: &str, filter: &DiagnosticFilter) -> Option<QueryResult> {
+    let client = DaemonClient::detect(data_dir).ok().flatten()?;
+    let request = WarmQueryRequest::Query {
+        query: query.to_string(),
+        filter: filter.clone(),
+    };
+    match client.request(&request).await {
+        Ok(result) => Some(result),
+        Err(e) => {
+            tracing::debug!("Running the query locally; {} did not answer it: {}", client.owner(), e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{DiagnosticSeverity, Position, Range};
+    use crate::query::executor::Value;
+
+    fn diagnostic(file: &str, severity: DiagnosticSeverity) -> Diagnostic {
+        Diagnostic::new(
+            file.to_string(),
+            Range {
+                start: Position { line: 1, character: 0 },
+                end: Position { line: 1, character: 5 },
+            },
+            severity,
+            "problem".to_string(),
+            "rustc".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_queries_see_updates_and_filters() -> Result<()> {
+        let service = WarmQueryService::new(CalendarConfig::default()).await?;
+        let count = |result: QueryResult| result.rows[0].values[0].clone();
+
+        service
+            .update(
+                vec![
+                    diagnostic("a.rs", DiagnosticSeverity::Error),
+                    diagnostic("b.rs", DiagnosticSeverity::Warning),
+                ],
+                Utc::now(),
+            )
+            .await?;
+        let all = service.execute("SELECT COUNT(*) FROM diagnostics", &DiagnosticFilter::default()).await?;
+        assert_eq!(count(all), Value::Integer(2));
+
+        let errors = DiagnosticFilter {
+            severities: Some(vec![DiagnosticSeverity::Error]),
+            ..Default::default()
+        };
+        let request = serde_json::to_value(WarmQueryRequest::Query {
+            query: "SELECT COUNT(*) FROM diagnostics".to_string(),
+            filter: errors,
+        })?;
+        let result: QueryResult = serde_json::from_value(service.handle(request).await?)?;
+        assert_eq!(count(result), Value::Integer(1));
+
+        // A new snapshot replaces cached results
+        service.update(vec![diagnostic("c.rs", DiagnosticSeverity::Hint)], Utc::now()).await?;
+        let all = service.execute("SELECT COUNT(*) FROM diagnostics", &DiagnosticFilter::default()).await?;
+        assert_eq!(count(all), Value::Integer(1));
+
+        assert!(service.references("parse").await.is_err());
+        Ok(())
+    }
+}
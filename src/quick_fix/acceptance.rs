@@ -0,0 +1,286 @@
+//! What humans do with suggested fixes
+//!
+//! Editor extensions report whether a suggested fix was accepted as is,
+//! accepted after editing, or rejected. Outcomes are stored per diagnostic
+//! fingerprint and language in a small SQLite database, reported by
+//! `quick-fix stats`, and fed back into [`FixConfidenceScorer`] so fixes that
+//! people keep rejecting stop being ranked as confidently.
+//!
+//! [`FixConfidenceScorer`]: super::FixConfidenceScorer
+
+use super::confidence::detect_language_from_file;
+use crate::core::Diagnostic;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use utoipa::ToSchema;
+
+/// What the human did with a suggested fix
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FixOutcome {
+    /// Applied unchanged
+    Accepted,
+    /// Applied, then edited
+    Modified,
+    /// Dismissed or undone
+    Rejected,
+}
+
+impl FixOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FixOutcome::Accepted => "accepted",
+            FixOutcome::Modified => "modified",
+            FixOutcome::Rejected => "rejected",
+        }
+    }
+}
+
+/// Outcome of a suggested fix, as reported by an editor extension
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FixOutcomeReport {
+    /// `fingerprint` of the [`RankedFix`](super::RankedFix) the outcome is for
+    pub fingerprint: String,
+    pub outcome: FixOutcome,
+    /// Language of the file; derived from `file` when omitted
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub file: Option<String>,
+    /// Confidence the fix was suggested with
+    #[serde(default)]
+    pub confidence: Option<f32>,
+}
+
+impl FixOutcomeReport {
+    /// Language the outcome is recorded under
+    pub fn language(&self) -> String {
+        match (&self.language, &self.file) {
+            (Some(language), _) => language.to_lowercase(),
+            (None, Some(file)) => detect_language_from_file(file),
+            (None, None) => crate::core::constants::lsp_constants::UNKNOWN.to_string(),
+        }
+    }
+}
+
+/// Outcomes recorded for one fingerprint in one language
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AcceptanceStats {
+    pub fingerprint: String,
+    pub language: String,
+    pub accepted: u64,
+    pub modified: u64,
+    pub rejected: u64,
+    /// Mean confidence of the suggestions, when editors reported it
+    pub mean_confidence: Option<f64>,
+}
+
+impl AcceptanceStats {
+    pub fn total(&self) -> u64 {
+        self.accepted + self.modified + self.rejected
+    }
+
+    /// Share of suggestions that were kept; a modified fix counts as half a success
+    pub fn acceptance_rate(&self) -> f32 {
+        match self.total() {
+            0 => 0.0,
+            total => (self.accepted as f32 + 0.5 * self.modified as f32) / total as f32,
+        }
+    }
+}
+
+/// Stable identifier of the kind of problem a diagnostic reports
+///
+/// Diagnostics with a code are identified by source and code
+/// (`rustc:E0308`). Others are identified by a hash of their message with
+/// quoted names and numbers removed, so the same mistake on different
+/// symbols shares a fingerprint.
+pub fn fix_fingerprint(diagnostic: &Diagnostic) -> String {
+    if let Some(code) = diagnostic.code.as_deref().filter(|code| !code.is_empty()) {
+        return format!("{}:{}", diagnostic.source, code);
+    }
+
+    let mut normalized = String::with_capacity(diagnostic.message.len());
+    let mut quote: Option<char> = None;
+    for c in diagnostic.message.chars() {
+        match quote {
+            Some(open) if c == open => quote = None,
+            Some(_) => {}
+            None if matches!(c, '\'' | '"' | '`') => {
+                quote = Some(c);
+                normalized.push('_');
+            }
+            None if c.is_ascii_digit() => {
+                if !normalized.ends_with('#') {
+                    normalized.push('#');
+                }
+            }
+            None => normalized.push(c.to_ascii_lowercase()),
+        }
+    }
+    let digest = Sha256::digest(normalized.trim().as_bytes());
+    let hash: String = digest.iter().take(6).map(|byte| format!("{byte:02x}")).collect();
+    format!("{}:{}", diagnostic.source, hash)
+}
+
+/// SQLite-backed store of fix outcomes
+pub struct AcceptanceStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl AcceptanceStore {
+    /// Default location of the outcome database
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(crate::config::data_dir()?.join("fix_outcomes.db"))
+    }
+
+    /// Open or create the outcome database at `path`
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).context("Failed to create fix outcome directory")?;
+        }
+        let conn = Connection::open(path).context("Failed to open fix outcome database")?;
+        Self::from_connection(conn)
+    }
+
+    /// Outcome store that is discarded when dropped
+    pub fn in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS fix_outcomes (
+                fingerprint TEXT NOT NULL,
+                language TEXT NOT NULL,
+                outcome TEXT NOT NULL,
+                confidence REAL,
+                file TEXT,
+                recorded_at TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_fix_outcomes_fingerprint
+                ON fix_outcomes (fingerprint, language);
+            "#,
+        )
+        .context("Failed to initialize fix outcome schema")?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Record the outcome of one suggested fix
+    pub async fn record(&self, report: &FixOutcomeReport) -> Result<()> {
+        if report.fingerprint.trim().is_empty() {
+            anyhow::bail!("Fix outcome is missing a fingerprint");
+        }
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO fix_outcomes (fingerprint, language, outcome, confidence, file, recorded_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                report.fingerprint,
+                report.language(),
+                report.outcome.as_str(),
+                report.confidence.map(f64::from),
+                report.file,
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Outcomes per fingerprint and language, most reported first
+    pub async fn stats(&self, language: Option<&str>) -> Result<Vec<AcceptanceStats>> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn.prepare(
+            "SELECT fingerprint, language,
+                    SUM(outcome = 'accepted'), SUM(outcome = 'modified'), SUM(outcome = 'rejected'),
+                    AVG(confidence)
+             FROM fix_outcomes
+             WHERE ?1 IS NULL OR language = ?1
+             GROUP BY fingerprint, language
+             ORDER BY COUNT(*) DESC, fingerprint, language",
+        )?;
+        let stats = stmt
+            .query_map(params![language.map(str::to_lowercase)], |row| {
+                Ok(AcceptanceStats {
+                    fingerprint: row.get(0)?,
+                    language: row.get(1)?,
+                    accepted: row.get::<_, i64>(2)? as u64,
+                    modified: row.get::<_, i64>(3)? as u64,
+                    rejected: row.get::<_, i64>(4)? as u64,
+                    mean_confidence: row.get(5)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{DiagnosticSeverity, Position, Range};
+    use crate::quick_fix::FixConfidenceScorer;
+
+    fn diagnostic(message: &str, code: Option<&str>) -> Diagnostic {
+        let mut diagnostic = Diagnostic::new(
+            "src/app.ts".to_string(),
+            Range {
+                start: Position { line: 0, character: 0 },
+                end: Position { line: 0, character: 4 },
+            },
+            DiagnosticSeverity::Error,
+            message.to_string(),
+            "typescript".to_string(),
+        );
+        diagnostic.code = code.map(String::from);
+        diagnostic
+    }
+
+    #[tokio::test]
+    async fn test_outcomes_calibrate_confidence() -> Result<()> {
+        let flagged = diagnostic("Type 'string' is not assignable to type 'number'", Some("TS2322"));
+        assert_eq!(fix_fingerprint(&flagged), "typescript:TS2322");
+        assert_eq!(
+            fix_fingerprint(&diagnostic("Cannot find name 'foo' at 3", None)),
+            fix_fingerprint(&diagnostic("Cannot find name 'barBaz' at 41", None))
+        );
+
+        let store = AcceptanceStore::in_memory()?;
+        for outcome in [FixOutcome::Rejected, FixOutcome::Rejected, FixOutcome::Rejected, FixOutcome::Modified] {
+            store
+                .record(&FixOutcomeReport {
+                    fingerprint: fix_fingerprint(&flagged),
+                    outcome,
+                    language: None,
+                    file: Some("src/app.ts".to_string()),
+                    confidence: Some(0.8),
+                })
+                .await?;
+        }
+
+        let stats = store.stats(None).await?;
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].language, "typescript");
+        assert_eq!((stats[0].accepted, stats[0].modified, stats[0].rejected), (0, 1, 3));
+        assert_eq!(stats[0].acceptance_rate(), 0.125);
+        assert!(store.stats(Some("rust")).await?.is_empty());
+
+        let (before, _) = FixConfidenceScorer::new().score_fix(&flagged, "number", false);
+        let (after, factors) = FixConfidenceScorer::new()
+            .with_acceptance(&stats)
+            .score_fix(&flagged, "number", false);
+        assert!(after.value() < before.value());
+        assert!(factors.historical_success < 0.85);
+        Ok(())
+    }
+}
@@ -0,0 +1,285 @@
+//! Fix confidence calibrated from annotation feedback
+//!
+//! `ai-training annotate` records a quality judgment on every training pair
+//! it reviews. [`ConfidenceCalibration`] ingests those judgments per
+//! diagnostic fingerprint and language and persists them, so
+//! [`FixConfidenceScorer`] can trust patterns whose fixes reviewers rate
+//! highly and distrust those they reject. From the same judgments it
+//! recommends the lowest auto-apply threshold at which the fixes
+//! reviewers saw were reliably good.
+//!
+//! Re-ingesting a dataset replaces its pairs' earlier judgments instead of
+//! counting them twice, so calibration can be rerun after every review.
+//!
+//! [`FixConfidenceScorer`]: super::FixConfidenceScorer
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use super::acceptance::fix_fingerprint;
+use crate::ai_training::review::{ORIGINAL_CONFIDENCE_KEY, QUALITY_KEY};
+use crate::ai_training::{FixQuality, TrainingDataset};
+
+/// Share of fixes at or above the recommended threshold that must be rated good or better
+pub const TARGET_PRECISION: f32 = 0.9;
+
+/// Judgments needed at or above a threshold before it is recommended
+pub const MIN_THRESHOLD_SAMPLES: usize = 20;
+
+/// Lowest auto-apply threshold ever recommended
+const MIN_AUTO_APPLY: f32 = 0.6;
+
+/// One reviewer judgment of a fix
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationSample {
+    pub fingerprint: String,
+    pub language: String,
+    pub quality: FixQuality,
+    /// Confidence the fix had before it was rated
+    pub confidence: f32,
+}
+
+impl CalibrationSample {
+    fn is_good(&self) -> bool {
+        self.quality >= FixQuality::Good
+    }
+}
+
+/// Calibrated weight of one fingerprint in one language
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatternCalibration {
+    pub fingerprint: String,
+    pub language: String,
+    pub samples: usize,
+    /// Mean [`FixQuality::weight`] of the judgments (0.0-1.0)
+    pub mean_quality: f32,
+}
+
+/// What one ingestion changed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IngestSummary {
+    /// Judgments read from the dataset
+    pub ingested: usize,
+    /// Of those, how many replaced an earlier judgment of the same pair
+    pub replaced: usize,
+    /// Annotated pairs without a diagnostic to attribute the judgment to
+    pub skipped: usize,
+}
+
+/// Reviewer judgments of fixes, keyed by training pair id
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfidenceCalibration {
+    samples: BTreeMap<String, CalibrationSample>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl ConfidenceCalibration {
+    pub fn default_path() -> PathBuf {
+        crate::config::data_dir()
+            .unwrap_or_else(|_| std::env::temp_dir().join("lspbridge"))
+            .join("fix_calibration.json")
+    }
+
+    /// Load calibration from disk, starting empty if the file does not exist
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read fix calibration {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse fix calibration {}", path.display()))
+    }
+
+    /// Persist the calibration to disk
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write fix calibration {}", path.display()))
+    }
+
+    /// Record the judgments of every annotated pair in a dataset
+    ///
+    /// A pair's judgment is attributed to the fingerprint of its first
+    /// diagnostic, the one the fix was made for.
+    pub fn ingest(&mut self, dataset: &TrainingDataset) -> IngestSummary {
+        let mut summary = IngestSummary::default();
+        for pair in &dataset.pairs {
+            let Some(quality) = pair
+                .metadata
+                .get(QUALITY_KEY)
+                .and_then(|value| serde_json::from_value::<FixQuality>(value.clone()).ok())
+            else {
+                continue;
+            };
+            let Some(diagnostic) = pair.diagnostics.first() else {
+                summary.skipped += 1;
+                continue;
+            };
+            let confidence = pair
+                .metadata
+                .get(ORIGINAL_CONFIDENCE_KEY)
+                .and_then(|value| value.as_f64())
+                .map_or(pair.confidence.score, |score| score as f32);
+
+            let sample = CalibrationSample {
+                fingerprint: fix_fingerprint(diagnostic),
+                language: pair.language.to_lowercase(),
+                quality,
+                confidence,
+            };
+            if self.samples.insert(pair.id.clone(), sample).is_some() {
+                summary.replaced += 1;
+            }
+            summary.ingested += 1;
+        }
+        if summary.ingested > 0 {
+            self.updated_at = Some(Utc::now());
+        }
+        summary
+    }
+
+    pub fn sample_count(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Per-pattern calibration, most judged first
+    pub fn patterns(&self) -> Vec<PatternCalibration> {
+        let mut totals: HashMap<(&str, &str), (usize, f32)> = HashMap::new();
+        for sample in self.samples.values() {
+            let entry = totals.entry((&sample.fingerprint, &sample.language)).or_default();
+            entry.0 += 1;
+            entry.1 += sample.quality.weight();
+        }
+
+        let mut patterns: Vec<PatternCalibration> = totals
+            .into_iter()
+            .map(|((fingerprint, language), (samples, quality))| PatternCalibration {
+                fingerprint: fingerprint.to_string(),
+                language: language.to_string(),
+                samples,
+                mean_quality: quality / samples as f32,
+            })
+            .collect();
+        patterns.sort_by(|a, b| {
+            b.samples
+                .cmp(&a.samples)
+                .then_with(|| a.fingerprint.cmp(&b.fingerprint))
+                .then_with(|| a.language.cmp(&b.language))
+        });
+        patterns
+    }
+
+    /// Lowest auto-apply threshold at which judged fixes were reliably good
+    ///
+    /// A candidate threshold needs [`MIN_THRESHOLD_SAMPLES`] judgments at or
+    /// above it, of which at least [`TARGET_PRECISION`] were rated good or
+    /// perfect. `None` until there are enough judgments.
+    pub fn recommended_auto_apply(&self) -> Option<f32> {
+        let mut samples: Vec<&CalibrationSample> = self.samples.values().collect();
+        samples.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+
+        let mut good = 0;
+        let mut recommended = None;
+        for (index, sample) in samples.iter().enumerate() {
+            if sample.is_good() {
+                good += 1;
+            }
+            let seen = index + 1;
+            // Only cut between distinct confidences, so every fix at the threshold counts
+            let boundary = samples
+                .get(seen)
+                .map_or(true, |next| next.confidence < sample.confidence);
+            if boundary && seen >= MIN_THRESHOLD_SAMPLES && good as f32 / seen as f32 >= TARGET_PRECISION {
+                recommended = Some(sample.confidence.max(MIN_AUTO_APPLY));
+            }
+        }
+        recommended
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai_training::{FixConfidence, TrainingPair};
+    use crate::core::semantic_context::SemanticContext;
+    use crate::core::{Diagnostic, DiagnosticSeverity, Position, Range};
+
+    fn pair(code: &str, quality: FixQuality, confidence: f32) -> TrainingPair {
+        let mut diagnostic = Diagnostic::new(
+            "src/lib.rs".to_string(),
+            Range {
+                start: Position { line: 0, character: 0 },
+                end: Position { line: 0, character: 1 },
+            },
+            DiagnosticSeverity::Error,
+            "mismatched types".to_string(),
+            "rustc".to_string(),
+        );
+        diagnostic.code = Some(code.to_string());
+        let mut pair = TrainingPair::new(
+            "let x: u32 = \"1\";".to_string(),
+            "let x: u32 = 1;".to_string(),
+            vec![diagnostic],
+            SemanticContext::default(),
+            "Rust".to_string(),
+        );
+        pair.confidence = FixConfidence::new(confidence * quality.weight());
+        pair.add_metadata(ORIGINAL_CONFIDENCE_KEY.to_string(), serde_json::json!(confidence));
+        pair.add_metadata(QUALITY_KEY.to_string(), serde_json::json!(quality));
+        pair
+    }
+
+    fn dataset(pairs: Vec<TrainingPair>) -> TrainingDataset {
+        let mut dataset = TrainingDataset::new("calibration".to_string(), "test".to_string());
+        dataset.pairs = pairs;
+        dataset
+    }
+
+    #[test]
+    fn test_reingesting_replaces_judgments() {
+        let mut dataset = dataset(vec![
+            pair("E0308", FixQuality::Perfect, 0.8),
+            pair("E0308", FixQuality::Good, 0.8),
+            pair("E0382", FixQuality::Incorrect, 0.7),
+        ]);
+        let mut calibration = ConfidenceCalibration::default();
+        assert_eq!(calibration.ingest(&dataset).ingested, 3);
+
+        dataset.pairs[2].add_metadata(QUALITY_KEY.to_string(), serde_json::json!(FixQuality::Poor));
+        let summary = calibration.ingest(&dataset);
+        assert_eq!((summary.ingested, summary.replaced), (3, 3));
+        assert_eq!(calibration.sample_count(), 3);
+
+        let patterns = calibration.patterns();
+        assert_eq!(patterns[0].fingerprint, "rustc:E0308");
+        assert_eq!(patterns[0].language, "rust");
+        assert_eq!(patterns[0].samples, 2);
+        assert!((patterns[0].mean_quality - 0.9).abs() < 1e-5);
+        assert!((patterns[1].mean_quality - FixQuality::Poor.weight()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_recommended_threshold() {
+        // Fixes scored 0.8 and up were good; below that half were wrong
+        let mut pairs: Vec<TrainingPair> = (0..20)
+            .map(|i| pair("E0308", FixQuality::Good, 0.8 + i as f32 * 0.005))
+            .collect();
+        pairs.extend((0..10).map(|i| {
+            let quality = if i % 2 == 0 { FixQuality::Perfect } else { FixQuality::Incorrect };
+            pair("E0308", quality, 0.7)
+        }));
+
+        let mut calibration = ConfidenceCalibration::default();
+        calibration.ingest(&dataset(pairs[..19].to_vec()));
+        assert_eq!(calibration.recommended_auto_apply(), None);
+
+        calibration.ingest(&dataset(pairs));
+        assert_eq!(calibration.recommended_auto_apply(), Some(0.8));
+    }
+}
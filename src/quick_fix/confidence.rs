@@ -1,3 +1,5 @@
+use super::acceptance::{fix_fingerprint, AcceptanceStats};
+use super::calibration::ConfidenceCalibration;
 use crate::core::constants::{languages, lsp_constants};
 use crate::core::types::{Diagnostic, DiagnosticSeverity};
 use serde::{Deserialize, Serialize};
@@ -61,8 +63,111 @@ pub struct ConfidenceFactors {
     pub language_confidence: f32,
     /// Whether fix comes from LSP code action (0.0-1.0)
     pub lsp_confidence: f32,
+    /// How much the diagnostic says about the problem: code, range, related locations (0.0-1.0)
+    #[serde(default)]
+    pub context_completeness: f32,
 }
 
+impl ConfidenceFactors {
+    /// Each factor with its weight and share of the final score, in weight order
+    pub fn contributions(&self) -> Vec<FactorContribution> {
+        let factors = [
+            ("pattern_recognition", "Pattern recognition", self.pattern_recognition, 0.25),
+            ("historical_success", "Historical success", self.historical_success, 0.20),
+            ("fix_complexity", "Fix simplicity", self.fix_complexity, 0.15),
+            ("safety_score", "Safety", self.safety_score, 0.15),
+            ("lsp_confidence", "Language server action", self.lsp_confidence, 0.15),
+            ("language_confidence", "Language confidence", self.language_confidence, 0.10),
+            ("context_completeness", "Context completeness", self.context_completeness, 0.10),
+        ];
+        let total_weight: f32 = factors.iter().map(|(_, _, _, weight)| weight).sum();
+
+        factors
+            .into_iter()
+            .map(|(factor, label, value, weight)| FactorContribution {
+                factor: factor.to_string(),
+                label: label.to_string(),
+                value,
+                weight: weight / total_weight,
+                contribution: value * weight / total_weight,
+            })
+            .collect()
+    }
+
+    /// Why the factors add up to the score they do
+    pub fn explain(&self) -> ConfidenceExplanation {
+        let factors = self.contributions();
+        ConfidenceExplanation {
+            score: ConfidenceScore::new(factors.iter().map(|f| f.contribution).sum()),
+            factors,
+        }
+    }
+}
+
+/// One factor's part in a confidence score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FactorContribution {
+    /// Field name in [`ConfidenceFactors`]
+    pub factor: String,
+    pub label: String,
+    /// Factor value (0.0-1.0)
+    pub value: f32,
+    /// Normalized weight; the weights of all factors sum to 1.0
+    pub weight: f32,
+    /// `value * weight`; the contributions sum to the score
+    pub contribution: f32,
+}
+
+impl FactorContribution {
+    /// Score lost to this factor falling short of 1.0
+    pub fn shortfall(&self) -> f32 {
+        (1.0 - self.value) * self.weight
+    }
+}
+
+/// Confidence score broken down by factor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfidenceExplanation {
+    pub score: ConfidenceScore,
+    pub factors: Vec<FactorContribution>,
+}
+
+impl ConfidenceExplanation {
+    /// Factors costing the most score, largest shortfall first
+    pub fn limiting_factors(&self, count: usize) -> Vec<&FactorContribution> {
+        let mut factors: Vec<_> = self.factors.iter().filter(|f| f.shortfall() > 0.0).collect();
+        factors.sort_by(|a, b| b.shortfall().total_cmp(&a.shortfall()));
+        factors.truncate(count);
+        factors
+    }
+
+    /// Plain-text breakdown, one line per factor
+    pub fn render(&self) -> String {
+        let mut out = format!("confidence {:.2} =\n", self.score.value());
+        for factor in &self.factors {
+            out.push_str(&format!(
+                "  {:<24} {:.2} x {:.2} = {:.3}\n",
+                factor.label, factor.value, factor.weight, factor.contribution
+            ));
+        }
+        let limiting = self.limiting_factors(2);
+        if !limiting.is_empty() {
+            let names: Vec<_> = limiting
+                .iter()
+                .map(|f| format!("{} (-{:.2})", f.label.to_lowercase(), f.shortfall()))
+                .collect();
+            out.push_str(&format!("  held back most by {}\n", names.join(", ")));
+        }
+        out
+    }
+}
+
+/// Reported outcomes that count as much as the built-in success rate
+const ACCEPTANCE_PRIOR_WEIGHT: f32 = 5.0;
+
+/// Reviewer judgments that count as much as the built-in pattern rate
+const CALIBRATION_PRIOR_WEIGHT: f32 = 5.0;
+
 /// Fix confidence scorer
 pub struct FixConfidenceScorer {
     /// Historical success rates by error pattern
@@ -71,6 +176,10 @@ pub struct FixConfidenceScorer {
     language_modifiers: HashMap<String, f32>,
     /// User-configured thresholds
     thresholds: ConfidenceThreshold,
+    /// Acceptance rate and number of reported outcomes by fingerprint and language
+    acceptance: HashMap<(String, String), (f32, u64)>,
+    /// Mean reviewer judgment and number of judgments by fingerprint and language
+    calibration: HashMap<(String, String), (f32, usize)>,
 }
 
 impl FixConfidenceScorer {
@@ -99,6 +208,8 @@ impl FixConfidenceScorer {
             pattern_success_rates,
             language_modifiers,
             thresholds: ConfidenceThreshold::default(),
+            acceptance: HashMap::new(),
+            calibration: HashMap::new(),
         }
     }
 
@@ -107,6 +218,34 @@ impl FixConfidenceScorer {
         self
     }
 
+    /// Calibrate historical success with fix outcomes reported by editors
+    ///
+    /// The reported acceptance rate is blended with the built-in rate, so a
+    /// handful of outcomes nudges the score and many outcomes dominate it.
+    pub fn with_acceptance(mut self, stats: &[AcceptanceStats]) -> Self {
+        for stat in stats.iter().filter(|stat| stat.total() > 0) {
+            self.acceptance.insert(
+                (stat.fingerprint.clone(), stat.language.clone()),
+                (stat.acceptance_rate(), stat.total()),
+            );
+        }
+        self
+    }
+
+    /// Calibrate pattern recognition with reviewers' judgments of fixes
+    ///
+    /// Blended with the built-in pattern rate the same way reported outcomes
+    /// are, so well-reviewed patterns gain confidence and rejected ones lose it.
+    pub fn with_calibration(mut self, calibration: &ConfidenceCalibration) -> Self {
+        for pattern in calibration.patterns() {
+            self.calibration.insert(
+                (pattern.fingerprint, pattern.language),
+                (pattern.mean_quality, pattern.samples),
+            );
+        }
+        self
+    }
+
     pub fn score_fix(
         &self,
         diagnostic: &Diagnostic,
@@ -125,12 +264,21 @@ impl FixConfidenceScorer {
         fix_text: &str,
         has_lsp_action: bool,
     ) -> ConfidenceFactors {
-        // Pattern recognition score
-        let pattern_recognition = if let Some(code) = &diagnostic.code {
+        // Pattern recognition score, calibrated by reviewer judgments
+        let language = detect_language_from_file(&diagnostic.file);
+        let fingerprint = fix_fingerprint(diagnostic);
+        let pattern_rate = if let Some(code) = &diagnostic.code {
             self.pattern_success_rates.get(code).copied().unwrap_or(0.5)
         } else {
             0.3
         };
+        let pattern_recognition = match self.calibration.get(&(fingerprint.clone(), language.clone())) {
+            Some(&(quality, samples)) => {
+                let samples = samples as f32;
+                (quality * samples + pattern_rate * CALIBRATION_PRIOR_WEIGHT) / (samples + CALIBRATION_PRIOR_WEIGHT)
+            }
+            None => pattern_rate,
+        };
 
         // Fix complexity (simple heuristics)
         let fix_complexity = match fix_text.len() {
@@ -141,8 +289,15 @@ impl FixConfidenceScorer {
             _ => 0.2,         // Very complex fix
         };
 
-        // Historical success (would be loaded from persistent storage)
-        let historical_success = pattern_recognition; // For now, use pattern rate
+        // Historical success: the pattern rate, calibrated by reported outcomes
+        let historical_success = match self.acceptance.get(&(fingerprint, language.clone())) {
+            Some(&(rate, outcomes)) => {
+                let outcomes = outcomes as f32;
+                (rate * outcomes + pattern_recognition * ACCEPTANCE_PRIOR_WEIGHT)
+                    / (outcomes + ACCEPTANCE_PRIOR_WEIGHT)
+            }
+            None => pattern_recognition,
+        };
 
         // Safety score based on severity and fix type
         let safety_score = match diagnostic.severity {
@@ -153,7 +308,6 @@ impl FixConfidenceScorer {
         };
 
         // Language confidence
-        let language = detect_language_from_file(&diagnostic.file);
         let language_confidence = self
             .language_modifiers
             .get(&language)
@@ -163,6 +317,26 @@ impl FixConfidenceScorer {
         // LSP confidence boost
         let lsp_confidence = if has_lsp_action { 0.95 } else { 0.5 };
 
+        // Context completeness: a coded, located diagnostic with related
+        // locations pins the problem down better than a bare message
+        let mut context_completeness: f32 = 0.2;
+        if diagnostic.code.is_some() {
+            context_completeness += 0.3;
+        }
+        if diagnostic.range.start != diagnostic.range.end {
+            context_completeness += 0.2;
+        }
+        if diagnostic
+            .related_information
+            .as_ref()
+            .is_some_and(|related| !related.is_empty())
+        {
+            context_completeness += 0.2;
+        }
+        if !diagnostic.source.is_empty() {
+            context_completeness += 0.1;
+        }
+
         ConfidenceFactors {
             pattern_recognition,
             fix_complexity,
@@ -170,24 +344,13 @@ impl FixConfidenceScorer {
             safety_score,
             language_confidence,
             lsp_confidence,
+            context_completeness: context_completeness.min(1.0),
         }
     }
 
     fn calculate_weighted_score(&self, factors: &ConfidenceFactors) -> f32 {
         // Weighted average with different importance for each factor
-        let weights = [
-            (factors.pattern_recognition, 0.25),
-            (factors.fix_complexity, 0.15),
-            (factors.historical_success, 0.20),
-            (factors.safety_score, 0.15),
-            (factors.language_confidence, 0.10),
-            (factors.lsp_confidence, 0.15),
-        ];
-
-        let total_weight: f32 = weights.iter().map(|(_, w)| w).sum();
-        let weighted_sum: f32 = weights.iter().map(|(v, w)| v * w).sum();
-
-        weighted_sum / total_weight
+        factors.contributions().iter().map(|f| f.contribution).sum()
     }
 
     pub fn update_success_rate(&mut self, pattern: &str, success: bool) {
@@ -214,7 +377,7 @@ impl Default for FixConfidenceScorer {
     }
 }
 
-fn detect_language_from_file(file_path: &str) -> String {
+pub(crate) fn detect_language_from_file(file_path: &str) -> String {
     if file_path.ends_with(".ts") || file_path.ends_with(".tsx") {
         languages::TYPESCRIPT.to_string()
     } else if file_path.ends_with(".js") || file_path.ends_with(".jsx") {
@@ -285,4 +448,73 @@ mod tests {
         assert!(score.value() > 0.5); // Should have decent confidence
         assert!(factors.lsp_confidence > 0.9); // LSP action should boost confidence
     }
+
+    #[test]
+    fn test_explanation_adds_up_to_score() {
+        let scorer = FixConfidenceScorer::new();
+        let mut diagnostic = Diagnostic::new(
+            "src/main.rs".to_string(),
+            Range {
+                start: Position { line: 3, character: 4 },
+                end: Position { line: 3, character: 9 },
+            },
+            DiagnosticSeverity::Error,
+            "mismatched types".to_string(),
+            "rustc".to_string(),
+        );
+        diagnostic.code = Some("E0308".to_string());
+
+        let (score, factors) = scorer.score_fix(&diagnostic, "x as u32", false);
+        let explanation = factors.explain();
+        assert!((explanation.score.value() - score.value()).abs() < 1e-5);
+        let weights: f32 = explanation.factors.iter().map(|f| f.weight).sum();
+        assert!((weights - 1.0).abs() < 1e-5);
+
+        // No language server action is the biggest gap for this fix
+        let limiting = explanation.limiting_factors(1);
+        assert_eq!(limiting[0].factor, "lsp_confidence");
+        assert!(explanation.render().contains("held back most by language server action"));
+    }
+
+    #[test]
+    fn test_calibration_moves_pattern_confidence() {
+        use crate::ai_training::review::QUALITY_KEY;
+        use crate::ai_training::{FixQuality, TrainingDataset, TrainingPair};
+
+        let mut diagnostic = Diagnostic::new(
+            "src/main.rs".to_string(),
+            Range {
+                start: Position { line: 3, character: 4 },
+                end: Position { line: 3, character: 9 },
+            },
+            DiagnosticSeverity::Error,
+            "borrow of moved value".to_string(),
+            "rustc".to_string(),
+        );
+        diagnostic.code = Some("E0382".to_string());
+
+        let mut dataset = TrainingDataset::new("reviews".to_string(), String::new());
+        for _ in 0..15 {
+            let mut pair = TrainingPair::new(
+                String::new(),
+                String::new(),
+                vec![diagnostic.clone()],
+                Default::default(),
+                "rust".to_string(),
+            );
+            pair.add_metadata(QUALITY_KEY.to_string(), serde_json::json!(FixQuality::Incorrect));
+            dataset.pairs.push(pair);
+        }
+        let mut calibration = ConfidenceCalibration::default();
+        calibration.ingest(&dataset);
+
+        let (base, base_factors) = FixConfidenceScorer::new().score_fix(&diagnostic, ".clone()", false);
+        let (calibrated, factors) = FixConfidenceScorer::new()
+            .with_calibration(&calibration)
+            .score_fix(&diagnostic, ".clone()", false);
+        // 15 rejections against a prior of 5 at 0.70
+        assert!((factors.pattern_recognition - 0.175).abs() < 1e-5);
+        assert!(factors.pattern_recognition < base_factors.pattern_recognition);
+        assert!(calibrated.value() < base.value());
+    }
 }
@@ -1,15 +1,22 @@
+use crate::core::errors::FileError;
+use crate::core::file_guard::{FileGuard, SkippedFile};
+use crate::core::telemetry::{self, Operation};
 use crate::core::types::{Diagnostic, Range};
 use crate::core::utils::FileUtils;
 use crate::quick_fix::confidence::{ConfidenceScore, ConfidenceThreshold};
+use crate::quick_fix::transaction::{FixTransaction, TransactionResult};
 use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
 use std::path::PathBuf;
-use tokio::fs;
+use std::time::Instant;
+use utoipa::ToSchema;
 
 /// Represents a single edit to apply
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct FixEdit {
     /// File to edit
+    #[schema(value_type = String)]
     pub file_path: PathBuf,
     /// Range to replace
     pub range: Range,
@@ -20,11 +27,12 @@ pub struct FixEdit {
 }
 
 /// Result of applying a fix
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct FixResult {
     /// Whether the fix was successfully applied
     pub success: bool,
     /// Files that were modified
+    #[schema(value_type = Vec<String>)]
     pub modified_files: Vec<PathBuf>,
     /// Error message if failed
     pub error: Option<String>,
@@ -33,8 +41,9 @@ pub struct FixResult {
 }
 
 /// Backup of original file content
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct FileBackup {
+    #[schema(value_type = String)]
     pub file_path: PathBuf,
     pub original_content: String,
     pub timestamp: chrono::DateTime<chrono::Utc>,
@@ -46,8 +55,8 @@ pub struct FixApplicationEngine {
     create_backups: bool,
     /// Whether to preserve formatting
     preserve_formatting: bool,
-    /// Maximum file size to process (in bytes)
-    max_file_size: usize,
+    /// Size and binary limits for files being fixed
+    file_guard: FileGuard,
 }
 
 impl FixApplicationEngine {
@@ -55,7 +64,7 @@ impl FixApplicationEngine {
         Self {
             create_backups: true,
             preserve_formatting: true,
-            max_file_size: 10 * 1024 * 1024, // 10MB
+            file_guard: FileGuard::default(),
         }
     }
 
@@ -69,25 +78,41 @@ impl FixApplicationEngine {
         self
     }
 
+    /// Refuse to edit files the guard skips, e.g. ones over `processing.file_size_limit_mb`
+    pub fn with_file_guard(mut self, file_guard: FileGuard) -> Self {
+        self.file_guard = file_guard;
+        self
+    }
+
+    /// Files left untouched because they were too large or binary
+    pub fn skipped_files(&self) -> Vec<SkippedFile> {
+        self.file_guard.skipped()
+    }
+
     /// Apply a single fix edit
+    #[tracing::instrument(name = "quick_fix", skip_all, fields(file = %edit.file_path.display()))]
     pub async fn apply_fix(&self, edit: &FixEdit) -> Result<FixResult> {
-        // Validate file exists and is not too large
-        let metadata = fs::metadata(&edit.file_path)
-            .await
-            .context("Failed to read file metadata")?;
-
-        if metadata.len() > self.max_file_size as u64 {
-            return Ok(FixResult {
-                success: false,
-                modified_files: vec![],
-                error: Some(format!("File too large: {} bytes", metadata.len())),
-                backup: None,
-            });
-        }
+        let started = Instant::now();
+        let result = self.write_fix(edit).await;
+        let applied = result.as_ref().is_ok_and(|result| result.success);
+        telemetry::record_operation(Operation::QuickFix, started.elapsed(), usize::from(applied), applied);
+        result
+    }
 
-        // Read original content
-        let original_content =
-            FileUtils::read_with_context(&edit.file_path, "source file for fix").await?;
+    async fn write_fix(&self, edit: &FixEdit) -> Result<FixResult> {
+        // Read original content, skipping oversized and binary files
+        let original_content = match self.file_guard.read_to_string_async(&edit.file_path).await {
+            Ok(content) => content,
+            Err(FileError::Skipped { reason, .. }) => {
+                return Ok(FixResult {
+                    success: false,
+                    modified_files: vec![],
+                    error: Some(format!("File skipped: {reason}")),
+                    backup: None,
+                });
+            }
+            Err(e) => return Err(e).context("Failed to read source file for fix"),
+        };
 
         // Create backup if enabled
         let backup = if self.create_backups {
@@ -114,6 +139,92 @@ impl FixApplicationEngine {
         })
     }
 
+    /// Apply every edit of a transaction, or none of them
+    ///
+    /// All files are read and edited in memory before any is written, so an
+    /// unreadable file or invalid edit changes nothing. If a write fails,
+    /// files already written are restored. Backups of every modified file
+    /// are returned regardless of [`with_backups`](Self::with_backups), as
+    /// the transaction's rollback session needs them.
+    #[tracing::instrument(name = "quick_fix", skip_all, fields(edits = transaction.len()))]
+    pub async fn apply_transaction(&self, transaction: &FixTransaction) -> Result<TransactionResult> {
+        let started = Instant::now();
+        let result = self.write_transaction(transaction).await;
+        let applied = result.as_ref().is_ok_and(|result| result.success);
+        let items = if applied { transaction.len() } else { 0 };
+        telemetry::record_operation(Operation::QuickFix, started.elapsed(), items, applied);
+        result
+    }
+
+    async fn write_transaction(&self, transaction: &FixTransaction) -> Result<TransactionResult> {
+        // (file, original, edited)
+        let mut staged = Vec::new();
+        for file in transaction.files() {
+            let original = match self.file_guard.read_to_string_async(&file).await {
+                Ok(content) => content,
+                Err(FileError::Skipped { reason, .. }) => {
+                    return Ok(TransactionResult::failed(format!("{} skipped: {reason}", file.display())));
+                }
+                Err(e) => return Err(e).with_context(|| format!("Failed to read {} for fix", file.display())),
+            };
+
+            // Apply from the bottom of the file up so earlier ranges stay valid
+            let mut edits: Vec<&FixEdit> = transaction.edits.iter().filter(|edit| edit.file_path == file).collect();
+            edits.sort_by_key(|edit| Reverse((edit.range.start.line, edit.range.start.character)));
+            let overlapping = edits.windows(2).any(|pair| {
+                let (below, above) = (&pair[0].range, &pair[1].range);
+                (above.end.line, above.end.character) > (below.start.line, below.start.character)
+            });
+            if overlapping {
+                return Ok(TransactionResult::failed(format!("Overlapping edits in {}", file.display())));
+            }
+
+            let mut content = original.clone();
+            for edit in edits {
+                content = self
+                    .apply_edit_to_content(&content, edit)
+                    .with_context(|| format!("Invalid edit for {}", file.display()))?;
+            }
+            staged.push((file, original, content));
+        }
+
+        for (written, (file, _, content)) in staged.iter().enumerate() {
+            if let Err(e) = FileUtils::write_with_context(file, content, "modified file").await {
+                let mut unrestored = Vec::new();
+                for (file, original, _) in &staged[..written] {
+                    if FileUtils::write_with_context(file, original, "restored file").await.is_err() {
+                        unrestored.push(file.display().to_string());
+                    }
+                }
+                let error = if unrestored.is_empty() {
+                    format!("{e:#}; no files were changed")
+                } else {
+                    format!("{e:#}; could not restore {}", unrestored.join(", "))
+                };
+                return Ok(TransactionResult::failed(error));
+            }
+        }
+
+        let timestamp = chrono::Utc::now();
+        let (modified_files, backups) = staged
+            .into_iter()
+            .map(|(file, original_content, _)| {
+                let backup = FileBackup {
+                    file_path: file.clone(),
+                    original_content,
+                    timestamp,
+                };
+                (file, backup)
+            })
+            .unzip();
+        Ok(TransactionResult {
+            success: true,
+            modified_files,
+            error: None,
+            backups,
+        })
+    }
+
     /// Apply multiple fixes, stopping on first error
     pub async fn apply_fixes(&self, edits: &[FixEdit]) -> Result<Vec<FixResult>> {
         let mut results = Vec::new();
@@ -172,7 +283,7 @@ impl FixApplicationEngine {
     }
 
     /// Apply edit to file content
-    fn apply_edit_to_content(&self, content: &str, edit: &FixEdit) -> Result<String> {
+    pub(crate) fn apply_edit_to_content(&self, content: &str, edit: &FixEdit) -> Result<String> {
         let lines: Vec<&str> = content.lines().collect();
 
         // Validate range
@@ -279,7 +390,7 @@ impl FixEdit {
 mod tests {
     use super::*;
     use tempfile::NamedTempFile;
-    use tokio;
+    use tokio::fs;
     use crate::core::types::{Position, Range};
 
     #[tokio::test]
@@ -354,4 +465,28 @@ mod tests {
         let new_content = fs::read_to_string(&file_path).await.unwrap();
         assert_eq!(new_content, "function test() {\n    console.log(x);\n}\n");
     }
+
+    #[tokio::test]
+    async fn test_oversized_file_is_skipped() {
+        let engine = FixApplicationEngine::new().with_file_guard(FileGuard::with_max_bytes(8));
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let file_path = temp_file.path().to_path_buf();
+        fs::write(&file_path, "let value = 1;\n").await.unwrap();
+
+        let edit = FixEdit::from_lsp_text_edit(
+            file_path.clone(),
+            Range {
+                start: Position { line: 0, character: 4 },
+                end: Position { line: 0, character: 9 },
+            },
+            "v".to_string(),
+        );
+
+        let result = engine.apply_fix(&edit).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().starts_with("File skipped: too large"));
+        assert_eq!(engine.skipped_files().len(), 1);
+        assert_eq!(fs::read_to_string(&file_path).await.unwrap(), "let value = 1;\n");
+    }
 }
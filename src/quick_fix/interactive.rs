@@ -0,0 +1,603 @@
+//! Reviewing fixes one at a time
+//!
+//! `quick-fix apply` applies everything at or above a confidence threshold.
+//! `quick-fix interactive` walks through the candidates instead, best first:
+//! each fix is shown with its colored diff and confidence and can be
+//! accepted, skipped, or edited before it is applied. [`FixReview`] holds the
+//! decisions and knows nothing about terminals; [`FixReviewer`] draws it
+//! with ratatui.
+
+use super::suggestions::unified_diff;
+use super::{FixApplicationEngine, FixEdit, FixOutcome, RankedFix};
+use crate::core::Diagnostic;
+use anyhow::{anyhow, Result};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::{Frame, Terminal};
+use std::io::Stdout;
+
+/// A diagnostic and the fix proposed for it
+#[derive(Debug, Clone)]
+pub struct FixCandidate {
+    pub diagnostic: Diagnostic,
+    /// Always carries an edit
+    pub fix: RankedFix,
+}
+
+impl FixCandidate {
+    /// Candidate for the fix's edit, or `None` if it has no edit to apply
+    pub fn new(diagnostic: Diagnostic, fix: RankedFix) -> Option<Self> {
+        fix.edit.as_ref()?;
+        Some(Self { diagnostic, fix })
+    }
+
+    pub fn edit(&self) -> &FixEdit {
+        self.fix.edit.as_ref().expect("candidates carry an edit")
+    }
+}
+
+/// What the reviewer decided for a candidate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixDecision {
+    Pending,
+    Accepted,
+    /// Accepted with replacement text changed by the reviewer
+    Edited,
+    Skipped,
+}
+
+impl FixDecision {
+    /// Outcome recorded for the acceptance statistics, if the candidate was decided
+    pub fn outcome(self) -> Option<FixOutcome> {
+        match self {
+            FixDecision::Pending => None,
+            FixDecision::Accepted => Some(FixOutcome::Accepted),
+            FixDecision::Edited => Some(FixOutcome::Modified),
+            FixDecision::Skipped => Some(FixOutcome::Rejected),
+        }
+    }
+}
+
+/// Candidates with a decision each and a cursor
+#[derive(Debug, Clone)]
+pub struct FixReview {
+    candidates: Vec<FixCandidate>,
+    decisions: Vec<FixDecision>,
+    current: usize,
+}
+
+impl FixReview {
+    pub fn new(candidates: Vec<FixCandidate>) -> Self {
+        let decisions = vec![FixDecision::Pending; candidates.len()];
+        Self {
+            candidates,
+            decisions,
+            current: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.candidates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.candidates.is_empty()
+    }
+
+    /// Zero-based index of the candidate under review; equals `len()` once past the last one
+    pub fn position(&self) -> usize {
+        self.current
+    }
+
+    /// Candidate under review and its decision so far
+    pub fn current(&self) -> Option<(&FixCandidate, FixDecision)> {
+        let candidate = self.candidates.get(self.current)?;
+        Some((candidate, self.decisions[self.current]))
+    }
+
+    /// Decide the current candidate and move to the next
+    pub fn decide(&mut self, decision: FixDecision) {
+        if let Some(slot) = self.decisions.get_mut(self.current) {
+            *slot = decision;
+            self.current += 1;
+        }
+    }
+
+    /// Replace the current fix's text, accept it and move to the next
+    ///
+    /// The diff is recomputed against `source`, the current file content.
+    pub fn edit(&mut self, new_text: String, source: Option<&str>) {
+        let Some(candidate) = self.candidates.get_mut(self.current) else {
+            return;
+        };
+        let edit = candidate.fix.edit.as_mut().expect("candidates carry an edit");
+        edit.new_text = new_text;
+        candidate.fix.diff = source.and_then(|source| {
+            let patched = FixApplicationEngine::new().apply_edit_to_content(source, edit).ok()?;
+            unified_diff(&candidate.diagnostic.file, source, &patched)
+        });
+        self.decide(FixDecision::Edited);
+    }
+
+    /// Return to the previous candidate, keeping its decision until it is changed
+    pub fn back(&mut self) {
+        self.current = self.current.saturating_sub(1);
+    }
+
+    /// Accept every undecided candidate at or above `threshold` and finish
+    pub fn accept_remaining(&mut self, threshold: f32) {
+        for (candidate, decision) in self.candidates.iter().zip(self.decisions.iter_mut()) {
+            if *decision == FixDecision::Pending && candidate.fix.confidence >= threshold {
+                *decision = FixDecision::Accepted;
+            }
+        }
+        self.current = self.candidates.len();
+    }
+
+    pub fn count(&self, decision: FixDecision) -> usize {
+        self.decisions.iter().filter(|&&d| d == decision).count()
+    }
+
+    /// Candidates with their decisions
+    pub fn decisions(&self) -> impl Iterator<Item = (&FixCandidate, FixDecision)> {
+        self.candidates.iter().zip(self.decisions.iter().copied())
+    }
+
+    /// Edits to apply, last position first within each file so earlier edits don't shift later ones
+    ///
+    /// An edit overlapping one that comes later in its file is left out.
+    pub fn edits(&self) -> Vec<FixEdit> {
+        let mut edits: Vec<FixEdit> = self
+            .decisions()
+            .filter(|(_, decision)| matches!(decision, FixDecision::Accepted | FixDecision::Edited))
+            .map(|(candidate, _)| candidate.edit().clone())
+            .collect();
+        edits.sort_by(|a, b| {
+            a.file_path
+                .cmp(&b.file_path)
+                .then_with(|| position(b, false).cmp(&position(a, false)))
+        });
+
+        let mut kept: Vec<FixEdit> = Vec::with_capacity(edits.len());
+        for edit in edits {
+            let overlaps = kept
+                .last()
+                .is_some_and(|later| later.file_path == edit.file_path && position(&edit, true) > position(later, false));
+            if !overlaps {
+                kept.push(edit);
+            }
+        }
+        kept
+    }
+}
+
+fn position(edit: &FixEdit, end: bool) -> (u32, u32) {
+    let at = if end { &edit.range.end } else { &edit.range.start };
+    (at.line, at.character)
+}
+
+/// Full-screen review of a [`FixReview`]
+///
+/// The candidates are listed on the left with their confidence and
+/// decision, and the diff of the one under review is previewed on the right.
+pub struct FixReviewer {
+    review: FixReview,
+    /// Confidence at which fixes would be applied automatically
+    threshold: f32,
+}
+
+impl FixReviewer {
+    pub fn new(review: FixReview, threshold: f32) -> Self {
+        Self { review, threshold }
+    }
+
+    /// Run until every candidate is reviewed or the reviewer finishes early
+    ///
+    /// `a` (or Enter) accepts, `s` skips, `e` edits the replacement text in
+    /// `$EDITOR`, `b` goes back, `A` accepts the rest at or above the
+    /// threshold and `q` finishes. Returns `None` when the review is
+    /// abandoned with Ctrl-C, in which case nothing should be applied.
+    pub fn run(mut self) -> Result<Option<FixReview>> {
+        let mut terminal = ReviewTerminal::enter()?;
+        let mut scroll = 0u16;
+        let mut status = String::new();
+
+        loop {
+            terminal.terminal.draw(|frame| self.render(frame, scroll, &status))?;
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind == KeyEventKind::Release {
+                continue;
+            }
+            if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                return Ok(None);
+            }
+
+            status.clear();
+            let reviewing = self.review.current().is_some();
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => break,
+                KeyCode::Enter if !reviewing => break,
+                KeyCode::Char('a') | KeyCode::Char('y') | KeyCode::Enter => self.review.decide(FixDecision::Accepted),
+                KeyCode::Char('s') | KeyCode::Char('n') => self.review.decide(FixDecision::Skipped),
+                KeyCode::Char('e') if reviewing => status = self.edit(&mut terminal),
+                KeyCode::Char('b') | KeyCode::Left => self.review.back(),
+                KeyCode::Char('A') => self.review.accept_remaining(self.threshold),
+                KeyCode::Char('j') | KeyCode::Down => scroll = scroll.saturating_add(1),
+                KeyCode::Char('k') | KeyCode::Up => scroll = scroll.saturating_sub(1),
+                _ => continue,
+            }
+            if matches!(key.code, KeyCode::Char('j' | 'k') | KeyCode::Down | KeyCode::Up) {
+                continue;
+            }
+            scroll = 0;
+        }
+        Ok(Some(self.review))
+    }
+
+    /// Header, candidate list, diff preview and key help, with `scroll` lines of the diff skipped
+    fn render(&self, frame: &mut Frame, scroll: u16, status: &str) {
+        let review = &self.review;
+        let rows = Layout::vertical([Constraint::Length(1), Constraint::Min(1), Constraint::Length(1)])
+            .split(frame.size());
+        let header = Line::from(vec![
+            Span::styled(
+                format!("Quick fix {}/{}", (review.position() + 1).min(review.len()), review.len()),
+                Style::new().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!(
+                "  {} accepted, {} edited, {} skipped",
+                review.count(FixDecision::Accepted),
+                review.count(FixDecision::Edited),
+                review.count(FixDecision::Skipped),
+            )),
+        ]);
+        frame.render_widget(Paragraph::new(header), rows[0]);
+
+        let columns = Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).split(rows[1]);
+        let items: Vec<ListItem> = review
+            .decisions()
+            .map(|(candidate, decision)| {
+                let mark = match decision {
+                    FixDecision::Pending => " ",
+                    FixDecision::Accepted => "✓",
+                    FixDecision::Edited => "✎",
+                    FixDecision::Skipped => "✗",
+                };
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{mark} ")),
+                    self.confidence(candidate.fix.confidence),
+                    Span::raw(format!(
+                        " {}:{} {}",
+                        candidate.diagnostic.file,
+                        candidate.diagnostic.range.start.line + 1,
+                        candidate.fix.title
+                    )),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title("Fixes"))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        let mut selected = ListState::default().with_selected(review.current().map(|_| review.position()));
+        frame.render_stateful_widget(list, columns[0], &mut selected);
+
+        let preview = match review.current() {
+            Some((candidate, decision)) => self.preview(candidate, decision),
+            None => vec![
+                Line::raw(format!("All fixes reviewed; {} will be applied.", review.edits().len())),
+                Line::raw(""),
+                Line::styled(
+                    "Enter/q apply   b back   Ctrl-C abort without applying",
+                    Style::new().add_modifier(Modifier::DIM),
+                ),
+            ],
+        };
+        let preview = Paragraph::new(preview)
+            .block(Block::default().borders(Borders::ALL).title("Diff"))
+            .scroll((scroll, 0));
+        frame.render_widget(preview, columns[1]);
+
+        let footer = if status.is_empty() {
+            Line::styled(
+                "a accept   s skip   e edit   b back   A accept rest ≥ threshold   q finish   j/k scroll",
+                Style::new().add_modifier(Modifier::DIM),
+            )
+        } else {
+            Line::styled(status.to_string(), Style::new().fg(Color::Yellow))
+        };
+        frame.render_widget(Paragraph::new(footer), rows[2]);
+    }
+
+    /// The diagnostic, the fix and its diff
+    fn preview(&self, candidate: &FixCandidate, decision: FixDecision) -> Vec<Line<'static>> {
+        let diagnostic = &candidate.diagnostic;
+        let mut lines = vec![
+            Line::raw(format!(
+                "{}:{}:{}  {} {}",
+                diagnostic.file,
+                diagnostic.range.start.line + 1,
+                diagnostic.range.start.character + 1,
+                diagnostic.severity.to_string().to_lowercase(),
+                diagnostic.message
+            )),
+            Line::from(vec![
+                Span::raw(format!("Fix: {}  confidence ", candidate.fix.title)),
+                self.confidence(candidate.fix.confidence),
+                Span::raw(format!(
+                    " (auto-apply at {:.2}){}",
+                    self.threshold,
+                    match decision {
+                        FixDecision::Pending => String::new(),
+                        decided => format!("  [{decided:?}]").to_lowercase(),
+                    }
+                )),
+            ]),
+            Line::raw(""),
+        ];
+        match &candidate.fix.diff {
+            Some(diff) => lines.extend(diff.lines().map(diff_line)),
+            None => lines.extend(
+                candidate
+                    .edit()
+                    .new_text
+                    .lines()
+                    .map(|line| Line::styled(format!("+{line}"), Style::new().fg(Color::Green))),
+            ),
+        }
+        lines
+    }
+
+    /// Confidence colored by how it compares to the threshold
+    fn confidence(&self, confidence: f32) -> Span<'static> {
+        let color = if confidence >= self.threshold {
+            Color::Green
+        } else if confidence >= self.threshold * 0.7 {
+            Color::Yellow
+        } else {
+            Color::Red
+        };
+        Span::styled(format!("{confidence:.2}"), Style::new().fg(color))
+    }
+
+    /// Edit the current fix's replacement text in `$VISUAL` or `$EDITOR`, returning a status line
+    fn edit(&mut self, terminal: &mut ReviewTerminal) -> String {
+        let Some((candidate, _)) = self.review.current() else {
+            return String::new();
+        };
+        let edit = candidate.edit();
+        let extension = edit.file_path.extension().and_then(|e| e.to_str()).unwrap_or("txt");
+        let file = std::env::temp_dir().join(format!("lspbridge-fix-{}.{extension}", std::process::id()));
+        if let Err(e) = std::fs::write(&file, &edit.new_text) {
+            return format!("Failed to write {}: {e}", file.display());
+        }
+
+        let editor = std::env::var("VISUAL")
+            .or_else(|_| std::env::var("EDITOR"))
+            .unwrap_or_else(|_| "vi".to_string());
+        let mut parts = editor.split_whitespace();
+        let Some(program) = parts.next() else {
+            return "$EDITOR is empty".to_string();
+        };
+        terminal.suspend();
+        let outcome = std::process::Command::new(program).args(parts).arg(&file).status();
+        if let Err(e) = terminal.resume() {
+            return format!("Failed to restore the terminal: {e}");
+        }
+        match outcome {
+            Ok(status) if status.success() => {}
+            Ok(status) => {
+                let _ = std::fs::remove_file(&file);
+                return format!("{program} exited with {status}; fix unchanged");
+            }
+            Err(e) => {
+                let _ = std::fs::remove_file(&file);
+                return format!("Failed to run {program}: {e}");
+            }
+        }
+
+        let text = std::fs::read_to_string(&file);
+        let _ = std::fs::remove_file(&file);
+        let Ok(mut text) = text else {
+            return "Edited text is unreadable; fix unchanged".to_string();
+        };
+        // Editors add a final newline the replacement didn't have
+        if !edit.new_text.ends_with('\n') && text.ends_with('\n') {
+            text.pop();
+        }
+        if text == edit.new_text {
+            return "Text unchanged; accept or skip the fix as is".to_string();
+        }
+        let source = std::fs::read_to_string(&edit.file_path).ok();
+        self.review.edit(text, source.as_deref());
+        String::new()
+    }
+}
+
+fn diff_line(line: &str) -> Line<'static> {
+    let style = if line.starts_with("+++") || line.starts_with("---") {
+        Style::new().add_modifier(Modifier::BOLD)
+    } else if line.starts_with('+') {
+        Style::new().fg(Color::Green)
+    } else if line.starts_with('-') {
+        Style::new().fg(Color::Red)
+    } else if line.starts_with("@@") {
+        Style::new().fg(Color::Cyan)
+    } else {
+        Style::new()
+    };
+    Line::styled(line.to_string(), style)
+}
+
+/// Raw mode on the alternate screen under a ratatui terminal, restored on drop
+struct ReviewTerminal {
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+    active: bool,
+}
+
+impl ReviewTerminal {
+    fn enter() -> Result<Self> {
+        if !atty::is(atty::Stream::Stdout) {
+            return Err(anyhow!("quick-fix interactive needs a terminal; use quick-fix apply --dry-run"));
+        }
+        let mut terminal = Self {
+            terminal: Terminal::new(CrosstermBackend::new(std::io::stdout()))?,
+            active: false,
+        };
+        terminal.resume()?;
+        Ok(terminal)
+    }
+
+    /// Hand the terminal back, e.g. to an editor
+    fn suspend(&mut self) {
+        if self.active {
+            let _ = self.terminal.show_cursor();
+            let _ = crossterm::execute!(std::io::stdout(), crossterm::terminal::LeaveAlternateScreen);
+            let _ = crossterm::terminal::disable_raw_mode();
+            self.active = false;
+        }
+    }
+
+    fn resume(&mut self) -> Result<()> {
+        crossterm::terminal::enable_raw_mode()?;
+        crossterm::execute!(std::io::stdout(), crossterm::terminal::EnterAlternateScreen)?;
+        self.terminal.hide_cursor()?;
+        // Whatever ran meanwhile drew over the screen ratatui remembers
+        self.terminal.clear()?;
+        self.active = true;
+        Ok(())
+    }
+}
+
+impl Drop for ReviewTerminal {
+    fn drop(&mut self) {
+        self.suspend();
+    }
+}
+
+/// Record what the reviewer did with each decided fix
+pub async fn record_outcomes(review: &FixReview, store: &super::AcceptanceStore) -> Result<()> {
+    for (candidate, decision) in review.decisions() {
+        let Some(outcome) = decision.outcome() else {
+            continue;
+        };
+        store
+            .record(&super::FixOutcomeReport {
+                fingerprint: candidate.fix.fingerprint.clone(),
+                outcome,
+                language: None,
+                file: Some(candidate.diagnostic.file.clone()),
+                confidence: Some(candidate.fix.confidence),
+            })
+            .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{DiagnosticSeverity, Position, Range};
+    use std::path::PathBuf;
+
+    fn candidate(file: &str, line: u32, confidence: f32) -> FixCandidate {
+        let range = Range {
+            start: Position { line, character: 4 },
+            end: Position { line, character: 8 },
+        };
+        let diagnostic = Diagnostic::new(
+            file.to_string(),
+            range.clone(),
+            DiagnosticSeverity::Error,
+            "use of moved value".to_string(),
+            "rustc".to_string(),
+        );
+        let fix = RankedFix {
+            diagnostic_id: diagnostic.id.clone(),
+            diagnostic_message: diagnostic.message.clone(),
+            fingerprint: "rust:E0382".to_string(),
+            title: "Clone the value".to_string(),
+            confidence,
+            is_automatic: true,
+            code_snippet: None,
+            prerequisites: Vec::new(),
+            edit: Some(FixEdit {
+                file_path: PathBuf::from(file),
+                range,
+                new_text: "name.clone()".to_string(),
+                description: None,
+            }),
+            diff: None,
+        };
+        FixCandidate::new(diagnostic, fix).unwrap()
+    }
+
+    #[test]
+    fn test_review_decisions_and_edit_order() {
+        let mut review = FixReview::new(vec![
+            candidate("a.rs", 1, 0.95),
+            candidate("a.rs", 9, 0.6),
+            candidate("b.rs", 3, 0.4),
+            candidate("b.rs", 5, 0.92),
+        ]);
+
+        review.decide(FixDecision::Accepted);
+        review.decide(FixDecision::Skipped);
+        review.back();
+        assert_eq!(review.current().unwrap().1, FixDecision::Skipped);
+        review.edit("name.to_owned()".to_string(), Some("fn f() {}\n"));
+        assert_eq!(review.position(), 2);
+        review.accept_remaining(0.9);
+        assert!(review.current().is_none());
+
+        assert_eq!(review.count(FixDecision::Accepted), 2);
+        assert_eq!(review.count(FixDecision::Edited), 1);
+        assert_eq!(review.count(FixDecision::Pending), 1);
+        let decisions: Vec<_> = review.decisions().map(|(_, decision)| decision.outcome()).collect();
+        assert_eq!(
+            decisions,
+            [Some(FixOutcome::Accepted), Some(FixOutcome::Modified), None, Some(FixOutcome::Accepted)]
+        );
+
+        // Bottom-up within each file
+        let edits: Vec<_> = review
+            .edits()
+            .into_iter()
+            .map(|edit| (edit.file_path.display().to_string(), edit.range.start.line, edit.new_text))
+            .collect();
+        assert_eq!(
+            edits,
+            [
+                ("a.rs".to_string(), 9, "name.to_owned()".to_string()),
+                ("a.rs".to_string(), 1, "name.clone()".to_string()),
+                ("b.rs".to_string(), 5, "name.clone()".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reviewer_lists_candidates_and_previews_the_diff() {
+        let mut first = candidate("a.rs", 1, 0.95);
+        first.fix.diff = Some("--- a.rs\n+++ a.rs\n@@ -2 +2 @@\n-name\n+name.clone()\n".to_string());
+        let reviewer = FixReviewer::new(FixReview::new(vec![first, candidate("b.rs", 3, 0.4)]), 0.9);
+
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(120, 12)).unwrap();
+        terminal.draw(|frame| reviewer.render(frame, 0, "")).unwrap();
+        let buffer = terminal.backend().buffer();
+        let screen: Vec<String> = (0..buffer.area.height)
+            .map(|y| (0..buffer.area.width).map(|x| buffer.get(x, y).symbol()).collect())
+            .collect();
+        let screen = screen.join("\n");
+
+        assert!(screen.contains("Quick fix 1/2"));
+        assert!(screen.contains("0.95 a.rs:2 Clone the value"));
+        assert!(screen.contains("0.40 b.rs:4 Clone the value"));
+        assert!(screen.contains("+name.clone()"));
+    }
+}
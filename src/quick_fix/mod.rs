@@ -1,12 +1,33 @@
+pub mod acceptance;
+pub mod calibration;
 pub mod confidence;
 pub mod engine;
+pub mod interactive;
+pub mod rename_impact;
 pub mod rollback;
+pub mod suggestions;
+pub mod transaction;
 pub mod verification;
+pub mod whatif;
 
-pub use confidence::{ConfidenceScore, ConfidenceThreshold, FixConfidenceScorer};
+pub use acceptance::{
+    fix_fingerprint, AcceptanceStats, AcceptanceStore, FixOutcome, FixOutcomeReport,
+};
+pub use calibration::{ConfidenceCalibration, IngestSummary, PatternCalibration};
+pub use confidence::{
+    ConfidenceExplanation, ConfidenceFactors, ConfidenceScore, ConfidenceThreshold, FactorContribution,
+    FixConfidenceScorer,
+};
 pub use engine::{FixApplicationEngine, FixEdit, FixResult};
+pub use interactive::{FixCandidate, FixDecision, FixReview, FixReviewer};
+pub use rename_impact::{FileImpact, RenameImpact, RenameImpactAnalyzer};
 pub use rollback::{RollbackManager, RollbackState};
-pub use verification::{FixVerifier, VerificationResult};
+pub use suggestions::{
+    FixSuggestionService, FixSuggestionsResponse, RankedFix, SuggestFixesRequest,
+};
+pub use transaction::{FixTransaction, TransactionOutcome, TransactionResult};
+pub use verification::{BuildStatus, FixVerifier, VerificationResult};
+pub use whatif::{CargoCheck, HealthMetrics, SandboxCheck, SandboxFix, StaticScanCheck, WhatIfAnalyzer, WhatIfReport};
 
 use clap::Subcommand;
 
@@ -15,12 +36,10 @@ use clap::Subcommand;
 pub enum QuickFixAction {
     /// Apply available quick fixes
     Apply {
-        /// Confidence threshold for auto-applying fixes (0.0-1.0)
-        #[arg(short = 't', long, default_value = "0.9")]
-        threshold: f64,
-        /// Only fix errors (skip warnings)
-        #[arg(long)]
-        errors_only: bool,
+        /// Confidence threshold for auto-applying fixes (0.0-1.0); defaults to the
+        /// threshold `quick-fix calibrate` recommends, or 0.9 before there is one
+        #[arg(short = 't', long)]
+        threshold: Option<f64>,
         /// Verify fixes pass tests
         #[arg(long)]
         verify_tests: bool,
@@ -33,9 +52,27 @@ pub enum QuickFixAction {
         /// Dry run - show what would be fixed
         #[arg(short, long)]
         dry_run: bool,
-        /// File pattern to fix (e.g. "*.rs")
+        /// Break each confidence score down by contributing factor
+        #[arg(long)]
+        explain: bool,
+        /// Which diagnostics to fix
+        #[command(flatten)]
+        filter: crate::cli::DiagnosticFilterArgs,
+    },
+    /// Review fixes one at a time, accepting, skipping or editing each
+    Interactive {
+        /// Leave out fixes below this confidence (0.0-1.0)
+        #[arg(long, default_value = "0.3")]
+        min_confidence: f32,
+        /// Confidence at which `apply` would use a fix; `A` accepts the rest at or above it
+        #[arg(short = 't', long, default_value = "0.9")]
+        threshold: f32,
+        /// Create backups before applying fixes
         #[arg(short, long)]
-        files: Option<String>,
+        backup: bool,
+        /// Which diagnostics to fix
+        #[command(flatten)]
+        filter: crate::cli::DiagnosticFilterArgs,
     },
     /// Rollback previously applied fixes
     Rollback {
@@ -48,11 +85,35 @@ pub enum QuickFixAction {
     },
     /// Analyze fix confidence scores
     Analyze {
-        /// Show detailed confidence factors
+        /// Show each score's factor breakdown (always included in JSON)
         #[arg(short, long)]
         detailed: bool,
         /// Output format
         #[arg(short, long, value_enum, default_value = "table")]
         format: crate::cli::OutputFormat,
     },
+    /// Calibrate fix confidence from the quality ratings in annotated training datasets
+    ///
+    /// Ratings are kept per diagnostic pattern and language in the data
+    /// directory; re-running on the same dataset replaces its earlier ratings.
+    Calibrate {
+        /// Datasets annotated with `ai-training annotate`
+        #[arg(required = true)]
+        datasets: Vec<std::path::PathBuf>,
+        /// Discard earlier calibration before ingesting
+        #[arg(long)]
+        reset: bool,
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: crate::cli::OutputFormat,
+    },
+    /// Report how often suggested fixes were accepted in editors
+    Stats {
+        /// Only report outcomes for this language
+        #[arg(short, long)]
+        language: Option<String>,
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "table")]
+        format: crate::cli::OutputFormat,
+    },
 }
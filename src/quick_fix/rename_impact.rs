@@ -0,0 +1,249 @@
+//! Impact reports for rename fixes
+//!
+//! A fix that renames a symbol touches every place the symbol is used, not
+//! just the line its diagnostic points at. Before such a fix is applied,
+//! [`RenameImpactAnalyzer`] looks the symbol up in the workspace
+//! [`SymbolIndex`] and reports the files and references a full rename would
+//! touch, which of them are tests, and whether the symbol is part of the
+//! public API, so reviewers see the blast radius in the preview.
+
+use super::engine::FixEdit;
+use crate::core::api_surface::{ApiSurfaceAnalyzer, ApiSurfaceInfo};
+use crate::core::symbol_index::{is_identifier_char, SymbolIndex};
+use crate::core::{Diagnostic, DiagnosticSeverity, Position, Range};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// References to the renamed symbol in one file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileImpact {
+    pub file: PathBuf,
+    /// Zero-based lines of the references
+    pub lines: Vec<u32>,
+    pub is_test: bool,
+}
+
+/// What renaming a symbol would touch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RenameImpact {
+    pub symbol: String,
+    pub new_name: String,
+    pub files: Vec<FileImpact>,
+    pub total_references: usize,
+    /// References from test code
+    pub test_references: usize,
+    /// The public item the symbol names, if it is exported
+    pub public_api: Option<ApiSurfaceInfo>,
+}
+
+impl RenameImpact {
+    pub fn test_files(&self) -> impl Iterator<Item = &FileImpact> {
+        self.files.iter().filter(|file| file.is_test)
+    }
+
+    /// Human-readable report for fix previews
+    pub fn render(&self) -> String {
+        let mut output = format!(
+            "Rename `{}` -> `{}`: {} reference(s) in {} file(s)\n",
+            self.symbol,
+            self.new_name,
+            self.total_references,
+            self.files.len()
+        );
+        for file in &self.files {
+            let lines: Vec<String> = file.lines.iter().map(|line| (line + 1).to_string()).collect();
+            output.push_str(&format!(
+                "  {}{}: line {}\n",
+                file.file.display(),
+                if file.is_test { " (test)" } else { "" },
+                lines.join(", ")
+            ));
+        }
+        let test_files = self.test_files().count();
+        if test_files > 0 {
+            output.push_str(&format!(
+                "  Tests: {} reference(s) in {} test file(s)\n",
+                self.test_references, test_files
+            ));
+        }
+        match &self.public_api {
+            Some(api) => output.push_str(&format!(
+                "  Public API: `{}` ({}) is exported; renaming it is a {} change\n",
+                api.item,
+                api.kind,
+                if api.impact.is_breaking() { "breaking" } else { "compatible" }
+            )),
+            None => output.push_str("  Public API: not exported\n"),
+        }
+        output
+    }
+}
+
+/// Builds [`RenameImpact`] reports from a workspace symbol index
+pub struct RenameImpactAnalyzer {
+    index: SymbolIndex,
+    api_surface: ApiSurfaceAnalyzer,
+}
+
+impl RenameImpactAnalyzer {
+    pub fn new(index: SymbolIndex) -> Self {
+        let api_surface = ApiSurfaceAnalyzer::new(index.root());
+        Self { index, api_surface }
+    }
+
+    /// Index the workspace at `root` and analyze renames against it
+    pub fn for_workspace(root: &Path) -> Result<Self> {
+        Ok(Self::new(SymbolIndex::build(root)?))
+    }
+
+    /// Impact of `edit` if it renames a symbol, reading the edited file
+    pub fn analyze(&self, edit: &FixEdit) -> Result<Option<RenameImpact>> {
+        let path = self.resolve(&edit.file_path);
+        let source = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Ok(self.analyze_source(edit, &source))
+    }
+
+    /// Impact of `edit` on `source` if it renames a symbol
+    pub fn analyze_source(&self, edit: &FixEdit, source: &str) -> Option<RenameImpact> {
+        let symbol = renamed_symbol(edit, source)?;
+        let references = self.index.references(&symbol);
+
+        let mut files: BTreeMap<&Path, FileImpact> = BTreeMap::new();
+        for occurrence in references {
+            files
+                .entry(&occurrence.file)
+                .or_insert_with(|| FileImpact {
+                    file: occurrence.file.clone(),
+                    lines: Vec::new(),
+                    is_test: occurrence.in_test,
+                })
+                .lines
+                .push(occurrence.line);
+        }
+
+        Some(RenameImpact {
+            public_api: self.public_api(&symbol),
+            new_name: edit.new_text.clone(),
+            total_references: references.len(),
+            test_references: references.iter().filter(|occurrence| occurrence.in_test).count(),
+            files: files.into_values().collect(),
+            symbol,
+        })
+    }
+
+    /// The exported item named `symbol`, checked at each of its non-test occurrences
+    fn public_api(&self, symbol: &str) -> Option<ApiSurfaceInfo> {
+        let mut sources: BTreeMap<&Path, Option<String>> = BTreeMap::new();
+        self.index
+            .references(symbol)
+            .iter()
+            .filter(|occurrence| !occurrence.in_test)
+            .find_map(|occurrence| {
+                let source = sources
+                    .entry(&occurrence.file)
+                    .or_insert_with(|| std::fs::read_to_string(self.resolve(&occurrence.file)).ok())
+                    .as_deref()?;
+                let position = Position {
+                    line: occurrence.line,
+                    character: occurrence.character,
+                };
+                let probe = Diagnostic::new(
+                    occurrence.file.to_string_lossy().to_string(),
+                    Range {
+                        start: position.clone(),
+                        end: position,
+                    },
+                    DiagnosticSeverity::Hint,
+                    format!("rename {symbol}"),
+                    "rename-impact".to_string(),
+                );
+                self.api_surface
+                    .analyze_source(&occurrence.file, source, &probe)
+                    .filter(|info| info.item == symbol)
+            })
+    }
+
+    fn resolve(&self, file: &Path) -> PathBuf {
+        if file.is_absolute() {
+            file.to_path_buf()
+        } else {
+            self.index.root().join(file)
+        }
+    }
+}
+
+/// The identifier an edit replaces, if the edit swaps one identifier for another
+pub fn renamed_symbol(edit: &FixEdit, source: &str) -> Option<String> {
+    let is_identifier = |text: &str| {
+        !text.is_empty()
+            && !text.starts_with(|c: char| c.is_ascii_digit())
+            && text.chars().all(is_identifier_char)
+    };
+
+    let range = &edit.range;
+    if range.start.line != range.end.line || !is_identifier(&edit.new_text) {
+        return None;
+    }
+    let line = source.lines().nth(range.start.line as usize)?;
+    let chars: Vec<char> = line.chars().collect();
+    let (start, end) = (range.start.character as usize, range.end.character as usize);
+    if start >= end || end > chars.len() {
+        return None;
+    }
+    // The range must cover a whole identifier, not part of a longer one
+    let bounded = (start == 0 || !is_identifier_char(chars[start - 1]))
+        && !chars.get(end).is_some_and(|c| is_identifier_char(*c));
+    let old: String = chars[start..end].iter().collect();
+    (bounded && is_identifier(&old) && old != edit.new_text).then_some(old)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(file: &str, line: u32, start: u32, end: u32, new_text: &str) -> FixEdit {
+        FixEdit {
+            file_path: PathBuf::from(file),
+            range: Range {
+                start: Position { line, character: start },
+                end: Position { line, character: end },
+            },
+            new_text: new_text.to_string(),
+            description: Some("Rename symbol".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_rename_impact_report() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let lib = "pub fn parse_cfg(input: &str) -> usize {\n    input.len()\n}\n";
+        let main = "fn main() {\n    let n = lsp::parse_cfg(\"x\");\n    println!(\"{}\", parse_cfg(\"y\") + n);\n}\n";
+        let test = "#[test]\nfn parses() {\n    assert_eq!(lsp::parse_cfg(\"ab\"), 2);\n}\n";
+        std::fs::create_dir_all(dir.path().join("src"))?;
+        std::fs::create_dir_all(dir.path().join("tests"))?;
+        std::fs::write(dir.path().join("src/lib.rs"), lib)?;
+        std::fs::write(dir.path().join("src/main.rs"), main)?;
+        std::fs::write(dir.path().join("tests/parse.rs"), test)?;
+
+        let analyzer = RenameImpactAnalyzer::for_workspace(dir.path())?;
+        let impact = analyzer.analyze(&edit("src/lib.rs", 0, 7, 16, "parse_config"))?.unwrap();
+
+        assert_eq!(impact.symbol, "parse_cfg");
+        assert_eq!(impact.total_references, 4);
+        assert_eq!(impact.test_references, 1);
+        assert_eq!(impact.files.len(), 3);
+        assert_eq!(impact.files[1].lines, vec![1, 2]);
+        assert_eq!(impact.test_files().count(), 1);
+        let api = impact.public_api.as_ref().unwrap();
+        assert!(api.impact.is_breaking());
+        assert!(impact.render().contains("tests/parse.rs (test): line 3"));
+
+        // Edits that don't replace a whole identifier aren't renames
+        assert!(analyzer.analyze(&edit("src/lib.rs", 0, 7, 12, "read"))?.is_none());
+        assert!(analyzer.analyze(&edit("src/lib.rs", 0, 7, 16, "a b"))?.is_none());
+        Ok(())
+    }
+}
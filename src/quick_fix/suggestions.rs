@@ -0,0 +1,447 @@
+//! Ranked fix suggestions for a cursor location
+//!
+//! Editor extensions ask for fixes at a file and range (a lightbulb request)
+//! and need an answer well under 100ms. [`FixSuggestionService`] runs the
+//! language analyzers over the diagnostics at that location, turns automatic
+//! suggestions into concrete [`FixEdit`]s with a unified diff, and ranks them
+//! by blending the analyzer's confidence with [`FixConfidenceScorer`].
+//!
+//! Both file contents and per-diagnostic suggestions are cached, keyed by the
+//! file's modification time, so repeated requests while the cursor moves
+//! around a file are served without re-reading or re-analysing anything.
+
+use crate::analyzers::{
+    FixSuggestion, JavaAnalyzer, KotlinAnalyzer, LanguageAnalyzer, RustAnalyzer, TypeScriptAnalyzer,
+};
+use crate::core::{Diagnostic, Position, Range};
+use crate::quick_fix::{fix_fingerprint, FixApplicationEngine, FixConfidenceScorer, FixEdit};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Instant, SystemTime};
+use utoipa::ToSchema;
+
+/// Lines of unchanged context around each diff hunk
+const DIFF_CONTEXT_LINES: usize = 2;
+
+/// Location an editor asks fixes for
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SuggestFixesRequest {
+    /// File the cursor is in, absolute or relative to the workspace
+    pub file: String,
+    /// Cursor position or selection
+    pub range: Range,
+}
+
+/// A fix suggestion ranked for display as an editor code action
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RankedFix {
+    /// Diagnostic the fix addresses
+    pub diagnostic_id: String,
+    pub diagnostic_message: String,
+    /// Kind of problem fixed; editors send it back when reporting the fix's outcome
+    pub fingerprint: String,
+    /// Short title for the code action
+    pub title: String,
+    /// Blended analyzer and scorer confidence (0.0 - 1.0)
+    pub confidence: f32,
+    /// Whether the fix can be applied without further input
+    pub is_automatic: bool,
+    pub code_snippet: Option<String>,
+    pub prerequisites: Vec<String>,
+    /// Concrete edit, when the suggestion maps onto the source
+    pub edit: Option<FixEdit>,
+    /// Unified diff of `edit` against the current file contents
+    pub diff: Option<String>,
+}
+
+/// Fixes available at a location, best first
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FixSuggestionsResponse {
+    pub file: String,
+    pub range: Range,
+    pub fixes: Vec<RankedFix>,
+    /// Time spent answering the request
+    pub elapsed_ms: u64,
+    /// Whether every suggestion came from the cache
+    pub cached: bool,
+}
+
+#[derive(Clone)]
+struct CachedSource {
+    modified: Option<SystemTime>,
+    content: Arc<String>,
+}
+
+#[derive(Clone)]
+struct CachedFixes {
+    modified: Option<SystemTime>,
+    fixes: Vec<RankedFix>,
+}
+
+/// Produces ranked, diff-backed fix suggestions from warm caches
+pub struct FixSuggestionService {
+    analyzers: Vec<Box<dyn LanguageAnalyzer>>,
+    scorer: FixConfidenceScorer,
+    engine: FixApplicationEngine,
+    sources: DashMap<PathBuf, CachedSource>,
+    fixes: DashMap<String, CachedFixes>,
+}
+
+impl Default for FixSuggestionService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FixSuggestionService {
+    pub fn new() -> Self {
+        Self {
+            analyzers: vec![
+                Box::new(RustAnalyzer::new()),
+                Box::new(TypeScriptAnalyzer::new()),
+                Box::new(JavaAnalyzer::new()),
+                Box::new(KotlinAnalyzer::new()),
+            ],
+            scorer: FixConfidenceScorer::new(),
+            engine: FixApplicationEngine::new(),
+            sources: DashMap::new(),
+            fixes: DashMap::new(),
+        }
+    }
+
+    /// Use a custom confidence scorer for ranking
+    pub fn with_scorer(mut self, scorer: FixConfidenceScorer) -> Self {
+        self.scorer = scorer;
+        self
+    }
+
+    /// Compute and cache suggestions ahead of editor requests
+    pub fn warm(&self, diagnostics: &[Diagnostic]) {
+        for diagnostic in diagnostics {
+            self.fixes_for(diagnostic);
+        }
+    }
+
+    /// Ranked fixes for a single diagnostic, best first
+    pub fn fixes(&self, diagnostic: &Diagnostic) -> Vec<RankedFix> {
+        let (mut fixes, _) = self.fixes_for(diagnostic);
+        fixes.sort_by(|a, b| {
+            b.confidence
+                .partial_cmp(&a.confidence)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        fixes
+    }
+
+    /// Drop all cached sources and suggestions
+    pub fn clear(&self) {
+        self.sources.clear();
+        self.fixes.clear();
+    }
+
+    /// Ranked fixes for the diagnostics in `file` that overlap `range`
+    pub fn suggest_fixes(
+        &self,
+        diagnostics: &[Diagnostic],
+        file: &str,
+        range: &Range,
+    ) -> FixSuggestionsResponse {
+        let started = Instant::now();
+        let mut cached = true;
+        let mut fixes = Vec::new();
+
+        for diagnostic in diagnostics
+            .iter()
+            .filter(|d| same_file(&d.file, file) && overlaps(&d.range, range))
+        {
+            let (diagnostic_fixes, hit) = self.fixes_for(diagnostic);
+            cached &= hit;
+            fixes.extend(diagnostic_fixes);
+        }
+
+        fixes.sort_by(|a, b| {
+            b.confidence
+                .partial_cmp(&a.confidence)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(b.edit.is_some().cmp(&a.edit.is_some()))
+        });
+
+        FixSuggestionsResponse {
+            file: file.to_string(),
+            range: range.clone(),
+            fixes,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+            cached,
+        }
+    }
+
+    /// Suggestions for one diagnostic and whether they came from the cache
+    fn fixes_for(&self, diagnostic: &Diagnostic) -> (Vec<RankedFix>, bool) {
+        let path = PathBuf::from(&diagnostic.file);
+        let modified = modified_time(&path);
+
+        if let Some(entry) = self.fixes.get(&diagnostic.id) {
+            if entry.modified == modified {
+                return (entry.fixes.clone(), true);
+            }
+        }
+
+        let suggestions: Vec<FixSuggestion> = self
+            .analyzers
+            .iter()
+            .filter(|a| a.can_analyze(diagnostic))
+            .flat_map(|a| a.suggest_fix(diagnostic, None))
+            .collect();
+
+        let source = if suggestions.iter().any(|s| s.is_automatic) {
+            self.source(&path, modified)
+        } else {
+            None
+        };
+
+        let fixes: Vec<RankedFix> = suggestions
+            .into_iter()
+            .map(|suggestion| self.rank(diagnostic, suggestion, source.as_ref().map(|s| s.as_str())))
+            .collect();
+
+        self.fixes.insert(
+            diagnostic.id.clone(),
+            CachedFixes {
+                modified,
+                fixes: fixes.clone(),
+            },
+        );
+        (fixes, false)
+    }
+
+    fn rank(
+        &self,
+        diagnostic: &Diagnostic,
+        suggestion: FixSuggestion,
+        source: Option<&str>,
+    ) -> RankedFix {
+        let edit = match (suggestion.is_automatic, source) {
+            (true, Some(source)) => edit_for(diagnostic, &suggestion, source),
+            _ => None,
+        };
+        let diff = match (&edit, source) {
+            (Some(edit), Some(source)) => self
+                .engine
+                .apply_edit_to_content(source, edit)
+                .ok()
+                .and_then(|patched| unified_diff(&diagnostic.file, source, &patched)),
+            _ => None,
+        };
+
+        let fix_text = edit
+            .as_ref()
+            .map(|e| e.new_text.as_str())
+            .or(suggestion.code_snippet.as_deref())
+            .unwrap_or_default();
+        let (score, _factors) = self.scorer.score_fix(diagnostic, fix_text, false);
+        // Fixes we can't turn into an edit are still useful hints, just ranked lower
+        let applicable = if edit.is_some() { 1.0 } else { 0.8 };
+        let confidence = ((suggestion.confidence + score.value()) / 2.0 * applicable).clamp(0.0, 1.0);
+
+        RankedFix {
+            diagnostic_id: diagnostic.id.clone(),
+            diagnostic_message: diagnostic.message.clone(),
+            fingerprint: fix_fingerprint(diagnostic),
+            title: suggestion.description,
+            confidence,
+            is_automatic: suggestion.is_automatic && edit.is_some(),
+            code_snippet: suggestion.code_snippet,
+            prerequisites: suggestion.prerequisites,
+            edit,
+            diff,
+        }
+    }
+
+    fn source(&self, path: &Path, modified: Option<SystemTime>) -> Option<Arc<String>> {
+        if let Some(entry) = self.sources.get(path) {
+            if entry.modified == modified {
+                return Some(entry.content.clone());
+            }
+        }
+        let content = Arc::new(std::fs::read_to_string(path).ok()?);
+        self.sources.insert(
+            path.to_path_buf(),
+            CachedSource {
+                modified,
+                content: content.clone(),
+            },
+        );
+        Some(content)
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn same_file(diagnostic_file: &str, requested: &str) -> bool {
+    let diagnostic_file = Path::new(diagnostic_file);
+    diagnostic_file == Path::new(requested) || diagnostic_file.ends_with(requested)
+}
+
+fn overlaps(a: &Range, b: &Range) -> bool {
+    let key = |p: &Position| (p.line, p.character);
+    key(&a.start) <= key(&b.end) && key(&b.start) <= key(&a.end)
+}
+
+/// Map an analyzer snippet onto the diagnostic's source
+///
+/// Snippets are templates: a leading `.` is a method call appended to the
+/// flagged expression, `value` stands for the flagged text, and `use` /
+/// `import` lines go at the top of the file.
+fn edit_for(diagnostic: &Diagnostic, suggestion: &FixSuggestion, source: &str) -> Option<FixEdit> {
+    let snippet = suggestion.code_snippet.as_deref()?;
+    let range = &diagnostic.range;
+    let file_path = PathBuf::from(&diagnostic.file);
+
+    let (range, new_text) = if snippet.starts_with("use ") || snippet.starts_with("import ") {
+        let top = Position { line: 0, character: 0 };
+        (
+            Range {
+                start: top.clone(),
+                end: top,
+            },
+            format!("{snippet}\n"),
+        )
+    } else if range.start.line != range.end.line {
+        return None;
+    } else if let Some(call) = snippet.strip_prefix('.') {
+        (
+            Range {
+                start: range.end.clone(),
+                end: range.end.clone(),
+            },
+            format!(".{call}"),
+        )
+    } else if snippet.contains("value") {
+        let line = source.lines().nth(range.start.line as usize)?;
+        let flagged = line.get(range.start.character as usize..range.end.character as usize)?;
+        if flagged.is_empty() {
+            return None;
+        }
+        (range.clone(), snippet.replace("value", flagged))
+    } else {
+        return None;
+    };
+
+    Some(
+        FixEdit::from_lsp_text_edit(file_path, range, new_text)
+            .with_description(suggestion.description.clone()),
+    )
+}
+
+/// Single-hunk unified diff between two versions of a file
+pub(crate) fn unified_diff(file: &str, before: &str, after: &str) -> Option<String> {
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    if prefix == old.len() && prefix == new.len() {
+        return None;
+    }
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let start = prefix.saturating_sub(DIFF_CONTEXT_LINES);
+    let old_end = (old.len() - suffix + DIFF_CONTEXT_LINES).min(old.len());
+    let new_end = (new.len() - suffix + DIFF_CONTEXT_LINES).min(new.len());
+
+    let mut diff = vec![
+        format!("--- a/{file}"),
+        format!("+++ b/{file}"),
+        format!(
+            "@@ -{},{} +{},{} @@",
+            start + 1,
+            old_end - start,
+            start + 1,
+            new_end - start
+        ),
+    ];
+    diff.extend(old[start..prefix].iter().map(|l| format!(" {l}")));
+    diff.extend(old[prefix..old.len() - suffix].iter().map(|l| format!("-{l}")));
+    diff.extend(new[prefix..new.len() - suffix].iter().map(|l| format!("+{l}")));
+    diff.extend(old[old.len() - suffix..old_end].iter().map(|l| format!(" {l}")));
+    Some(diff.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::DiagnosticSeverity;
+    use std::io::Write;
+
+    fn range(line: u32, start: u32, end: u32) -> Range {
+        Range {
+            start: Position { line, character: start },
+            end: Position { line, character: end },
+        }
+    }
+
+    #[test]
+    fn test_suggestions_at_cursor_are_ranked_with_diffs() {
+        let mut file = tempfile::Builder::new().suffix(".rs").tempfile().unwrap();
+        write!(file, "fn main() {{\n    let s = name;\n    takes(s);\n}}\n").unwrap();
+        let path = file.path().to_string_lossy().to_string();
+
+        let mut diagnostic = Diagnostic::new(
+            path.clone(),
+            range(1, 12, 16),
+            DiagnosticSeverity::Error,
+            "mismatched types: expected `String`, found `&str`".to_string(),
+            "rustc".to_string(),
+        );
+        diagnostic.code = Some("E0308".to_string());
+        let elsewhere = Diagnostic::new(
+            path.clone(),
+            range(3, 0, 1),
+            DiagnosticSeverity::Error,
+            "mismatched types: expected `String`, found `&str`".to_string(),
+            "rustc".to_string(),
+        );
+        let diagnostics = vec![diagnostic.clone(), elsewhere];
+
+        let service = FixSuggestionService::new();
+        let response = service.suggest_fixes(&diagnostics, &path, &range(1, 14, 14));
+
+        assert!(!response.cached);
+        assert!(!response.fixes.is_empty());
+        assert!(response.fixes.iter().all(|f| f.diagnostic_id == diagnostic.id));
+        assert!(response
+            .fixes
+            .windows(2)
+            .all(|w| w[0].confidence >= w[1].confidence));
+
+        let best = &response.fixes[0];
+        let edit = best.edit.as_ref().expect("top fix has an edit");
+        assert_eq!(edit.new_text, ".to_string()");
+        let diff = best.diff.as_deref().unwrap();
+        assert!(diff.contains("-    let s = name;"));
+        assert!(diff.contains("+    let s = name.to_string();"));
+
+        // The second request is served from cache
+        let again = service.suggest_fixes(&diagnostics, &path, &range(1, 14, 14));
+        assert!(again.cached);
+        assert_eq!(again.fixes.len(), response.fixes.len());
+    }
+
+    #[test]
+    fn test_unified_diff_hunk() {
+        let diff = unified_diff("a.rs", "a\nb\nc\nd\ne\nf\n", "a\nb\nc\nX\ne\nf\n").unwrap();
+        assert_eq!(
+            diff,
+            "--- a/a.rs\n+++ b/a.rs\n@@ -2,5 +2,5 @@\n b\n c\n-d\n+X\n e\n f"
+        );
+        assert!(unified_diff("a.rs", "same\n", "same\n").is_none());
+    }
+}
@@ -0,0 +1,258 @@
+//! Fixes spanning several files, applied as one unit
+//!
+//! A [`FixEdit`] targets a single file, but renames, moved imports and
+//! signature changes need edits in several at once; applying them one by
+//! one can leave the workspace half-fixed when one fails. A
+//! [`FixTransaction`] groups those edits.
+//! [`FixApplicationEngine::apply_transaction`] stages every file in memory
+//! before writing any, and restores the ones already written if a write
+//! fails. [`FixTransaction::apply`] also records all backups in a single
+//! rollback session, builds once for the whole transaction, and rolls the
+//! session back when the build breaks.
+
+use crate::quick_fix::engine::{FileBackup, FixApplicationEngine, FixEdit};
+use crate::quick_fix::rollback::RollbackManager;
+use crate::quick_fix::verification::{BuildStatus, FixVerifier};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Edits across one or more files that succeed or fail together
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixTransaction {
+    /// What the transaction fixes, used as the rollback session description
+    pub description: String,
+    /// Edits in any order; edits to the same file must not overlap
+    pub edits: Vec<FixEdit>,
+}
+
+/// Result of applying a transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionResult {
+    /// Whether every edit was applied; when false, no file was changed
+    pub success: bool,
+    /// Files that were modified
+    pub modified_files: Vec<PathBuf>,
+    /// Error message if failed
+    pub error: Option<String>,
+    /// Original content of every modified file
+    pub backups: Vec<FileBackup>,
+}
+
+/// What [`FixTransaction::apply`] did
+#[derive(Debug, Clone)]
+pub struct TransactionOutcome {
+    pub result: TransactionResult,
+    /// Rollback session holding the transaction's backups, if it was applied
+    pub session_id: Option<String>,
+    /// Build run after applying, if a verifier checked it
+    pub build_status: Option<BuildStatus>,
+    /// Whether the transaction was undone because its build failed
+    pub rolled_back: bool,
+}
+
+impl FixTransaction {
+    pub fn new(description: impl Into<String>) -> Self {
+        Self {
+            description: description.into(),
+            edits: Vec::new(),
+        }
+    }
+
+    pub fn with_edit(mut self, edit: FixEdit) -> Self {
+        self.edits.push(edit);
+        self
+    }
+
+    pub fn push(&mut self, edit: FixEdit) {
+        self.edits.push(edit);
+    }
+
+    pub fn len(&self) -> usize {
+        self.edits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Files the transaction edits, in order of their first edit
+    pub fn files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = Vec::new();
+        for edit in &self.edits {
+            if !files.contains(&edit.file_path) {
+                files.push(edit.file_path.clone());
+            }
+        }
+        files
+    }
+
+    /// Apply the transaction, save its rollback session and verify the build once
+    ///
+    /// When the build fails, or can't be run, the session is rolled back
+    /// before returning.
+    pub async fn apply(
+        &self,
+        engine: &FixApplicationEngine,
+        rollback: &mut RollbackManager,
+        verifier: Option<&FixVerifier>,
+    ) -> Result<TransactionOutcome> {
+        let result = engine.apply_transaction(self).await?;
+        if !result.success {
+            return Ok(TransactionOutcome {
+                result,
+                session_id: None,
+                build_status: None,
+                rolled_back: false,
+            });
+        }
+
+        let state = RollbackManager::create_state(result.backups.clone(), self.description.clone());
+        let session_id = state.session_id.clone();
+        rollback.save_state(state).await?;
+
+        let Some(verifier) = verifier else {
+            return Ok(TransactionOutcome {
+                result,
+                session_id: Some(session_id),
+                build_status: None,
+                rolled_back: false,
+            });
+        };
+
+        let build_status = match verifier.verify_transaction(&result).await {
+            Ok(build_status) => build_status,
+            Err(e) => {
+                rollback.rollback(&session_id).await?;
+                return Err(e.context(format!("Rolled back session {session_id}")));
+            }
+        };
+        let rolled_back = !build_status.success;
+        if rolled_back {
+            rollback
+                .rollback(&session_id)
+                .await
+                .with_context(|| format!("Build failed and session {session_id} could not be rolled back"))?;
+        }
+
+        Ok(TransactionOutcome {
+            result,
+            session_id: Some(session_id),
+            build_status: Some(build_status),
+            rolled_back,
+        })
+    }
+}
+
+impl TransactionResult {
+    pub(crate) fn failed(error: String) -> Self {
+        Self {
+            success: false,
+            modified_files: vec![],
+            error: Some(error),
+            backups: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{Position, Range};
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    fn edit(file: &std::path::Path, line: u32, start: u32, end: u32, new_text: &str) -> FixEdit {
+        FixEdit::from_lsp_text_edit(
+            file.to_path_buf(),
+            Range {
+                start: Position { line, character: start },
+                end: Position { line, character: end },
+            },
+            new_text.to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_rename_across_files_rolls_back_as_one_session() {
+        let workspace = TempDir::new().unwrap();
+        let state_dir = TempDir::new().unwrap();
+        let lib = workspace.path().join("lib.rs");
+        let main = workspace.path().join("main.rs");
+        fs::write(&lib, "pub fn load_cfg() {}\n").await.unwrap();
+        fs::write(&main, "fn main() { load_cfg(); load_cfg(); }\n").await.unwrap();
+
+        let transaction = FixTransaction::new("Rename load_cfg to load_config")
+            .with_edit(edit(&main, 0, 12, 20, "load_config"))
+            .with_edit(edit(&lib, 0, 7, 15, "load_config"))
+            .with_edit(edit(&main, 0, 24, 32, "load_config"));
+        assert_eq!(transaction.files(), vec![main.clone(), lib.clone()]);
+
+        let mut rollback = RollbackManager::new(state_dir.path().to_path_buf());
+        rollback.init().await.unwrap();
+        let outcome = transaction
+            .apply(&FixApplicationEngine::new(), &mut rollback, None)
+            .await
+            .unwrap();
+        assert!(outcome.result.success);
+        assert_eq!(outcome.result.backups.len(), 2);
+        assert_eq!(fs::read_to_string(&lib).await.unwrap(), "pub fn load_config() {}\n");
+        assert_eq!(
+            fs::read_to_string(&main).await.unwrap(),
+            "fn main() { load_config(); load_config(); }\n"
+        );
+
+        rollback.rollback(&outcome.session_id.unwrap()).await.unwrap();
+        assert_eq!(fs::read_to_string(&lib).await.unwrap(), "pub fn load_cfg() {}\n");
+        assert_eq!(
+            fs::read_to_string(&main).await.unwrap(),
+            "fn main() { load_cfg(); load_cfg(); }\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_edit_leaves_every_file_untouched() {
+        let workspace = TempDir::new().unwrap();
+        let a = workspace.path().join("a.ts");
+        let b = workspace.path().join("b.ts");
+        fs::write(&a, "let a = 1;\n").await.unwrap();
+        fs::write(&b, "let b = 2;\n").await.unwrap();
+        let engine = FixApplicationEngine::new();
+
+        let out_of_range = FixTransaction::new("broken")
+            .with_edit(edit(&a, 0, 4, 5, "x"))
+            .with_edit(edit(&b, 9, 0, 1, "y"));
+        assert!(engine.apply_transaction(&out_of_range).await.is_err());
+
+        let overlapping = FixTransaction::new("overlapping")
+            .with_edit(edit(&a, 0, 4, 5, "x"))
+            .with_edit(edit(&b, 0, 0, 6, "const b"))
+            .with_edit(edit(&b, 0, 4, 5, "c"));
+        let result = engine.apply_transaction(&overlapping).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().starts_with("Overlapping edits"));
+
+        assert_eq!(fs::read_to_string(&a).await.unwrap(), "let a = 1;\n");
+        assert_eq!(fs::read_to_string(&b).await.unwrap(), "let b = 2;\n");
+    }
+
+    #[tokio::test]
+    async fn test_unverifiable_build_rolls_back() {
+        let workspace = TempDir::new().unwrap();
+        let state_dir = TempDir::new().unwrap();
+        let file = workspace.path().join("lib.rs");
+        fs::write(&file, "fn f() {}\n").await.unwrap();
+
+        let mut rollback = RollbackManager::new(state_dir.path().to_path_buf());
+        rollback.init().await.unwrap();
+        // Builds never run in untrusted workspaces
+        let verifier = FixVerifier::new().with_build_check(true);
+        let transaction = FixTransaction::new("rename").with_edit(edit(&file, 0, 3, 4, "g"));
+        let error = transaction
+            .apply(&FixApplicationEngine::new(), &mut rollback, Some(&verifier))
+            .await
+            .unwrap_err();
+        assert!(format!("{error:#}").starts_with("Rolled back session"));
+        assert_eq!(fs::read_to_string(&file).await.unwrap(), "fn f() {}\n");
+    }
+}
@@ -6,10 +6,14 @@ use std::path::PathBuf;
 use std::process::Command;
 // Note: CaptureService would need proper generics in real implementation
 use crate::core::constants::{build_systems, languages};
+use crate::core::workspace_trust::{untrusted_error, TrustLevel};
+use crate::multi_repo::monorepo::BazelTargetMap;
 use crate::quick_fix::engine::FixResult;
+use crate::quick_fix::transaction::TransactionResult;
+use utoipa::ToSchema;
 
 /// Result of verifying a fix
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct VerificationResult {
     /// Whether the fix resolved the original issue
     pub issue_resolved: bool,
@@ -27,7 +31,7 @@ pub struct VerificationResult {
     pub performance_impact: Option<PerformanceImpact>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct BuildStatus {
     pub success: bool,
     pub errors: Vec<String>,
@@ -35,7 +39,7 @@ pub struct BuildStatus {
     pub duration_ms: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TestResults {
     pub total: usize,
     pub passed: usize,
@@ -44,7 +48,7 @@ pub struct TestResults {
     pub failures: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PerformanceImpact {
     /// Change in bundle size (bytes)
     pub bundle_size_delta: i64,
@@ -66,6 +70,12 @@ pub struct FixVerifier {
     pub check_build: bool,
     /// Whether to use LSP for diagnostic re-capture
     pub use_lsp_validation: bool,
+    /// Bazel target map for building only affected targets
+    bazel_targets: Option<BazelTargetMap>,
+    /// Workspace whose build and test commands are run
+    workspace: PathBuf,
+    /// Build and test commands only run in trusted workspaces
+    trust: TrustLevel,
 }
 
 impl FixVerifier {
@@ -117,6 +127,9 @@ impl FixVerifier {
             run_tests: false,
             check_build: true,
             use_lsp_validation: true,
+            bazel_targets: None,
+            workspace: PathBuf::from("."),
+            trust: TrustLevel::Untrusted,
         }
     }
 
@@ -147,6 +160,46 @@ impl FixVerifier {
         self
     }
 
+    /// Build only the Bazel targets owning modified files
+    pub fn with_bazel_targets(mut self, targets: BazelTargetMap) -> Self {
+        self.bazel_targets = Some(targets);
+        self
+    }
+
+    /// Trust level of the workspace; build and test checks fail in untrusted ones
+    pub fn with_workspace_trust(mut self, workspace: impl Into<PathBuf>, trust: TrustLevel) -> Self {
+        self.workspace = workspace.into();
+        self.trust = trust;
+        self
+    }
+
+    fn ensure_trusted(&self, action: &str) -> Result<()> {
+        match self.trust {
+            TrustLevel::Trusted => Ok(()),
+            TrustLevel::Untrusted => Err(untrusted_error(&self.workspace, action)),
+        }
+    }
+
+    /// Build command for the modified files
+    ///
+    /// In Bazel workspaces this is `bazel build` over the affected targets;
+    /// otherwise the per-language default is used.
+    fn build_command_for(&self, files: &[PathBuf]) -> Vec<String> {
+        if let Some(targets) = &self.bazel_targets {
+            let affected = targets.affected_targets(files);
+            if !affected.is_empty() {
+                let mut command = vec!["bazel".to_string(), "build".to_string()];
+                command.extend(affected);
+                return command;
+            }
+        }
+
+        self.build_commands
+            .get(&detect_language_from_files(files))
+            .cloned()
+            .unwrap_or_else(|| vec!["make".to_string()])
+    }
+
     /// Verify a fix by re-running diagnostics and checks
     pub async fn verify_fix(
         &self,
@@ -212,6 +265,31 @@ impl FixVerifier {
         })
     }
 
+    /// Build once over every file a transaction modified
+    ///
+    /// A transaction's edits only make sense together, so it is checked as
+    /// a whole rather than per edit. Reports success without building when
+    /// build checks are off.
+    pub async fn verify_transaction(&self, result: &TransactionResult) -> Result<BuildStatus> {
+        if !result.success {
+            return Ok(BuildStatus {
+                success: false,
+                errors: vec!["Transaction was not applied".to_string()],
+                warnings: vec![],
+                duration_ms: 0,
+            });
+        }
+        if !self.check_build {
+            return Ok(BuildStatus {
+                success: true,
+                errors: vec![],
+                warnings: vec![],
+                duration_ms: 0,
+            });
+        }
+        self.check_build_status(&result.modified_files).await
+    }
+
     /// Validate fix using LSP diagnostic recapture
     async fn validate_fix_with_lsp(
         &self,
@@ -328,13 +406,8 @@ impl FixVerifier {
 
     /// Check build status
     async fn check_build_status(&self, files: &[PathBuf]) -> Result<BuildStatus> {
-        let language = detect_language_from_files(files);
-
-        let commands = self
-            .build_commands
-            .get(&language)
-            .cloned()
-            .unwrap_or_else(|| vec!["make".to_string()]);
+        self.ensure_trusted("run the build")?;
+        let commands = self.build_command_for(files);
 
         let start = std::time::Instant::now();
 
@@ -371,6 +444,7 @@ impl FixVerifier {
 
     /// Run tests for modified files
     async fn run_tests_for_files(&self, files: &[PathBuf]) -> Result<TestResults> {
+        self.ensure_trusted("run the tests")?;
         let language = detect_language_from_files(files);
 
         let commands = self
@@ -461,6 +535,32 @@ mod tests {
         assert_eq!(detect_language_from_files(&files), "rust");
     }
 
+    #[test]
+    fn test_bazel_build_command_uses_affected_targets() {
+        let mut targets = BazelTargetMap::new("/ws");
+        targets.add_build_file("lib", r#"rust_library(name = "lib", srcs = glob(["*.rs"]))"#);
+        let verifier = FixVerifier::new().with_bazel_targets(targets);
+
+        assert_eq!(
+            verifier.build_command_for(&[PathBuf::from("/ws/lib/mod.rs")]),
+            vec!["bazel", "build", "//lib:lib"]
+        );
+        assert_eq!(
+            verifier.build_command_for(&[PathBuf::from("/ws/other/main.rs")]),
+            vec!["cargo", "check"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_untrusted_workspace_never_runs_the_build() {
+        let verifier = FixVerifier::new().with_workspace_trust("/untrusted/repo", TrustLevel::Untrusted);
+        let error = verifier
+            .check_build_status(&[PathBuf::from("test.rs")])
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("lspbridge trust /untrusted/repo"));
+    }
+
     #[tokio::test]
     async fn test_build_status() {
         let verifier = FixVerifier::new();
@@ -0,0 +1,660 @@
+//! What-if analysis of automatic fixes
+//!
+//! Forks the workspace into a throwaway sandbox directory, applies every
+//! automatic fix at or above a confidence threshold to the sandboxed copies
+//! of the affected files, and compares the health of the forked snapshot
+//! with the original. The working tree is never written to.
+//!
+//! What a fix resolved is decided by re-checking the sandbox: each
+//! [`SandboxCheck`] runs once before the fixes are applied and once after,
+//! and a diagnostic counts as resolved when the second run no longer reports
+//! it. Diagnostics the second run reports that the first did not are counted
+//! as introduced by the fixes. Fixes for diagnostics no check can reproduce,
+//! such as those from a language server, are reported as unverified and
+//! leave the "after" metrics unchanged.
+
+use super::engine::{FixApplicationEngine, FixEdit};
+use super::suggestions::FixSuggestionService;
+use crate::capture::importers::clippy::RUSTC_SOURCE;
+use crate::capture::ClippyImporter;
+use crate::core::health_dashboard::metrics::subprojects::HOT_SPOT_THRESHOLD;
+use crate::core::workspace_trust::{untrusted_error, TrustLevel};
+use crate::core::{Diagnostic, DiagnosticSeverity, ScanConfig, StaticScanner, SCAN_SOURCE};
+use crate::history::health_score;
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+/// Health of a set of diagnostics
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthMetrics {
+    pub total: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    pub files_with_errors: usize,
+    pub hot_spots: usize,
+    /// 0.0 (worst) to 1.0 (best), weighted like the history trend analyzer
+    pub health_score: f32,
+}
+
+impl HealthMetrics {
+    pub fn from_diagnostics<'a>(diagnostics: impl IntoIterator<Item = &'a Diagnostic>) -> Self {
+        let mut per_file: HashMap<&str, (usize, usize)> = HashMap::new();
+        let mut total = 0;
+        for diagnostic in diagnostics {
+            total += 1;
+            let counts = per_file.entry(diagnostic.file.as_str()).or_default();
+            match diagnostic.severity {
+                DiagnosticSeverity::Error => counts.0 += 1,
+                DiagnosticSeverity::Warning => counts.1 += 1,
+                _ => {}
+            }
+        }
+
+        let errors: usize = per_file.values().map(|(e, _)| e).sum();
+        let warnings: usize = per_file.values().map(|(_, w)| w).sum();
+        let files = per_file.len().max(1) as f64;
+        let hot_spots = per_file
+            .values()
+            .filter(|(e, w)| e + w >= HOT_SPOT_THRESHOLD)
+            .count();
+
+        Self {
+            total,
+            errors,
+            warnings,
+            files_with_errors: per_file.values().filter(|(e, _)| *e > 0).count(),
+            hot_spots,
+            health_score: health_score(errors as f64 / files, warnings as f64 / files, hot_spots),
+        }
+    }
+}
+
+/// A fix applied in the sandbox
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SandboxFix {
+    pub diagnostic_id: String,
+    pub file: String,
+    pub line: u32,
+    pub title: String,
+    pub confidence: f32,
+    /// Whether a re-check of the sandbox confirmed the diagnostic is gone;
+    /// `None` when no check covers it
+    pub verified: Option<bool>,
+}
+
+/// Outcome of a what-if run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhatIfReport {
+    pub threshold: f32,
+    /// Diagnostics with an automatic fix at or above the threshold
+    pub candidates: usize,
+    pub applied: Vec<SandboxFix>,
+    /// Candidates skipped because they overlapped another fix or failed to apply
+    pub skipped: usize,
+    /// Applied fixes no check could confirm, so not counted as resolving anything
+    pub unverified: usize,
+    /// Diagnostics the re-check found only after the fixes were applied
+    pub introduced: Vec<Diagnostic>,
+    pub before: HealthMetrics,
+    pub after: HealthMetrics,
+}
+
+impl WhatIfReport {
+    /// Change in health score, in percentage points
+    pub fn health_improvement(&self) -> f32 {
+        (self.after.health_score - self.before.health_score) * 100.0
+    }
+}
+
+/// Re-analysis of the sandbox, run before and after fixes are applied
+#[async_trait]
+pub trait SandboxCheck: Send + Sync {
+    /// Name shown when the check fails
+    fn name(&self) -> &str;
+
+    /// Whether the check reproduces diagnostics like this one
+    fn covers(&self, diagnostic: &Diagnostic) -> bool;
+
+    /// Diagnostics for `files`, which are paths inside `sandbox`
+    async fn check(&self, sandbox: &Path, files: &[PathBuf]) -> Result<Vec<Diagnostic>>;
+}
+
+/// Re-runs the static scanner on the sandboxed files
+pub struct StaticScanCheck {
+    config: ScanConfig,
+}
+
+impl StaticScanCheck {
+    pub fn new(config: ScanConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl SandboxCheck for StaticScanCheck {
+    fn name(&self) -> &str {
+        "static scan"
+    }
+
+    fn covers(&self, diagnostic: &Diagnostic) -> bool {
+        diagnostic.source == SCAN_SOURCE
+    }
+
+    async fn check(&self, sandbox: &Path, files: &[PathBuf]) -> Result<Vec<Diagnostic>> {
+        let scanner = StaticScanner::new(sandbox, self.config.clone())?;
+        let mut diagnostics = Vec::new();
+        for file in files {
+            diagnostics.extend(scanner.scan_file(file).await?);
+        }
+        Ok(diagnostics)
+    }
+}
+
+/// Runs `cargo check` in the sandbox, reproducing compiler diagnostics
+///
+/// Build scripts and proc macros are project code, so the check only runs
+/// in trusted workspaces. Artifacts go to the workspace's own target
+/// directory so dependencies are not rebuilt.
+pub struct CargoCheck {
+    workspace: PathBuf,
+    trust: TrustLevel,
+}
+
+impl CargoCheck {
+    pub fn new(workspace: impl Into<PathBuf>, trust: TrustLevel) -> Self {
+        Self {
+            workspace: workspace.into(),
+            trust,
+        }
+    }
+}
+
+#[async_trait]
+impl SandboxCheck for CargoCheck {
+    fn name(&self) -> &str {
+        "cargo check"
+    }
+
+    fn covers(&self, diagnostic: &Diagnostic) -> bool {
+        diagnostic.source == RUSTC_SOURCE
+    }
+
+    async fn check(&self, sandbox: &Path, files: &[PathBuf]) -> Result<Vec<Diagnostic>> {
+        if self.trust == TrustLevel::Untrusted {
+            return Err(untrusted_error(&self.workspace, "run cargo check"));
+        }
+
+        let target_dir = std::env::var_os("CARGO_TARGET_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| self.workspace.join("target"));
+        let output = Command::new("cargo")
+            .args(["check", "--all-targets", "--message-format", "json"])
+            .current_dir(sandbox)
+            .env("CARGO_TARGET_DIR", target_dir)
+            .kill_on_drop(true)
+            .output()
+            .await
+            .context("Failed to run cargo check")?;
+
+        // Compile errors fail the build but are exactly what we are after
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if !output.status.success() && !stdout.contains("\"compiler-message\"") {
+            return Err(anyhow!(
+                "cargo check exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        let files: HashSet<&Path> = files.iter().map(PathBuf::as_path).collect();
+        Ok(ClippyImporter::new()
+            .with_base_dir(sandbox)
+            .import(&stdout)?
+            .diagnostics
+            .into_iter()
+            .filter(|d| files.contains(Path::new(&d.file)))
+            .collect())
+    }
+}
+
+/// Best automatic fix for a diagnostic
+struct Candidate<'a> {
+    diagnostic: &'a Diagnostic,
+    confidence: f32,
+    title: String,
+    edit: FixEdit,
+}
+
+/// Forks diagnostics into a sandbox and applies confident fixes there
+pub struct WhatIfAnalyzer {
+    threshold: f32,
+    service: FixSuggestionService,
+    engine: FixApplicationEngine,
+    workspace: PathBuf,
+    checks: Vec<Box<dyn SandboxCheck>>,
+}
+
+impl WhatIfAnalyzer {
+    pub fn new(threshold: f32) -> Self {
+        Self {
+            threshold,
+            service: FixSuggestionService::new(),
+            engine: FixApplicationEngine::new().with_backups(false),
+            workspace: PathBuf::from("."),
+            checks: Vec::new(),
+        }
+    }
+
+    /// Use a preconfigured suggestion service (e.g. with a custom scorer)
+    pub fn with_service(mut self, service: FixSuggestionService) -> Self {
+        self.service = service;
+        self
+    }
+
+    /// Workspace forked into the sandbox; fixes outside it are skipped
+    pub fn with_workspace(mut self, workspace: impl Into<PathBuf>) -> Self {
+        self.workspace = workspace.into();
+        self
+    }
+
+    /// Re-check the sandbox with `check` to decide which diagnostics are resolved
+    pub fn with_check(mut self, check: impl SandboxCheck + 'static) -> Self {
+        self.checks.push(Box::new(check));
+        self
+    }
+
+    /// Apply confident fixes in a sandbox and compare health before and after
+    pub async fn analyze(&self, diagnostics: &[Diagnostic]) -> Result<WhatIfReport> {
+        let workspace = self
+            .workspace
+            .canonicalize()
+            .with_context(|| format!("Cannot open workspace {}", self.workspace.display()))?;
+
+        // Best automatic fix per diagnostic, grouped by workspace-relative file
+        let mut candidates: HashMap<PathBuf, Vec<Candidate>> = HashMap::new();
+        let mut candidate_count = 0;
+        for diagnostic in diagnostics {
+            let best = self.service.fixes(diagnostic).into_iter().find(|fix| {
+                fix.is_automatic && fix.edit.is_some() && fix.confidence >= self.threshold
+            });
+            if let Some(fix) = best {
+                let edit = fix.edit.expect("filtered on edit");
+                candidate_count += 1;
+                let Some(relative) = relative_to(&workspace, &edit.file_path) else {
+                    tracing::debug!("Skipping fix outside the workspace: {}", edit.file_path.display());
+                    continue;
+                };
+                candidates.entry(relative).or_default().push(Candidate {
+                    diagnostic,
+                    confidence: fix.confidence,
+                    title: fix.title,
+                    edit,
+                });
+            }
+        }
+
+        let sandbox = Sandbox::fork(&workspace)?;
+        let files: Vec<PathBuf> = candidates.keys().map(|file| sandbox.dir.join(file)).collect();
+        let mut baselines = Vec::new();
+        for check in &self.checks {
+            baselines.push(self.run_check(check.as_ref(), &sandbox, &files).await);
+        }
+
+        let mut applied = Vec::new();
+        let mut modified = Vec::new();
+        for (file, mut fixes) in candidates {
+            let sandboxed = sandbox.dir.join(&file);
+
+            // Apply bottom-up so earlier edits don't shift later ranges
+            fixes.sort_by_key(|fix| std::cmp::Reverse(position(&fix.edit)));
+            let mut floor: Option<(u32, u32)> = None;
+            let mut fixed_any = false;
+
+            for Candidate {
+                diagnostic,
+                confidence,
+                title,
+                edit,
+            } in fixes
+            {
+                let end = (edit.range.end.line, edit.range.end.character);
+                if floor.is_some_and(|floor| end > floor) {
+                    continue;
+                }
+                let edit = FixEdit {
+                    file_path: sandboxed.clone(),
+                    ..edit
+                };
+                match self.engine.apply_fix(&edit).await {
+                    Ok(result) if result.success => {
+                        floor = Some(position(&edit));
+                        fixed_any = true;
+                        applied.push((
+                            diagnostic,
+                            SandboxFix {
+                                diagnostic_id: diagnostic.id.clone(),
+                                file: diagnostic.file.clone(),
+                                line: diagnostic.range.start.line + 1,
+                                title,
+                                confidence,
+                                verified: None,
+                            },
+                        ));
+                    }
+                    Ok(result) => tracing::debug!("Fix did not apply: {:?}", result.error),
+                    Err(e) => tracing::debug!("Fix did not apply: {}", e),
+                }
+            }
+            if fixed_any {
+                modified.push(file);
+            }
+        }
+
+        // Compare each check's findings in the modified files before and after
+        let modified_files: Vec<PathBuf> = modified.iter().map(|file| sandbox.dir.join(file)).collect();
+        let in_modified = |d: &Diagnostic| modified_files.iter().any(|file| file == Path::new(&d.file));
+        let mut resolved = HashSet::new();
+        let mut introduced = Vec::new();
+        for (check, baseline) in self.checks.iter().zip(baselines) {
+            let Some(mut baseline) = baseline else { continue };
+            baseline.retain(|d| in_modified(d));
+            let Some(after) = self.run_check(check.as_ref(), &sandbox, &modified_files).await else {
+                continue;
+            };
+            let mut removed = finding_counts(&sandbox.dir, &baseline);
+            for (key, count) in finding_counts(&sandbox.dir, &after) {
+                let left = removed.entry(key).or_insert(0);
+                *left = left.saturating_sub(count);
+            }
+            let mut added = finding_counts(&sandbox.dir, &after);
+            for (key, count) in finding_counts(&sandbox.dir, &baseline) {
+                let left = added.entry(key).or_insert(0);
+                *left = left.saturating_sub(count);
+            }
+
+            for diagnostic in diagnostics.iter().filter(|d| check.covers(d)) {
+                let Some(file) = relative_to(&workspace, Path::new(&diagnostic.file)) else {
+                    continue;
+                };
+                if let Some(left) = removed.get_mut(&finding_key(file, diagnostic)) {
+                    if *left > 0 {
+                        *left -= 1;
+                        resolved.insert(diagnostic.id.clone());
+                    }
+                }
+            }
+            for mut diagnostic in after {
+                let Some(file) = relative_to(&sandbox.dir, Path::new(&diagnostic.file)) else {
+                    continue;
+                };
+                if let Some(left) = added.get_mut(&finding_key(file.clone(), &diagnostic)) {
+                    if *left > 0 {
+                        *left -= 1;
+                        diagnostic.file = workspace.join(file).to_string_lossy().into_owned();
+                        introduced.push(diagnostic);
+                    }
+                }
+            }
+        }
+
+        let mut unverified = 0;
+        let applied: Vec<SandboxFix> = applied
+            .into_iter()
+            .map(|(diagnostic, mut fix)| {
+                fix.verified = self
+                    .checks
+                    .iter()
+                    .any(|check| check.covers(diagnostic))
+                    .then(|| resolved.contains(&diagnostic.id));
+                unverified += usize::from(fix.verified.is_none());
+                fix
+            })
+            .collect();
+
+        let before = HealthMetrics::from_diagnostics(diagnostics);
+        let after = HealthMetrics::from_diagnostics(
+            diagnostics
+                .iter()
+                .filter(|d| !resolved.contains(&d.id))
+                .chain(&introduced),
+        );
+
+        Ok(WhatIfReport {
+            threshold: self.threshold,
+            candidates: candidate_count,
+            skipped: candidate_count - applied.len(),
+            applied,
+            unverified,
+            introduced,
+            before,
+            after,
+        })
+    }
+
+    /// Run a check, treating its diagnostics as unverified if it fails
+    async fn run_check(&self, check: &dyn SandboxCheck, sandbox: &Sandbox, files: &[PathBuf]) -> Option<Vec<Diagnostic>> {
+        match check.check(&sandbox.dir, files).await {
+            Ok(diagnostics) => Some(diagnostics),
+            Err(e) => {
+                tracing::warn!("{} failed in the what-if sandbox: {}", check.name(), e);
+                None
+            }
+        }
+    }
+}
+
+fn position(edit: &FixEdit) -> (u32, u32) {
+    (edit.range.start.line, edit.range.start.character)
+}
+
+/// `path` relative to `root`; relative paths are taken as already relative
+fn relative_to(root: &Path, path: &Path) -> Option<PathBuf> {
+    if path.is_relative() {
+        return Some(path.to_path_buf());
+    }
+    path.strip_prefix(root).ok().map(Path::to_path_buf)
+}
+
+/// What identifies a finding across runs; positions move as fixes edit the file
+type FindingKey = (PathBuf, Option<String>, String);
+
+fn finding_key(file: PathBuf, diagnostic: &Diagnostic) -> FindingKey {
+    (file, diagnostic.code.clone(), diagnostic.message.clone())
+}
+
+fn finding_counts(root: &Path, diagnostics: &[Diagnostic]) -> HashMap<FindingKey, usize> {
+    let mut counts = HashMap::new();
+    for diagnostic in diagnostics {
+        if let Some(file) = relative_to(root, Path::new(&diagnostic.file)) {
+            *counts.entry(finding_key(file, diagnostic)).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// Directories never forked: version control and build output
+const UNFORKED_DIRS: &[&str] = &[".git", "target", "node_modules"];
+
+/// Temporary copy of a workspace, removed on drop
+struct Sandbox {
+    dir: PathBuf,
+}
+
+impl Sandbox {
+    /// Copy `workspace` into a new sandbox, leaving out [`UNFORKED_DIRS`]
+    fn fork(workspace: &Path) -> Result<Self> {
+        let dir = std::env::temp_dir().join(format!("lspbridge-whatif-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).context("Failed to create what-if sandbox")?;
+        let sandbox = Self {
+            dir: dir.canonicalize()?,
+        };
+
+        let entries = walkdir::WalkDir::new(workspace)
+            .into_iter()
+            .filter_entry(|entry| !(entry.file_type().is_dir() && UNFORKED_DIRS.iter().any(|d| entry.file_name() == *d)));
+        for entry in entries {
+            let entry = entry.context("Failed to read workspace")?;
+            let Ok(relative) = entry.path().strip_prefix(workspace) else { continue };
+            let target = sandbox.dir.join(relative);
+            if entry.file_type().is_dir() {
+                std::fs::create_dir_all(&target)?;
+            } else if entry.file_type().is_file() {
+                std::fs::copy(entry.path(), &target)
+                    .with_context(|| format!("Failed to fork {}", entry.path().display()))?;
+            }
+        }
+        Ok(sandbox)
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Position, Range};
+
+    fn diagnostic(file: &str, line: u32, severity: DiagnosticSeverity) -> Diagnostic {
+        Diagnostic::new(
+            file.to_string(),
+            Range {
+                start: Position { line, character: 0 },
+                end: Position { line, character: 1 },
+            },
+            severity,
+            "problem".to_string(),
+            "test".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_health_metrics_counts() {
+        let mut diagnostics: Vec<_> = (0..5)
+            .map(|line| diagnostic("a.rs", line, DiagnosticSeverity::Error))
+            .collect();
+        diagnostics.push(diagnostic("b.rs", 0, DiagnosticSeverity::Warning));
+        diagnostics.push(diagnostic("b.rs", 1, DiagnosticSeverity::Hint));
+
+        let metrics = HealthMetrics::from_diagnostics(&diagnostics);
+        assert_eq!(metrics.total, 7);
+        assert_eq!((metrics.errors, metrics.warnings), (5, 1));
+        assert_eq!(metrics.files_with_errors, 1);
+        assert_eq!(metrics.hot_spots, 1);
+
+        let empty = HealthMetrics::from_diagnostics(&[]);
+        assert!(empty.health_score > metrics.health_score);
+    }
+
+    fn mismatch(file: &str, line: u32) -> Diagnostic {
+        let mut mismatch = diagnostic(file, line, DiagnosticSeverity::Error);
+        mismatch.range = Range {
+            start: Position { line, character: 20 },
+            end: Position { line, character: 24 },
+        };
+        mismatch.message = "mismatched types: expected `String`, found `&str`".to_string();
+        mismatch.code = Some("E0308".to_string());
+        mismatch.source = "rustc".to_string();
+        mismatch
+    }
+
+    /// Reports `&str` literals assigned to `String` bindings, like the compiler
+    struct MismatchCheck;
+
+    #[async_trait]
+    impl SandboxCheck for MismatchCheck {
+        fn name(&self) -> &str {
+            "mismatch"
+        }
+
+        fn covers(&self, diagnostic: &Diagnostic) -> bool {
+            diagnostic.source == "rustc"
+        }
+
+        async fn check(&self, _sandbox: &Path, files: &[PathBuf]) -> Result<Vec<Diagnostic>> {
+            let mut diagnostics = Vec::new();
+            for file in files {
+                let content = std::fs::read_to_string(file)?;
+                for (line, text) in content.lines().enumerate() {
+                    if text.contains(": String = \"") && !text.contains(".to_string()") {
+                        diagnostics.push(mismatch(&file.to_string_lossy(), line as u32));
+                    }
+                }
+            }
+            Ok(diagnostics)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_analysis_leaves_working_tree_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().canonicalize().unwrap().join("lib.rs");
+        let source = "fn main() {\n    let s: String = \"hi\";\n}\n";
+        std::fs::write(&file, source).unwrap();
+        let diagnostics = [mismatch(&file.to_string_lossy(), 1)];
+
+        // Without a check nothing confirms the fix, so health is unchanged
+        let report = WhatIfAnalyzer::new(0.0)
+            .with_workspace(dir.path())
+            .analyze(&diagnostics)
+            .await
+            .unwrap();
+        assert_eq!(report.applied.len(), 1);
+        assert_eq!(report.unverified, 1);
+        assert_eq!(report.before.errors, 1);
+        assert_eq!(report.after.errors, 1);
+
+        let report = WhatIfAnalyzer::new(0.0)
+            .with_workspace(dir.path())
+            .with_check(MismatchCheck)
+            .analyze(&diagnostics)
+            .await
+            .unwrap();
+        assert_eq!(report.applied[0].verified, Some(true));
+        assert_eq!(report.unverified, 0);
+        assert!(report.introduced.is_empty());
+        assert_eq!(report.after.errors, 0);
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), source);
+    }
+
+    #[tokio::test]
+    async fn test_unresolved_fixes_are_not_counted() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().canonicalize().unwrap().join("lib.rs");
+        std::fs::write(&file, "fn main() {\n    let s: String = \"hi\";\n}\n").unwrap();
+
+        // A check that still reports the mismatch after the fix
+        struct Stubborn;
+
+        #[async_trait]
+        impl SandboxCheck for Stubborn {
+            fn name(&self) -> &str {
+                "stubborn"
+            }
+
+            fn covers(&self, _diagnostic: &Diagnostic) -> bool {
+                true
+            }
+
+            async fn check(&self, _sandbox: &Path, files: &[PathBuf]) -> Result<Vec<Diagnostic>> {
+                Ok(files.iter().map(|file| mismatch(&file.to_string_lossy(), 1)).collect())
+            }
+        }
+
+        let report = WhatIfAnalyzer::new(0.0)
+            .with_workspace(dir.path())
+            .with_check(Stubborn)
+            .analyze(&[mismatch(&file.to_string_lossy(), 1)])
+            .await
+            .unwrap();
+        assert_eq!(report.applied[0].verified, Some(false));
+        assert_eq!(report.after.errors, 1);
+    }
+}
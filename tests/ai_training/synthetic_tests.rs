@@ -163,3 +163,27 @@ fn test_transformation_metadata() {
         assert_eq!(diag.file, "synthetic.typescript");
     }
 }
+
+#[test]
+fn test_go_mutation_operators() {
+    let injector = ErrorInjector::new();
+
+    let code = r#"package main
+
+type User struct{ Name string }
+
+func main() {
+	user := &User{Name: "a"}
+	println(user.Name)
+}
+"#;
+
+    let pairs = injector
+        .inject_errors(code, "go", Some(DifficultyLevel::Advanced), 1)
+        .unwrap();
+    let pair = &pairs[0];
+    assert!(pair.before_code.contains("var user *User"));
+    assert_eq!(pair.metadata["mutation"], "nil_dereference");
+    assert_eq!(pair.metadata["error_stage"], "runtime");
+    assert_eq!(pair.metadata["verified"], false);
+}
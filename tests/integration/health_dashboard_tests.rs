@@ -57,6 +57,7 @@ async fn test_health_monitor_with_custom_config() -> Result<()> {
         enable_recommendations: true,
         max_alerts: 500,
         max_history_entries: 5000,
+        ..MonitoringConfig::default()
     };
 
     let monitor = HealthMonitor::new(processor, Some(monitoring_config)).await?;
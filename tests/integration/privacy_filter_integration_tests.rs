@@ -282,6 +282,9 @@ async fn test_privacy_policy_get_set_integration() -> Result<(), Box<dyn std::er
         max_diagnostics_per_file: 5,
         anonymize_file_paths: false,
         encrypt_exports: true,
+        redaction_patterns: Vec::new(),
+        license_rules: Vec::new(),
+        audit_log: None,
     };
     
     let cache = MemoryCache::new(100, 3600);
@@ -58,6 +58,7 @@ fn create_raw_diagnostics(diagnostics: Vec<Diagnostic>) -> RawDiagnostics {
             root_path: "/tmp/test".to_string(),
             language: Some("rust".to_string()),
             version: Some("1.0.0".to_string()),
+            roots: vec![],
         }),
     }
 }
@@ -156,6 +157,9 @@ async fn test_custom_privacy_policy_integration() -> Result<(), Box<dyn std::err
         max_diagnostics_per_file: 2,
         anonymize_file_paths: true,
         encrypt_exports: false,
+        redaction_patterns: Vec::new(),
+        license_rules: Vec::new(),
+        audit_log: None,
     };
     
     let mut capture = DiagnosticsCapture::with_privacy_policy(custom_policy.clone());
@@ -92,6 +92,7 @@ fn main() {
             root_path: temp_dir.path().to_string_lossy().to_string(),
             language: Some("rust".to_string()),
             version: Some("0.1.0".to_string()),
+            roots: vec![],
         }),
     };
     
@@ -163,6 +164,7 @@ async fn test_diagnostic_query_workflow() -> Result<(), Box<dyn std::error::Erro
         order_by: None,
         limit: Some(10),
         time_range: None,
+        join: None,
     };
     
     let _results = engine.get_all_diagnostics().await?;
@@ -177,6 +179,7 @@ async fn test_diagnostic_query_workflow() -> Result<(), Box<dyn std::error::Erro
         order_by: None,
         limit: Some(10),
         time_range: None,
+        join: None,
     };
     
     let _pattern_results = engine.get_all_diagnostics().await?;